@@ -20,8 +20,8 @@ use std::sync::Arc;
 use std::time::Duration;
 use futures::Future;
 use futures::sync::oneshot;
-use types::{HealthInfo, HealthStatus, Health};
-use time::{TimeChecker, MAX_DRIFT};
+use types::{HealthInfo, HealthStatus, Health, Readiness, ReadinessThresholds};
+use time::{TimeChecker, Error};
 use parity_reactor::Remote;
 use parking_lot::Mutex;
 use {SyncStatus};
@@ -35,12 +35,39 @@ pub struct NodeHealth {
 	sync_status: Arc<SyncStatus>,
 	time: TimeChecker,
 	remote: Remote,
+	readiness: ReadinessThresholds,
 }
 
 impl NodeHealth {
 	/// Creates new `NodeHealth`.
-	pub fn new(sync_status: Arc<SyncStatus>, time: TimeChecker, remote: Remote) -> Self {
-		NodeHealth { sync_status, time, remote, }
+	pub fn new(sync_status: Arc<SyncStatus>, time: TimeChecker, remote: Remote, readiness: ReadinessThresholds) -> Self {
+		NodeHealth { sync_status, time, remote, readiness, }
+	}
+
+	/// Check whether the node is synced and connected well enough to serve requests.
+	///
+	/// The node is never ready during a major import, or before it knows how far behind it is.
+	pub fn readiness(&self) -> Readiness {
+		let importing = self.sync_status.is_major_importing();
+		let blocks_behind = self.sync_status.blocks_behind();
+		let (peers, _) = self.sync_status.peers();
+		let synced = blocks_behind.map_or(false, |behind| behind <= self.readiness.max_blocks_behind);
+
+		Readiness {
+			ready: !importing && synced && peers >= self.readiness.min_peers,
+			blocks_behind,
+			peers,
+		}
+	}
+
+	/// Returns the current time drift in milliseconds, as measured against NTP servers.
+	pub fn time_drift(&self) -> Box<Future<Item = i64, Error = Error> + Send> {
+		self.time.time_drift()
+	}
+
+	/// Returns the time checker used by this instance.
+	pub fn time_checker(&self) -> &TimeChecker {
+		&self.time
 	}
 
 	/// Query latest health report.
@@ -48,6 +75,7 @@ impl NodeHealth {
 		trace!(target: "dapps", "Checking node health.");
 		// Check timediff
 		let sync_status = self.sync_status.clone();
+		let checker = self.time.clone();
 		let time = self.time.time_drift();
 		let (tx, rx) = oneshot::channel();
 		let tx = Arc::new(Mutex::new(Some(tx)));
@@ -93,7 +121,7 @@ impl NodeHealth {
 			// Check time
 			let time = {
 				let (status, message, details) = match time {
-					Ok(Ok(diff)) if diff < MAX_DRIFT && diff > -MAX_DRIFT => {
+					Ok(Ok(diff)) if checker.is_tolerable(diff) => {
 						(HealthStatus::Ok, "".into(), diff)
 					},
 					Ok(Ok(diff)) => {
@@ -116,7 +144,78 @@ impl NodeHealth {
 				HealthInfo { status, message, details, }
 			};
 
-			Ok(Health { peers, sync, time})
+			// Check disk space
+			let disk = {
+				let (status, message, details) = match sync_status.disk_space() {
+					Some((free, true)) => {
+						(HealthStatus::Bad, format!(
+							"Your node is running out of disk space: {} MB left. Block import has been paused to protect the database. Free up some disk space to resume it.",
+							free / (1024 * 1024),
+						), Some(free))
+					},
+					Some((free, false)) => (HealthStatus::Ok, "".into(), Some(free)),
+					None => (HealthStatus::Ok, "".into(), None),
+				};
+
+				HealthInfo { status, message, details, }
+			};
+
+			Ok(Health { peers, sync, time, disk })
 		}))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use futures_cpupool::CpuPool;
+	use parity_reactor::Remote;
+	use time::TimeChecker;
+	use types::ReadinessThresholds;
+	use SyncStatus;
+	use super::NodeHealth;
+
+	#[derive(Debug)]
+	struct FakeSync {
+		importing: bool,
+		blocks_behind: Option<u64>,
+		peers: usize,
+	}
+
+	impl SyncStatus for FakeSync {
+		fn is_major_importing(&self) -> bool { self.importing }
+		fn peers(&self) -> (usize, usize) { (self.peers, 25) }
+		fn blocks_behind(&self) -> Option<u64> { self.blocks_behind }
+		fn disk_space(&self) -> Option<(u64, bool)> { None }
+	}
+
+	fn ready(importing: bool, blocks_behind: Option<u64>, peers: usize) -> bool {
+		let sync = FakeSync { importing, blocks_behind, peers };
+		let thresholds = ReadinessThresholds { max_blocks_behind: 10, min_peers: 1 };
+		NodeHealth::new(Arc::new(sync), TimeChecker::new::<String>(&[], CpuPool::new(1)), Remote::new_sync(), thresholds)
+			.readiness()
+			.ready
+	}
+
+	#[test]
+	fn should_be_ready_when_synced_with_peers() {
+		assert!(ready(false, Some(0), 1));
+		assert!(ready(false, Some(10), 3));
+	}
+
+	#[test]
+	fn should_not_be_ready_when_behind_or_without_peers() {
+		assert!(!ready(false, Some(11), 3));
+		assert!(!ready(false, Some(0), 0));
+	}
+
+	#[test]
+	fn should_not_be_ready_during_major_import() {
+		assert!(!ready(true, Some(0), 3));
+	}
+
+	#[test]
+	fn should_not_be_ready_before_highest_block_is_known() {
+		assert!(!ready(false, None, 3));
+	}
+}
@@ -36,7 +36,7 @@ mod types;
 
 pub use futures_cpupool::CpuPool;
 pub use health::NodeHealth;
-pub use types::{Health, HealthInfo, HealthStatus};
+pub use types::{Health, HealthInfo, HealthStatus, Readiness, ReadinessThresholds};
 pub use time::{TimeChecker, Error};
 
 /// Indicates sync status
@@ -46,4 +46,12 @@ pub trait SyncStatus: ::std::fmt::Debug + Send + Sync {
 
 	/// Returns number of connected and ideal peers.
 	fn peers(&self) -> (usize, usize);
+
+	/// Returns number of blocks between the best block and the highest block seen on the network,
+	/// if the highest block is known.
+	fn blocks_behind(&self) -> Option<u64>;
+
+	/// Returns bytes of free space left for the database and whether block import
+	/// has been paused because of running low on it, or `None` if disk space is not monitored.
+	fn disk_space(&self) -> Option<(u64, bool)>;
 }
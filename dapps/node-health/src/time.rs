@@ -202,6 +202,7 @@ type BoxFuture<A, B> = Box<Future<Item = A, Error = B> + Send>;
 pub struct TimeChecker<N: Ntp = SimpleNtp> {
 	ntp: N,
 	last_result: Arc<RwLock<(time::Instant, VecDeque<Result<i64, Error>>)>>,
+	max_drift: i64,
 }
 
 impl TimeChecker<SimpleNtp> {
@@ -217,15 +218,35 @@ impl TimeChecker<SimpleNtp> {
 		TimeChecker {
 			ntp,
 			last_result,
+			max_drift: MAX_DRIFT,
 		}
 	}
 }
 
+impl<N: Ntp> TimeChecker<N> {
+	/// Sets the maximal tolerated time drift in milliseconds (`MAX_DRIFT` by default).
+	pub fn with_max_drift(mut self, max_drift: i64) -> Self {
+		self.max_drift = max_drift;
+		self
+	}
+
+	/// Returns the maximal tolerated time drift in milliseconds.
+	pub fn max_drift(&self) -> i64 {
+		self.max_drift
+	}
+
+	/// Checks if given time drift is within the tolerated range.
+	pub fn is_tolerable(&self, drift: i64) -> bool {
+		drift < self.max_drift && drift > -self.max_drift
+	}
+}
+
 impl<N: Ntp> TimeChecker<N> where <N::Future as IntoFuture>::Future: Send + 'static {
 	/// Updates the time
 	pub fn update(&self) -> BoxFuture<i64, Error> {
 		trace!(target: "dapps", "Updating time from NTP.");
 		let last_result = self.last_result.clone();
+		let max_drift = self.max_drift;
 		Box::new(self.ntp.drift().into_future().then(move |res| {
 			let res = res.map(|d| d.num_milliseconds());
 
@@ -239,7 +260,7 @@ impl<N: Ntp> TimeChecker<N> where <N::Future as IntoFuture>::Future: Send + 'sta
 			let has_all_results = results.len() >= MAX_RESULTS;
 			let valid_till = time::Instant::now() + time::Duration::from_secs(
 				match res {
-					Ok(time) if has_all_results && time < MAX_DRIFT => UPDATE_TIMEOUT_OK_SECS,
+					Ok(time) if has_all_results && time < max_drift => UPDATE_TIMEOUT_OK_SECS,
 					Ok(_) if has_all_results => UPDATE_TIMEOUT_WARN_SECS,
 					Err(_) if has_all_results => UPDATE_TIMEOUT_ERR_SECS,
 					_ => UPDATE_TIMEOUT_INCOMPLETE_SECS,
@@ -294,7 +315,7 @@ mod tests {
 	use std::time::Instant;
 	use time::Duration;
 	use futures::{future, Future};
-	use super::{Ntp, TimeChecker, Error};
+	use super::{Ntp, TimeChecker, Error, MAX_DRIFT};
 	use parking_lot::RwLock;
 
 	#[derive(Clone)]
@@ -324,6 +345,7 @@ mod tests {
 		TimeChecker {
 			ntp: FakeNtp::new(),
 			last_result: last_result,
+			max_drift: MAX_DRIFT,
 		}
 	}
 
@@ -354,4 +376,19 @@ mod tests {
 		assert_eq!(diff2, 150);
 		assert_eq!(time.ntp.1.get(), 1);
 	}
+
+	#[test]
+	fn should_check_drift_against_configured_maximum() {
+		// given
+		let time = time_checker().with_max_drift(100);
+
+		// when
+		let diff = time.time_drift().wait().unwrap();
+
+		// then
+		assert_eq!(time.max_drift(), 100);
+		assert!(!time.is_tolerable(diff));
+		assert!(!time.is_tolerable(-diff));
+		assert!(time.is_tolerable(99));
+	}
 }
@@ -54,4 +54,37 @@ pub struct Health {
 	pub sync: HealthInfo<bool>,
 	/// Time diff info.
 	pub time: HealthInfo<i64>,
+	/// Free disk space info.
+	pub disk: HealthInfo<Option<u64>>,
+}
+
+/// Thresholds for the node to be reported as ready to serve requests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReadinessThresholds {
+	/// Maximal number of blocks the node may be behind the highest block seen on the network.
+	pub max_blocks_behind: u64,
+	/// Minimal number of connected peers.
+	pub min_peers: usize,
+}
+
+impl Default for ReadinessThresholds {
+	fn default() -> Self {
+		ReadinessThresholds {
+			max_blocks_behind: 10,
+			min_peers: 1,
+		}
+	}
+}
+
+/// Readiness of the node to serve requests.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Readiness {
+	/// Whether the node is synced and connected to enough peers.
+	pub ready: bool,
+	/// Number of blocks behind the highest block seen on the network, if it is known.
+	#[serde(rename = "blocksBehind")]
+	pub blocks_behind: Option<u64>,
+	/// Number of connected peers.
+	pub peers: usize,
 }
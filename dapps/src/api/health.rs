@@ -0,0 +1,61 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use hyper::{Method, StatusCode};
+
+use api::response;
+use api::types::Liveness;
+use endpoint::{Endpoint, Request, Response, EndpointPath};
+use futures::future;
+use node_health::NodeHealth;
+
+/// Liveness and readiness probes, e.g. for Kubernetes.
+#[derive(Clone)]
+pub struct HealthApi {
+	health: NodeHealth,
+}
+
+impl Endpoint for HealthApi {
+	fn respond(&self, path: EndpointPath, req: Request) -> Response {
+		if let Method::Options = *req.method() {
+			return Box::new(future::ok(response::empty()));
+		}
+
+		let probe = path.app_params.get(0).map(String::as_str);
+		trace!(target: "dapps", "Handling /health request: {:?}", probe);
+		Box::new(future::ok(match probe {
+			// answering at all means the node is alive.
+			Some("live") => response::as_json(StatusCode::Ok, &Liveness { alive: true }),
+			Some("ready") => {
+				let readiness = self.health.readiness();
+				let status = match readiness.ready {
+					true => StatusCode::Ok, // HTTP 200
+					false => StatusCode::ServiceUnavailable, // HTTP 503
+				};
+				response::as_json(status, &readiness)
+			},
+			_ => response::not_found(),
+		}))
+	}
+}
+
+impl HealthApi {
+	pub fn new(health: NodeHealth) -> Box<Endpoint> {
+		Box::new(HealthApi {
+			health,
+		})
+	}
+}
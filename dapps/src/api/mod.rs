@@ -17,7 +17,9 @@
 //! REST API
 
 mod api;
+mod health;
 mod response;
 mod types;
 
 pub use self::api::RestApi;
+pub use self::health::HealthApi;
@@ -25,3 +25,11 @@ pub struct ApiError {
 	/// More technical error details.
 	pub detail: String,
 }
+
+/// Response of the liveness probe.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Liveness {
+	/// Always true, since the node answered.
+	pub alive: bool,
+}
@@ -18,6 +18,8 @@
 
 use std::fs;
 
+use ethereum_types::H256;
+use hash_fetch::BlobStore;
 use linked_hash_map::LinkedHashMap;
 use page::local;
 use handlers::FetchControl;
@@ -45,7 +47,9 @@ impl ContentCache {
 		self.cache.get_refresh(content_id)
 	}
 
-	pub fn clear_garbage(&mut self, expected_size: usize) -> Vec<(String, ContentStatus)> {
+	/// Removes the least recently used content over `expected_size`.
+	/// Content served from the blob store is released to the store instead of being removed.
+	pub fn clear_garbage(&mut self, expected_size: usize, store: Option<&BlobStore>) -> Vec<(String, ContentStatus)> {
 		let len = self.cache.len();
 
 		if len <= expected_size {
@@ -63,6 +67,12 @@ impl ContentCache {
 					// Mark as aborted
 					fetch.abort()
 				},
+				ContentStatus::Ready(ref endpoint) if store.map_or(false, |store| endpoint.path().starts_with(store.path())) => {
+					trace!(target: "dapps", "Releasing {} because of limit.", entry.0);
+					if let (Some(store), Ok(hash)) = (store, entry.0.parse::<H256>()) {
+						store.unpin(&hash);
+					}
+				},
 				ContentStatus::Ready(ref endpoint) => {
 					trace!(target: "dapps", "Removing {} because of limit.", entry.0);
 					// Remove path (dir or file)
@@ -101,7 +111,7 @@ mod tests {
 		cache.insert("c".into(), ContentStatus::Fetching(Default::default()));
 
 		// when
-		let res = cache.clear_garbage(2);
+		let res = cache.clear_garbage(2, None);
 
 		// then
 		assert_eq!(cache.len(), 2);
@@ -118,11 +128,40 @@ mod tests {
 
 		// when
 		cache.get("a");
-		let res = cache.clear_garbage(2);
+		let res = cache.clear_garbage(2, None);
 
 		// then
 		assert_eq!(cache.len(), 2);
 		assert_eq!(only_keys(res), vec!["b"]);
 	}
 
+	#[test]
+	fn should_release_stored_content_to_the_store() {
+		use std::env;
+		use std::io::Write;
+		use futures_cpupool::CpuPool;
+		use hash::keccak;
+		use page::{local, PageCache};
+
+		// given
+		let path = env::temp_dir().join(::random_filename());
+		let store = BlobStore::open(&path, 1).unwrap();
+		let temp = store.temp_path();
+		fs::File::create(&temp).unwrap().write_all(b"content").unwrap();
+		let stored = store.insert(keccak(b"content"), &temp).unwrap();
+
+		let mut cache = ContentCache::default();
+		let endpoint = local::Dapp::single_file(CpuPool::new(1), stored.clone(), ::mime_guess::get_mime_type("txt"), PageCache::Enabled);
+		cache.insert(format!("{:x}", keccak(b"content")), ContentStatus::Ready(endpoint));
+
+		// when
+		cache.clear_garbage(0, Some(&store));
+
+		// then
+		assert_eq!(cache.len(), 0);
+		// the store evicts the content once it's released
+		assert!(store.get(&keccak(b"content")).is_none());
+		assert!(!stored.exists());
+		fs::remove_dir_all(path).unwrap();
+	}
 }
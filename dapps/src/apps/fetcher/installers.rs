@@ -15,25 +15,32 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use zip;
+use cid::Cid;
 use std::{fs, fmt};
 use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use ethereum_types::H256;
 use fetch;
 use futures_cpupool::CpuPool;
 use hash::keccak_pipe;
+use hash_fetch::BlobStore;
 use mime_guess::Mime;
 
+use apps::fetcher::ipfs;
 use apps::manifest::{MANIFEST_FILENAME, deserialize_manifest, serialize_manifest, Manifest};
 use handlers::{ContentValidator, ValidatorResponse};
 use page::{local, PageCache};
 
 type OnDone = Box<Fn(Option<local::Dapp>) + Send>;
 
+/// Writes the response to `content_path/filename`, or to the blob store if there is one.
+/// The content directory is created in both cases.
 fn write_response_and_check_hash(
 	id: &str,
 	mut content_path: PathBuf,
 	filename: &str,
+	store: Option<&BlobStore>,
 	response: fetch::Response
 ) -> Result<(fs::File, PathBuf), ValidationError> {
 	// try to parse id
@@ -49,7 +56,13 @@ fn write_response_and_check_hash(
 	fs::create_dir_all(&content_path)?;
 
 	// append filename
-	content_path.push(filename);
+	let content_path = match store {
+		Some(store) => store.temp_path(),
+		None => {
+			content_path.push(filename);
+			content_path
+		},
+	};
 
 	// Now write the response
 	let mut file = io::BufWriter::new(fs::File::create(&content_path)?);
@@ -60,10 +73,17 @@ fn write_response_and_check_hash(
 
 	// Validate hash
 	if id == hash {
+		let content_path = match store {
+			Some(store) => store.insert(hash, &content_path)?,
+			None => content_path,
+		};
 		// The writing above changed the file Read position, which we need later. So we just create a new file handle
 		// here.
 		Ok((fs::File::open(&content_path)?, content_path))
 	} else {
+		if store.is_some() {
+			let _ = fs::remove_file(&content_path);
+		}
 		Err(ValidationError::HashMismatch {
 			expected: id,
 			got: hash,
@@ -71,20 +91,45 @@ fn write_response_and_check_hash(
 	}
 }
 
+/// Writes the file read from the CAR archive in the response to `content_path/filename`.
+/// The content directory is created.
+fn write_ipfs_content(
+	root: &Cid,
+	mut content_path: PathBuf,
+	filename: &str,
+	response: fetch::Response
+) -> Result<(fs::File, PathBuf), ValidationError> {
+	if content_path.exists() {
+		warn!(target: "dapps", "Overwriting existing content at {}", root);
+		fs::remove_dir_all(&content_path)?
+	}
+	fs::create_dir_all(&content_path)?;
+	content_path.push(filename);
+
+	let mut car = Vec::new();
+	fetch::BodyReader::new(response).read_to_end(&mut car)?;
+	let content = ipfs::unpack_car(root, &car).map_err(ValidationError::Ipfs)?;
+	fs::File::create(&content_path)?.write_all(&content)?;
+
+	Ok((fs::File::open(&content_path)?, content_path))
+}
+
 pub struct Content {
 	id: String,
 	mime: Mime,
 	content_path: PathBuf,
+	store: Option<Arc<BlobStore>>,
 	on_done: OnDone,
 	pool: CpuPool,
 }
 
 impl Content {
-	pub fn new(id: String, mime: Mime, content_path: PathBuf, on_done: OnDone, pool: CpuPool) -> Self {
+	pub fn new(id: String, mime: Mime, content_path: PathBuf, store: Option<Arc<BlobStore>>, on_done: OnDone, pool: CpuPool) -> Self {
 		Content {
 			id,
 			mime,
 			content_path,
+			store,
 			on_done,
 			pool,
 		}
@@ -98,9 +143,10 @@ impl ContentValidator for Content {
 		let pool = self.pool;
 		let id = self.id.clone();
 		let mime = self.mime;
+		let store = self.store;
 		let validate = move |content_path: PathBuf| {
 			// Create dir
-			let (_, content_path) = write_response_and_check_hash(&id, content_path, &id, response)?;
+			let (_, content_path) = write_response_and_check_hash(&id, content_path, &id, store.as_ref().map(|store| &**store), response)?;
 
 			Ok(local::Dapp::single_file(pool, content_path, mime, PageCache::Enabled))
 		};
@@ -122,20 +168,30 @@ impl ContentValidator for Content {
 pub struct Dapp {
 	id: String,
 	dapps_path: PathBuf,
+	store: Option<Arc<BlobStore>>,
+	ipfs_root: Option<Cid>,
 	on_done: OnDone,
 	pool: CpuPool,
 }
 
 impl Dapp {
-	pub fn new(id: String, dapps_path: PathBuf, on_done: OnDone, pool: CpuPool) -> Self {
+	pub fn new(id: String, dapps_path: PathBuf, store: Option<Arc<BlobStore>>, on_done: OnDone, pool: CpuPool) -> Self {
 		Dapp {
 			id,
 			dapps_path,
+			store,
+			ipfs_root: None,
 			on_done,
 			pool,
 		}
 	}
 
+	/// Read the bundle from a CAR archive of the given IPFS CID, instead of checking its hash against the id.
+	pub fn with_ipfs_root(mut self, root: Cid) -> Self {
+		self.ipfs_root = Some(root);
+		self
+	}
+
 	fn find_manifest(zip: &mut zip::ZipArchive<fs::File>) -> Result<(Manifest, PathBuf), ValidationError> {
 		for i in 0..zip.len() {
 			let mut file = zip.by_index(i)?;
@@ -159,6 +215,62 @@ impl Dapp {
 
 		Err(ValidationError::ManifestNotFound)
 	}
+
+	/// Unpacks the bundle into `dapp_path`, which has to exist.
+	fn unpack(id: String, pool: CpuPool, file: fs::File, zip_path: &Path, dapp_path: PathBuf) -> Result<local::Dapp, ValidationError> {
+		trace!(target: "dapps", "Opening dapp bundle at {:?}", zip_path);
+		// Unpack archive
+		let mut zip = zip::ZipArchive::new(file)?;
+		// First find manifest file
+		let (mut manifest, manifest_dir) = Self::find_manifest(&mut zip)?;
+		// Overwrite id to match hash
+		manifest.id = Some(id);
+
+		// Unpack zip
+		for i in 0..zip.len() {
+			let mut file = zip.by_index(i)?;
+			let is_dir = file.name().chars().rev().next() == Some('/');
+
+			let file_path = PathBuf::from(file.name());
+			let location_in_manifest_base = file_path.strip_prefix(&manifest_dir);
+			// Create files that are inside manifest directory
+			if let Ok(location_in_manifest_base) = location_in_manifest_base {
+				let p = dapp_path.join(location_in_manifest_base);
+				// Check if it's a directory
+				if is_dir {
+					fs::create_dir_all(p)?;
+				} else {
+					let mut target = fs::File::create(p)?;
+					io::copy(&mut file, &mut target)?;
+				}
+			}
+		}
+
+		// Write manifest
+		let manifest_str = serialize_manifest(&manifest).map_err(ValidationError::ManifestSerialization)?;
+		let manifest_path = dapp_path.join(MANIFEST_FILENAME);
+		let mut manifest_file = fs::File::create(manifest_path)?;
+		manifest_file.write_all(manifest_str.as_bytes())?;
+		// Create endpoint
+		Ok(local::Dapp::new(pool, dapp_path, manifest.into(), PageCache::Enabled))
+	}
+
+	/// Installs a bundle kept in the blob store at `zip_path` into `dapps_path`, without fetching it.
+	pub fn install_stored(id: String, dapps_path: PathBuf, zip_path: &Path, pool: CpuPool) -> Result<local::Dapp, ValidationError> {
+		let target = dapps_path.join(&id);
+		if target.exists() {
+			fs::remove_dir_all(&target)?;
+		}
+		fs::create_dir_all(&target)?;
+
+		let result = fs::File::open(zip_path)
+			.map_err(ValidationError::from)
+			.and_then(|file| Self::unpack(id, pool, file, zip_path, target.clone()));
+		if result.is_err() {
+			let _ = fs::remove_dir_all(&target);
+		}
+		result
+	}
 }
 
 impl ContentValidator for Dapp {
@@ -167,47 +279,25 @@ impl ContentValidator for Dapp {
 	fn validate_and_install(self, response: fetch::Response) -> Result<ValidatorResponse, ValidationError> {
 		let id = self.id.clone();
 		let pool = self.pool;
+		let store = self.store;
+		let ipfs_root = self.ipfs_root;
 		let validate = move |dapp_path: PathBuf| {
-			let (file, zip_path) = write_response_and_check_hash(&id, dapp_path.clone(), &format!("{}.zip", id), response)?;
-			trace!(target: "dapps", "Opening dapp bundle at {:?}", zip_path);
-			// Unpack archive
-			let mut zip = zip::ZipArchive::new(file)?;
-			// First find manifest file
-			let (mut manifest, manifest_dir) = Self::find_manifest(&mut zip)?;
-			// Overwrite id to match hash
-			manifest.id = Some(id);
-
-			// Unpack zip
-			for i in 0..zip.len() {
-				let mut file = zip.by_index(i)?;
-				let is_dir = file.name().chars().rev().next() == Some('/');
-
-				let file_path = PathBuf::from(file.name());
-				let location_in_manifest_base = file_path.strip_prefix(&manifest_dir);
-				// Create files that are inside manifest directory
-				if let Ok(location_in_manifest_base) = location_in_manifest_base {
-					let p = dapp_path.join(location_in_manifest_base);
-					// Check if it's a directory
-					if is_dir {
-						fs::create_dir_all(p)?;
-					} else {
-						let mut target = fs::File::create(p)?;
-						io::copy(&mut file, &mut target)?;
-					}
-				}
+			let store = match ipfs_root {
+				Some(_) => None,
+				None => store.as_ref().map(|store| &**store),
+			};
+			let (file, zip_path) = match ipfs_root {
+				Some(ref root) => write_ipfs_content(root, dapp_path.clone(), &format!("{}.zip", id), response)?,
+				None => write_response_and_check_hash(&id, dapp_path.clone(), &format!("{}.zip", id), store, response)?,
+			};
+			let endpoint = Self::unpack(id.clone(), pool, file, &zip_path, dapp_path);
+
+			// Remove zip, unless it's kept in the blob store
+			match store {
+				Some(store) => store.unpin(&id.parse().expect("the id is validated by `write_response_and_check_hash`; qed")),
+				None => fs::remove_file(&zip_path)?,
 			}
-
-			// Remove zip
-			fs::remove_file(&zip_path)?;
-
-			// Write manifest
-			let manifest_str = serialize_manifest(&manifest).map_err(ValidationError::ManifestSerialization)?;
-			let manifest_path = dapp_path.join(MANIFEST_FILENAME);
-			let mut manifest_file = fs::File::create(manifest_path)?;
-			manifest_file.write_all(manifest_str.as_bytes())?;
-			// Create endpoint
-			let endpoint = local::Dapp::new(pool, dapp_path, manifest.into(), PageCache::Enabled);
-			Ok(endpoint)
+			endpoint
 		};
 
 		// Prepare directory for dapp
@@ -232,6 +322,7 @@ pub enum ValidationError {
 	ManifestNotFound,
 	ManifestSerialization(String),
 	HashMismatch { expected: H256, got: H256, },
+	Ipfs(String),
 }
 
 impl fmt::Display for ValidationError {
@@ -247,6 +338,7 @@ impl fmt::Display for ValidationError {
 			ValidationError::HashMismatch { ref expected, ref got } => {
 				write!(f, "Hash of downloaded content did not match. Expected:{:?}, Got:{:?}.", expected, got)
 			},
+			ValidationError::Ipfs(ref err) => write!(f, "Invalid IPFS content: {}", err),
 		}
 	}
 }
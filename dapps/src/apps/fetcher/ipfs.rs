@@ -0,0 +1,302 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Verified reading of dapp bundles fetched from an IPFS gateway as CAR archives.
+//!
+//! Every block in the archive is checked against the multihash of its CID,
+//! and the bundle is put together by walking the DAG from the requested CID,
+//! so a gateway can't serve anything but the content the CID refers to.
+
+use std::collections::HashMap;
+
+use cid::{Cid, Codec, ToCid};
+use multihash;
+
+/// Largest bundle put together from an archive.
+const MAX_CONTENT_SIZE: usize = 64 * 1024 * 1024;
+
+/// UnixFS node types which hold file data.
+const UNIXFS_RAW: u64 = 0;
+const UNIXFS_FILE: u64 = 2;
+
+/// Returns whether content of the given CID can be read and verified.
+pub fn is_supported(cid: &Cid) -> bool {
+	let supported_codec = match cid.codec {
+		Codec::Raw | Codec::DagProtobuf => true,
+		_ => false,
+	};
+
+	supported_codec && multihash::decode(&cid.hash)
+		.and_then(|mh| multihash::encode(mh.alg, &[]))
+		.is_ok()
+}
+
+/// Reads the file with the given root CID from a CAR archive.
+pub fn unpack_car(root: &Cid, car: &[u8]) -> Result<Vec<u8>, String> {
+	let blocks = read_car(car)?;
+	let mut content = Vec::new();
+	read_file(root, &blocks, &mut content)?;
+	Ok(content)
+}
+
+/// Reads and verifies all blocks of a CAR (v1) archive, keyed by their multihash.
+fn read_car(mut car: &[u8]) -> Result<HashMap<Vec<u8>, &[u8]>, String> {
+	// the header only lists the roots, the requested one is known already
+	let header_len = read_varint(&mut car)? as usize;
+	take(&mut car, header_len)?;
+
+	let mut blocks = HashMap::new();
+	while !car.is_empty() {
+		let section_len = read_varint(&mut car)? as usize;
+		let mut section = take(&mut car, section_len)?;
+		let cid_len = cid_len(section)?;
+		let cid = take(&mut section, cid_len)?.to_cid().map_err(|e| format!("Invalid CID in archive: {:?}", e))?;
+
+		let mh = multihash::decode(&cid.hash).map_err(|e| format!("Invalid multihash in archive: {:?}", e))?;
+		let hash = multihash::encode(mh.alg, section).map_err(|e| format!("Unsupported hash in archive: {:?}", e))?;
+		if hash != cid.hash {
+			return Err(format!("Block {} doesn't match its hash", cid));
+		}
+
+		blocks.insert(cid.hash, section);
+	}
+
+	Ok(blocks)
+}
+
+/// Appends the content of the file at `cid` to `content`.
+fn read_file(cid: &Cid, blocks: &HashMap<Vec<u8>, &[u8]>, content: &mut Vec<u8>) -> Result<(), String> {
+	let block = match blocks.get(&cid.hash) {
+		Some(block) => *block,
+		None => return Err(format!("Block {} is missing from the archive", cid)),
+	};
+
+	match cid.codec {
+		Codec::Raw => content.extend_from_slice(block),
+		Codec::DagProtobuf => {
+			let (data, links) = read_dag_node(block)?;
+			let (node_type, data) = read_unixfs_data(data)?;
+			if node_type != UNIXFS_RAW && node_type != UNIXFS_FILE {
+				return Err(format!("Node {} is not a file", cid));
+			}
+
+			content.extend_from_slice(data);
+			for link in links {
+				read_file(&link, blocks, content)?;
+			}
+		},
+		_ => return Err(format!("Block {} has an unsupported codec", cid)),
+	}
+
+	if content.len() > MAX_CONTENT_SIZE {
+		return Err("Content is too large".into());
+	}
+
+	Ok(())
+}
+
+/// Decodes a DAG-PB node into its data and the CIDs of its links.
+fn read_dag_node(node: &[u8]) -> Result<(&[u8], Vec<Cid>), String> {
+	let mut data: &[u8] = &[];
+	let mut links = Vec::new();
+
+	for field in read_protobuf(node)? {
+		match field {
+			(1, Field::Bytes(bytes)) => data = bytes,
+			(2, Field::Bytes(link)) => {
+				for field in read_protobuf(link)? {
+					if let (1, Field::Bytes(hash)) = field {
+						links.push(hash.to_cid().map_err(|e| format!("Invalid link: {:?}", e))?);
+					}
+				}
+			},
+			_ => {},
+		}
+	}
+
+	Ok((data, links))
+}
+
+/// Decodes UnixFS node data into the node type and the file data it holds.
+fn read_unixfs_data(node: &[u8]) -> Result<(u64, &[u8]), String> {
+	let mut node_type = None;
+	let mut data: &[u8] = &[];
+
+	for field in read_protobuf(node)? {
+		match field {
+			(1, Field::Varint(value)) => node_type = Some(value),
+			(2, Field::Bytes(bytes)) => data = bytes,
+			_ => {},
+		}
+	}
+
+	node_type.map(|node_type| (node_type, data)).ok_or_else(|| "UnixFS node without a type".into())
+}
+
+enum Field<'a> {
+	Varint(u64),
+	Bytes(&'a [u8]),
+	Fixed,
+}
+
+/// Decodes the fields of a protobuf message.
+fn read_protobuf(mut message: &[u8]) -> Result<Vec<(u64, Field)>, String> {
+	let mut fields = Vec::new();
+	while !message.is_empty() {
+		let key = read_varint(&mut message)?;
+		let field = match key & 0x7 {
+			0 => Field::Varint(read_varint(&mut message)?),
+			1 => { take(&mut message, 8)?; Field::Fixed },
+			2 => {
+				let len = read_varint(&mut message)? as usize;
+				Field::Bytes(take(&mut message, len)?)
+			},
+			5 => { take(&mut message, 4)?; Field::Fixed },
+			wire_type => return Err(format!("Unsupported protobuf wire type {}", wire_type)),
+		};
+		fields.push((key >> 3, field));
+	}
+	Ok(fields)
+}
+
+/// Length of the binary CID at the start of `data`.
+fn cid_len(data: &[u8]) -> Result<usize, String> {
+	// CIDv0 is a bare SHA2-256 multihash
+	if data.len() >= 2 && data[0] == 0x12 && data[1] == 0x20 {
+		return Ok(34);
+	}
+
+	let mut rest = data;
+	// version, codec and hash type
+	for _ in 0..3 {
+		read_varint(&mut rest)?;
+	}
+	let digest_len = read_varint(&mut rest)? as usize;
+	take(&mut rest, digest_len)?;
+
+	Ok(data.len() - rest.len())
+}
+
+/// Reads an unsigned LEB128 varint.
+fn read_varint(data: &mut &[u8]) -> Result<u64, String> {
+	let mut value = 0u64;
+	for shift in 0..10 {
+		let byte = *take(data, 1)?.first().expect("one byte is taken; qed");
+		value |= u64::from(byte & 0x7f) << (7 * shift);
+		if byte & 0x80 == 0 {
+			return Ok(value);
+		}
+	}
+	Err("Varint is too long".into())
+}
+
+/// Splits `len` bytes off the start of `data`.
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+	if data.len() < len {
+		return Err("Unexpected end of data".into());
+	}
+	let (head, tail) = data.split_at(len);
+	*data = tail;
+	Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+	use cid::{Cid, Codec, Version, ToCid};
+	use multihash::{self, Hash};
+	use super::*;
+
+	fn varint(mut value: usize) -> Vec<u8> {
+		let mut out = Vec::new();
+		loop {
+			let byte = (value & 0x7f) as u8;
+			value >>= 7;
+			if value == 0 {
+				out.push(byte);
+				return out;
+			}
+			out.push(byte | 0x80);
+		}
+	}
+
+	fn bytes_field(field: u8, data: &[u8]) -> Vec<u8> {
+		let mut out = vec![field << 3 | 2];
+		out.extend(varint(data.len()));
+		out.extend_from_slice(data);
+		out
+	}
+
+	fn car(blocks: &[(&Cid, &[u8])]) -> Vec<u8> {
+		// roots are ignored, so the header needn't be valid DAG-CBOR
+		let mut out = varint(1);
+		out.push(0);
+		for &(cid, block) in blocks {
+			let cid = cid.to_bytes();
+			out.extend(varint(cid.len() + block.len()));
+			out.extend(cid);
+			out.extend_from_slice(block);
+		}
+		out
+	}
+
+	fn raw_cid(data: &[u8]) -> Cid {
+		Cid::new(Codec::Raw, Version::V1, &multihash::encode(Hash::SHA2256, data).unwrap())
+	}
+
+	// `ipfs add` of "hello world\n"
+	const HELLO_CID: &'static str = "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o";
+	const HELLO_NODE: &'static str = "0a120802120c68656c6c6f20776f726c640a180c";
+
+	#[test]
+	fn should_unpack_single_block_file() {
+		let cid = HELLO_CID.to_cid().unwrap();
+		let node: Vec<u8> = ::rustc_hex::FromHex::from_hex(HELLO_NODE).unwrap();
+
+		assert!(is_supported(&cid));
+		assert_eq!(unpack_car(&cid, &car(&[(&cid, &node)])), Ok(b"hello world\n".to_vec()));
+	}
+
+	#[test]
+	fn should_unpack_file_with_links_in_order() {
+		let (first, second) = (raw_cid(b"hello "), raw_cid(b"world"));
+		let mut node = Vec::new();
+		node.extend(bytes_field(2, &bytes_field(1, &first.to_bytes())));
+		node.extend(bytes_field(2, &bytes_field(1, &second.to_bytes())));
+		node.extend(bytes_field(1, &[0x08, 0x02]));
+		let root = Cid::new(Codec::DagProtobuf, Version::V1, &multihash::encode(Hash::SHA2256, &node).unwrap());
+
+		let archive = car(&[(&second, b"world"), (&root, &node), (&first, b"hello ")]);
+
+		assert_eq!(unpack_car(&root, &archive), Ok(b"hello world".to_vec()));
+	}
+
+	#[test]
+	fn should_reject_tampered_or_missing_blocks() {
+		let cid = raw_cid(b"hello");
+
+		assert!(unpack_car(&cid, &car(&[(&cid, b"jello")])).is_err());
+		assert!(unpack_car(&cid, &car(&[(&raw_cid(b"jello"), b"jello")])).is_err());
+		assert!(unpack_car(&cid, &car(&[(&cid, b"hello")])[..10]).is_err());
+	}
+
+	#[test]
+	fn should_reject_directories() {
+		let node = bytes_field(1, &[0x08, 0x01]);
+		let cid = Cid::new(Codec::DagProtobuf, Version::V1, &multihash::encode(Hash::SHA2256, &node).unwrap());
+
+		assert!(unpack_car(&cid, &car(&[(&cid, &node)])).is_err());
+	}
+}
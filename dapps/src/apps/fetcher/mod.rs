@@ -17,23 +17,31 @@
 //! Fetchable Dapps support.
 //! Manages downloaded (cached) Dapps and downloads them when necessary.
 //! Uses `URLHint` to resolve addresses into Dapps bundle file location.
+//! Dapps registered by IPFS CID are fetched through an IPFS HTTP gateway instead.
 
 mod installers;
+mod ipfs;
 
 use std::{fs, env};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use cid::{ToCid, Codec};
 use futures::{future, Future};
 use futures_cpupool::CpuPool;
 use fetch::{Client as FetchClient, Fetch};
-use hash_fetch::urlhint::{URLHintContract, URLHint, URLHintResult};
+use hash_fetch::BlobStore;
+use hash_fetch::urlhint::{URLHintContract, URLHint, URLHintResult, Content};
+use mime_guess;
+use hash::keccak;
+use multihash::{self, Hash};
 
 use hyper::StatusCode;
 
-use ethereum_types::H256;
+use ethereum_types::{H256, Address};
 use {SyncStatus, random_filename};
 use parking_lot::Mutex;
-use page::local;
+use page::{local, PageCache};
 use handlers::{ContentHandler, ContentFetcherHandler};
 use endpoint::{self, Endpoint, EndpointPath};
 use apps::cache::{ContentCache, ContentStatus};
@@ -45,6 +53,25 @@ pub trait Fetcher: Endpoint + 'static {
 	fn contains(&self, content_id: &str) -> bool;
 }
 
+/// Returns the content id of a dapp bundle registered by IPFS CID.
+/// Raw and DAG-PB (UnixFS file) CIDs are supported, with any hash `multihash` can compute.
+/// Raw CIDs with a Keccak-256 hash use the hash as the id, like other hash-addressed content,
+/// other CIDs use the Keccak-256 hash of the binary CID.
+pub fn ipfs_content_id(cid: &str) -> Result<H256, String> {
+	let cid = cid.to_cid().map_err(|e| format!("Invalid IPFS CID {}: {:?}", cid, e))?;
+	let mh = multihash::decode(&cid.hash).map_err(|e| format!("Invalid IPFS CID multihash: {:?}", e))?;
+
+	if !ipfs::is_supported(&cid) {
+		return Err("Only raw and UnixFS file IPFS CIDs with a supported hash are supported for dapps.".into());
+	}
+
+	if cid.codec == Codec::Raw && mh.alg == Hash::Keccak256 {
+		Ok(H256::from_slice(mh.digest))
+	} else {
+		Ok(keccak(cid.to_bytes()))
+	}
+}
+
 pub struct ContentFetcher<F: Fetch = FetchClient, R: URLHint + 'static = URLHintContract> {
 	cache_path: PathBuf,
 	resolver: R,
@@ -53,6 +80,9 @@ pub struct ContentFetcher<F: Fetch = FetchClient, R: URLHint + 'static = URLHint
 	fetch: F,
 	pool: CpuPool,
 	only_content: bool,
+	ipfs_gateway: String,
+	ipfs_dapps: HashMap<H256, String>,
+	store: Option<Arc<BlobStore>>,
 }
 
 impl<R: URLHint + 'static, F: Fetch> Drop for ContentFetcher<F, R> {
@@ -80,6 +110,9 @@ impl<R: URLHint + 'static, F: Fetch> ContentFetcher<F, R> {
 			fetch,
 			pool,
 			only_content: true,
+			ipfs_gateway: String::new(),
+			ipfs_dapps: HashMap::new(),
+			store: None,
 		}
 	}
 
@@ -88,6 +121,31 @@ impl<R: URLHint + 'static, F: Fetch> ContentFetcher<F, R> {
 		self
 	}
 
+	/// Serve dapps registered by IPFS CID, fetching them from the given gateway.
+	/// CIDs which aren't supported by `ipfs_content_id` are skipped.
+	pub fn with_ipfs_dapps(mut self, gateway: String, cids: Vec<String>) -> Self {
+		self.ipfs_gateway = gateway.trim_right_matches('/').to_owned();
+		self.ipfs_dapps = cids.into_iter()
+			.filter_map(|cid| match ipfs_content_id(&cid) {
+				Ok(id) => {
+					info!(target: "dapps", "IPFS dapp {} available as {:x}", cid, id);
+					Some((id, cid))
+				},
+				Err(e) => {
+					warn!(target: "dapps", "{}", e);
+					None
+				},
+			})
+			.collect();
+		self
+	}
+
+	/// Download content into the given blob store instead of the temporary cache directory.
+	pub fn with_store(mut self, store: Option<Arc<BlobStore>>) -> Self {
+		self.store = store;
+		self
+	}
+
 	fn not_found() -> endpoint::Response {
 		Box::new(future::ok(ContentHandler::error(
 			StatusCode::NotFound,
@@ -123,10 +181,42 @@ impl<R: URLHint + 'static, F: Fetch> ContentFetcher<F, R> {
 	// resolve contract call synchronously.
 	// TODO: port to futures-based hyper and make it all async.
 	fn resolve(&self, content_id: H256) -> Option<URLHintResult> {
+		if let Some(cid) = self.ipfs_dapps.get(&content_id) {
+			return Some(URLHintResult::GithubDapp(Content {
+				// fetch as a CAR archive, so that every block can be verified
+				url: format!("{}/ipfs/{}?format=car", self.ipfs_gateway, cid),
+				mime: mime_guess::get_mime_type("zip"),
+				owner: Address::default(),
+			}));
+		}
+
 		self.resolver.resolve(content_id)
 			.wait()
 			.unwrap_or_else(|e| { warn!("Error resolving content-id: {}", e); None })
 	}
+
+	/// Installs content kept in the blob store, so that it's not fetched again.
+	/// Stored content is served straight from the store, pinned until it's removed from the cache.
+	fn from_store(&self, content_id: &str, hash: H256, content: &URLHintResult) -> Option<local::Dapp> {
+		let store = self.store.as_ref()?;
+		// bundles fetched from IPFS are not stored
+		if self.ipfs_dapps.contains_key(&hash) {
+			return None;
+		}
+
+		let path = store.pin(&hash)?;
+		trace!(target: "dapps", "Installing {} from blob store", content_id);
+		match *content {
+			URLHintResult::Content(ref content) => {
+				Some(local::Dapp::single_file(self.pool.clone(), path, content.mime.clone(), PageCache::Enabled))
+			},
+			URLHintResult::Dapp(_) | URLHintResult::GithubDapp(_) => {
+				let dapp = installers::Dapp::install_stored(content_id.to_owned(), self.cache_path.clone(), &path, self.pool.clone());
+				store.unpin(&hash);
+				dapp.map_err(|e| warn!(target: "dapps", "Unable to install {} from blob store: {}", content_id, e)).ok()
+			},
+		}
+	}
 }
 
 impl<R: URLHint + 'static, F: Fetch> Fetcher for ContentFetcher<F, R> {
@@ -190,57 +280,72 @@ impl<R: URLHint + 'static, F: Fetch> Endpoint for ContentFetcher<F, R> {
 						Some(URLHintResult::Dapp(_)) if self.only_content => {
 							(None, Self::dapps_disabled())
 						},
-						Some(content) => {
-							let handler = match content {
-								URLHintResult::Dapp(dapp) => {
-									ContentFetcherHandler::new(
-										req.method(),
-										&dapp.url(),
-										path,
-										installers::Dapp::new(
-											content_id.clone(),
-											self.cache_path.clone(),
-											Box::new(on_done),
+						Some(content) => match self.from_store(&content_id, content_hex, &content) {
+							Some(endpoint) => {
+								let handler = endpoint.to_response(&path);
+								(Some(ContentStatus::Ready(endpoint)), handler)
+							},
+							None => {
+								let handler = match content {
+									URLHintResult::Dapp(dapp) => {
+										ContentFetcherHandler::new(
+											req.method(),
+											&dapp.url(),
+											path,
+											installers::Dapp::new(
+												content_id.clone(),
+												self.cache_path.clone(),
+												self.store.clone(),
+												Box::new(on_done),
+												self.pool.clone(),
+											),
+											self.fetch.clone(),
 											self.pool.clone(),
-										),
-										self.fetch.clone(),
-										self.pool.clone(),
-									)
-								},
-							    URLHintResult::GithubDapp(content) => {
-									ContentFetcherHandler::new(
-										req.method(),
-										&content.url,
-										path,
-										installers::Dapp::new(
+										)
+									},
+								    URLHintResult::GithubDapp(content) => {
+										let installer = installers::Dapp::new(
 											content_id.clone(),
 											self.cache_path.clone(),
+											self.store.clone(),
 											Box::new(on_done),
 											self.pool.clone(),
-										),
-										self.fetch.clone(),
-										self.pool.clone(),
-									)
-							    },
-								URLHintResult::Content(content) => {
-									ContentFetcherHandler::new(
-										req.method(),
-										&content.url,
-										path,
-										installers::Content::new(
-											content_id.clone(),
-											content.mime,
-											self.cache_path.clone(),
-											Box::new(on_done),
+										);
+										let installer = match self.ipfs_dapps.get(&content_hex) {
+											Some(cid) => installer.with_ipfs_root(cid.to_cid().expect("IPFS dapps are checked by `ipfs_content_id`; qed")),
+											None => installer,
+										};
+
+										ContentFetcherHandler::new(
+											req.method(),
+											&content.url,
+											path,
+											installer,
+											self.fetch.clone(),
+											self.pool.clone(),
+										)
+								    },
+									URLHintResult::Content(content) => {
+										ContentFetcherHandler::new(
+											req.method(),
+											&content.url,
+											path,
+											installers::Content::new(
+												content_id.clone(),
+												content.mime,
+												self.cache_path.clone(),
+												self.store.clone(),
+												Box::new(on_done),
+												self.pool.clone(),
+											),
+											self.fetch.clone(),
 											self.pool.clone(),
-										),
-										self.fetch.clone(),
-										self.pool.clone(),
-									)
-								},
-							};
-
-							(Some(ContentStatus::Fetching(handler.fetch_control())), Box::new(handler) as endpoint::Response)
+										)
+									},
+								};
+
+								(Some(ContentStatus::Fetching(handler.fetch_control())), Box::new(handler) as endpoint::Response)
+							},
 						},
 						None if self.sync.is_major_importing() => {
 							(None, Self::still_syncing())
@@ -256,7 +361,7 @@ impl<R: URLHint + 'static, F: Fetch> Endpoint for ContentFetcher<F, R> {
 		};
 
 		if let Some(status) = new_status {
-			cache.clear_garbage(MAX_CACHED_DAPPS);
+			cache.clear_garbage(MAX_CACHED_DAPPS, self.store.as_ref().map(|store| &**store));
 			cache.insert(content_id, status);
 		}
 
@@ -276,7 +381,9 @@ mod tests {
 	use apps::cache::ContentStatus;
 	use endpoint::EndpointInfo;
 	use page::local;
-	use super::{ContentFetcher, Fetcher};
+	use cid::ToCid;
+	use hash::keccak;
+	use super::{ContentFetcher, Fetcher, ipfs_content_id};
 	use {SyncStatus};
 
 	#[derive(Clone)]
@@ -292,6 +399,8 @@ mod tests {
 	impl SyncStatus for FakeSync {
 		fn is_major_importing(&self) -> bool { self.0 }
 		fn peers(&self) -> (usize, usize) { (0, 5) }
+		fn blocks_behind(&self) -> Option<u64> { Some(0) }
+		fn disk_space(&self) -> Option<(u64, bool)> { None }
 	}
 
 	#[test]
@@ -326,4 +435,35 @@ mod tests {
 		assert_eq!(fetcher.contains("test2"), true);
 		assert_eq!(fetcher.contains("test3"), false);
 	}
+
+	#[test]
+	fn should_parse_ipfs_content_id() {
+		// `raw` with Keccak-256
+		assert_eq!(
+			ipfs_content_id("zb34WAp1Q5fhtLGZ3w3jhnTWaNbVV5ZZvGq4vuJQzERj6Pu3H"),
+			Ok("c3b5cb6e409f13267b2a15fdf3a78b891b4e7bf876a3574383cdd0cb98d31a54".parse().unwrap())
+		);
+		// `dag-pb` with SHA2-256
+		let cid = "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o";
+		assert_eq!(ipfs_content_id(cid), Ok(keccak(cid.to_cid().unwrap().to_bytes())));
+		// `eth-block` with Keccak-256
+		assert!(ipfs_content_id("z43AaGF5tmkT9SEX6urrhwpEW5ZSaACY73Vw357ZXTsur2fR8BM").is_err());
+		assert!(ipfs_content_id("foo").is_err());
+	}
+
+	#[test]
+	fn should_contain_ipfs_dapps() {
+		// given
+		let pool = ::futures_cpupool::CpuPool::new(1);
+		let fetcher = ContentFetcher::new(
+			FakeResolver,
+			Arc::new(FakeSync(false)),
+			Client::new().unwrap(),
+			pool,
+		).allow_dapps(true).with_ipfs_dapps("https://ipfs.io/".into(), vec!["zb34WAp1Q5fhtLGZ3w3jhnTWaNbVV5ZZvGq4vuJQzERj6Pu3H".into()]);
+
+		// then
+		assert_eq!(fetcher.contains("c3b5cb6e409f13267b2a15fdf3a78b891b4e7bf876a3574383cdd0cb98d31a54"), true);
+		assert_eq!(fetcher.contains("c3b5cb6e409f13267b2a15fdf3a78b891b4e7bf876a3574383cdd0cb98d31a55"), false);
+	}
 }
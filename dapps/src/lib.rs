@@ -18,10 +18,12 @@
 #![warn(missing_docs)]
 
 extern crate base32;
+extern crate cid;
 extern crate futures_cpupool;
 extern crate itertools;
 extern crate linked_hash_map;
 extern crate mime_guess;
+extern crate multihash;
 extern crate parking_lot;
 extern crate rand;
 extern crate rustc_hex;
@@ -83,6 +85,7 @@ use node_health::NodeHealth;
 pub use registrar::{RegistrarClient, Asynchronous};
 pub use node_health::SyncStatus;
 pub use page::builtin::Dapp;
+pub use apps::fetcher::ipfs_content_id;
 
 /// Validates Web Proxy tokens
 pub trait WebProxyTokens: Send + Sync {
@@ -160,13 +163,16 @@ impl Middleware {
 		sync_status: Arc<SyncStatus>,
 		web_proxy_tokens: Arc<WebProxyTokens>,
 		fetch: F,
+		ipfs_gateway: String,
+		ipfs_dapps: Vec<String>,
+		blob_store: Option<Arc<hash_fetch::BlobStore>>,
 	) -> Self {
 		let content_fetcher = Arc::new(apps::fetcher::ContentFetcher::new(
 			hash_fetch::urlhint::URLHintContract::new(registrar),
 			sync_status.clone(),
 			fetch.clone(),
 			pool.clone(),
-		).allow_dapps(true));
+		).allow_dapps(true).with_ipfs_dapps(ipfs_gateway, ipfs_dapps).with_store(blob_store));
 		let (local_endpoints, endpoints) = apps::all_endpoints(
 			dapps_path.clone(),
 			extra_dapps,
@@ -213,6 +219,7 @@ fn special_endpoints(
 ) -> HashMap<router::SpecialEndpoint, Option<Box<endpoint::Endpoint>>> {
 	let mut special = HashMap::new();
 	special.insert(router::SpecialEndpoint::Rpc, None);
+	special.insert(router::SpecialEndpoint::Health, Some(api::HealthApi::new(health.clone())));
 	special.insert(router::SpecialEndpoint::Api, Some(api::RestApi::new(
 		content_fetcher,
 		health,
@@ -35,6 +35,7 @@ use handlers;
 pub enum SpecialEndpoint {
 	Rpc,
 	Api,
+	Health,
 	Home,
 	None,
 }
@@ -242,6 +243,7 @@ fn extract_endpoint(url: &Uri, extra_host: Option<&header::Host>, dapps_domain:
 		match path[0].as_ref() {
 			apps::RPC_PATH => SpecialEndpoint::Rpc,
 			apps::API_PATH => SpecialEndpoint::Api,
+			apps::HEALTH_PATH => SpecialEndpoint::Health,
 			apps::HOME_PAGE => SpecialEndpoint::Home,
 			_ => SpecialEndpoint::None,
 		}
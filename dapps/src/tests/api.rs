@@ -63,6 +63,48 @@ fn should_handle_ping() {
 	assert_security_headers(&response.headers);
 }
 
+#[test]
+fn should_report_liveness() {
+	// given
+	let server = serve();
+
+	// when
+	let response = request(server,
+		"\
+			GET /health/live HTTP/1.1\r\n\
+			Host: 127.0.0.1:8080\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	response.assert_status("HTTP/1.1 200 OK");
+	response.assert_header("Content-Type", "application/json");
+	assert_eq!(response.body, format!("0E\n{}\n0\n\n", r#"{"alive":true}"#));
+}
+
+#[test]
+fn should_report_not_ready_without_peers() {
+	// given
+	let server = serve();
+
+	// when
+	let response = request(server,
+		"\
+			GET /health/ready HTTP/1.1\r\n\
+			Host: 127.0.0.1:8080\r\n\
+			Connection: close\r\n\
+			\r\n\
+		"
+	);
+
+	// then
+	response.assert_status("HTTP/1.1 503 Service Unavailable");
+	response.assert_header("Content-Type", "application/json");
+	assert_eq!(response.body, format!("2A\n{}\n0\n\n", r#"{"ready":false,"blocksBehind":0,"peers":0}"#));
+}
+
 #[test]
 fn should_try_to_resolve_dapp() {
 	// given
@@ -41,6 +41,8 @@ struct FakeSync(bool);
 impl SyncStatus for FakeSync {
 	fn is_major_importing(&self) -> bool { self.0 }
 	fn peers(&self) -> (usize, usize) { (0, 5) }
+	fn blocks_behind(&self) -> Option<u64> { Some(0) }
+	fn disk_space(&self) -> Option<(u64, bool)> { None }
 }
 
 fn init_logger() {
@@ -206,6 +208,7 @@ impl Server {
 			sync_status.clone(),
 			TimeChecker::new::<String>(&[], CpuPool::new(1)),
 			remote.clone(),
+			Default::default(),
 		);
 		let pool = ::futures_cpupool::CpuPool::new(1);
 		let middleware =
@@ -219,6 +222,9 @@ impl Server {
 				sync_status,
 				web_proxy_tokens,
 				fetch,
+				"https://ipfs.io".into(),
+				Vec::new(),
+				None,
 			);
 
 		let mut allowed_hosts: Option<Vec<Host>> = allowed_hosts.into();
@@ -644,6 +644,8 @@ impl HeaderChain {
 			BlockId::Latest => {
 				Some(self.best_block.read().hash)
 			}
+			// resolved by the client, which knows the engine's finality rule.
+			BlockId::Finalized => None,
 		}
 	}
 
@@ -698,6 +700,7 @@ impl HeaderChain {
 
 				load_from_db(hash)
 			}
+			BlockId::Finalized => None,
 		}
 	}
 
@@ -719,7 +722,8 @@ impl HeaderChain {
 				if self.best_block.read().number < num { return None }
 				candidates.get(&num).map(|era| era.candidates[0].total_difficulty)
 			}
-			BlockId::Latest => Some(self.best_block.read().total_difficulty)
+			BlockId::Latest => Some(self.best_block.read().total_difficulty),
+			BlockId::Finalized => None,
 		}
 	}
 
@@ -19,7 +19,7 @@
 use std::sync::{Weak, Arc};
 
 use ethcore::block_status::BlockStatus;
-use ethcore::client::{ClientReport, EnvInfo, ClientIoMessage};
+use ethcore::client::{ClientReport, EnvInfo, ClientIoMessage, FinalityRule, FinalityStatus};
 use ethcore::engines::{epoch, EthEngine, EpochChange, EpochTransition, Proof};
 use ethcore::machine::EthereumMachine;
 use ethcore::error::{Error, BlockImportError};
@@ -61,6 +61,8 @@ pub struct Config {
 	pub check_seal: bool,
 	/// Disable hardcoded sync.
 	pub no_hardcoded_sync: bool,
+	/// Number of confirmations after which a block is considered final.
+	pub finality_depth: u64,
 }
 
 impl Default for Config {
@@ -71,6 +73,7 @@ impl Default for Config {
 			verify_full: true,
 			check_seal: true,
 			no_hardcoded_sync: false,
+			finality_depth: 12,
 		}
 	}
 }
@@ -132,6 +135,9 @@ pub trait LightChainClient: Send + Sync {
 
 	/// Get a report of import activity since the last call.
 	fn report(&self) -> ClientReport;
+
+	/// Get the finality of the chain.
+	fn finality_status(&self) -> FinalityStatus;
 }
 
 /// An actor listening to light chain events.
@@ -163,9 +169,11 @@ pub struct Client<T> {
 	report: RwLock<ClientReport>,
 	import_lock: Mutex<()>,
 	db: Arc<KeyValueDB>,
+	chain_col: Option<u32>,
 	listeners: RwLock<Vec<Weak<LightChainNotify>>>,
 	fetcher: T,
 	verify_full: bool,
+	finality_depth: u64,
 }
 
 impl<T: ChainDataFetcher> Client<T> {
@@ -189,9 +197,11 @@ impl<T: ChainDataFetcher> Client<T> {
 			report: RwLock::new(ClientReport::default()),
 			import_lock: Mutex::new(()),
 			db: db,
+			chain_col: chain_col,
 			listeners: RwLock::new(vec![]),
 			fetcher: fetcher,
 			verify_full: config.verify_full,
+			finality_depth: config.finality_depth,
 		})
 	}
 
@@ -250,12 +260,46 @@ impl<T: ChainDataFetcher> Client<T> {
 
 	/// Attempt to get a block hash by block id.
 	pub fn block_hash(&self, id: BlockId) -> Option<H256> {
-		self.chain.block_hash(id)
+		self.chain.block_hash(self.resolve_finalized(id))
 	}
 
 	/// Get a block header by Id.
 	pub fn block_header(&self, id: BlockId) -> Option<encoded::Header> {
-		self.chain.block_header(id)
+		self.chain.block_header(self.resolve_finalized(id))
+	}
+
+	/// Get the finality of the chain.
+	///
+	/// Headers don't carry the marks of engines finalizing blocks themselves, so those
+	/// fall back to the configured number of confirmations, like engines without a notion of finality.
+	pub fn finality_status(&self) -> FinalityStatus {
+		let best = self.chain.best_block();
+		let (rule, finalized_number) = match self.engine.finality_rule() {
+			FinalityRule::Instant => (FinalityRule::Instant, best.number),
+			_ => (FinalityRule::Depth, best.number.saturating_sub(self.finality_depth)),
+		};
+		// headers preceding hardcoded sync aren't stored, so finality can't be told below them.
+		let finalized_hash = self.chain.block_hash(BlockId::Number(finalized_number))
+			.unwrap_or_else(|| self.chain.genesis_hash());
+
+		FinalityStatus {
+			rule: rule,
+			depth: match rule {
+				FinalityRule::Depth => Some(self.finality_depth),
+				_ => None,
+			},
+			best_number: best.number,
+			best_hash: best.hash,
+			finalized_number: if finalized_hash == self.chain.genesis_hash() { 0 } else { finalized_number },
+			finalized_hash: finalized_hash,
+		}
+	}
+
+	fn resolve_finalized(&self, id: BlockId) -> BlockId {
+		match id {
+			BlockId::Finalized => BlockId::Hash(self.finality_status().finalized_hash),
+			id => id,
+		}
 	}
 
 	/// Get the best block header.
@@ -265,12 +309,12 @@ impl<T: ChainDataFetcher> Client<T> {
 
 	/// Get a block's chain score.
 	pub fn score(&self, id: BlockId) -> Option<U256> {
-		self.chain.score(id)
+		self.chain.score(self.resolve_finalized(id))
 	}
 
 	/// Get an iterator over a block and its ancestry.
 	pub fn ancestry_iter(&self, start: BlockId) -> AncestryIter {
-		self.chain.ancestry_iter(start)
+		self.chain.ancestry_iter(self.resolve_finalized(start))
 	}
 
 	/// Get the signing chain id.
@@ -589,6 +633,10 @@ impl<T: ChainDataFetcher> LightChainClient for Client<T> {
 		self.engine().params().eip86_transition
 	}
 
+	fn finality_status(&self) -> FinalityStatus {
+		Client::finality_status(self)
+	}
+
 	fn report(&self) -> ClientReport {
 		Client::report(self)
 	}
@@ -624,4 +672,25 @@ impl<T: ChainDataFetcher> ::ethcore::client::EngineClient for Client<T> {
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header> {
 		Client::block_header(self, id)
 	}
+
+	fn engine_data(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.db.get(self.chain_col, &engine_data_key(key))
+			.expect("Low level database error. Some issue with disk?")
+			.map(|value| value.into_vec())
+	}
+
+	fn set_engine_data(&self, key: &[u8], value: Vec<u8>) {
+		let mut batch = self.db.transaction();
+		batch.put_vec(self.chain_col, &engine_data_key(key), value);
+		if let Err(e) = self.db.write(batch) {
+			warn!(target: "client", "Failed to persist engine data: {}", e);
+		}
+	}
+}
+
+/// Key of engine data in the chain column, prefixed to stay clear of the header chain entries.
+fn engine_data_key(key: &[u8]) -> Vec<u8> {
+	let mut prefixed = b"engine:".to_vec();
+	prefixed.extend_from_slice(key);
+	prefixed
 }
@@ -37,12 +37,17 @@ extern crate tempdir;
 #[macro_use]
 extern crate log;
 
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::Weak;
+use std::time::Duration;
 
 use lru_cache::LruCache;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 
-use ethcore::client::{BlockChainClient, BlockId};
+use ethcore::client::{BlockChainClient, BlockId, ChainNotify, ChainRoute};
 use ethereum_types::{H256, Address};
 use network::{ConnectionFilter, ConnectionDirection};
 use devp2p::NodeId;
@@ -51,12 +56,40 @@ use_contract!(peer_set, "PeerSet", "res/peer_set.json");
 
 const MAX_CACHE_SIZE: usize = 4096;
 
+/// Where a connection decision comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+	/// The permissions contract.
+	Contract,
+	/// The local override file.
+	Override,
+}
+
+/// Connection decision for a node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Permission {
+	/// Node id.
+	pub node: NodeId,
+	/// Whether connections with the node are allowed.
+	pub allowed: bool,
+	/// Where the decision comes from.
+	pub source: Source,
+	/// Hash of the block the decision was made at.
+	pub block: H256,
+}
+
 /// Connection filter that uses a contract to manage permissions.
+///
+/// Decisions are made against the latest block and dropped whenever a new block is imported,
+/// so changes to the contract take effect immediately. Entries of the local override file
+/// take precedence over the contract.
 pub struct NodeFilter {
 	contract: peer_set::PeerSet,
 	client: Weak<BlockChainClient>,
 	contract_address: Address,
-	permission_cache: Mutex<LruCache<(H256, NodeId), bool>>,
+	overrides_path: Option<PathBuf>,
+	overrides: RwLock<HashMap<NodeId, bool>>,
+	permission_cache: Mutex<LruCache<NodeId, Permission>>,
 }
 
 impl NodeFilter {
@@ -66,13 +99,86 @@ impl NodeFilter {
 			contract: peer_set::PeerSet::default(),
 			client,
 			contract_address,
+			overrides_path: None,
+			overrides: RwLock::new(HashMap::new()),
 			permission_cache: Mutex::new(LruCache::new(MAX_CACHE_SIZE)),
 		}
 	}
+
+	/// Use a local file of `allow <node id>` and `deny <node id>` lines to override the contract.
+	/// The file is read again whenever a new block is imported.
+	pub fn with_overrides(mut self, path: PathBuf) -> Result<NodeFilter, String> {
+		*self.overrides.get_mut() = read_overrides(&path)?;
+		self.overrides_path = Some(path);
+		Ok(self)
+	}
+
+	/// Decisions made at the latest block, including all override entries.
+	pub fn permissions(&self) -> Vec<Permission> {
+		let block = match self.client.upgrade().and_then(|client| client.block_hash(BlockId::Latest)) {
+			Some(block) => block,
+			None => return Vec::new(),
+		};
+
+		let overrides = self.overrides.read();
+		let mut permissions: Vec<_> = overrides.iter()
+			.map(|(node, allowed)| Permission { node: *node, allowed: *allowed, source: Source::Override, block })
+			.collect();
+		permissions.extend(self.permission_cache.lock().iter()
+			.filter(|&(node, permission)| permission.block == block && !overrides.contains_key(node))
+			.map(|(_, permission)| permission.clone()));
+		permissions.sort_by_key(|permission| permission.node);
+		permissions
+	}
+
+	fn reload_overrides(&self) {
+		if let Some(ref path) = self.overrides_path {
+			match read_overrides(path) {
+				Ok(overrides) => *self.overrides.write() = overrides,
+				Err(e) => warn!(target: "network", "Keeping previous node permission overrides: {}", e),
+			}
+		}
+	}
+}
+
+/// Parses the override file. Empty lines and lines starting with `#` are ignored.
+/// Node ids may also be given as enode URLs.
+fn read_overrides(path: &Path) -> Result<HashMap<NodeId, bool>, String> {
+	let mut content = String::new();
+	fs::File::open(path).and_then(|mut f| f.read_to_string(&mut content))
+		.map_err(|e| format!("Unable to read node permission overrides from {}: {}", path.display(), e))?;
+
+	let mut overrides = HashMap::new();
+	for (number, line) in content.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		let invalid = || format!("Invalid node permission override at {}:{}: {}", path.display(), number + 1, line);
+		let mut parts = line.split_whitespace();
+		let allowed = match parts.next() {
+			Some("allow") => true,
+			Some("deny") => false,
+			_ => return Err(invalid()),
+		};
+		let node = match (parts.next(), parts.next()) {
+			(Some(node), None) => node,
+			_ => return Err(invalid()),
+		};
+		let node = node.trim_left_matches("enode://").split('@').next().unwrap_or("").trim_left_matches("0x");
+		let node: NodeId = node.parse().map_err(|_| invalid())?;
+		overrides.insert(node, allowed);
+	}
+	Ok(overrides)
 }
 
 impl ConnectionFilter for NodeFilter {
 	fn connection_allowed(&self, own_id: &NodeId, connecting_id: &NodeId, _direction: ConnectionDirection) -> bool {
+		if let Some(allowed) = self.overrides.read().get(connecting_id) {
+			return *allowed;
+		}
+
 		let client = match self.client.upgrade() {
 			Some(client) => client,
 			None => return false,
@@ -83,11 +189,11 @@ impl ConnectionFilter for NodeFilter {
 			None => return false,
 		};
 
-		let key = (block_hash, *connecting_id);
-
 		let mut cache = self.permission_cache.lock();
-		if let Some(res) = cache.get_mut(&key) {
-			return *res;
+		if let Some(permission) = cache.get_mut(connecting_id) {
+			if permission.block == block_hash {
+				return permission.allowed;
+			}
 		}
 
 		let address = self.contract_address;
@@ -98,17 +204,33 @@ impl ConnectionFilter for NodeFilter {
 
 		let allowed = self.contract.functions()
 			.connection_allowed()
-			.call(own_low, own_high, id_low, id_high, &|data| client.call_contract(BlockId::Latest, address, data))
+			.call(own_low, own_high, id_low, id_high, &|data| client.call_contract(BlockId::Hash(block_hash), address, data))
 			.unwrap_or_else(|e| {
 				debug!("Error callling peer set contract: {:?}", e);
 				false
 			});
 
-		cache.insert(key, allowed);
+		cache.insert(*connecting_id, Permission {
+			node: *connecting_id,
+			allowed,
+			source: Source::Contract,
+			block: block_hash,
+		});
 		allowed
 	}
 }
 
+impl ChainNotify for NodeFilter {
+	fn new_blocks(&self, imported: Vec<H256>, _invalid: Vec<H256>, _route: ChainRoute, _sealed: Vec<H256>, _proposed: Vec<Vec<u8>>, _duration: Duration) {
+		if imported.is_empty() {
+			return;
+		}
+
+		self.permission_cache.lock().clear();
+		self.reload_overrides();
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use std::sync::{Arc, Weak};
@@ -116,9 +238,11 @@ mod test {
 	use ethcore::client::{BlockChainClient, Client, ClientConfig};
 	use ethcore::miner::Miner;
 	use ethcore::test_helpers;
+	use std::fs::File;
+	use std::io::Write;
 	use network::{ConnectionDirection, ConnectionFilter, NodeId};
 	use io::IoChannel;
-	use super::NodeFilter;
+	use super::{NodeFilter, Source};
 	use tempdir::TempDir;
 
 	/// Contract code: https://gist.github.com/arkpar/467dbcc73cbb85b0997a7a10ffa0695f
@@ -149,4 +273,45 @@ mod test {
 		assert!(filter.connection_allowed(&self2, &node1, ConnectionDirection::Inbound));
 		assert!(filter.connection_allowed(&self2, &node2, ConnectionDirection::Inbound));
 	}
+
+	#[test]
+	fn node_filter_overrides() {
+		let contract_addr = "0000000000000000000000000000000000000005".into();
+		let data = include_bytes!("../res/node_filter.json");
+		let tempdir = TempDir::new("").unwrap();
+		let spec = Spec::load(&tempdir.path(), &data[..]).unwrap();
+		let client_db = test_helpers::new_db();
+
+		let client = Client::new(
+			ClientConfig::default(),
+			&spec,
+			client_db,
+			Arc::new(Miner::new_for_tests(&spec, None)),
+			IoChannel::disconnected(),
+		).unwrap();
+
+		let path = tempdir.path().join("overrides");
+		File::create(&path).unwrap().write_all(b"# emergency entries\n\ndeny 0x00000000000000000000000000000000000000000000000000000000000000110000000000000000000000000000000000000000000000000000000000000012\n").unwrap();
+		let filter = NodeFilter::new(Arc::downgrade(&client) as Weak<BlockChainClient>, contract_addr)
+			.with_overrides(path.clone())
+			.unwrap();
+		let self1: NodeId = "00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002".into();
+		let node1: NodeId = "00000000000000000000000000000000000000000000000000000000000000110000000000000000000000000000000000000000000000000000000000000012".into();
+		let node2: NodeId = "00000000000000000000000000000000000000000000000000000000000000210000000000000000000000000000000000000000000000000000000000000022".into();
+
+		assert!(!filter.connection_allowed(&self1, &node1, ConnectionDirection::Inbound));
+		assert!(filter.connection_allowed(&self1, &node2, ConnectionDirection::Inbound));
+
+		let permissions = filter.permissions();
+		assert_eq!(permissions.len(), 2);
+		assert_eq!((permissions[0].node, permissions[0].allowed, permissions[0].source), (node1, false, Source::Override));
+		assert_eq!((permissions[1].node, permissions[1].allowed, permissions[1].source), (node2, true, Source::Contract));
+
+		File::create(&path).unwrap().write_all(b"allow enode://00000000000000000000000000000000000000000000000000000000000000110000000000000000000000000000000000000000000000000000000000000012@127.0.0.1:30303\n").unwrap();
+		filter.reload_overrides();
+		assert!(filter.connection_allowed(&self1, &node1, ConnectionDirection::Inbound));
+
+		File::create(&path).unwrap().write_all(b"block 0x01\n").unwrap();
+		assert!(NodeFilter::new(Arc::downgrade(&client) as Weak<BlockChainClient>, contract_addr).with_overrides(path).is_err());
+	}
 }
@@ -66,6 +66,24 @@ error_chain! {
 			display("State is not available"),
 		}
 
+		#[doc = "Block is not known."]
+		BlockNotFound {
+			description("Block is not known."),
+			display("Block is not known"),
+		}
+
+		#[doc = "Private state was not requested."]
+		UnexpectedPrivateState {
+			description("Private state was not requested."),
+			display("Private state was not requested"),
+		}
+
+		#[doc = "Private state doesn't match the state of the block."]
+		InvalidPrivateStateProof {
+			description("Private state doesn't match the state of the block."),
+			display("Private state doesn't match the state of the block"),
+		}
+
 		#[doc = "State is incorrect."]
 		StateIncorrect {
 			description("State is incorrect."),
@@ -120,6 +138,24 @@ error_chain! {
 			display("Account for validating private transactions not set."),
 		}
 
+		#[doc = "Private contract must have at least one validator."]
+		NoValidators {
+			description("Private contract must have at least one validator."),
+			display("Private contract must have at least one validator."),
+		}
+
+		#[doc = "Private contract has been retired in favour of its successor."]
+		ContractRetired(successor: Address) {
+			description("Private contract has been retired."),
+			display("Private contract has been retired, its successor is {}", successor),
+		}
+
+		#[doc = "Successor contract doesn't carry the code and the state of the retired contract."]
+		InvalidSuccessor(successor: Address) {
+			description("Successor contract doesn't carry the code and the state of the retired contract."),
+			display("Contract {} doesn't carry the code and the state of the retired contract", successor),
+		}
+
 		#[doc = "Account for signing requests to key server not set."]
 		KeyServerAccountNotSet {
 			description("Account for signing requests to key server not set."),
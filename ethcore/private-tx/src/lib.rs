@@ -15,6 +15,12 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Private transactions module.
+//!
+//! Encrypted code and state of private contracts are kept on-chain in the public
+//! contract (see `res/private.json`) and decrypted with the key from the key server.
+//! A validator which doesn't have the public state of a block may request the private
+//! state from its peers (`Provider::request_private_state`); responses carry proofs of
+//! the contract calls reading it and are checked against the state root of the block.
 
 // Recursion limit required because of
 // error_chain foreign_links.
@@ -38,6 +44,7 @@ extern crate ethjson;
 extern crate fetch;
 extern crate futures;
 extern crate keccak_hash as hash;
+extern crate kvdb;
 extern crate parking_lot;
 extern crate patricia_trie as trie;
 extern crate rlp;
@@ -60,8 +67,9 @@ extern crate rand;
 extern crate ethcore_logger;
 
 pub use encryptor::{Encryptor, SecretStoreEncryptor, EncryptorConfig, NoopEncryptor};
-pub use private_transactions::{PrivateTransactionDesc, VerificationStore, PrivateTransactionSigningDesc, SigningStore};
-pub use messages::{PrivateTransaction, SignedPrivateTransaction};
+pub use private_transactions::{PrivateTransactionDesc, VerificationStore, PrivateTransactionSigningDesc, SigningStore,
+	PrivateTransactionStatus, PrivateTransactionState};
+pub use messages::{PrivateTransaction, SignedPrivateTransaction, PrivateStateRequest, PrivateStateResponse};
 pub use error::{Error, ErrorKind};
 
 use std::sync::{Arc, Weak};
@@ -76,16 +84,19 @@ use ethkey::{Signature, recover, public_to_address};
 use io::IoChannel;
 use ethcore::executive::{Executive, TransactOptions};
 use ethcore::executed::{Executed};
+use ethcore::state::{check_proof, ProvedExecution};
 use transaction::{SignedTransaction, Transaction, Action, UnverifiedTransaction};
 use ethcore::{contract_address as ethcore_contract_address};
 use ethcore::client::{
-	Client, ChainNotify, ChainRoute, ChainMessageType, ClientIoMessage, BlockId, CallContract
+	Client, ChainNotify, ChainRoute, ChainMessageType, ClientIoMessage, BlockId, CallContract,
+	BlockChainClient, BlockInfo, Nonce, ProvingBlockChainClient,
 };
 use ethcore::account_provider::AccountProvider;
 use ethcore::miner::{self, Miner, MinerService};
 use ethcore::trace::{Tracer, VMTracer};
 use rustc_hex::FromHex;
 use ethkey::Password;
+use kvdb::DBValue;
 
 // Source avaiable at https://github.com/parity-contracts/private-tx/blob/master/contracts/PrivateContract.sol
 const DEFAULT_STUB_CONTRACT: &'static str = include_str!("../res/private.evm");
@@ -95,6 +106,13 @@ use_contract!(private, "PrivateContract", "res/private.json");
 /// Initialization vector length.
 const INIT_VEC_LEN: usize = 16;
 
+/// Gas of the calls reading code and state of the private contract for peers.
+const PRIVATE_STATE_CALL_GAS: u64 = 50_000_000;
+
+/// Marks both the private transaction retiring a contract (followed by the successor address
+/// in the transaction data) and the final private state of the retired contract.
+const RETIREMENT_MARKER: [u8; 32] = *b"parity-private-contract-retired!";
+
 /// Configurtion for private transaction provider
 #[derive(Default, PartialEq, Debug, Clone)]
 pub struct ProviderConfig {
@@ -131,6 +149,10 @@ pub struct Provider {
 	miner: Arc<Miner>,
 	accounts: Arc<AccountProvider>,
 	channel: IoChannel<ClientIoMessage>,
+	/// Contracts and blocks, private state of which is requested from peers
+	private_state_requests: Mutex<HashSet<(Address, H256)>>,
+	/// Encrypted code and state of contracts at blocks, received from peers
+	synced_private_states: RwLock<HashMap<(Address, H256), (Bytes, Bytes)>>,
 }
 
 #[derive(Debug)]
@@ -163,6 +185,8 @@ impl Provider where {
 			miner,
 			accounts,
 			channel,
+			private_state_requests: Mutex::default(),
+			synced_private_states: RwLock::default(),
 		}
 	}
 
@@ -299,17 +323,23 @@ impl Provider where {
 		Ok(())
 	}
 
-	fn last_required_signature(&self, desc: &PrivateTransactionSigningDesc, sign: Signature) -> Result<bool, Error>  {
-		if desc.received_signatures.contains(&sign) {
-			return Ok(false);
-		}
+	/// Returns status of private transaction created by this node, by the hash of the original transaction.
+	pub fn private_transaction_status(&self, transaction_hash: &H256) -> Option<PrivateTransactionStatus> {
+		self.transactions_for_signing.lock().status(transaction_hash)
+	}
+
+	/// Returns validator, who has signed the state, and whether this is the last required signature.
+	fn last_required_signature(&self, desc: &PrivateTransactionSigningDesc, sign: Signature) -> Result<(Address, bool), Error>  {
 		let state_hash = self.calculate_state_hash(&desc.state, desc.contract_nonce);
 		match recover(&sign, &state_hash) {
 			Ok(public) => {
 				let sender = public_to_address(&public);
+				if desc.received_signatures.contains(&sign) {
+					return Ok((sender, false));
+				}
 				match desc.validators.contains(&sender) {
 					true => {
-						Ok(desc.received_signatures.len() + 1 == desc.validators.len())
+						Ok((sender, desc.received_signatures.len() + 1 == desc.validators.len()))
 					}
 					false => {
 						trace!("Sender's state doesn't correspond to validator's");
@@ -335,7 +365,11 @@ impl Provider where {
 	}
 
 	fn iv_from_transaction(transaction: &SignedTransaction) -> H128 {
-		let nonce = keccak(&transaction.nonce.rlp_bytes());
+		Self::iv_from_nonce(&transaction.nonce)
+	}
+
+	fn iv_from_nonce(nonce: &U256) -> H128 {
+		let nonce = keccak(&nonce.rlp_bytes());
 		let (iv, _) = nonce.split_at(INIT_VEC_LEN);
 		H128::from_slice(iv)
 	}
@@ -358,24 +392,132 @@ impl Provider where {
 
 	fn get_decrypted_state(&self, address: &Address, block: BlockId) -> Result<Bytes, Error> {
 		let contract = private::PrivateContract::default();
-		let state = contract.functions()
-			.state()
-			.call(&|data| self.client.call_contract(block, *address, data))
-			.map_err(|e| ErrorKind::Call(format!("Contract call failed {:?}", e)))?;
+		let state = match contract.functions().state().call(&|data| self.client.call_contract(block, *address, data)) {
+			Ok(state) => state,
+			Err(e) => match self.synced_private_state_at(address, block) {
+				Some((_, state)) => state,
+				None => bail!(ErrorKind::Call(format!("Contract call failed {:?}", e))),
+			},
+		};
 
-		self.decrypt(address, &state)
+		let state = self.decrypt(address, &state)?;
+		if let Some(successor) = Self::retirement_successor(&state) {
+			bail!(ErrorKind::ContractRetired(successor));
+		}
+		Ok(state)
 	}
 
 	fn get_decrypted_code(&self, address: &Address, block: BlockId) -> Result<Bytes, Error> {
 		let contract = private::PrivateContract::default();
-		let code = contract.functions()
-			.code()
-			.call(&|data| self.client.call_contract(block, *address, data))
-			.map_err(|e| ErrorKind::Call(format!("Contract call failed {:?}", e)))?;
+		let code = match contract.functions().code().call(&|data| self.client.call_contract(block, *address, data)) {
+			Ok(code) => code,
+			Err(e) => match self.synced_private_state_at(address, block) {
+				Some((code, _)) => code,
+				None => bail!(ErrorKind::Call(format!("Contract call failed {:?}", e))),
+			},
+		};
 
 		self.decrypt(address, &code)
 	}
 
+	/// Request encrypted code and state of the private contract at the given block from peers.
+	/// Lets a validator, which doesn't have the public state of the block, obtain the private state
+	/// without copying it out-of-band. Responses are checked against the state root of the block.
+	pub fn request_private_state(&self, contract: &Address, block: BlockId) -> Result<(), Error> {
+		let block_hash = self.client.block_hash(block).ok_or(ErrorKind::BlockNotFound)?;
+		trace!("Requesting private state of {:?} at {:?}", contract, block_hash);
+		self.private_state_requests.lock().insert((*contract, block_hash));
+		let request = PrivateStateRequest {
+			contract: *contract,
+			block_hash,
+		};
+		let message = request.rlp_bytes().into_vec();
+		self.notify(|notify| notify.broadcast(ChainMessageType::PrivateStateRequest(message.clone())));
+		Ok(())
+	}
+
+	/// Encrypted code and state of the private contract at the given block, received from peers.
+	pub fn synced_private_state(&self, contract: &Address, block_hash: &H256) -> Option<(Bytes, Bytes)> {
+		self.synced_private_states.read().get(&(*contract, *block_hash)).cloned()
+	}
+
+	fn synced_private_state_at(&self, contract: &Address, block: BlockId) -> Option<(Bytes, Bytes)> {
+		self.client.block_hash(block).and_then(|hash| self.synced_private_state(contract, &hash))
+	}
+
+	fn private_state_call(contract: &Address, data: Bytes, caller_nonce: U256) -> SignedTransaction {
+		Transaction {
+			nonce: caller_nonce,
+			action: Action::Call(*contract),
+			gas: PRIVATE_STATE_CALL_GAS.into(),
+			gas_price: U256::zero(),
+			value: U256::zero(),
+			data,
+		}.fake_sign(Address::default())
+	}
+
+	/// Returns the output of the call to the public contract, if it is proved by the state of the block.
+	fn check_private_state_call(&self, response: &PrivateStateResponse, data: Bytes, proof: &[Bytes]) -> Result<Bytes, Error> {
+		let block = BlockId::Hash(response.block_hash);
+		let header = self.client.block_header(block).ok_or(ErrorKind::BlockNotFound)?;
+		let mut env_info = self.client.env_info(block).ok_or(ErrorKind::BlockNotFound)?;
+		let transaction = Self::private_state_call(&response.contract, data, response.caller_nonce);
+		env_info.gas_limit = transaction.gas;
+		let proof: Vec<DBValue> = proof.iter().map(|node| DBValue::from_slice(node)).collect();
+		match check_proof(&proof, header.state_root(), &transaction, self.client.engine().machine(), &env_info) {
+			ProvedExecution::Complete(executed) => Ok(executed.output),
+			_ => bail!(ErrorKind::InvalidPrivateStateProof),
+		}
+	}
+
+	/// Respond to the private state request of a peer. Returns `None` if the state of the block is not available.
+	/// Code and state are kept encrypted in the public contract, so the response doesn't reveal anything
+	/// which is not on-chain already.
+	pub fn private_state_request(&self, rlp: &[u8]) -> Result<Option<Bytes>, Error> {
+		let request: PrivateStateRequest = Rlp::new(rlp).as_val()?;
+		trace!("Private state of {:?} at {:?} requested", request.contract, request.block_hash);
+		let block = BlockId::Hash(request.block_hash);
+		let caller_nonce = match self.client.nonce(&Address::default(), block) {
+			Some(nonce) => nonce,
+			None => return Ok(None),
+		};
+
+		let contract = private::PrivateContract::default();
+		let code_call = Self::private_state_call(&request.contract, contract.functions().code().input(), caller_nonce);
+		let state_call = Self::private_state_call(&request.contract, contract.functions().state().input(), caller_nonce);
+		match (self.client.prove_transaction(code_call, block), self.client.prove_transaction(state_call, block)) {
+			(Some((_, code_proof)), Some((_, state_proof))) => Ok(Some(PrivateStateResponse {
+				contract: request.contract,
+				block_hash: request.block_hash,
+				caller_nonce,
+				code_proof: code_proof.into_iter().map(|node| node.into_vec()).collect(),
+				state_proof: state_proof.into_iter().map(|node| node.into_vec()).collect(),
+			}.rlp_bytes().into_vec())),
+			_ => Ok(None),
+		}
+	}
+
+	/// Import private state received from a peer. Only requested states are accepted and only
+	/// after they are proved by the state root of the block.
+	pub fn import_private_state(&self, rlp: &[u8]) -> Result<(), Error> {
+		let response: PrivateStateResponse = Rlp::new(rlp).as_val()?;
+		let key = (response.contract, response.block_hash);
+		if !self.private_state_requests.lock().contains(&key) {
+			bail!(ErrorKind::UnexpectedPrivateState);
+		}
+
+		let contract = private::PrivateContract::default();
+		let code = self.check_private_state_call(&response, contract.functions().code().input(), &response.code_proof)?;
+		let code = contract.functions().code().output(&code).map_err(|_| ErrorKind::InvalidPrivateStateProof)?;
+		let state = self.check_private_state_call(&response, contract.functions().state().input(), &response.state_proof)?;
+		let state = contract.functions().state().output(&state).map_err(|_| ErrorKind::InvalidPrivateStateProof)?;
+
+		trace!("Private state of {:?} at {:?} synced", response.contract, response.block_hash);
+		self.private_state_requests.lock().remove(&key);
+		self.synced_private_states.write().insert(key, (code, state));
+		Ok(())
+	}
+
 	pub fn get_contract_nonce(&self, address: &Address, block: BlockId) -> Result<U256, Error> {
 		let contract = private::PrivateContract::default();
 		Ok(contract.functions()
@@ -452,6 +594,39 @@ impl Provider where {
 		})
 	}
 
+	/// Returns the successor address if the data is a retirement marker.
+	fn retirement_successor(data: &[u8]) -> Option<Address> {
+		let marker_len = RETIREMENT_MARKER.len();
+		match data.len() == marker_len + 20 && data[..marker_len] == RETIREMENT_MARKER[..] {
+			true => Some(Address::from_slice(&data[marker_len..])),
+			false => None,
+		}
+	}
+
+	/// Data of the private transaction retiring the contract in favour of the successor.
+	/// Private transaction with this data must be sent to the retired contract by one of its validators
+	/// once the successor is deployed (see `public_rotation_transaction`).
+	pub fn retirement_transaction_data(successor: &Address) -> Bytes {
+		let mut data = RETIREMENT_MARKER.to_vec();
+		data.extend_from_slice(successor);
+		data
+	}
+
+	/// Final encrypted state of the retired contract. Validators only agree on it once the successor
+	/// carries exactly the code and the state of the retired contract.
+	fn retirement_state(&self, block: BlockId, contract_address: &Address, source: &SignedTransaction, successor: &Address) -> Result<Bytes, Error> {
+		let sender = source.sender();
+		if !self.get_validators(block, contract_address)?.contains(&sender) {
+			bail!(ErrorKind::NotAuthorised(sender));
+		}
+		if self.get_decrypted_code(contract_address, block)? != self.get_decrypted_code(successor, block)? ||
+			self.get_decrypted_state(contract_address, block)? != self.get_decrypted_state(successor, block)? {
+			bail!(ErrorKind::InvalidSuccessor(*successor));
+		}
+		trace!("Retiring private contract {:?} in favour of {:?}", contract_address, successor);
+		self.encrypt(contract_address, &Self::iv_from_transaction(source), &Self::retirement_transaction_data(successor))
+	}
+
 	fn generate_constructor(validators: &[Address], code: Bytes, storage: Bytes) -> Bytes {
 		let constructor_code = DEFAULT_STUB_CONTRACT.from_hex().expect("Default contract code is valid");
 		let private = private::PrivateContract::default();
@@ -504,10 +679,53 @@ impl Provider where {
 		executed.contract_address))
 	}
 
+	/// Create public deployment transaction of a successor private contract, which carries the code and
+	/// the state of the given contract and is validated by the new set of validators. Code and state are
+	/// re-encrypted with the key of the successor contract. Only current validators may rotate validators.
+	/// Once the successor is deployed, the given contract must be retired with a private transaction
+	/// carrying `retirement_transaction_data`, which freezes its state and redirects callers to the successor.
+	pub fn public_rotation_transaction(&self, block: BlockId, contract_address: &Address, sender: &Address, validators: &[Address], gas_price: U256) -> Result<(Transaction, Address), Error> {
+		if validators.is_empty() {
+			bail!(ErrorKind::NoValidators);
+		}
+		if !self.get_validators(block, contract_address)?.contains(sender) {
+			bail!(ErrorKind::NotAuthorised(*sender));
+		}
+
+		let code = self.get_decrypted_code(contract_address, block)?;
+		let state = self.get_decrypted_state(contract_address, block)?;
+
+		let env_info = self.client.env_info(block).ok_or(ErrorKind::StatePruned)?;
+		let nonce = self.client.state_at(block).ok_or(ErrorKind::StatePruned)?.nonce(sender)?;
+		let scheme = self.client.engine().create_address_scheme(env_info.number);
+		let (new_address, _) = ethcore_contract_address(scheme, sender, &nonce, &[]);
+		trace!("Rotating validators of {:?}, successor contract: {:?}", contract_address, new_address);
+
+		let encrypted_code = self.encrypt(&new_address, &Self::iv_from_address(&new_address), &code)?;
+		let encrypted_state = self.encrypt(&new_address, &Self::iv_from_nonce(&nonce), &state)?;
+		let gas: u64 = 650000 +
+			validators.len() as u64 * 30000 +
+			encrypted_code.len() as u64 * 8000 +
+			encrypted_state.len() as u64 * 8000;
+		Ok((Transaction {
+			nonce: nonce,
+			action: Action::Create,
+			gas: gas.into(),
+			gas_price: gas_price,
+			value: 0.into(),
+			data: Self::generate_constructor(validators, encrypted_code, encrypted_state)
+		},
+		new_address))
+	}
+
 	/// Create encrypted public contract deployment transaction. Returns updated encrypted state.
 	pub fn execute_private_transaction(&self, block: BlockId, source: &SignedTransaction) -> Result<Bytes, Error> {
-		if let Action::Create = source.action {
-			bail!(ErrorKind::BadTransactonType);
+		let contract_address = match source.action {
+			Action::Create => bail!(ErrorKind::BadTransactonType),
+			Action::Call(ref contract_address) => contract_address,
+		};
+		if let Some(successor) = Self::retirement_successor(&source.data) {
+			return self.retirement_state(block, contract_address, source, &successor);
 		}
 		let result = self.execute_private(source, TransactOptions::with_no_tracing(), block)?;
 		Ok(result.state)
@@ -619,11 +837,26 @@ impl Importer for Arc<Provider> {
 			Some(desc) => desc,
 		};
 
-		let last = self.last_required_signature(&desc, tx.signature())?;
+		let transaction_hash = desc.original_transaction.hash();
+		let result = self.process_signature(&private_hash, desc, tx.signature());
+		if let Err(ref err) = result {
+			self.transactions_for_signing.lock().note_error(&transaction_hash, format!("{}", err));
+		}
+		result
+	}
+}
+
+impl Provider {
+	/// Store the signature of the private transaction created by this node.
+	/// Creates corresponding public transaction and sends it to the chain if this is the last required signature.
+	fn process_signature(&self, private_hash: &H256, desc: PrivateTransactionSigningDesc, signature: Signature) -> Result<(), Error> {
+		let transaction_hash = desc.original_transaction.hash();
+		let (signer, last) = self.last_required_signature(&desc, signature)?;
+		self.transactions_for_signing.lock().note_signed_by(&transaction_hash, signer);
 
 		if last {
 			let mut signatures = desc.received_signatures.clone();
-			signatures.push(tx.signature());
+			signatures.push(signature);
 			let rsv: Vec<Signature> = signatures.into_iter().map(|sign| sign.into_electrum().into()).collect();
 			//Create public transaction
 			let public_tx = self.public_transaction(
@@ -641,15 +874,20 @@ impl Importer for Arc<Provider> {
 			let password = find_account_password(&self.passwords, &*self.accounts, &signer_account);
 			let signature = self.accounts.sign(signer_account, password, hash)?;
 			let signed = SignedTransaction::new(public_tx.with_signature(signature, chain_id))?;
+			let public_hash = signed.hash();
 			match self.miner.import_own_transaction(&*self.client, signed.into()) {
-				Ok(_) => trace!("Public transaction added to queue"),
+				Ok(_) => {
+					trace!("Public transaction added to queue");
+					self.transactions_for_signing.lock().note_state(&transaction_hash, PrivateTransactionState::Signed(public_hash));
+				},
 				Err(err) => {
 					trace!("Failed to add transaction to queue, error: {:?}", err);
+					self.transactions_for_signing.lock().note_state(&transaction_hash, PrivateTransactionState::Failed);
 					bail!(err);
 				}
 			}
 			//Remove from store for signing
-			match self.transactions_for_signing.lock().remove(&private_hash) {
+			match self.transactions_for_signing.lock().remove(private_hash) {
 				Ok(_) => {}
 				Err(err) => {
 					trace!("Failed to remove transaction from signing store, error: {:?}", err);
@@ -658,7 +896,7 @@ impl Importer for Arc<Provider> {
 			}
 		} else {
 			//Add signature to the store
-			match self.transactions_for_signing.lock().add_signature(&private_hash, tx.signature()) {
+			match self.transactions_for_signing.lock().add_signature(private_hash, signature) {
 				Ok(_) => trace!("Signature stored for private transaction"),
 				Err(err) => {
 					trace!("Failed to add signature to signing store, error: {:?}", err);
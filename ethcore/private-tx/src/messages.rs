@@ -37,6 +37,32 @@ impl PrivateTransaction {
 	}
 }
 
+/// Request for the encrypted code and state of the private contract at the given block
+#[derive(Default, Debug, Clone, PartialEq, RlpEncodable, RlpDecodable, Eq)]
+pub struct PrivateStateRequest {
+	/// Address of the contract
+	pub contract: Address,
+	/// Hash of the block
+	pub block_hash: H256,
+}
+
+/// Proofs of the encrypted code and state of the private contract at the given block.
+/// Both are proved as calls to the public contract from the zero address, so that they
+/// can be checked against the state root of the block.
+#[derive(Default, Debug, Clone, PartialEq, RlpEncodable, RlpDecodable, Eq)]
+pub struct PrivateStateResponse {
+	/// Address of the contract
+	pub contract: Address,
+	/// Hash of the block
+	pub block_hash: H256,
+	/// Nonce of the zero address at the block
+	pub caller_nonce: U256,
+	/// Proof of the `code()` call
+	pub code_proof: Vec<Bytes>,
+	/// Proof of the `state()` call
+	pub state_proof: Vec<Bytes>,
+}
+
 /// Message about private transaction's signing
 #[derive(Default, Debug, Clone, PartialEq, RlpEncodable, RlpDecodable, Eq)]
 pub struct SignedPrivateTransaction {
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use bytes::Bytes;
 use ethcore_miner::pool;
@@ -155,11 +155,46 @@ pub struct PrivateTransactionSigningDesc {
 	pub contract_nonce: U256,
 }
 
+/// State of private transaction created by this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrivateTransactionState {
+	/// Waiting for signatures of validators.
+	Pending,
+	/// All signatures are collected, public transaction with given hash is sent to the queue.
+	Signed(H256),
+	/// Public transaction creation failed.
+	Failed,
+}
+
+/// Lifecycle status of private transaction created by this node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateTransactionStatus {
+	/// Current state of the transaction.
+	pub state: PrivateTransactionState,
+	/// Validators required to sign the transaction.
+	pub validators: Vec<Address>,
+	/// Validators which have already signed the transaction.
+	pub signed_by: Vec<Address>,
+	/// Errors occurred while processing signatures.
+	pub errors: Vec<String>,
+}
+
+impl PrivateTransactionStatus {
+	/// Validators which haven't signed the transaction yet.
+	pub fn missing_validators(&self) -> Vec<Address> {
+		self.validators.iter().filter(|v| !self.signed_by.contains(v)).cloned().collect()
+	}
+}
+
 /// Storage for private transactions for signing
 #[derive(Default)]
 pub struct SigningStore {
 	/// Transactions and descriptors for signing
 	transactions: HashMap<H256, PrivateTransactionSigningDesc>,
+	/// Statuses of transactions with key - hash of the original transaction
+	statuses: HashMap<H256, PrivateTransactionStatus>,
+	/// Hashes of transactions with known statuses, oldest first
+	statuses_order: VecDeque<H256>,
 }
 
 impl SigningStore {
@@ -176,6 +211,22 @@ impl SigningStore {
 			bail!(ErrorKind::QueueIsFull);
 		}
 
+		let transaction_hash = transaction.hash();
+		if !self.statuses.contains_key(&transaction_hash) {
+			if self.statuses_order.len() >= MAX_QUEUE_LEN {
+				if let Some(oldest) = self.statuses_order.pop_front() {
+					self.statuses.remove(&oldest);
+				}
+			}
+			self.statuses_order.push_back(transaction_hash);
+		}
+		self.statuses.insert(transaction_hash, PrivateTransactionStatus {
+			state: PrivateTransactionState::Pending,
+			validators: validators.clone(),
+			signed_by: Vec::new(),
+			errors: Vec::new(),
+		});
+
 		self.transactions.insert(private_hash, PrivateTransactionSigningDesc {
 			original_transaction: transaction.clone(),
 			validators: validators.clone(),
@@ -205,4 +256,94 @@ impl SigningStore {
 		}
 		Ok(())
 	}
+
+	/// Get status of the private transaction by the hash of the original transaction
+	pub fn status(&self, transaction_hash: &H256) -> Option<PrivateTransactionStatus> {
+		self.statuses.get(transaction_hash).cloned()
+	}
+
+	/// Note that the validator has signed the pending transaction
+	pub fn note_signed_by(&mut self, transaction_hash: &H256, validator: Address) {
+		if let Some(status) = self.statuses.get_mut(transaction_hash) {
+			if status.state == PrivateTransactionState::Pending && !status.signed_by.contains(&validator) {
+				status.signed_by.push(validator);
+			}
+		}
+	}
+
+	/// Update state of the transaction. Only pending transaction may become signed or failed,
+	/// both of these states are final.
+	pub fn note_state(&mut self, transaction_hash: &H256, state: PrivateTransactionState) {
+		if let Some(status) = self.statuses.get_mut(transaction_hash) {
+			if status.state == PrivateTransactionState::Pending {
+				status.state = state;
+			}
+		}
+	}
+
+	/// Note an error occurred while processing the transaction
+	pub fn note_error(&mut self, transaction_hash: &H256, error: String) {
+		if let Some(status) = self.statuses.get_mut(transaction_hash) {
+			status.errors.push(error);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethkey::{Generator, Random};
+	use transaction::Transaction;
+	use super::*;
+
+	fn signing_store_with_transaction(validators: Vec<Address>) -> (SigningStore, H256) {
+		let keypair = Random.generate().unwrap();
+		let transaction = Transaction::default().sign(keypair.secret(), None);
+		let transaction_hash = transaction.hash();
+		let mut store = SigningStore::default();
+		store.add_transaction(1.into(), transaction, validators, Vec::new(), 0.into()).unwrap();
+		(store, transaction_hash)
+	}
+
+	#[test]
+	fn new_transaction_is_pending() {
+		let (store, hash) = signing_store_with_transaction(vec![1.into(), 2.into()]);
+		let status = store.status(&hash).unwrap();
+		assert_eq!(status.state, PrivateTransactionState::Pending);
+		assert_eq!(status.missing_validators(), vec![Address::from(1), Address::from(2)]);
+		assert!(store.status(&2.into()).is_none());
+	}
+
+	#[test]
+	fn pending_transaction_collects_signatures_and_errors() {
+		let (mut store, hash) = signing_store_with_transaction(vec![1.into(), 2.into()]);
+		store.note_signed_by(&hash, 2.into());
+		store.note_signed_by(&hash, 2.into());
+		store.note_error(&hash, "State is incorrect".into());
+		let status = store.status(&hash).unwrap();
+		assert_eq!(status.state, PrivateTransactionState::Pending);
+		assert_eq!(status.signed_by, vec![Address::from(2)]);
+		assert_eq!(status.missing_validators(), vec![Address::from(1)]);
+		assert_eq!(status.errors, vec!["State is incorrect".to_owned()]);
+	}
+
+	#[test]
+	fn signed_transaction_state_is_final() {
+		let (mut store, hash) = signing_store_with_transaction(vec![1.into()]);
+		store.note_signed_by(&hash, 1.into());
+		store.note_state(&hash, PrivateTransactionState::Signed(3.into()));
+		store.note_state(&hash, PrivateTransactionState::Failed);
+		store.note_state(&hash, PrivateTransactionState::Pending);
+		store.note_signed_by(&hash, 4.into());
+		let status = store.status(&hash).unwrap();
+		assert_eq!(status.state, PrivateTransactionState::Signed(3.into()));
+		assert_eq!(status.signed_by, vec![Address::from(1)]);
+	}
+
+	#[test]
+	fn failed_transaction_state_is_final() {
+		let (mut store, hash) = signing_store_with_transaction(vec![1.into()]);
+		store.note_state(&hash, PrivateTransactionState::Failed);
+		store.note_state(&hash, PrivateTransactionState::Signed(3.into()));
+		assert_eq!(store.status(&hash).unwrap().state, PrivateTransactionState::Failed);
+	}
 }
@@ -24,12 +24,14 @@ extern crate ethcore_io;
 extern crate ethcore_logger;
 extern crate ethcore_private_tx;
 extern crate ethcore_transaction;
+extern crate rlp;
 
 #[macro_use]
 extern crate log;
 
 use std::sync::Arc;
 use rustc_hex::FromHex;
+use rlp::Encodable;
 
 use ethcore::CreateContractAddress;
 use ethcore::account_provider::AccountProvider;
@@ -42,7 +44,7 @@ use ethcore_transaction::{Transaction, Action};
 use ethkey::{Secret, KeyPair, Signature};
 use hash::keccak;
 
-use ethcore_private_tx::{NoopEncryptor, Provider, ProviderConfig};
+use ethcore_private_tx::{NoopEncryptor, Provider, ProviderConfig, Error, ErrorKind, PrivateStateRequest, PrivateStateResponse};
 
 #[test]
 fn private_contract() {
@@ -143,4 +145,124 @@ fn private_contract() {
 	let query_tx = query_tx.sign(&key1.secret(), chain_id);
 	let result = pm.private_call(BlockId::Latest, &query_tx).unwrap();
 	assert_eq!(result.output, "2a00000000000000000000000000000000000000000000000000000000000000".from_hex().unwrap());
+
+	// Rotate validators: only current validators are allowed to do that
+	trace!("Rotating validators");
+	let new_validators = vec![key4.address()];
+	assert!(pm.public_rotation_transaction(BlockId::Latest, &address, &key1.address(), &new_validators, 0.into()).is_err());
+	assert!(pm.public_rotation_transaction(BlockId::Latest, &address, &key3.address(), &[], 0.into()).is_err());
+	let (public_tx, new_address) = pm.public_rotation_transaction(BlockId::Latest, &address, &key3.address(), &new_validators, 0.into()).unwrap();
+	let (expected_address, _) = contract_address(CreateContractAddress::FromSenderAndNonce, &key3.address(), &0.into(), &[]);
+	assert_eq!(new_address, expected_address);
+	let public_tx = public_tx.sign(&key3.secret(), chain_id);
+	push_block_with_transactions(&client, &[public_tx]);
+
+	trace!("Querying private state of the successor contract");
+	let mut query_tx = Transaction::default();
+	query_tx.action = Action::Call(new_address.clone());
+	query_tx.data = "0c55699c".from_hex().unwrap();  // getX
+	query_tx.gas = 50000.into();
+	query_tx.nonce = 3.into();
+	let query_tx = query_tx.sign(&key1.secret(), chain_id);
+	let result = pm.private_call(BlockId::Latest, &query_tx).unwrap();
+	assert_eq!(result.output, "2a00000000000000000000000000000000000000000000000000000000000000".from_hex().unwrap());
+	assert_eq!(pm.get_validators(BlockId::Latest, &new_address).unwrap(), new_validators);
+
+	trace!("Retiring the rotated contract");
+	let mut retire_tx = Transaction::default();
+	retire_tx.action = Action::Call(address.clone());
+	retire_tx.data = Provider::retirement_transaction_data(&Default::default());
+	retire_tx.gas = 120000.into();
+	retire_tx.nonce = 1.into();
+	let retire_tx = retire_tx.sign(&key3.secret(), None);
+	// successor must carry the code and the state of the retired contract
+	assert!(pm.execute_private_transaction(BlockId::Latest, &retire_tx).is_err());
+	let mut retire_tx = Transaction::default();
+	retire_tx.action = Action::Call(address.clone());
+	retire_tx.data = Provider::retirement_transaction_data(&new_address);
+	retire_tx.gas = 120000.into();
+	retire_tx.nonce = 1.into();
+	// only validators of the retired contract may retire it
+	assert!(pm.execute_private_transaction(BlockId::Latest, &retire_tx.clone().sign(&key1.secret(), None)).is_err());
+	let retire_tx = retire_tx.sign(&key3.secret(), None);
+	let private_contract_nonce = pm.get_contract_nonce(&address, BlockId::Latest).unwrap();
+	let private_state = pm.execute_private_transaction(BlockId::Latest, &retire_tx).unwrap();
+	let nonced_state_hash = pm.calculate_state_hash(&private_state, private_contract_nonce);
+	let signatures: Vec<_> = [&key3, &key4].iter().map(|k|
+		Signature::from(::ethkey::sign(&k.secret(), &nonced_state_hash).unwrap().into_electrum())).collect();
+	let public_tx = pm.public_transaction(private_state, &retire_tx, &signatures, 3.into(), 0.into()).unwrap();
+	let public_tx = public_tx.sign(&key1.secret(), chain_id);
+	push_block_with_transactions(&client, &[public_tx]);
+
+	trace!("Querying the retired contract");
+	let mut query_tx = Transaction::default();
+	query_tx.action = Action::Call(address.clone());
+	query_tx.data = "0c55699c".from_hex().unwrap();  // getX
+	query_tx.gas = 50000.into();
+	query_tx.nonce = 4.into();
+	let query_tx = query_tx.sign(&key1.secret(), chain_id);
+	match pm.private_call(BlockId::Latest, &query_tx) {
+		Err(Error(ErrorKind::ContractRetired(successor), _)) => assert_eq!(successor, new_address),
+		Err(e) => panic!("Unexpected error of the call to the retired contract: {}", e),
+		Ok(_) => panic!("Retired contract must not be callable"),
+	}
+	assert!(pm.public_rotation_transaction(BlockId::Latest, &address, &key3.address(), &new_validators, 0.into()).is_err());
+}
+
+#[test]
+fn private_state_sync() {
+	// This uses a simple private contract: contract Test1 { bytes32 public x; function setX(bytes32 _x) { x = _x; } }
+	ethcore_logger::init_log();
+	let client = generate_dummy_client(0);
+	let chain_id = client.signing_chain_id();
+	let key1 = KeyPair::from_secret(Secret::from("0000000000000000000000000000000000000000000000000000000000000011")).unwrap();
+	let ap = Arc::new(AccountProvider::transient_provider());
+	ap.insert_account(key1.secret().clone(), &"".into()).unwrap();
+
+	let io = ethcore_io::IoChannel::disconnected();
+	let miner = Arc::new(Miner::new_for_tests(&::ethcore::spec::Spec::new_test(), None));
+	let pm = Arc::new(Provider::new(
+			client.clone(),
+			miner,
+			ap.clone(),
+			Box::new(NoopEncryptor::default()),
+			ProviderConfig::default(),
+			io,
+	));
+
+	let (address, _) = contract_address(CreateContractAddress::FromSenderAndNonce, &key1.address(), &0.into(), &[]);
+	let private_contract_test = "6060604052341561000f57600080fd5b60d88061001d6000396000f30060606040526000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff1680630c55699c146046578063bc64b76d14607457600080fd5b3415605057600080fd5b60566098565b60405180826000191660001916815260200191505060405180910390f35b3415607e57600080fd5b6096600480803560001916906020019091905050609e565b005b60005481565b8060008160001916905550505600a165627a7a723058206acbdf4b15ca4c2d43e1b1879b830451a34f1e9d02ff1f2f394d8d857e79d2080029".from_hex().unwrap();
+	let mut private_create_tx = Transaction::default();
+	private_create_tx.action = Action::Create;
+	private_create_tx.data = private_contract_test.clone();
+	private_create_tx.gas = 200000.into();
+	let private_create_tx_signed = private_create_tx.sign(&key1.secret(), None);
+	let (public_tx, _) = pm.public_creation_transaction(BlockId::Latest, &private_create_tx_signed, &[key1.address()], 0.into()).unwrap();
+	let public_tx = public_tx.sign(&key1.secret(), chain_id);
+	push_block_with_transactions(&client, &[public_tx]);
+	let block_hash = client.block_hash(BlockId::Latest).unwrap();
+
+	let request = PrivateStateRequest { contract: address, block_hash };
+	let response = pm.private_state_request(&request.rlp_bytes()).unwrap().unwrap();
+
+	// responses are only accepted for requested states
+	assert!(pm.import_private_state(&response).is_err());
+	pm.request_private_state(&address, BlockId::Latest).unwrap();
+
+	// proof which doesn't match the state of the block is rejected
+	let mut tampered: PrivateStateResponse = ::rlp::decode(&response);
+	tampered.state_proof.pop();
+	assert!(pm.import_private_state(&tampered.rlp_bytes()).is_err());
+	let mut tampered: PrivateStateResponse = ::rlp::decode(&response);
+	tampered.caller_nonce = 1.into();
+	assert!(pm.import_private_state(&tampered.rlp_bytes()).is_err());
+	assert!(pm.synced_private_state(&address, &block_hash).is_none());
+
+	assert!(pm.import_private_state(&response).is_ok());
+	let (code, state) = pm.synced_private_state(&address, &block_hash).unwrap();
+	assert_eq!(code, private_contract_test[0x1d..0x1d + 0xd8].to_vec());
+	assert!(state.is_empty());
+
+	// the state is not requested anymore
+	assert!(pm.import_private_state(&response).is_err());
 }
@@ -61,6 +61,14 @@ impl PrivateTxHandler for PrivateTxService {
 	fn import_signed_private_transaction(&self, rlp: &[u8]) -> Result<(), String> {
 		self.provider.import_signed_private_transaction(rlp).map_err(|e| e.to_string())
 	}
+
+	fn private_state_request(&self, rlp: &[u8]) -> Result<Option<Vec<u8>>, String> {
+		self.provider.private_state_request(rlp).map_err(|e| e.to_string())
+	}
+
+	fn import_private_state(&self, rlp: &[u8]) -> Result<(), String> {
+		self.provider.import_private_state(rlp).map_err(|e| e.to_string())
+	}
 }
 
 /// Client service setup. Creates and registers client and network services with the IO subsystem.
@@ -18,15 +18,16 @@
 
 mod stores;
 
-use self::stores::{AddressBook, DappsSettingsStore, NewDappsPolicy};
+use self::stores::{AddressBook, WatchList, AccountNotesStore, DappsSettingsStore, NewDappsPolicy};
 
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::path::PathBuf;
 use std::time::{Instant, Duration};
 
 use ethstore::accounts_dir::MemoryDirectory;
 use ethstore::ethkey::{Address, Message, Public, Secret, Password, Random, Generator};
-use ethjson::misc::AccountMeta;
+use ethjson::misc::{AccountMeta, AccountNotes};
 use ethstore::{
 	SimpleSecretStore, SecretStore, Error as SSError, EthStore, EthMultiStore,
 	random_string, SecretVaultRef, StoreAccountRef, OpaqueSecret,
@@ -125,6 +126,10 @@ pub struct AccountProvider {
 	unlocked: RwLock<HashMap<StoreAccountRef, AccountData>>,
 	/// Address book.
 	address_book: RwLock<AddressBook>,
+	/// Addresses tracked without keys.
+	watch_list: RwLock<WatchList>,
+	/// Tags and notes of accounts and addresses.
+	account_notes: RwLock<AccountNotesStore>,
 	/// Dapps settings.
 	dapps_settings: RwLock<DappsSettingsStore>,
 	/// Accounts on disk
@@ -150,6 +155,9 @@ pub struct AccountProviderSettings {
 	pub unlock_keep_secret: bool,
 	/// Disallowed accounts.
 	pub blacklisted_accounts: Vec<Address>,
+	/// Directory of the address book, watch-only addresses and account notes.
+	/// They're kept with the keys if not given.
+	pub address_book_path: Option<PathBuf>,
 }
 
 impl Default for AccountProviderSettings {
@@ -159,6 +167,7 @@ impl Default for AccountProviderSettings {
 			hardware_wallet_classic_key: false,
 			unlock_keep_secret: false,
 			blacklisted_accounts: vec![],
+			address_book_path: None,
 		}
 	}
 }
@@ -185,8 +194,10 @@ impl AccountProvider {
 			}
 		}
 
+		let address_book_path = settings.address_book_path.unwrap_or_else(|| sstore.local_path());
+
 		// Remove blacklisted accounts from address book.
-		let mut address_book = AddressBook::new(&sstore.local_path());
+		let mut address_book = AddressBook::new(&address_book_path);
 		for addr in &settings.blacklisted_accounts {
 			address_book.remove(*addr);
 		}
@@ -195,6 +206,8 @@ impl AccountProvider {
 			unlocked_secrets: RwLock::new(HashMap::new()),
 			unlocked: RwLock::new(HashMap::new()),
 			address_book: RwLock::new(address_book),
+			watch_list: RwLock::new(WatchList::new(&address_book_path)),
+			account_notes: RwLock::new(AccountNotesStore::new(&address_book_path)),
 			dapps_settings: RwLock::new(DappsSettingsStore::new(&sstore.local_path())),
 			sstore: sstore,
 			transient_sstore: transient_sstore(),
@@ -210,6 +223,8 @@ impl AccountProvider {
 			unlocked_secrets: RwLock::new(HashMap::new()),
 			unlocked: RwLock::new(HashMap::new()),
 			address_book: RwLock::new(AddressBook::transient()),
+			watch_list: RwLock::new(WatchList::transient()),
+			account_notes: RwLock::new(AccountNotesStore::transient()),
 			dapps_settings: RwLock::new(DappsSettingsStore::transient()),
 			sstore: Box::new(EthStore::open(Box::new(MemoryDirectory::default())).expect("MemoryDirectory load always succeeds; qed")),
 			transient_sstore: transient_sstore(),
@@ -504,7 +519,66 @@ impl AccountProvider {
 
 	/// Removes and address from the address book
 	pub fn remove_address(&self, addr: Address) {
-		self.address_book.write().remove(addr)
+		self.address_book.write().remove(addr);
+		if !self.has_account(addr) && !self.is_watch_only(&addr) {
+			self.account_notes.write().remove(addr);
+		}
+	}
+
+	/// Returns tags and notes of each address that has any.
+	pub fn notes_info(&self) -> HashMap<Address, AccountNotes> {
+		self.account_notes.read().get()
+	}
+
+	/// Sets tags of an account or any other address.
+	pub fn set_account_tags(&self, address: Address, tags: Vec<String>) {
+		self.account_notes.write().set_tags(address, tags)
+	}
+
+	/// Sets notes of an account or any other address.
+	pub fn set_account_notes(&self, address: Address, notes: String) {
+		self.account_notes.write().set_notes(address, notes)
+	}
+
+	/// Returns a human readable name of given address, looking it up in accounts,
+	/// the address book and watch-only addresses, in that order.
+	pub fn address_name(&self, address: &Address) -> Option<String> {
+		let account_name = match self.has_account(*address) || self.is_hardware_address(address) {
+			true => self.account_meta(*address).ok().map(|meta| meta.name),
+			false => None,
+		};
+		account_name
+			.or_else(|| self.address_book.read().name(address).map(Into::into))
+			.or_else(|| self.watch_list.read().name(address).map(Into::into))
+			.and_then(|name| match name.is_empty() {
+				true => None,
+				false => Some(name),
+			})
+	}
+
+	/// Starts tracking an address without a key. Watch-only addresses can never sign,
+	/// so addresses of existing accounts are rejected.
+	pub fn add_watch_only(&self, address: Address, name: String) -> Result<(), Error> {
+		if self.has_account(address) || self.is_hardware_address(&address) {
+			return Err(SSError::Custom("The address belongs to an existing account.".into()));
+		}
+		self.watch_list.write().insert(address, name);
+		Ok(())
+	}
+
+	/// Stops tracking a watch-only address. Returns false if it wasn't tracked.
+	pub fn remove_watch_only(&self, address: Address) -> bool {
+		self.watch_list.write().remove(address)
+	}
+
+	/// Returns each watch-only address along with metadata.
+	pub fn watch_only_info(&self) -> HashMap<Address, AccountMeta> {
+		self.watch_list.read().get()
+	}
+
+	/// Returns true if given address is tracked without a key.
+	pub fn is_watch_only(&self, address: &Address) -> bool {
+		self.watch_list.read().contains(address)
 	}
 
 	/// Returns each account along with name and meta.
@@ -851,9 +925,11 @@ impl AccountProvider {
 mod tests {
 	use super::{AccountProvider, Unlock, DappId};
 	use std::time::{Duration, Instant};
+	use ethstore::ethkey::Message;
 	use ethstore::ethkey::{Generator, Random, Address};
 	use ethstore::{StoreAccountRef, Derivation};
 	use ethereum_types::H256;
+	use ethjson::misc::AccountNotes;
 
 	#[test]
 	fn unlock_account_temp() {
@@ -1078,4 +1154,40 @@ mod tests {
 		assert_eq!(ap.accounts_info().unwrap().keys().cloned().collect::<Vec<Address>>(), vec![]);
 		assert_eq!(ap.accounts().unwrap(), vec![]);
 	}
+
+	#[test]
+	fn should_not_sign_with_watch_only_addresses() {
+		let kp = Random.generate().unwrap();
+		let ap = AccountProvider::transient_provider();
+		assert!(ap.insert_account(kp.secret().clone(), &"test".into()).is_ok());
+		assert!(ap.add_watch_only(kp.address(), "Mine".into()).is_err());
+
+		let watched: Address = 5.into();
+		ap.add_watch_only(watched, "Treasury".into()).unwrap();
+		assert!(ap.is_watch_only(&watched));
+		assert_eq!(ap.watch_only_info()[&watched].name, "Treasury");
+		assert!(ap.sign(watched, Some("test".into()), Message::default()).is_err());
+
+		assert!(ap.remove_watch_only(watched));
+		assert!(!ap.remove_watch_only(watched));
+	}
+
+	#[test]
+	fn should_name_addresses_and_keep_notes() {
+		let kp = Random.generate().unwrap();
+		let ap = AccountProvider::transient_provider();
+		ap.insert_account(kp.secret().clone(), &"test".into()).unwrap();
+		ap.set_account_name(kp.address(), "Main".into()).unwrap();
+		ap.set_address_name(kp.address(), "Shadowed".into());
+		ap.set_address_name(5.into(), "Exchange".into());
+		assert_eq!(ap.address_name(&kp.address()), Some("Main".into()));
+		assert_eq!(ap.address_name(&5.into()), Some("Exchange".into()));
+		assert_eq!(ap.address_name(&6.into()), None);
+
+		ap.set_account_tags(5.into(), vec!["cex".into()]);
+		ap.set_account_notes(kp.address(), "Cold storage".into());
+		ap.remove_address(5.into());
+		ap.remove_address(kp.address());
+		assert_eq!(ap.notes_info(), hash_map![kp.address() => AccountNotes { tags: vec![], notes: "Cold storage".into() }]);
+	}
 }
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Address Book and Dapps Settings Store
+//! Address Book, Watch List, Account Notes and Dapps Settings Store
 
 use std::{fs, fmt, hash, ops};
 use std::sync::atomic::{self, AtomicUsize};
@@ -24,6 +24,7 @@ use std::path::{Path, PathBuf};
 use ethstore::ethkey::Address;
 use ethjson::misc::{
 	AccountMeta,
+	AccountNotes,
 	DappsSettings as JsonSettings,
 	DappsHistory as JsonDappsHistory,
 	NewDappsPolicy as JsonNewDappsPolicy,
@@ -57,6 +58,11 @@ impl AddressBook {
 		self.cache.clone()
 	}
 
+	/// Get the name of given address.
+	pub fn name(&self, a: &Address) -> Option<&str> {
+		self.cache.get(a).map(|m| &*m.name)
+	}
+
 	fn save(&self) {
 		self.cache.save(AccountMeta::write)
 	}
@@ -88,6 +94,123 @@ impl AddressBook {
 	}
 }
 
+/// Disk-backed list of watch-only addresses, which are tracked but have no keys.
+pub struct WatchList {
+	cache: DiskMap<Address, AccountMeta>,
+}
+
+impl WatchList {
+	/// Creates new watch list at given directory.
+	pub fn new(path: &Path) -> Self {
+		let mut r = WatchList {
+			cache: DiskMap::new(path, "watch_only.json")
+		};
+		r.cache.revert(AccountMeta::read);
+		r
+	}
+
+	/// Creates transient watch list (no changes are saved to disk).
+	pub fn transient() -> Self {
+		WatchList {
+			cache: DiskMap::transient()
+		}
+	}
+
+	/// Get the watched addresses.
+	pub fn get(&self) -> HashMap<Address, AccountMeta> {
+		self.cache.clone()
+	}
+
+	/// Returns true if given address is watched.
+	pub fn contains(&self, a: &Address) -> bool {
+		self.cache.contains_key(a)
+	}
+
+	/// Get the name of given watched address.
+	pub fn name(&self, a: &Address) -> Option<&str> {
+		self.cache.get(a).map(|m| &*m.name)
+	}
+
+	/// Starts watching given address, or renames it if it's already watched.
+	pub fn insert(&mut self, a: Address, name: String) {
+		{
+			let x = self.cache.entry(a)
+				.or_insert_with(|| AccountMeta {name: Default::default(), meta: "{}".to_owned(), uuid: None});
+			x.name = name;
+		}
+		self.save();
+	}
+
+	/// Stops watching given address. Returns false if it wasn't watched.
+	pub fn remove(&mut self, a: Address) -> bool {
+		let removed = self.cache.remove(&a).is_some();
+		self.save();
+		removed
+	}
+
+	fn save(&self) {
+		self.cache.save(AccountMeta::write)
+	}
+}
+
+/// Disk-backed map of tags and notes attached to addresses.
+pub struct AccountNotesStore {
+	cache: DiskMap<Address, AccountNotes>,
+}
+
+impl AccountNotesStore {
+	/// Creates new notes store at given directory.
+	pub fn new(path: &Path) -> Self {
+		let mut r = AccountNotesStore {
+			cache: DiskMap::new(path, "account_notes.json")
+		};
+		r.cache.revert(AccountNotes::read);
+		r
+	}
+
+	/// Creates transient notes store (no changes are saved to disk).
+	pub fn transient() -> Self {
+		AccountNotesStore {
+			cache: DiskMap::transient()
+		}
+	}
+
+	/// Get tags and notes of all addresses.
+	pub fn get(&self) -> HashMap<Address, AccountNotes> {
+		self.cache.clone()
+	}
+
+	/// Sets tags of given address.
+	pub fn set_tags(&mut self, a: Address, tags: Vec<String>) {
+		self.cache.entry(a).or_insert_with(Default::default).tags = tags;
+		self.prune(a);
+		self.save();
+	}
+
+	/// Sets notes of given address.
+	pub fn set_notes(&mut self, a: Address, notes: String) {
+		self.cache.entry(a).or_insert_with(Default::default).notes = notes;
+		self.prune(a);
+		self.save();
+	}
+
+	/// Removes tags and notes of given address.
+	pub fn remove(&mut self, a: Address) {
+		self.cache.remove(&a);
+		self.save();
+	}
+
+	fn prune(&mut self, a: Address) {
+		if self.cache.get(&a).map_or(false, |n| n.tags.is_empty() && n.notes.is_empty()) {
+			self.cache.remove(&a);
+		}
+	}
+
+	fn save(&self) {
+		self.cache.save(AccountNotes::write)
+	}
+}
+
 /// Dapps user settings
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub struct DappsSettings {
@@ -366,10 +489,10 @@ impl<K: hash::Hash + Eq, V> DiskMap<K, V> {
 
 #[cfg(test)]
 mod tests {
-	use super::{AddressBook, DappsSettingsStore, DappsSettings, NewDappsPolicy};
+	use super::{AddressBook, WatchList, AccountNotesStore, DappsSettingsStore, DappsSettings, NewDappsPolicy};
 	use account_provider::DappId;
 	use std::collections::HashMap;
-	use ethjson::misc::AccountMeta;
+	use ethjson::misc::{AccountMeta, AccountNotes};
 	use tempdir::TempDir;
 
 	#[test]
@@ -397,6 +520,36 @@ mod tests {
 			1.into() => AccountMeta{name: "One".to_owned(), meta: "{}".to_owned(), uuid: None},
 			3.into() => AccountMeta{name: "Three".to_owned(), meta: "{}".to_owned(), uuid: None}
 		]);
+		assert_eq!(b.name(&3.into()), Some("Three"));
+		assert_eq!(b.name(&2.into()), None);
+	}
+
+	#[test]
+	fn should_save_and_reload_watch_list() {
+		let tempdir = TempDir::new("").unwrap();
+		let mut b = WatchList::new(tempdir.path());
+		b.insert(1.into(), "One".to_owned());
+		b.insert(2.into(), "Two".to_owned());
+		assert!(b.remove(2.into()));
+
+		let b = WatchList::new(tempdir.path());
+		assert!(b.contains(&1.into()));
+		assert_eq!(b.name(&1.into()), Some("One"));
+		assert_eq!(b.name(&2.into()), None);
+		assert_eq!(b.get(), hash_map![1.into() => AccountMeta{name: "One".to_owned(), meta: "{}".to_owned(), uuid: None}]);
+	}
+
+	#[test]
+	fn should_save_and_reload_account_notes() {
+		let tempdir = TempDir::new("").unwrap();
+		let mut b = AccountNotesStore::new(tempdir.path());
+		b.set_tags(1.into(), vec!["exchange".to_owned()]);
+		b.set_notes(1.into(), "Deposit address".to_owned());
+		b.set_notes(2.into(), "Temporary".to_owned());
+		b.set_notes(2.into(), String::new());
+
+		let b = AccountNotesStore::new(tempdir.path());
+		assert_eq!(b.get(), hash_map![1.into() => AccountNotes{tags: vec!["exchange".to_owned()], notes: "Deposit address".to_owned()}]);
 	}
 
 	#[test]
@@ -22,7 +22,7 @@ use std::collections::HashSet;
 use hash::{keccak, KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP};
 use triehash::ordered_trie_root;
 
-use rlp::{Rlp, RlpStream, Encodable, Decodable, DecoderError, encode_list};
+use rlp::{Rlp, RlpStream, Encodable, Decodable, DecoderError, encode, encode_list};
 use ethereum_types::{H256, U256, Address, Bloom};
 use bytes::Bytes;
 use unexpected::{Mismatch, OutOfBounds};
@@ -36,6 +36,7 @@ use receipt::{Receipt, TransactionOutcome};
 use state::State;
 use state_db::StateDB;
 use trace::Tracing;
+use types::state_diff::StateDiff;
 use transaction::{UnverifiedTransaction, SignedTransaction, Error as TransactionError};
 use verification::PreverifiedBlock;
 use views::BlockView;
@@ -93,6 +94,7 @@ pub struct ExecutedBlock {
 	transactions_set: HashSet<H256>,
 	state: State<StateDB>,
 	traces: Tracing,
+	state_diffs: Option<Vec<StateDiff>>,
 	last_hashes: Arc<LastHashes>,
 	is_finalized: bool,
 	metadata: Option<Vec<u8>>,
@@ -113,6 +115,7 @@ impl ExecutedBlock {
 			} else {
 				Tracing::Disabled
 			},
+			state_diffs: None,
 			last_hashes: last_hashes,
 			is_finalized: false,
 			metadata: None,
@@ -173,6 +176,9 @@ pub trait IsBlock {
 	/// Get all information concerning transaction tracing in this block.
 	fn traces(&self) -> &Tracing { &self.block().traces }
 
+	/// Get state diffs of transactions in this block, if recorded.
+	fn state_diffs(&self) -> Option<&[StateDiff]> { self.block().state_diffs.as_ref().map(|d| &d[..]) }
+
 	/// Get all uncles in this block.
 	fn uncles(&self) -> &[Header] { &self.block().uncles }
 
@@ -237,6 +243,8 @@ impl ::parity_machine::WithMetadata for ExecutedBlock {
 pub struct OpenBlock<'x> {
 	block: ExecutedBlock,
 	engine: &'x EthEngine,
+	// Size of the RLP of the transactions and uncles pushed so far.
+	body_size: usize,
 }
 
 /// Just like `OpenBlock`, except that we've applied `Engine::on_close_block`, finished up the non-seal header fields,
@@ -289,6 +297,7 @@ impl<'x> OpenBlock<'x> {
 		let mut r = OpenBlock {
 			block: ExecutedBlock::new(state, last_hashes, tracing),
 			engine: engine,
+			body_size: 0,
 		};
 
 		r.block.header.set_parent_hash(parent.hash());
@@ -297,7 +306,7 @@ impl<'x> OpenBlock<'x> {
 		r.block.header.set_timestamp(engine.open_block_header_timestamp(parent.timestamp()));
 		r.block.header.set_extra_data(extra_data);
 
-		let gas_floor_target = cmp::max(gas_range_target.0, engine.params().min_gas_limit);
+		let gas_floor_target = cmp::max(gas_range_target.0, engine.params().min_gas_limit(number));
 		let gas_ceil_target = cmp::max(gas_range_target.1, gas_floor_target);
 
 		engine.machine().populate_from_parent(&mut r.block.header, parent, gas_floor_target, gas_ceil_target);
@@ -334,6 +343,7 @@ impl<'x> OpenBlock<'x> {
 		}
 		// TODO: check number
 		// TODO: check not a direct ancestor (use last_hashes for that)
+		self.body_size += encode(&valid_uncle_header).len();
 		self.block.uncles.push(valid_uncle_header);
 		Ok(())
 	}
@@ -343,6 +353,11 @@ impl<'x> OpenBlock<'x> {
 		self.block.env_info()
 	}
 
+	/// Record state diffs of transactions pushed into the block from now on.
+	pub fn record_state_diffs(&mut self) {
+		self.block.state_diffs = Some(Vec::new());
+	}
+
 	/// Push a transaction into the block.
 	///
 	/// If valid, it will be executed, and archived together with the receipt.
@@ -351,18 +366,40 @@ impl<'x> OpenBlock<'x> {
 			return Err(TransactionError::AlreadyImported.into());
 		}
 
+		let transaction_size = encode(&t).len();
+		if let Some(max_block_size) = self.engine.params().max_block_size(self.block.header.number()) {
+			let size = self.size() + transaction_size;
+			if size > max_block_size {
+				return Err(BlockError::TooLarge(OutOfBounds { min: None, max: Some(max_block_size), found: size }).into());
+			}
+		}
+
 		let env_info = self.env_info();
-		let outcome = self.block.state.apply(&env_info, self.engine.machine(), &t, self.block.traces.is_enabled())?;
+		let tracing = self.block.traces.is_enabled();
+		let outcome = match self.block.state_diffs {
+			Some(_) => self.block.state.apply_with_state_diff(&env_info, self.engine.machine(), &t, tracing)?,
+			None => self.block.state.apply(&env_info, self.engine.machine(), &t, tracing)?,
+		};
 
 		self.block.transactions_set.insert(h.unwrap_or_else(||t.hash()));
 		self.block.transactions.push(t.into());
+		self.body_size += transaction_size;
 		if let Tracing::Enabled(ref mut traces) = self.block.traces {
 			traces.push(outcome.trace.into());
 		}
+		if let Some(ref mut state_diffs) = self.block.state_diffs {
+			state_diffs.push(outcome.state_diff.expect("state diff is produced when requested; qed"));
+		}
 		self.block.receipts.push(outcome.receipt);
 		Ok(self.block.receipts.last().expect("receipt just pushed; qed"))
 	}
 
+	/// Size of the block RLP built so far, without the seal.
+	pub fn size(&self) -> usize {
+		// the block, transactions and uncles lists take at least a byte each
+		encode(&self.block.header).len() + self.body_size + 3
+	}
+
 	/// Push transactions onto the block.
 	#[cfg(not(feature = "slow-blocks"))]
 	fn push_transactions(&mut self, transactions: Vec<SignedTransaction>) -> Result<(), Error> {
@@ -402,7 +439,7 @@ impl<'x> OpenBlock<'x> {
 		self.block.header.set_uncles_hash(*header.uncles_hash());
 		self.block.header.set_transactions_root(*header.transactions_root());
 		// TODO: that's horrible. set only for backwards compatibility
-		if header.extra_data().len() > self.engine.maximum_extra_data_size() {
+		if header.extra_data().len() > self.engine.maximum_extra_data_size(header.number()) {
 			warn!("Couldn't set extradata. Ignoring.");
 		} else {
 			self.block.header.set_extra_data(header.extra_data().clone());
@@ -516,9 +553,12 @@ impl ClosedBlock {
 		block.state = self.unclosed_state;
 		block.metadata = self.unclosed_metadata;
 		block.is_finalized = self.unclosed_finalization_state;
+		let body_size = block.transactions.iter().map(|t| encode(t).len()).sum::<usize>()
+			+ block.uncles.iter().map(|u| encode(u).len()).sum::<usize>();
 		OpenBlock {
 			block: block,
 			engine: engine,
+			body_size: body_size,
 		}
 	}
 }
@@ -548,14 +588,15 @@ impl LockedBlock {
 	/// Provide a valid seal in order to turn this into a `SealedBlock`.
 	///
 	/// NOTE: This does not check the validity of `seal` with the engine.
-	pub fn seal(self, engine: &EthEngine, seal: Vec<Bytes>) -> Result<SealedBlock, BlockError> {
+	pub fn seal(self, engine: &EthEngine, seal: Vec<Bytes>) -> Result<SealedBlock, Error> {
 		let expected_seal_fields = engine.seal_fields(self.header());
 		let mut s = self;
 		if seal.len() != expected_seal_fields {
 			return Err(BlockError::InvalidSealArity(
-				Mismatch { expected: expected_seal_fields, found: seal.len() }));
+				Mismatch { expected: expected_seal_fields, found: seal.len() }).into());
 		}
 		s.block.header.set_seal(seal);
+		engine.on_seal_block(&mut s.block.header)?;
 		s.block.header.compute_hash();
 		Ok(SealedBlock { block: s.block, uncle_bytes: s.uncle_bytes })
 	}
@@ -570,6 +611,9 @@ impl LockedBlock {
 	) -> Result<SealedBlock, (Error, LockedBlock)> {
 		let mut s = self;
 		s.block.header.set_seal(seal);
+		if let Err(e) = engine.on_seal_block(&mut s.block.header) {
+			return Err((e, s));
+		}
 		s.block.header.compute_hash();
 
 		// TODO: passing state context to avoid engines owning it?
@@ -616,6 +660,7 @@ fn enact(
 	uncles: Vec<Header>,
 	engine: &EthEngine,
 	tracing: bool,
+	state_diffs: bool,
 	db: StateDB,
 	parent: &Header,
 	last_hashes: Arc<LastHashes>,
@@ -645,6 +690,10 @@ fn enact(
 		ancestry,
 	)?;
 
+	if state_diffs {
+		b.record_state_diffs();
+	}
+
 	b.populate_from(&header);
 	b.push_transactions(transactions)?;
 
@@ -660,6 +709,7 @@ pub fn enact_verified(
 	block: PreverifiedBlock,
 	engine: &EthEngine,
 	tracing: bool,
+	state_diffs: bool,
 	db: StateDB,
 	parent: &Header,
 	last_hashes: Arc<LastHashes>,
@@ -675,6 +725,7 @@ pub fn enact_verified(
 		view.uncles(),
 		engine,
 		tracing,
+		state_diffs,
 		db,
 		parent,
 		last_hashes,
@@ -690,7 +741,7 @@ mod tests {
 	use super::*;
 	use engines::EthEngine;
 	use vm::LastHashes;
-	use error::Error;
+	use error::{Error, ErrorKind};
 	use header::Header;
 	use factory::Factories;
 	use state_db::StateDB;
@@ -777,6 +828,43 @@ mod tests {
 		let _ = b.seal(&*spec.engine, vec![]);
 	}
 
+	#[test]
+	fn open_block_respects_max_block_size() {
+		use ethkey::{Generator, Random};
+		use spec::*;
+		use tempdir::TempDir;
+		use transaction::{Action, Transaction};
+
+		let tempdir = TempDir::new("").unwrap();
+		let json = String::from_utf8(include_bytes!("../res/null.json").to_vec()).unwrap()
+			.replace("\"eip658Transition\": \"0x0\"", "\"eip658Transition\": \"0x0\", \"maxBlockSize\": \"0x800\"");
+		let spec = Spec::load(&tempdir.path(), json.as_bytes()).unwrap();
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(&*spec.engine, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false, &mut Vec::new().into_iter()).unwrap();
+
+		let keypair = Random.generate().unwrap();
+		let transaction = |nonce: u64| Transaction {
+			nonce: nonce.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Call(Address::zero()),
+			value: 0.into(),
+			data: vec![1; 1000],
+		}.sign(keypair.secret(), None);
+
+		let empty_size = b.size();
+		b.push_transaction(transaction(0), None).unwrap();
+		assert!(b.size() > empty_size + 1000);
+
+		match b.push_transaction(transaction(1), None) {
+			Err(Error(ErrorKind::Block(BlockError::TooLarge(oob)), _)) => assert_eq!(oob.max, Some(0x800)),
+			other => panic!("expected block to be too large, got {:?}", other.map(|_| ())),
+		}
+		assert_eq!(b.block().transactions().len(), 1);
+	}
+
 	#[test]
 	fn enact_block() {
 		use spec::*;
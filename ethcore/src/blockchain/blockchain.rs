@@ -21,6 +21,7 @@ use std::sync::Arc;
 use std::mem;
 use itertools::Itertools;
 use blooms_db;
+use era_db;
 use heapsize::HeapSizeOf;
 use ethereum_types::{H256, Bloom, BloomRef, U256};
 use parking_lot::{Mutex, RwLock};
@@ -34,12 +35,12 @@ use log_entry::{LogEntry, LocalizedLogEntry};
 use receipt::Receipt;
 use blockchain::best_block::{BestBlock, BestAncientBlock};
 use blockchain::block_info::{BlockInfo, BlockLocation, BranchBecomingCanonChainData};
-use blockchain::extras::{BlockReceipts, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions};
+use blockchain::extras::{BlockReceipts, CompactBlockReceipts, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions};
 use types::blockchain_info::BlockChainInfo;
 use types::tree_route::TreeRoute;
 use blockchain::update::{ExtrasUpdate, ExtrasInsert};
-use blockchain::{CacheSize, ImportRoute, Config};
-use db::{self, Writable, Readable, CacheUpdatePolicy};
+use blockchain::{CacheSize, ImportRoute, Config, ReceiptsStorage, TransactionIndex};
+use db::{self, Key, Writable, Readable, CacheUpdatePolicy};
 use cache_manager::CacheManager;
 use encoded;
 use engines::ForkChoice;
@@ -60,6 +61,11 @@ pub trait BlockChainDB: Send + Sync {
 
 	/// Trace blooms database.
 	fn trace_blooms(&self) -> &blooms_db::Database;
+
+	/// Append-only storage of ancient block bodies and receipts, if enabled.
+	fn ancient(&self) -> Option<&era_db::Database> {
+		None
+	}
 }
 
 /// Generic database handler. This trait contains one function `open`. When called, it opens database with a
@@ -191,6 +197,9 @@ enum CacheId {
 	BlockReceipts(H256),
 }
 
+/// Number of blocks whose transactions are indexed or dropped in a single database transaction.
+const TRANSACTION_INDEX_BATCH: BlockNumber = 10_000;
+
 /// Structure providing fast access to blockchain data.
 ///
 /// **Does not do input data verification.**
@@ -215,6 +224,8 @@ pub struct BlockChain {
 	block_receipts: RwLock<HashMap<H256, BlockReceipts>>,
 
 	db: Arc<BlockChainDB>,
+	receipts_storage: ReceiptsStorage,
+	tx_index: TransactionIndex,
 
 	cache_man: Mutex<CacheManager<CacheId>>,
 
@@ -298,9 +309,12 @@ impl BlockProvider for BlockChain {
 			}
 		}
 
-		// Read from DB and populate cache
-		let b = self.db.key_value().get(db::COL_BODIES, hash)
-			.expect("Low level database error. Some issue with disk?")?;
+		// Read from DB, falling back to ancient blocks, and populate cache
+		let b = match self.db.key_value().get(db::COL_BODIES, hash)
+			.expect("Low level database error. Some issue with disk?") {
+			Some(b) => b.into_vec(),
+			None => self.ancient_data(hash, |ancient, number| ancient.body(number))?,
+		};
 
 		let body = encoded::Body::new(decompress(&b, blocks_swapper()).into_vec());
 		let mut write = self.block_bodies.write();
@@ -333,7 +347,19 @@ impl BlockProvider for BlockChain {
 
 	/// Get receipts of block with given hash.
 	fn block_receipts(&self, hash: &H256) -> Option<BlockReceipts> {
-		let result = self.db.key_value().read_with_cache(db::COL_EXTRA, &self.block_receipts, hash)?;
+		let result = match self.db.key_value().read_with_cache(db::COL_EXTRA, &self.block_receipts, hash) {
+			Some(result) => result,
+			None => {
+				let receipts = self.ancient_data(hash, |ancient, number| ancient.receipts(number))?;
+				// blocks are frozen without receipts if they had none stored.
+				if receipts.is_empty() {
+					return None;
+				}
+				let result: BlockReceipts = ::rlp::decode(&receipts).expect("ancient receipts are encoded by the freezer; qed");
+				self.block_receipts.write().insert(*hash, result.clone());
+				result
+			},
+		};
 		self.cache_man.lock().note_used(CacheId::BlockReceipts(*hash));
 		Some(result)
 	}
@@ -344,6 +370,19 @@ impl BlockProvider for BlockChain {
 		BloomRef<'a>: From<B>,
 		II: IntoIterator<Item = B, IntoIter = I> + Copy,
 		I: Iterator<Item = B> {
+		if self.receipts_storage == ReceiptsStorage::Light {
+			// the blooms index is not maintained, check the header of every block instead.
+			let blooms: Vec<BloomRef> = blooms.into_iter().map(Into::into).collect();
+			return (from_block..to_block + 1)
+				.filter(|number| self.block_hash(*number)
+					.and_then(|hash| self.block_header_data(&hash))
+					.map_or(false, |header| {
+						let log_bloom = header.log_bloom();
+						blooms.iter().any(|bloom| log_bloom.contains_bloom(*bloom))
+					}))
+				.collect();
+		}
+
 		self.db.blooms()
 			.filter(from_block, to_block, blooms)
 			.expect("Low level database error. Some issue with disk?")
@@ -528,6 +567,8 @@ impl BlockChain {
 			transaction_addresses: RwLock::new(HashMap::new()),
 			block_receipts: RwLock::new(HashMap::new()),
 			db: db.clone(),
+			receipts_storage: config.receipts_storage,
+			tx_index: config.tx_index,
 			cache_man: Mutex::new(cache_man),
 			pending_best_block: RwLock::new(None),
 			pending_block_hashes: RwLock::new(HashMap::new()),
@@ -1066,13 +1107,26 @@ impl BlockChain {
 
 		{
 			let mut write_receipts = self.block_receipts.write();
-			batch.extend_with_cache(db::COL_EXTRA, &mut *write_receipts, update.block_receipts, CacheUpdatePolicy::Remove);
+			match self.receipts_storage {
+				ReceiptsStorage::Full => {
+					batch.extend_with_cache(db::COL_EXTRA, &mut *write_receipts, update.block_receipts, CacheUpdatePolicy::Remove);
+				},
+				ReceiptsStorage::Compact | ReceiptsStorage::Light => {
+					for (hash, receipts) in update.block_receipts {
+						write_receipts.remove(&hash);
+						batch.put(db::COL_EXTRA, &Key::<BlockReceipts>::key(&hash), &::rlp::encode(&CompactBlockReceipts(&receipts)));
+					}
+				},
+			}
 		}
 
-		if let Some((block, blooms)) = update.blocks_blooms {
-			self.db.blooms()
-				.insert_blooms(block, blooms.iter())
-				.expect("Low level database error. Some issue with disk?");
+		match update.blocks_blooms {
+			Some((block, ref blooms)) if self.receipts_storage != ReceiptsStorage::Light => {
+				self.db.blooms()
+					.insert_blooms(block, blooms.iter())
+					.expect("Low level database error. Some issue with disk?");
+			},
+			_ => {},
 		}
 
 		// These cached values must be updated last with all four locks taken to avoid
@@ -1268,6 +1322,32 @@ impl BlockChain {
 
 	/// This function returns modified transaction addresses.
 	fn prepare_transaction_addresses_update(&self, block_bytes: &[u8], info: &BlockInfo) -> HashMap<H256, Option<TransactionAddress>> {
+		match self.tx_index {
+			TransactionIndex::All => self.prepare_all_transaction_addresses_update(block_bytes, info),
+			TransactionIndex::Recent(blocks) => {
+				// blocks imported out of order may already be outside of the window.
+				if info.number + blocks <= self.best_block_number() {
+					return HashMap::new();
+				}
+
+				let mut update = self.prepare_all_transaction_addresses_update(block_bytes, info);
+				if info.location != BlockLocation::Branch && info.number >= blocks {
+					// drop the index of the canonical block leaving the window.
+					let body = self.block_hash(info.number - blocks).and_then(|hash| self.block_body(&hash));
+					if let Some(body) = body {
+						for hash in body.transaction_hashes() {
+							update.entry(hash).or_insert(None);
+						}
+					}
+				}
+				update
+			},
+			TransactionIndex::None => HashMap::new(),
+		}
+	}
+
+	/// This function returns modified transaction addresses, indexing all transactions.
+	fn prepare_all_transaction_addresses_update(&self, block_bytes: &[u8], info: &BlockInfo) -> HashMap<H256, Option<TransactionAddress>> {
 		let block = view!(BlockView, block_bytes);
 		let transaction_hashes = block.transaction_hashes();
 
@@ -1364,6 +1444,105 @@ impl BlockChain {
 		}
 	}
 
+	/// Reads data of the canon block `hash` from the ancient blocks database.
+	fn ancient_data<F>(&self, hash: &H256, read: F) -> Option<Vec<u8>>
+		where F: Fn(&era_db::Database, BlockNumber) -> ::std::io::Result<Option<Vec<u8>>>
+	{
+		let ancient = self.db.ancient()?;
+		let number = self.block_number(hash)?;
+		if self.block_hash(number).as_ref() != Some(hash) {
+			return None;
+		}
+		read(ancient, number).expect("Low level database error. Some issue with disk?")
+	}
+
+	/// Moves bodies and receipts of canon blocks more than `threshold` blocks behind the best block
+	/// from the key-value database to the ancient blocks database. At most `limit` blocks are moved.
+	/// Stops at the first block whose body is missing, e.g. below the first block of a warp synced chain.
+	/// Returns the number of moved blocks.
+	pub fn freeze_ancient_blocks(&self, threshold: BlockNumber, limit: BlockNumber) -> Result<BlockNumber, Error> {
+		let ancient = match self.db.ancient() {
+			Some(ancient) => ancient,
+			None => return Ok(0),
+		};
+
+		let from = ancient.next_number();
+		let to = ::std::cmp::min(self.best_block_number().saturating_sub(threshold), from + limit);
+		let mut batch = DBTransaction::new();
+		let mut number = from;
+		while number < to {
+			let hash = match self.block_hash(number) {
+				Some(hash) => hash,
+				None => break,
+			};
+			let body = match self.db.key_value().get(db::COL_BODIES, &hash)? {
+				Some(body) => body,
+				None => break,
+			};
+			let receipts_key = Key::<BlockReceipts>::key(&hash);
+			let receipts = self.db.key_value().get(db::COL_EXTRA, &receipts_key)?;
+
+			ancient.append(number, &body, receipts.as_ref().map_or(&[][..], |r| &**r))?;
+			batch.delete(db::COL_BODIES, &hash);
+			batch.delete(db::COL_EXTRA, &receipts_key);
+			number += 1;
+		}
+
+		if number > from {
+			// the key-value data is removed only once the ancient data is durable.
+			ancient.flush()?;
+			self.db.key_value().write(batch)?;
+			trace!(target: "blockchain", "Froze blocks #{}..#{}", from, number - 1);
+		}
+
+		Ok(number - from)
+	}
+
+	/// Indexes or drops the transactions of canonical blocks, so that the transaction index built
+	/// with the `previous` policy matches the policy of the chain.
+	/// Returns the number of blocks whose transactions were indexed or dropped.
+	pub fn update_transaction_index(&self, previous: TransactionIndex) -> Result<BlockNumber, Error> {
+		let best = self.best_block_number();
+		let previous_from = previous.first_indexed(best);
+		let from = self.tx_index.first_indexed(best);
+		let (start, end, index) = match from < previous_from {
+			true => (from, previous_from, true),
+			false => (previous_from, from, false),
+		};
+
+		let mut number = start;
+		while number < end {
+			let batch_end = ::std::cmp::min(number + TRANSACTION_INDEX_BATCH, end);
+			let mut batch = DBTransaction::new();
+			{
+				let mut write_txs = self.transaction_addresses.write();
+				for n in number..batch_end {
+					let hash = match self.block_hash(n) {
+						Some(hash) => hash,
+						None => continue,
+					};
+					// blocks below the first block of a warp synced chain have no bodies.
+					let body = match self.block_body(&hash) {
+						Some(body) => body,
+						None => continue,
+					};
+					for (i, tx_hash) in body.transaction_hashes().into_iter().enumerate() {
+						match index {
+							true => batch.write(db::COL_EXTRA, &tx_hash, &TransactionAddress { block_hash: hash, index: i }),
+							false => batch.delete(db::COL_EXTRA, &Key::<TransactionAddress>::key(&tx_hash)),
+						}
+						write_txs.remove(&tx_hash);
+					}
+				}
+			}
+			self.db.key_value().write(batch)?;
+			info!(target: "blockchain", "{} transactions of blocks #{}..#{}", if index { "Indexed" } else { "Dropped index of" }, number, batch_end - 1);
+			number = batch_end;
+		}
+
+		Ok(end - start)
+	}
+
 	/// Get best block hash.
 	pub fn best_block_hash(&self) -> H256 {
 		self.best_block.read().header.hash()
@@ -1696,6 +1875,63 @@ mod tests {
 		assert_eq!(bc.transaction_address(&t1_hash), None);
 	}
 
+	#[test]
+	fn recent_transaction_index() {
+		use blockchain::TransactionIndex;
+
+		let t1 = Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Create,
+			value: 100.into(),
+			data: vec![],
+		}.sign(&secret(), None);
+
+		let t2 = Transaction {
+			nonce: 1.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Create,
+			value: 100.into(),
+			data: vec![],
+		}.sign(&secret(), None);
+
+		let genesis = BlockBuilder::genesis();
+		let b1 = genesis.add_block_with_transactions(iter::once(t1.clone()));
+		let b2 = b1.add_block_with_transactions(iter::once(t2.clone()));
+		let b3 = b2.add_block();
+
+		let db = new_db();
+		let config = Config { tx_index: TransactionIndex::Recent(2), ..Config::default() };
+		let bc = BlockChain::new(config, &genesis.last().encoded(), db.clone());
+		insert_block(&db, &bc, &b1.last().encoded(), vec![]);
+		insert_block(&db, &bc, &b2.last().encoded(), vec![]);
+		assert!(bc.transaction_address(&t1.hash()).is_some());
+		assert!(bc.transaction_address(&t2.hash()).is_some());
+
+		// block #1 leaves the window of the two most recent blocks.
+		insert_block(&db, &bc, &b3.last().encoded(), vec![]);
+		assert!(bc.transaction_address(&t1.hash()).is_none());
+		assert!(bc.transaction_address(&t2.hash()).is_some());
+
+		// a fresh chain doesn't find it either.
+		let bc = new_chain(&genesis.last().encoded(), db.clone());
+		assert!(bc.transaction_address(&t1.hash()).is_none());
+
+		// indexing all transactions backfills the blocks which left the window.
+		assert_eq!(bc.update_transaction_index(TransactionIndex::Recent(2)).unwrap(), 2);
+		assert!(bc.transaction_address(&t1.hash()).is_some());
+		assert!(bc.transaction_address(&t2.hash()).is_some());
+
+		// disabling the index drops all of them.
+		let config = Config { tx_index: TransactionIndex::None, ..Config::default() };
+		let bc = BlockChain::new(config, &genesis.last().encoded(), db.clone());
+		assert_eq!(bc.update_transaction_index(TransactionIndex::All).unwrap(), 4);
+		assert!(bc.transaction_address(&t1.hash()).is_none());
+		assert!(bc.transaction_address(&t2.hash()).is_none());
+	}
+
 	#[test]
 	fn test_overwriting_transaction_addresses() {
 		let t1 = Transaction {
@@ -1939,6 +2175,47 @@ mod tests {
 		assert_eq!(bc.best_block_number(), 49);
 	}
 
+	#[test]
+	fn can_freeze_ancient_blocks() {
+		let genesis = BlockBuilder::genesis();
+		let first_10 = genesis.add_blocks(10);
+		let generator = BlockGenerator::new(vec![first_10]);
+
+		let db = new_db();
+		let bc = new_chain(&genesis.last().encoded(), db.clone());
+		let receipt = Receipt {
+			outcome: TransactionOutcome::StateRoot(H256::default()),
+			gas_used: 10_000.into(),
+			log_bloom: Default::default(),
+			logs: vec![],
+		};
+
+		let mut block_hashes = vec![genesis.last().hash()];
+		for block in generator {
+			block_hashes.push(block.hash());
+			insert_block(&db, &bc, &block.encoded(), vec![receipt.clone()]);
+		}
+		let bodies: Vec<_> = block_hashes.iter().map(|h| bc.block_body(h).unwrap()).collect();
+
+		// blocks more than 4 blocks behind the best, at most 3 at once.
+		assert_eq!(bc.freeze_ancient_blocks(4, 3).unwrap(), 3);
+		assert_eq!(bc.freeze_ancient_blocks(4, 100).unwrap(), 3);
+		assert_eq!(bc.freeze_ancient_blocks(4, 100).unwrap(), 0);
+		assert_eq!(db.ancient().unwrap().next_number(), 6);
+
+		// frozen data is no longer in the key-value database, but still readable.
+		let bc = new_chain(&genesis.last().encoded(), db.clone());
+		for (number, hash) in block_hashes.iter().enumerate() {
+			let frozen = number < 6;
+			assert_eq!(db.key_value().get(::db::COL_BODIES, hash).unwrap().is_none(), frozen);
+			assert_eq!(bc.block_body(hash).unwrap().rlp().as_raw(), bodies[number].rlp().as_raw());
+			if number > 0 {
+				assert_eq!(bc.block_receipts(hash).unwrap().receipts, vec![receipt.clone()]);
+			}
+		}
+		assert!(bc.block_receipts(&block_hashes[0]).is_none());
+	}
+
 	#[test]
 	fn can_collect_garbage() {
 		let bc = generate_dummy_blockchain(3000);
@@ -2220,6 +2497,34 @@ mod tests {
 		assert_eq!(blocks_ba, vec![3]);
 	}
 
+	#[test]
+	fn light_receipts_storage() {
+		use blockchain::ReceiptsStorage;
+
+		let bloom_b1: Bloom = "00000020000000000000000000000000000000000000000002000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008000400000000000000000000002000".into();
+
+		let genesis = BlockBuilder::genesis();
+		let b1 = genesis.add_block_with_bloom(bloom_b1);
+		let b1_hash = b1.last().hash();
+
+		let db = new_db();
+		let config = Config { receipts_storage: ReceiptsStorage::Light, ..Config::default() };
+		let bc = BlockChain::new(config, &genesis.last().encoded(), db.clone());
+		let receipts = vec![Receipt::new(TransactionOutcome::StatusCode(1), 21_000.into(), vec![
+			LogEntry { address: 1.into(), topics: vec![2.into(), 3.into()], data: vec![4] },
+			LogEntry { address: 1.into(), topics: vec![3.into()], data: vec![] },
+		])];
+		insert_block(&db, &bc, &b1.last().encoded(), receipts.clone());
+
+		// the blooms index is untouched, but the header blooms still answer the query.
+		assert!(db.blooms().filter(0, 5, Some(&bloom_b1)).unwrap().is_empty());
+		assert_eq!(bc.blocks_with_bloom(Some(&bloom_b1), 0, 5), vec![1]);
+
+		// receipts are stored compactly and come back intact.
+		let bc = BlockChain::new(Config::default(), &genesis.last().encoded(), db.clone());
+		assert_eq!(bc.block_receipts(&b1_hash).unwrap().receipts, receipts);
+	}
+
 	#[test]
 	fn test_insert_unordered() {
 		let bloom_b1: Bloom = "00000020000000000000000000000000000000000000000002000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008000400000000000000000000002000".into();
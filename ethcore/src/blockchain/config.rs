@@ -16,6 +16,106 @@
 
 //! Blockchain configuration.
 
+use std::fmt;
+use std::str::FromStr;
+
+/// How block receipts are stored in the database.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ReceiptsStorage {
+	/// Receipts are stored as they are encoded on the network.
+	Full,
+	/// Addresses and topics of logs are deduplicated per block and receipt blooms are
+	/// recomputed when read.
+	Compact,
+	/// Like `Compact`, and the log blooms index is not maintained. Log queries fall back
+	/// to scanning the blooms of every block header in the requested range.
+	Light,
+}
+
+impl Default for ReceiptsStorage {
+	fn default() -> Self {
+		ReceiptsStorage::Full
+	}
+}
+
+impl ReceiptsStorage {
+	/// Returns the name of the mode, as accepted by `from_str`.
+	pub fn as_str(&self) -> &'static str {
+		match *self {
+			ReceiptsStorage::Full => "full",
+			ReceiptsStorage::Compact => "compact",
+			ReceiptsStorage::Light => "light",
+		}
+	}
+}
+
+impl FromStr for ReceiptsStorage {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"full" => Ok(ReceiptsStorage::Full),
+			"compact" => Ok(ReceiptsStorage::Compact),
+			"light" => Ok(ReceiptsStorage::Light),
+			other => Err(format!("Invalid receipts storage mode: {}", other)),
+		}
+	}
+}
+
+/// Which transactions are indexed by hash.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TransactionIndex {
+	/// Transactions of all canonical blocks are indexed.
+	All,
+	/// Only transactions of the given number of most recent canonical blocks are indexed.
+	Recent(u64),
+	/// Transactions are not indexed.
+	None,
+}
+
+impl Default for TransactionIndex {
+	fn default() -> Self {
+		TransactionIndex::All
+	}
+}
+
+impl TransactionIndex {
+	/// Number of the first block whose transactions are indexed when `best_block` is the best block.
+	pub fn first_indexed(&self, best_block: u64) -> u64 {
+		match *self {
+			TransactionIndex::All => 0,
+			TransactionIndex::Recent(blocks) => (best_block + 1).saturating_sub(blocks),
+			TransactionIndex::None => best_block + 1,
+		}
+	}
+}
+
+impl fmt::Display for TransactionIndex {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			TransactionIndex::All => write!(f, "all"),
+			TransactionIndex::Recent(blocks) => write!(f, "recent:{}", blocks),
+			TransactionIndex::None => write!(f, "none"),
+		}
+	}
+}
+
+impl FromStr for TransactionIndex {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"all" => Ok(TransactionIndex::All),
+			"none" => Ok(TransactionIndex::None),
+			other if other.starts_with("recent:") => match other["recent:".len()..].parse() {
+				Ok(0) | Err(_) => Err(format!("Invalid number of recent blocks to index transactions of: {}", other)),
+				Ok(blocks) => Ok(TransactionIndex::Recent(blocks)),
+			},
+			other => Err(format!("Invalid transaction index: {}", other)),
+		}
+	}
+}
+
 /// Blockchain configuration.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Config {
@@ -23,6 +123,10 @@ pub struct Config {
 	pub pref_cache_size: usize,
 	/// Maximum cache size in bytes.
 	pub max_cache_size: usize,
+	/// How block receipts are stored.
+	pub receipts_storage: ReceiptsStorage,
+	/// Which transactions are indexed by hash.
+	pub tx_index: TransactionIndex,
 }
 
 impl Default for Config {
@@ -30,6 +134,8 @@ impl Default for Config {
 		Config {
 			pref_cache_size: 1 << 14,
 			max_cache_size: 1 << 20,
+			receipts_storage: ReceiptsStorage::default(),
+			tx_index: TransactionIndex::default(),
 		}
 	}
 }
@@ -17,15 +17,17 @@
 //! Blockchain DB extras.
 
 use std::ops;
+use std::collections::HashMap;
 use std::io::Write;
 use db::Key;
 use engines::epoch::{Transition as EpochTransition};
 use header::BlockNumber;
-use receipt::Receipt;
+use log_entry::LogEntry;
+use receipt::{Receipt, TransactionOutcome};
 use rlp;
 
 use heapsize::HeapSizeOf;
-use ethereum_types::{H256, H264, U256};
+use ethereum_types::{H256, H264, U256, Address};
 use kvdb::PREFIX_LEN as DB_PREFIX_LEN;
 
 /// Represents index of extra data in database
@@ -223,7 +225,7 @@ impl HeapSizeOf for TransactionAddress {
 }
 
 /// Contains all block receipts.
-#[derive(Clone, RlpEncodableWrapper, RlpDecodableWrapper)]
+#[derive(Clone, RlpEncodableWrapper)]
 pub struct BlockReceipts {
 	pub receipts: Vec<Receipt>,
 }
@@ -236,12 +238,139 @@ impl BlockReceipts {
 	}
 }
 
+impl rlp::Decodable for BlockReceipts {
+	fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+		// a compact encoding starts with its version, a full one with a receipt.
+		if rlp.item_count()? == 4 && rlp.at(0)?.is_data() {
+			decode_compact_receipts(rlp).map(BlockReceipts::new)
+		} else {
+			rlp.as_list().map(BlockReceipts::new)
+		}
+	}
+}
+
 impl HeapSizeOf for BlockReceipts {
 	fn heap_size_of_children(&self) -> usize {
 		self.receipts.heap_size_of_children()
 	}
 }
 
+/// Version of the compact receipts encoding.
+const COMPACT_RECEIPTS_VERSION: u8 = 1;
+
+/// Block receipts encoded for storage with `ReceiptsStorage::Compact`.
+///
+/// Addresses and topics of all logs in the block are stored once in dictionaries
+/// and referred to by index. Receipt blooms are dropped and recomputed from the logs
+/// when the receipts are read.
+pub struct CompactBlockReceipts<'a>(pub &'a BlockReceipts);
+
+impl<'a> rlp::Encodable for CompactBlockReceipts<'a> {
+	fn rlp_append(&self, s: &mut rlp::RlpStream) {
+		let mut addresses: Vec<&Address> = Vec::new();
+		let mut topics: Vec<&H256> = Vec::new();
+		let mut address_index = HashMap::new();
+		let mut topic_index = HashMap::new();
+
+		for log in self.0.receipts.iter().flat_map(|r| r.logs.iter()) {
+			address_index.entry(&log.address).or_insert_with(|| {
+				addresses.push(&log.address);
+				addresses.len() - 1
+			});
+			for topic in &log.topics {
+				topic_index.entry(topic).or_insert_with(|| {
+					topics.push(topic);
+					topics.len() - 1
+				});
+			}
+		}
+
+		s.begin_list(4);
+		s.append(&COMPACT_RECEIPTS_VERSION);
+		s.begin_list(addresses.len());
+		for address in &addresses {
+			s.append(*address);
+		}
+		s.begin_list(topics.len());
+		for topic in &topics {
+			s.append(*topic);
+		}
+		s.begin_list(self.0.receipts.len());
+		for receipt in &self.0.receipts {
+			match receipt.outcome {
+				TransactionOutcome::Unknown => {
+					s.begin_list(2);
+				},
+				TransactionOutcome::StateRoot(ref root) => {
+					s.begin_list(3);
+					s.append(root);
+				},
+				TransactionOutcome::StatusCode(ref status_code) => {
+					s.begin_list(3);
+					s.append(status_code);
+				},
+			}
+			s.append(&receipt.gas_used);
+			s.begin_list(receipt.logs.len());
+			for log in &receipt.logs {
+				s.begin_list(3);
+				s.append(&address_index[&log.address]);
+				s.begin_list(log.topics.len());
+				for topic in &log.topics {
+					s.append(&topic_index[topic]);
+				}
+				s.append(&log.data);
+			}
+		}
+	}
+}
+
+fn decode_compact_receipts(rlp: &rlp::Rlp) -> Result<Vec<Receipt>, rlp::DecoderError> {
+	let version: u8 = rlp.val_at(0)?;
+	if version != COMPACT_RECEIPTS_VERSION {
+		return Err(rlp::DecoderError::Custom("Unknown compact receipts version"));
+	}
+
+	let addresses: Vec<Address> = rlp.list_at(1)?;
+	let topics: Vec<H256> = rlp.list_at(2)?;
+	let lookup = |dictionary_len: usize, index: usize| if index < dictionary_len {
+		Ok(index)
+	} else {
+		Err(rlp::DecoderError::Custom("Compact receipts dictionary index out of bounds"))
+	};
+
+	rlp.at(3)?.iter().map(|receipt| {
+		let (outcome, offset) = match receipt.item_count()? {
+			2 => (TransactionOutcome::Unknown, 0),
+			3 => {
+				let first = receipt.at(0)?;
+				let outcome = if first.is_data() && first.data()?.len() <= 1 {
+					TransactionOutcome::StatusCode(first.as_val()?)
+				} else {
+					TransactionOutcome::StateRoot(first.as_val()?)
+				};
+				(outcome, 1)
+			},
+			_ => return Err(rlp::DecoderError::RlpIncorrectListLen),
+		};
+
+		let logs = receipt.at(offset + 1)?.iter().map(|log| {
+			let address = lookup(addresses.len(), log.val_at(0)?)?;
+			let log_topics = log.at(1)?.iter()
+				.map(|topic| Ok(topics[lookup(topics.len(), topic.as_val()?)?]))
+				.collect::<Result<Vec<_>, rlp::DecoderError>>()?;
+
+			Ok(LogEntry {
+				address: addresses[address],
+				topics: log_topics,
+				data: log.val_at(2)?,
+			})
+		}).collect::<Result<Vec<_>, rlp::DecoderError>>()?;
+
+		Ok(Receipt::new(outcome, receipt.val_at(offset)?, logs))
+	}).collect()
+}
+
 /// Candidate transitions to an epoch with specific number.
 #[derive(Clone, RlpEncodable, RlpDecodable)]
 pub struct EpochTransitions {
@@ -252,7 +381,10 @@ pub struct EpochTransitions {
 #[cfg(test)]
 mod tests {
 	use rlp::*;
-	use super::BlockReceipts;
+	use ethereum_types::{H256, U256, Address};
+	use log_entry::LogEntry;
+	use receipt::{Receipt, TransactionOutcome};
+	use super::{BlockReceipts, CompactBlockReceipts};
 
 	#[test]
 	fn encode_block_receipts() {
@@ -265,4 +397,27 @@ mod tests {
 		assert!(s.is_finished(), "List should be finished now");
 		s.out();
 	}
+
+	#[test]
+	fn compact_block_receipts_roundtrip() {
+		let log = |address: u64, topics: &[u64]| LogEntry {
+			address: Address::from(address),
+			topics: topics.iter().map(|t| H256::from(*t)).collect(),
+			data: vec![address as u8; 4],
+		};
+		let br = BlockReceipts::new(vec![
+			Receipt::new(TransactionOutcome::StateRoot(H256::from(7)), U256::from(21_000), vec![log(1, &[1, 2]), log(2, &[2])]),
+			Receipt::new(TransactionOutcome::StatusCode(1), U256::from(42_000), vec![log(1, &[1, 3])]),
+			Receipt::new(TransactionOutcome::Unknown, U256::from(63_000), vec![]),
+		]);
+
+		let full = encode(&br);
+		let compact = encode(&CompactBlockReceipts(&br));
+		assert!(compact.len() < full.len());
+
+		let decoded: BlockReceipts = decode(&compact).unwrap();
+		assert_eq!(decoded.receipts, br.receipts);
+		let decoded: BlockReceipts = decode(&full).unwrap();
+		assert_eq!(decoded.receipts, br.receipts);
+	}
 }
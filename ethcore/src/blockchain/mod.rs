@@ -30,7 +30,7 @@ pub mod generator;
 
 pub use self::blockchain::{BlockProvider, BlockChain, BlockChainDB, BlockChainDBHandler};
 pub use self::cache::CacheSize;
-pub use self::config::Config;
+pub use self::config::{Config, ReceiptsStorage, TransactionIndex};
 pub use self::extras::{BlockReceipts, BlockDetails, TransactionAddress};
 pub use self::import_route::ImportRoute;
 pub use self::update::ExtrasInsert;
@@ -17,7 +17,7 @@
 use std::cmp::{max, min};
 use std::io::{self, Read};
 
-use byteorder::{ByteOrder, BigEndian};
+use byteorder::{ByteOrder, BigEndian, LittleEndian};
 use ethcore_crypto::digest;
 use num::{BigUint, Zero, One};
 
@@ -84,6 +84,21 @@ impl Pricer for AltBn128PairingPricer {
 	}
 }
 
+/// A pricing model for the Blake2 compression function. This computes a price per round.
+struct Blake2FPricer {
+	gas_per_round: usize,
+}
+
+impl Pricer for Blake2FPricer {
+	fn cost(&self, input: &[u8]) -> U256 {
+		if input.len() < 4 {
+			return U256::zero();
+		}
+		let rounds = BigEndian::read_u32(&input[..4]);
+		U256::from(self.gas_per_round) * U256::from(rounds)
+	}
+}
+
 impl Pricer for ModexpPricer {
 	fn cost(&self, input: &[u8]) -> U256 {
 		let mut reader = input.chain(io::repeat(0));
@@ -197,6 +212,11 @@ impl From<ethjson::spec::Builtin> for Builtin {
 					pair: pricer.pair,
 				})
 			}
+			ethjson::spec::Pricing::Blake2F(pricer) => {
+				Box::new(Blake2FPricer {
+					gas_per_round: pricer.gas_per_round,
+				})
+			}
 		};
 
 		Builtin {
@@ -207,9 +227,27 @@ impl From<ethjson::spec::Builtin> for Builtin {
 	}
 }
 
+/// Check a builtin from a chain spec for mistakes which would otherwise only be
+/// noticed when it is instantiated or called.
+pub fn check(b: &ethjson::spec::Builtin) -> Result<(), String> {
+	if native_builtin(&b.name).is_none() {
+		return Err(format!("invalid builtin name: {}", b.name));
+	}
+
+	match b.pricing {
+		ethjson::spec::Pricing::Modexp(ref exp) if exp.divisor == 0 =>
+			Err(format!("zero modexp divisor for builtin {}", b.name)),
+		_ => Ok(()),
+	}
+}
+
 // Ethereum builtin creator.
 fn ethereum_builtin(name: &str) -> Box<Impl> {
-	match name {
+	native_builtin(name).unwrap_or_else(|| panic!("invalid builtin name: {}", name))
+}
+
+fn native_builtin(name: &str) -> Option<Box<Impl>> {
+	Some(match name {
 		"identity" => Box::new(Identity) as Box<Impl>,
 		"ecrecover" => Box::new(EcRecover) as Box<Impl>,
 		"sha256" => Box::new(Sha256) as Box<Impl>,
@@ -218,8 +256,9 @@ fn ethereum_builtin(name: &str) -> Box<Impl> {
 		"alt_bn128_add" => Box::new(Bn128AddImpl) as Box<Impl>,
 		"alt_bn128_mul" => Box::new(Bn128MulImpl) as Box<Impl>,
 		"alt_bn128_pairing" => Box::new(Bn128PairingImpl) as Box<Impl>,
-		_ => panic!("invalid builtin name: {}", name),
-	}
+		"blake2_f" => Box::new(Blake2FImpl) as Box<Impl>,
+		_ => return None,
+	})
 }
 
 // Ethereum builtins:
@@ -229,6 +268,7 @@ fn ethereum_builtin(name: &str) -> Box<Impl> {
 // - sha256
 // - ripemd160
 // - modexp (EIP198)
+// - blake2_f (EIP152)
 
 #[derive(Debug)]
 struct Identity;
@@ -254,6 +294,9 @@ struct Bn128MulImpl;
 #[derive(Debug)]
 struct Bn128PairingImpl;
 
+#[derive(Debug)]
+struct Blake2FImpl;
+
 impl Impl for Identity {
 	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), Error> {
 		output.write(0, input);
@@ -308,6 +351,106 @@ impl Impl for Ripemd160 {
 	}
 }
 
+/// Initialization vector of BLAKE2b, as defined in RFC 7693.
+const BLAKE2B_IV: [u64; 8] = [
+	0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+	0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+/// Message word permutations of BLAKE2b, as defined in RFC 7693.
+const BLAKE2B_SIGMA: [[usize; 16]; 10] = [
+	[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+	[14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+	[11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+	[7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+	[9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+	[2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+	[12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+	[13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+	[6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+	[10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+// BLAKE2b mixing function.
+fn blake2b_g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+	v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+	v[d] = (v[d] ^ v[a]).rotate_right(32);
+	v[c] = v[c].wrapping_add(v[d]);
+	v[b] = (v[b] ^ v[c]).rotate_right(24);
+	v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+	v[d] = (v[d] ^ v[a]).rotate_right(16);
+	v[c] = v[c].wrapping_add(v[d]);
+	v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+// BLAKE2b compression function F with a configurable number of rounds.
+fn blake2b_f(h: &mut [u64; 8], m: &[u64; 16], t: [u64; 2], f: bool, rounds: usize) {
+	let mut v = [0u64; 16];
+	v[..8].copy_from_slice(&h[..]);
+	v[8..].copy_from_slice(&BLAKE2B_IV[..]);
+
+	v[12] ^= t[0];
+	v[13] ^= t[1];
+	if f {
+		v[14] = !v[14];
+	}
+
+	for i in 0..rounds {
+		let s = &BLAKE2B_SIGMA[i % 10];
+		blake2b_g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+		blake2b_g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+		blake2b_g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+		blake2b_g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+		blake2b_g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+		blake2b_g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+		blake2b_g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+		blake2b_g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+	}
+
+	for i in 0..8 {
+		h[i] ^= v[i] ^ v[i + 8];
+	}
+}
+
+impl Impl for Blake2FImpl {
+	fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), Error> {
+		// rounds (4 bytes), state (64 bytes), message (128 bytes), offset counters (16 bytes), final flag (1 byte)
+		if input.len() != 213 {
+			return Err(Error::from("Invalid input length for blake2_f: expected 213 bytes"));
+		}
+
+		let rounds = BigEndian::read_u32(&input[..4]) as usize;
+
+		let mut h = [0u64; 8];
+		for (i, word) in h.iter_mut().enumerate() {
+			*word = LittleEndian::read_u64(&input[4 + i * 8..]);
+		}
+
+		let mut m = [0u64; 16];
+		for (i, word) in m.iter_mut().enumerate() {
+			*word = LittleEndian::read_u64(&input[68 + i * 8..]);
+		}
+
+		let t = [LittleEndian::read_u64(&input[196..]), LittleEndian::read_u64(&input[204..])];
+
+		let f = match input[212] {
+			0 => false,
+			1 => true,
+			_ => return Err(Error::from("Invalid final block indicator flag for blake2_f")),
+		};
+
+		blake2b_f(&mut h, &m, t, f, rounds);
+
+		let mut out = [0u8; 64];
+		for (i, word) in h.iter().enumerate() {
+			LittleEndian::write_u64(&mut out[i * 8..], *word);
+		}
+		output.write(0, &out[..]);
+
+		Ok(())
+	}
+}
+
 // calculate modexp: exponentiation by squaring. the `num` crate has pow, but not modular.
 fn modexp(mut base: BigUint, mut exp: BigUint, modulus: BigUint) -> BigUint {
 	use num::Integer;
@@ -535,7 +678,7 @@ impl Bn128PairingImpl {
 
 #[cfg(test)]
 mod tests {
-	use super::{Builtin, Linear, ethereum_builtin, Pricer, ModexpPricer, modexp as me};
+	use super::{Builtin, Linear, ethereum_builtin, check, Pricer, ModexpPricer, modexp as me};
 	use ethjson;
 	use ethereum_types::U256;
 	use bytes::BytesRef;
@@ -981,6 +1124,22 @@ mod tests {
 		let _ = ethereum_builtin("foo");
 	}
 
+	#[test]
+	fn checks_spec_builtins() {
+		let builtin = |name: &str, pricing| ethjson::spec::Builtin {
+			name: name.to_owned(),
+			pricing: pricing,
+			activate_at: None,
+		};
+
+		let linear = ethjson::spec::Pricing::Linear(ethjson::spec::Linear { base: 10, word: 20 });
+		assert!(check(&builtin("identity", linear.clone())).is_ok());
+		assert!(check(&builtin("foo", linear)).is_err());
+
+		let modexp = ethjson::spec::Pricing::Modexp(ethjson::spec::Modexp { divisor: 0 });
+		assert!(check(&builtin("modexp", modexp)).is_err());
+	}
+
 	#[test]
 	fn is_active() {
 		let pricer = Box::new(Linear { base: 10, word: 20} );
@@ -1015,6 +1174,45 @@ mod tests {
 		assert_eq!(i, o);
 	}
 
+	#[test]
+	fn blake2_f() {
+		let f = ethereum_builtin("blake2_f");
+
+		// EIP-152 test vector 5: "abc" with 12 rounds, matching BLAKE2b-512("abc")
+		let i = FromHex::from_hex("0000000c48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b61626300000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000001").unwrap();
+		let expected = FromHex::from_hex("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923").unwrap();
+
+		let mut o = [255u8; 64];
+		f.execute(&i[..], &mut BytesRef::Fixed(&mut o[..])).expect("Builtin should not fail");
+		assert_eq!(&o[..], &expected[..]);
+
+		// invalid final block indicator
+		let mut invalid = i.clone();
+		invalid[212] = 2;
+		assert!(f.execute(&invalid[..], &mut BytesRef::Fixed(&mut o[..])).is_err());
+
+		// invalid input length
+		assert!(f.execute(&i[..212], &mut BytesRef::Fixed(&mut o[..])).is_err());
+	}
+
+	#[test]
+	fn blake2_f_cost() {
+		let b = Builtin::from(ethjson::spec::Builtin {
+			name: "blake2_f".to_owned(),
+			pricing: ethjson::spec::Pricing::Blake2F(ethjson::spec::Blake2F {
+				gas_per_round: 1,
+			}),
+			activate_at: Some(ethjson::uint::Uint(100.into())),
+		});
+
+		let mut input = [0u8; 213];
+		input[3] = 12;
+		assert_eq!(b.cost(&input[..]), U256::from(12));
+		assert_eq!(b.cost(&[]), U256::zero());
+		assert!(!b.is_active(99));
+		assert!(b.is_active(100));
+	}
+
 	#[test]
 	fn from_json() {
 		let b = Builtin::from(ethjson::spec::Builtin {
@@ -18,6 +18,7 @@ use bytes::Bytes;
 use ethereum_types::H256;
 use transaction::UnverifiedTransaction;
 use blockchain::ImportRoute;
+use types::reorg_alert::ReorgAlert;
 use std::time::Duration;
 use std::collections::HashMap;
 
@@ -29,6 +30,8 @@ pub enum ChainMessageType {
 	PrivateTransaction(Vec<u8>),
 	/// Message with signed private transaction
 	SignedPrivateTransaction(Vec<u8>),
+	/// Request for the private state of the contract
+	PrivateStateRequest(Vec<u8>),
 }
 
 /// Route type to indicate whether it is enacted or retracted.
@@ -150,4 +153,9 @@ pub trait ChainNotify : Send + Sync {
 	) {
 		// does nothing by default
 	}
+
+	/// fires when block import is halted by a reorganization deeper than allowed
+	fn reorg_halted(&self, _alert: &ReorgAlert) {
+		// does nothing by default
+	}
 }
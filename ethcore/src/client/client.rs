@@ -19,22 +19,24 @@ use std::fmt;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
 use std::sync::{Arc, Weak};
-use std::time::{Instant, Duration};
+use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 
 // util
 use hash::keccak;
 use bytes::Bytes;
 use itertools::Itertools;
 use journaldb;
-use trie::{TrieSpec, TrieFactory, Trie};
+use trie::{TrieSpec, TrieFactory, Trie, TrieDB};
 use kvdb::{DBValue, KeyValueDB, DBTransaction};
+use hashdb::HashDB;
 use util_error::UtilError;
 
 // other
 use ethereum_types::{H256, Address, U256};
 use block::{IsBlock, LockedBlock, Drain, ClosedBlock, OpenBlock, enact_verified, SealedBlock};
-use blockchain::{BlockChain, BlockChainDB, BlockProvider, TreeRoute, ImportRoute, TransactionAddress, ExtrasInsert};
+use blockchain::{BlockChain, BlockChainDB, BlockProvider, TreeRoute, ImportRoute, TransactionAddress, TransactionIndex, ExtrasInsert};
 use client::ancient_import::AncientVerifier;
+use client::witness;
 use client::Error as ClientError;
 use client::{
 	Nonce, Balance, ChainInfo, BlockInfo, CallContract, TransactionInfo,
@@ -47,7 +49,7 @@ use client::{
 	BlockId, TransactionId, UncleId, TraceId, ClientConfig, BlockChainClient,
 	TraceFilter, CallAnalytics, BlockImportError, Mode,
 	ChainNotify, ChainRoute, PruningInfo, ProvingBlockChainClient, EngineInfo, ChainMessageType,
-	IoClient,
+	IoClient, ReorgAlert, FinalityRule, FinalityStatus,
 };
 use encoded;
 use engines::{EthEngine, EpochTransition, ForkChoice};
@@ -73,6 +75,8 @@ use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Databa
 use transaction::{self, LocalizedTransaction, UnverifiedTransaction, SignedTransaction, Transaction, Action};
 use types::filter::Filter;
 use types::ancestry_action::AncestryAction;
+use types::state_diff::StateDiff;
+use types::state_range::{StateRange, RangeAccount, RangeStorage};
 use verification;
 use verification::{PreverifiedBlock, Verifier};
 use verification::queue::BlockQueue;
@@ -93,6 +97,10 @@ const MAX_ANCIENT_BLOCKS_QUEUE_SIZE: usize = 4096;
 const MAX_ANCIENT_BLOCKS_TO_IMPORT: usize = 4;
 const MAX_QUEUE_SIZE_TO_SLEEP_ON: usize = 2;
 const MIN_HISTORY_SIZE: u64 = 8;
+// Min number of blocks behind the best block before bodies and receipts are moved to era files.
+const MIN_ANCIENT_BLOCKS_THRESHOLD: u64 = 1024;
+// Max number of blocks moved to era files per tick.
+const MAX_ANCIENT_BLOCKS_TO_FREEZE: u64 = 10_000;
 
 /// Report on the status of a client.
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
@@ -164,6 +172,20 @@ struct Importer {
 
 	/// Ethereum engine to be used during import
 	pub engine: Arc<EthEngine>,
+
+	/// Blocks held back by a reorganization deeper than allowed
+	reorg_guard: Mutex<ReorgGuard>,
+}
+
+/// State of import halted by a deep reorganization.
+#[derive(Default)]
+struct ReorgGuard {
+	/// Reorganization waiting for confirmation.
+	alert: Option<ReorgAlert>,
+	/// Verified blocks held back until the reorganization is accepted.
+	held: Vec<PreverifiedBlock>,
+	/// Block allowed to reorganize the chain regardless of depth.
+	accepted: Option<H256>,
 }
 
 /// Blockchain database client backed by a persistent database. Owns and manages a blockchain and a block queue.
@@ -177,6 +199,10 @@ pub struct Client {
 	/// knows it can't proceed further.
 	enabled: AtomicBool,
 
+	/// Flag used to pause block import temporarily, e.g. when the disk is
+	/// about to run out of space and further writes could corrupt the database.
+	import_paused: AtomicBool,
+
 	/// Operating mode for the client
 	mode: Mutex<Mode>,
 
@@ -236,6 +262,9 @@ pub struct Client {
 	/// A closure to call when we want to restart the client
 	exit_handler: Mutex<Option<Box<Fn(String) + 'static + Send>>>,
 
+	/// Latest block found marked as finalized, where the next search can stop
+	last_finalized: RwLock<Option<(BlockNumber, H256)>>,
+
 	importer: Importer,
 }
 
@@ -255,6 +284,7 @@ impl Importer {
 			miner,
 			ancient_verifier: AncientVerifier::new(engine.clone()),
 			engine,
+			reorg_guard: Mutex::new(ReorgGuard::default()),
 		})
 	}
 
@@ -266,7 +296,13 @@ impl Importer {
 			return 0;
 		}
 
+		// Leave verified blocks in the queue until import is resumed.
+		if client.import_paused.load(AtomicOrdering::SeqCst) {
+			return 0;
+		}
+
 		let max_blocks_to_import = 4;
+		let mut halted = None;
 		let (imported_blocks, import_results, invalid_blocks, imported, proposed_blocks, duration, is_empty) = {
 			let mut imported_blocks = Vec::with_capacity(max_blocks_to_import);
 			let mut invalid_blocks = HashSet::new();
@@ -274,14 +310,24 @@ impl Importer {
 			let mut import_results = Vec::with_capacity(max_blocks_to_import);
 
 			let _import_lock = self.import_lock.lock();
-			let blocks = self.block_queue.drain(max_blocks_to_import);
+			let blocks = {
+				let mut guard = self.reorg_guard.lock();
+				if guard.alert.is_some() {
+					return 0;
+				}
+				match guard.held.is_empty() {
+					true => self.block_queue.drain(max_blocks_to_import),
+					false => ::std::mem::replace(&mut guard.held, Vec::new()),
+				}
+			};
 			if blocks.is_empty() {
 				return 0;
 			}
 			trace_time!("import_verified_blocks");
 			let start = Instant::now();
 
-			for block in blocks {
+			let mut blocks = blocks.into_iter();
+			while let Some(block) = blocks.next() {
 				let header = block.header.clone();
 				let bytes = block.bytes.clone();
 				let hash = header.hash();
@@ -292,6 +338,16 @@ impl Importer {
 					continue;
 				}
 
+				if let Some(alert) = self.check_reorg_depth(&header, client) {
+					error!(target: "client", "Block import halted: #{} ({}) would retract {} blocks down to #{} ({}), exceeding the maximal reorganization depth. Use parity_acceptReorg to proceed.",
+						alert.new_number, alert.new_hash, alert.depth, alert.ancestor_number, alert.ancestor_hash);
+					let mut guard = self.reorg_guard.lock();
+					guard.alert = Some(alert.clone());
+					guard.held = Some(block).into_iter().chain(blocks.by_ref()).collect();
+					halted = Some(alert);
+					break;
+				}
+
 				if let Ok(closed_block) = self.check_and_close_block(block, client) {
 					if self.engine.is_proposal(&header) {
 						self.block_queue.mark_as_good(&[hash]);
@@ -342,11 +398,58 @@ impl Importer {
 			}
 		}
 
+		if let Some(alert) = halted {
+			client.notify(|notify| notify.reorg_halted(&alert));
+		}
+
 		let db = client.db.read();
 		db.key_value().flush().expect("DB flush failed.");
 		imported
 	}
 
+	/// Check if importing the block would retract more canonical blocks than allowed.
+	fn check_reorg_depth(&self, header: &Header, client: &Client) -> Option<ReorgAlert> {
+		let max_depth = client.config.max_reorg_depth?;
+		let hash = header.hash();
+		{
+			let mut guard = self.reorg_guard.lock();
+			if guard.accepted == Some(hash) {
+				guard.accepted = None;
+				return None;
+			}
+		}
+
+		let chain = client.chain.read();
+		let best_hash = chain.best_block_hash();
+		if *header.parent_hash() == best_hash {
+			return None;
+		}
+
+		// only reorganizations making the block canonical are relevant.
+		let parent_difficulty = chain.block_details(header.parent_hash())?.total_difficulty;
+		if parent_difficulty + *header.difficulty() <= chain.best_block_total_difficulty() {
+			return None;
+		}
+
+		let route = chain.tree_route(best_hash, *header.parent_hash())?;
+		let depth = route.index as u64;
+		if depth <= max_depth {
+			return None;
+		}
+
+		let best_number = chain.best_block_number();
+		Some(ReorgAlert {
+			best_number,
+			best_hash,
+			ancestor_number: best_number - depth,
+			ancestor_hash: route.ancestor,
+			new_number: header.number(),
+			new_hash: hash,
+			depth,
+			detected_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+		})
+	}
+
 	fn check_and_close_block(&self, block: PreverifiedBlock, client: &Client) -> Result<LockedBlock, ()> {
 		let engine = &*self.engine;
 		let header = block.header.clone();
@@ -401,6 +504,7 @@ impl Importer {
 			block,
 			engine,
 			client.tracedb.read().tracing_enabled(),
+			client.tracedb.read().state_diffs_enabled(),
 			db,
 			&parent,
 			last_hashes,
@@ -470,6 +574,7 @@ impl Importer {
 		// Commit results
 		let receipts = block.receipts().to_owned();
 		let traces = block.traces().clone().drain();
+		let state_diffs = block.state_diffs().map(|d| d.to_vec());
 
 		assert_eq!(header.hash(), view!(BlockView, block_data).header_view().hash());
 
@@ -547,6 +652,7 @@ impl Importer {
 
 		client.tracedb.read().import(&mut batch, TraceImportRequest {
 			traces: traces.into(),
+			state_diffs: state_diffs,
 			block_hash: hash.clone(),
 			block_number: number,
 			enacted: route.enacted.clone(),
@@ -736,6 +842,15 @@ impl Client {
 			config.history
 		};
 
+		// keep enough eras to serve queries within the state query window.
+		let history = match config.state_query_history {
+			Some(window) if window > history => {
+				info!(target: "client", "Raising pruning history to {} to serve state queries", window);
+				window
+			},
+			_ => history,
+		};
+
 		if !chain.block_header_data(&chain.best_block_hash()).map_or(true, |h| state_db.journal_db().contains(&h.state_root())) {
 			warn!("State root not found for block #{} ({:x})", chain.best_block_number(), chain.best_block_hash());
 		}
@@ -753,6 +868,7 @@ impl Client {
 
 		let client = Arc::new(Client {
 			enabled: AtomicBool::new(true),
+			import_paused: AtomicBool::new(false),
 			sleep_state: Mutex::new(SleepState::new(awake)),
 			liveness: AtomicBool::new(awake),
 			mode: Mutex::new(config.mode.clone()),
@@ -778,6 +894,7 @@ impl Client {
 			registrar: registry::Registry::default(),
 			registrar_address,
 			exit_handler: Mutex::new(None),
+			last_finalized: RwLock::new(None),
 			importer,
 		});
 
@@ -928,6 +1045,29 @@ impl Client {
 		self.importer.import_verified_blocks(self)
 	}
 
+	/// Pause block import. Blocks keep being verified and queued, but nothing is written
+	/// to the database until `resume_import` is called.
+	pub fn pause_import(&self) {
+		if !self.import_paused.swap(true, AtomicOrdering::SeqCst) {
+			warn!(target: "client", "Block import paused.");
+		}
+	}
+
+	/// Resume block import paused with `pause_import`.
+	pub fn resume_import(&self) {
+		if self.import_paused.swap(false, AtomicOrdering::SeqCst) {
+			info!(target: "client", "Block import resumed.");
+			if let Err(e) = self.io_channel.lock().send(ClientIoMessage::BlockVerified) {
+				debug!(target: "client", "Error sending BlockVerified message: {:?}", e);
+			}
+		}
+	}
+
+	/// Returns true if block import is paused.
+	pub fn is_import_paused(&self) -> bool {
+		self.import_paused.load(AtomicOrdering::SeqCst)
+	}
+
 	// use a state-proving closure for the given block.
 	fn with_proving_caller<F, T>(&self, id: BlockId, with_call: F) -> T
 		where F: FnOnce(&::machine::Call) -> T
@@ -1037,6 +1177,11 @@ impl Client {
 				return None;
 			}
 
+			// states outside of the query window are treated as pruned
+			if db.is_pruned() && !self.is_within_state_query_history(block_number) {
+				return None;
+			}
+
 			let root = header.state_root();
 			State::from_existing(db, root, self.engine.account_start_nonce(block_number), self.factories.clone()).ok()
 		})
@@ -1084,6 +1229,36 @@ impl Client {
 		self.chain.read().collect_garbage();
 		self.importer.block_queue.collect_garbage();
 		self.tracedb.read().collect_garbage();
+		self.freeze_ancient_blocks();
+	}
+
+	/// Indexes or drops the transactions of canonical blocks after the transaction index
+	/// policy changed from `previous` to the configured one.
+	pub fn update_transaction_index(&self, previous: TransactionIndex) -> Result<(), EthcoreError> {
+		let current = self.config.blockchain.tx_index;
+		if previous == current {
+			return Ok(());
+		}
+
+		let blocks = self.chain.read().update_transaction_index(previous)?;
+		info!("Transaction index of {} blocks updated from {} to {}.", blocks, previous, current);
+		Ok(())
+	}
+
+	fn freeze_ancient_blocks(&self) {
+		let threshold = match self.config.ancient_blocks_threshold {
+			Some(threshold) => ::std::cmp::max(threshold, MIN_ANCIENT_BLOCKS_THRESHOLD),
+			None => return,
+		};
+
+		// moving blocks competes with import for the disk.
+		if !self.importer.block_queue.queue_info().is_empty() {
+			return;
+		}
+
+		if let Err(e) = self.chain.read().freeze_ancient_blocks(threshold, MAX_ANCIENT_BLOCKS_TO_FREEZE) {
+			warn!(target: "client", "Failed to move ancient blocks to era files: {}", e);
+		}
 	}
 
 	fn check_snooze(&self) {
@@ -1156,24 +1331,167 @@ impl Client {
 		Ok(())
 	}
 
+	/// Returns true if transaction traces are recorded.
+	pub fn tracing_enabled(&self) -> bool {
+		self.tracedb.read().tracing_enabled()
+	}
+
+	/// Deletes traces and state diffs of canon blocks before given block, except genesis.
+	/// Returns the number of blocks whose traces have been deleted.
+	pub fn prune_traces(&self, before: BlockNumber) -> Result<u64, EthcoreError> {
+		const BATCH_SIZE: u64 = 10_000;
+
+		let tracedb = self.tracedb.read();
+		if !tracedb.tracing_enabled() {
+			return Err("Tracing is not enabled for this database.".into());
+		}
+
+		let chain = self.chain.read();
+		let before = ::std::cmp::min(before, chain.best_block_number() + 1);
+		let mut number = 1;
+		while number < before {
+			let end = ::std::cmp::min(number + BATCH_SIZE, before);
+			let mut batch = DBTransaction::new();
+			for n in number..end {
+				let hash = chain.block_hash(n).ok_or_else(|| format!("Block #{} not found.", n))?;
+				tracedb.remove(&mut batch, &hash);
+			}
+			self.db.read().key_value().write(batch)?;
+			debug!(target: "client", "Pruned traces of blocks #{}..#{}", number, end - 1);
+			number = end;
+		}
+
+		Ok(before.saturating_sub(1))
+	}
+
+	/// Re-executes given canon block and stores its traces, replacing existing ones.
+	/// Requires the state of the parent block to be available.
+	pub fn retrace_block(&self, number: BlockNumber) -> Result<(), EthcoreError> {
+		if !self.tracing_enabled() {
+			return Err("Tracing is not enabled for this database.".into());
+		}
+		if number == 0 {
+			return Ok(());
+		}
+
+		let block = self.block(BlockId::Number(number)).ok_or_else(|| format!("Block #{} not found.", number))?;
+		let header = block.decode_header();
+		let hash = header.hash();
+		let parent = self.block_header_decoded(BlockId::Hash(*header.parent_hash()))
+			.ok_or_else(|| format!("Parent of block #{} not found.", number))?;
+
+		if self.state_at(BlockId::Hash(parent.hash())).is_none() {
+			return Err(format!("State of block #{} is not available. Re-executing blocks requires their parent state.", parent.number()).into());
+		}
+
+		let transactions = block.transactions().into_iter()
+			.map(SignedTransaction::new)
+			.collect::<Result<Vec<_>, _>>()?;
+		let last_hashes = self.build_last_hashes(&parent.hash());
+		let db = self.state_db.read().boxed_clone_canon(&parent.hash());
+
+		let tracedb = self.tracedb.read();
+		let chain = self.chain.read();
+		let is_epoch_begin = chain.epoch_transition(parent.number(), parent.hash()).is_some();
+		let locked_block = enact_verified(
+			PreverifiedBlock { header, transactions, bytes: block.into_inner() },
+			&*self.engine,
+			true,
+			tracedb.state_diffs_enabled(),
+			db,
+			&parent,
+			last_hashes,
+			self.factories.clone(),
+			is_epoch_begin,
+			&mut chain.ancestry_with_metadata_iter(parent.hash()),
+		)?;
+
+		let mut batch = DBTransaction::new();
+		tracedb.import(&mut batch, TraceImportRequest {
+			traces: locked_block.traces().clone().drain().into(),
+			state_diffs: locked_block.state_diffs().map(|d| d.to_vec()),
+			block_hash: hash,
+			block_number: number,
+			enacted: vec![hash],
+			retracted: 0,
+		});
+		self.db.read().key_value().write(batch)?;
+
+		Ok(())
+	}
+
+	/// Re-executes given block on top of the given state database, without tracing.
+	/// The parent of the block has to be imported.
+	fn reenact_block(&self, block: PreverifiedBlock, db: StateDB, factories: Factories) -> Result<LockedBlock, EthcoreError> {
+		if block.header.number() == 0 {
+			return Err("The genesis block is not executed.".into());
+		}
+		let parent = self.block_header_decoded(BlockId::Hash(*block.header.parent_hash()))
+			.ok_or_else(|| format!("Parent of block #{} not found.", block.header.number()))?;
+		let last_hashes = self.build_last_hashes(&parent.hash());
+
+		let chain = self.chain.read();
+		let is_epoch_begin = chain.epoch_transition(parent.number(), parent.hash()).is_some();
+		enact_verified(
+			block,
+			&*self.engine,
+			false,
+			false,
+			db,
+			&parent,
+			last_hashes,
+			factories,
+			is_epoch_begin,
+			&mut chain.ancestry_with_metadata_iter(parent.hash()),
+		)
+	}
+
 	/// Ask the client what the history parameter is.
 	pub fn pruning_history(&self) -> u64 {
 		self.history
 	}
 
-	fn block_hash(chain: &BlockChain, id: BlockId) -> Option<H256> {
+	/// Returns true if state of given block is within the configured state query window.
+	fn is_within_state_query_history(&self, block_number: BlockNumber) -> bool {
+		match self.config.state_query_history {
+			Some(window) => block_number.saturating_add(window) > self.chain.read().best_block_number(),
+			None => true,
+		}
+	}
+
+	fn resolve_block_hash(&self, chain: &BlockChain, id: BlockId) -> Option<H256> {
 		match id {
 			BlockId::Hash(hash) => Some(hash),
 			BlockId::Number(number) => chain.block_hash(number),
 			BlockId::Earliest => chain.block_hash(0),
 			BlockId::Latest => Some(chain.best_block_hash()),
+			BlockId::Finalized => Some(self.finalized_block(chain).1),
+		}
+	}
+
+	/// Latest canon block considered final under the engine's finality rule.
+	/// Falls back to genesis if no finalized block is found.
+	fn finalized_block(&self, chain: &BlockChain) -> (BlockNumber, H256) {
+		let genesis = (0, chain.genesis_hash());
+		match self.engine.finality_rule() {
+			FinalityRule::Instant => (chain.best_block_number(), chain.best_block_hash()),
+			FinalityRule::Depth => {
+				let number = chain.best_block_number().saturating_sub(self.config.finality_depth);
+				chain.block_hash(number).map_or(genesis, |hash| (number, hash))
+			},
+			FinalityRule::Marked => {
+				let known = *self.last_finalized.read();
+				let finalized = latest_finalized(chain, known);
+				*self.last_finalized.write() = Some(finalized);
+				finalized
+			},
 		}
 	}
 
 	fn transaction_address(&self, id: TransactionId) -> Option<TransactionAddress> {
 		match id {
 			TransactionId::Hash(ref hash) => self.chain.read().transaction_address(hash),
-			TransactionId::Location(id, index) => Self::block_hash(&self.chain.read(), id).map(|hash| TransactionAddress {
+			TransactionId::Location(id, index) => self.resolve_block_hash(&self.chain.read(), id).map(|hash| TransactionAddress {
 				block_hash: hash,
 				index: index,
 			})
@@ -1224,38 +1542,41 @@ impl Client {
 		t: &SignedTransaction,
 		analytics: CallAnalytics,
 	) -> Result<Executed, CallError> {
-		fn call<V, T>(
-			state: &mut State<StateDB>,
-			env_info: &EnvInfo,
-			machine: &::machine::EthereumMachine,
-			state_diff: bool,
-			transaction: &SignedTransaction,
-			options: TransactOptions<T, V>,
-		) -> Result<Executed<T::Output, V::Output>, CallError> where
-			T: trace::Tracer,
-			V: trace::VMTracer,
-		{
-			let options = options
-				.dont_check_nonce()
-				.save_output_from_contract();
-			let original_state = if state_diff { Some(state.clone()) } else { None };
-
-			let mut ret = Executive::new(state, env_info, machine).transact_virtual(transaction, options)?;
+		let state_diff = analytics.state_diffing;
+		if state_diff {
+			state.record_accesses();
+		}
 
-			if let Some(original) = original_state {
-				ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
-			}
-			Ok(ret)
+		match (analytics.transaction_tracing, analytics.vm_tracing) {
+			(true, true) => Self::do_virtual_call_with(state, env_info, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
+			(true, false) => Self::do_virtual_call_with(state, env_info, machine, state_diff, t, TransactOptions::with_tracing()),
+			(false, true) => Self::do_virtual_call_with(state, env_info, machine, state_diff, t, TransactOptions::with_vm_tracing()),
+			(false, false) => Self::do_virtual_call_with(state, env_info, machine, state_diff, t, TransactOptions::with_no_tracing()),
 		}
+	}
 
-		let state_diff = analytics.state_diffing;
+	fn do_virtual_call_with<V, T>(
+		state: &mut State<StateDB>,
+		env_info: &EnvInfo,
+		machine: &::machine::EthereumMachine,
+		state_diff: bool,
+		transaction: &SignedTransaction,
+		options: TransactOptions<T, V>,
+	) -> Result<Executed<T::Output, V::Output>, CallError> where
+		T: trace::Tracer,
+		V: trace::VMTracer,
+	{
+		let options = options
+			.dont_check_nonce()
+			.save_output_from_contract();
+		let original_state = if state_diff { Some(state.clone()) } else { None };
 
-		match (analytics.transaction_tracing, analytics.vm_tracing) {
-			(true, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
-			(true, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing()),
-			(false, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_vm_tracing()),
-			(false, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_no_tracing()),
+		let mut ret = Executive::new(state, env_info, machine).transact_virtual(transaction, options)?;
+
+		if let Some(original) = original_state {
+			ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
 		}
+		Ok(ret)
 	}
 
 	fn block_number_ref(&self, id: &BlockId) -> Option<BlockNumber> {
@@ -1264,6 +1585,7 @@ impl Client {
 			BlockId::Hash(ref hash) => self.chain.read().block_number(hash),
 			BlockId::Earliest => Some(0),
 			BlockId::Latest => Some(self.chain.read().best_block_number()),
+			BlockId::Finalized => Some(self.finalized_block(&self.chain.read()).0),
 		}
 	}
 
@@ -1336,7 +1658,7 @@ impl BlockInfo for Client {
 	fn block_header(&self, id: BlockId) -> Option<::encoded::Header> {
 		let chain = self.chain.read();
 
-		Self::block_hash(&chain, id).and_then(|hash| chain.block_header_data(&hash))
+		self.resolve_block_hash(&chain, id).and_then(|hash| chain.block_header_data(&hash))
 	}
 
 	fn best_block_header(&self) -> Header {
@@ -1346,7 +1668,7 @@ impl BlockInfo for Client {
 	fn block(&self, id: BlockId) -> Option<encoded::Block> {
 		let chain = self.chain.read();
 
-		Self::block_hash(&chain, id).and_then(|hash| chain.block(&hash))
+		self.resolve_block_hash(&chain, id).and_then(|hash| chain.block(&hash))
 	}
 
 	fn code_hash(&self, address: &Address, id: BlockId) -> Option<H256> {
@@ -1443,6 +1765,21 @@ impl Call for Client {
 		Self::do_virtual_call(&machine, &env_info, state, transaction, analytics)
 	}
 
+	fn profile(&self, transaction: &SignedTransaction, state: &mut Self::State, header: &Header) -> Result<Executed<trace::FlatTrace, trace::Profile>, CallError> {
+		let env_info = EnvInfo {
+			number: header.number(),
+			author: header.author().clone(),
+			timestamp: header.timestamp(),
+			difficulty: header.difficulty().clone(),
+			last_hashes: self.build_last_hashes(header.parent_hash()),
+			gas_used: U256::default(),
+			gas_limit: U256::max_value(),
+		};
+		let options = TransactOptions::new(trace::NoopTracer, trace::Profiler::default());
+
+		Self::do_virtual_call_with(self.engine.machine(), &env_info, state, false, transaction, options)
+	}
+
 	fn call_many(&self, transactions: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError> {
 		let mut env_info = EnvInfo {
 			number: header.number(),
@@ -1466,6 +1803,26 @@ impl Call for Client {
 		Ok(results)
 	}
 
+	fn preview(&self, transaction: &SignedTransaction, state: &Self::State, header: &Header) -> Result<Executed, CallError> {
+		let env_info = EnvInfo {
+			number: header.number() + 1,
+			author: header.author().clone(),
+			timestamp: header.timestamp(),
+			difficulty: header.difficulty().clone(),
+			last_hashes: self.build_last_hashes(&header.hash()),
+			gas_used: U256::default(),
+			gas_limit: *header.gas_limit(),
+		};
+		let options = TransactOptions::with_no_tracing().save_output_from_contract();
+
+		let mut state = state.clone();
+		state.record_accesses();
+		let original = state.clone();
+		let mut ret = Executive::new(&mut state, &env_info, self.engine.machine()).transact(transaction, options)?;
+		ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
+		Ok(ret)
+	}
+
 	fn estimate_gas(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<U256, CallError> {
 		let (mut upper, max_upper, env_info) = {
 			let init = *header.gas_limit();
@@ -1487,24 +1844,35 @@ impl Call for Client {
 		let sender = t.sender();
 		let options = || TransactOptions::with_tracing().dont_check_nonce();
 
-		let cond = |gas| {
+		let exec = |gas| {
 			let mut tx = t.as_unsigned().clone();
 			tx.gas = gas;
 			let tx = tx.fake_sign(sender);
 
 			let mut clone = state.clone();
-			Ok(Executive::new(&mut clone, &env_info, self.engine.machine())
+			Executive::new(&mut clone, &env_info, self.engine.machine())
 				.transact_virtual(&tx, options())
+				.ok()
+		};
+
+		let cond = |gas| {
+			Ok(exec(gas)
 				.map(|r| r.exception.is_none())
 				.unwrap_or(false))
 		};
 
 		if !cond(upper)? {
 			upper = max_upper;
-			if !cond(upper)? {
-				trace!(target: "estimate_gas", "estimate_gas failed with {}", upper);
-				let err = ExecutionError::Internal(format!("Requires higher than upper limit of {}", upper));
-				return Err(err.into())
+			match exec(upper) {
+				Some(ref executed) if executed.exception.is_none() => {},
+				executed => {
+					trace!(target: "estimate_gas", "estimate_gas failed with {}", upper);
+					if let Some(reason) = executed.and_then(|executed| executed.revert_reason()) {
+						return Err(CallError::Reverted(reason))
+					}
+					let err = ExecutionError::Internal(format!("Requires higher than upper limit of {}", upper));
+					return Err(err.into())
+				},
 			}
 		}
 		let lower = t.gas_required(&self.engine.schedule(env_info.number)).into();
@@ -1629,12 +1997,12 @@ impl BlockChainClient for Client {
 	fn block_body(&self, id: BlockId) -> Option<encoded::Body> {
 		let chain = self.chain.read();
 
-		Self::block_hash(&chain, id).and_then(|hash| chain.block_body(&hash))
+		self.resolve_block_hash(&chain, id).and_then(|hash| chain.block_body(&hash))
 	}
 
 	fn block_status(&self, id: BlockId) -> BlockStatus {
 		let chain = self.chain.read();
-		match Self::block_hash(&chain, id) {
+		match self.resolve_block_hash(&chain, id) {
 			Some(ref hash) if chain.is_known(hash) => BlockStatus::InChain,
 			Some(hash) => self.importer.block_queue.status(&hash).into(),
 			None => BlockStatus::Unknown
@@ -1644,7 +2012,7 @@ impl BlockChainClient for Client {
 	fn block_total_difficulty(&self, id: BlockId) -> Option<U256> {
 		let chain = self.chain.read();
 
-		Self::block_hash(&chain, id).and_then(|hash| chain.block_details(&hash)).map(|d| d.total_difficulty)
+		self.resolve_block_hash(&chain, id).and_then(|hash| chain.block_details(&hash)).map(|d| d.total_difficulty)
 	}
 
 	fn storage_root(&self, address: &Address, id: BlockId) -> Option<H256> {
@@ -1653,7 +2021,7 @@ impl BlockChainClient for Client {
 
 	fn block_hash(&self, id: BlockId) -> Option<H256> {
 		let chain = self.chain.read();
-		Self::block_hash(&chain, id)
+		self.resolve_block_hash(&chain, id)
 	}
 
 	fn code(&self, address: &Address, state: StateOrBlock) -> Option<Option<Bytes>> {
@@ -1755,6 +2123,39 @@ impl BlockChainClient for Client {
 		Some(keys)
 	}
 
+	fn account_range(&self, id: BlockId, start: &H256, count: u64) -> Option<StateRange<RangeAccount>> {
+		let state = self.state_at(id)?;
+		let (root, db) = state.drop();
+
+		trie_range(db.as_hashdb(), &root, start, count, |hash, preimage, value| {
+			Ok(RangeAccount {
+				hash: hash,
+				address: preimage.map(|address| Address::from_slice(&address)),
+				account: ::rlp::decode(value)?,
+			})
+		})
+	}
+
+	fn storage_range(&self, id: BlockId, account: &Address, start: &H256, count: u64) -> Option<StateRange<RangeStorage>> {
+		let state = self.state_at(id)?;
+		let root = match state.storage_root(account) {
+			Ok(Some(root)) => root,
+			Ok(None) => return Some(StateRange { items: Vec::new(), next: None }),
+			Err(_) => return None,
+		};
+
+		let (_, db) = state.drop();
+		let account_db = self.factories.accountdb.readonly(db.as_hashdb(), keccak(account));
+		trie_range(account_db.as_hashdb(), &root, start, count, |hash, preimage, value| {
+			let value: U256 = ::rlp::decode(value)?;
+			Ok(RangeStorage {
+				hash: hash,
+				key: preimage.map(|key| H256::from_slice(&key)),
+				value: value.into(),
+			})
+		})
+	}
+
 	fn transaction(&self, id: TransactionId) -> Option<LocalizedTransaction> {
 		self.transaction_address(id).and_then(|address| self.chain.read().transaction(&address))
 	}
@@ -1788,6 +2189,10 @@ impl BlockChainClient for Client {
 			}))
 	}
 
+	fn transaction_index(&self) -> TransactionIndex {
+		self.config.blockchain.tx_index
+	}
+
 	fn tree_route(&self, from: &H256, to: &H256) -> Option<TreeRoute> {
 		let chain = self.chain.read();
 		match chain.is_known(from) && chain.is_known(to) {
@@ -1830,7 +2235,7 @@ impl BlockChainClient for Client {
 			let is_canon = |id| {
 				match id {
 					// If it is referred by number, then it is always on the canon chain.
-					&BlockId::Earliest | &BlockId::Latest | &BlockId::Number(_) => true,
+					&BlockId::Earliest | &BlockId::Latest | &BlockId::Finalized | &BlockId::Number(_) => true,
 					// If it is referred by hash, we see whether a hash -> number -> hash conversion gives us the same
 					// result.
 					&BlockId::Hash(ref hash) => chain.is_canon(hash),
@@ -1848,9 +2253,9 @@ impl BlockChainClient for Client {
 					.collect::<Vec<H256>>()
 			} else {
 				// Otherwise, we use a slower version that finds a link between from_block and to_block.
-				let from_hash = Self::block_hash(&chain, filter.from_block)?;
+				let from_hash = self.resolve_block_hash(&chain, filter.from_block)?;
 				let from_number = chain.block_number(&from_hash)?;
-				let to_hash = Self::block_hash(&chain, filter.from_block)?;
+				let to_hash = self.resolve_block_hash(&chain, filter.from_block)?;
 
 				let blooms = filter.bloom_possibilities();
 				let bloom_match = |header: &encoded::Header| {
@@ -1949,6 +2354,29 @@ impl BlockChainClient for Client {
 			.and_then(|number| self.tracedb.read().block_traces(number))
 	}
 
+	fn transaction_state_diff(&self, transaction: TransactionId) -> Option<StateDiff> {
+		if !self.tracedb.read().state_diffs_enabled() {
+			return None;
+		}
+
+		self.transaction_address(transaction)
+			.and_then(|tx_address| {
+				self.block_number(BlockId::Hash(tx_address.block_hash))
+					.and_then(|number| self.tracedb.read().transaction_state_diff(number, tx_address.index))
+			})
+	}
+
+	fn block_state_diffs(&self, block: BlockId) -> Option<Vec<(H256, StateDiff)>> {
+		if !self.tracedb.read().state_diffs_enabled() {
+			return None;
+		}
+
+		let number = self.block_number(block)?;
+		let hashes = self.block_body(BlockId::Number(number))?.transaction_hashes();
+		self.tracedb.read().block_state_diffs(number)
+			.map(|diffs| hashes.into_iter().zip(diffs).collect())
+	}
+
 	fn last_hashes(&self) -> LastHashes {
 		(*self.build_last_hashes(&self.chain.read().best_block_hash())).clone()
 	}
@@ -2006,6 +2434,45 @@ impl BlockChainClient for Client {
 	fn eip86_transition(&self) -> u64 {
 		self.engine().params().eip86_transition
 	}
+
+	fn pending_reorg(&self) -> Option<ReorgAlert> {
+		self.importer.reorg_guard.lock().alert.clone()
+	}
+
+	fn accept_reorg(&self) -> bool {
+		{
+			let mut guard = self.importer.reorg_guard.lock();
+			match guard.alert.take() {
+				Some(alert) => {
+					warn!(target: "client", "Accepted reorganization to #{} ({}), resuming block import.", alert.new_number, alert.new_hash);
+					guard.accepted = Some(alert.new_hash);
+				},
+				None => return false,
+			}
+		}
+
+		if let Err(e) = self.io_channel.lock().send(ClientIoMessage::BlockVerified) {
+			debug!(target: "client", "Error sending BlockVerified message: {:?}", e);
+		}
+		true
+	}
+
+	fn finality_status(&self) -> FinalityStatus {
+		let chain = self.chain.read();
+		let rule = self.engine.finality_rule();
+		let (finalized_number, finalized_hash) = self.finalized_block(&chain);
+		FinalityStatus {
+			rule: rule,
+			depth: match rule {
+				FinalityRule::Depth => Some(self.config.finality_depth),
+				_ => None,
+			},
+			best_number: chain.best_block_number(),
+			best_hash: chain.best_block_hash(),
+			finalized_number: finalized_number,
+			finalized_hash: finalized_hash,
+		}
+	}
 }
 
 impl IoClient for Client {
@@ -2015,9 +2482,10 @@ impl IoClient for Client {
 		self.queue_transactions.queue(&mut self.io_channel.lock(), len, move |client| {
 			trace_time!("import_queued_transactions");
 
+			let next_block = client.chain.read().best_block_number() + 1;
 			let txs: Vec<UnverifiedTransaction> = transactions
 				.iter()
-				.filter_map(|bytes| client.engine.decode_transaction(bytes).ok())
+				.filter_map(|bytes| client.engine.decode_transaction(bytes, next_block).ok())
 				.collect();
 
 			client.notify(|notify| {
@@ -2032,6 +2500,9 @@ impl IoClient for Client {
 
 	fn queue_ancient_block(&self, block_bytes: Bytes, receipts_bytes: Bytes) -> Result<H256, BlockImportError> {
 		trace_time!("queue_ancient_block");
+		if self.is_import_paused() {
+			bail!(BlockImportErrorKind::Other("Block import is paused".into()));
+		}
 		let header: Header = ::rlp::Rlp::new(&block_bytes).val_at(0)?;
 		let hash = header.hash();
 
@@ -2269,6 +2740,48 @@ impl super::traits::EngineClient for Client {
 	fn block_header(&self, id: BlockId) -> Option<::encoded::Header> {
 		BlockChainClient::block_header(self, id)
 	}
+
+	fn engine_data(&self, key: &[u8]) -> Option<Bytes> {
+		self.db.read().key_value().get(::db::COL_EXTRA, &engine_data_key(key))
+			.expect("Low level database error. Some issue with disk?")
+			.map(|value| value.into_vec())
+	}
+
+	fn set_engine_data(&self, key: &[u8], value: Bytes) {
+		let mut batch = DBTransaction::new();
+		batch.put_vec(::db::COL_EXTRA, &engine_data_key(key), value);
+		if let Err(e) = self.db.read().key_value().write(batch) {
+			warn!(target: "client", "Failed to persist engine data: {}", e);
+		}
+	}
+}
+
+/// Walks back from the best block to the latest block marked as finalized.
+/// Blocks never lose finality, so the walk stops at `known` if it is still canon.
+fn latest_finalized(chain: &BlockChain, known: Option<(BlockNumber, H256)>) -> (BlockNumber, H256) {
+	let known = known.and_then(|(number, hash)| match chain.block_hash(number) {
+		Some(canon) if canon == hash => Some((number, hash)),
+		_ => None,
+	});
+
+	let mut hash = chain.best_block_hash();
+	loop {
+		match chain.block_details(&hash) {
+			Some(ref details) if details.is_finalized => return (details.number, hash),
+			Some(ref details) if known.map_or(false, |(number, _)| details.number <= number) => {
+				return known.expect("checked by the guard; qed");
+			},
+			Some(ref details) if details.number > 0 => hash = details.parent,
+			_ => return (0, chain.genesis_hash()),
+		}
+	}
+}
+
+/// Key of engine data in the extras column, prefixed to stay clear of the chain extras.
+fn engine_data_key(key: &[u8]) -> Vec<u8> {
+	let mut prefixed = b"engine:".to_vec();
+	prefixed.extend_from_slice(key);
+	prefixed
 }
 
 impl ProvingBlockChainClient for Client {
@@ -2302,6 +2815,51 @@ impl ProvingBlockChainClient for Client {
 		)
 	}
 
+	fn block_witness(&self, id: BlockId) -> Result<Vec<Bytes>, EthcoreError> {
+		let block = self.block(id).ok_or_else(|| format!("Block {:?} not found.", id))?;
+		let header = block.decode_header();
+		if header.number() > 0 && self.state_at(BlockId::Hash(*header.parent_hash())).is_none() {
+			return Err(format!("State of block #{} is not available. Re-executing blocks requires their parent state.", header.number() - 1).into());
+		}
+		let transactions = block.transactions().into_iter()
+			.map(SignedTransaction::new)
+			.collect::<Result<Vec<_>, _>>()?;
+		let block = PreverifiedBlock { header, transactions, bytes: block.into_inner() };
+
+		// a fresh state database, so that no account is served from the shared caches,
+		// and without the accounts bloom, so that absent accounts are proven absent.
+		let recorder = witness::Recorder::new(self.state_db.read().journal_db().boxed_clone());
+		let witness = recorder.witness();
+		let db = StateDB::new(Box::new(recorder), 0).without_account_bloom();
+		self.reenact_block(block, db, self.factories.clone())?;
+
+		let nodes = witness.lock().drain().map(|(_, node)| node.into_vec()).collect();
+		Ok(nodes)
+	}
+
+	fn verify_block_witness(&self, block: Bytes, witness: &[Bytes]) -> Result<bool, EthcoreError> {
+		let header: Header = {
+			let rlp = ::rlp::Rlp::new(&block);
+			if rlp.item_count()? != 3 {
+				return Err(::rlp::DecoderError::RlpIncorrectListLen.into());
+			}
+			rlp.val_at(0)?
+		};
+
+		// the block is checked like an imported one, except for its state transition.
+		verification::verify_block_basic(&header, &block, &*self.engine)?;
+		let block = verification::verify_block_unordered(header.clone(), block, &*self.engine, true)?;
+
+		// witness items are keyed by their hash only.
+		let mut factories = self.factories.clone();
+		factories.accountdb = ::account_db::Factory::Plain;
+
+		let db = StateDB::new(witness::witness_db(witness), 0).without_account_bloom();
+		let locked_block = self.reenact_block(block, db, factories)?;
+
+		Ok(self.verifier.verify_block_final(&header, locked_block.block().header()).is_ok())
+	}
+
 	fn epoch_signal(&self, hash: H256) -> Option<Vec<u8>> {
 		// pending transitions are never deleted, and do not contain
 		// finality proofs by definition.
@@ -2315,6 +2873,43 @@ impl Drop for Client {
 	}
 }
 
+/// Reads up to `count` items of the secure trie at `root`, starting with the first key not below `start`.
+/// Preimages of the keys are looked up in `db`, where a fat DB keeps them.
+fn trie_range<T, F>(db: &HashDB, root: &H256, start: &H256, count: u64, decode: F) -> Option<StateRange<T>> where
+	F: Fn(H256, Option<DBValue>, &[u8]) -> Result<T, ::rlp::DecoderError>,
+{
+	let trie = match TrieDB::new(db, root) {
+		Ok(trie) => trie,
+		Err(e) => {
+			trace!(target: "client", "trie_range: Couldn't open the trie: {:?}", e);
+			return None;
+		}
+	};
+
+	let mut iter = trie.iter().ok()?;
+	iter.seek(start).ok()?;
+
+	let mut items = Vec::new();
+	for item in iter {
+		let (key, value) = item.ok()?;
+		let hash = H256::from_slice(&key);
+		if items.len() as u64 == count {
+			return Some(StateRange { items, next: Some(hash) });
+		}
+
+		let preimage = db.get(&keccak(&hash));
+		match decode(hash, preimage, &value) {
+			Ok(item) => items.push(item),
+			Err(e) => {
+				warn!(target: "client", "trie_range: Corrupt trie item {:?}: {:?}", hash, e);
+				return None;
+			}
+		}
+	}
+
+	Some(StateRange { items, next: None })
+}
+
 /// Returns `LocalizedReceipt` given `LocalizedTransaction`
 /// and a vector of receipts from given block up to transaction index.
 fn transaction_receipt(machine: &::machine::EthereumMachine, mut tx: LocalizedTransaction, mut receipts: Vec<Receipt>) -> LocalizedReceipt {
@@ -2365,6 +2960,32 @@ fn transaction_receipt(machine: &::machine::EthereumMachine, mut tx: LocalizedTr
 #[cfg(test)]
 mod tests {
 
+	#[test]
+	fn finds_finalized_block_far_behind_best() {
+		use ethereum_types::H256;
+		use kvdb::DBTransaction;
+		use blockchain::BlockProvider;
+		use test_helpers::generate_dummy_blockchain;
+		use super::latest_finalized;
+
+		let chain = generate_dummy_blockchain(6000);
+		let genesis = (0, chain.genesis_hash());
+		assert_eq!(latest_finalized(&chain, None), genesis);
+
+		let finalized = (10, chain.block_hash(10).unwrap());
+		let mut batch = DBTransaction::new();
+		chain.mark_finalized(&mut batch, finalized.1).unwrap();
+		chain.commit();
+		assert_eq!(latest_finalized(&chain, None), finalized);
+
+		// the search stops at a previously found finalized block
+		let known = (20, chain.block_hash(20).unwrap());
+		assert_eq!(latest_finalized(&chain, Some(known)), known);
+
+		// unless it isn't canon anymore
+		assert_eq!(latest_finalized(&chain, Some((20, H256::from(1)))), finalized);
+	}
+
 	#[test]
 	fn should_not_cache_details_before_commit() {
 		use client::{BlockChainClient, ChainInfo};
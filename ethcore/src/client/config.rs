@@ -22,6 +22,8 @@ use journaldb;
 
 pub use std::time::Duration;
 pub use blockchain::Config as BlockChainConfig;
+pub use blockchain::{ReceiptsStorage, TransactionIndex};
+pub use blooms_db::Config as BloomsConfig;
 pub use trace::Config as TraceConfig;
 pub use evm::VMType;
 
@@ -110,6 +112,8 @@ pub struct ClientConfig {
 	pub db_compaction: DatabaseCompactionProfile,
 	/// Should db have WAL enabled?
 	pub db_wal: bool,
+	/// Should a corrupted db be repaired automatically on startup?
+	pub db_auto_repair: bool,
 	/// Operating mode
 	pub mode: Mode,
 	/// The chain spec name
@@ -124,8 +128,20 @@ pub struct ClientConfig {
 	pub history: u64,
 	/// Ideal memory usage for state pruning history.
 	pub history_mem: usize,
+	/// Number of most recent blocks whose state must remain queryable, including the best block.
+	/// States of older blocks are reported as pruned. Must not be zero.
+	pub state_query_history: Option<u64>,
 	/// Check seal valididity on block import
 	pub check_seal: bool,
+	/// Halt import on reorganizations retracting more than this number of blocks.
+	pub max_reorg_depth: Option<u64>,
+	/// Number of confirmations after which a block is considered final
+	/// by engines without their own notion of finality.
+	pub finality_depth: u64,
+	/// Move bodies and receipts of blocks this far behind the best block to era files.
+	pub ancient_blocks_threshold: Option<u64>,
+	/// Layout of newly created bloom indexes.
+	pub blooms: BloomsConfig,
 }
 
 #[cfg(test)]
@@ -0,0 +1,346 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Index of the transactions sent from and to each address, for explorer-style queries.
+
+use std::cmp;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use bytes::Bytes;
+use ethereum_types::{H256, Address};
+use header::BlockNumber;
+use kvdb::{DBTransaction, KeyValueDB};
+use parking_lot::Mutex;
+use rlp::{self, RlpStream};
+use transaction::{Action, SignedTransaction};
+
+use client::{BlockChainClient, ChainInfo, ChainNotify, ChainRoute};
+use ids::BlockId;
+
+/// Blocks indexed on top of the imported ones while handling an import notification.
+const BLOCKS_PER_NOTIFICATION: u64 = 64;
+/// Blocks indexed in a single database transaction while catching up.
+const BLOCKS_PER_BATCH: u64 = 1024;
+
+const LAST_INDEXED_KEY: &'static [u8] = b"last";
+const VERSION_KEY: &'static [u8] = b"version";
+
+/// Layout of the index, to be bumped with every change of the keys or values.
+/// An index of another version is dropped and built again.
+const VERSION: u8 = 1;
+
+const SENT: u8 = 1;
+const RECEIVED: u8 = 2;
+
+/// Transaction sent from or to an address, as recorded in the index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressTransaction {
+	/// Number of the including block.
+	pub block_number: BlockNumber,
+	/// Index of the transaction in the block.
+	pub transaction_index: usize,
+	/// Transaction hash.
+	pub hash: H256,
+	/// Whether the transaction was sent by the address.
+	pub sent: bool,
+	/// Whether the transaction was sent to the address.
+	pub received: bool,
+}
+
+/// Key of a transaction under an address: address, block number and transaction index.
+/// Numbers are stored inverted and big-endian, so the transactions of each address are
+/// kept newest first and a query can seek to its last block.
+fn transaction_key(address: &Address, number: BlockNumber, index: usize) -> [u8; 32] {
+	let (number, index) = (!number, !(index as u32));
+	let mut key = [0u8; 32];
+	key[..20].copy_from_slice(address);
+	key[20..28].copy_from_slice(&[
+		(number >> 56) as u8, (number >> 48) as u8, (number >> 40) as u8, (number >> 32) as u8,
+		(number >> 24) as u8, (number >> 16) as u8, (number >> 8) as u8, number as u8,
+	]);
+	key[28..].copy_from_slice(&[(index >> 24) as u8, (index >> 16) as u8, (index >> 8) as u8, index as u8]);
+	key
+}
+
+/// Decodes an entry of the index, skipping entries which aren't transactions.
+fn decode_transaction(key: &[u8], value: &[u8]) -> Option<AddressTransaction> {
+	if key.len() != 32 || value.len() != 33 {
+		return None;
+	}
+
+	Some(AddressTransaction {
+		block_number: !key[20..28].iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64),
+		transaction_index: !key[28..32].iter().fold(0u32, |acc, byte| (acc << 8) | *byte as u32) as usize,
+		hash: H256::from_slice(&value[..32]),
+		sent: value[32] & SENT != 0,
+		received: value[32] & RECEIVED != 0,
+	})
+}
+
+/// Index of transactions by sender and recipient, kept in its own database.
+///
+/// Follows the canonical chain: transactions of retracted blocks are removed from the index.
+/// Contract creations are indexed under the sender only.
+pub struct ExplorerIndex {
+	db: Arc<KeyValueDB>,
+	client: Weak<BlockChainClient>,
+	update_lock: Mutex<()>,
+}
+
+impl ExplorerIndex {
+	/// Create new index in given database, following given client's chain.
+	pub fn new(db: Arc<KeyValueDB>, client: Weak<BlockChainClient>) -> Self {
+		let index = ExplorerIndex {
+			db,
+			client,
+			update_lock: Mutex::new(()),
+		};
+		index.check_version();
+		index
+	}
+
+	/// Drops the contents of an index of another version, so that it's built again.
+	fn check_version(&self) {
+		let version = self.db.get(None, VERSION_KEY).expect("Low level database error. Some issue with disk?");
+		if version.as_ref().map(|version| &version[..]) == Some(&[VERSION][..]) {
+			return;
+		}
+
+		let mut batch = DBTransaction::new();
+		for (key, _) in self.db.iter(None) {
+			batch.delete(None, &key);
+		}
+		if !batch.ops.is_empty() {
+			info!(target: "explorer", "The explorer index is of an older version, indexing all blocks again.");
+		}
+		batch.put(None, VERSION_KEY, &[VERSION]);
+		self.db.write(batch).expect("Low level database error. Some issue with disk?");
+	}
+
+	/// Number and hash of the last indexed block.
+	pub fn last_indexed(&self) -> Option<(BlockNumber, H256)> {
+		self.db.get(None, LAST_INDEXED_KEY)
+			.expect("Low level database error. Some issue with disk?")
+			.map(|value| {
+				let rlp = rlp::Rlp::new(&value);
+				(
+					rlp.val_at(0).expect("stored by `update`; qed"),
+					rlp.val_at(1).expect("stored by `update`; qed"),
+				)
+			})
+	}
+
+	/// Up to `limit` transactions sent from or to given address in the given range of blocks (inclusive),
+	/// newest first, skipping those rejected by `filter`. With `after` given, only the transactions
+	/// older than the one at that position (block number and transaction index) are returned.
+	///
+	/// Only the entries of the address from the starting position down to the last returned
+	/// or rejected one are read.
+	pub fn address_transactions<F>(
+		&self,
+		address: &Address,
+		from: BlockNumber,
+		to: BlockNumber,
+		after: Option<(BlockNumber, usize)>,
+		limit: usize,
+		mut filter: F,
+	) -> Vec<AddressTransaction> where
+		F: FnMut(&AddressTransaction) -> bool,
+	{
+		if from > to || limit == 0 {
+			return Vec::new();
+		}
+
+		let seek = match after {
+			Some((number, index)) if number <= to => transaction_key(address, number, index),
+			// the last transaction of block `to` comes first
+			_ => transaction_key(address, to, u32::max_value() as usize),
+		};
+		self.db.iter_from_prefix(None, &seek)
+			.take_while(|&(ref key, _)| key.starts_with(&address[..]))
+			.filter_map(|(key, value)| decode_transaction(&key, &value))
+			.skip_while(|transaction| after.map_or(false, |after| (transaction.block_number, transaction.transaction_index) >= after))
+			.take_while(|transaction| transaction.block_number >= from)
+			.filter(|transaction| filter(transaction))
+			.take(limit)
+			.collect()
+	}
+
+	/// Indexes all blocks up to the best block. Blocks while another update is in progress.
+	pub fn catch_up(&self) {
+		let _lock = self.update_lock.lock();
+		while !self.update(BLOCKS_PER_BATCH) {
+			if let Some((number, _)) = self.last_indexed() {
+				info!(target: "explorer", "Indexed transactions by address up to block #{}", number);
+			}
+		}
+	}
+
+	/// Indexes at most `max_blocks` blocks, after removing blocks which are no longer canonical.
+	/// Returns true if the index has caught up with the best block.
+	fn update(&self, max_blocks: u64) -> bool {
+		let client = match self.client.upgrade() {
+			Some(client) => client,
+			None => return true,
+		};
+
+		let mut batch = DBTransaction::new();
+		let mut last = self.last_indexed();
+
+		if let Some((number, hash)) = last {
+			if client.block_hash(BlockId::Number(number)) != Some(hash) {
+				let best = client.chain_info().best_block_hash;
+				let route = match client.tree_route(&hash, &best) {
+					Some(route) => route,
+					None => {
+						warn!(target: "explorer", "Unable to find the route from the last indexed block #{} ({}) to the best block.", number, hash);
+						return true;
+					},
+				};
+				for retracted in &route.blocks[..route.index] {
+					self.index_block(&*client, &mut batch, retracted, false);
+				}
+				last = client.block_number(BlockId::Hash(route.ancestor)).map(|number| (number, route.ancestor));
+			}
+		}
+
+		let best = client.chain_info().best_block_number;
+		let from = last.map_or(0, |(number, _)| number + 1);
+		let to = cmp::min(best, from + max_blocks.saturating_sub(1));
+		for number in from..to + 1 {
+			let hash = match client.block_hash(BlockId::Number(number)) {
+				Some(hash) => hash,
+				None => break,
+			};
+			self.index_block(&*client, &mut batch, &hash, true);
+			last = Some((number, hash));
+		}
+
+		if let Some((number, hash)) = last {
+			let mut stream = RlpStream::new_list(2);
+			stream.append(&number).append(&hash);
+			batch.put(None, LAST_INDEXED_KEY, &stream.out());
+		}
+		self.db.write(batch).expect("Low level database error. Some issue with disk?");
+
+		to >= best
+	}
+
+	fn index_block(&self, client: &BlockChainClient, batch: &mut DBTransaction, hash: &H256, insert: bool) {
+		let block = match client.block(BlockId::Hash(*hash)) {
+			Some(block) => block,
+			None => return,
+		};
+		let number = block.number();
+
+		for (index, transaction) in block.transactions().into_iter().enumerate() {
+			let transaction = match SignedTransaction::new(transaction) {
+				Ok(transaction) => transaction,
+				Err(_) => continue,
+			};
+			let sender = transaction.sender();
+			let mut addresses = vec![(sender, SENT)];
+			if let Action::Call(to) = transaction.action {
+				match to == sender {
+					true => addresses[0].1 |= RECEIVED,
+					false => addresses.push((to, RECEIVED)),
+				}
+			}
+
+			for (address, direction) in addresses {
+				let key = transaction_key(&address, number, index);
+				match insert {
+					true => {
+						let mut value = transaction.hash().to_vec();
+						value.push(direction);
+						batch.put(None, &key, &value)
+					},
+					false => batch.delete(None, &key),
+				}
+			}
+		}
+	}
+}
+
+impl ChainNotify for ExplorerIndex {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, route: ChainRoute, _sealed: Vec<H256>, _proposed: Vec<Bytes>, _duration: Duration) {
+		// `catch_up` running in the background will index the new blocks as well
+		if let Some(_lock) = self.update_lock.try_lock() {
+			// keep pace with the import and make up some of the lag, if any
+			self.update(route.enacted().len() as u64 + BLOCKS_PER_NOTIFICATION);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use ethereum_types::Address;
+	use kvdb::{DBTransaction, KeyValueDB};
+	use kvdb_memorydb;
+	use transaction::SignedTransaction;
+	use client::{BlockChainClient, EachBlockWith, TestBlockChainClient};
+	use ids::BlockId;
+	use super::{AddressTransaction, ExplorerIndex, VERSION_KEY};
+
+	#[test]
+	fn should_index_transactions_by_sender() {
+		let client = Arc::new(TestBlockChainClient::new());
+		client.add_blocks(3, EachBlockWith::Transaction);
+		let chain = client.clone() as Arc<BlockChainClient>;
+		let index = ExplorerIndex::new(Arc::new(kvdb_memorydb::create(0)), Arc::downgrade(&chain));
+
+		index.catch_up();
+		assert_eq!(index.last_indexed(), Some((3, client.block_hash(BlockId::Latest).unwrap())));
+
+		let block = client.block(BlockId::Number(2)).unwrap();
+		let transaction = SignedTransaction::new(block.transactions().remove(0)).unwrap();
+		assert_eq!(index.address_transactions(&transaction.sender(), 0, 3, None, 10, |_| true), vec![AddressTransaction {
+			block_number: 2,
+			transaction_index: 0,
+			hash: transaction.hash(),
+			sent: true,
+			received: false,
+		}]);
+		assert!(index.address_transactions(&transaction.sender(), 3, 3, None, 10, |_| true).is_empty());
+		assert!(index.address_transactions(&transaction.sender(), 0, 3, None, 10, |t| !t.sent).is_empty());
+		assert!(index.address_transactions(&5.into(), 0, 3, None, 10, |_| true).is_empty());
+
+		assert_eq!(index.address_transactions(&transaction.sender(), 0, 3, Some((3, 0)), 10, |_| true).len(), 1);
+		assert!(index.address_transactions(&transaction.sender(), 0, 3, Some((2, 0)), 10, |_| true).is_empty());
+	}
+
+	#[test]
+	fn should_drop_index_of_other_version() {
+		let client = Arc::new(TestBlockChainClient::new());
+		client.add_blocks(3, EachBlockWith::Transaction);
+		let chain = client.clone() as Arc<BlockChainClient>;
+		let db = Arc::new(kvdb_memorydb::create(0));
+		ExplorerIndex::new(db.clone(), Arc::downgrade(&chain)).catch_up();
+
+		let mut batch = DBTransaction::new();
+		batch.put(None, VERSION_KEY, &[0]);
+		batch.put(None, &[1u8; 32], &[2u8; 40]);
+		db.write(batch).unwrap();
+
+		let index = ExplorerIndex::new(db.clone(), Arc::downgrade(&chain));
+		assert_eq!(index.last_indexed(), None);
+		assert!(index.address_transactions(&Address::from_slice(&[1u8; 20]), 0, u64::max_value(), None, 10, |_| true).is_empty());
+		index.catch_up();
+		assert_eq!(index.last_indexed(), Some((3, client.block_hash(BlockId::Latest).unwrap())));
+	}
+}
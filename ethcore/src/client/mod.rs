@@ -22,16 +22,19 @@ mod config;
 mod error;
 #[cfg(any(test, feature = "test-helpers"))]
 mod evm_test_client;
+mod explorer;
 mod io_message;
 #[cfg(any(test, feature = "test-helpers"))]
 mod test_client;
 mod trace;
+mod witness;
 
 pub use self::client::*;
-pub use self::config::{Mode, ClientConfig, DatabaseCompactionProfile, BlockChainConfig, VMType};
+pub use self::config::{Mode, ClientConfig, DatabaseCompactionProfile, BlockChainConfig, BloomsConfig, ReceiptsStorage, TransactionIndex, VMType};
 pub use self::error::Error;
 #[cfg(any(test, feature = "test-helpers"))]
 pub use self::evm_test_client::{EvmTestClient, EvmTestError, TransactResult};
+pub use self::explorer::{ExplorerIndex, AddressTransaction};
 pub use self::io_message::ClientIoMessage;
 #[cfg(any(test, feature = "test-helpers"))]
 pub use self::test_client::{TestBlockChainClient, EachBlockWith};
@@ -46,6 +49,8 @@ pub use self::traits::{BlockChainClient, EngineClient, ProvingBlockChainClient,
 pub use types::ids::*;
 pub use types::trace_filter::Filter as TraceFilter;
 pub use types::pruning_info::PruningInfo;
+pub use types::reorg_alert::ReorgAlert;
+pub use types::finality::{FinalityRule, FinalityStatus};
 pub use types::call_analytics::CallAnalytics;
 
 pub use executive::{Executed, Executive, TransactOptions};
@@ -18,11 +18,11 @@
 
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrder};
 use std::sync::Arc;
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, BTreeMap, BTreeSet};
 use std::mem;
 use itertools::Itertools;
 use rustc_hex::FromHex;
-use hash::keccak;
+use hash::{keccak, KECCAK_EMPTY};
 use ethereum_types::{H256, U256, Address};
 use parking_lot::RwLock;
 use journaldb;
@@ -33,20 +33,21 @@ use rlp::{Rlp, RlpStream};
 use ethkey::{Generator, Random};
 use ethcore_miner::pool::VerifiedTransaction;
 use transaction::{self, Transaction, LocalizedTransaction, SignedTransaction, Action};
-use blockchain::{TreeRoute, BlockReceipts};
+use blockchain::{TreeRoute, BlockReceipts, TransactionIndex};
 use client::{
 	Nonce, Balance, ChainInfo, BlockInfo, ReopenBlock, CallContract, TransactionInfo, RegistryInfo,
 	PrepareOpenBlock, BlockChainClient, BlockChainInfo, BlockStatus, BlockId, Mode,
 	TransactionId, UncleId, TraceId, TraceFilter, LastHashes, CallAnalytics, BlockImportError,
 	ProvingBlockChainClient, ScheduleInfo, ImportSealedBlock, BroadcastProposalBlock, ImportBlock, StateOrBlock,
-	Call, StateClient, EngineInfo, AccountData, BlockChain, BlockProducer, SealedBlockImporter, IoClient
+	Call, StateClient, EngineInfo, AccountData, BlockChain, BlockProducer, SealedBlockImporter, IoClient, ReorgAlert,
+	FinalityRule, FinalityStatus
 };
 use db::{NUM_COLUMNS, COL_STATE};
 use header::{Header as BlockHeader, BlockNumber};
 use filter::Filter;
 use log_entry::LocalizedLogEntry;
 use receipt::{Receipt, LocalizedReceipt, TransactionOutcome};
-use error::ImportResult;
+use error::{ImportResult, Error as EthcoreError};
 use vm::Schedule;
 use miner::{self, Miner, MinerService};
 use spec::Spec;
@@ -57,7 +58,9 @@ use verification::queue::QueueInfo;
 use block::{OpenBlock, SealedBlock, ClosedBlock};
 use executive::Executed;
 use error::CallError;
-use trace::LocalizedTrace;
+use trace::{FlatTrace, LocalizedTrace, Profile};
+use types::state_diff::StateDiff;
+use types::state_range::{StateRange, RangeAccount, RangeStorage};
 use state_db::StateDB;
 use header::Header;
 use encoded;
@@ -90,6 +93,10 @@ pub struct TestBlockChainClient {
 	pub code: RwLock<HashMap<Address, Bytes>>,
 	/// Execution result.
 	pub execution_result: RwLock<Option<Result<Executed, CallError>>>,
+	/// Profile returned along with the execution result.
+	pub execution_profile: RwLock<Profile>,
+	/// Data persisted by the engine.
+	pub engine_data: RwLock<HashMap<Bytes, Bytes>>,
 	/// Transaction receipts.
 	pub receipts: RwLock<HashMap<TransactionId, LocalizedReceipt>>,
 	/// Logs
@@ -108,10 +115,16 @@ pub struct TestBlockChainClient {
 	pub first_block: RwLock<Option<(H256, u64)>>,
 	/// Traces to return
 	pub traces: RwLock<Option<Vec<LocalizedTrace>>>,
+	/// State diffs to return
+	pub state_diffs: RwLock<Option<Vec<(H256, StateDiff)>>>,
 	/// Pruning history size to report.
 	pub history: RwLock<Option<u64>>,
 	/// Is disabled
 	pub disabled: AtomicBool,
+	/// Reorganization halting block import.
+	pub pending_reorg: RwLock<Option<ReorgAlert>>,
+	/// Transaction index policy to report.
+	pub tx_index: RwLock<TransactionIndex>,
 }
 
 /// Used for generating test client blocks.
@@ -167,6 +180,8 @@ impl TestBlockChainClient {
 			storage: RwLock::new(HashMap::new()),
 			code: RwLock::new(HashMap::new()),
 			execution_result: RwLock::new(None),
+			execution_profile: RwLock::new(Profile::default()),
+			engine_data: RwLock::new(HashMap::new()),
 			receipts: RwLock::new(HashMap::new()),
 			logs: RwLock::new(Vec::new()),
 			queue_size: AtomicUsize::new(0),
@@ -176,8 +191,11 @@ impl TestBlockChainClient {
 			ancient_block: RwLock::new(None),
 			first_block: RwLock::new(None),
 			traces: RwLock::new(None),
+			state_diffs: RwLock::new(None),
 			history: RwLock::new(None),
 			disabled: AtomicBool::new(false),
+			pending_reorg: RwLock::new(None),
+			tx_index: RwLock::new(TransactionIndex::All),
 		};
 
 		// insert genesis hash.
@@ -198,6 +216,11 @@ impl TestBlockChainClient {
 		*self.execution_result.write() = Some(result);
 	}
 
+	/// Set the profile returned along with the execution result.
+	pub fn set_execution_profile(&self, profile: Profile) {
+		*self.execution_profile.write() = profile;
+	}
+
 	/// Set the balance of account `address` to `balance`.
 	pub fn set_balance(&self, address: Address, balance: U256) {
 		self.balances.write().insert(address, balance);
@@ -321,7 +344,7 @@ impl TestBlockChainClient {
 			BlockId::Hash(hash) => Some(hash),
 			BlockId::Number(n) => self.numbers.read().get(&(n as usize)).cloned(),
 			BlockId::Earliest => self.numbers.read().get(&0).cloned(),
-			BlockId::Latest => self.numbers.read().get(&(self.numbers.read().len() - 1)).cloned()
+			BlockId::Latest | BlockId::Finalized => self.numbers.read().get(&(self.numbers.read().len() - 1)).cloned()
 		}
 	}
 
@@ -350,6 +373,24 @@ impl TestBlockChainClient {
 		self.insert_transaction_with_gas_price_to_queue(U256::from(20_000_000_000u64))
 	}
 
+	/// Inserts a local transaction to miners transactions queue.
+	pub fn insert_local_transaction_to_queue(&self) -> H256 {
+		let keypair = Random.generate().unwrap();
+		let tx = Transaction {
+			action: Action::Create,
+			value: U256::from(100),
+			data: "3331600055".from_hex().unwrap(),
+			gas: U256::from(100_000),
+			gas_price: U256::from(20_000_000_000u64),
+			nonce: U256::zero()
+		};
+		let signed_tx = tx.sign(keypair.secret(), None);
+		self.set_balance(signed_tx.sender(), 10_000_000_000_000_000_000u64.into());
+		let hash = signed_tx.hash();
+		self.miner.import_own_transaction(self, signed_tx.into()).unwrap();
+		hash
+	}
+
 	/// Set reported history size.
 	pub fn set_history(&self, h: Option<u64>) {
 		*self.history.write() = h;
@@ -575,6 +616,26 @@ impl Call for TestBlockChainClient {
 		Ok(res)
 	}
 
+	fn preview(&self, _t: &SignedTransaction, _state: &Self::State, _header: &Header) -> Result<Executed, CallError> {
+		self.execution_result.read().clone().unwrap()
+	}
+
+	fn profile(&self, _t: &SignedTransaction, _state: &mut Self::State, _header: &Header) -> Result<Executed<FlatTrace, Profile>, CallError> {
+		self.execution_result.read().clone().unwrap().map(|executed| Executed {
+			exception: executed.exception,
+			gas: executed.gas,
+			gas_used: executed.gas_used,
+			refunded: executed.refunded,
+			cumulative_gas_used: executed.cumulative_gas_used,
+			logs: executed.logs,
+			contracts_created: executed.contracts_created,
+			output: executed.output,
+			trace: executed.trace,
+			vm_trace: Some(self.execution_profile.read().clone()),
+			state_diff: executed.state_diff,
+		})
+	}
+
 	fn estimate_gas(&self, _t: &SignedTransaction, _state: &Self::State, _header: &Header) -> Result<U256, CallError> {
 		Ok(21000.into())
 	}
@@ -585,6 +646,8 @@ impl StateInfo for () {
 	fn balance(&self, _address: &Address) -> trie::Result<U256> { unimplemented!() }
 	fn storage_at(&self, _address: &Address, _key: &H256) -> trie::Result<H256> { unimplemented!() }
 	fn code(&self, _address: &Address) -> trie::Result<Option<Arc<Bytes>>> { unimplemented!() }
+	fn code_hash(&self, _address: &Address) -> trie::Result<H256> { Ok(KECCAK_EMPTY) }
+	fn accessed(&self) -> Option<BTreeMap<Address, BTreeSet<H256>>> { None }
 }
 
 impl StateClient for TestBlockChainClient {
@@ -602,7 +665,7 @@ impl StateClient for TestBlockChainClient {
 
 impl EngineInfo for TestBlockChainClient {
 	fn engine(&self) -> &EthEngine {
-		unimplemented!()
+		&*self.spec.engine
 	}
 }
 
@@ -648,6 +711,14 @@ impl BlockChainClient for TestBlockChainClient {
 	fn list_storage(&self, _id: BlockId, _account: &Address, _after: Option<&H256>, _count: u64) -> Option<Vec<H256>> {
 		None
 	}
+
+	fn account_range(&self, _id: BlockId, _start: &H256, _count: u64) -> Option<StateRange<RangeAccount>> {
+		None
+	}
+
+	fn storage_range(&self, _id: BlockId, _account: &Address, _start: &H256, _count: u64) -> Option<StateRange<RangeStorage>> {
+		None
+	}
 	fn transaction(&self, _id: TransactionId) -> Option<LocalizedTransaction> {
 		None	// Simple default.
 	}
@@ -664,6 +735,10 @@ impl BlockChainClient for TestBlockChainClient {
 		self.receipts.read().get(&id).cloned()
 	}
 
+	fn transaction_index(&self) -> TransactionIndex {
+		*self.tx_index.read()
+	}
+
 	fn logs(&self, filter: Filter) -> Vec<LocalizedLogEntry> {
 		let mut logs = self.logs.read().clone();
 		let len = logs.len();
@@ -681,7 +756,7 @@ impl BlockChainClient for TestBlockChainClient {
 		match id {
 			BlockId::Number(number) => Some(number),
 			BlockId::Earliest => Some(0),
-			BlockId::Latest => Some(self.chain_info().best_block_number),
+			BlockId::Latest | BlockId::Finalized => Some(self.chain_info().best_block_number),
 			BlockId::Hash(ref h) =>
 				self.numbers.read().iter().find(|&(_, hash)| hash == h).map(|e| *e.0 as u64)
 		}
@@ -707,7 +782,7 @@ impl BlockChainClient for TestBlockChainClient {
 		match id {
 			BlockId::Number(number) if (number as usize) < self.blocks.read().len() => BlockStatus::InChain,
 			BlockId::Hash(ref hash) if self.blocks.read().get(hash).is_some() => BlockStatus::InChain,
-			BlockId::Latest | BlockId::Earliest => BlockStatus::InChain,
+			BlockId::Latest | BlockId::Earliest | BlockId::Finalized => BlockStatus::InChain,
 			_ => BlockStatus::Unknown,
 		}
 	}
@@ -806,6 +881,14 @@ impl BlockChainClient for TestBlockChainClient {
 		self.traces.read().clone()
 	}
 
+	fn transaction_state_diff(&self, _id: TransactionId) -> Option<StateDiff> {
+		self.state_diffs.read().clone().and_then(|diffs| diffs.into_iter().next()).map(|(_, diff)| diff)
+	}
+
+	fn block_state_diffs(&self, _id: BlockId) -> Option<Vec<(H256, StateDiff)>> {
+		self.state_diffs.read().clone()
+	}
+
 	fn ready_transactions(&self, max_len: usize) -> Vec<Arc<VerifiedTransaction>> {
 		self.miner.ready_transactions(self, max_len, miner::PendingOrdering::Priority)
 	}
@@ -848,6 +931,22 @@ impl BlockChainClient for TestBlockChainClient {
 	fn registrar_address(&self) -> Option<Address> { None }
 
 	fn eip86_transition(&self) -> u64 { u64::max_value() }
+
+	fn pending_reorg(&self) -> Option<ReorgAlert> { self.pending_reorg.read().clone() }
+
+	fn accept_reorg(&self) -> bool { self.pending_reorg.write().take().is_some() }
+
+	fn finality_status(&self) -> FinalityStatus {
+		let info = self.chain_info();
+		FinalityStatus {
+			rule: FinalityRule::Instant,
+			depth: None,
+			best_number: info.best_block_number,
+			best_hash: info.best_block_hash,
+			finalized_number: info.best_block_number,
+			finalized_hash: info.best_block_hash,
+		}
+	}
 }
 
 impl IoClient for TestBlockChainClient {
@@ -879,6 +978,14 @@ impl ProvingBlockChainClient for TestBlockChainClient {
 		None
 	}
 
+	fn block_witness(&self, _: BlockId) -> Result<Vec<Bytes>, EthcoreError> {
+		Err("Block witnesses are not supported by the test client.".into())
+	}
+
+	fn verify_block_witness(&self, _: Bytes, _: &[Bytes]) -> Result<bool, EthcoreError> {
+		Err("Block witnesses are not supported by the test client.".into())
+	}
+
 	fn epoch_signal(&self, _: H256) -> Option<Vec<u8>> {
 		None
 	}
@@ -911,4 +1018,12 @@ impl super::traits::EngineClient for TestBlockChainClient {
 	fn block_header(&self, id: BlockId) -> Option<::encoded::Header> {
 		BlockChainClient::block_header(self, id)
 	}
+
+	fn engine_data(&self, key: &[u8]) -> Option<Bytes> {
+		self.engine_data.read().get(key).cloned()
+	}
+
+	fn set_engine_data(&self, key: &[u8], value: Bytes) {
+		self.engine_data.write().insert(key.to_vec(), value);
+	}
 }
@@ -20,18 +20,18 @@ use std::sync::Arc;
 use itertools::Itertools;
 
 use block::{OpenBlock, SealedBlock, ClosedBlock};
-use blockchain::TreeRoute;
+use blockchain::{TreeRoute, TransactionIndex};
 use client::Mode;
 use encoded;
 use vm::LastHashes;
-use error::{ImportResult, CallError, BlockImportError};
+use error::{ImportResult, CallError, BlockImportError, Error as EthcoreError};
 use evm::Schedule;
 use executive::Executed;
 use filter::Filter;
 use header::{BlockNumber};
 use log_entry::LocalizedLogEntry;
 use receipt::LocalizedReceipt;
-use trace::LocalizedTrace;
+use trace::{FlatTrace, LocalizedTrace, Profile};
 use transaction::{self, LocalizedTransaction, SignedTransaction};
 use verification::queue::QueueInfo as BlockQueueInfo;
 use state::StateInfo;
@@ -50,6 +50,10 @@ use types::call_analytics::CallAnalytics;
 use types::blockchain_info::BlockChainInfo;
 use types::block_status::BlockStatus;
 use types::pruning_info::PruningInfo;
+use types::reorg_alert::ReorgAlert;
+use types::finality::FinalityStatus;
+use types::state_diff::StateDiff;
+use types::state_range::{StateRange, RangeAccount, RangeStorage};
 
 /// State information to be used during client query
 pub enum StateOrBlock {
@@ -188,6 +192,15 @@ pub trait Call {
 	/// Returns a vector of successes or a failure if any of the transaction fails.
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError>;
 
+	/// Executes a signed transaction on a copy of the given state as if it was included
+	/// in the block following `header`, checking the sender's nonce and balance.
+	/// The result includes the state diff of the execution.
+	fn preview(&self, tx: &SignedTransaction, state: &Self::State, header: &Header) -> Result<Executed, CallError>;
+
+	/// Makes a non-persistent transaction call, profiling gas and wall-clock time
+	/// spent by opcode and call frame.
+	fn profile(&self, tx: &SignedTransaction, state: &mut Self::State, header: &Header) -> Result<Executed<FlatTrace, Profile>, CallError>;
+
 	/// Estimates how much gas will be necessary for a call.
 	fn estimate_gas(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<U256, CallError>;
 }
@@ -212,7 +225,7 @@ pub trait IoClient: Sync + Send {
 
 /// Blockchain database client. Owns and manages a blockchain and a block queue.
 pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContract + RegistryInfo + ImportBlock
-+ IoClient {
++ IoClient + EngineInfo {
 	/// Look up the block number for the given block ID.
 	fn block_number(&self, id: BlockId) -> Option<BlockNumber>;
 
@@ -265,6 +278,16 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// If `after` is set the list starts with the following item.
 	fn list_storage(&self, id: BlockId, account: &Address, after: Option<&H256>, count: u64) -> Option<Vec<H256>>;
 
+	/// Get up to `count` accounts of the state of block `id`, in order of their address hash,
+	/// starting with the first account whose address hash is not below `start`.
+	/// Returns `None` if the state is not available.
+	fn account_range(&self, id: BlockId, start: &H256, count: u64) -> Option<StateRange<RangeAccount>>;
+
+	/// Get up to `count` storage slots of `account` in the state of block `id`, in order of
+	/// their key hash, starting with the first slot whose key hash is not below `start`.
+	/// Returns `None` if the state is not available.
+	fn storage_range(&self, id: BlockId, account: &Address, start: &H256, count: u64) -> Option<StateRange<RangeStorage>>;
+
 	/// Get transaction with given hash.
 	fn transaction(&self, id: TransactionId) -> Option<LocalizedTransaction>;
 
@@ -274,6 +297,9 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// Get transaction receipt with given hash.
 	fn transaction_receipt(&self, id: TransactionId) -> Option<LocalizedReceipt>;
 
+	/// Which transactions are looked up by hash.
+	fn transaction_index(&self) -> TransactionIndex;
+
 	/// Get a tree route between `from` and `to`.
 	/// See `BlockChain::tree_route`.
 	fn tree_route(&self, from: &H256, to: &H256) -> Option<TreeRoute>;
@@ -317,6 +343,12 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// Returns traces created by transaction from block.
 	fn block_traces(&self, trace: BlockId) -> Option<Vec<LocalizedTrace>>;
 
+	/// Returns recorded state diff of given transaction.
+	fn transaction_state_diff(&self, id: TransactionId) -> Option<StateDiff>;
+
+	/// Returns recorded state diffs of transactions in given block, along with their hashes.
+	fn block_state_diffs(&self, id: BlockId) -> Option<Vec<(H256, StateDiff)>>;
+
 	/// Get last hashes starting from best block.
 	fn last_hashes(&self) -> LastHashes;
 
@@ -380,6 +412,16 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 
 	/// Get the EIP-86 transition block number.
 	fn eip86_transition(&self) -> u64;
+
+	/// Returns the reorganization which halted block import, if any.
+	fn pending_reorg(&self) -> Option<ReorgAlert>;
+
+	/// Accept the pending reorganization and resume block import.
+	/// Returns `false` if import is not halted.
+	fn accept_reorg(&self) -> bool;
+
+	/// Returns the best block and the latest block considered final by the consensus engine.
+	fn finality_status(&self) -> FinalityStatus;
 }
 
 /// Provides `reopen_block` method
@@ -448,6 +490,12 @@ pub trait EngineClient: Sync + Send + ChainInfo {
 
 	/// Get raw block header data by block id.
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header>;
+
+	/// Get data the engine persisted under the given key.
+	fn engine_data(&self, key: &[u8]) -> Option<Bytes>;
+
+	/// Persist engine data under the given key, so that it survives restarts.
+	fn set_engine_data(&self, key: &[u8], value: Bytes);
 }
 
 /// Extended client interface for providing proofs of the state.
@@ -468,6 +516,16 @@ pub trait ProvingBlockChainClient: BlockChainClient {
 	/// to reproduce it.
 	fn prove_transaction(&self, transaction: SignedTransaction, id: BlockId) -> Option<(Bytes, Vec<DBValue>)>;
 
+	/// Re-execute a block, recording every state trie node and code read from its parent state.
+	/// Requires the state of the parent block to be available.
+	fn block_witness(&self, id: BlockId) -> Result<Vec<Bytes>, EthcoreError>;
+
+	/// Verify the given encoded block and re-execute it using only the state items of the given witness.
+	/// The parent of the block has to be imported.
+	/// Returns whether the resulting state root, receipts and gas used match the block header.
+	/// Fails if the witness is missing items needed to execute the block.
+	fn verify_block_witness(&self, block: Bytes, witness: &[Bytes]) -> Result<bool, EthcoreError>;
+
 	/// Get an epoch change signal by block hash.
 	fn epoch_signal(&self, hash: H256) -> Option<Vec<u8>>;
 }
@@ -0,0 +1,187 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block witnesses: the set of state trie nodes touched while executing a block.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use ethereum_types::H256;
+use hashdb::{HashDB, DBValue};
+use journaldb::{self, JournalDB};
+use kvdb::{self, DBTransaction};
+use kvdb_memorydb;
+use memorydb::MemoryDB;
+use parking_lot::Mutex;
+use util_error::UtilError;
+
+use db;
+
+/// Journal database which records every value read from the underlying database.
+///
+/// Values inserted through the recorder are not recorded when read back, so the
+/// witness only contains nodes of the state the execution started from.
+pub struct Recorder {
+	base: Box<JournalDB>,
+	inserted: HashSet<H256>,
+	witness: Arc<Mutex<HashMap<H256, DBValue>>>,
+}
+
+impl Recorder {
+	/// Create a new recorder over the given journal database.
+	pub fn new(base: Box<JournalDB>) -> Self {
+		Recorder {
+			base: base,
+			inserted: HashSet::new(),
+			witness: Default::default(),
+		}
+	}
+
+	/// Handle to the recorded values, shared with all clones of the recorder.
+	pub fn witness(&self) -> Arc<Mutex<HashMap<H256, DBValue>>> {
+		self.witness.clone()
+	}
+}
+
+impl HashDB for Recorder {
+	fn keys(&self) -> HashMap<H256, i32> {
+		self.base.keys()
+	}
+
+	fn get(&self, key: &H256) -> Option<DBValue> {
+		let value = self.base.get(key);
+		if let Some(ref value) = value {
+			if !self.inserted.contains(key) {
+				self.witness.lock().insert(*key, value.clone());
+			}
+		}
+		value
+	}
+
+	fn contains(&self, key: &H256) -> bool {
+		self.get(key).is_some()
+	}
+
+	fn insert(&mut self, value: &[u8]) -> H256 {
+		let key = self.base.insert(value);
+		self.inserted.insert(key);
+		key
+	}
+
+	fn emplace(&mut self, key: H256, value: DBValue) {
+		self.inserted.insert(key);
+		self.base.emplace(key, value)
+	}
+
+	fn remove(&mut self, key: &H256) {
+		self.base.remove(key)
+	}
+}
+
+impl JournalDB for Recorder {
+	fn boxed_clone(&self) -> Box<JournalDB> {
+		Box::new(Recorder {
+			base: self.base.boxed_clone(),
+			inserted: self.inserted.clone(),
+			witness: self.witness.clone(),
+		})
+	}
+
+	fn mem_used(&self) -> usize {
+		self.base.mem_used()
+	}
+
+	fn is_empty(&self) -> bool {
+		self.base.is_empty()
+	}
+
+	fn latest_era(&self) -> Option<u64> {
+		self.base.latest_era()
+	}
+
+	fn journal_under(&mut self, batch: &mut DBTransaction, now: u64, id: &H256) -> Result<u32, UtilError> {
+		self.base.journal_under(batch, now, id)
+	}
+
+	fn mark_canonical(&mut self, batch: &mut DBTransaction, era: u64, id: &H256) -> Result<u32, UtilError> {
+		self.base.mark_canonical(batch, era, id)
+	}
+
+	fn inject(&mut self, batch: &mut DBTransaction) -> Result<u32, UtilError> {
+		self.base.inject(batch)
+	}
+
+	fn state(&self, id: &H256) -> Option<Bytes> {
+		self.base.state(id)
+	}
+
+	fn is_pruned(&self) -> bool {
+		self.base.is_pruned()
+	}
+
+	fn backing(&self) -> &Arc<kvdb::KeyValueDB> {
+		self.base.backing()
+	}
+
+	fn consolidate(&mut self, overlay: MemoryDB) {
+		self.base.consolidate(overlay)
+	}
+}
+
+/// Journal database holding only the nodes of a witness, in memory.
+///
+/// Must be used with plain (not mangled) account databases, since witness
+/// nodes are keyed by their hash only.
+pub fn witness_db(witness: &[Bytes]) -> Box<JournalDB> {
+	let backing = Arc::new(kvdb_memorydb::create(db::NUM_COLUMNS.unwrap_or(0)));
+	let mut jdb = journaldb::new(backing, journaldb::Algorithm::Archive, db::COL_STATE);
+	for node in witness {
+		jdb.insert(node);
+	}
+	jdb
+}
+
+#[cfg(test)]
+mod tests {
+	use hashdb::HashDB;
+	use journaldb;
+	use hash::keccak;
+	use test_helpers::new_db;
+	use db;
+	use super::{Recorder, witness_db};
+
+	#[test]
+	fn records_reads_of_existing_values_only() {
+		let mut base = journaldb::new(new_db().key_value().clone(), journaldb::Algorithm::Archive, db::COL_STATE);
+		let existing = base.insert(b"existing");
+
+		let mut recorder = Recorder::new(base);
+		let inserted = recorder.insert(b"inserted");
+		assert!(recorder.get(&existing).is_some());
+		assert!(recorder.get(&inserted).is_some());
+
+		let witness = recorder.witness();
+		let witness = witness.lock();
+		assert_eq!(witness.len(), 1);
+		assert_eq!(&*witness[&existing], b"existing");
+
+		let values: Vec<_> = witness.values().map(|value| value.to_vec()).collect();
+		let checked = witness_db(&values);
+		assert!(checked.contains(&keccak(b"existing")));
+		assert!(!checked.contains(&inserted));
+	}
+}
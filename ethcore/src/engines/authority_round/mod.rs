@@ -36,7 +36,7 @@ use hash::keccak;
 use header::{Header, BlockNumber, ExtendedHeader};
 
 use super::signer::EngineSigner;
-use super::validator_set::{ValidatorSet, SimpleList, new_validator_set};
+use super::validator_set::{ValidatorSet, ValidatorSetInfo, MisbehaviorReport, SimpleList, new_validator_set};
 
 use self::finality::RollingFinality;
 
@@ -47,6 +47,8 @@ use rlp::{encode, Decodable, DecoderError, Encodable, RlpStream, Rlp};
 use ethereum_types::{H256, H520, Address, U128, U256};
 use parking_lot::{Mutex, RwLock};
 use unexpected::{Mismatch, OutOfBounds};
+use types::ancestry_action::AncestryAction;
+use types::finality::FinalityRule;
 
 mod finality;
 
@@ -1361,9 +1363,92 @@ impl Engine<EthereumMachine> for AuthorityRound {
 		}
 	}
 
+	fn ancestry_actions(&self, block: &ExecutedBlock, ancestry: &mut Iterator<Item=ExtendedHeader>) -> Vec<AncestryAction> {
+		// with immediate transitions there is no finality to track.
+		if self.immediate_transitions { return Vec::new() }
+
+		let client = match self.client.read().as_ref().and_then(|weak| weak.upgrade()) {
+			Some(client) => client,
+			None => {
+				warn!(target: "engine", "Unable to check for finality: missing client ref.");
+				return Vec::new();
+			}
+		};
+
+		let head = block.header();
+		let mut epoch_manager = self.epoch_manager.lock();
+		if !epoch_manager.zoom_to(&*client, &self.machine, &*self.validators, head) {
+			return Vec::new();
+		}
+
+		// a separate checker over the unfinalized ancestry, the epoch manager's one
+		// is driven by `is_epoch_end`.
+		let mut finality_checker = RollingFinality::blank(epoch_manager.finality_checker.validators().clone().into_inner());
+		let epoch_transition_hash = epoch_manager.epoch_transition_hash;
+
+		let mut parent_empty_steps_signers = match header_empty_steps_signers(head, self.empty_steps_transition) {
+			Ok(empty_step_signers) => empty_step_signers,
+			Err(_) => {
+				warn!(target: "finality", "Failed to get empty step signatures from block {}", head.hash());
+				return Vec::new();
+			}
+		};
+
+		let ancestry_iter = ancestry
+			.take_while(|extended| !extended.is_finalized && extended.header.number() != 0)
+			.map(|extended| {
+				let header = extended.header;
+				let mut signers = vec![header.author().clone()];
+				signers.extend(parent_empty_steps_signers.drain(..));
+
+				match header_empty_steps_signers(&header, self.empty_steps_transition) {
+					Ok(empty_step_signers) => {
+						parent_empty_steps_signers = empty_step_signers;
+						Some((header.hash(), signers))
+					},
+					Err(_) => {
+						warn!(target: "finality", "Failed to get empty step signatures from block {}", header.hash());
+						None
+					},
+				}
+			})
+			.while_some()
+			.take_while(|&(h, _)| h != epoch_transition_hash);
+
+		if let Err(_) = finality_checker.build_ancestry_subchain(ancestry_iter) {
+			debug!(target: "engine", "inconsistent validator set within epoch");
+			return Vec::new();
+		}
+
+		// the block itself isn't inserted yet, so only its ancestors can be marked.
+		let head_hash = head.hash();
+		finality_checker.push_hash(head_hash, vec![head.author().clone()])
+			.map(|finalized| finalized.into_iter()
+				.filter(|hash| *hash != head_hash)
+				.map(AncestryAction::MarkFinalized)
+				.collect())
+			.unwrap_or_default()
+	}
+
 	fn fork_choice(&self, new: &ExtendedHeader, current: &ExtendedHeader) -> super::ForkChoice {
 		super::total_difficulty_fork_choice(new, current)
 	}
+
+	fn finality_rule(&self) -> FinalityRule {
+		if self.immediate_transitions {
+			FinalityRule::Depth
+		} else {
+			FinalityRule::Marked
+		}
+	}
+
+	fn validator_set_info(&self, parent: &H256) -> Option<Result<ValidatorSetInfo, String>> {
+		Some(ValidatorSetInfo::new(&*self.validators, parent))
+	}
+
+	fn misbehavior_reports(&self) -> Option<Vec<MisbehaviorReport>> {
+		Some(self.validators.reports())
+	}
 }
 
 #[cfg(test)]
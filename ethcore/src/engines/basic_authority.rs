@@ -29,7 +29,7 @@ use header::{Header, ExtendedHeader};
 use client::EngineClient;
 use machine::{AuxiliaryData, Call, EthereumMachine};
 use super::signer::EngineSigner;
-use super::validator_set::{ValidatorSet, SimpleList, new_validator_set};
+use super::validator_set::{ValidatorSet, ValidatorSetInfo, MisbehaviorReport, SimpleList, new_validator_set};
 
 /// `BasicAuthority` params.
 #[derive(Debug, PartialEq)]
@@ -195,6 +195,14 @@ impl Engine<EthereumMachine> for BasicAuthority {
 	fn fork_choice(&self, new: &ExtendedHeader, current: &ExtendedHeader) -> super::ForkChoice {
 		super::total_difficulty_fork_choice(new, current)
 	}
+
+	fn validator_set_info(&self, parent: &H256) -> Option<Result<ValidatorSetInfo, String>> {
+		Some(ValidatorSetInfo::new(&*self.validators, parent))
+	}
+
+	fn misbehavior_reports(&self) -> Option<Vec<MisbehaviorReport>> {
+		Some(self.validators.reports())
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,639 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique (EIP-225) proof-of-authority engine.
+//!
+//! Blocks are signed by a set of authorized signers, which is changed by the signers
+//! voting on accounts in the headers they seal: the beneficiary is the voted account
+//! and the nonce tells whether to authorize or deauthorize it. Every `epoch` blocks a
+//! checkpoint resets the votes and lists the current signers in its extra data.
+
+mod snapshot;
+mod util;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Weak, Arc};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use ethereum_types::{H256, U256, Address};
+use ethkey::{Password, Signature};
+use lru_cache::LruCache;
+use parking_lot::{Mutex, RwLock};
+use rand::{self, Rng};
+use rlp;
+use account_provider::AccountProvider;
+use block::*;
+use client::{BlockId, EngineClient};
+use engines::{Engine, Seal, EngineError, ForkChoice};
+use error::{BlockError, Error};
+use ethjson;
+use header::{Header, ExtendedHeader};
+use io::{IoContext, IoHandler, IoService, TimerToken};
+use machine::EthereumMachine;
+use unexpected::{Mismatch, OutOfBounds};
+use super::signer::EngineSigner;
+use self::util::{NONCE_AUTH_VOTE, NONCE_DROP_VOTE};
+
+pub use self::snapshot::{Snapshot, Vote, Tally};
+
+/// Default minimum number of seconds between consecutive blocks.
+const DEFAULT_PERIOD: u64 = 15;
+/// Default number of blocks between checkpoints.
+const DEFAULT_EPOCH: u64 = 30_000;
+/// Difficulty of a block signed by the in-turn signer.
+const DIFF_INTURN: u64 = 2;
+/// Difficulty of a block signed by an out-of-turn signer.
+const DIFF_NOTURN: u64 = 1;
+/// Delay per signer allowed for out-of-turn signers, to avoid them all sealing at once.
+const WIGGLE_TIME_MS: u64 = 500;
+/// Number of signer snapshots kept in memory.
+const SNAPSHOT_CACHE_SIZE: usize = 128;
+/// Number of blocks between signer snapshots persisted to the database.
+const SNAPSHOT_PERSIST_INTERVAL: u64 = 1024;
+/// Interval of checking whether it's time to seal the next block.
+const STEP_INTERVAL_MS: u64 = 1000;
+
+/// `Clique` params.
+#[derive(Debug, PartialEq)]
+pub struct CliqueParams {
+	/// Minimum number of seconds between consecutive blocks.
+	pub period: u64,
+	/// Number of blocks between checkpoints.
+	pub epoch: u64,
+}
+
+impl From<ethjson::spec::CliqueParams> for CliqueParams {
+	fn from(p: ethjson::spec::CliqueParams) -> Self {
+		let epoch = p.epoch.map_or(DEFAULT_EPOCH, Into::into);
+		CliqueParams {
+			period: p.period.map_or(DEFAULT_PERIOD, Into::into),
+			epoch: if epoch == 0 { DEFAULT_EPOCH } else { epoch },
+		}
+	}
+}
+
+/// Engine using `Clique` proof-of-authority consensus.
+pub struct Clique {
+	machine: EthereumMachine,
+	period: u64,
+	epoch: u64,
+	signer: RwLock<EngineSigner>,
+	client: RwLock<Option<Weak<EngineClient>>>,
+	snapshots: Mutex<LruCache<H256, Snapshot>>,
+	proposals: RwLock<BTreeMap<Address, bool>>,
+	step_service: IoService<()>,
+}
+
+impl Clique {
+	/// Create a new instance of Clique engine.
+	pub fn new(our_params: CliqueParams, machine: EthereumMachine) -> Result<Arc<Self>, Error> {
+		let engine = Arc::new(
+			Clique {
+				machine: machine,
+				period: our_params.period,
+				epoch: our_params.epoch,
+				signer: Default::default(),
+				client: RwLock::new(None),
+				snapshots: Mutex::new(LruCache::new(SNAPSHOT_CACHE_SIZE)),
+				proposals: RwLock::new(BTreeMap::new()),
+				step_service: IoService::<()>::start()?,
+			});
+
+		// the step timer also runs with no period, so that an out-of-turn signer
+		// seals pending transactions once its wiggle time has passed
+		let handler = StepHandler { engine: Arc::downgrade(&engine) };
+		engine.step_service.register_handler(Arc::new(handler))?;
+
+		Ok(engine)
+	}
+
+	/// Signer snapshot after the block with the given hash.
+	///
+	/// Walks back to the closest cached or persisted snapshot or checkpoint and replays
+	/// the headers since. Recent signers aren't known at a checkpoint, so they start out empty.
+	pub fn snapshot(&self, hash: H256) -> Result<Snapshot, Error> {
+		if let Some(snapshot) = self.snapshots.lock().get_mut(&hash) {
+			return Ok(snapshot.clone());
+		}
+
+		let client = self.client.read().as_ref().and_then(Weak::upgrade)
+			.ok_or(EngineError::RequiresClient)?;
+
+		let mut headers = Vec::new();
+		let mut current = hash;
+		let mut snapshot = loop {
+			if let Some(snapshot) = self.snapshots.lock().get_mut(&current) {
+				break snapshot.clone();
+			}
+
+			let header = client.block_header(BlockId::Hash(current))
+				.ok_or(EngineError::CliqueMissingCheckpoint(current))?
+				.decode()?;
+			if header.number() % SNAPSHOT_PERSIST_INTERVAL == 0 {
+				let persisted = client.engine_data(&snapshot_key(&current))
+					.and_then(|bytes| rlp::decode::<Snapshot>(&bytes).map_err(|e| {
+						warn!(target: "engine", "Ignoring corrupt persisted snapshot of block {}: {}", current, e);
+					}).ok());
+				if let Some(snapshot) = persisted {
+					break snapshot;
+				}
+			}
+			if header.number() % self.epoch == 0 {
+				break Snapshot::new(header.number(), current, util::checkpoint_signers(&header)?);
+			}
+
+			current = *header.parent_hash();
+			headers.push(header);
+		};
+
+		for header in headers.iter().rev() {
+			snapshot.apply(header, util::recover_signer(header)?, self.epoch)?;
+			self.persist(&*client, &snapshot);
+		}
+
+		self.snapshots.lock().insert(snapshot.hash, snapshot.clone());
+		Ok(snapshot)
+	}
+
+	/// Persists snapshots at regular intervals, so that they needn't be rebuilt from
+	/// the last checkpoint after a restart.
+	fn persist(&self, client: &EngineClient, snapshot: &Snapshot) {
+		if snapshot.number % SNAPSHOT_PERSIST_INTERVAL == 0 {
+			client.set_engine_data(&snapshot_key(&snapshot.hash), rlp::encode(snapshot).into_vec());
+		}
+	}
+
+	/// Accounts this node votes on in the blocks it seals, and whether to authorize them.
+	pub fn proposals(&self) -> BTreeMap<Address, bool> {
+		self.proposals.read().clone()
+	}
+
+	/// Start voting on `address` in the blocks this node seals.
+	pub fn propose(&self, address: Address, authorize: bool) {
+		self.proposals.write().insert(address, authorize);
+	}
+
+	/// Stop voting on `address`. Returns whether there was such a proposal.
+	pub fn discard(&self, address: &Address) -> bool {
+		self.proposals.write().remove(address).is_some()
+	}
+
+	fn is_checkpoint(&self, header: &Header) -> bool {
+		header.number() % self.epoch == 0
+	}
+}
+
+fn snapshot_key(hash: &H256) -> Vec<u8> {
+	let mut key = b"clique-snapshot-".to_vec();
+	key.extend_from_slice(hash);
+	key
+}
+
+fn unix_now() -> Duration {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+struct StepHandler {
+	engine: Weak<Clique>,
+}
+
+const ENGINE_TIMEOUT_TOKEN: TimerToken = 23;
+
+impl IoHandler<()> for StepHandler {
+	fn initialize(&self, io: &IoContext<()>) {
+		io.register_timer(ENGINE_TIMEOUT_TOKEN, Duration::from_millis(STEP_INTERVAL_MS))
+			.unwrap_or_else(|e| warn!(target: "engine", "Failed to start consensus step timer: {}.", e))
+	}
+
+	fn timeout(&self, _io: &IoContext<()>, timer: TimerToken) {
+		if timer == ENGINE_TIMEOUT_TOKEN {
+			if let Some(engine) = self.engine.upgrade() {
+				engine.step();
+			}
+		}
+	}
+}
+
+impl Engine<EthereumMachine> for Clique {
+	fn name(&self) -> &str { "Clique" }
+
+	fn machine(&self) -> &EthereumMachine { &self.machine }
+
+	// Two fields - mix hash and the nonce carrying the vote.
+	fn seal_fields(&self, _header: &Header) -> usize { util::SEAL_FIELDS }
+
+	fn seals_internally(&self) -> Option<bool> {
+		Some(self.signer.read().is_some())
+	}
+
+	fn step(&self) {
+		if self.signer.read().is_some() {
+			if let Some(client) = self.client.read().as_ref().and_then(Weak::upgrade) {
+				client.update_sealing();
+			}
+		}
+	}
+
+	fn stop(&self) {
+		self.step_service.stop()
+	}
+
+	/// Attempt to seal the block internally. The signature itself is added to the extra data
+	/// in `on_seal_block`.
+	fn generate_seal(&self, block: &ExecutedBlock, parent: &Header) -> Seal {
+		let header = block.header();
+		let signer = match self.signer.read().address() {
+			Some(signer) => signer,
+			None => return Seal::None,
+		};
+
+		if self.period == 0 && block.transactions().is_empty() {
+			trace!(target: "engine", "generate_seal: not sealing empty block with no period");
+			return Seal::None;
+		}
+
+		let snapshot = match self.snapshot(parent.hash()) {
+			Ok(snapshot) => snapshot,
+			Err(e) => {
+				warn!(target: "engine", "generate_seal: failed to compute signers: {}", e);
+				return Seal::None;
+			},
+		};
+		if !snapshot.signers.contains(&signer) {
+			trace!(target: "engine", "generate_seal: {} is not an authorized signer", signer);
+			return Seal::None;
+		}
+		if snapshot.recently_signed(header.number(), &signer) {
+			trace!(target: "engine", "generate_seal: {} signed recently, waiting for others", signer);
+			return Seal::None;
+		}
+
+		// out-of-turn signers wait a bit so the in-turn one gets a chance first
+		let mut due = Duration::from_secs(header.timestamp());
+		if !snapshot.inturn(header.number(), &signer) {
+			let wiggle = (snapshot.signers.len() as u64 / 2 + 1) * WIGGLE_TIME_MS;
+			due += Duration::from_millis(rand::thread_rng().gen_range(0, wiggle));
+		}
+		if unix_now() < due {
+			trace!(target: "engine", "generate_seal: block #{} is not due yet", header.number());
+			return Seal::None;
+		}
+
+		let author = header.author();
+		let nonce = if !author.is_zero() && !snapshot.signers.contains(author) {
+			NONCE_AUTH_VOTE
+		} else {
+			NONCE_DROP_VOTE
+		};
+		Seal::Regular(vec![
+			rlp::encode(&H256::zero()).into_vec(),
+			rlp::encode(&nonce).into_vec(),
+		])
+	}
+
+	fn on_seal_block(&self, header: &mut Header) -> Result<(), Error> {
+		let signers = if self.is_checkpoint(header) {
+			self.snapshot(*header.parent_hash())?.signers
+		} else {
+			BTreeSet::new()
+		};
+
+		let mut extra_data = util::build_extra_data(header.extra_data(), &signers);
+		header.set_extra_data(extra_data.clone());
+		let signature = self.sign(util::seal_hash(header)?)?;
+		util::set_signature(&mut extra_data, &signature);
+		header.set_extra_data(extra_data);
+		Ok(())
+	}
+
+	fn verify_local_seal(&self, _header: &Header) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn verify_block_basic(&self, header: &Header) -> Result<(), Error> {
+		if header.number() == 0 {
+			return Ok(());
+		}
+
+		let checkpoint = self.is_checkpoint(header);
+		if checkpoint {
+			util::checkpoint_signers(header)?;
+			if !header.author().is_zero() {
+				return Err(EngineError::CliqueWrongAuthorCheckpoint(
+					Mismatch { expected: Address::zero(), found: *header.author() }
+				).into());
+			}
+		} else if util::signers_length(header)? != 0 {
+			return Err(EngineError::CliqueUnexpectedSigners.into());
+		}
+
+		let nonce = util::nonce(header)?;
+		if nonce != NONCE_DROP_VOTE && (checkpoint || nonce != NONCE_AUTH_VOTE) {
+			return Err(EngineError::CliqueInvalidNonce(nonce).into());
+		}
+
+		let mix_hash = util::mix_hash(header)?;
+		if !mix_hash.is_zero() {
+			return Err(BlockError::MismatchedH256SealElement(
+				Mismatch { expected: H256::zero(), found: mix_hash }
+			).into());
+		}
+
+		let difficulty = *header.difficulty();
+		if difficulty != DIFF_INTURN.into() && difficulty != DIFF_NOTURN.into() {
+			return Err(BlockError::DifficultyOutOfBounds(
+				OutOfBounds { min: Some(DIFF_NOTURN.into()), max: Some(DIFF_INTURN.into()), found: difficulty }
+			).into());
+		}
+
+		Ok(())
+	}
+
+	fn verify_block_family(&self, header: &Header, parent: &Header) -> Result<(), Error> {
+		if header.number() == 0 {
+			return Ok(());
+		}
+
+		let mut snapshot = self.snapshot(parent.hash())?;
+		if self.is_checkpoint(header) && util::checkpoint_signers(header)? != snapshot.signers {
+			return Err(EngineError::CliqueFaultyCheckpointSigners.into());
+		}
+
+		let signer = util::recover_signer(header)?;
+		let expected: U256 = if snapshot.inturn(header.number(), &signer) { DIFF_INTURN } else { DIFF_NOTURN }.into();
+		if *header.difficulty() != expected {
+			return Err(BlockError::InvalidDifficulty(
+				Mismatch { expected, found: *header.difficulty() }
+			).into());
+		}
+
+		snapshot.apply(header, signer, self.epoch)?;
+		if let Some(client) = self.client.read().as_ref().and_then(Weak::upgrade) {
+			self.persist(&*client, &snapshot);
+		}
+		self.snapshots.lock().insert(snapshot.hash, snapshot);
+		Ok(())
+	}
+
+	fn populate_from_parent(&self, header: &mut Header, parent: &Header) {
+		let snapshot = match self.snapshot(parent.hash()) {
+			Ok(snapshot) => snapshot,
+			Err(e) => {
+				warn!(target: "engine", "populate_from_parent: failed to compute signers: {}", e);
+				return;
+			},
+		};
+
+		let inturn = self.signer.read().address().map_or(false, |signer| snapshot.inturn(header.number(), &signer));
+		header.set_difficulty(if inturn { DIFF_INTURN } else { DIFF_NOTURN }.into());
+
+		// vote on one of the proposals that would still change something
+		let candidate = if self.is_checkpoint(header) {
+			None
+		} else {
+			self.proposals.read().iter()
+				.find(|&(address, &authorize)| snapshot.is_valid_vote(address, authorize))
+				.map(|(address, _)| *address)
+		};
+		header.set_author(candidate.unwrap_or_default());
+	}
+
+	fn open_block_header_timestamp(&self, parent_timestamp: u64) -> u64 {
+		::std::cmp::max(unix_now().as_secs(), parent_timestamp + self.period)
+	}
+
+	fn is_timestamp_valid(&self, header_timestamp: u64, parent_timestamp: u64) -> bool {
+		header_timestamp >= parent_timestamp + self.period
+	}
+
+	fn register_client(&self, client: Weak<EngineClient>) {
+		*self.client.write() = Some(client);
+	}
+
+	fn set_signer(&self, ap: Arc<AccountProvider>, address: Address, password: Password) {
+		self.signer.write().set(ap, address, password);
+	}
+
+	fn sign(&self, hash: H256) -> Result<Signature, Error> {
+		Ok(self.signer.read().sign(hash)?)
+	}
+
+	fn fork_choice(&self, new: &ExtendedHeader, current: &ExtendedHeader) -> ForkChoice {
+		super::total_difficulty_fork_choice(new, current)
+	}
+
+	fn as_clique(&self) -> Option<&Clique> {
+		Some(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeSet;
+	use std::sync::Arc;
+	use ethereum_types::{H64, H256, Address};
+	use ethkey::{self, KeyPair};
+	use hash::keccak;
+	use rlp;
+	use account_provider::AccountProvider;
+	use block::*;
+	use client::{BlockChainClient, BlockId, EachBlockWith, EngineClient, TestBlockChainClient};
+	use engines::{EngineError, Seal};
+	use error::{Error, ErrorKind};
+	use header::{Header, BlockNumber};
+	use spec::Spec;
+	use test_helpers::get_temp_state_db;
+	use transaction::{Action, Transaction};
+	use super::{Snapshot, util, snapshot_key, SNAPSHOT_PERSIST_INTERVAL};
+	use super::util::{NONCE_AUTH_VOTE, NONCE_DROP_VOTE};
+
+	fn keypair(secret: &str) -> KeyPair {
+		KeyPair::from_secret(keccak(secret).into()).unwrap()
+	}
+
+	fn signed_header(number: BlockNumber, author: Address, nonce: H64, signers: &[Address], signer: &KeyPair) -> Header {
+		let mut header = Header::default();
+		header.set_number(number);
+		header.set_author(author);
+		header.set_difficulty(1.into());
+		header.set_seal(vec![rlp::encode(&H256::zero()).into_vec(), rlp::encode(&nonce).into_vec()]);
+
+		let mut extra_data = util::build_extra_data(b"vanity", signers);
+		header.set_extra_data(extra_data.clone());
+		let signature = ethkey::sign(signer.secret(), &util::seal_hash(&header).unwrap()).unwrap();
+		util::set_signature(&mut extra_data, &signature);
+		header.set_extra_data(extra_data);
+		header
+	}
+
+	fn apply(snapshot: &mut Snapshot, header: &Header, epoch: u64) -> Result<(), Error> {
+		let signer = util::recover_signer(header)?;
+		snapshot.apply(header, signer, epoch)
+	}
+
+	fn snapshot_of(signers: &[&KeyPair]) -> Snapshot {
+		Snapshot::new(0, H256::zero(), signers.iter().map(|k| k.address()).collect())
+	}
+
+	#[test]
+	fn recovers_signer() {
+		let a = keypair("0");
+		let header = signed_header(1, Address::zero(), NONCE_DROP_VOTE, &[], &a);
+		assert_eq!(util::recover_signer(&header).unwrap(), a.address());
+		assert_eq!(&header.extra_data()[..6], b"vanity");
+	}
+
+	#[test]
+	fn rejects_unauthorized_and_recent_signers() {
+		let (a, b, c) = (keypair("0"), keypair("1"), keypair("2"));
+		let mut snapshot = snapshot_of(&[&a, &b]);
+
+		match apply(&mut snapshot.clone(), &signed_header(1, Address::zero(), NONCE_DROP_VOTE, &[], &c), 100) {
+			Err(Error(ErrorKind::Engine(EngineError::NotAuthorized(address)), _)) => assert_eq!(address, c.address()),
+			other => panic!("unexpected result: {:?}", other),
+		}
+
+		apply(&mut snapshot, &signed_header(1, Address::zero(), NONCE_DROP_VOTE, &[], &a), 100).unwrap();
+		match apply(&mut snapshot.clone(), &signed_header(2, Address::zero(), NONCE_DROP_VOTE, &[], &a), 100) {
+			Err(Error(ErrorKind::Engine(EngineError::CliqueTooRecentlySigned(address)), _)) => assert_eq!(address, a.address()),
+			other => panic!("unexpected result: {:?}", other),
+		}
+
+		apply(&mut snapshot, &signed_header(2, Address::zero(), NONCE_DROP_VOTE, &[], &b), 100).unwrap();
+		apply(&mut snapshot, &signed_header(3, Address::zero(), NONCE_DROP_VOTE, &[], &a), 100).unwrap();
+	}
+
+	#[test]
+	fn majority_authorizes_signer() {
+		let (a, b, c) = (keypair("0"), keypair("1"), keypair("2"));
+		let mut snapshot = snapshot_of(&[&a, &b]);
+
+		apply(&mut snapshot, &signed_header(1, c.address(), NONCE_AUTH_VOTE, &[], &a), 100).unwrap();
+		assert!(!snapshot.signers.contains(&c.address()));
+		assert_eq!(snapshot.tally[&c.address()].votes, 1);
+
+		apply(&mut snapshot, &signed_header(2, c.address(), NONCE_AUTH_VOTE, &[], &b), 100).unwrap();
+		assert!(snapshot.signers.contains(&c.address()));
+		assert!(snapshot.votes.is_empty());
+		assert!(snapshot.tally.is_empty());
+	}
+
+	#[test]
+	fn deauthorized_signer_votes_are_discarded() {
+		let (a, b, c, d) = (keypair("0"), keypair("1"), keypair("2"), keypair("3"));
+		let mut snapshot = snapshot_of(&[&a, &b, &c]);
+
+		apply(&mut snapshot, &signed_header(1, d.address(), NONCE_AUTH_VOTE, &[], &c), 100).unwrap();
+		apply(&mut snapshot, &signed_header(2, c.address(), NONCE_DROP_VOTE, &[], &a), 100).unwrap();
+		assert_eq!(snapshot.tally.len(), 2);
+
+		apply(&mut snapshot, &signed_header(3, c.address(), NONCE_DROP_VOTE, &[], &b), 100).unwrap();
+		let expected: BTreeSet<_> = vec![a.address(), b.address()].into_iter().collect();
+		assert_eq!(snapshot.signers, expected);
+		assert!(snapshot.votes.is_empty());
+		assert!(snapshot.tally.is_empty());
+	}
+
+	#[test]
+	fn checkpoint_resets_votes() {
+		let (a, b, c) = (keypair("0"), keypair("1"), keypair("2"));
+		let mut snapshot = snapshot_of(&[&a, &b]);
+		let signers: Vec<_> = snapshot.signers.iter().cloned().collect();
+
+		apply(&mut snapshot, &signed_header(1, c.address(), NONCE_AUTH_VOTE, &[], &a), 3).unwrap();
+		apply(&mut snapshot, &signed_header(2, Address::zero(), NONCE_DROP_VOTE, &[], &b), 3).unwrap();
+		assert_eq!(snapshot.votes.len(), 1);
+
+		apply(&mut snapshot, &signed_header(3, Address::zero(), NONCE_DROP_VOTE, &signers, &a), 3).unwrap();
+		assert!(snapshot.votes.is_empty());
+		assert!(snapshot.tally.is_empty());
+	}
+
+	#[test]
+	fn snapshot_rlp_roundtrip() {
+		let (a, b, c) = (keypair("0"), keypair("1"), keypair("2"));
+		let mut snapshot = snapshot_of(&[&a, &b]);
+		apply(&mut snapshot, &signed_header(1, c.address(), NONCE_AUTH_VOTE, &[], &a), 100).unwrap();
+		assert!(!snapshot.recents.is_empty() && !snapshot.votes.is_empty() && !snapshot.tally.is_empty());
+
+		let decoded: Snapshot = rlp::decode(&rlp::encode(&snapshot)).unwrap();
+		assert_eq!(decoded, snapshot);
+	}
+
+	#[test]
+	fn loads_persisted_snapshot() {
+		let client = Arc::new(TestBlockChainClient::new_with_spec(Spec::new_test_clique()));
+		let engine = &*client.spec.engine;
+		let engine_client: Arc<EngineClient> = client.clone();
+		engine.register_client(Arc::downgrade(&engine_client));
+
+		client.add_blocks(SNAPSHOT_PERSIST_INTERVAL as usize, EachBlockWith::Nothing);
+		let hash = client.block_hash(BlockId::Number(SNAPSHOT_PERSIST_INTERVAL)).unwrap();
+
+		// the unsealed test blocks can't be replayed from genesis
+		assert!(engine.as_clique().unwrap().snapshot(hash).is_err());
+
+		let a = keypair("0");
+		let snapshot = Snapshot::new(SNAPSHOT_PERSIST_INTERVAL, hash, vec![a.address()].into_iter().collect());
+		engine_client.set_engine_data(&snapshot_key(&hash), rlp::encode(&snapshot).into_vec());
+		assert_eq!(engine.as_clique().unwrap().snapshot(hash).unwrap(), snapshot);
+	}
+
+	#[test]
+	fn seals_and_verifies_block() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let signer = tap.insert_account(keccak("0").into(), &"".into()).unwrap();
+
+		let client = Arc::new(TestBlockChainClient::new_with_spec(Spec::new_test_clique()));
+		let engine = &*client.spec.engine;
+		let engine_client: Arc<EngineClient> = client.clone();
+		engine.register_client(Arc::downgrade(&engine_client));
+		engine.set_signer(tap, signer, "".into());
+		engine.as_clique().unwrap().propose(keypair("2").address(), true);
+
+		let genesis_header = client.spec.genesis_header();
+		let db = client.spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, signer, (3141562.into(), 31415620.into()), vec![], false, &mut Vec::new().into_iter()).unwrap();
+		assert_eq!(*b.header().author(), keypair("2").address());
+
+		// no empty blocks without a block period
+		assert_eq!(engine.generate_seal(b.block(), &genesis_header), Seal::None);
+
+		let transaction = Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 21_000.into(),
+			action: Action::Call(Address::zero()),
+			value: 1.into(),
+			data: vec![],
+		}.sign(keypair("0").secret(), None);
+		b.push_transaction(transaction, None).unwrap();
+		b.set_timestamp(1);
+
+		let b = b.close_and_lock();
+		let seal = match engine.generate_seal(b.block(), &genesis_header) {
+			Seal::Regular(seal) => seal,
+			_ => panic!("signer should seal the block"),
+		};
+		let sealed = b.seal(engine, seal).unwrap();
+		let header = sealed.header();
+
+		assert_eq!(util::recover_signer(header).unwrap(), signer);
+		assert_eq!(util::nonce(header).unwrap(), NONCE_AUTH_VOTE);
+		engine.verify_block_basic(header).unwrap();
+		engine.verify_block_family(header, &genesis_header).unwrap();
+	}
+}
@@ -0,0 +1,247 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique signer snapshots and vote tallying.
+
+use std::collections::{BTreeMap, BTreeSet};
+use ethereum_types::{H256, Address};
+use engines::EngineError;
+use error::{BlockError, Error};
+use header::{Header, BlockNumber};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use unexpected::Mismatch;
+use super::util::{self, NONCE_AUTH_VOTE, NONCE_DROP_VOTE};
+
+/// A vote a signer cast in one of its blocks.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct Vote {
+	/// Signer that cast the vote.
+	pub signer: Address,
+	/// Block in which the vote was cast.
+	pub block: BlockNumber,
+	/// Account being voted on.
+	pub address: Address,
+	/// Whether to authorize or deauthorize the voted account.
+	pub authorize: bool,
+}
+
+/// Running tally of the votes for an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+	/// Whether the votes are about authorizing or deauthorizing the account.
+	pub authorize: bool,
+	/// Number of votes cast so far.
+	pub votes: usize,
+}
+
+/// Authorization state of the signers at a given block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+	/// Block number where the snapshot was created.
+	pub number: BlockNumber,
+	/// Block hash where the snapshot was created.
+	pub hash: H256,
+	/// Authorized signers at this moment.
+	pub signers: BTreeSet<Address>,
+	/// Recent signers, to prevent a single signer from spamming blocks.
+	pub recents: BTreeMap<BlockNumber, Address>,
+	/// Votes cast since the last checkpoint, in chronological order.
+	pub votes: Vec<Vote>,
+	/// Current vote tally, to avoid recalculating.
+	pub tally: BTreeMap<Address, Tally>,
+}
+
+impl Snapshot {
+	/// Creates a snapshot with the given signers and no votes, as at a checkpoint.
+	pub fn new(number: BlockNumber, hash: H256, signers: BTreeSet<Address>) -> Self {
+		Snapshot {
+			number,
+			hash,
+			signers,
+			recents: BTreeMap::new(),
+			votes: Vec::new(),
+			tally: BTreeMap::new(),
+		}
+	}
+
+	/// Number of consecutive blocks out of which a signer may only sign one.
+	fn signer_limit(&self) -> BlockNumber {
+		self.signers.len() as BlockNumber / 2 + 1
+	}
+
+	/// Whether `signer` is the in-turn signer of block `number`.
+	pub fn inturn(&self, number: BlockNumber, signer: &Address) -> bool {
+		if self.signers.is_empty() {
+			return false;
+		}
+		let turn = (number % self.signers.len() as BlockNumber) as usize;
+		self.signers.iter().nth(turn) == Some(signer)
+	}
+
+	/// Whether `signer` signed too recently to be allowed to sign block `number`.
+	pub fn recently_signed(&self, number: BlockNumber, signer: &Address) -> bool {
+		let limit = self.signer_limit();
+		self.recents.iter().any(|(&seen, recent)| recent == signer && (number < limit || seen > number - limit))
+	}
+
+	/// Whether a vote on `address` would change anything, i.e. it's not already
+	/// in the state it is voted into.
+	pub fn is_valid_vote(&self, address: &Address, authorize: bool) -> bool {
+		self.signers.contains(address) != authorize
+	}
+
+	fn cast(&mut self, address: Address, authorize: bool) -> bool {
+		if !self.is_valid_vote(&address, authorize) {
+			return false;
+		}
+		self.tally.entry(address)
+			.or_insert(Tally { authorize, votes: 0 })
+			.votes += 1;
+		true
+	}
+
+	fn uncast(&mut self, address: &Address, authorize: bool) {
+		let remove = match self.tally.get_mut(address) {
+			Some(tally) if tally.authorize == authorize => {
+				tally.votes -= 1;
+				tally.votes == 0
+			},
+			_ => false,
+		};
+		if remove {
+			self.tally.remove(address);
+		}
+	}
+
+	/// Moves the snapshot to the given child header, signed by `signer`.
+	/// Fails if the signer wasn't allowed to sign it.
+	pub fn apply(&mut self, header: &Header, signer: Address, epoch: u64) -> Result<(), Error> {
+		let number = header.number();
+		if number != self.number + 1 {
+			return Err(BlockError::InvalidNumber(Mismatch { expected: self.number + 1, found: number }).into())
+		}
+
+		// votes are reset at every checkpoint
+		if number % epoch == 0 {
+			self.votes.clear();
+			self.tally.clear();
+		}
+
+		// the oldest recent signer is allowed to sign again
+		let limit = self.signer_limit();
+		if number >= limit {
+			self.recents.remove(&(number - limit));
+		}
+
+		if !self.signers.contains(&signer) {
+			return Err(EngineError::NotAuthorized(signer).into())
+		}
+		if self.recently_signed(number, &signer) {
+			return Err(EngineError::CliqueTooRecentlySigned(signer).into())
+		}
+		self.recents.insert(number, signer);
+
+		// a new vote from the signer on the same account replaces the previous one
+		let address = *header.author();
+		if let Some(position) = self.votes.iter().position(|vote| vote.signer == signer && vote.address == address) {
+			let vote = self.votes.remove(position);
+			self.uncast(&vote.address, vote.authorize);
+		}
+
+		let nonce = util::nonce(header)?;
+		let authorize = if nonce == NONCE_AUTH_VOTE {
+			true
+		} else if nonce == NONCE_DROP_VOTE {
+			false
+		} else {
+			return Err(EngineError::CliqueInvalidNonce(nonce).into())
+		};
+		if self.cast(address, authorize) {
+			self.votes.push(Vote { signer, block: number, address, authorize });
+		}
+
+		let passed = match self.tally.get(&address) {
+			Some(tally) if tally.votes > self.signers.len() / 2 => Some(tally.authorize),
+			_ => None,
+		};
+		if let Some(authorize) = passed {
+			if authorize {
+				self.signers.insert(address);
+			} else {
+				self.signers.remove(&address);
+
+				// the signer list shrunk, so the limit did too
+				let limit = self.signer_limit();
+				if number >= limit {
+					self.recents.remove(&(number - limit));
+				}
+
+				// discard all votes the deauthorized signer cast
+				let (discarded, kept): (Vec<Vote>, Vec<Vote>) = self.votes.drain(..).partition(|vote| vote.signer == address);
+				self.votes = kept;
+				for vote in discarded {
+					self.uncast(&vote.address, vote.authorize);
+				}
+			}
+
+			// the vote passed, so votes on the account start over
+			self.votes.retain(|vote| vote.address != address);
+			self.tally.remove(&address);
+		}
+
+		self.number = number;
+		self.hash = header.hash();
+		Ok(())
+	}
+}
+
+impl Encodable for Snapshot {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(6);
+		s.append(&self.number);
+		s.append(&self.hash);
+		s.append_list(&self.signers.iter().cloned().collect::<Vec<_>>());
+		s.begin_list(self.recents.len());
+		for (number, signer) in &self.recents {
+			s.begin_list(2).append(number).append(signer);
+		}
+		s.append_list(&self.votes);
+		s.begin_list(self.tally.len());
+		for (address, tally) in &self.tally {
+			s.begin_list(3).append(address).append(&tally.authorize).append(&tally.votes);
+		}
+	}
+}
+
+impl Decodable for Snapshot {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		let recents = rlp.at(3)?.iter()
+			.map(|recent| Ok((recent.val_at(0)?, recent.val_at(1)?)))
+			.collect::<Result<_, DecoderError>>()?;
+		let tally = rlp.at(5)?.iter()
+			.map(|tally| Ok((tally.val_at(0)?, Tally { authorize: tally.val_at(1)?, votes: tally.val_at(2)? })))
+			.collect::<Result<_, DecoderError>>()?;
+
+		Ok(Snapshot {
+			number: rlp.val_at(0)?,
+			hash: rlp.val_at(1)?,
+			signers: rlp.list_at::<Address>(2)?.into_iter().collect(),
+			recents,
+			votes: rlp.list_at(4)?,
+			tally,
+		})
+	}
+}
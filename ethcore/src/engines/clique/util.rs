@@ -0,0 +1,143 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique header encoding helpers.
+
+use std::collections::BTreeSet;
+use ethereum_types::{H64, H256, H520, Address};
+use ethkey::{self, Signature};
+use hash::keccak;
+use rlp::{Rlp, RlpStream};
+use engines::EngineError;
+use error::{BlockError, Error};
+use header::Header;
+use unexpected::Mismatch;
+
+/// Fixed number of extra-data prefix bytes reserved for signer vanity.
+pub const VANITY_LENGTH: usize = 32;
+/// Fixed number of extra-data suffix bytes reserved for the signer seal.
+pub const SIGNATURE_LENGTH: usize = 65;
+/// Number of seal fields: mix hash and nonce.
+pub const SEAL_FIELDS: usize = 2;
+/// Nonce of a header voting to add the beneficiary as a signer.
+pub const NONCE_AUTH_VOTE: H64 = H64([0xff; 8]);
+/// Nonce of a header voting to remove the beneficiary from the signers.
+pub const NONCE_DROP_VOTE: H64 = H64([0; 8]);
+
+/// Checks the extra data is long enough to hold the vanity and the signature.
+fn check_extra_data(header: &Header) -> Result<(), Error> {
+	let len = header.extra_data().len();
+	if len < VANITY_LENGTH {
+		return Err(EngineError::CliqueMissingVanity.into())
+	}
+	if len < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(EngineError::CliqueMissingSignature.into())
+	}
+	Ok(())
+}
+
+fn seal_field(header: &Header, index: usize) -> Result<Rlp, Error> {
+	match header.seal().get(index) {
+		Some(field) => Ok(Rlp::new(field)),
+		None => Err(BlockError::InvalidSealArity(
+			Mismatch { expected: SEAL_FIELDS, found: header.seal().len() }
+		).into()),
+	}
+}
+
+/// The mix hash of a clique header, which must always be zero.
+pub fn mix_hash(header: &Header) -> Result<H256, Error> {
+	Ok(seal_field(header, 0)?.as_val()?)
+}
+
+/// The nonce of a clique header, carrying the vote on the beneficiary.
+pub fn nonce(header: &Header) -> Result<H64, Error> {
+	Ok(seal_field(header, 1)?.as_val()?)
+}
+
+/// Hash signed by the block signer: the full header without the signature
+/// at the end of the extra data.
+pub fn seal_hash(header: &Header) -> Result<H256, Error> {
+	check_extra_data(header)?;
+	let extra_data = header.extra_data();
+
+	let mut s = RlpStream::new_list(13 + header.seal().len());
+	s.append(header.parent_hash());
+	s.append(header.uncles_hash());
+	s.append(header.author());
+	s.append(header.state_root());
+	s.append(header.transactions_root());
+	s.append(header.receipts_root());
+	s.append(header.log_bloom());
+	s.append(header.difficulty());
+	s.append(&header.number());
+	s.append(header.gas_limit());
+	s.append(header.gas_used());
+	s.append(&header.timestamp());
+	s.append(&extra_data[..extra_data.len() - SIGNATURE_LENGTH].to_vec());
+	for field in header.seal() {
+		s.append_raw(field, 1);
+	}
+	Ok(keccak(s.out()))
+}
+
+/// Recovers the address which signed the header.
+pub fn recover_signer(header: &Header) -> Result<Address, Error> {
+	let hash = seal_hash(header)?;
+	let extra_data = header.extra_data();
+	let signature: Signature = H520::from_slice(&extra_data[extra_data.len() - SIGNATURE_LENGTH..]).into();
+	Ok(ethkey::public_to_address(&ethkey::recover(&signature, &hash)?))
+}
+
+/// Signers listed between the vanity and the signature of a checkpoint header.
+pub fn checkpoint_signers(header: &Header) -> Result<BTreeSet<Address>, Error> {
+	check_extra_data(header)?;
+	let extra_data = header.extra_data();
+	let signers = &extra_data[VANITY_LENGTH..extra_data.len() - SIGNATURE_LENGTH];
+	if signers.is_empty() {
+		return Err(EngineError::CliqueCheckpointNoSigner.into())
+	}
+	if signers.len() % 20 != 0 {
+		return Err(EngineError::CliqueCheckpointInvalidSigners(signers.len()).into())
+	}
+	Ok(signers.chunks(20).map(Address::from_slice).collect())
+}
+
+/// Length of the signer list embedded in the extra data.
+pub fn signers_length(header: &Header) -> Result<usize, Error> {
+	check_extra_data(header)?;
+	Ok(header.extra_data().len() - VANITY_LENGTH - SIGNATURE_LENGTH)
+}
+
+/// Builds the extra data of a header from the given vanity and signers, leaving
+/// a zeroed signature in place. The vanity is truncated or zero-padded to `VANITY_LENGTH` bytes.
+pub fn build_extra_data<'a, I>(vanity: &[u8], signers: I) -> Vec<u8>
+	where I: IntoIterator<Item = &'a Address>
+{
+	let mut extra_data = vanity[..vanity.len().min(VANITY_LENGTH)].to_vec();
+	extra_data.resize(VANITY_LENGTH, 0);
+	for signer in signers {
+		extra_data.extend_from_slice(&**signer);
+	}
+	extra_data.extend_from_slice(&[0u8; SIGNATURE_LENGTH]);
+	extra_data
+}
+
+/// Replaces the signature at the end of the extra data.
+pub fn set_signature(extra_data: &mut Vec<u8>, signature: &Signature) {
+	let len = extra_data.len();
+	extra_data[len - SIGNATURE_LENGTH..].copy_from_slice(&**signature);
+}
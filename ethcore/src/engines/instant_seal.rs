@@ -14,20 +14,121 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::sync::{Weak, Arc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use client::EngineClient;
 use engines::{Engine, Seal};
+use error::Error;
+use io::{IoContext, IoHandler, IoService, TimerToken};
+use machine::EthereumMachine;
 use parity_machine::{Machine, Transactions, TotalScoredHeader};
+use parking_lot::{Mutex, RwLock};
+use types::finality::FinalityRule;
+
+/// What triggers sealing of a new block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SealMode {
+	/// Seal a block as soon as there are transactions to include.
+	Transaction,
+	/// Seal a block, possibly empty, every given interval.
+	Interval(Duration),
+	/// Seal blocks only on request.
+	Manual,
+}
+
+impl Default for SealMode {
+	fn default() -> Self {
+		SealMode::Transaction
+	}
+}
 
 /// An engine which does not provide any consensus mechanism, just seals blocks internally.
-/// Only seals blocks which have transactions.
-pub struct InstantSeal<M> {
+/// By default only seals blocks which have transactions.
+pub struct InstantSeal<M: Machine> {
 	machine: M,
+	mode: RwLock<SealMode>,
+	seal_requested: Arc<AtomicBool>,
+	time_offset: RwLock<u64>,
+	client: Arc<RwLock<Option<Weak<M::EngineClient>>>>,
+	interval_service: Mutex<Option<IoService<()>>>,
 }
 
-impl<M> InstantSeal<M> {
+impl<M: Machine> InstantSeal<M> {
 	/// Returns new instance of InstantSeal over the given state machine.
 	pub fn new(machine: M) -> Self {
 		InstantSeal {
 			machine: machine,
+			mode: RwLock::new(SealMode::default()),
+			seal_requested: Arc::new(AtomicBool::new(false)),
+			time_offset: RwLock::new(0),
+			client: Arc::new(RwLock::new(None)),
+			interval_service: Mutex::new(None),
+		}
+	}
+
+	/// Current sealing trigger.
+	pub fn mode(&self) -> SealMode {
+		*self.mode.read()
+	}
+
+	/// Makes the next block be sealed, even if it has no transactions.
+	pub fn request_seal(&self) {
+		self.seal_requested.store(true, Ordering::SeqCst);
+	}
+
+	/// Moves the timestamps of new blocks forward by the given number of seconds.
+	/// Returns the total shift.
+	pub fn increase_time(&self, seconds: u64) -> u64 {
+		let mut offset = self.time_offset.write();
+		*offset = offset.saturating_add(seconds);
+		*offset
+	}
+}
+
+impl InstantSeal<EthereumMachine> {
+	/// Changes what triggers sealing of new blocks.
+	pub fn set_mode(&self, mode: SealMode) -> Result<(), Error> {
+		let mut service = self.interval_service.lock();
+		if let Some(service) = service.take() {
+			service.stop();
+		}
+
+		if let SealMode::Interval(interval) = mode {
+			let interval_service = IoService::<()>::start()?;
+			interval_service.register_handler(Arc::new(IntervalHandler {
+				interval,
+				seal_requested: self.seal_requested.clone(),
+				client: self.client.clone(),
+			}))?;
+			*service = Some(interval_service);
+		}
+
+		*self.mode.write() = mode;
+		Ok(())
+	}
+}
+
+struct IntervalHandler {
+	interval: Duration,
+	seal_requested: Arc<AtomicBool>,
+	client: Arc<RwLock<Option<Weak<EngineClient>>>>,
+}
+
+const INTERVAL_TIMER_TOKEN: TimerToken = 23;
+
+impl IoHandler<()> for IntervalHandler {
+	fn initialize(&self, io: &IoContext<()>) {
+		io.register_timer(INTERVAL_TIMER_TOKEN, self.interval)
+			.unwrap_or_else(|e| warn!(target: "engine", "Failed to start block interval timer: {}.", e))
+	}
+
+	fn timeout(&self, _io: &IoContext<()>, timer: TimerToken) {
+		if timer == INTERVAL_TIMER_TOKEN {
+			self.seal_requested.store(true, Ordering::SeqCst);
+			if let Some(client) = self.client.read().as_ref().and_then(Weak::upgrade) {
+				client.update_sealing();
+			}
 		}
 	}
 }
@@ -45,8 +146,18 @@ impl<M: Machine> Engine<M> for InstantSeal<M>
 
 	fn seals_internally(&self) -> Option<bool> { Some(true) }
 
+	fn seals_empty_blocks(&self) -> bool {
+		self.seal_requested.load(Ordering::SeqCst)
+	}
+
 	fn generate_seal(&self, block: &M::LiveBlock, _parent: &M::Header) -> Seal {
-		if block.transactions().is_empty() { Seal::None } else { Seal::Regular(Vec::new()) }
+		let requested = self.seal_requested.swap(false, Ordering::SeqCst);
+		let seal = match self.mode() {
+			SealMode::Transaction => requested || !block.transactions().is_empty(),
+			SealMode::Interval(_) | SealMode::Manual => requested,
+		};
+
+		if seal { Seal::Regular(Vec::new()) } else { Seal::None }
 	}
 
 	fn verify_local_seal(&self, _header: &M::Header) -> Result<(), M::Error> {
@@ -57,16 +168,30 @@ impl<M: Machine> Engine<M> for InstantSeal<M>
 		use std::{time, cmp};
 
 		let now = time::SystemTime::now().duration_since(time::UNIX_EPOCH).unwrap_or_default();
-		cmp::max(now.as_secs(), parent_timestamp)
+		cmp::max(now.as_secs().saturating_add(*self.time_offset.read()), parent_timestamp)
 	}
 
 	fn is_timestamp_valid(&self, header_timestamp: u64, parent_timestamp: u64) -> bool {
 		header_timestamp >= parent_timestamp
 	}
 
+	fn register_client(&self, client: Weak<M::EngineClient>) {
+		*self.client.write() = Some(client);
+	}
+
+	fn stop(&self) {
+		if let Some(service) = self.interval_service.lock().as_ref() {
+			service.stop();
+		}
+	}
+
 	fn fork_choice(&self, new: &M::ExtendedHeader, current: &M::ExtendedHeader) -> super::ForkChoice {
 		super::total_difficulty_fork_choice(new, current)
 	}
+
+	fn finality_rule(&self) -> FinalityRule { FinalityRule::Instant }
+
+	fn as_instant_seal(&self) -> Option<&InstantSeal<M>> { Some(self) }
 }
 
 #[cfg(test)]
@@ -78,6 +203,7 @@ mod tests {
 	use header::Header;
 	use block::*;
 	use engines::Seal;
+	use super::SealMode;
 
 	#[test]
 	fn instant_can_seal() {
@@ -93,6 +219,41 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn instant_seals_on_request() {
+		let spec = Spec::new_instant();
+		let engine = &*spec.engine;
+		let instant = engine.as_instant_seal().unwrap();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let genesis_header = spec.genesis_header();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, Address::default(), (3141562.into(), 31415620.into()), vec![], false, &mut Vec::new().into_iter()).unwrap();
+		let b = b.close_and_lock();
+
+		// empty blocks are only sealed on request
+		assert_eq!(engine.generate_seal(b.block(), &genesis_header), Seal::None);
+		assert!(!engine.seals_empty_blocks());
+		instant.request_seal();
+		assert!(engine.seals_empty_blocks());
+		assert_eq!(engine.generate_seal(b.block(), &genesis_header), Seal::Regular(Vec::new()));
+		assert_eq!(engine.generate_seal(b.block(), &genesis_header), Seal::None);
+
+		instant.set_mode(SealMode::Manual).unwrap();
+		assert_eq!(engine.generate_seal(b.block(), &genesis_header), Seal::None);
+		instant.request_seal();
+		assert_eq!(engine.generate_seal(b.block(), &genesis_header), Seal::Regular(Vec::new()));
+	}
+
+	#[test]
+	fn instant_increases_time() {
+		let engine = Spec::new_instant().engine;
+		let instant = engine.as_instant_seal().unwrap();
+
+		assert_eq!(instant.increase_time(100), 100);
+		assert_eq!(instant.increase_time(20), 120);
+		assert!(engine.open_block_header_timestamp(0) >= 120);
+	}
+
 	#[test]
 	fn instant_cant_verify() {
 		let engine = Spec::new_instant().engine;
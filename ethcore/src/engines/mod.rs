@@ -27,14 +27,17 @@ mod validator_set;
 mod vote_collector;
 
 pub mod block_reward;
+pub mod clique;
 pub mod epoch;
 
 pub use self::authority_round::AuthorityRound;
 pub use self::basic_authority::BasicAuthority;
+pub use self::clique::Clique;
 pub use self::epoch::{EpochVerifier, Transition as EpochTransition};
-pub use self::instant_seal::InstantSeal;
+pub use self::instant_seal::{InstantSeal, SealMode};
 pub use self::null_engine::NullEngine;
 pub use self::tendermint::Tendermint;
+pub use self::validator_set::{ValidatorSetInfo, PendingValidatorChange, MisbehaviorReport, MisbehaviorKind};
 
 use std::sync::{Weak, Arc};
 use std::collections::{BTreeMap, HashMap};
@@ -53,10 +56,11 @@ use transaction::{self, UnverifiedTransaction, SignedTransaction};
 
 use ethkey::{Password, Signature};
 use parity_machine::{Machine, LocalizedMachine as Localized, TotalScoredHeader};
-use ethereum_types::{H256, U256, Address};
+use ethereum_types::{H64, H256, U256, Address};
 use unexpected::{Mismatch, OutOfBounds};
 use bytes::Bytes;
 use types::ancestry_action::AncestryAction;
+use types::finality::FinalityRule;
 
 /// Default EIP-210 contract code.
 /// As defined in https://github.com/ethereum/EIPs/pull/210
@@ -92,6 +96,26 @@ pub enum EngineError {
 	MalformedMessage(String),
 	/// Requires client ref, but none registered.
 	RequiresClient,
+	/// Clique header extra data is missing the signer vanity.
+	CliqueMissingVanity,
+	/// Clique header extra data is missing the signer seal.
+	CliqueMissingSignature,
+	/// Clique checkpoint header doesn't list any signers.
+	CliqueCheckpointNoSigner,
+	/// Clique checkpoint header lists signers of invalid total length.
+	CliqueCheckpointInvalidSigners(usize),
+	/// Clique checkpoint header lists signers other than the authorized ones.
+	CliqueFaultyCheckpointSigners,
+	/// Clique header which is not a checkpoint lists signers.
+	CliqueUnexpectedSigners,
+	/// Clique checkpoint header casts a vote.
+	CliqueWrongAuthorCheckpoint(Mismatch<Address>),
+	/// Clique header nonce is not a valid vote.
+	CliqueInvalidNonce(H64),
+	/// Clique signer signed one of the recent blocks.
+	CliqueTooRecentlySigned(Address),
+	/// Clique signers can't be computed because an ancestor header is unknown.
+	CliqueMissingCheckpoint(H256),
 }
 
 impl fmt::Display for EngineError {
@@ -107,6 +131,16 @@ impl fmt::Display for EngineError {
 			FailedSystemCall(ref msg) => format!("Failed to make system call: {}", msg),
 			MalformedMessage(ref msg) => format!("Received malformed consensus message: {}", msg),
 			RequiresClient => format!("Call requires client but none registered"),
+			CliqueMissingVanity => "Extra data is missing the vanity prefix".into(),
+			CliqueMissingSignature => "Extra data is missing the signature suffix".into(),
+			CliqueCheckpointNoSigner => "Checkpoint block doesn't list any signers".into(),
+			CliqueCheckpointInvalidSigners(len) => format!("Checkpoint block signer list has invalid length: {}", len),
+			CliqueFaultyCheckpointSigners => "Checkpoint block lists signers other than the authorized ones".into(),
+			CliqueUnexpectedSigners => "Non-checkpoint block lists signers".into(),
+			CliqueWrongAuthorCheckpoint(ref mis) => format!("Checkpoint block casts a vote: {}", mis),
+			CliqueInvalidNonce(ref nonce) => format!("Nonce is not a valid vote: {}", nonce),
+			CliqueTooRecentlySigned(ref address) => format!("Signer {} signed one of the recent blocks.", address),
+			CliqueMissingCheckpoint(ref hash) => format!("Unknown ancestor {} while computing signers", hash),
 		};
 
 		f.write_fmt(format_args!("Engine error ({})", msg))
@@ -228,11 +262,21 @@ pub trait Engine<M: Machine>: Sync + Send {
 		Ok(())
 	}
 
+	/// Header transformation functions, after the seal fields are set.
+	/// Allows engines which sign blocks outside of the seal to do so.
+	fn on_seal_block(&self, _header: &mut M::Header) -> Result<(), M::Error> {
+		Ok(())
+	}
+
 	/// None means that it requires external input (e.g. PoW) to seal a block.
 	/// Some(true) means the engine is currently prime for seal generation (i.e. node is the current validator).
 	/// Some(false) means that the node might seal internally but is not qualified now.
 	fn seals_internally(&self) -> Option<bool> { None }
 
+	/// Whether a block without transactions should be handed to `generate_seal` right away,
+	/// instead of after the maximum reseal period.
+	fn seals_empty_blocks(&self) -> bool { false }
+
 	/// Attempt to seal the block internally.
 	///
 	/// If `Some` is returned, then you get a valid seal.
@@ -366,8 +410,25 @@ pub trait Engine<M: Machine>: Sync + Send {
 		Vec::new()
 	}
 
+	/// How the engine decides that a block can no longer be reverted.
+	fn finality_rule(&self) -> FinalityRule { FinalityRule::Depth }
+
 	/// Check whether the given new block is the best block, after finalization check.
 	fn fork_choice(&self, new: &M::ExtendedHeader, best: &M::ExtendedHeader) -> ForkChoice;
+
+	/// Validators of the block after `parent` and validator set changes awaiting finality.
+	/// `None` if the engine doesn't use a validator set.
+	fn validator_set_info(&self, _parent: &H256) -> Option<Result<ValidatorSetInfo, String>> { None }
+
+	/// Validator misbehaviour reported by this node, oldest first.
+	/// `None` if the engine doesn't use a validator set.
+	fn misbehavior_reports(&self) -> Option<Vec<MisbehaviorReport>> { None }
+
+	/// Access to the signer voting of the Clique engine, `None` for other engines.
+	fn as_clique(&self) -> Option<&Clique> { None }
+
+	/// Access to the sealing controls of the InstantSeal engine, `None` for other engines.
+	fn as_instant_seal(&self) -> Option<&InstantSeal<M>> { None }
 }
 
 /// Check whether a given block is the best block based on the default total difficulty rule.
@@ -406,8 +467,8 @@ pub trait EthEngine: Engine<::machine::EthereumMachine> {
 	}
 
 	/// Some intrinsic operation parameters; by default they take their value from the `spec()`'s `engine_params`.
-	fn maximum_extra_data_size(&self) -> usize {
-		self.machine().maximum_extra_data_size()
+	fn maximum_extra_data_size(&self, block_number: BlockNumber) -> usize {
+		self.machine().maximum_extra_data_size(block_number)
 	}
 
 	/// The nonce with which accounts begin at given block.
@@ -456,9 +517,10 @@ pub trait EthEngine: Engine<::machine::EthereumMachine> {
 		self.machine().additional_params()
 	}
 
-	/// Performs pre-validation of RLP decoded transaction before other processing
-	fn decode_transaction(&self, transaction: &[u8]) -> Result<UnverifiedTransaction, transaction::Error> {
-		self.machine().decode_transaction(transaction)
+	/// Performs pre-validation of RLP decoded transaction to be included in block `block_number`
+	/// before other processing
+	fn decode_transaction(&self, transaction: &[u8], block_number: BlockNumber) -> Result<UnverifiedTransaction, transaction::Error> {
+		self.machine().decode_transaction(transaction, block_number)
 	}
 }
 
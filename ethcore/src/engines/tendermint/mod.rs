@@ -44,12 +44,13 @@ use engines::{Engine, Seal, EngineError, ConstructedVerifier};
 use engines::block_reward::{self, RewardKind};
 use io::IoService;
 use super::signer::EngineSigner;
-use super::validator_set::{ValidatorSet, SimpleList};
+use super::validator_set::{ValidatorSet, ValidatorSetInfo, MisbehaviorReport, SimpleList};
 use super::transition::TransitionHandler;
 use super::vote_collector::VoteCollector;
 use self::message::*;
 use self::params::TendermintParams;
 use machine::{AuxiliaryData, EthereumMachine};
+use types::finality::FinalityRule;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Step {
@@ -768,6 +769,16 @@ impl Engine<EthereumMachine> for Tendermint {
 	fn fork_choice(&self, new: &ExtendedHeader, current: &ExtendedHeader) -> super::ForkChoice {
 		super::total_difficulty_fork_choice(new, current)
 	}
+
+	fn finality_rule(&self) -> FinalityRule { FinalityRule::Instant }
+
+	fn validator_set_info(&self, parent: &H256) -> Option<Result<ValidatorSetInfo, String>> {
+		Some(ValidatorSetInfo::new(&*self.validators, parent))
+	}
+
+	fn misbehavior_reports(&self) -> Option<Vec<MisbehaviorReport>> {
+		Some(self.validators.reports())
+	}
 }
 
 #[cfg(test)]
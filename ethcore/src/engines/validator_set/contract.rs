@@ -27,7 +27,7 @@ use client::EngineClient;
 use header::{Header, BlockNumber};
 use machine::{AuxiliaryData, Call, EthereumMachine};
 
-use super::{ValidatorSet, SimpleList, SystemCall};
+use super::{ValidatorSet, SimpleList, SystemCall, PendingValidatorChange};
 use super::safe_contract::ValidatorSafeContract;
 
 use_contract!(validator_report, "ValidatorReport", "res/contracts/validator_report.json");
@@ -110,6 +110,10 @@ impl ValidatorSet for ValidatorContract {
 		self.validators.count_with_caller(bh, caller)
 	}
 
+	fn pending_changes(&self) -> Vec<PendingValidatorChange> {
+		self.validators.pending_changes()
+	}
+
 	fn report_malicious(&self, address: &Address, _set_block: BlockNumber, block: BlockNumber, proof: Bytes) {
 		let data = self.provider.functions().report_malicious().input(*address, block, proof);
 		match self.transact(data) {
@@ -22,6 +22,7 @@ mod simple_list;
 mod safe_contract;
 mod contract;
 mod multi;
+mod recorder;
 
 use std::sync::Weak;
 use ids::BlockId;
@@ -38,20 +39,82 @@ pub use self::simple_list::SimpleList;
 use self::contract::ValidatorContract;
 use self::safe_contract::ValidatorSafeContract;
 use self::multi::Multi;
+use self::recorder::ReportRecorder;
 use super::SystemCall;
 
 /// Creates a validator set from spec.
 pub fn new_validator_set(spec: ValidatorSpec) -> Box<ValidatorSet> {
+	Box::new(ReportRecorder::new(new_set(spec)))
+}
+
+fn new_set(spec: ValidatorSpec) -> Box<ValidatorSet> {
 	match spec {
 		ValidatorSpec::List(list) => Box::new(SimpleList::new(list.into_iter().map(Into::into).collect())),
 		ValidatorSpec::SafeContract(address) => Box::new(ValidatorSafeContract::new(address.into())),
 		ValidatorSpec::Contract(address) => Box::new(ValidatorContract::new(address.into())),
 		ValidatorSpec::Multi(sequence) => Box::new(
-			Multi::new(sequence.into_iter().map(|(block, set)| (block.into(), new_validator_set(set))).collect())
+			Multi::new(sequence.into_iter().map(|(block, set)| (block.into(), new_set(set))).collect())
 		),
 	}
 }
 
+/// Validator set change signalled by a block, but not finalized yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingValidatorChange {
+	/// Hash of the block signalling the change.
+	pub block_hash: H256,
+	/// Number of the block signalling the change.
+	pub block_number: BlockNumber,
+	/// Validators after the change.
+	pub validators: Vec<Address>,
+}
+
+/// Severity of reported validator misbehaviour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MisbehaviorKind {
+	/// Benign misbehaviour, e.g. a missed step.
+	Benign,
+	/// Malicious misbehaviour, e.g. double signing.
+	Malicious,
+}
+
+/// Validator misbehaviour reported by this node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MisbehaviorReport {
+	/// Reported validator.
+	pub validator: Address,
+	/// Severity of the misbehaviour.
+	pub kind: MisbehaviorKind,
+	/// Block at which the validator set was activated.
+	pub set_block: BlockNumber,
+	/// Block at which the misbehaviour happened.
+	pub block: BlockNumber,
+	/// UNIX timestamp of the report.
+	pub timestamp: u64,
+}
+
+/// Current state of a validator set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorSetInfo {
+	/// Validators of the block after the queried one.
+	pub validators: Vec<Address>,
+	/// Changes signalled but not finalized yet.
+	pub pending_changes: Vec<PendingValidatorChange>,
+}
+
+impl ValidatorSetInfo {
+	/// Queries the given set for validators after `parent`.
+	pub fn new(set: &ValidatorSet, parent: &H256) -> Result<Self, String> {
+		let validators = set.validators(parent)
+			.ok_or_else(|| format!("Validators after block {} could not be obtained.", parent))?;
+
+		Ok(ValidatorSetInfo {
+			validators,
+			pending_changes: set.pending_changes(),
+		})
+	}
+}
+
 /// A validator set.
 pub trait ValidatorSet: Send + Sync {
 	/// Get the default "Call" helper, for use in general operation.
@@ -133,6 +196,20 @@ pub trait ValidatorSet: Send + Sync {
 	/// Returns the current number of validators.
 	fn count_with_caller(&self, parent_block_hash: &H256, caller: &Call) -> usize;
 
+	/// Returns all validators of the block after `parent`,
+	/// `None` if the list could not be obtained.
+	fn validators(&self, parent: &H256) -> Option<Vec<Address>> {
+		let count = self.count(parent);
+		if count == usize::max_value() { return None }
+		Some((0..count).map(|nonce| self.get(parent, nonce)).collect())
+	}
+
+	/// Returns validator set changes which were signalled, but not finalized yet.
+	fn pending_changes(&self) -> Vec<PendingValidatorChange> { Vec::new() }
+
+	/// Returns misbehaviour reported to this set, oldest first.
+	fn reports(&self) -> Vec<MisbehaviorReport> { Vec::new() }
+
 	/// Notifies about malicious behaviour.
 	fn report_malicious(&self, _validator: &Address, _set_block: BlockNumber, _block: BlockNumber, _proof: Bytes) {}
 	/// Notifies about benign misbehaviour.
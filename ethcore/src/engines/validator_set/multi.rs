@@ -25,7 +25,7 @@ use ids::BlockId;
 use header::{BlockNumber, Header};
 use client::EngineClient;
 use machine::{AuxiliaryData, Call, EthereumMachine};
-use super::{SystemCall, ValidatorSet};
+use super::{SystemCall, ValidatorSet, PendingValidatorChange};
 
 type BlockNumberLookup = Box<Fn(BlockId) -> Result<BlockNumber, String> + Send + Sync + 'static>;
 
@@ -123,6 +123,10 @@ impl ValidatorSet for Multi {
 			.map_or_else(usize::max_value, |set| set.count_with_caller(bh, caller))
 	}
 
+	fn pending_changes(&self) -> Vec<PendingValidatorChange> {
+		self.sets.values().flat_map(|set| set.pending_changes()).collect()
+	}
+
 	fn report_malicious(&self, validator: &Address, set_block: BlockNumber, block: BlockNumber, proof: Bytes) {
 		self.correct_set_by_number(set_block).1.report_malicious(validator, set_block, block, proof);
 	}
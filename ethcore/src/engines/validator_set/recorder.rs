@@ -0,0 +1,174 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Validator set wrapper keeping a log of reported misbehaviour.
+
+use std::collections::VecDeque;
+use std::sync::Weak;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use ethereum_types::{H256, Address};
+use parking_lot::RwLock;
+
+use client::EngineClient;
+use header::{Header, BlockNumber};
+use ids::BlockId;
+use machine::{AuxiliaryData, Call, EthereumMachine};
+
+use super::{ValidatorSet, SimpleList, SystemCall, MisbehaviorKind, MisbehaviorReport, PendingValidatorChange};
+
+/// Maximum number of reports kept in memory.
+const MAX_REPORTS: usize = 1024;
+
+/// Forwards everything to the inner set, recording reports on the way.
+pub struct ReportRecorder {
+	inner: Box<ValidatorSet>,
+	reports: RwLock<VecDeque<MisbehaviorReport>>,
+}
+
+impl ReportRecorder {
+	pub fn new(inner: Box<ValidatorSet>) -> Self {
+		ReportRecorder {
+			inner,
+			reports: RwLock::new(VecDeque::new()),
+		}
+	}
+
+	fn record(&self, validator: &Address, kind: MisbehaviorKind, set_block: BlockNumber, block: BlockNumber) {
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)
+			.map(|d| d.as_secs())
+			.unwrap_or(0);
+
+		let mut reports = self.reports.write();
+		if reports.len() == MAX_REPORTS {
+			reports.pop_front();
+		}
+		reports.push_back(MisbehaviorReport {
+			validator: *validator,
+			kind,
+			set_block,
+			block,
+			timestamp,
+		});
+	}
+}
+
+impl ValidatorSet for ReportRecorder {
+	fn default_caller(&self, id: BlockId) -> Box<Call> {
+		self.inner.default_caller(id)
+	}
+
+	fn contains(&self, parent: &H256, address: &Address) -> bool {
+		self.inner.contains(parent, address)
+	}
+
+	fn get(&self, parent: &H256, nonce: usize) -> Address {
+		self.inner.get(parent, nonce)
+	}
+
+	fn count(&self, parent: &H256) -> usize {
+		self.inner.count(parent)
+	}
+
+	fn on_epoch_begin(&self, first: bool, header: &Header, call: &mut SystemCall) -> Result<(), ::error::Error> {
+		self.inner.on_epoch_begin(first, header, call)
+	}
+
+	fn genesis_epoch_data(&self, header: &Header, call: &Call) -> Result<Vec<u8>, String> {
+		self.inner.genesis_epoch_data(header, call)
+	}
+
+	fn is_epoch_end(&self, first: bool, chain_head: &Header) -> Option<Vec<u8>> {
+		self.inner.is_epoch_end(first, chain_head)
+	}
+
+	fn signals_epoch_end(
+		&self,
+		first: bool,
+		header: &Header,
+		aux: AuxiliaryData,
+	) -> ::engines::EpochChange<EthereumMachine> {
+		self.inner.signals_epoch_end(first, header, aux)
+	}
+
+	fn epoch_set(&self, first: bool, machine: &EthereumMachine, number: BlockNumber, proof: &[u8]) -> Result<(SimpleList, Option<H256>), ::error::Error> {
+		self.inner.epoch_set(first, machine, number, proof)
+	}
+
+	fn contains_with_caller(&self, bh: &H256, address: &Address, caller: &Call) -> bool {
+		self.inner.contains_with_caller(bh, address, caller)
+	}
+
+	fn get_with_caller(&self, bh: &H256, nonce: usize, caller: &Call) -> Address {
+		self.inner.get_with_caller(bh, nonce, caller)
+	}
+
+	fn count_with_caller(&self, bh: &H256, caller: &Call) -> usize {
+		self.inner.count_with_caller(bh, caller)
+	}
+
+	fn validators(&self, parent: &H256) -> Option<Vec<Address>> {
+		self.inner.validators(parent)
+	}
+
+	fn pending_changes(&self) -> Vec<PendingValidatorChange> {
+		self.inner.pending_changes()
+	}
+
+	fn reports(&self) -> Vec<MisbehaviorReport> {
+		self.reports.read().iter().cloned().collect()
+	}
+
+	fn report_malicious(&self, validator: &Address, set_block: BlockNumber, block: BlockNumber, proof: Bytes) {
+		self.record(validator, MisbehaviorKind::Malicious, set_block, block);
+		self.inner.report_malicious(validator, set_block, block, proof)
+	}
+
+	fn report_benign(&self, validator: &Address, set_block: BlockNumber, block: BlockNumber) {
+		self.record(validator, MisbehaviorKind::Benign, set_block, block);
+		self.inner.report_benign(validator, set_block, block)
+	}
+
+	fn register_client(&self, client: Weak<EngineClient>) {
+		self.inner.register_client(client)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::Address;
+	use engines::validator_set::{ValidatorSet, SimpleList, MisbehaviorKind};
+	use super::ReportRecorder;
+
+	#[test]
+	fn records_reports() {
+		let validator = Address::from(1);
+		let set = ReportRecorder::new(Box::new(SimpleList::new(vec![validator])));
+		assert!(set.reports().is_empty());
+
+		set.report_benign(&validator, 0, 5);
+		set.report_malicious(&validator, 0, 7, Vec::new());
+
+		let reports = set.reports();
+		assert_eq!(reports.len(), 2);
+		assert_eq!(reports[0].kind, MisbehaviorKind::Benign);
+		assert_eq!(reports[0].block, 5);
+		assert_eq!(reports[1].kind, MisbehaviorKind::Malicious);
+		assert_eq!(reports[1].block, 7);
+		assert_eq!(reports[1].validator, validator);
+	}
+}
@@ -35,12 +35,14 @@ use ids::BlockId;
 use log_entry::LogEntry;
 use receipt::Receipt;
 
-use super::{SystemCall, ValidatorSet};
+use super::{SystemCall, ValidatorSet, PendingValidatorChange};
 use super::simple_list::SimpleList;
 
 use_contract!(validator_set, "ValidatorSet", "res/contracts/validator_set.json");
 
 const MEMOIZE_CAPACITY: usize = 500;
+// Maximum number of signalled changes remembered while awaiting finality.
+const MAX_PENDING_CHANGES: usize = 32;
 
 // TODO: ethabi should be able to generate this.
 const EVENT_NAME: &'static [u8] = &*b"InitiateChange(bytes32,address[])";
@@ -79,6 +81,7 @@ pub struct ValidatorSafeContract {
 	validators: RwLock<MemoryLruCache<H256, SimpleList>>,
 	provider: validator_set::ValidatorSet,
 	client: RwLock<Option<Weak<EngineClient>>>, // TODO [keorn]: remove
+	pending: RwLock<Vec<PendingValidatorChange>>,
 }
 
 // first proof is just a state proof call of `getValidators` at header's state.
@@ -206,6 +209,7 @@ impl ValidatorSafeContract {
 			validators: RwLock::new(MemoryLruCache::new(MEMOIZE_CAPACITY)),
 			provider: validator_set::ValidatorSet::default(),
 			client: RwLock::new(None),
+			pending: RwLock::new(Vec::new()),
 		}
 	}
 
@@ -299,7 +303,10 @@ impl ValidatorSet for ValidatorSafeContract {
 			.map(|out| (out, Vec::new()))) // generate no proofs in general
 	}
 
-	fn on_epoch_begin(&self, _first: bool, _header: &Header, caller: &mut SystemCall) -> Result<(), ::error::Error> {
+	fn on_epoch_begin(&self, _first: bool, header: &Header, caller: &mut SystemCall) -> Result<(), ::error::Error> {
+		// changes signalled before this block are finalized by now.
+		self.pending.write().retain(|change| change.block_number >= header.number());
+
 		let data = self.provider.functions().finalize_change().input();
 		caller(self.contract_address, data)
 			.map(|_| ())
@@ -347,6 +354,19 @@ impl ValidatorSet for ValidatorSafeContract {
 					info!(target: "engine", "Signal for transition within contract. New list: {:?}",
 						&*list);
 
+					let mut pending = self.pending.write();
+					let hash = header.hash();
+					if !pending.iter().any(|change| change.block_hash == hash) {
+						if pending.len() == MAX_PENDING_CHANGES {
+							pending.remove(0);
+						}
+						pending.push(PendingValidatorChange {
+							block_hash: hash,
+							block_number: header.number(),
+							validators: list.into_inner(),
+						});
+					}
+
 					let proof = encode_proof(&header, receipts);
 					::engines::EpochChange::Yes(::engines::Proof::Known(proof))
 				}
@@ -440,6 +460,10 @@ impl ValidatorSet for ValidatorSafeContract {
 				 }))
 	}
 
+	fn pending_changes(&self) -> Vec<PendingValidatorChange> {
+		self.pending.read().clone()
+	}
+
 	fn register_client(&self, client: Weak<EngineClient>) {
 		trace!(target: "engine", "Setting up contract caller.");
 		*self.client.write() = Some(client);
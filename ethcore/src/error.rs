@@ -42,6 +42,8 @@ pub enum BlockError {
 	TooManyUncles(OutOfBounds<usize>),
 	/// Extra data is of an invalid length.
 	ExtraDataOutOfBounds(OutOfBounds<usize>),
+	/// Block RLP is too large.
+	TooLarge(OutOfBounds<usize>),
 	/// Seal is incorrect format.
 	InvalidSealArity(Mismatch<usize>),
 	/// Block has too much gas used.
@@ -108,6 +110,7 @@ impl fmt::Display for BlockError {
 		let msg = match *self {
 			TooManyUncles(ref oob) => format!("Block has too many uncles. {}", oob),
 			ExtraDataOutOfBounds(ref oob) => format!("Extra block data too long. {}", oob),
+			TooLarge(ref oob) => format!("Block too large. {}", oob),
 			InvalidSealArity(ref mis) => format!("Block seal in incorrect format: {}", mis),
 			TooMuchGasUsed(ref oob) => format!("Block has too much gas used. {}", oob),
 			InvalidUnclesHash(ref mis) => format!("Block has invalid uncles hash: {}", mis),
@@ -153,6 +156,43 @@ impl error::Error for BlockError {
 	}
 }
 
+/// Errors concerning the decoding of raw blocks, before any of their contents is verified.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BlockDecodeError {
+	/// Block or its header is not valid RLP.
+	InvalidRlp(::rlp::DecoderError),
+	/// Block is not a list of the header, transactions and uncles.
+	IncorrectItemCount(usize),
+	/// Bytes following the block.
+	TrailingBytes(usize),
+}
+
+impl From<::rlp::DecoderError> for BlockDecodeError {
+	fn from(err: ::rlp::DecoderError) -> Self {
+		BlockDecodeError::InvalidRlp(err)
+	}
+}
+
+impl fmt::Display for BlockDecodeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use self::BlockDecodeError::*;
+
+		let msg = match *self {
+			InvalidRlp(ref err) => format!("Invalid RLP: {}", err),
+			IncorrectItemCount(count) => format!("Block has {} items instead of 3", count),
+			TrailingBytes(count) => format!("{} bytes following the block", count),
+		};
+
+		f.write_fmt(format_args!("Block decoding error ({})", msg))
+	}
+}
+
+impl error::Error for BlockDecodeError {
+	fn description(&self) -> &str {
+		"Block decoding error"
+	}
+}
+
 error_chain! {
 	types {
 		ImportError, ImportErrorKind, ImportErrorResultExt, ImportErrorResult;
@@ -191,6 +231,7 @@ error_chain! {
 	foreign_links {
 		Block(BlockError) #[doc = "Block error"];
 		Decoder(::rlp::DecoderError) #[doc = "Rlp decoding error"];
+		BlockDecode(BlockDecodeError) #[doc = "Raw block decoding error"];
 	}
 
 	errors {
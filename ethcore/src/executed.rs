@@ -26,6 +26,9 @@ use state_diff::StateDiff;
 
 use std::{fmt, error};
 
+/// Selector of `Error(string)`, used by Solidity to encode revert reasons.
+const REVERT_REASON_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
 /// Transaction execution receipt.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Executed<T = FlatTrace, V = VMTrace> {
@@ -69,6 +72,31 @@ pub struct Executed<T = FlatTrace, V = VMTrace> {
 	pub state_diff: Option<StateDiff>,
 }
 
+impl<T, V> Executed<T, V> {
+	/// Returns the reason given for reverting the execution, if it was reverted
+	/// with a message (e.g. Solidity's `revert("...")` or `require(..., "...")`).
+	pub fn revert_reason(&self) -> Option<String> {
+		match self.exception {
+			Some(vm::Error::Reverted) => decode_revert_reason(&self.output),
+			_ => None,
+		}
+	}
+}
+
+/// Decodes a revert reason ABI-encoded as a call to `Error(string)`.
+pub fn decode_revert_reason(output: &[u8]) -> Option<String> {
+	use ethabi::{decode, ParamType, Token};
+
+	if output.len() < 4 || output[..4] != REVERT_REASON_SELECTOR {
+		return None;
+	}
+
+	match decode(&[ParamType::String], &output[4..]).ok()?.pop() {
+		Some(Token::String(reason)) => Some(reason),
+		_ => None,
+	}
+}
+
 /// Result of executing the transaction.
 #[derive(PartialEq, Debug, Clone)]
 pub enum ExecutionError {
@@ -167,6 +195,8 @@ pub enum CallError {
 	StateCorrupt,
 	/// Error executing.
 	Execution(ExecutionError),
+	/// Couldn't find an amount of gas that didn't revert, reverted with given reason.
+	Reverted(String),
 }
 
 impl From<ExecutionError> for CallError {
@@ -185,6 +215,7 @@ impl fmt::Display for CallError {
 			Exceptional => "An exception happened in the execution".into(),
 			StateCorrupt => "Stored state found to be corrupted.".into(),
 			Execution(ref e) => format!("{}", e),
+			Reverted(ref reason) => format!("Reverted: {}", reason),
 		};
 
 		f.write_fmt(format_args!("Transaction execution error ({}).", msg))
@@ -193,3 +224,21 @@ impl fmt::Display for CallError {
 
 /// Transaction execution result.
 pub type ExecutionResult = Result<Executed, ExecutionError>;
+
+#[cfg(test)]
+mod tests {
+	use rustc_hex::FromHex;
+	use super::decode_revert_reason;
+
+	#[test]
+	fn should_decode_revert_reason() {
+		let output = "08c379a0\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			000000000000000000000000000000000000000000000000000000000000000e\
+			4e6f7420617574686f7269736564000000000000000000000000000000000000".from_hex().unwrap();
+
+		assert_eq!(decode_revert_reason(&output), Some("Not authorised".into()));
+		assert_eq!(decode_revert_reason(&output[4..]), None);
+		assert_eq!(decode_revert_reason(&[]), None);
+	}
+}
@@ -34,7 +34,6 @@ use tx_filter::TransactionFilter;
 
 use ethereum_types::{U256, Address};
 use bytes::BytesRef;
-use rlp::Rlp;
 use vm::{CallType, ActionParams, ActionValue, ParamsType};
 use vm::{EnvInfo, Schedule, CreateContractAddress};
 
@@ -203,7 +202,7 @@ impl EthereumMachine {
 
 		if let Some(ref ethash_params) = self.ethash_extensions {
 			let gas_limit = {
-				let bound_divisor = self.params().gas_limit_bound_divisor;
+				let bound_divisor = self.params().gas_limit_bound_divisor(header.number());
 				let lower_limit = gas_limit - gas_limit / bound_divisor + 1.into();
 				let upper_limit = gas_limit + gas_limit / bound_divisor - 1.into();
 				let gas_limit = if gas_limit < gas_floor_target {
@@ -234,7 +233,7 @@ impl EthereumMachine {
 		}
 
 		header.set_gas_limit({
-			let bound_divisor = self.params().gas_limit_bound_divisor;
+			let bound_divisor = self.params().gas_limit_bound_divisor(header.number());
 			if gas_limit < gas_floor_target {
 				cmp::min(gas_floor_target, gas_limit + gas_limit / bound_divisor - 1.into())
 			} else {
@@ -284,7 +283,7 @@ impl EthereumMachine {
 	}
 
 	/// Some intrinsic operation parameters; by default they take their value from the `spec()`'s `engine_params`.
-	pub fn maximum_extra_data_size(&self) -> usize { self.params().maximum_extra_data_size }
+	pub fn maximum_extra_data_size(&self, block_number: BlockNumber) -> usize { self.params().maximum_extra_data_size(block_number) }
 
 	/// The nonce with which accounts begin at given block.
 	pub fn account_start_nonce(&self, block: u64) -> U256 {
@@ -361,14 +360,14 @@ impl EthereumMachine {
 		]
 	}
 
-	/// Performs pre-validation of RLP decoded transaction before other processing
-	pub fn decode_transaction(&self, transaction: &[u8]) -> Result<UnverifiedTransaction, transaction::Error> {
-		let rlp = Rlp::new(&transaction);
-		if rlp.as_raw().len() > self.params().max_transaction_size {
-			debug!("Rejected oversized transaction of {} bytes", rlp.as_raw().len());
+	/// Performs pre-validation of RLP decoded transaction to be included in block `block_number`
+	/// before other processing
+	pub fn decode_transaction(&self, transaction: &[u8], block_number: BlockNumber) -> Result<UnverifiedTransaction, transaction::Error> {
+		if transaction.len() > self.params().max_transaction_size(block_number) {
+			debug!("Rejected oversized transaction of {} bytes", transaction.len());
 			return Err(transaction::Error::TooBig)
 		}
-		rlp.as_val().map_err(|e| transaction::Error::InvalidRlp(e.to_string()))
+		UnverifiedTransaction::from_rlp_bytes(transaction)
 	}
 }
 
@@ -17,17 +17,19 @@
 use std::time::{Instant, Duration};
 use std::collections::{BTreeMap, HashSet, HashMap};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
 
 use ansi_term::Colour;
 use bytes::Bytes;
 use engines::{EthEngine, Seal};
-use error::{Error, ErrorKind, ExecutionError};
+use error::{Error, ErrorKind, ExecutionError, BlockError};
 use ethcore_miner::gas_pricer::GasPricer;
 use ethcore_miner::pool::{self, TransactionQueue, VerifiedTransaction, QueueStatus, PrioritizationStrategy};
 use ethcore_miner::work_notify::NotifyWork;
 use ethereum_types::{H256, U256, Address};
 use parking_lot::{Mutex, RwLock};
 use rayon::prelude::*;
+use rlp;
 use transaction::{
 	self,
 	Action,
@@ -96,6 +98,10 @@ const DEFAULT_MINIMAL_GAS_PRICE: u64 = 20_000_000_000;
 /// in case we have only a fraction of available block gas limit left.
 const MAX_SKIPPED_TRANSACTIONS: usize = 8;
 
+/// Room left for the seal when filling a block up to the maximum block size,
+/// since the seal is only added once the block is closed.
+const SEAL_SIZE_RESERVE: usize = 512;
+
 /// Configures the behaviour of the miner.
 #[derive(Debug, PartialEq)]
 pub struct MinerOptions {
@@ -208,6 +214,8 @@ pub struct Miner {
 	transaction_queue: Arc<TransactionQueue>,
 	engine: Arc<EthEngine>,
 	accounts: Option<Arc<AccountProvider>>,
+	// Set while sealing must not happen, e.g. when the local clock is skewed.
+	sealing_paused: AtomicBool,
 }
 
 impl Miner {
@@ -245,6 +253,7 @@ impl Miner {
 			transaction_queue: Arc::new(TransactionQueue::new(limits, verifier_options, tx_queue_strategy)),
 			accounts,
 			engine: spec.engine.clone(),
+			sealing_paused: AtomicBool::new(false),
 		}
 	}
 
@@ -264,6 +273,25 @@ impl Miner {
 		}, GasPricer::new_fixed(minimal_gas_price), spec, accounts)
 	}
 
+	/// Stop sealing new blocks and handing out work until `resume_sealing` is called.
+	pub fn pause_sealing(&self) {
+		if !self.sealing_paused.swap(true, AtomicOrdering::SeqCst) {
+			warn!(target: "miner", "Block sealing paused.");
+		}
+	}
+
+	/// Resume sealing paused with `pause_sealing`.
+	pub fn resume_sealing(&self) {
+		if self.sealing_paused.swap(false, AtomicOrdering::SeqCst) {
+			info!(target: "miner", "Block sealing resumed.");
+		}
+	}
+
+	/// Returns true if sealing is paused.
+	pub fn is_sealing_paused(&self) -> bool {
+		self.sealing_paused.load(AtomicOrdering::SeqCst)
+	}
+
 	/// Clear all pending block states
 	pub fn clear(&self) {
 		self.sealing.lock().queue.reset();
@@ -368,6 +396,7 @@ impl Miner {
 
 		let client = self.pool_client(chain);
 		let engine_params = self.engine.params();
+		let max_block_size = engine_params.max_block_size(block_number);
 		let min_tx_gas: U256 = self.engine.schedule(chain_info.best_block_number).tx_gas.into();
 		let nonce_cap: Option<U256> = if chain_info.best_block_number + 1 >= engine_params.dust_protection_transition {
 			Some((engine_params.nonce_cap_increment * (chain_info.best_block_number + 1)).into())
@@ -406,6 +435,25 @@ impl Miner {
 			let hash = transaction.hash();
 			let sender = transaction.sender();
 
+			if let Some(max_block_size) = max_block_size {
+				let transaction_size = rlp::encode(&transaction).len();
+				if open_block.size() + transaction_size + SEAL_SIZE_RESERVE > max_block_size {
+					debug!(target: "miner", "Skipping adding transaction to block because of block size: {:?} ({} bytes)", hash, transaction_size);
+
+					// a transaction which doesn't fit even into an empty block can never be included
+					if transaction_size + SEAL_SIZE_RESERVE > max_block_size {
+						invalid_transactions.insert(hash);
+					}
+
+					skipped_transactions += 1;
+					if skipped_transactions > MAX_SKIPPED_TRANSACTIONS {
+						debug!(target: "miner", "Reached skipped transactions threshold. Assuming block is full.");
+						break;
+					}
+					continue;
+				}
+			}
+
 			// Re-verify transaction again vs current state.
 			let result = client.verify_signed(&transaction)
 				.map_err(|e| e.into())
@@ -454,6 +502,10 @@ impl Miner {
 				Err(Error(ErrorKind::Execution(ExecutionError::InvalidNonce { expected, got }), _)) => {
 					debug!(target: "miner", "Skipping adding transaction to block because of invalid nonce: {:?} (expected: {:?}, got: {:?})", hash, expected, got);
 				},
+				// size is checked above with room for the seal, so the block can only be full here
+				Err(Error(ErrorKind::Block(BlockError::TooLarge(_)), _)) => {
+					debug!(target: "miner", "Skipping adding transaction to block because of block size: {:?}", hash);
+				},
 				// already have transaction - ignore
 				Err(Error(ErrorKind::Transaction(transaction::Error::AlreadyImported), _)) => {},
 				Err(Error(ErrorKind::Transaction(transaction::Error::NotAllowed), _)) => {
@@ -551,6 +603,7 @@ impl Miner {
 			let sealing = self.sealing.lock();
 			if block.transactions().is_empty()
 				&& !self.forced_sealing()
+				&& !self.engine.seals_empty_blocks()
 				&& Instant::now() <= sealing.next_mandatory_reseal
 			{
 				return false
@@ -968,6 +1021,11 @@ impl miner::MinerService for Miner {
 	{
 		trace!(target: "miner", "update_sealing");
 
+		if self.is_sealing_paused() {
+			trace!(target: "miner", "update_sealing: sealing is paused");
+			return;
+		}
+
 		// Do nothing if reseal is not required,
 		// but note that `requires_reseal` updates internal state.
 		if !self.requires_reseal(chain.chain_info().best_block_number) {
@@ -1015,7 +1073,7 @@ impl miner::MinerService for Miner {
 	fn work_package<C>(&self, chain: &C) -> Option<(H256, BlockNumber, u64, U256)> where
 		C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + Nonce + Sync,
 	{
-		if self.engine.seals_internally().is_some() {
+		if self.engine.seals_internally().is_some() || self.is_sealing_paused() {
 			return None;
 		}
 
@@ -1363,6 +1421,44 @@ mod tests {
 		assert_eq!(client.chain_info().best_block_number, 4 as BlockNumber);
 	}
 
+	#[test]
+	fn should_not_seal_while_paused() {
+		let spec = Spec::new_instant();
+		let miner = Miner::new_for_tests(&spec, None);
+
+		let client = generate_dummy_client(2);
+		miner.pause_sealing();
+
+		let import = miner.import_external_transactions(&*client, vec![transaction_with_chain_id(spec.chain_id()).into()]).pop().unwrap();
+		assert_eq!(import.unwrap(), ());
+
+		miner.update_sealing(&*client);
+		client.flush_queue();
+		assert_eq!(client.chain_info().best_block_number, 2 as BlockNumber);
+
+		miner.resume_sealing();
+		miner.update_sealing(&*client);
+		client.flush_queue();
+		assert_eq!(client.chain_info().best_block_number, 3 as BlockNumber);
+	}
+
+	#[test]
+	fn internal_seals_empty_block_on_request() {
+		let spec = Spec::new_instant();
+		let miner = Miner::new_for_tests(&spec, None);
+
+		let client = generate_dummy_client(2);
+
+		miner.update_sealing(&*client);
+		client.flush_queue();
+		assert_eq!(client.chain_info().best_block_number, 2 as BlockNumber);
+
+		spec.engine.as_instant_seal().unwrap().request_seal();
+		miner.update_sealing(&*client);
+		client.flush_queue();
+		assert_eq!(client.chain_info().best_block_number, 3 as BlockNumber);
+	}
+
 	#[test]
 	fn should_fail_setting_engine_signer_without_account_provider() {
 		let spec = Spec::new_instant;
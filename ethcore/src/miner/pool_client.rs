@@ -147,7 +147,7 @@ impl<'a, C: 'a> pool::client::Client for PoolClient<'a, C> where
 	}
 
 	fn decode_transaction(&self, transaction: &[u8]) -> Result<UnverifiedTransaction, transaction::Error> {
-		self.engine.decode_transaction(transaction)
+		self.engine.decode_transaction(transaction, self.best_block_header.number() + 1)
 	}
 }
 
@@ -33,12 +33,13 @@ use vm::{EnvInfo, CallType, ActionValue, ActionParams, ParamsType};
 
 use builtin::Builtin;
 use encoded;
-use engines::{EthEngine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, DEFAULT_BLOCKHASH_CONTRACT};
+use engines::{EthEngine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, Clique, DEFAULT_BLOCKHASH_CONTRACT};
 use error::Error;
 use executive::Executive;
 use factory::Factories;
 use header::{BlockNumber, Header};
 use machine::EthereumMachine;
+use pod_account::PodAccount;
 use pod_state::PodState;
 use spec::Genesis;
 use spec::seal::Generic as GenericSeal;
@@ -123,6 +124,8 @@ pub struct CommonParams {
 	pub remove_dust_contracts: bool,
 	/// Wasm activation blocknumber, if any disabled initially.
 	pub wasm_activation_transition: BlockNumber,
+	/// Wasm gas metering costs.
+	pub wasm_costs: ::vm::WasmCosts,
 	/// Gas limit bound divisor (how much gas limit can change per block)
 	pub gas_limit_bound_divisor: U256,
 	/// Registrar contract address.
@@ -137,6 +140,43 @@ pub struct CommonParams {
 	pub transaction_permission_contract: Option<Address>,
 	/// Maximum size of transaction's RLP payload
 	pub max_transaction_size: usize,
+	/// Maximum size of block RLP, if any.
+	pub max_block_size: Option<usize>,
+	/// Param changes scheduled at future blocks, ordered by block number.
+	pub transitions: Vec<ParamsTransition>,
+}
+
+/// Params changed from the given block on. `None` keeps the previous value.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct ParamsTransition {
+	/// First block the changes apply to.
+	pub block: BlockNumber,
+	/// Minimum gas limit.
+	pub min_gas_limit: Option<U256>,
+	/// Gas limit bound divisor.
+	pub gas_limit_bound_divisor: Option<U256>,
+	/// Maximum size of extra data.
+	pub maximum_extra_data_size: Option<usize>,
+	/// Maximum size of transaction's RLP payload.
+	pub max_transaction_size: Option<usize>,
+	/// Maximum contract code size that can be deployed.
+	pub max_code_size: Option<u64>,
+	/// Maximum size of block RLP.
+	pub max_block_size: Option<usize>,
+}
+
+impl From<ethjson::spec::ParamsTransition> for ParamsTransition {
+	fn from(t: ethjson::spec::ParamsTransition) -> Self {
+		ParamsTransition {
+			block: t.block.into(),
+			min_gas_limit: t.min_gas_limit.map(Into::into),
+			gas_limit_bound_divisor: t.gas_limit_bound_divisor.map(Into::into),
+			maximum_extra_data_size: t.maximum_extra_data_size.map(Into::into),
+			max_transaction_size: t.max_transaction_size.map(Into::into),
+			max_code_size: t.max_code_size.map(Into::into),
+			max_block_size: t.max_block_size.map(Into::into),
+		}
+	}
 }
 
 impl CommonParams {
@@ -161,12 +201,48 @@ impl CommonParams {
 	/// Returns max code size at given block.
 	pub fn max_code_size(&self, block_number: u64) -> u64 {
 		if block_number >= self.max_code_size_transition {
-			self.max_code_size
+			self.scheduled(block_number, self.max_code_size, |t| t.max_code_size)
 		} else {
 			u64::max_value()
 		}
 	}
 
+	/// Returns min gas limit at given block.
+	pub fn min_gas_limit(&self, block_number: BlockNumber) -> U256 {
+		self.scheduled(block_number, self.min_gas_limit, |t| t.min_gas_limit)
+	}
+
+	/// Returns gas limit bound divisor at given block.
+	pub fn gas_limit_bound_divisor(&self, block_number: BlockNumber) -> U256 {
+		self.scheduled(block_number, self.gas_limit_bound_divisor, |t| t.gas_limit_bound_divisor)
+	}
+
+	/// Returns maximum size of extra data at given block.
+	pub fn maximum_extra_data_size(&self, block_number: BlockNumber) -> usize {
+		self.scheduled(block_number, self.maximum_extra_data_size, |t| t.maximum_extra_data_size)
+	}
+
+	/// Returns maximum size of transaction's RLP payload at given block.
+	pub fn max_transaction_size(&self, block_number: BlockNumber) -> usize {
+		self.scheduled(block_number, self.max_transaction_size, |t| t.max_transaction_size)
+	}
+
+	/// Returns maximum size of block RLP at given block, if limited.
+	pub fn max_block_size(&self, block_number: BlockNumber) -> Option<usize> {
+		self.scheduled(block_number, self.max_block_size, |t| t.max_block_size.map(Some))
+	}
+
+	/// Value of the latest transition at or before `block_number` changing the param, or `base`.
+	fn scheduled<T, F>(&self, block_number: BlockNumber, base: T, param: F) -> T where
+		F: Fn(&ParamsTransition) -> Option<T>,
+	{
+		self.transitions.iter()
+			.take_while(|t| t.block <= block_number)
+			.filter_map(param)
+			.last()
+			.unwrap_or(base)
+	}
+
 	/// Apply common spec config parameters to the schedule.
 	pub fn update_schedule(&self, block_number: u64, schedule: &mut ::vm::Schedule) {
 		schedule.have_create2 = block_number >= self.eip86_transition;
@@ -184,7 +260,7 @@ impl CommonParams {
 			};
 		}
 		if block_number >= self.wasm_activation_transition {
-			schedule.wasm = Some(Default::default());
+			schedule.wasm = Some(self.wasm_costs.clone());
 		}
 	}
 
@@ -198,9 +274,16 @@ impl CommonParams {
 	}
 }
 
-impl From<ethjson::spec::Params> for CommonParams {
-	fn from(p: ethjson::spec::Params) -> Self {
-		CommonParams {
+impl CommonParams {
+	/// Load common parameters from the JSON spec, validating values which the JSON
+	/// schema can't express.
+	fn load(p: ethjson::spec::Params) -> Result<Self, Error> {
+		let wasm_costs = match p.wasm_costs {
+			Some(costs) => wasm_costs(costs)?,
+			None => Default::default(),
+		};
+
+		Ok(CommonParams {
 			account_start_nonce: p.account_start_nonce.map_or_else(U256::zero, Into::into),
 			maximum_extra_data_size: p.maximum_extra_data_size.into(),
 			network_id: p.network_id.into(),
@@ -279,10 +362,49 @@ impl From<ethjson::spec::Params> for CommonParams {
 				BlockNumber::max_value,
 				Into::into
 			),
-		}
+			wasm_costs: wasm_costs,
+			max_block_size: p.max_block_size.map(Into::into),
+			transitions: p.transitions.map_or_else(Vec::new, |t| t.into_iter().map(Into::into).collect()),
+		})
 	}
 }
 
+/// Wasm costs from the spec, falling back to defaults for missing values.
+///
+/// These meter Parity's wasm runtime (the `env` import ABI with a `call` export),
+/// not eWASM. Every cost is a 32-bit value; larger values are rejected rather than truncated.
+fn wasm_costs(c: ethjson::spec::WasmCosts) -> Result<::vm::WasmCosts, Error> {
+	let defaults = ::vm::WasmCosts::default();
+	let cost = |name: &str, value: Option<ethjson::uint::Uint>, default: u32| -> Result<u32, Error> {
+		match value {
+			None => Ok(default),
+			Some(v) => {
+				let v: U256 = v.into();
+				if v > U256::from(u32::max_value()) {
+					Err(format!("Invalid wasmCosts.{}: {} does not fit into 32 bits", name, v).into())
+				} else {
+					Ok(v.low_u32())
+				}
+			}
+		}
+	};
+
+	Ok(::vm::WasmCosts {
+		regular: cost("regular", c.regular, defaults.regular)?,
+		div: cost("div", c.div, defaults.div)?,
+		mul: cost("mul", c.mul, defaults.mul)?,
+		mem: cost("mem", c.mem, defaults.mem)?,
+		static_u256: cost("staticU256", c.static_u256, defaults.static_u256)?,
+		static_address: cost("staticAddress", c.static_address, defaults.static_address)?,
+		initial_mem: cost("initialMem", c.initial_mem, defaults.initial_mem)?,
+		grow_mem: cost("growMem", c.grow_mem, defaults.grow_mem)?,
+		memcpy: cost("memcpy", c.memcpy, defaults.memcpy)?,
+		max_stack_height: cost("maxStackHeight", c.max_stack_height, defaults.max_stack_height)?,
+		opcodes_mul: cost("opcodesMul", c.opcodes_mul, defaults.opcodes_mul)?,
+		opcodes_div: cost("opcodesDiv", c.opcodes_div, defaults.opcodes_div)?,
+	})
+}
+
 /// Runtime parameters for the spec that are related to how the software should run the chain,
 /// rather than integral properties of the chain itself.
 #[derive(Debug, Clone, Copy)]
@@ -432,11 +554,11 @@ impl From<SpecHardcodedSync> for ethjson::spec::HardcodedSync {
 	}
 }
 
-fn load_machine_from(s: ethjson::spec::Spec) -> EthereumMachine {
+fn load_machine_from(s: ethjson::spec::Spec) -> Result<EthereumMachine, Error> {
 	let builtins = s.accounts.builtins().into_iter().map(|p| (p.0.into(), From::from(p.1))).collect();
-	let params = CommonParams::from(s.params);
+	let params = CommonParams::load(s.params)?;
 
-	Spec::machine(&s.engine, params, builtins)
+	Ok(Spec::machine(&s.engine, params, builtins))
 }
 
 /// Load from JSON object.
@@ -448,7 +570,7 @@ fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Er
 		.collect();
 	let g = Genesis::from(s.genesis);
 	let GenericSeal(seal_rlp) = g.seal.into();
-	let params = CommonParams::from(s.params);
+	let params = CommonParams::load(s.params)?;
 
 	let hardcoded_sync = if let Some(ref hs) = s.hardcoded_sync {
 		if let Ok(header) = hs.header.from_hex() {
@@ -506,6 +628,56 @@ fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Er
 	Ok(s)
 }
 
+/// Check spec json for mistakes which would otherwise only surface as a panic
+/// once the engine or a builtin is instantiated.
+fn check_json(s: &ethjson::spec::Spec) -> Vec<String> {
+	use ethjson::spec::Engine;
+
+	let mut problems = Vec::new();
+
+	for (address, builtin) in s.accounts.builtins() {
+		if let Err(e) = ::builtin::check(&builtin) {
+			let address: Address = address.into();
+			problems.push(format!("Builtin at {:?}: {}", address, e));
+		}
+	}
+
+	let validators = match s.engine {
+		Engine::AuthorityRound(ref aura) => {
+			if aura.params.step_duration.0.is_zero() {
+				problems.push("authorityRound: stepDuration must be non-zero".into());
+			}
+			Some(&aura.params.validators)
+		},
+		Engine::BasicAuthority(ref basic) => Some(&basic.params.validators),
+		Engine::Tendermint(ref tendermint) => Some(&tendermint.params.validators),
+		_ => None,
+	};
+
+	if let Some(validators) = validators {
+		check_validator_set(validators, &mut problems);
+	}
+
+	problems
+}
+
+fn check_validator_set(set: &ethjson::spec::ValidatorSet, problems: &mut Vec<String>) {
+	use ethjson::spec::ValidatorSet;
+
+	match *set {
+		ValidatorSet::List(ref list) if list.is_empty() => problems.push("Validator list is empty".into()),
+		ValidatorSet::Multi(ref sets) => {
+			if !sets.keys().any(|block| block.0.is_zero()) {
+				problems.push("Multi validator set has to specify the set for block 0".into());
+			}
+			for set in sets.values() {
+				check_validator_set(set, problems);
+			}
+		},
+		_ => {},
+	}
+}
+
 macro_rules! load_bundled {
 	($e:expr) => {
 		Spec::load(
@@ -557,6 +729,8 @@ impl Spec {
 				.expect("Failed to start AuthorityRound consensus engine."),
 			ethjson::spec::Engine::Tendermint(tendermint) => Tendermint::new(tendermint.params.into(), machine)
 				.expect("Failed to start the Tendermint consensus engine."),
+			ethjson::spec::Engine::Clique(clique) => Clique::new(clique.params.into(), machine)
+				.expect("Failed to start the Clique consensus engine."),
 		}
 	}
 
@@ -739,6 +913,24 @@ impl Spec {
 		Ok(())
 	}
 
+	/// Sets genesis balances of given accounts, creating the accounts which don't exist.
+	pub fn prefund_accounts<I>(&mut self, accounts: I) -> Result<(), Error> where
+		I: IntoIterator<Item=(Address, U256)>,
+	{
+		let start_nonce = self.params().account_start_nonce;
+		let mut state = self.genesis_state.get().clone();
+		for (address, balance) in accounts {
+			state.entry(address).or_insert_with(|| PodAccount {
+				balance: U256::zero(),
+				nonce: start_nonce,
+				code: Some(Vec::new()),
+				storage: BTreeMap::new(),
+			}).balance = balance;
+		}
+
+		self.set_genesis_state(PodState::from(state))
+	}
+
 	/// Returns `false` if the memoized state root is invalid. `true` otherwise.
 	pub fn is_state_root_valid(&self) -> bool {
 		// TODO: get rid of this function and ensure state root always is valid.
@@ -763,7 +955,7 @@ impl Spec {
 	pub fn load_machine<R: Read>(reader: R) -> Result<EthereumMachine, String> {
 		ethjson::spec::Spec::load(reader)
 			.map_err(fmt_err)
-			.map(load_machine_from)
+			.and_then(|s| load_machine_from(s).map_err(fmt_err))
 
 	}
 
@@ -780,6 +972,54 @@ impl Spec {
 		)
 	}
 
+	/// Loads spec from json file like `load`, but reports mistakes in engine params and
+	/// builtins instead of panicking on them, and checks the genesis block against the params
+	/// and the genesis accounts. Returns all problems found.
+	pub fn load_checked<'a, T: Into<SpecParams<'a>>, R>(params: T, reader: R) -> Result<Self, Vec<String>>
+	where
+		R: Read,
+	{
+		let json = ethjson::spec::Spec::load(reader).map_err(|e| vec![fmt_err(e)])?;
+		let problems = check_json(&json);
+		if !problems.is_empty() {
+			return Err(problems);
+		}
+
+		let spec = load_from(params.into(), json).map_err(|e| vec![fmt_err(e)])?;
+		let problems = spec.check_genesis();
+		if problems.is_empty() {
+			Ok(spec)
+		} else {
+			Err(problems)
+		}
+	}
+
+	/// Check the genesis header against the params and the genesis accounts.
+	fn check_genesis(&self) -> Vec<String> {
+		let mut problems = Vec::new();
+
+		let min_gas_limit = self.params().min_gas_limit(0);
+		if self.gas_limit < min_gas_limit {
+			problems.push(format!("Genesis gas limit {} is below the minimum gas limit {}", self.gas_limit, min_gas_limit));
+		}
+
+		// a state root given in the genesis is taken at face value when loading,
+		// so rebuild the genesis state to check it.
+		let declared = self.state_root();
+		match self.run_constructors(&Default::default(), BasicBackend(MemoryDB::new())) {
+			Ok(_) => {
+				let computed = self.state_root();
+				if computed != declared {
+					problems.push(format!("Genesis state root {:?} doesn't match the genesis accounts, which give {:?}", declared, computed));
+				}
+			},
+			Err(e) => problems.push(format!("Unable to build the genesis state: {}", e)),
+		}
+		*self.state_root_memo.write() = declared;
+
+		problems
+	}
+
 	/// initialize genesis epoch data, using in-memory database for
 	/// constructor.
 	pub fn genesis_epoch_data(&self) -> Result<Vec<u8>, String> {
@@ -905,6 +1145,13 @@ impl Spec {
 		load_bundled!("tendermint")
 	}
 
+	/// Create a new Spec with Clique consensus and no block period.
+	/// Accounts with secrets keccak("0") and keccak("1") are the initial signers.
+	#[cfg(any(test, feature = "test-helpers"))]
+	pub fn new_test_clique() -> Self {
+		load_bundled!("clique")
+	}
+
 	/// TestList.sol used in both specs: https://github.com/paritytech/contracts/pull/30/files
 	/// Accounts with secrets keccak("0") and keccak("1") are initially the validators.
 	/// Create a new Spec with BasicAuthority which uses a contract at address 5 to determine
@@ -951,6 +1198,27 @@ mod tests {
 		assert!(Spec::load(&tempdir.path(), &[] as &[u8]).is_err());
 	}
 
+	#[test]
+	fn load_checked_accepts_valid_spec() {
+		let tempdir = TempDir::new("").unwrap();
+		let spec = Spec::load_checked(&tempdir.path(), include_bytes!("../../res/null.json") as &[u8]).unwrap();
+		assert_eq!(spec.state_root(), Spec::new_null().state_root());
+	}
+
+	#[test]
+	fn load_checked_reports_problems() {
+		let tempdir = TempDir::new("").unwrap();
+		let json = String::from_utf8(include_bytes!("../../res/null.json").to_vec()).unwrap()
+			.replace(r#""name": "identity""#, r#""name": "foo""#);
+		let problems = Spec::load_checked(&tempdir.path(), json.as_bytes()).unwrap_err();
+		assert_eq!(problems, vec!["Builtin at 0x0000000000000000000000000000000000000004: invalid builtin name: foo".to_owned()]);
+
+		let json = String::from_utf8(include_bytes!("../../res/null.json").to_vec()).unwrap()
+			.replace(r#""gasLimit": "0x2fefd8""#, r#""gasLimit": "0x1000", "stateRoot": "0x0000000000000000000000000000000000000000000000000000000000000001""#);
+		let problems = Spec::load_checked(&tempdir.path(), json.as_bytes()).unwrap_err();
+		assert_eq!(problems.len(), 2);
+	}
+
 	#[test]
 	fn test_chain() {
 		let test_spec = Spec::new_test();
@@ -984,4 +1252,93 @@ mod tests {
 		assert_eq!(state.storage_at(&address, &H256::zero()).unwrap(), expected);
 		assert_eq!(state.balance(&address).unwrap(), 1.into());
 	}
+
+	#[test]
+	fn prefunds_genesis_accounts() {
+		let mut spec = Spec::new_instant();
+		let root = spec.state_root();
+		let address: Address = "f39fd6e51aad88f6f4ce6ab8827279cfffb92266".into();
+		spec.prefund_accounts(vec![(address, 1000.into())]).unwrap();
+		assert!(spec.state_root() != root);
+
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let state = State::from_existing(db.boxed_clone(), spec.state_root(), spec.engine.account_start_nonce(0), Default::default()).unwrap();
+		assert_eq!(state.balance(&address).unwrap(), 1000.into());
+		assert_eq!(state.balance(&"0000000000000000000000000000000000001337".into()).unwrap(), 1.into());
+	}
+
+	#[test]
+	fn wasm_costs_fall_back_to_defaults() {
+		let costs = wasm_costs(ethjson::spec::WasmCosts {
+			regular: Some(ethjson::uint::Uint(2.into())),
+			grow_mem: Some(ethjson::uint::Uint(4096.into())),
+			..Default::default()
+		}).unwrap();
+
+		assert_eq!(costs, ::vm::WasmCosts {
+			regular: 2,
+			grow_mem: 4096,
+			..Default::default()
+		});
+	}
+
+	#[test]
+	fn spec_with_out_of_range_wasm_costs_fails_to_load() {
+		let tempdir = TempDir::new("").unwrap();
+		let json = String::from_utf8(include_bytes!("../../res/null.json").to_vec()).unwrap()
+			.replace("\"eip658Transition\": \"0x0\"", "\"eip658Transition\": \"0x0\", \"wasmCosts\": { \"regular\": \"0x100000000\" }");
+
+		let err = Spec::load(&tempdir.path(), json.as_bytes()).err().unwrap();
+		assert!(err.contains("wasmCosts.regular"), "{}", err);
+		assert!(Spec::load_machine(json.as_bytes()).is_err());
+	}
+
+	#[test]
+	fn wasm_costs_out_of_range_are_rejected() {
+		assert!(wasm_costs(ethjson::spec::WasmCosts {
+			max_stack_height: Some(ethjson::uint::Uint(U256::from(u32::max_value()) + 1.into())),
+			..Default::default()
+		}).is_err());
+		assert!(wasm_costs(ethjson::spec::WasmCosts {
+			grow_mem: Some(ethjson::uint::Uint(U256::max_value())),
+			..Default::default()
+		}).is_err());
+		assert_eq!(wasm_costs(ethjson::spec::WasmCosts {
+			memcpy: Some(ethjson::uint::Uint(u32::max_value().into())),
+			..Default::default()
+		}).unwrap().memcpy, u32::max_value());
+	}
+
+	#[test]
+	fn scheduled_params_apply_from_transition_block() {
+		let params = CommonParams {
+			min_gas_limit: 5000.into(),
+			max_transaction_size: 300,
+			max_code_size: 24576,
+			transitions: vec![
+				ParamsTransition {
+					block: 10,
+					min_gas_limit: Some(8000.into()),
+					max_block_size: Some(100_000),
+					..Default::default()
+				},
+				ParamsTransition {
+					block: 20,
+					min_gas_limit: Some(10000.into()),
+					max_code_size: Some(49152),
+					..Default::default()
+				},
+			],
+			..Default::default()
+		};
+
+		assert_eq!(params.min_gas_limit(9), 5000.into());
+		assert_eq!(params.min_gas_limit(10), 8000.into());
+		assert_eq!(params.min_gas_limit(25), 10000.into());
+		assert_eq!(params.max_block_size(9), None);
+		assert_eq!(params.max_block_size(25), Some(100_000));
+		assert_eq!(params.max_code_size(15), 24576);
+		assert_eq!(params.max_code_size(20), 49152);
+		assert_eq!(params.max_transaction_size(25), 300);
+	}
 }
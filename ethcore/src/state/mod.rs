@@ -69,7 +69,9 @@ pub struct ApplyOutcome<T, V> {
 	/// The trace for the applied transaction, empty if tracing was not produced.
 	pub trace: Vec<T>,
 	/// The VM trace for the applied transaction, None if tracing was not produced.
-	pub vm_trace: Option<V>
+	pub vm_trace: Option<V>,
+	/// The state diff of the applied transaction, None if it was not produced.
+	pub state_diff: Option<StateDiff>,
 }
 
 /// Result type for the execution ("application") of a transaction.
@@ -311,6 +313,8 @@ pub struct State<B: Backend> {
 	cache: RefCell<HashMap<Address, AccountEntry>>,
 	// The original account is preserved in
 	checkpoints: RefCell<Vec<HashMap<Address, Option<AccountEntry>>>>,
+	// Accounts and storage keys accessed since `record_accesses`, if recording.
+	accessed: RefCell<Option<BTreeMap<Address, BTreeSet<H256>>>>,
 	account_start_nonce: U256,
 	factories: Factories,
 }
@@ -346,6 +350,12 @@ pub trait StateInfo {
 
 	/// Get accounts' code.
 	fn code(&self, a: &Address) -> trie::Result<Option<Arc<Bytes>>>;
+
+	/// Get an account's code hash.
+	fn code_hash(&self, a: &Address) -> trie::Result<H256>;
+
+	/// Get accounts and storage keys accessed since access recording was started, if it was.
+	fn accessed(&self) -> Option<BTreeMap<Address, BTreeSet<H256>>>;
 }
 
 impl<B: Backend> StateInfo for State<B> {
@@ -353,6 +363,8 @@ impl<B: Backend> StateInfo for State<B> {
 	fn balance(&self, a: &Address) -> trie::Result<U256> { State::balance(self, a) }
 	fn storage_at(&self, address: &Address, key: &H256) -> trie::Result<H256> { State::storage_at(self, address, key) }
 	fn code(&self, address: &Address) -> trie::Result<Option<Arc<Bytes>>> { State::code(self, address) }
+	fn code_hash(&self, address: &Address) -> trie::Result<H256> { State::code_hash(self, address) }
+	fn accessed(&self) -> Option<BTreeMap<Address, BTreeSet<H256>>> { self.accessed.borrow().clone() }
 }
 
 const SEC_TRIE_DB_UNWRAP_STR: &'static str = "A state can only be created with valid root. Creating a SecTrieDB with a valid root will not fail. \
@@ -373,6 +385,7 @@ impl<B: Backend> State<B> {
 			root: root,
 			cache: RefCell::new(HashMap::new()),
 			checkpoints: RefCell::new(Vec::new()),
+			accessed: RefCell::new(None),
 			account_start_nonce: account_start_nonce,
 			factories: factories,
 		}
@@ -389,6 +402,7 @@ impl<B: Backend> State<B> {
 			root: root,
 			cache: RefCell::new(HashMap::new()),
 			checkpoints: RefCell::new(Vec::new()),
+			accessed: RefCell::new(None),
 			account_start_nonce: account_start_nonce,
 			factories: factories
 		};
@@ -401,6 +415,20 @@ impl<B: Backend> State<B> {
 		self.factories.vm.clone()
 	}
 
+	/// Start recording accounts and storage keys accessed, forgetting whatever was recorded before.
+	pub fn record_accesses(&mut self) {
+		*self.accessed.get_mut() = Some(BTreeMap::new());
+	}
+
+	fn note_access(&self, address: &Address, key: Option<&H256>) {
+		if let Some(ref mut accessed) = *self.accessed.borrow_mut() {
+			let keys = accessed.entry(*address).or_insert_with(BTreeSet::new);
+			if let Some(key) = key {
+				keys.insert(*key);
+			}
+		}
+	}
+
 	/// Create a recoverable checkpoint of this state.
 	pub fn checkpoint(&mut self) {
 		self.checkpoints.get_mut().push(HashMap::new());
@@ -541,6 +569,8 @@ impl<B: Backend> State<B> {
 
 	/// Mutate storage of account `address` so that it is `value` for `key`.
 	pub fn storage_at(&self, address: &Address, key: &H256) -> trie::Result<H256> {
+		self.note_access(address, Some(key));
+
 		// Storage key search and update works like this:
 		// 1. If there's an entry for the account in the local cache check for the key and return it if found.
 		// 2. If there's an entry for the account in the global cache check for the key or load it into that account.
@@ -708,6 +738,11 @@ impl<B: Backend> State<B> {
 	{
 		let options = TransactOptions::new(tracer, vm_tracer);
 		let e = self.execute(env_info, machine, t, options, false)?;
+		self.apply_outcome(env_info, machine, e, None)
+	}
+
+	// Produce the outcome of an executed transaction, committing the changes if required.
+	fn apply_outcome<T, V>(&mut self, env_info: &EnvInfo, machine: &Machine, e: Executed<T, V>, state_diff: Option<StateDiff>) -> ApplyResult<T, V> {
 		let params = machine.params();
 
 		let eip658 = env_info.number >= params.eip658_transition;
@@ -735,6 +770,7 @@ impl<B: Backend> State<B> {
 			output,
 			trace: e.trace,
 			vm_trace: e.vm_trace,
+			state_diff,
 		})
 	}
 
@@ -910,6 +946,99 @@ impl<B: Backend> State<B> {
 		Ok(pod_state::diff_pod(&pod_state_pre, &pod_state_post))
 	}
 
+	/// Execute a given transaction producing a receipt, an optional trace and the diff of the state it changed.
+	/// This will change the state accordingly.
+	pub fn apply_with_state_diff(&mut self, env_info: &EnvInfo, machine: &Machine, t: &SignedTransaction, tracing: bool) -> ApplyResult<FlatTrace, VMTrace> {
+		// the checkpoint collects the original entries of the accounts the transaction modifies
+		self.checkpoint();
+		let executed = if tracing {
+			self.execute(env_info, machine, t, TransactOptions::with_tracing(), false)
+		} else {
+			self.execute(env_info, machine, t, TransactOptions::with_no_tracing(), false)
+		};
+
+		let diff = match executed {
+			Ok(e) => self.diff_since_checkpoint().map(|diff| (e, diff)).map_err(Error::from),
+			Err(err) => Err(err.into()),
+		};
+
+		match diff {
+			Ok((e, state_diff)) => {
+				// the diff must be taken before the changes are committed
+				self.discard_checkpoint();
+				self.apply_outcome(env_info, machine, e, Some(state_diff))
+			},
+			Err(err) => {
+				self.revert_to_checkpoint();
+				Err(err)
+			},
+		}
+	}
+
+	/// Diff of the changes made since the last checkpoint.
+	///
+	/// Only accounts modified after the checkpoint are visited, so unlike `diff_from`
+	/// the cost doesn't grow with the number of cached accounts.
+	fn diff_since_checkpoint(&self) -> trie::Result<StateDiff> {
+		let checkpoints = self.checkpoints.borrow();
+		let checkpoint = match checkpoints.last() {
+			Some(checkpoint) => checkpoint,
+			None => return Ok(StateDiff { raw: Default::default() }),
+		};
+		let cache = self.cache.borrow();
+
+		let mut pre = BTreeMap::new();
+		let mut post = BTreeMap::new();
+		for (address, original) in checkpoint {
+			// entries which were not cached before the checkpoint are read from the trie
+			let loaded: Option<Account>;
+			let original = match *original {
+				Some(ref entry) => entry.account.as_ref(),
+				None => {
+					let db = self.factories.trie.readonly(self.db.as_hashdb(), &self.root)?;
+					let from_rlp = |b: &[u8]| { Account::from_rlp(b).expect("decoding db value failed") };
+					loaded = db.get_with(address, from_rlp)?;
+					loaded.as_ref()
+				},
+			};
+			let current = cache.get(address).and_then(|entry| entry.account.as_ref());
+
+			let keys = original.iter().chain(current.iter())
+				.flat_map(|account| account.storage_changes().keys().cloned())
+				.collect::<BTreeSet<_>>();
+
+			if let Some(account) = original {
+				pre.insert(*address, self.pod_account(address, account, &keys)?);
+			}
+			if let Some(account) = current {
+				post.insert(*address, self.pod_account(address, account, &keys)?);
+			}
+		}
+
+		Ok(pod_state::diff_pod(&PodState::from(pre), &PodState::from(post)))
+	}
+
+	/// Snapshot of the given account with the values of the given storage keys.
+	fn pod_account(&self, address: &Address, account: &Account, keys: &BTreeSet<H256>) -> trie::Result<PodAccount> {
+		let accountdb = self.factories.accountdb.readonly(self.db.as_hashdb(), account.address_hash(address));
+		let code = match account.code() {
+			Some(code) => Some(code.to_vec()),
+			None => accountdb.as_hashdb().get(&account.code_hash()).map(|code| code.into_vec()),
+		};
+
+		let mut storage = BTreeMap::new();
+		for key in keys {
+			storage.insert(*key, account.storage_at(accountdb.as_hashdb(), key)?);
+		}
+
+		Ok(PodAccount {
+			balance: *account.balance(),
+			nonce: *account.nonce(),
+			code: code,
+			storage: storage,
+		})
+	}
+
 	// load required account data from the databases.
 	fn update_account_cache(require: RequireCache, account: &mut Account, state_db: &B, db: &HashDB) {
 		if let RequireCache::None = require {
@@ -945,6 +1074,8 @@ impl<B: Backend> State<B> {
 	/// Populates local cache if nothing found.
 	fn ensure_cached<F, U>(&self, a: &Address, require: RequireCache, check_null: bool, f: F) -> trie::Result<U>
 		where F: Fn(Option<&Account>) -> U {
+		self.note_access(a, None);
+
 		// check local cache first
 		if let Some(ref mut maybe_acc) = self.cache.borrow_mut().get_mut(a) {
 			if let Some(ref mut account) = maybe_acc.account {
@@ -993,6 +1124,8 @@ impl<B: Backend> State<B> {
 	fn require_or_from<'a, F, G>(&'a self, a: &Address, require_code: bool, default: F, not_default: G) -> trie::Result<RefMut<'a, Account>>
 		where F: FnOnce() -> Account, G: FnOnce(&mut Account),
 	{
+		self.note_access(a, None);
+
 		let contains_key = self.cache.borrow().contains_key(a);
 		if !contains_key {
 			match self.db.get_cached_account(a) {
@@ -1114,6 +1247,7 @@ impl Clone for State<StateDB> {
 			root: self.root.clone(),
 			cache: RefCell::new(cache),
 			checkpoints: RefCell::new(Vec::new()),
+			accessed: RefCell::new(self.accessed.borrow().clone()),
 			account_start_nonce: self.account_start_nonce.clone(),
 			factories: self.factories.clone(),
 		}
@@ -1137,6 +1271,7 @@ mod tests {
 	use ethcore_logger::init_log;
 	use trace::{FlatTrace, TraceError, trace};
 	use evm::CallType;
+	use types::account_diff::Diff;
 
 	fn secret() -> Secret {
 		keccak("").into()
@@ -1188,6 +1323,108 @@ mod tests {
 		assert_eq!(result.trace, expected_trace);
 	}
 
+	#[test]
+	fn should_apply_transaction_with_state_diff() {
+		init_log();
+
+		let mut state = get_temp_state();
+
+		let mut info = EnvInfo::default();
+		info.gas_limit = 1_000_000.into();
+		let machine = make_frontier_machine(5);
+
+		let t = Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Create,
+			value: 100.into(),
+			data: FromHex::from_hex("601080600c6000396000f3006000355415600957005b60203560003555").unwrap(),
+		}.sign(&secret(), None);
+
+		state.add_balance(&t.sender(), &(100.into()), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		let result = state.apply_with_state_diff(&info, &machine, &t, false).unwrap();
+		let diff = result.state_diff.unwrap();
+
+		let contract = Address::from_str("8988167e088c87cd314df6d3c2b83da5acb93ace").unwrap();
+		assert_eq!(diff.len(), 2);
+		assert_eq!(diff[&t.sender()].nonce, Diff::Changed(0.into(), 1.into()));
+		assert_eq!(diff[&t.sender()].balance, Diff::Changed(100.into(), 0.into()));
+		assert_eq!(diff[&contract].balance, Diff::Born(100.into()));
+		assert_eq!(diff[&contract].code, Diff::Born(vec![96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53]));
+	}
+
+	#[test]
+	fn should_diff_only_accounts_changed_by_transaction() {
+		init_log();
+
+		let mut state = get_temp_state();
+
+		let mut info = EnvInfo::default();
+		info.gas_limit = 1_000_000.into();
+		let machine = make_frontier_machine(5);
+
+		let create = Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Create,
+			value: 100.into(),
+			data: FromHex::from_hex("601080600c6000396000f3006000355415600957005b60203560003555").unwrap(),
+		}.sign(&secret(), None);
+		let contract = Address::from_str("8988167e088c87cd314df6d3c2b83da5acb93ace").unwrap();
+		let unrelated = Address::from(0xff);
+
+		state.add_balance(&create.sender(), &(100.into()), CleanupMode::NoEmpty).unwrap();
+		state.add_balance(&unrelated, &(5.into()), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		state.apply_with_state_diff(&info, &machine, &create, false).unwrap();
+
+		// a transaction with a bad nonce fails without leaving the checkpoint behind
+		assert!(state.apply_with_state_diff(&info, &machine, &create, false).is_err());
+
+		// store 2 under key 1
+		let call = Transaction {
+			nonce: 1.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Call(contract),
+			value: 0.into(),
+			data: FromHex::from_hex("00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002").unwrap(),
+		}.sign(&secret(), None);
+		let result = state.apply_with_state_diff(&info, &machine, &call, false).unwrap();
+		let diff = result.state_diff.unwrap();
+
+		assert_eq!(diff[&call.sender()].nonce, Diff::Changed(1.into(), 2.into()));
+		assert_eq!(diff[&contract].storage[&H256::from(1)], Diff::Changed(H256::zero(), H256::from(2)));
+		assert_eq!(diff[&contract].balance, Diff::Same);
+		assert!(!diff.get().contains_key(&unrelated));
+	}
+
+	#[test]
+	fn should_record_accesses_of_unmodified_accounts() {
+		let mut state = get_temp_state();
+		let a = Address::from(0xaa);
+		let b = Address::from(0xbb);
+		state.add_balance(&a, &(5.into()), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		assert_eq!(StateInfo::accessed(&state), None);
+
+		state.record_accesses();
+		state.balance(&a).unwrap();
+		state.storage_at(&b, &H256::from(1)).unwrap();
+
+		let accessed = StateInfo::accessed(&state).unwrap();
+		assert_eq!(accessed.len(), 2);
+		assert!(accessed[&a].is_empty());
+		assert_eq!(accessed[&b].iter().collect::<Vec<_>>(), vec![&H256::from(1)]);
+
+		// restarting forgets previous accesses
+		state.record_accesses();
+		assert_eq!(StateInfo::accessed(&state), Some(BTreeMap::new()));
+	}
+
 	#[test]
 	fn should_work_when_cloned() {
 		init_log();
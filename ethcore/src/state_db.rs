@@ -151,6 +151,14 @@ impl StateDB {
 		}
 	}
 
+	/// Disable the accounts bloom, so that every account is looked up in the state trie.
+	/// Used when the bloom does not describe the state, e.g. when executing against a partial state.
+	pub fn without_account_bloom(mut self) -> StateDB {
+		let parts = vec![!0u64; ACCOUNT_BLOOM_SPACE / 8];
+		self.account_bloom = Arc::new(Mutex::new(Bloom::from_parts(&parts, 1)));
+		self
+	}
+
 	/// Loads accounts bloom from the database
 	/// This bloom is used to handle request for the non-existant account fast
 	pub fn load_bloom(db: &KeyValueDB) -> Bloom {
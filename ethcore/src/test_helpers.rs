@@ -41,6 +41,7 @@ use std::sync::Arc;
 use transaction::{Action, Transaction, SignedTransaction};
 use views::BlockView;
 use blooms_db;
+use era_db;
 use kvdb::KeyValueDB;
 use kvdb_rocksdb;
 use tempdir::TempDir;
@@ -267,8 +268,10 @@ pub fn new_db() -> Arc<BlockChainDB> {
 	struct TestBlockChainDB {
 		_blooms_dir: TempDir,
 		_trace_blooms_dir: TempDir,
+		_ancient_dir: TempDir,
 		blooms: blooms_db::Database,
 		trace_blooms: blooms_db::Database,
+		ancient: era_db::Database,
 		key_value: Arc<KeyValueDB>,
 	}
 
@@ -284,16 +287,23 @@ pub fn new_db() -> Arc<BlockChainDB> {
 		fn trace_blooms(&self) -> &blooms_db::Database {
 			&self.trace_blooms
 		}
+
+		fn ancient(&self) -> Option<&era_db::Database> {
+			Some(&self.ancient)
+		}
 	}
 
 	let blooms_dir = TempDir::new("").unwrap();
 	let trace_blooms_dir = TempDir::new("").unwrap();
+	let ancient_dir = TempDir::new("").unwrap();
 
 	let db = TestBlockChainDB {
 		blooms: blooms_db::Database::open(blooms_dir.path()).unwrap(),
 		trace_blooms: blooms_db::Database::open(trace_blooms_dir.path()).unwrap(),
+		ancient: era_db::Database::open(ancient_dir.path()).unwrap(),
 		_blooms_dir: blooms_dir,
 		_trace_blooms_dir: trace_blooms_dir,
+		_ancient_dir: ancient_dir,
 		key_value: Arc::new(::kvdb_memorydb::create(::db::NUM_COLUMNS.unwrap()))
 	};
 
@@ -493,6 +503,7 @@ impl ChainNotify for TestNotify {
 			ChainMessageType::Consensus(data) => data,
 			ChainMessageType::SignedPrivateTransaction(data) => data,
 			ChainMessageType::PrivateTransaction(data) => data,
+			ChainMessageType::PrivateStateRequest(data) => data,
 		};
 		self.messages.write().push(data);
 	}
@@ -18,7 +18,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use hash::keccak;
 use io::IoChannel;
-use client::{BlockChainClient, Client, ClientConfig, BlockId, ChainInfo, BlockInfo, PrepareOpenBlock, ImportSealedBlock, ImportBlock};
+use client::{BlockChainClient, Client, ClientConfig, BlockId, FinalityRule, ChainInfo, BlockInfo, PrepareOpenBlock, ImportSealedBlock, ImportBlock};
 use state::{self, State, CleanupMode};
 use executive::{Executive, TransactOptions};
 use ethereum;
@@ -28,14 +28,14 @@ use test_helpers::{
 	generate_dummy_client_with_data, get_good_dummy_block, get_bad_state_dummy_block
 };
 use types::filter::Filter;
-use ethereum_types::{U256, Address};
+use ethereum_types::{U256, Address, H256};
 use miner::{Miner, PendingOrdering};
 use spec::Spec;
 use views::BlockView;
 use ethkey::KeyPair;
 use transaction::{PendingTransaction, Transaction, Action, Condition};
 use miner::MinerService;
-use rlp::{RlpStream, EMPTY_LIST_RLP};
+use rlp::{Rlp, RlpStream, EMPTY_LIST_RLP};
 use tempdir::TempDir;
 use test_helpers;
 
@@ -55,6 +55,53 @@ fn imports_from_empty() {
 	client.flush_queue();
 }
 
+#[test]
+fn state_outside_of_query_history_is_pruned() {
+	let spec = Spec::new_test();
+	let client = Client::new(
+		ClientConfig {
+			pruning: ::journaldb::Algorithm::OverlayRecent,
+			state_query_history: Some(2),
+			..Default::default()
+		},
+		&spec,
+		test_helpers::new_db(),
+		Arc::new(Miner::new_for_tests(&spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+	for block in get_good_dummy_block_seq(5) {
+		client.import_block(block).unwrap();
+	}
+	client.flush_queue();
+	client.import_verified_blocks();
+
+	assert_eq!(client.chain_info().best_block_number, 5);
+	assert!(client.state_at(BlockId::Latest).is_some());
+	assert!(client.state_at(BlockId::Number(5)).is_some());
+	assert!(client.state_at(BlockId::Number(4)).is_some());
+	assert!(client.state_at(BlockId::Number(3)).is_none());
+	assert!(client.state_at(BlockId::Earliest).is_none());
+}
+
+#[test]
+fn state_query_history_raises_pruning_history() {
+	let spec = Spec::new_test();
+	let client = Client::new(
+		ClientConfig {
+			pruning: ::journaldb::Algorithm::OverlayRecent,
+			history: 4,
+			state_query_history: Some(16),
+			..Default::default()
+		},
+		&spec,
+		test_helpers::new_db(),
+		Arc::new(Miner::new_for_tests(&spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+
+	assert_eq!(client.pruning_history(), 16);
+}
+
 #[test]
 fn should_return_registrar() {
 	let db = test_helpers::new_db();
@@ -109,7 +156,7 @@ fn imports_good_block() {
 
 #[test]
 fn fails_to_import_block_with_invalid_rlp() {
-	use error::{BlockImportError, BlockImportErrorKind};
+	use error::{BlockImportError, BlockImportErrorKind, BlockDecodeError};
 
 	let client = generate_dummy_client(6);
 	let mut rlp = RlpStream::new_list(3);
@@ -119,12 +166,37 @@ fn fails_to_import_block_with_invalid_rlp() {
 	let invalid_header_block = rlp.out();
 
 	match client.import_block(invalid_header_block) {
-		Err(BlockImportError(BlockImportErrorKind::Decoder(_), _)) => (), // all good
+		Err(BlockImportError(BlockImportErrorKind::BlockDecode(BlockDecodeError::InvalidRlp(_)), _)) => (), // all good
 		Err(_) => panic!("Should fail with a decoder error"),
 		Ok(_) => panic!("Should not import block with invalid header"),
 	}
 }
 
+#[test]
+fn fails_to_import_malformed_blocks() {
+	use error::{BlockImportError, BlockImportErrorKind, BlockDecodeError};
+
+	let client = generate_dummy_client(0);
+	let block = get_good_dummy_block();
+
+	let mut trailing = block.clone();
+	trailing.push(0);
+	match client.import_block(trailing) {
+		Err(BlockImportError(BlockImportErrorKind::BlockDecode(BlockDecodeError::TrailingBytes(1)), _)) => (),
+		Err(e) => panic!("Should fail with trailing bytes, got {:?}", e),
+		Ok(_) => panic!("Should not import block with trailing bytes"),
+	}
+
+	let mut header_only = RlpStream::new_list(2);
+	header_only.append_raw(Rlp::new(&block).at(0).unwrap().as_raw(), 1);
+	header_only.append_raw(&EMPTY_LIST_RLP, 1);
+	match client.import_block(header_only.out()) {
+		Err(BlockImportError(BlockImportErrorKind::BlockDecode(BlockDecodeError::IncorrectItemCount(2)), _)) => (),
+		Err(e) => panic!("Should fail with an incorrect item count, got {:?}", e),
+		Ok(_) => panic!("Should not import block without uncles"),
+	}
+}
+
 #[test]
 fn query_none_block() {
 	let db = test_helpers::new_db();
@@ -263,6 +335,99 @@ fn can_handle_long_fork() {
 	assert_eq!(2000, client.chain_info().best_block_number);
 }
 
+#[test]
+fn halts_import_on_deep_reorg() {
+	let test_spec = Spec::new_test();
+	let mut config = ClientConfig::default();
+	config.max_reorg_depth = Some(2);
+	let client = Client::new(
+		config,
+		&test_spec,
+		test_helpers::new_db(),
+		Arc::new(Miner::new_for_tests(&test_spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+
+	push_blocks_to_client(&client, 10, 1, 5);
+	push_blocks_to_client(&client, 20, 1, 7);
+	client.flush_queue();
+	for _ in 0..10 {
+		client.import_verified_blocks();
+	}
+	assert_eq!(client.chain_info().best_block_number, 5);
+
+	let alert = client.pending_reorg().unwrap();
+	assert_eq!(alert.depth, 5);
+	assert_eq!(alert.ancestor_number, 0);
+	assert_eq!(alert.new_number, 6);
+
+	assert!(client.accept_reorg());
+	assert!(!client.accept_reorg());
+	for _ in 0..10 {
+		client.import_verified_blocks();
+	}
+	assert_eq!(client.chain_info().best_block_number, 7);
+	assert!(client.pending_reorg().is_none());
+}
+
+#[test]
+fn pauses_and_resumes_import() {
+	let test_spec = Spec::new_test();
+	let client = Client::new(
+		ClientConfig::default(),
+		&test_spec,
+		test_helpers::new_db(),
+		Arc::new(Miner::new_for_tests(&test_spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+
+	client.pause_import();
+	assert!(client.is_import_paused());
+	push_blocks_to_client(&client, 10, 1, 5);
+	client.flush_queue();
+	for _ in 0..10 {
+		client.import_verified_blocks();
+	}
+	assert_eq!(client.chain_info().best_block_number, 0);
+
+	client.resume_import();
+	assert!(!client.is_import_paused());
+	for _ in 0..10 {
+		client.import_verified_blocks();
+	}
+	assert_eq!(client.chain_info().best_block_number, 5);
+}
+
+#[test]
+fn reports_depth_finality() {
+	let test_spec = Spec::new_test();
+	let mut config = ClientConfig::default();
+	config.finality_depth = 3;
+	let client = Client::new(
+		config,
+		&test_spec,
+		test_helpers::new_db(),
+		Arc::new(Miner::new_for_tests(&test_spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+
+	let status = client.finality_status();
+	assert_eq!(status.rule, FinalityRule::Depth);
+	assert_eq!(status.finalized_number, 0);
+
+	push_blocks_to_client(&client, 10, 1, 5);
+	client.flush_queue();
+	client.import_verified_blocks();
+
+	let status = client.finality_status();
+	assert_eq!(status.depth, Some(3));
+	assert_eq!(status.best_number, 5);
+	assert_eq!(status.finalized_number, 2);
+	assert_eq!(Some(status.finalized_hash), client.block_hash(BlockId::Number(2)));
+	assert_eq!(client.block_hash(BlockId::Finalized), client.block_hash(BlockId::Number(2)));
+	assert_eq!(client.block_number(BlockId::Finalized), Some(2));
+}
+
 #[test]
 fn can_mine() {
 	let dummy_blocks = get_good_dummy_block_seq(2);
@@ -380,3 +545,53 @@ fn transaction_proof() {
 	assert_eq!(state.balance(&Address::default()).unwrap(), 5.into());
 	assert_eq!(state.balance(&address).unwrap(), 95.into());
 }
+
+#[test]
+fn block_witness() {
+	use ::client::ProvingBlockChainClient;
+
+	let client = generate_dummy_client_with_data(3, 2, &[1.into()]);
+	let block = client.block(BlockId::Number(2)).unwrap().into_inner();
+	let witness = client.block_witness(BlockId::Number(2)).unwrap();
+	assert!(!witness.is_empty());
+	assert!(client.verify_block_witness(block.clone(), &witness).unwrap());
+
+	// every recorded item is needed to re-execute the block.
+	for i in 0..witness.len() {
+		let mut incomplete = witness.clone();
+		incomplete.remove(i);
+		assert!(!client.verify_block_witness(block.clone(), &incomplete).unwrap_or(false));
+
+		let mut truncated = witness.clone();
+		truncated[i].pop();
+		assert!(!client.verify_block_witness(block.clone(), &truncated).unwrap_or(false));
+	}
+
+	// the witness is bound to the block it was recorded for.
+	let other_block = client.block(BlockId::Number(3)).unwrap().into_inner();
+	assert!(!client.verify_block_witness(other_block, &witness).unwrap_or(false));
+	assert!(client.verify_block_witness(block[..block.len() - 1].to_vec(), &witness).is_err());
+
+	assert!(client.block_witness(BlockId::Number(0)).is_err());
+}
+
+#[test]
+fn account_and_storage_ranges() {
+	let client = generate_dummy_client(1);
+	let all = client.account_range(BlockId::Latest, &H256::zero(), 1000).unwrap();
+	assert!(all.next.is_none());
+	assert!(all.items.len() > 1);
+	assert!(all.items.windows(2).all(|pair| pair[0].hash < pair[1].hash));
+
+	let first = client.account_range(BlockId::Latest, &H256::zero(), 1).unwrap();
+	assert_eq!(first.items, &all.items[..1]);
+	assert_eq!(first.next, Some(all.items[1].hash));
+
+	let second = client.account_range(BlockId::Latest, &first.next.unwrap(), 1).unwrap();
+	assert_eq!(second.items[0], all.items[1]);
+
+	// accounts without storage have nothing to iterate over.
+	let storage = client.storage_range(BlockId::Latest, &Address::random(), &H256::zero(), 10).unwrap();
+	assert!(storage.items.is_empty());
+	assert!(storage.next.is_none());
+}
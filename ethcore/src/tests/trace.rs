@@ -205,4 +205,16 @@ fn can_trace_block_and_uncle_reward() {
 	// Test1. Check block filter
 	let traces = client.block_traces(BlockId::Number(3));
 	assert_eq!(traces.unwrap().len(), 3);
+
+	// Test2. Check that pruned traces are restored by re-execution
+	let traces: Vec<_> = (1..4).map(|n| client.block_traces(BlockId::Number(n))).collect();
+	assert_eq!(client.prune_traces(4).unwrap(), 3);
+	assert!(client.block_traces(BlockId::Number(3)).is_none());
+	assert!(client.block_traces(BlockId::Number(0)).is_some(), "Genesis trace should be always present.");
+
+	for n in 1..4 {
+		client.retrace_block(n).unwrap();
+	}
+	let retraced: Vec<_> = (1..4).map(|n| client.block_traces(BlockId::Number(n))).collect();
+	assert_eq!(traces, retraced);
 }
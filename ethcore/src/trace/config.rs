@@ -22,6 +22,8 @@ pub struct Config {
 	/// Indicates if tracing should be enabled or not.
 	/// If it's None, it will be automatically configured.
 	pub enabled: bool,
+	/// Indicates if state diffs of transactions should be recorded along with traces.
+	pub state_diffs: bool,
 	/// Preferef cache-size.
 	pub pref_cache_size: usize,
 	/// Max cache-size.
@@ -32,6 +34,7 @@ impl Default for Config {
 	fn default() -> Self {
 		Config {
 			enabled: false,
+			state_diffs: false,
 			pref_cache_size: 15 * 1024 * 1024,
 			max_cache_size: 20 * 1024 * 1024,
 		}
@@ -26,6 +26,8 @@ use header::BlockNumber;
 use trace::{LocalizedTrace, Config, Filter, Database as TraceDatabase, ImportRequest, DatabaseExtras};
 use db::{self, Key, Writable, Readable, CacheUpdatePolicy};
 use super::flat::{FlatTrace, FlatBlockTraces, FlatTransactionTraces};
+use super::state_diffs::BlockStateDiffs;
+use types::state_diff::StateDiff;
 use cache_manager::CacheManager;
 
 const TRACE_DB_VER: &'static [u8] = b"1.0";
@@ -34,6 +36,8 @@ const TRACE_DB_VER: &'static [u8] = b"1.0";
 enum TraceDBIndex {
 	/// Block traces index.
 	BlockTraces = 0,
+	/// Block state diffs index.
+	BlockStateDiffs = 1,
 }
 
 impl Key<FlatBlockTraces> for H256 {
@@ -47,6 +51,17 @@ impl Key<FlatBlockTraces> for H256 {
 	}
 }
 
+impl Key<BlockStateDiffs> for H256 {
+	type Target = H264;
+
+	fn key(&self) -> H264 {
+		let mut result = H264::default();
+		result[0] = TraceDBIndex::BlockStateDiffs as u8;
+		result[1..33].copy_from_slice(self);
+		result
+	}
+}
+
 /// Database to store transaction execution trace.
 ///
 /// Whenever a transaction is executed by EVM it's execution trace is stored
@@ -62,6 +77,8 @@ pub struct TraceDB<T> where T: DatabaseExtras {
 	db: Arc<BlockChainDB>,
 	/// tracing enabled
 	enabled: bool,
+	/// state diffs recording enabled
+	state_diffs: bool,
 	/// extras
 	extras: Arc<T>,
 }
@@ -81,6 +98,7 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 			cache_manager: RwLock::new(CacheManager::new(config.pref_cache_size, config.max_cache_size, 10 * 1024)),
 			db,
 			enabled: config.enabled,
+			state_diffs: config.enabled && config.state_diffs,
 			extras: extras,
 		}
 	}
@@ -119,6 +137,12 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 		result
 	}
 
+	/// Returns state diffs for block with hash.
+	fn state_diffs(&self, block_hash: &H256) -> Option<Vec<StateDiff>> {
+		let diffs: Option<BlockStateDiffs> = self.db.key_value().read(db::COL_TRACE, block_hash);
+		diffs.map(Into::into)
+	}
+
 	/// Returns vector of transaction traces for given block.
 	fn transactions_traces(&self, block_hash: &H256) -> Option<Vec<FlatTransactionTraces>> {
 		self.traces(block_hash).map(Into::into)
@@ -180,6 +204,10 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 		self.enabled
 	}
 
+	fn state_diffs_enabled(&self) -> bool {
+		self.state_diffs
+	}
+
 	/// Traces of import request's enacted blocks are expected to be already in database
 	/// or to be the currently inserted trace.
 	fn import(&self, batch: &mut DBTransaction, request: ImportRequest) {
@@ -224,6 +252,23 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 			// note_used must be called after locking traces to avoid cache/traces deadlock on garbage collection
 			self.note_trace_used(request.block_hash);
 		}
+
+		// insert new block state diffs into the database
+		if self.state_diffs_enabled() {
+			if let Some(state_diffs) = request.state_diffs {
+				batch.write(db::COL_TRACE, &request.block_hash, &BlockStateDiffs::from(state_diffs));
+			}
+		}
+	}
+
+	fn remove(&self, batch: &mut DBTransaction, block_hash: &H256) {
+		if !self.tracing_enabled() {
+			return;
+		}
+
+		self.traces.write().remove(block_hash);
+		Writable::delete::<FlatBlockTraces, _>(batch, db::COL_TRACE, block_hash);
+		Writable::delete::<BlockStateDiffs, _>(batch, db::COL_TRACE, block_hash);
 	}
 
 	fn trace(&self, block_number: BlockNumber, tx_position: usize, trace_position: Vec<usize>) -> Option<LocalizedTrace> {
@@ -309,6 +354,17 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 			)
 	}
 
+	fn transaction_state_diff(&self, block_number: BlockNumber, tx_position: usize) -> Option<StateDiff> {
+		self.extras.block_hash(block_number)
+			.and_then(|block_hash| self.state_diffs(&block_hash))
+			.and_then(|diffs| diffs.into_iter().nth(tx_position))
+	}
+
+	fn block_state_diffs(&self, block_number: BlockNumber) -> Option<Vec<StateDiff>> {
+		self.extras.block_hash(block_number)
+			.and_then(|block_hash| self.state_diffs(&block_hash))
+	}
+
 	fn filter(&self, filter: &Filter) -> Vec<LocalizedTrace> {
 		let possibilities = filter.bloom_possibilities();
 		let numbers = self.db.trace_blooms()
@@ -320,9 +376,11 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 				let number = n as BlockNumber;
 				let hash = self.extras.block_hash(number)
 					.expect("Expected to find block hash. Extras db is probably corrupted");
-				let traces = self.traces(&hash)
-					.expect("Expected to find a trace. Db is probably corrupted.");
-				self.matching_block_traces(filter, traces, hash, number)
+				// traces of old blocks might have been pruned
+				match self.traces(&hash) {
+					Some(traces) => self.matching_block_traces(filter, traces, hash, number),
+					None => Vec::new(),
+				}
 			})
 			.collect()
 	}
@@ -330,7 +388,7 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 
 #[cfg(test)]
 mod tests {
-	use std::collections::HashMap;
+	use std::collections::{BTreeMap, HashMap};
 	use std::sync::Arc;
 	use ethereum_types::{H256, U256, Address};
 	use kvdb::{DBTransaction};
@@ -341,6 +399,8 @@ mod tests {
 	use trace::flat::{FlatTrace, FlatBlockTraces, FlatTransactionTraces};
 	use evm::CallType;
 	use test_helpers::new_db;
+	use types::account_diff::{AccountDiff, Diff};
+	use types::state_diff::StateDiff;
 
 	struct NoopExtras;
 
@@ -427,6 +487,7 @@ mod tests {
 				}),
 				result: Res::FailedCall(TraceError::OutOfGas),
 			}])]),
+			state_diffs: None,
 			block_hash: block_hash.clone(),
 			block_number: block_number,
 			enacted: vec![block_hash],
@@ -449,6 +510,7 @@ mod tests {
 				}),
 				result: Res::FailedCall(TraceError::OutOfGas),
 			}])]),
+			state_diffs: None,
 			block_hash: block_hash.clone(),
 			block_number: block_number,
 			enacted: vec![],
@@ -615,6 +677,78 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_import_state_diffs() {
+		let db = new_db();
+		let mut config = Config::default();
+		config.enabled = true;
+		config.state_diffs = true;
+		let block_1 = H256::from(0xa1);
+		let tx_1 = H256::from(0xff);
+
+		let mut extras = Extras::default();
+		extras.block_hashes.insert(0, H256::default());
+		extras.block_hashes.insert(1, block_1.clone());
+		extras.transaction_hashes.insert(1, vec![tx_1.clone()]);
+
+		let tracedb = TraceDB::new(config, db.clone(), Arc::new(extras));
+
+		let mut raw = BTreeMap::new();
+		raw.insert(Address::from(1), AccountDiff {
+			balance: Diff::Changed(10.into(), 7.into()),
+			nonce: Diff::Changed(0.into(), 1.into()),
+			code: Diff::Same,
+			storage: BTreeMap::new(),
+		});
+		let diff = StateDiff { raw };
+
+		let mut request = create_simple_import_request(1, block_1.clone());
+		request.state_diffs = Some(vec![diff.clone()]);
+		let mut batch = DBTransaction::new();
+		tracedb.import(&mut batch, request);
+		db.key_value().write(batch).unwrap();
+
+		assert_eq!(tracedb.block_state_diffs(1), Some(vec![diff.clone()]));
+		assert_eq!(tracedb.transaction_state_diff(1, 0), Some(diff));
+		assert_eq!(tracedb.transaction_state_diff(1, 1), None);
+		assert_eq!(tracedb.block_state_diffs(0), None);
+	}
+
+	#[test]
+	fn test_remove() {
+		let db = new_db();
+		let mut config = Config::default();
+		config.enabled = true;
+		let block_1 = H256::from(0xa1);
+		let tx_1 = H256::from(0xff);
+
+		let mut extras = Extras::default();
+		extras.block_hashes.insert(0, H256::default());
+		extras.block_hashes.insert(1, block_1.clone());
+		extras.transaction_hashes.insert(1, vec![tx_1.clone()]);
+
+		let tracedb = TraceDB::new(config, db.clone(), Arc::new(extras));
+
+		let request = create_simple_import_request(1, block_1.clone());
+		let mut batch = DBTransaction::new();
+		tracedb.import(&mut batch, request);
+		db.key_value().write(batch).unwrap();
+		assert!(tracedb.block_traces(1).is_some());
+
+		let mut batch = DBTransaction::new();
+		tracedb.remove(&mut batch, &block_1);
+		db.key_value().write(batch).unwrap();
+		assert_eq!(tracedb.block_traces(1), None);
+
+		// blooms of removed blocks are still there, but they shouldn't match anything
+		let filter = Filter {
+			range: (1..1),
+			from_address: AddressesFilter::from(vec![Address::from(1)]),
+			to_address: AddressesFilter::from(vec![]),
+		};
+		assert!(tracedb.filter(&filter).is_empty());
+	}
+
 	#[test]
 	fn query_genesis() {
 		let db = new_db();
@@ -18,11 +18,14 @@
 use ethereum_types::H256;
 use header::BlockNumber;
 use trace::FlatBlockTraces;
+use types::state_diff::StateDiff;
 
 /// Traces import request.
 pub struct ImportRequest {
 	/// Traces to import.
 	pub traces: FlatBlockTraces,
+	/// State diffs of block transactions, if recorded.
+	pub state_diffs: Option<Vec<StateDiff>>,
 	/// Hash of traces block.
 	pub block_hash: H256,
 	/// Number of traces block.
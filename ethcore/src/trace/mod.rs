@@ -21,12 +21,14 @@ mod db;
 mod executive_tracer;
 mod import;
 mod noop_tracer;
+mod profiler;
 mod types;
 
 pub use self::config::Config;
 pub use self::db::TraceDB;
 pub use self::noop_tracer::{NoopTracer, NoopVMTracer};
 pub use self::executive_tracer::{ExecutiveTracer, ExecutiveVMTracer};
+pub use self::profiler::{Profiler, Profile, ProfileStats, op_name};
 pub use self::import::ImportRequest;
 pub use self::localized::LocalizedTrace;
 
@@ -34,6 +36,7 @@ pub use self::types::{filter, flat, localized, trace, Tracing};
 pub use self::types::error::Error as TraceError;
 pub use self::types::trace::{VMTrace, VMOperation, VMExecutedOperation, MemoryDiff, StorageDiff, RewardType};
 pub use self::types::flat::{FlatTrace, FlatTransactionTraces, FlatBlockTraces};
+pub use self::types::state_diffs::BlockStateDiffs;
 pub use self::types::filter::{Filter, AddressesFilter};
 
 use ethereum_types::{H256, U256, Address};
@@ -42,6 +45,7 @@ use bytes::Bytes;
 use self::trace::{Call, Create};
 use vm::ActionParams;
 use header::BlockNumber;
+use types::state_diff::StateDiff;
 
 /// This trait is used by executive to build traces.
 pub trait Tracer: Send {
@@ -137,9 +141,15 @@ pub trait Database {
 	/// Returns true if tracing is enabled. Otherwise false.
 	fn tracing_enabled(&self) -> bool;
 
+	/// Returns true if state diffs of transactions are recorded. Otherwise false.
+	fn state_diffs_enabled(&self) -> bool;
+
 	/// Imports new block traces.
 	fn import(&self, batch: &mut DBTransaction, request: ImportRequest);
 
+	/// Removes traces and state diffs of given block.
+	fn remove(&self, batch: &mut DBTransaction, block_hash: &H256);
+
 	/// Returns localized trace at given position.
 	fn trace(&self, block_number: BlockNumber, tx_position: usize, trace_position: Vec<usize>) -> Option<LocalizedTrace>;
 
@@ -149,6 +159,12 @@ pub trait Database {
 	/// Returns localized traces created in given block.
 	fn block_traces(&self, block_number: BlockNumber) -> Option<Vec<LocalizedTrace>>;
 
+	/// Returns state diff of a single transaction.
+	fn transaction_state_diff(&self, block_number: BlockNumber, tx_position: usize) -> Option<StateDiff>;
+
+	/// Returns state diffs of all transactions in given block.
+	fn block_state_diffs(&self, block_number: BlockNumber) -> Option<Vec<StateDiff>>;
+
 	/// Filter traces matching given filter.
 	fn filter(&self, filter: &Filter) -> Vec<LocalizedTrace>;
 }
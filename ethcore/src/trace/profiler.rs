@@ -0,0 +1,168 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Gas and time profiling VM tracer.
+//!
+//! Costs are aggregated per opcode and per call frame. Frames are named after
+//! the instruction that entered them (e.g. `root;CALL@7;CREATE@15`), and the
+//! cost of a CALL/CREATE excludes whatever was spent inside the frame it opened,
+//! so that the collapsed stacks can be fed directly to `flamegraph.pl`.
+
+use std::collections::BTreeMap;
+use std::mem;
+use std::time::{Duration, Instant};
+
+use ethereum_types::U256;
+use trace::VMTracer;
+
+/// Name of the outermost call frame.
+const ROOT_FRAME: &'static str = "root";
+
+/// Aggregated costs of a group of executed instructions.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProfileStats {
+	/// Number of instructions executed.
+	pub count: usize,
+	/// Gas spent, excluding nested frames.
+	pub gas: U256,
+	/// Wall-clock time spent, excluding nested frames.
+	pub time: Duration,
+}
+
+impl ProfileStats {
+	fn record(&mut self, gas: U256, time: Duration) {
+		self.count += 1;
+		self.gas = self.gas.saturating_add(gas);
+		self.time += time;
+	}
+
+	fn merge(&mut self, other: &ProfileStats) {
+		self.count += other.count;
+		self.gas = self.gas.saturating_add(other.gas);
+		self.time += other.time;
+	}
+}
+
+/// Execution profile.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Profile {
+	/// Costs by opcode.
+	pub opcodes: BTreeMap<u8, ProfileStats>,
+	/// Costs by call frame.
+	pub frames: BTreeMap<String, ProfileStats>,
+	/// Gas spent by frame and opcode, keyed by the collapsed stack.
+	pub stacks: BTreeMap<String, U256>,
+}
+
+impl Profile {
+	fn merge(&mut self, other: Profile) {
+		for (op, stats) in other.opcodes {
+			self.opcodes.entry(op).or_insert_with(ProfileStats::default).merge(&stats);
+		}
+		for (frame, stats) in other.frames {
+			self.frames.entry(frame).or_insert_with(ProfileStats::default).merge(&stats);
+		}
+		for (stack, gas) in other.stacks {
+			let entry = self.stacks.entry(stack).or_insert_with(U256::zero);
+			*entry = entry.saturating_add(gas);
+		}
+	}
+
+	/// Collapsed stacks weighted by gas, one line per stack, as consumed by flamegraph tools.
+	pub fn folded(&self) -> Vec<String> {
+		self.stacks.iter()
+			.map(|(stack, gas)| format!("{} {}", stack, gas))
+			.collect()
+	}
+}
+
+/// Returns the mnemonic of given instruction, or its hex value for undefined ones.
+pub fn op_name(instruction: u8) -> String {
+	match ::evm::INSTRUCTIONS[instruction as usize].name {
+		"" => format!("0x{:02x}", instruction),
+		name => name.to_owned(),
+	}
+}
+
+/// VM tracer aggregating gas and wall-clock time by opcode and call frame.
+#[derive(Default)]
+pub struct Profiler {
+	depth: usize,
+	frame: String,
+	pc: usize,
+	instruction: u8,
+	current_gas: U256,
+	started: Option<Instant>,
+	gas_used: U256,
+	time: Duration,
+	subtraces_gas: U256,
+	subtraces_time: Duration,
+	profile: Profile,
+}
+
+impl VMTracer for Profiler {
+	type Output = Profile;
+
+	fn trace_next_instruction(&mut self, pc: usize, instruction: u8, current_gas: U256) -> bool {
+		self.pc = pc;
+		self.instruction = instruction;
+		self.current_gas = current_gas;
+		true
+	}
+
+	fn trace_prepare_execute(&mut self, _pc: usize, _instruction: u8, _gas_cost: U256) {
+		self.started = Some(Instant::now());
+	}
+
+	fn trace_executed(&mut self, gas_left: U256, _stack_push: &[U256], _mem_diff: Option<(usize, &[u8])>, _store_diff: Option<(U256, U256)>) {
+		let time = self.started.take().map(|started| started.elapsed()).unwrap_or_default();
+		let gas = self.current_gas.saturating_sub(gas_left);
+		self.gas_used = self.gas_used.saturating_add(gas);
+		self.time += time;
+
+		// nested frames are accounted for separately
+		let gas = gas.saturating_sub(mem::replace(&mut self.subtraces_gas, U256::zero()));
+		let time = time.checked_sub(mem::replace(&mut self.subtraces_time, Duration::default())).unwrap_or_default();
+
+		self.profile.opcodes.entry(self.instruction).or_insert_with(ProfileStats::default).record(gas, time);
+		self.profile.frames.entry(self.frame.clone()).or_insert_with(ProfileStats::default).record(gas, time);
+		let stack = self.profile.stacks.entry(format!("{};{}", self.frame, op_name(self.instruction))).or_insert_with(U256::zero);
+		*stack = stack.saturating_add(gas);
+	}
+
+	fn prepare_subtrace(&self, _code: &[u8]) -> Self {
+		let frame = match self.depth {
+			0 => ROOT_FRAME.to_owned(),
+			_ => format!("{};{}@{}", self.frame, op_name(self.instruction), self.pc),
+		};
+
+		Profiler {
+			depth: self.depth + 1,
+			frame,
+			..Default::default()
+		}
+	}
+
+	fn done_subtrace(&mut self, sub: Self) {
+		self.subtraces_gas = self.subtraces_gas.saturating_add(sub.gas_used);
+		self.subtraces_time += sub.time;
+		self.profile.merge(sub.profile);
+	}
+
+	fn drain(self) -> Option<Profile> {
+		Some(self.profile)
+	}
+}
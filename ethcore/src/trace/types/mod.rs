@@ -21,6 +21,7 @@ pub mod filter;
 pub mod flat;
 pub mod trace;
 pub mod localized;
+pub mod state_diffs;
 
 use self::flat::FlatTransactionTraces;
 
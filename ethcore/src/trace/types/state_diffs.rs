@@ -0,0 +1,35 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State diffs of transactions in a block.
+
+use types::state_diff::StateDiff;
+
+/// Represents state diffs produced by transactions in a single block.
+#[derive(Debug, PartialEq, Clone, Default, RlpEncodableWrapper, RlpDecodableWrapper)]
+pub struct BlockStateDiffs(Vec<StateDiff>);
+
+impl From<Vec<StateDiff>> for BlockStateDiffs {
+	fn from(v: Vec<StateDiff>) -> Self {
+		BlockStateDiffs(v)
+	}
+}
+
+impl Into<Vec<StateDiff>> for BlockStateDiffs {
+	fn into(self) -> Vec<StateDiff> {
+		self.0
+	}
+}
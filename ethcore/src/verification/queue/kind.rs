@@ -69,7 +69,7 @@ pub mod blocks {
 	use super::{Kind, BlockLike};
 
 	use engines::EthEngine;
-	use error::{Error, ErrorKind, BlockError};
+	use error::{Error, ErrorKind, BlockError, BlockDecodeError};
 	use header::Header;
 	use verification::{PreverifiedBlock, verify_block_basic, verify_block_unordered};
 
@@ -119,9 +119,22 @@ pub mod blocks {
 
 	impl Unverified {
 		/// Create an `Unverified` from raw bytes.
-		pub fn from_rlp(bytes: Bytes) -> Result<Self, ::rlp::DecoderError> {
-
-			let header = ::rlp::Rlp::new(&bytes).val_at(0)?; 
+		///
+		/// Only the header is decoded here, but the bytes have to hold exactly one block of three items,
+		/// so malformed blocks are rejected before they are queued.
+		pub fn from_rlp(bytes: Bytes) -> Result<Self, BlockDecodeError> {
+			let header = {
+				let rlp = ::rlp::Rlp::new(&bytes);
+				let length = rlp.payload_info()?.total();
+				if length != bytes.len() {
+					return Err(BlockDecodeError::TrailingBytes(bytes.len().saturating_sub(length)));
+				}
+				let count = rlp.item_count()?;
+				if count != 3 {
+					return Err(BlockDecodeError::IncorrectItemCount(count));
+				}
+				rlp.val_at(0)?
+			};
 			Ok(Unverified {
 				header: header,
 				bytes: bytes,
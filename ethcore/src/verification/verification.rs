@@ -60,6 +60,11 @@ impl HeapSizeOf for PreverifiedBlock {
 
 /// Phase 1 quick block verification. Only does checks that are cheap. Operates on a single block
 pub fn verify_block_basic(header: &Header, bytes: &[u8], engine: &EthEngine) -> Result<(), Error> {
+	if let Some(max_block_size) = engine.params().max_block_size(header.number()) {
+		if bytes.len() > max_block_size {
+			return Err(From::from(BlockError::TooLarge(OutOfBounds { min: None, max: Some(max_block_size), found: bytes.len() })));
+		}
+	}
 	verify_header_params(&header, engine, true)?;
 	verify_block_integrity(bytes, &header.transactions_root(), &header.uncles_hash())?;
 	engine.verify_block_basic(&header)?;
@@ -266,11 +271,11 @@ pub fn verify_header_params(header: &Header, engine: &EthEngine, is_full: bool)
 	if header.gas_used() > header.gas_limit() {
 		return Err(From::from(BlockError::TooMuchGasUsed(OutOfBounds { max: Some(header.gas_limit().clone()), min: None, found: header.gas_used().clone() })));
 	}
-	let min_gas_limit = engine.params().min_gas_limit;
+	let min_gas_limit = engine.params().min_gas_limit(header.number());
 	if header.gas_limit() < &min_gas_limit {
 		return Err(From::from(BlockError::InvalidGasLimit(OutOfBounds { min: Some(min_gas_limit), max: None, found: header.gas_limit().clone() })));
 	}
-	let maximum_extra_data_size = engine.maximum_extra_data_size();
+	let maximum_extra_data_size = engine.maximum_extra_data_size(header.number());
 	if header.number() != 0 && header.extra_data().len() > maximum_extra_data_size {
 		return Err(From::from(BlockError::ExtraDataOutOfBounds(OutOfBounds { min: None, max: Some(maximum_extra_data_size), found: header.extra_data().len() })));
 	}
@@ -306,7 +311,7 @@ fn verify_parent(header: &Header, parent: &Header, engine: &EthEngine) -> Result
 	assert!(header.parent_hash().is_zero() || &parent.hash() == header.parent_hash(),
 			"Parent hash should already have been verified; qed");
 
-	let gas_limit_divisor = engine.params().gas_limit_bound_divisor;
+	let gas_limit_divisor = engine.params().gas_limit_bound_divisor(header.number());
 
 	if !engine.is_timestamp_valid(header.timestamp(), parent.timestamp()) {
 		let min = SystemTime::now() + Duration::from_secs(parent.timestamp() + 1);
@@ -673,14 +678,14 @@ mod tests {
 			TooMuchGasUsed(OutOfBounds { max: Some(header.gas_limit().clone()), min: None, found: header.gas_used().clone() }));
 
 		header = good.clone();
-		header.extra_data_mut().resize(engine.maximum_extra_data_size() + 1, 0u8);
+		header.extra_data_mut().resize(engine.maximum_extra_data_size(good.number()) + 1, 0u8);
 		check_fail(basic_test(&create_test_block(&header), engine),
-			ExtraDataOutOfBounds(OutOfBounds { max: Some(engine.maximum_extra_data_size()), min: None, found: header.extra_data().len() }));
+			ExtraDataOutOfBounds(OutOfBounds { max: Some(engine.maximum_extra_data_size(good.number())), min: None, found: header.extra_data().len() }));
 
 		header = good.clone();
-		header.extra_data_mut().resize(engine.maximum_extra_data_size() + 1, 0u8);
+		header.extra_data_mut().resize(engine.maximum_extra_data_size(good.number()) + 1, 0u8);
 		check_fail(basic_test(&create_test_block(&header), engine),
-			ExtraDataOutOfBounds(OutOfBounds { max: Some(engine.maximum_extra_data_size()), min: None, found: header.extra_data().len() }));
+			ExtraDataOutOfBounds(OutOfBounds { max: Some(engine.maximum_extra_data_size(good.number())), min: None, found: header.extra_data().len() }));
 
 		header = good.clone();
 		header.set_uncles_hash(good_uncles_hash.clone());
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, HashSet, BTreeMap};
 use std::io;
 use std::ops::Range;
 use std::time::Duration;
@@ -28,6 +28,7 @@ use ethereum_types::{H256, H512, U256};
 use io::{TimerToken};
 use ethcore::ethstore::ethkey::Secret;
 use ethcore::client::{BlockChainClient, ChainNotify, ChainRoute, ChainMessageType};
+use ethcore::encoded;
 use ethcore::snapshot::SnapshotService;
 use ethcore::header::BlockNumber;
 use sync_io::NetSyncIo;
@@ -36,12 +37,13 @@ use std::net::{SocketAddr, AddrParseError};
 use std::str::FromStr;
 use parking_lot::RwLock;
 use chain::{ETH_PROTOCOL_VERSION_63, ETH_PROTOCOL_VERSION_62,
-	PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3};
+	PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4};
 use light::client::AsLightClient;
 use light::Provider;
 use light::net::{self as light_net, LightProtocol, Params as LightParams, Capabilities, Handler as LightHandler, EventContext};
 use network::IpFilter;
 use private_tx::PrivateTxHandler;
+use fork_monitor::{ForkAlertHandler, ForkStatus};
 use transaction::UnverifiedTransaction;
 
 /// Parity sync protocol
@@ -86,8 +88,27 @@ impl WarpSync {
 	}
 }
 
+/// Determines which peers receive pending transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionPropagation {
+	/// Send transactions to all peers.
+	All,
+	/// Send transactions to a random sample of sqrt(n) peers.
+	Sqrt,
+	/// Never propagate transactions (listen-only).
+	None,
+	/// Send only local transactions and only to the configured peer group.
+	Private,
+}
+
+impl Default for TransactionPropagation {
+	fn default() -> Self {
+		TransactionPropagation::Sqrt
+	}
+}
+
 /// Sync configuration
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct SyncConfig {
 	/// Max blocks to download ahead
 	pub max_download_ahead_blocks: usize,
@@ -105,6 +126,20 @@ pub struct SyncConfig {
 	pub warp_sync: WarpSync,
 	/// Enable light client server.
 	pub serve_light: bool,
+	/// Minimal length of a competing branch which raises a fork alert.
+	pub fork_alert_depth: Option<BlockNumber>,
+	/// Time without best block progress after which the sync is considered stalled.
+	pub stall_timeout: Option<Duration>,
+	/// Number of lagging peers to disconnect when the sync stalls.
+	pub stall_rotate_peers: usize,
+	/// Restart node discovery when the sync stalls.
+	pub stall_refresh_discovery: bool,
+	/// Download the best peer's head first and link it backwards to our chain.
+	pub head_first: bool,
+	/// Pending transactions propagation policy.
+	pub tx_propagation: TransactionPropagation,
+	/// Node IDs of peers receiving local transactions in private propagation mode.
+	pub tx_propagation_peers: HashSet<H512>,
 }
 
 impl Default for SyncConfig {
@@ -118,6 +153,13 @@ impl Default for SyncConfig {
 			fork_block: None,
 			warp_sync: WarpSync::Disabled,
 			serve_light: false,
+			fork_alert_depth: None,
+			stall_timeout: None,
+			stall_rotate_peers: 2,
+			stall_refresh_discovery: false,
+			head_first: false,
+			tx_propagation: TransactionPropagation::default(),
+			tx_propagation_peers: HashSet::new(),
 		}
 	}
 }
@@ -135,6 +177,13 @@ pub trait SyncProvider: Send + Sync {
 
 	/// Returns propagation count for pending transactions.
 	fn transactions_stats(&self) -> BTreeMap<H256, TransactionStats>;
+
+	/// Returns competing branches detected on the network.
+	fn fork_status(&self) -> ForkStatus;
+
+	/// Returns the network head block along with its total difficulty, if head-first
+	/// sync has linked it with our chain ahead of the regular import.
+	fn head_block(&self) -> Option<(encoded::Block, U256)>;
 }
 
 /// Transaction stats
@@ -293,7 +342,7 @@ impl EthSync {
 			})
 		};
 
-		let chain_sync = ChainSync::new(params.config, &*params.chain, params.private_tx_handler.clone());
+		let chain_sync = ChainSync::new(params.config.clone(), &*params.chain, params.private_tx_handler.clone());
 		let service = NetworkService::new(params.network_config.clone().into_basic()?, connection_filter)?;
 
 		let sync = Arc::new(EthSync {
@@ -312,6 +361,11 @@ impl EthSync {
 
 		Ok(sync)
 	}
+
+	/// Register a handler notified about newly detected forks.
+	pub fn add_fork_alert_handler(&self, handler: Arc<ForkAlertHandler>) {
+		self.eth_handler.sync.write().add_fork_alert_handler(handler);
+	}
 }
 
 impl SyncProvider for EthSync {
@@ -357,6 +411,14 @@ impl SyncProvider for EthSync {
 			.map(|(hash, stats)| (*hash, stats.into()))
 			.collect()
 	}
+
+	fn fork_status(&self) -> ForkStatus {
+		self.eth_handler.sync.read().fork_status()
+	}
+
+	fn head_block(&self) -> Option<(encoded::Block, U256)> {
+		self.eth_handler.sync.read().head_block()
+	}
 }
 
 const PEERS_TIMER: TimerToken = 0;
@@ -479,7 +541,7 @@ impl ChainNotify for EthSync {
 		self.network.register_protocol(self.eth_handler.clone(), self.subprotocol_name, &[ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63])
 			.unwrap_or_else(|e| warn!("Error registering ethereum protocol: {:?}", e));
 		// register the warp sync subprotocol
-		self.network.register_protocol(self.eth_handler.clone(), WARP_SYNC_PROTOCOL_ID, &[PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3])
+		self.network.register_protocol(self.eth_handler.clone(), WARP_SYNC_PROTOCOL_ID, &[PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4])
 			.unwrap_or_else(|e| warn!("Error registering snapshot sync protocol: {:?}", e));
 
 		// register the light protocol.
@@ -504,6 +566,7 @@ impl ChainNotify for EthSync {
 				ChainMessageType::Consensus(message) => self.eth_handler.sync.write().propagate_consensus_packet(&mut sync_io, message),
 				ChainMessageType::PrivateTransaction(message) => self.eth_handler.sync.write().propagate_private_transaction(&mut sync_io, message),
 				ChainMessageType::SignedPrivateTransaction(message) => self.eth_handler.sync.write().propagate_signed_private_transaction(&mut sync_io, message),
+				ChainMessageType::PrivateStateRequest(message) => self.eth_handler.sync.write().request_private_state(&mut sync_io, message),
 			}
 		});
 	}
@@ -619,6 +682,8 @@ pub struct NetworkConfiguration {
 	pub max_pending_peers: u32,
 	/// Reserved snapshot sync peers.
 	pub snapshot_peers: u32,
+	/// Minimal number of regular peer slots kept for peers supporting given subprotocol.
+	pub min_protocol_peers: HashMap<ProtocolId, u32>,
 	/// List of reserved node addresses.
 	pub reserved_nodes: Vec<String>,
 	/// The non-reserved peer mode.
@@ -656,6 +721,7 @@ impl NetworkConfiguration {
 			min_peers: self.min_peers,
 			max_handshakes: self.max_pending_peers,
 			reserved_protocols: hash_map![WARP_SYNC_PROTOCOL_ID => self.snapshot_peers],
+			min_protocol_peers: self.min_protocol_peers,
 			reserved_nodes: self.reserved_nodes,
 			ip_filter: self.ip_filter,
 			non_reserved_mode: if self.allow_non_reserved { NonReservedPeerMode::Accept } else { NonReservedPeerMode::Deny },
@@ -680,6 +746,7 @@ impl From<BasicNetworkConfiguration> for NetworkConfiguration {
 			min_peers: other.min_peers,
 			max_pending_peers: other.max_handshakes,
 			snapshot_peers: *other.reserved_protocols.get(&WARP_SYNC_PROTOCOL_ID).unwrap_or(&0),
+			min_protocol_peers: other.min_protocol_peers,
 			reserved_nodes: other.reserved_nodes,
 			ip_filter: other.ip_filter,
 			allow_non_reserved: match other.non_reserved_mode { NonReservedPeerMode::Accept => true, _ => false } ,
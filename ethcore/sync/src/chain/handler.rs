@@ -45,11 +45,13 @@ use super::{
 	MAX_NEW_BLOCK_AGE,
 	MAX_NEW_HASHES,
 	PAR_PROTOCOL_VERSION_1,
-	PAR_PROTOCOL_VERSION_3,
+	PAR_PROTOCOL_VERSION_4,
 	BLOCK_BODIES_PACKET,
 	BLOCK_HEADERS_PACKET,
+	GET_PRIVATE_STATE_PACKET,
 	NEW_BLOCK_HASHES_PACKET,
 	NEW_BLOCK_PACKET,
+	PRIVATE_STATE_DATA_PACKET,
 	PRIVATE_TRANSACTION_PACKET,
 	RECEIPTS_PACKET,
 	SIGNED_PRIVATE_TRANSACTION_PACKET,
@@ -82,6 +84,8 @@ impl SyncHandler {
 			SNAPSHOT_DATA_PACKET => SyncHandler::on_snapshot_data(sync, io, peer, &rlp),
 			PRIVATE_TRANSACTION_PACKET => SyncHandler::on_private_transaction(sync, io, peer, &rlp),
 			SIGNED_PRIVATE_TRANSACTION_PACKET => SyncHandler::on_signed_private_transaction(sync, io, peer, &rlp),
+			GET_PRIVATE_STATE_PACKET => SyncHandler::on_private_state_request(sync, io, peer, &rlp),
+			PRIVATE_STATE_DATA_PACKET => SyncHandler::on_private_state_data(sync, io, peer, &rlp),
 			_ => {
 				debug!(target: "sync", "{}: Unknown packet {}", peer, packet_id);
 				Ok(())
@@ -153,6 +157,7 @@ impl SyncHandler {
 		let h = keccak(&header_rlp.as_raw());
 		trace!(target: "sync", "{} -> NewBlock ({})", peer_id, h);
 		let header: BlockHeader = header_rlp.as_val()?;
+		sync.fork_monitor.note_header(io.chain(), peer_id, &header);
 		if header.number() > sync.highest_block.unwrap_or(0) {
 			sync.highest_block = Some(header.number());
 		}
@@ -280,6 +285,15 @@ impl SyncHandler {
 
 	/// Called by peer once it has new block bodies
 	fn on_peer_block_bodies(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		let is_head_body_request = match sync.peers.get(&peer_id) {
+			Some(peer) if peer.asking == PeerAsking::HeadBody => true,
+			_ => false,
+		};
+
+		if is_head_body_request {
+			return SyncHandler::on_peer_head_data(sync, io, peer_id, r, PeerAsking::HeadBody);
+		}
+
 		sync.clear_peer_download(peer_id);
 		let block_set = sync.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
 		if !sync.reset_peer_asking(peer_id, PeerAsking::BlockBodies) {
@@ -365,6 +379,36 @@ impl SyncHandler {
 		return Ok(());
 	}
 
+	/// Called by peer once it has head-first sync headers or the head body
+	fn on_peer_head_data(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp, asking: PeerAsking) -> Result<(), PacketDecodeError> {
+		sync.reset_peer_asking(peer_id, asking.clone());
+		trace!(target: "sync", "{} -> {:?} ({} entries)", peer_id, asking, r.item_count()?);
+		let result = match sync.head_sync {
+			Some(ref mut head_sync) => match asking {
+				PeerAsking::HeadHeaders => head_sync.import_headers(io.chain(), r),
+				_ => head_sync.import_body(r),
+			},
+			None => Ok(()),
+		};
+
+		match result {
+			Err(DownloaderImportError::Invalid) => {
+				io.disable_peer(peer_id);
+				sync.deactivate_peer(io, peer_id);
+				sync.continue_sync(io);
+				return Ok(());
+			},
+			Err(DownloaderImportError::Useless) => {
+				sync.deactivate_peer(io, peer_id);
+			},
+			Ok(()) => (),
+		}
+
+		sync.sync_peer(io, peer_id, false);
+		sync.continue_sync(io);
+		Ok(())
+	}
+
 	/// Called by peer once it has new block headers during sync
 	fn on_peer_block_headers(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
 		let is_fork_header_request = match sync.peers.get(&peer_id) {
@@ -376,6 +420,15 @@ impl SyncHandler {
 			return SyncHandler::on_peer_fork_header(sync, io, peer_id, r);
 		}
 
+		let is_head_headers_request = match sync.peers.get(&peer_id) {
+			Some(peer) if peer.asking == PeerAsking::HeadHeaders => true,
+			_ => false,
+		};
+
+		if is_head_headers_request {
+			return SyncHandler::on_peer_head_data(sync, io, peer_id, r, PeerAsking::HeadHeaders);
+		}
+
 		sync.clear_peer_download(peer_id);
 		let expected_hash = sync.peers.get(&peer_id).and_then(|p| p.asking_hash);
 		let allowed = sync.peers.get(&peer_id).map(|p| p.is_allowed()).unwrap_or(false);
@@ -658,7 +711,7 @@ impl SyncHandler {
 		}
 
 		if false
-			|| (warp_protocol && (peer.protocol_version < PAR_PROTOCOL_VERSION_1.0 || peer.protocol_version > PAR_PROTOCOL_VERSION_3.0))
+			|| (warp_protocol && (peer.protocol_version < PAR_PROTOCOL_VERSION_1.0 || peer.protocol_version > PAR_PROTOCOL_VERSION_4.0))
 			|| (!warp_protocol && (peer.protocol_version < ETH_PROTOCOL_VERSION_62.0 || peer.protocol_version > ETH_PROTOCOL_VERSION_63.0))
 		{
 			io.disable_peer(peer_id);
@@ -742,6 +795,39 @@ impl SyncHandler {
 		}
 		Ok(())
 	}
+
+	/// Called when peer requests private state of the contract
+	fn on_private_state_request(sync: &ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
+			trace!(target: "sync", "{} Ignoring packet from unconfirmed/unknown peer", peer_id);
+			return Ok(());
+		}
+
+		trace!(target: "sync", "Received private state request from {:?}", peer_id);
+
+		match sync.private_tx_handler.private_state_request(r.as_raw()) {
+			Ok(Some(data)) => io.respond(PRIVATE_STATE_DATA_PACKET, data).unwrap_or_else(
+				|e| debug!(target: "sync", "Error sending private state: {:?}", e)),
+			Ok(None) => trace!(target: "sync", "No private state to return to {:?}", peer_id),
+			Err(e) => trace!(target: "sync", "Ignoring private state request: {}", e),
+		}
+		Ok(())
+	}
+
+	/// Called when peer sends us private state of the contract
+	fn on_private_state_data(sync: &ChainSync, _io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
+			trace!(target: "sync", "{} Ignoring packet from unconfirmed/unknown peer", peer_id);
+			return Ok(());
+		}
+
+		trace!(target: "sync", "Received private state from {:?}", peer_id);
+
+		if let Err(e) = sync.private_tx_handler.import_private_state(r.as_raw()) {
+			trace!(target: "sync", "Ignoring private state: {}", e);
+		}
+		Ok(())
+	}
 }
 
 #[cfg(test)]
@@ -103,17 +103,20 @@ use plain_hasher::H256FastMap;
 use parking_lot::RwLock;
 use bytes::Bytes;
 use rlp::{Rlp, RlpStream, DecoderError};
-use network::{self, PeerId, PacketId};
+use network::{self, PeerId, PacketId, NodeId};
 use ethcore::header::{BlockNumber};
 use ethcore::client::{BlockChainClient, BlockStatus, BlockId, BlockChainInfo, BlockQueueInfo};
+use ethcore::encoded;
 use ethcore::snapshot::{RestorationStatus};
 use sync_io::SyncIo;
-use super::{WarpSync, SyncConfig};
+use super::{WarpSync, SyncConfig, TransactionPropagation};
 use block_sync::{BlockDownloader, BlockDownloaderImportError as DownloaderImportError};
 use rand::Rng;
 use snapshot::{Snapshot};
 use api::{EthProtocolInfo as PeerInfoDigest, WARP_SYNC_PROTOCOL_ID};
 use private_tx::PrivateTxHandler;
+use fork_monitor::{ForkMonitor, ForkAlertHandler, ForkStatus};
+use head_sync::{HeadSync, HeadRequest};
 use transactions_stats::{TransactionsStats, Stats as TransactionStats};
 use transaction::UnverifiedTransaction;
 
@@ -136,6 +139,8 @@ pub const PAR_PROTOCOL_VERSION_1: (u8, u8) = (1, 0x15);
 pub const PAR_PROTOCOL_VERSION_2: (u8, u8) = (2, 0x16);
 /// 3 version of Parity protocol (private transactions messages added).
 pub const PAR_PROTOCOL_VERSION_3: (u8, u8) = (3, 0x18);
+/// 4 version of Parity protocol (private state messages added).
+pub const PAR_PROTOCOL_VERSION_4: (u8, u8) = (4, 0x1a);
 
 pub const MAX_BODIES_TO_SEND: usize = 256;
 pub const MAX_HEADERS_TO_SEND: usize = 512;
@@ -180,6 +185,8 @@ pub const SNAPSHOT_DATA_PACKET: u8 = 0x14;
 pub const CONSENSUS_DATA_PACKET: u8 = 0x15;
 const PRIVATE_TRANSACTION_PACKET: u8 = 0x16;
 const SIGNED_PRIVATE_TRANSACTION_PACKET: u8 = 0x17;
+const GET_PRIVATE_STATE_PACKET: u8 = 0x18;
+const PRIVATE_STATE_DATA_PACKET: u8 = 0x19;
 
 const MAX_SNAPSHOT_CHUNKS_DOWNLOAD_AHEAD: usize = 3;
 
@@ -284,6 +291,8 @@ pub enum PeerAsking {
 	BlockReceipts,
 	SnapshotManifest,
 	SnapshotData,
+	HeadHeaders,
+	HeadBody,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -412,6 +421,22 @@ pub struct ChainSync {
 	private_tx_handler: Arc<PrivateTxHandler>,
 	/// Enable warp sync.
 	warp_sync: WarpSync,
+	/// Competing branches monitor.
+	fork_monitor: ForkMonitor,
+	/// Best block number and the time it was first seen.
+	last_progress: (BlockNumber, Instant),
+	/// Time without progress after which the sync is considered stalled.
+	stall_timeout: Option<Duration>,
+	/// Number of lagging peers to disconnect on stall.
+	stall_rotate_peers: usize,
+	/// Restart discovery on stall.
+	stall_refresh_discovery: bool,
+	/// Reverse header download towards the network head, if enabled.
+	head_sync: Option<HeadSync>,
+	/// Pending transactions propagation policy.
+	tx_propagation: TransactionPropagation,
+	/// Peers receiving local transactions in private propagation mode.
+	tx_propagation_peers: HashSet<NodeId>,
 }
 
 impl ChainSync {
@@ -439,6 +464,14 @@ impl ChainSync {
 			transactions_stats: TransactionsStats::default(),
 			private_tx_handler,
 			warp_sync: config.warp_sync,
+			fork_monitor: ForkMonitor::new(config.fork_alert_depth),
+			last_progress: (best_block, Instant::now()),
+			stall_timeout: config.stall_timeout,
+			stall_rotate_peers: config.stall_rotate_peers,
+			stall_refresh_discovery: config.stall_refresh_discovery,
+			head_sync: if config.head_first { Some(HeadSync::new()) } else { None },
+			tx_propagation: config.tx_propagation,
+			tx_propagation_peers: config.tx_propagation_peers,
 		};
 		sync.update_targets(chain);
 		sync
@@ -493,6 +526,22 @@ impl ChainSync {
 		self.transactions_stats.stats()
 	}
 
+	/// Returns competing branches detected on the network
+	pub fn fork_status(&self) -> ForkStatus {
+		self.fork_monitor.status()
+	}
+
+	/// Returns the head block linked by head-first sync, with its total difficulty
+	pub fn head_block(&self) -> Option<(encoded::Block, U256)> {
+		let head_sync = self.head_sync.as_ref()?;
+		Some((head_sync.head_block()?, head_sync.target_difficulty()?))
+	}
+
+	/// Register a handler notified about newly detected forks
+	pub fn add_fork_alert_handler(&mut self, handler: Arc<ForkAlertHandler>) {
+		self.fork_monitor.add_handler(handler);
+	}
+
 	/// Updates transactions were received by a peer
 	pub fn transactions_received(&mut self, txs: &[UnverifiedTransaction], peer_id: PeerId) {
 		if let Some(peer_info) = self.peers.get_mut(&peer_id) {
@@ -747,6 +796,17 @@ impl ChainSync {
 					}
 
 					let have_latest = io.chain().block_status(BlockId::Hash(peer_latest)) != BlockStatus::Unknown;
+					if !have_latest && higher_difficulty {
+						if let Some(request) = self.head_sync.as_mut().and_then(|h| {
+							if let Some(pd) = peer_difficulty {
+								h.set_target(peer_latest, pd);
+							}
+							h.request()
+						}) {
+							SyncRequester::request_head(self, io, peer_id, request);
+							return;
+						}
+					}
 					trace!(target: "sync", "Considering peer {}, force={}, td={:?}, our td={}, latest={}, have_latest={}, state={:?}", peer_id, force, peer_difficulty, syncing_difficulty, peer_latest, have_latest, self.state);
 					if !have_latest && (higher_difficulty || force || self.state == SyncState::NewBlocks) {
 						// check if got new blocks to download
@@ -834,6 +894,11 @@ impl ChainSync {
 						self.snapshot.clear_chunk_download(&hash);
 					}
 				},
+				PeerAsking::HeadHeaders | PeerAsking::HeadBody => {
+					if let Some(ref mut head_sync) = self.head_sync {
+						head_sync.clear_download();
+					}
+				},
 				_ => (),
 			}
 		}
@@ -911,6 +976,8 @@ impl ChainSync {
 				PeerAsking::ForkHeader => elapsed > FORK_HEADER_TIMEOUT,
 				PeerAsking::SnapshotManifest => elapsed > SNAPSHOT_MANIFEST_TIMEOUT,
 				PeerAsking::SnapshotData => elapsed > SNAPSHOT_DATA_TIMEOUT,
+				PeerAsking::HeadHeaders => elapsed > HEADERS_TIMEOUT,
+				PeerAsking::HeadBody => elapsed > BODIES_TIMEOUT,
 			};
 			if timeout {
 				debug!(target:"sync", "Timeout {}", peer_id);
@@ -1054,14 +1121,66 @@ impl ChainSync {
 		self.peers.iter().filter_map(|(id, p)| if p.protocol_version >= PAR_PROTOCOL_VERSION_3.0 { Some(*id) } else { None }).collect()
 	}
 
+	fn get_private_state_peers(&self) -> Vec<PeerId> {
+		self.peers.iter().filter_map(|(id, p)| if p.protocol_version >= PAR_PROTOCOL_VERSION_4.0 { Some(*id) } else { None }).collect()
+	}
+
 	/// Maintain other peers. Send out any new blocks and transactions
 	pub fn maintain_sync(&mut self, io: &mut SyncIo) {
 		self.maybe_start_snapshot_sync(io);
 		self.check_resume(io);
+		self.check_stall(io);
+	}
+
+	/// Detect sync making no progress while better peers are available.
+	/// Disconnects the most lagging peers and restarts the sync.
+	fn check_stall(&mut self, io: &mut SyncIo) {
+		let stall_timeout = match self.stall_timeout {
+			Some(timeout) => timeout,
+			None => return,
+		};
+
+		let chain_info = io.chain().chain_info();
+		if chain_info.best_block_number != self.last_progress.0 {
+			self.last_progress = (chain_info.best_block_number, Instant::now());
+			return;
+		}
+
+		if self.peers.is_empty() || self.last_progress.1.elapsed() < stall_timeout || self.status().is_snapshot_syncing() {
+			return;
+		}
+
+		let behind = self.highest_block.map_or(false, |n| n > chain_info.best_block_number) ||
+			self.peers.values().any(|p| p.difficulty.map_or(false, |d| d > chain_info.pending_total_difficulty));
+		if !behind {
+			self.last_progress.1 = Instant::now();
+			return;
+		}
+
+		warn!(target: "sync", "Sync stalled at #{} for {}s (state: {:?}, highest: {:?}, peers: {}, active: {}, queue: {})",
+			chain_info.best_block_number, self.last_progress.1.elapsed().as_secs(), self.state, self.highest_block,
+			self.peers.len(), self.active_peers.len(), io.chain().queue_info().total_queue_size());
+
+		let mut lagging: Vec<(PeerId, U256)> = self.peers.iter()
+			.map(|(id, p)| (*id, p.difficulty.unwrap_or_else(U256::zero)))
+			.collect();
+		lagging.sort_by(|a, b| a.1.cmp(&b.1));
+		for (peer_id, difficulty) in lagging.into_iter().take(self.stall_rotate_peers) {
+			debug!(target: "sync", "Rotating out peer {} (td: {}, client: {})", peer_id, difficulty, io.peer_info(peer_id));
+			io.disconnect_peer(peer_id);
+			SyncHandler::on_peer_aborting(self, io, peer_id);
+		}
+
+		if self.stall_refresh_discovery {
+			io.refresh_discovery();
+		}
+
+		self.last_progress.1 = Instant::now();
+		self.restart(io);
 	}
 
 	/// called when block is imported to chain - propagates the blocks and updates transactions sent to peers
-	pub fn chain_new_blocks(&mut self, io: &mut SyncIo, _imported: &[H256], invalid: &[H256], enacted: &[H256], _retracted: &[H256], sealed: &[H256], proposed: &[Bytes]) {
+	pub fn chain_new_blocks(&mut self, io: &mut SyncIo, imported: &[H256], invalid: &[H256], enacted: &[H256], _retracted: &[H256], sealed: &[H256], proposed: &[Bytes]) {
 		let queue_info = io.chain().queue_info();
 		let is_syncing = self.status().is_syncing(queue_info);
 
@@ -1075,6 +1194,10 @@ impl ChainSync {
 			self.restart(io);
 		}
 
+		if !imported.is_empty() {
+			self.fork_monitor.note_imported(io.chain());
+		}
+
 		if !is_syncing && !enacted.is_empty() && !self.peers.is_empty() {
 			// Select random peer to re-broadcast transactions to.
 			let peer = random::new().gen_range(0, self.peers.len());
@@ -1129,6 +1252,11 @@ impl ChainSync {
 	pub fn propagate_signed_private_transaction(&mut self, io: &mut SyncIo, packet: Bytes) {
 		SyncPropagator::propagate_signed_private_transaction(self, io, packet);
 	}
+
+	/// Request private state from peers.
+	pub fn request_private_state(&mut self, io: &mut SyncIo, packet: Bytes) {
+		SyncPropagator::request_private_state(self, io, packet);
+	}
 }
 
 #[cfg(test)]
@@ -1328,6 +1456,36 @@ pub mod tests {
 		assert!(result.is_ok());
 	}
 
+	#[test]
+	fn rotates_peers_when_stalled() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(10, EachBlockWith::Nothing);
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut config = SyncConfig::default();
+		config.stall_timeout = Some(Duration::from_secs(60));
+		config.stall_rotate_peers = 1;
+		config.stall_refresh_discovery = true;
+		let mut sync = ChainSync::new(config, &client, Arc::new(NoopPrivateTxHandler));
+		insert_dummy_peer(&mut sync, 0, client.block_hash_delta_minus(1));
+		insert_dummy_peer(&mut sync, 1, client.block_hash_delta_minus(1));
+		sync.peers.get_mut(&1).unwrap().difficulty = Some(U256::max_value());
+
+		// progress recently made.
+		{
+			let mut io = TestIo::new(&mut client, &ss, &queue, None);
+			sync.check_stall(&mut io);
+			assert!(io.to_disconnect.is_empty());
+		}
+
+		sync.last_progress.1 = Instant::now() - Duration::from_secs(120);
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+		sync.check_stall(&mut io);
+		assert_eq!(io.to_disconnect.len(), 1);
+		assert!(io.to_disconnect.contains(&0));
+		assert!(io.discovery_refreshed);
+	}
+
 	#[test]
 	fn should_add_transactions_to_queue() {
 		fn sender(tx: &UnverifiedTransaction) -> Address {
@@ -25,6 +25,7 @@ use sync_io::SyncIo;
 use std::cmp;
 use std::collections::HashSet;
 use transaction::SignedTransaction;
+use api::TransactionPropagation;
 
 use super::{
 	random,
@@ -38,6 +39,7 @@ use super::{
 	CONSENSUS_DATA_PACKET,
 	NEW_BLOCK_HASHES_PACKET,
 	NEW_BLOCK_PACKET,
+	GET_PRIVATE_STATE_PACKET,
 	PRIVATE_TRANSACTION_PACKET,
 	SIGNED_PRIVATE_TRANSACTION_PACKET,
 	TRANSACTIONS_PACKET,
@@ -108,10 +110,10 @@ impl SyncPropagator {
 		sent
 	}
 
-	/// propagates new transactions to all peers
+	/// propagates new transactions to peers selected by the propagation policy
 	pub fn propagate_new_transactions(sync: &mut ChainSync, io: &mut SyncIo) -> usize {
 		// Early out if nobody to send to.
-		if sync.peers.is_empty() {
+		if sync.peers.is_empty() || sync.tx_propagation == TransactionPropagation::None {
 			return 0;
 		}
 
@@ -120,21 +122,23 @@ impl SyncPropagator {
 			return 0;
 		}
 
+		let only_local = sync.tx_propagation == TransactionPropagation::Private;
 		let (transactions, service_transactions): (Vec<_>, Vec<_>) = transactions.iter()
+			.filter(|tx| !only_local || tx.is_local())
 			.map(|tx| tx.signed())
 			.partition(|tx| !tx.gas_price.is_zero());
 
 		// usual transactions could be propagated to all peers
 		let mut affected_peers = HashSet::new();
 		if !transactions.is_empty() {
-			let peers = SyncPropagator::select_peers_for_transactions(sync, |_| true);
+			let peers = SyncPropagator::select_peers_for_transactions(sync, &*io, |_| true);
 			affected_peers = SyncPropagator::propagate_transactions_to_peers(sync, io, peers, transactions);
 		}
 
 		// most of times service_transactions will be empty
 		// => there's no need to merge packets
 		if !service_transactions.is_empty() {
-			let service_transactions_peers = SyncPropagator::select_peers_for_transactions(sync, |peer_id| accepts_service_transaction(&io.peer_info(*peer_id)));
+			let service_transactions_peers = SyncPropagator::select_peers_for_transactions(sync, &*io, |peer_id| accepts_service_transaction(&io.peer_info(*peer_id)));
 			let service_transactions_affected_peers = SyncPropagator::propagate_transactions_to_peers(sync, io, service_transactions_peers, service_transactions);
 			affected_peers.extend(&service_transactions_affected_peers);
 		}
@@ -306,19 +310,38 @@ impl SyncPropagator {
 		}
 	}
 
-	fn select_peers_for_transactions<F>(sync: &ChainSync, filter: F) -> Vec<PeerId>
+	/// Send private state request to peers.
+	pub fn request_private_state(sync: &mut ChainSync, io: &mut SyncIo, packet: Bytes) {
+		let lucky_peers = ChainSync::select_random_peers(&sync.get_private_state_peers());
+		trace!(target: "sync", "Sending private state request to {:?}", lucky_peers);
+		for peer_id in lucky_peers {
+			SyncPropagator::send_packet(io, peer_id, GET_PRIVATE_STATE_PACKET, packet.clone());
+		}
+	}
+
+	fn select_peers_for_transactions<F>(sync: &ChainSync, io: &SyncIo, filter: F) -> Vec<PeerId>
 		where F: Fn(&PeerId) -> bool {
-		// sqrt(x)/x scaled to max u32
-		let fraction = ((sync.peers.len() as f64).powf(-0.5) * (u32::max_value() as f64).round()) as u32;
-		let small = sync.peers.len() < MIN_PEERS_PROPAGATION;
-
-		let mut random = random::new();
-		sync.peers.keys()
-			.cloned()
-			.filter(filter)
-			.filter(|_| small || random.next_u32() < fraction)
-			.take(MAX_PEERS_PROPAGATION)
-			.collect()
+		let peers = sync.peers.keys().cloned().filter(filter);
+		match sync.tx_propagation {
+			TransactionPropagation::None => Vec::new(),
+			TransactionPropagation::All => peers.collect(),
+			TransactionPropagation::Private => peers
+				.filter(|peer_id| io.peer_session_info(*peer_id)
+					.and_then(|info| info.id)
+					.map_or(false, |id| sync.tx_propagation_peers.contains(&id)))
+				.collect(),
+			TransactionPropagation::Sqrt => {
+				// sqrt(x)/x scaled to max u32
+				let fraction = ((sync.peers.len() as f64).powf(-0.5) * (u32::max_value() as f64).round()) as u32;
+				let small = sync.peers.len() < MIN_PEERS_PROPAGATION;
+
+				let mut random = random::new();
+				peers
+					.filter(|_| small || random.next_u32() < fraction)
+					.take(MAX_PEERS_PROPAGATION)
+					.collect()
+			},
+		}
 	}
 
 	/// Generic packet sender
@@ -594,6 +617,51 @@ mod tests {
 		assert_eq!(io.packets.len(), 2);
 	}
 
+	#[test]
+	fn should_not_propagate_transactions_in_listen_only_mode() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		client.insert_transaction_to_queue();
+		client.insert_local_transaction_to_queue();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		sync.tx_propagation = TransactionPropagation::None;
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+
+		assert_eq!(SyncPropagator::propagate_new_transactions(&mut sync, &mut io), 0);
+		assert!(io.packets.is_empty());
+	}
+
+	#[test]
+	fn should_propagate_only_local_transactions_to_private_group() {
+		let mut client = TestBlockChainClient::new();
+		client.insert_transaction_to_queue();
+		let local_hash = client.insert_local_transaction_to_queue();
+		let block_hash = client.block_hash_delta_minus(1);
+		let mut sync = ChainSync::new(SyncConfig::default(), &client, Arc::new(NoopPrivateTxHandler));
+		sync.tx_propagation = TransactionPropagation::Private;
+		sync.tx_propagation_peers.insert(1.into());
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+
+		// peer#1 is in the private group, peer#2 is not
+		insert_dummy_peer(&mut sync, 1, block_hash);
+		io.node_ids.insert(1, 1.into());
+		insert_dummy_peer(&mut sync, 2, block_hash);
+		io.node_ids.insert(2, 2.into());
+
+		SyncPropagator::propagate_new_transactions(&mut sync, &mut io);
+
+		assert_eq!(io.packets.len(), 1);
+		assert_eq!(io.packets[0].recipient, 1);
+		let rlp = Rlp::new(&*io.packets[0].data);
+		assert_eq!(rlp.item_count().unwrap(), 1);
+		let tx: UnverifiedTransaction = rlp.val_at(0).unwrap();
+		assert_eq!(tx.hash(), local_hash);
+	}
+
 	#[test]
 	fn should_propagate_service_transaction_is_sent_as_separate_message() {
 		let mut client = TestBlockChainClient::new();
@@ -16,6 +16,7 @@
 
 use api::WARP_SYNC_PROTOCOL_ID;
 use block_sync::BlockRequest;
+use head_sync::HeadRequest;
 use bytes::Bytes;
 use ethcore::header::BlockNumber;
 use ethereum_types::H256;
@@ -55,6 +56,27 @@ impl SyncRequester {
 		}
 	}
 
+	/// Perform head-first sync request
+	pub fn request_head(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, request: HeadRequest) {
+		match request {
+			HeadRequest::Headers { start, count } => {
+				trace!(target: "sync", "{} <- GetBlockHeaders (head): {} entries back from {}", peer_id, count, start);
+				let mut rlp = RlpStream::new_list(4);
+				rlp.append(&start);
+				rlp.append(&count);
+				rlp.append(&0u32);
+				rlp.append(&1u32);
+				SyncRequester::send_request(sync, io, peer_id, PeerAsking::HeadHeaders, GET_BLOCK_HEADERS_PACKET, rlp.out());
+			},
+			HeadRequest::Body { hash } => {
+				trace!(target: "sync", "{} <- GetBlockBodies (head): {}", peer_id, hash);
+				let mut rlp = RlpStream::new_list(1);
+				rlp.append(&hash);
+				SyncRequester::send_request(sync, io, peer_id, PeerAsking::HeadBody, GET_BLOCK_BODIES_PACKET, rlp.out());
+			},
+		}
+	}
+
 	/// Request block bodies from a peer
 	fn request_bodies(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, hashes: Vec<H256>, set: BlockSet) {
 		let mut rlp = RlpStream::new_list(hashes.len());
@@ -0,0 +1,310 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fork monitoring.
+//! Watches headers announced by peers and raises an alert whenever a peer
+//! follows a branch which diverged from our canonical chain deeper than
+//! the configured threshold.
+
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ethereum_types::H256;
+use ethcore::client::{BlockChainClient, BlockId};
+use ethcore::header::{BlockNumber, Header};
+use network::PeerId;
+
+/// Maximal number of alerts kept in memory.
+const MAX_FORK_ALERTS: usize = 64;
+/// Maximal number of announced headers with an unknown parent kept for a later check.
+const MAX_UNRESOLVED_HEADERS: usize = 256;
+
+/// A competing branch detected on the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkAlert {
+	/// Peer which announced the competing branch.
+	pub peer_id: PeerId,
+	/// Number of the best common ancestor.
+	pub ancestor_number: BlockNumber,
+	/// Hash of the best common ancestor.
+	pub ancestor_hash: H256,
+	/// Our best block number at the time of detection.
+	pub our_number: BlockNumber,
+	/// Our best block hash at the time of detection.
+	pub our_hash: H256,
+	/// Latest known block number of the competing branch.
+	pub their_number: BlockNumber,
+	/// Latest known block hash of the competing branch.
+	pub their_hash: H256,
+	/// Length of the competing branch since the common ancestor.
+	pub depth: BlockNumber,
+	/// UNIX timestamp (seconds) when the branch was first detected.
+	pub detected_at: u64,
+}
+
+/// Fork monitoring status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkStatus {
+	/// Minimal branch length which triggers an alert, `None` if monitoring is disabled.
+	pub alert_depth: Option<BlockNumber>,
+	/// Recently detected forks, oldest first.
+	pub alerts: Vec<ForkAlert>,
+}
+
+/// Receives notifications about newly detected forks.
+pub trait ForkAlertHandler: Send + Sync {
+	/// Called once for every newly detected competing branch.
+	fn on_fork_alert(&self, alert: &ForkAlert);
+}
+
+/// Keeps track of competing branches announced by peers.
+pub struct ForkMonitor {
+	/// Minimal branch length which triggers an alert. `None` disables monitoring.
+	alert_depth: Option<BlockNumber>,
+	/// Recent alerts, oldest first.
+	alerts: VecDeque<ForkAlert>,
+	/// Handlers notified about new alerts.
+	handlers: Vec<Arc<ForkAlertHandler>>,
+	/// Announced headers whose parent wasn't imported yet, oldest first.
+	unresolved: VecDeque<(PeerId, Header)>,
+}
+
+impl ForkMonitor {
+	/// Create a new fork monitor.
+	pub fn new(alert_depth: Option<BlockNumber>) -> Self {
+		ForkMonitor {
+			alert_depth,
+			alerts: VecDeque::new(),
+			handlers: Vec::new(),
+			unresolved: VecDeque::new(),
+		}
+	}
+
+	/// Register a handler for new alerts.
+	pub fn add_handler(&mut self, handler: Arc<ForkAlertHandler>) {
+		self.handlers.push(handler);
+	}
+
+	/// Current monitoring status.
+	pub fn status(&self) -> ForkStatus {
+		ForkStatus {
+			alert_depth: self.alert_depth,
+			alerts: self.alerts.iter().cloned().collect(),
+		}
+	}
+
+	/// Check a header announced by a peer against our canonical chain.
+	/// Headers with an unknown parent are kept and checked again once new blocks are imported.
+	pub fn note_header(&mut self, chain: &BlockChainClient, peer_id: PeerId, header: &Header) {
+		if self.alert_depth.is_none() {
+			return;
+		}
+
+		if !self.check_header(chain, peer_id, header) {
+			self.unresolved.retain(|&(_, ref h)| h.hash() != header.hash());
+			if self.unresolved.len() == MAX_UNRESOLVED_HEADERS {
+				self.unresolved.pop_front();
+			}
+			self.unresolved.push_back((peer_id, header.clone()));
+		}
+	}
+
+	/// Check again all announced headers whose parent was unknown, called after blocks are imported.
+	pub fn note_imported(&mut self, chain: &BlockChainClient) {
+		if self.alert_depth.is_none() || self.unresolved.is_empty() {
+			return;
+		}
+
+		let unresolved = mem::replace(&mut self.unresolved, VecDeque::new());
+		for (peer_id, header) in unresolved {
+			if !self.check_header(chain, peer_id, &header) {
+				self.unresolved.push_back((peer_id, header));
+			}
+		}
+	}
+
+	/// Number of announced headers waiting for their parent.
+	pub fn unresolved_count(&self) -> usize {
+		self.unresolved.len()
+	}
+
+	/// Returns `false` if the parent of the header is not known yet.
+	fn check_header(&mut self, chain: &BlockChainClient, peer_id: PeerId, header: &Header) -> bool {
+		if chain.block_number(BlockId::Hash(*header.parent_hash())).is_none() {
+			return false;
+		}
+
+		let chain_info = chain.chain_info();
+		let route = match chain.tree_route(&chain_info.best_block_hash, header.parent_hash()) {
+			Some(route) => route,
+			None => return false,
+		};
+		let ancestor_number = match chain.block_number(BlockId::Hash(route.ancestor)) {
+			Some(number) => number,
+			None => return true,
+		};
+
+		self.note_branch(
+			peer_id,
+			(chain_info.best_block_number, chain_info.best_block_hash),
+			(header.number(), header.hash()),
+			(ancestor_number, route.ancestor),
+		);
+		true
+	}
+
+	/// Record a branch given its head and the common ancestor with our best block.
+	/// Returns `true` if a new alert was raised.
+	fn note_branch(
+		&mut self,
+		peer_id: PeerId,
+		ours: (BlockNumber, H256),
+		theirs: (BlockNumber, H256),
+		ancestor: (BlockNumber, H256),
+	) -> bool {
+		let alert_depth = match self.alert_depth {
+			Some(depth) => depth,
+			None => return false,
+		};
+
+		// the branch simply extends our chain.
+		if ancestor.1 == ours.1 || theirs.0 <= ancestor.0 {
+			return false;
+		}
+
+		let depth = theirs.0 - ancestor.0;
+		if depth < alert_depth {
+			return false;
+		}
+
+		// already known branch, update its head.
+		if let Some(alert) = self.alerts.iter_mut().find(|a| a.ancestor_hash == ancestor.1) {
+			if theirs.0 > alert.their_number {
+				alert.their_number = theirs.0;
+				alert.their_hash = theirs.1;
+				alert.depth = depth;
+			}
+			return false;
+		}
+
+		let alert = ForkAlert {
+			peer_id,
+			ancestor_number: ancestor.0,
+			ancestor_hash: ancestor.1,
+			our_number: ours.0,
+			our_hash: ours.1,
+			their_number: theirs.0,
+			their_hash: theirs.1,
+			depth,
+			detected_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+		};
+
+		warn!(target: "fork", "Fork detected: peer={} ancestor=#{} ({}) ours=#{} ({}) theirs=#{} ({}) depth={}",
+			alert.peer_id, alert.ancestor_number, alert.ancestor_hash, alert.our_number, alert.our_hash,
+			alert.their_number, alert.their_hash, alert.depth);
+
+		for handler in &self.handlers {
+			handler.on_fork_alert(&alert);
+		}
+
+		if self.alerts.len() == MAX_FORK_ALERTS {
+			self.alerts.pop_front();
+		}
+		self.alerts.push_back(alert);
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use parking_lot::Mutex;
+	use ethereum_types::H256;
+	use ethcore::client::{TestBlockChainClient, EachBlockWith, BlockChainClient, BlockId};
+	use super::{ForkMonitor, ForkAlert, ForkAlertHandler};
+
+	#[derive(Default)]
+	struct CollectingHandler(Mutex<Vec<ForkAlert>>);
+
+	impl ForkAlertHandler for CollectingHandler {
+		fn on_fork_alert(&self, alert: &ForkAlert) {
+			self.0.lock().push(alert.clone());
+		}
+	}
+
+	#[test]
+	fn ignores_extensions_of_canonical_chain() {
+		let mut monitor = ForkMonitor::new(Some(1));
+		let best = (10, H256::from(10));
+		assert!(!monitor.note_branch(0, best, (12, H256::from(12)), best));
+		assert!(monitor.status().alerts.is_empty());
+	}
+
+	#[test]
+	fn ignores_shallow_branches() {
+		let mut monitor = ForkMonitor::new(Some(3));
+		assert!(!monitor.note_branch(0, (10, H256::from(10)), (10, H256::from(110)), (8, H256::from(8))));
+		assert!(monitor.status().alerts.is_empty());
+	}
+
+	#[test]
+	fn disabled_monitor_never_alerts() {
+		let mut monitor = ForkMonitor::new(None);
+		assert!(!monitor.note_branch(0, (10, H256::from(10)), (20, H256::from(120)), (1, H256::from(1))));
+	}
+
+	#[test]
+	fn alerts_once_per_branch_and_notifies_handlers() {
+		let handler = Arc::new(CollectingHandler::default());
+		let mut monitor = ForkMonitor::new(Some(2));
+		monitor.add_handler(handler.clone());
+
+		let ours = (10, H256::from(10));
+		let ancestor = (7, H256::from(7));
+		assert!(monitor.note_branch(1, ours, (10, H256::from(110)), ancestor));
+		assert!(!monitor.note_branch(2, ours, (11, H256::from(111)), ancestor));
+
+		let alerts = monitor.status().alerts;
+		assert_eq!(alerts.len(), 1);
+		assert_eq!(alerts[0].peer_id, 1);
+		assert_eq!(alerts[0].their_number, 11);
+		assert_eq!(alerts[0].depth, 4);
+		assert_eq!(handler.0.lock().len(), 1);
+	}
+
+	#[test]
+	fn rechecks_headers_once_parent_is_imported() {
+		let other = TestBlockChainClient::new();
+		other.add_blocks(10, EachBlockWith::Nothing);
+		let header = other.block_header(BlockId::Number(8)).unwrap().decode().unwrap();
+
+		let client = TestBlockChainClient::new();
+		client.add_blocks(5, EachBlockWith::Nothing);
+		let mut monitor = ForkMonitor::new(Some(1));
+
+		monitor.note_header(&client, 0, &header);
+		monitor.note_header(&client, 1, &header);
+		assert_eq!(monitor.unresolved_count(), 1);
+
+		monitor.note_imported(&client);
+		assert_eq!(monitor.unresolved_count(), 1);
+
+		client.add_blocks(5, EachBlockWith::Nothing);
+		monitor.note_imported(&client);
+		assert_eq!(monitor.unresolved_count(), 0);
+	}
+}
@@ -0,0 +1,349 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Head-first sync.
+//! Fetches the head block of the best peer first and then walks the header
+//! chain backwards until it links up with our best block. Once linked, the head
+//! is exposed to RPC while the regular downloader streams bodies forward.
+
+use ethereum_types::{H256, U256};
+use hash::keccak;
+use triehash::ordered_trie_root;
+use bytes::Bytes;
+use rlp::{Rlp, RlpStream};
+use ethcore::client::{BlockChainClient, BlockId, BlockStatus};
+use ethcore::encoded;
+use ethcore::header::{BlockNumber, Header as BlockHeader};
+use block_sync::BlockDownloaderImportError;
+
+const MAX_HEADERS_TO_REQUEST: u64 = 192;
+
+/// Data that needs to be requested from a peer.
+#[derive(Debug, PartialEq)]
+pub enum HeadRequest {
+	/// Headers walking backwards from the given hash.
+	Headers {
+		start: H256,
+		count: u64,
+	},
+	/// Body of the head block.
+	Body {
+		hash: H256,
+	},
+}
+
+/// Downloads the header chain backwards from the best known network head.
+pub struct HeadSync {
+	/// Head hash and total difficulty we are linking to.
+	target: Option<(H256, U256)>,
+	/// Head block header.
+	header: Option<Bytes>,
+	/// Head block body.
+	body: Option<Bytes>,
+	/// Hash of the next header expected while walking backwards. `None` once linked.
+	next: Option<H256>,
+	/// Lowest downloaded header, checked against its parent when the next one arrives.
+	lowest: Option<BlockHeader>,
+	/// Number of headers verified so far.
+	verified: u64,
+	/// Headers request in flight.
+	downloading_headers: bool,
+	/// Body request in flight.
+	downloading_body: bool,
+}
+
+impl HeadSync {
+	/// Create a new idle instance.
+	pub fn new() -> Self {
+		HeadSync {
+			target: None,
+			header: None,
+			body: None,
+			next: None,
+			lowest: None,
+			verified: 0,
+			downloading_headers: false,
+			downloading_body: false,
+		}
+	}
+
+	/// Start syncing towards a new head if it is better than the current one.
+	pub fn set_target(&mut self, hash: H256, total_difficulty: U256) {
+		if self.target.map_or(false, |(h, td)| h == hash || td >= total_difficulty) {
+			return;
+		}
+		trace!(target: "sync", "Head sync target {} (td: {})", hash, total_difficulty);
+		*self = HeadSync::new();
+		self.target = Some((hash, total_difficulty));
+		self.next = Some(hash);
+	}
+
+	/// Total difficulty of the target head.
+	pub fn target_difficulty(&self) -> Option<U256> {
+		self.target.map(|(_, td)| td)
+	}
+
+	/// Check if the header chain is linked with our chain.
+	pub fn is_linked(&self) -> bool {
+		self.header.is_some() && self.next.is_none()
+	}
+
+	/// Number of the head block, if known.
+	pub fn head_number(&self) -> Option<BlockNumber> {
+		self.header.as_ref().map(|h| encoded::Header::new(h.clone()).number())
+	}
+
+	/// Number of headers verified so far.
+	pub fn verified_headers(&self) -> u64 {
+		self.verified
+	}
+
+	/// Returns the head block once it is linked with our chain and its body is downloaded.
+	pub fn head_block(&self) -> Option<encoded::Block> {
+		if !self.is_linked() {
+			return None;
+		}
+		match (self.header.as_ref(), self.body.as_ref()) {
+			(Some(header), Some(body)) => {
+				let body = Rlp::new(body);
+				let mut block = RlpStream::new_list(3);
+				block.append_raw(header, 1);
+				block.append_raw(body.at(0).ok()?.as_raw(), 1);
+				block.append_raw(body.at(1).ok()?.as_raw(), 1);
+				Some(encoded::Block::new(block.out()))
+			},
+			_ => None,
+		}
+	}
+
+	/// Find data to download. Returned request is marked as being downloaded.
+	pub fn request(&mut self) -> Option<HeadRequest> {
+		if let Some(hash) = self.header.as_ref().map(|h| keccak(h)) {
+			if self.body.is_none() && !self.downloading_body {
+				self.downloading_body = true;
+				return Some(HeadRequest::Body { hash });
+			}
+		}
+		match self.next {
+			Some(start) if !self.downloading_headers => {
+				self.downloading_headers = true;
+				Some(HeadRequest::Headers { start, count: MAX_HEADERS_TO_REQUEST })
+			},
+			_ => None,
+		}
+	}
+
+	/// Unmark all requests as being downloaded.
+	pub fn clear_download(&mut self) {
+		self.downloading_headers = false;
+		self.downloading_body = false;
+	}
+
+	/// Import headers received in reverse order.
+	pub fn import_headers(&mut self, chain: &BlockChainClient, r: &Rlp) -> Result<(), BlockDownloaderImportError> {
+		self.downloading_headers = false;
+		let item_count = r.item_count().unwrap_or(0);
+		if self.next.is_none() {
+			return Err(BlockDownloaderImportError::Useless);
+		}
+		if item_count == 0 {
+			return Err(BlockDownloaderImportError::Useless);
+		}
+
+		for i in 0..item_count {
+			let next = match self.next {
+				Some(next) => next,
+				None => break,
+			};
+			let raw = r.at(i).map_err(|_| BlockDownloaderImportError::Invalid)?.as_raw();
+			let header: BlockHeader = r.val_at(i).map_err(|e| {
+				trace!(target: "sync", "Error decoding block header RLP: {:?}", e);
+				BlockDownloaderImportError::Invalid
+			})?;
+			if header.hash() != next {
+				trace!(target: "sync", "Unexpected header in reverse chain: {}, expected {}", header.hash(), next);
+				return Err(BlockDownloaderImportError::Invalid);
+			}
+			if self.lowest.as_ref().map_or(false, |child| header.number() + 1 != child.number()) {
+				trace!(target: "sync", "Reverse header chain is not contiguous at {}", header.number());
+				return Err(BlockDownloaderImportError::Invalid);
+			}
+			let engine = chain.engine();
+			let verified = engine.verify_block_basic(&header)
+				.and_then(|_| engine.verify_block_unordered(&header))
+				.and_then(|_| match self.lowest {
+					Some(ref child) => engine.verify_block_family(child, &header),
+					None => Ok(()),
+				});
+			if let Err(e) = verified {
+				trace!(target: "sync", "Invalid header #{} in reverse chain: {:?}", header.number(), e);
+				return Err(BlockDownloaderImportError::Invalid);
+			}
+			if self.header.is_none() {
+				trace!(target: "sync", "Got head header #{} ({})", header.number(), next);
+				self.header = Some(raw.to_vec());
+			}
+			self.verified += 1;
+
+			match chain.block_status(BlockId::Hash(*header.parent_hash())) {
+				BlockStatus::InChain => {
+					let parent = chain.block_header(BlockId::Hash(*header.parent_hash()))
+						.and_then(|parent| parent.decode().ok())
+						.ok_or(BlockDownloaderImportError::Useless)?;
+					if let Err(e) = chain.engine().verify_block_family(&header, &parent) {
+						trace!(target: "sync", "Head chain does not link with our block #{}: {:?}", parent.number(), e);
+						return Err(BlockDownloaderImportError::Invalid);
+					}
+					debug!(target: "sync", "Head #{} linked with our chain after {} headers", self.head_number().unwrap_or(0), self.verified);
+					self.next = None;
+				},
+				BlockStatus::Bad => return Err(BlockDownloaderImportError::Invalid),
+				_ => {
+					if header.number() == 0 {
+						return Err(BlockDownloaderImportError::Invalid);
+					}
+					self.next = Some(*header.parent_hash());
+				},
+			}
+			self.lowest = Some(header);
+		}
+		Ok(())
+	}
+
+	/// Import the body of the head block.
+	pub fn import_body(&mut self, r: &Rlp) -> Result<(), BlockDownloaderImportError> {
+		self.downloading_body = false;
+		let header: BlockHeader = match self.header {
+			Some(ref header) => Rlp::new(header).as_val().map_err(|_| BlockDownloaderImportError::Invalid)?,
+			None => return Err(BlockDownloaderImportError::Useless),
+		};
+		if r.item_count().unwrap_or(0) != 1 {
+			return Err(BlockDownloaderImportError::Useless);
+		}
+		let body = r.at(0).map_err(|_| BlockDownloaderImportError::Invalid)?;
+		let txs = body.at(0).map_err(|_| BlockDownloaderImportError::Invalid)?;
+		let uncles = body.at(1).map_err(|_| BlockDownloaderImportError::Invalid)?;
+		if ordered_trie_root(txs.iter().map(|r| r.as_raw())) != *header.transactions_root() ||
+			keccak(uncles.as_raw()) != *header.uncles_hash() {
+			trace!(target: "sync", "Head body does not match header {}", header.hash());
+			return Err(BlockDownloaderImportError::Invalid);
+		}
+		self.body = Some(body.as_raw().to_vec());
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethcore::client::{BlockChainClient, BlockInfo, ImportBlock, EachBlockWith, TestBlockChainClient, BlockId};
+	use ethcore::ethereum;
+	use ethcore::header::Header as BlockHeader;
+	use ethereum_types::H256;
+	use rlp::{Rlp, RlpStream};
+	use block_sync::BlockDownloaderImportError;
+	use super::{HeadSync, HeadRequest};
+
+	fn headers_rlp(chain: &TestBlockChainClient, numbers: &[u64]) -> Vec<u8> {
+		let mut rlp = RlpStream::new_list(numbers.len());
+		for n in numbers {
+			rlp.append_raw(&chain.block_header(BlockId::Number(*n)).unwrap().into_inner(), 1);
+		}
+		rlp.out()
+	}
+
+	#[test]
+	fn links_reverse_chain_and_fetches_head_body() {
+		let remote = TestBlockChainClient::new();
+		remote.add_blocks(10, EachBlockWith::Transaction);
+		let local = TestBlockChainClient::new();
+		for n in 1..6 {
+			local.import_block(remote.block(BlockId::Number(n)).unwrap().into_inner()).unwrap();
+		}
+
+		let head = remote.block_header(BlockId::Number(10)).unwrap().hash();
+		let mut sync = HeadSync::new();
+		sync.set_target(head, 100.into());
+		assert_eq!(sync.request(), Some(HeadRequest::Headers { start: head, count: 192 }));
+
+		sync.import_headers(&local, &Rlp::new(&headers_rlp(&remote, &[10, 9, 8]))).unwrap();
+		assert!(!sync.is_linked());
+		assert_eq!(sync.head_number(), Some(10));
+		match sync.request() {
+			Some(HeadRequest::Body { hash }) => assert_eq!(hash, head),
+			_ => panic!("Expected head body request"),
+		}
+		let body = remote.block_body(BlockId::Number(10)).unwrap();
+		let mut bodies = RlpStream::new_list(1);
+		bodies.append_raw(body.rlp().as_raw(), 1);
+		sync.import_body(&Rlp::new(&bodies.out())).unwrap();
+
+		assert_eq!(sync.request(), Some(HeadRequest::Headers { start: remote.block_header(BlockId::Number(7)).unwrap().hash(), count: 192 }));
+		sync.import_headers(&local, &Rlp::new(&headers_rlp(&remote, &[7, 6]))).unwrap();
+		assert!(sync.is_linked());
+		assert_eq!(sync.verified_headers(), 5);
+		assert_eq!(sync.head_block().unwrap().hash(), head);
+		assert_eq!(sync.request(), None);
+	}
+
+	#[test]
+	fn rejects_unlinked_headers() {
+		let remote = TestBlockChainClient::new();
+		remote.add_blocks(10, EachBlockWith::Nothing);
+		let local = TestBlockChainClient::new();
+
+		let mut sync = HeadSync::new();
+		sync.set_target(remote.block_header(BlockId::Number(10)).unwrap().hash(), 100.into());
+		sync.request();
+		let result = sync.import_headers(&local, &Rlp::new(&headers_rlp(&remote, &[10, 8])));
+		assert_eq!(result, Err(BlockDownloaderImportError::Invalid));
+
+		let mut header = BlockHeader::new();
+		header.set_number(10);
+		header.set_parent_hash(H256::from(9));
+		let mut rlp = RlpStream::new_list(1);
+		rlp.append(&header);
+		let mut sync = HeadSync::new();
+		sync.set_target(H256::from(10), 100.into());
+		sync.request();
+		assert_eq!(sync.import_headers(&local, &Rlp::new(&rlp.out())), Err(BlockDownloaderImportError::Invalid));
+	}
+
+	#[test]
+	fn rejects_headers_failing_engine_verification() {
+		let remote = TestBlockChainClient::new();
+		remote.add_blocks(10, EachBlockWith::Nothing);
+		// unsealed headers are never valid for an ethash chain.
+		let local = TestBlockChainClient::new_with_spec(ethereum::new_frontier_test());
+
+		let mut sync = HeadSync::new();
+		sync.set_target(remote.block_header(BlockId::Number(10)).unwrap().hash(), 100.into());
+		sync.request();
+		let result = sync.import_headers(&local, &Rlp::new(&headers_rlp(&remote, &[10, 9])));
+		assert_eq!(result, Err(BlockDownloaderImportError::Invalid));
+		assert_eq!(sync.head_number(), None);
+		assert!(sync.head_block().is_none());
+	}
+
+	#[test]
+	fn keeps_better_target() {
+		let mut sync = HeadSync::new();
+		sync.set_target(H256::from(1), 100.into());
+		sync.set_target(H256::from(2), 50.into());
+		assert_eq!(sync.target_difficulty(), Some(100.into()));
+		sync.set_target(H256::from(3), 200.into());
+		assert_eq!(sync.request(), Some(HeadRequest::Headers { start: H256::from(3), count: 192 }));
+	}
+}
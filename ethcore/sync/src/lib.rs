@@ -59,6 +59,8 @@ extern crate trace_time;
 
 mod chain;
 mod blocks;
+mod fork_monitor;
+mod head_sync;
 mod block_sync;
 mod sync_io;
 mod private_tx;
@@ -75,5 +77,6 @@ mod api;
 pub use api::*;
 pub use chain::{SyncStatus, SyncState};
 pub use devp2p::validate_node_url;
+pub use fork_monitor::{ForkAlert, ForkAlertHandler, ForkStatus};
 pub use network::{NonReservedPeerMode, Error, ErrorKind, ConnectionFilter, ConnectionDirection};
 pub use private_tx::{PrivateTxHandler, NoopPrivateTxHandler, SimplePrivateTxHandler};
@@ -23,6 +23,12 @@ pub trait PrivateTxHandler: Send + Sync + 'static {
 
 	/// Function called on new signed private transaction received.
 	fn import_signed_private_transaction(&self, rlp: &[u8]) -> Result<(), String>;
+
+	/// Function called on private state request received. Returns the response to send back, if any.
+	fn private_state_request(&self, rlp: &[u8]) -> Result<Option<Vec<u8>>, String>;
+
+	/// Function called on private state received.
+	fn import_private_state(&self, rlp: &[u8]) -> Result<(), String>;
 }
 
 /// Nonoperative private transaction handler.
@@ -36,6 +42,14 @@ impl PrivateTxHandler for NoopPrivateTxHandler {
 	fn import_signed_private_transaction(&self, _rlp: &[u8]) -> Result<(), String> {
 		Ok(())
 	}
+
+	fn private_state_request(&self, _rlp: &[u8]) -> Result<Option<Vec<u8>>, String> {
+		Ok(None)
+	}
+
+	fn import_private_state(&self, _rlp: &[u8]) -> Result<(), String> {
+		Ok(())
+	}
 }
 
 /// Simple private transaction handler. Used for tests.
@@ -45,6 +59,10 @@ pub struct SimplePrivateTxHandler {
 	pub txs: Mutex<Vec<Vec<u8>>>,
 	/// imported signed private transactions
 	pub signed_txs: Mutex<Vec<Vec<u8>>>,
+	/// received private state requests
+	pub state_requests: Mutex<Vec<Vec<u8>>>,
+	/// imported private states
+	pub states: Mutex<Vec<Vec<u8>>>,
 }
 
 impl PrivateTxHandler for SimplePrivateTxHandler {
@@ -57,4 +75,15 @@ impl PrivateTxHandler for SimplePrivateTxHandler {
 		self.signed_txs.lock().push(rlp.to_vec());
 		Ok(())
 	}
+
+	fn private_state_request(&self, rlp: &[u8]) -> Result<Option<Vec<u8>>, String> {
+		self.state_requests.lock().push(rlp.to_vec());
+		// answer with the request itself, so that tests could check the response is delivered
+		Ok(Some(rlp.to_vec()))
+	}
+
+	fn import_private_state(&self, rlp: &[u8]) -> Result<(), String> {
+		self.states.lock().push(rlp.to_vec());
+		Ok(())
+	}
 }
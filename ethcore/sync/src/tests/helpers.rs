@@ -20,7 +20,7 @@ use std::time::Duration;
 use ethereum_types::H256;
 use parking_lot::{RwLock, Mutex};
 use bytes::Bytes;
-use network::{self, PeerId, ProtocolId, PacketId, SessionInfo};
+use network::{self, PeerId, ProtocolId, PacketId, SessionInfo, NodeId};
 use tests::snapshot::*;
 use ethcore::client::{TestBlockChainClient, BlockChainClient, Client as EthcoreClient,
 	ClientConfig, ChainNotify, ChainRoute, ChainMessageType, ClientIoMessage};
@@ -33,7 +33,7 @@ use ethcore::test_helpers;
 use sync_io::SyncIo;
 use io::{IoChannel, IoContext, IoHandler};
 use api::WARP_SYNC_PROTOCOL_ID;
-use chain::{ChainSync, ETH_PROTOCOL_VERSION_63, PAR_PROTOCOL_VERSION_3};
+use chain::{ChainSync, ETH_PROTOCOL_VERSION_63, PAR_PROTOCOL_VERSION_4};
 use SyncConfig;
 use private_tx::SimplePrivateTxHandler;
 
@@ -57,6 +57,8 @@ pub struct TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 	pub to_disconnect: HashSet<PeerId>,
 	pub packets: Vec<TestPacket>,
 	pub peers_info: HashMap<PeerId, String>,
+	pub node_ids: HashMap<PeerId, NodeId>,
+	pub discovery_refreshed: bool,
 	overlay: RwLock<HashMap<BlockNumber, Bytes>>,
 }
 
@@ -71,6 +73,8 @@ impl<'p, C> TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 			overlay: RwLock::new(HashMap::new()),
 			packets: Vec::new(),
 			peers_info: HashMap::new(),
+			node_ids: HashMap::new(),
+			discovery_refreshed: false,
 		}
 	}
 }
@@ -90,6 +94,10 @@ impl<'p, C> SyncIo for TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 		self.to_disconnect.insert(peer_id);
 	}
 
+	fn refresh_discovery(&mut self) {
+		self.discovery_refreshed = true;
+	}
+
 	fn is_expired(&self) -> bool {
 		false
 	}
@@ -130,8 +138,18 @@ impl<'p, C> SyncIo for TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 		self.snapshot_service
 	}
 
-	fn peer_session_info(&self, _peer_id: PeerId) -> Option<SessionInfo> {
-		None
+	fn peer_session_info(&self, peer_id: PeerId) -> Option<SessionInfo> {
+		self.node_ids.get(&peer_id).map(|id| SessionInfo {
+			id: Some(*id),
+			client_version: self.peer_info(peer_id),
+			protocol_version: 5,
+			capabilities: Vec::new(),
+			peer_capabilities: Vec::new(),
+			ping: None,
+			originated: true,
+			remote_address: String::new(),
+			local_address: String::new(),
+		})
 	}
 
 	fn eth_protocol_version(&self, _peer: PeerId) -> u8 {
@@ -139,7 +157,7 @@ impl<'p, C> SyncIo for TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 	}
 
 	fn protocol_version(&self, protocol: &ProtocolId, peer_id: PeerId) -> u8 {
-		if protocol == &WARP_SYNC_PROTOCOL_ID { PAR_PROTOCOL_VERSION_3.0 } else { self.eth_protocol_version(peer_id) }
+		if protocol == &WARP_SYNC_PROTOCOL_ID { PAR_PROTOCOL_VERSION_4.0 } else { self.eth_protocol_version(peer_id) }
 	}
 
 	fn chain_overlay(&self) -> &RwLock<HashMap<BlockNumber, Bytes>> {
@@ -232,6 +250,7 @@ impl<C> EthPeer<C> where C: FlushingBlockChainClient {
 			ChainMessageType::Consensus(data) => self.sync.write().propagate_consensus_packet(&mut io, data),
 			ChainMessageType::PrivateTransaction(data) => self.sync.write().propagate_private_transaction(&mut io, data),
 			ChainMessageType::SignedPrivateTransaction(data) => self.sync.write().propagate_signed_private_transaction(&mut io, data),
+			ChainMessageType::PrivateStateRequest(data) => self.sync.write().request_private_state(&mut io, data),
 		}
 	}
 
@@ -146,4 +146,17 @@ fn send_private_transaction() {
 	assert!(pm0.import_signed_private_transaction(&signed_private_transaction).is_ok());
 	let local_transactions = net.peer(0).miner.local_transactions();
 	assert_eq!(local_transactions.len(), 1);
+
+	// Sync private state of the contract from the validator
+	assert!(pm0.request_private_state(&address, BlockId::Latest).is_ok());
+	net.sync();
+
+	let received_state_requests = validator_handler.state_requests.lock().clone();
+	assert_eq!(received_state_requests.len(), 1);
+	assert_eq!(sender_handler.states.lock().len(), 1);
+
+	let private_state = pm1.private_state_request(&received_state_requests[0]).unwrap().unwrap();
+	assert!(pm0.import_private_state(&private_state).is_ok());
+	let block_hash = client0.block_hash(BlockId::Latest).unwrap();
+	assert!(pm0.synced_private_state(&address, &block_hash).is_some());
 }
@@ -325,6 +325,19 @@ impl UnverifiedTransaction {
 		self
 	}
 
+	/// Decodes a transaction from bytes holding exactly one RLP-encoded transaction.
+	///
+	/// Bytes following the transaction are rejected instead of ignored, so a transaction
+	/// has only one valid encoding. Decoding failures are reported as `Error::InvalidRlp`.
+	pub fn from_rlp_bytes(bytes: &[u8]) -> Result<Self, error::Error> {
+		let rlp = Rlp::new(bytes);
+		let length = rlp.payload_info()?.total();
+		if length != bytes.len() {
+			return Err(error::Error::InvalidRlp(format!("{} bytes following the transaction", bytes.len().saturating_sub(length))));
+		}
+		Ok(rlp.as_val()?)
+	}
+
 	/// Checks is signature is empty.
 	pub fn is_unsigned(&self) -> bool {
 		self.r.is_zero() && self.s.is_zero()
@@ -477,6 +490,13 @@ impl SignedTransaction {
 		}
 	}
 
+	/// Decodes a transaction with `UnverifiedTransaction::from_rlp_bytes` and recovers its sender.
+	///
+	/// A sender which can't be recovered is reported as `Error::InvalidSignature`.
+	pub fn from_rlp_bytes(bytes: &[u8]) -> Result<Self, error::Error> {
+		Ok(SignedTransaction::new(UnverifiedTransaction::from_rlp_bytes(bytes)?)?)
+	}
+
 	/// Returns transaction sender.
 	pub fn sender(&self) -> Address {
 		self.sender
@@ -594,6 +614,29 @@ mod tests {
 		assert_eq!(t.chain_id(), None);
 	}
 
+	#[test]
+	fn should_decode_only_complete_transactions() {
+		let bytes = ::rustc_hex::FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+		let t = SignedTransaction::from_rlp_bytes(&bytes).unwrap();
+		assert_eq!(t.sender(), "0f65fe9276bc9a24ae7083ae28e2660ef72df99e".into());
+
+		let mut trailing = bytes.clone();
+		trailing.push(0);
+		assert_eq!(UnverifiedTransaction::from_rlp_bytes(&trailing), Err(error::Error::InvalidRlp("1 bytes following the transaction".into())));
+		match UnverifiedTransaction::from_rlp_bytes(&bytes[..bytes.len() - 1]) {
+			Err(error::Error::InvalidRlp(_)) => {},
+			other => panic!("Unexpected result: {:?}", other),
+		}
+
+		let mut invalid_v = RlpStream::new_list(9);
+		invalid_v.append(&t.nonce).append(&t.gas_price).append(&t.gas).append(&t.action).append(&t.value).append(&t.data);
+		invalid_v.append(&30u64).append(&t.r).append(&t.s);
+		match SignedTransaction::from_rlp_bytes(&invalid_v.out()) {
+			Err(error::Error::InvalidSignature(_)) => {},
+			other => panic!("Unexpected result: {:?}", other),
+		}
+	}
+
 	#[test]
 	fn signing() {
 		use ethkey::{Random, Generator};
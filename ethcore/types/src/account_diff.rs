@@ -21,6 +21,7 @@ use std::fmt;
 use std::collections::BTreeMap;
 use ethereum_types::{H256, U256};
 use bytes::Bytes;
+use rlp::{Rlp, RlpStream, Encodable, Decodable, DecoderError};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 /// Diff type for specifying a change (or not).
@@ -49,6 +50,45 @@ impl<T> Diff<T> where T: Eq {
 	pub fn is_same(&self) -> bool { match *self { Diff::Same => true, _ => false }}
 }
 
+impl<T> Encodable for Diff<T> where T: Eq + Encodable {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		match *self {
+			Diff::Same => {
+				s.begin_list(1);
+				s.append(&0u8);
+			},
+			Diff::Born(ref x) => {
+				s.begin_list(2);
+				s.append(&1u8);
+				s.append(x);
+			},
+			Diff::Changed(ref pre, ref post) => {
+				s.begin_list(3);
+				s.append(&2u8);
+				s.append(pre);
+				s.append(post);
+			},
+			Diff::Died(ref x) => {
+				s.begin_list(2);
+				s.append(&3u8);
+				s.append(x);
+			},
+		}
+	}
+}
+
+impl<T> Decodable for Diff<T> where T: Eq + Decodable {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		match rlp.val_at::<u8>(0)? {
+			0 => Ok(Diff::Same),
+			1 => Ok(Diff::Born(rlp.val_at(1)?)),
+			2 => Ok(Diff::Changed(rlp.val_at(1)?, rlp.val_at(2)?)),
+			3 => Ok(Diff::Died(rlp.val_at(1)?)),
+			_ => Err(DecoderError::Custom("Unknown diff type.")),
+		}
+	}
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 /// Account diff.
 pub struct AccountDiff {
@@ -85,6 +125,36 @@ impl fmt::Display for Existance {
 	}
 }
 
+impl Encodable for AccountDiff {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(4);
+		s.append(&self.balance);
+		s.append(&self.nonce);
+		s.append(&self.code);
+		s.begin_list(self.storage.len());
+		for (key, diff) in &self.storage {
+			s.begin_list(2);
+			s.append(key);
+			s.append(diff);
+		}
+	}
+}
+
+impl Decodable for AccountDiff {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		let storage = rlp.at(3)?.iter()
+			.map(|item| Ok((item.val_at(0)?, item.val_at(1)?)))
+			.collect::<Result<_, DecoderError>>()?;
+
+		Ok(AccountDiff {
+			balance: rlp.val_at(0)?,
+			nonce: rlp.val_at(1)?,
+			code: rlp.val_at(2)?,
+			storage,
+		})
+	}
+}
+
 impl AccountDiff {
 	/// Get `Existance` projection.
 	pub fn existance(&self) -> Existance {
@@ -0,0 +1,48 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block finality.
+
+use ethereum_types::H256;
+use BlockNumber;
+
+/// How blocks become irreversible under a consensus engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinalityRule {
+	/// Blocks are final as soon as they are sealed.
+	Instant,
+	/// Blocks are final once the engine marks them so, e.g. when enough validators built on top of them.
+	Marked,
+	/// Blocks are final once buried under the configured number of blocks.
+	Depth,
+}
+
+/// Finality of the canonical chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalityStatus {
+	/// Rule used to determine finality.
+	pub rule: FinalityRule,
+	/// Number of blocks a block has to be buried under, for the `Depth` rule.
+	pub depth: Option<u64>,
+	/// Best block number.
+	pub best_number: BlockNumber,
+	/// Best block hash.
+	pub best_hash: H256,
+	/// Number of the latest finalized block.
+	pub finalized_number: BlockNumber,
+	/// Hash of the latest finalized block.
+	pub finalized_hash: H256,
+}
@@ -31,6 +31,8 @@ pub enum BlockId {
 	Earliest,
 	/// Latest mined block.
 	Latest,
+	/// Latest block considered final by the consensus engine.
+	Finalized,
 }
 
 /// Uniquely identifies transaction.
@@ -34,14 +34,17 @@ pub mod block_status;
 pub mod blockchain_info;
 pub mod call_analytics;
 pub mod filter;
+pub mod finality;
 pub mod ids;
 pub mod log_entry;
 pub mod pruning_info;
 pub mod receipt;
+pub mod reorg_alert;
 pub mod restoration_status;
 pub mod security_level;
 pub mod snapshot_manifest;
 pub mod state_diff;
+pub mod state_range;
 pub mod trace_filter;
 pub mod tree_route;
 pub mod verification_queue_info;
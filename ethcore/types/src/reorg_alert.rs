@@ -0,0 +1,41 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Deep chain reorganization alert.
+
+use ethereum_types::H256;
+use BlockNumber;
+
+/// A reorganization deeper than the configured limit which halted block import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorgAlert {
+	/// Our best block number when the reorganization was detected.
+	pub best_number: BlockNumber,
+	/// Our best block hash when the reorganization was detected.
+	pub best_hash: H256,
+	/// Number of the common ancestor.
+	pub ancestor_number: BlockNumber,
+	/// Hash of the common ancestor.
+	pub ancestor_hash: H256,
+	/// Number of the block which would become the new best block.
+	pub new_number: BlockNumber,
+	/// Hash of the block which would become the new best block.
+	pub new_hash: H256,
+	/// Number of canonical blocks which would be retracted.
+	pub depth: u64,
+	/// UNIX timestamp (seconds) when import was halted.
+	pub detected_at: u64,
+}
@@ -20,6 +20,7 @@ use std::fmt;
 use std::ops::*;
 use std::collections::BTreeMap;
 use ethereum_types::Address;
+use rlp::{Rlp, RlpStream, Encodable, Decodable, DecoderError};
 use account_diff::*;
 
 /// Expression for the delta between two system states. Encoded the
@@ -37,6 +38,27 @@ impl StateDiff {
 	}
 }
 
+impl Encodable for StateDiff {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(self.raw.len());
+		for (address, diff) in &self.raw {
+			s.begin_list(2);
+			s.append(address);
+			s.append(diff);
+		}
+	}
+}
+
+impl Decodable for StateDiff {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		let raw = rlp.iter()
+			.map(|item| Ok((item.val_at(0)?, item.val_at(1)?)))
+			.collect::<Result<_, DecoderError>>()?;
+
+		Ok(StateDiff { raw })
+	}
+}
+
 impl fmt::Display for StateDiff {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		for (add, acc) in &self.raw {
@@ -53,3 +75,31 @@ impl Deref for StateDiff {
 		&self.raw
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use ethereum_types::H256;
+	use rlp;
+	use account_diff::{AccountDiff, Diff};
+	use super::StateDiff;
+
+	#[test]
+	fn should_encode_and_decode_state_diff() {
+		let mut storage = BTreeMap::new();
+		storage.insert(H256::from(1), Diff::Changed(H256::from(2), H256::from(3)));
+		storage.insert(H256::from(4), Diff::Died(H256::from(5)));
+
+		let mut raw = BTreeMap::new();
+		raw.insert(10.into(), AccountDiff {
+			balance: Diff::Changed(100.into(), 90.into()),
+			nonce: Diff::Same,
+			code: Diff::Born(vec![0x60, 0x00]),
+			storage,
+		});
+		let diff = StateDiff { raw };
+
+		let encoded = rlp::encode(&diff);
+		assert_eq!(rlp::decode::<StateDiff>(&encoded).unwrap(), diff);
+	}
+}
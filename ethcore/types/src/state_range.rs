@@ -0,0 +1,51 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pages of accounts and storage slots of a state, in trie order.
+
+use ethereum_types::{H256, Address};
+use basic_account::BasicAccount;
+
+/// Account of an account range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeAccount {
+	/// Hash of the address, the key of the account in the state trie.
+	pub hash: H256,
+	/// The address, if the state keeps the preimages of its keys (fat DB).
+	pub address: Option<Address>,
+	/// The account.
+	pub account: BasicAccount,
+}
+
+/// Storage slot of a storage range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeStorage {
+	/// Hash of the key, the key of the slot in the storage trie.
+	pub hash: H256,
+	/// The key, if the state keeps the preimages of its keys (fat DB).
+	pub key: Option<H256>,
+	/// The value.
+	pub value: H256,
+}
+
+/// A page of items of a trie.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateRange<T> {
+	/// The items, ordered by hash.
+	pub items: Vec<T>,
+	/// Hash of the item following the last one returned, if any.
+	pub next: Option<H256>,
+}
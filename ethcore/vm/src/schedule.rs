@@ -120,6 +120,7 @@ pub struct Schedule {
 }
 
 /// Wasm cost table
+#[derive(Debug, Clone, PartialEq)]
 pub struct WasmCosts {
 	/// Default opcode cost
 	pub regular: u32,
@@ -52,6 +52,7 @@ mod extended;
 pub mod brain_recover;
 pub mod crypto;
 pub mod math;
+pub mod mnemonic;
 
 pub use self::parity_wordlist::Error as WordlistError;
 pub use self::brain::Brain;
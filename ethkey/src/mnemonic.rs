@@ -0,0 +1,174 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Keys of BIP-39 mnemonic phrases.
+//!
+//! Phrases are checked against the English wordlist and their checksum, so a mistyped word
+//! is reported instead of giving a different key.
+//! Only ASCII phrases and passphrases are supported, since they need no Unicode normalization.
+
+use ethcore_crypto::{digest, pbkdf2};
+use extended::{ExtendedKeyPair, Derivation, DerivationError};
+use {Error, Secret};
+
+lazy_static! {
+	/// The BIP-39 English wordlist, sorted.
+	static ref WORDS: Vec<&'static str> = include_str!("../res/bip39/english.txt").lines().collect();
+}
+
+/// Derivation path of the first account used by most Ethereum wallets.
+pub const DEFAULT_DERIVATION_PATH: &'static str = "m/44'/60'/0'/0/0";
+
+const SEED_ITERATIONS: u32 = 2048;
+const HARDENED: u32 = 0x8000_0000;
+
+/// Computes the 512-bit seed of a mnemonic phrase, protected with an optional passphrase.
+pub fn seed(phrase: &str, passphrase: &str) -> Result<[u8; 64], Error> {
+	if !phrase.is_ascii() || !passphrase.is_ascii() {
+		return Err(Error::Custom("Only ASCII mnemonic phrases and passphrases are supported".into()));
+	}
+
+	let words: Vec<&str> = phrase.split_whitespace().collect();
+	if ![12, 15, 18, 21, 24].contains(&words.len()) {
+		return Err(Error::Custom(format!("Mnemonic phrase has to consist of 12, 15, 18, 21 or 24 words, got {}", words.len())));
+	}
+	validate(&words)?;
+
+	let phrase = words.join(" ");
+	let salt = format!("mnemonic{}", passphrase);
+	let mut seed = [0u8; 64];
+	pbkdf2::sha512(SEED_ITERATIONS, pbkdf2::Salt(salt.as_bytes()), pbkdf2::Secret(phrase.as_bytes()), &mut seed);
+	Ok(seed)
+}
+
+/// Checks that all words are in the wordlist and that the checksum matches the entropy they encode.
+fn validate(words: &[&str]) -> Result<(), Error> {
+	// every word encodes 11 bits, the last `len / 33` of them are the checksum
+	let mut bits = Vec::with_capacity(words.len() * 11);
+	for (position, word) in words.iter().enumerate() {
+		// the word itself isn't part of the error, as it would end up in logs
+		let index = WORDS.binary_search_by(|probe| (*probe).cmp(*word))
+			.map_err(|_| Error::Custom(format!("Word {} of the mnemonic phrase is not in the BIP-39 English wordlist", position + 1)))?;
+		bits.extend((0..11).rev().map(|bit| index >> bit & 1 == 1));
+	}
+
+	let checksum_len = bits.len() / 33;
+	let (entropy, checksum) = bits.split_at(bits.len() - checksum_len);
+	let entropy: Vec<u8> = entropy.chunks(8).map(|byte| byte.iter().fold(0u8, |acc, &bit| acc << 1 | bit as u8)).collect();
+	let hash = digest::sha256(&entropy);
+
+	match checksum.iter().enumerate().all(|(i, &bit)| (hash[i / 8] >> (7 - i % 8) & 1 == 1) == bit) {
+		true => Ok(()),
+		false => Err(Error::Custom("Invalid mnemonic phrase checksum, one of the words is wrong".into())),
+	}
+}
+
+/// Parses a BIP-32 derivation path like `m/44'/60'/0'/0/0`.
+pub fn derivation_path(path: &str) -> Result<Vec<u32>, Error> {
+	let invalid = || Error::Custom(format!("Invalid derivation path: {}", path));
+
+	let mut parts = path.split('/');
+	if parts.next() != Some("m") {
+		return Err(invalid());
+	}
+
+	parts.map(|part| {
+		let (index, hardened) = match part.ends_with('\'') || part.ends_with('h') {
+			true => (&part[..part.len() - 1], HARDENED),
+			false => (part, 0),
+		};
+		match index.parse::<u32>() {
+			Ok(index) if index < HARDENED => Ok(index | hardened),
+			_ => Err(invalid()),
+		}
+	}).collect()
+}
+
+/// Derives the extended key at given path from a mnemonic phrase.
+///
+/// Deriving many keys below a common path from its extended key avoids computing the seed again for each of them.
+pub fn extended_key(phrase: &str, passphrase: &str, path: &str) -> Result<ExtendedKeyPair, Error> {
+	let path = derivation_path(path)?;
+	let seed = seed(phrase, passphrase)?;
+	let derivation_error = |e: DerivationError| Error::Custom(format!("Key derivation failed: {:?}", e));
+
+	let mut key = ExtendedKeyPair::with_seed(&seed).map_err(&derivation_error)?;
+	for index in path {
+		key = key.derive(Derivation::from(index)).map_err(&derivation_error)?;
+	}
+	Ok(key)
+}
+
+/// Derives the secret at given path from a mnemonic phrase.
+pub fn secret(phrase: &str, passphrase: &str, path: &str) -> Result<Secret, Error> {
+	Ok(extended_key(phrase, passphrase, path)?.secret().as_raw().clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use rustc_hex::FromHex;
+	use {KeyPair, Address, Derivation};
+	use super::{seed, secret, extended_key, derivation_path, DEFAULT_DERIVATION_PATH, WORDS};
+
+	const PHRASE: &'static str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+	#[test]
+	fn should_compute_seed() {
+		// test vector from the BIP-39 reference implementation
+		let expected: Vec<u8> = "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04".from_hex().unwrap();
+		assert_eq!(&seed(PHRASE, "TREZOR").unwrap()[..], &expected[..]);
+		assert!(seed("abandon about", "").is_err());
+	}
+
+	#[test]
+	fn should_validate_phrase() {
+		assert!(seed("legal winner thank year wave sausage worth useful legal winner thank yellow", "").is_ok());
+		assert!(seed(&format!("{} zoo zoo zoo zoo zoo zoo vote", "zoo ".repeat(17)), "").is_ok());
+		// wrong checksum
+		assert!(seed("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon", "").is_err());
+		// unknown word
+		assert!(seed("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abuot", "").is_err());
+	}
+
+	#[test]
+	fn should_load_wordlist() {
+		assert_eq!(WORDS.len(), 2048);
+		assert_eq!((WORDS[0], WORDS[2047]), ("abandon", "zoo"));
+		assert!(WORDS.windows(2).all(|w| w[0] < w[1]));
+	}
+
+	#[test]
+	fn should_parse_derivation_path() {
+		assert_eq!(derivation_path(DEFAULT_DERIVATION_PATH).unwrap(), vec![0x8000_002c, 0x8000_003c, 0x8000_0000, 0, 0]);
+		assert_eq!(derivation_path("m").unwrap(), Vec::<u32>::new());
+		assert!(derivation_path("44'/60'").is_err());
+		assert!(derivation_path("m/2147483648").is_err());
+	}
+
+	#[test]
+	fn should_derive_first_account() {
+		let secret = secret(PHRASE, "", DEFAULT_DERIVATION_PATH).unwrap();
+		let expected: Address = "9858effd232b4033e47d90003d41ec34ecaeda94".parse().unwrap();
+		assert_eq!(KeyPair::from_secret(secret).unwrap().address(), expected);
+	}
+
+	#[test]
+	fn should_derive_accounts_from_extended_key() {
+		let account = extended_key(PHRASE, "", "m/44'/60'/0'/0").unwrap();
+		let first = account.derive(Derivation::from(0)).unwrap();
+		assert_eq!(first.secret().as_raw(), &secret(PHRASE, "", DEFAULT_DERIVATION_PATH).unwrap());
+	}
+}
@@ -21,6 +21,7 @@ use std::time::Duration;
 pub mod json;
 pub mod std_json;
 pub mod simple;
+pub mod profile;
 
 /// Formats duration into human readable format.
 pub fn format_time(time: &Duration) -> String {
@@ -31,3 +32,8 @@ pub fn format_time(time: &Duration) -> String {
 pub fn as_micros(time: &Duration) -> u64 {
 	time.as_secs() * 1_000_000 + time.subsec_nanos() as u64 / 1_000
 }
+
+/// Formats the time as nanoseconds.
+pub fn as_nanos(time: &Duration) -> u64 {
+	time.as_secs() * 1_000_000_000 + time.subsec_nanos() as u64
+}
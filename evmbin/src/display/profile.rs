@@ -0,0 +1,221 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Gas and time profiling VM output.
+//!
+//! Costs are collected by `ethcore::trace::Profiler`; this informant only
+//! renders them as JSON or as collapsed stacks for `flamegraph.pl`.
+
+use ethcore::trace::{self, Profile, Profiler, op_name};
+use ethereum_types::U256;
+use bytes::ToPretty;
+
+use display;
+use info as vm;
+
+/// Profile output format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+	/// One JSON object per opcode and per frame.
+	Json,
+	/// Collapsed stacks weighted by gas, as consumed by flamegraph tools.
+	Folded,
+}
+
+impl Default for Format {
+	fn default() -> Self {
+		Format::Json
+	}
+}
+
+/// Execution profile together with its requested output format.
+#[derive(Debug, Default)]
+pub struct Report {
+	/// Requested output format.
+	pub format: Format,
+	/// Collected profile.
+	pub profile: Profile,
+}
+
+impl Report {
+	/// Renders the profile in its requested format, one line per entry.
+	pub fn lines(&self) -> Vec<String> {
+		match self.format {
+			Format::Json => {
+				let opcodes = self.profile.opcodes.iter().map(|(op, stats)| format!(
+					"{{\"op\":{op},\"opName\":\"{name}\",\"count\":{count},\"gas\":\"0x{gas:x}\",\"time\":{time}}}",
+					op = op,
+					name = op_name(*op),
+					count = stats.count,
+					gas = stats.gas,
+					time = display::as_nanos(&stats.time),
+				));
+				let frames = self.profile.frames.iter().map(|(frame, stats)| format!(
+					"{{\"frame\":\"{frame}\",\"count\":{count},\"gas\":\"0x{gas:x}\",\"time\":{time}}}",
+					frame = frame,
+					count = stats.count,
+					gas = stats.gas,
+					time = display::as_nanos(&stats.time),
+				));
+				opcodes.chain(frames).collect()
+			},
+			Format::Folded => self.profile.folded(),
+		}
+	}
+}
+
+/// Profiling informant.
+#[derive(Default)]
+pub struct Informant {
+	format: Format,
+	profiler: Profiler,
+}
+
+impl Informant {
+	/// Create a new profiling informant rendering the results in given format.
+	pub fn new(format: Format) -> Self {
+		Informant {
+			format,
+			..Default::default()
+		}
+	}
+}
+
+impl vm::Informant for Informant {
+	fn before_test(&mut self, name: &str, action: &str) {
+		println!("Test: {} ({})", name, action);
+	}
+
+	fn finish(result: vm::RunResult<Self::Output>) {
+		let (profile, summary) = match result {
+			Ok(success) => (success.traces, format!(
+				"{{\"output\":\"0x{output}\",\"gasUsed\":\"{gas:x}\",\"time\":{time}}}",
+				output = success.output.to_hex(),
+				gas = success.gas_used,
+				time = display::as_micros(&success.time),
+			)),
+			Err(failure) => (failure.traces, format!(
+				"{{\"error\":\"{error}\",\"gasUsed\":\"{gas:x}\",\"time\":{time}}}",
+				error = failure.error,
+				gas = failure.gas_used,
+				time = display::as_micros(&failure.time),
+			)),
+		};
+
+		let profile = profile.unwrap_or_default();
+		for line in profile.lines() {
+			println!("{}", line);
+		}
+
+		// keep folded output consumable by flamegraph tools
+		match profile.format {
+			Format::Json => println!("{}", summary),
+			Format::Folded => eprintln!("{}", summary),
+		}
+	}
+}
+
+impl trace::VMTracer for Informant {
+	type Output = Report;
+
+	fn trace_next_instruction(&mut self, pc: usize, instruction: u8, current_gas: U256) -> bool {
+		self.profiler.trace_next_instruction(pc, instruction, current_gas)
+	}
+
+	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256) {
+		self.profiler.trace_prepare_execute(pc, instruction, gas_cost)
+	}
+
+	fn trace_executed(&mut self, gas_left: U256, stack_push: &[U256], mem_diff: Option<(usize, &[u8])>, store_diff: Option<(U256, U256)>) {
+		self.profiler.trace_executed(gas_left, stack_push, mem_diff, store_diff)
+	}
+
+	fn prepare_subtrace(&self, code: &[u8]) -> Self where Self: Sized {
+		Informant {
+			format: self.format,
+			profiler: self.profiler.prepare_subtrace(code),
+		}
+	}
+
+	fn done_subtrace(&mut self, sub: Self) {
+		self.profiler.done_subtrace(sub.profiler)
+	}
+
+	fn drain(self) -> Option<Self::Output> {
+		let format = self.format;
+		self.profiler.drain().map(|profile| Report { format, profile })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use info::tests::run_test;
+
+	fn compare_folded(report: Option<Report>, expected: &str) {
+		let expected = expected.split("\n")
+			.map(|x| x.trim())
+			.filter(|x| !x.is_empty())
+			.map(|x| x.to_owned())
+			.collect::<Vec<_>>();
+		assert_eq!(report.unwrap().lines(), expected);
+	}
+
+	#[test]
+	fn should_fold_gas_by_frame_and_opcode() {
+		run_test(
+			Informant::new(Format::Folded),
+			&compare_folded,
+			"6001600201",
+			0xffff,
+			r#"
+root;ADD 3
+root;PUSH1 6
+			"#,
+		);
+	}
+
+	#[test]
+	fn should_skip_failed_instruction() {
+		run_test(
+			Informant::new(Format::Folded),
+			&compare_folded,
+			"60F8d6",
+			0xffff,
+			r#"
+root;PUSH1 3
+			"#,
+		);
+	}
+
+	#[test]
+	fn should_profile_nested_frames() {
+		run_test(
+			Informant::default(),
+			&|report: Option<Report>, _: &str| {
+				let profile = report.unwrap().profile;
+				let created = &profile.frames["root;CREATE@15"];
+				assert_eq!(created.count, 7);
+				assert_eq!(created.gas, 14.into());
+				assert_eq!(profile.opcodes[&0x34].count, 11);
+				assert_eq!(profile.opcodes[&0x34].gas, 22.into());
+			},
+			"32343434345830f138343438323439f0",
+			0xffff,
+			"",
+		);
+	}
+}
@@ -59,7 +59,7 @@ EVM implementation for Parity.
   Copyright 2016, 2017 Parity Technologies (UK) Ltd
 
 Usage:
-    parity-evm state-test <file> [--json --std-json --only NAME --chain CHAIN]
+    parity-evm state-test <file> [--json --std-json --profile --flamegraph --only NAME --chain CHAIN]
     parity-evm stats [options]
     parity-evm stats-jsontests-vm <file>
     parity-evm [options]
@@ -86,6 +86,10 @@ State test options:
 General options:
     --json             Display verbose results in JSON.
 	--std-json         Display results in standardized JSON format.
+    --profile          Display gas and time spent per opcode and call frame
+                       in JSON.
+    --flamegraph       Display gas spent per call frame and opcode as
+                       collapsed stacks, suitable for flamegraph tools.
     --chain CHAIN      Chain spec file path.
     -h, --help         Display this message and exit.
 "#;
@@ -103,6 +107,10 @@ fn main() {
 		run_call(args, display::json::Informant::default())
 	} else if args.flag_std_json {
 		run_call(args, display::std_json::Informant::default())
+	} else if args.flag_profile {
+		run_call(args, display::profile::Informant::new(display::profile::Format::Json))
+	} else if args.flag_flamegraph {
+		run_call(args, display::profile::Informant::new(display::profile::Format::Folded))
 	} else {
 		run_call(args, display::simple::Informant::default())
 	}
@@ -181,6 +189,12 @@ fn run_state_test(args: Args) {
 				} else if args.flag_std_json {
 					let i = display::std_json::Informant::default();
 					info::run_transaction(&name, idx, &spec, &pre, post_root, &env_info, transaction, i)
+				} else if args.flag_profile {
+					let i = display::profile::Informant::new(display::profile::Format::Json);
+					info::run_transaction(&name, idx, &spec, &pre, post_root, &env_info, transaction, i)
+				} else if args.flag_flamegraph {
+					let i = display::profile::Informant::new(display::profile::Format::Folded);
+					info::run_transaction(&name, idx, &spec, &pre, post_root, &env_info, transaction, i)
 				} else {
 					let i = display::simple::Informant::default();
 					info::run_transaction(&name, idx, &spec, &pre, post_root, &env_info, transaction, i)
@@ -234,6 +248,8 @@ struct Args {
 	flag_chain: Option<String>,
 	flag_json: bool,
 	flag_std_json: bool,
+	flag_profile: bool,
+	flag_flamegraph: bool,
 }
 
 impl Args {
@@ -320,6 +336,8 @@ mod tests {
 			"parity-evm",
 			"--json",
 			"--std-json",
+			"--profile",
+			"--flamegraph",
 			"--gas", "1",
 			"--gas-price", "2",
 			"--from", "0000000000000000000000000000000000000003",
@@ -331,6 +349,8 @@ mod tests {
 
 		assert_eq!(args.flag_json, true);
 		assert_eq!(args.flag_std_json, true);
+		assert_eq!(args.flag_profile, true);
+		assert_eq!(args.flag_flamegraph, true);
 		assert_eq!(args.gas(), Ok(1.into()));
 		assert_eq!(args.gas_price(), Ok(2.into()));
 		assert_eq!(args.from(), Ok(3.into()));
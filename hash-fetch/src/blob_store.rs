@@ -0,0 +1,351 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Content-addressed store for downloaded files.
+
+use std::{fs, io};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use ethereum_types::H256;
+use hash::keccak_buffer;
+use parking_lot::Mutex;
+
+/// Directory of files which are not stored yet.
+const TEMP_DIR: &'static str = "tmp";
+
+struct Entry {
+	size: u64,
+	last_used: u64,
+	pins: usize,
+}
+
+#[derive(Default)]
+struct Index {
+	entries: HashMap<H256, Entry>,
+	size: u64,
+	clock: u64,
+}
+
+impl Index {
+	fn insert(&mut self, hash: H256, size: u64) {
+		// a replaced file is still in use by whoever pinned it
+		let pins = self.entries.get(&hash).map_or(0, |entry| entry.pins);
+		self.remove(&hash);
+		self.clock += 1;
+		self.size += size;
+		self.entries.insert(hash, Entry { size, last_used: self.clock, pins });
+	}
+
+	fn touch(&mut self, hash: &H256, pin: bool) -> bool {
+		match self.entries.get_mut(hash) {
+			Some(entry) => {
+				self.clock += 1;
+				entry.last_used = self.clock;
+				if pin {
+					entry.pins += 1;
+				}
+				true
+			},
+			None => false,
+		}
+	}
+
+	fn unpin(&mut self, hash: &H256) {
+		if let Some(entry) = self.entries.get_mut(hash) {
+			entry.pins = entry.pins.saturating_sub(1);
+		}
+	}
+
+	fn remove(&mut self, hash: &H256) {
+		if let Some(entry) = self.entries.remove(hash) {
+			self.size -= entry.size;
+		}
+	}
+
+	fn least_recently_used(&self) -> Option<H256> {
+		self.entries.iter()
+			.filter(|&(_, entry)| entry.pins == 0)
+			.min_by_key(|&(_, entry)| entry.last_used)
+			.map(|(hash, _)| *hash)
+	}
+}
+
+/// Files stored by the keccak hash of their content.
+///
+/// Once the total size of the stored files exceeds the quota,
+/// the least recently used ones are removed. The store is the only owner of the files:
+/// users pin the files they read and unpin them when done, pinned files are never removed.
+pub struct BlobStore {
+	path: PathBuf,
+	quota: u64,
+	index: Mutex<Index>,
+}
+
+impl BlobStore {
+	/// Opens the store in the given directory, keeping at most `quota` bytes.
+	///
+	/// Files already in the directory are indexed, oldest first,
+	/// and files left over in its temporary directory are removed.
+	pub fn open<P: Into<PathBuf>>(path: P, quota: u64) -> io::Result<Self> {
+		let path = path.into();
+		let temp = path.join(TEMP_DIR);
+		fs::create_dir_all(&temp)?;
+		for entry in fs::read_dir(&temp)? {
+			let _ = fs::remove_file(entry?.path());
+		}
+
+		let mut blobs = Vec::new();
+		for entry in fs::read_dir(&path)? {
+			let entry = entry?;
+			let metadata = entry.metadata()?;
+			if !metadata.is_file() {
+				continue;
+			}
+
+			let hash = match entry.file_name().to_str().and_then(|name| name.parse::<H256>().ok()) {
+				Some(hash) => hash,
+				None => continue,
+			};
+			let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+			blobs.push((modified, hash, metadata.len()));
+		}
+		blobs.sort();
+
+		let store = BlobStore {
+			path,
+			quota,
+			index: Default::default(),
+		};
+		{
+			let mut index = store.index.lock();
+			for (_, hash, size) in blobs {
+				index.insert(hash, size);
+			}
+			store.evict(&mut index);
+		}
+		Ok(store)
+	}
+
+	/// Returns the path of the file with the given hash, if it's stored.
+	///
+	/// The file is marked as used, so it's evicted last.
+	/// It must not be modified or removed by the caller.
+	pub fn get(&self, hash: &H256) -> Option<PathBuf> {
+		self.lookup(hash, false)
+	}
+
+	/// Returns the path of the file with the given hash, if it's stored,
+	/// and pins it until `unpin` is called.
+	pub fn pin(&self, hash: &H256) -> Option<PathBuf> {
+		self.lookup(hash, true)
+	}
+
+	/// Releases a file pinned by `pin` or `insert`.
+	/// It may be removed once it's not pinned anymore.
+	pub fn unpin(&self, hash: &H256) {
+		let mut index = self.index.lock();
+		index.unpin(hash);
+		self.evict(&mut index);
+	}
+
+	fn lookup(&self, hash: &H256, pin: bool) -> Option<PathBuf> {
+		let mut index = self.index.lock();
+		if !index.touch(hash, pin) {
+			return None;
+		}
+
+		let path = self.blob_path(hash);
+		if path.exists() {
+			Some(path)
+		} else {
+			index.remove(hash);
+			None
+		}
+	}
+
+	/// Returns a fresh path to write a file to before it's added with `insert`.
+	pub fn temp_path(&self) -> PathBuf {
+		self.path.join(TEMP_DIR).join(random_filename())
+	}
+
+	/// Adds the file at the given path under the given hash and returns its new path.
+	/// The file is pinned until `unpin` is called.
+	///
+	/// Files written to `temp_path` are moved into the store, other files are copied.
+	/// Fails if the content of the file doesn't match the hash.
+	pub fn insert(&self, hash: H256, file: &Path) -> io::Result<PathBuf> {
+		let content_hash = keccak_buffer(&mut io::BufReader::new(fs::File::open(file)?))?;
+		if content_hash != hash {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!("Content hash mismatch: expected {:?}, got {:?}", hash, content_hash),
+			));
+		}
+
+		let path = self.blob_path(&hash);
+		let size = fs::metadata(file)?.len();
+		if file.starts_with(self.path.join(TEMP_DIR)) {
+			fs::rename(file, &path)?;
+		} else {
+			// Copy to a temporary file first, so that a partially written file is never served.
+			let temp = self.temp_path();
+			fs::copy(file, &temp)?;
+			fs::rename(temp, &path)?;
+		}
+
+		let mut index = self.index.lock();
+		index.insert(hash, size);
+		index.touch(&hash, true);
+		self.evict(&mut index);
+		Ok(path)
+	}
+
+	/// Removes the file with the given hash.
+	pub fn remove(&self, hash: &H256) {
+		let mut index = self.index.lock();
+		index.remove(hash);
+		let _ = fs::remove_file(self.blob_path(hash));
+	}
+
+	/// Directory of the stored files.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Total size of the stored files in bytes.
+	pub fn size(&self) -> u64 {
+		self.index.lock().size
+	}
+
+	fn blob_path(&self, hash: &H256) -> PathBuf {
+		self.path.join(format!("{:x}", hash))
+	}
+
+	fn evict(&self, index: &mut Index) {
+		while index.size > self.quota {
+			let hash = match index.least_recently_used() {
+				Some(hash) => hash,
+				None => break,
+			};
+
+			debug!(target: "fetch", "Evicting {:?} from blob store", hash);
+			index.remove(&hash);
+			if let Err(err) = fs::remove_file(self.blob_path(&hash)) {
+				warn!(target: "fetch", "Unable to remove {:?} from blob store: {}", hash, err);
+			}
+		}
+	}
+}
+
+/// Random file name suitable for temporary files.
+pub fn random_filename() -> String {
+	use ::rand::Rng;
+
+	let mut rng = ::rand::OsRng::new().expect("Reliable random source is required to work.");
+	rng.gen_ascii_chars().take(12).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::{self, File};
+	use std::io::Write;
+	use std::path::PathBuf;
+	use hash::keccak;
+	use super::{BlobStore, random_filename};
+
+	fn store_path() -> PathBuf {
+		let mut path = ::std::env::temp_dir();
+		path.push(random_filename());
+		path
+	}
+
+	fn add(store: &BlobStore, content: &[u8]) -> PathBuf {
+		let temp = store.temp_path();
+		File::create(&temp).unwrap().write_all(content).unwrap();
+		let path = store.insert(keccak(content), &temp).unwrap();
+		store.unpin(&keccak(content));
+		path
+	}
+
+	#[test]
+	fn should_store_files_by_hash() {
+		let path = store_path();
+		let store = BlobStore::open(&path, 1024).unwrap();
+
+		let stored = add(&store, b"content");
+		assert_eq!(store.get(&keccak(b"content")), Some(stored.clone()));
+		assert_eq!(store.size(), 7);
+
+		// wrong hash is rejected
+		let temp = store.temp_path();
+		File::create(&temp).unwrap().write_all(b"other").unwrap();
+		assert!(store.insert(keccak(b"content"), &temp).is_err());
+
+		// reopened store finds the file again
+		drop(store);
+		let store = BlobStore::open(&path, 1024).unwrap();
+		assert_eq!(store.get(&keccak(b"content")), Some(stored));
+		assert_eq!(store.size(), 7);
+
+		fs::remove_dir_all(path).unwrap();
+	}
+
+	#[test]
+	fn should_evict_least_recently_used_files() {
+		let path = store_path();
+		let store = BlobStore::open(&path, 10).unwrap();
+
+		let first = add(&store, b"first");
+		add(&store, b"other");
+		// make `other` the least recently used file
+		assert!(store.get(&keccak(b"first")).is_some());
+		add(&store, b"third");
+
+		assert!(store.get(&keccak(b"other")).is_none());
+		assert!(first.exists());
+		assert!(store.get(&keccak(b"third")).is_some());
+		assert_eq!(store.size(), 10);
+
+		fs::remove_dir_all(path).unwrap();
+	}
+
+	#[test]
+	fn should_not_evict_pinned_files() {
+		let path = store_path();
+		let store = BlobStore::open(&path, 10).unwrap();
+
+		let first = add(&store, b"first");
+		assert_eq!(store.pin(&keccak(b"first")), Some(first.clone()));
+		add(&store, b"other");
+		add(&store, b"third");
+
+		// the pinned file is kept over the quota
+		assert!(first.exists());
+		assert!(store.get(&keccak(b"other")).is_none());
+		assert_eq!(store.size(), 10);
+
+		// and can be removed once it's released
+		store.unpin(&keccak(b"first"));
+		add(&store, b"fifth");
+		assert!(!first.exists());
+		assert!(store.get(&keccak(b"third")).is_some());
+		assert_eq!(store.size(), 10);
+
+		fs::remove_dir_all(path).unwrap();
+	}
+}
@@ -24,11 +24,13 @@ use std::path::PathBuf;
 use hash::keccak_buffer;
 use fetch::{self, Fetch};
 use futures_cpupool::CpuPool;
-use futures::{Future, IntoFuture};
+use futures::{future, Future};
+use futures::future::{Either, Loop};
 use parity_reactor::Remote;
 use urlhint::{URLHintContract, URLHint, URLHintResult};
 use registrar::{RegistrarClient, Asynchronous};
 use ethereum_types::H256;
+use blob_store::{BlobStore, random_filename};
 
 /// API for fetching by hash.
 pub trait HashFetch: Send + Sync + 'static {
@@ -38,7 +40,13 @@ pub trait HashFetch: Send + Sync + 'static {
 	/// 2. `on_done` - callback function invoked when the content is ready (or there was error during fetch)
 	///
 	/// This function may fail immediately when fetch cannot be initialized or content cannot be resolved.
+	/// Content kept in a blob store is served from there, and must not be modified or removed.
+	/// It's only guaranteed to exist until `on_done` returns, so it has to be copied to be kept.
 	fn fetch(&self, hash: H256, abort: fetch::Abort, on_done: Box<Fn(Result<PathBuf, Error>) + Send>);
+
+	/// Fetch the detached signature published next to hash-addressed content, i.e. at the content's
+	/// address with a `.sig` suffix. The signature itself is not validated.
+	fn fetch_signature(&self, hash: H256, abort: fetch::Abort, on_done: Box<Fn(Result<PathBuf, Error>) + Send>);
 }
 
 /// Hash-fetching error.
@@ -90,14 +98,20 @@ impl From<io::Error> for Error {
 	}
 }
 
-fn validate_hash(path: PathBuf, hash: H256, body: fetch::BodyReader) -> Result<PathBuf, Error> {
-	// Read the response
+fn save(path: PathBuf, body: fetch::BodyReader) -> Result<PathBuf, Error> {
 	let mut reader = io::BufReader::new(body);
 	let mut writer = io::BufWriter::new(fs::File::create(&path)?);
 	io::copy(&mut reader, &mut writer)?;
 	writer.flush()?;
+	Ok(path)
+}
 
-	// And validate the hash
+fn validate_hash(path: PathBuf, hash: H256, body: fetch::BodyReader) -> Result<PathBuf, Error> {
+	// Read the response and validate the hash
+	check_hash(save(path, body)?, hash)
+}
+
+fn check_hash(path: PathBuf, hash: H256) -> Result<PathBuf, Error> {
 	let mut file_reader = io::BufReader::new(fs::File::open(&path)?);
 	let content_hash = keccak_buffer(&mut file_reader)?;
 	if content_hash != hash {
@@ -107,6 +121,24 @@ fn validate_hash(path: PathBuf, hash: H256, body: fetch::BodyReader) -> Result<P
 	}
 }
 
+/// Returns the stored content if it's still intact, pinned in the store.
+/// Broken content is removed from the store.
+fn from_store(store: &BlobStore, hash: H256) -> Option<PathBuf> {
+	let path = store.pin(&hash)?;
+	match check_hash(path, hash) {
+		Ok(path) => {
+			debug!(target: "fetch", "Serving {:?} from blob store", hash);
+			Some(path)
+		},
+		Err(err) => {
+			warn!(target: "fetch", "Discarding stored content of {:?}: {:?}", hash, err);
+			store.unpin(&hash);
+			store.remove(&hash);
+			None
+		},
+	}
+}
+
 /// Default Hash-fetching client using on-chain contract to resolve hashes to URLs.
 pub struct Client<F: Fetch + 'static = fetch::Client> {
 	pool: CpuPool,
@@ -114,6 +146,8 @@ pub struct Client<F: Fetch + 'static = fetch::Client> {
 	fetch: F,
 	remote: Remote,
 	random_path: Arc<Fn() -> PathBuf + Sync + Send>,
+	mirrors: Vec<String>,
+	store: Option<Arc<BlobStore>>,
 }
 
 impl<F: Fetch + 'static> Client<F> {
@@ -125,69 +159,135 @@ impl<F: Fetch + 'static> Client<F> {
 			fetch: fetch,
 			remote: remote,
 			random_path: Arc::new(random_temp_path),
+			mirrors: Vec::new(),
+			store: None,
 		}
 	}
+
+	/// Fall back to the given mirrors, in order, if content can't be fetched from the URL
+	/// registered in the contract. Content is requested from `<mirror>/<hash>`.
+	pub fn with_mirrors(mut self, mirrors: Vec<String>) -> Self {
+		self.mirrors = mirrors;
+		self
+	}
+
+	/// Download content into the given blob store, so that it's only fetched once.
+	pub fn with_store(mut self, store: Arc<BlobStore>) -> Self {
+		self.store = Some(store);
+		self
+	}
 }
 
 impl<F: Fetch + 'static> HashFetch for Client<F> {
 	fn fetch(&self, hash: H256, abort: fetch::Abort, on_done: Box<Fn(Result<PathBuf, Error>) + Send>) {
-		debug!(target: "fetch", "Fetching: {:?}", hash);
+		self.fetch_from_urls(hash, "", abort, on_done)
+	}
+
+	fn fetch_signature(&self, hash: H256, abort: fetch::Abort, on_done: Box<Fn(Result<PathBuf, Error>) + Send>) {
+		self.fetch_from_urls(hash, ".sig", abort, on_done)
+	}
+}
+
+impl<F: Fetch + 'static> Client<F> {
+	/// Fetches the content the hash resolves to, with `suffix` appended to its addresses.
+	/// Without a suffix the content itself is fetched: it's validated against the hash and kept in the store.
+	fn fetch_from_urls(&self, hash: H256, suffix: &'static str, abort: fetch::Abort, on_done: Box<Fn(Result<PathBuf, Error>) + Send>) {
+		debug!(target: "fetch", "Fetching: {:?}{}", hash, suffix);
 
+		let content = suffix.is_empty();
 		let random_path = self.random_path.clone();
 		let remote_fetch = self.fetch.clone();
 		let pool = self.pool.clone();
-		let future = self.contract.resolve(hash)
-			.map_err(|e| { warn!("Error resolving URL: {}", e); Error::NoResolution })
-			.and_then(|maybe_url| maybe_url.ok_or(Error::NoResolution))
-			.map(|content| match content {
-					URLHintResult::Dapp(dapp) => {
-						dapp.url()
-					},
-					URLHintResult::GithubDapp(content) => {
-						content.url
-					},
-					URLHintResult::Content(content) => {
-						content.url
-					},
-			})
-			.into_future()
-			.and_then(move |url| {
-				debug!(target: "fetch", "Resolved {:?} to {:?}. Fetching...", hash, url);
-				remote_fetch.get(&url, abort).from_err()
+		let store = if content { self.store.clone() } else { None };
+		let cached = store.clone();
+		let pinned = store.clone();
+		let mirrors = self.mirrors.iter()
+			.map(|mirror| format!("{}/{:x}{}", mirror.trim_right_matches('/'), hash, suffix))
+			.collect::<Vec<_>>();
+		let resolve = self.contract.resolve(hash);
+
+		let future = self.pool.spawn_fn(move || Ok(cached.and_then(|store| from_store(&store, hash))))
+			.and_then(move |from_cache| match from_cache {
+				Some(path) => Either::A(future::ok(path)),
+				None => Either::B(resolve
+					.then(move |res| {
+						let mut urls = match res {
+							Ok(Some(URLHintResult::Dapp(dapp))) => vec![dapp.url() + suffix],
+							Ok(Some(URLHintResult::GithubDapp(content))) => vec![content.url + suffix],
+							Ok(Some(URLHintResult::Content(content))) => vec![content.url + suffix],
+							Ok(None) => vec![],
+							Err(e) => { warn!("Error resolving URL: {}", e); vec![] },
+						};
+						urls.extend(mirrors);
+						Ok::<_, Error>(urls)
+					})
+					.and_then(move |urls| future::loop_fn((urls.into_iter(), Error::NoResolution), move |(mut urls, last_error)| {
+						let url = match urls.next() {
+							Some(url) => url,
+							None => return Either::A(future::err(last_error)),
+						};
+						debug!(target: "fetch", "Resolved {:?} to {:?}. Fetching...", hash, url);
+						let random_path = random_path.clone();
+						let store = store.clone();
+						let pool = pool.clone();
+						Either::B(remote_fetch.get(&url, abort.clone()).from_err()
+							.and_then(move |response| {
+								if !response.is_success() {
+									Err(Error::InvalidStatus)
+								} else {
+									Ok(response)
+								}
+							})
+							.and_then(move |response| pool.spawn_fn(move || {
+								let path = match store {
+									Some(ref store) => store.temp_path(),
+									None => random_path(),
+								};
+								let body = fetch::BodyReader::new(response);
+								let res = match content {
+									true => {
+										debug!(target: "fetch", "Content fetched, validating hash ({:?})", hash);
+										validate_hash(path.clone(), hash, body)
+									},
+									false => save(path.clone(), body),
+								}
+									.and_then(|path| match store {
+										Some(ref store) => Ok(store.insert(hash, &path)?),
+										None => Ok(path),
+									});
+								if let Err(ref err) = res {
+									trace!(target: "fetch", "Error: {:?}", err);
+									// Remove temporary file in case of error
+									let _ = fs::remove_file(&path);
+								}
+								res
+							}))
+							.then(move |res| match res {
+								Ok(path) => Ok(Loop::Break(path)),
+								Err(err) => {
+									warn!(target: "fetch", "Unable to fetch {:?} from {}: {:?}", hash, url, err);
+									Ok(Loop::Continue((urls, err)))
+								},
+							}))
+					}))),
 			})
-			.and_then(move |response| {
-				if !response.is_success() {
-					Err(Error::InvalidStatus)
-				} else {
-					Ok(response)
+			.then(move |res| {
+				// stored content is pinned until the callback is done with it
+				let unpin = res.is_ok();
+				on_done(res);
+				if let (true, Some(store)) = (unpin, pinned) {
+					store.unpin(&hash);
 				}
-			})
-			.and_then(move |response| pool.spawn_fn(move || {
-				debug!(target: "fetch", "Content fetched, validating hash ({:?})", hash);
-				let path = random_path();
-				let res = validate_hash(path.clone(), hash, fetch::BodyReader::new(response));
-				if let Err(ref err) = res {
-					trace!(target: "fetch", "Error: {:?}", err);
-					// Remove temporary file in case of error
-					let _ = fs::remove_file(&path);
-				}
-				res
-			}))
-			.then(move |res| { on_done(res); Ok(()) as Result<(), ()> });
+				Ok(()) as Result<(), ()>
+			});
 
 		self.remote.spawn(future);
 	}
 }
 
 fn random_temp_path() -> PathBuf {
-	use ::rand::Rng;
-	use ::std::env;
-
-	let mut rng = ::rand::OsRng::new().expect("Reliable random source is required to work.");
-	let file: String = rng.gen_ascii_chars().take(12).collect();
-
-	let mut path = env::temp_dir();
-	path.push(file);
+	let mut path = ::std::env::temp_dir();
+	path.push(random_filename());
 	path
 }
 
@@ -195,11 +295,15 @@ fn random_temp_path() -> PathBuf {
 mod tests {
 	use fake_fetch::FakeFetch;
 	use rustc_hex::FromHex;
+	use std::fs::{self, File};
+	use std::io::Read;
 	use std::sync::{Arc, mpsc};
+	use ethereum_types::H256;
 	use parking_lot::Mutex;
 	use futures_cpupool::CpuPool;
 	use parity_reactor::Remote;
 	use urlhint::tests::{FakeRegistrar, URLHINT};
+	use blob_store::BlobStore;
 	use super::{Error, Client, HashFetch, random_temp_path};
 
 	fn registrar() -> FakeRegistrar {
@@ -287,4 +391,93 @@ mod tests {
 		let result = rx.recv().unwrap();
 		assert!(result.is_ok(), "Should return path, got: {:?}", result);
 	}
+
+	#[test]
+	fn should_fail_over_to_mirrors() {
+		// given
+		let contract = Arc::new(FakeRegistrar::new());
+		let fetch = FakeFetch::new(Some(1));
+		let client = Client::with_fetch(contract.clone(), CpuPool::new(1), fetch, Remote::new_sync())
+			.with_mirrors(vec!["invalid url".into(), "https://mirror.parity.io/".into()]);
+
+		// when
+		let (tx, rx) = mpsc::channel();
+		client.fetch("0x2be00befcf008bc0e7d9cdefc194db9c75352e8632f48498b5a6bfce9f02c88e".into(),
+			Default::default(),
+			Box::new(move |result| { tx.send(result).unwrap(); }));
+
+		// then
+		let result = rx.recv().unwrap();
+		assert!(result.is_ok(), "Should return path, got: {:?}", result);
+	}
+
+	#[test]
+	fn should_fetch_signature_without_validating_hash() {
+		// given
+		let contract = Arc::new(FakeRegistrar::new());
+		let fetch = FakeFetch::new(Some(1));
+		let client = Client::with_fetch(contract.clone(), CpuPool::new(1), fetch, Remote::new_sync())
+			.with_mirrors(vec!["https://mirror.parity.io/".into()]);
+
+		// when
+		let (tx, rx) = mpsc::channel();
+		client.fetch_signature(2.into(), Default::default(), Box::new(move |result| { tx.send(result).unwrap(); }));
+
+		// then
+		let path = rx.recv().unwrap().unwrap();
+		let mut content = String::new();
+		File::open(&path).unwrap().read_to_string(&mut content).unwrap();
+		assert_eq!(content, "Some content");
+		let _ = fs::remove_file(path);
+	}
+
+	#[test]
+	fn should_serve_verified_content_from_store() {
+		// given
+		let hash: H256 = "0x2be00befcf008bc0e7d9cdefc194db9c75352e8632f48498b5a6bfce9f02c88e".into();
+		let cache = random_temp_path();
+		let store = Arc::new(BlobStore::open(&cache, 1024).unwrap());
+		let client = Client::with_fetch(Arc::new(registrar()), CpuPool::new(1), FakeFetch::new(Some(1)), Remote::new_sync())
+			.with_store(store.clone());
+		let (tx, rx) = mpsc::channel();
+		client.fetch(hash, Default::default(), Box::new(move |result| { tx.send(result).unwrap(); }));
+		assert!(rx.recv().unwrap().is_ok());
+
+		// when
+		let client = Client::with_fetch(Arc::new(FakeRegistrar::new()), CpuPool::new(1), FakeFetch::new(None::<usize>), Remote::new_sync())
+			.with_store(store.clone());
+		let (tx, rx) = mpsc::channel();
+		client.fetch(hash, Default::default(), Box::new(move |result| { tx.send(result).unwrap(); }));
+
+		// then
+		let path = rx.recv().unwrap().unwrap();
+		let mut content = String::new();
+		File::open(&path).unwrap().read_to_string(&mut content).unwrap();
+		assert_eq!(content, "Some content");
+		assert_eq!(store.get(&hash), Some(path));
+		let _ = fs::remove_dir_all(cache);
+	}
+
+	#[test]
+	fn should_keep_stored_content_until_callback_is_done() {
+		// given
+		let hash: H256 = "0x2be00befcf008bc0e7d9cdefc194db9c75352e8632f48498b5a6bfce9f02c88e".into();
+		let cache = random_temp_path();
+		// content doesn't fit the quota
+		let store = Arc::new(BlobStore::open(&cache, 1).unwrap());
+		let client = Client::with_fetch(Arc::new(registrar()), CpuPool::new(1), FakeFetch::new(Some(1)), Remote::new_sync())
+			.with_store(store.clone());
+
+		// when
+		let (tx, rx) = mpsc::channel();
+		client.fetch(hash, Default::default(), Box::new(move |result| {
+			let mut content = String::new();
+			File::open(result.unwrap()).unwrap().read_to_string(&mut content).unwrap();
+			tx.send(content).unwrap();
+		}));
+
+		// then
+		assert_eq!(rx.recv().unwrap(), "Some content");
+		let _ = fs::remove_dir_all(cache);
+	}
 }
@@ -29,6 +29,7 @@ extern crate futures_cpupool;
 extern crate keccak_hash as hash;
 extern crate mime;
 extern crate mime_guess;
+extern crate parking_lot;
 extern crate parity_reactor;
 extern crate rand;
 extern crate rustc_hex;
@@ -41,15 +42,15 @@ extern crate ethabi_derive;
 #[macro_use]
 extern crate ethabi_contract;
 #[cfg(test)]
-extern crate parking_lot;
-#[cfg(test)]
 extern crate hyper;
 #[cfg(test)]
 extern crate fake_fetch;
 
+mod blob_store;
 mod client;
 
 pub mod urlhint;
 
+pub use blob_store::BlobStore;
 pub use client::{HashFetch, Client, Error};
 pub use fetch::Abort;
@@ -39,6 +39,11 @@ pub enum Error {
 	TransactionNotFound,
 	StateRootNotFound,
 	ContractNotFound,
+	UnsupportedFormat,
+	BlockTooLarge,
+	NotPinned,
+	StoreFull,
+	StoreFailed,
 }
 
 /// Convert Error into Out, handy when switching from Rust's Result-based
@@ -55,6 +60,11 @@ impl From<Error> for Out {
 			TransactionNotFound => Out::NotFound("Transaction not found"),
 			StateRootNotFound => Out::NotFound("State root not found"),
 			ContractNotFound => Out::NotFound("Contract not found"),
+			UnsupportedFormat => Out::Bad("Block format not supported"),
+			BlockTooLarge => Out::TooLarge("Block too large"),
+			NotPinned => Out::NotFound("Not pinned"),
+			StoreFull => Out::TooLarge("Store is full"),
+			StoreFailed => Out::Failed("Failed to write to the store"),
 		}
 	}
 }
@@ -16,6 +16,7 @@
 
 extern crate multihash;
 extern crate cid;
+extern crate parking_lot;
 extern crate unicase;
 
 extern crate rlp;
@@ -27,25 +28,28 @@ extern crate jsonrpc_http_server as http;
 
 pub mod error;
 mod route;
+mod store;
 
 use std::thread;
 use std::sync::{mpsc, Arc};
 use std::net::{SocketAddr, IpAddr};
 
-use core::futures::future::{self, FutureResult};
-use core::futures::{self, Future};
+use core::futures::future;
+use core::futures::{self, Future, Stream};
 use ethcore::client::BlockChainClient;
 use http::hyper::header::{self, Vary, ContentType};
 use http::hyper::{Method, StatusCode};
 use http::hyper::{self, server};
 use unicase::Ascii;
 
-use error::ServerError;
-use route::Out;
+use error::{Error, ServerError};
+use route::{Out, MAX_BLOCK_SIZE};
 
 pub use http::{AccessControlAllowOrigin, Host, DomainsValidation};
+pub use store::Store;
 
 /// Request/response handler
+#[derive(Clone)]
 pub struct IpfsHandler {
 	/// Allowed CORS domains
 	cors_domains: Option<Vec<AccessControlAllowOrigin>>,
@@ -53,6 +57,12 @@ pub struct IpfsHandler {
 	allowed_hosts: Option<Vec<Host>>,
 	/// Reference to the Blockchain Client
 	client: Arc<BlockChainClient>,
+	/// Blocks put or pinned through the API
+	store: Arc<Store>,
+	/// Prefix of all routes, empty or starting with `/`
+	path_prefix: String,
+	/// Largest response body served, in bytes
+	max_response_size: usize,
 }
 
 impl IpfsHandler {
@@ -60,70 +70,122 @@ impl IpfsHandler {
 		&*self.client
 	}
 
-	pub fn new(cors: DomainsValidation<AccessControlAllowOrigin>, hosts: DomainsValidation<Host>, client: Arc<BlockChainClient>) -> Self {
+	pub fn new(
+		cors: DomainsValidation<AccessControlAllowOrigin>,
+		hosts: DomainsValidation<Host>,
+		client: Arc<BlockChainClient>,
+		store: Arc<Store>,
+		path_prefix: &str,
+		max_response_size: usize,
+	) -> Self {
+		let path_prefix = path_prefix.trim_matches('/');
+
 		IpfsHandler {
 			cors_domains: cors.into(),
 			allowed_hosts: hosts.into(),
 			client: client,
+			store: store,
+			path_prefix: if path_prefix.is_empty() { String::new() } else { format!("/{}", path_prefix) },
+			max_response_size: max_response_size,
 		}
 	}
-	pub fn on_request(&self, req: hyper::Request) -> (Option<header::AccessControlAllowOrigin>, Out) {
+
+	pub fn on_request(&self, req: hyper::Request) -> Box<Future<Item=(Option<header::AccessControlAllowOrigin>, Out), Error=hyper::Error>> {
 		match *req.method() {
 			Method::Get | Method::Post => {},
-			_ => return (None, Out::Bad("Invalid Request")),
+			_ => return Box::new(future::ok((None, Out::Bad("Invalid Request")))),
 		}
 
 		if !http::is_host_allowed(&req, &self.allowed_hosts) {
-			return (None, Out::Bad("Disallowed Host header"));
+			return Box::new(future::ok((None, Out::Bad("Disallowed Host header"))));
 		}
 
 		let cors_header = http::cors_header(&req, &self.cors_domains);
 		if cors_header == http::CorsHeader::Invalid {
-			return (None, Out::Bad("Disallowed Origin header"));
+			return Box::new(future::ok((None, Out::Bad("Disallowed Origin header"))));
 		}
 
-		let path = req.uri().path();
-		let query = req.uri().query();
-		return (cors_header.into(), self.route(path, query));
+		let path = req.uri().path().to_owned();
+		let query = req.uri().query().map(ToOwned::to_owned);
+		let handler = self.clone();
+
+		// `block/put` takes the block as request body, stop reading it once it's too large
+		let body = req.body().map_err(BodyError::Hyper).fold(Vec::new(), |mut body, chunk| {
+			if body.len() + chunk.len() > MAX_BLOCK_SIZE {
+				return Err(BodyError::TooLarge);
+			}
+			body.extend_from_slice(&chunk);
+			Ok(body)
+		});
+
+		Box::new(body.then(move |body| match body {
+			Ok(body) => Ok((cors_header.into(), handler.route(&path, query.as_ref().map(String::as_str), &body))),
+			Err(BodyError::TooLarge) => Ok((cors_header.into(), Error::BlockTooLarge.into())),
+			Err(BodyError::Hyper(err)) => Err(err),
+		}))
 	}
 }
 
+/// Error reading the request body.
+enum BodyError {
+	Hyper(hyper::Error),
+	TooLarge,
+}
+
 impl server::Service for IpfsHandler {
 	type Request = hyper::Request;
 	type Response = hyper::Response;
 	type Error = hyper::Error;
-	type Future = FutureResult<hyper::Response, hyper::Error>;
+	type Future = Box<Future<Item=hyper::Response, Error=hyper::Error>>;
 
 	fn call(&self, request: Self::Request) -> Self::Future {
-		let (cors_header, out) = self.on_request(request);
-
-		let mut res = match out {
-			Out::OctetStream(bytes) => {
-				hyper::Response::new()
-					.with_status(StatusCode::Ok)
-					.with_header(ContentType::octet_stream())
-					.with_body(bytes)
-			},
-			Out::NotFound(reason) => {
-				hyper::Response::new()
-					.with_status(StatusCode::NotFound)
-					.with_header(ContentType::plaintext())
-					.with_body(reason)
-			},
-			Out::Bad(reason) => {
-				hyper::Response::new()
-					.with_status(StatusCode::BadRequest)
-					.with_header(ContentType::plaintext())
-					.with_body(reason)
-			}
-		};
+		Box::new(self.on_request(request).map(|(cors_header, out)| {
+			let mut res = match out {
+				Out::OctetStream(bytes) => {
+					hyper::Response::new()
+						.with_status(StatusCode::Ok)
+						.with_header(ContentType::octet_stream())
+						.with_body(bytes)
+				},
+				Out::Json(json) => {
+					hyper::Response::new()
+						.with_status(StatusCode::Ok)
+						.with_header(ContentType::json())
+						.with_body(json)
+				},
+				Out::NotFound(reason) => {
+					hyper::Response::new()
+						.with_status(StatusCode::NotFound)
+						.with_header(ContentType::plaintext())
+						.with_body(reason)
+				},
+				Out::Bad(reason) => {
+					hyper::Response::new()
+						.with_status(StatusCode::BadRequest)
+						.with_header(ContentType::plaintext())
+						.with_body(reason)
+				},
+				Out::TooLarge(reason) => {
+					hyper::Response::new()
+						.with_status(StatusCode::PayloadTooLarge)
+						.with_header(ContentType::plaintext())
+						.with_body(reason)
+				},
+				Out::Failed(reason) => {
+					hyper::Response::new()
+						.with_status(StatusCode::InternalServerError)
+						.with_header(ContentType::plaintext())
+						.with_body(reason)
+				},
+			};
 
-		if let Some(cors_header) = cors_header {
-			res.headers_mut().set(cors_header);
-			res.headers_mut().set(Vary::Items(vec![Ascii::new("Origin".into())]));
-		}
+			if let Some(cors_header) = cors_header {
+				res.headers_mut().set(cors_header);
+				res.headers_mut().set(Vary::Items(vec![Ascii::new("Origin".into())]));
+			}
 
-		future::ok(res)
+			res
+		}))
 	}
 }
 
@@ -155,6 +217,9 @@ pub fn start_server(
 	interface: String,
 	cors: DomainsValidation<AccessControlAllowOrigin>,
 	hosts: DomainsValidation<Host>,
+	path_prefix: String,
+	max_response_size: usize,
+	store: Store,
 	client: Arc<BlockChainClient>
 ) -> Result<Listening, ServerError> {
 
@@ -163,12 +228,13 @@ pub fn start_server(
 	let hosts: Option<Vec<_>> = hosts.into();
 	let hosts: DomainsValidation<_> = hosts.map(move |hosts| include_current_interface(hosts, interface, port)).into();
 
+	let store = Arc::new(store);
 	let (close, shutdown_signal) = futures::sync::oneshot::channel::<()>();
 	let (tx, rx) = mpsc::sync_channel(1);
 	let thread = thread::spawn(move || {
 		let send = |res| tx.send(res).expect("rx end is never dropped; qed");
 		let server = match server::Http::new().bind(&addr, move || {
-			Ok(IpfsHandler::new(cors.clone(), hosts.clone(), client.clone()))
+			Ok(IpfsHandler::new(cors.clone(), hosts.clone(), client.clone(), store.clone(), &path_prefix, max_response_size))
 		}) {
 			Ok(server) => {
 				send(Ok(()));
@@ -16,7 +16,7 @@
 
 use {rlp, multihash, IpfsHandler};
 use error::{Error, Result};
-use cid::{ToCid, Codec};
+use cid::{Cid, ToCid, Codec, Version};
 
 use multihash::Hash;
 use ethereum_types::H256;
@@ -25,25 +25,45 @@ use ethcore::client::{BlockId, TransactionId};
 
 type Reason = &'static str;
 
+/// Largest block accepted by `block/put`, same as go-ipfs.
+pub const MAX_BLOCK_SIZE: usize = 1024 * 1024;
+
 /// Keeps the state of the response to send out
 #[derive(Debug, PartialEq)]
 pub enum Out {
 	OctetStream(Bytes),
+	Json(String),
 	NotFound(Reason),
 	Bad(Reason),
+	TooLarge(Reason),
+	Failed(Reason),
 }
 
 impl IpfsHandler {
 	/// Route path + query string to a specialized method
-	pub fn route(&self, path: &str, query: Option<&str>) -> Out {
-		match path {
-			"/api/v0/block/get" => {
-				let arg = query.and_then(|q| get_param(q, "arg")).unwrap_or("");
-
-				self.route_cid(arg).unwrap_or_else(Into::into)
+	pub fn route(&self, path: &str, query: Option<&str>, body: &[u8]) -> Out {
+		let path = match strip_prefix(path, &self.path_prefix) {
+			Some(path) => path,
+			None => return Out::NotFound("Route not found"),
+		};
+		let arg = query.and_then(|q| get_param(q, "arg")).unwrap_or("");
+
+		let out = match path {
+			"/api/v0/block/get" => self.route_cid(arg).unwrap_or_else(Into::into),
+			"/api/v0/block/put" => {
+				let format = query.and_then(|q| get_param(q, "format")).unwrap_or("raw");
+
+				self.put(format, body).unwrap_or_else(Into::into)
 			},
+			"/api/v0/pin/add" => self.pin(arg).unwrap_or_else(Into::into),
+			"/api/v0/pin/rm" => self.unpin(arg).unwrap_or_else(Into::into),
 
 			_ => Out::NotFound("Route not found")
+		};
+
+		match out {
+			Out::OctetStream(ref data) if data.len() > self.max_response_size => Out::TooLarge("Response too large"),
+			out => out,
 		}
 	}
 
@@ -51,12 +71,11 @@ impl IpfsHandler {
 	/// route further by the CID's codec.
 	fn route_cid(&self, cid: &str) -> Result<Out> {
 		let cid = cid.to_cid()?;
+		let hash = cid_hash(&cid)?;
 
-		let mh = multihash::decode(&cid.hash)?;
-
-		if mh.alg != Hash::Keccak256 { return Err(Error::UnsupportedHash); }
-
-		let hash: H256 = mh.digest.into();
+		if let Some(data) = self.store.get(&hash) {
+			return Ok(Out::OctetStream(data));
+		}
 
 		match cid.codec {
 			Codec::EthereumBlock => self.block(hash),
@@ -104,6 +123,106 @@ impl IpfsHandler {
 
 		Ok(Out::OctetStream(data))
 	}
+
+	/// Store a raw block of the given format, returning its CID.
+	fn put(&self, format: &str, data: &[u8]) -> Result<Out> {
+		let codec = match format {
+			"raw" => Codec::Raw,
+			"eth-block" => Codec::EthereumBlock,
+			"eth-block-list" => Codec::EthereumBlockList,
+			"eth-tx" => Codec::EthereumTx,
+			"eth-tx-receipt" => Codec::EthereumTxReceipt,
+			"eth-state-trie" => Codec::EthereumStateTrie,
+			_ => return Err(Error::UnsupportedFormat),
+		};
+
+		if data.len() > MAX_BLOCK_SIZE { return Err(Error::BlockTooLarge); }
+
+		let (cid, hash) = block_cid(codec, data)?;
+		self.store.pin(hash, vec![(hash, data.to_vec())])?;
+
+		Ok(Out::Json(format!(r#"{{"Key":"{}","Size":{}}}"#, cid, data.len())))
+	}
+
+	/// Pin chain data by CID, so it's served from the store. Pinning a block also pins
+	/// its transactions and receipts.
+	fn pin(&self, cid: &str) -> Result<Out> {
+		let cid = cid.to_cid()?;
+		let hash = cid_hash(&cid)?;
+		let mut pins = Vec::new();
+		let mut blocks = Vec::new();
+
+		if cid.codec == Codec::EthereumBlock {
+			let block_id = BlockId::Hash(hash);
+			let header = self.client().block_header(block_id).ok_or(Error::BlockNotFound)?;
+			let body = self.client().block_body(block_id).ok_or(Error::BlockNotFound)?;
+
+			let mut add = |codec, data: Bytes| -> Result<()> {
+				let (cid, hash) = block_cid(codec, &data)?;
+				pins.push(cid.to_string());
+				blocks.push((hash, data));
+				Ok(())
+			};
+
+			add(Codec::EthereumBlock, header.into_inner())?;
+			for tx in body.transactions_rlp().iter() {
+				add(Codec::EthereumTx, tx.as_raw().to_vec())?;
+			}
+			// receipts of ancient blocks may be missing after warp sync
+			if let Some(receipts) = self.client().block_receipts(&hash) {
+				for receipt in rlp::Rlp::new(&receipts).iter() {
+					add(Codec::EthereumTxReceipt, receipt.as_raw().to_vec())?;
+				}
+			}
+		} else if let Out::OctetStream(data) = self.route_cid(&cid.to_string())? {
+			pins.push(cid.to_string());
+			blocks.push((hash, data));
+		}
+
+		self.store.pin(hash, blocks)?;
+
+		let pins = pins.iter().map(|cid| format!(r#""{}""#, cid)).collect::<Vec<_>>();
+		Ok(Out::Json(format!(r#"{{"Pins":[{}]}}"#, pins.join(","))))
+	}
+
+	/// Remove a pin by CID, along with the transactions and receipts pinned with a block.
+	fn unpin(&self, cid: &str) -> Result<Out> {
+		let cid = cid.to_cid()?;
+		let hash = cid_hash(&cid)?;
+
+		if !self.store.unpin(&hash)? { return Err(Error::NotPinned); }
+
+		Ok(Out::Json(format!(r#"{{"Pins":["{}"]}}"#, cid)))
+	}
+}
+
+/// Get the CID of data of the given codec, along with its Keccak-256 hash.
+fn block_cid(codec: Codec, data: &[u8]) -> Result<(Cid, H256)> {
+	let cid = Cid::new(codec, Version::V1, &multihash::encode(Hash::Keccak256, data)?);
+	let hash = cid_hash(&cid)?;
+
+	Ok((cid, hash))
+}
+
+/// Get the Keccak-256 hash from a Content ID.
+fn cid_hash(cid: &Cid) -> Result<H256> {
+	let mh = multihash::decode(&cid.hash)?;
+
+	if mh.alg != Hash::Keccak256 { return Err(Error::UnsupportedHash); }
+
+	Ok(mh.digest.into())
+}
+
+/// Strip the configured prefix from a request path.
+fn strip_prefix<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+	if !path.starts_with(prefix) {
+		return None;
+	}
+
+	match &path[prefix.len()..] {
+		rest if rest.starts_with('/') => Some(rest),
+		_ => None,
+	}
 }
 
 /// Get a query parameter's value by name.
@@ -117,10 +236,11 @@ fn get_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
 mod tests {
 	use std::sync::Arc;
 	use super::*;
-	use ethcore::client::TestBlockChainClient;
+	use ethcore::client::{TestBlockChainClient, EachBlockWith};
+	use store::Store;
 
 	fn get_mocked_handler() -> IpfsHandler {
-		IpfsHandler::new(None.into(), None.into(), Arc::new(TestBlockChainClient::new()))
+		IpfsHandler::new(None.into(), None.into(), Arc::new(TestBlockChainClient::new()), Default::default(), "", 1024 * 1024)
 	}
 
 	#[test]
@@ -138,6 +258,27 @@ mod tests {
 		assert_eq!(get_param("bar&foo", "foo"), None);
 	}
 
+	#[test]
+	fn test_strip_prefix() {
+		assert_eq!(strip_prefix("/api/v0/block/get", ""), Some("/api/v0/block/get"));
+		assert_eq!(strip_prefix("/ipfs/api/v0/block/get", "/ipfs"), Some("/api/v0/block/get"));
+		assert_eq!(strip_prefix("/ipfsapi/v0/block/get", "/ipfs"), None);
+		assert_eq!(strip_prefix("/api/v0/block/get", "/ipfs"), None);
+	}
+
+	#[test]
+	fn route_with_prefix() {
+		let handler = IpfsHandler::new(None.into(), None.into(), Arc::new(TestBlockChainClient::new()), Default::default(), "/ipfs/", 2);
+
+		assert_eq!(handler.route("/api/v0/block/put", None, b"foo"), Out::NotFound("Route not found"));
+		let cid = Cid::new(Codec::Raw, Version::V1, &multihash::encode(Hash::Keccak256, b"foo").unwrap()).to_string();
+		assert_eq!(handler.route("/ipfs/api/v0/block/put", None, b"foo"), Out::Json(format!(r#"{{"Key":"{}","Size":3}}"#, cid)));
+
+		let out = handler.route("/ipfs/api/v0/block/get", Some(&format!("arg={}", cid)), &[]);
+
+		assert_eq!(out, Out::TooLarge("Response too large"));
+	}
+
 	#[test]
 	fn cid_route_block() {
 		let handler = get_mocked_handler();
@@ -212,7 +353,7 @@ mod tests {
 	fn route_block() {
 		let handler = get_mocked_handler();
 
-		let out = handler.route("/api/v0/block/get", Some("arg=z43AaGF5tmkT9SEX6urrhwpEW5ZSaACY73Vw357ZXTsur2fR8BM"));
+		let out = handler.route("/api/v0/block/get", Some("arg=z43AaGF5tmkT9SEX6urrhwpEW5ZSaACY73Vw357ZXTsur2fR8BM"), &[]);
 
 		assert_eq!(out, Out::NotFound("Block not found"));
 	}
@@ -221,7 +362,7 @@ mod tests {
 	fn route_block_missing_query() {
 		let handler = get_mocked_handler();
 
-		let out = handler.route("/api/v0/block/get", None);
+		let out = handler.route("/api/v0/block/get", None, &[]);
 
 		assert_eq!(out, Out::Bad("CID parsing failed"));
 	}
@@ -230,16 +371,69 @@ mod tests {
 	fn route_block_invalid_query() {
 		let handler = get_mocked_handler();
 
-		let out = handler.route("/api/v0/block/get", Some("arg=foobarz43AaGF5tmkT9SEX6urrhwpEW5ZSaACY73Vw357ZXTsur2fR8BM"));
+		let out = handler.route("/api/v0/block/get", Some("arg=foobarz43AaGF5tmkT9SEX6urrhwpEW5ZSaACY73Vw357ZXTsur2fR8BM"), &[]);
 
 		assert_eq!(out, Out::Bad("CID parsing failed"));
 	}
 
+	#[test]
+	fn route_block_put() {
+		let handler = get_mocked_handler();
+		let cid = Cid::new(Codec::Raw, Version::V1, &multihash::encode(Hash::Keccak256, b"foo").unwrap()).to_string();
+
+		let out = handler.route("/api/v0/block/put", None, b"foo");
+
+		assert_eq!(out, Out::Json(format!(r#"{{"Key":"{}","Size":3}}"#, cid)));
+		assert_eq!(handler.route("/api/v0/block/get", Some(&format!("arg={}", cid)), &[]), Out::OctetStream(b"foo".to_vec()));
+	}
+
+	#[test]
+	fn route_block_put_invalid() {
+		let handler = get_mocked_handler();
+
+		assert_eq!(handler.route("/api/v0/block/put", Some("format=bitcoin-block"), b"foo"), Out::Bad("Block format not supported"));
+		assert_eq!(handler.route("/api/v0/block/put", None, &vec![0u8; MAX_BLOCK_SIZE + 1]), Out::TooLarge("Block too large"));
+	}
+
+	#[test]
+	fn route_pin_block() {
+		let client = Arc::new(TestBlockChainClient::new());
+		client.add_blocks(1, EachBlockWith::Transaction);
+		let handler = IpfsHandler::new(None.into(), None.into(), client.clone(), Default::default(), "", 1024 * 1024);
+		let header = handler.client().block_header(BlockId::Number(1)).unwrap().into_inner();
+		let body = handler.client().block_body(BlockId::Number(1)).unwrap();
+		let tx = body.transactions_rlp().at(0).unwrap().as_raw().to_vec();
+		let (cid, hash) = block_cid(Codec::EthereumBlock, &header).unwrap();
+		let (tx_cid, tx_hash) = block_cid(Codec::EthereumTx, &tx).unwrap();
+		let query = format!("arg={}", cid);
+
+		// receipts are pinned too, if the client has them
+		match handler.route("/api/v0/pin/add", Some(&query), &[]) {
+			Out::Json(json) => assert!(json.starts_with(&format!(r#"{{"Pins":["{}","{}""#, cid, tx_cid))),
+			out => panic!("Unexpected response: {:?}", out),
+		}
+		assert_eq!(handler.store.get(&hash), Some(header));
+		assert_eq!(handler.store.get(&tx_hash), Some(tx));
+
+		assert_eq!(handler.route("/api/v0/pin/rm", Some(&query), &[]), Out::Json(format!(r#"{{"Pins":["{}"]}}"#, cid)));
+		assert_eq!(handler.store.get(&hash), None);
+		assert_eq!(handler.store.get(&tx_hash), None);
+		assert_eq!(handler.route("/api/v0/pin/rm", Some(&query), &[]), Out::NotFound("Not pinned"));
+	}
+
+	#[test]
+	fn route_block_put_store_full() {
+		let handler = IpfsHandler::new(None.into(), None.into(), Arc::new(TestBlockChainClient::new()), Arc::new(Store::new(4)), "", 1024);
+
+		assert_eq!(handler.route("/api/v0/block/put", None, b"foo"), Out::Json(format!(r#"{{"Key":"{}","Size":3}}"#, block_cid(Codec::Raw, b"foo").unwrap().0)));
+		assert_eq!(handler.route("/api/v0/block/put", None, b"bar"), Out::TooLarge("Store is full"));
+	}
+
 	#[test]
 	fn route_invalid_route() {
 		let handler = get_mocked_handler();
 
-		let out = handler.route("/foo/bar/baz", Some("arg=z43AaGF5tmkT9SEX6urrhwpEW5ZSaACY73Vw357ZXTsur2fR8BM"));
+		let out = handler.route("/foo/bar/baz", Some("arg=z43AaGF5tmkT9SEX6urrhwpEW5ZSaACY73Vw357ZXTsur2fR8BM"), &[]);
 
 		assert_eq!(out, Out::NotFound("Route not found"));
 	}
@@ -0,0 +1,277 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{fs, io};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+use ethereum_types::H256;
+use parking_lot::RwLock;
+
+use error::{Error, Result};
+
+/// Default limit of the total size of stored blocks.
+pub const DEFAULT_MAX_SIZE: usize = 64 * 1024 * 1024;
+
+/// File listing the pins, one per line: the pinned hash followed by the hashes of the blocks it covers.
+const PINS_FILE: &'static str = "pins";
+
+#[derive(Default)]
+struct Inner {
+	blocks: HashMap<H256, Bytes>,
+	/// Hashes of the blocks covered by each pin.
+	pins: HashMap<H256, Vec<H256>>,
+	size: usize,
+}
+
+/// Store of blocks put or pinned through the API, keyed by their Keccak-256 hash.
+///
+/// Blocks are kept as long as a pin covers them and their total size is bounded.
+/// If opened in a directory, blocks and pins survive a restart.
+pub struct Store {
+	path: Option<PathBuf>,
+	max_size: usize,
+	inner: RwLock<Inner>,
+}
+
+impl Default for Store {
+	fn default() -> Self {
+		Store::new(DEFAULT_MAX_SIZE)
+	}
+}
+
+impl Store {
+	/// In-memory store keeping at most `max_size` bytes.
+	pub fn new(max_size: usize) -> Self {
+		Store {
+			path: None,
+			max_size,
+			inner: Default::default(),
+		}
+	}
+
+	/// Opens the store in the given directory, keeping at most `max_size` bytes.
+	///
+	/// Pins whose blocks are missing from the directory are dropped.
+	pub fn open<P: Into<PathBuf>>(path: P, max_size: usize) -> io::Result<Self> {
+		let path = path.into();
+		fs::create_dir_all(&path)?;
+
+		let mut inner = Inner::default();
+		let pins = match read_file(&path.join(PINS_FILE)) {
+			Ok(pins) => String::from_utf8_lossy(&pins).into_owned(),
+			Err(ref err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+			Err(err) => return Err(err),
+		};
+
+		'pins: for line in pins.lines() {
+			let mut hashes = Vec::new();
+			for hash in line.split_whitespace() {
+				match hash.parse::<H256>() {
+					Ok(hash) => hashes.push(hash),
+					Err(_) => continue 'pins,
+				}
+			}
+			if hashes.is_empty() {
+				continue;
+			}
+
+			let root = hashes.remove(0);
+			let mut blocks = Vec::new();
+			for hash in &hashes {
+				if inner.blocks.contains_key(hash) {
+					continue;
+				}
+				match read_file(&path.join(format!("{:x}", hash))) {
+					Ok(data) => blocks.push((*hash, data)),
+					Err(_) => continue 'pins,
+				}
+			}
+
+			for (hash, data) in blocks {
+				inner.size += data.len();
+				inner.blocks.insert(hash, data);
+			}
+			inner.pins.insert(root, hashes);
+		}
+
+		Ok(Store {
+			path: Some(path),
+			max_size,
+			inner: RwLock::new(inner),
+		})
+	}
+
+	/// Get a stored block.
+	pub fn get(&self, hash: &H256) -> Option<Bytes> {
+		self.inner.read().blocks.get(hash).cloned()
+	}
+
+	/// Pin the given blocks under `root`. Does nothing if `root` is pinned already.
+	///
+	/// Fails if the blocks don't fit in the store.
+	pub fn pin(&self, root: H256, blocks: Vec<(H256, Bytes)>) -> Result<()> {
+		let mut inner = self.inner.write();
+		if inner.pins.contains_key(&root) {
+			return Ok(());
+		}
+
+		let mut hashes = Vec::with_capacity(blocks.len());
+		let mut new_blocks = HashMap::new();
+		for (hash, data) in blocks {
+			hashes.push(hash);
+			if !inner.blocks.contains_key(&hash) {
+				new_blocks.insert(hash, data);
+			}
+		}
+
+		let new_size = new_blocks.values().map(Vec::len).sum::<usize>();
+		if inner.size + new_size > self.max_size {
+			return Err(Error::StoreFull);
+		}
+
+		if let Some(ref path) = self.path {
+			for (hash, data) in &new_blocks {
+				write_file(path, &format!("{:x}", hash), data).map_err(|_| Error::StoreFailed)?;
+			}
+		}
+
+		inner.size += new_size;
+		inner.blocks.extend(new_blocks);
+		inner.pins.insert(root, hashes);
+		self.save_pins(&inner)
+	}
+
+	/// Remove a pin along with the blocks no other pin covers. Returns `false` if `root` wasn't pinned.
+	pub fn unpin(&self, root: &H256) -> Result<bool> {
+		let mut inner = self.inner.write();
+		let hashes = match inner.pins.remove(root) {
+			Some(hashes) => hashes,
+			None => return Ok(false),
+		};
+
+		for hash in hashes {
+			if inner.pins.values().any(|pinned| pinned.contains(&hash)) {
+				continue;
+			}
+			if let Some(data) = inner.blocks.remove(&hash) {
+				inner.size -= data.len();
+				if let Some(ref path) = self.path {
+					let _ = fs::remove_file(path.join(format!("{:x}", hash)));
+				}
+			}
+		}
+
+		self.save_pins(&inner).map(|_| true)
+	}
+
+	/// Total size of the stored blocks in bytes.
+	pub fn size(&self) -> usize {
+		self.inner.read().size
+	}
+
+	fn save_pins(&self, inner: &Inner) -> Result<()> {
+		let path = match self.path {
+			Some(ref path) => path,
+			None => return Ok(()),
+		};
+
+		let mut pins = String::new();
+		for (root, hashes) in &inner.pins {
+			pins.push_str(&format!("{:x}", root));
+			for hash in hashes {
+				pins.push_str(&format!(" {:x}", hash));
+			}
+			pins.push('\n');
+		}
+
+		write_file(path, PINS_FILE, pins.as_bytes()).map_err(|_| Error::StoreFailed)
+	}
+}
+
+fn read_file(path: &Path) -> io::Result<Bytes> {
+	let mut data = Vec::new();
+	fs::File::open(path)?.read_to_end(&mut data)?;
+	Ok(data)
+}
+
+/// Write to a temporary file first, so that a partially written file is never loaded.
+fn write_file(dir: &Path, name: &str, data: &[u8]) -> io::Result<()> {
+	let temp = dir.join(format!("{}.tmp", name));
+	fs::File::create(&temp)?.write_all(data)?;
+	fs::rename(temp, dir.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+	extern crate tempdir;
+
+	use self::tempdir::TempDir;
+	use super::*;
+
+	#[test]
+	fn should_remove_blocks_no_other_pin_covers() {
+		let store = Store::default();
+		let (a, b, c) = (H256::from(1), H256::from(2), H256::from(3));
+
+		store.pin(a, vec![(a, b"a".to_vec()), (b, b"b".to_vec())]).unwrap();
+		store.pin(c, vec![(b, b"b".to_vec()), (c, b"c".to_vec())]).unwrap();
+		assert_eq!(store.size(), 3);
+
+		assert_eq!(store.unpin(&a), Ok(true));
+		assert_eq!(store.get(&a), None);
+		assert_eq!(store.get(&b), Some(b"b".to_vec()));
+		assert_eq!(store.unpin(&a), Ok(false));
+
+		assert_eq!(store.unpin(&c), Ok(true));
+		assert_eq!(store.get(&b), None);
+		assert_eq!(store.size(), 0);
+	}
+
+	#[test]
+	fn should_reject_blocks_over_the_limit() {
+		let store = Store::new(4);
+
+		store.pin(H256::from(1), vec![(H256::from(1), b"abc".to_vec())]).unwrap();
+		assert_eq!(store.pin(H256::from(2), vec![(H256::from(2), b"de".to_vec())]), Err(Error::StoreFull));
+		assert_eq!(store.get(&H256::from(2)), None);
+		// blocks stored already don't count twice
+		store.pin(H256::from(3), vec![(H256::from(1), b"abc".to_vec()), (H256::from(3), b"d".to_vec())]).unwrap();
+		assert_eq!(store.size(), 4);
+	}
+
+	#[test]
+	fn should_keep_pins_across_restarts() {
+		let dir = TempDir::new("ipfs-store").unwrap();
+		let (a, b) = (H256::from(1), H256::from(2));
+
+		{
+			let store = Store::open(dir.path(), 1024).unwrap();
+			store.pin(a, vec![(a, b"a".to_vec()), (b, b"b".to_vec())]).unwrap();
+			store.pin(b, vec![(b, b"b".to_vec())]).unwrap();
+			store.unpin(&a).unwrap();
+		}
+
+		let store = Store::open(dir.path(), 1024).unwrap();
+		assert_eq!(store.get(&a), None);
+		assert_eq!(store.get(&b), Some(b"b".to_vec()));
+		assert_eq!(store.size(), 1);
+		assert_eq!(store.unpin(&b), Ok(true));
+		assert!(!dir.path().join(format!("{:x}", b)).exists());
+	}
+}
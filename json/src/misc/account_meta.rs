@@ -30,3 +30,16 @@ pub struct AccountMeta {
 }
 
 impl_serialization!(hash::Address => AccountMeta);
+
+/// Tags and notes attached to an address
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountNotes {
+	/// Tags used to group addresses.
+	#[serde(default)]
+	pub tags: Vec<String>,
+	/// Free-form notes about the address.
+	#[serde(default)]
+	pub notes: String,
+}
+
+impl_serialization!(hash::Address => AccountNotes);
@@ -51,4 +51,4 @@ mod account_meta;
 mod dapps_settings;
 
 pub use self::dapps_settings::{DappsSettings, DappsHistory, NewDappsPolicy};
-pub use self::account_meta::AccountMeta;
+pub use self::account_meta::{AccountMeta, AccountNotes};
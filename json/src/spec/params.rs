@@ -16,6 +16,8 @@
 
 //! Spec params deserialization.
 
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
 use uint::{self, Uint};
 use hash::{H256, Address};
 use bytes::Bytes;
@@ -143,6 +145,102 @@ pub struct Params {
 	/// Wasm activation block height, if not activated from start
 	#[serde(rename="wasmActivationTransition")]
 	pub wasm_activation_transition: Option<Uint>,
+	/// Wasm gas metering costs. Defaults are used for missing values.
+	#[serde(rename="wasmCosts")]
+	pub wasm_costs: Option<WasmCosts>,
+	/// Maximum size of block RLP, unlimited if not set.
+	#[serde(rename="maxBlockSize")]
+	pub max_block_size: Option<Uint>,
+	/// Changes to the params above scheduled at future blocks.
+	#[serde(default, deserialize_with="validate_transitions")]
+	pub transitions: Option<Vec<ParamsTransition>>,
+}
+
+/// Params changed from the given block on.
+#[derive(Debug, PartialEq, Default, Deserialize)]
+pub struct ParamsTransition {
+	/// First block the changes apply to.
+	pub block: Uint,
+	/// See `Params` docs.
+	#[serde(rename="minGasLimit")]
+	pub min_gas_limit: Option<Uint>,
+	/// See `Params` docs.
+	#[serde(rename="gasLimitBoundDivisor")]
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub gas_limit_bound_divisor: Option<Uint>,
+	/// See `Params` docs.
+	#[serde(rename="maximumExtraDataSize")]
+	pub maximum_extra_data_size: Option<Uint>,
+	/// See `Params` docs.
+	#[serde(rename="maxTransactionSize")]
+	pub max_transaction_size: Option<Uint>,
+	/// See `Params` docs.
+	#[serde(rename="maxCodeSize")]
+	pub max_code_size: Option<Uint>,
+	/// See `Params` docs.
+	#[serde(rename="maxBlockSize")]
+	pub max_block_size: Option<Uint>,
+}
+
+impl ParamsTransition {
+	fn is_empty(&self) -> bool {
+		self.min_gas_limit.is_none() && self.gas_limit_bound_divisor.is_none() &&
+			self.maximum_extra_data_size.is_none() && self.max_transaction_size.is_none() &&
+			self.max_code_size.is_none() && self.max_block_size.is_none()
+	}
+}
+
+/// Transitions must change at least one param and be ordered by strictly increasing block number.
+fn validate_transitions<'de, D>(d: D) -> Result<Option<Vec<ParamsTransition>>, D::Error> where D: Deserializer<'de> {
+	let transitions: Option<Vec<ParamsTransition>> = Option::deserialize(d)?;
+
+	if let Some(ref transitions) = transitions {
+		if let Some(t) = transitions.iter().find(|t| t.is_empty()) {
+			return Err(Error::custom(format!("params transition at block {} changes nothing", t.block.0)));
+		}
+		if let Some(pair) = transitions.windows(2).find(|pair| pair[0].block >= pair[1].block) {
+			return Err(Error::custom(format!("params transition at block {} must come after block {}", pair[1].block.0, pair[0].block.0)));
+		}
+	}
+
+	Ok(transitions)
+}
+
+/// Wasm gas metering costs.
+#[derive(Debug, PartialEq, Default, Deserialize)]
+pub struct WasmCosts {
+	/// Default opcode cost.
+	pub regular: Option<Uint>,
+	/// Div operations multiplier.
+	pub div: Option<Uint>,
+	/// Mul operations multiplier.
+	pub mul: Option<Uint>,
+	/// Memory (load/store) operations multiplier.
+	pub mem: Option<Uint>,
+	/// Static query of U256 value from env-info.
+	#[serde(rename="staticU256")]
+	pub static_u256: Option<Uint>,
+	/// Static query of Address value from env-info.
+	#[serde(rename="staticAddress")]
+	pub static_address: Option<Uint>,
+	/// Memory stipend in 64kb pages.
+	#[serde(rename="initialMem")]
+	pub initial_mem: Option<Uint>,
+	/// Grow memory cost per 64kb page.
+	#[serde(rename="growMem")]
+	pub grow_mem: Option<Uint>,
+	/// Memory copy cost per byte.
+	pub memcpy: Option<Uint>,
+	/// Max stack height.
+	#[serde(rename="maxStackHeight")]
+	pub max_stack_height: Option<Uint>,
+	/// Opcode cost multiplier.
+	#[serde(rename="opcodesMul")]
+	pub opcodes_mul: Option<Uint>,
+	/// Opcode cost divisor.
+	#[serde(rename="opcodesDiv")]
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub opcodes_div: Option<Uint>,
 }
 
 #[cfg(test)]
@@ -150,7 +248,7 @@ mod tests {
 	use serde_json;
 	use uint::Uint;
 	use ethereum_types::U256;
-	use spec::params::Params;
+	use spec::params::{Params, ParamsTransition, WasmCosts};
 
 	#[test]
 	fn params_deserialization() {
@@ -163,7 +261,11 @@ mod tests {
 			"accountStartNonce": "0x01",
 			"gasLimitBoundDivisor": "0x20",
 			"maxCodeSize": "0x1000",
-			"wasmActivationTransition": "0x1010"
+			"wasmActivationTransition": "0x1010",
+			"wasmCosts": {
+				"regular": "0x2",
+				"growMem": "0x1000"
+			}
 		}"#;
 
 		let deserialized: Params = serde_json::from_str(s).unwrap();
@@ -176,6 +278,70 @@ mod tests {
 		assert_eq!(deserialized.gas_limit_bound_divisor, Uint(U256::from(0x20)));
 		assert_eq!(deserialized.max_code_size, Some(Uint(U256::from(0x1000))));
 		assert_eq!(deserialized.wasm_activation_transition, Some(Uint(U256::from(0x1010))));
+		assert_eq!(deserialized.wasm_costs, Some(WasmCosts {
+			regular: Some(Uint(U256::from(2))),
+			grow_mem: Some(Uint(U256::from(0x1000))),
+			..Default::default()
+		}));
+		assert_eq!(deserialized.transitions, None);
+	}
+
+	#[test]
+	fn params_transitions_deserialization() {
+		let s = r#"{
+			"maximumExtraDataSize": "0x20",
+			"networkID" : "0x1",
+			"minGasLimit": "0x1388",
+			"gasLimitBoundDivisor": "0x20",
+			"transitions": [
+				{ "block": "0x10", "minGasLimit": "0x2000" },
+				{ "block": "0x20", "gasLimitBoundDivisor": "0x400", "maxBlockSize": "0x8000" }
+			]
+		}"#;
+
+		let deserialized: Params = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.transitions, Some(vec![
+			ParamsTransition {
+				block: Uint(U256::from(0x10)),
+				min_gas_limit: Some(Uint(U256::from(0x2000))),
+				..Default::default()
+			},
+			ParamsTransition {
+				block: Uint(U256::from(0x20)),
+				gas_limit_bound_divisor: Some(Uint(U256::from(0x400))),
+				max_block_size: Some(Uint(U256::from(0x8000))),
+				..Default::default()
+			},
+		]));
+	}
+
+	#[test]
+	fn rejects_unordered_transitions() {
+		let s = r#"{
+			"maximumExtraDataSize": "0x20",
+			"networkID" : "0x1",
+			"minGasLimit": "0x1388",
+			"gasLimitBoundDivisor": "0x20",
+			"transitions": [
+				{ "block": "0x20", "minGasLimit": "0x2000" },
+				{ "block": "0x10", "minGasLimit": "0x3000" }
+			]
+		}"#;
+
+		assert!(serde_json::from_str::<Params>(s).is_err());
+	}
+
+	#[test]
+	fn rejects_empty_transition() {
+		let s = r#"{
+			"maximumExtraDataSize": "0x20",
+			"networkID" : "0x1",
+			"minGasLimit": "0x1388",
+			"gasLimitBoundDivisor": "0x20",
+			"transitions": [{ "block": "0x20" }]
+		}"#;
+
+		assert!(serde_json::from_str::<Params>(s).is_err());
 	}
 
 	#[test]
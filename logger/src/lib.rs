@@ -28,23 +28,29 @@ extern crate time;
 #[macro_use]
 extern crate lazy_static;
 
+mod request_id;
 mod rotating;
 
-use std::{env, thread, fs};
+use std::{env, thread, fs, io};
 use std::sync::{Weak, Arc};
 use std::io::Write;
 use env_logger::LogBuilder;
 use regex::Regex;
 use ansi_term::Colour;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use rlog::{Log, LogLevelFilter, LogMetadata, LogRecord, MaxLogLevelFilter};
 
-pub use rotating::{RotatingLogger, init_log};
+pub use request_id::{with_request_id, request_id};
+pub use rotating::{RotatingLogger, LogEntry, DEFAULT_RECORDS, init_log};
+pub use rlog::LogLevel;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Config {
 	pub mode: Option<String>,
 	pub color: bool,
 	pub file: Option<String>,
+	/// Number of structured log records kept in memory.
+	pub buffer_size: usize,
 }
 
 impl Default for Config {
@@ -53,18 +59,38 @@ impl Default for Config {
 			mode: None,
 			color: !cfg!(windows),
 			file: None,
+			buffer_size: DEFAULT_RECORDS,
 		}
 	}
 }
 
 lazy_static! {
 	static ref ROTATING_LOGGER : Mutex<Weak<RotatingLogger>> = Mutex::new(Default::default());
+	/// Filter consulted by the installed logger; replaced by `set_log_targets`.
+	static ref FILTER: RwLock<Option<env_logger::Logger>> = RwLock::new(None);
+	static ref MAX_LEVEL: Mutex<Option<MaxLogLevelFilter>> = Mutex::new(None);
 }
 
-/// Sets up the logger
-pub fn setup_log(config: &Config) -> Result<Arc<RotatingLogger>, String> {
-	use rlog::*;
+/// Logger which formats records itself and leaves filtering to a replaceable `FILTER`.
+struct Logger {
+	format: Box<Fn(&LogRecord) -> String + Send + Sync>,
+}
 
+impl Log for Logger {
+	fn enabled(&self, metadata: &LogMetadata) -> bool {
+		FILTER.read().as_ref().map_or(false, |filter| filter.enabled(metadata))
+	}
+
+	fn log(&self, record: &LogRecord) {
+		if FILTER.read().as_ref().map_or(false, |filter| filter.matches(record)) {
+			let _ = writeln!(io::stderr(), "{}", (self.format)(record));
+		}
+	}
+}
+
+/// Builds the log filter: quiet defaults, then `RUST_LOG`, then `mode`.
+/// Returns the builder and the resulting levels string.
+fn filter_builder(mode: Option<&str>) -> (LogBuilder, String) {
 	let mut levels = String::new();
 	let mut builder = LogBuilder::new();
 	// Disable info logging by default for some modules:
@@ -81,14 +107,51 @@ pub fn setup_log(config: &Config) -> Result<Arc<RotatingLogger>, String> {
 		builder.parse(&lvl);
 	}
 
-	if let Some(ref s) = config.mode {
+	if let Some(s) = mode {
 		levels.push_str(s);
 		builder.parse(s);
 	}
 
+	(builder, levels)
+}
+
+/// Checks `targets` (e.g. `sync=trace,miner=debug`) for directives the filter would silently ignore.
+fn validate_targets(targets: &str) -> Result<(), String> {
+	for directive in targets.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+		let mut parts = directive.split('=');
+		let (target, level) = (parts.next(), parts.next());
+		match (target, level, parts.next()) {
+			(Some(_), None, None) => {},
+			(Some(t), Some(l), None) if !t.is_empty() && l.parse::<LogLevelFilter>().is_ok() => {},
+			_ => return Err(format!("Invalid logging directive: {}", directive)),
+		}
+	}
+	Ok(())
+}
+
+/// Replaces the filter of the running logger with one built from `targets`,
+/// using the same syntax as `--logging`. Returns the new levels string.
+pub fn set_log_targets(targets: &str) -> Result<String, String> {
+	validate_targets(targets)?;
+
+	let (mut builder, levels) = filter_builder(Some(targets));
+	let filter = builder.build();
+	if let Some(ref max_level) = *MAX_LEVEL.lock() {
+		max_level.set(filter.filter());
+	}
+	*FILTER.write() = Some(filter);
+	Ok(levels)
+}
+
+/// Sets up the logger
+pub fn setup_log(config: &Config) -> Result<Arc<RotatingLogger>, String> {
+	use rlog::*;
+
+	let (mut builder, levels) = filter_builder(config.mode.as_ref().map(String::as_str));
+
 	let isatty = atty::is(atty::Stream::Stderr);
 	let enable_color = config.color && isatty;
-	let logs = Arc::new(RotatingLogger::new(levels));
+	let logs = Arc::new(RotatingLogger::with_capacity(levels, config.buffer_size));
 	let logger = logs.clone();
 	let mut open_options = fs::OpenOptions::new();
 
@@ -102,11 +165,13 @@ pub fn setup_log(config: &Config) -> Result<Arc<RotatingLogger>, String> {
 	let format = move |record: &LogRecord| {
 		let timestamp = time::strftime("%Y-%m-%d %H:%M:%S %Z", &time::now()).unwrap();
 
+		let request = request_id().map_or_else(Default::default, |id| format!("{} ", Colour::Cyan.paint(format!("[req:{}]", id))));
+
 		let with_color = if max_log_level() <= LogLevelFilter::Info {
-			format!("{} {}", Colour::Black.bold().paint(timestamp), record.args())
+			format!("{} {}{}", Colour::Black.bold().paint(timestamp), request, record.args())
 		} else {
 			let name = thread::current().name().map_or_else(Default::default, |x| format!("{}", Colour::Blue.bold().paint(x)));
-			format!("{} {} {} {}  {}", Colour::Black.bold().paint(timestamp), name, record.level(), record.target(), record.args())
+			format!("{} {} {} {}  {}{}", Colour::Black.bold().paint(timestamp), name, record.level(), record.target(), request, record.args())
 		};
 
 		let removed_color = kill_color(with_color.as_ref());
@@ -122,6 +187,12 @@ pub fn setup_log(config: &Config) -> Result<Arc<RotatingLogger>, String> {
 			let _ = file.write_all(b"\n");
 		}
 		logger.append(removed_color);
+		logger.record(LogEntry {
+			timestamp: time::get_time().sec,
+			level: record.level(),
+			target: record.target().into(),
+			message: kill_color(&record.args().to_string()),
+		});
 		if !isatty && record.level() <= LogLevel::Info && atty::is(atty::Stream::Stdout) {
 			// duplicate INFO/WARN output to console
 			println!("{}", ret);
@@ -130,8 +201,13 @@ pub fn setup_log(config: &Config) -> Result<Arc<RotatingLogger>, String> {
 		ret
     };
 
-	builder.format(format);
-	builder.init()
+	let filter = builder.build();
+	set_logger(|max_level| {
+		max_level.set(filter.filter());
+		*MAX_LEVEL.lock() = Some(max_level);
+		*FILTER.write() = Some(filter);
+		Box::new(Logger { format: Box::new(format) })
+	})
 		.and_then(|_| {
 			*ROTATING_LOGGER.lock() = Arc::downgrade(&logs);
 			Ok(logs)
@@ -151,6 +227,15 @@ fn kill_color(s: &str) -> String {
 	RE.replace_all(s, "").to_string()
 }
 
+#[test]
+fn should_validate_log_targets() {
+	assert!(validate_targets("sync=trace,miner=debug").is_ok());
+	assert!(validate_targets("sync,debug, ").is_ok());
+	assert!(validate_targets("sync=loud").is_err());
+	assert!(validate_targets("=trace").is_err());
+	assert!(validate_targets("sync=trace=debug").is_err());
+}
+
 #[test]
 fn should_remove_colour() {
 	let before = "test";
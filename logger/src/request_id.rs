@@ -0,0 +1,60 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tagging log lines with the id of the request being served.
+
+use std::cell::RefCell;
+use std::mem;
+
+thread_local! {
+	static REQUEST_ID: RefCell<Option<String>> = RefCell::new(None);
+}
+
+struct Restore(Option<String>);
+
+impl Drop for Restore {
+	fn drop(&mut self) {
+		let previous = self.0.take();
+		REQUEST_ID.with(|id| *id.borrow_mut() = previous);
+	}
+}
+
+/// Runs `f` with all log lines it produces on the current thread tagged with given request id.
+pub fn with_request_id<F, T>(id: &str, f: F) -> T where F: FnOnce() -> T {
+	let previous = REQUEST_ID.with(|current| mem::replace(&mut *current.borrow_mut(), Some(id.to_owned())));
+	let _restore = Restore(previous);
+	f()
+}
+
+/// Id of the request served by the current thread, if any.
+pub fn request_id() -> Option<String> {
+	REQUEST_ID.with(|id| id.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{with_request_id, request_id};
+
+	#[test]
+	fn should_restore_previous_request_id() {
+		assert_eq!(request_id(), None);
+		with_request_id("outer", || {
+			with_request_id("inner", || assert_eq!(request_id(), Some("inner".into())));
+			assert_eq!(request_id(), Some("outer".into()));
+		});
+		assert_eq!(request_id(), None);
+	}
+}
@@ -17,7 +17,8 @@
 //! Common log helper functions
 
 use std::env;
-use rlog::LogLevelFilter;
+use std::collections::VecDeque;
+use rlog::{LogLevel, LogLevelFilter};
 use env_logger::LogBuilder;
 use arrayvec::ArrayVec;
 
@@ -45,12 +46,29 @@ pub fn init_log() {
 
 const LOG_SIZE : usize = 128;
 
-/// Logger implementation that keeps up to `LOG_SIZE` log elements.
+/// Default number of structured log records kept in memory.
+pub const DEFAULT_RECORDS: usize = 10_000;
+
+/// A structured log record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+	/// Unix timestamp, in seconds.
+	pub timestamp: i64,
+	pub level: LogLevel,
+	pub target: String,
+	pub message: String,
+}
+
+/// Logger implementation that keeps up to `LOG_SIZE` log elements
+/// and a configurable number of structured records.
 pub struct RotatingLogger {
 	/// Defined logger levels
-	levels: String,
+	levels: RwLock<String>,
 	/// Logs array. Latest log is always at index 0
 	logs: RwLock<ArrayVec<[String; LOG_SIZE]>>,
+	/// Structured records. Latest record is always at the front.
+	records: RwLock<VecDeque<LogEntry>>,
+	capacity: usize,
 }
 
 impl RotatingLogger {
@@ -58,10 +76,46 @@ impl RotatingLogger {
 	/// Creates new `RotatingLogger` with given levels.
 	/// It does not enforce levels - it's just read only.
 	pub fn new(levels: String) -> Self {
+		Self::with_capacity(levels, DEFAULT_RECORDS)
+	}
+
+	/// Creates new `RotatingLogger` keeping up to `capacity` structured records.
+	pub fn with_capacity(levels: String, capacity: usize) -> Self {
 		RotatingLogger {
-			levels: levels,
+			levels: RwLock::new(levels),
 			logs: RwLock::new(ArrayVec::<[_; LOG_SIZE]>::new()),
+			records: RwLock::new(VecDeque::new()),
+			capacity: capacity,
+		}
+	}
+
+	/// Append new structured record
+	pub fn record(&self, entry: LogEntry) {
+		if self.capacity == 0 {
+			return;
+		}
+
+		let mut records = self.records.write();
+		if records.len() >= self.capacity {
+			records.pop_back();
 		}
+		records.push_front(entry);
+	}
+
+	/// Returns up to `limit` most recent records, latest first, which are at least as severe
+	/// as `level` and come from `target` or one of its submodules.
+	pub fn recent(&self, level: Option<LogLevel>, target: Option<&str>, limit: usize) -> Vec<LogEntry> {
+		let matches_target = |entry: &LogEntry| match target {
+			Some(target) => entry.target == target || entry.target.starts_with(&format!("{}::", target)),
+			None => true,
+		};
+
+		self.records.read().iter()
+			.filter(|entry| level.map_or(true, |level| entry.level <= level))
+			.filter(|entry| matches_target(entry))
+			.take(limit)
+			.cloned()
+			.collect()
 	}
 
 	/// Append new log entry
@@ -74,8 +128,15 @@ impl RotatingLogger {
 	}
 
 	/// Return levels
-	pub fn levels(&self) -> &str {
-		&self.levels
+	pub fn levels(&self) -> String {
+		self.levels.read().clone()
+	}
+
+	/// Reconfigures the running logger with new levels (e.g. `sync=trace,miner=debug`).
+	pub fn set_levels(&self, levels: &str) -> Result<(), String> {
+		let levels = ::set_log_targets(levels)?;
+		*self.levels.write() = levels;
+		Ok(())
 	}
 
 	/// Return logs
@@ -87,7 +148,8 @@ impl RotatingLogger {
 
 #[cfg(test)]
 mod test {
-	use super::RotatingLogger;
+	use rlog::LogLevel;
+	use super::{LogEntry, RotatingLogger};
 
 	fn logger() -> RotatingLogger {
 		RotatingLogger::new("test".to_owned())
@@ -120,4 +182,26 @@ mod test {
 		assert_eq!(logs[1], "a".to_owned());
 		assert_eq!(logs.len(), 2);
 	}
+
+	fn entry(level: LogLevel, target: &str, message: &str) -> LogEntry {
+		LogEntry { timestamp: 0, level: level, target: target.into(), message: message.into() }
+	}
+
+	#[test]
+	fn should_filter_recent_records() {
+		// given
+		let logger = RotatingLogger::with_capacity("test".to_owned(), 3);
+
+		// when
+		logger.record(entry(LogLevel::Info, "sync", "a"));
+		logger.record(entry(LogLevel::Debug, "sync::chain", "b"));
+		logger.record(entry(LogLevel::Warn, "miner", "c"));
+		logger.record(entry(LogLevel::Trace, "syncer", "d"));
+
+		// then
+		assert_eq!(logger.recent(None, None, 10).iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), vec!["d", "c", "b"]);
+		assert_eq!(logger.recent(None, Some("sync"), 10), vec![entry(LogLevel::Debug, "sync::chain", "b")]);
+		assert_eq!(logger.recent(Some(LogLevel::Info), None, 10), vec![entry(LogLevel::Warn, "miner", "c")]);
+		assert_eq!(logger.recent(None, None, 1).len(), 1);
+	}
 }
@@ -147,6 +147,11 @@ impl VerifiedTransaction {
 		self.insertion_id
 	}
 
+	/// Returns `true` if the transaction was submitted locally.
+	pub fn is_local(&self) -> bool {
+		self.priority.is_local()
+	}
+
 	/// Gets wrapped `SignedTransaction`
 	pub fn signed(&self) -> &transaction::SignedTransaction {
 		&self.transaction
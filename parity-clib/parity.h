@@ -27,7 +27,8 @@ struct ParityParams {
 
 	/// Callback function to call when the client receives an RPC request to change its chain spec.
 	///
-	/// Will only be called if you enable the `--can-restart` flag.
+	/// Will only be called if you enable the `--can-restart` flag. If NULL, the client switches
+	/// chains in-process instead.
 	///
 	/// The first parameter of the callback is the value of `on_client_restart_cb_custom`.
 	/// The second and third parameters of the callback are the string pointer and length.
@@ -102,6 +103,51 @@ void parity_destroy(void* parity);
 ///
 int parity_rpc(void* parity, const char* rpc, size_t len, char* out_str, size_t* out_len);
 
+/// Performs an RPC request asynchronously.
+///
+/// Returns immediately. The JSON response is passed to `callback` from a background thread pool
+/// once the request is finished, along with `custom` as its first parameter.
+///
+/// - `rpc` and `len` must contain the JSON string representing the RPC request.
+/// - On success, the function returns 0. On failure, it returns 1 and `callback` is never called.
+///
+/// **Important**: The response passed to `callback` isn't null terminated and is only valid for the
+///                duration of the call.
+///
+int parity_rpc_query(void* parity, const char* rpc, size_t len,
+	void (*callback)(void* custom, const char* response, size_t response_len), void* custom);
+
+/// Performs a pub-sub RPC request, e.g. `eth_subscribe`, in a new session.
+///
+/// The JSON response and then every notification of the subscriptions made in the session are
+/// passed to `callback` from a background thread pool, along with `custom` as its first parameter.
+///
+/// On success, the session object will be written to the `void*` pointed by `out`. Pass it to
+/// `parity_unsubscribe` to close the session and all of its subscriptions.
+///
+/// Returns 0 on success, and non-zero on error.
+///
+int parity_subscribe(void* parity, const char* rpc, size_t len,
+	void (*callback)(void* custom, const char* response, size_t response_len), void* custom, void** out);
+
+/// Closes a session created with `parity_subscribe`, along with all of its subscriptions.
+///
+/// Waits for a running invocation of the session's `callback` to return. Once this function
+/// returns, `callback` is never called again. Must not be called from within `callback`.
+void parity_unsubscribe(void* session);
+
+/// Sets up logging for the whole process, so that it can be used before the client is started.
+/// `parity_start` then keeps using this logger instead of the one from its configuration.
+///
+/// - `log_mode` and `log_mode_len` contain the logging levels, in the format of the `--logging`
+///   flag. Pass an empty string for the default levels.
+/// - `log_file` and `log_file_len` contain the path of a file to write the log to, in addition to
+///   the standard output. Pass an empty string to not write to a file.
+///
+/// Returns 0 on success, and non-zero on error, e.g. if logging is already set up.
+///
+int parity_set_logger(const char* log_mode, size_t log_mode_len, const char* log_file, size_t log_file_len);
+
 #ifdef __cplusplus
 }
 #endif
@@ -17,13 +17,30 @@
 //! Note that all the structs and functions here are documented in `parity.h`, to avoid
 //! duplicating documentation.
 
+extern crate futures;
+extern crate futures_cpupool;
 extern crate parity;
 
+#[cfg(test)]
+extern crate tempdir;
+
+use std::mem;
 use std::os::raw::{c_char, c_void, c_int};
 use std::panic;
 use std::ptr;
 use std::slice;
 use std::str;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering, ATOMIC_BOOL_INIT};
+
+use futures::{Future, Stream};
+use futures::sync::mpsc;
+use futures_cpupool::CpuPool;
+
+/// Number of threads running the callbacks of asynchronous queries and subscriptions.
+const CALLBACK_THREADS: usize = 2;
+
+static LOGGER_SET: AtomicBool = ATOMIC_BOOL_INIT;
 
 #[repr(C)]
 pub struct ParityParams {
@@ -32,6 +49,47 @@ pub struct ParityParams {
 	pub on_client_restart_cb_custom: *mut c_void,
 }
 
+type CCallback = Option<extern "C" fn(*mut c_void, *const c_char, usize)>;
+
+// A C callback together with its custom parameter, which may be invoked from any thread.
+struct Callback(CCallback, *mut c_void);
+
+unsafe impl Send for Callback {}
+unsafe impl Sync for Callback {}
+
+impl Callback {
+	fn call(&self, msg: &str) {
+		if let Some(ref cb) = self.0 {
+			cb(self.1, msg.as_bytes().as_ptr() as *const _, msg.len())
+		}
+	}
+}
+
+// The running client together with the pool driving its asynchronous queries.
+struct Client {
+	client: parity::RunningClient,
+	pool: CpuPool,
+}
+
+// A session opened by `parity_subscribe`. The callback is taken out when unsubscribing, and is
+// only invoked while its lock is held, so that it's never called after `parity_unsubscribe` returns.
+struct Subscription {
+	session: Arc<parity::PubSubSession>,
+	callback: Arc<Mutex<Option<Callback>>>,
+}
+
+fn notify(callback: &Mutex<Option<Callback>>, msg: &str) {
+	if let Ok(callback) = callback.lock() {
+		if let Some(ref callback) = *callback {
+			callback.call(msg);
+		}
+	}
+}
+
+unsafe fn str_from_raw<'a>(ptr: *const c_char, len: usize) -> Option<&'a str> {
+	str::from_utf8(slice::from_raw_parts(ptr as *const u8, len)).ok()
+}
+
 #[no_mangle]
 pub extern fn parity_config_from_cli(args: *const *const c_char, args_lens: *const usize, len: usize, output: *mut *mut c_void) -> c_int {
 	unsafe {
@@ -91,22 +149,21 @@ pub extern fn parity_start(cfg: *const ParityParams, output: *mut *mut c_void) -
 
 			let config = Box::from_raw(cfg.configuration as *mut parity::Configuration);
 
-			let on_client_restart_cb = {
-				struct Cb(Option<extern "C" fn(*mut c_void, *const c_char, usize)>, *mut c_void);
-				unsafe impl Send for Cb {}
-				unsafe impl Sync for Cb {}
-				impl Cb {
-					fn call(&self, new_chain: String) {
-						if let Some(ref cb) = self.0 {
-							cb(self.1, new_chain.as_bytes().as_ptr() as *const _, new_chain.len())
-						}
-					}
-				}
-				let cb = Cb(cfg.on_client_restart_cb, cfg.on_client_restart_cb_custom);
-				move |new_chain: String| { cb.call(new_chain); }
+			// falls back to the logger set up with `parity_set_logger`, if any.
+			let logger = match parity::setup_log(&config.logger_config()) {
+				Ok(logger) => logger,
+				Err(_) => return 1,
+			};
+
+			let action = if cfg.on_client_restart_cb.is_some() {
+				let cb = Callback(cfg.on_client_restart_cb, cfg.on_client_restart_cb_custom);
+				parity::start_with_logger(*config, logger, move |new_chain: String| { cb.call(&new_chain); }, || {})
+			} else {
+				// when switching chains fails, the client stops answering queries until it's destroyed.
+				parity::start_restartable(*config, logger, || {}, |_| {})
 			};
 
-			let action = match parity::start(*config, on_client_restart_cb, || {}) {
+			let action = match action {
 				Ok(action) => action,
 				Err(_) => return 1,
 			};
@@ -115,7 +172,11 @@ pub extern fn parity_start(cfg: *const ParityParams, output: *mut *mut c_void) -
 				parity::ExecutionAction::Instant(Some(s)) => { println!("{}", s); 0 },
 				parity::ExecutionAction::Instant(None) => 0,
 				parity::ExecutionAction::Running(client) => {
-					*output = Box::into_raw(Box::<parity::RunningClient>::new(client)) as *mut c_void;
+					let client = Client {
+						client: client,
+						pool: CpuPool::new(CALLBACK_THREADS),
+					};
+					*output = Box::into_raw(Box::new(client)) as *mut c_void;
 					0
 				}
 			}
@@ -127,8 +188,8 @@ pub extern fn parity_start(cfg: *const ParityParams, output: *mut *mut c_void) -
 pub extern fn parity_destroy(client: *mut c_void) {
 	unsafe {
 		let _ = panic::catch_unwind(|| {
-			let client = Box::from_raw(client as *mut parity::RunningClient);
-			client.shutdown();
+			let client = Box::from_raw(client as *mut Client);
+			client.client.shutdown();
 		});
 	}
 }
@@ -137,7 +198,7 @@ pub extern fn parity_destroy(client: *mut c_void) {
 pub extern fn parity_rpc(client: *mut c_void, query: *const char, len: usize, out_str: *mut c_char, out_len: *mut usize) -> c_int {
 	unsafe {
 		panic::catch_unwind(|| {
-			let client: &mut parity::RunningClient = &mut *(client as *mut parity::RunningClient);
+			let client: &Client = &*(client as *const Client);
 
 			let query_str = {
 				let string = slice::from_raw_parts(query as *const u8, len);
@@ -147,7 +208,7 @@ pub extern fn parity_rpc(client: *mut c_void, query: *const char, len: usize, ou
 				}
 			};
 
-			if let Some(output) = client.rpc_query_sync(query_str) {
+			if let Some(output) = client.client.rpc_query_sync(query_str) {
 				let q_out_len = output.as_bytes().len();
 				if *out_len < q_out_len {
 					return 1;
@@ -162,3 +223,209 @@ pub extern fn parity_rpc(client: *mut c_void, query: *const char, len: usize, ou
 		}).unwrap_or(1)
 	}
 }
+
+#[no_mangle]
+pub extern fn parity_rpc_query(client: *mut c_void, query: *const c_char, len: usize, callback: CCallback, custom: *mut c_void) -> c_int {
+	unsafe {
+		panic::catch_unwind(|| {
+			let client: &Client = &*(client as *const Client);
+
+			let query_str = match str_from_raw(query, len) {
+				Some(q) => q,
+				None => return 1,
+			};
+
+			let cb = Callback(callback, custom);
+			let response = client.client.rpc_query(query_str, None).map(move |response| {
+				if let Some(response) = response {
+					cb.call(&response);
+				}
+			});
+			client.pool.spawn(response).forget();
+			0
+		}).unwrap_or(1)
+	}
+}
+
+#[no_mangle]
+pub extern fn parity_subscribe(client: *mut c_void, query: *const c_char, len: usize, callback: CCallback, custom: *mut c_void, output: *mut *mut c_void) -> c_int {
+	unsafe {
+		panic::catch_unwind(|| {
+			*output = ptr::null_mut();
+			let client: &Client = &*(client as *const Client);
+
+			let query_str = match str_from_raw(query, len) {
+				Some(q) => q,
+				None => return 1,
+			};
+
+			let (sender, receiver) = mpsc::channel(128);
+			let session = Arc::new(parity::PubSubSession::new(sender));
+			let callback = Arc::new(Mutex::new(Some(Callback(callback, custom))));
+
+			// forward the response and then every notification until the session is closed.
+			let (on_response, on_notification) = (callback.clone(), callback.clone());
+			let forward = client.client.rpc_query(query_str, Some(session.clone()))
+				.map(move |response| if let Some(response) = response {
+					notify(&on_response, &response);
+				})
+				.and_then(move |_| receiver.for_each(move |notification| {
+					notify(&on_notification, &notification);
+					Ok(())
+				}));
+			client.pool.spawn(forward).forget();
+
+			let subscription = Subscription {
+				session: session,
+				callback: callback,
+			};
+			*output = Box::into_raw(Box::new(subscription)) as *mut c_void;
+			0
+		}).unwrap_or(1)
+	}
+}
+
+#[no_mangle]
+pub extern fn parity_unsubscribe(subscription: *mut c_void) {
+	unsafe {
+		let _ = panic::catch_unwind(|| {
+			let subscription = Box::from_raw(subscription as *mut Subscription);
+			// waits for a running callback to return.
+			if let Ok(mut callback) = subscription.callback.lock() {
+				*callback = None;
+			}
+			drop(subscription.session);
+		});
+	}
+}
+
+#[no_mangle]
+pub extern fn parity_set_logger(log_mode: *const c_char, log_mode_len: usize, log_file: *const c_char, log_file_len: usize) -> c_int {
+	unsafe {
+		panic::catch_unwind(|| {
+			let mut config = parity::LoggerConfig::default();
+			if log_mode_len > 0 {
+				match str_from_raw(log_mode, log_mode_len) {
+					Some(mode) => config.mode = Some(mode.to_owned()),
+					None => return 1,
+				}
+			}
+			if log_file_len > 0 {
+				match str_from_raw(log_file, log_file_len) {
+					Some(file) => config.file = Some(file.to_owned()),
+					None => return 1,
+				}
+			}
+
+			if LOGGER_SET.swap(true, Ordering::SeqCst) {
+				return 1;
+			}
+
+			match parity::setup_log(&config) {
+				Ok(logger) => {
+					// the logger stays installed until the process exits.
+					mem::forget(logger);
+					0
+				},
+				Err(_) => {
+					LOGGER_SET.store(false, Ordering::SeqCst);
+					1
+				},
+			}
+		}).unwrap_or(1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::os::raw::{c_char, c_void};
+	use std::ptr;
+	use std::sync::Mutex;
+	use std::thread;
+	use std::time::Duration;
+
+	use tempdir::TempDir;
+
+	use super::*;
+
+	extern "C" fn collect(custom: *mut c_void, msg: *const c_char, len: usize) {
+		let messages = unsafe { &*(custom as *const Mutex<Vec<String>>) };
+		let msg = unsafe { str_from_raw(msg, len) }.expect("responses are valid UTF-8").to_owned();
+		messages.lock().unwrap().push(msg);
+	}
+
+	fn wait_for(messages: &Mutex<Vec<String>>, count: usize) -> Vec<String> {
+		for _ in 0..100 {
+			if messages.lock().unwrap().len() >= count {
+				break;
+			}
+			thread::sleep(Duration::from_millis(50));
+		}
+		messages.lock().unwrap().clone()
+	}
+
+	fn start_dev_node(base_path: &str) -> *mut c_void {
+		let args = vec![
+			"--chain=dev".to_owned(),
+			format!("--base-path={}", base_path),
+			"--dev-manual-seal".to_owned(),
+			"--no-config".to_owned(),
+			"--no-discovery".to_owned(),
+			"--no-jsonrpc".to_owned(),
+			"--no-ws".to_owned(),
+			"--no-ipc".to_owned(),
+			"--no-dapps".to_owned(),
+			"--no-secretstore".to_owned(),
+		];
+		let arg_ptrs = args.iter().map(|arg| arg.as_ptr() as *const c_char).collect::<Vec<_>>();
+		let arg_lens = args.iter().map(|arg| arg.len()).collect::<Vec<_>>();
+
+		let mut config = ptr::null_mut();
+		assert_eq!(parity_config_from_cli(arg_ptrs.as_ptr(), arg_lens.as_ptr(), args.len(), &mut config), 0);
+
+		let params = ParityParams {
+			configuration: config,
+			on_client_restart_cb: None,
+			on_client_restart_cb_custom: ptr::null_mut(),
+		};
+		let mut client = ptr::null_mut();
+		assert_eq!(parity_start(&params, &mut client), 0);
+		assert!(!client.is_null());
+		client
+	}
+
+	fn query(client: *mut c_void, query: &str, messages: &Mutex<Vec<String>>) -> i32 {
+		parity_rpc_query(client, query.as_ptr() as *const c_char, query.len(), Some(collect), messages as *const _ as *mut c_void)
+	}
+
+	#[test]
+	fn queries_and_subscriptions_invoke_callbacks() {
+		let dir = TempDir::new("parity-clib").unwrap();
+		let client = start_dev_node(dir.path().to_str().unwrap());
+		let mine = r#"{"jsonrpc":"2.0","method":"evm_mine","params":[],"id":1}"#;
+
+		let responses = Mutex::new(Vec::new());
+		assert_eq!(query(client, r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#, &responses), 0);
+		assert_eq!(wait_for(&responses, 1), vec![r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()]);
+
+		let notifications = Mutex::new(Vec::new());
+		let subscribe = r#"{"jsonrpc":"2.0","method":"eth_subscribe","params":["newHeads"],"id":1}"#;
+		let mut session = ptr::null_mut();
+		assert_eq!(parity_subscribe(client, subscribe.as_ptr() as *const c_char, subscribe.len(),
+			Some(collect), &notifications as *const _ as *mut c_void, &mut session), 0);
+		assert!(wait_for(&notifications, 1)[0].contains(r#""result":"0x"#));
+
+		assert_eq!(query(client, mine, &responses), 0);
+		assert!(wait_for(&notifications, 2)[1].contains("eth_subscription"));
+
+		// no callback may run once unsubscribing returns.
+		parity_unsubscribe(session);
+		let received = notifications.lock().unwrap().len();
+		assert_eq!(query(client, mine, &responses), 0);
+		wait_for(&responses, 3);
+		thread::sleep(Duration::from_millis(200));
+		assert_eq!(notifications.lock().unwrap().len(), received);
+
+		parity_destroy(client);
+	}
+}
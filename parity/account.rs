@@ -14,12 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::fs::File;
+use std::io::Read;
 use std::path::PathBuf;
 use ethcore::ethstore::{EthStore, SecretStore, import_account, import_accounts, read_geth_accounts};
 use ethcore::ethstore::accounts_dir::RootDiskDirectory;
 use ethcore::ethstore::SecretVaultRef;
 use ethcore::account_provider::{AccountProvider, AccountProviderSettings};
-use helpers::{password_prompt, password_from_file};
+use ethkey::{Secret, mnemonic};
+use helpers::{password_prompt, password_from_file, flush_stdout};
 use params::SpecType;
 
 #[derive(Debug, PartialEq)]
@@ -27,7 +30,8 @@ pub enum AccountCmd {
 	New(NewAccount),
 	List(ListAccounts),
 	Import(ImportAccounts),
-	ImportFromGeth(ImportFromGethAccounts)
+	ImportFromGeth(ImportFromGethAccounts),
+	Recover(RecoverAccount),
 }
 
 #[derive(Debug, PartialEq)]
@@ -51,6 +55,25 @@ pub struct ImportAccounts {
 	pub spec: SpecType,
 }
 
+/// Parameters for creating an account from an existing key, offline.
+#[derive(Debug, PartialEq)]
+pub struct RecoverAccount {
+	pub iterations: u32,
+	pub path: String,
+	pub spec: SpecType,
+	pub password_file: Option<String>,
+	/// file with the private key or the mnemonic phrase, read from the terminal if not given
+	pub secret_file: Option<String>,
+	/// recover from a BIP-39 mnemonic phrase instead of a private key
+	pub mnemonic: Option<MnemonicOptions>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MnemonicOptions {
+	pub passphrase_file: Option<String>,
+	pub derivation_path: String,
+}
+
 /// Parameters for geth accounts' import
 #[derive(Debug, PartialEq)]
 pub struct ImportFromGethAccounts {
@@ -66,7 +89,8 @@ pub fn execute(cmd: AccountCmd) -> Result<String, String> {
 		AccountCmd::New(new_cmd) => new(new_cmd),
 		AccountCmd::List(list_cmd) => list(list_cmd),
 		AccountCmd::Import(import_cmd) => import(import_cmd),
-		AccountCmd::ImportFromGeth(import_geth_cmd) => import_geth(import_geth_cmd)
+		AccountCmd::ImportFromGeth(import_geth_cmd) => import_geth(import_geth_cmd),
+		AccountCmd::Recover(recover_cmd) => recover(recover_cmd),
 	}
 }
 
@@ -97,6 +121,55 @@ fn new(n: NewAccount) -> Result<String, String> {
 	Ok(format!("0x{:x}", new_account))
 }
 
+fn recover(r: RecoverAccount) -> Result<String, String> {
+	let secret = match r.mnemonic {
+		Some(ref options) => {
+			let phrase = read_secret(&r.secret_file, "mnemonic phrase")?;
+			let passphrase = match options.passphrase_file {
+				Some(ref file) => password_from_file(file.clone())?,
+				None => "".into(),
+			};
+			mnemonic::secret(&phrase, passphrase.as_str(), &options.derivation_path).map_err(|e| format!("{}", e))?
+		},
+		None => {
+			let key = read_secret(&r.secret_file, "private key")?;
+			let key = key.trim();
+			let key = if key.starts_with("0x") { &key[2..] } else { key };
+			key.parse::<Secret>().map_err(|_| "Invalid private key. Expected 64 hex characters.".to_owned())?
+		},
+	};
+
+	let password = match r.password_file {
+		Some(file) => password_from_file(file)?,
+		None => password_prompt()?,
+	};
+
+	let dir = Box::new(keys_dir(r.path, r.spec)?);
+	let secret_store = Box::new(secret_store(dir, Some(r.iterations))?);
+	let acc_provider = AccountProvider::new(secret_store, AccountProviderSettings::default());
+	let address = acc_provider.insert_account(secret, &password).map_err(|e| format!("Could not create account: {}", e))?;
+	Ok(format!("0x{:x}", address))
+}
+
+/// Reads a secret from given file, or from the terminal without echoing it.
+fn read_secret(file: &Option<String>, what: &str) -> Result<String, String> {
+	use rpassword::read_password;
+
+	match *file {
+		Some(ref file) => {
+			let mut secret = String::new();
+			File::open(file).and_then(|mut f| f.read_to_string(&mut secret))
+				.map_err(|e| format!("Unable to read {} from {}: {}", what, file, e))?;
+			Ok(secret)
+		},
+		None => {
+			print!("Type {}: ", what);
+			flush_stdout();
+			read_password().map_err(|_| format!("Unable to ask for {} on non-interactive terminal.", what))
+		},
+	}
+}
+
 fn list(list_cmd: ListAccounts) -> Result<String, String> {
 	let dir = Box::new(keys_dir(list_cmd.path, list_cmd.spec)?);
 	let secret_store = Box::new(secret_store(dir, None)?);
@@ -141,3 +214,61 @@ fn import_geth(i: ImportFromGethAccounts) -> Result<String, String> {
 		Err(err) => Err(format!("Import geth accounts failed. {}", err))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::fs::File;
+	use std::io::Write;
+	use std::path::Path;
+	use tempdir::TempDir;
+	use params::SpecType;
+	use super::{recover, list, RecoverAccount, MnemonicOptions, ListAccounts};
+
+	fn write(dir: &Path, name: &str, content: &str) -> String {
+		let path = dir.join(name);
+		File::create(&path).unwrap().write_all(content.as_bytes()).unwrap();
+		path.to_str().unwrap().to_owned()
+	}
+
+	fn recover_cmd(dir: &Path, secret: &str, mnemonic: Option<MnemonicOptions>) -> RecoverAccount {
+		RecoverAccount {
+			iterations: 1024,
+			path: dir.join("keys").to_str().unwrap().to_owned(),
+			spec: SpecType::Dev,
+			password_file: Some(write(dir, "password", "password\n")),
+			secret_file: Some(write(dir, "secret", secret)),
+			mnemonic,
+		}
+	}
+
+	#[test]
+	fn should_recover_account_from_private_key() {
+		let dir = TempDir::new("recover").unwrap();
+		let cmd = recover_cmd(dir.path(), "0x0000000000000000000000000000000000000000000000000000000000000001\n", None);
+		let keys = cmd.path.clone();
+
+		assert_eq!(recover(cmd), Ok("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf".into()));
+		assert_eq!(list(ListAccounts { path: keys, spec: SpecType::Dev }), Ok("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf".into()));
+	}
+
+	#[test]
+	fn should_recover_account_from_mnemonic() {
+		let dir = TempDir::new("recover").unwrap();
+		let options = || Some(MnemonicOptions {
+			passphrase_file: None,
+			derivation_path: "m/44'/60'/0'/0/0".into(),
+		});
+
+		let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+		assert_eq!(recover(recover_cmd(dir.path(), phrase, options())), Ok("0x9858effd232b4033e47d90003d41ec34ecaeda94".into()));
+
+		let wrong_checksum = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+		assert!(recover(recover_cmd(dir.path(), wrong_checksum, options())).is_err());
+	}
+
+	#[test]
+	fn should_reject_invalid_private_key() {
+		let dir = TempDir::new("recover").unwrap();
+		assert!(recover(recover_cmd(dir.path(), "0x1234", None)).is_err());
+	}
+}
@@ -0,0 +1,359 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runs user commands whenever the chain head changes.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use ethcore::client::{BlockChainClient, BlockId, ChainNotify, ChainRoute, Client};
+use ethereum_types::H256;
+use parking_lot::{Condvar, Mutex};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Commands run on chain head events.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HooksConfig {
+	/// Command run whenever the best block changes.
+	pub on_new_block: Option<String>,
+	/// Command run whenever blocks are retracted from the best chain.
+	pub on_reorg: Option<String>,
+	/// Time after which a command is killed.
+	pub timeout: Duration,
+	/// Maximal number of commands running at once. As many events wait for a command to finish,
+	/// further ones are skipped.
+	pub max_concurrent: usize,
+}
+
+impl Default for HooksConfig {
+	fn default() -> Self {
+		HooksConfig {
+			on_new_block: None,
+			on_reorg: None,
+			timeout: Duration::from_secs(30),
+			max_concurrent: 4,
+		}
+	}
+}
+
+impl HooksConfig {
+	/// Returns true if any command is configured.
+	pub fn is_enabled(&self) -> bool {
+		self.on_new_block.is_some() || self.on_reorg.is_some()
+	}
+}
+
+/// Chain head event passed to a command in environment variables and as JSON on stdin.
+#[derive(Debug, Clone, PartialEq)]
+struct Event {
+	kind: &'static str,
+	number: u64,
+	hash: H256,
+	parent_hash: H256,
+	timestamp: u64,
+	retracted: Vec<H256>,
+	enacted: Vec<H256>,
+}
+
+impl Event {
+	fn env(&self) -> Vec<(&'static str, String)> {
+		vec![
+			("PARITY_EVENT", self.kind.to_owned()),
+			("PARITY_BLOCK_NUMBER", self.number.to_string()),
+			("PARITY_BLOCK_HASH", format!("0x{:x}", self.hash)),
+			("PARITY_BLOCK_PARENT_HASH", format!("0x{:x}", self.parent_hash)),
+			("PARITY_BLOCK_TIMESTAMP", self.timestamp.to_string()),
+			("PARITY_RETRACTED_COUNT", self.retracted.len().to_string()),
+			("PARITY_ENACTED_COUNT", self.enacted.len().to_string()),
+		]
+	}
+
+	fn to_json(&self) -> String {
+		let hashes = |hashes: &[H256]| hashes.iter()
+			.map(|hash| format!(r#""0x{:x}""#, hash))
+			.collect::<Vec<_>>()
+			.join(",");
+
+		format!(
+			r#"{{"event":"{}","number":{},"hash":"0x{:x}","parentHash":"0x{:x}","timestamp":{},"retracted":[{}],"enacted":[{}]}}"#,
+			self.kind, self.number, self.hash, self.parent_hash, self.timestamp,
+			hashes(&self.retracted), hashes(&self.enacted),
+		)
+	}
+}
+
+/// Starts queued commands in order, at most `max_concurrent` at once.
+struct Runner {
+	queue: Mutex<mpsc::SyncSender<(String, Event)>>,
+	skipped: AtomicUsize,
+}
+
+impl Runner {
+	fn new(max_concurrent: usize, timeout: Duration) -> Result<Self, String> {
+		let (sender, receiver) = mpsc::sync_channel::<(String, Event)>(max_concurrent);
+		let running = Arc::new((Mutex::new(0usize), Condvar::new()));
+
+		// the only thread starting commands, so they start in the order of the events
+		thread::Builder::new().name("block-hooks".into()).spawn(move || {
+			loop {
+				// events wait in the queue until a command can be started
+				{
+					let &(ref count, ref finished) = &*running;
+					let mut count = count.lock();
+					while *count >= max_concurrent {
+						finished.wait(&mut count);
+					}
+				}
+				let (command, event) = match receiver.recv() {
+					Ok(next) => next,
+					Err(_) => break,
+				};
+				*running.0.lock() += 1;
+
+				let slot = running.clone();
+				let spawned = thread::Builder::new().name("block-hook".into()).spawn(move || {
+					if let Err(e) = execute(&command, &event, timeout) {
+						warn!(target: "hooks", "Command `{}` failed for block #{}: {}", command, event.number, e);
+					}
+					let &(ref count, ref finished) = &*slot;
+					*count.lock() -= 1;
+					finished.notify_one();
+				});
+				if let Err(e) = spawned {
+					warn!(target: "hooks", "Unable to spawn a thread for a command: {}", e);
+					*running.0.lock() -= 1;
+				}
+			}
+		}).map_err(|e| format!("Unable to spawn block hooks thread: {}", e))?;
+
+		Ok(Runner {
+			queue: Mutex::new(sender),
+			skipped: Default::default(),
+		})
+	}
+
+	/// Queues a command. Returns false if it's skipped because the queue is full.
+	fn push(&self, command: &str, event: Event) -> bool {
+		let number = event.number;
+		match self.queue.lock().try_send((command.to_owned(), event)) {
+			Ok(()) => {
+				let skipped = self.skipped.swap(0, Ordering::SeqCst);
+				if skipped > 0 {
+					info!(target: "hooks", "Running commands again, {} events were skipped.", skipped);
+				}
+				true
+			},
+			Err(_) => {
+				if self.skipped.fetch_add(1, Ordering::SeqCst) == 0 {
+					warn!(target: "hooks", "Commands can't keep up with the chain, skipping events from block #{}.", number);
+				}
+				false
+			},
+		}
+	}
+}
+
+/// Spawns the configured commands on new blocks and reorganizations.
+/// Nothing is run while the node is catching up with the chain.
+pub struct BlockHooks {
+	config: HooksConfig,
+	client: Weak<Client>,
+	is_major_importing: Box<Fn() -> bool + Send + Sync>,
+	runner: Runner,
+}
+
+impl BlockHooks {
+	/// Create new `BlockHooks`.
+	pub fn new<F>(config: HooksConfig, client: Weak<Client>, is_major_importing: F) -> Result<Self, String> where
+		F: Fn() -> bool + Send + Sync + 'static,
+	{
+		let runner = Runner::new(config.max_concurrent, config.timeout)?;
+		Ok(BlockHooks {
+			config,
+			client,
+			is_major_importing: Box::new(is_major_importing),
+			runner,
+		})
+	}
+
+	fn event(&self, kind: &'static str, route: &ChainRoute) -> Option<Event> {
+		let client = self.client.upgrade()?;
+		let hash = *route.enacted().last()?;
+		let header = client.block_header(BlockId::Hash(hash))?;
+
+		Some(Event {
+			kind,
+			number: header.number(),
+			hash,
+			parent_hash: header.parent_hash(),
+			timestamp: header.timestamp(),
+			retracted: route.retracted().to_vec(),
+			enacted: route.enacted().to_vec(),
+		})
+	}
+
+}
+
+impl ChainNotify for BlockHooks {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, route: ChainRoute, _sealed: Vec<H256>, _proposed: Vec<Bytes>, _duration: Duration) {
+		if (self.is_major_importing)() {
+			return;
+		}
+
+		if let Some(ref command) = self.config.on_reorg {
+			if !route.retracted().is_empty() {
+				if let Some(event) = self.event("reorg", &route) {
+					self.runner.push(command, event);
+				}
+			}
+		}
+
+		if let Some(ref command) = self.config.on_new_block {
+			if let Some(event) = self.event("newBlock", &route) {
+				self.runner.push(command, event);
+			}
+		}
+	}
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+	let mut shell = Command::new("sh");
+	shell.arg("-c").arg(command);
+	shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+	let mut shell = Command::new("cmd");
+	shell.arg("/C").arg(command);
+	shell
+}
+
+/// Runs the command until it exits or the timeout passes.
+fn execute(command: &str, event: &Event, timeout: Duration) -> Result<(), String> {
+	let mut child = shell(command)
+		.envs(event.env())
+		.stdin(Stdio::piped())
+		.stdout(Stdio::null())
+		.spawn()
+		.map_err(|e| format!("Unable to start: {}", e))?;
+
+	if let Some(mut stdin) = child.stdin.take() {
+		// written separately, so a command which doesn't read its input can't block us
+		let json = event.to_json();
+		thread::spawn(move || {
+			let _ = stdin.write_all(json.as_bytes());
+		});
+	}
+
+	let started = Instant::now();
+	loop {
+		match child.try_wait() {
+			Ok(Some(status)) => return match status.success() {
+				true => Ok(()),
+				false => Err(format!("Exited with {}", status)),
+			},
+			Ok(None) if started.elapsed() >= timeout => {
+				let _ = child.kill();
+				let _ = child.wait();
+				return Err(format!("Killed after {}s", timeout.as_secs()));
+			},
+			Ok(None) => thread::sleep(POLL_INTERVAL),
+			Err(e) => return Err(format!("Unable to wait for the command: {}", e)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::File;
+	use std::io::Read;
+	use std::path::Path;
+	use std::thread;
+	use std::time::{Duration, Instant};
+	use tempdir::TempDir;
+	use super::{Event, Runner, execute};
+
+	fn event() -> Event {
+		Event {
+			kind: "reorg",
+			number: 10,
+			hash: 10.into(),
+			parent_hash: 9.into(),
+			timestamp: 1_500_000_000,
+			retracted: vec![7.into()],
+			enacted: vec![8.into(), 10.into()],
+		}
+	}
+
+	#[test]
+	fn should_serialize_event() {
+		assert_eq!(
+			event().to_json(),
+			r#"{"event":"reorg","number":10,"hash":"0x000000000000000000000000000000000000000000000000000000000000000a","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000009","timestamp":1500000000,"retracted":["0x0000000000000000000000000000000000000000000000000000000000000007"],"enacted":["0x0000000000000000000000000000000000000000000000000000000000000008","0x000000000000000000000000000000000000000000000000000000000000000a"]}"#
+		);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn should_pass_event_and_enforce_timeout() {
+		let timeout = Duration::from_secs(5);
+		assert_eq!(execute(r#"test "$PARITY_BLOCK_NUMBER" = 10 && grep -q '"event":"reorg"'"#, &event(), timeout), Ok(()));
+		assert!(execute("exit 3", &event(), timeout).is_err());
+
+		let started = Instant::now();
+		assert!(execute("sleep 10", &event(), Duration::from_millis(100)).is_err());
+		assert!(started.elapsed() < timeout);
+	}
+
+	fn read(path: &Path) -> String {
+		let mut content = String::new();
+		let _ = File::open(path).and_then(|mut file| file.read_to_string(&mut content));
+		content
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn should_start_commands_in_order_and_skip_when_full() {
+		let tempdir = TempDir::new("").unwrap();
+		let log = tempdir.path().join("log");
+		let command = format!("sleep 0.2; echo $PARITY_BLOCK_NUMBER >> {}", log.display());
+		let runner = Runner::new(1, Duration::from_secs(5)).unwrap();
+
+		let events: Vec<_> = (1..4).map(|number| Event { number, ..event() }).collect();
+		// the first one runs, the second one waits in the queue and the third one is skipped
+		assert!(runner.push(&command, events[0].clone()));
+		thread::sleep(Duration::from_millis(100));
+		assert!(runner.push(&command, events[1].clone()));
+		assert!(!runner.push(&command, events[2].clone()));
+
+		let started = Instant::now();
+		while read(&log).lines().count() < 2 {
+			assert!(started.elapsed() < Duration::from_secs(5));
+			thread::sleep(Duration::from_millis(50));
+		}
+		assert_eq!(read(&log), "1\n2\n");
+
+		assert!(runner.push(&command, events[2].clone()));
+	}
+}
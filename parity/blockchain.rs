@@ -15,18 +15,20 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::str::{FromStr, from_utf8};
-use std::{io, fs};
-use std::io::{BufReader, BufRead};
+use std::{cmp, io, fs};
+use std::io::{BufReader, BufRead, Write};
+use std::path::{Path, PathBuf};
 use std::time::{Instant, Duration};
 use std::thread::sleep;
 use std::sync::Arc;
+use std::cell::RefCell;
 use rustc_hex::FromHex;
 use hash::{keccak, KECCAK_NULL_RLP};
-use ethereum_types::{U256, H256, Address};
+use ethereum_types::{U256, H256, Address, Bloom};
 use bytes::ToPretty;
 use rlp::PayloadInfo;
 use ethcore::account_provider::AccountProvider;
-use ethcore::client::{Mode, DatabaseCompactionProfile, VMType, BlockImportError, Nonce, Balance, BlockChainClient, BlockId, BlockInfo, ImportBlock};
+use ethcore::client::{Mode, DatabaseCompactionProfile, VMType, BlockImportError, Nonce, Balance, BlockChainClient, BlockId, BlockInfo, ChainInfo, ImportBlock, BloomsConfig, ReceiptsStorage, ExplorerIndex};
 use ethcore::error::{ImportErrorKind, BlockImportErrorKind};
 use ethcore::miner::Miner;
 use ethcore::verification::queue::VerifierSettings;
@@ -34,11 +36,12 @@ use ethcore_service::ClientService;
 use cache::CacheConfig;
 use informant::{Informant, FullNodeInformantData, MillisecondDuration};
 use params::{SpecType, Pruning, Switch, tracing_switch_to_bool, fatdb_switch_to_bool};
-use helpers::{to_client_config, execute_upgrades};
+use helpers::{to_client_config, execute_upgrades, copy_dir};
 use dir::Directories;
 use user_defaults::UserDefaults;
 use ethcore_private_tx;
 use db;
+use blooms_db;
 
 #[derive(Debug, PartialEq)]
 pub enum DataFormat {
@@ -64,12 +67,93 @@ impl FromStr for DataFormat {
 	}
 }
 
+/// How often progress of import and export is reported, in seconds.
+const PROGRESS_INTERVAL_SECS: u64 = 10;
+
+/// Periodic progress of a long-running import or export.
+/// Reported as JSON lines on stderr and, optionally, written to a status file.
+struct Progress {
+	operation: &'static str,
+	status_file: Option<PathBuf>,
+	started: Instant,
+	last_report: Instant,
+	first_block: u64,
+	/// Work done and total work (bytes read or blocks written), used for the ETA.
+	done: u64,
+	total: Option<u64>,
+}
+
+impl Progress {
+	fn new(operation: &'static str, first_block: u64, total: Option<u64>, status_file: Option<PathBuf>) -> Self {
+		Progress {
+			operation,
+			status_file,
+			started: Instant::now(),
+			last_report: Instant::now(),
+			first_block,
+			done: 0,
+			total,
+		}
+	}
+
+	fn advance(&mut self, amount: u64) {
+		self.done += amount;
+	}
+
+	fn is_due(&self) -> bool {
+		self.last_report.elapsed() >= Duration::from_secs(PROGRESS_INTERVAL_SECS)
+	}
+
+	/// Prints the progress and writes it to the status file. Failing to write the file doesn't
+	/// stop the operation, it's only logged.
+	fn report(&mut self, current_block: u64, finished: bool) {
+		self.last_report = Instant::now();
+
+		let status = self.status(current_block, finished, self.started.elapsed().as_milliseconds()).to_string();
+		eprintln!("{}", status);
+
+		if let Some(ref path) = self.status_file {
+			let tmp = path.with_extension("tmp");
+			let written = fs::File::create(&tmp)
+				.and_then(|mut file| file.write_all(status.as_bytes()))
+				.and_then(|_| fs::rename(&tmp, path));
+			if let Err(e) = written {
+				warn!("Cannot write progress file {}: {}", path.display(), e);
+			}
+		}
+	}
+
+	fn status(&self, current_block: u64, finished: bool, elapsed: u64) -> ::serde_json::Value {
+		let blocks = current_block.saturating_sub(self.first_block);
+		let blocks_per_sec = if elapsed == 0 { 0.0 } else { (blocks * 1000) as f64 / elapsed as f64 };
+		let eta = match self.total {
+			Some(total) if self.done > 0 && !finished => Some(elapsed * total.saturating_sub(self.done) / self.done / 1000),
+			_ => None,
+		};
+
+		json!({
+			"operation": self.operation,
+			"block": current_block,
+			"blocksPerSec": blocks_per_sec,
+			"elapsedSecs": elapsed / 1000,
+			"etaSecs": eta,
+			"finished": finished,
+		})
+	}
+}
+
 #[derive(Debug, PartialEq)]
 pub enum BlockchainCmd {
 	Kill(KillBlockchain),
+	Backup(BackupBlockchain),
+	Restore(RestoreBlockchain),
+	RebuildBlooms(RebuildBlooms),
+	IndexAddresses(IndexAddresses),
 	Import(ImportBlockchain),
 	Export(ExportBlockchain),
 	ExportState(ExportState),
+	PruneTraces(PruneTraces),
+	BackfillTraces(BackfillTraces),
 }
 
 #[derive(Debug, PartialEq)]
@@ -79,6 +163,48 @@ pub struct KillBlockchain {
 	pub pruning: Pruning,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct BackupBlockchain {
+	pub spec: SpecType,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub target: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RestoreBlockchain {
+	pub spec: SpecType,
+	pub dirs: Directories,
+	pub source: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RebuildBlooms {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub fat_db: Switch,
+	pub blooms: BloomsConfig,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IndexAddresses {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub fat_db: Switch,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ImportBlockchain {
 	pub spec: SpecType,
@@ -98,6 +224,7 @@ pub struct ImportBlockchain {
 	pub with_color: bool,
 	pub verifier_settings: VerifierSettings,
 	pub light: bool,
+	pub progress_file: Option<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -117,6 +244,7 @@ pub struct ExportBlockchain {
 	pub from_block: BlockId,
 	pub to_block: BlockId,
 	pub check_seal: bool,
+	pub progress_file: Option<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -141,9 +269,42 @@ pub struct ExportState {
 	pub max_balance: Option<U256>,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct PruneTraces {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub fat_db: Switch,
+	pub before: BlockId,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BackfillTraces {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub fat_db: Switch,
+	pub from_block: BlockId,
+	pub to_block: BlockId,
+}
+
 pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 	match cmd {
 		BlockchainCmd::Kill(kill_cmd) => kill_db(kill_cmd),
+		BlockchainCmd::Backup(backup_cmd) => backup_db(backup_cmd),
+		BlockchainCmd::Restore(restore_cmd) => restore_db(restore_cmd),
+		BlockchainCmd::RebuildBlooms(rebuild_cmd) => execute_rebuild_blooms(rebuild_cmd),
+		BlockchainCmd::IndexAddresses(index_cmd) => execute_index_addresses(index_cmd),
 		BlockchainCmd::Import(import_cmd) => {
 			if import_cmd.light {
 				execute_import_light(import_cmd)
@@ -153,6 +314,8 @@ pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 		}
 		BlockchainCmd::Export(export_cmd) => execute_export(export_cmd),
 		BlockchainCmd::ExportState(export_cmd) => execute_export_state(export_cmd),
+		BlockchainCmd::PruneTraces(prune_cmd) => execute_prune_traces(prune_cmd),
+		BlockchainCmd::BackfillTraces(backfill_cmd) => execute_backfill_traces(backfill_cmd),
 	}
 }
 
@@ -200,6 +363,7 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 		verify_full: true,
 		check_seal: cmd.check_seal,
 		no_hardcoded_sync: true,
+		..Default::default()
 	};
 
 	config.queue.max_mem_use = cmd.cache_config.queue() as usize * 1024 * 1024;
@@ -209,7 +373,8 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 	let db = db::open_db(&client_path.to_str().expect("DB path could not be converted to string."),
 						 &cmd.cache_config,
 						 &cmd.compaction,
-						 cmd.wal).map_err(|e| format!("Failed to open database: {:?}", e))?;
+						 cmd.wal,
+						 false).map_err(|e| format!("Failed to open database: {:?}", e))?;
 
 	// TODO: could epoch signals be avilable at the end of the file?
 	let fetch = ::light::client::fetch::unavailable();
@@ -221,6 +386,14 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 
 	let client = service.client();
 
+	let file_size = cmd.file_path.as_ref().and_then(|f| fs::metadata(f).ok()).map(|m| m.len());
+	let progress = RefCell::new(Progress::new(
+		"import",
+		client.best_block_header().number(),
+		file_size,
+		cmd.progress_file.map(PathBuf::from),
+	));
+
 	let mut instream: Box<io::Read> = match cmd.file_path {
 		Some(f) => Box::new(fs::File::open(&f).map_err(|_| format!("Cannot open given file: {}", f))?),
 		None => Box::new(io::stdin()),
@@ -242,7 +415,9 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 		}
 	};
 
-	let do_import = |bytes: Vec<u8>| {
+	// `read` is the number of bytes read from the file for the block, the ETA is based on it.
+	let do_import = |bytes: Vec<u8>, read: usize| {
+		progress.borrow_mut().advance(read as u64);
 		while client.queue_info().is_full() { sleep(Duration::from_secs(1)); }
 
 		let header: ::ethcore::header::Header = ::rlp::Rlp::new(&bytes).val_at(0)
@@ -250,8 +425,8 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 
 		if client.best_block_header().number() >= header.number() { return Ok(()) }
 
-		if header.number() % 10000 == 0 {
-			info!("#{}", header.number());
+		if progress.borrow().is_due() {
+			progress.borrow_mut().report(header.number(), false);
 		}
 
 		match client.import_header(header) {
@@ -280,7 +455,8 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 				let s = PayloadInfo::from(&bytes).map_err(|e| format!("Invalid RLP in the file/stream: {:?}", e))?.total();
 				bytes.resize(s, 0);
 				instream.read_exact(&mut bytes[n..]).map_err(|_| "Error reading from the file/stream.")?;
-				do_import(bytes)?;
+				let read = bytes.len();
+				do_import(bytes, read)?;
 			}
 		}
 		DataFormat::Hex => {
@@ -289,11 +465,13 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 				let s = if first_read > 0 {from_utf8(&first_bytes).unwrap().to_owned() + &(s[..])} else {s};
 				first_read = 0;
 				let bytes = s.from_hex().map_err(|_| format!("{} Invalid hex in file/stream.",line!()) )?;
-				do_import(bytes)?;
+				// the line and its line break
+				do_import(bytes, s.len() + 1)?;
 			}
 		}
 	}
 	client.flush_queue();
+	progress.borrow_mut().report(client.best_block_header().number(), true);
 
 	let ms = timer.elapsed().as_milliseconds();
 	let report = client.report();
@@ -388,6 +566,14 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 
 	let client = service.client();
 
+	let file_size = cmd.file_path.as_ref().and_then(|f| fs::metadata(f).ok()).map(|m| m.len());
+	let progress = RefCell::new(Progress::new(
+		"import",
+		client.chain_info().best_block_number,
+		file_size,
+		cmd.progress_file.map(PathBuf::from),
+	));
+
 	let mut instream: Box<io::Read> = match cmd.file_path {
 		Some(f) => Box::new(fs::File::open(&f).map_err(|_| format!("Cannot open given file: {}", f))?),
 		None => Box::new(io::stdin()),
@@ -414,15 +600,19 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 			client: client.clone(),
 			sync: None,
 			net: None,
+			db_size: None,
 		},
 		None,
 		None,
 		cmd.with_color,
+		Default::default(),
 	));
 
 	service.register_io_handler(informant).map_err(|_| "Unable to register informant handler".to_owned())?;
 
-	let do_import = |bytes| {
+	// `read` is the number of bytes read from the file for the block, the ETA is based on it.
+	let do_import = |bytes: Vec<u8>, read: usize| {
+		progress.borrow_mut().advance(read as u64);
 		while client.queue_info().is_full() { sleep(Duration::from_secs(1)); }
 		match client.import_block(bytes) {
 			Err(BlockImportError(BlockImportErrorKind::Import(ImportErrorKind::AlreadyInChain), _)) => {
@@ -433,6 +623,9 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 			},
 			Ok(_) => {},
 		}
+		if progress.borrow().is_due() {
+			progress.borrow_mut().report(client.chain_info().best_block_number, false);
+		}
 		Ok(())
 	};
 
@@ -450,7 +643,8 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 				let s = PayloadInfo::from(&bytes).map_err(|e| format!("Invalid RLP in the file/stream: {:?}", e))?.total();
 				bytes.resize(s, 0);
 				instream.read_exact(&mut bytes[n..]).map_err(|_| "Error reading from the file/stream.")?;
-				do_import(bytes)?;
+				let read = bytes.len();
+				do_import(bytes, read)?;
 			}
 		}
 		DataFormat::Hex => {
@@ -461,11 +655,13 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 				let s = if first_read > 0 {from_utf8(&first_bytes).unwrap().to_owned() + &(s[..])} else {s};
 				first_read = 0;
 				let bytes = s.from_hex().map_err(|_| format!("{} Invalid hex in file/stream.", current_line) )?;
-				do_import(bytes)?;
+				// the line and its line break
+				do_import(bytes, s.len() + 1)?;
 			}
 		}
 	}
 	client.flush_queue();
+	progress.borrow_mut().report(client.chain_info().best_block_number, true);
 
 	// save user defaults
 	user_defaults.pruning = algorithm;
@@ -604,10 +800,12 @@ fn execute_export(cmd: ExportBlockchain) -> Result<(), String> {
 
 	let from = client.block_number(cmd.from_block).ok_or("From block could not be found")?;
 	let to = client.block_number(cmd.to_block).ok_or("To block could not be found")?;
+	let mut progress = Progress::new("export", from, Some((to + 1).saturating_sub(from)), cmd.progress_file.map(PathBuf::from));
 
 	for i in from..(to + 1) {
-		if i % 10000 == 0 {
-			info!("#{}", i);
+		progress.advance(1);
+		if progress.is_due() {
+			progress.report(i, false);
 		}
 		let b = client.block(BlockId::Number(i)).ok_or("Error exporting incomplete chain")?.into_inner();
 		match format {
@@ -620,6 +818,7 @@ fn execute_export(cmd: ExportBlockchain) -> Result<(), String> {
 		}
 	}
 
+	progress.report(to, true);
 	info!("Export completed.");
 	Ok(())
 }
@@ -744,6 +943,76 @@ fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 	Ok(())
 }
 
+fn execute_prune_traces(cmd: PruneTraces) -> Result<(), String> {
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		Switch::Auto,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.wal,
+		cmd.cache_config,
+		false,
+	)?;
+
+	let client = service.client();
+	if !client.tracing_enabled() {
+		return Err("Tracing is not enabled for this database. Nothing to prune.".into());
+	}
+
+	let before = client.block_number(cmd.before).ok_or("Block could not be found")?;
+	let pruned = client.prune_traces(before).map_err(|e| format!("Failed to prune traces: {}", e))?;
+
+	info!("Pruned traces of {} blocks.", pruned);
+	Ok(())
+}
+
+fn execute_backfill_traces(cmd: BackfillTraces) -> Result<(), String> {
+	let timer = Instant::now();
+
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		Switch::Auto,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.wal,
+		cmd.cache_config,
+		false,
+	)?;
+
+	let client = service.client();
+	if !client.tracing_enabled() {
+		return Err("Tracing is not enabled for this database. Enable it with --tracing on, which requires a resync.".into());
+	}
+
+	let from = client.block_number(cmd.from_block).ok_or("From block could not be found")?;
+	let to = client.block_number(cmd.to_block).ok_or("To block could not be found")?;
+
+	// re-execution needs the state preceding every block of the range
+	let earliest_state = client.pruning_info().earliest_state;
+	if from.saturating_sub(1) < earliest_state {
+		return Err(format!("State of block #{} is not available; the earliest available state is #{}. Backfilling traces requires an archive node (--pruning archive).", from.saturating_sub(1), earliest_state));
+	}
+
+	for i in from..(to + 1) {
+		if i % 10000 == 0 {
+			info!("#{}", i);
+		}
+		client.retrace_block(i).map_err(|e| format!("Failed to re-execute block #{}: {}", i, e))?;
+	}
+
+	let ms = timer.elapsed().as_milliseconds();
+	info!("Backfilled traces of {} blocks in {}.{:03}s.", to.saturating_sub(from) + 1, ms / 1000, ms % 1000);
+	Ok(())
+}
+
 pub fn kill_db(cmd: KillBlockchain) -> Result<(), String> {
 	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
 	let genesis_hash = spec.genesis_header().hash();
@@ -759,9 +1028,215 @@ pub fn kill_db(cmd: KillBlockchain) -> Result<(), String> {
 	Ok(())
 }
 
+/// Number of header blooms inserted into the index at once.
+const REBUILD_BLOOMS_BATCH: u64 = 4096;
+
+fn execute_rebuild_blooms(cmd: RebuildBlooms) -> Result<(), String> {
+	let timer = Instant::now();
+	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
+	let genesis_hash = spec.genesis_header().hash();
+	let db_dirs = cmd.dirs.database(genesis_hash, None, spec.data_dir);
+	let user_defaults_path = db_dirs.user_defaults_path();
+	let mut user_defaults = UserDefaults::load(&user_defaults_path)?;
+	let client_path = db_dirs.client_path(cmd.pruning.to_algorithm(&user_defaults));
+
+	// the new index is built next to the old one, which is replaced once it's complete.
+	let blooms_path = client_path.join("blooms");
+	let rebuild_path = client_path.join("blooms.rebuild");
+	if rebuild_path.exists() {
+		fs::remove_dir_all(&rebuild_path).map_err(|e| format!("Error removing {}: {}", rebuild_path.display(), e))?;
+	}
+
+	{
+		let service = start_client(
+			cmd.dirs,
+			cmd.spec,
+			cmd.pruning,
+			cmd.pruning_history,
+			cmd.pruning_memory,
+			Switch::Auto,
+			cmd.fat_db,
+			cmd.compaction,
+			cmd.wal,
+			cmd.cache_config,
+			false,
+		)?;
+		let client = service.client();
+
+		fs::create_dir_all(&rebuild_path).map_err(|e| format!("Error creating {}: {}", rebuild_path.display(), e))?;
+		let blooms = blooms_db::Database::open_with_config(&rebuild_path, cmd.blooms)
+			.map_err(|e| format!("Error creating bloom index: {}", e))?;
+
+		let best = client.chain_info().best_block_number;
+		let mut from = 0;
+		while from <= best {
+			let to = cmp::min(from + REBUILD_BLOOMS_BATCH, best + 1);
+			// blocks below the first block of a warp synced chain get empty blooms.
+			let batch: Vec<Bloom> = (from..to)
+				.map(|number| client.block_header(BlockId::Number(number))
+					.map(|header| header.log_bloom())
+					.unwrap_or_default())
+				.collect();
+			blooms.insert_blooms(from, batch.iter()).map_err(|e| format!("Error writing bloom index: {}", e))?;
+			info!("#{}", to - 1);
+			from = to;
+		}
+	}
+
+	fs::remove_dir_all(&blooms_path).map_err(|e| format!("Error removing {}: {}", blooms_path.display(), e))?;
+	fs::rename(&rebuild_path, &blooms_path).map_err(|e| format!("Error replacing bloom index: {}", e))?;
+
+	// the index is complete again, so the node may leave the light receipts storage.
+	if user_defaults.receipts_storage == ReceiptsStorage::Light {
+		user_defaults.receipts_storage = ReceiptsStorage::Compact;
+		user_defaults.save(&user_defaults_path)?;
+		info!("Receipts storage switched from light to compact.");
+	}
+
+	let ms = timer.elapsed().as_milliseconds();
+	info!("Rebuilt bloom index with {} levels and fan out {} in {}.{:03}s.", cmd.blooms.levels, cmd.blooms.fan_out, ms / 1000, ms % 1000);
+	Ok(())
+}
+
+fn execute_index_addresses(cmd: IndexAddresses) -> Result<(), String> {
+	let timer = Instant::now();
+	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
+	let genesis_hash = spec.genesis_header().hash();
+	let db_dirs = cmd.dirs.database(genesis_hash, None, spec.data_dir);
+	let user_defaults = UserDefaults::load(&db_dirs.user_defaults_path())?;
+	let explorer_path = db_dirs.explorer_path(cmd.pruning.to_algorithm(&user_defaults));
+
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		Switch::Auto,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.wal,
+		cmd.cache_config,
+		false,
+	)?;
+	let client = service.client() as Arc<BlockChainClient>;
+
+	let index = ExplorerIndex::new(db::open_explorer_db(&explorer_path)?, Arc::downgrade(&client));
+	let first = index.last_indexed().map_or(0, |(number, _)| number + 1);
+	index.catch_up();
+	let last = index.last_indexed().map_or(0, |(number, _)| number);
+
+	let ms = timer.elapsed().as_milliseconds();
+	info!("Indexed transactions of {} blocks by address in {}.{:03}s.", (last + 1).saturating_sub(first), ms / 1000, ms % 1000);
+	Ok(())
+}
+
+/// Backup layout: the client database, the chain's keys and address book, the user defaults and the chain the backup is of.
+const BACKUP_DB_DIR: &'static str = "db";
+const BACKUP_KEYS_DIR: &'static str = "keys";
+const BACKUP_ADDRESS_BOOK_DIR: &'static str = "address_book";
+const BACKUP_USER_DEFAULTS: &'static str = "user_defaults";
+const BACKUP_CHAIN: &'static str = "chain.json";
+
+pub fn backup_db(cmd: BackupBlockchain) -> Result<(), String> {
+	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
+	let genesis_hash = spec.genesis_header().hash();
+	let db_dirs = cmd.dirs.database(genesis_hash, None, spec.data_dir.clone());
+	let mut user_defaults = UserDefaults::load(&db_dirs.user_defaults_path())?;
+	let algorithm = cmd.pruning.to_algorithm(&user_defaults);
+	let client_path = db_dirs.client_path(algorithm);
+	if !client_path.exists() {
+		return Err(format!("No database found at {}", client_path.display()));
+	}
+
+	let target = Path::new(&cmd.target);
+	let is_empty = fs::read_dir(target).map(|mut entries| entries.next().is_none()).unwrap_or(true);
+	if !is_empty {
+		return Err(format!("Backup directory {} is not empty", target.display()));
+	}
+
+	info!("Backing up database at {} to {}", client_path.display(), target.display());
+	db::backup_db(&client_path, &target.join(BACKUP_DB_DIR))?;
+
+	let keys_path = cmd.dirs.keys_path(&spec.data_dir);
+	if keys_path.exists() {
+		copy_dir(&keys_path, &target.join(BACKUP_KEYS_DIR)).map_err(|e| format!("Error copying keys: {}", e))?;
+	}
+	let address_book_path = cmd.dirs.address_book_path(&spec.data_dir);
+	if address_book_path.exists() {
+		copy_dir(&address_book_path, &target.join(BACKUP_ADDRESS_BOOK_DIR)).map_err(|e| format!("Error copying address book: {}", e))?;
+	}
+
+	// the backup holds the database of this pruning algorithm only.
+	user_defaults.pruning = algorithm;
+	user_defaults.save(&target.join(BACKUP_USER_DEFAULTS))?;
+
+	let chain = json!({
+		"chain": spec.data_dir,
+		"genesis": format!("{:x}", genesis_hash),
+	});
+	fs::File::create(target.join(BACKUP_CHAIN))
+		.and_then(|mut file| file.write_all(chain.to_string().as_bytes()))
+		.map_err(|e| format!("Error writing backup chain: {}", e))?;
+	info!("Backup completed.");
+	Ok(())
+}
+
+pub fn restore_db(cmd: RestoreBlockchain) -> Result<(), String> {
+	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
+	let genesis_hash = spec.genesis_header().hash();
+	let db_dirs = cmd.dirs.database(genesis_hash, None, spec.data_dir.clone());
+
+	let source = Path::new(&cmd.source);
+	let backup_user_defaults = source.join(BACKUP_USER_DEFAULTS);
+	if !backup_user_defaults.exists() || !source.join(BACKUP_DB_DIR).exists() || !source.join(BACKUP_CHAIN).exists() {
+		return Err(format!("{} does not contain a backup", source.display()));
+	}
+
+	let chain: ::serde_json::Value = fs::File::open(source.join(BACKUP_CHAIN))
+		.map_err(|e| e.to_string())
+		.and_then(|file| ::serde_json::from_reader(file).map_err(|e| e.to_string()))
+		.map_err(|e| format!("Error reading backup chain: {}", e))?;
+	let backup_genesis = chain["genesis"].as_str().and_then(|genesis| genesis.parse::<H256>().ok());
+	if backup_genesis != Some(genesis_hash) {
+		return Err(format!(
+			"Backup at {} is of chain {} with genesis {}, not of {} with genesis {:x}. Restore it with the matching --chain.",
+			source.display(), chain["chain"].as_str().unwrap_or("unknown"), chain["genesis"].as_str().unwrap_or("unknown"), spec.data_dir, genesis_hash,
+		));
+	}
+
+	let mut user_defaults = UserDefaults::load(&backup_user_defaults)?;
+	let client_path = db_dirs.client_path(user_defaults.pruning);
+	if client_path.exists() {
+		return Err(format!("Database already exists at {}. Remove it with `parity db kill` before restoring.", client_path.display()));
+	}
+
+	info!("Restoring database at {} from {}", client_path.display(), source.display());
+	copy_dir(&source.join(BACKUP_DB_DIR), &client_path).map_err(|e| format!("Error restoring database: {}", e))?;
+
+	let backup_keys = source.join(BACKUP_KEYS_DIR);
+	if backup_keys.exists() {
+		copy_dir(&backup_keys, &cmd.dirs.keys_path(&spec.data_dir)).map_err(|e| format!("Error restoring keys: {}", e))?;
+	}
+	let backup_address_book = source.join(BACKUP_ADDRESS_BOOK_DIR);
+	if backup_address_book.exists() {
+		copy_dir(&backup_address_book, &cmd.dirs.address_book_path(&spec.data_dir)).map_err(|e| format!("Error restoring address book: {}", e))?;
+	}
+
+	user_defaults.is_first_launch = false;
+	user_defaults.save(&db_dirs.user_defaults_path())?;
+	info!("Backup restored.");
+	Ok(())
+}
+
 #[cfg(test)]
 mod test {
-	use super::DataFormat;
+	use std::fs;
+	use tempdir::TempDir;
+	use dir::Directories;
+	use ethcore::client::ChainInfo;
+	use params::{SpecType, Pruning, Switch};
+	use super::{DataFormat, Progress, BackupBlockchain, RestoreBlockchain, start_client, backup_db, restore_db};
 
 	#[test]
 	fn test_data_format_parsing() {
@@ -769,4 +1244,71 @@ mod test {
 		assert_eq!(DataFormat::Binary, "bin".parse().unwrap());
 		assert_eq!(DataFormat::Hex, "hex".parse().unwrap());
 	}
+
+	#[test]
+	fn test_progress_status() {
+		let mut progress = Progress::new("import", 100, Some(1000), None);
+		assert_eq!(progress.status(100, false, 0)["etaSecs"], json!(null));
+
+		progress.advance(250);
+		let status = progress.status(150, false, 10_000);
+		assert_eq!(status["block"], json!(150));
+		assert_eq!(status["blocksPerSec"], json!(5.0));
+		// a quarter done in 10 seconds
+		assert_eq!(status["etaSecs"], json!(30));
+		assert_eq!(progress.status(200, true, 10_000)["etaSecs"], json!(null));
+	}
+
+	#[test]
+	fn test_progress_file() {
+		let dir = TempDir::new("progress").unwrap();
+		let path = dir.path().join("progress.json");
+
+		Progress::new("export", 0, Some(1), Some(path.clone())).report(1, true);
+		let status: ::serde_json::Value = ::serde_json::from_reader(fs::File::open(&path).unwrap()).unwrap();
+		assert_eq!(status["operation"], json!("export"));
+		assert_eq!(status["finished"], json!(true));
+
+		// failing to write the file is not an error
+		Progress::new("export", 0, Some(1), Some(dir.path().join("missing/progress.json"))).report(1, true);
+	}
+
+	fn dirs(path: &::std::path::Path) -> Directories {
+		let dir = |name: &str| path.join(name).to_string_lossy().into_owned();
+		Directories {
+			base: dir(""),
+			state: dir(""),
+			db: dir("chains"),
+			cache: dir("cache"),
+			keys: dir("keys"),
+			signer: dir("signer"),
+			dapps: dir("dapps"),
+			secretstore: dir("secretstore"),
+		}
+	}
+
+	fn start_dev_client(path: &::std::path::Path) -> ::ethcore_service::ClientService {
+		start_client(dirs(path), SpecType::Dev, Pruning::Auto, 64, 32, Switch::Auto, Switch::Auto, Default::default(), true, Default::default(), false).unwrap()
+	}
+
+	#[test]
+	fn test_backup_and_restore() {
+		let tempdir = TempDir::new("backup").unwrap();
+		let (node, restored, backup) = (tempdir.path().join("node"), tempdir.path().join("restored"), tempdir.path().join("backup"));
+		drop(start_dev_client(&node));
+
+		backup_db(BackupBlockchain { spec: SpecType::Dev, dirs: dirs(&node), pruning: Pruning::Auto, target: backup.to_string_lossy().into_owned() }).unwrap();
+		let restore = |spec| restore_db(RestoreBlockchain { spec, dirs: dirs(&restored), source: backup.to_string_lossy().into_owned() });
+
+		// a backup is only restored for the chain it's of
+		assert!(restore(SpecType::Kovan).unwrap_err().contains("not of kovan"));
+		restore(SpecType::Dev).unwrap();
+
+		let service = start_dev_client(&restored);
+		assert_eq!(service.client().chain_info().best_block_number, 0);
+		drop(service);
+
+		// an existing database is never overwritten
+		assert!(restore(SpecType::Dev).unwrap_err().contains("already exists"));
+	}
 }
@@ -64,6 +64,27 @@ usage! {
 				"<PATH>...",
 				"Path to the accounts",
 			}
+
+			CMD cmd_account_recover
+			{
+				"Create an account from a private key or a BIP-39 mnemonic phrase, without starting the node. The key is read from the terminal unless --secret-file is given",
+
+				FLAG flag_account_recover_mnemonic: (bool) = false,
+				"--mnemonic",
+				"Recover from a BIP-39 mnemonic phrase instead of a hex-encoded private key.",
+
+				ARG arg_account_recover_secret_file: (Option<String>) = None,
+				"--secret-file=[FILE]",
+				"Read the private key or the mnemonic phrase from FILE.",
+
+				ARG arg_account_recover_passphrase_file: (Option<String>) = None,
+				"--passphrase-file=[FILE]",
+				"Read the passphrase protecting the mnemonic phrase from FILE.",
+
+				ARG arg_account_recover_derivation_path: (String) = "m/44'/60'/0'/0/0",
+				"--derivation-path=[PATH]",
+				"Derivation path of the account recovered from a mnemonic phrase.",
+			}
 		}
 
 		CMD cmd_wallet
@@ -91,6 +112,10 @@ usage! {
 			ARG arg_import_file: (Option<String>) = None,
 			"[FILE]",
 			"Path to the file to import from",
+
+			ARG arg_import_progress_file: (Option<String>) = None,
+			"--progress-file=[FILE]",
+			"Write the latest progress as JSON to FILE, in addition to stderr.",
 		}
 
 		CMD cmd_export
@@ -116,6 +141,10 @@ usage! {
 				ARG arg_export_blocks_file: (Option<String>) = None,
 				"[FILE]",
 				"Path to the exported file",
+
+				ARG arg_export_blocks_progress_file: (Option<String>) = None,
+				"--progress-file=[FILE]",
+				"Write the latest progress as JSON to FILE, in addition to stderr.",
 			}
 
 			CMD cmd_export_state
@@ -162,12 +191,46 @@ usage! {
 
 			CMD cmd_signer_new_token {
 				"Generate new token",
+
+				ARG arg_signer_token_name: (Option<String>) = None,
+				"--name=[NAME]",
+				"Name used to identify and revoke the token.",
+
+				ARG arg_signer_token_scope: (Option<String>) = None,
+				"--scope=[METHODS]",
+				"Limit the token to a comma-delimited list of RPC namespaces (e.g. signer) or methods (e.g. signer_requestsToConfirm).",
+
+				ARG arg_signer_token_expiry: (Option<u64>) = None,
+				"--expiry=[SECS]",
+				"Number of seconds after which the token expires.",
+			}
+
+			CMD cmd_signer_tokens {
+				"List authorization tokens",
+			}
+
+			CMD cmd_signer_revoke_token
+			{
+				"Revoke authorization token",
+
+				ARG arg_signer_revoke_token_id: (Option<String>) = None,
+				"<ID>",
+				"ID or name of the token",
 			}
 
 			CMD cmd_signer_list {
 				"List",
 			}
 
+			CMD cmd_signer_approve_all
+			{
+				"Approve all requests matching a filter",
+
+				ARG arg_signer_approve_all_matching: (Option<String>) = None,
+				"--matching=[FILTER]",
+				"Comma-delimited list of conditions requests have to match, e.g. kind=transaction,to=0x...,method=transfer,max-value=1000000000000000000. Possible keys are: kind (transaction, sign, decrypt), from, to, method, max-value (in wei).",
+			}
+
 			CMD cmd_signer_sign
 			{
 				"Sign",
@@ -230,12 +293,117 @@ usage! {
 			CMD cmd_db_kill {
 				"Clean the database",
 			}
+
+			CMD cmd_db_prune_traces {
+				"Delete traces of old blocks",
+
+				ARG arg_db_prune_traces_before: (Option<String>) = None,
+				"--before=[BLOCK]",
+				"Delete traces of blocks preceding block BLOCK, which may be an index, hash or latest.",
+			}
+
+			CMD cmd_db_trace_backfill {
+				"Regenerate traces of a range of blocks by re-executing them",
+
+				ARG arg_db_trace_backfill_range: (Option<String>) = None,
+				"<RANGE>",
+				"Blocks to re-execute, given as FROM..TO (inclusive), e.g. 4000000..4100000. TO may be omitted to re-execute up to the latest block. Only works on archive nodes (--pruning archive) whose database has tracing enabled, since every block is re-executed on top of the state of its parent.",
+			}
+
+			CMD cmd_db_rebuild_blooms {
+				"Rebuild the log bloom index from block headers, using the layout given by --blooms-levels and --blooms-fan-out",
+			}
+
+			CMD cmd_db_index_addresses {
+				"Build the index of transactions by address used by --enable-explorer-index up to the latest block, continuing where it left off",
+			}
+
+			CMD cmd_db_backup {
+				"Take a consistent copy of the database, keys and user defaults, also while the node is running",
+
+				ARG arg_db_backup_dir: (Option<String>) = None,
+				"<DIR>",
+				"Directory to write the backup to. It must not exist or be empty.",
+			}
+
+			CMD cmd_db_restore_backup {
+				"Restore the database, keys and user defaults from a backup made with `parity db backup`",
+
+				ARG arg_db_restore_backup_dir: (Option<String>) = None,
+				"<DIR>",
+				"Directory containing the backup.",
+			}
+		}
+
+		CMD cmd_chain
+		{
+			"Inspect chain specifications",
+
+			CMD cmd_chain_validate_spec {
+				"Check a chain specification and print its genesis hash without creating a database",
+
+				ARG arg_chain_validate_spec_file: (Option<String>) = None,
+				"[SPEC]",
+				"Chain specification to check, given as a path or a built-in chain name. Defaults to the value of --chain.",
+			}
+		}
+
+		CMD cmd_updater
+		{
+			"Manage installed updates",
+
+			CMD cmd_updater_rollback {
+				"Switch back to the previously installed release. The release rolled back from won't be installed again.",
+			}
+		}
+
+		CMD cmd_setup
+		{
+			"Create a configuration file by answering a few questions",
+
+			ARG arg_setup_answers_file: (Option<String>) = None,
+			"--answers-file=[FILE]",
+			"Read the answers from FILE, one per line, instead of asking for them. Empty lines select the default answer.",
+		}
+
+		CMD cmd_service
+		{
+			"Manage the Parity Windows service",
+
+			CMD cmd_service_install {
+				"Register Parity as a Windows service started on boot. Options given along with this command are used by the service, e.g. `parity service install --config C:\\parity\\config.toml`. Use --log-file to keep the logs.",
+			}
+
+			CMD cmd_service_remove {
+				"Unregister the Parity Windows service.",
+			}
+
+			CMD cmd_service_run {
+				"Run the node as a Windows service. This is started by Windows, register the service with `parity service install` instead.",
+			}
 		}
 
 		CMD cmd_export_hardcoded_sync
 		{
 			"Export the hardcoded sync JSON file from the existing light client database",
 		}
+
+		CMD cmd_test_runner
+		{
+			"Run JSON consensus tests (GeneralStateTests and BlockchainTests) and report the result of each test",
+
+			ARG arg_test_runner_chain_tests: (Option<String>) = None,
+			"--chain-tests=[PATH]",
+			"Test file, or directory searched for test files recursively.",
+
+			ARG arg_test_runner_fork: (Option<String>) = None,
+			"--fork=[FORK]",
+			"Only run tests of the given fork, e.g. Byzantium.",
+
+			ARG arg_test_runner_spec: (Option<String>) = None,
+			"--spec=[SPEC]",
+			"Run the tests of --fork with the given chain specification in place of the built-in one, given as a path or a built-in chain name.",
+		}
 	}
 	{
 		// Global flags and arguments
@@ -288,6 +456,30 @@ usage! {
 			"--release-track=[TRACK]",
 			"Set which release track we should use for updates. TRACK can be one of: stable - Stable releases; beta - Beta releases; nightly - Nightly releases (unstable); testing - Testing releases (do not use); current - Whatever track this executable was released on.",
 
+			ARG arg_auto_update_channel: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.auto_update_channel.clone(),
+			"--auto-update-channel=[CHANNEL]",
+			"Set the release channel to follow for updates, overriding --release-track. CHANNEL can be one of: stable, beta, nightly.",
+
+			ARG arg_auto_update_max_version: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.auto_update_max_version.clone(),
+			"--auto-update-max-version=[VERSION]",
+			"Never update past the given VERSION (e.g. 1.12.3), to pin the fleet during staged rollouts.",
+
+			ARG arg_auto_update_trusted_keys: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.auto_update_trusted_keys.clone().map(|vec| vec.join(",")),
+			"--auto-update-trusted-keys=[KEYS]",
+			"Comma separated list of public keys trusted to sign releases. When set, an update is only installed if its binary is signed by one of these keys. The hex encoded detached signature is fetched along with the binary, from its address with a .sig suffix.",
+
+			ARG arg_auto_update_keep: (usize) = 2usize, or |c: &Config| c.parity.as_ref()?.auto_update_keep.clone(),
+			"--auto-update-keep=[N]",
+			"Number of previously installed releases to keep, so that `parity updater rollback` can switch back to them.",
+
+			ARG arg_hash_fetch_mirrors: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.hash_fetch_mirrors.clone().map(|vec| vec.join(",")),
+			"--hash-fetch-mirrors=[URLS]",
+			"Comma separated list of mirror URLs to fetch release binaries from, in order, when the URL registered on-chain is unavailable. Content is requested from <mirror>/<hash>.",
+
+			ARG arg_blob_store_size: (u32) = 256u32, or |c: &Config| c.parity.as_ref()?.blob_store_size.clone(),
+			"--blob-store-size=[MB]",
+			"Maximal size of content downloaded by hash, like dapps and release binaries, kept in the cache directory. Least recently used content is removed first.",
+
 			ARG arg_chain: (String) = "foundation", or |c: &Config| c.parity.as_ref()?.chain.clone(),
 			"--chain=[CHAIN]",
 			"Specify the blockchain type. CHAIN may be either a JSON chain specification file or olympic, frontier, homestead, mainnet, morden, ropsten, classic, expanse, tobalaba, musicoin, ellaism, easthub, social, testnet, kovan or dev.",
@@ -300,14 +492,38 @@ usage! {
 			"--identity=[NAME]",
 			"Specify your node's name.",
 
+			ARG arg_rpc_prefix: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.rpc_prefix.clone(),
+			"--rpc-prefix=[NAME]",
+			"When several chains run in one process, route in-process JSON-RPC calls whose method is prefixed with NAME: (e.g. NAME:eth_blockNumber) to this chain.",
+
 			ARG arg_base_path: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.base_path.clone(),
 			"-d, --base-path=[PATH]",
 			"Specify the base data storage path.",
 
+			ARG arg_state_path: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.state_path.clone(),
+			"--state-path=[PATH]",
+			"Specify a separate writable path for node state (database, keys, user defaults, signer codes, network config and IPC sockets). Chain spec caches and dapps are still read from the base path, so it may be mounted read-only.",
+
 			ARG arg_db_path: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.db_path.clone(),
 			"--db-path=[PATH]",
 			"Specify the database directory path",
 
+			ARG arg_on_new_block: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.on_new_block.clone(),
+			"--on-new-block=[CMD]",
+			"Run CMD in a shell whenever the best block changes, except while catching up with the chain. Commands are started in the order of the blocks. Block data is passed in PARITY_* environment variables and as JSON on stdin.",
+
+			ARG arg_on_reorg: (Option<String>) = None, or |c: &Config| c.parity.as_ref()?.on_reorg.clone(),
+			"--on-reorg=[CMD]",
+			"Run CMD in a shell whenever blocks are retracted from the best chain. Block data is passed like for --on-new-block.",
+
+			ARG arg_hook_timeout: (u64) = 30u64, or |c: &Config| c.parity.as_ref()?.hook_timeout.clone(),
+			"--hook-timeout=[SECS]",
+			"Kill commands run by --on-new-block and --on-reorg after SECS seconds.",
+
+			ARG arg_hook_max_concurrent: (usize) = 4usize, or |c: &Config| c.parity.as_ref()?.hook_max_concurrent.clone(),
+			"--hook-max-concurrent=[NUM]",
+			"Maximal number of commands run by --on-new-block and --on-reorg at once. NUM more events wait for a command to finish, further ones are skipped.",
+
 		["Convenience Options"]
 			FLAG flag_unsafe_expose: (bool) = false, or |c: &Config| c.misc.as_ref()?.unsafe_expose,
 			"--unsafe-expose",
@@ -380,6 +596,14 @@ usage! {
 			"--ui-path=[PATH]",
 			"Specify directory where Trusted UIs tokens should be stored.",
 
+			ARG arg_ui_auto_approve: (Option<String>) = None, or |c: &Config| c.ui.as_ref()?.auto_approve.clone(),
+			"--ui-auto-approve=[FILE]",
+			"Specify a JSON file with rules for automatic approval of transactions in the signer queue. Matching transactions are confirmed using passwords from --password, all other requests wait for manual confirmation.",
+
+			ARG arg_signer_notify_url: (Option<String>) = None, or |c: &Config| c.ui.as_ref()?.notify_url.clone(),
+			"--signer-notify-url=[URL]",
+			"POST a JSON summary (id, type, from, to, value and decoded method) of every new signer confirmation request to URL.",
+
 		["Networking Options"]
 			FLAG flag_no_warp: (bool) = false, or |c: &Config| c.network.as_ref()?.warp.clone().map(|w| !w),
 			"--no-warp",
@@ -401,6 +625,14 @@ usage! {
 			"--no-serve-light",
 			"Disable serving of light peers.",
 
+			FLAG flag_sync_stall_refresh_discovery: (bool) = false, or |c: &Config| c.network.as_ref()?.sync_stall_refresh_discovery.clone(),
+			"--sync-stall-refresh-discovery",
+			"Restart node discovery whenever the sync is detected to be stalled.",
+
+			FLAG flag_head_first_sync: (bool) = false, or |c: &Config| c.network.as_ref()?.head_first_sync.clone(),
+			"--head-first-sync",
+			"Fetch the best peer's head block first and verify its header chain backwards to our best block, so the latest block is available over RPC before the chain is fully imported.",
+
 			ARG arg_warp_barrier: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.warp_barrier.clone(),
 			"--warp-barrier=[NUM]",
 			"When warp enabled never attempt regular sync before warping to block NUM.",
@@ -425,6 +657,10 @@ usage! {
 			"--snapshot-peers=[NUM]",
 			"Allow additional NUM peers for a snapshot sync.",
 
+			ARG arg_min_protocol_peers: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.min_protocol_peers.as_ref().map(|vec| vec.join(",")),
+			"--min-protocol-peers=[LIST]",
+			"Keep at least NUM of the regular peer slots for peers supporting the given subprotocol. LIST should be comma-delimited PROTOCOL:NUM pairs, e.g. par:4,pip:2 keeps 4 slots for snapshot-capable and 2 for light-serving peers.",
+
 			ARG arg_nat: (String) = "any", or |c: &Config| c.network.as_ref()?.nat.clone(),
 			"--nat=[METHOD]",
 			"Specify method to use for determining public address. Must be one of: any, none, upnp, extip:<IP>.",
@@ -453,6 +689,46 @@ usage! {
 			"--reserved-peers=[FILE]",
 			"Provide a file containing enodes, one per line. These nodes will always have a reserved slot on top of the normal maximum peers.",
 
+			ARG arg_node_permission_overrides: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.node_permission_overrides.clone(),
+			"--node-permission-overrides=[FILE]",
+			"Provide a file of 'allow <node id>' and 'deny <node id>' lines taking precedence over the chain's node permissions contract. The file is read again on every new block.",
+
+			ARG arg_fork_alert_depth: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.fork_alert_depth.clone(),
+			"--fork-alert-depth=[NUM]",
+			"Log a fork alert whenever a peer follows a branch which diverged from our chain at least NUM blocks ago. Alerts are also available via parity_forkStatus.",
+
+			ARG arg_fork_alert_webhook: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.fork_alert_webhook.clone(),
+			"--fork-alert-webhook=[URL]",
+			"POST every new fork alert as JSON to URL. Requires --fork-alert-depth.",
+
+			ARG arg_max_reorg_depth: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.max_reorg_depth.clone(),
+			"--max-reorg-depth=[NUM]",
+			"Halt block import whenever a block would retract more than NUM blocks of our chain. Import resumes once the reorganization is accepted with parity_acceptReorg.",
+
+			ARG arg_finality_depth: (u64) = 12u64, or |c: &Config| c.network.as_ref()?.finality_depth.clone(),
+			"--finality-depth=[NUM]",
+			"Consider blocks final once they have NUM confirmations, for consensus engines without their own notion of finality. Used by the finalized block tag and parity_finalityStatus.",
+
+			ARG arg_reorg_alert_webhook: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.reorg_alert_webhook.clone(),
+			"--reorg-alert-webhook=[URL]",
+			"POST a JSON alert to URL whenever block import is halted. Requires --max-reorg-depth.",
+
+			ARG arg_sync_stall_timeout: (u64) = 0u64, or |c: &Config| c.network.as_ref()?.sync_stall_timeout.clone(),
+			"--sync-stall-timeout=[SECS]",
+			"Consider the sync stalled when the best block has not changed for SECS seconds while better peers are connected. 0 disables stall detection (default).",
+
+			ARG arg_sync_stall_rotate_peers: (usize) = 2usize, or |c: &Config| c.network.as_ref()?.sync_stall_rotate_peers.clone(),
+			"--sync-stall-rotate-peers=[NUM]",
+			"Disconnect NUM most lagging peers when the sync is stalled.",
+
+			ARG arg_tx_propagation: (String) = "sqrt", or |c: &Config| c.network.as_ref()?.tx_propagation.clone(),
+			"--tx-propagation=[POLICY]",
+			"Specify which peers receive pending transactions. POLICY must be one of: all - every peer; sqrt - random sample of square root of peers; none - do not propagate (listen-only); private - only local transactions, only to peers listed in --tx-propagation-peers.",
+
+			ARG arg_tx_propagation_peers: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.tx_propagation_peers.as_ref().map(|vec| vec.join(",")),
+			"--tx-propagation-peers=[NODES]",
+			"Peers receiving local transactions in private propagation mode. NODES should be comma-delimited enodes or node IDs.",
+
 		["API and Console Options – RPC"]
 			FLAG flag_no_jsonrpc: (bool) = false, or |c: &Config| c.rpc.as_ref()?.disable.clone(),
 			"--no-jsonrpc",
@@ -466,9 +742,13 @@ usage! {
 			"--jsonrpc-interface=[IP]",
 			"Specify the hostname portion of the JSONRPC API server, IP should be an interface's IP address, or all (all interfaces) or local.",
 
+			ARG arg_jsonrpc_extra_binds: (Option<String>) = None, or |c: &Config| c.rpc.as_ref()?.extra_binds.as_ref().map(|vec| vec.join(";")),
+			"--jsonrpc-extra-binds=[IP:PORT=APIS;...]",
+			"Serve the JSONRPC API on additional addresses, each with its own APIs, using a semicolon-delimited list of IP:PORT=APIS entries, example: 192.168.1.5:8545=safe. IP can also be all or local.",
+
 			ARG arg_jsonrpc_apis: (String) = "web3,eth,pubsub,net,parity,private,parity_pubsub,traces,rpc,shh,shh_pubsub", or |c: &Config| c.rpc.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-apis=[APIS]",
-			"Specify the APIs available through the JSONRPC interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains following apis: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
+			"Specify the APIs available through the JSONRPC interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, clique, evm, traces, debug, explorer, rpc, secretstore, shh, shh_pubsub, shh_set. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains following apis: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
 
 			ARG arg_jsonrpc_hosts: (String) = "none", or |c: &Config| c.rpc.as_ref()?.hosts.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-hosts=[HOSTS]",
@@ -478,6 +758,18 @@ usage! {
 			"--jsonrpc-threads=[THREADS]",
 			"Turn on additional processing threads in all RPC servers. Setting this to non-zero value allows parallel cpu-heavy queries execution.",
 
+			ARG arg_jsonrpc_heavy_threads: (usize) = 0usize, or |c: &Config| c.rpc.as_ref()?.heavy_threads,
+			"--jsonrpc-heavy-threads=[THREADS]",
+			"Process expensive RPC methods (see --jsonrpc-heavy-methods) in a separate pool of THREADS threads, so they can't starve cheap calls. 0 processes them along with other calls.",
+
+			ARG arg_jsonrpc_heavy_methods: (String) = "eth_getLogs,trace_*", or |c: &Config| c.rpc.as_ref()?.heavy_methods.as_ref().map(|vec| vec.join(",")),
+			"--jsonrpc-heavy-methods=[METHODS]",
+			"Comma-separated list of RPC methods processed in the --jsonrpc-heavy-threads pool. A method ending with * matches all methods with that prefix.",
+
+			ARG arg_jsonrpc_priority_methods: (String) = "parity_nodeHealth,eth_syncing,net_peerCount", or |c: &Config| c.rpc.as_ref()?.priority_methods.as_ref().map(|vec| vec.join(",")),
+			"--jsonrpc-priority-methods=[METHODS]",
+			"Comma-separated list of RPC methods served from a reserved thread, so health and sync status checks get answered even when the node is saturated with heavy queries. A method ending with * matches all methods with that prefix. Empty list disables the reserved lane.",
+
 			ARG arg_jsonrpc_cors: (String) = "none", or |c: &Config| c.rpc.as_ref()?.cors.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-cors=[URL]",
 			"Specify CORS header for JSON-RPC API responses. Special options: \"all\", \"none\".",
@@ -486,6 +778,18 @@ usage! {
 			"--jsonrpc-server-threads=[NUM]",
 			"Enables multiple threads handling incoming connections for HTTP JSON-RPC server.",
 
+			ARG arg_health_max_blocks_behind: (u64) = 10u64, or |c: &Config| c.rpc.as_ref()?.health_max_blocks_behind,
+			"--health-max-blocks-behind=[NUM]",
+			"Report the node as not ready on /health/ready when it is more than NUM blocks behind the highest block seen on the network.",
+
+			ARG arg_health_min_peers: (usize) = 1usize, or |c: &Config| c.rpc.as_ref()?.health_min_peers,
+			"--health-min-peers=[NUM]",
+			"Report the node as not ready on /health/ready when it is connected to fewer than NUM peers.",
+
+			FLAG flag_resolve_names: (bool) = false, or |c: &Config| c.rpc.as_ref()?.resolve_names.clone(),
+			"--resolve-names",
+			"Accept names registered in the chain's registrar in place of addresses in eth_call, eth_sendTransaction and similar RPC methods, and for --author. Names are resolved at the block of the call, and shown in signer confirmations.",
+
 		["API and Console Options – WebSockets"]
 			FLAG flag_no_ws: (bool) = false, or |c: &Config| c.websockets.as_ref()?.disable.clone(),
 			"--no-ws",
@@ -499,14 +803,22 @@ usage! {
 			"--ws-interface=[IP]",
 			"Specify the hostname portion of the WebSockets server, IP should be an interface's IP address, or all (all interfaces) or local.",
 
+			ARG arg_ws_extra_binds: (Option<String>) = None, or |c: &Config| c.websockets.as_ref()?.extra_binds.as_ref().map(|vec| vec.join(";")),
+			"--ws-extra-binds=[IP:PORT=APIS;...]",
+			"Serve the WebSockets API on additional addresses, each with its own APIs, using a semicolon-delimited list of IP:PORT=APIS entries, example: 192.168.1.5:8546=safe. IP can also be all or local. --ws-origin-apis doesn't apply to these addresses.",
+
 			ARG arg_ws_apis: (String) = "web3,eth,pubsub,net,parity,parity_pubsub,private,traces,rpc,shh,shh_pubsub", or |c: &Config| c.websockets.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--ws-apis=[APIS]",
-			"Specify the APIs available through the WebSockets interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains following apis: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
+			"Specify the APIs available through the WebSockets interface using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, clique, evm, traces, debug, explorer, rpc, secretstore, shh, shh_pubsub, shh_set. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains following apis: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
 
 			ARG arg_ws_origins: (String) = "parity://*,chrome-extension://*,moz-extension://*", or |c: &Config| c.websockets.as_ref()?.origins.as_ref().map(|vec| vec.join(",")),
 			"--ws-origins=[URL]",
 			"Specify Origin header values allowed to connect. Special options: \"all\", \"none\".",
 
+			ARG arg_ws_origin_apis: (Option<String>) = None, or |c: &Config| c.websockets.as_ref()?.origin_apis.as_ref().map(|vec| vec.join(";")),
+			"--ws-origin-apis=[ORIGIN=APIS;...]",
+			"Restrict the APIs available to WebSockets sessions opened from given origins, using a semicolon-delimited list of ORIGIN=APIS mappings, example: https://wallet.example.com=eth,net,web3;https://*=web3. Only APIs enabled with --ws-apis are available. An origin ending with * matches all origins with that prefix. The first matching origin is used.",
+
 			ARG arg_ws_hosts: (String) = "none", or |c: &Config| c.websockets.as_ref()?.hosts.as_ref().map(|vec| vec.join(",")),
 			"--ws-hosts=[HOSTS]",
 			"List of allowed Host header values. This option will validate the Host header sent by the browser, it is additional security against some attack vectors. Special options: \"all\", \"none\".",
@@ -526,7 +838,11 @@ usage! {
 
 			ARG arg_ipc_apis: (String) = "web3,eth,pubsub,net,parity,parity_pubsub,parity_accounts,private,traces,rpc,shh,shh_pubsub", or |c: &Config| c.ipc.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--ipc-apis=[APIS]",
-			"Specify custom API set available via JSON-RPC over IPC using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, traces, rpc, secretstore, shh, shh_pubsub. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
+			"Specify custom API set available via JSON-RPC over IPC using a comma-delimited list of API names. Possible names are: all, safe, web3, net, eth, pubsub, personal, signer, parity, parity_pubsub, parity_accounts, parity_set, clique, evm, traces, debug, explorer, rpc, secretstore, shh, shh_pubsub, shh_set. You can also disable a specific API by putting '-' in the front, example: all,-personal. safe contains: web3, net, eth, pubsub, parity, parity_pubsub, traces, rpc, shh, shh_pubsub",
+
+			ARG arg_ipc_notification_queue: (usize) = 1024usize, or |c: &Config| c.ipc.as_ref()?.notification_queue.clone(),
+			"--ipc-notification-queue=[NUM]",
+			"Maximum number of subscription notifications buffered for an IPC connection which is not reading them fast enough. Notifications over the limit are dropped.",
 
 		["API and Console Options – Dapps"]
 			FLAG flag_no_dapps: (bool) = false, or |c: &Config| c.dapps.as_ref()?.disable.clone(),
@@ -537,6 +853,14 @@ usage! {
 			"--dapps-path=[PATH]",
 			"Specify directory where dapps should be installed.",
 
+			ARG arg_dapps_ipfs: (Option<String>) = None, or |c: &Config| c.dapps.as_ref()?.ipfs.as_ref().map(|vec| vec.join(",")),
+			"--dapps-ipfs=[CIDS]",
+			"Comma separated list of dapps to serve by IPFS CID. Each CID must address a dapp bundle (zip with a manifest) added as a raw block or a UnixFS file. The dapp is served under the hex Keccak-256 hash of its binary CID, or the digest for raw Keccak-256 CIDs.",
+
+			ARG arg_dapps_ipfs_gateway: (String) = "https://ipfs.io", or |c: &Config| c.dapps.as_ref()?.ipfs_gateway.clone(),
+			"--dapps-ipfs-gateway=[URL]",
+			"IPFS HTTP gateway used to fetch dapps given with --dapps-ipfs. It must serve CAR archives (?format=car), which are verified block by block.",
+
 		["API and Console Options – IPFS"]
 			FLAG flag_ipfs_api: (bool) = false, or |c: &Config| c.ipfs.as_ref()?.enable.clone(),
 			"--ipfs-api",
@@ -558,6 +882,18 @@ usage! {
 			"--ipfs-api-cors=[URL]",
 			"Specify CORS header for IPFS API responses. Special options: \"all\", \"none\".",
 
+			ARG arg_ipfs_api_path_prefix: (String) = "", or |c: &Config| c.ipfs.as_ref()?.path_prefix.clone(),
+			"--ipfs-api-path-prefix=[PATH]",
+			"Serve the IPFS API under the given path prefix (e.g. /ipfs), so it can share a reverse proxy with other services.",
+
+			ARG arg_ipfs_api_max_response_size: (usize) = 1024usize, or |c: &Config| c.ipfs.as_ref()?.max_response_size.clone(),
+			"--ipfs-api-max-response-size=[KB]",
+			"Largest response the IPFS API serves, in kilobytes. Larger responses are refused with 413 Payload Too Large.",
+
+			ARG arg_ipfs_api_store_size: (usize) = 64usize, or |c: &Config| c.ipfs.as_ref()?.store_size.clone(),
+			"--ipfs-api-store-size=[MB]",
+			"Total size of the blocks put or pinned through the IPFS API, in megabytes. Blocks over the limit are refused.",
+
 		["Secret Store Options"]
 			FLAG flag_no_secretstore: (bool) = false, or |c: &Config| c.secretstore.as_ref()?.disable.clone(),
 			"--no-secretstore",
@@ -631,6 +967,18 @@ usage! {
 			"--secretstore-admin=[PUBLIC]",
 			"Hex-encoded public key of secret store administrator.",
 
+			ARG arg_secretstore_tls_cert: (Option<String>) = None, or |c: &Config| c.secretstore.as_ref()?.tls_cert.clone(),
+			"--secretstore-tls-cert=[PATH]",
+			"Path to PEM-encoded certificate of this key server. Enables mutual TLS between key servers. Certificates of all key servers must be issued by --secretstore-tls-ca for 'secretstore' DNS name. Certificate files are reloaded when changed.",
+
+			ARG arg_secretstore_tls_key: (Option<String>) = None, or |c: &Config| c.secretstore.as_ref()?.tls_key.clone(),
+			"--secretstore-tls-key=[PATH]",
+			"Path to PEM-encoded private key of this key server TLS certificate.",
+
+			ARG arg_secretstore_tls_ca: (Option<String>) = None, or |c: &Config| c.secretstore.as_ref()?.tls_ca.clone(),
+			"--secretstore-tls-ca=[PATH]",
+			"Path to PEM-encoded certificate of CA which has issued TLS certificates of all key servers.",
+
 		["Sealing/Mining Options"]
 			FLAG flag_force_sealing: (bool) = false, or |c: &Config| c.mining.as_ref()?.force_sealing.clone(),
 			"--force-sealing",
@@ -656,6 +1004,10 @@ usage! {
 			"--infinite-pending-block",
 			"Pending block will be created with maximal possible gas limit and will execute all transactions in the queue. Note that such block is invalid and should never be attempted to be mined.",
 
+			FLAG flag_dev_manual_seal: (bool) = false, or |c: &Config| c.mining.as_ref()?.dev_manual_seal.clone(),
+			"--dev-manual-seal",
+			"Seal blocks only when requested with the evm_mine RPC. Applies to chains using the instant seal engine, e.g. the dev chain.",
+
 			FLAG flag_no_persistent_txqueue: (bool) = false, or |c: &Config| c.parity.as_ref()?.no_persistent_txqueue,
 			"--no-persistent-txqueue",
 			"Don't save pending local transactions to disk to be restored whenever the node restarts.",
@@ -676,6 +1028,18 @@ usage! {
 			"--reseal-max-period=[MS]",
 			"Specify the maximum time since last block to enable force-sealing. MS is time measured in milliseconds.",
 
+			ARG arg_dev_block_time: (Option<u64>) = None, or |c: &Config| c.mining.as_ref()?.dev_block_time.clone(),
+			"--dev-block-time=[SECONDS]",
+			"Seal a block, even if empty, every SECONDS seconds instead of on every transaction. Applies to chains using the instant seal engine, e.g. the dev chain.",
+
+			ARG arg_dev_accounts: (usize) = 0usize, or |c: &Config| c.mining.as_ref()?.dev_accounts.clone(),
+			"--dev-accounts=[NUM]",
+			"Prefund NUM deterministic accounts at the genesis of the dev chain and unlock them. The accounts are derived from the \"test test ... junk\" mnemonic used by most test frameworks, their keys are printed at startup. Requires --chain dev.",
+
+			ARG arg_dev_balance: (String) = "10000000000000000000000", or |c: &Config| c.mining.as_ref()?.dev_balance.clone(),
+			"--dev-balance=[WEI]",
+			"Genesis balance of each account created with --dev-accounts, in wei.",
+
 			ARG arg_work_queue_size: (usize) = 20usize, or |c: &Config| c.mining.as_ref()?.work_queue_size.clone(),
 			"--work-queue-size=[ITEMS]",
 			"Specify the number of historical work packages which are kept cached lest a solution is found for them later. High values take more memory but result in fewer unusable solutions.",
@@ -746,7 +1110,7 @@ usage! {
 
 			ARG arg_author: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.author.clone(),
 			"--author=[ADDRESS]",
-			"Specify the block author (aka \"coinbase\") address for sending block rewards from sealed blocks. NOTE: MINING WILL NOT WORK WITHOUT THIS OPTION.", // Sealing/Mining Option
+			"Specify the block author (aka \"coinbase\") address for sending block rewards from sealed blocks. With --resolve-names, ADDRESS can also be a name registered in the chain's registrar. NOTE: MINING WILL NOT WORK WITHOUT THIS OPTION.", // Sealing/Mining Option
 
 			ARG arg_engine_signer: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.engine_signer.clone(),
 			"--engine-signer=[ADDRESS]",
@@ -786,6 +1150,34 @@ usage! {
 			"-v, --version",
 			"Show information about version.",
 
+			FLAG flag_json: (bool) = false, or |_| None,
+			"--json",
+			"Print machine-readable JSON output. Applies to --version, signer list and signer approve-all.",
+
+			ARG arg_warnings: (String) = "text", or |_| None,
+			"--warnings=[FORMAT]",
+			"Format of deprecation warnings printed on startup. FORMAT may be one of: text, json.",
+
+			FLAG flag_strict_flags: (bool) = false, or |_| None,
+			"--strict-flags",
+			"Refuse to start if any deprecated option is used, instead of warning about it.",
+
+			ARG arg_informant: (String) = "full", or |c: &Config| c.misc.as_ref()?.informant.clone(),
+			"--informant=[MODE]",
+			"Periodic status output. MODE may be one of: off - print nothing; compact - a short uncoloured line; full - the regular status line; json - one JSON object per line.",
+
+			ARG arg_informant_columns: (String) = "peers,queue,mem,gas", or |c: &Config| c.misc.as_ref()?.informant_columns.as_ref().map(|vec| vec.join(",")),
+			"--informant-columns=[COLUMNS]",
+			"Comma-separated list of columns the status output includes. Possible values are: peers, queue, mem, db (database size on disk), gas (import speed).",
+
+			ARG arg_informant_interval: (u64) = 5u64, or |c: &Config| c.misc.as_ref()?.informant_interval,
+			"--informant-interval=[SECS]",
+			"Number of seconds between status outputs.",
+
+			FLAG flag_systemd_notify: (bool) = false, or |c: &Config| c.misc.as_ref()?.systemd_notify,
+			"--systemd-notify",
+			"Notify systemd when the node is ready and send watchdog pings (WatchdogSec=) while it is responsive. Use with Type=notify units.",
+
 			FLAG flag_no_config: (bool) = false, or |_| None,
 			"--no-config",
 			"Don't load a configuration file.",
@@ -794,6 +1186,14 @@ usage! {
 			"--ntp-servers=[HOSTS]",
 			"Comma separated list of NTP servers to provide current time (host:port). Used to verify node health. Parity uses pool.ntp.org NTP servers; consider joining the pool: http://www.pool.ntp.org/join.html",
 
+			ARG arg_max_time_drift: (i64) = 10000i64, or |c: &Config| c.misc.as_ref()?.max_time_drift,
+			"--max-time-drift=[MS]",
+			"Maximal tolerated difference between the local clock and NTP time, in milliseconds. Exceeding it is reported in node health.",
+
+			FLAG flag_strict_time_drift: (bool) = false, or |c: &Config| c.misc.as_ref()?.strict_time_drift,
+			"--strict-time-drift",
+			"Refuse to seal blocks while the local clock drifts from NTP time by more than --max-time-drift. Blocks sealed with a skewed clock may be rejected by the network.",
+
 			ARG arg_logging: (Option<String>) = None, or |c: &Config| c.misc.as_ref()?.logging.clone(),
 			"-l, --logging=[LOGGING]",
 			"Specify the logging level. Must conform to the same format as RUST_LOG.",
@@ -802,11 +1202,19 @@ usage! {
 			"--log-file=[FILENAME]",
 			"Specify a filename into which logging should be appended.",
 
+			ARG arg_log_buffer: (usize) = 10000usize, or |c: &Config| c.misc.as_ref()?.log_buffer,
+			"--log-buffer=[RECORDS]",
+			"Number of recent log records kept in memory for the parity_recentLogs RPC.",
+
 		["Footprint Options"]
 			FLAG flag_fast_and_loose: (bool) = false, or |c: &Config| c.footprint.as_ref()?.fast_and_loose.clone(),
 			"--fast-and-loose",
 			"Disables DB WAL, which gives a significant speed up but means an unclean exit is unrecoverable.",
 
+			FLAG flag_db_auto_repair: (bool) = false, or |c: &Config| c.footprint.as_ref()?.db_auto_repair.clone(),
+			"--db-auto-repair",
+			"Attempt to repair a corrupted database automatically on startup. Without this flag Parity refuses to start and lists the affected columns.",
+
 			FLAG flag_scale_verifiers: (bool) = false, or |c: &Config| c.footprint.as_ref()?.scale_verifiers.clone(),
 			"--scale-verifiers",
 			"Automatically scale amount of verifier threads based on workload. Not guaranteed to be faster.",
@@ -815,6 +1223,10 @@ usage! {
 			"--tracing=[BOOL]",
 			"Indicates if full transaction tracing should be enabled. Works only if client had been fully synced with tracing enabled. BOOL may be one of auto, on, off. auto uses last used value of this option (off if it does not exist).", // footprint option
 
+			FLAG flag_tracing_state_diffs: (bool) = false, or |c: &Config| c.footprint.as_ref()?.tracing_state_diffs.clone(),
+			"--tracing-state-diffs",
+			"Record state diffs of imported transactions along with their traces, so they can be queried with trace_stateDiffAt without re-execution. Works only with --tracing on.",
+
 			ARG arg_pruning: (String) = "auto", or |c: &Config| c.footprint.as_ref()?.pruning.clone(),
 			"--pruning=[METHOD]",
 			"Configure pruning of the state/storage trie. METHOD may be one of auto, archive, fast: archive - keep all state trie data. No pruning. fast - maintain journal overlay. Fast but 50MB used. auto - use the method most recently synced or default to fast if none synced.",
@@ -823,10 +1235,38 @@ usage! {
 			"--pruning-history=[NUM]",
 			"Set a minimum number of recent states to keep in memory when pruning is active.",
 
+			ARG arg_state_query_history: (Option<u64>) = None, or |c: &Config| c.footprint.as_ref()?.state_query_history.clone(),
+			"--state-query-history=[NUM]",
+			"Guarantee that the state of the NUM most recent blocks is available to queries such as eth_call and eth_getBalance when pruning is active. Queries for older states fail with a state pruned error. Raises --pruning-history if needed. NUM must be at least 1; omit the option to keep every available state queryable.",
+
 			ARG arg_pruning_memory: (usize) = 32usize, or |c: &Config| c.footprint.as_ref()?.pruning_memory.clone(),
 			"--pruning-memory=[MB]",
 			"The ideal amount of memory in megabytes to use to store recent states. As many states as possible will be kept within this limit, and at least --pruning-history states will always be kept.",
 
+			ARG arg_ancient_blocks_threshold: (Option<u64>) = None, or |c: &Config| c.footprint.as_ref()?.ancient_blocks_threshold.clone(),
+			"--ancient-blocks-threshold=[NUM]",
+			"Move bodies and receipts of blocks more than NUM blocks behind the best block out of the database into append-only era files in the ancient directory of the database. At least 1024.",
+
+			ARG arg_blooms_levels: (u32) = 3u32, or |c: &Config| c.footprint.as_ref()?.blooms_levels.clone(),
+			"--blooms-levels=[NUM]",
+			"Number of levels of the log bloom index used by log queries to skip blocks. Applies to new databases; use parity db rebuild-blooms to change an existing index.",
+
+			ARG arg_blooms_fan_out: (u64) = 16u64, or |c: &Config| c.footprint.as_ref()?.blooms_fan_out.clone(),
+			"--blooms-fan-out=[NUM]",
+			"Number of blooms summarised by a single bloom of the level above in the log bloom index. Must be a power of two.",
+
+			ARG arg_receipts_storage: (Option<String>) = None, or |c: &Config| c.footprint.as_ref()?.receipts_storage.clone(),
+			"--receipts-storage=[MODE]",
+			"How block receipts are stored. MODE may be one of: full - store receipts as received; compact - deduplicate log addresses and topics and drop receipt blooms, which are recomputed when read; light - like compact, and do not maintain the log bloom index, so eth_getLogs has to scan every block header in the requested range. Defaults to the mode the database was last run with, or full for a new database. Leaving light requires parity db rebuild-blooms.",
+
+			ARG arg_tx_index: (Option<String>) = None, or |c: &Config| c.footprint.as_ref()?.tx_index.clone(),
+			"--tx-index=[POLICY]",
+			"Which transactions can be looked up by hash. POLICY may be one of: all - index every transaction; recent:<n> - index only transactions of the <n> most recent blocks; none - do not index transactions. Defaults to the policy the database was last run with, or all for a new database. Changing the policy of an existing database indexes or drops transactions of past blocks on startup.",
+
+			FLAG flag_enable_explorer_index: (bool) = false, or |c: &Config| c.footprint.as_ref()?.enable_explorer_index.clone(),
+			"--enable-explorer-index",
+			"Maintain an index of the transactions sent from and to each address in a separate database, so the explorer API, parity_getTransactionsByAddress included, can return address histories. The explorer API is not part of the safe APIs, enable it with --jsonrpc-apis or --ws-apis. Missing blocks are indexed in the background, or ahead of time with parity db index-addresses.",
+
 			ARG arg_cache_size_db: (u32) = 128u32, or |c: &Config| c.footprint.as_ref()?.cache_size_db.clone(),
 			"--cache-size-db=[MB]",
 			"Override database cache size.",
@@ -859,6 +1299,10 @@ usage! {
 			"--num-verifiers=[INT]",
 			"Amount of verifier threads to use or to begin with, if verifier auto-scaling is enabled.",
 
+			ARG arg_min_disk_space: (u64) = 1024u64, or |c: &Config| c.footprint.as_ref()?.min_disk_space.clone(),
+			"--min-disk-space=[MB]",
+			"Pause block import when free space on the database disk drops below MB, to avoid corrupting the database. Import resumes once space is freed. Use 0 to disable the check.",
+
 		["Import/export Options"]
 			FLAG flag_no_seal_check: (bool) = false, or |_| None,
 			"--no-seal-check",
@@ -878,6 +1322,22 @@ usage! {
 			"--whisper-pool-size=[MB]",
 			"Target size of the whisper message pool in megabytes.",
 
+			ARG arg_whisper_mailserver_key: (Option<String>) = None, or |c: &Config| c.whisper.as_ref()?.mailserver_key.clone(),
+			"--whisper-mailserver-key=[KEY]",
+			"Enable the whisper mail server. Relayed envelopes are stored in the node database and served to peers presenting the given 32-byte hex symmetric key.",
+
+			ARG arg_whisper_min_pow: (f64) = 0f64, or |c: &Config| c.whisper.as_ref()?.min_pow,
+			"--whisper-min-pow=[POW]",
+			"Minimum PoW of whisper envelopes accepted from peers. The requirement is announced to peers.",
+
+			ARG arg_whisper_bloom_filter: (Option<String>) = None, or |c: &Config| c.whisper.as_ref()?.bloom_filter.clone(),
+			"--whisper-bloom-filter=[BLOOM]",
+			"64-byte hex bloom filter of whisper topics accepted from peers. The filter is announced to peers. Accepts all topics if not set.",
+
+			ARG arg_whisper_max_envelopes_per_peer: (Option<usize>) = None, or |c: &Config| c.whisper.as_ref()?.max_envelopes_per_peer,
+			"--whisper-max-envelopes-per-peer=[NUM]",
+			"Maximum number of whisper envelopes accepted from a single peer per second. Excess envelopes are dropped.",
+
 		["Legacy Options"]
 			FLAG flag_warp: (bool) = false, or |_| None,
 			"--warp",
@@ -1069,6 +1529,7 @@ struct Config {
 	misc: Option<Misc>,
 	stratum: Option<Stratum>,
 	whisper: Option<Whisper>,
+	chains: Option<Vec<Config>>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1081,13 +1542,25 @@ struct Operating {
 	auto_update_delay: Option<u16>,
 	auto_update_check_frequency: Option<u16>,
 	release_track: Option<String>,
+	auto_update_channel: Option<String>,
+	auto_update_max_version: Option<String>,
+	auto_update_trusted_keys: Option<Vec<String>>,
+	auto_update_keep: Option<usize>,
+	hash_fetch_mirrors: Option<Vec<String>>,
+	blob_store_size: Option<u32>,
 	no_download: Option<bool>,
 	no_consensus: Option<bool>,
 	chain: Option<String>,
 	base_path: Option<String>,
+	state_path: Option<String>,
 	db_path: Option<String>,
 	keys_path: Option<String>,
 	identity: Option<String>,
+	rpc_prefix: Option<String>,
+	on_new_block: Option<String>,
+	on_reorg: Option<String>,
+	hook_timeout: Option<u64>,
+	hook_max_concurrent: Option<usize>,
 	light: Option<bool>,
 	no_persistent_txqueue: Option<bool>,
 	no_hardcoded_sync: Option<bool>,
@@ -1123,6 +1596,8 @@ struct PrivateTransactions {
 #[serde(deny_unknown_fields)]
 struct Ui {
 	path: Option<String>,
+	auto_approve: Option<String>,
+	notify_url: Option<String>,
 
 	#[serde(rename="force")]
 	_legacy_force: Option<bool>,
@@ -1146,6 +1621,7 @@ struct Network {
 	min_peers: Option<u16>,
 	max_peers: Option<u16>,
 	snapshot_peers: Option<u16>,
+	min_protocol_peers: Option<Vec<String>>,
 	max_pending_peers: Option<u16>,
 	nat: Option<String>,
 	allow_ips: Option<String>,
@@ -1155,7 +1631,19 @@ struct Network {
 	node_key: Option<String>,
 	reserved_peers: Option<String>,
 	reserved_only: Option<bool>,
+	node_permission_overrides: Option<String>,
 	no_serve_light: Option<bool>,
+	fork_alert_depth: Option<u64>,
+	fork_alert_webhook: Option<String>,
+	max_reorg_depth: Option<u64>,
+	finality_depth: Option<u64>,
+	reorg_alert_webhook: Option<String>,
+	sync_stall_timeout: Option<u64>,
+	sync_stall_rotate_peers: Option<usize>,
+	sync_stall_refresh_discovery: Option<bool>,
+	head_first_sync: Option<bool>,
+	tx_propagation: Option<String>,
+	tx_propagation_peers: Option<Vec<String>>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1169,6 +1657,13 @@ struct Rpc {
 	hosts: Option<Vec<String>>,
 	server_threads: Option<usize>,
 	processing_threads: Option<usize>,
+	heavy_threads: Option<usize>,
+	heavy_methods: Option<Vec<String>>,
+	priority_methods: Option<Vec<String>>,
+	health_max_blocks_behind: Option<u64>,
+	health_min_peers: Option<usize>,
+	resolve_names: Option<bool>,
+	extra_binds: Option<Vec<String>>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1178,9 +1673,11 @@ struct Ws {
 	port: Option<u16>,
 	interface: Option<String>,
 	apis: Option<Vec<String>>,
+	origin_apis: Option<Vec<String>>,
 	origins: Option<Vec<String>>,
 	hosts: Option<Vec<String>>,
 	max_connections: Option<usize>,
+	extra_binds: Option<Vec<String>>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1189,6 +1686,7 @@ struct Ipc {
 	disable: Option<bool>,
 	path: Option<String>,
 	apis: Option<Vec<String>>,
+	notification_queue: Option<usize>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1200,6 +1698,8 @@ struct Dapps {
 	hosts: Option<Vec<String>>,
 	cors: Option<String>,
 	path: Option<String>,
+	ipfs: Option<Vec<String>>,
+	ipfs_gateway: Option<String>,
 	user: Option<String>,
 	pass: Option<String>,
 }
@@ -1218,6 +1718,9 @@ struct SecretStore {
 	service_contract_doc_sretr: Option<String>,
 	self_secret: Option<String>,
 	admin_public: Option<String>,
+	tls_cert: Option<String>,
+	tls_key: Option<String>,
+	tls_ca: Option<String>,
 	nodes: Option<Vec<String>>,
 	server_set_contract: Option<String>,
 	interface: Option<String>,
@@ -1235,6 +1738,9 @@ struct Ipfs {
 	interface: Option<String>,
 	cors: Option<Vec<String>>,
 	hosts: Option<Vec<String>>,
+	path_prefix: Option<String>,
+	max_response_size: Option<usize>,
+	store_size: Option<usize>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1272,6 +1778,10 @@ struct Mining {
 	notify_work: Option<Vec<String>>,
 	refuse_service_transactions: Option<bool>,
 	infinite_pending_block: Option<bool>,
+	dev_block_time: Option<u64>,
+	dev_accounts: Option<usize>,
+	dev_balance: Option<String>,
+	dev_manual_seal: Option<bool>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1286,10 +1796,19 @@ struct Stratum {
 #[serde(deny_unknown_fields)]
 struct Footprint {
 	tracing: Option<String>,
+	tracing_state_diffs: Option<bool>,
 	pruning: Option<String>,
 	pruning_history: Option<u64>,
+	state_query_history: Option<u64>,
+	ancient_blocks_threshold: Option<u64>,
+	blooms_levels: Option<u32>,
+	blooms_fan_out: Option<u64>,
+	receipts_storage: Option<String>,
+	tx_index: Option<String>,
+	enable_explorer_index: Option<bool>,
 	pruning_memory: Option<usize>,
 	fast_and_loose: Option<bool>,
+	db_auto_repair: Option<bool>,
 	cache_size: Option<u32>,
 	cache_size_db: Option<u32>,
 	cache_size_blocks: Option<u32>,
@@ -1299,6 +1818,7 @@ struct Footprint {
 	fat_db: Option<String>,
 	scale_verifiers: Option<bool>,
 	num_verifiers: Option<usize>,
+	min_disk_space: Option<u64>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1311,11 +1831,18 @@ struct Snapshots {
 #[serde(deny_unknown_fields)]
 struct Misc {
 	ntp_servers: Option<Vec<String>>,
+	max_time_drift: Option<i64>,
+	strict_time_drift: Option<bool>,
 	logging: Option<String>,
 	log_file: Option<String>,
+	log_buffer: Option<usize>,
 	color: Option<bool>,
 	ports_shift: Option<u16>,
 	unsafe_expose: Option<bool>,
+	informant: Option<String>,
+	informant_columns: Option<Vec<String>>,
+	informant_interval: Option<u64>,
+	systemd_notify: Option<bool>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1323,6 +1850,10 @@ struct Misc {
 struct Whisper {
 	enabled: Option<bool>,
 	pool_size: Option<usize>,
+	mailserver_key: Option<String>,
+	min_pow: Option<f64>,
+	bloom_filter: Option<String>,
+	max_envelopes_per_peer: Option<usize>,
 }
 
 #[cfg(test)]
@@ -1500,6 +2031,7 @@ mod tests {
 			cmd_account_new: false,
 			cmd_account_list: false,
 			cmd_account_import: false,
+			cmd_account_recover: false,
 			cmd_wallet: false,
 			cmd_wallet_import: false,
 			cmd_import: false,
@@ -1508,33 +2040,72 @@ mod tests {
 			cmd_export_state: false,
 			cmd_signer: false,
 			cmd_signer_list: false,
+			cmd_signer_approve_all: false,
 			cmd_signer_sign: false,
 			cmd_signer_reject: false,
 			cmd_signer_new_token: false,
+			cmd_signer_tokens: false,
+			cmd_signer_revoke_token: false,
 			cmd_snapshot: false,
 			cmd_restore: false,
 			cmd_tools: false,
 			cmd_tools_hash: false,
 			cmd_db: false,
 			cmd_db_kill: false,
+			cmd_db_prune_traces: false,
+			cmd_db_trace_backfill: false,
+			cmd_db_rebuild_blooms: false,
+			cmd_db_index_addresses: false,
+			cmd_db_backup: false,
+			cmd_db_restore_backup: false,
+			cmd_chain: false,
+			cmd_chain_validate_spec: false,
+			cmd_updater: false,
+			cmd_updater_rollback: false,
+			cmd_setup: false,
+			cmd_service: false,
+			cmd_service_install: false,
+			cmd_service_remove: false,
+			cmd_service_run: false,
 			cmd_export_hardcoded_sync: false,
+			cmd_test_runner: false,
 
 			// Arguments
 			arg_daemon_pid_file: None,
 			arg_import_file: None,
+			arg_import_progress_file: None,
 			arg_import_format: None,
 			arg_export_blocks_file: None,
+			arg_export_blocks_progress_file: None,
 			arg_export_blocks_format: None,
 			arg_export_state_file: None,
 			arg_export_state_format: None,
 			arg_snapshot_file: None,
 			arg_restore_file: None,
 			arg_tools_hash_file: None,
+			arg_db_prune_traces_before: None,
+			arg_db_trace_backfill_range: None,
+			arg_db_backup_dir: None,
+			arg_db_restore_backup_dir: None,
+			arg_chain_validate_spec_file: None,
+			arg_setup_answers_file: None,
+			arg_test_runner_chain_tests: None,
+			arg_test_runner_fork: None,
+			arg_test_runner_spec: None,
 
 			arg_signer_sign_id: None,
 			arg_signer_reject_id: None,
+			arg_signer_approve_all_matching: None,
+			arg_signer_token_name: None,
+			arg_signer_token_scope: None,
+			arg_signer_token_expiry: None,
+			arg_signer_revoke_token_id: None,
 			arg_dapp_path: None,
 			arg_account_import_path: None,
+			flag_account_recover_mnemonic: false,
+			arg_account_recover_secret_file: None,
+			arg_account_recover_passphrase_file: None,
+			arg_account_recover_derivation_path: "m/44'/60'/0'/0/0".into(),
 			arg_wallet_import_path: None,
 
 			// -- Operating Options
@@ -1545,14 +2116,26 @@ mod tests {
 			arg_auto_update_delay: 200u16,
 			arg_auto_update_check_frequency: 50u16,
 			arg_release_track: "current".into(),
+			arg_auto_update_channel: None,
+			arg_auto_update_max_version: None,
+			arg_auto_update_trusted_keys: None,
+			arg_auto_update_keep: 2usize,
+			arg_hash_fetch_mirrors: None,
+			arg_blob_store_size: 256u32,
 			flag_public_node: false,
 			flag_no_download: false,
 			flag_no_consensus: false,
 			arg_chain: "xyz".into(),
 			arg_base_path: Some("$HOME/.parity".into()),
+			arg_state_path: None,
 			arg_db_path: Some("$HOME/.parity/chains".into()),
+			arg_on_new_block: None,
+			arg_on_reorg: None,
+			arg_hook_timeout: 30u64,
+			arg_hook_max_concurrent: 4usize,
 			arg_keys_path: "$HOME/.parity/keys".into(),
 			arg_identity: "".into(),
+			arg_rpc_prefix: None,
 			flag_light: false,
 			flag_no_hardcoded_sync: false,
 			flag_no_persistent_txqueue: false,
@@ -1586,6 +2169,8 @@ mod tests {
 			arg_ui_interface: "local".into(),
 			arg_ui_hosts: "none".into(),
 			arg_ui_path: "$HOME/.parity/signer".into(),
+			arg_ui_auto_approve: None,
+			arg_signer_notify_url: None,
 			flag_ui_no_validation: false,
 
 			// -- Networking Options
@@ -1596,6 +2181,7 @@ mod tests {
 			arg_max_peers: Some(50u16),
 			arg_max_pending_peers: 64u16,
 			arg_snapshot_peers: 0u16,
+			arg_min_protocol_peers: None,
 			arg_allow_ips: "all".into(),
 			arg_nat: "any".into(),
 			arg_network_id: Some(1),
@@ -1603,27 +2189,48 @@ mod tests {
 			flag_no_discovery: false,
 			arg_node_key: None,
 			arg_reserved_peers: Some("./path_to_file".into()),
+			arg_node_permission_overrides: None,
 			flag_reserved_only: false,
 			flag_no_ancient_blocks: false,
 			flag_no_serve_light: false,
+			flag_sync_stall_refresh_discovery: false,
+			flag_head_first_sync: false,
+			arg_fork_alert_depth: None,
+			arg_fork_alert_webhook: None,
+			arg_max_reorg_depth: None,
+			arg_finality_depth: 12u64,
+			arg_reorg_alert_webhook: None,
+			arg_sync_stall_timeout: 0u64,
+			arg_sync_stall_rotate_peers: 2usize,
+			arg_tx_propagation: "sqrt".into(),
+			arg_tx_propagation_peers: None,
 
 			// -- API and Console Options
 			// RPC
 			flag_no_jsonrpc: false,
 			arg_jsonrpc_port: 8545u16,
 			arg_jsonrpc_interface: "local".into(),
+			arg_jsonrpc_extra_binds: None,
 			arg_jsonrpc_cors: "null".into(),
 			arg_jsonrpc_apis: "web3,eth,net,parity,traces,rpc,secretstore".into(),
 			arg_jsonrpc_hosts: "none".into(),
 			arg_jsonrpc_server_threads: None,
+			arg_health_max_blocks_behind: 10u64,
+			arg_health_min_peers: 1usize,
+			flag_resolve_names: false,
 			arg_jsonrpc_threads: 4,
+			arg_jsonrpc_heavy_threads: 0usize,
+			arg_jsonrpc_heavy_methods: "eth_getLogs,trace_*".into(),
+			arg_jsonrpc_priority_methods: "parity_nodeHealth,eth_syncing,net_peerCount".into(),
 
 			// WS
 			flag_no_ws: false,
 			arg_ws_port: 8546u16,
 			arg_ws_interface: "local".into(),
+			arg_ws_extra_binds: None,
 			arg_ws_apis: "web3,eth,net,parity,traces,rpc,secretstore".into(),
 			arg_ws_origins: "none".into(),
+			arg_ws_origin_apis: None,
 			arg_ws_hosts: "none".into(),
 			arg_ws_max_connections: 100,
 
@@ -1631,9 +2238,12 @@ mod tests {
 			flag_no_ipc: false,
 			arg_ipc_path: "$HOME/.parity/jsonrpc.ipc".into(),
 			arg_ipc_apis: "web3,eth,net,parity,parity_accounts,personal,traces,rpc,secretstore".into(),
+			arg_ipc_notification_queue: 1024usize,
 
 			// DAPPS
 			arg_dapps_path: "$HOME/.parity/dapps".into(),
+			arg_dapps_ipfs: None,
+			arg_dapps_ipfs_gateway: "https://ipfs.io".into(),
 			flag_no_dapps: false,
 
 			// SECRETSTORE
@@ -1648,6 +2258,9 @@ mod tests {
 			arg_secretstore_doc_sretr_contract: Some("none".into()),
 			arg_secretstore_secret: None,
 			arg_secretstore_admin_public: None,
+			arg_secretstore_tls_cert: None,
+			arg_secretstore_tls_key: None,
+			arg_secretstore_tls_ca: None,
 			arg_secretstore_nodes: "".into(),
 			arg_secretstore_server_set_contract: Some("registry".into()),
 			arg_secretstore_interface: "local".into(),
@@ -1662,6 +2275,9 @@ mod tests {
 			arg_ipfs_api_interface: "local".into(),
 			arg_ipfs_api_cors: "null".into(),
 			arg_ipfs_api_hosts: "none".into(),
+			arg_ipfs_api_path_prefix: "".into(),
+			arg_ipfs_api_max_response_size: 1024usize,
+			arg_ipfs_api_store_size: 64usize,
 
 			// -- Sealing/Mining Options
 			arg_author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
@@ -1696,6 +2312,10 @@ mod tests {
 			arg_notify_work: Some("http://localhost:3001".into()),
 			flag_refuse_service_transactions: false,
 			flag_infinite_pending_block: false,
+			arg_dev_block_time: None,
+			arg_dev_accounts: 0usize,
+			arg_dev_balance: "10000000000000000000000".into(),
+			flag_dev_manual_seal: false,
 
 			flag_stratum: false,
 			arg_stratum_interface: "local".to_owned(),
@@ -1704,8 +2324,16 @@ mod tests {
 
 			// -- Footprint Options
 			arg_tracing: "auto".into(),
+			flag_tracing_state_diffs: false,
 			arg_pruning: "auto".into(),
 			arg_pruning_history: 64u64,
+			arg_state_query_history: None,
+			arg_ancient_blocks_threshold: None,
+			arg_blooms_levels: 3u32,
+			arg_blooms_fan_out: 16u64,
+			arg_receipts_storage: None,
+			arg_tx_index: None,
+			flag_enable_explorer_index: false,
 			arg_pruning_memory: 500usize,
 			arg_cache_size_db: 64u32,
 			arg_cache_size_blocks: 8u32,
@@ -1713,10 +2341,12 @@ mod tests {
 			arg_cache_size_state: 25u32,
 			arg_cache_size: Some(128),
 			flag_fast_and_loose: false,
+			flag_db_auto_repair: false,
 			arg_db_compaction: "ssd".into(),
 			arg_fat_db: "auto".into(),
 			flag_scale_verifiers: true,
 			arg_num_verifiers: Some(6),
+			arg_min_disk_space: 1024u64,
 
 			// -- Import/Export Options
 			arg_export_blocks_from: "1".into(),
@@ -1736,6 +2366,10 @@ mod tests {
 			// -- Whisper options.
 			flag_whisper: false,
 			arg_whisper_pool_size: 20,
+			arg_whisper_mailserver_key: None,
+			arg_whisper_min_pow: 0f64,
+			arg_whisper_bloom_filter: None,
+			arg_whisper_max_envelopes_per_peer: None,
 
 			// -- Legacy Options
 			flag_warp: false,
@@ -1779,14 +2413,57 @@ mod tests {
 
 			// -- Miscellaneous Options
 			arg_ntp_servers: "0.parity.pool.ntp.org:123,1.parity.pool.ntp.org:123,2.parity.pool.ntp.org:123,3.parity.pool.ntp.org:123".into(),
+			arg_max_time_drift: 10000i64,
+			flag_strict_time_drift: false,
 			flag_version: false,
+			flag_json: false,
+			arg_warnings: "text".into(),
+			flag_strict_flags: false,
+			arg_informant: "full".into(),
+			arg_informant_columns: "peers,queue,mem,gas".into(),
+			arg_informant_interval: 5u64,
+			flag_systemd_notify: false,
 			arg_logging: Some("own_tx=trace".into()),
 			arg_log_file: Some("/var/log/parity.log".into()),
+			arg_log_buffer: 10000usize,
 			flag_no_color: false,
 			flag_no_config: false,
+
+			chains: Vec::new(),
 		});
 	}
 
+	#[test]
+	fn should_parse_chains_from_config() {
+		// given
+		let config = toml::from_str(r#"
+			[parity]
+			chain = "foundation"
+
+			[[chains]]
+			[chains.parity]
+			chain = "kovan"
+			base_path = "/tmp/kovan"
+			rpc_prefix = "kovan"
+			[chains.network]
+			port = 30304
+		"#).unwrap();
+
+		// when
+		let args = Args::parse_with_config(&["parity", "--no-ws"], config).unwrap();
+
+		// then
+		assert_eq!(args.arg_chain, "foundation");
+		assert_eq!(args.chains.len(), 1);
+		assert_eq!(args.chains[0].arg_chain, "kovan");
+		assert_eq!(args.chains[0].arg_base_path, Some("/tmp/kovan".into()));
+		assert_eq!(args.chains[0].arg_port, 30304);
+		assert_eq!(args.chains[0].arg_rpc_prefix, Some("kovan".into()));
+		// the command line only configures the primary chain
+		assert!(args.flag_no_ws);
+		assert!(!args.chains[0].flag_no_ws);
+	}
+
 	#[test]
 	fn should_parse_config_and_return_errors() {
 		let config1 = Args::parse_config(include_str!("./tests/config.invalid1.toml"));
@@ -1820,13 +2497,25 @@ mod tests {
 				auto_update_delay: None,
 				auto_update_check_frequency: None,
 				release_track: None,
+				auto_update_channel: None,
+				auto_update_max_version: None,
+				auto_update_trusted_keys: None,
+				auto_update_keep: None,
+				hash_fetch_mirrors: None,
+				blob_store_size: None,
 				no_download: None,
 				no_consensus: None,
 				chain: Some("./chain.json".into()),
 				base_path: None,
+				state_path: None,
 				db_path: None,
 				keys_path: None,
 				identity: None,
+				rpc_prefix: None,
+				on_new_block: None,
+				on_reorg: None,
+				hook_timeout: None,
+				hook_max_concurrent: None,
 				light: None,
 				no_hardcoded_sync: None,
 				no_persistent_txqueue: None,
@@ -1842,6 +2531,8 @@ mod tests {
 			}),
 			ui: Some(Ui {
 				path: None,
+				auto_approve: None,
+				notify_url: None,
 				_legacy_force: None,
 				_legacy_disable: Some(true),
 				_legacy_port: None,
@@ -1857,6 +2548,7 @@ mod tests {
 				max_peers: Some(20),
 				max_pending_peers: Some(30),
 				snapshot_peers: Some(40),
+				min_protocol_peers: None,
 				allow_ips: Some("public".into()),
 				nat: Some("any".into()),
 				id: None,
@@ -1865,16 +2557,30 @@ mod tests {
 				node_key: None,
 				reserved_peers: Some("./path/to/reserved_peers".into()),
 				reserved_only: Some(true),
+				node_permission_overrides: None,
 				no_serve_light: None,
+				fork_alert_depth: None,
+				fork_alert_webhook: None,
+				max_reorg_depth: None,
+				finality_depth: None,
+				reorg_alert_webhook: None,
+				sync_stall_timeout: None,
+				sync_stall_rotate_peers: None,
+				sync_stall_refresh_discovery: None,
+				head_first_sync: None,
+				tx_propagation: None,
+				tx_propagation_peers: None,
 			}),
 			websockets: Some(Ws {
 				disable: Some(true),
 				port: None,
 				interface: None,
 				apis: None,
+				origin_apis: None,
 				origins: Some(vec!["none".into()]),
 				hosts: None,
 				max_connections: None,
+				extra_binds: None,
 			}),
 			rpc: Some(Rpc {
 				disable: Some(true),
@@ -1885,16 +2591,26 @@ mod tests {
 				hosts: None,
 				server_threads: None,
 				processing_threads: None,
+				heavy_threads: None,
+				heavy_methods: None,
+				priority_methods: None,
+				health_max_blocks_behind: None,
+				health_min_peers: None,
+				resolve_names: None,
+				extra_binds: None,
 			}),
 			ipc: Some(Ipc {
 				disable: None,
 				path: None,
 				apis: Some(vec!["rpc".into(), "eth".into()]),
+				notification_queue: None,
 			}),
 			dapps: Some(Dapps {
 				disable: None,
 				port: Some(8080),
 				path: None,
+				ipfs: None,
+				ipfs_gateway: None,
 				interface: None,
 				hosts: None,
 				cors: None,
@@ -1913,6 +2629,9 @@ mod tests {
 				service_contract_doc_sretr: None,
 				self_secret: None,
 				admin_public: None,
+				tls_cert: None,
+				tls_key: None,
+				tls_ca: None,
 				nodes: None,
 				server_set_contract: None,
 				interface: None,
@@ -1928,6 +2647,9 @@ mod tests {
 				interface: None,
 				cors: None,
 				hosts: None,
+				path_prefix: None,
+				max_response_size: None,
+				store_size: None,
 			}),
 			mining: Some(Mining {
 				author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
@@ -1962,13 +2684,26 @@ mod tests {
 				notify_work: None,
 				refuse_service_transactions: None,
 				infinite_pending_block: None,
+				dev_block_time: None,
+				dev_accounts: None,
+				dev_balance: None,
+				dev_manual_seal: None,
 			}),
 			footprint: Some(Footprint {
 				tracing: Some("on".into()),
+				tracing_state_diffs: None,
 				pruning: Some("fast".into()),
 				pruning_history: Some(64),
+				state_query_history: None,
+				ancient_blocks_threshold: None,
+				blooms_levels: None,
+				blooms_fan_out: None,
+				receipts_storage: None,
+				tx_index: None,
+				enable_explorer_index: None,
 				pruning_memory: None,
 				fast_and_loose: None,
+				db_auto_repair: None,
 				cache_size: None,
 				cache_size_db: Some(256),
 				cache_size_blocks: Some(16),
@@ -1978,23 +2713,36 @@ mod tests {
 				fat_db: Some("off".into()),
 				scale_verifiers: Some(false),
 				num_verifiers: None,
+				min_disk_space: None,
 			}),
 			snapshots: Some(Snapshots {
 				disable_periodic: Some(true),
 			}),
 			misc: Some(Misc {
 				ntp_servers: Some(vec!["0.parity.pool.ntp.org:123".into()]),
+				max_time_drift: None,
+				strict_time_drift: None,
 				logging: Some("own_tx=trace".into()),
 				log_file: Some("/var/log/parity.log".into()),
+				log_buffer: None,
 				color: Some(true),
 				ports_shift: Some(0),
 				unsafe_expose: Some(false),
+				informant: None,
+				informant_columns: None,
+				informant_interval: None,
+				systemd_notify: None,
 			}),
 			whisper: Some(Whisper {
 				enabled: Some(true),
 				pool_size: Some(50),
+				mailserver_key: None,
+				min_pow: None,
+				bloom_filter: None,
+				max_envelopes_per_peer: None,
 			}),
 			stratum: None,
+			chains: None,
 		});
 	}
 
@@ -199,7 +199,7 @@ macro_rules! usage {
 		}
 
 		/// Parsed command line arguments.
-		#[derive(Debug, PartialEq)]
+		#[derive(Debug, PartialEq, Clone)]
 		pub struct Args {
 			$(
 				pub $subc: bool,
@@ -230,6 +230,9 @@ macro_rules! usage {
 					pub $arg: $($arg_type_tt)+,
 				)*
 			)*
+
+			/// Additional chains to run in the same process, one per `[[chains]]` section of the config file.
+			pub chains: Vec<Args>,
 		}
 
 		impl Default for Args {
@@ -263,6 +266,8 @@ macro_rules! usage {
 							$arg: Default::default(),
 						)*
 					)*
+
+					chains: Vec::new(),
 				}
 			}
 		}
@@ -513,8 +518,14 @@ macro_rules! usage {
 		}
 
 		impl RawArgs {
-			fn into_args(self, config: Config) -> Args {
+			fn into_args(self, mut config: Config) -> Args {
 				let mut args = Args::default();
+
+				// every chain is configured by its own section only, the command line configures the primary chain.
+				args.chains = config.chains.take().unwrap_or_default().into_iter()
+					.map(|chain| RawArgs::default().into_args(chain))
+					.collect();
+
 				$(
 					args.$subc = self.$subc;
 
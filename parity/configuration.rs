@@ -18,41 +18,54 @@ use std::time::Duration;
 use std::io::Read;
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::cmp;
 use cli::{Args, ArgsError};
 use hash::keccak;
-use ethereum_types::{U256, H256, Address};
+use ethereum_types::{U256, H256, H512, Address};
 use parity_version::{version_data, version};
 use bytes::Bytes;
 use ansi_term::Colour;
 use sync::{NetworkConfiguration, validate_node_url, self};
 use ethcore::ethstore::ethkey::{Secret, Public};
-use ethcore::client::{VMType};
+use ethcore::client::{VMType, BloomsConfig};
+use ethcore::engines::SealMode;
 use ethcore::miner::{stratum, MinerOptions};
 use ethcore::verification::queue::VerifierSettings;
 use miner::pool;
 
-use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration};
+use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration, Bind};
+use rpc_apis::ApiSet;
 use parity_rpc::NetworkSettings;
 use cache::CacheConfig;
-use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_queue_penalization, passwords_from_files};
+use helpers::{to_duration, to_mode, to_block_id, to_block_range, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_queue_penalization, passwords_from_files};
 use dir::helpers::{replace_home, replace_home_and_local};
-use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType};
+use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType, DevAccounts};
 use ethcore_logger::Config as LogConfig;
 use dir::{self, Directories, default_hypervisor_path, default_local_path, default_data_path};
 use dapps::Configuration as DappsConfiguration;
 use ipfs::Configuration as IpfsConfiguration;
 use ethcore_private_tx::{ProviderConfig, EncryptorConfig};
-use secretstore::{NodeSecretKey, Configuration as SecretStoreConfiguration, ContractAddress as SecretStoreContractAddress};
+use secretstore::{NodeSecretKey, Configuration as SecretStoreConfiguration, ContractAddress as SecretStoreContractAddress,
+	TlsConfiguration as SecretStoreTlsConfiguration};
 use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
+use semver::Version;
+use parity_whisper::net::Limits as WhisperLimits;
 use run::RunCmd;
-use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, DataFormat};
+use deprecated::handle_deprecated;
+use informant::{self, InformantConfig};
+use block_hooks::HooksConfig;
+use signer::TokenSpec;
+use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, BackupBlockchain, RestoreBlockchain, RebuildBlooms, IndexAddresses, ExportState, PruneTraces, BackfillTraces, DataFormat};
 use export_hardcoded_sync::ExportHsyncCmd;
+use validate_spec::ValidateSpecCmd;
+use test_runner::TestRunnerCmd;
+use setup::SetupCmd;
+use service::ServiceCmd;
 use presale::ImportWallet;
-use account::{AccountCmd, NewAccount, ListAccounts, ImportAccounts, ImportFromGethAccounts};
+use account::{AccountCmd, NewAccount, ListAccounts, ImportAccounts, ImportFromGethAccounts, RecoverAccount, MnemonicOptions};
 use snapshot::{self, SnapshotCommand};
-use network::{IpFilter};
+use network::{IpFilter, ProtocolId};
 
 const DEFAULT_MAX_PEERS: u16 = 50;
 const DEFAULT_MIN_PEERS: u16 = 25;
@@ -60,11 +73,18 @@ const DEFAULT_MIN_PEERS: u16 = 25;
 #[derive(Debug, PartialEq)]
 pub enum Cmd {
 	Run(RunCmd),
+	RunMulti(Vec<RunCmd>),
 	Version,
+	VersionJson,
 	Account(AccountCmd),
 	ImportPresaleWallet(ImportWallet),
 	Blockchain(BlockchainCmd),
-	SignerToken(WsConfiguration, LogConfig),
+	SignerToken(WsConfiguration, LogConfig, TokenSpec),
+	SignerTokens(PathBuf),
+	SignerRevokeToken {
+		id: String,
+		authfile: PathBuf,
+	},
 	SignerSign {
 		id: Option<usize>,
 		pwfile: Option<PathBuf>,
@@ -73,7 +93,15 @@ pub enum Cmd {
 	},
 	SignerList {
 		port: u16,
-		authfile: PathBuf
+		authfile: PathBuf,
+		json: bool,
+	},
+	SignerApproveAll {
+		filter: Option<String>,
+		pwfile: Option<PathBuf>,
+		port: u16,
+		authfile: PathBuf,
+		json: bool,
 	},
 	SignerReject {
 		id: Option<usize>,
@@ -83,10 +111,14 @@ pub enum Cmd {
 	Snapshot(SnapshotCommand),
 	Hash(Option<String>),
 	ExportHardcodedSync(ExportHsyncCmd),
+	ValidateSpec(ValidateSpecCmd),
+	TestRunner(TestRunnerCmd),
+	Setup(SetupCmd),
+	Service(ServiceCmd),
+	UpdaterRollback(PathBuf),
 }
 
 pub struct Execute {
-	pub logger: LogConfig,
 	pub cmd: Cmd,
 }
 
@@ -142,13 +174,27 @@ impl Configuration {
 		let secretstore_conf = self.secretstore_config()?;
 		let format = self.format()?;
 
-		let cmd = if self.args.flag_version {
+		let cmd = if self.args.flag_version && self.args.flag_json {
+			Cmd::VersionJson
+		} else if self.args.flag_version {
 			Cmd::Version
 		} else if self.args.cmd_signer {
 			let authfile = ::signer::codes_path(&ws_conf.signer_path);
 
 			if self.args.cmd_signer_new_token {
-				Cmd::SignerToken(ws_conf, logger_config.clone())
+				let spec = TokenSpec {
+					name: self.args.arg_signer_token_name.clone(),
+					scope: self.args.arg_signer_token_scope.as_ref().map(|scope| scope.split(',').map(Into::into).collect()),
+					expiry: self.args.arg_signer_token_expiry,
+				};
+				Cmd::SignerToken(ws_conf, logger_config.clone(), spec)
+			} else if self.args.cmd_signer_tokens {
+				Cmd::SignerTokens(authfile)
+			} else if self.args.cmd_signer_revoke_token {
+				Cmd::SignerRevokeToken {
+					id: self.args.arg_signer_revoke_token_id.clone().ok_or_else(|| "Token ID is required.".to_owned())?,
+					authfile: authfile,
+				}
 			} else if self.args.cmd_signer_sign {
 				let pwfile = self.accounts_config()?.password_files.first().map(|pwfile| {
 					PathBuf::from(pwfile)
@@ -169,18 +215,123 @@ impl Configuration {
 				Cmd::SignerList {
 					port: ws_conf.port,
 					authfile: authfile,
+					json: self.args.flag_json,
+				}
+			} else if self.args.cmd_signer_approve_all {
+				let pwfile = self.accounts_config()?.password_files.first().map(|pwfile| {
+					PathBuf::from(pwfile)
+				});
+				if let Some(ref filter) = self.args.arg_signer_approve_all_matching {
+					filter.parse::<::rpc_cli::RequestFilter>()?;
+				}
+				Cmd::SignerApproveAll {
+					filter: self.args.arg_signer_approve_all_matching.clone(),
+					pwfile: pwfile,
+					port: ws_conf.port,
+					authfile: authfile,
+					json: self.args.flag_json,
 				}
 			} else {
 				unreachable!();
 			}
+		} else if self.args.cmd_setup {
+			Cmd::Setup(SetupCmd {
+				answers_file: self.args.arg_setup_answers_file.as_ref().map(PathBuf::from),
+				// the same location the configuration is loaded from
+				config_path: replace_home(&default_data_path(), &self.args.arg_config).into(),
+				base_path: dirs.base.clone().into(),
+			})
+		} else if self.args.cmd_service && self.args.cmd_service_install {
+			Cmd::Service(ServiceCmd::Install)
+		} else if self.args.cmd_service && self.args.cmd_service_remove {
+			Cmd::Service(ServiceCmd::Remove)
+		} else if self.args.cmd_updater && self.args.cmd_updater_rollback {
+			Cmd::UpdaterRollback(default_hypervisor_path())
 		} else if self.args.cmd_tools && self.args.cmd_tools_hash {
 			Cmd::Hash(self.args.arg_tools_hash_file)
+		} else if self.args.cmd_chain && self.args.cmd_chain_validate_spec {
+			let spec = match self.args.arg_chain_validate_spec_file {
+				Some(ref file) => file.parse()?,
+				None => spec,
+			};
+			Cmd::ValidateSpec(ValidateSpecCmd {
+				spec: spec,
+				dirs: dirs,
+			})
 		} else if self.args.cmd_db && self.args.cmd_db_kill {
 			Cmd::Blockchain(BlockchainCmd::Kill(KillBlockchain {
 				spec: spec,
 				dirs: dirs,
 				pruning: pruning,
 			}))
+		} else if self.args.cmd_db && self.args.cmd_db_rebuild_blooms {
+			Cmd::Blockchain(BlockchainCmd::RebuildBlooms(RebuildBlooms {
+				spec: spec,
+				cache_config: cache_config,
+				dirs: dirs,
+				pruning: pruning,
+				pruning_history: pruning_history,
+				pruning_memory: self.args.arg_pruning_memory,
+				compaction: compaction,
+				wal: wal,
+				fat_db: fat_db,
+				blooms: self.blooms_config(),
+			}))
+		} else if self.args.cmd_db && self.args.cmd_db_index_addresses {
+			Cmd::Blockchain(BlockchainCmd::IndexAddresses(IndexAddresses {
+				spec: spec,
+				cache_config: cache_config,
+				dirs: dirs,
+				pruning: pruning,
+				pruning_history: pruning_history,
+				pruning_memory: self.args.arg_pruning_memory,
+				compaction: compaction,
+				wal: wal,
+				fat_db: fat_db,
+			}))
+		} else if self.args.cmd_db && self.args.cmd_db_backup {
+			Cmd::Blockchain(BlockchainCmd::Backup(BackupBlockchain {
+				spec: spec,
+				dirs: dirs,
+				pruning: pruning,
+				target: self.args.arg_db_backup_dir.clone().ok_or("Backup directory is required")?,
+			}))
+		} else if self.args.cmd_db && self.args.cmd_db_restore_backup {
+			Cmd::Blockchain(BlockchainCmd::Restore(RestoreBlockchain {
+				spec: spec,
+				dirs: dirs,
+				source: self.args.arg_db_restore_backup_dir.clone().ok_or("Backup directory is required")?,
+			}))
+		} else if self.args.cmd_db && self.args.cmd_db_prune_traces {
+			let before = self.args.arg_db_prune_traces_before.as_ref().ok_or("--before is required")?;
+			Cmd::Blockchain(BlockchainCmd::PruneTraces(PruneTraces {
+				spec: spec,
+				cache_config: cache_config,
+				dirs: dirs,
+				pruning: pruning,
+				pruning_history: pruning_history,
+				pruning_memory: self.args.arg_pruning_memory,
+				compaction: compaction,
+				wal: wal,
+				fat_db: fat_db,
+				before: to_block_id(before)?,
+			}))
+		} else if self.args.cmd_db && self.args.cmd_db_trace_backfill {
+			let range = self.args.arg_db_trace_backfill_range.as_ref().ok_or("Block range is required")?;
+			let (from_block, to_block) = to_block_range(range)?;
+			Cmd::Blockchain(BlockchainCmd::BackfillTraces(BackfillTraces {
+				spec: spec,
+				cache_config: cache_config,
+				dirs: dirs,
+				pruning: pruning,
+				pruning_history: pruning_history,
+				pruning_memory: self.args.arg_pruning_memory,
+				compaction: compaction,
+				wal: wal,
+				fat_db: fat_db,
+				from_block: from_block,
+				to_block: to_block,
+			}))
 		} else if self.args.cmd_account {
 			let account_cmd = if self.args.cmd_account_new {
 				let new_acc = NewAccount {
@@ -203,6 +354,22 @@ impl Configuration {
 					spec: spec,
 				};
 				AccountCmd::Import(import_acc)
+			} else if self.args.cmd_account_recover {
+				let recover_acc = RecoverAccount {
+					iterations: self.args.arg_keys_iterations,
+					path: dirs.keys,
+					spec: spec,
+					password_file: self.accounts_config()?.password_files.first().map(|x| x.to_owned()),
+					secret_file: self.args.arg_account_recover_secret_file.clone(),
+					mnemonic: match self.args.flag_account_recover_mnemonic {
+						true => Some(MnemonicOptions {
+							passphrase_file: self.args.arg_account_recover_passphrase_file.clone(),
+							derivation_path: self.args.arg_account_recover_derivation_path.clone(),
+						}),
+						false => None,
+					},
+				};
+				AccountCmd::Recover(recover_acc)
 			} else {
 				unreachable!();
 			};
@@ -244,6 +411,7 @@ impl Configuration {
 				with_color: logger_config.color,
 				verifier_settings: self.verifier_settings(),
 				light: self.args.flag_light,
+				progress_file: self.args.arg_import_progress_file.clone(),
 			};
 			Cmd::Blockchain(BlockchainCmd::Import(import_cmd))
 		} else if self.args.cmd_export {
@@ -264,6 +432,7 @@ impl Configuration {
 					from_block: to_block_id(&self.args.arg_export_blocks_from)?,
 					to_block: to_block_id(&self.args.arg_export_blocks_to)?,
 					check_seal: !self.args.flag_no_seal_check,
+					progress_file: self.args.arg_export_blocks_progress_file.clone(),
 				};
 				Cmd::Blockchain(BlockchainCmd::Export(export_cmd))
 			} else if self.args.cmd_export_state {
@@ -335,6 +504,19 @@ impl Configuration {
 				wal: wal,
 			};
 			Cmd::ExportHardcodedSync(export_hs_cmd)
+		} else if self.args.cmd_test_runner {
+			if self.args.arg_test_runner_spec.is_some() && self.args.arg_test_runner_fork.is_none() {
+				return Err("--spec requires --fork to select the tests to run with it.".into());
+			}
+			Cmd::TestRunner(TestRunnerCmd {
+				path: self.args.arg_test_runner_chain_tests.clone().ok_or("--chain-tests is required")?,
+				fork: self.args.arg_test_runner_fork.clone(),
+				spec: match self.args.arg_test_runner_spec {
+					Some(ref spec) => Some(spec.parse()?),
+					None => None,
+				},
+				dirs: dirs,
+			})
 		} else {
 			let daemon = if self.args.cmd_daemon {
 				Some(self.args.arg_daemon_pid_file.clone().expect("CLI argument is required; qed"))
@@ -343,7 +525,7 @@ impl Configuration {
 			};
 
 			let verifier_settings = self.verifier_settings();
-			let whisper_config = self.whisper_config();
+			let whisper_config = self.whisper_config()?;
 			let (private_provider_conf, private_enc_conf, private_tx_enabled) = self.private_provider_config()?;
 
 			let run_cmd = RunCmd {
@@ -359,6 +541,8 @@ impl Configuration {
 				gas_price_percentile: self.args.arg_gas_price_percentile,
 				poll_lifetime: self.args.arg_poll_lifetime,
 				ntp_servers: self.ntp_servers(),
+				max_time_drift: self.max_time_drift()?,
+				strict_time_drift: self.args.flag_strict_time_drift,
 				ws_conf: ws_conf,
 				http_conf: http_conf,
 				ipc_conf: ipc_conf,
@@ -369,11 +553,16 @@ impl Configuration {
 				miner_extras: self.miner_extras()?,
 				stratum: self.stratum_options()?,
 				update_policy: update_policy,
+				hash_fetch_mirrors: self.hash_fetch_mirrors(),
+				blob_store_size: self.args.arg_blob_store_size as u64 * 1024 * 1024,
+				informant: self.informant_config()?,
 				mode: mode,
 				tracing: tracing,
+				tracing_state_diffs: self.args.flag_tracing_state_diffs,
 				fat_db: fat_db,
 				compaction: compaction,
 				wal: wal,
+				db_auto_repair: self.args.flag_db_auto_repair,
 				vm_type: vm_type,
 				warp_sync: warp_sync,
 				warp_barrier: self.args.arg_warp_barrier,
@@ -397,12 +586,73 @@ impl Configuration {
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
 				whisper: whisper_config,
 				no_hardcoded_sync: self.args.flag_no_hardcoded_sync,
+				fork_alert_depth: self.args.arg_fork_alert_depth,
+				fork_alert_webhook: self.args.arg_fork_alert_webhook.clone(),
+				node_permission_overrides: self.args.arg_node_permission_overrides.clone(),
+				max_reorg_depth: self.args.arg_max_reorg_depth,
+				finality_depth: self.args.arg_finality_depth,
+				state_query_history: self.state_query_history()?,
+				ancient_blocks_threshold: self.args.arg_ancient_blocks_threshold,
+				blooms: self.blooms_config(),
+				receipts_storage: match self.args.arg_receipts_storage {
+					Some(ref mode) => Some(mode.parse()?),
+					None => None,
+				},
+				tx_index: match self.args.arg_tx_index {
+					Some(ref policy) => Some(policy.parse()?),
+					None => None,
+				},
+				explorer_index: self.args.flag_enable_explorer_index,
+				resolve_names: self.args.flag_resolve_names,
+				dev_accounts: self.dev_accounts()?,
+				reorg_alert_webhook: self.args.arg_reorg_alert_webhook.clone(),
+				hooks: self.hooks_config()?,
+				ui_auto_approve: self.args.arg_ui_auto_approve.clone(),
+				signer_notify_url: self.args.arg_signer_notify_url.clone(),
+				sync_stall_timeout: match self.args.arg_sync_stall_timeout {
+					0 => None,
+					secs => Some(secs),
+				},
+				sync_stall_rotate_peers: self.args.arg_sync_stall_rotate_peers,
+				sync_stall_refresh_discovery: self.args.flag_sync_stall_refresh_discovery,
+				head_first_sync: self.args.flag_head_first_sync,
+				tx_propagation: self.tx_propagation()?,
+				tx_propagation_peers: self.tx_propagation_peers()?,
+				rpc_prefix: self.args.arg_rpc_prefix.clone(),
+				health_readiness: node_health::ReadinessThresholds {
+					max_blocks_behind: self.args.arg_health_max_blocks_behind,
+					min_peers: self.args.arg_health_min_peers,
+				},
+				min_disk_space: self.args.arg_min_disk_space,
 			};
-			Cmd::Run(run_cmd)
+
+			if self.args.cmd_service_run {
+				Cmd::Service(ServiceCmd::Run(run_cmd))
+			} else if self.args.chains.is_empty() {
+				check_chain_conflicts(Some(&run_cmd))?;
+				Cmd::Run(run_cmd)
+			} else {
+				let mut run_cmds = vec![run_cmd];
+				for mut chain in self.args.chains {
+					// chain entries follow the primary chain's handling of deprecated options
+					chain.flag_strict_flags = self.args.flag_strict_flags;
+					chain.arg_warnings = self.args.arg_warnings.clone();
+					handle_deprecated(&mut chain)?;
+					match (Configuration { args: chain }).into_command()?.cmd {
+						Cmd::Run(mut chain_cmd) => {
+							// only the primary chain may daemonize the process.
+							chain_cmd.daemon = None;
+							run_cmds.push(chain_cmd);
+						},
+						_ => return Err("Chains can only be configured to run a client and can't be nested.".into()),
+					}
+				}
+				check_chain_conflicts(&run_cmds)?;
+				Cmd::RunMulti(run_cmds)
+			}
 		};
 
 		Ok(Execute {
-			logger: logger_config,
 			cmd: cmd,
 		})
 	}
@@ -416,17 +666,39 @@ impl Configuration {
 		let ceil = to_u256(&self.args.arg_gas_cap)?;
 		let extras = MinerExtras {
 			author: self.author()?,
+			author_name: self.author_name(),
 			extra_data: self.extra_data()?,
 			gas_range_target: (floor, ceil),
 			engine_signer: self.engine_signer()?,
 			work_notify: self.work_notify(),
+			seal_mode: self.seal_mode()?,
 		};
 
 		Ok(extras)
 	}
 
+	fn seal_mode(&self) -> Result<Option<SealMode>, String> {
+		match (self.args.arg_dev_block_time, self.args.flag_dev_manual_seal) {
+			(Some(_), true) => Err("--dev-block-time and --dev-manual-seal can't be used together.".into()),
+			(Some(0), false) => Err("--dev-block-time has to be greater than 0.".into()),
+			(Some(seconds), false) => Ok(Some(SealMode::Interval(Duration::from_secs(seconds)))),
+			(None, true) => Ok(Some(SealMode::Manual)),
+			(None, false) => Ok(None),
+		}
+	}
+
 	fn author(&self) -> Result<Address, String> {
-		to_address(self.args.arg_etherbase.clone().or(self.args.arg_author.clone()))
+		match self.author_name() {
+			Some(_) => Ok(Address::default()),
+			None => to_address(self.args.arg_author.clone()),
+		}
+	}
+
+	fn author_name(&self) -> Option<String> {
+		match self.args.arg_author {
+			Some(ref author) if self.args.flag_resolve_names && !author.starts_with("0x") && to_address(Some(author.clone())).is_err() => Some(author.clone()),
+			_ => None,
+		}
 	}
 
 	fn engine_signer(&self) -> Result<Address, String> {
@@ -454,11 +726,24 @@ impl Configuration {
 		}
 	}
 
-	fn logger_config(&self) -> LogConfig {
+	/// Logging configuration to set up the logger with before calling `start`.
+	pub fn logger_config(&self) -> LogConfig {
 		LogConfig {
 			mode: self.args.arg_logging.clone(),
 			color: !self.args.flag_no_color && !cfg!(windows),
-			file: self.args.arg_log_file.as_ref().map(|log_file| replace_home(&self.directories().base, log_file)),
+			file: self.args.arg_log_file.as_ref().map(|log_file| replace_home(&self.directories().state, log_file)),
+			buffer_size: self.args.arg_log_buffer,
+		}
+	}
+
+	fn dev_accounts(&self) -> Result<Option<DevAccounts>, String> {
+		match self.args.arg_dev_accounts {
+			0 => Ok(None),
+			_ if self.chain()? != SpecType::Dev => Err("--dev-accounts requires --chain dev.".into()),
+			count => Ok(Some(DevAccounts {
+				count: count,
+				balance: to_u256(&self.args.arg_dev_balance)?,
+			})),
 		}
 	}
 
@@ -499,6 +784,57 @@ impl Configuration {
 		self.args.arg_snapshot_peers as u32
 	}
 
+	fn min_protocol_peers(&self) -> Result<HashMap<ProtocolId, u32>, String> {
+		let mut ret = HashMap::new();
+		let list = match self.args.arg_min_protocol_peers {
+			Some(ref list) => list,
+			None => return Ok(ret),
+		};
+
+		for entry in list.split(',').filter(|e| !e.is_empty()) {
+			let mut parts = entry.splitn(2, ':');
+			let (protocol, num) = match (parts.next(), parts.next()) {
+				(Some(protocol), Some(num)) if protocol.len() == 3 => (protocol, num),
+				_ => return Err(format!("Invalid protocol peers entry: {}. Expected PROTOCOL:NUM.", entry)),
+			};
+			let num = num.parse::<u32>().map_err(|_| format!("Invalid number of protocol peers: {}", entry))?;
+			let mut id: ProtocolId = [0; 3];
+			id.clone_from_slice(protocol.as_bytes());
+			ret.insert(id, num);
+		}
+
+		Ok(ret)
+	}
+
+	fn tx_propagation(&self) -> Result<sync::TransactionPropagation, String> {
+		let policy = match self.args.arg_tx_propagation.as_str() {
+			"all" => sync::TransactionPropagation::All,
+			"sqrt" => sync::TransactionPropagation::Sqrt,
+			"none" => sync::TransactionPropagation::None,
+			"private" => sync::TransactionPropagation::Private,
+			other => return Err(format!("Invalid transaction propagation policy: {}", other)),
+		};
+		if policy == sync::TransactionPropagation::Private && self.args.arg_tx_propagation_peers.is_none() {
+			return Err("Private transaction propagation requires --tx-propagation-peers.".into());
+		}
+		Ok(policy)
+	}
+
+	fn tx_propagation_peers(&self) -> Result<HashSet<H512>, String> {
+		let list = match self.args.arg_tx_propagation_peers {
+			Some(ref list) => list,
+			None => return Ok(HashSet::new()),
+		};
+
+		list.split(',').filter(|e| !e.is_empty()).map(|entry| {
+			let id = match entry.trim_left_matches("enode://").split('@').next() {
+				Some(id) => id.trim_left_matches("0x"),
+				None => entry,
+			};
+			id.parse::<H512>().map_err(|_| format!("Invalid node ID: {}", entry))
+		}).collect()
+	}
+
 	fn work_notify(&self) -> Vec<String> {
 		self.args.arg_notify_work.as_ref().map_or_else(Vec::new, |s| s.split(',').map(|s| s.to_owned()).collect())
 	}
@@ -591,6 +927,63 @@ impl Configuration {
 		self.args.arg_ntp_servers.split(",").map(str::to_owned).collect()
 	}
 
+	fn blooms_config(&self) -> BloomsConfig {
+		BloomsConfig {
+			levels: self.args.arg_blooms_levels,
+			fan_out: self.args.arg_blooms_fan_out,
+		}
+	}
+
+	fn state_query_history(&self) -> Result<Option<u64>, String> {
+		match self.args.arg_state_query_history {
+			Some(0) => Err("State query history must be at least one block".into()),
+			history => Ok(history),
+		}
+	}
+
+	fn max_time_drift(&self) -> Result<i64, String> {
+		match self.args.arg_max_time_drift {
+			drift if drift <= 0 => Err("Maximal time drift must be at least one millisecond".into()),
+			drift => Ok(drift),
+		}
+	}
+
+	fn hooks_config(&self) -> Result<HooksConfig, String> {
+		if self.args.arg_hook_max_concurrent == 0 {
+			return Err("Hook concurrency limit must be at least one".into());
+		}
+		if self.args.arg_hook_timeout == 0 {
+			return Err("Hook timeout must be at least one second".into());
+		}
+
+		Ok(HooksConfig {
+			on_new_block: self.args.arg_on_new_block.clone(),
+			on_reorg: self.args.arg_on_reorg.clone(),
+			timeout: Duration::from_secs(self.args.arg_hook_timeout),
+			max_concurrent: self.args.arg_hook_max_concurrent,
+		})
+	}
+
+	fn informant_config(&self) -> Result<InformantConfig, String> {
+		if self.args.arg_informant_interval == 0 {
+			return Err("Informant interval must be at least one second".into());
+		}
+
+		Ok(InformantConfig {
+			mode: self.args.arg_informant.parse()?,
+			columns: informant::parse_columns(&self.args.arg_informant_columns)?,
+			interval: Duration::from_secs(self.args.arg_informant_interval),
+			systemd_notify: self.args.flag_systemd_notify,
+		})
+	}
+
+	fn hash_fetch_mirrors(&self) -> Vec<String> {
+		match self.args.arg_hash_fetch_mirrors {
+			Some(ref mirrors) => mirrors.split(',').map(str::to_owned).filter(|s| !s.is_empty()).collect(),
+			None => Vec::new(),
+		}
+	}
+
 	fn dapps_config(&self) -> DappsConfiguration {
 		DappsConfiguration {
 			enabled: self.dapps_enabled(),
@@ -600,6 +993,10 @@ impl Configuration {
 			} else {
 				vec![]
 			},
+			ipfs_gateway: self.args.arg_dapps_ipfs_gateway.clone(),
+			ipfs_dapps: self.args.arg_dapps_ipfs.as_ref()
+				.map(|cids| cids.split(',').map(|cid| cid.trim().to_owned()).collect())
+				.unwrap_or_default(),
 		}
 	}
 
@@ -623,6 +1020,7 @@ impl Configuration {
 			http_port: self.args.arg_ports_shift + self.args.arg_secretstore_http_port,
 			data_path: self.directories().secretstore,
 			admin_public: self.secretstore_admin_public()?,
+			tls: self.secretstore_tls()?,
 		})
 	}
 
@@ -633,6 +1031,9 @@ impl Configuration {
 			interface: self.ipfs_interface(),
 			cors: self.ipfs_cors(),
 			hosts: self.ipfs_hosts(),
+			path_prefix: self.args.arg_ipfs_api_path_prefix.clone(),
+			max_response_size: self.args.arg_ipfs_api_max_response_size * 1024,
+			store_size: self.args.arg_ipfs_api_store_size * 1024 * 1024,
 		}
 	}
 
@@ -686,7 +1087,7 @@ impl Configuration {
 	}
 
 	fn extra_data(&self) -> Result<Bytes, String> {
-		match self.args.arg_extradata.as_ref().or(self.args.arg_extra_data.as_ref()) {
+		match self.args.arg_extra_data.as_ref() {
 			Some(x) if x.len() <= 32 => Ok(x.as_bytes().to_owned()),
 			None => Ok(version_data()),
 			Some(_) => Err("Extra data must be at most 32 characters".into()),
@@ -750,9 +1151,10 @@ impl Configuration {
 		ret.max_peers = self.max_peers();
 		ret.min_peers = self.min_peers();
 		ret.snapshot_peers = self.snapshot_peers();
+		ret.min_protocol_peers = self.min_protocol_peers()?;
 		ret.ip_filter = self.ip_filter()?;
 		ret.max_pending_peers = self.max_pending_peers();
-		let mut net_path = PathBuf::from(self.directories().base);
+		let mut net_path = PathBuf::from(self.directories().state);
 		net_path.push("network");
 		ret.config_path = Some(net_path.to_str().unwrap().to_owned());
 		ret.reserved_nodes = self.init_reserved_nodes()?;
@@ -844,13 +1246,55 @@ impl Configuration {
 		Self::parse_hosts(&self.args.arg_ws_origins)
 	}
 
+	fn ws_origin_apis(&self) -> Result<Vec<(String, ApiSet)>, String> {
+		let mappings = match self.args.arg_ws_origin_apis {
+			Some(ref mappings) => mappings,
+			None => return Ok(Vec::new()),
+		};
+
+		mappings.split(';').map(str::trim).filter(|m| !m.is_empty()).map(|mapping| {
+			let mut parts = mapping.rsplitn(2, '=');
+			match (parts.next(), parts.next()) {
+				(Some(apis), Some(origin)) => Ok((origin.to_owned(), apis.parse()?)),
+				_ => Err(format!("Invalid WebSockets origin APIs: {}. Expected ORIGIN=APIS.", mapping)),
+			}
+		}).collect()
+	}
+
+	fn extra_binds(&self, binds: &Option<String>) -> Result<Vec<Bind>, String> {
+		let binds = match *binds {
+			Some(ref binds) => binds,
+			None => return Ok(Vec::new()),
+		};
+
+		binds.split(';').map(str::trim).filter(|b| !b.is_empty()).map(|bind| {
+			let invalid = || format!("Invalid bind: {}. Expected INTERFACE:PORT=APIS.", bind);
+			let mut parts = bind.splitn(2, '=');
+			let (address, apis) = match (parts.next(), parts.next()) {
+				(Some(address), Some(apis)) => (address, apis),
+				_ => return Err(invalid()),
+			};
+			let mut address = address.rsplitn(2, ':');
+			let (port, interface) = match (address.next().and_then(|port| port.parse::<u16>().ok()), address.next()) {
+				(Some(port), Some(interface)) => (port, interface),
+				_ => return Err(invalid()),
+			};
+
+			Ok(Bind {
+				interface: self.interface(interface),
+				port: self.args.arg_ports_shift + port,
+				apis: apis.parse()?,
+			})
+		}).collect()
+	}
+
 	fn ipfs_hosts(&self) -> Option<Vec<String>> {
 		self.hosts(&self.args.arg_ipfs_api_hosts, &self.ipfs_interface())
 	}
 
 	fn ipc_config(&self) -> Result<IpcConfiguration, String> {
 		let conf = IpcConfiguration {
-			enabled: !(self.args.flag_ipcdisable || self.args.flag_ipc_off || self.args.flag_no_ipc),
+			enabled: !self.args.flag_no_ipc,
 			socket_addr: self.ipc_path(),
 			apis: {
 				let mut apis = self.args.arg_ipcapi.clone().unwrap_or(self.args.arg_ipc_apis.clone());
@@ -862,6 +1306,7 @@ impl Configuration {
 				}
 				apis.parse()?
 			},
+			notification_queue: self.args.arg_ipc_notification_queue,
 		};
 
 		Ok(conf)
@@ -880,6 +1325,10 @@ impl Configuration {
 				_ => 1,
 			},
 			processing_threads: self.args.arg_jsonrpc_threads,
+			heavy_threads: self.args.arg_jsonrpc_heavy_threads,
+			heavy_methods: split_methods(&self.args.arg_jsonrpc_heavy_methods),
+			priority_methods: split_methods(&self.args.arg_jsonrpc_priority_methods),
+			extra_binds: self.extra_binds(&self.args.arg_jsonrpc_extra_binds)?,
 		};
 
 		Ok(conf)
@@ -897,12 +1346,14 @@ impl Configuration {
 			interface: self.ws_interface(),
 			port: self.args.arg_ports_shift + self.args.arg_ws_port,
 			apis: self.args.arg_ws_apis.parse()?,
+			origin_apis: self.ws_origin_apis()?,
 			hosts: self.ws_hosts(),
 			origins: self.ws_origins(),
 			signer_path: self.directories().signer.into(),
 			support_token_api,
 			dapps_address: http.address(),
 			max_connections: self.args.arg_ws_max_connections,
+			extra_binds: self.extra_binds(&self.args.arg_ws_extra_binds)?,
 		};
 
 		Ok(conf)
@@ -954,18 +1405,39 @@ impl Configuration {
 				"all" => UpdateFilter::All,
 				_ => return Err("Invalid value for `--auto-update`. See `--help` for more information.".into()),
 			},
-			track: match self.args.arg_release_track.as_ref() {
-				"stable" => ReleaseTrack::Stable,
-				"beta" => ReleaseTrack::Beta,
-				"nightly" => ReleaseTrack::Nightly,
-				"testing" => ReleaseTrack::Testing,
-				"current" => ReleaseTrack::Unknown,
-				_ => return Err("Invalid value for `--releases-track`. See `--help` for more information.".into()),
+			track: match self.args.arg_auto_update_channel {
+				Some(ref channel) => match channel.as_ref() {
+					"stable" => ReleaseTrack::Stable,
+					"beta" => ReleaseTrack::Beta,
+					"nightly" => ReleaseTrack::Nightly,
+					_ => return Err("Invalid value for `--auto-update-channel`. See `--help` for more information.".into()),
+				},
+				None => match self.args.arg_release_track.as_ref() {
+					"stable" => ReleaseTrack::Stable,
+					"beta" => ReleaseTrack::Beta,
+					"nightly" => ReleaseTrack::Nightly,
+					"testing" => ReleaseTrack::Testing,
+					"current" => ReleaseTrack::Unknown,
+					_ => return Err("Invalid value for `--releases-track`. See `--help` for more information.".into()),
+				},
 			},
 			path: default_hypervisor_path(),
 			max_size: 128 * 1024 * 1024,
 			max_delay: self.args.arg_auto_update_delay as u64,
 			frequency: self.args.arg_auto_update_check_frequency as u64,
+			max_version: match self.args.arg_auto_update_max_version {
+				Some(ref version) => Some(Version::parse(version)
+					.map_err(|e| format!("Invalid value for `--auto-update-max-version`: {}", e))?),
+				None => None,
+			},
+			trusted_keys: match self.args.arg_auto_update_trusted_keys {
+				Some(ref keys) => keys.split(',')
+					.map(|key| key.trim().trim_left_matches("0x").parse()
+						.map_err(|_| format!("Invalid public key in `--auto-update-trusted-keys`: {}", key)))
+					.collect::<Result<_, _>>()?,
+				None => Vec::new(),
+			},
+			keep_previous: self.args.arg_auto_update_keep,
 		})
 	}
 
@@ -973,25 +1445,28 @@ impl Configuration {
 		let local_path = default_local_path();
 		let base_path = self.args.arg_base_path.as_ref().or_else(|| self.args.arg_datadir.as_ref()).map_or_else(|| default_data_path(), |s| s.clone());
 		let data_path = replace_home("", &base_path);
-		let is_using_base_path = self.args.arg_base_path.is_some();
+		// Mutable data is kept in the state path, `$BASE` in the mutable paths refers to it.
+		let state_path = self.args.arg_state_path.as_ref().map_or_else(|| data_path.clone(), |s| replace_home(&data_path, s));
+		let is_using_base_path = self.args.arg_base_path.is_some() || self.args.arg_state_path.is_some();
 		// If base_path is set and db_path is not we default to base path subdir instead of LOCAL.
 		let base_db_path = if is_using_base_path && self.args.arg_db_path.is_none() {
 			"$BASE/chains"
 		} else {
 			self.args.arg_db_path.as_ref().map_or(dir::CHAINS_PATH, |s| &s)
 		};
-		let cache_path = if is_using_base_path { "$BASE/cache" } else { dir::CACHE_PATH };
+		let cache_path = if self.args.arg_base_path.is_some() { "$BASE/cache" } else { dir::CACHE_PATH };
 
-		let db_path = replace_home_and_local(&data_path, &local_path, &base_db_path);
+		let db_path = replace_home_and_local(&state_path, &local_path, &base_db_path);
 		let cache_path = replace_home_and_local(&data_path, &local_path, cache_path);
-		let keys_path = replace_home(&data_path, &self.args.arg_keys_path);
+		let keys_path = replace_home(&state_path, &self.args.arg_keys_path);
 		let dapps_path = replace_home(&data_path, &self.args.arg_dapps_path);
-		let secretstore_path = replace_home(&data_path, &self.args.arg_secretstore_path);
-		let ui_path = replace_home(&data_path, &self.args.arg_ui_path);
+		let secretstore_path = replace_home(&state_path, &self.args.arg_secretstore_path);
+		let ui_path = replace_home(&state_path, &self.args.arg_ui_path);
 
 		Directories {
 			keys: keys_path,
 			base: data_path,
+			state: state_path,
 			cache: cache_path,
 			db: db_path,
 			dapps: dapps_path,
@@ -1005,7 +1480,7 @@ impl Configuration {
 			geth_ipc_path(self.args.flag_testnet)
 		} else {
 			parity_ipc_path(
-				&self.directories().base,
+				&self.directories().state,
 				&self.args.arg_ipcpath.clone().unwrap_or(self.args.arg_ipc_path.clone()),
 				self.args.arg_ports_shift,
 			)
@@ -1063,6 +1538,19 @@ impl Configuration {
 		}
 	}
 
+	fn secretstore_tls(&self) -> Result<Option<SecretStoreTlsConfiguration>, String> {
+		let base = self.directories().base;
+		match (self.args.arg_secretstore_tls_cert.as_ref(), self.args.arg_secretstore_tls_key.as_ref(), self.args.arg_secretstore_tls_ca.as_ref()) {
+			(Some(cert), Some(key), Some(ca)) => Ok(Some(SecretStoreTlsConfiguration {
+				certificate_path: replace_home(&base, cert),
+				private_key_path: replace_home(&base, key),
+				ca_path: replace_home(&base, ca),
+			})),
+			(None, None, None) => Ok(None),
+			_ => Err("Secret store TLS requires all of --secretstore-tls-cert, --secretstore-tls-key and --secretstore-tls-ca".into()),
+		}
+	}
+
 	fn secretstore_nodes(&self) -> Result<BTreeMap<Public, (String, u16)>, String> {
 		let mut nodes = BTreeMap::new();
 		for node in self.args.arg_secretstore_nodes.split(',').filter(|n| n != &"") {
@@ -1092,7 +1580,7 @@ impl Configuration {
 	}
 
 	fn rpc_enabled(&self) -> bool {
-		!self.args.flag_jsonrpc_off && !self.args.flag_no_jsonrpc
+		!self.args.flag_no_jsonrpc
 	}
 
 	fn ws_enabled(&self) -> bool {
@@ -1100,7 +1588,7 @@ impl Configuration {
 	}
 
 	fn dapps_enabled(&self) -> bool {
-		!self.args.flag_dapps_off && !self.args.flag_no_dapps && self.rpc_enabled() && cfg!(feature = "dapps")
+		!self.args.flag_no_dapps && self.rpc_enabled() && cfg!(feature = "dapps")
 	}
 
 	fn secretstore_enabled(&self) -> bool {
@@ -1153,14 +1641,40 @@ impl Configuration {
 		settings
 	}
 
-	fn whisper_config(&self) -> ::whisper::Config {
-		::whisper::Config {
+	fn whisper_config(&self) -> Result<::whisper::Config, String> {
+		let mailserver_key = match self.args.arg_whisper_mailserver_key {
+			Some(ref key) => Some(key.parse::<H256>().map_err(|_| format!("Invalid whisper mail server key: {}", key))?),
+			None => None,
+		};
+
+		let min_pow = self.args.arg_whisper_min_pow;
+		if !min_pow.is_finite() || min_pow < 0f64 {
+			return Err(format!("Invalid whisper minimum PoW: {}", min_pow));
+		}
+
+		let bloom = match self.args.arg_whisper_bloom_filter {
+			Some(ref bloom) => Some(bloom.parse::<H512>().map_err(|_| format!("Invalid whisper bloom filter: {}", bloom))?),
+			None => None,
+		};
+
+		Ok(::whisper::Config {
 			enabled: self.args.flag_whisper,
 			target_message_pool_size: self.args.arg_whisper_pool_size * 1024 * 1024,
-		}
+			mailserver_key: mailserver_key,
+			mailserver_path: Path::new(&self.directories().db).join("whisper"),
+			limits: WhisperLimits {
+				min_pow: min_pow,
+				bloom: bloom,
+				max_envelopes_per_second: self.args.arg_whisper_max_envelopes_per_peer,
+			},
+		})
 	}
 }
 
+fn split_methods(methods: &str) -> Vec<String> {
+	methods.split(',').map(str::trim).filter(|m| !m.is_empty()).map(Into::into).collect()
+}
+
 fn into_secretstore_service_contract_address(s: Option<&String>) -> Result<Option<SecretStoreContractAddress>, String> {
 	match s.map(String::as_str) {
 		None | Some("none") => Ok(None),
@@ -1169,6 +1683,59 @@ fn into_secretstore_service_contract_address(s: Option<&String>) -> Result<Optio
 	}
 }
 
+/// Make sure chains running in the same process don't share a database, IPC path, port or RPC prefix,
+/// and that the servers of a chain don't share a port.
+fn check_chain_conflicts<'a, I: IntoIterator<Item = &'a RunCmd>>(cmds: I) -> Result<(), String> {
+	let mut paths = HashSet::new();
+	let mut ports = HashMap::new();
+	let mut prefixes = HashSet::new();
+	for cmd in cmds {
+		let mut used_paths = vec![format!("{} database in {}", cmd.spec, cmd.dirs.db)];
+		if cmd.ipc_conf.enabled {
+			used_paths.push(format!("IPC path {}", cmd.ipc_conf.socket_addr));
+		}
+
+		let mut used_ports = Vec::new();
+		if let Some(port) = cmd.net_conf.listen_address.as_ref().and_then(|address| address.rsplit(':').next()).and_then(|port| port.parse::<u16>().ok()) {
+			used_ports.push((port, "the network"));
+		}
+		if cmd.http_conf.enabled {
+			used_ports.push((cmd.http_conf.port, "JSON-RPC"));
+			used_ports.extend(cmd.http_conf.extra_binds.iter().map(|bind| (bind.port, "an extra JSON-RPC bind")));
+		}
+		if cmd.ws_conf.enabled {
+			used_ports.push((cmd.ws_conf.port, "WebSockets"));
+			used_ports.extend(cmd.ws_conf.extra_binds.iter().map(|bind| (bind.port, "an extra WebSockets bind")));
+		}
+		if cmd.secretstore_conf.enabled {
+			used_ports.push((cmd.secretstore_conf.port, "the secret store"));
+			if cmd.secretstore_conf.http_enabled {
+				used_ports.push((cmd.secretstore_conf.http_port, "the secret store HTTP API"));
+			}
+		}
+		if let Some(ref stratum) = cmd.stratum {
+			used_ports.push((stratum.port, "Stratum"));
+		}
+
+		for path in used_paths {
+			if !paths.insert(path.clone()) {
+				return Err(format!("Chain {} uses the same {} as another chain.", cmd.spec, path));
+			}
+		}
+		for (port, service) in used_ports {
+			if let Some(other) = ports.insert(port, service) {
+				return Err(format!("Chain {} uses port {} for {}, which is already used for {}.", cmd.spec, port, service, other));
+			}
+		}
+		if let Some(ref prefix) = cmd.rpc_prefix {
+			if !prefixes.insert(prefix.clone()) {
+				return Err(format!("Chain {} uses the same RPC prefix {} as another chain.", cmd.spec, prefix));
+			}
+		}
+	}
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use std::io::Write;
@@ -1182,7 +1749,7 @@ mod tests {
 	use parity_rpc::NetworkSettings;
 	use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 
-	use account::{AccountCmd, NewAccount, ImportAccounts, ListAccounts};
+	use account::{AccountCmd, NewAccount, ImportAccounts, ListAccounts, RecoverAccount, MnemonicOptions};
 	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat, ExportState};
 	use cli::Args;
 	use dir::{Directories, default_hypervisor_path};
@@ -1192,6 +1759,7 @@ mod tests {
 	use rpc::WsConfiguration;
 	use rpc_apis::ApiSet;
 	use run::RunCmd;
+	use informant::{Column, InformantConfig, InformantMode};
 
 	use network::{AllowIP, IpFilter};
 
@@ -1216,6 +1784,13 @@ mod tests {
 		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Version);
 	}
 
+	#[test]
+	fn test_command_version_json() {
+		let args = vec!["parity", "--version", "--json"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::VersionJson);
+	}
+
 	#[test]
 	fn test_command_account_new() {
 		let args = vec!["parity", "account", "new"];
@@ -1251,6 +1826,23 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_account_recover() {
+		let args = vec!["parity", "account", "recover", "--mnemonic", "--secret-file", "phrase.txt", "--password", "pwd"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Account(AccountCmd::Recover(RecoverAccount {
+			iterations: 10240,
+			path: Directories::default().keys,
+			spec: SpecType::default(),
+			password_file: Some("pwd".into()),
+			secret_file: Some("phrase.txt".into()),
+			mnemonic: Some(MnemonicOptions {
+				passphrase_file: None,
+				derivation_path: "m/44'/60'/0'/0/0".into(),
+			}),
+		})));
+	}
+
 	#[test]
 	fn test_command_wallet_import() {
 		let args = vec!["parity", "wallet", "import", "my_wallet.json", "--password", "pwd"];
@@ -1286,6 +1878,7 @@ mod tests {
 			with_color: !cfg!(windows),
 			verifier_settings: Default::default(),
 			light: false,
+			progress_file: None,
 		})));
 	}
 
@@ -1309,9 +1902,227 @@ mod tests {
 			from_block: BlockId::Number(1),
 			to_block: BlockId::Latest,
 			check_seal: true,
+			progress_file: None,
 		})));
 	}
 
+	#[test]
+	fn test_command_blockchain_export_progress_file() {
+		let args = vec!["parity", "export", "blocks", "--progress-file", "progress.json", "blockchain.json"];
+		let conf = parse(&args);
+		match conf.into_command().unwrap().cmd {
+			Cmd::Blockchain(BlockchainCmd::Export(cmd)) => assert_eq!(cmd.progress_file, Some("progress.json".into())),
+			cmd => panic!("unexpected command: {:?}", cmd),
+		}
+	}
+
+	#[test]
+	fn test_command_db_rebuild_blooms() {
+		let args = vec!["parity", "db", "rebuild-blooms", "--blooms-levels", "4", "--blooms-fan-out", "8"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::RebuildBlooms(RebuildBlooms {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			wal: true,
+			fat_db: Default::default(),
+			blooms: BloomsConfig { levels: 4, fan_out: 8 },
+		})));
+	}
+
+	#[test]
+	fn test_command_db_index_addresses() {
+		let args = vec!["parity", "db", "index-addresses"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::IndexAddresses(IndexAddresses {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			wal: true,
+			fat_db: Default::default(),
+		})));
+	}
+
+	#[test]
+	fn test_command_db_backup() {
+		let args = vec!["parity", "db", "backup", "/tmp/backup"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::Backup(BackupBlockchain {
+			spec: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			target: "/tmp/backup".into(),
+		})));
+
+		let args = vec!["parity", "db", "restore-backup", "/tmp/backup"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::Restore(RestoreBlockchain {
+			spec: Default::default(),
+			dirs: Default::default(),
+			source: "/tmp/backup".into(),
+		})));
+	}
+
+	#[test]
+	fn test_command_db_prune_traces() {
+		let args = vec!["parity", "db", "prune-traces", "--before", "1000"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::PruneTraces(PruneTraces {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			wal: true,
+			fat_db: Default::default(),
+			before: BlockId::Number(1000),
+		})));
+	}
+
+	#[test]
+	fn test_command_db_trace_backfill() {
+		let args = vec!["parity", "db", "trace-backfill", "1000.."];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::BackfillTraces(BackfillTraces {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			wal: true,
+			fat_db: Default::default(),
+			from_block: BlockId::Number(1000),
+			to_block: BlockId::Latest,
+		})));
+	}
+
+	#[test]
+	fn test_run_multiple_chains() {
+		let mut conf = parse(&["parity"]);
+		conf.args.chains = vec![
+			parse(&["parity", "--chain", "kovan", "--port", "30304", "--jsonrpc-port", "8645", "--ws-port", "8646", "--no-ipc", "--no-secretstore"]).args,
+		];
+		match conf.into_command().unwrap().cmd {
+			Cmd::RunMulti(cmds) => {
+				assert_eq!(cmds.len(), 2);
+				assert_eq!(cmds[0].spec, SpecType::Foundation);
+				assert_eq!(cmds[1].spec, SpecType::Kovan);
+				assert_eq!(cmds[1].http_conf.port, 8645);
+			},
+			_ => panic!("Should be Cmd::RunMulti"),
+		}
+
+		let mut conf = parse(&["parity"]);
+		conf.args.chains = vec![parse(&["parity", "--chain", "kovan"]).args];
+		assert!(conf.into_command().is_err());
+
+		let mut conf = parse(&["parity", "--no-secretstore", "--jsonrpc-extra-binds", "all:8645=eth"]);
+		conf.args.chains = vec![
+			parse(&["parity", "--chain", "kovan", "--port", "30304", "--jsonrpc-port", "8645", "--ws-port", "8646", "--no-ipc", "--no-secretstore"]).args,
+		];
+		assert!(conf.into_command().is_err());
+
+		// the same port on a different interface or service still conflicts.
+		let mut conf = parse(&["parity", "--stratum", "--no-secretstore"]);
+		conf.args.chains = vec![
+			parse(&["parity", "--chain", "kovan", "--port", "30304", "--jsonrpc-port", "8645", "--ws-port", "8008", "--no-ipc", "--no-secretstore"]).args,
+		];
+		assert!(conf.into_command().is_err());
+
+		let mut conf = parse(&["parity", "--rpc-prefix", "eth", "--no-secretstore"]);
+		conf.args.chains = vec![
+			parse(&["parity", "--chain", "kovan", "--port", "30304", "--jsonrpc-port", "8645", "--ws-port", "8646", "--no-ipc", "--no-secretstore", "--rpc-prefix", "eth"]).args,
+		];
+		assert!(conf.into_command().is_err());
+
+		// deprecated options of chain entries are mapped, or rejected with --strict-flags.
+		let chain = || parse(&["parity", "--chain", "kovan", "--port", "30304", "--jsonrpc-port", "8645", "--ws-port", "8646", "--ipc-off", "--no-secretstore"]).args;
+		let mut conf = parse(&["parity", "--no-secretstore"]);
+		conf.args.chains = vec![chain()];
+		assert!(conf.into_command().is_ok());
+
+		let mut conf = parse(&["parity", "--no-secretstore", "--strict-flags"]);
+		conf.args.chains = vec![chain()];
+		assert!(conf.into_command().is_err());
+	}
+
+	#[test]
+	fn test_command_chain_validate_spec() {
+		let args = vec!["parity", "chain", "validate-spec", "spec.json"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::ValidateSpec(ValidateSpecCmd {
+			spec: SpecType::Custom("spec.json".into()),
+			dirs: Default::default(),
+		}));
+
+		let args = vec!["parity", "--chain", "kovan", "chain", "validate-spec"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::ValidateSpec(ValidateSpecCmd {
+			spec: SpecType::Kovan,
+			dirs: Default::default(),
+		}));
+	}
+
+	#[test]
+	fn test_command_test_runner() {
+		let args = vec!["parity", "test-runner", "--chain-tests", "GeneralStateTests", "--fork", "Byzantium", "--spec", "spec.json"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::TestRunner(TestRunnerCmd {
+			path: "GeneralStateTests".into(),
+			fork: Some("Byzantium".into()),
+			spec: Some(SpecType::Custom("spec.json".into())),
+			dirs: Default::default(),
+		}));
+
+		assert!(parse(&["parity", "test-runner"]).into_command().is_err());
+		assert!(parse(&["parity", "test-runner", "--chain-tests", "tests", "--spec", "spec.json"]).into_command().is_err());
+	}
+
+	#[test]
+	fn test_command_setup() {
+		let args = vec!["parity", "setup", "--answers-file", "answers.txt", "--config", "/tmp/parity/config.toml"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Setup(SetupCmd {
+			answers_file: Some("answers.txt".into()),
+			config_path: "/tmp/parity/config.toml".into(),
+			base_path: Directories::default().base.into(),
+		}));
+	}
+
+	#[test]
+	fn test_command_updater_rollback() {
+		let args = vec!["parity", "updater", "rollback"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::UpdaterRollback(default_hypervisor_path()));
+	}
+
+	#[test]
+	fn test_command_service() {
+		let conf = parse(&["parity", "service", "install", "--chain", "kovan"]);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Service(ServiceCmd::Install));
+
+		let conf = parse(&["parity", "service", "remove"]);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Service(ServiceCmd::Remove));
+
+		let conf = parse(&["parity", "service", "run", "--chain", "kovan"]);
+		match conf.into_command().unwrap().cmd {
+			Cmd::Service(ServiceCmd::Run(cmd)) => assert_eq!(cmd.spec, SpecType::Kovan),
+			_ => panic!("Should be Cmd::Service(ServiceCmd::Run)"),
+		}
+	}
+
 	#[test]
 	fn test_command_state_export() {
 		let args = vec!["parity", "export", "state", "state.json"];
@@ -1357,6 +2168,7 @@ mod tests {
 			from_block: BlockId::Number(1),
 			to_block: BlockId::Latest,
 			check_seal: true,
+			progress_file: None,
 		})));
 	}
 
@@ -1376,11 +2188,58 @@ mod tests {
 			dapps_address: Some("127.0.0.1:8545".into()),
 			support_token_api: true,
 			max_connections: 100,
+			origin_apis: Vec::new(),
+			extra_binds: Vec::new(),
 		}, LogConfig {
             color: true,
             mode: None,
             file: None,
-        } ));
+            buffer_size: 10000,
+        }, TokenSpec::default()));
+	}
+
+	#[test]
+	fn test_command_signer_new_scoped_token() {
+		let args = vec!["parity", "signer", "new-token", "--name", "monitoring", "--scope", "signer_requestsToConfirm,parity", "--expiry", "3600"];
+		let conf = parse(&args);
+		match conf.into_command().unwrap().cmd {
+			Cmd::SignerToken(_, _, spec) => assert_eq!(spec, TokenSpec {
+				name: Some("monitoring".into()),
+				scope: Some(vec!["signer_requestsToConfirm".into(), "parity".into()]),
+				expiry: Some(3600),
+			}),
+			cmd => panic!("Unexpected command: {:?}", cmd),
+		}
+	}
+
+	#[test]
+	fn test_command_signer_approve_all() {
+		let args = vec!["parity", "signer", "approve-all", "--matching", "kind=transaction,method=transfer", "--json"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::SignerApproveAll {
+			filter: Some("kind=transaction,method=transfer".into()),
+			pwfile: None,
+			port: 8546,
+			authfile: ::signer::codes_path(&Directories::default().signer),
+			json: true,
+		});
+	}
+
+	#[test]
+	fn test_command_signer_approve_all_rejects_empty_filter() {
+		let args = vec!["parity", "signer", "approve-all", "--matching", ""];
+		let conf = parse(&args);
+		assert!(conf.into_command().is_err());
+	}
+
+	#[test]
+	fn test_command_signer_revoke_token() {
+		let args = vec!["parity", "signer", "revoke-token", "monitoring"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::SignerRevokeToken {
+			id: "monitoring".into(),
+			authfile: ::signer::codes_path(&Directories::default().signer),
+		});
 	}
 
 	#[test]
@@ -1394,6 +2253,67 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn test_ws_origin_apis() {
+		let args = vec!["parity", "--ws-origin-apis", "https://wallet.example.com=eth,net; https://*=web3"];
+		let conf = parse(&args);
+
+		let origin_apis = conf.ws_config().unwrap().origin_apis;
+		assert_eq!(origin_apis.len(), 2);
+		assert_eq!(origin_apis[0], ("https://wallet.example.com".into(), "eth,net".parse().unwrap()));
+		assert_eq!(origin_apis[1], ("https://*".into(), "web3".parse().unwrap()));
+
+		let args = vec!["parity", "--ws-origin-apis", "parity://signer"];
+		assert!(parse(&args).ws_config().is_err());
+	}
+
+	#[test]
+	fn should_reserve_priority_lane_for_health_checks_by_default() {
+		let http = parse(&["parity"]).http_config().unwrap();
+		assert_eq!(http.priority_methods, ::rpc::DEFAULT_PRIORITY_METHODS.iter().map(|m| m.to_string()).collect::<Vec<_>>());
+		assert!(!http.priority_methods.contains(&"eth_blockNumber".to_owned()));
+	}
+
+	#[test]
+	fn test_extra_binds() {
+		let args = vec!["parity", "--jsonrpc-extra-binds", "192.168.1.5:8555=eth,net; local:8556=safe", "--ws-extra-binds", "all:8557=pubsub"];
+		let conf = parse(&args);
+
+		let http = conf.http_config().unwrap();
+		assert_eq!(http.extra_binds, vec![
+			Bind { interface: "192.168.1.5".into(), port: 8555, apis: "eth,net".parse().unwrap() },
+			Bind { interface: "127.0.0.1".into(), port: 8556, apis: "safe".parse().unwrap() },
+		]);
+		let ws = conf.ws_config().unwrap();
+		assert_eq!(ws.extra_binds, vec![Bind { interface: "0.0.0.0".into(), port: 8557, apis: "pubsub".parse().unwrap() }]);
+
+		let args = vec!["parity", "--jsonrpc-extra-binds", "192.168.1.5=eth"];
+		assert!(parse(&args).http_config().is_err());
+
+		// extra binds can't take a port of another server.
+		let args = vec!["parity", "--no-secretstore", "--jsonrpc-extra-binds", "all:8546=eth"];
+		assert!(parse(&args).into_command().is_err());
+		let args = vec!["parity", "--no-secretstore", "--ws-extra-binds", "local:8557=eth; all:8557=pubsub"];
+		assert!(parse(&args).into_command().is_err());
+		let args = vec!["parity", "--no-secretstore", "--jsonrpc-extra-binds", "all:8555=eth", "--ws-extra-binds", "all:8557=pubsub"];
+		assert!(parse(&args).into_command().is_ok());
+	}
+
+	#[test]
+	fn test_hooks_config() {
+		let args = vec!["parity", "--on-new-block", "./notify.sh", "--hook-timeout", "5"];
+		let hooks = parse(&args).hooks_config().unwrap();
+		assert_eq!(hooks.on_new_block, Some("./notify.sh".into()));
+		assert_eq!(hooks.on_reorg, None);
+		assert_eq!(hooks.timeout, Duration::from_secs(5));
+		assert_eq!(hooks.max_concurrent, 4);
+
+		let args = vec!["parity", "--hook-max-concurrent", "0"];
+		assert!(parse(&args).hooks_config().is_err());
+		let args = vec!["parity", "--hook-timeout", "0"];
+		assert!(parse(&args).hooks_config().is_err());
+	}
+
 	#[test]
 	fn test_run_cmd() {
 		let args = vec!["parity"];
@@ -1416,6 +2336,8 @@ mod tests {
 				"2.parity.pool.ntp.org:123".into(),
 				"3.parity.pool.ntp.org:123".into(),
 			],
+			max_time_drift: 10_000,
+			strict_time_drift: false,
 			ws_conf: Default::default(),
 			http_conf: Default::default(),
 			ipc_conf: Default::default(),
@@ -1435,11 +2357,19 @@ mod tests {
 				max_size: 128 * 1024 * 1024,
 				max_delay: 100,
 				frequency: 20,
+				max_version: None,
+				trusted_keys: Vec::new(),
+				keep_previous: 2,
 			},
+			hash_fetch_mirrors: Vec::new(),
+			blob_store_size: 256 * 1024 * 1024,
+			informant: Default::default(),
 			mode: Default::default(),
 			tracing: Default::default(),
+			tracing_state_diffs: false,
 			compaction: Default::default(),
 			wal: true,
+			db_auto_repair: false,
 			vm_type: Default::default(),
 			geth_compatibility: false,
 			net_settings: Default::default(),
@@ -1463,6 +2393,32 @@ mod tests {
 			no_hardcoded_sync: false,
 			no_persistent_txqueue: false,
 			whisper: Default::default(),
+			fork_alert_depth: None,
+			fork_alert_webhook: None,
+			node_permission_overrides: None,
+			max_reorg_depth: None,
+			finality_depth: 12,
+			state_query_history: None,
+			ancient_blocks_threshold: None,
+			blooms: Default::default(),
+			receipts_storage: None,
+			tx_index: None,
+			explorer_index: false,
+			resolve_names: false,
+			dev_accounts: None,
+			reorg_alert_webhook: None,
+			hooks: Default::default(),
+			ui_auto_approve: None,
+			signer_notify_url: None,
+			sync_stall_timeout: None,
+			sync_stall_rotate_peers: 2,
+			sync_stall_refresh_discovery: false,
+			head_first_sync: false,
+			tx_propagation: Default::default(),
+			tx_propagation_peers: Default::default(),
+			rpc_prefix: None,
+			health_readiness: Default::default(),
+			min_disk_space: 1024,
 		};
 		expected.secretstore_conf.enabled = cfg!(feature = "secretstore");
 		expected.secretstore_conf.http_enabled = cfg!(feature = "secretstore");
@@ -1491,6 +2447,44 @@ mod tests {
 		assert!(conf.miner_options().is_err());
 	}
 
+	#[test]
+	fn should_parse_dev_seal_mode() {
+		let conf0 = parse(&["parity", "--chain", "dev"]);
+		let conf1 = parse(&["parity", "--chain", "dev", "--dev-block-time", "5"]);
+		let conf2 = parse(&["parity", "--chain", "dev", "--dev-manual-seal"]);
+		let conf3 = parse(&["parity", "--chain", "dev", "--dev-block-time", "5", "--dev-manual-seal"]);
+		let conf4 = parse(&["parity", "--chain", "dev", "--dev-block-time", "0"]);
+
+		assert_eq!(conf0.miner_extras().unwrap().seal_mode, None);
+		assert_eq!(conf1.miner_extras().unwrap().seal_mode, Some(SealMode::Interval(Duration::from_secs(5))));
+		assert_eq!(conf2.miner_extras().unwrap().seal_mode, Some(SealMode::Manual));
+		assert!(conf3.miner_extras().is_err());
+		assert!(conf4.miner_extras().is_err());
+	}
+
+	#[test]
+	fn should_parse_dev_accounts() {
+		let conf0 = parse(&["parity", "--chain", "dev"]);
+		let conf1 = parse(&["parity", "--chain", "dev", "--dev-accounts", "3", "--dev-balance", "0x100"]);
+		let conf2 = parse(&["parity", "--dev-accounts", "3"]);
+
+		assert_eq!(conf0.dev_accounts().unwrap(), None);
+		assert_eq!(conf1.dev_accounts().unwrap(), Some(DevAccounts { count: 3, balance: 256.into() }));
+		assert!(conf2.dev_accounts().is_err());
+	}
+
+	#[test]
+	fn should_parse_author_name() {
+		let conf0 = parse(&["parity", "--author", "miner"]);
+		let conf1 = parse(&["parity", "--author", "miner", "--resolve-names"]);
+		let conf2 = parse(&["parity", "--author", "0x0000000000000000000000000000000000000001", "--resolve-names"]);
+
+		assert!(conf0.miner_extras().is_err());
+		assert_eq!(conf1.miner_extras().unwrap().author_name, Some("miner".into()));
+		assert_eq!(conf2.miner_extras().unwrap().author_name, None);
+		assert_eq!(conf2.miner_extras().unwrap().author, Address::from(1));
+	}
+
 	#[test]
 	fn should_parse_updater_options() {
 		// when
@@ -1498,6 +2492,11 @@ mod tests {
 		let conf1 = parse(&["parity", "--auto-update", "all", "--no-consensus", "--auto-update-delay", "300"]);
 		let conf2 = parse(&["parity", "--no-download", "--auto-update=all", "--release-track=beta", "--auto-update-delay=300", "--auto-update-check-frequency=100"]);
 		let conf3 = parse(&["parity", "--auto-update=xxx"]);
+		let conf4 = parse(&["parity", "--release-track=beta", "--auto-update-channel=stable", "--auto-update-max-version=1.12.3"]);
+		let conf5 = parse(&["parity", "--auto-update-channel=testing"]);
+		let conf6 = parse(&["parity", "--auto-update-max-version=latest"]);
+		let conf7 = parse(&["parity", "--auto-update-trusted-keys=0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001"]);
+		let conf8 = parse(&["parity", "--auto-update-trusted-keys=0x01"]);
 
 		// then
 		assert_eq!(conf0.update_policy().unwrap(), UpdatePolicy {
@@ -1509,6 +2508,9 @@ mod tests {
 			max_size: 128 * 1024 * 1024,
 			max_delay: 100,
 			frequency: 20,
+			max_version: None,
+			trusted_keys: Vec::new(),
+			keep_previous: 2,
 		});
 		assert_eq!(conf1.update_policy().unwrap(), UpdatePolicy {
 			enable_downloading: true,
@@ -1519,6 +2521,9 @@ mod tests {
 			max_size: 128 * 1024 * 1024,
 			max_delay: 300,
 			frequency: 20,
+			max_version: None,
+			trusted_keys: Vec::new(),
+			keep_previous: 2,
 		});
 		assert_eq!(conf2.update_policy().unwrap(), UpdatePolicy {
 			enable_downloading: false,
@@ -1529,8 +2534,53 @@ mod tests {
 			max_size: 128 * 1024 * 1024,
 			max_delay: 300,
 			frequency: 100,
+			max_version: None,
+			trusted_keys: Vec::new(),
+			keep_previous: 2,
 		});
 		assert!(conf3.update_policy().is_err());
+		assert_eq!(conf4.update_policy().unwrap(), UpdatePolicy {
+			enable_downloading: true,
+			require_consensus: true,
+			filter: UpdateFilter::Critical,
+			track: ReleaseTrack::Stable,
+			path: default_hypervisor_path(),
+			max_size: 128 * 1024 * 1024,
+			max_delay: 100,
+			frequency: 20,
+			max_version: Some(Version::parse("1.12.3").unwrap()),
+			trusted_keys: Vec::new(),
+			keep_previous: 2,
+		});
+		assert!(conf5.update_policy().is_err());
+		assert!(conf6.update_policy().is_err());
+		assert_eq!(conf7.update_policy().unwrap().trusted_keys, vec![1.into()]);
+		assert!(conf8.update_policy().is_err());
+	}
+
+	#[test]
+	fn should_parse_informant_config() {
+		let conf0 = parse(&["parity"]);
+		let conf1 = parse(&["parity", "--informant=json", "--informant-columns=peers,db", "--informant-interval=10"]);
+		let conf2 = parse(&["parity", "--informant-columns=peers,disk"]);
+
+		assert_eq!(conf0.informant_config(), Ok(Default::default()));
+		assert_eq!(conf1.informant_config(), Ok(InformantConfig {
+			mode: InformantMode::Json,
+			columns: vec![Column::Peers, Column::DbSize],
+			interval: Duration::from_secs(10),
+			systemd_notify: false,
+		}));
+		assert!(conf2.informant_config().is_err());
+	}
+
+	#[test]
+	fn should_parse_hash_fetch_mirrors() {
+		let conf0 = parse(&["parity"]);
+		let conf1 = parse(&["parity", "--hash-fetch-mirrors=https://a.example.com,https://b.example.com/"]);
+
+		assert_eq!(conf0.hash_fetch_mirrors(), Vec::<String>::new());
+		assert_eq!(conf1.hash_fetch_mirrors(), vec!["https://a.example.com".to_owned(), "https://b.example.com/".to_owned()]);
 	}
 
 	#[test]
@@ -1631,6 +2681,22 @@ mod tests {
 		assert_eq!(conf2.ipfs_cors(), Some(vec!["http://parity.io".into(),"http://something.io".into()]));
 	}
 
+	#[test]
+	fn should_parse_ipfs_path_prefix_and_response_size() {
+		// given
+
+		// when
+		let conf0 = parse(&["parity"]);
+		let conf1 = parse(&["parity", "--ipfs-api-path-prefix", "/ipfs", "--ipfs-api-max-response-size", "64"]);
+
+		// then
+		assert_eq!(conf0.ipfs_config().path_prefix, "");
+		assert_eq!(conf0.ipfs_config().max_response_size, 1024 * 1024);
+		assert_eq!(conf0.ipfs_config().store_size, 64 * 1024 * 1024);
+		assert_eq!(conf1.ipfs_config().path_prefix, "/ipfs");
+		assert_eq!(conf1.ipfs_config().max_response_size, 64 * 1024);
+	}
+
 	#[test]
 	fn should_parse_ui_configuration() {
 		// given
@@ -1673,6 +2739,21 @@ mod tests {
 		assert_eq!(extra_dapps, vec![tempdir.path().to_owned()]);
 	}
 
+	#[test]
+	fn should_parse_ipfs_dapps() {
+		// given
+
+		// when
+		let conf0 = parse(&["parity"]);
+		let conf1 = parse(&["parity", "--dapps-ipfs", "zb34WAp1Q5fhtLGZ3w3jhnTWaNbVV5ZZvGq4vuJQzERj6Pu3H, foo", "--dapps-ipfs-gateway", "http://localhost:8080"]);
+
+		// then
+		assert_eq!(conf0.dapps_config().ipfs_dapps, Vec::<String>::new());
+		assert_eq!(conf0.dapps_config().ipfs_gateway, "https://ipfs.io");
+		assert_eq!(conf1.dapps_config().ipfs_dapps, vec!["zb34WAp1Q5fhtLGZ3w3jhnTWaNbVV5ZZvGq4vuJQzERj6Pu3H".to_owned(), "foo".into()]);
+		assert_eq!(conf1.dapps_config().ipfs_gateway, "http://localhost:8080");
+	}
+
 	#[test]
 	fn should_not_bail_on_empty_line_in_reserved_peers() {
 		let tempdir = TempDir::new("").unwrap();
@@ -1918,6 +2999,22 @@ mod tests {
 		assert_eq!(base.directories().cache, "/test/cache");
 	}
 
+	#[test]
+	fn should_keep_mutable_data_in_state_path() {
+		let conf = parse(&["parity", "--base-path", "/image", "--state-path", "/data"]);
+		let dirs = conf.directories();
+
+		assert_eq!(dirs.base, "/image");
+		assert_eq!(dirs.state, "/data");
+		assert_eq!(dirs.cache, "/image/cache");
+		assert_eq!(dirs.dapps, "/image/dapps");
+		assert_eq!(dirs.db, "/data/chains");
+		assert_eq!(dirs.keys, "/data/keys");
+		assert_eq!(dirs.secretstore, "/data/secretstore");
+		assert_eq!(dirs.ipc_path(), PathBuf::from("/data/ipc"));
+		assert_eq!(dirs.blobs_path(), PathBuf::from("/data/blobs"));
+	}
+
 	#[test]
 	fn should_respect_only_max_peers_and_default() {
 		let args = vec!["parity", "--max-peers=50"];
@@ -1969,4 +3066,128 @@ mod tests {
 			_ => panic!("Should be Cmd::Run"),
 		}
 	}
+
+	#[test]
+	fn should_parse_min_protocol_peers() {
+		let args = vec!["parity", "--min-protocol-peers=par:4,pip:2"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		match conf.into_command().unwrap().cmd {
+			Cmd::Run(c) => {
+				assert_eq!(c.net_conf.min_protocol_peers.len(), 2);
+				assert_eq!(c.net_conf.min_protocol_peers[b"par"], 4);
+				assert_eq!(c.net_conf.min_protocol_peers[b"pip"], 2);
+			},
+			_ => panic!("Should be Cmd::Run"),
+		}
+	}
+
+	#[test]
+	fn should_reject_invalid_min_protocol_peers() {
+		let args = vec!["parity", "--min-protocol-peers=snapshot:4"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.into_command().is_err());
+	}
+
+	#[test]
+	fn should_parse_state_query_history() {
+		let args = vec!["parity", "--state-query-history=1024"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		match conf.into_command().unwrap().cmd {
+			Cmd::Run(c) => {
+				assert_eq!(c.state_query_history, Some(1024));
+				assert_eq!(c.pruning_history, 64);
+			},
+			_ => panic!("Should be Cmd::Run"),
+		}
+	}
+
+	#[test]
+	fn should_reject_zero_state_query_history() {
+		let args = vec!["parity", "--state-query-history=0"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.into_command().is_err());
+	}
+
+	#[test]
+	fn should_reject_non_positive_max_time_drift() {
+		for drift in &["--max-time-drift=0", "--max-time-drift=-1000"] {
+			let conf = Configuration::parse_cli(&["parity", drift]).unwrap();
+			assert!(conf.into_command().is_err());
+		}
+	}
+
+	#[test]
+	fn should_not_limit_state_queries_by_default() {
+		let args = vec!["parity"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		match conf.into_command().unwrap().cmd {
+			Cmd::Run(c) => assert_eq!(c.state_query_history, None),
+			_ => panic!("Should be Cmd::Run"),
+		}
+	}
+
+	#[test]
+	fn should_parse_private_tx_propagation() {
+		let node = "a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c";
+		let args = vec!["parity", "--tx-propagation=private", "--tx-propagation-peers=enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		match conf.into_command().unwrap().cmd {
+			Cmd::Run(c) => {
+				assert_eq!(c.tx_propagation, sync::TransactionPropagation::Private);
+				assert!(c.tx_propagation_peers.contains(&node.parse::<H512>().unwrap()));
+			},
+			_ => panic!("Should be Cmd::Run"),
+		}
+
+		let args = vec!["parity", "--tx-propagation=private"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.into_command().is_err());
+	}
+
+	#[test]
+	fn should_parse_secretstore_tls() {
+		let conf = parse(&["parity", "--secretstore-tls-cert=/tls/node.crt", "--secretstore-tls-key=/tls/node.key", "--secretstore-tls-ca=/tls/ca.crt"]);
+		assert_eq!(conf.secretstore_config().unwrap().tls, Some(SecretStoreTlsConfiguration {
+			certificate_path: "/tls/node.crt".into(),
+			private_key_path: "/tls/node.key".into(),
+			ca_path: "/tls/ca.crt".into(),
+		}));
+
+		let conf = parse(&["parity"]);
+		assert_eq!(conf.secretstore_config().unwrap().tls, None);
+
+		let conf = parse(&["parity", "--secretstore-tls-cert=/tls/node.crt"]);
+		assert!(conf.secretstore_config().is_err());
+	}
+
+	#[test]
+	fn should_parse_whisper_mailserver_key() {
+		let key = "0x0000000000000000000000000000000000000000000000000000000000000001";
+		let conf = parse(&["parity", "--whisper", "--whisper-mailserver-key", key]);
+		let whisper = conf.whisper_config().unwrap();
+		assert_eq!(whisper.mailserver_key, Some(H256::from(1)));
+		assert!(whisper.mailserver_path.ends_with("whisper"));
+
+		let conf = parse(&["parity", "--whisper"]);
+		assert_eq!(conf.whisper_config().unwrap().mailserver_key, None);
+
+		let conf = parse(&["parity", "--whisper-mailserver-key", "0x01"]);
+		assert!(conf.whisper_config().is_err());
+	}
+
+	#[test]
+	fn should_parse_whisper_limits() {
+		let conf = parse(&["parity", "--whisper", "--whisper-min-pow", "0.5", "--whisper-max-envelopes-per-peer", "100"]);
+		assert_eq!(conf.whisper_config().unwrap().limits, WhisperLimits {
+			min_pow: 0.5,
+			bloom: None,
+			max_envelopes_per_second: Some(100),
+		});
+
+		let conf = parse(&["parity", "--whisper", "--whisper-min-pow", "-1"]);
+		assert!(conf.whisper_config().is_err());
+
+		let conf = parse(&["parity", "--whisper", "--whisper-bloom-filter", "0x00"]);
+		assert!(conf.whisper_config().is_err());
+	}
 }
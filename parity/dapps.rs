@@ -24,6 +24,7 @@ use ethcore::client::{Client, BlockChainClient, BlockId, CallContract};
 use sync::LightSync;
 use futures::{Future, future, IntoFuture};
 use futures_cpupool::CpuPool;
+use hash_fetch::BlobStore;
 use hash_fetch::fetch::Client as FetchClient;
 use registrar::{RegistrarClient, Asynchronous};
 use light::client::LightChainClient;
@@ -39,6 +40,8 @@ pub struct Configuration {
 	pub enabled: bool,
 	pub dapps_path: PathBuf,
 	pub extra_dapps: Vec<PathBuf>,
+	pub ipfs_gateway: String,
+	pub ipfs_dapps: Vec<String>,
 }
 
 impl Default for Configuration {
@@ -48,6 +51,8 @@ impl Default for Configuration {
 			enabled: true,
 			dapps_path: replace_home(&data_dir, "$BASE/dapps").into(),
 			extra_dapps: vec![],
+			ipfs_gateway: "https://ipfs.io".into(),
+			ipfs_dapps: vec![],
 		}
 	}
 }
@@ -159,6 +164,7 @@ pub struct Dependencies {
 	pub fetch: FetchClient,
 	pub pool: CpuPool,
 	pub signer: Arc<SignerService>,
+	pub blob_store: Arc<BlobStore>,
 }
 
 pub fn new(configuration: Configuration, deps: Dependencies) -> Result<Option<Middleware>, String> {
@@ -171,6 +177,8 @@ pub fn new(configuration: Configuration, deps: Dependencies) -> Result<Option<Mi
 		configuration.dapps_path,
 		configuration.extra_dapps,
 		rpc::DAPPS_DOMAIN,
+		configuration.ipfs_gateway,
+		configuration.ipfs_dapps,
 	).map(Some)
 }
 
@@ -196,6 +204,8 @@ mod server {
 		_dapps_path: PathBuf,
 		_extra_dapps: Vec<PathBuf>,
 		_dapps_domain: &str,
+		_ipfs_gateway: String,
+		_ipfs_dapps: Vec<String>,
 	) -> Result<Middleware, String> {
 		Err("Your Parity version has been compiled without WebApps support.".into())
 	}
@@ -221,7 +231,13 @@ mod server {
 		dapps_path: PathBuf,
 		extra_dapps: Vec<PathBuf>,
 		dapps_domain: &str,
+		ipfs_gateway: String,
+		ipfs_dapps: Vec<String>,
 	) -> Result<Middleware, String> {
+		for cid in &ipfs_dapps {
+			parity_dapps::ipfs_content_id(cid).map_err(|e| format!("Invalid value for `--dapps-ipfs`: {}", e))?;
+		}
+
 		let signer = deps.signer;
 		let web_proxy_tokens = Arc::new(move |token| signer.web_proxy_access_token_domain(&token));
 
@@ -235,6 +251,9 @@ mod server {
 			deps.sync_status,
 			web_proxy_tokens,
 			deps.fetch,
+			ipfs_gateway,
+			ipfs_dapps,
+			Some(deps.blob_store),
 		))
 	}
 
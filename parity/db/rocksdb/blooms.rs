@@ -25,7 +25,7 @@ use super::open_database;
 
 pub fn migrate_blooms<P: AsRef<Path>>(path: P, config: &DatabaseConfig) -> Result<(), Error> {
 	// init
-	let db = open_database(&path.as_ref().to_string_lossy(), config)?;
+	let db = open_database(&path.as_ref().to_string_lossy(), config, Default::default())?;
 
 	// possible optimization:
 	// pre-allocate space on disk for faster migration
@@ -45,13 +45,13 @@ pub const TO_V12: ChangeColumns = ChangeColumns {
 /// Database is assumed to be at default version, when no version file is found.
 const DEFAULT_VERSION: u32 = 5;
 /// Current version of database models.
-const CURRENT_VERSION: u32 = 13;
+pub const CURRENT_VERSION: u32 = 13;
 /// A version of database at which blooms-db was introduced
 const BLOOMS_DB_VERSION: u32 = 13;
 /// Defines how many items are migrated to the new version of database at once.
 const BATCH_SIZE: usize = 1024;
 /// Version file name.
-const VERSION_FILE_NAME: &'static str = "db_version";
+pub const VERSION_FILE_NAME: &'static str = "db_version";
 
 /// Migration related erorrs.
 #[derive(Debug)]
@@ -229,6 +229,7 @@ pub fn migrate(path: &Path, compaction_profile: &DatabaseCompactionProfile) -> R
 				compaction: compaction_profile,
 				columns: db::NUM_COLUMNS,
 				wal: true,
+				repair_on_corruption: true,
 			};
 
 			migrate_blooms(path, &db_config).map_err(Error::BloomsDB)?;
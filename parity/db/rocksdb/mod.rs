@@ -21,25 +21,28 @@ use std::fs;
 use std::sync::Arc;
 use std::path::Path;
 use blooms_db;
+use era_db;
 use ethcore::{BlockChainDBHandler, BlockChainDB};
 use ethcore::error::Error;
 use ethcore::db::NUM_COLUMNS;
-use ethcore::client::{ClientConfig, DatabaseCompactionProfile};
-use kvdb::KeyValueDB;
+use ethcore::client::{ClientConfig, DatabaseCompactionProfile, BloomsConfig};
+use kvdb::{self, KeyValueDB};
 use self::kvdb_rocksdb::{Database, DatabaseConfig};
 
 use cache::CacheConfig;
+use helpers::copy_dir;
 
 mod blooms;
 mod migration;
 mod helpers;
 
-pub use self::migration::migrate;
+pub use self::migration::{migrate, CURRENT_VERSION};
 
 struct AppDB {
 	key_value: Arc<KeyValueDB>,
 	blooms: blooms_db::Database,
 	trace_blooms: blooms_db::Database,
+	ancient: era_db::Database,
 }
 
 impl BlockChainDB for AppDB {
@@ -54,6 +57,10 @@ impl BlockChainDB for AppDB {
 	fn trace_blooms(&self) -> &blooms_db::Database {
 		&self.trace_blooms
 	}
+
+	fn ancient(&self) -> Option<&era_db::Database> {
+		Some(&self.ancient)
+	}
 }
 
 /// Open a secret store DB using the given secret store data path. The DB path is one level beneath the data path.
@@ -64,7 +71,22 @@ pub fn open_secretstore_db(data_path: &str) -> Result<Arc<KeyValueDB>, String> {
 	let mut db_path = PathBuf::from(data_path);
 	db_path.push("db");
 	let db_path = db_path.to_str().ok_or_else(|| "Invalid secretstore path".to_string())?;
-	Ok(Arc::new(Database::open_default(&db_path).map_err(|e| format!("Error opening database: {:?}", e))?))
+	let db_config = DatabaseConfig::with_columns(::ethcore_secretstore::NUM_COLUMNS);
+	Ok(Arc::new(Database::open(&db_config, &db_path).map_err(|e| format!("Error opening database: {:?}", e))?))
+}
+
+/// Open a whisper mail server DB at the given path.
+pub fn open_whisper_db(db_path: &Path) -> Result<Arc<KeyValueDB>, String> {
+	let db_path = db_path.to_str().ok_or_else(|| "Invalid whisper mail server path".to_string())?;
+	let db_config = DatabaseConfig::with_columns(None);
+	Ok(Arc::new(Database::open(&db_config, &db_path).map_err(|e| format!("Error opening database: {:?}", e))?))
+}
+
+/// Open an explorer index DB at the given path.
+pub fn open_explorer_db(db_path: &Path) -> Result<Arc<KeyValueDB>, String> {
+	let db_path = db_path.to_str().ok_or_else(|| "Invalid explorer index path".to_string())?;
+	let db_config = DatabaseConfig::with_columns(None);
+	Ok(Arc::new(Database::open(&db_config, &db_path).map_err(|e| format!("Error opening database: {:?}", e))?))
 }
 
 /// Create a restoration db handler using the config generated by `client_path` and `client_config`.
@@ -73,34 +95,90 @@ pub fn restoration_db_handler(client_path: &Path, client_config: &ClientConfig)
 
 	struct RestorationDBHandler {
 		config: DatabaseConfig,
+		blooms: BloomsConfig,
 	}
 
 	impl BlockChainDBHandler for RestorationDBHandler {
 		fn open(&self, db_path: &Path) -> Result<Arc<BlockChainDB>, Error> {
-			open_database(&db_path.to_string_lossy(), &self.config)
+			open_database(&db_path.to_string_lossy(), &self.config, self.blooms)
 		}
 	}
 
 	Box::new(RestorationDBHandler {
 		config: client_db_config,
+		blooms: client_config.blooms,
 	})
 }
 
 /// Open a new main DB.
-pub fn open_db(client_path: &str, cache_config: &CacheConfig, compaction: &DatabaseCompactionProfile, wal: bool) -> Result<Arc<BlockChainDB>, Error> {
+pub fn open_db(client_path: &str, cache_config: &CacheConfig, compaction: &DatabaseCompactionProfile, wal: bool, auto_repair: bool) -> Result<Arc<BlockChainDB>, Error> {
 	let path = Path::new(client_path);
 
 	let db_config = DatabaseConfig {
 		memory_budget: Some(cache_config.blockchain() as usize * 1024 * 1024),
 		compaction: helpers::compaction_profile(&compaction, path),
 		wal,
+		repair_on_corruption: auto_repair,
 		.. DatabaseConfig::with_columns(NUM_COLUMNS)
 	};
 
-	open_database(client_path, &db_config)
+	open_database(client_path, &db_config, Default::default())
+}
+
+/// Human-readable name of a blockchain database column, as listed in the corruption marker.
+fn column_description(column: &str) -> &str {
+	match column {
+		"col0" => "state",
+		"col1" => "headers",
+		"col2" => "bodies",
+		"col3" => "extras",
+		"col4" => "traces",
+		"col5" => "account blooms",
+		"col6" => "node info",
+		"col7" => "light chain",
+		other => other,
+	}
+}
+
+/// Replaces the database error with an actionable one if the database is known to be corrupted.
+fn corruption_hint(client_path: &str, err: kvdb::Error) -> Error {
+	match Database::corrupted_columns(client_path) {
+		Some(columns) => {
+			let columns = match columns.is_empty() {
+				true => "unknown".to_owned(),
+				false => columns.iter().map(|c| column_description(c)).collect::<Vec<_>>().join(", "),
+			};
+			format!("Database at {} is corrupted (affected columns: {}). \
+				Restart with --db-auto-repair to attempt an automatic repair, \
+				or remove the database with `parity db kill` and resync. Original error: {}", client_path, columns, err).into()
+		},
+		None => err.into(),
+	}
+}
+
+/// Copies the client database at `client_path` into `target`. The database may be in use.
+pub fn backup_db(client_path: &Path, target: &Path) -> Result<(), String> {
+	kvdb_rocksdb::create_checkpoint(client_path, target)
+		.map_err(|e| format!("Error creating database checkpoint: {}", e))?;
+
+	// blooms and ancient blocks are copied after the checkpoint so that they are never behind the chain.
+	for blooms in &["blooms", "trace_blooms", "ancient"] {
+		let from = client_path.join(blooms);
+		if from.exists() {
+			copy_dir(&from, &target.join(blooms)).map_err(|e| format!("Error copying {}: {}", blooms, e))?;
+		}
+	}
+
+	let version = client_path.join(migration::VERSION_FILE_NAME);
+	if version.exists() {
+		fs::copy(&version, target.join(migration::VERSION_FILE_NAME)).map_err(|e| format!("Error copying database version: {}", e))?;
+	}
+
+	Ok(())
 }
 
-pub fn open_database(client_path: &str, config: &DatabaseConfig) -> Result<Arc<BlockChainDB>, Error> {
+/// Open the main DB at `client_path`. Bloom indexes which do not exist yet are created with the `blooms` layout.
+pub fn open_database(client_path: &str, config: &DatabaseConfig, blooms: BloomsConfig) -> Result<Arc<BlockChainDB>, Error> {
 	let path = Path::new(client_path);
 
 	let blooms_path = path.join("blooms");
@@ -109,9 +187,10 @@ pub fn open_database(client_path: &str, config: &DatabaseConfig) -> Result<Arc<B
 	fs::create_dir_all(&trace_blooms_path)?;
 
 	let db = AppDB {
-		key_value: Arc::new(Database::open(&config, client_path)?),
-		blooms: blooms_db::Database::open(blooms_path)?,
-		trace_blooms: blooms_db::Database::open(trace_blooms_path)?,
+		key_value: Arc::new(Database::open(&config, client_path).map_err(|e| corruption_hint(client_path, e))?),
+		blooms: blooms_db::Database::open_with_config(blooms_path, blooms)?,
+		trace_blooms: blooms_db::Database::open_with_config(trace_blooms_path, blooms)?,
+		ancient: era_db::Database::open(path.join("ancient"))?,
 	};
 
 	Ok(Arc::new(db))
@@ -15,15 +15,32 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::fmt;
+use std::str::FromStr;
 use cli::Args;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Deprecated {
 	DoesNothing(&'static str),
 	Replaced(&'static str, &'static str),
 	Removed(&'static str),
 }
 
+impl Deprecated {
+	fn option(&self) -> &'static str {
+		match *self {
+			Deprecated::DoesNothing(s) | Deprecated::Replaced(s, _) | Deprecated::Removed(s) => s,
+		}
+	}
+
+	fn kind(&self) -> &'static str {
+		match *self {
+			Deprecated::DoesNothing(_) => "does_nothing",
+			Deprecated::Replaced(_, _) => "replaced",
+			Deprecated::Removed(_) => "removed",
+		}
+	}
+}
+
 impl fmt::Display for Deprecated {
 	fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
 		match *self {
@@ -34,87 +51,164 @@ impl fmt::Display for Deprecated {
 	}
 }
 
-pub fn find_deprecated(args: &Args) -> Vec<Deprecated> {
-	let mut result = vec![];
-
-	if args.flag_warp {
-		result.push(Deprecated::DoesNothing("--warp"));
-	}
-
-	if args.flag_jsonrpc {
-		result.push(Deprecated::DoesNothing("--jsonrpc"));
-	}
+/// What happened to the value of a deprecated option.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Mapping {
+	/// There is nothing to carry over, or no unambiguous replacement exists.
+	None,
+	/// The value was carried over to the replacement option.
+	Applied,
+	/// The replacement option is set to a different value, which the deprecated option overrides.
+	Conflict,
+}
 
-	if args.flag_rpc {
-		result.push(Deprecated::DoesNothing("--rpc"));
-	}
+#[derive(Debug, PartialEq)]
+pub struct Warning {
+	pub deprecated: Deprecated,
+	pub mapping: Mapping,
+}
 
-	if args.flag_jsonrpc_off {
-		result.push(Deprecated::Replaced("--jsonrpc-off", "--no-jsonrpc"));
+impl fmt::Display for Warning {
+	fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+		write!(f, "{}", self.deprecated)?;
+		match (self.mapping, self.deprecated) {
+			(Mapping::Applied, Deprecated::Replaced(_, new)) => write!(f, " Its value has been applied to '{}'.", new),
+			(Mapping::Conflict, Deprecated::Replaced(old, new)) => write!(f, " '{}' is also set, but '{}' takes precedence; its value has been applied.", new, old),
+			_ => Ok(()),
+		}
 	}
+}
 
-	if args.flag_webapp {
-		result.push(Deprecated::DoesNothing("--webapp"));
-	}
+/// How deprecation warnings are printed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WarningsFormat {
+	Text,
+	Json,
+}
 
-	if args.flag_dapps_off {
-		result.push(Deprecated::Replaced("--dapps-off", "--no-dapps"));
-	}
+impl FromStr for WarningsFormat {
+	type Err = String;
 
-	if args.flag_ipcdisable {
-		result.push(Deprecated::Replaced("--ipcdisable", "--no-ipc"));
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(WarningsFormat::Text),
+			"json" => Ok(WarningsFormat::Json),
+			other => Err(format!("Invalid warnings format: {}. Expected 'text' or 'json'.", other)),
+		}
 	}
+}
 
-	if args.flag_ipc_off {
-		result.push(Deprecated::Replaced("--ipc-off", "--no-ipc"));
-	}
+/// Carries the value of a deprecated option over to its replacement.
+/// Returns `false` if the replacement held a different value, which has been overridden.
+type MapFn = fn(&mut Args) -> bool;
 
-	if args.arg_etherbase.is_some() {
-		result.push(Deprecated::Replaced("--etherbase", "--author"));
-	}
+struct Rule {
+	deprecated: Deprecated,
+	is_set: fn(&Args) -> bool,
+	map: Option<MapFn>,
+}
 
-	if args.arg_extradata.is_some() {
-		result.push(Deprecated::Replaced("--extradata", "--extra-data"));
-	}
+fn rule(deprecated: Deprecated, is_set: fn(&Args) -> bool, map: Option<MapFn>) -> Rule {
+	Rule { deprecated, is_set, map }
+}
 
-	// Removed in 1.7
-	if args.arg_dapps_port.is_some() {
-		result.push(Deprecated::Replaced("--dapps-port", "--jsonrpc-port"));
-	}
+/// The deprecated option takes precedence, as it did before it was deprecated.
+fn map_option(old: &Option<String>, new: &mut Option<String>) -> bool {
+	let same = new.is_none() || *new == *old;
+	*new = old.clone();
+	same
+}
 
-	if args.arg_dapps_interface.is_some() {
-		result.push(Deprecated::Replaced("--dapps-interface", "--jsonrpc-interface"));
-	}
+fn rules() -> Vec<Rule> {
+	vec![
+		rule(Deprecated::DoesNothing("--warp"), |a| a.flag_warp, None),
+		rule(Deprecated::DoesNothing("--jsonrpc"), |a| a.flag_jsonrpc, None),
+		rule(Deprecated::DoesNothing("--rpc"), |a| a.flag_rpc, None),
+		rule(Deprecated::Replaced("--jsonrpc-off", "--no-jsonrpc"), |a| a.flag_jsonrpc_off, Some(|a| { a.flag_no_jsonrpc = true; true })),
+		rule(Deprecated::DoesNothing("--webapp"), |a| a.flag_webapp, None),
+		rule(Deprecated::Replaced("--dapps-off", "--no-dapps"), |a| a.flag_dapps_off, Some(|a| { a.flag_no_dapps = true; true })),
+		rule(Deprecated::Replaced("--ipcdisable", "--no-ipc"), |a| a.flag_ipcdisable, Some(|a| { a.flag_no_ipc = true; true })),
+		rule(Deprecated::Replaced("--ipc-off", "--no-ipc"), |a| a.flag_ipc_off, Some(|a| { a.flag_no_ipc = true; true })),
+		rule(Deprecated::Replaced("--etherbase", "--author"), |a| a.arg_etherbase.is_some(), Some(|a| map_option(&a.arg_etherbase, &mut a.arg_author))),
+		rule(Deprecated::Replaced("--extradata", "--extra-data"), |a| a.arg_extradata.is_some(), Some(|a| map_option(&a.arg_extradata, &mut a.arg_extra_data))),
+		// Removed in 1.7
+		// The replacements have non-optional defaults, so the old values can't be carried over unambiguously.
+		rule(Deprecated::Replaced("--dapps-port", "--jsonrpc-port"), |a| a.arg_dapps_port.is_some(), None),
+		rule(Deprecated::Replaced("--dapps-interface", "--jsonrpc-interface"), |a| a.arg_dapps_interface.is_some(), None),
+		rule(Deprecated::Replaced("--dapps-hosts", "--jsonrpc-hosts"), |a| a.arg_dapps_hosts.is_some(), None),
+		rule(Deprecated::Replaced("--dapps-cors", "--jsonrpc-cors"), |a| a.arg_dapps_cors.is_some(), None),
+		rule(Deprecated::Removed("--dapps-user"), |a| a.arg_dapps_user.is_some(), None),
+		rule(Deprecated::Removed("--dapps-pass"), |a| a.arg_dapps_pass.is_some(), None),
+		rule(Deprecated::Replaced("--dapps-apis-all", "--jsonrpc-apis"), |a| a.flag_dapps_apis_all, None),
+		// Removed in 1.8
+	]
+}
 
-	if args.arg_dapps_hosts.is_some() {
-		result.push(Deprecated::Replaced("--dapps-hosts", "--jsonrpc-hosts"));
-	}
+pub fn find_deprecated(args: &Args) -> Vec<Deprecated> {
+	rules().into_iter()
+		.filter(|rule| (rule.is_set)(args))
+		.map(|rule| rule.deprecated)
+		.collect()
+}
 
-	if args.arg_dapps_cors.is_some() {
-		result.push(Deprecated::Replaced("--dapps-cors", "--jsonrpc-cors"));
-	}
+/// Maps deprecated options to their replacements where that is unambiguous.
+pub fn apply_replacements(args: &mut Args) -> Vec<Warning> {
+	rules().into_iter()
+		.filter(|rule| (rule.is_set)(args))
+		.map(|rule| {
+			let mapping = match rule.map {
+				Some(map) if map(args) => Mapping::Applied,
+				Some(_) => Mapping::Conflict,
+				None => Mapping::None,
+			};
+			Warning { deprecated: rule.deprecated, mapping }
+		})
+		.collect()
+}
 
-	if args.arg_dapps_user.is_some() {
-		result.push(Deprecated::Removed("--dapps-user"));
+/// Formats warnings for output. JSON output is a single array, one object per option.
+pub fn format_warnings(warnings: &[Warning], format: WarningsFormat) -> String {
+	match format {
+		WarningsFormat::Text => warnings.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"),
+		WarningsFormat::Json => {
+			let warnings: Vec<_> = warnings.iter().map(|w| json!({
+				"option": w.deprecated.option(),
+				"kind": w.deprecated.kind(),
+				"replacement": match w.deprecated {
+					Deprecated::Replaced(_, new) => Some(new),
+					_ => None,
+				},
+				"mapped": w.mapping == Mapping::Applied,
+				"message": w.to_string(),
+			})).collect();
+			json!(warnings).to_string()
+		},
 	}
+}
 
-	if args.arg_dapps_pass.is_some() {
-		result.push(Deprecated::Removed("--dapps-pass"));
+/// Applies replacements for deprecated options and prints warnings about them.
+/// Fails if any deprecated option is used together with `--strict-flags`.
+pub fn handle_deprecated(args: &mut Args) -> Result<(), String> {
+	let format = args.arg_warnings.parse()?;
+	if args.flag_strict_flags {
+		let deprecated = find_deprecated(args);
+		if !deprecated.is_empty() {
+			let options = deprecated.iter().map(Deprecated::option).collect::<Vec<_>>().join(", ");
+			return Err(format!("Deprecated options are not allowed with --strict-flags: {}", options));
+		}
 	}
 
-	if args.flag_dapps_apis_all {
-		result.push(Deprecated::Replaced("--dapps-apis-all", "--jsonrpc-apis"));
+	let warnings = apply_replacements(args);
+	if !warnings.is_empty() {
+		println!("{}", format_warnings(&warnings, format));
 	}
-
-	// Removed in 1.8
-
-	result
+	Ok(())
 }
 
 #[cfg(test)]
 mod tests {
 	use cli::Args;
-	use super::{Deprecated, find_deprecated};
+	use super::{Deprecated, Mapping, Warning, WarningsFormat, find_deprecated, apply_replacements, format_warnings};
 
 	#[test]
 	fn test_find_deprecated() {
@@ -159,4 +253,38 @@ mod tests {
 			Deprecated::Replaced("--dapps-apis-all", "--jsonrpc-apis"),
 		]);
 	}
+
+	#[test]
+	fn test_apply_replacements() {
+		let mut args = Args::default();
+		args.flag_ipc_off = true;
+		args.arg_etherbase = Some("0x01".into());
+		args.arg_author = Some("0x02".into());
+		args.arg_extradata = Some("old".into());
+		args.arg_extra_data = Some("new".into());
+		args.arg_dapps_port = Some(8080);
+
+		assert_eq!(apply_replacements(&mut args), vec![
+			Warning { deprecated: Deprecated::Replaced("--ipc-off", "--no-ipc"), mapping: Mapping::Applied },
+			Warning { deprecated: Deprecated::Replaced("--etherbase", "--author"), mapping: Mapping::Conflict },
+			Warning { deprecated: Deprecated::Replaced("--extradata", "--extra-data"), mapping: Mapping::Conflict },
+			Warning { deprecated: Deprecated::Replaced("--dapps-port", "--jsonrpc-port"), mapping: Mapping::None },
+		]);
+		assert!(args.flag_no_ipc);
+		assert_eq!(args.arg_author, Some("0x01".into()));
+		assert_eq!(args.arg_extra_data, Some("old".into()));
+	}
+
+	#[test]
+	fn test_format_warnings_json() {
+		let warnings = vec![
+			Warning { deprecated: Deprecated::Replaced("--ipc-off", "--no-ipc"), mapping: Mapping::Applied },
+			Warning { deprecated: Deprecated::DoesNothing("--warp"), mapping: Mapping::None },
+		];
+
+		assert_eq!(
+			format_warnings(&warnings, WarningsFormat::Json),
+			r#"[{"kind":"replaced","mapped":true,"message":"Option '--ipc-off' is deprecated. Please use '--no-ipc' instead. Its value has been applied to '--no-ipc'.","option":"--ipc-off","replacement":"--no-ipc"},{"kind":"does_nothing","mapped":false,"message":"Option '--warp' does nothing. It's on by default.","option":"--warp","replacement":null}]"#
+		);
+	}
 }
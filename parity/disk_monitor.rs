@@ -0,0 +1,110 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pauses block import before the database disk runs out of space.
+
+use std::path::PathBuf;
+use std::sync::Weak;
+use std::time::Duration;
+
+use ethcore::client::{Client, ClientIoMessage};
+use io::{TimerToken, IoContext, IoHandler};
+use parking_lot::Mutex;
+
+const DISK_TIMER: TimerToken = 0;
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Periodically checks free space on the database disk and pauses block import
+/// while it is below the threshold, so that RocksDB never fails mid-write.
+pub struct DiskSpaceMonitor {
+	path: PathBuf,
+	threshold: u64,
+	client: Weak<Client>,
+	/// Last known free space and whether it was below the threshold.
+	status: Mutex<Option<(u64, bool)>>,
+}
+
+impl DiskSpaceMonitor {
+	/// Create new `DiskSpaceMonitor` watching the disk of `path`.
+	/// `threshold` is the minimal free space in bytes.
+	pub fn new(path: PathBuf, threshold: u64, client: Weak<Client>) -> Self {
+		DiskSpaceMonitor {
+			path,
+			threshold,
+			client,
+			status: Mutex::new(None),
+		}
+	}
+
+	/// Last known free space in bytes and whether block import is paused because of it.
+	pub fn status(&self) -> Option<(u64, bool)> {
+		*self.status.lock()
+	}
+
+	/// Check free space and pause or resume block import accordingly.
+	pub fn check(&self) {
+		let free = match ::dir::available_space(&self.path) {
+			Ok(free) => free,
+			Err(e) => {
+				debug!(target: "disk", "Unable to check free space of {}: {}", self.path.display(), e);
+				return;
+			},
+		};
+		let client = match self.client.upgrade() {
+			Some(client) => client,
+			None => return,
+		};
+
+		let mut status = self.status.lock();
+		let was_low = status.map_or(false, |(_, low)| low);
+		// Require some headroom before resuming, so import doesn't flap around the threshold.
+		let low = match was_low {
+			true => free < self.threshold.saturating_add(self.threshold / 10),
+			false => free < self.threshold,
+		};
+
+		if low && !was_low {
+			client.pause_import();
+			error!(
+				"Only {} MB of disk space left for the database at {} (minimum is {} MB). Block import has been paused to avoid corrupting the database. Free up disk space or move the database to a larger disk with --db-path; import resumes automatically.",
+				free / MB,
+				self.path.display(),
+				self.threshold / MB,
+			);
+		} else if !low && was_low {
+			client.resume_import();
+			info!("Disk space recovered: {} MB available. Resuming block import.", free / MB);
+		}
+
+		*status = Some((free, low));
+	}
+}
+
+const MB: u64 = 1024 * 1024;
+
+impl IoHandler<ClientIoMessage> for DiskSpaceMonitor {
+	fn initialize(&self, io: &IoContext<ClientIoMessage>) {
+		self.check();
+		io.register_timer(DISK_TIMER, DISK_CHECK_INTERVAL).expect("Error registering timer");
+	}
+
+	fn timeout(&self, _io: &IoContext<ClientIoMessage>, timer: TimerToken) {
+		if timer == DISK_TIMER {
+			self.check();
+		}
+	}
+}
+
@@ -0,0 +1,131 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pauses sealing while the local clock drifts too far from NTP time.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ethcore::client::ClientIoMessage;
+use ethcore::miner::Miner;
+use futures::Future;
+use io::{TimerToken, IoContext, IoHandler};
+use node_health::NodeHealth;
+use parity_reactor::Remote;
+
+const DRIFT_TIMER: TimerToken = 0;
+const DRIFT_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically checks the time drift and pauses sealing while it exceeds
+/// the configured maximum, since blocks sealed with a skewed clock are likely invalid.
+pub struct DriftGuard {
+	health: NodeHealth,
+	miner: Arc<Miner>,
+	remote: Remote,
+}
+
+impl DriftGuard {
+	/// Create new `DriftGuard`.
+	pub fn new(health: NodeHealth, miner: Arc<Miner>, remote: Remote) -> Self {
+		DriftGuard {
+			health,
+			miner,
+			remote,
+		}
+	}
+
+	/// Check the time drift and pause or resume sealing accordingly.
+	pub fn check(&self) {
+		let miner = self.miner.clone();
+		let time = self.health.time_checker().clone();
+		self.remote.spawn(self.health.time_drift().then(move |res| {
+			match res {
+				Ok(drift) if !time.is_tolerable(drift) => {
+					if !miner.is_sealing_paused() {
+						error!(
+							"Local clock is off by {}ms (maximum is {}ms). Sealing has been paused to avoid producing invalid blocks. Synchronize your clock (e.g. with ntpd or chrony); sealing resumes automatically.",
+							drift,
+							time.max_drift(),
+						);
+					}
+					miner.pause_sealing();
+				},
+				Ok(_) => miner.resume_sealing(),
+				// Keep the current state if the drift can't be measured.
+				Err(err) => debug!("Unable to measure time drift: {}", err),
+			}
+			Ok(())
+		}));
+	}
+}
+
+impl IoHandler<ClientIoMessage> for DriftGuard {
+	fn initialize(&self, io: &IoContext<ClientIoMessage>) {
+		self.check();
+		io.register_timer(DRIFT_TIMER, DRIFT_CHECK_INTERVAL).expect("Error registering timer");
+	}
+
+	fn timeout(&self, _io: &IoContext<ClientIoMessage>, timer: TimerToken) {
+		if timer == DRIFT_TIMER {
+			self.check();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use ethcore::client::ClientIoMessage;
+	use ethcore::miner::Miner;
+	use ethcore::spec::Spec;
+	use io::{IoChannel, IoContext, IoHandler};
+	use node_health::{CpuPool, NodeHealth, ReadinessThresholds, SyncStatus, TimeChecker};
+	use parity_reactor::Remote;
+	use super::DriftGuard;
+
+	#[derive(Debug)]
+	struct FakeSync;
+
+	impl SyncStatus for FakeSync {
+		fn is_major_importing(&self) -> bool { false }
+		fn peers(&self) -> (usize, usize) { (1, 25) }
+		fn blocks_behind(&self) -> Option<u64> { Some(0) }
+		fn disk_space(&self) -> Option<(u64, bool)> { None }
+	}
+
+	fn start_guard(max_drift: i64) -> Arc<Miner> {
+		let miner = Arc::new(Miner::new_for_tests(&Spec::new_test(), None));
+		// Without NTP servers the checker reports the initial drift of 0ms.
+		let time = TimeChecker::new::<String>(&[], CpuPool::new(1)).with_max_drift(max_drift);
+		let health = NodeHealth::new(Arc::new(FakeSync), time, Remote::new_sync(), ReadinessThresholds::default());
+		let guard = DriftGuard::new(health, miner.clone(), Remote::new_sync());
+		guard.initialize(&IoContext::<ClientIoMessage>::new(IoChannel::disconnected(), 0));
+		miner
+	}
+
+	#[test]
+	fn should_pause_sealing_right_after_startup() {
+		// no drift is tolerated at all
+		let miner = start_guard(0);
+		assert!(miner.is_sealing_paused());
+	}
+
+	#[test]
+	fn should_not_pause_sealing_with_tolerable_drift() {
+		let miner = start_guard(10_000);
+		assert!(!miner.is_sealing_paused());
+	}
+}
@@ -82,6 +82,7 @@ pub fn execute(cmd: ExportHsyncCmd) -> Result<String, String> {
 		verify_full: true,
 		check_seal: true,
 		no_hardcoded_sync: true,
+		..Default::default()
 	};
 
 	config.queue.max_mem_use = cmd.cache_config.queue() as usize * 1024 * 1024;
@@ -90,7 +91,8 @@ pub fn execute(cmd: ExportHsyncCmd) -> Result<String, String> {
 	let db = db::open_db(&db_dirs.client_path(algorithm).to_str().expect("DB path could not be converted to string."),
 						 &cmd.cache_config,
 						 &cmd.compaction,
-						 cmd.wal).map_err(|e| format!("Failed to open database {:?}", e))?;
+						 cmd.wal,
+						 false).map_err(|e| format!("Failed to open database {:?}", e))?;
 
 	let service = light_client::Service::start(config, &spec, UnavailableDataFetcher, db, cache)
 		.map_err(|e| format!("Error starting light client: {}", e))?;
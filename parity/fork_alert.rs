@@ -0,0 +1,102 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sends HTTP notifications about detected forks.
+
+use ethcore::header::BlockNumber;
+use ethereum_types::H256;
+use hash_fetch::fetch::Client as FetchClient;
+use network::PeerId;
+use parity_reactor::Remote;
+use sync::{ForkAlert, ForkAlertHandler};
+use webhook::Webhook;
+
+/// POSTs detected forks to a webhook.
+pub struct ForkAlertPoster {
+	webhook: Webhook,
+}
+
+impl ForkAlertPoster {
+	/// Create new `ForkAlertPoster`.
+	pub fn new(url: &str, fetch: FetchClient, remote: Remote) -> Result<Self, String> {
+		Ok(ForkAlertPoster {
+			webhook: Webhook::new("fork alert", url, fetch, remote)?,
+		})
+	}
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AlertJson<'a> {
+	peer_id: PeerId,
+	ancestor_number: BlockNumber,
+	ancestor_hash: &'a H256,
+	our_number: BlockNumber,
+	our_hash: &'a H256,
+	their_number: BlockNumber,
+	their_hash: &'a H256,
+	depth: BlockNumber,
+	detected_at: u64,
+}
+
+impl<'a> From<&'a ForkAlert> for AlertJson<'a> {
+	fn from(alert: &'a ForkAlert) -> Self {
+		AlertJson {
+			peer_id: alert.peer_id,
+			ancestor_number: alert.ancestor_number,
+			ancestor_hash: &alert.ancestor_hash,
+			our_number: alert.our_number,
+			our_hash: &alert.our_hash,
+			their_number: alert.their_number,
+			their_hash: &alert.their_hash,
+			depth: alert.depth,
+			detected_at: alert.detected_at,
+		}
+	}
+}
+
+impl ForkAlertHandler for ForkAlertPoster {
+	fn on_fork_alert(&self, alert: &ForkAlert) {
+		self.webhook.post(&AlertJson::from(alert));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use sync::ForkAlert;
+	use super::AlertJson;
+
+	#[test]
+	fn should_serialize_alert() {
+		let alert = ForkAlert {
+			peer_id: 5,
+			ancestor_number: 7,
+			ancestor_hash: 7.into(),
+			our_number: 10,
+			our_hash: 10.into(),
+			their_number: 12,
+			their_hash: 12.into(),
+			depth: 5,
+			detected_at: 1_500_000_000,
+		};
+
+		assert_eq!(
+			serde_json::to_string(&AlertJson::from(&alert)).unwrap(),
+			r#"{"peerId":5,"ancestorNumber":7,"ancestorHash":"0x0000000000000000000000000000000000000000000000000000000000000007","ourNumber":10,"ourHash":"0x000000000000000000000000000000000000000000000000000000000000000a","theirNumber":12,"theirHash":"0x000000000000000000000000000000000000000000000000000000000000000c","depth":5,"detectedAt":1500000000}"#
+		);
+	}
+}
@@ -14,8 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::io;
+use std::{io, fs};
 use std::io::{Write, BufReader, BufRead};
+use std::path::Path;
 use std::time::Duration;
 use std::fs::File;
 use ethereum_types::{U256, clean_0x, Address};
@@ -78,6 +79,18 @@ pub fn to_block_id(s: &str) -> Result<BlockId, String> {
 	}
 }
 
+pub fn to_block_range(s: &str) -> Result<(BlockId, BlockId), String> {
+	let mut parts = s.splitn(2, "..");
+	let from = parts.next().unwrap_or("");
+	let to = parts.next().ok_or_else(|| "Invalid block range. Expected FROM..TO.".to_owned())?;
+	let to = match to {
+		"" => BlockId::Latest,
+		to => to_block_id(to)?,
+	};
+
+	Ok((to_block_id(from)?, to))
+}
+
 pub fn to_u256(s: &str) -> Result<U256, String> {
 	if let Ok(decimal) = U256::from_dec_str(s) {
 		Ok(decimal)
@@ -195,6 +208,7 @@ pub fn default_network_config() -> ::sync::NetworkConfiguration {
 		max_peers: 50,
 		min_peers: 25,
 		snapshot_peers: 0,
+		min_protocol_peers: Default::default(),
 		max_pending_peers: 64,
 		ip_filter: IpFilter::default(),
 		reserved_nodes: Vec::new(),
@@ -261,7 +275,7 @@ pub fn execute_upgrades(
 	compaction_profile: &DatabaseCompactionProfile
 ) -> Result<(), String> {
 
-	upgrade_data_paths(base_path, dirs, pruning);
+	upgrade_data_paths(base_path, dirs, pruning)?;
 
 	match upgrade(Some(&dirs.path)) {
 		Ok(upgrades_applied) if upgrades_applied > 0 => {
@@ -322,6 +336,22 @@ pub fn passwords_from_files(files: &[String]) -> Result<Vec<Password>, String> {
 	Ok(passwords?.into_iter().flat_map(|x| x).collect())
 }
 
+/// Recursively copies the contents of directory `from` into `to`, creating it if needed.
+/// Existing files are left untouched.
+pub fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+	fs::create_dir_all(to)?;
+	for entry in fs::read_dir(from)? {
+		let entry = entry?;
+		let target = to.join(entry.file_name());
+		if entry.file_type()?.is_dir() {
+			copy_dir(&entry.path(), &target)?;
+		} else if !target.exists() {
+			fs::copy(entry.path(), &target)?;
+		}
+	}
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use std::time::Duration;
@@ -332,7 +362,7 @@ mod tests {
 	use ethcore::client::{Mode, BlockId};
 	use ethcore::miner::PendingSet;
 	use ethkey::Password;
-	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, password_from_file};
+	use super::{to_duration, to_mode, to_block_id, to_block_range, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, password_from_file};
 
 	#[test]
 	fn test_to_duration() {
@@ -376,6 +406,15 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_to_block_range() {
+		assert_eq!(to_block_range("1..10").unwrap(), (BlockId::Number(1), BlockId::Number(10)));
+		assert_eq!(to_block_range("5..").unwrap(), (BlockId::Number(5), BlockId::Latest));
+		assert_eq!(to_block_range("5..latest").unwrap(), (BlockId::Number(5), BlockId::Latest));
+		assert!(to_block_range("5").is_err());
+		assert!(to_block_range("..5").is_err());
+	}
+
 	#[test]
 	fn test_to_u256() {
 		assert_eq!(to_u256("0").unwrap(), U256::from(0));
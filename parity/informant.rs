@@ -18,6 +18,10 @@ extern crate ansi_term;
 use self::ansi_term::Colour::{White, Yellow, Green, Cyan, Blue};
 use self::ansi_term::{Colour, Style};
 
+use std::cmp;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::{Arc};
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
 use std::time::{Instant, Duration};
@@ -40,6 +44,7 @@ use parity_rpc::informant::RpcStats;
 use ethereum_types::H256;
 use bytes::Bytes;
 use parking_lot::{RwLock, Mutex};
+use systemd;
 
 /// Format byte counts to standard denominations.
 pub fn format_bytes(b: usize) -> String {
@@ -49,6 +54,135 @@ pub fn format_bytes(b: usize) -> String {
 	}
 }
 
+/// Total size of all files below `path`, in bytes. Unreadable entries are skipped.
+fn dir_size(path: &Path) -> u64 {
+	let entries = match fs::read_dir(path) {
+		Ok(entries) => entries,
+		Err(_) => return 0,
+	};
+
+	entries.filter_map(Result::ok).map(|entry| match entry.metadata() {
+		Ok(ref meta) if meta.is_dir() => dir_size(&entry.path()),
+		Ok(meta) => meta.len(),
+		Err(_) => 0,
+	}).sum()
+}
+
+/// How often the size of the database on disk is recalculated.
+const DB_SIZE_REFRESH: Duration = Duration::from_secs(60);
+
+/// Size of a database directory on disk. Walking the directory is expensive,
+/// so the size is recalculated at most every `DB_SIZE_REFRESH`.
+pub struct DbSize {
+	path: PathBuf,
+	last: Mutex<Option<(Instant, u64)>>,
+}
+
+impl DbSize {
+	pub fn new(path: PathBuf) -> Self {
+		DbSize {
+			path,
+			last: Mutex::new(None),
+		}
+	}
+
+	fn get(&self) -> u64 {
+		let mut last = self.last.lock();
+		match *last {
+			Some((at, size)) if at.elapsed() < DB_SIZE_REFRESH => size,
+			_ => {
+				let size = dir_size(&self.path);
+				*last = Some((Instant::now(), size));
+				size
+			},
+		}
+	}
+}
+
+/// How the informant reports node status.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InformantMode {
+	/// No periodic status output.
+	Off,
+	/// A short uncoloured line.
+	Compact,
+	/// The regular coloured status line.
+	Full,
+	/// One JSON object per line.
+	Json,
+}
+
+impl FromStr for InformantMode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"off" => Ok(InformantMode::Off),
+			"compact" => Ok(InformantMode::Compact),
+			"full" => Ok(InformantMode::Full),
+			"json" => Ok(InformantMode::Json),
+			other => Err(format!("Invalid informant mode: {}", other)),
+		}
+	}
+}
+
+/// A piece of information the informant can report.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+	/// Connected and maximal peers.
+	Peers,
+	/// Unverified and verified queue sizes.
+	Queue,
+	/// Memory used by caches.
+	Mem,
+	/// Size of the client database on disk.
+	DbSize,
+	/// Import speed: blocks, transactions and gas per second.
+	Gas,
+}
+
+impl FromStr for Column {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"peers" => Ok(Column::Peers),
+			"queue" => Ok(Column::Queue),
+			"mem" => Ok(Column::Mem),
+			"db" => Ok(Column::DbSize),
+			"gas" => Ok(Column::Gas),
+			other => Err(format!("Invalid informant column: {}", other)),
+		}
+	}
+}
+
+/// Parses a comma-separated list of informant columns.
+pub fn parse_columns(s: &str) -> Result<Vec<Column>, String> {
+	s.split(',').map(str::trim).filter(|c| !c.is_empty()).map(str::parse).collect()
+}
+
+/// Informant output configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InformantConfig {
+	pub mode: InformantMode,
+	pub columns: Vec<Column>,
+	/// How often the status line is refreshed.
+	pub interval: Duration,
+	/// Report readiness and send watchdog pings to systemd.
+	pub systemd_notify: bool,
+}
+
+impl Default for InformantConfig {
+	fn default() -> Self {
+		InformantConfig {
+			mode: InformantMode::Full,
+			columns: vec![Column::Peers, Column::Queue, Column::Mem, Column::Gas],
+			interval: Duration::from_secs(5),
+			systemd_notify: false,
+		}
+	}
+}
+
 /// Something that can be converted to milliseconds.
 pub trait MillisecondDuration {
 	/// Get the value in milliseconds.
@@ -102,6 +236,7 @@ pub struct Report {
 	queue_info: BlockQueueInfo,
 	cache_sizes: CacheSizes,
 	sync_info: Option<SyncInfo>,
+	db_size: Option<u64>,
 }
 
 /// Something which can provide data to the informant.
@@ -121,6 +256,8 @@ pub struct FullNodeInformantData {
 	pub client: Arc<Client>,
 	pub sync: Option<Arc<SyncProvider>>,
 	pub net: Option<Arc<ManageNetwork>>,
+	/// Database directory, if its size should be reported.
+	pub db_size: Option<DbSize>,
 }
 
 impl InformantData for FullNodeInformantData {
@@ -169,6 +306,7 @@ impl InformantData for FullNodeInformantData {
 			queue_info,
 			cache_sizes,
 			sync_info,
+			db_size: self.db_size.as_ref().map(DbSize::get),
 		}
 	}
 }
@@ -211,6 +349,7 @@ impl InformantData for LightNodeInformantData {
 			queue_info,
 			cache_sizes,
 			sync_info,
+			db_size: None,
 		}
 	}
 }
@@ -218,6 +357,7 @@ impl InformantData for LightNodeInformantData {
 pub struct Informant<T> {
 	last_tick: RwLock<Instant>,
 	with_color: bool,
+	config: InformantConfig,
 	target: T,
 	snapshot: Option<Arc<SnapshotService>>,
 	rpc_stats: Option<Arc<RpcStats>>,
@@ -226,6 +366,7 @@ pub struct Informant<T> {
 	skipped_txs: AtomicUsize,
 	in_shutdown: AtomicBool,
 	last_report: Mutex<ClientReport>,
+	watchdog: Option<Duration>,
 }
 
 impl<T: InformantData> Informant<T> {
@@ -235,10 +376,17 @@ impl<T: InformantData> Informant<T> {
 		snapshot: Option<Arc<SnapshotService>>,
 		rpc_stats: Option<Arc<RpcStats>>,
 		with_color: bool,
+		config: InformantConfig,
 	) -> Self {
+		let watchdog = match config.systemd_notify {
+			true => systemd::watchdog_interval(),
+			false => None,
+		};
+
 		Informant {
 			last_tick: RwLock::new(Instant::now()),
 			with_color: with_color,
+			config: config,
 			target: target,
 			snapshot: snapshot,
 			rpc_stats: rpc_stats,
@@ -247,17 +395,38 @@ impl<T: InformantData> Informant<T> {
 			skipped_txs: AtomicUsize::new(0),
 			in_shutdown: AtomicBool::new(false),
 			last_report: Mutex::new(Default::default()),
+			watchdog: watchdog,
 		}
 	}
 
 	/// Signal that we're shutting down; no more output necessary.
 	pub fn shutdown(&self) {
 		self.in_shutdown.store(true, ::std::sync::atomic::Ordering::SeqCst);
+		if self.config.systemd_notify {
+			systemd::notify("STOPPING=1");
+		}
+	}
+
+	fn shows(&self, column: Column) -> bool {
+		self.config.columns.contains(&column)
+	}
+
+	/// Tells the systemd watchdog that the client is alive.
+	/// Only called once the client has answered, so a stuck client stops the pings.
+	fn notify_watchdog(&self) {
+		if self.watchdog.is_some() {
+			systemd::notify("WATCHDOG=1");
+		}
 	}
 
 	pub fn tick(&self) {
 		let elapsed = self.last_tick.read().elapsed();
-		if elapsed < Duration::from_secs(5) {
+		if self.config.mode == InformantMode::Off || elapsed < self.config.interval {
+			// nothing to print, but the watchdog still needs to hear from the client
+			if self.watchdog.is_some() {
+				self.target.report();
+				self.notify_watchdog();
+			}
 			return;
 		}
 
@@ -268,29 +437,105 @@ impl<T: InformantData> Informant<T> {
 			*last_report = full_report.client_report.clone();
 			(diffed, full_report)
 		};
+		self.notify_watchdog();
 
 		let Report {
 			importing,
-			chain_info,
-			queue_info,
-			cache_sizes,
-			sync_info,
+			ref chain_info,
+			ref queue_info,
+			ref cache_sizes,
+			ref sync_info,
+			db_size,
 			..
 		} = full_report;
 
-		let rpc_stats = self.rpc_stats.as_ref();
 		let snapshot_sync = sync_info.as_ref().map_or(false, |s| s.snapshot_sync) && self.snapshot.as_ref().map_or(false, |s|
 			match s.status() {
 				RestorationStatus::Ongoing { .. } | RestorationStatus::Initializing { .. } => true,
 				_ => false,
 			}
 		);
-		if !importing && !snapshot_sync && elapsed < Duration::from_secs(30) {
+		if !importing && !snapshot_sync && elapsed < self.config.interval * 6 {
 			return;
 		}
 
 		*self.last_tick.write() = Instant::now();
 
+		let millis = elapsed.as_milliseconds();
+		let blocks_per_sec = (client_report.blocks_imported * 1000) as f64 / millis as f64;
+		let txs_per_sec = (client_report.transactions_applied * 1000) as f64 / millis as f64;
+		let mgas_per_sec = (client_report.gas_processed / From::from(millis * 1000)).low_u64();
+
+		let rates = (blocks_per_sec, txs_per_sec, mgas_per_sec);
+		match self.config.mode {
+			// written to stdout as is, so that every line can be parsed
+			InformantMode::Json => println!("{}", self.json_status(&full_report, rates)),
+			InformantMode::Compact => info!(target: "import", "{}", self.compact_status(&full_report, rates)),
+			InformantMode::Full | InformantMode::Off => self.print_full(&full_report, snapshot_sync, rates),
+		}
+	}
+
+	fn json_status(&self, report: &Report, (blocks_per_sec, txs_per_sec, mgas_per_sec): (f64, f64, u64)) -> ::serde_json::Value {
+		let Report { importing, ref chain_info, ref queue_info, ref cache_sizes, ref sync_info, db_size, .. } = *report;
+		let mut status = ::serde_json::Map::new();
+		status.insert("importing".into(), json!(importing));
+		status.insert("bestBlock".into(), json!(chain_info.best_block_number));
+		status.insert("bestHash".into(), json!(format!("{:#x}", chain_info.best_block_hash)));
+		if self.shows(Column::Peers) {
+			if let Some(ref sync_info) = *sync_info {
+				status.insert("peers".into(), json!(sync_info.num_peers));
+				status.insert("maxPeers".into(), json!(sync_info.max_peers));
+			}
+		}
+		if self.shows(Column::Queue) {
+			status.insert("unverifiedQueue".into(), json!(queue_info.unverified_queue_size));
+			status.insert("verifiedQueue".into(), json!(queue_info.verified_queue_size));
+		}
+		if self.shows(Column::Mem) {
+			status.insert("mem".into(), json!(cache_sizes.sizes));
+		}
+		if self.shows(Column::DbSize) {
+			status.insert("dbSize".into(), json!(db_size));
+		}
+		if self.shows(Column::Gas) {
+			status.insert("blocksPerSec".into(), json!(blocks_per_sec));
+			if self.target.executes_transactions() {
+				status.insert("txsPerSec".into(), json!(txs_per_sec));
+				status.insert("mgasPerSec".into(), json!(mgas_per_sec));
+			}
+		}
+		::serde_json::Value::Object(status)
+	}
+
+	fn compact_status(&self, report: &Report, (blocks_per_sec, _, mgas_per_sec): (f64, f64, u64)) -> String {
+		let Report { importing, ref chain_info, ref queue_info, ref cache_sizes, ref sync_info, db_size, .. } = *report;
+		let mut columns = vec![format!("#{}", chain_info.best_block_number)];
+		if self.shows(Column::Peers) {
+			if let Some(ref sync_info) = *sync_info {
+				columns.push(format!("{}/{} peers", sync_info.num_peers, sync_info.max_peers));
+			}
+		}
+		if self.shows(Column::Queue) {
+			columns.push(format!("{}+{} queued", queue_info.unverified_queue_size, queue_info.verified_queue_size));
+		}
+		if self.shows(Column::Mem) {
+			columns.push(format!("{} mem", format_bytes(cache_sizes.sizes.values().sum())));
+		}
+		if let (true, Some(size)) = (self.shows(Column::DbSize), db_size) {
+			columns.push(format!("{} db", format_bytes(size as usize)));
+		}
+		if importing && self.shows(Column::Gas) {
+			columns.push(match self.target.executes_transactions() {
+				true => format!("{:.2} blk/s {} Mgas/s", blocks_per_sec, mgas_per_sec),
+				false => format!("{:.1} hdr/s", blocks_per_sec),
+			});
+		}
+		format!("{} {}", if importing { "Syncing" } else { "Idle" }, columns.join(", "))
+	}
+
+	fn print_full(&self, report: &Report, snapshot_sync: bool, (blocks_per_sec, txs_per_sec, mgas_per_sec): (f64, f64, u64)) {
+		let Report { importing, ref chain_info, ref queue_info, ref cache_sizes, ref sync_info, db_size, .. } = *report;
+		let rpc_stats = self.rpc_stats.as_ref();
 		let paint = |c: Style, t: String| match self.with_color && atty::is(atty::Stream::Stdout) {
 			true => format!("{}", c.paint(t)),
 			false => t,
@@ -299,22 +544,27 @@ impl<T: InformantData> Informant<T> {
 		info!(target: "import", "{}  {}  {}  {}",
 			match importing {
 				true => match snapshot_sync {
-					false => format!("Syncing {} {}  {}  {}+{} Qed",
+					false => format!("Syncing {} {}{}{}",
 						paint(White.bold(), format!("{:>8}", format!("#{}", chain_info.best_block_number))),
 						paint(White.bold(), format!("{}", chain_info.best_block_hash)),
-						if self.target.executes_transactions() {
-							format!("{} blk/s {} tx/s {} Mgas/s",
-								paint(Yellow.bold(), format!("{:7.2}", (client_report.blocks_imported * 1000) as f64 / elapsed.as_milliseconds() as f64)),
-								paint(Yellow.bold(), format!("{:6.1}", (client_report.transactions_applied * 1000) as f64 / elapsed.as_milliseconds() as f64)),
-								paint(Yellow.bold(), format!("{:4}", (client_report.gas_processed / From::from(elapsed.as_milliseconds() * 1000)).low_u64()))
-							)
-						} else {
-							format!("{} hdr/s",
-								paint(Yellow.bold(), format!("{:6.1}", (client_report.blocks_imported * 1000) as f64 / elapsed.as_milliseconds() as f64))
-							)
+						match (self.shows(Column::Gas), self.target.executes_transactions()) {
+							(true, true) => format!("  {} blk/s {} tx/s {} Mgas/s",
+								paint(Yellow.bold(), format!("{:7.2}", blocks_per_sec)),
+								paint(Yellow.bold(), format!("{:6.1}", txs_per_sec)),
+								paint(Yellow.bold(), format!("{:4}", mgas_per_sec))
+							),
+							(true, false) => format!("  {} hdr/s",
+								paint(Yellow.bold(), format!("{:6.1}", blocks_per_sec))
+							),
+							(false, _) => String::new(),
 						},
-						paint(Green.bold(), format!("{:5}", queue_info.unverified_queue_size)),
-						paint(Green.bold(), format!("{:5}", queue_info.verified_queue_size))
+						match self.shows(Column::Queue) {
+							true => format!("  {}+{} Qed",
+								paint(Green.bold(), format!("{:5}", queue_info.unverified_queue_size)),
+								paint(Green.bold(), format!("{:5}", queue_info.verified_queue_size))
+							),
+							false => String::new(),
+						}
 					),
 					true => {
 						self.snapshot.as_ref().map_or(String::new(), |s|
@@ -332,8 +582,8 @@ impl<T: InformantData> Informant<T> {
 				},
 				false => String::new(),
 			},
-			match sync_info.as_ref() {
-				Some(ref sync_info) => format!("{}{}/{} peers",
+			match *sync_info {
+				Some(ref sync_info) if self.shows(Column::Peers) => format!("{}{}/{} peers",
 					match importing {
 						true => format!("{}   ", paint(Green.bold(), format!("{:>8}", format!("#{}", sync_info.last_imported_block_number)))),
 						false => match sync_info.last_imported_old_block_number {
@@ -346,7 +596,16 @@ impl<T: InformantData> Informant<T> {
 				),
 				_ => String::new(),
 			},
-			cache_sizes.display(Blue.bold(), &paint),
+			format!("{}{}",
+				match self.shows(Column::Mem) {
+					true => cache_sizes.display(Blue.bold(), &paint),
+					false => String::new(),
+				},
+				match db_size {
+					Some(size) if self.shows(Column::DbSize) => format!(" {:>8} disk", paint(Blue.bold(), format_bytes(size as usize))),
+					_ => String::new(),
+				}
+			),
 			match rpc_stats {
 				Some(ref rpc_stats) => format!(
 					"RPC: {} conn, {} req/s, {} µs",
@@ -362,6 +621,10 @@ impl<T: InformantData> Informant<T> {
 
 impl ChainNotify for Informant<FullNodeInformantData> {
 	fn new_blocks(&self, imported: Vec<H256>, _invalid: Vec<H256>, _route: ChainRoute, _sealed: Vec<H256>, _proposed: Vec<Bytes>, duration: Duration) {
+		if self.config.mode == InformantMode::Off {
+			return;
+		}
+
 		let mut last_import = self.last_import.lock();
 		let client = &self.target.client;
 
@@ -407,6 +670,10 @@ impl ChainNotify for Informant<FullNodeInformantData> {
 
 impl LightChainNotify for Informant<LightNodeInformantData> {
 	fn new_headers(&self, good: &[H256]) {
+		if self.config.mode == InformantMode::Off {
+			return;
+		}
+
 		let mut last_import = self.last_import.lock();
 		let client = &self.target.client;
 
@@ -436,12 +703,126 @@ const INFO_TIMER: TimerToken = 0;
 
 impl<T: InformantData> IoHandler<ClientIoMessage> for Informant<T> {
 	fn initialize(&self, io: &IoContext<ClientIoMessage>) {
-		io.register_timer(INFO_TIMER, Duration::from_secs(5)).expect("Error registering timer");
+		// the watchdog is pinged from `tick`, twice per watchdog interval so a single late timer doesn't get the node killed
+		let interval = match self.watchdog {
+			Some(watchdog) => cmp::min(self.config.interval, watchdog / 2),
+			None => self.config.interval,
+		};
+		io.register_timer(INFO_TIMER, interval).expect("Error registering timer");
 	}
 
 	fn timeout(&self, _io: &IoContext<ClientIoMessage>, timer: TimerToken) {
-		if timer == INFO_TIMER && !self.in_shutdown.load(AtomicOrdering::SeqCst) {
-			self.tick();
+		match timer {
+			INFO_TIMER if !self.in_shutdown.load(AtomicOrdering::SeqCst) => self.tick(),
+			_ => {},
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::fs::File;
+	use std::io::Write;
+	use tempdir::TempDir;
+	use super::*;
+
+	struct FakeData(bool);
+
+	impl InformantData for FakeData {
+		fn executes_transactions(&self) -> bool { self.0 }
+
+		fn is_major_importing(&self) -> bool { true }
+
+		fn report(&self) -> Report { report() }
+	}
+
+	fn report() -> Report {
+		let mut cache_sizes = CacheSizes::default();
+		cache_sizes.insert("db", 1024);
+		cache_sizes.insert("queue", 1024);
+
+		Report {
+			importing: true,
+			chain_info: BlockChainInfo {
+				total_difficulty: 0.into(),
+				pending_total_difficulty: 0.into(),
+				genesis_hash: H256::zero(),
+				best_block_hash: H256::from(1),
+				best_block_number: 42,
+				best_block_timestamp: 0,
+				ancient_block_hash: None,
+				ancient_block_number: None,
+				first_block_hash: None,
+				first_block_number: None,
+			},
+			client_report: Default::default(),
+			queue_info: BlockQueueInfo {
+				unverified_queue_size: 3,
+				verified_queue_size: 2,
+				verifying_queue_size: 0,
+				max_queue_size: 0,
+				max_mem_use: 0,
+				mem_used: 0,
+			},
+			cache_sizes,
+			sync_info: Some(SyncInfo {
+				last_imported_block_number: 42,
+				last_imported_old_block_number: None,
+				num_peers: 5,
+				max_peers: 25,
+				snapshot_sync: false,
+			}),
+			db_size: Some(2048),
+		}
+	}
+
+	fn informant(executes_transactions: bool, columns: Vec<Column>) -> Informant<FakeData> {
+		Informant::new(FakeData(executes_transactions), None, None, false, InformantConfig {
+			columns,
+			..Default::default()
+		})
+	}
+
+	#[test]
+	fn should_format_selected_columns_compactly() {
+		let all = vec![Column::Peers, Column::Queue, Column::Mem, Column::DbSize, Column::Gas];
+
+		assert_eq!(
+			informant(true, all).compact_status(&report(), (1.5, 10.0, 3)),
+			"Syncing #42, 5/25 peers, 3+2 queued, 2 KiB mem, 2 KiB db, 1.50 blk/s 3 Mgas/s"
+		);
+		assert_eq!(informant(false, vec![Column::Peers]).compact_status(&report(), (1.5, 0.0, 0)), "Syncing #42, 5/25 peers");
+	}
+
+	#[test]
+	fn should_report_selected_columns_as_json() {
+		let status = informant(false, vec![Column::Queue, Column::Gas]).json_status(&report(), (1.5, 0.0, 0));
+
+		assert_eq!(status, json!({
+			"importing": true,
+			"bestBlock": 42,
+			"bestHash": format!("{:#x}", H256::from(1)),
+			"unverifiedQueue": 3,
+			"verifiedQueue": 2,
+			"blocksPerSec": 1.5,
+		}));
+	}
+
+	#[test]
+	fn should_parse_columns() {
+		assert_eq!(parse_columns("peers, db,gas"), Ok(vec![Column::Peers, Column::DbSize, Column::Gas]));
+		assert_eq!(parse_columns(""), Ok(vec![]));
+		assert!(parse_columns("peers,disk").is_err());
+	}
+
+	#[test]
+	fn should_not_walk_the_database_on_every_tick() {
+		let dir = TempDir::new("informant").unwrap();
+		File::create(dir.path().join("a")).unwrap().write_all(b"abc").unwrap();
+		let db_size = DbSize::new(dir.path().to_owned());
+
+		assert_eq!(db_size.get(), 3);
+		File::create(dir.path().join("b")).unwrap().write_all(b"abc").unwrap();
+		assert_eq!(db_size.get(), 3);
+	}
+}
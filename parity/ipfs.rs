@@ -14,8 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::path::PathBuf;
 use std::sync::Arc;
-use parity_ipfs_api::{self, AccessControlAllowOrigin, Host, Listening};
+use parity_ipfs_api::{self, AccessControlAllowOrigin, Host, Listening, Store};
 use parity_ipfs_api::error::ServerError;
 use ethcore::client::BlockChainClient;
 
@@ -26,6 +27,9 @@ pub struct Configuration {
 	pub interface: String,
 	pub cors: Option<Vec<String>>,
 	pub hosts: Option<Vec<String>>,
+	pub path_prefix: String,
+	pub max_response_size: usize,
+	pub store_size: usize,
 }
 
 impl Default for Configuration {
@@ -36,23 +40,30 @@ impl Default for Configuration {
 			interface: "127.0.0.1".into(),
 			cors: Some(vec![]),
 			hosts: Some(vec![]),
+			path_prefix: String::new(),
+			max_response_size: 1024 * 1024,
+			store_size: 64 * 1024 * 1024,
 		}
 	}
 }
 
-pub fn start_server(conf: Configuration, client: Arc<BlockChainClient>) -> Result<Option<Listening>, ServerError> {
+pub fn start_server(conf: Configuration, store_path: PathBuf, client: Arc<BlockChainClient>) -> Result<Option<Listening>, ServerError> {
 	if !conf.enabled {
 		return Ok(None);
 	}
 
 	let cors = conf.cors.map(|cors| cors.into_iter().map(AccessControlAllowOrigin::from).collect());
 	let hosts = conf.hosts.map(|hosts| hosts.into_iter().map(Host::from).collect());
+	let store = Store::open(store_path, conf.store_size)?;
 
 	parity_ipfs_api::start_server(
 		conf.port,
 		conf.interface,
 		cors.into(),
 		hosts.into(),
+		conf.path_prefix,
+		conf.max_response_size,
+		store,
 		client
 	).map(Some)
 }
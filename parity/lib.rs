@@ -28,7 +28,7 @@ extern crate env_logger;
 extern crate futures;
 extern crate futures_cpupool;
 extern crate atty;
-extern crate jsonrpc_core;
+pub extern crate jsonrpc_core;
 extern crate num_cpus;
 extern crate number_prefix;
 extern crate parking_lot;
@@ -38,12 +38,14 @@ extern crate rpassword;
 extern crate rustc_hex;
 extern crate semver;
 extern crate serde;
+#[macro_use]
 extern crate serde_json;
 #[macro_use]
 extern crate serde_derive;
 extern crate toml;
 
 extern crate blooms_db;
+extern crate era_db;
 extern crate ethcore;
 extern crate ethcore_bytes as bytes;
 extern crate ethcore_io as io;
@@ -56,6 +58,7 @@ extern crate ethcore_service;
 extern crate ethcore_sync as sync;
 extern crate ethcore_transaction as transaction;
 extern crate ethereum_types;
+extern crate ethjson;
 extern crate ethkey;
 extern crate kvdb;
 extern crate node_health;
@@ -74,6 +77,7 @@ extern crate node_filter;
 extern crate keccak_hash as hash;
 extern crate journaldb;
 extern crate registrar;
+extern crate tempdir;
 
 #[macro_use]
 extern crate log as rlog;
@@ -90,51 +94,66 @@ extern crate pretty_assertions;
 
 #[cfg(windows)] extern crate winapi;
 
-#[cfg(test)]
-extern crate tempdir;
-
 #[cfg(feature = "memory_profiling")]
 extern crate alloc_system;
 
 mod account;
+mod block_hooks;
 mod blockchain;
 mod cache;
 mod cli;
 mod configuration;
 mod dapps;
 mod export_hardcoded_sync;
+mod fork_alert;
 mod ipfs;
 mod deprecated;
+mod disk_monitor;
+mod drift_guard;
 mod helpers;
 mod informant;
 mod light_helpers;
 mod modules;
 mod params;
 mod presale;
+mod reorg_alert;
 mod rpc;
 mod rpc_apis;
 mod run;
 mod secretstore;
+mod service;
 mod signer;
+mod setup;
+mod signer_notify;
 mod snapshot;
+mod systemd;
+mod test_node;
+mod test_runner;
 mod upgrade;
 mod url;
 mod user_defaults;
+mod validate_spec;
+mod webhook;
 mod whisper;
 mod db;
 
+pub mod rpc_client;
+
 use std::io::BufReader;
 use std::fs::File;
+use std::sync::Arc;
 use hash::keccak_buffer;
 use cli::Args;
 use configuration::{Cmd, Execute};
-use deprecated::find_deprecated;
-use ethcore_logger::setup_log;
+use deprecated::handle_deprecated;
 #[cfg(feature = "memory_profiling")]
 use alloc_system::System;
 
 pub use self::configuration::Configuration;
-pub use self::run::RunningClient;
+pub use self::run::{RunningClient, RpcHandle};
+pub use ethcore_logger::{Config as LoggerConfig, setup_log, RotatingLogger};
+pub use parity_rpc::PubSubSession;
+pub use self::test_node::{test_node, TestNodeBuilder, TestNode};
 
 #[cfg(feature = "memory_profiling")]
 #[global_allocator]
@@ -150,6 +169,25 @@ fn print_hash_of(maybe_file: Option<String>) -> Result<String, String> {
 	}
 }
 
+fn print_version_json() -> String {
+	let (track, version, commit) = parity_version::raw_package_info();
+	let chains: Vec<String> = params::SpecType::builtin().iter().map(ToString::to_string).collect();
+	let info = json!({
+		"version": version,
+		"track": track,
+		"commit": commit,
+		"platform": parity_version::platform(),
+		"features": {
+			"dapps": cfg!(feature = "dapps"),
+			"secretstore": cfg!(feature = "secretstore"),
+		},
+		"chains": chains,
+		"dbVersion": db::CURRENT_VERSION,
+		"rpcApis": rpc_apis::API_NAMES,
+	});
+	serde_json::to_string_pretty(&info).expect("serialization of a json! value never fails; qed")
+}
+
 #[cfg(feature = "deadlock_detection")]
 fn run_deadlock_detection_thread() {
 	use std::thread;
@@ -192,14 +230,11 @@ pub enum ExecutionAction {
 	Running(RunningClient),
 }
 
-fn execute<Cr, Rr>(command: Execute, on_client_rq: Cr, on_updater_rq: Rr) -> Result<ExecutionAction, String>
+fn execute<Cr, Rr>(command: Execute, logger: Arc<RotatingLogger>,
+						on_client_rq: Cr, on_updater_rq: Rr) -> Result<ExecutionAction, String>
 	where Cr: Fn(String) + 'static + Send,
 		  Rr: Fn() + 'static + Send
 {
-	// TODO: move this to `main()` and expose in the C API so that users can setup logging the way
-	// 		they want
-	let logger = setup_log(&command.logger).expect("Logger is initialized only once; qed");
-
 	#[cfg(feature = "deadlock_detection")]
 	run_deadlock_detection_thread();
 
@@ -212,17 +247,34 @@ fn execute<Cr, Rr>(command: Execute, on_client_rq: Cr, on_updater_rq: Rr) -> Res
 			let outcome = run::execute(run_cmd, logger, on_client_rq, on_updater_rq)?;
 			Ok(ExecutionAction::Running(outcome))
 		},
+		Cmd::RunMulti(run_cmds) => {
+			let outcome = run::execute_multi(run_cmds, logger, on_client_rq, on_updater_rq)?;
+			Ok(ExecutionAction::Running(outcome))
+		},
 		Cmd::Version => Ok(ExecutionAction::Instant(Some(Args::print_version()))),
+		Cmd::VersionJson => Ok(ExecutionAction::Instant(Some(print_version_json()))),
 		Cmd::Hash(maybe_file) => print_hash_of(maybe_file).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::Account(account_cmd) => account::execute(account_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::ImportPresaleWallet(presale_cmd) => presale::execute(presale_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::Blockchain(blockchain_cmd) => blockchain::execute(blockchain_cmd).map(|_| ExecutionAction::Instant(None)),
-		Cmd::SignerToken(ws_conf, logger_config) => signer::execute(ws_conf, logger_config).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerToken(ws_conf, logger_config, spec) => signer::execute(ws_conf, logger_config, spec).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerTokens(authfile) => signer::list_tokens(&authfile).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerRevokeToken { id, authfile } => signer::revoke_token(&authfile, &id).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::SignerSign { id, pwfile, port, authfile } => rpc_cli::signer_sign(id, pwfile, port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
-		Cmd::SignerList { port, authfile } => rpc_cli::signer_list(port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerList { port, authfile, json } => rpc_cli::signer_list(port, authfile, json).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerApproveAll { filter, pwfile, port, authfile, json } => rpc_cli::signer_approve_all(filter, pwfile, port, authfile, json).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::SignerReject { id, port, authfile } => rpc_cli::signer_reject(id, port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::Snapshot(snapshot_cmd) => snapshot::execute(snapshot_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::ExportHardcodedSync(export_hs_cmd) => export_hardcoded_sync::execute(export_hs_cmd).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::ValidateSpec(validate_spec_cmd) => validate_spec::execute(validate_spec_cmd).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::TestRunner(test_runner_cmd) => test_runner::execute(test_runner_cmd).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::Setup(setup_cmd) => setup::execute(setup_cmd).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::Service(service::ServiceCmd::Run(run_cmd)) => service::execute(service::ServiceCmd::Run(run_cmd), logger).map(|_| ExecutionAction::Instant(None)),
+		Cmd::Service(service_cmd) => service::execute(service_cmd, logger).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::UpdaterRollback(path) => updater::rollback(&path).map(|previous| ExecutionAction::Instant(Some(match previous {
+			Some(previous) => format!("Rolled back to {}. Restart Parity to run it.", previous),
+			None => "Rolled back to the originally installed binary. Restart Parity to run it.".into(),
+		}))),
 	}
 }
 
@@ -236,17 +288,67 @@ fn execute<Cr, Rr>(command: Execute, on_client_rq: Cr, on_updater_rq: Rr) -> Res
 /// The first parameter is the command line arguments that you would pass when running the parity
 /// binary.
 ///
+/// Logging is set up according to the configuration, unless a logger is already running.
+///
 /// On error, returns what to print on stderr.
 pub fn start<Cr, Rr>(conf: Configuration, on_client_rq: Cr, on_updater_rq: Rr) -> Result<ExecutionAction, String>
 	where Cr: Fn(String) + 'static + Send,
 			Rr: Fn() + 'static + Send
 {
-	let deprecated = find_deprecated(&conf.args);
-	for d in deprecated {
-		println!("{}", d);
-	}
+	let logger = setup_log(&conf.logger_config())?;
+	start_with_logger(conf, logger, on_client_rq, on_updater_rq)
+}
 
-	execute(conf.into_command()?, on_client_rq, on_updater_rq)
+/// Starts the parity client like `start`, using a logger set up beforehand with `setup_log`.
+///
+/// On error, returns what to print on stderr.
+pub fn start_with_logger<Cr, Rr>(mut conf: Configuration, logger: Arc<RotatingLogger>,
+						on_client_rq: Cr, on_updater_rq: Rr) -> Result<ExecutionAction, String>
+	where Cr: Fn(String) + 'static + Send,
+			Rr: Fn() + 'static + Send
+{
+	handle_deprecated(&mut conf.args)?;
+
+	execute(conf.into_command()?, logger, on_client_rq, on_updater_rq)
+}
+
+/// Starts the parity client like `start`, but switches chains in-process when the client receives
+/// an RPC request to do so, instead of leaving the restart to the caller.
+///
+/// `on_updater_rq` is the action to perform when the updater has a new binary to execute.
+///
+/// `on_failure` is called with what to print on stderr when neither the requested chain nor the
+/// previous one can be started, after which the client is stopped and should be shut down.
+///
+/// On error, returns what to print on stderr.
+pub fn start_restartable<Rr, Ff>(mut conf: Configuration, logger: Arc<RotatingLogger>, on_updater_rq: Rr, on_failure: Ff) -> Result<ExecutionAction, String>
+	where Rr: Fn() + 'static + Send + Sync,
+		  Ff: FnOnce(String) + 'static + Send
+{
+	handle_deprecated(&mut conf.args)?;
+
+	let args = conf.args.clone();
+	match conf.into_command()?.cmd {
+		Cmd::Run(run_cmd) => {
+			if let Some(ref dapp) = run_cmd.dapp {
+				open_dapp(&run_cmd.dapps_conf, &run_cmd.http_conf, dapp)?;
+			}
+
+			let reconfigure = move |chain: &str| {
+				let mut args = args.clone();
+				args.flag_testnet = false;
+				args.arg_chain = chain.to_owned();
+				match (Configuration { args }).into_command()?.cmd {
+					Cmd::Run(run_cmd) => Ok(run_cmd),
+					_ => Err("Switching chains has to run a client".to_owned()),
+				}
+			};
+
+			let outcome = run::execute_restartable(run_cmd, logger, reconfigure, on_updater_rq, on_failure)?;
+			Ok(ExecutionAction::Running(outcome))
+		},
+		cmd => execute(Execute { cmd }, logger, |_| {}, on_updater_rq),
+	}
 }
 
 fn open_dapp(dapps_conf: &dapps::Configuration, rpc_conf: &rpc::HttpConfiguration, dapp: &str) -> Result<(), String> {
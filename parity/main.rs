@@ -38,7 +38,7 @@ use std::sync::Arc;
 use ctrlc::CtrlC;
 use dir::default_hypervisor_path;
 use fdlimit::raise_fd_limit;
-use parity::{start, ExecutionAction};
+use parity::{start_with_logger, start_restartable, setup_log, ExecutionAction};
 use parking_lot::{Condvar, Mutex};
 
 fn updates_path(name: &str) -> PathBuf {
@@ -129,20 +129,31 @@ fn main_direct(force_can_restart: bool) -> i32 {
 
 	let can_restart = force_can_restart || conf.args.flag_can_restart;
 
+	let logger = match setup_log(&conf.logger_config()) {
+		Ok(logger) => logger,
+		Err(err) => {
+			writeln!(&mut stdio::stderr(), "{}", err).expect("StdErr available; qed");
+			global_cleanup();
+			return 1;
+		},
+	};
+
 	// increase max number of open files
 	raise_fd_limit();
 
-	let exit = Arc::new((Mutex::new((false, None)), Condvar::new()));
+	// (restart requested, chain to restart with, failure to report)
+	let exit = Arc::new((Mutex::new((false, None, None)), Condvar::new()));
 
 	let exec = if can_restart {
 		let e1 = exit.clone();
 		let e2 = exit.clone();
-		start(conf,
-			move |new_chain: String| { *e1.0.lock() = (true, Some(new_chain)); e1.1.notify_all(); },
-			move || { *e2.0.lock() = (true, None); e2.1.notify_all(); })
+		start_with_logger(conf, logger,
+			move |new_chain: String| { *e1.0.lock() = (true, Some(new_chain), None); e1.1.notify_all(); },
+			move || { *e2.0.lock() = (true, None, None); e2.1.notify_all(); })
 	} else {
-		trace!(target: "mode", "Not hypervised: not setting exit handlers.");
-		start(conf, move |_| {}, move || {})
+		trace!(target: "mode", "Not hypervised: switching chains in-process.");
+		let e = exit.clone();
+		start_restartable(conf, logger, move || {}, move |err: String| { e.0.lock().2 = Some(err); e.1.notify_all(); })
 	};
 
 	let res = match exec {
@@ -155,16 +166,25 @@ fn main_direct(force_can_restart: bool) -> i32 {
 					move || { e.1.notify_all(); }
 				});
 
-				// Wait for signal
-				let mut lock = exit.0.lock();
-				let _ = exit.1.wait(&mut lock);
+				// Wait for signal, unless the client has failed already
+				let state = {
+					let mut lock = exit.0.lock();
+					if lock.2.is_none() {
+						let _ = exit.1.wait(&mut lock);
+					}
+					lock.clone()
+				};
 
 				client.shutdown();
 
-				match &*lock {
-					&(true, ref spec_name_override) => {
-						if let &Some(ref spec_name) = spec_name_override {
-							set_spec_name_override(spec_name.clone());
+				match state {
+					(_, _, Some(err)) => {
+						writeln!(&mut stdio::stderr(), "{}", err).expect("StdErr available; qed");
+						1
+					},
+					(true, spec_name_override, None) => {
+						if let Some(spec_name) = spec_name_override {
+							set_spec_name_override(spec_name);
 						}
 						PLEASE_RESTART_EXIT_CODE
 					},
@@ -17,7 +17,7 @@
 use std::sync::Arc;
 
 use ethcore::client::BlockChainClient;
-use sync::{self, AttachedProtocol, SyncConfig, NetworkConfiguration, Params, ConnectionFilter};
+use sync::{self, AttachedProtocol, SyncConfig, NetworkConfiguration, Params, ConnectionFilter, ForkAlertHandler};
 use ethcore::snapshot::SnapshotService;
 use light::Provider;
 
@@ -37,6 +37,7 @@ pub fn sync(
 	_log_settings: &LogConfig,
 	attached_protos: Vec<AttachedProtocol>,
 	connection_filter: Option<Arc<ConnectionFilter>>,
+	fork_alert_handler: Option<Arc<ForkAlertHandler>>,
 ) -> Result<SyncModules, sync::Error> {
 	let eth_sync = EthSync::new(Params {
 		config: sync_cfg,
@@ -49,5 +50,9 @@ pub fn sync(
 	},
 	connection_filter)?;
 
+	if let Some(handler) = fork_alert_handler {
+		eth_sync.add_fork_alert_handler(handler);
+	}
+
 	Ok((eth_sync.clone() as Arc<SyncProvider>, eth_sync.clone() as Arc<ManageNetwork>, eth_sync.clone() as Arc<ChainNotify>))
 }
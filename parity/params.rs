@@ -17,10 +17,12 @@
 use std::{str, fs, fmt};
 use std::time::Duration;
 
-use ethcore::client::Mode;
+use ethcore::client::{Mode, ReceiptsStorage};
+use ethcore::engines::SealMode;
 use ethcore::ethereum;
 use ethcore::spec::{Spec, SpecParams};
 use ethereum_types::{U256, Address};
+use ethkey::{KeyPair, Derivation, mnemonic};
 use futures_cpupool::CpuPool;
 use hash_fetch::fetch::Client as FetchClient;
 use journaldb::Algorithm;
@@ -52,6 +54,27 @@ impl Default for SpecType {
 	}
 }
 
+impl SpecType {
+	/// Chain specs compiled into the binary.
+	pub fn builtin() -> Vec<SpecType> {
+		vec![
+			SpecType::Foundation,
+			SpecType::Classic,
+			SpecType::Morden,
+			SpecType::Ropsten,
+			SpecType::Kovan,
+			SpecType::Tobalaba,
+			SpecType::Olympic,
+			SpecType::Expanse,
+			SpecType::Musicoin,
+			SpecType::Ellaism,
+			SpecType::Easthub,
+			SpecType::Social,
+			SpecType::Dev,
+		]
+	}
+}
+
 impl str::FromStr for SpecType {
 	type Err = String;
 
@@ -263,24 +286,62 @@ impl GasPricerConfig {
 #[derive(Debug, PartialEq)]
 pub struct MinerExtras {
 	pub author: Address,
+	/// Registry name of the author, resolved once the client is started.
+	pub author_name: Option<String>,
 	pub engine_signer: Address,
 	pub extra_data: Vec<u8>,
 	pub gas_range_target: (U256, U256),
 	pub work_notify: Vec<String>,
+	pub seal_mode: Option<SealMode>,
 }
 
 impl Default for MinerExtras {
 	fn default() -> Self {
 		MinerExtras {
 			author: Default::default(),
+			author_name: None,
 			engine_signer: Default::default(),
 			extra_data: version_data(),
 			gas_range_target: (4_700_000.into(), 6_283_184.into()),
 			work_notify: Default::default(),
+			seal_mode: None,
 		}
 	}
 }
 
+/// Mnemonic of the development accounts, the same most Ethereum test frameworks use.
+pub const DEV_MNEMONIC: &'static str = "test test test test test test test test test test test junk";
+
+/// Derivation path of the parent key of the development accounts.
+const DEV_DERIVATION_PATH: &'static str = "m/44'/60'/0'/0";
+
+/// Deterministic accounts prefunded in the genesis of the development chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DevAccounts {
+	/// Number of accounts.
+	pub count: usize,
+	/// Genesis balance of each account, in wei.
+	pub balance: U256,
+}
+
+impl DevAccounts {
+	/// Key pairs of the accounts, derived from `DEV_MNEMONIC` at the default derivation path.
+	pub fn key_pairs(&self) -> Result<Vec<KeyPair>, String> {
+		let parent = mnemonic::extended_key(DEV_MNEMONIC, "", DEV_DERIVATION_PATH).map_err(|e| format!("{}", e))?;
+		(0..self.count as u32).map(|index| {
+			let key = parent.derive(Derivation::from(index)).map_err(|e| format!("Key derivation failed: {:?}", e))?;
+			KeyPair::from_secret(key.secret().as_raw().clone()).map_err(|e| format!("{}", e))
+		}).collect()
+	}
+
+	/// Adds the accounts with given key pairs to the genesis state of the spec.
+	pub fn prefund(&self, spec: &mut Spec, pairs: &[KeyPair]) -> Result<(), String> {
+		let balance = self.balance;
+		let accounts = pairs.iter().map(|pair| (pair.address(), balance));
+		spec.prefund_accounts(accounts).map_err(|e| format!("Failed to prefund development accounts: {}", e))
+	}
+}
+
 /// 3-value enum.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Switch {
@@ -334,11 +395,32 @@ pub fn mode_switch_to_bool(switch: Option<Mode>, user_defaults: &UserDefaults) -
 	Ok(switch.unwrap_or(user_defaults.mode.clone()))
 }
 
+pub fn receipts_storage_switch(switch: Option<ReceiptsStorage>, user_defaults: &UserDefaults) -> Result<ReceiptsStorage, String> {
+	match (user_defaults.is_first_launch, switch, user_defaults.receipts_storage) {
+		// the log bloom index is missing the blocks imported in light mode.
+		(false, Some(requested), ReceiptsStorage::Light) if requested != ReceiptsStorage::Light =>
+			Err(format!("Receipts storage can't be switched from light to {} before the log bloom index is rebuilt with parity db rebuild-blooms", requested.as_str())),
+		(_, Some(requested), _) => Ok(requested),
+		(_, None, stored) => Ok(stored),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use journaldb::Algorithm;
 	use user_defaults::UserDefaults;
-	use super::{SpecType, Pruning, ResealPolicy, Switch, tracing_switch_to_bool};
+	use ethcore::client::ReceiptsStorage;
+	use super::{SpecType, Pruning, ResealPolicy, Switch, DevAccounts, tracing_switch_to_bool, receipts_storage_switch};
+
+	#[test]
+	fn test_dev_accounts_are_deterministic() {
+		let accounts = DevAccounts { count: 2, balance: 1.into() };
+		let pairs = accounts.key_pairs().unwrap();
+		assert_eq!(pairs.len(), 2);
+		assert_eq!(pairs[0].address(), "f39fd6e51aad88f6f4ce6ab8827279cfffb92266".into());
+		assert_eq!(pairs[1].address(), "70997970c51812dc3a010c7d01b50e0d17dc79c8".into());
+		assert_eq!(*pairs[0].secret(), "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".into());
+	}
 
 	#[test]
 	fn test_spec_type_parsing() {
@@ -437,4 +519,21 @@ mod tests {
 		assert!(tracing_switch_to_bool(Switch::On, &user_defaults_with_tracing(false, true)).unwrap());
 		assert!(tracing_switch_to_bool(Switch::On, &user_defaults_with_tracing(false, false)).is_err());
 	}
+
+	#[test]
+	fn test_receipts_storage_switch() {
+		let mut ud = UserDefaults::default();
+		ud.receipts_storage = ReceiptsStorage::Light;
+		assert_eq!(receipts_storage_switch(Some(ReceiptsStorage::Full), &ud), Ok(ReceiptsStorage::Full));
+
+		ud.is_first_launch = false;
+		assert_eq!(receipts_storage_switch(None, &ud), Ok(ReceiptsStorage::Light));
+		assert_eq!(receipts_storage_switch(Some(ReceiptsStorage::Light), &ud), Ok(ReceiptsStorage::Light));
+		assert!(receipts_storage_switch(Some(ReceiptsStorage::Compact), &ud).is_err());
+		assert!(receipts_storage_switch(Some(ReceiptsStorage::Full), &ud).is_err());
+
+		ud.receipts_storage = ReceiptsStorage::Compact;
+		assert_eq!(receipts_storage_switch(Some(ReceiptsStorage::Full), &ud), Ok(ReceiptsStorage::Full));
+		assert_eq!(receipts_storage_switch(Some(ReceiptsStorage::Light), &ud), Ok(ReceiptsStorage::Light));
+	}
 }
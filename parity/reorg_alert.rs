@@ -0,0 +1,98 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sends HTTP notifications when block import is halted by a deep reorganization.
+
+use ethcore::client::{ChainNotify, ReorgAlert};
+use ethcore::header::BlockNumber;
+use ethereum_types::H256;
+use hash_fetch::fetch::Client as FetchClient;
+use parity_reactor::Remote;
+use webhook::Webhook;
+
+/// POSTs halting reorganizations to a webhook.
+pub struct ReorgAlertPoster {
+	webhook: Webhook,
+}
+
+impl ReorgAlertPoster {
+	/// Create new `ReorgAlertPoster`.
+	pub fn new(url: &str, fetch: FetchClient, remote: Remote) -> Result<Self, String> {
+		Ok(ReorgAlertPoster {
+			webhook: Webhook::new("reorg alert", url, fetch, remote)?,
+		})
+	}
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AlertJson<'a> {
+	best_number: BlockNumber,
+	best_hash: &'a H256,
+	ancestor_number: BlockNumber,
+	ancestor_hash: &'a H256,
+	new_number: BlockNumber,
+	new_hash: &'a H256,
+	depth: BlockNumber,
+	detected_at: u64,
+}
+
+impl<'a> From<&'a ReorgAlert> for AlertJson<'a> {
+	fn from(alert: &'a ReorgAlert) -> Self {
+		AlertJson {
+			best_number: alert.best_number,
+			best_hash: &alert.best_hash,
+			ancestor_number: alert.ancestor_number,
+			ancestor_hash: &alert.ancestor_hash,
+			new_number: alert.new_number,
+			new_hash: &alert.new_hash,
+			depth: alert.depth,
+			detected_at: alert.detected_at,
+		}
+	}
+}
+
+impl ChainNotify for ReorgAlertPoster {
+	fn reorg_halted(&self, alert: &ReorgAlert) {
+		self.webhook.post(&AlertJson::from(alert));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::client::ReorgAlert;
+	use super::AlertJson;
+
+	#[test]
+	fn should_serialize_alert() {
+		let alert = ReorgAlert {
+			best_number: 10,
+			best_hash: 10.into(),
+			ancestor_number: 5,
+			ancestor_hash: 5.into(),
+			new_number: 11,
+			new_hash: 11.into(),
+			depth: 5,
+			detected_at: 1_500_000_000,
+		};
+
+		assert_eq!(
+			serde_json::to_string(&AlertJson::from(&alert)).unwrap(),
+			r#"{"bestNumber":10,"bestHash":"0x000000000000000000000000000000000000000000000000000000000000000a","ancestorNumber":5,"ancestorHash":"0x0000000000000000000000000000000000000000000000000000000000000005","newNumber":11,"newHash":"0x000000000000000000000000000000000000000000000000000000000000000b","depth":5,"detectedAt":1500000000}"#
+		);
+	}
+}
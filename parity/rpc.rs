@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::io;
+use std::{io, mem};
 use std::sync::Arc;
 use std::path::PathBuf;
 use std::collections::HashSet;
@@ -26,15 +26,32 @@ use helpers::parity_ipc_path;
 use jsonrpc_core::MetaIoHandler;
 use parity_reactor::TokioRemote;
 use parity_rpc::informant::{RpcStats, Middleware};
+use parity_rpc::drain::RpcDrain;
+use parity_rpc::names::NameResolver;
 use parity_rpc::{self as rpc, Metadata, DomainsValidation};
+use parking_lot::Mutex;
 use rpc_apis::{self, ApiSet};
 
 pub use parity_rpc::{IpcServer, HttpServer, RequestMiddleware};
 pub use parity_rpc::ws::Server as WsServer;
-pub use parity_rpc::informant::CpuPool;
+pub use parity_rpc::informant::{CpuPool, Lanes, MethodPool};
 
 pub const DAPPS_DOMAIN: &'static str = "web3.site";
 
+/// Health and sync status methods served from the reserved lane by default.
+/// Keep it to cheap checks, the lane has a single thread.
+pub const DEFAULT_PRIORITY_METHODS: &'static [&'static str] = &[
+	"parity_nodeHealth", "eth_syncing", "net_peerCount",
+];
+
+/// Additional address of an RPC transport, served with its own set of APIs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bind {
+	pub interface: String,
+	pub port: u16,
+	pub apis: ApiSet,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct HttpConfiguration {
 	pub enabled: bool,
@@ -45,12 +62,34 @@ pub struct HttpConfiguration {
 	pub hosts: Option<Vec<String>>,
 	pub server_threads: usize,
 	pub processing_threads: usize,
+	/// Threads of the separate pool for `heavy_methods`, 0 to process them with other calls.
+	pub heavy_threads: usize,
+	pub heavy_methods: Vec<String>,
+	/// Methods served from a reserved lane, even when the node is saturated.
+	pub priority_methods: Vec<String>,
+	/// Additional addresses served by separate server instances.
+	pub extra_binds: Vec<Bind>,
 }
 
 impl HttpConfiguration {
 	pub fn address(&self) -> Option<rpc::Host> {
 		address(self.enabled, &self.interface, self.port, &self.hosts)
 	}
+
+	/// Processing lanes for priority and heavy methods, shared by all RPC servers.
+	pub fn lanes(&self) -> Lanes {
+		Lanes {
+			// status calls are cheap, a single reserved thread is enough
+			priority: match self.priority_methods.is_empty() {
+				true => None,
+				false => Some(MethodPool::new(CpuPool::new(1), self.priority_methods.clone())),
+			},
+			heavy: match self.heavy_threads {
+				0 => None,
+				threads => Some(MethodPool::new(CpuPool::new(threads), self.heavy_methods.clone())),
+			},
+		}
+	}
 }
 
 impl Default for HttpConfiguration {
@@ -64,6 +103,10 @@ impl Default for HttpConfiguration {
 			hosts: Some(vec![]),
 			server_threads: 1,
 			processing_threads: 4,
+			heavy_threads: 0,
+			heavy_methods: vec!["eth_getLogs".into(), "trace_*".into()],
+			priority_methods: DEFAULT_PRIORITY_METHODS.iter().map(|m| m.to_string()).collect(),
+			extra_binds: Vec::new(),
 		}
 	}
 }
@@ -73,6 +116,8 @@ pub struct IpcConfiguration {
 	pub enabled: bool,
 	pub socket_addr: String,
 	pub apis: ApiSet,
+	/// Subscription notifications buffered for each connection which is not reading them fast enough.
+	pub notification_queue: usize,
 }
 
 impl Default for IpcConfiguration {
@@ -86,6 +131,7 @@ impl Default for IpcConfiguration {
 				parity_ipc_path(&data_dir, "$BASE/jsonrpc.ipc", 0)
 			},
 			apis: ApiSet::IpcContext,
+			notification_queue: 1024,
 		}
 	}
 }
@@ -96,12 +142,16 @@ pub struct WsConfiguration {
 	pub interface: String,
 	pub port: u16,
 	pub apis: ApiSet,
+	/// APIs of sessions opened from matching origins, instead of `apis`.
+	pub origin_apis: Vec<(String, ApiSet)>,
 	pub max_connections: usize,
 	pub origins: Option<Vec<String>>,
 	pub hosts: Option<Vec<String>>,
 	pub signer_path: PathBuf,
 	pub support_token_api: bool,
 	pub dapps_address: Option<rpc::Host>,
+	/// Additional addresses served by separate server instances, without `origin_apis`.
+	pub extra_binds: Vec<Bind>,
 }
 
 impl Default for WsConfiguration {
@@ -112,12 +162,14 @@ impl Default for WsConfiguration {
 			interface: "127.0.0.1".into(),
 			port: 8546,
 			apis: ApiSet::UnsafeContext,
+			origin_apis: Vec::new(),
 			max_connections: 100,
 			origins: Some(vec!["parity://*".into(),"chrome-extension://*".into(), "moz-extension://*".into()]),
 			hosts: Some(Vec::new()),
 			signer_path: replace_home(&data_dir, "$BASE/signer").into(),
 			support_token_api: true,
 			dapps_address: Some("127.0.0.1:8545".into()),
+			extra_binds: Vec::new(),
 		}
 	}
 }
@@ -143,45 +195,89 @@ pub struct Dependencies<D: rpc_apis::Dependencies> {
 	pub apis: Arc<D>,
 	pub remote: TokioRemote,
 	pub stats: Arc<RpcStats>,
+	pub drain: Arc<RpcDrain>,
 	pub pool: Option<CpuPool>,
+	pub lanes: Lanes,
+	pub names: Option<Arc<NameResolver>>,
+}
+
+/// Server handles which are closed early once `parity_drainRpc` grace period is over.
+pub type DrainableServer<T> = Arc<Mutex<Vec<T>>>;
+
+pub fn close_on_drain<T: Send + 'static>(drain: &RpcDrain, servers: Vec<T>, close: fn(T)) -> DrainableServer<T> {
+	let servers = Arc::new(Mutex::new(servers));
+	let weak = Arc::downgrade(&servers);
+	drain.on_finish(move || {
+		let servers = match weak.upgrade() {
+			Some(servers) => servers,
+			None => return,
+		};
+		let servers = mem::replace(&mut *servers.lock(), Vec::new());
+		if !servers.is_empty() {
+			info!("RPC server drained, shutting down.");
+			servers.into_iter().for_each(close);
+		}
+	});
+	servers
 }
 
 pub fn new_ws<D: rpc_apis::Dependencies>(
 	conf: WsConfiguration,
 	deps: &Dependencies<D>,
-) -> Result<Option<WsServer>, String> {
+) -> Result<Vec<WsServer>, String> {
 	if !conf.enabled {
-		return Ok(None);
+		return Ok(Vec::new());
+	}
+
+	let mut servers = vec![
+		start_ws(&conf, &conf.interface, conf.port, &conf.apis, &conf.origin_apis, deps, "--ws-port and --ws-interface options")?
+	];
+	for bind in &conf.extra_binds {
+		servers.push(start_ws(&conf, &bind.interface, bind.port, &bind.apis, &conf.origin_apis, deps, "--ws-extra-binds option")?);
 	}
 
+	Ok(servers)
+}
+
+fn start_ws<D: rpc_apis::Dependencies>(
+	conf: &WsConfiguration,
+	interface: &str,
+	port: u16,
+	apis: &ApiSet,
+	origin_apis: &[(String, ApiSet)],
+	deps: &Dependencies<D>,
+	options: &str,
+) -> Result<WsServer, String> {
 	let domain = DAPPS_DOMAIN;
-	let url = format!("{}:{}", conf.interface, conf.port);
+	let url = format!("{}:{}", interface, port);
 	let addr = url.parse().map_err(|_| format!("Invalid WebSockets listen host/port given: {}", url))?;
 
+	let signer_path;
+	let path = match conf.support_token_api {
+		true => {
+			signer_path = ::signer::codes_path(&conf.signer_path);
+			Some(signer_path.as_path())
+		},
+		false => None
+	};
 	let full_handler = setup_apis(rpc_apis::ApiSet::SafeContext, deps);
+	let apis = apis.list_apis();
 	let handler = {
-		let mut handler = MetaIoHandler::with_middleware((
-			rpc::WsDispatcher::new(full_handler),
-			Middleware::new(deps.stats.clone(), deps.apis.activity_notifier(), deps.pool.clone())
-		));
-		let apis = conf.apis.list_apis();
+		let dispatcher = origin_apis.iter().fold(rpc::WsDispatcher::new(full_handler).with_authcodes(path.map(|path| path.to_path_buf())), |dispatcher, &(ref origin, ref origin_apis)| {
+			// origins can only narrow down the APIs of the address
+			let origin_apis = origin_apis.list_apis().intersection(&apis).cloned().collect();
+			dispatcher.with_origin(origin.clone(), setup_apis(ApiSet::List(origin_apis), deps))
+		});
+		let mut handler = MetaIoHandler::with_middleware((dispatcher, rpc_middleware(deps)));
 		deps.apis.extend_with_set(&mut handler, &apis);
 
 		handler
 	};
 
 	let remote = deps.remote.clone();
-	let allowed_origins = into_domains(with_domain(conf.origins, domain, &conf.dapps_address));
-	let allowed_hosts = into_domains(with_domain(conf.hosts, domain, &Some(url.clone().into())));
+	let allowed_origins = into_domains(with_domain(conf.origins.clone(), domain, &conf.dapps_address));
+	let allowed_hosts = into_domains(with_domain(conf.hosts.clone(), domain, &Some(url.clone().into())));
 
-	let signer_path;
-	let path = match conf.support_token_api {
-		true => {
-			signer_path = ::signer::codes_path(&conf.signer_path);
-			Some(signer_path.as_path())
-		},
-		false => None
-	};
 	let start_result = rpc::start_ws(
 		&addr,
 		handler,
@@ -189,39 +285,61 @@ pub fn new_ws<D: rpc_apis::Dependencies>(
 		allowed_origins,
 		allowed_hosts,
 		conf.max_connections,
-		rpc::WsExtractor::new(path.clone()),
-		rpc::WsExtractor::new(path.clone()),
+		rpc::WsExtractor::new(path.clone()).with_origin_apis(origin_apis.iter().map(|&(ref origin, _)| origin.clone()).collect()),
+		rpc::DrainMiddleware::new(deps.drain.clone(), rpc::WsExtractor::new(path.clone())),
 		rpc::WsStats::new(deps.stats.clone()),
 	);
 
 	match start_result {
-		Ok(server) => Ok(Some(server)),
+		Ok(server) => Ok(server),
 		Err(rpc::ws::Error(rpc::ws::ErrorKind::Io(ref err), _)) if err.kind() == io::ErrorKind::AddrInUse => Err(
-			format!("WebSockets address {} is already in use, make sure that another instance of an Ethereum client is not running or change the address using the --ws-port and --ws-interface options.", url)
+			format!("WebSockets address {} is already in use, make sure that another instance of an Ethereum client is not running or change the address using the {}.", url, options)
 		),
 		Err(e) => Err(format!("WebSockets error: {:?}", e)),
 	}
 }
 
+/// Starts the HTTP server at the configured address and its extra binds.
+/// The dapps middleware is only served at the configured address.
 pub fn new_http<D: rpc_apis::Dependencies>(
 	id: &str,
 	options: &str,
 	conf: HttpConfiguration,
 	deps: &Dependencies<D>,
 	middleware: Option<dapps::Middleware>,
-) -> Result<Option<HttpServer>, String> {
+) -> Result<Vec<HttpServer>, String> {
 	if !conf.enabled {
-		return Ok(None);
+		return Ok(Vec::new());
 	}
 
+	let mut servers = vec![
+		start_http(id, &conf, &conf.interface, conf.port, conf.apis.clone(), deps, middleware, &format!("--{}-port and --{}-interface options", options, options))?
+	];
+	for bind in &conf.extra_binds {
+		servers.push(start_http(id, &conf, &bind.interface, bind.port, bind.apis.clone(), deps, None, &format!("--{}-extra-binds option", options))?);
+	}
+
+	Ok(servers)
+}
+
+fn start_http<D: rpc_apis::Dependencies>(
+	id: &str,
+	conf: &HttpConfiguration,
+	interface: &str,
+	port: u16,
+	apis: ApiSet,
+	deps: &Dependencies<D>,
+	middleware: Option<dapps::Middleware>,
+	options: &str,
+) -> Result<HttpServer, String> {
 	let domain = DAPPS_DOMAIN;
-	let url = format!("{}:{}", conf.interface, conf.port);
+	let url = format!("{}:{}", interface, port);
 	let addr = url.parse().map_err(|_| format!("Invalid {} listen host/port given: {}", id, url))?;
-	let handler = setup_apis(conf.apis, deps);
+	let handler = setup_apis(apis, deps);
 	let remote = deps.remote.clone();
 
-	let cors_domains = into_domains(conf.cors);
-	let allowed_hosts = into_domains(with_domain(conf.hosts, domain, &Some(url.clone().into())));
+	let cors_domains = into_domains(conf.cors.clone());
+	let allowed_hosts = into_domains(with_domain(conf.hosts.clone(), domain, &Some(url.clone().into())));
 
 	let start_result = rpc::start_http(
 		&addr,
@@ -230,14 +348,14 @@ pub fn new_http<D: rpc_apis::Dependencies>(
 		handler,
 		remote,
 		rpc::RpcExtractor,
-		middleware,
+		Some(rpc::DrainMiddleware::new(deps.drain.clone(), middleware)),
 		conf.server_threads,
 	);
 
 	match start_result {
-		Ok(server) => Ok(Some(server)),
+		Ok(server) => Ok(server),
 		Err(ref err) if err.kind() == io::ErrorKind::AddrInUse => Err(
-			format!("{} address {} is already in use, make sure that another instance of an Ethereum client is not running or change the address using the --{}-port and --{}-interface options.", id, url, options, options)
+			format!("{} address {} is already in use, make sure that another instance of an Ethereum client is not running or change the address using the {}.", id, url, options)
 		),
 		Err(e) => Err(format!("{} error: {:?}", id, e)),
 	}
@@ -263,7 +381,8 @@ pub fn new_ipc<D: rpc_apis::Dependencies>(
 		}
 	}
 
-	match rpc::start_ipc(&conf.socket_addr, handler, remote, rpc::RpcExtractor) {
+	let extractor = rpc::IpcExtractor::new(remote.clone(), conf.notification_queue);
+	match rpc::start_ipc(&conf.socket_addr, handler, remote, extractor) {
 		Ok(server) => Ok(Some(server)),
 		Err(io_error) => Err(format!("IPC error: {}", io_error)),
 	}
@@ -298,12 +417,18 @@ fn with_domain(items: Option<Vec<String>>, domain: &str, dapps_address: &Option<
 	})
 }
 
+fn rpc_middleware<D: rpc_apis::Dependencies>(deps: &Dependencies<D>) -> Middleware<D::Notifier> {
+	let middleware = Middleware::new(deps.stats.clone(), deps.apis.activity_notifier(), deps.pool.clone(), deps.lanes.clone());
+	match deps.names {
+		Some(ref names) => middleware.with_names(names.clone()),
+		None => middleware,
+	}
+}
+
 pub fn setup_apis<D>(apis: ApiSet, deps: &Dependencies<D>) -> MetaIoHandler<Metadata, Middleware<D::Notifier>>
 	where D: rpc_apis::Dependencies
 {
-	let mut handler = MetaIoHandler::with_middleware(
-		Middleware::new(deps.stats.clone(), deps.apis.activity_notifier(), deps.pool.clone())
-	);
+	let mut handler = MetaIoHandler::with_middleware(rpc_middleware(deps));
 	let apis = apis.list_apis();
 	deps.apis.extend_with_set(&mut handler, &apis);
 
@@ -24,7 +24,7 @@ pub use parity_rpc::dapps::{DappsService, LocalDapp};
 
 use ethcore_service::PrivateTxService;
 use ethcore::account_provider::AccountProvider;
-use ethcore::client::Client;
+use ethcore::client::{Client, ExplorerIndex};
 use ethcore::miner::Miner;
 use ethcore::snapshot::SnapshotService;
 use ethcore_logger::RotatingLogger;
@@ -35,13 +35,16 @@ use jsonrpc_core::{self as core, MetaIoHandler};
 use light::client::LightChainClient;
 use light::{TransactionQueue as LightTransactionQueue, Cache as LightDataCache};
 use miner::external::ExternalMiner;
+use node_filter::NodeFilter;
 use node_health::NodeHealth;
 use parity_reactor;
 use parity_rpc::dispatch::{FullDispatcher, LightDispatcher};
+use parity_rpc::drain::RpcDrain;
 use parity_rpc::informant::{ActivityNotifier, ClientNotifier};
 use parity_rpc::{Metadata, NetworkSettings, Host};
 use parking_lot::{Mutex, RwLock};
 use ethcore_private_tx::Provider as PrivateTransactionManager;
+use registrar::{RegistrarClient, Asynchronous};
 use updater::Updater;
 
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
@@ -68,6 +71,14 @@ pub enum Api {
 	ParitySet,
 	/// Traces (Safe)
 	Traces,
+	/// Debug (Safe but expensive: re-executes calls with VM tracing)
+	Debug,
+	/// Clique - Signer voting (UNSAFE: Side Effects affecting sealed blocks)
+	Clique,
+	/// Evm - Dev chain block sealing (UNSAFE: Side Effects affecting sealed blocks)
+	Evm,
+	/// Explorer - Block lists and address history (Safe, but may be expensive)
+	Explorer,
 	/// Rpc (Safe)
 	Rpc,
 	/// SecretStore (UNSAFE: arbitrary hash signing)
@@ -80,8 +91,17 @@ pub enum Api {
 	Whisper,
 	/// Whisper Pub-Sub (Safe but same concerns as above).
 	WhisperPubSub,
+	/// Whisper - Set methods (UNSAFE: Side Effects affecting node operation)
+	WhisperSet,
 }
 
+/// Names of all RPC namespaces this build can expose.
+pub const API_NAMES: &'static [&'static str] = &[
+	"web3", "net", "eth", "pubsub", "personal", "signer", "parity", "parity_pubsub",
+	"parity_accounts", "parity_set", "traces", "debug", "clique", "evm", "explorer", "rpc",
+	"secretstore", "private", "shh", "shh_pubsub", "shh_set",
+];
+
 impl FromStr for Api {
 	type Err = String;
 
@@ -100,11 +120,16 @@ impl FromStr for Api {
 			"parity_accounts" => Ok(ParityAccounts),
 			"parity_set" => Ok(ParitySet),
 			"traces" => Ok(Traces),
+			"debug" => Ok(Debug),
+			"clique" => Ok(Clique),
+			"evm" => Ok(Evm),
+			"explorer" => Ok(Explorer),
 			"rpc" => Ok(Rpc),
 			"secretstore" => Ok(SecretStore),
 			"private" => Ok(Private),
 			"shh" => Ok(Whisper),
 			"shh_pubsub" => Ok(WhisperPubSub),
+			"shh_set" => Ok(WhisperSet),
 			api => Err(format!("Unknown api: {}", api))
 		}
 	}
@@ -184,11 +209,16 @@ fn to_modules(apis: &HashSet<Api>) -> BTreeMap<String, String> {
 			Api::ParityPubSub => ("parity_pubsub", "1.0"),
 			Api::ParitySet => ("parity_set", "1.0"),
 			Api::Traces => ("traces", "1.0"),
+			Api::Debug => ("debug", "1.0"),
+			Api::Clique => ("clique", "1.0"),
+			Api::Evm => ("evm", "1.0"),
+			Api::Explorer => ("explorer", "1.0"),
 			Api::Rpc => ("rpc", "1.0"),
 			Api::SecretStore => ("secretstore", "1.0"),
 			Api::Private => ("private", "1.0"),
 			Api::Whisper => ("shh", "1.0"),
 			Api::WhisperPubSub => ("shh_pubsub", "1.0"),
+			Api::WhisperSet => ("shh_set", "1.0"),
 		};
 		modules.insert(name.into(), version.into());
 	}
@@ -225,6 +255,8 @@ pub struct FullDependencies {
 	pub settings: Arc<NetworkSettings>,
 	pub net_service: Arc<ManageNetwork>,
 	pub updater: Arc<Updater>,
+	pub registrar: Arc<RegistrarClient<Call=Asynchronous>>,
+	pub node_filter: Option<Arc<NodeFilter>>,
 	pub health: NodeHealth,
 	pub geth_compatibility: bool,
 	pub dapps_service: Option<Arc<DappsService>>,
@@ -236,6 +268,8 @@ pub struct FullDependencies {
 	pub whisper_rpc: Option<::whisper::RpcFactory>,
 	pub gas_price_percentile: usize,
 	pub poll_lifetime: u32,
+	pub rpc_drain: Arc<RpcDrain>,
+	pub explorer_index: Option<Arc<ExplorerIndex>>,
 }
 
 impl FullDependencies {
@@ -261,6 +295,22 @@ impl FullDependencies {
 			}
 		}
 
+		macro_rules! add_registrar_methods {
+			($handler:expr, $deps:expr, $nonces:expr) => {
+				{
+					let deps = &$deps;
+					let dispatcher = FullDispatcher::new(deps.client.clone(), deps.miner.clone(), $nonces, deps.gas_price_percentile);
+					if deps.signer_service.is_enabled() {
+						let signing = SigningQueueClient::new(&deps.signer_service, dispatcher, deps.remote.clone(), &deps.secret_store);
+						$handler.extend_with(ParityRegistrarClient::new(signing, deps.registrar.clone()).to_delegate())
+					} else {
+						let signing = SigningUnsafeClient::new(&deps.secret_store, dispatcher);
+						$handler.extend_with(ParityRegistrarClient::new(signing, deps.registrar.clone()).to_delegate())
+					}
+				}
+			}
+		}
+
 		let nonces = Arc::new(Mutex::new(dispatch::Reservations::with_pool(self.pool.clone())));
 		let dispatcher = FullDispatcher::new(
 			self.client.clone(),
@@ -289,7 +339,8 @@ impl FullDependencies {
 							allow_pending_receipt_query: !self.geth_compatibility,
 							send_block_number_in_get_work: !self.geth_compatibility,
 							gas_price_percentile: self.gas_price_percentile,
-							poll_lifetime: self.poll_lifetime
+							poll_lifetime: self.poll_lifetime,
+							fat_receipts: self.client.tracing_enabled(),
 						}
 					);
 					handler.extend_with(client.to_delegate());
@@ -303,7 +354,7 @@ impl FullDependencies {
 				},
 				Api::EthPubSub => {
 					if !for_generic_pubsub {
-						let client = EthPubSubClient::new(self.client.clone(), self.remote.clone());
+						let client = EthPubSubClient::with_accounts(self.client.clone(), self.remote.clone(), self.secret_store.clone());
 						let h = client.handler();
 						self.miner.add_transactions_listener(Box::new(move |hashes| if let Some(h) = h.upgrade() {
 							h.notify_new_transactions(hashes);
@@ -339,6 +390,7 @@ impl FullDependencies {
 						signer,
 						self.dapps_address.clone(),
 						self.ws_address.clone(),
+						self.node_filter.clone(),
 					).to_delegate());
 
 					if !for_generic_pubsub {
@@ -362,14 +414,33 @@ impl FullDependencies {
 						&self.miner,
 						&self.updater,
 						&self.net_service,
+						self.logger.clone(),
 						self.dapps_service.clone(),
 						self.fetch.clone(),
 						self.pool.clone(),
-					).to_delegate())
+						self.rpc_drain.clone(),
+					).to_delegate());
+					handler.extend_with(ParityNoncesClient::new(dispatcher.clone()).to_delegate());
+
+					if !for_generic_pubsub {
+						add_registrar_methods!(handler, self, nonces.clone());
+					}
 				},
 				Api::Traces => {
 					handler.extend_with(TracesClient::new(&self.client).to_delegate())
 				},
+				Api::Debug => {
+					handler.extend_with(DebugClient::new(&self.client).to_delegate())
+				},
+				Api::Clique => {
+					handler.extend_with(CliqueClient::new(&self.client).to_delegate())
+				},
+				Api::Evm => {
+					handler.extend_with(EvmClient::new(&self.client).to_delegate())
+				},
+				Api::Explorer => {
+					handler.extend_with(ExplorerClient::new(&self.client, self.explorer_index.clone()).to_delegate())
+				},
 				Api::Rpc => {
 					let modules = to_modules(&apis);
 					handler.extend_with(RpcClient::new(modules).to_delegate());
@@ -390,9 +461,19 @@ impl FullDependencies {
 							handler.extend_with(
 								::parity_whisper::rpc::WhisperPubSub::to_delegate(whisper)
 							);
+							let bridge = whisper_rpc.make_handler(self.net.clone());
+							handler.extend_with(
+								::parity_whisper::rpc::WhisperBridge::to_delegate(bridge)
+							);
 						}
 					}
 				},
+				Api::WhisperSet => {
+					if let Some(ref whisper_rpc) = self.whisper_rpc {
+						let whisper = whisper_rpc.make_handler(self.net.clone());
+						handler.extend_with(::parity_whisper::rpc::WhisperSet::to_delegate(whisper));
+					}
+				},
 				Api::Private => {
 					handler.extend_with(PrivateClient::new(self.private_tx_service.as_ref().map(|p| p.provider())).to_delegate());
 				},
@@ -439,6 +520,7 @@ pub struct LightDependencies<T> {
 	pub on_demand: Arc<::light::on_demand::OnDemand>,
 	pub cache: Arc<Mutex<LightDataCache>>,
 	pub transaction_queue: Arc<RwLock<LightTransactionQueue>>,
+	pub registrar: Arc<RegistrarClient<Call=Asynchronous>>,
 	pub dapps_service: Option<Arc<DappsService>>,
 	pub dapps_address: Option<Host>,
 	pub ws_address: Option<Host>,
@@ -450,6 +532,7 @@ pub struct LightDependencies<T> {
 	pub private_tx_service: Option<Arc<PrivateTransactionManager>>,
 	pub gas_price_percentile: usize,
 	pub poll_lifetime: u32,
+	pub rpc_drain: Arc<RpcDrain>,
 }
 
 impl<C: LightChainClient + 'static> LightDependencies<C> {
@@ -490,6 +573,23 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 			}
 		}
 
+		macro_rules! add_registrar_methods {
+			($handler:expr, $deps:expr) => {
+				{
+					let deps = &$deps;
+					let dispatcher = dispatcher.clone();
+					let secret_store = deps.secret_store.clone();
+					if deps.signer_service.is_enabled() {
+						let signing = SigningQueueClient::new(&deps.signer_service, dispatcher, deps.remote.clone(), &secret_store);
+						$handler.extend_with(ParityRegistrarClient::new(signing, deps.registrar.clone()).to_delegate())
+					} else {
+						let signing = SigningUnsafeClient::new(&secret_store, dispatcher);
+						$handler.extend_with(ParityRegistrarClient::new(signing, deps.registrar.clone()).to_delegate())
+					}
+				}
+			}
+		}
+
 		for api in apis {
 			match *api {
 				Api::Web3 => {
@@ -576,14 +676,33 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 				Api::ParitySet => {
 					handler.extend_with(light::ParitySetClient::new(
 						self.sync.clone(),
+						self.logger.clone(),
 						self.dapps_service.clone(),
 						self.fetch.clone(),
 						self.pool.clone(),
-					).to_delegate())
+						self.rpc_drain.clone(),
+					).to_delegate());
+					handler.extend_with(ParityNoncesClient::new(dispatcher.clone()).to_delegate());
+
+					if !for_generic_pubsub {
+						add_registrar_methods!(handler, self);
+					}
 				},
 				Api::Traces => {
 					handler.extend_with(light::TracesClient.to_delegate())
 				},
+				Api::Debug => {
+					warn!(target: "rpc", "Debug API is not available in light client mode.");
+				},
+				Api::Clique => {
+					warn!(target: "rpc", "Clique API is not available in light client mode.");
+				},
+				Api::Evm => {
+					warn!(target: "rpc", "Evm API is not available in light client mode.");
+				},
+				Api::Explorer => {
+					warn!(target: "rpc", "Explorer API is not available in light client mode.");
+				},
 				Api::Rpc => {
 					let modules = to_modules(&apis);
 					handler.extend_with(RpcClient::new(modules).to_delegate());
@@ -601,6 +720,14 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 					if let Some(ref whisper_rpc) = self.whisper_rpc {
 						let whisper = whisper_rpc.make_handler(self.net.clone());
 						handler.extend_with(::parity_whisper::rpc::WhisperPubSub::to_delegate(whisper));
+						let bridge = whisper_rpc.make_handler(self.net.clone());
+						handler.extend_with(::parity_whisper::rpc::WhisperBridge::to_delegate(bridge));
+					}
+				},
+				Api::WhisperSet => {
+					if let Some(ref whisper_rpc) = self.whisper_rpc {
+						let whisper = whisper_rpc.make_handler(self.net.clone());
+						handler.extend_with(::parity_whisper::rpc::WhisperSet::to_delegate(whisper));
 					}
 				},
 				Api::Private => {
@@ -656,24 +783,36 @@ impl ApiSet {
 			},
 			ApiSet::IpcContext => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::Debug);
+				public_list.insert(Api::Explorer);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list
 			},
 			ApiSet::SafeContext => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::Debug);
+				public_list.insert(Api::Explorer);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list.insert(Api::ParitySet);
+				public_list.insert(Api::WhisperSet);
+				public_list.insert(Api::Clique);
+				public_list.insert(Api::Evm);
 				public_list.insert(Api::Signer);
 				public_list.insert(Api::SecretStore);
 				public_list
 			},
 			ApiSet::All => {
 				public_list.insert(Api::Traces);
+				public_list.insert(Api::Debug);
+				public_list.insert(Api::Explorer);
 				public_list.insert(Api::ParityPubSub);
 				public_list.insert(Api::ParityAccounts);
 				public_list.insert(Api::ParitySet);
+				public_list.insert(Api::WhisperSet);
+				public_list.insert(Api::Clique);
+				public_list.insert(Api::Evm);
 				public_list.insert(Api::Signer);
 				public_list.insert(Api::Personal);
 				public_list.insert(Api::SecretStore);
@@ -692,7 +831,14 @@ impl ApiSet {
 
 #[cfg(test)]
 mod test {
-	use super::{Api, ApiSet};
+	use super::{Api, ApiSet, API_NAMES};
+
+	#[test]
+	fn test_api_names_are_parseable() {
+		for name in API_NAMES {
+			assert!(name.parse::<Api>().is_ok(), "{} should be a known api", name);
+		}
+	}
 
 	#[test]
 	fn test_api_parsing() {
@@ -706,11 +852,16 @@ mod test {
 		assert_eq!(Api::ParityAccounts, "parity_accounts".parse().unwrap());
 		assert_eq!(Api::ParitySet, "parity_set".parse().unwrap());
 		assert_eq!(Api::Traces, "traces".parse().unwrap());
+		assert_eq!(Api::Debug, "debug".parse().unwrap());
+		assert_eq!(Api::Clique, "clique".parse().unwrap());
+		assert_eq!(Api::Evm, "evm".parse().unwrap());
+		assert_eq!(Api::Explorer, "explorer".parse().unwrap());
 		assert_eq!(Api::Rpc, "rpc".parse().unwrap());
 		assert_eq!(Api::SecretStore, "secretstore".parse().unwrap());
 		assert_eq!(Api::Private, "private".parse().unwrap());
 		assert_eq!(Api::Whisper, "shh".parse().unwrap());
 		assert_eq!(Api::WhisperPubSub, "shh_pubsub".parse().unwrap());
+		assert_eq!(Api::WhisperSet, "shh_set".parse().unwrap());
 		assert!("rp".parse::<Api>().is_err());
 	}
 
@@ -739,7 +890,9 @@ mod test {
 			// safe
 			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::Whisper, Api::WhisperPubSub, Api::Private,
 			// semi-safe
-			Api::ParityAccounts
+			Api::ParityAccounts,
+			// expensive
+			Api::Debug, Api::Explorer,
 		].into_iter().collect();
 		assert_eq!(ApiSet::IpcContext.list_apis(), expected);
 	}
@@ -752,7 +905,9 @@ mod test {
 			// semi-safe
 			Api::ParityAccounts,
 			// Unsafe
-			Api::ParitySet, Api::Signer,
+			Api::ParitySet, Api::WhisperSet, Api::Clique, Api::Evm, Api::Signer,
+			// expensive
+			Api::Debug, Api::Explorer,
 		].into_iter().collect();
 		assert_eq!(ApiSet::SafeContext.list_apis(), expected);
 	}
@@ -762,9 +917,10 @@ mod test {
 		assert_eq!("all".parse::<ApiSet>().unwrap(), ApiSet::List(vec![
 			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore, Api::Whisper, Api::WhisperPubSub,
 			Api::ParityAccounts,
-			Api::ParitySet, Api::Signer,
+			Api::ParitySet, Api::WhisperSet, Api::Clique, Api::Evm, Api::Signer,
 			Api::Personal,
 			Api::Private,
+			Api::Debug, Api::Explorer,
 		].into_iter().collect()));
 	}
 
@@ -773,8 +929,9 @@ mod test {
 		assert_eq!("personal,all,-personal".parse::<ApiSet>().unwrap(), ApiSet::List(vec![
 			Api::Web3, Api::Net, Api::Eth, Api::EthPubSub, Api::Parity, Api::ParityPubSub, Api::Traces, Api::Rpc, Api::SecretStore, Api::Whisper, Api::WhisperPubSub,
 			Api::ParityAccounts,
-			Api::ParitySet, Api::Signer,
-			Api::Private
+			Api::ParitySet, Api::WhisperSet, Api::Clique, Api::Evm, Api::Signer,
+			Api::Private,
+			Api::Debug, Api::Explorer,
 		].into_iter().collect()));
 	}
 
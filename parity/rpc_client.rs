@@ -0,0 +1,364 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Typed client of the node's JSON-RPC APIs.
+//!
+//! The bindings are declared in this module with the parameter and result types of the server's
+//! API definitions (re-exported in `parity_rpc::v1::client`), and a test checks that every method
+//! is served by a dev node. Only methods whose types can be both serialized and deserialized are covered.
+//! `Option` parameters are trailing parameters of the server's API and are omitted when `None`.
+//!
+//! ```no_run
+//! # extern crate futures;
+//! # extern crate parity;
+//! # use futures::Future;
+//! # fn main() {
+//! let node = parity::test_node().start().unwrap();
+//! let eth = parity::rpc_client::EthClient::new(node.rpc_handle());
+//! let block = eth.block_number().wait().unwrap();
+//! # }
+//! ```
+
+use std::fmt;
+
+use futures::Future;
+use jsonrpc_core::{self, Call, Id, MethodCall, Output, Params, Request, Response, Version};
+use parity_rpc::v1::client::{BlockNumber, Bytes, H160, H256, H512, H520, U64, U256};
+use parity_rpc::Metadata;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{self, Value};
+
+use run::RpcHandle;
+
+/// Boxed future of a typed RPC call.
+pub type BoxFuture<T> = Box<Future<Item = T, Error = Error> + Send>;
+
+/// Error of a typed RPC call.
+#[derive(Debug)]
+pub enum Error {
+	/// The node didn't respond, e.g. because it has been shut down.
+	NoResponse,
+	/// The node returned an error.
+	Rpc(jsonrpc_core::Error),
+	/// The result couldn't be decoded into the type of the method's definition.
+	Decode(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Error::NoResponse => write!(f, "No response from the node"),
+			Error::Rpc(ref e) => write!(f, "RPC error: {}", e.message),
+			Error::Decode(ref e) => write!(f, "Invalid result: {}", e),
+		}
+	}
+}
+
+/// Dispatches JSON-RPC requests to a node.
+pub trait Transport: Send + Sync {
+	/// Dispatches a request and returns the response, if any.
+	fn handle(&self, request: Request) -> Box<Future<Item = Option<Response>, Error = ()> + Send>;
+}
+
+impl Transport for RpcHandle {
+	fn handle(&self, request: Request) -> Box<Future<Item = Option<Response>, Error = ()> + Send> {
+		RpcHandle::handle(self, request, None)
+	}
+}
+
+impl Transport for jsonrpc_core::MetaIoHandler<Metadata> {
+	fn handle(&self, request: Request) -> Box<Future<Item = Option<Response>, Error = ()> + Send> {
+		Box::new(self.handle_rpc_request(request, Metadata::default()))
+	}
+}
+
+fn call<T, R>(transport: &T, method: &str, mut params: Vec<Value>) -> BoxFuture<R> where
+	T: Transport,
+	R: DeserializeOwned + Send + 'static,
+{
+	// omitted trailing parameters take the server's defaults
+	while params.last() == Some(&Value::Null) {
+		params.pop();
+	}
+
+	let request = Request::Single(Call::MethodCall(MethodCall {
+		jsonrpc: Some(Version::V2),
+		method: method.into(),
+		params: Some(Params::Array(params)),
+		id: Id::Num(1),
+	}));
+
+	Box::new(transport.handle(request)
+		.map_err(|_| Error::NoResponse)
+		.and_then(|response| match response {
+			Some(Response::Single(Output::Success(success))) => serde_json::from_value(success.result).map_err(Error::Decode),
+			Some(Response::Single(Output::Failure(failure))) => Err(Error::Rpc(failure.error)),
+			Some(Response::Batch(_)) | None => Err(Error::NoResponse),
+		}))
+}
+
+fn param<T: Serialize>(value: &T) -> Value {
+	serde_json::to_value(value).expect("RPC types are always serializable; qed")
+}
+
+macro_rules! rpc_client {
+	(
+		$(#[$doc:meta])*
+		pub struct $name:ident {
+			$(
+				$(#[$method_doc:meta])*
+				fn $method:ident($rpc_name:expr $(, $param:ident: $param_ty:ty)*) -> $result:ty;
+			)*
+		}
+	) => {
+		$(#[$doc])*
+		pub struct $name<T> {
+			transport: T,
+		}
+
+		impl<T: Transport> $name<T> {
+			/// Creates a client dispatching calls to the given transport.
+			pub fn new(transport: T) -> Self {
+				$name { transport: transport }
+			}
+
+			/// Names of the RPC methods called by this client.
+			pub fn methods() -> &'static [&'static str] {
+				&[$($rpc_name),*]
+			}
+
+			$(
+				$(#[$method_doc])*
+				pub fn $method(&self $(, $param: $param_ty)*) -> BoxFuture<$result> {
+					call(&self.transport, $rpc_name, vec![$(param(&$param)),*])
+				}
+			)*
+		}
+	}
+}
+
+rpc_client! {
+	/// Typed client of the `web3` API.
+	pub struct Web3Client {
+		/// Returns the node's version string.
+		fn client_version("web3_clientVersion") -> String;
+		/// Returns the keccak-256 hash of the given data.
+		fn sha3("web3_sha3", data: Bytes) -> H256;
+	}
+}
+
+rpc_client! {
+	/// Typed client of the `net` API.
+	pub struct NetClient {
+		/// Returns the id of the network the node is connected to.
+		fn version("net_version") -> String;
+		/// Returns the number of connected peers.
+		fn peer_count("net_peerCount") -> String;
+		/// Returns true if the node is listening for network connections.
+		fn is_listening("net_listening") -> bool;
+	}
+}
+
+rpc_client! {
+	/// Typed client of the `eth` API.
+	pub struct EthClient {
+		/// Returns the protocol version.
+		fn protocol_version("eth_protocolVersion") -> String;
+		/// Returns the number of hashes per second the node is mining with.
+		fn hashrate("eth_hashrate") -> U256;
+		/// Returns true if the node is mining.
+		fn is_mining("eth_mining") -> bool;
+		/// Returns the current gas price.
+		fn gas_price("eth_gasPrice") -> U256;
+		/// Returns the block author.
+		fn author("eth_coinbase") -> H160;
+		/// Returns the accounts of the node.
+		fn accounts("eth_accounts") -> Vec<H160>;
+		/// Returns the number of the best block.
+		fn block_number("eth_blockNumber") -> U256;
+		/// Returns the balance of an account, at the latest block if `block` is `None`.
+		fn balance("eth_getBalance", address: H160, block: Option<BlockNumber>) -> U256;
+		/// Returns a storage value of an account, at the latest block if `block` is `None`.
+		fn storage_at("eth_getStorageAt", address: H160, position: U256, block: Option<BlockNumber>) -> H256;
+		/// Returns the nonce of an account, at the latest block if `block` is `None`.
+		fn transaction_count("eth_getTransactionCount", address: H160, block: Option<BlockNumber>) -> U256;
+		/// Returns the number of transactions in the block with the given hash.
+		fn block_transaction_count_by_hash("eth_getBlockTransactionCountByHash", hash: H256) -> Option<U256>;
+		/// Returns the number of transactions in the block with the given number.
+		fn block_transaction_count_by_number("eth_getBlockTransactionCountByNumber", block: BlockNumber) -> Option<U256>;
+		/// Returns the number of uncles of the block with the given hash.
+		fn block_uncles_count_by_hash("eth_getUncleCountByBlockHash", hash: H256) -> Option<U256>;
+		/// Returns the number of uncles of the block with the given number.
+		fn block_uncles_count_by_number("eth_getUncleCountByBlockNumber", block: BlockNumber) -> Option<U256>;
+		/// Returns the code of an account, at the latest block if `block` is `None`.
+		fn code_at("eth_getCode", address: H160, block: Option<BlockNumber>) -> Bytes;
+		/// Imports a signed transaction and returns its hash.
+		fn send_raw_transaction("eth_sendRawTransaction", transaction: Bytes) -> H256;
+		/// Imports a signed transaction and returns its hash.
+		fn submit_transaction("eth_submitTransaction", transaction: Bytes) -> H256;
+		/// Returns the available compilers.
+		fn compilers("eth_getCompilers") -> Vec<String>;
+		/// Creates a filter of new blocks and returns its id.
+		fn new_block_filter("eth_newBlockFilter") -> U256;
+		/// Creates a filter of new pending transactions and returns its id.
+		fn new_pending_transaction_filter("eth_newPendingTransactionFilter") -> U256;
+	}
+}
+
+rpc_client! {
+	/// Typed client of the `parity` API.
+	pub struct ParityClient {
+		/// Returns the maximal number of transactions in the queue.
+		fn transactions_limit("parity_transactionsLimit") -> usize;
+		/// Returns the extra data of mined blocks.
+		fn extra_data("parity_extraData") -> Bytes;
+		/// Returns the gas floor target of mined blocks.
+		fn gas_floor_target("parity_gasFloorTarget") -> U256;
+		/// Returns the gas ceiling target of mined blocks.
+		fn gas_ceil_target("parity_gasCeilTarget") -> U256;
+		/// Returns the minimal gas price of transactions included in mined blocks.
+		fn min_gas_price("parity_minGasPrice") -> U256;
+		/// Returns the latest log lines.
+		fn dev_logs("parity_devLogs") -> Vec<String>;
+		/// Returns the log levels.
+		fn dev_logs_levels("parity_devLogsLevels") -> String;
+		/// Returns the name of the chain the node's network is on.
+		fn net_chain("parity_netChain") -> String;
+		/// Returns the network port.
+		fn net_port("parity_netPort") -> u16;
+		/// Returns the node name.
+		fn node_name("parity_nodeName") -> String;
+		/// Returns the default extra data.
+		fn default_extra_data("parity_defaultExtraData") -> Bytes;
+		/// Returns the number of transactions waiting for confirmation.
+		fn unsigned_transactions_count("parity_unsignedTransactionsCount") -> usize;
+		/// Returns a new secret phrase.
+		fn generate_secret_phrase("parity_generateSecretPhrase") -> String;
+		/// Returns the address of the account derived from a secret phrase.
+		fn phrase_to_address("parity_phraseToAddress", phrase: String) -> H160;
+		/// Returns the address of the registry contract, if any.
+		fn registry_address("parity_registryAddress") -> Option<H160>;
+		/// Encrypts a message with the given public key.
+		fn encrypt_message("parity_encryptMessage", key: H512, message: Bytes) -> Bytes;
+		/// Returns the URL of the dapps server.
+		fn dapps_url("parity_dappsUrl") -> String;
+		/// Returns the URL of the WebSockets server.
+		fn ws_url("parity_wsUrl") -> String;
+		/// Returns the next nonce of an account, including pending transactions.
+		fn next_nonce("parity_nextNonce", address: H160) -> U256;
+		/// Returns the operating mode.
+		fn mode("parity_mode") -> String;
+		/// Returns the chain id used for transaction signing, if any.
+		fn chain_id("parity_chainId") -> Option<U64>;
+		/// Returns the name of the chain.
+		fn chain("parity_chain") -> String;
+		/// Returns the enode URL of the node.
+		fn enode("parity_enode") -> String;
+		/// Returns the CID of the given data in IPFS.
+		fn ipfs_cid("parity_cidV0", data: Bytes) -> String;
+		/// Returns the difference between the node's clock and the network time, in milliseconds.
+		fn time_drift("parity_timeDrift") -> i64;
+	}
+}
+
+rpc_client! {
+	/// Typed client of the `personal` API.
+	pub struct PersonalClient {
+		/// Returns the accounts of the node.
+		fn accounts("personal_listAccounts") -> Vec<H160>;
+		/// Creates an account with the given password and returns its address.
+		fn new_account("personal_newAccount", password: String) -> H160;
+		/// Signs data with an account, unlocked with the given password.
+		fn sign("personal_sign", data: Bytes, address: H160, password: String) -> H520;
+		/// Returns the address of the account which signed the data.
+		fn ec_recover("personal_ecRecover", data: Bytes, signature: H520) -> H160;
+	}
+}
+
+rpc_client! {
+	/// Typed client of the `parity_accounts` API.
+	pub struct ParityAccountsClient {
+		/// Removes an address from the address book.
+		fn remove_address("parity_removeAddress", address: H160) -> bool;
+		/// Sets the name of an account or address.
+		fn set_account_name("parity_setAccountName", address: H160, name: String) -> bool;
+		/// Sets the metadata of an account or address.
+		fn set_account_meta("parity_setAccountMeta", address: H160, meta: String) -> bool;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use futures::Future;
+	use jsonrpc_core::MetaIoHandler;
+	use parity_rpc::v1::{Web3, Web3Client as Web3Server};
+	use parity_rpc::v1::client::{BlockNumber, Bytes, H256};
+	use parity_rpc::Metadata;
+	use parity_version::version;
+	use run::RpcHandle;
+	use test_node::test_node;
+	use super::{Web3Client, NetClient, EthClient, ParityClient, PersonalClient, ParityAccountsClient, Error, param};
+
+	fn web3() -> Web3Client<MetaIoHandler<Metadata>> {
+		let mut io = MetaIoHandler::default();
+		io.extend_with(Web3Server::new().to_delegate());
+		Web3Client::new(io)
+	}
+
+	#[test]
+	fn should_decode_typed_results() {
+		let client = web3();
+		assert_eq!(client.client_version().wait().unwrap(), version().replacen("/", "//", 1));
+		assert_eq!(
+			client.sha3(Bytes::new(b"hello world".to_vec())).wait().unwrap(),
+			"47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad".parse::<H256>().unwrap()
+		);
+	}
+
+	#[test]
+	fn should_return_rpc_errors() {
+		let client = EthClient::new(MetaIoHandler::<Metadata>::default());
+		match client.block_number().wait() {
+			Err(Error::Rpc(ref e)) => assert_eq!(e.message, "Method not found"),
+			other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+		}
+	}
+
+	#[test]
+	fn should_list_called_methods() {
+		assert_eq!(Web3Client::<MetaIoHandler<Metadata>>::methods(), &["web3_clientVersion", "web3_sha3"]);
+		assert_eq!(param(&Some(BlockNumber::Latest)), json!("latest"));
+	}
+
+	#[test]
+	fn should_only_call_methods_served_by_the_node() {
+		let node = test_node().start().unwrap();
+		let methods = Web3Client::<RpcHandle>::methods().iter()
+			.chain(NetClient::<RpcHandle>::methods())
+			.chain(EthClient::<RpcHandle>::methods())
+			.chain(ParityClient::<RpcHandle>::methods())
+			.chain(PersonalClient::<RpcHandle>::methods())
+			.chain(ParityAccountsClient::<RpcHandle>::methods());
+
+		for method in methods {
+			// parameters are left out, the node has to know the method to complain about them
+			if let Err(e) = node.call(method, json!([])) {
+				assert!(!e.contains("Method not found"), "{} is not served by the node", method);
+			}
+		}
+	}
+}
@@ -15,42 +15,55 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::any::Any;
+use std::collections::HashSet;
 use std::fmt;
-use std::sync::{Arc, Weak};
+use std::fs;
+use std::sync::{mpsc, Arc, Weak};
 use std::time::{Duration, Instant};
 use std::thread;
 
 use ansi_term::Colour;
+use ethereum_types::H512;
 use ethcore::account_provider::{AccountProvider, AccountProviderSettings};
-use ethcore::client::{Client, Mode, DatabaseCompactionProfile, VMType, BlockChainClient, BlockInfo};
+use ethcore::client::{Client, Mode, DatabaseCompactionProfile, VMType, BlockChainClient, BlockId, BlockInfo, BloomsConfig, ReceiptsStorage, TransactionIndex, ExplorerIndex};
 use ethcore::ethstore::ethkey;
 use ethcore::miner::{stratum, Miner, MinerService, MinerOptions};
 use ethcore::snapshot;
-use ethcore::spec::{SpecParams, OptimizeFor};
+use ethcore::spec::{Spec, SpecParams, OptimizeFor};
 use ethcore::verification::queue::VerifierSettings;
 use ethcore_logger::{Config as LogConfig, RotatingLogger};
 use ethcore_service::ClientService;
 use sync::{self, SyncConfig};
 use miner::work_notify::WorkPoster;
+use futures::{future, Future};
 use futures_cpupool::CpuPool;
 use hash_fetch::{self, fetch};
-use informant::{Informant, LightNodeInformantData, FullNodeInformantData};
+use fork_alert::ForkAlertPoster;
+use reorg_alert::ReorgAlertPoster;
+use block_hooks::{BlockHooks, HooksConfig};
+use disk_monitor::DiskSpaceMonitor;
+use drift_guard::DriftGuard;
+use signer_notify::SignerNotifier;
+use systemd;
+use informant::{Column as InformantColumn, DbSize, Informant, InformantConfig, LightNodeInformantData, FullNodeInformantData};
 use journaldb::Algorithm;
 use light::Cache as LightDataCache;
 use miner::external::ExternalMiner;
 use node_filter::NodeFilter;
 use node_health;
 use parity_reactor::EventLoop;
-use parity_rpc::{Origin, Metadata, NetworkSettings, informant, is_major_importing};
+use parity_rpc::{Origin, Metadata, NetworkSettings, PubSubSession, drain, informant, is_major_importing};
+use parity_rpc::names::NameResolver;
 use updater::{UpdatePolicy, Updater};
 use parity_version::version;
+use parking_lot::Mutex;
 use ethcore_private_tx::{ProviderConfig, EncryptorConfig, SecretStoreEncryptor};
 use params::{
-	SpecType, Pruning, AccountsConfig, GasPricerConfig, MinerExtras, Switch,
-	tracing_switch_to_bool, fatdb_switch_to_bool, mode_switch_to_bool
+	SpecType, Pruning, AccountsConfig, GasPricerConfig, MinerExtras, Switch, DevAccounts,
+	tracing_switch_to_bool, fatdb_switch_to_bool, mode_switch_to_bool, receipts_storage_switch
 };
 use helpers::{to_client_config, execute_upgrades, passwords_from_files};
-use upgrade::upgrade_key_location;
+use upgrade::{upgrade_key_location, upgrade_address_book_location};
 use dir::{Directories, DatabaseDirectories};
 use cache::CacheConfig;
 use user_defaults::UserDefaults;
@@ -60,10 +73,11 @@ use jsonrpc_core;
 use modules;
 use rpc;
 use rpc_apis;
+use serde_json;
 use secretstore;
 use signer;
 use db;
-use ethkey::Password;
+use ethkey::{KeyPair, Password};
 
 // how often to take periodic snapshots.
 const SNAPSHOT_PERIOD: u64 = 5000;
@@ -93,6 +107,8 @@ pub struct RunCmd {
 	pub gas_price_percentile: usize,
 	pub poll_lifetime: u32,
 	pub ntp_servers: Vec<String>,
+	pub max_time_drift: i64,
+	pub strict_time_drift: bool,
 	pub ws_conf: rpc::WsConfiguration,
 	pub http_conf: rpc::HttpConfiguration,
 	pub ipc_conf: rpc::IpcConfiguration,
@@ -104,11 +120,16 @@ pub struct RunCmd {
 	pub gas_pricer_conf: GasPricerConfig,
 	pub miner_extras: MinerExtras,
 	pub update_policy: UpdatePolicy,
+	pub hash_fetch_mirrors: Vec<String>,
+	pub blob_store_size: u64,
+	pub informant: InformantConfig,
 	pub mode: Option<Mode>,
 	pub tracing: Switch,
+	pub tracing_state_diffs: bool,
 	pub fat_db: Switch,
 	pub compaction: DatabaseCompactionProfile,
 	pub wal: bool,
+	pub db_auto_repair: bool,
 	pub vm_type: VMType,
 	pub geth_compatibility: bool,
 	pub net_settings: NetworkSettings,
@@ -131,6 +152,32 @@ pub struct RunCmd {
 	pub no_persistent_txqueue: bool,
 	pub whisper: ::whisper::Config,
 	pub no_hardcoded_sync: bool,
+	pub fork_alert_depth: Option<u64>,
+	pub fork_alert_webhook: Option<String>,
+	pub node_permission_overrides: Option<String>,
+	pub max_reorg_depth: Option<u64>,
+	pub finality_depth: u64,
+	pub state_query_history: Option<u64>,
+	pub ancient_blocks_threshold: Option<u64>,
+	pub blooms: BloomsConfig,
+	pub receipts_storage: Option<ReceiptsStorage>,
+	pub tx_index: Option<TransactionIndex>,
+	pub explorer_index: bool,
+	pub resolve_names: bool,
+	pub dev_accounts: Option<DevAccounts>,
+	pub reorg_alert_webhook: Option<String>,
+	pub hooks: HooksConfig,
+	pub ui_auto_approve: Option<String>,
+	pub signer_notify_url: Option<String>,
+	pub sync_stall_timeout: Option<u64>,
+	pub sync_stall_rotate_peers: usize,
+	pub sync_stall_refresh_discovery: bool,
+	pub head_first_sync: bool,
+	pub tx_propagation: sync::TransactionPropagation,
+	pub tx_propagation_peers: HashSet<H512>,
+	pub rpc_prefix: Option<String>,
+	pub health_readiness: node_health::ReadinessThresholds,
+	pub min_disk_space: u64,
 }
 
 // node info fetcher for the local store.
@@ -158,13 +205,30 @@ impl ::local_store::NodeInfo for FullNodeInfo {
 type LightClient = ::light::client::Client<::light_helpers::EpochFetch>;
 
 // helper for light execution.
-fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<RunningClient, String> {
+/// Event loop and thread pool, shared by all clients running in the process.
+#[derive(Clone)]
+struct Runtime {
+	event_loop: Arc<EventLoop>,
+	cpu_pool: CpuPool,
+}
+
+impl Runtime {
+	fn new() -> Self {
+		Runtime {
+			event_loop: Arc::new(EventLoop::spawn()),
+			cpu_pool: CpuPool::new(4),
+		}
+	}
+}
+
+fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>, runtime: Runtime) -> Result<RunningClient, String> {
 	use light::client as light_client;
 	use sync::{LightSyncParams, LightSync, ManageNetwork};
 	use parking_lot::{Mutex, RwLock};
 
 	// load spec
-	let spec = cmd.spec.spec(SpecParams::new(cmd.dirs.cache.as_ref(), OptimizeFor::Memory))?;
+	let mut spec = cmd.spec.spec(SpecParams::new(cmd.dirs.cache.as_ref(), OptimizeFor::Memory))?;
+	let dev_key_pairs = prefund_dev_accounts(cmd.dev_accounts.as_ref(), &mut spec)?;
 
 	// load genesis hash
 	let genesis_hash = spec.genesis_header().hash();
@@ -191,6 +255,9 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	print_running_environment(&spec.name, &cmd.dirs, &db_dirs, &cmd.dapps_conf);
 
 	info!("Running in experimental {} mode.", Colour::Blue.bold().paint("Light Client"));
+	if cmd.resolve_names {
+		warn!("--resolve-names is not supported by the light client, names won't be resolved.");
+	}
 
 	// TODO: configurable cache size.
 	let cache = LightDataCache::new(Default::default(), Duration::from_secs(60 * GAS_CORPUS_EXPIRATION_MINUTES));
@@ -203,6 +270,7 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 		verify_full: true,
 		check_seal: cmd.check_seal,
 		no_hardcoded_sync: cmd.no_hardcoded_sync,
+		finality_depth: cmd.finality_depth,
 	};
 
 	config.queue.max_mem_use = cmd.cache_config.queue() as usize * 1024 * 1024;
@@ -221,7 +289,8 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	let db = db::open_db(&db_dirs.client_path(algorithm).to_str().expect("DB path could not be converted to string."),
 						 &cmd.cache_config,
 						 &cmd.compaction,
-						 cmd.wal).map_err(|e| format!("Failed to open database {:?}", e))?;
+						 cmd.wal,
+						 cmd.db_auto_repair).map_err(|e| format!("Failed to open database {:?}", e))?;
 
 	let service = light_client::Service::start(config, &spec, fetch, db, cache.clone())
 		.map_err(|e| format!("Error starting light client: {}", e))?;
@@ -238,7 +307,7 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 
 	let mut attached_protos = Vec::new();
 	let whisper_factory = if cmd.whisper.enabled {
-		let whisper_factory = ::whisper::setup(cmd.whisper.target_message_pool_size, &mut attached_protos)
+		let whisper_factory = ::whisper::setup(&cmd.whisper, &mut attached_protos)
 			.map_err(|e| format!("Failed to initialize whisper: {}", e))?;
 		whisper_factory
 	} else {
@@ -259,8 +328,8 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	let light_sync = Arc::new(light_sync);
 	*sync_handle.write() = Arc::downgrade(&light_sync);
 
-	// spin up event loop
-	let event_loop = EventLoop::spawn();
+	// event loop is shared with other chains running in the process.
+	let event_loop = runtime.event_loop.clone();
 
 	// queue cull service.
 	let queue_cull = Arc::new(::light_helpers::QueueCull {
@@ -276,18 +345,25 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	// start the network.
 	light_sync.start_network();
 
-	let cpu_pool = CpuPool::new(4);
+	let cpu_pool = runtime.cpu_pool.clone();
 
 	// fetch service
 	let fetch = fetch::Client::new().map_err(|e| format!("Error starting fetch client: {:?}", e))?;
+	let blob_store = Arc::new(hash_fetch::BlobStore::open(cmd.dirs.blobs_path(), cmd.blob_store_size)
+		.map_err(|e| format!("Error opening blob store: {}", e))?);
 	let passwords = passwords_from_files(&cmd.acc_conf.password_files)?;
 
 	// prepare account provider
-	let account_provider = Arc::new(prepare_account_provider(&cmd.spec, &cmd.dirs, &spec.data_dir, cmd.acc_conf, &passwords)?);
+	let account_provider = Arc::new(prepare_account_provider(&cmd.spec, &cmd.dirs, &spec.data_dir, cmd.acc_conf, &dev_key_pairs, &passwords)?);
 	let rpc_stats = Arc::new(informant::RpcStats::default());
+	let rpc_drain = Arc::new(drain::RpcDrain::default());
 
 	// the dapps server
-	let signer_service = Arc::new(signer::new_service(&cmd.ws_conf, &cmd.logger_config));
+	let signer_service = Arc::new(signer::new_service(&cmd.ws_conf, &cmd.logger_config, cmd.ui_auto_approve.as_ref(), &passwords)?
+		.with_account_names(account_provider.clone()));
+	if let Some(ref url) = cmd.signer_notify_url {
+		SignerNotifier::new(url, fetch.clone(), event_loop.remote())?.attach(&signer_service);
+	}
 	let (node_health, dapps_deps) = {
 		let contract_client = ::dapps::LightRegistrar {
 			client: client.clone(),
@@ -295,7 +371,7 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 			on_demand: on_demand.clone(),
 		};
 
-		struct LightSyncStatus(Arc<LightSync>);
+		struct LightSyncStatus(Arc<LightSync>, Arc<LightClient>);
 		impl fmt::Debug for LightSyncStatus {
 			fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 				write!(fmt, "Light Sync Status")
@@ -307,13 +383,19 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 				let peers = sync::LightSyncProvider::peer_numbers(&*self.0);
 				(peers.connected, peers.max)
 			}
+			fn blocks_behind(&self) -> Option<u64> {
+				let best = self.1.chain_info().best_block_number;
+				self.0.highest_block().map(|highest| highest.saturating_sub(best))
+			}
+			fn disk_space(&self) -> Option<(u64, bool)> { None }
 		}
 
-		let sync_status = Arc::new(LightSyncStatus(light_sync.clone()));
+		let sync_status = Arc::new(LightSyncStatus(light_sync.clone(), client.clone()));
 		let node_health = node_health::NodeHealth::new(
 			sync_status.clone(),
-			node_health::TimeChecker::new(&cmd.ntp_servers, cpu_pool.clone()),
+			node_health::TimeChecker::new(&cmd.ntp_servers, cpu_pool.clone()).with_max_drift(cmd.max_time_drift),
 			event_loop.remote(),
+			cmd.health_readiness,
 		);
 
 		(node_health.clone(), dapps::Dependencies {
@@ -323,6 +405,7 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 			fetch: fetch.clone(),
 			pool: cpu_pool.clone(),
 			signer: signer_service.clone(),
+			blob_store: blob_store.clone(),
 		})
 	};
 
@@ -342,6 +425,7 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 		on_demand: on_demand,
 		cache: cache.clone(),
 		transaction_queue: txq,
+		registrar: dapps_deps.contract_client.clone(),
 		dapps_service: dapps_service,
 		dapps_address: cmd.dapps_conf.address(cmd.http_conf.address()),
 		ws_address: cmd.ws_conf.address(),
@@ -352,18 +436,22 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 		whisper_rpc: whisper_factory,
 		private_tx_service: None, //TODO: add this to client.
 		gas_price_percentile: cmd.gas_price_percentile,
-		poll_lifetime: cmd.poll_lifetime
+		poll_lifetime: cmd.poll_lifetime,
+		rpc_drain: rpc_drain.clone(),
 	});
 
 	let dependencies = rpc::Dependencies {
 		apis: deps_for_rpc_apis.clone(),
 		remote: event_loop.raw_remote(),
 		stats: rpc_stats.clone(),
+		drain: rpc_drain.clone(),
 		pool: if cmd.http_conf.processing_threads > 0 {
 			Some(rpc::CpuPool::new(cmd.http_conf.processing_threads))
 		} else {
 			None
 		},
+		lanes: cmd.http_conf.lanes(),
+		names: None,
 	};
 
 	// start rpc servers
@@ -371,6 +459,8 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	let ws_server = rpc::new_ws(cmd.ws_conf, &dependencies)?;
 	let http_server = rpc::new_http("HTTP JSON-RPC", "jsonrpc", cmd.http_conf.clone(), &dependencies, dapps_middleware)?;
 	let ipc_server = rpc::new_ipc(cmd.ipc_conf, &dependencies)?;
+	let ws_server = rpc::close_on_drain(&rpc_drain, ws_server, rpc::WsServer::close);
+	let http_server = rpc::close_on_drain(&rpc_drain, http_server, rpc::HttpServer::close);
 
 	// the informant
 	let informant = Arc::new(Informant::new(
@@ -382,13 +472,18 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 		None,
 		Some(rpc_stats),
 		cmd.logger_config.color,
+		cmd.informant.clone(),
 	));
 	service.add_notify(informant.clone());
 	service.register_handler(informant.clone()).map_err(|_| "Unable to register informant handler".to_owned())?;
 
+	if cmd.informant.systemd_notify {
+		systemd::notify("READY=1");
+	}
+
 	Ok(RunningClient {
 		inner: RunningClientInner::Light {
-			rpc: rpc_direct,
+			rpc: RpcHandle { inner: RpcHandleInner::Light(Arc::new(rpc_direct)) },
 			informant,
 			client,
 			keep_alive: Box::new((event_loop, service, ws_server, http_server, ipc_server)),
@@ -396,13 +491,14 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	})
 }
 
-fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq: Cr,
+fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, runtime: Runtime, on_client_rq: Cr,
 						on_updater_rq: Rr) -> Result<RunningClient, String>
 	where Cr: Fn(String) + 'static + Send,
 		  Rr: Fn() + 'static + Send
 {
 	// load spec
-	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
+	let mut spec = cmd.spec.spec(&cmd.dirs.cache)?;
+	let dev_key_pairs = prefund_dev_accounts(cmd.dev_accounts.as_ref(), &mut spec)?;
 
 	// load genesis hash
 	let genesis_hash = spec.genesis_header().hash();
@@ -428,6 +524,12 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	// get the mode
 	let mode = mode_switch_to_bool(cmd.mode, &user_defaults)?;
 	trace!(target: "mode", "mode is {:?}", mode);
+
+	// check how receipts are stored
+	let receipts_storage = receipts_storage_switch(cmd.receipts_storage, &user_defaults)?;
+
+	// get the transaction index policy
+	let tx_index = cmd.tx_index.unwrap_or(user_defaults.tx_index);
 	let network_enabled = match mode { Mode::Dark(_) | Mode::Off => false, _ => true, };
 
 	// get the update policy
@@ -504,19 +606,28 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	};
 	sync_config.download_old_blocks = cmd.download_old_blocks;
 	sync_config.serve_light = cmd.serve_light;
+	sync_config.fork_alert_depth = cmd.fork_alert_depth;
+	sync_config.stall_timeout = cmd.sync_stall_timeout.map(Duration::from_secs);
+	sync_config.stall_rotate_peers = cmd.sync_stall_rotate_peers;
+	sync_config.stall_refresh_discovery = cmd.sync_stall_refresh_discovery;
+	sync_config.head_first = cmd.head_first_sync;
+	sync_config.tx_propagation = cmd.tx_propagation;
+	sync_config.tx_propagation_peers = cmd.tx_propagation_peers.clone();
 
 	let passwords = passwords_from_files(&cmd.acc_conf.password_files)?;
 
 	// prepare account provider
-	let account_provider = Arc::new(prepare_account_provider(&cmd.spec, &cmd.dirs, &spec.data_dir, cmd.acc_conf, &passwords)?);
+	let account_provider = Arc::new(prepare_account_provider(&cmd.spec, &cmd.dirs, &spec.data_dir, cmd.acc_conf, &dev_key_pairs, &passwords)?);
 
-	let cpu_pool = CpuPool::new(4);
+	let cpu_pool = runtime.cpu_pool.clone();
 
-	// spin up event loop
-	let event_loop = EventLoop::spawn();
+	// event loop is shared with other chains running in the process.
+	let event_loop = runtime.event_loop.clone();
 
 	// fetch service
 	let fetch = fetch::Client::new().map_err(|e| format!("Error starting fetch client: {:?}", e))?;
+	let blob_store = Arc::new(hash_fetch::BlobStore::open(cmd.dirs.blobs_path(), cmd.blob_store_size)
+		.map_err(|e| format!("Error opening blob store: {}", e))?);
 
 	// create miner
 	let miner = Arc::new(Miner::new(
@@ -551,6 +662,14 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		}
 	}
 
+	if let Some(seal_mode) = cmd.miner_extras.seal_mode {
+		match spec.engine.as_instant_seal() {
+			Some(engine) => engine.set_mode(seal_mode)
+				.map_err(|e| format!("Failed to set the sealing mode: {}", e))?,
+			None => warn!("The --dev-block-time and --dev-manual-seal flags have no effect on chains not using the instant seal engine."),
+		}
+	}
+
 	// display warning if using --no-hardcoded-sync
 	if cmd.no_hardcoded_sync {
 		warn!("The --no-hardcoded-sync flag has no effect if you don't use --light");
@@ -574,6 +693,18 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	);
 
 	client_config.queue.verifier_settings = cmd.verifier_settings;
+	client_config.max_reorg_depth = cmd.max_reorg_depth;
+	client_config.finality_depth = cmd.finality_depth;
+	client_config.state_query_history = cmd.state_query_history;
+	client_config.ancient_blocks_threshold = cmd.ancient_blocks_threshold;
+	client_config.blooms = cmd.blooms;
+	client_config.blockchain.receipts_storage = receipts_storage;
+	client_config.blockchain.tx_index = tx_index;
+	client_config.db_auto_repair = cmd.db_auto_repair;
+	client_config.tracing.state_diffs = tracing && cmd.tracing_state_diffs;
+	if cmd.tracing_state_diffs && !tracing {
+		warn!("State diffs are not recorded because tracing is disabled. Use --tracing on to enable it.");
+	}
 
 	// set up bootnodes
 	let mut net_conf = cmd.net_conf;
@@ -586,7 +717,7 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 
 	let restoration_db_handler = db::restoration_db_handler(&client_path, &client_config);
 	let client_db = restoration_db_handler.open(&client_path)
-		.map_err(|e| format!("Failed to open database {:?}", e))?;
+		.map_err(|e| format!("Failed to open database: {}", e))?;
 
 	// create client service.
 	let service = ClientService::start(
@@ -608,13 +739,50 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 
 	// take handle to client
 	let client = service.client();
+
+	// bring the transaction index in line with its policy before any block is imported.
+	client.update_transaction_index(user_defaults.tx_index)
+		.map_err(|e| format!("Failed to update the transaction index: {}", e))?;
 	// Update miners block gas limit
 	miner.update_transaction_queue_limits(*client.best_block_header().gas_limit());
 
+	// resolve registry names passed in place of addresses
+	let names = match cmd.resolve_names {
+		true => Some(Arc::new(NameResolver::with_client(client.clone()))),
+		false => None,
+	};
+	if let Some(ref name) = cmd.miner_extras.author_name {
+		let author = names.as_ref()
+			.and_then(|names| names.resolve(name, BlockId::Latest))
+			.ok_or_else(|| format!("Author {} is not registered in the chain's registrar.", name))?;
+		info!("Resolved author {} to {}", name, author);
+		// the engine signer takes precedence, like with the author given as an address
+		if engine_signer == Default::default() {
+			miner.set_author(author, None).expect("Fails only if password is Some; password is None; qed");
+		}
+	}
+
 	// take handle to private transactions service
 	let private_tx_service = service.private_tx_service();
 	let private_tx_provider = private_tx_service.provider();
-	let connection_filter = connection_filter_address.map(|a| Arc::new(NodeFilter::new(Arc::downgrade(&client) as Weak<BlockChainClient>, a)));
+	let connection_filter = match connection_filter_address {
+		Some(address) => {
+			let filter = NodeFilter::new(Arc::downgrade(&client) as Weak<BlockChainClient>, address);
+			let filter = match cmd.node_permission_overrides {
+				Some(ref path) => filter.with_overrides(path.into())?,
+				None => filter,
+			};
+			let filter = Arc::new(filter);
+			service.add_notify(filter.clone());
+			Some(filter)
+		},
+		None => {
+			if cmd.node_permission_overrides.is_some() {
+				warn!("The --node-permission-overrides flag has no effect: the chain doesn't use a node permissions contract.");
+			}
+			None
+		},
+	};
 	let snapshot_service = service.snapshot_service();
 
 	// initialize the local node information store.
@@ -655,6 +823,17 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	// register it as an IO service to update periodically.
 	service.register_io_handler(store).map_err(|_| "Unable to register local store handler".to_owned())?;
 
+	// pause block import before the database disk fills up.
+	let disk_monitor = match cmd.min_disk_space {
+		0 => None,
+		min_disk_space => {
+			let threshold = min_disk_space.saturating_mul(1024 * 1024);
+			let monitor = Arc::new(DiskSpaceMonitor::new(client_path.clone(), threshold, Arc::downgrade(&client)));
+			service.register_io_handler(monitor.clone()).map_err(|_| "Unable to register disk space monitor".to_owned())?;
+			Some(monitor)
+		},
+	};
+
 	// create external miner
 	let external_miner = Arc::new(ExternalMiner::default());
 
@@ -664,10 +843,21 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 			.map_err(|e| format!("Stratum start error: {:?}", e))?;
 	}
 
+	let fork_alert_handler = match cmd.fork_alert_webhook {
+		Some(ref url) if cmd.fork_alert_depth.is_some() => {
+			Some(Arc::new(ForkAlertPoster::new(url, fetch.clone(), event_loop.remote())?) as Arc<::sync::ForkAlertHandler>)
+		},
+		Some(_) => {
+			warn!("The --fork-alert-webhook flag has no effect without --fork-alert-depth");
+			None
+		},
+		None => None,
+	};
+
 	let mut attached_protos = Vec::new();
 
 	let whisper_factory = if cmd.whisper.enabled {
-		let whisper_factory = ::whisper::setup(cmd.whisper.target_message_pool_size, &mut attached_protos)
+		let whisper_factory = ::whisper::setup(&cmd.whisper, &mut attached_protos)
 			.map_err(|e| format!("Failed to initialize whisper: {}", e))?;
 
 		whisper_factory
@@ -686,10 +876,47 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		&cmd.logger_config,
 		attached_protos,
 		connection_filter.clone().map(|f| f as Arc<::sync::ConnectionFilter + 'static>),
+		fork_alert_handler,
 	).map_err(|e| format!("Sync error: {}", e))?;
 
 	service.add_notify(chain_notify.clone());
 
+	match cmd.reorg_alert_webhook {
+		Some(ref url) if cmd.max_reorg_depth.is_some() => {
+			service.add_notify(Arc::new(ReorgAlertPoster::new(url, fetch.clone(), event_loop.remote())?));
+		},
+		Some(_) => warn!("The --reorg-alert-webhook flag has no effect without --max-reorg-depth"),
+		None => {},
+	}
+
+	if cmd.hooks.is_enabled() {
+		let sync = sync_provider.clone();
+		let importing_client = client.clone();
+		let hooks = BlockHooks::new(
+			cmd.hooks.clone(),
+			Arc::downgrade(&client),
+			move || is_major_importing(Some(sync.status().state), importing_client.queue_info()),
+		)?;
+		service.add_notify(Arc::new(hooks));
+	}
+
+	let explorer_index = match cmd.explorer_index {
+		true => {
+			let db = db::open_explorer_db(&db_dirs.explorer_path(algorithm))?;
+			let chain = client.clone() as Arc<BlockChainClient>;
+			let index = Arc::new(ExplorerIndex::new(db, Arc::downgrade(&chain)));
+			service.add_notify(index.clone());
+
+			let catch_up = index.clone();
+			thread::Builder::new()
+				.name("explorer-index".into())
+				.spawn(move || catch_up.catch_up())
+				.map_err(|e| format!("Unable to start the explorer index: {}", e))?;
+			Some(index)
+		},
+		false => None,
+	};
+
 	// provider not added to a notification center is effectively disabled
 	// TODO [debris] refactor it later on
 	if cmd.private_tx_enabled {
@@ -713,19 +940,26 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		Arc::downgrade(&sync_provider),
 		update_policy,
 		hash_fetch::Client::with_fetch(contract_client.clone(), cpu_pool.clone(), updater_fetch, event_loop.remote())
+			.with_mirrors(cmd.hash_fetch_mirrors.clone())
+			.with_store(blob_store.clone())
 	);
 	service.add_notify(updater.clone());
 
 	// set up dependencies for rpc servers
 	let rpc_stats = Arc::new(informant::RpcStats::default());
+	let rpc_drain = Arc::new(drain::RpcDrain::default());
 	let secret_store = account_provider.clone();
-	let signer_service = Arc::new(signer::new_service(&cmd.ws_conf, &cmd.logger_config));
+	let signer_service = Arc::new(signer::new_service(&cmd.ws_conf, &cmd.logger_config, cmd.ui_auto_approve.as_ref(), &passwords)?
+		.with_account_names(account_provider.clone()));
+	if let Some(ref url) = cmd.signer_notify_url {
+		SignerNotifier::new(url, fetch.clone(), event_loop.remote())?.attach(&signer_service);
+	}
 
 	// the dapps server
 	let (node_health, dapps_deps) = {
 		let (sync, client) = (sync_provider.clone(), client.clone());
 
-		struct SyncStatus(Arc<sync::SyncProvider>, Arc<Client>, sync::NetworkConfiguration);
+		struct SyncStatus(Arc<sync::SyncProvider>, Arc<Client>, sync::NetworkConfiguration, Option<Arc<DiskSpaceMonitor>>);
 		impl fmt::Debug for SyncStatus {
 			fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 				write!(fmt, "Dapps Sync Status")
@@ -739,13 +973,21 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 				let status = self.0.status();
 				(status.num_peers, status.current_max_peers(self.2.min_peers, self.2.max_peers) as usize)
 			}
+			fn blocks_behind(&self) -> Option<u64> {
+				let best = self.1.chain_info().best_block_number;
+				self.0.status().highest_block_number.map(|highest| highest.saturating_sub(best))
+			}
+			fn disk_space(&self) -> Option<(u64, bool)> {
+				self.3.as_ref().and_then(|monitor| monitor.status())
+			}
 		}
 
-		let sync_status = Arc::new(SyncStatus(sync, client, net_conf));
+		let sync_status = Arc::new(SyncStatus(sync, client, net_conf, disk_monitor));
 		let node_health = node_health::NodeHealth::new(
 			sync_status.clone(),
-			node_health::TimeChecker::new(&cmd.ntp_servers, cpu_pool.clone()),
+			node_health::TimeChecker::new(&cmd.ntp_servers, cpu_pool.clone()).with_max_drift(cmd.max_time_drift),
 			event_loop.remote(),
+			cmd.health_readiness,
 		);
 		(node_health.clone(), dapps::Dependencies {
 			sync_status,
@@ -754,8 +996,16 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 			fetch: fetch.clone(),
 			pool: cpu_pool.clone(),
 			signer: signer_service.clone(),
+			blob_store: blob_store.clone(),
 		})
 	};
+
+	// refuse to seal blocks with a skewed clock.
+	if cmd.strict_time_drift {
+		let guard = Arc::new(DriftGuard::new(node_health.clone(), miner.clone(), event_loop.remote()));
+		service.register_io_handler(guard).map_err(|_| "Unable to register time drift guard".to_owned())?;
+	}
+
 	let dapps_middleware = dapps::new(cmd.dapps_conf.clone(), dapps_deps.clone())?;
 
 	let dapps_service = dapps::service(&dapps_middleware);
@@ -773,6 +1023,8 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		settings: Arc::new(cmd.net_settings.clone()),
 		net_service: manage_network.clone(),
 		updater: updater.clone(),
+		registrar: dapps_deps.contract_client.clone(),
+		node_filter: connection_filter,
 		geth_compatibility: cmd.geth_compatibility,
 		dapps_service: dapps_service,
 		dapps_address: cmd.dapps_conf.address(cmd.http_conf.address()),
@@ -784,17 +1036,22 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		private_tx_service: Some(private_tx_service.clone()),
 		gas_price_percentile: cmd.gas_price_percentile,
 		poll_lifetime: cmd.poll_lifetime,
+		rpc_drain: rpc_drain.clone(),
+		explorer_index: explorer_index,
 	});
 
 	let dependencies = rpc::Dependencies {
 		apis: deps_for_rpc_apis.clone(),
 		remote: event_loop.raw_remote(),
 		stats: rpc_stats.clone(),
+		drain: rpc_drain.clone(),
 		pool: if cmd.http_conf.processing_threads > 0 {
 			Some(rpc::CpuPool::new(cmd.http_conf.processing_threads))
 		} else {
 			None
 		},
+		lanes: cmd.http_conf.lanes(),
+		names: names,
 
 	};
 
@@ -803,6 +1060,8 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	let ws_server = rpc::new_ws(cmd.ws_conf.clone(), &dependencies)?;
 	let ipc_server = rpc::new_ipc(cmd.ipc_conf, &dependencies)?;
 	let http_server = rpc::new_http("HTTP JSON-RPC", "jsonrpc", cmd.http_conf.clone(), &dependencies, dapps_middleware)?;
+	let ws_server = rpc::close_on_drain(&rpc_drain, ws_server, rpc::WsServer::close);
+	let http_server = rpc::close_on_drain(&rpc_drain, http_server, rpc::HttpServer::close);
 
 	// secret store key server
 	let secretstore_deps = secretstore::Dependencies {
@@ -815,7 +1074,7 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	let secretstore_key_server = secretstore::start(cmd.secretstore_conf.clone(), secretstore_deps)?;
 
 	// the ipfs server
-	let ipfs_server = ipfs::start_server(cmd.ipfs_conf.clone(), client.clone())?;
+	let ipfs_server = ipfs::start_server(cmd.ipfs_conf.clone(), db_dirs.ipfs_path(), client.clone())?;
 
 	// the informant
 	let informant = Arc::new(Informant::new(
@@ -823,10 +1082,15 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 			client: service.client(),
 			sync: Some(sync_provider.clone()),
 			net: Some(manage_network.clone()),
+			db_size: match cmd.informant.columns.contains(&InformantColumn::DbSize) {
+				true => Some(DbSize::new(client_path.clone())),
+				false => None,
+			},
 		},
 		Some(snapshot_service.clone()),
 		Some(rpc_stats.clone()),
 		cmd.logger_config.color,
+		cmd.informant.clone(),
 	));
 	service.add_notify(informant.clone());
 	service.register_io_handler(informant.clone()).map_err(|_| "Unable to register informant handler".to_owned())?;
@@ -837,6 +1101,8 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	user_defaults.tracing = tracing;
 	user_defaults.fat_db = fat_db;
 	user_defaults.mode = mode;
+	user_defaults.receipts_storage = receipts_storage;
+	user_defaults.tx_index = tx_index;
 	user_defaults.save(&user_defaults_path)?;
 
 	// tell client how to save the default mode if it gets changed.
@@ -869,9 +1135,13 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	client.set_exit_handler(on_client_rq);
 	updater.set_exit_handler(on_updater_rq);
 
+	if cmd.informant.systemd_notify {
+		systemd::notify("READY=1");
+	}
+
 	Ok(RunningClient {
 		inner: RunningClientInner::Full {
-			rpc: rpc_direct,
+			rpc: RpcHandle { inner: RpcHandleInner::Full(Arc::new(rpc_direct)) },
 			informant,
 			client,
 			client_service: Arc::new(service),
@@ -890,37 +1160,61 @@ pub struct RunningClient {
 
 enum RunningClientInner {
 	Light {
-		rpc: jsonrpc_core::MetaIoHandler<Metadata, informant::Middleware<rpc_apis::LightClientNotifier>>,
+		rpc: RpcHandle,
 		informant: Arc<Informant<LightNodeInformantData>>,
 		client: Arc<LightClient>,
 		keep_alive: Box<Any>,
 	},
 	Full {
-		rpc: jsonrpc_core::MetaIoHandler<Metadata, informant::Middleware<informant::ClientNotifier>>,
+		rpc: RpcHandle,
 		informant: Arc<Informant<FullNodeInformantData>>,
 		client: Arc<Client>,
 		client_service: Arc<ClientService>,
 		keep_alive: Box<Any>,
 	},
+	/// Several chains running in one process with their RPC prefixes, the first of which is the primary one.
+	Multi(Vec<(Option<String>, RunningClient)>),
+	/// Client which is re-initialized in-process when asked to switch chains.
+	Restartable {
+		rpc: Arc<Mutex<Option<RpcHandle>>>,
+		requests: Mutex<mpsc::Sender<Option<String>>>,
+		supervisor: thread::JoinHandle<()>,
+	},
 }
 
 impl RunningClient {
+	/// Returns a handle dispatching JSON-RPC requests to the client in-process, without going through
+	/// the HTTP, WebSockets or IPC servers. All APIs are available.
+	///
+	/// When several chains run in one process, methods prefixed with a chain's `--rpc-prefix`
+	/// (e.g. `kovan:eth_blockNumber`) are routed to that chain and unprefixed ones to the primary chain.
+	///
+	/// Handles should be dropped before calling `shutdown()`, which waits for the client to be released.
+	pub fn rpc_handle(&self) -> RpcHandle {
+		match self.inner {
+			RunningClientInner::Light { ref rpc, .. } => rpc.clone(),
+			RunningClientInner::Full { ref rpc, .. } => rpc.clone(),
+			RunningClientInner::Multi(ref clients) => RpcHandle {
+				inner: RpcHandleInner::Multi(Arc::new(clients.iter()
+					.map(|&(ref prefix, ref client)| (prefix.clone(), client.rpc_handle()))
+					.collect())),
+			},
+			RunningClientInner::Restartable { ref rpc, .. } => rpc.lock().clone()
+				.unwrap_or(RpcHandle { inner: RpcHandleInner::Stopped }),
+		}
+	}
+
 	/// Performs a synchronous RPC query.
 	/// Blocks execution until the result is ready.
 	pub fn rpc_query_sync(&self, request: &str) -> Option<String> {
-		let metadata = Metadata {
-			origin: Origin::CApi,
-			session: None,
-		};
+		self.rpc_handle().request(request, None).wait().unwrap_or(None)
+	}
 
-		match self.inner {
-			RunningClientInner::Light { ref rpc, .. } => {
-				rpc.handle_request_sync(request, metadata)
-			},
-			RunningClientInner::Full { ref rpc, .. } => {
-				rpc.handle_request_sync(request, metadata)
-			},
-		}
+	/// Performs an asynchronous RPC query.
+	///
+	/// Notifications of subscriptions made by the query are sent to `session`, if given.
+	pub fn rpc_query(&self, request: &str, session: Option<Arc<PubSubSession>>) -> Box<Future<Item = Option<String>, Error = ()> + Send> {
+		self.rpc_handle().request(request, session)
 	}
 
 	/// Shuts down the client.
@@ -954,11 +1248,151 @@ impl RunningClient {
 				drop(informant);
 				drop(client);
 				wait_for_drop(weak_client);
-			}
+			},
+			RunningClientInner::Multi(clients) => {
+				for (_, client) in clients.into_iter().rev() {
+					client.shutdown();
+				}
+			},
+			RunningClientInner::Restartable { rpc, requests, supervisor } => {
+				// the client is owned and shut down by the supervisor thread.
+				drop(rpc);
+				let _ = requests.lock().send(None);
+				let _ = supervisor.join();
+			},
 		}
 	}
 }
 
+/// Dispatches JSON-RPC requests to a running client in-process. Cheap to clone.
+#[derive(Clone)]
+pub struct RpcHandle {
+	inner: RpcHandleInner,
+}
+
+#[derive(Clone)]
+enum RpcHandleInner {
+	Light(Arc<jsonrpc_core::MetaIoHandler<Metadata, informant::Middleware<rpc_apis::LightClientNotifier>>>),
+	Full(Arc<jsonrpc_core::MetaIoHandler<Metadata, informant::Middleware<informant::ClientNotifier>>>),
+	/// Several chains, routed by method prefix. The first one is the primary chain.
+	Multi(Arc<Vec<(Option<String>, RpcHandle)>>),
+	/// The client failed to restart and no longer answers requests.
+	Stopped,
+}
+
+impl RpcHandle {
+	fn metadata(session: Option<Arc<PubSubSession>>) -> Metadata {
+		Metadata {
+			origin: Origin::CApi,
+			session: session,
+			authorization: None,
+			origin_apis: None,
+			resolved_names: Default::default(),
+		}
+	}
+
+	/// Dispatches a serialized JSON-RPC request and returns the serialized response, if any.
+	///
+	/// Notifications of subscriptions made by the request are sent to `session`, if given.
+	pub fn request(&self, request: &str, session: Option<Arc<PubSubSession>>) -> Box<Future<Item = Option<String>, Error = ()> + Send> {
+		let metadata = Self::metadata(session);
+		match self.inner {
+			RpcHandleInner::Light(ref rpc) => Box::new(rpc.handle_request(request, metadata)),
+			RpcHandleInner::Full(ref rpc) => Box::new(rpc.handle_request(request, metadata)),
+			RpcHandleInner::Multi(ref chains) => match serde_json::from_str::<jsonrpc_core::Request>(request) {
+				Ok(request) => Box::new(self.handle(request, metadata.session).map(|response| response.map(|response| {
+					serde_json::to_string(&response).expect("serialization of a response never fails; qed")
+				}))),
+				// let the primary chain produce the parse error.
+				Err(_) => chains[0].1.request(request, metadata.session),
+			},
+			RpcHandleInner::Stopped => Box::new(future::ok(None)),
+		}
+	}
+
+	/// Dispatches a typed JSON-RPC request, skipping (de)serialization of the request and response.
+	pub fn handle(&self, request: jsonrpc_core::Request, session: Option<Arc<PubSubSession>>) -> Box<Future<Item = Option<jsonrpc_core::Response>, Error = ()> + Send> {
+		let metadata = Self::metadata(session);
+		match self.inner {
+			RpcHandleInner::Light(ref rpc) => Box::new(rpc.handle_rpc_request(request, metadata)),
+			RpcHandleInner::Full(ref rpc) => Box::new(rpc.handle_rpc_request(request, metadata)),
+			RpcHandleInner::Multi(ref chains) => match request {
+				jsonrpc_core::Request::Single(call) => Box::new(Self::route(chains, call, metadata.session)
+					.map(|output| output.map(jsonrpc_core::Response::Single))),
+				jsonrpc_core::Request::Batch(calls) => {
+					let outputs = calls.into_iter()
+						.map(|call| Self::route(chains, call, metadata.session.clone()))
+						.collect::<Vec<_>>();
+					Box::new(future::join_all(outputs).map(|outputs| {
+						let outputs = outputs.into_iter().filter_map(|output| output).collect::<Vec<_>>();
+						if outputs.is_empty() { None } else { Some(jsonrpc_core::Response::Batch(outputs)) }
+					}))
+				},
+			},
+			RpcHandleInner::Stopped => Box::new(future::ok(None)),
+		}
+	}
+
+	/// Dispatches a single call to the chain named by its method prefix, stripping the prefix.
+	fn route(chains: &[(Option<String>, RpcHandle)], call: jsonrpc_core::Call, session: Option<Arc<PubSubSession>>) -> Box<Future<Item = Option<jsonrpc_core::Output>, Error = ()> + Send> {
+		use jsonrpc_core::{Call, Error, ErrorCode, Failure, Output, Request, Response};
+
+		let prefixed = match call {
+			Call::MethodCall(ref call) => split_prefix(&call.method),
+			Call::Notification(ref notification) => split_prefix(&notification.method),
+			Call::Invalid(_) => None,
+		};
+
+		let (handle, call) = match prefixed {
+			None => (&chains[0].1, call),
+			Some((prefix, method)) => match chains.iter().find(|&&(ref chain, _)| chain.as_ref() == Some(&prefix)) {
+				Some(&(_, ref handle)) => (handle, match call {
+					Call::MethodCall(mut call) => { call.method = method; Call::MethodCall(call) },
+					Call::Notification(mut notification) => { notification.method = method; Call::Notification(notification) },
+					call => call,
+				}),
+				None => return Box::new(future::ok(match call {
+					Call::MethodCall(call) => Some(Output::Failure(Failure {
+						jsonrpc: call.jsonrpc,
+						error: Error {
+							code: ErrorCode::MethodNotFound,
+							message: format!("No chain is running with RPC prefix {}", prefix),
+							data: None,
+						},
+						id: call.id,
+					})),
+					_ => None,
+				})),
+			},
+		};
+
+		Box::new(handle.handle(Request::Single(call), session).map(|response| match response {
+			Some(Response::Single(output)) => Some(output),
+			_ => None,
+		}))
+	}
+
+	/// Calls a JSON-RPC method and returns its result.
+	pub fn call(&self, method: &str, params: jsonrpc_core::Params) -> Box<Future<Item = jsonrpc_core::Value, Error = jsonrpc_core::Error> + Send> {
+		use jsonrpc_core::{Call, Error, Id, MethodCall, Output, Request, Response, Version};
+
+		let request = Request::Single(Call::MethodCall(MethodCall {
+			jsonrpc: Some(Version::V2),
+			method: method.into(),
+			params: Some(params),
+			id: Id::Num(1),
+		}));
+
+		Box::new(self.handle(request, None)
+			.map_err(|_| Error::internal_error())
+			.and_then(|response| match response {
+				Some(Response::Single(Output::Success(success))) => Ok(success.result),
+				Some(Response::Single(Output::Failure(failure))) => Err(failure.error),
+				_ => Err(Error::internal_error()),
+			}))
+	}
+}
+
 /// Executes the given run command.
 ///
 /// `on_client_rq` is the action to perform when the client receives an RPC request to be restarted
@@ -971,11 +1405,161 @@ pub fn execute<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>,
 						on_client_rq: Cr, on_updater_rq: Rr) -> Result<RunningClient, String>
 	where Cr: Fn(String) + 'static + Send,
 		  Rr: Fn() + 'static + Send
+{
+	execute_with_runtime(cmd, logger, Runtime::new(), on_client_rq, on_updater_rq)
+}
+
+/// Splits `prefix:method` into its parts.
+fn split_prefix(method: &str) -> Option<(String, String)> {
+	let mut parts = method.splitn(2, ':');
+	match (parts.next(), parts.next()) {
+		(Some(prefix), Some(method)) => Some((prefix.to_owned(), method.to_owned())),
+		_ => None,
+	}
+}
+
+/// Executes several run commands in one process, sharing the event loop and thread pools.
+///
+/// The first command is the primary chain: only it handles restart and update requests
+/// and answers unprefixed in-process RPC queries.
+///
+/// On error, returns what to print on stderr.
+pub fn execute_multi<Cr, Rr>(cmds: Vec<RunCmd>, logger: Arc<RotatingLogger>,
+						on_client_rq: Cr, on_updater_rq: Rr) -> Result<RunningClient, String>
+	where Cr: Fn(String) + 'static + Send,
+		  Rr: Fn() + 'static + Send
+{
+	let runtime = Runtime::new();
+	let mut cmds = cmds.into_iter();
+	let primary = cmds.next().ok_or_else(|| "No chain to run.".to_owned())?;
+	let primary_prefix = primary.rpc_prefix.clone();
+	let mut clients = vec![(primary_prefix, execute_with_runtime(primary, logger.clone(), runtime.clone(), on_client_rq, on_updater_rq)?)];
+
+	for cmd in cmds {
+		let prefix = cmd.rpc_prefix.clone();
+		match execute_with_runtime(cmd, logger.clone(), runtime.clone(), |_| {}, || {}) {
+			Ok(client) => clients.push((prefix, client)),
+			Err(e) => {
+				for (_, client) in clients.into_iter().rev() {
+					client.shutdown();
+				}
+				return Err(e);
+			},
+		}
+	}
+
+	Ok(RunningClient {
+		inner: RunningClientInner::Multi(clients),
+	})
+}
+
+/// Executes the given run command, switching chains in-process when requested over RPC
+/// (`parity_setChain`) instead of leaving the restart to the caller.
+///
+/// `reconfigure` builds the run command for the given chain name.
+///
+/// `on_updater_rq` is the action to perform when the updater has a new binary to execute.
+///
+/// `on_failure` is called with what to print on stderr when neither the requested chain nor the
+/// previous one can be started. The client is stopped then and no longer answers RPC requests.
+///
+/// On error, returns what to print on stderr.
+pub fn execute_restartable<F, Rr, Ff>(cmd: RunCmd, logger: Arc<RotatingLogger>,
+						reconfigure: F, on_updater_rq: Rr, on_failure: Ff) -> Result<RunningClient, String>
+	where F: Fn(&str) -> Result<RunCmd, String> + 'static + Send,
+		  Rr: Fn() + 'static + Send + Sync,
+		  Ff: FnOnce(String) + 'static + Send
+{
+	let (requests, restarts) = mpsc::channel();
+	let (started_tx, started) = mpsc::channel();
+	let rpc = Arc::new(Mutex::new(None));
+
+	let start = {
+		let requests = requests.clone();
+		let on_updater_rq = Arc::new(on_updater_rq);
+		move |cmd: RunCmd| {
+			let requests = requests.clone();
+			let on_updater_rq = on_updater_rq.clone();
+			execute(cmd, logger.clone(),
+				move |chain: String| { let _ = requests.send(Some(chain)); },
+				move || on_updater_rq())
+		}
+	};
+
+	// the client stack is created, switched and shut down by the supervisor thread only.
+	let supervisor = {
+		let rpc = rpc.clone();
+		thread::Builder::new().name("chain-switch".into()).spawn(move || {
+			let mut chain = cmd.spec.to_string();
+			let mut client = match start(cmd) {
+				Ok(client) => client,
+				Err(e) => {
+					let _ = started_tx.send(Err(e));
+					return;
+				},
+			};
+			*rpc.lock() = Some(client.rpc_handle());
+			let _ = started_tx.send(Ok(()));
+			let mut client = Some(client);
+
+			while let Ok(Some(new_chain)) = restarts.recv() {
+				*rpc.lock() = None;
+				if let Some(client) = client.take() {
+					client.shutdown();
+				}
+
+				info!("Switching to chain {}", Colour::White.bold().paint(new_chain.as_str()));
+				let restarted = match reconfigure(&new_chain).and_then(&start) {
+					Ok(restarted) => {
+						chain = new_chain;
+						Ok(restarted)
+					},
+					Err(e) => {
+						warn!("Unable to switch to chain {}: {}", new_chain, e);
+						reconfigure(&chain).and_then(&start)
+							.map_err(|restart_err| format!("Unable to switch to chain {}: {}. Unable to restart chain {}: {}", new_chain, e, chain, restart_err))
+					},
+				};
+
+				match restarted {
+					Ok(restarted) => {
+						*rpc.lock() = Some(restarted.rpc_handle());
+						client = Some(restarted);
+					},
+					Err(e) => {
+						error!("{}", e);
+						on_failure(e);
+						break;
+					},
+				}
+			}
+
+			if let Some(client) = client {
+				client.shutdown();
+			}
+		}).map_err(|e| format!("Unable to start chain switching thread: {}", e))?
+	};
+
+	started.recv().map_err(|_| "Chain switching thread stopped unexpectedly".to_owned())??;
+
+	Ok(RunningClient {
+		inner: RunningClientInner::Restartable {
+			rpc,
+			requests: Mutex::new(requests),
+			supervisor,
+		},
+	})
+}
+
+fn execute_with_runtime<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, runtime: Runtime,
+						on_client_rq: Cr, on_updater_rq: Rr) -> Result<RunningClient, String>
+	where Cr: Fn(String) + 'static + Send,
+		  Rr: Fn() + 'static + Send
 {
 	if cmd.light {
-		execute_light_impl(cmd, logger)
+		execute_light_impl(cmd, logger, runtime)
 	} else {
-		execute_impl(cmd, logger, on_client_rq, on_updater_rq)
+		execute_impl(cmd, logger, runtime, on_client_rq, on_updater_rq)
 	}
 }
 
@@ -1003,13 +1587,16 @@ fn print_running_environment(spec_name: &String, dirs: &Directories, db_dirs: &D
 	info!("Path to dapps {}", Colour::White.bold().paint(dapps_conf.dapps_path.to_string_lossy().into_owned()));
 }
 
-fn prepare_account_provider(spec: &SpecType, dirs: &Directories, data_dir: &str, cfg: AccountsConfig, passwords: &[Password]) -> Result<AccountProvider, String> {
+fn prepare_account_provider(spec: &SpecType, dirs: &Directories, data_dir: &str, cfg: AccountsConfig, dev_key_pairs: &[KeyPair], passwords: &[Password]) -> Result<AccountProvider, String> {
 	use ethcore::ethstore::EthStore;
 	use ethcore::ethstore::accounts_dir::RootDiskDirectory;
 
 	let path = dirs.keys_path(data_dir);
 	upgrade_key_location(&dirs.legacy_keys_path(cfg.testnet), &path);
 	let dir = Box::new(RootDiskDirectory::create(&path).map_err(|e| format!("Could not open keys directory: {}", e))?);
+	let address_book_path = dirs.address_book_path(data_dir);
+	fs::create_dir_all(&address_book_path).map_err(|e| format!("Could not create address book directory: {}", e))?;
+	upgrade_address_book_location(&path, &address_book_path);
 	let account_settings = AccountProviderSettings {
 		enable_hardware_wallets: cfg.enable_hardware_wallets,
 		hardware_wallet_classic_key: spec == &SpecType::Classic,
@@ -1020,6 +1607,7 @@ fn prepare_account_provider(spec: &SpecType, dirs: &Directories, data_dir: &str,
 				"00a329c0648769a73afac7f9381e08fb43dbea72".into()
 			],
 		},
+		address_book_path: Some(address_book_path),
 	};
 
 	let ethstore = EthStore::open_with_iterations(dir, cfg.iterations).map_err(|e| format!("Could not open keys directory: {}", e))?;
@@ -1051,13 +1639,16 @@ fn prepare_account_provider(spec: &SpecType, dirs: &Directories, data_dir: &str,
 	if let SpecType::Dev = *spec {
 		insert_dev_account(&account_provider);
 	}
+	if !dev_key_pairs.is_empty() {
+		insert_dev_accounts(&account_provider, dev_key_pairs)?;
+	}
 
 	Ok(account_provider)
 }
 
 fn insert_dev_account(account_provider: &AccountProvider) {
 	let secret: ethkey::Secret = "4d5db4107d237df6a3d58ee5f70ae63d73d7658d4026f2eefd2f204c81682cb7".into();
-	let dev_account = ethkey::KeyPair::from_secret(secret.clone()).expect("Valid secret produces valid key;qed");
+	let dev_account = KeyPair::from_secret(secret.clone()).expect("Valid secret produces valid key;qed");
 	if !account_provider.has_account(dev_account.address()) {
 		match account_provider.insert_account(secret, &Password::from(String::new())) {
 			Err(e) => warn!("Unable to add development account: {}", e),
@@ -1072,6 +1663,35 @@ fn insert_dev_account(account_provider: &AccountProvider) {
 	}
 }
 
+/// Derives the development accounts and adds them to the genesis state of the spec.
+fn prefund_dev_accounts(dev_accounts: Option<&DevAccounts>, spec: &mut Spec) -> Result<Vec<KeyPair>, String> {
+	match dev_accounts {
+		Some(dev_accounts) => {
+			let pairs = dev_accounts.key_pairs()?;
+			dev_accounts.prefund(spec, &pairs)?;
+			println!("Development accounts, prefunded with {} wei each. Their keys are public, never use them outside of the dev chain!", dev_accounts.balance);
+			Ok(pairs)
+		},
+		None => Ok(Vec::new()),
+	}
+}
+
+fn insert_dev_accounts(account_provider: &AccountProvider, pairs: &[KeyPair]) -> Result<(), String> {
+	let password = Password::from(String::new());
+	for (index, pair) in pairs.iter().enumerate() {
+		let address = pair.address();
+		if !account_provider.has_account(address) {
+			account_provider.insert_account(pair.secret().clone(), &password)
+				.map_err(|e| format!("Unable to add development account {:?}: {}", address, e))?;
+			let _ = account_provider.set_account_name(address, format!("Development Account {}", index));
+		}
+		account_provider.unlock_account_permanently(address, password.clone())
+			.map_err(|e| format!("Unable to unlock development account {:?}: {}", address, e))?;
+		println!("  #{} {:?} (secret 0x{:x})", index, address, pair.secret());
+	}
+	Ok(())
+}
+
 // Construct an error `String` with an adaptive hint on how to create an account.
 fn build_create_account_hint(spec: &SpecType, keys: &str) -> String {
 	format!("You can create an account via RPC, UI or `parity account new --chain {} --keys-path {}`.", spec, keys)
@@ -1100,3 +1720,164 @@ fn wait_for_drop<T>(w: Weak<T>) {
 
 	warn!("Shutdown timeout reached, exiting uncleanly.");
 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::{mpsc, Arc};
+	use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+	use std::thread;
+	use std::time::Duration;
+
+	use ethcore_logger::RotatingLogger;
+	use futures::Future;
+	use jsonrpc_core::{Call, Id, MetaIoHandler, MethodCall, Output, Params, Request, Response, Value, Version};
+	use parity_rpc::informant::{Middleware, RpcStats, Lanes};
+	use rpc_apis::LightClientNotifier;
+	use tempdir::TempDir;
+
+	use configuration::{Cmd, Configuration};
+	use super::{RpcHandle, RpcHandleInner, RunCmd, RunningClient, RunningClientInner, execute_restartable};
+
+	fn handle(chain: &'static str) -> RpcHandle {
+		let mut io = MetaIoHandler::with_middleware(Middleware::new(Arc::new(RpcStats::default()), LightClientNotifier, None, Lanes::default()));
+		io.add_method("parity_chain", move |_| Ok(Value::String(chain.into())));
+		RpcHandle { inner: RpcHandleInner::Light(Arc::new(io)) }
+	}
+
+	fn method_call(method: &str, id: u64) -> Call {
+		Call::MethodCall(MethodCall {
+			jsonrpc: Some(Version::V2),
+			method: method.into(),
+			params: Some(Params::Array(vec![])),
+			id: Id::Num(id),
+		})
+	}
+
+	#[test]
+	fn dispatches_serialized_requests() {
+		let rpc = handle("dev");
+		let response = rpc.request(r#"{"jsonrpc":"2.0","method":"parity_chain","params":[],"id":1}"#, None).wait().unwrap();
+		assert_eq!(response, Some(r#"{"jsonrpc":"2.0","result":"dev","id":1}"#.to_owned()));
+
+		let response = rpc.request(r#"{"jsonrpc":"2.0","method":"parity_unknown","params":[],"id":2}"#, None).wait().unwrap();
+		assert!(response.unwrap().contains(r#""code":-32601"#));
+	}
+
+	#[test]
+	fn dispatches_typed_requests_and_batches() {
+		let rpc = handle("dev");
+		let response = rpc.handle(Request::Batch(vec![method_call("parity_chain", 1), method_call("parity_chain", 2)]), None).wait().unwrap();
+		match response {
+			Some(Response::Batch(outputs)) => {
+				assert_eq!(outputs.len(), 2);
+				assert!(outputs.iter().all(|output| match *output {
+					Output::Success(ref success) => success.result == Value::String("dev".into()),
+					_ => false,
+				}));
+			},
+			_ => panic!("Expected a batch response"),
+		}
+	}
+
+	#[test]
+	fn calls_return_results_and_errors() {
+		let rpc = handle("dev");
+		assert_eq!(rpc.call("parity_chain", Params::Array(vec![])).wait(), Ok(Value::String("dev".into())));
+		assert!(rpc.call("parity_unknown", Params::Array(vec![])).wait().is_err());
+	}
+
+	#[test]
+	fn routes_prefixed_calls_to_their_chain() {
+		let rpc = RpcHandle { inner: RpcHandleInner::Multi(Arc::new(vec![
+			(None, handle("foundation")),
+			(Some("kovan".into()), handle("kovan")),
+		])) };
+
+		assert_eq!(rpc.call("parity_chain", Params::Array(vec![])).wait(), Ok(Value::String("foundation".into())));
+		assert_eq!(rpc.call("kovan:parity_chain", Params::Array(vec![])).wait(), Ok(Value::String("kovan".into())));
+		assert!(rpc.call("ropsten:parity_chain", Params::Array(vec![])).wait().is_err());
+
+		let response = rpc.request(r#"[{"jsonrpc":"2.0","method":"kovan:parity_chain","params":[],"id":1},{"jsonrpc":"2.0","method":"parity_chain","params":[],"id":2}]"#, None).wait().unwrap();
+		assert_eq!(response, Some(r#"[{"jsonrpc":"2.0","result":"kovan","id":1},{"jsonrpc":"2.0","result":"foundation","id":2}]"#.to_owned()));
+	}
+
+	#[test]
+	fn stopped_client_answers_nothing() {
+		let rpc = RpcHandle { inner: RpcHandleInner::Stopped };
+		assert_eq!(rpc.request(r#"{"jsonrpc":"2.0","method":"parity_chain","params":[],"id":1}"#, None).wait(), Ok(None));
+		assert!(rpc.call("parity_chain", Params::Array(vec![])).wait().is_err());
+	}
+
+	fn dev_cmd(base_path: &str) -> RunCmd {
+		let args = vec![
+			"parity".to_owned(),
+			"--no-config".into(),
+			"--chain=dev".into(),
+			format!("--base-path={}", base_path),
+			"--no-discovery".into(),
+			"--no-jsonrpc".into(),
+			"--no-ws".into(),
+			"--no-ipc".into(),
+			"--no-dapps".into(),
+			"--no-secretstore".into(),
+			"--no-persistent-txqueue".into(),
+		];
+		match Configuration::parse_cli(&args).unwrap().into_command().unwrap().cmd {
+			Cmd::Run(cmd) => cmd,
+			_ => panic!("Should be Cmd::Run"),
+		}
+	}
+
+	fn switch_chain(client: &RunningClient, chain: &str) {
+		match client.inner {
+			RunningClientInner::Restartable { ref requests, .. } => requests.lock().send(Some(chain.into())).unwrap(),
+			_ => panic!("Should be a restartable client"),
+		}
+	}
+
+	#[test]
+	fn restartable_client_falls_back_to_its_chain_and_reports_when_that_fails() {
+		let dir = TempDir::new("parity-restartable").unwrap();
+		let base_path = dir.path().to_str().unwrap().to_owned();
+		let broken = Arc::new(AtomicBool::new(false));
+		let restarts = Arc::new(AtomicUsize::new(0));
+		let (failures_tx, failures) = mpsc::channel();
+
+		let reconfigure = {
+			let (broken, restarts, base_path) = (broken.clone(), restarts.clone(), base_path.clone());
+			move |chain: &str| {
+				if chain != "dev" {
+					return Err(format!("Unknown chain {}", chain));
+				}
+				restarts.fetch_add(1, Ordering::SeqCst);
+				match broken.load(Ordering::SeqCst) {
+					true => Err("Database is broken".to_owned()),
+					false => Ok(dev_cmd(&base_path)),
+				}
+			}
+		};
+		let client = execute_restartable(dev_cmd(&base_path), Arc::new(RotatingLogger::new(String::new())), reconfigure,
+			|| {}, move |e| { let _ = failures_tx.send(e); }).unwrap();
+		let block_number = r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#;
+		assert!(client.rpc_query_sync(block_number).is_some());
+
+		// switching to an unknown chain restarts the previous one.
+		switch_chain(&client, "unknown");
+		while restarts.load(Ordering::SeqCst) == 0 {
+			thread::sleep(Duration::from_millis(50));
+		}
+		while client.rpc_query_sync(block_number).is_none() {
+			assert!(failures.try_recv().is_err());
+			thread::sleep(Duration::from_millis(50));
+		}
+
+		// if the previous chain can't be restarted either, the client stops and reports the failure.
+		broken.store(true, Ordering::SeqCst);
+		switch_chain(&client, "unknown");
+		let failure = failures.recv_timeout(Duration::from_secs(60)).unwrap();
+		assert!(failure.contains("Unable to restart chain dev: Database is broken"));
+		assert!(client.rpc_query_sync(block_number).is_none());
+
+		client.shutdown();
+	}
+}
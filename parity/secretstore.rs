@@ -82,6 +82,19 @@ pub struct Configuration {
 	pub data_path: String,
 	/// Administrator public key.
 	pub admin_public: Option<Public>,
+	/// Mutual TLS configuration of connections between key servers.
+	pub tls: Option<TlsConfiguration>,
+}
+
+/// Secret store mutual TLS configuration.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TlsConfiguration {
+	/// Path to certificate of this node.
+	pub certificate_path: String,
+	/// Path to private key of this node.
+	pub private_key_path: String,
+	/// Path to CA certificate.
+	pub ca_path: String,
 }
 
 /// Secret store dependencies
@@ -165,6 +178,9 @@ mod server {
 			if conf.acl_check_contract_address.is_none() {
 				warn!("Running SecretStore with disabled ACL check: {}", Red.bold().paint("everyone has access to stored keys"));
 			}
+			if conf.key_server_set_contract_address.is_some() && !conf.nodes.is_empty() {
+				warn!("Ignoring --secretstore-nodes: key servers set is read from the server set contract. Use --secretstore-server-set-contract=none to use static nodes list.");
+			}
 
 			let key_server_name = format!("{}:{}", conf.interface, conf.port);
 			let mut cconf = ethcore_secretstore::ServiceConfiguration {
@@ -192,6 +208,11 @@ mod server {
 					allow_connecting_to_higher_nodes: true,
 					admin_public: conf.admin_public,
 					auto_migrate_enabled: conf.auto_migrate_enabled,
+					tls: conf.tls.map(|tls| ethcore_secretstore::TlsConfiguration {
+						certificate_path: tls.certificate_path,
+						private_key_path: tls.private_key_path,
+						ca_path: tls.ca_path,
+					}),
 				},
 			};
 
@@ -226,6 +247,7 @@ impl Default for Configuration {
 			service_contract_doc_sretr_address: None,
 			self_secret: None,
 			admin_public: None,
+			tls: None,
 			nodes: BTreeMap::new(),
 			key_server_set_contract_address: Some(ContractAddress::Registry),
 			interface: "127.0.0.1".to_owned(),
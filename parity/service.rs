@@ -0,0 +1,338 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Windows service integration.
+
+use std::sync::Arc;
+
+use ethcore_logger::RotatingLogger;
+use run::RunCmd;
+
+/// Name the service is registered under, also used as the event log source.
+pub const SERVICE_NAME: &'static str = "parity";
+
+#[derive(Debug, PartialEq)]
+pub enum ServiceCmd {
+	/// Register the service, started with the options given on the command line.
+	Install,
+	/// Unregister the service.
+	Remove,
+	/// Run the node under the Service Control Manager.
+	Run(RunCmd),
+}
+
+pub fn execute(cmd: ServiceCmd, logger: Arc<RotatingLogger>) -> Result<String, String> {
+	match cmd {
+		ServiceCmd::Install => platform::install(),
+		ServiceCmd::Remove => platform::remove(),
+		ServiceCmd::Run(run_cmd) => platform::run(run_cmd, logger).map(|_| String::new()),
+	}
+}
+
+/// Command line the service is started with: `parity service run` followed by
+/// the options given to `parity service install`.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn service_command_line<I: IntoIterator<Item = String>>(exe: &str, args: I) -> String {
+	fn quote(arg: &str) -> String {
+		if !arg.is_empty() && !arg.contains(|c: char| c == ' ' || c == '\t' || c == '"') {
+			return arg.to_owned();
+		}
+		format!("\"{}\"", arg.replace('"', "\\\""))
+	}
+
+	let mut subcommand = ["service", "install"].iter().peekable();
+	let mut command_line = format!("{} service run", quote(exe));
+	for arg in args {
+		if subcommand.peek().map_or(false, |expected| **expected == arg) {
+			subcommand.next();
+			continue;
+		}
+		command_line.push(' ');
+		command_line.push_str(&quote(&arg));
+	}
+	command_line
+}
+
+#[cfg(windows)]
+mod platform {
+	use std::cell::Cell;
+	use std::ffi::OsStr;
+	use std::os::windows::ffi::OsStrExt;
+	use std::sync::{mpsc, Arc};
+	use std::{env, io, ptr};
+
+	use ethcore_logger::RotatingLogger;
+	use parking_lot::Mutex;
+	use run::{self, RunCmd};
+	use winapi::shared::minwindef::{DWORD, LPVOID, WORD};
+	use winapi::shared::winerror::{ERROR_CALL_NOT_IMPLEMENTED, ERROR_SERVICE_SPECIFIC_ERROR, NO_ERROR};
+	use winapi::um::winbase::{DeregisterEventSource, RegisterEventSourceW, ReportEventW};
+	use winapi::um::winnt::{
+		DELETE, EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE, LPWSTR,
+		SERVICE_AUTO_START, SERVICE_ERROR_NORMAL, SERVICE_WIN32_OWN_PROCESS,
+	};
+	use winapi::um::winsvc::{
+		CloseServiceHandle, CreateServiceW, DeleteService, OpenSCManagerW, OpenServiceW,
+		RegisterServiceCtrlHandlerExW, SetServiceStatus, StartServiceCtrlDispatcherW,
+		SC_HANDLE, SC_MANAGER_CONNECT, SC_MANAGER_CREATE_SERVICE, SERVICE_ACCEPT_SHUTDOWN,
+		SERVICE_ACCEPT_STOP, SERVICE_ALL_ACCESS, SERVICE_CONTROL_INTERROGATE, SERVICE_CONTROL_SHUTDOWN,
+		SERVICE_CONTROL_STOP, SERVICE_RUNNING, SERVICE_START_PENDING, SERVICE_STATUS,
+		SERVICE_STATUS_HANDLE, SERVICE_STOPPED, SERVICE_STOP_PENDING, SERVICE_TABLE_ENTRYW,
+	};
+	use super::{SERVICE_NAME, service_command_line};
+
+	const SERVICE_DISPLAY_NAME: &'static str = "Parity Ethereum";
+	/// Time the SCM should wait for the node to start or stop before considering it hung.
+	const WAIT_HINT_MS: DWORD = 120_000;
+
+	// The service main function doesn't take a context, the command is handed over here.
+	static mut SERVICE_CMD: Option<(RunCmd, Arc<RotatingLogger>)> = None;
+
+	fn wide(s: &str) -> Vec<u16> {
+		OsStr::new(s).encode_wide().chain(Some(0)).collect()
+	}
+
+	fn event_log(kind: WORD, message: &str) {
+		let source = wide(SERVICE_NAME);
+		let message = wide(message);
+		unsafe {
+			let handle = RegisterEventSourceW(ptr::null(), source.as_ptr());
+			if handle.is_null() {
+				return;
+			}
+			let mut strings = [message.as_ptr()];
+			ReportEventW(handle, kind, 0, 0, ptr::null_mut(), 1, 0, strings.as_mut_ptr(), ptr::null_mut());
+			DeregisterEventSource(handle);
+		}
+	}
+
+	unsafe fn open_manager(access: DWORD) -> Result<SC_HANDLE, String> {
+		let manager = OpenSCManagerW(ptr::null(), ptr::null(), access);
+		if manager.is_null() {
+			return Err(format!("Unable to open the Service Control Manager: {}", io::Error::last_os_error()));
+		}
+		Ok(manager)
+	}
+
+	pub fn install() -> Result<String, String> {
+		let exe = env::current_exe().map_err(|e| format!("Unable to locate the Parity executable: {}", e))?;
+		let command_line = service_command_line(&exe.to_string_lossy(), env::args().skip(1));
+		let name = wide(SERVICE_NAME);
+		let display_name = wide(SERVICE_DISPLAY_NAME);
+		let path = wide(&command_line);
+
+		unsafe {
+			let manager = open_manager(SC_MANAGER_CREATE_SERVICE)?;
+			let service = CreateServiceW(
+				manager,
+				name.as_ptr(),
+				display_name.as_ptr(),
+				SERVICE_ALL_ACCESS,
+				SERVICE_WIN32_OWN_PROCESS,
+				SERVICE_AUTO_START,
+				SERVICE_ERROR_NORMAL,
+				path.as_ptr(),
+				ptr::null(),
+				ptr::null_mut(),
+				ptr::null(),
+				ptr::null(),
+				ptr::null(),
+			);
+			let result = if service.is_null() {
+				Err(format!("Unable to install the {} service: {}", SERVICE_NAME, io::Error::last_os_error()))
+			} else {
+				CloseServiceHandle(service);
+				Ok(format!("Installed the {} service as: {}", SERVICE_NAME, command_line))
+			};
+			CloseServiceHandle(manager);
+			result
+		}
+	}
+
+	pub fn remove() -> Result<String, String> {
+		let name = wide(SERVICE_NAME);
+
+		unsafe {
+			let manager = open_manager(SC_MANAGER_CONNECT)?;
+			let service = OpenServiceW(manager, name.as_ptr(), DELETE);
+			let result = if service.is_null() {
+				Err(format!("Unable to open the {} service: {}", SERVICE_NAME, io::Error::last_os_error()))
+			} else {
+				let deleted = DeleteService(service);
+				let error = io::Error::last_os_error();
+				CloseServiceHandle(service);
+				match deleted {
+					0 => Err(format!("Unable to remove the {} service: {}", SERVICE_NAME, error)),
+					_ => Ok(format!("Removed the {} service.", SERVICE_NAME)),
+				}
+			};
+			CloseServiceHandle(manager);
+			result
+		}
+	}
+
+	pub fn run(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<(), String> {
+		let mut name = wide(SERVICE_NAME);
+		let table = [
+			SERVICE_TABLE_ENTRYW { lpServiceName: name.as_mut_ptr(), lpServiceProc: Some(service_main) },
+			SERVICE_TABLE_ENTRYW { lpServiceName: ptr::null_mut(), lpServiceProc: None },
+		];
+
+		unsafe {
+			SERVICE_CMD = Some((cmd, logger));
+			// Blocks until the service has stopped.
+			if StartServiceCtrlDispatcherW(table.as_ptr()) == 0 {
+				return Err(format!(
+					"Unable to connect to the Service Control Manager: {}. `parity service run` is started by Windows, use `parity service install` to register the service.",
+					io::Error::last_os_error()
+				));
+			}
+		}
+		Ok(())
+	}
+
+	/// Reports service state to the SCM.
+	struct Status {
+		handle: SERVICE_STATUS_HANDLE,
+		checkpoint: Cell<DWORD>,
+	}
+
+	impl Status {
+		fn report(&self, state: DWORD, exit_code: DWORD) {
+			let pending = state == SERVICE_START_PENDING || state == SERVICE_STOP_PENDING;
+			let checkpoint = match pending {
+				true => self.checkpoint.get() + 1,
+				false => 0,
+			};
+			self.checkpoint.set(checkpoint);
+
+			let mut status = SERVICE_STATUS {
+				dwServiceType: SERVICE_WIN32_OWN_PROCESS,
+				dwCurrentState: state,
+				dwControlsAccepted: match state {
+					SERVICE_RUNNING => SERVICE_ACCEPT_STOP | SERVICE_ACCEPT_SHUTDOWN,
+					_ => 0,
+				},
+				dwWin32ExitCode: match exit_code {
+					0 => NO_ERROR,
+					_ => ERROR_SERVICE_SPECIFIC_ERROR,
+				},
+				dwServiceSpecificExitCode: exit_code,
+				dwCheckPoint: checkpoint,
+				dwWaitHint: if pending { WAIT_HINT_MS } else { 0 },
+			};
+			unsafe {
+				SetServiceStatus(self.handle, &mut status);
+			}
+		}
+	}
+
+	unsafe extern "system" fn service_main(_argc: DWORD, _argv: *mut LPWSTR) {
+		let (cmd, logger) = match SERVICE_CMD.take() {
+			Some(service) => service,
+			None => return,
+		};
+
+		let (stop_tx, stop_rx) = mpsc::channel::<()>();
+		// Lives as long as the process, the handler may be called until the service is stopped.
+		let context = Box::into_raw(Box::new(Mutex::new(stop_tx))) as LPVOID;
+		let name = wide(SERVICE_NAME);
+		let handle = RegisterServiceCtrlHandlerExW(name.as_ptr(), Some(control_handler), context);
+		if handle.is_null() {
+			event_log(EVENTLOG_ERROR_TYPE, &format!("Unable to register service control handler: {}", io::Error::last_os_error()));
+			return;
+		}
+
+		let status = Status { handle, checkpoint: Cell::new(0) };
+		status.report(SERVICE_START_PENDING, 0);
+
+		let on_client_rq = |_: String| event_log(EVENTLOG_WARNING_TYPE, "Switching chains is not supported when running as a service. Change --chain and restart the service instead.");
+		let on_updater_rq = || event_log(EVENTLOG_WARNING_TYPE, "A new release is ready. Restart the service to run it.");
+
+		match run::execute(cmd, logger, on_client_rq, on_updater_rq) {
+			Ok(client) => {
+				status.report(SERVICE_RUNNING, 0);
+				event_log(EVENTLOG_INFORMATION_TYPE, "Parity service started.");
+
+				let _ = stop_rx.recv();
+
+				status.report(SERVICE_STOP_PENDING, 0);
+				client.shutdown();
+				event_log(EVENTLOG_INFORMATION_TYPE, "Parity service stopped.");
+				status.report(SERVICE_STOPPED, 0);
+			},
+			Err(err) => {
+				event_log(EVENTLOG_ERROR_TYPE, &err);
+				status.report(SERVICE_STOPPED, 1);
+			},
+		}
+	}
+
+	unsafe extern "system" fn control_handler(control: DWORD, _event_type: DWORD, _event_data: LPVOID, context: LPVOID) -> DWORD {
+		match control {
+			SERVICE_CONTROL_STOP | SERVICE_CONTROL_SHUTDOWN => {
+				let stop = &*(context as *const Mutex<mpsc::Sender<()>>);
+				let _ = stop.lock().send(());
+				NO_ERROR
+			},
+			SERVICE_CONTROL_INTERROGATE => NO_ERROR,
+			_ => ERROR_CALL_NOT_IMPLEMENTED,
+		}
+	}
+}
+
+#[cfg(not(windows))]
+mod platform {
+	use std::sync::Arc;
+
+	use ethcore_logger::RotatingLogger;
+	use run::RunCmd;
+
+	const UNSUPPORTED: &'static str = "Services are only supported on Windows. Use `parity daemon` or a service manager such as systemd instead.";
+
+	pub fn install() -> Result<String, String> {
+		Err(UNSUPPORTED.into())
+	}
+
+	pub fn remove() -> Result<String, String> {
+		Err(UNSUPPORTED.into())
+	}
+
+	pub fn run(_cmd: RunCmd, _logger: Arc<RotatingLogger>) -> Result<(), String> {
+		Err(UNSUPPORTED.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::service_command_line;
+
+	fn args(args: &[&str]) -> Vec<String> {
+		args.iter().map(|arg| arg.to_string()).collect()
+	}
+
+	#[test]
+	fn should_pass_install_options_to_service() {
+		assert_eq!(
+			service_command_line(r"C:\Program Files\Parity\parity.exe", args(&["service", "install", "--config", r"C:\parity\config.toml"])),
+			r#""C:\Program Files\Parity\parity.exe" service run --config C:\parity\config.toml"#
+		);
+		assert_eq!(
+			service_command_line("parity.exe", args(&["--chain", "kovan", "service", "install", "--identity", "my node"])),
+			r#"parity.exe service run --chain kovan --identity "my node""#
+		);
+	}
+}
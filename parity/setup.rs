@@ -0,0 +1,254 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Interactive first-run setup writing a configuration file.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq)]
+pub struct SetupCmd {
+	/// File with one answer per line, used instead of asking. Empty lines select the default.
+	pub answers_file: Option<PathBuf>,
+	/// Where to write the configuration.
+	pub config_path: PathBuf,
+	/// Base path, used to check the available disk space.
+	pub base_path: PathBuf,
+}
+
+const MB: u64 = 1024 * 1024;
+const GB: u64 = 1024 * MB;
+
+/// Detected resources of the machine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Resources {
+	memory: Option<u64>,
+	disk: Option<u64>,
+}
+
+/// Chosen settings.
+#[derive(Debug, PartialEq)]
+struct Answers {
+	chain: String,
+	pruning: String,
+	cache_size: u64,
+	rpc: String,
+}
+
+/// Cache size in MB suggested for the given total memory: a quarter of it, within sensible bounds.
+fn suggested_cache_size(memory: Option<u64>) -> u64 {
+	match memory {
+		Some(memory) => ::std::cmp::min(::std::cmp::max(memory / 4 / MB, 128), 8192),
+		None => 1024,
+	}
+}
+
+#[cfg(target_os = "linux")]
+fn total_memory() -> Option<u64> {
+	let mut meminfo = String::new();
+	File::open("/proc/meminfo").and_then(|mut f| f.read_to_string(&mut meminfo)).ok()?;
+	meminfo.lines()
+		.find(|line| line.starts_with("MemTotal:"))
+		.and_then(|line| line.split_whitespace().nth(1))
+		.and_then(|kb| kb.parse::<u64>().ok())
+		.map(|kb| kb * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn total_memory() -> Option<u64> {
+	None
+}
+
+fn available_disk(base_path: &Path) -> Option<u64> {
+	// the base path may not exist yet; check the closest existing ancestor
+	let mut path = base_path;
+	while !path.exists() {
+		path = path.parent()?;
+	}
+	::dir::available_space(path).ok()
+}
+
+/// Asks the questions, reading answers with `answer`, which gets the prompt and returns
+/// the trimmed answer (empty for the default).
+fn ask<F>(resources: Resources, mut answer: F) -> Result<Answers, String> where F: FnMut(&str) -> Result<String, String> {
+	fn choose<F>(answer: &mut F, question: &str, options: &[&str], default: &str) -> Result<String, String> where F: FnMut(&str) -> Result<String, String> {
+		let prompt = format!("{} [{}] (default: {}): ", question, options.join("/"), default);
+		let value = answer(&prompt)?;
+		match value.as_str() {
+			"" => Ok(default.into()),
+			v if options.contains(&v) => Ok(v.into()),
+			v => Err(format!("Invalid answer to \"{}\": {}. Expected one of: {}", question, v, options.join(", "))),
+		}
+	}
+
+	let chain = {
+		let prompt = "Chain to sync, a built-in name or a path to a chain specification (default: foundation): ";
+		match answer(prompt)?.as_str() {
+			"" => "foundation".to_owned(),
+			chain => chain.to_owned(),
+		}
+	};
+
+	let low_disk = resources.disk.map_or(false, |disk| disk < 100 * GB);
+	let pruning = choose(&mut answer, "Keep the state of old blocks (archive) or only recent ones (fast)?", &["fast", "archive"], "fast")?;
+	if pruning == "archive" && low_disk {
+		warn!("An archive node of a major chain needs far more disk space than the {} GB available.", resources.disk.unwrap_or(0) / GB);
+	}
+
+	let suggested = suggested_cache_size(resources.memory);
+	let cache_size = {
+		let prompt = format!("Total cache size in MB (default: {}): ", suggested);
+		match answer(&prompt)?.as_str() {
+			"" => suggested,
+			size => size.parse().map_err(|_| format!("Invalid cache size: {}", size))?,
+		}
+	};
+
+	let rpc = choose(&mut answer, "Expose the JSON-RPC and WebSockets APIs to this machine only (local), to all interfaces (all), or disable them (off)?", &["local", "all", "off"], "local")?;
+
+	Ok(Answers { chain, pruning, cache_size, rpc })
+}
+
+fn config(answers: &Answers, resources: Resources) -> String {
+	let mut config = String::new();
+	config.push_str("# Generated by `parity setup`. See `parity --help` for all options.\n\n");
+
+	config.push_str("[parity]\n");
+	config.push_str("# Blockchain to sync.\n");
+	config.push_str(&format!("chain = \"{}\"\n\n", answers.chain.replace('\\', "\\\\").replace('"', "\\\"")));
+
+	config.push_str("[footprint]\n");
+	config.push_str("# \"fast\" keeps only the state of recent blocks, \"archive\" keeps all of it.\n");
+	if let Some(disk) = resources.disk {
+		config.push_str(&format!("# {} GB of disk space were available during setup.\n", disk / GB));
+	}
+	config.push_str(&format!("pruning = \"{}\"\n", answers.pruning));
+	match resources.memory {
+		Some(memory) => config.push_str(&format!("# Total cache size in MB, suggested from {} MB of memory.\n", memory / MB)),
+		None => config.push_str("# Total cache size in MB.\n"),
+	}
+	config.push_str(&format!("cache_size = {}\n", answers.cache_size));
+
+	for section in &["rpc", "websockets"] {
+		config.push_str(&format!("\n[{}]\n", section));
+		match answers.rpc.as_str() {
+			"off" => config.push_str("disable = true\n"),
+			"all" => {
+				config.push_str("# Reachable from other machines. Make sure a firewall restricts who can connect.\n");
+				config.push_str("interface = \"all\"\n");
+			},
+			_ => config.push_str("interface = \"local\"\n"),
+		}
+	}
+
+	config
+}
+
+pub fn execute(cmd: SetupCmd) -> Result<String, String> {
+	if cmd.config_path.exists() {
+		return Err(format!("Configuration file {} already exists. Remove it to run the setup again.", cmd.config_path.display()));
+	}
+
+	let resources = Resources {
+		memory: total_memory(),
+		disk: available_disk(&cmd.base_path),
+	};
+
+	let answers = match cmd.answers_file {
+		Some(ref path) => {
+			let mut content = String::new();
+			File::open(path).and_then(|mut f| f.read_to_string(&mut content))
+				.map_err(|e| format!("Unable to read answers from {}: {}", path.display(), e))?;
+			let mut lines = content.lines();
+			ask(resources, |_| Ok(lines.next().unwrap_or("").trim().to_owned()))?
+		},
+		None => {
+			let stdin = io::stdin();
+			let mut stdin = stdin.lock();
+			ask(resources, |prompt| {
+				print!("{}", prompt);
+				io::stdout().flush().map_err(|e| e.to_string())?;
+				let mut line = String::new();
+				stdin.read_line(&mut line).map_err(|e| format!("Unable to read answer: {}", e))?;
+				Ok(line.trim().to_owned())
+			})?
+		},
+	};
+
+	if let Some(parent) = cmd.config_path.parent() {
+		fs::create_dir_all(parent).map_err(|e| format!("Unable to create {}: {}", parent.display(), e))?;
+	}
+	File::create(&cmd.config_path)
+		.and_then(|mut f| f.write_all(config(&answers, resources).as_bytes()))
+		.map_err(|e| format!("Unable to write configuration to {}: {}", cmd.config_path.display(), e))?;
+
+	Ok(format!("Configuration written to {}. Start Parity with `parity` to use it.", cmd.config_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+	use tempdir::TempDir;
+	use cli::Args;
+	use super::{execute, ask, suggested_cache_size, Answers, Resources, SetupCmd, GB, MB};
+
+	#[test]
+	fn should_suggest_cache_size() {
+		assert_eq!(suggested_cache_size(None), 1024);
+		assert_eq!(suggested_cache_size(Some(256 * MB)), 128);
+		assert_eq!(suggested_cache_size(Some(16 * GB)), 4096);
+		assert_eq!(suggested_cache_size(Some(128 * GB)), 8192);
+	}
+
+	#[test]
+	fn should_use_defaults_and_reject_invalid_answers() {
+		let resources = Resources { memory: Some(8 * GB), disk: None };
+		assert_eq!(ask(resources, |_| Ok(String::new())), Ok(Answers {
+			chain: "foundation".into(),
+			pruning: "fast".into(),
+			cache_size: 2048,
+			rpc: "local".into(),
+		}));
+
+		let mut answers = vec!["kovan", "full"].into_iter();
+		assert!(ask(resources, |_| Ok(answers.next().unwrap_or("").into())).is_err());
+	}
+
+	#[test]
+	fn should_write_loadable_config() {
+		let tempdir = TempDir::new("").unwrap();
+		let answers_file = tempdir.path().join("answers");
+		::std::fs::File::create(&answers_file).and_then(|mut f| {
+			use std::io::Write;
+			f.write_all(b"kovan\narchive\n512\nall\n")
+		}).unwrap();
+		let config_path = tempdir.path().join("config.toml");
+
+		let cmd = SetupCmd {
+			answers_file: Some(answers_file),
+			config_path: config_path.clone(),
+			base_path: tempdir.path().into(),
+		};
+		assert!(execute(cmd).is_ok());
+
+		let args = Args::parse(&["parity", "--config", config_path.to_str().unwrap()]).unwrap();
+		assert_eq!(args.arg_chain, "kovan");
+		assert_eq!(args.arg_pruning, "archive");
+		assert_eq!(args.arg_cache_size, Some(512));
+		assert_eq!(args.arg_jsonrpc_interface, "all");
+		assert_eq!(args.arg_ws_interface, "all");
+	}
+}
@@ -19,26 +19,71 @@ use std::path::{Path, PathBuf};
 
 use ansi_term::Colour::White;
 use ethcore_logger::Config as LogConfig;
+use ethkey::Password;
 use rpc;
 use rpc_apis;
 use parity_rpc;
 use path::restrict_permissions_owner;
 
 pub const CODES_FILENAME: &'static str = "authcodes";
+pub const SIGNATURES_FILENAME: &'static str = "signatures";
 
 pub struct NewToken {
 	pub token: String,
 	pub message: String,
 }
 
-pub fn new_service(ws_conf: &rpc::WsConfiguration, logger_config: &LogConfig) -> rpc_apis::SignerService {
+/// Properties of a newly generated token.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TokenSpec {
+	pub name: Option<String>,
+	pub scope: Option<Vec<String>>,
+	pub expiry: Option<u64>,
+}
+
+pub fn new_service(
+	ws_conf: &rpc::WsConfiguration,
+	logger_config: &LogConfig,
+	auto_approve: Option<&String>,
+	passwords: &[Password],
+) -> Result<rpc_apis::SignerService, String> {
 	let logger_config_color = logger_config.color;
 	let signer_path = ws_conf.signer_path.clone();
 	let signer_enabled = ws_conf.support_token_api;
 
-	rpc_apis::SignerService::new(move || {
-		generate_new_token(&signer_path, logger_config_color).map_err(|e| format!("{:?}", e))
+	let method_registry = method_registry(&signer_path);
+	let token_store = codes_path(&signer_path);
+
+	let service = rpc_apis::SignerService::new(move || {
+		generate_new_token(&signer_path, logger_config_color, &TokenSpec::default()).map_err(|e| format!("{:?}", e))
 	}, signer_enabled)
+		.with_method_registry(method_registry)
+		.with_token_store(token_store);
+
+	match auto_approve {
+		Some(path) => {
+			let rules = parity_rpc::signer::ApprovalRules::from_file(Path::new(path))?;
+			if passwords.is_empty() {
+				warn!("Auto-approval rules are enabled, but no passwords were provided with --password.");
+			}
+			info!("Auto-approving signer requests matching {} rule(s) from {}", rules.rules.len(), path);
+			Ok(service.with_auto_approval(rules, passwords.to_vec()))
+		},
+		None => Ok(service),
+	}
+}
+
+/// Loads method signatures used to describe pending transactions.
+fn method_registry(path: &Path) -> parity_rpc::signer::MethodRegistry {
+	let path = path.join(SIGNATURES_FILENAME);
+	if !path.exists() {
+		return Default::default();
+	}
+
+	parity_rpc::signer::MethodRegistry::from_file(&path).unwrap_or_else(|e| {
+		warn!("Unable to read method signatures from {}: {}", path.display(), e);
+		Default::default()
+	})
 }
 
 pub fn codes_path(path: &Path) -> PathBuf {
@@ -48,12 +93,12 @@ pub fn codes_path(path: &Path) -> PathBuf {
 	p
 }
 
-pub fn execute(ws_conf: rpc::WsConfiguration, logger_config: LogConfig) -> Result<String, String> {
-	Ok(generate_token_and_url(&ws_conf, &logger_config)?.message)
+pub fn execute(ws_conf: rpc::WsConfiguration, logger_config: LogConfig, spec: TokenSpec) -> Result<String, String> {
+	Ok(generate_token_and_url(&ws_conf, &logger_config, &spec)?.message)
 }
 
-pub fn generate_token_and_url(ws_conf: &rpc::WsConfiguration, logger_config: &LogConfig) -> Result<NewToken, String> {
-	let code = generate_new_token(&ws_conf.signer_path, logger_config.color).map_err(|err| format!("Error generating token: {:?}", err))?;
+pub fn generate_token_and_url(ws_conf: &rpc::WsConfiguration, logger_config: &LogConfig, spec: &TokenSpec) -> Result<NewToken, String> {
+	let code = generate_new_token(&ws_conf.signer_path, logger_config.color, spec).map_err(|err| format!("Error generating token: {:?}", err))?;
 	let colored = |s: String| match logger_config.color {
 		true => format!("{}", White.bold().paint(s)),
 		false => s,
@@ -71,11 +116,44 @@ Generated token:
 	})
 }
 
-fn generate_new_token(path: &Path, logger_config_color: bool) -> io::Result<String> {
+/// Lists tokens stored in given authorization codes file.
+pub fn list_tokens(authfile: &Path) -> Result<String, String> {
+	let mut codes = parity_rpc::AuthCodes::from_file(authfile).map_err(|e| format!("Error reading tokens: {:?}", e))?;
+	codes.clear_garbage();
+	let tokens = codes.tokens();
+	if tokens.is_empty() {
+		return Ok("No authorization tokens.".into());
+	}
+
+	Ok(tokens.into_iter().map(|token| format!(
+		"{} name: {}, scope: {}, created: {}, last used: {}, expires: {}",
+		token.id,
+		token.name.unwrap_or_else(|| "-".into()),
+		token.scope.map_or_else(|| "all".into(), |scope| scope.join(",")),
+		token.created_at,
+		token.last_used_at.map_or_else(|| "never".into(), |t| t.to_string()),
+		token.expires_at.map_or_else(|| "never".into(), |t| t.to_string()),
+	)).collect::<Vec<_>>().join("\n"))
+}
+
+/// Revokes tokens with given id or name.
+pub fn revoke_token(authfile: &Path, id: &str) -> Result<String, String> {
+	let mut codes = parity_rpc::AuthCodes::from_file(authfile).map_err(|e| format!("Error reading tokens: {:?}", e))?;
+	codes.clear_garbage();
+	match codes.revoke(id) {
+		0 => Err(format!("No token matching {}", id)),
+		revoked => {
+			codes.to_file(authfile).map_err(|e| format!("Error saving tokens: {:?}", e))?;
+			Ok(format!("Revoked {} token(s).", revoked))
+		},
+	}
+}
+
+fn generate_new_token(path: &Path, logger_config_color: bool, spec: &TokenSpec) -> io::Result<String> {
 	let path = codes_path(path);
 	let mut codes = parity_rpc::AuthCodes::from_file(&path)?;
 	codes.clear_garbage();
-	let code = codes.generate_new()?;
+	let code = codes.generate_scoped(spec.name.clone(), spec.scope.clone(), spec.expiry)?;
 	codes.to_file(&path)?;
 	trace!("New key code created: {}", match logger_config_color {
 		true => format!("{}", White.bold().paint(&code[..])),
@@ -0,0 +1,127 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sends HTTP notifications about new signer confirmation requests.
+
+use std::sync::Arc;
+
+use hash_fetch::fetch::{Client as FetchClient, Fetch};
+use parity_reactor::Remote;
+use parity_rpc::signer::{SignerService, QueueEvent, ConfirmationRequest, ConfirmationPayload};
+use serde_json::Value;
+use webhook::Webhook;
+
+/// POSTs a summary of every new confirmation request to a webhook.
+pub struct SignerNotifier<F: Fetch = FetchClient> {
+	webhook: Webhook<F>,
+}
+
+impl<F: Fetch> SignerNotifier<F> {
+	/// Create new `SignerNotifier`.
+	pub fn new(url: &str, fetch: F, remote: Remote) -> Result<Self, String> {
+		Ok(SignerNotifier {
+			webhook: Webhook::new("signer notification", url, fetch, remote)?,
+		})
+	}
+
+	/// Start listening to new requests in the queue of given signer service.
+	pub fn attach(self, signer: &Arc<SignerService>) {
+		let service = Arc::downgrade(signer);
+		signer.queue().on_event(move |event| {
+			let id = match event {
+				QueueEvent::NewRequest(id) => id,
+				_ => return,
+			};
+			let request = service.upgrade().and_then(|signer| {
+				signer.requests_with_details().into_iter().find(|request| request.id == id.into())
+			});
+			if let Some(request) = request {
+				self.notify(&request);
+			}
+		});
+	}
+
+	fn notify(&self, request: &ConfirmationRequest) {
+		self.webhook.post(&request_to_json(request));
+	}
+}
+
+fn request_to_json(request: &ConfirmationRequest) -> Value {
+	let (kind, from, to, value) = match request.payload {
+		ConfirmationPayload::SendTransaction(ref t) => ("sendTransaction", t.from.clone(), t.to.clone(), t.value),
+		ConfirmationPayload::SignTransaction(ref t) => ("signTransaction", t.from.clone(), t.to.clone(), t.value),
+		ConfirmationPayload::EthSignMessage(ref r) => ("sign", Some(r.address.clone()), None, None),
+		ConfirmationPayload::Decrypt(ref r) => ("decrypt", Some(r.address.clone()), None, None),
+	};
+	let method = request.details.as_ref().and_then(|details| details.method.clone());
+
+	json!({
+		"id": request.id,
+		"type": kind,
+		"from": from,
+		"to": to,
+		"value": value,
+		"method": method,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::Address;
+	use parity_reactor::Remote;
+	use parity_rpc::signer::{ConfirmationRequest, ConfirmationPayload, ConfirmationDetails, TransactionRequest};
+	use webhook::tests::{RecordingFetch, post_request};
+	use super::{SignerNotifier, request_to_json};
+
+	fn request() -> ConfirmationRequest {
+		ConfirmationRequest {
+			id: 15.into(),
+			payload: ConfirmationPayload::SendTransaction(TransactionRequest {
+				from: Some(Address::from(1).into()),
+				to: Some(Address::from(2).into()),
+				value: Some(1_000.into()),
+				..Default::default()
+			}),
+			origin: Default::default(),
+			details: Some(ConfirmationDetails {
+				method: Some("transfer(address,uint256)".into()),
+				..Default::default()
+			}),
+		}
+	}
+
+	#[test]
+	fn should_serialize_request() {
+		assert_eq!(request_to_json(&request()), json!({
+			"id": "0xf",
+			"type": "sendTransaction",
+			"from": "0x0000000000000000000000000000000000000001",
+			"to": "0x0000000000000000000000000000000000000002",
+			"value": "0x3e8",
+			"method": "transfer(address,uint256)",
+		}));
+	}
+
+	#[test]
+	fn should_post_request_summary() {
+		let fetch = RecordingFetch::default();
+		let notifier = SignerNotifier::new("http://localhost:8000/signer", fetch.clone(), Remote::new_sync()).unwrap();
+
+		notifier.notify(&request());
+
+		assert_eq!(fetch.requests(), vec![post_request("http://localhost:8000/signer", &request_to_json(&request()).to_string())]);
+	}
+}
@@ -0,0 +1,79 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Service manager notifications (`sd_notify`) for units with `Type=notify`.
+
+use std::env;
+use std::process;
+use std::time::Duration;
+
+/// Sends `state` (e.g. `READY=1`) to the service manager.
+/// Does nothing unless started by systemd with a notification socket.
+#[cfg(unix)]
+pub fn notify(state: &str) {
+	use std::os::unix::net::UnixDatagram;
+
+	let socket = match env::var("NOTIFY_SOCKET") {
+		Ok(socket) => socket,
+		Err(_) => return,
+	};
+
+	if socket.starts_with('@') {
+		warn!(target: "systemd", "Abstract notification socket {} is not supported.", socket);
+		return;
+	}
+
+	let result = UnixDatagram::unbound().and_then(|sender| sender.send_to(state.as_bytes(), &socket));
+	if let Err(e) = result {
+		warn!(target: "systemd", "Unable to notify the service manager: {}", e);
+	}
+}
+
+#[cfg(not(unix))]
+pub fn notify(_state: &str) {}
+
+/// How often the service manager expects `WATCHDOG=1`, if the watchdog is enabled for this process.
+pub fn watchdog_interval() -> Option<Duration> {
+	// the watchdog applies to the main process only
+	if let Ok(pid) = env::var("WATCHDOG_PID") {
+		if pid.parse::<u32>().ok() != Some(process::id()) {
+			return None;
+		}
+	}
+
+	parse_watchdog_usec(&env::var("WATCHDOG_USEC").ok()?)
+}
+
+fn parse_watchdog_usec(usec: &str) -> Option<Duration> {
+	match usec.parse::<u64>() {
+		Ok(0) | Err(_) => None,
+		Ok(usec) => Some(Duration::new(usec / 1_000_000, (usec % 1_000_000) as u32 * 1_000)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+	use super::parse_watchdog_usec;
+
+	#[test]
+	fn should_parse_watchdog_interval() {
+		assert_eq!(parse_watchdog_usec("30000000"), Some(Duration::from_secs(30)));
+		assert_eq!(parse_watchdog_usec("1500000"), Some(Duration::from_millis(1500)));
+		assert_eq!(parse_watchdog_usec("0"), None);
+		assert_eq!(parse_watchdog_usec("soon"), None);
+	}
+}
@@ -0,0 +1,217 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! In-process dev chain node for integration tests of embedding projects.
+
+use std::path::Path;
+
+use serde_json::{self, Value};
+use tempdir::TempDir;
+
+use configuration::Configuration;
+use run::{RunningClient, RpcHandle};
+use {start, ExecutionAction};
+
+/// Creates a builder for a dev chain node running in this process.
+///
+/// The node keeps its data in a temporary directory and doesn't open any RPC servers
+/// or look for peers. It is shut down and its data removed when the returned `TestNode` is dropped.
+///
+/// ```no_run
+/// let node = parity::test_node().manual_seal().start().unwrap();
+/// node.seal_block().unwrap();
+/// let response = node.rpc(r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#);
+/// assert_eq!(response.unwrap(), r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#);
+/// ```
+pub fn test_node() -> TestNodeBuilder {
+	TestNodeBuilder::default()
+}
+
+/// Configures a dev chain node for tests. Created by `test_node()`.
+#[derive(Debug, Default)]
+pub struct TestNodeBuilder {
+	manual_seal: bool,
+	extra_args: Vec<String>,
+}
+
+impl TestNodeBuilder {
+	/// Seal blocks only when `TestNode::seal_block` is called, instead of for every transaction.
+	pub fn manual_seal(mut self) -> Self {
+		self.manual_seal = true;
+		self
+	}
+
+	/// Passes an additional command line argument to the node, e.g. `--gasprice=0`.
+	pub fn arg<S: Into<String>>(mut self, arg: S) -> Self {
+		self.extra_args.push(arg.into());
+		self
+	}
+
+	/// Starts the node.
+	pub fn start(self) -> Result<TestNode, String> {
+		let dir = TempDir::new("parity-test-node").map_err(|e| format!("Unable to create temporary directory: {}", e))?;
+		let base_path = dir.path().to_str().ok_or_else(|| "Temporary directory path is not valid UTF-8".to_owned())?.to_owned();
+		let conf = Configuration::parse_cli(&self.args(&base_path)).map_err(|e| format!("Invalid test node arguments: {:?}", e))?;
+
+		match start(conf, |_| {}, || {})? {
+			ExecutionAction::Running(client) => Ok(TestNode {
+				client: Some(client),
+				dir: dir,
+			}),
+			ExecutionAction::Instant(_) => Err("Test node arguments have to start a client".into()),
+		}
+	}
+
+	fn args(&self, base_path: &str) -> Vec<String> {
+		let mut args: Vec<String> = vec![
+			"parity".into(),
+			"--no-config".into(),
+			"--chain=dev".into(),
+			format!("--base-path={}", base_path),
+			"--mode=active".into(),
+			"--port=0".into(),
+			"--no-discovery".into(),
+			"--no-jsonrpc".into(),
+			"--no-ws".into(),
+			"--no-ipc".into(),
+			"--no-dapps".into(),
+			"--no-secretstore".into(),
+			"--no-hardware-wallets".into(),
+			"--no-persistent-txqueue".into(),
+			"--no-periodic-snapshot".into(),
+		];
+		if self.manual_seal {
+			args.push("--dev-manual-seal".into());
+		}
+		args.extend(self.extra_args.iter().cloned());
+		args
+	}
+}
+
+/// Dev chain node running in this process. Shuts down and removes its data when dropped.
+pub struct TestNode {
+	client: Option<RunningClient>,
+	dir: TempDir,
+}
+
+impl TestNode {
+	/// Directory holding the node's data.
+	pub fn base_path(&self) -> &Path {
+		self.dir.path()
+	}
+
+	/// Dispatches a raw JSON-RPC request to the node and waits for the response.
+	/// All APIs are available, including the unsafe ones.
+	pub fn rpc(&self, request: &str) -> Option<String> {
+		self.client.as_ref().and_then(|client| client.rpc_query_sync(request))
+	}
+
+	/// Handle dispatching requests to the node, e.g. for the typed clients in `rpc_client`.
+	pub fn rpc_handle(&self) -> RpcHandle {
+		self.client.as_ref().expect("client is only taken on drop; qed").rpc_handle()
+	}
+
+	/// Calls a JSON-RPC method and returns its result, or the error reported by the node.
+	pub fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+		let request = json!({
+			"jsonrpc": "2.0",
+			"method": method,
+			"params": params,
+			"id": 1,
+		});
+		let response = self.rpc(&request.to_string()).ok_or_else(|| format!("No response to {}", method))?;
+		let response: Value = serde_json::from_str(&response).map_err(|e| format!("Invalid response to {}: {}", method, e))?;
+
+		if let Some(error) = response.get("error") {
+			return Err(format!("{} failed: {}", method, error));
+		}
+		response.get("result").cloned().ok_or_else(|| format!("Missing result in response to {}", method))
+	}
+
+	/// Seals a new block with the pending transactions, even if there are none.
+	pub fn seal_block(&self) -> Result<(), String> {
+		self.call("evm_mine", json!([])).map(|_| ())
+	}
+}
+
+impl Drop for TestNode {
+	fn drop(&mut self) {
+		if let Some(client) = self.client.take() {
+			client.shutdown();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::test_node;
+
+	#[test]
+	fn builds_dev_chain_args() {
+		let args = test_node().manual_seal().arg("--gasprice=0").args("/tmp/node");
+		assert_eq!(args[0], "parity");
+		assert!(args.contains(&"--chain=dev".to_owned()));
+		assert!(args.contains(&"--base-path=/tmp/node".to_owned()));
+		assert!(args.contains(&"--dev-manual-seal".to_owned()));
+		assert_eq!(args.last().unwrap(), "--gasprice=0");
+	}
+
+	#[test]
+	fn seals_blocks_on_request_and_cleans_up() {
+		let node = test_node().manual_seal().start().unwrap();
+		let base_path = node.base_path().to_owned();
+		assert!(base_path.exists());
+
+		assert_eq!(node.call("eth_blockNumber", json!([])), Ok(json!("0x0")));
+		node.seal_block().unwrap();
+		node.seal_block().unwrap();
+		assert_eq!(node.call("eth_blockNumber", json!([])), Ok(json!("0x2")));
+
+		let response = node.rpc(r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":7}"#);
+		assert_eq!(response, Some(r#"{"jsonrpc":"2.0","result":"0x2","id":7}"#.to_owned()));
+		assert!(node.call("eth_noSuchMethod", json!([])).is_err());
+
+		drop(node);
+		assert!(!base_path.exists());
+	}
+
+	#[test]
+	fn reads_accounts_info_from_the_state() {
+		let node = test_node().manual_seal().start().unwrap();
+
+		let info = node.call("parity_getAccountsInfoAt", json!([[
+			"0x00a329c0648769a73afac7f9381e08fb43dbea72",
+			"0x0000000000000000000000000000000000001337",
+			"0x00000000000000000000000000000000000000ff",
+		], "latest"])).unwrap();
+
+		// the dev account, the registry created in the genesis and an empty account
+		assert_eq!(info[0], json!({
+			"address": "0x00a329c0648769a73afac7f9381e08fb43dbea72",
+			"balance": "0x100000000000000000000000000000000000000000000000000",
+			"nonce": "0x0",
+			"hasCode": false,
+		}));
+		assert_eq!(info[1]["balance"], json!("0x1"));
+		assert_eq!(info[1]["hasCode"], json!(true));
+		assert_eq!(info[2], json!({
+			"address": "0x00000000000000000000000000000000000000ff",
+			"balance": "0x0",
+			"nonce": "0x0",
+			"hasCode": false,
+		}));
+	}
+}
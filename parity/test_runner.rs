@@ -0,0 +1,380 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runner of the JSON consensus tests of ethereum/tests.
+//!
+//! `GeneralStateTests` are executed transaction by transaction with the same executor the client uses,
+//! `BlockchainTests` are imported into a client backed by a temporary database.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ethcore::block::Block;
+use ethcore::client::{Client, ClientConfig, ChainInfo, ImportBlock, EvmTestClient, EnvInfo, TransactResult};
+use ethcore::miner::Miner;
+use ethcore::pod_state::PodState;
+use ethcore::spec::{Genesis, Spec, SpecParams, OptimizeFor};
+use ethcore::trace;
+use ethereum_types::H256;
+use ethjson;
+use ethjson::state::test::ForkSpec;
+use io::IoChannel;
+use serde_json::{self, Value};
+use tempdir::TempDir;
+use transaction::SignedTransaction;
+
+use cache::CacheConfig;
+use dir::Directories;
+use params::SpecType;
+use db;
+
+#[derive(Debug, PartialEq)]
+pub struct TestRunnerCmd {
+	/// File or directory of test files.
+	pub path: String,
+	/// Only run tests of this fork.
+	pub fork: Option<String>,
+	/// Specification to run the tests of `fork` with in place of the built-in one.
+	pub spec: Option<SpecType>,
+	pub dirs: Directories,
+}
+
+/// Outcome of a single test.
+#[derive(Debug, PartialEq)]
+enum Outcome {
+	Passed,
+	Failed(String),
+	Skipped(String),
+}
+
+pub fn execute(cmd: TestRunnerCmd) -> Result<String, String> {
+	let mut files = Vec::new();
+	collect_files(Path::new(&cmd.path), &mut files)
+		.map_err(|e| format!("Could not read tests at {}: {}", cmd.path, e))?;
+	if files.is_empty() {
+		return Err(format!("No test files found at {}", cmd.path));
+	}
+	files.sort();
+
+	let (mut passed, mut skipped) = (0, 0);
+	let mut failed = Vec::new();
+	for file in &files {
+		for (name, outcome) in run_file(&cmd, file) {
+			match outcome {
+				Outcome::Passed => {
+					passed += 1;
+					println!("ok      {}", name);
+				},
+				Outcome::Failed(reason) => {
+					println!("FAIL    {}: {}", name, reason);
+					failed.push(name);
+				},
+				Outcome::Skipped(reason) => {
+					skipped += 1;
+					println!("skipped {}: {}", name, reason);
+				},
+			}
+		}
+	}
+
+	let summary = format!("{} passed, {} failed, {} skipped.", passed, failed.len(), skipped);
+	match failed.is_empty() {
+		true => Ok(summary),
+		false => Err(format!("{}\nFailed tests:\n{}", summary, failed.into_iter().map(|name| format!("  - {}", name)).collect::<Vec<_>>().join("\n"))),
+	}
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> ::std::io::Result<()> {
+	if !path.is_dir() {
+		files.push(path.to_owned());
+		return Ok(());
+	}
+
+	for entry in fs::read_dir(path)? {
+		let path = entry?.path();
+		if path.is_dir() {
+			collect_files(&path, files)?;
+		} else if path.extension().map_or(false, |extension| extension == "json") {
+			files.push(path);
+		}
+	}
+	Ok(())
+}
+
+fn run_file(cmd: &TestRunnerCmd, file: &Path) -> Vec<(String, Outcome)> {
+	let file_name = file.display().to_string();
+	let mut data = Vec::new();
+	if let Err(e) = fs::File::open(file).and_then(|mut f| f.read_to_end(&mut data)) {
+		return vec![(file_name, Outcome::Failed(format!("Could not read file: {}", e)))];
+	}
+
+	// both kinds of tests are objects of named tests, told apart by their fields.
+	let first = serde_json::from_slice::<Value>(&data).ok()
+		.and_then(|tests| tests.as_object().and_then(|tests| tests.values().next().cloned()));
+	let is_field = |field: &str| first.as_ref().and_then(|test| test.get(field)).is_some();
+
+	if is_field("blocks") {
+		match ethjson::blockchain::Test::load(&data[..]) {
+			Ok(tests) => tests.into_iter()
+				.map(|(name, test)| (format!("{}: {}", file_name, name), run_blockchain_test(cmd, test)))
+				.collect(),
+			Err(e) => vec![(file_name, Outcome::Failed(format!("Invalid blockchain test: {}", e)))],
+		}
+	} else if is_field("post") {
+		match ethjson::state::test::Test::load(&data[..]) {
+			Ok(tests) => tests.into_iter()
+				.flat_map(|(name, test)| run_state_test(cmd, &format!("{}: {}", file_name, name), test))
+				.collect(),
+			Err(e) => vec![(file_name, Outcome::Failed(format!("Invalid state test: {}", e)))],
+		}
+	} else {
+		vec![(file_name, Outcome::Skipped("Not a state or blockchain test".into()))]
+	}
+}
+
+/// Returns the specification to run tests of given fork with, or the reason to skip them.
+fn fork_spec(cmd: &TestRunnerCmd, fork: &ForkSpec) -> Result<Spec, String> {
+	let fork_name = format!("{:?}", fork);
+	match cmd.fork {
+		Some(ref only) if *only != fork_name => return Err(format!("{} tests not selected", fork_name)),
+		_ => {},
+	}
+
+	match cmd.spec {
+		Some(ref spec) => spec.spec(SpecParams::new(cmd.dirs.cache.as_ref(), OptimizeFor::Memory)),
+		None => EvmTestClient::spec_from_json(fork).ok_or_else(|| format!("No built-in specification of {}", fork_name)),
+	}
+}
+
+fn run_state_test(cmd: &TestRunnerCmd, name: &str, test: ethjson::state::test::State) -> Vec<(String, Outcome)> {
+	let env: EnvInfo = test.env.into();
+	let pre: PodState = test.pre_state.into();
+	let transactions = test.transaction;
+	let mut outcomes = Vec::new();
+
+	for (fork, states) in test.post_states {
+		let total = states.len();
+		let spec = match fork_spec(cmd, &fork) {
+			Ok(spec) => spec,
+			Err(reason) => {
+				outcomes.push((format!("{} ({:?})", name, fork), Outcome::Skipped(reason)));
+				continue;
+			},
+		};
+
+		for (i, state) in states.into_iter().enumerate() {
+			let expected: H256 = state.hash.into();
+			let transaction: SignedTransaction = transactions.select(&state.indexes).into();
+			let result = EvmTestClient::from_pod_state(&spec, pre.clone())
+				.map(|mut client| client.transact(&env, transaction, trace::NoopTracer, trace::NoopVMTracer));
+
+			let outcome = match result {
+				Err(e) => Outcome::Failed(format!("Internal error: {:?}", e)),
+				Ok(TransactResult::Ok { state_root, .. }) | Ok(TransactResult::Err { state_root, .. }) if state_root == expected => Outcome::Passed,
+				Ok(TransactResult::Ok { state_root, .. }) =>
+					Outcome::Failed(format!("State root mismatch, got {:?}, expected {:?}", state_root, expected)),
+				Ok(TransactResult::Err { state_root, error }) =>
+					Outcome::Failed(format!("State root mismatch, got {:?}, expected {:?} (execution error: {:?})", state_root, expected, error)),
+			};
+			outcomes.push((format!("{} ({:?} {}/{})", name, fork, i + 1, total), outcome));
+		}
+	}
+
+	outcomes
+}
+
+fn run_blockchain_test(cmd: &TestRunnerCmd, test: ethjson::blockchain::BlockChain) -> Outcome {
+	let mut spec = match fork_spec(cmd, &test.network) {
+		Ok(spec) => spec,
+		Err(reason) => return Outcome::Skipped(reason),
+	};
+
+	let genesis = Genesis::from(test.genesis());
+	if let Err(e) = spec.set_genesis_state(test.pre_state.clone().into()) {
+		return Outcome::Failed(format!("Invalid pre state: {}", e));
+	}
+	spec.overwrite_genesis_params(genesis);
+
+	let expected: H256 = test.best_block.into();
+	match import_blocks(&spec, &test.blocks_rlp()) {
+		Ok(ref best) if *best == expected => Outcome::Passed,
+		Ok(best) => Outcome::Failed(format!("Best block mismatch, got {:?}, expected {:?}", best, expected)),
+		Err(e) => Outcome::Failed(e),
+	}
+}
+
+/// Imports given blocks into a new client and returns the hash of its best block.
+///
+/// Blocks which aren't valid RLP and blocks failing verification are left out, as the tests expect.
+fn import_blocks(spec: &Spec, blocks: &[Vec<u8>]) -> Result<H256, String> {
+	let dir = TempDir::new("parity-test-runner").map_err(|e| format!("Could not create database directory: {}", e))?;
+	let path = dir.path().to_str().expect("Temporary directory path is valid unicode; qed");
+	let db = db::open_db(path, &CacheConfig::default(), &Default::default(), false, false)
+		.map_err(|e| format!("Failed to open database: {:?}", e))?;
+
+	let client = Client::new(
+		ClientConfig::default(),
+		spec,
+		db,
+		Arc::new(Miner::new_for_tests(spec, None)),
+		IoChannel::disconnected(),
+	).map_err(|e| format!("Failed to start client: {}", e))?;
+
+	for block in blocks.iter().filter(|block| Block::is_good(block)) {
+		let _ = client.import_block(block.clone());
+		client.flush_queue();
+		client.import_verified_blocks();
+	}
+
+	Ok(client.chain_info().best_block_hash)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::File;
+	use std::io::Write;
+	use std::path::{Path, PathBuf};
+	use tempdir::TempDir;
+	use dir::Directories;
+	use params::SpecType;
+	use super::{TestRunnerCmd, Outcome, execute, run_file};
+
+	const STATE_TEST: &'static str = r#"{
+		"transfer": {
+			"env": {
+				"currentCoinbase": "2adc25665018aa1fe0e6bc666dac8fc2697ff9ba",
+				"currentDifficulty": "0x020000",
+				"currentGasLimit": "0x7fffffffffffffff",
+				"currentNumber": "0x01",
+				"currentTimestamp": "0x03e8",
+				"previousHash": "5e20a0453cecd065ea59c37ac63e079ee08998b6045136a8ce6635c7912ec0b6"
+			},
+			"post": {
+				"Byzantium": [
+					{ "hash": "0000000000000000000000000000000000000000000000000000000000000000", "indexes": { "data": 0, "gas": 0, "value": 0 } }
+				],
+				"Homestead": [
+					{ "hash": "0000000000000000000000000000000000000000000000000000000000000000", "indexes": { "data": 0, "gas": 0, "value": 0 } }
+				]
+			},
+			"pre": {
+				"a94f5374fce5edbc8e2a8697c15331677e6ebf0b": { "balance": "0x0de0b6b3a7640000", "code": "0x", "nonce": "0x00", "storage": {} }
+			},
+			"transaction": {
+				"data": [ "" ],
+				"gasLimit": [ "0x5208" ],
+				"gasPrice": "0x01",
+				"nonce": "0x00",
+				"secretKey": "45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+				"to": "095e7baea6a6c7c4c2dfeb977efac326af552d87",
+				"value": [ "0x01" ]
+			}
+		}
+	}"#;
+
+	fn write(dir: &TempDir, name: &str, content: &str) -> ::std::path::PathBuf {
+		let path = dir.path().join(name);
+		File::create(&path).unwrap().write_all(content.as_bytes()).unwrap();
+		path
+	}
+
+	/// Path of a file of the ethereum/tests submodule.
+	fn fixture(path: &str) -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("ethcore/res/ethereum/tests").join(path)
+	}
+
+	fn fixture_cmd(file: &Path, fork: &str, spec: Option<SpecType>) -> TestRunnerCmd {
+		TestRunnerCmd {
+			path: file.to_str().unwrap().into(),
+			fork: Some(fork.into()),
+			spec: spec,
+			dirs: Directories::default(),
+		}
+	}
+
+	/// Checks that the tests of `fork` passed and the tests of other forks were skipped.
+	fn assert_passed(outcomes: &[(String, Outcome)], fork: &str) {
+		assert!(outcomes.iter().any(|&(_, ref outcome)| *outcome == Outcome::Passed));
+		for &(ref name, ref outcome) in outcomes {
+			match *outcome {
+				Outcome::Passed => assert!(name.contains(fork), "{} shouldn't have run", name),
+				Outcome::Skipped(_) => assert!(!name.contains(&format!("({}", fork)), "{} shouldn't have been skipped", name),
+				Outcome::Failed(ref reason) => panic!("{} failed: {}", name, reason),
+			}
+		}
+	}
+
+	#[test]
+	fn should_pass_state_test() {
+		let file = fixture("GeneralStateTests/stExample/add11.json");
+		let outcomes = run_file(&fixture_cmd(&file, "Byzantium", None), &file);
+		assert_passed(&outcomes, "Byzantium");
+	}
+
+	#[test]
+	fn should_pass_blockchain_test() {
+		let file = fixture("BlockchainTests/bcGasPricerTest/RPC_API_Test.json");
+		let outcomes = run_file(&fixture_cmd(&file, "Frontier", None), &file);
+		assert_passed(&outcomes, "Frontier");
+	}
+
+	#[test]
+	fn should_pass_state_test_with_given_spec() {
+		let file = fixture("GeneralStateTests/stExample/add11.json");
+		let spec = Path::new(env!("CARGO_MANIFEST_DIR")).join("ethcore/res/ethereum/byzantium_test.json");
+		let cmd = || fixture_cmd(&file, "Byzantium", Some(SpecType::Custom(spec.to_str().unwrap().into())));
+
+		assert_passed(&run_file(&cmd(), &file), "Byzantium");
+		assert!(execute(cmd()).is_ok());
+	}
+
+	#[test]
+	fn should_report_each_state_test() {
+		let dir = TempDir::new("").unwrap();
+		let file = write(&dir, "transfer.json", STATE_TEST);
+		let cmd = TestRunnerCmd {
+			path: dir.path().to_str().unwrap().into(),
+			fork: Some("Byzantium".into()),
+			spec: None,
+			dirs: Directories::default(),
+		};
+
+		let outcomes = run_file(&cmd, &file);
+		assert_eq!(outcomes.len(), 2);
+		assert_eq!(outcomes[0], (format!("{}: transfer (Homestead)", file.display()), Outcome::Skipped("Homestead tests not selected".into())));
+		assert_eq!(outcomes[1].0, format!("{}: transfer (Byzantium 1/1)", file.display()));
+		match outcomes[1].1 {
+			Outcome::Failed(ref reason) => assert!(reason.starts_with("State root mismatch")),
+			ref other => panic!("Unexpected outcome: {:?}", other),
+		}
+	}
+
+	#[test]
+	fn should_skip_other_files() {
+		let dir = TempDir::new("").unwrap();
+		let file = write(&dir, "filler.json", r#"{"transfer": {"expect": []}}"#);
+		let cmd = TestRunnerCmd {
+			path: dir.path().to_str().unwrap().into(),
+			fork: None,
+			spec: None,
+			dirs: Directories::default(),
+		};
+
+		assert_eq!(run_file(&cmd, &file), vec![(file.display().to_string(), Outcome::Skipped("Not a state or blockchain test".into()))]);
+	}
+}
@@ -123,11 +123,15 @@ fn with_locked_version<F>(db_path: Option<&str>, script: F) -> Result<usize, Err
 			})
 			.unwrap_or(Version::new(0, 9, 0));
 
-	let mut lock = File::create(&path).map_err(|_| Error::CannotWriteVersionFile)?;
 	let result = script(&version);
 
+	// Replace the lock atomically, so that an interrupted write doesn't reset the version.
 	let written_version = Version::parse(CURRENT_VERSION)?;
-	lock.write_all(written_version.to_string().as_bytes()).map_err(|_| Error::CannotUpdateVersionFile)?;
+	let temp = path.with_extension("lock.tmp");
+	File::create(&temp)
+		.and_then(|mut lock| lock.write_all(written_version.to_string().as_bytes()).and_then(|_| lock.sync_all()))
+		.map_err(|_| Error::CannotWriteVersionFile)?;
+	fs::rename(&temp, &path).map_err(|_| Error::CannotUpdateVersionFile)?;
 	result
 }
 
@@ -144,74 +148,341 @@ fn file_exists(path: &Path) -> bool {
 	}
 }
 
-pub fn upgrade_key_location(from: &PathBuf, to: &PathBuf) {
-	match fs::create_dir_all(&to).and_then(|()| fs::read_dir(from)) {
-		Ok(entries) => {
-			let files: Vec<_> = entries.filter_map(|f| f.ok().and_then(|f| if f.file_type().ok().map_or(false, |f| f.is_file()) { f.file_name().to_str().map(|s| s.to_owned()) } else { None })).collect();
-			let mut num: usize = 0;
-			for name in files {
-				let mut from = from.clone();
-				from.push(&name);
-				let mut to = to.clone();
-				to.push(&name);
-				if !file_exists(&to) {
-					if let Err(e) = fs::rename(&from, &to) {
-						debug!("Error upgrading key {:?}: {:?}", from, e);
-					} else {
-						num += 1;
+/// A step of a data layout upgrade.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Migration {
+	/// Move a file or directory. Skipped if the source is missing or the destination exists.
+	Move(PathBuf, PathBuf),
+	/// Move the files of a directory into another one, skipping files which exist there already.
+	MoveFiles(PathBuf, PathBuf),
+}
+
+/// Outcome of a data layout upgrade.
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+	/// Moved paths, as `(source, destination)`.
+	pub moved: Vec<(PathBuf, PathBuf)>,
+	/// Paths left in place, with the reason.
+	pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl Summary {
+	fn report(&self) {
+		for &(ref source, ref dest) in &self.moved {
+			info!("Moved {} to {}", source.to_string_lossy(), dest.to_string_lossy());
+		}
+		for &(ref source, ref reason) in &self.skipped {
+			debug!("Skipped upgrading {}: {}", source.to_string_lossy(), reason);
+		}
+		if !self.moved.is_empty() {
+			info!("Upgrade complete: {} paths moved, {} skipped", self.moved.len(), self.skipped.len());
+		}
+	}
+}
+
+/// Expands a migration into the single moves to perform, given the current state of the file system.
+fn plan(migration: &Migration, summary: &mut Summary) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+	let mut moves = Vec::new();
+	match *migration {
+		Migration::Move(ref source, ref dest) => moves.push((source.clone(), dest.clone())),
+		Migration::MoveFiles(ref source, ref dest) => {
+			if file_exists(source) {
+				let mut files = Vec::new();
+				for entry in fs::read_dir(source)? {
+					let entry = entry?;
+					if entry.file_type()?.is_file() {
+						files.push(entry.file_name());
 					}
-				} else {
-					debug!("Skipped upgrading key {:?}", from);
 				}
-			}
-			if num > 0 {
-				info!("Moved {} keys from {} to {}", num, from.to_string_lossy(), to.to_string_lossy());
+				files.sort();
+				moves.extend(files.into_iter().map(|name| (source.join(&name), dest.join(name))));
 			}
 		},
-		Err(e) => {
-			debug!("Error moving keys from {:?} to {:?}: {:?}", from, to, e);
+	}
+
+	let mut planned: Vec<(PathBuf, PathBuf)> = Vec::new();
+	for (source, dest) in moves {
+		if !file_exists(&source) {
+			continue;
+		}
+		if file_exists(&dest) || planned.iter().any(|&(_, ref d)| *d == dest) {
+			summary.skipped.push((source, format!("{} already exists", dest.to_string_lossy())));
+		} else {
+			planned.push((source, dest));
 		}
 	}
+	Ok(planned)
 }
 
-fn upgrade_dir_location(source: &PathBuf, dest: &PathBuf) {
-	if file_exists(&source) {
-		if !file_exists(&dest) {
-			let mut parent = dest.clone();
-			parent.pop();
-			if let Err(e) = fs::create_dir_all(&parent).and_then(|()| fs::rename(&source, &dest)) {
-				debug!("Skipped path {:?} -> {:?} :{:?}", source, dest, e);
-			} else {
-				info!("Moved {} to {}", source.to_string_lossy(), dest.to_string_lossy());
+fn read_journal(journal: &Path) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+	let mut content = String::new();
+	File::open(journal)?.read_to_string(&mut content)?;
+	Ok(content.lines()
+		.filter_map(|line| {
+			let mut parts = line.splitn(2, '\t');
+			match (parts.next(), parts.next()) {
+				(Some(source), Some(dest)) => Some((PathBuf::from(source), PathBuf::from(dest))),
+				_ => None,
 			}
-		} else {
-			debug!("Skipped upgrading directory {:?}, Destination already exists at {:?}", source, dest);
+		})
+		.collect())
+}
+
+/// Makes the moves, recording each one in the journal before it's made.
+/// The journal is created with the first move.
+fn execute(journal: &Path, log: &mut Option<File>, moves: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+	for &(ref source, ref dest) in moves {
+		if log.is_none() {
+			if let Some(parent) = journal.parent() {
+				create_dir_all(parent)?;
+			}
+			*log = Some(File::create(journal)?);
+		}
+		let file = log.as_mut().expect("the journal is created above; qed");
+		writeln!(file, "{}\t{}", source.to_string_lossy(), dest.to_string_lossy())?;
+		file.sync_all()?;
+		if let Some(parent) = dest.parent() {
+			create_dir_all(parent)?;
 		}
+		fs::rename(source, dest)?;
 	}
+	Ok(())
 }
 
-fn upgrade_user_defaults(dirs: &DatabaseDirectories) {
-	let source = dirs.legacy_user_defaults_path();
-	let dest = dirs.user_defaults_path();
-	if file_exists(&source) {
-		if !file_exists(&dest) {
-			if let Err(e) = fs::rename(&source, &dest) {
-				debug!("Skipped upgrading user defaults {:?}:{:?}", dest, e);
-			}
-		} else {
-			debug!("Skipped upgrading user defaults {:?}, File exists at {:?}", source, dest);
+/// Plans and executes the migrations one by one, so that each one sees the changes of the previous ones.
+fn run(journal: &Path, migrations: &[Migration], summary: &mut Summary) -> Result<(), String> {
+	let mut log = None;
+	for migration in migrations {
+		let moves = plan(migration, summary).map_err(|e| format!("Unable to plan upgrade: {}", e))?;
+		execute(journal, &mut log, &moves).map_err(|e| format!("Error upgrading data layout: {}", e))?;
+		summary.moved.extend(moves);
+	}
+	Ok(())
+}
+
+/// Moves journaled paths back, most recent first, and removes the journal.
+fn roll_back(journal: &Path) -> io::Result<()> {
+	if !file_exists(journal) {
+		return Ok(());
+	}
+	let moves = read_journal(journal)?;
+	for &(ref source, ref dest) in moves.iter().rev() {
+		// the last entry may have been journaled without being moved
+		if file_exists(dest) && !file_exists(source) {
+			fs::rename(dest, source)?;
 		}
 	}
+	fs::remove_file(journal)
+}
+
+/// Executes the migrations as a whole. Every move is recorded in the `journal` file before it's
+/// made, so that if one fails, or the process is killed, all of them are reverted (the latter
+/// on the next run). Returns a summary of the changes.
+pub fn migrate(journal: &Path, migrations: &[Migration]) -> Result<Summary, String> {
+	if file_exists(journal) {
+		warn!("Reverting an interrupted upgrade recorded in {}", journal.to_string_lossy());
+		roll_back(journal).map_err(|e| format!("Unable to revert interrupted upgrade recorded in {}: {}", journal.to_string_lossy(), e))?;
+	}
+
+	let mut summary = Summary::default();
+	match run(journal, migrations, &mut summary) {
+		Ok(()) => {
+			if file_exists(journal) {
+				fs::remove_file(journal).map_err(|e| format!("Unable to remove upgrade journal {}: {}", journal.to_string_lossy(), e))?;
+			}
+			Ok(summary)
+		},
+		Err(e) => {
+			let reverted = match roll_back(journal) {
+				Ok(()) => "All changes were reverted.".to_owned(),
+				Err(err) => format!("Reverting failed ({}); the upgrade will be reverted on next start.", err),
+			};
+			Err(format!("{}. {}", e, reverted))
+		},
+	}
+}
+
+pub fn upgrade_key_location(from: &PathBuf, to: &PathBuf) {
+	let journal = to.join(".upgrade");
+	match migrate(&journal, &[Migration::MoveFiles(from.clone(), to.clone())]) {
+		Ok(summary) => {
+			if !summary.moved.is_empty() {
+				info!("Moved {} keys from {} to {}", summary.moved.len(), from.to_string_lossy(), to.to_string_lossy());
+			}
+			for (key, reason) in summary.skipped {
+				debug!("Skipped upgrading key {:?}: {}", key, reason);
+			}
+		},
+		Err(e) => warn!("Keys were not moved from {} to {}: {}", from.to_string_lossy(), to.to_string_lossy(), e),
+	}
 }
 
-pub fn upgrade_data_paths(base_path: &str, dirs: &DatabaseDirectories, pruning: Algorithm) {
+/// Files of the address book which were kept with the keys by older versions.
+const ADDRESS_BOOK_FILES: &'static [&'static str] = &["address_book.json", "watch_only.json", "account_notes.json"];
+
+/// Moves the address book out of the keys directory.
+pub fn upgrade_address_book_location(keys_path: &Path, to: &Path) {
+	let journal = to.join(".upgrade");
+	let migrations: Vec<_> = ADDRESS_BOOK_FILES.iter()
+		.map(|file| Migration::Move(keys_path.join(file), to.join(file)))
+		.collect();
+	match migrate(&journal, &migrations) {
+		Ok(summary) => summary.report(),
+		Err(e) => warn!("Address book was not moved from {} to {}: {}", keys_path.to_string_lossy(), to.to_string_lossy(), e),
+	}
+}
+
+/// Migrations of the data directory layout of older versions.
+fn data_path_migrations(base_path: &str, dirs: &DatabaseDirectories, pruning: Algorithm) -> Vec<Migration> {
+	let mut migrations = Vec::new();
 	let legacy_root_path = replace_home("", "$HOME/.parity");
 	let default_path = default_data_path();
 	if legacy_root_path != base_path && base_path == default_path {
-		upgrade_dir_location(&PathBuf::from(legacy_root_path), &PathBuf::from(&base_path));
+		migrations.push(Migration::Move(PathBuf::from(legacy_root_path), PathBuf::from(&base_path)));
+	}
+	migrations.push(Migration::Move(dirs.legacy_version_path(pruning), dirs.db_path(pruning)));
+	migrations.push(Migration::Move(dirs.legacy_snapshot_path(), dirs.snapshot_path()));
+	migrations.push(Migration::Move(dirs.legacy_network_path(), dirs.network_path()));
+	migrations.push(Migration::Move(dirs.legacy_user_defaults_path(), dirs.user_defaults_path()));
+	migrations
+}
+
+pub fn upgrade_data_paths(base_path: &str, dirs: &DatabaseDirectories, pruning: Algorithm) -> Result<Summary, String> {
+	// the journal can't be inside the base path, which may be moved itself
+	let journal = PathBuf::from(format!("{}.upgrade", base_path.trim_right_matches(|c| c == '/' || c == '\\')));
+	let summary = migrate(&journal, &data_path_migrations(base_path, dirs, pruning))?;
+	summary.report();
+	Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::{self, File};
+	use std::io::Write;
+	use tempdir::TempDir;
+	use super::{migrate, roll_back, upgrade_address_book_location, Migration, Summary};
+
+	#[test]
+	fn should_move_paths_and_report() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path();
+		let journal = path.join("journal");
+		fs::create_dir_all(path.join("old/keys")).unwrap();
+		fs::create_dir_all(path.join("new/keys")).unwrap();
+		File::create(path.join("old/keys/a")).unwrap();
+		File::create(path.join("old/keys/b")).unwrap();
+		File::create(path.join("new/keys/b")).unwrap();
+		File::create(path.join("old/user_defaults")).unwrap();
+
+		let summary = migrate(&journal, &[
+			Migration::MoveFiles(path.join("old/keys"), path.join("new/keys")),
+			Migration::Move(path.join("old/user_defaults"), path.join("new/db/user_defaults")),
+			Migration::Move(path.join("old/missing"), path.join("new/missing")),
+		]).unwrap();
+
+		assert_eq!(summary, Summary {
+			moved: vec![
+				(path.join("old/keys/a"), path.join("new/keys/a")),
+				(path.join("old/user_defaults"), path.join("new/db/user_defaults")),
+			],
+			skipped: vec![
+				(path.join("old/keys/b"), format!("{} already exists", path.join("new/keys/b").to_string_lossy())),
+			],
+		});
+		assert!(path.join("new/db/user_defaults").exists());
+		assert!(path.join("old/keys/b").exists());
+		assert!(!journal.exists());
+	}
+
+	#[test]
+	fn should_move_address_book_out_of_keys() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path();
+		fs::create_dir_all(path.join("keys/ethereum")).unwrap();
+		fs::create_dir_all(path.join("address_book/ethereum")).unwrap();
+		File::create(path.join("keys/ethereum/address_book.json")).unwrap();
+		File::create(path.join("keys/ethereum/watch_only.json")).unwrap();
+		File::create(path.join("address_book/ethereum/watch_only.json")).unwrap();
+		File::create(path.join("keys/ethereum/key")).unwrap();
+
+		upgrade_address_book_location(&path.join("keys/ethereum"), &path.join("address_book/ethereum"));
+
+		assert!(path.join("address_book/ethereum/address_book.json").exists());
+		assert!(!path.join("keys/ethereum/address_book.json").exists());
+		// existing files are kept
+		assert!(path.join("keys/ethereum/watch_only.json").exists());
+		assert!(path.join("keys/ethereum/key").exists());
+		assert!(!path.join("address_book/ethereum/.upgrade").exists());
+	}
+
+	#[test]
+	fn should_plan_each_migration_after_the_previous_one() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path();
+		let journal = path.join("journal");
+		fs::create_dir_all(path.join("legacy/keys")).unwrap();
+		File::create(path.join("legacy/keys/a")).unwrap();
+		File::create(path.join("legacy/user_defaults")).unwrap();
+
+		// the sources of the later migrations only exist once the base directory is moved
+		let summary = migrate(&journal, &[
+			Migration::Move(path.join("legacy"), path.join("base")),
+			Migration::MoveFiles(path.join("base/keys"), path.join("base/keys/ethereum")),
+			Migration::Move(path.join("base/user_defaults"), path.join("base/db/user_defaults")),
+		]).unwrap();
+
+		assert_eq!(summary.moved, vec![
+			(path.join("legacy"), path.join("base")),
+			(path.join("base/keys/a"), path.join("base/keys/ethereum/a")),
+			(path.join("base/user_defaults"), path.join("base/db/user_defaults")),
+		]);
+		assert!(path.join("base/keys/ethereum/a").exists());
+		assert!(path.join("base/db/user_defaults").exists());
+		assert!(!journal.exists());
+	}
+
+	#[test]
+	fn should_revert_interrupted_upgrade() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path();
+		let journal = path.join("journal");
+		fs::create_dir_all(path.join("new")).unwrap();
+		File::create(path.join("new/a")).unwrap();
+		File::create(path.join("b")).unwrap();
+		// `a` was moved, the process was killed before `b` was
+		File::create(&journal).unwrap().write_all(format!(
+			"{}\t{}\n{}\t{}\n",
+			path.join("a").to_string_lossy(), path.join("new/a").to_string_lossy(),
+			path.join("b").to_string_lossy(), path.join("new/b").to_string_lossy(),
+		).as_bytes()).unwrap();
+
+		roll_back(&journal).unwrap();
+
+		assert!(path.join("a").exists());
+		assert!(!path.join("new/a").exists());
+		assert!(path.join("b").exists());
+		assert!(!journal.exists());
+	}
+
+	#[test]
+	fn should_revert_all_moves_on_failure() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path();
+		let journal = path.join("journal");
+		File::create(path.join("a")).unwrap();
+		File::create(path.join("b")).unwrap();
+		// a file where the destination's parent directory should be
+		File::create(path.join("blocked")).unwrap();
+
+		let result = migrate(&journal, &[
+			Migration::Move(path.join("a"), path.join("new/a")),
+			Migration::Move(path.join("b"), path.join("blocked/b")),
+		]);
+
+		assert!(result.is_err());
+		assert!(path.join("a").exists());
+		assert!(!path.join("new/a").exists());
+		assert!(path.join("b").exists());
+		assert!(!journal.exists());
 	}
-	upgrade_dir_location(&dirs.legacy_version_path(pruning), &dirs.db_path(pruning));
-	upgrade_dir_location(&dirs.legacy_snapshot_path(), &dirs.snapshot_path());
-	upgrade_dir_location(&dirs.legacy_network_path(), &dirs.network_path());
-	upgrade_user_defaults(&dirs);
 }
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp;
 use std::fmt;
 use std::fs::File;
 use std::io::Write;
@@ -27,7 +28,11 @@ use serde_json::Value;
 use serde_json::de::from_reader;
 use serde_json::ser::to_string;
 use journaldb::Algorithm;
-use ethcore::client::Mode;
+use ethcore::client::{Mode, ReceiptsStorage, TransactionIndex};
+
+/// Version of the user defaults file format written by this release.
+/// Files without a version are treated as version 0.
+const SCHEMA_VERSION: u64 = 1;
 
 pub struct UserDefaults {
 	pub is_first_launch: bool,
@@ -35,12 +40,20 @@ pub struct UserDefaults {
 	pub tracing: bool,
 	pub fat_db: bool,
 	pub mode: Mode,
+	pub receipts_storage: ReceiptsStorage,
+	pub tx_index: TransactionIndex,
+	/// Format version of the loaded file.
+	pub version: u64,
+	/// Fields unknown to this release (e.g. written by a newer one), saved back unchanged.
+	pub extra: BTreeMap<String, Value>,
 }
 
 impl Serialize for UserDefaults {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where S: Serializer {
-		let mut map: BTreeMap<String, Value> = BTreeMap::new();
+		let mut map: BTreeMap<String, Value> = self.extra.clone();
+		// never downgrade the version, so that a newer release still recognizes its own fields
+		map.insert("version".into(), Value::Number(cmp::max(self.version, SCHEMA_VERSION).into()));
 		map.insert("is_first_launch".into(), Value::Bool(self.is_first_launch));
 		map.insert("pruning".into(), Value::String(self.pruning.as_str().into()));
 		map.insert("tracing".into(), Value::Bool(self.tracing));
@@ -59,6 +72,8 @@ impl Serialize for UserDefaults {
 			Mode::Active => "active",
 		};
 		map.insert("mode".into(), Value::String(mode_str.into()));
+		map.insert("receipts_storage".into(), Value::String(self.receipts_storage.as_str().into()));
+		map.insert("tx_index".into(), Value::String(self.tx_index.to_string()));
 
 		map.serialize(serializer)
 	}
@@ -82,6 +97,11 @@ impl<'a> Visitor<'a> for UserDefaultsVisitor {
 
 	fn visit_map<V>(self, visitor: V) -> Result<Self::Value, V::Error> where V: MapAccess<'a> {
 		let mut map: BTreeMap<String, Value> = Deserialize::deserialize(MapAccessDeserializer::new(visitor))?;
+		let version = match map.remove("version") {
+			Some(version) => version.as_u64().ok_or_else(|| Error::custom("invalid version value"))?,
+			None => 0,
+		};
+		map.remove("is_first_launch");
 		let pruning: Value = map.remove("pruning").ok_or_else(|| Error::custom("missing pruning"))?;
 		let pruning = pruning.as_str().ok_or_else(|| Error::custom("invalid pruning value"))?;
 		let pruning = pruning.parse().map_err(|_| Error::custom("invalid pruning method"))?;
@@ -105,6 +125,16 @@ impl<'a> Visitor<'a> for UserDefaultsVisitor {
 			"active" => Mode::Active,
 			_ => { return Err(Error::custom("invalid mode value")); },
 		};
+		map.remove("mode.timeout");
+		map.remove("mode.alarm");
+
+		let receipts_storage: Value = map.remove("receipts_storage").unwrap_or_else(|| Value::String("full".to_owned()));
+		let receipts_storage = receipts_storage.as_str().ok_or_else(|| Error::custom("invalid receipts_storage value"))?;
+		let receipts_storage = receipts_storage.parse().map_err(|_| Error::custom("invalid receipts storage mode"))?;
+
+		let tx_index: Value = map.remove("tx_index").unwrap_or_else(|| Value::String("all".to_owned()));
+		let tx_index = tx_index.as_str().ok_or_else(|| Error::custom("invalid tx_index value"))?;
+		let tx_index = tx_index.parse().map_err(|_| Error::custom("invalid transaction index policy"))?;
 
 		let user_defaults = UserDefaults {
 			is_first_launch: false,
@@ -112,6 +142,10 @@ impl<'a> Visitor<'a> for UserDefaultsVisitor {
 			tracing: tracing,
 			fat_db: fat_db,
 			mode: mode,
+			receipts_storage: receipts_storage,
+			tx_index: tx_index,
+			version: version,
+			extra: map,
 		};
 
 		Ok(user_defaults)
@@ -126,6 +160,10 @@ impl Default for UserDefaults {
 			tracing: false,
 			fat_db: false,
 			mode: Mode::Active,
+			receipts_storage: ReceiptsStorage::default(),
+			tx_index: TransactionIndex::default(),
+			version: SCHEMA_VERSION,
+			extra: BTreeMap::new(),
 		}
 	}
 }
@@ -133,8 +171,13 @@ impl Default for UserDefaults {
 impl UserDefaults {
 	pub fn load<P>(path: P) -> Result<Self, String> where P: AsRef<Path> {
 		match File::open(path) {
-			Ok(file) => match from_reader(file) {
-				Ok(defaults) => Ok(defaults),
+			Ok(file) => match from_reader::<_, UserDefaults>(file) {
+				Ok(defaults) => {
+					if defaults.version > SCHEMA_VERSION {
+						warn!("User defaults were written by a newer version of Parity; settings unknown to this version are kept as they are.");
+					}
+					Ok(defaults)
+				},
 				Err(e) => {
 					warn!("Error loading user defaults file: {:?}", e);
 					Ok(UserDefaults::default())
@@ -149,3 +192,34 @@ impl UserDefaults {
 		file.write_all(to_string(&self).unwrap().as_bytes()).map_err(|_| "Failed to save user defaults".to_owned())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::{UserDefaults, SCHEMA_VERSION};
+
+	#[test]
+	fn should_preserve_unknown_fields() {
+		let json = r#"{"version":7,"is_first_launch":false,"pruning":"fast","tracing":true,"fat_db":false,"mode":"active","mode.timeout":300,"new_setting":{"enabled":true}}"#;
+
+		let defaults: UserDefaults = serde_json::from_str(json).unwrap();
+		assert_eq!(defaults.version, 7);
+		assert!(defaults.tracing);
+		assert_eq!(defaults.extra.keys().collect::<Vec<_>>(), vec!["new_setting"]);
+
+		let saved = serde_json::to_string(&defaults).unwrap();
+		assert_eq!(saved, r#"{"fat_db":false,"is_first_launch":false,"mode":"active","new_setting":{"enabled":true},"pruning":"fast","receipts_storage":"full","tracing":true,"tx_index":"all","version":7}"#);
+	}
+
+	#[test]
+	fn should_load_unversioned_file() {
+		let json = r#"{"is_first_launch":false,"pruning":"archive","tracing":false}"#;
+
+		let defaults: UserDefaults = serde_json::from_str(json).unwrap();
+		assert_eq!(defaults.version, 0);
+		assert!(defaults.extra.is_empty());
+
+		let saved = serde_json::to_string(&defaults).unwrap();
+		assert!(saved.contains(&format!(r#""version":{}"#, SCHEMA_VERSION)));
+	}
+}
@@ -0,0 +1,54 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs;
+
+use ethcore::spec::{Spec, SpecParams, OptimizeFor};
+
+use dir::Directories;
+use params::SpecType;
+
+#[derive(Debug, PartialEq)]
+pub struct ValidateSpecCmd {
+	pub spec: SpecType,
+	pub dirs: Directories,
+}
+
+pub fn execute(cmd: ValidateSpecCmd) -> Result<String, String> {
+	let params = SpecParams::new(cmd.dirs.cache.as_ref(), OptimizeFor::Memory);
+
+	// built-in chains are checked by the test suite, only custom ones need validating.
+	let spec = match cmd.spec {
+		SpecType::Custom(ref filename) => {
+			let file = fs::File::open(filename).map_err(|e| format!("Could not load specification file at {}: {}", filename, e))?;
+			Spec::load_checked(params, file).map_err(|problems| {
+				let problems: Vec<_> = problems.into_iter().map(|p| format!("  - {}", p)).collect();
+				format!("Specification file at {} is invalid:\n{}", filename, problems.join("\n"))
+			})?
+		},
+		ref other => other.spec(params)?,
+	};
+
+	let genesis = spec.genesis_header();
+	Ok(format!(
+		"Name: {}\nEngine: {}\nGenesis hash: {:?}\nState root: {:?}\nBuiltins: {}",
+		spec.name,
+		spec.engine.name(),
+		genesis.hash(),
+		genesis.state_root(),
+		spec.engine.builtins().len(),
+	))
+}
@@ -0,0 +1,135 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! POSTs JSON notifications to a configured URL.
+
+use futures::Future;
+use hash_fetch::fetch::{Client as FetchClient, Fetch, Request, Url};
+use parity_reactor::Remote;
+use serde::Serialize;
+use serde_json;
+
+/// Sends serialized events to a webhook without blocking the caller.
+pub struct Webhook<F: Fetch = FetchClient> {
+	kind: &'static str,
+	url: Url,
+	client: F,
+	remote: Remote,
+}
+
+impl<F: Fetch> Webhook<F> {
+	/// Create new `Webhook`. `kind` names the notifications in errors and logs.
+	pub fn new(kind: &'static str, url: &str, fetch: F, remote: Remote) -> Result<Self, String> {
+		let url = Url::parse(url).map_err(|e| format!("Invalid {} URL {}: {}", kind, url, e))?;
+		Ok(Webhook {
+			kind,
+			url,
+			client: fetch,
+			remote,
+		})
+	}
+
+	/// POST given value serialized to JSON.
+	pub fn post<T: Serialize>(&self, value: &T) {
+		let body = match serde_json::to_string(value) {
+			Ok(body) => body,
+			Err(e) => {
+				warn!("Error serializing {}: {}", self.kind, e);
+				return;
+			},
+		};
+
+		let kind = self.kind;
+		let url = self.url.clone();
+		self.remote.spawn(self.client.fetch(
+			json_post(self.url.clone(), body),
+			Default::default()
+		).map_err(move |e| {
+			warn!("Error sending {} to {} : {}", kind, url, e);
+		}).map(|_| ()));
+	}
+}
+
+fn json_post(url: Url, body: String) -> Request {
+	let mut request = Request::post(url).with_body(body);
+	request.headers_mut().set_raw("Content-Type", "application/json");
+	request
+}
+
+#[cfg(test)]
+pub mod tests {
+	use std::sync::Arc;
+	use futures::future::{self, FutureResult};
+	use hash_fetch::fetch::{Abort, Error, Fetch, Request, Response, Url};
+	use parity_reactor::Remote;
+	use parking_lot::Mutex;
+	use super::{Webhook, json_post};
+
+	/// Records requests instead of sending them.
+	#[derive(Clone, Default)]
+	pub struct RecordingFetch(pub Arc<Mutex<Vec<Request>>>);
+
+	impl RecordingFetch {
+		/// Debug representations of the recorded requests.
+		pub fn requests(&self) -> Vec<String> {
+			self.0.lock().iter().map(|request| format!("{:?}", request)).collect()
+		}
+	}
+
+	impl Fetch for RecordingFetch {
+		type Result = FutureResult<Response, Error>;
+
+		fn fetch(&self, request: Request, _abort: Abort) -> Self::Result {
+			self.0.lock().push(request);
+			future::err(Error::Aborted)
+		}
+
+		fn get(&self, url: &str, abort: Abort) -> Self::Result {
+			self.fetch(Request::get(url.parse().expect("test URLs are valid")), abort)
+		}
+
+		fn post(&self, url: &str, abort: Abort) -> Self::Result {
+			self.fetch(Request::post(url.parse().expect("test URLs are valid")), abort)
+		}
+	}
+
+	/// Debug representation of a POST request with given JSON body.
+	pub fn post_request(url: &str, body: &str) -> String {
+		format!("{:?}", json_post(Url::parse(url).unwrap(), body.to_owned()))
+	}
+
+	#[derive(Serialize)]
+	struct Event {
+		name: &'static str,
+	}
+
+	#[test]
+	fn should_post_json() {
+		let fetch = RecordingFetch::default();
+		let webhook = Webhook::new("test event", "http://localhost:8000/events", fetch.clone(), Remote::new_sync()).unwrap();
+
+		webhook.post(&Event { name: "block" });
+
+		assert_eq!(fetch.requests(), vec![post_request("http://localhost:8000/events", r#"{"name":"block"}"#)]);
+		let content_type = fetch.0.lock()[0].headers().get_raw("Content-Type").and_then(|raw| raw.one()).map(|v| v.to_vec());
+		assert_eq!(content_type, Some(b"application/json".to_vec()));
+	}
+
+	#[test]
+	fn should_reject_invalid_url() {
+		assert!(Webhook::new("test event", "localhost", RecordingFetch::default(), Remote::new_sync()).is_err());
+	}
+}
@@ -16,6 +16,9 @@
 
 use std::sync::Arc;
 use std::io;
+use std::path::PathBuf;
+
+use ethereum_types::{H256, H512};
 
 use sync::{AttachedProtocol, ManageNetwork};
 use parity_rpc::Metadata;
@@ -24,10 +27,16 @@ use parity_whisper::net::{self as whisper_net, Network as WhisperNetwork};
 use parity_whisper::rpc::{WhisperClient, PoolHandle, FilterManager};
 
 /// Whisper config.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct Config {
 	pub enabled: bool,
 	pub target_message_pool_size: usize,
+	/// Symmetric key of the mail server. Mail server is disabled if `None`.
+	pub mailserver_key: Option<H256>,
+	/// Path to the mail server database.
+	pub mailserver_path: PathBuf,
+	/// Limits applied to envelopes received from peers.
+	pub limits: whisper_net::Limits,
 }
 
 impl Default for Config {
@@ -35,6 +44,9 @@ impl Default for Config {
 		Config {
 			enabled: false,
 			target_message_pool_size: 10 * 1024 * 1024,
+			mailserver_key: None,
+			mailserver_path: PathBuf::new(),
+			limits: Default::default(),
 		}
 	}
 }
@@ -62,6 +74,26 @@ impl PoolHandle for NetPoolHandle {
 	fn pool_status(&self) -> whisper_net::PoolStatus {
 		self.handle.pool_status()
 	}
+
+	fn set_min_pow(&self, min_pow: f64) {
+		self.net.with_proto_context(whisper_net::PROTOCOL_ID, &mut |ctx| {
+			self.handle.set_min_pow(min_pow, ctx);
+		});
+	}
+
+	fn set_bloom_filter(&self, bloom: Option<H512>) {
+		self.net.with_proto_context(whisper_net::PROTOCOL_ID, &mut |ctx| {
+			self.handle.set_bloom_filter(bloom, ctx);
+		});
+	}
+
+	fn request_messages(&self, mail_server: H512, request: whisper_net::MailRequest) -> bool {
+		let mut res = false;
+		self.net.with_proto_context(whisper_net::PROTOCOL_ID, &mut |ctx| {
+			res = self.handle.request_messages(&request, &mail_server, ctx);
+		});
+		res
+	}
 }
 
 /// Factory for standard whisper RPC.
@@ -79,13 +111,20 @@ impl RpcFactory {
 
 /// Sets up whisper protocol and RPC handler.
 ///
-/// Will target the given pool size.
+/// Will target the given pool size and run the mail server if its key is configured.
 #[cfg(not(feature = "ipc"))]
-pub fn setup(target_pool_size: usize, protos: &mut Vec<AttachedProtocol>)
+pub fn setup(config: &Config, protos: &mut Vec<AttachedProtocol>)
 	-> io::Result<Option<RpcFactory>>
 {
 	let manager = Arc::new(FilterManager::new()?);
-	let net = Arc::new(WhisperNetwork::new(target_pool_size, manager.clone()));
+	let mut net = WhisperNetwork::new(config.target_message_pool_size, manager.clone())
+		.with_limits(config.limits.clone());
+	if let Some(key) = config.mailserver_key {
+		let db = ::db::open_whisper_db(&config.mailserver_path)
+			.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+		net = net.with_mail_server(Arc::new(whisper_net::MailServer::new(db, None, key)));
+	}
+	let net = Arc::new(net);
 
 	protos.push(AttachedProtocol {
 		handler: net.clone() as Arc<_>,
@@ -107,7 +146,7 @@ pub fn setup(target_pool_size: usize, protos: &mut Vec<AttachedProtocol>)
 
 // TODO: make it possible to attach generic protocols in IPC.
 #[cfg(feature = "ipc")]
-pub fn setup(_target_pool_size: usize, _protos: &mut Vec<AttachedProtocol>)
+pub fn setup(_config: &Config, _protos: &mut Vec<AttachedProtocol>)
 	-> io::Result<Option<RpcFactory>>
 {
 	Ok(None)
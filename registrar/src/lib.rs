@@ -24,4 +24,4 @@ extern crate ethabi_derive;
 extern crate ethabi_contract;
 
 mod registrar;
-pub use registrar::{Registrar, RegistrarClient, Synchronous, Asynchronous};
+pub use registrar::{Registrar, RegistrarClient, RegistrarTransaction, Synchronous, Asynchronous};
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use futures::{Future, future, IntoFuture};
-use ethabi::{Address, Bytes};
+use ethabi::{Address, Bytes, Uint};
 use std::sync::Arc;
 use keccak_hash::keccak;
 
@@ -27,6 +27,15 @@ const DNS_A_RECORD: &'static str = "A";
 pub type Asynchronous = Box<Future<Item=Bytes, Error=String> + Send>;
 pub type Synchronous = Result<Bytes, String>;
 
+/// Transaction to be sent to the registrar contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistrarTransaction {
+	/// Address of the registrar contract
+	pub to: Address,
+	/// Encoded call
+	pub data: Bytes,
+}
+
 /// Registrar is dedicated interface to access the registrar contract
 /// which in turn generates an address when a client requests one
 pub struct Registrar {
@@ -61,6 +70,53 @@ impl Registrar {
 
 		Box::new(future)
 	}
+
+	/// Get the fee required to reserve a name
+	pub fn fee(&self) -> Box<Future<Item = Uint, Error = String> + Send> {
+		let registrar_address = match self.client.registrar_address() {
+			Ok(a) => a,
+			Err(e) => return Box::new(future::err(e)),
+		};
+
+		let fee = self.registrar.functions().fee();
+		let future = self.client.call_contract(registrar_address, fee.input()).and_then(move |output| {
+			fee.output(&output)
+		}
+		.map_err(|e| e.to_string()));
+
+		Box::new(future)
+	}
+
+	/// Compose a transaction reserving the given name. It has to be sent with the `fee` as value.
+	pub fn reserve(&self, name: &str) -> Result<RegistrarTransaction, String> {
+		let data = self.registrar.functions().reserve().input(keccak(name));
+		self.transaction(data)
+	}
+
+	/// Compose a transaction pointing the given name to an address
+	pub fn set_address(&self, name: &str, address: Address) -> Result<RegistrarTransaction, String> {
+		let data = self.registrar.functions().set_address().input(keccak(name), DNS_A_RECORD, address);
+		self.transaction(data)
+	}
+
+	/// Compose a transaction proposing the given name as the reverse entry of an address
+	pub fn propose_reverse(&self, name: &str, address: Address) -> Result<RegistrarTransaction, String> {
+		let data = self.registrar.functions().propose_reverse().input(name, address);
+		self.transaction(data)
+	}
+
+	/// Compose a transaction confirming the proposed reverse entry of the sender
+	pub fn confirm_reverse(&self, name: &str) -> Result<RegistrarTransaction, String> {
+		let data = self.registrar.functions().confirm_reverse().input(name);
+		self.transaction(data)
+	}
+
+	fn transaction(&self, data: Bytes) -> Result<RegistrarTransaction, String> {
+		Ok(RegistrarTransaction {
+			to: self.client.registrar_address()?,
+			data,
+		})
+	}
 }
 
 /// Registrar contract interface
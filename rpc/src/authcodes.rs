@@ -23,6 +23,7 @@ use rand::Rng;
 use rand::os::OsRng;
 use hash::keccak;
 use ethereum_types::H256;
+use rustc_hex::ToHex;
 
 /// Providing current time in seconds
 pub trait TimeProvider {
@@ -54,6 +55,8 @@ const TOKEN_LENGTH: usize = 16;
 const INITIAL_TOKEN: &'static str = "initial";
 /// Separator between fields in serialized tokens file.
 const SEPARATOR: &'static str = ";";
+/// Separator between methods in token scope.
+const SCOPE_SEPARATOR: &'static str = ",";
 /// Number of seconds to keep unused tokens.
 const UNUSED_TOKEN_TIMEOUT: u64 = 3600 * 24; // a day
 
@@ -63,6 +66,74 @@ struct Code {
 	created_at: time::Duration,
 	/// Duration since unix_epoch
 	last_used_at: Option<time::Duration>,
+	/// Name given to the token
+	name: Option<String>,
+	/// Method namespaces or methods the token grants access to, all if `None`
+	scope: Option<Vec<String>>,
+	/// Duration since unix_epoch
+	expires_at: Option<time::Duration>,
+}
+
+impl Code {
+	fn id(&self) -> String {
+		keccak(&self.code)[..4].to_hex()
+	}
+
+	fn is_expired(&self, now: u64) -> bool {
+		self.expires_at.map_or(false, |expires_at| expires_at.as_secs() <= now)
+	}
+
+	fn token(&self) -> H256 {
+		keccak(&self.code)
+	}
+}
+
+/// Access granted to a session authorized with a token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Authorization {
+	/// Hash used to authorize
+	pub hash: H256,
+	/// Identifies the token used to authorize, `None` for the initial token
+	pub token: Option<H256>,
+	/// Method namespaces or methods allowed, all if `None`
+	pub scope: Option<Vec<String>>,
+	/// Expiry timestamp of the token
+	pub expires_at: Option<u64>,
+}
+
+impl Authorization {
+	/// Checks if the authorization has expired.
+	pub fn is_expired(&self, now: u64) -> bool {
+		self.expires_at.map_or(false, |expires_at| expires_at <= now)
+	}
+
+	/// Checks if the authorization grants access to given method.
+	/// Scope entries match either whole namespaces (`signer`) or single methods (`signer_confirmRequest`).
+	pub fn allows(&self, method: &str) -> bool {
+		match self.scope {
+			None => true,
+			Some(ref scope) => scope.iter().any(|allowed| {
+				method == allowed || (method.starts_with(allowed.as_str()) && method[allowed.len()..].starts_with('_'))
+			}),
+		}
+	}
+}
+
+/// Publicly visible information about a token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenInfo {
+	/// Token identifier, used for revocation
+	pub id: String,
+	/// Token name
+	pub name: Option<String>,
+	/// Method namespaces or methods allowed, all if `None`
+	pub scope: Option<Vec<String>>,
+	/// Creation timestamp
+	pub created_at: u64,
+	/// Last usage timestamp
+	pub last_used_at: Option<u64>,
+	/// Expiry timestamp
+	pub expires_at: Option<u64>,
 }
 
 fn decode_time(val: &str) -> Option<time::Duration> {
@@ -74,6 +145,13 @@ fn encode_time(time: time::Duration) -> String {
 	format!("{}", time.as_secs())
 }
 
+fn non_empty(val: &str) -> Option<&str> {
+	match val.is_empty() {
+		true => None,
+		false => Some(val),
+	}
+}
+
 /// Manages authorization codes for `SignerUIs`
 pub struct AuthCodes<T: TimeProvider = DefaultTimeProvider> {
 	codes: Vec<Code>,
@@ -101,6 +179,9 @@ impl AuthCodes<DefaultTimeProvider> {
 				let token = parts.next();
 				let created = parts.next();
 				let used = parts.next();
+				let name = parts.next().and_then(non_empty);
+				let scope = parts.next().and_then(non_empty);
+				let expires = parts.next();
 
 				match token {
 					None => None,
@@ -111,6 +192,9 @@ impl AuthCodes<DefaultTimeProvider> {
 							last_used_at: used.and_then(decode_time),
 							created_at: created.and_then(decode_time)
 											.unwrap_or_else(|| time::Duration::from_secs(time_provider.now())),
+							name: name.map(Into::into),
+							scope: scope.map(|scope| scope.split(SCOPE_SEPARATOR).map(Into::into).collect()),
+							expires_at: expires.and_then(decode_time),
 						})
 					}
 				}
@@ -130,9 +214,17 @@ impl<T: TimeProvider> AuthCodes<T> {
 	pub fn to_file(&self, file: &Path) -> io::Result<()> {
 		let mut file = fs::File::create(file)?;
 		let content = self.codes.iter().map(|code| {
-			let mut data = vec![code.code.clone(), encode_time(code.created_at.clone())];
-			if let Some(used_at) = code.last_used_at {
-				data.push(encode_time(used_at));
+			let mut data = vec![
+				code.code.clone(),
+				encode_time(code.created_at.clone()),
+				code.last_used_at.map(encode_time).unwrap_or_default(),
+				code.name.clone().unwrap_or_default(),
+				code.scope.as_ref().map(|scope| scope.join(SCOPE_SEPARATOR)).unwrap_or_default(),
+				code.expires_at.map(encode_time).unwrap_or_default(),
+			];
+			// keep the old format for tokens without optional fields
+			while data.last().map_or(false, |field| field.is_empty()) {
+				data.pop();
 			}
 			data.join(SEPARATOR)
 		}).join("\n");
@@ -146,6 +238,9 @@ impl<T: TimeProvider> AuthCodes<T> {
 				code: code,
 				created_at: time::Duration::from_secs(now.now()),
 				last_used_at: None,
+				name: None,
+				scope: None,
+				expires_at: None,
 			}).collect(),
 			now: now,
 		}
@@ -154,11 +249,17 @@ impl<T: TimeProvider> AuthCodes<T> {
 	/// Checks if given hash is correct authcode of `SignerUI`
 	/// Updates this hash last used field in case it's valid.
 	pub fn is_valid(&mut self, hash: &H256, time: u64) -> bool {
+		self.authorize(hash, time).is_some()
+	}
+
+	/// Checks if given hash is correct authcode of `SignerUI` and returns access it grants.
+	/// Updates this hash last used field in case it's valid.
+	pub fn authorize(&mut self, hash: &H256, time: u64) -> Option<Authorization> {
 		let now = self.now.now();
 		// check time
 		if time >= now + TIME_THRESHOLD || time <= now - TIME_THRESHOLD {
 			warn!(target: "signer", "Received old authentication request. ({} vs {})", now, time);
-			return false;
+			return None;
 		}
 
 		let as_token = |code| keccak(format!("{}:{}", code, time));
@@ -169,23 +270,52 @@ impl<T: TimeProvider> AuthCodes<T> {
 			// Initial token can be used only once.
 			if initial {
 				let _ = self.generate_new();
+				return Some(Authorization { hash: *hash, token: None, scope: None, expires_at: None });
 			}
-			return initial;
+			return None;
 		}
 
 		// look for code
 		for code in &mut self.codes {
 			if &as_token(&code.code) == hash {
+				if code.is_expired(now) {
+					warn!(target: "signer", "Received authentication request with expired token {}.", code.id());
+					return None;
+				}
 				code.last_used_at = Some(time::Duration::from_secs(now));
-				return true;
+				return Some(Authorization {
+					hash: *hash,
+					token: Some(code.token()),
+					scope: code.scope.clone(),
+					expires_at: code.expires_at.map(|t| t.as_secs()),
+				});
 			}
 		}
 
-		false
+		None
 	}
 
 	/// Generates and returns a new code that can be used by `SignerUIs`
 	pub fn generate_new(&mut self) -> io::Result<String> {
+		self.generate_scoped(None, None, None)
+	}
+
+	/// Generates and returns a new named code limited to given methods and valid for `expiry` seconds.
+	pub fn generate_scoped(&mut self, name: Option<String>, scope: Option<Vec<String>>, expiry: Option<u64>) -> io::Result<String> {
+		let invalid = |val: &str| val.contains(SEPARATOR) || val.contains('\n');
+		if name.as_ref().map_or(false, |name| invalid(name.as_str())) {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "Token name contains invalid characters."));
+		}
+		if scope.as_ref().map_or(false, |scope| scope.is_empty() || scope.iter().any(|s| s.is_empty() || invalid(s.as_str()) || s.contains(SCOPE_SEPARATOR))) {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "Token scope is invalid."));
+		}
+		let now = self.now.now();
+		let expires_at = match expiry {
+			Some(expiry) => Some(now.checked_add(expiry)
+				.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Token expiry is too far in the future."))?),
+			None => None,
+		};
+
 		let mut rng = OsRng::new()?;
 		let code = rng.gen_ascii_chars().take(TOKEN_LENGTH).collect::<String>();
 		let readable_code = code.as_bytes()
@@ -196,12 +326,44 @@ impl<T: TimeProvider> AuthCodes<T> {
 		trace!(target: "signer", "New authentication token generated.");
 		self.codes.push(Code {
 			code: code,
-			created_at: time::Duration::from_secs(self.now.now()),
+			created_at: time::Duration::from_secs(now),
 			last_used_at: None,
+			name: name,
+			scope: scope,
+			expires_at: expires_at.map(time::Duration::from_secs),
 		});
 		Ok(readable_code)
 	}
 
+	/// Returns information about all tokens.
+	pub fn tokens(&self) -> Vec<TokenInfo> {
+		self.codes.iter().map(|code| TokenInfo {
+			id: code.id(),
+			name: code.name.clone(),
+			scope: code.scope.clone(),
+			created_at: code.created_at.as_secs(),
+			last_used_at: code.last_used_at.map(|t| t.as_secs()),
+			expires_at: code.expires_at.map(|t| t.as_secs()),
+		}).collect()
+	}
+
+	/// Removes tokens with given id or name. Returns number of removed tokens.
+	pub fn revoke(&mut self, id_or_name: &str) -> usize {
+		let len = self.codes.len();
+		self.codes.retain(|code| code.id() != id_or_name && code.name.as_ref().map_or(true, |name| name != id_or_name));
+		len - self.codes.len()
+	}
+
+	/// Checks if a session authorized earlier is still allowed, i.e. its token was neither revoked nor has expired.
+	pub fn is_active(&self, authorization: &Authorization) -> bool {
+		let now = self.now.now();
+		match authorization.token {
+			Some(ref token) => self.codes.iter().any(|code| code.token() == *token && !code.is_expired(now)),
+			// the initial token is replaced with a regular one on first use.
+			None => !authorization.is_expired(now),
+		}
+	}
+
 	/// Returns true if there are no tokens in this store
 	pub fn is_empty(&self) -> bool {
 		self.codes.is_empty()
@@ -218,6 +380,10 @@ impl<T: TimeProvider> AuthCodes<T> {
 			if code.last_used_at.is_none() && code.created_at <= threshold {
 				continue;
 			}
+			// Skip expired codes.
+			if code.is_expired(now) {
+				continue;
+			}
 			self.codes.push(code);
 		}
 	}
@@ -349,4 +515,70 @@ mod tests {
 		assert_eq!(content, format!("{};100;10000100\n{};100;100\n{};10000100", code1, code2, new_code));
 	}
 
+	#[test]
+	fn should_store_scoped_tokens() {
+		// given
+		let tempdir = TempDir::new("").unwrap();
+		let file_path = tempdir.path().join("file");
+		let time = Cell::new(100);
+		let mut codes = AuthCodes::new(vec![], || time.get());
+		let code = codes.generate_scoped(Some("bot".into()), Some(vec!["signer".into(), "eth_sendTransaction".into()]), Some(50))
+			.unwrap()
+			.replace('-', "");
+
+		// when
+		codes.to_file(&file_path).unwrap();
+		let mut content = String::new();
+		fs::File::open(&file_path).unwrap().read_to_string(&mut content).unwrap();
+
+		// then
+		assert_eq!(content, format!("{};100;;bot;signer,eth_sendTransaction;150", code));
+		let auth = codes.authorize(&generate_hash(&code, 100), 100).unwrap();
+		assert!(auth.allows("signer_confirmRequest"));
+		assert!(auth.allows("eth_sendTransaction"));
+		assert!(!auth.allows("eth_sendTransactionX"));
+		assert!(!auth.allows("signerX_confirmRequest"));
+		assert!(!auth.allows("personal_sign"));
+
+		let tokens = codes.tokens();
+		assert_eq!(tokens.len(), 1);
+		assert_eq!(tokens[0].name, Some("bot".into()));
+		assert_eq!(tokens[0].expires_at, Some(150));
+		assert_eq!(tokens[0].last_used_at, Some(100));
+	}
+
+	#[test]
+	fn should_reject_expired_and_revoked_tokens() {
+		// given
+		let time = Cell::new(100);
+		let mut codes = AuthCodes::new(vec![], || time.get());
+		let code1 = codes.generate_scoped(Some("short".into()), None, Some(10)).unwrap().replace('-', "");
+		let code2 = codes.generate_scoped(Some("long".into()), None, None).unwrap().replace('-', "");
+		let code3 = codes.generate_new().unwrap().replace('-', "");
+
+		// when
+		time.set(110);
+		let id3 = codes.tokens()[2].id.clone();
+
+		// then
+		assert!(!codes.is_valid(&generate_hash(&code1, 110), 110));
+		assert_eq!(codes.revoke("long"), 1);
+		assert!(!codes.is_valid(&generate_hash(&code2, 110), 110));
+		assert!(codes.is_valid(&generate_hash(&code3, 110), 110));
+		assert_eq!(codes.revoke(&id3), 1);
+		assert!(!codes.is_valid(&generate_hash(&code3, 110), 110));
+		codes.clear_garbage();
+		assert!(codes.is_empty());
+	}
+
+	#[test]
+	fn should_not_generate_invalid_tokens() {
+		let mut codes = AuthCodes::new(vec![], || 100);
+		assert!(codes.generate_scoped(Some("a;b".into()), None, None).is_err());
+		assert!(codes.generate_scoped(None, Some(vec![]), None).is_err());
+		assert!(codes.generate_scoped(None, Some(vec!["eth,net".into()]), None).is_err());
+		assert_eq!(codes.generate_scoped(None, None, Some(u64::max_value())).unwrap_err().kind(), ::std::io::ErrorKind::InvalidInput);
+		assert!(codes.is_empty());
+	}
+
 }
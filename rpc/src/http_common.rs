@@ -14,11 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Transport-specific metadata extractors.
+//! Transport-specific metadata extractors and the HTTP request middleware returning request ids.
 
-use jsonrpc_core;
+use std::sync::Arc;
+
+use ethcore_logger::with_request_id;
+use jsonrpc_core::{self, MetaIoHandler};
+use jsonrpc_core::futures::{future, Future, Stream};
 use http;
 use hyper;
+use serde_json;
+use v1::informant::new_request_id;
 
 /// HTTP RPC server impl-independent metadata extractor
 pub trait HttpMetaExtractor: Send + Sync + 'static {
@@ -29,12 +35,18 @@ pub trait HttpMetaExtractor: Send + Sync + 'static {
 }
 
 pub struct MetaExtractor<T> {
-	extractor: T,
+	extractor: Arc<T>,
 }
 
 impl<T> MetaExtractor<T> {
 	pub fn new(extractor: T) -> Self {
-		MetaExtractor { extractor }
+		MetaExtractor { extractor: Arc::new(extractor) }
+	}
+}
+
+impl<T> Clone for MetaExtractor<T> {
+	fn clone(&self) -> Self {
+		MetaExtractor { extractor: self.extractor.clone() }
 	}
 }
 
@@ -53,3 +65,131 @@ impl<M, T> http::MetaExtractor<M> for MetaExtractor<T> where
 		self.extractor.read_metadata(origin, user_agent, dapps_origin)
 	}
 }
+
+/// Largest request body served by `RequestIdMiddleware`, same as the server's limit.
+const MAX_REQUEST_BODY_SIZE: usize = 5 * 1024 * 1024;
+const REQUEST_ID_HEADER: &'static str = "X-Request-Id";
+
+/// RPC middleware dispatching requests to a handler shared with `RequestIdMiddleware`.
+pub struct Forward<M: jsonrpc_core::Metadata, S: jsonrpc_core::Middleware<M>> {
+	handler: Arc<MetaIoHandler<M, S>>,
+}
+
+impl<M: jsonrpc_core::Metadata, S: jsonrpc_core::Middleware<M>> Forward<M, S> {
+	pub fn new(handler: Arc<MetaIoHandler<M, S>>) -> Self {
+		Forward { handler }
+	}
+}
+
+impl<M: jsonrpc_core::Metadata, S: jsonrpc_core::Middleware<M>> jsonrpc_core::Middleware<M> for Forward<M, S> {
+	type Future = jsonrpc_core::FutureResponse;
+
+	fn on_request<F, X>(&self, request: jsonrpc_core::Request, meta: M, _process: F) -> Self::Future where
+		F: FnOnce(jsonrpc_core::Request, M) -> X,
+		X: Future<Item=Option<jsonrpc_core::Response>, Error=()> + Send + 'static,
+	{
+		Box::new(self.handler.handle_rpc_request(request, meta))
+	}
+}
+
+/// HTTP request middleware serving JSON-RPC calls itself, so that responses carry the
+/// `X-Request-Id` header, which the server can't add. The id of the request is used if given.
+///
+/// Requests with an `Origin` header are left to the server, which handles CORS for them
+/// (browsers don't expose the header to scripts anyway).
+pub struct RequestIdMiddleware<M: jsonrpc_core::Metadata, S: jsonrpc_core::Middleware<M>, T, R> {
+	handler: Arc<MetaIoHandler<M, S>>,
+	extractor: MetaExtractor<T>,
+	inner: Option<R>,
+}
+
+impl<M: jsonrpc_core::Metadata, S: jsonrpc_core::Middleware<M>, T, R> RequestIdMiddleware<M, S, T, R> {
+	/// Serves calls with given handler, after the `inner` middleware let the request through.
+	pub fn new(handler: Arc<MetaIoHandler<M, S>>, extractor: MetaExtractor<T>, inner: Option<R>) -> Self {
+		RequestIdMiddleware {
+			handler,
+			extractor,
+			inner,
+		}
+	}
+}
+
+impl<M, S, T, R> http::RequestMiddleware for RequestIdMiddleware<M, S, T, R> where
+	M: jsonrpc_core::Metadata,
+	S: jsonrpc_core::Middleware<M>,
+	T: HttpMetaExtractor<Metadata = M>,
+	R: http::RequestMiddleware,
+{
+	fn on_request(&self, req: hyper::Request) -> http::RequestMiddlewareAction {
+		let req = match self.inner {
+			Some(ref inner) => match inner.on_request(req) {
+				http::RequestMiddlewareAction::Proceed { should_continue_on_invalid_cors: false, request } => request,
+				action => return action,
+			},
+			None => req,
+		};
+
+		if !is_rpc_call(&req) {
+			return http::RequestMiddlewareAction::Proceed {
+				should_continue_on_invalid_cors: false,
+				request: req,
+			};
+		}
+
+		let id = incoming_request_id(&req).unwrap_or_else(new_request_id);
+		let meta = http::MetaExtractor::read_metadata(&self.extractor, &req);
+		let handler = self.handler.clone();
+
+		let response = req.body()
+			.fold(Vec::new(), |mut body, chunk| {
+				body.extend_from_slice(&chunk);
+				match body.len() > MAX_REQUEST_BODY_SIZE {
+					true => Err(hyper::Error::TooLarge),
+					false => Ok(body),
+				}
+			})
+			.and_then(move |body| {
+				let response = match String::from_utf8(body) {
+					Ok(body) => future::Either::A(with_request_id(&id, || handler.handle_request(&body, meta))),
+					Err(_) => {
+						let error = jsonrpc_core::Response::from(jsonrpc_core::Error::parse_error(), Some(jsonrpc_core::Version::V2));
+						future::Either::B(future::ok(serde_json::to_string(&error).ok()))
+					},
+				};
+
+				response.then(move |response| Ok::<_, hyper::Error>(match response {
+					Ok(body) => {
+						// same body as the server's, notifications get an empty one
+						let mut response = hyper::Response::new()
+							.with_header(hyper::header::ContentType::json())
+							.with_body(body.map(|body| body + "\n").unwrap_or_default());
+						response.headers_mut().set_raw(REQUEST_ID_HEADER, id);
+						response
+					},
+					Err(_) => hyper::Response::new().with_status(hyper::StatusCode::InternalServerError),
+				}))
+			});
+
+		http::RequestMiddlewareAction::Respond {
+			should_validate_hosts: true,
+			response: Box::new(response),
+		}
+	}
+}
+
+fn is_rpc_call(req: &hyper::Request) -> bool {
+	let is_json = req.headers().get::<hyper::header::ContentType>()
+		.map_or(false, |content_type| content_type.0.type_() == "application" && content_type.0.subtype() == "json");
+
+	*req.method() == hyper::Method::Post && is_json && !req.headers().has::<hyper::header::Origin>()
+}
+
+/// Request id given by the client, if it's short and printable.
+fn incoming_request_id(req: &hyper::Request) -> Option<String> {
+	let id = req.headers().get_raw(REQUEST_ID_HEADER)?.one()?;
+	let is_valid = !id.is_empty() && id.len() <= 64 && id.iter().all(|c| c.is_ascii_alphanumeric() || b"-_.:".contains(c));
+	match is_valid {
+		true => String::from_utf8(id.to_vec()).ok(),
+		false => None,
+	}
+}
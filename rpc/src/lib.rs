@@ -25,6 +25,7 @@ extern crate ansi_term;
 extern crate cid;
 extern crate futures_cpupool;
 extern crate itertools;
+extern crate lru_cache;
 extern crate multihash;
 extern crate order_stat;
 extern crate parking_lot;
@@ -60,11 +61,13 @@ extern crate ethkey;
 extern crate ethstore;
 extern crate fetch;
 extern crate keccak_hash as hash;
+extern crate node_filter;
 extern crate node_health;
 extern crate parity_reactor;
 extern crate parity_updater as updater;
 extern crate parity_version as version;
 extern crate patricia_trie as trie;
+extern crate registrar;
 extern crate rlp;
 extern crate stats;
 extern crate vm;
@@ -118,10 +121,10 @@ pub use http::{
 	AccessControlAllowOrigin, Host, DomainsValidation
 };
 
-pub use v1::{NetworkSettings, Metadata, Origin, informant, dispatch, signer, dapps};
+pub use v1::{NetworkSettings, Metadata, Origin, informant, dispatch, drain, names, signer, dapps};
 pub use v1::block_import::is_major_importing;
-pub use v1::extractors::{RpcExtractor, WsExtractor, WsStats, WsDispatcher};
-pub use authcodes::{AuthCodes, TimeProvider};
+pub use v1::extractors::{RpcExtractor, IpcExtractor, WsExtractor, WsStats, WsDispatcher, DrainMiddleware};
+pub use authcodes::{AuthCodes, TimeProvider, Authorization, TokenInfo};
 pub use http_common::HttpMetaExtractor;
 
 use std::net::SocketAddr;
@@ -147,18 +150,19 @@ pub fn start_http<M, S, H, T, R>(
 	T: HttpMetaExtractor<Metadata=M>,
 	R: RequestMiddleware,
 {
+	// the handler is shared with the middleware serving calls with the `X-Request-Id` header
+	let handler = ::std::sync::Arc::new(handler.into());
 	let extractor = http_common::MetaExtractor::new(extractor);
-	let mut builder = http::ServerBuilder::with_meta_extractor(handler, extractor)
+	let middleware = http_common::RequestIdMiddleware::new(handler.clone(), extractor.clone(), middleware);
+	let handler = jsonrpc_core::MetaIoHandler::with_middleware(http_common::Forward::new(handler));
+
+	Ok(http::ServerBuilder::with_meta_extractor(handler, extractor)
 		.threads(threads)
 		.event_loop_remote(remote)
 		.cors(cors_domains.into())
-		.allowed_hosts(allowed_hosts.into());
-
-	if let Some(dapps) = middleware {
-		builder = builder.request_middleware(dapps)
-	}
-
-	Ok(builder.start_http(addr)?)
+		.allowed_hosts(allowed_hosts.into())
+		.request_middleware(middleware)
+		.start_http(addr)?)
 }
 
 /// Start ipc server asynchronously and returns result with `Server` handle on success or an error.
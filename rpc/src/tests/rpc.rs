@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::sync::Arc;
+
 use devtools::http_client;
 use jsonrpc_core::MetaIoHandler;
 use http::{self, hyper};
@@ -21,8 +23,13 @@ use http::{self, hyper};
 use {HttpServer};
 use tests::helpers::Server;
 use v1::{extractors, Metadata};
+use v1::drain::RpcDrain;
 
 fn serve(handler: Option<MetaIoHandler<Metadata>>) -> Server<HttpServer> {
+	serve_with_drain(handler, Arc::new(RpcDrain::default()))
+}
+
+fn serve_with_drain(handler: Option<MetaIoHandler<Metadata>>, drain: Arc<RpcDrain>) -> Server<HttpServer> {
 	let address = "127.0.0.1:0".parse().unwrap();
 	let handler = handler.unwrap_or_default();
 
@@ -33,12 +40,12 @@ fn serve(handler: Option<MetaIoHandler<Metadata>>) -> Server<HttpServer> {
 		handler,
 		remote,
 		extractors::RpcExtractor,
-		Some(|request: hyper::Request| {
+		Some(extractors::DrainMiddleware::new(drain, Some(|request: hyper::Request| {
 			http::RequestMiddlewareAction::Proceed {
 				should_continue_on_invalid_cors: false,
 				request,
 			}
-		}),
+		}))),
 		1,
 	).unwrap())
 }
@@ -171,3 +178,124 @@ mod testsing {
 		assert_eq!(res.body, expected);
 	}
 }
+
+#[cfg(test)]
+mod request_id {
+	use jsonrpc_core::{MetaIoHandler, Value};
+	use super::{request, serve};
+
+	fn hello(extra_headers: &str) -> ::devtools::http_client::Response {
+		let mut io = MetaIoHandler::default();
+		io.add_method("hello", |_| Ok(Value::String("world".into())));
+		let server = serve(Some(io));
+		let address = server.server.address().to_owned();
+
+		let req = r#"{"method":"hello","params":[],"jsonrpc":"2.0","id":1}"#;
+		request(server,
+			&format!("\
+				POST / HTTP/1.1\r\n\
+				Host: {}\r\n\
+				Content-Type: application/json\r\n\
+				Content-Length: {}\r\n\
+				Connection: close\r\n\
+				{}\
+				\r\n\
+				{}
+			", address, req.len(), extra_headers, req)
+		)
+	}
+
+	fn request_id(response: &::devtools::http_client::Response) -> Option<String> {
+		response.headers.iter()
+			.find(|header| header.to_lowercase().starts_with("x-request-id:"))
+			.map(|header| header["x-request-id:".len()..].trim().to_owned())
+	}
+
+	#[test]
+	fn should_return_generated_request_id() {
+		// when
+		let res = hello("");
+
+		// then
+		res.assert_status("HTTP/1.1 200 OK");
+		assert_eq!(res.body, "2A\n{\"jsonrpc\":\"2.0\",\"result\":\"world\",\"id\":1}\n\n0\n\n");
+		let id = request_id(&res).expect("every response has an id");
+		assert_eq!(id.len(), 16);
+		assert_ne!(request_id(&hello("")), Some(id));
+	}
+
+	#[test]
+	fn should_return_given_request_id() {
+		// when
+		let res = hello("X-Request-Id: lb-1234.5\r\n");
+
+		// then
+		res.assert_status("HTTP/1.1 200 OK");
+		assert_eq!(request_id(&res), Some("lb-1234.5".into()));
+	}
+
+	#[test]
+	fn should_replace_invalid_request_id() {
+		// when
+		let res = hello("X-Request-Id: <script>\r\n");
+
+		// then
+		res.assert_status("HTTP/1.1 200 OK");
+		assert_eq!(request_id(&res).map(|id| id.len()), Some(16));
+	}
+}
+
+#[cfg(test)]
+mod draining {
+	use std::sync::Arc;
+	use std::time::Duration;
+	use jsonrpc_core::{MetaIoHandler, Value};
+	use v1::drain::RpcDrain;
+	use super::{request, serve_with_drain};
+
+	fn hello(drain: &Arc<RpcDrain>) -> ::devtools::http_client::Response {
+		let mut io = MetaIoHandler::default();
+		io.add_method("hello", |_| Ok(Value::String("world".into())));
+		let server = serve_with_drain(Some(io), drain.clone());
+		let address = server.server.address().to_owned();
+
+		let req = r#"{"method":"hello","params":[],"jsonrpc":"2.0","id":1}"#;
+		request(server,
+			&format!("\
+				POST / HTTP/1.1\r\n\
+				Host: {}\r\n\
+				Content-Type: application/json\r\n\
+				Content-Length: {}\r\n\
+				Connection: close\r\n\
+				\r\n\
+				{}
+			", address, req.len(), req)
+		)
+	}
+
+	#[test]
+	fn should_serve_requests_until_draining() {
+		// given
+		let drain = Arc::new(RpcDrain::default());
+
+		// when
+		let res = hello(&drain);
+
+		// then
+		res.assert_status("HTTP/1.1 200 OK");
+	}
+
+	#[test]
+	fn should_refuse_requests_while_draining() {
+		// given
+		let drain = Arc::new(RpcDrain::default());
+		drain.start(Duration::from_secs(60)).unwrap();
+
+		// when
+		let res = hello(&drain);
+
+		// then
+		res.assert_status("HTTP/1.1 503 Service Unavailable");
+		assert!(res.body.contains("RPC server is shutting down."));
+	}
+}
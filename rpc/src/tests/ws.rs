@@ -24,10 +24,16 @@ use rand;
 use ws;
 
 use v1::{extractors, informant};
+use v1::drain::RpcDrain;
 use tests::helpers::{GuardedAuthCodes, Server};
 
 /// Setup a mock signer for tests
 pub fn serve() -> (Server<ws::Server>, usize, GuardedAuthCodes) {
+	serve_with_drain(Arc::new(RpcDrain::default()))
+}
+
+/// Setup a mock signer for tests, refusing new connections while draining
+pub fn serve_with_drain(drain: Arc<RpcDrain>) -> (Server<ws::Server>, usize, GuardedAuthCodes) {
 	let port = 35000 + rand::random::<usize>() % 10000;
 	let address = format!("127.0.0.1:{}", port).parse().unwrap();
 	let io = MetaIoHandler::default();
@@ -42,7 +48,7 @@ pub fn serve() -> (Server<ws::Server>, usize, GuardedAuthCodes) {
 		ws::DomainsValidation::Disabled,
 		5,
 		extractors::WsExtractor::new(Some(&authcodes.path)),
-		extractors::WsExtractor::new(Some(&authcodes.path)),
+		extractors::DrainMiddleware::new(drain, extractors::WsExtractor::new(Some(&authcodes.path))),
 		extractors::WsStats::new(stats),
 	).unwrap());
 
@@ -56,10 +62,12 @@ pub fn request(server: Server<ws::Server>, request: &str) -> http_client::Respon
 
 #[cfg(test)]
 mod testing {
+	use std::sync::Arc;
 	use std::time;
 	use hash::keccak;
 	use devtools::http_client;
-	use super::{serve, request};
+	use v1::drain::RpcDrain;
+	use super::{serve, serve_with_drain, request};
 
 	#[test]
 	fn should_not_redirect_to_parity_host() {
@@ -182,4 +190,30 @@ mod testing {
 		assert_eq!(response2.status, "HTTP/1.1 403 Forbidden".to_owned());
 		http_client::assert_security_headers_present(&response2.headers, None);
 	}
+
+	#[test]
+	fn should_refuse_connections_while_draining() {
+		// given
+		let drain = Arc::new(RpcDrain::default());
+		let (server, port, _) = serve_with_drain(drain.clone());
+		drain.start(time::Duration::from_secs(60)).unwrap();
+
+		// when
+		let response = request(server,
+			&format!("\
+				GET / HTTP/1.1\r\n\
+				Host: 127.0.0.1:{}\r\n\
+				Connection: Upgrade\r\n\
+				Sec-WebSocket-Key: x3JJHMbDL1EzLkh9GBhXDw==\r\n\
+				Sec-WebSocket-Protocol: initial\r\n\
+				Sec-WebSocket-Version: 13\r\n\
+				\r\n\
+				{{}}
+			", port)
+		);
+
+		// then
+		assert_eq!(response.status, "HTTP/1.1 503 Service Unavailable".to_owned());
+		http_client::assert_security_headers_present(&response.headers, None);
+	}
 }
@@ -16,18 +16,25 @@
 
 //! Parity-specific metadata extractors.
 
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use authcodes;
+use authcodes::{self, Authorization, TimeProvider};
+use http;
 use http_common::HttpMetaExtractor;
+use hyper;
 use ipc;
 use jsonrpc_core as core;
+use jsonrpc_core::futures::{Async, AsyncSink, Future, Poll, Sink, Stream};
+use jsonrpc_core::futures::sync::mpsc;
 use jsonrpc_pubsub::Session;
+use http::tokio_core::reactor::Remote;
 use ws;
-use ethereum_types::H256;
 
 use v1::{Metadata, Origin};
+use v1::helpers::errors;
+use v1::helpers::drain::RpcDrain;
 use v1::informant::RpcStats;
 
 /// Common HTTP & IPC metadata extractor.
@@ -45,15 +52,118 @@ impl HttpMetaExtractor for RpcExtractor {
 				(None, _, _) => Origin::Rpc("unknown".into()),
 			},
 			session: None,
+			authorization: None,
+			origin_apis: None,
+			resolved_names: Default::default(),
 		}
 	}
 }
 
-impl ipc::MetaExtractor<Metadata> for RpcExtractor {
+/// IPC metadata extractor.
+///
+/// Subscription notifications are forwarded to the connection through a bounded queue,
+/// so a client which doesn't read its socket fast enough can't make the node buffer
+/// an unbounded number of them.
+pub struct IpcExtractor {
+	remote: Remote,
+	queue_size: usize,
+}
+
+impl IpcExtractor {
+	/// Creates new `IpcExtractor` buffering at most `queue_size` notifications per connection.
+	pub fn new(remote: Remote, queue_size: usize) -> Self {
+		IpcExtractor {
+			remote,
+			queue_size,
+		}
+	}
+}
+
+impl ipc::MetaExtractor<Metadata> for IpcExtractor {
 	fn extract(&self, req: &ipc::RequestContext) -> Metadata {
+		let (sender, receiver) = mpsc::channel(0);
+		let queue = NotificationQueue::new(req.session_id, receiver, req.sender.clone(), self.queue_size);
+		self.remote.spawn(move |_| queue);
+
 		Metadata {
 			origin: Origin::Ipc(req.session_id.into()),
-			session: Some(Arc::new(Session::new(req.sender.clone()))),
+			session: Some(Arc::new(Session::new(sender))),
+			authorization: None,
+			origin_apis: None,
+			resolved_names: Default::default(),
+		}
+	}
+}
+
+/// Forwards notifications of a session to its connection, keeping at most `limit` of them
+/// while the connection is not ready. Notifications over the limit are dropped.
+struct NotificationQueue {
+	session_id: u64,
+	incoming: mpsc::Receiver<String>,
+	connection: mpsc::Sender<String>,
+	queue: VecDeque<String>,
+	limit: usize,
+	dropped: usize,
+}
+
+impl NotificationQueue {
+	fn new(session_id: u64, incoming: mpsc::Receiver<String>, connection: mpsc::Sender<String>, limit: usize) -> Self {
+		NotificationQueue {
+			session_id,
+			incoming,
+			connection,
+			queue: VecDeque::new(),
+			limit,
+			dropped: 0,
+		}
+	}
+}
+
+impl Future for NotificationQueue {
+	type Item = ();
+	type Error = ();
+
+	fn poll(&mut self) -> Poll<(), ()> {
+		let mut session_closed = false;
+		loop {
+			match self.incoming.poll()? {
+				Async::Ready(Some(notification)) => if self.queue.len() < self.limit {
+					self.queue.push_back(notification);
+				} else {
+					self.dropped += 1;
+				},
+				Async::Ready(None) => {
+					session_closed = true;
+					break;
+				},
+				Async::NotReady => break,
+			}
+		}
+
+		if self.dropped > 0 {
+			warn!(target: "rpc", "IPC session {} is not reading notifications fast enough, dropped {} of them.", self.session_id, self.dropped);
+			self.dropped = 0;
+		}
+
+		while let Some(notification) = self.queue.pop_front() {
+			match self.connection.start_send(notification) {
+				Ok(AsyncSink::Ready) => {},
+				Ok(AsyncSink::NotReady(notification)) => {
+					self.queue.push_front(notification);
+					break;
+				},
+				// connection closed
+				Err(_) => return Ok(Async::Ready(())),
+			}
+		}
+
+		if self.connection.poll_complete().is_err() {
+			return Ok(Async::Ready(()));
+		}
+
+		match session_closed && self.queue.is_empty() {
+			true => Ok(Async::Ready(())),
+			false => Ok(Async::NotReady),
 		}
 	}
 }
@@ -61,6 +171,7 @@ impl ipc::MetaExtractor<Metadata> for RpcExtractor {
 /// WebSockets server metadata extractor and request middleware.
 pub struct WsExtractor {
 	authcodes_path: Option<PathBuf>,
+	origin_patterns: Vec<String>,
 }
 
 impl WsExtractor {
@@ -68,8 +179,21 @@ impl WsExtractor {
 	pub fn new(path: Option<&Path>) -> Self {
 		WsExtractor {
 			authcodes_path: path.map(|p| p.to_owned()),
+			origin_patterns: Vec::new(),
 		}
 	}
+
+	/// Matches the `Origin` header of new sessions against given patterns, in order.
+	/// The matched pattern selects the session's APIs in `WsDispatcher`.
+	pub fn with_origin_apis(mut self, patterns: Vec<String>) -> Self {
+		self.origin_patterns = patterns;
+		self
+	}
+
+	fn origin_apis(&self, origin: Option<&str>) -> Option<String> {
+		let origin = origin?;
+		self.origin_patterns.iter().find(|pattern| origin_matches(pattern, origin)).cloned()
+	}
 }
 
 impl ws::MetaExtractor<Metadata> for WsExtractor {
@@ -77,20 +201,26 @@ impl ws::MetaExtractor<Metadata> for WsExtractor {
 		let id = req.session_id as u64;
 
 		let dapp = req.origin.as_ref().map(|origin| (&**origin).into()).unwrap_or_default();
-		let origin = match self.authcodes_path {
-			Some(ref path) => {
-				let authorization = req.protocols.get(0).and_then(|p| auth_token_hash(&path, p, true));
-				match authorization {
-					Some(id) => Origin::Signer { session: id.into(), dapp: dapp },
-					None => Origin::Ws { session: id.into(), dapp: dapp },
-				}
-			},
+		let authorization = match self.authcodes_path {
+			Some(ref path) => req.protocols.get(0).and_then(|p| auth_token_hash(&path, p, true)),
+			None => None,
+		};
+		// the APIs of the session are fixed by its origin during the handshake
+		let origin_apis = match authorization {
+			Some(_) => None,
+			None => self.origin_apis(req.origin.as_ref().map(|origin| &**origin)),
+		};
+		let origin = match authorization {
+			Some(ref authorization) => Origin::Signer { session: authorization.hash.into(), dapp: dapp },
 			None => Origin::Ws { session: id.into(), dapp: dapp },
 		};
 		let session = Some(Arc::new(Session::new(req.sender())));
 		Metadata {
 			origin,
 			session,
+			authorization,
+			origin_apis,
+			resolved_names: Default::default(),
 		}
 	}
 }
@@ -146,7 +276,7 @@ fn add_security_headers(res: &mut ws::ws::Response) {
 	));
 }
 
-fn auth_token_hash(codes_path: &Path, protocol: &str, save_file: bool) -> Option<H256> {
+fn auth_token_hash(codes_path: &Path, protocol: &str, save_file: bool) -> Option<Authorization> {
 	let mut split = protocol.split('_');
 	let auth = split.next().and_then(|v| v.parse().ok());
 	let time = split.next().and_then(|v| u64::from_str_radix(v, 10).ok());
@@ -159,7 +289,7 @@ fn auth_token_hash(codes_path: &Path, protocol: &str, save_file: bool) -> Option
 				// remove old tokens
 				codes.clear_garbage();
 
-				let res = codes.is_valid(&auth, time);
+				let res = codes.authorize(&auth, time);
 
 				if save_file {
 					// make sure to save back authcodes - it might have been modified
@@ -168,17 +298,68 @@ fn auth_token_hash(codes_path: &Path, protocol: &str, save_file: bool) -> Option
 					}
 				}
 
-				if res {
-					Some(auth)
-				} else {
-					None
-				}
+				res
 			})
 	}
 
 	None
 }
 
+/// Request middleware refusing new requests and connections while the servers are draining.
+pub struct DrainMiddleware<T> {
+	drain: Arc<RpcDrain>,
+	inner: T,
+}
+
+impl<T> DrainMiddleware<T> {
+	/// Wraps given request middleware.
+	pub fn new(drain: Arc<RpcDrain>, inner: T) -> Self {
+		DrainMiddleware {
+			drain,
+			inner,
+		}
+	}
+}
+
+const DRAINING_MESSAGE: &'static str = "RPC server is shutting down.";
+
+impl<T: http::RequestMiddleware> http::RequestMiddleware for DrainMiddleware<Option<T>> {
+	fn on_request(&self, req: hyper::Request) -> http::RequestMiddlewareAction {
+		if self.drain.is_draining() {
+			let response = hyper::Response::new()
+				.with_status(hyper::StatusCode::ServiceUnavailable)
+				.with_header(hyper::header::Connection::close())
+				.with_body(DRAINING_MESSAGE);
+			return http::RequestMiddlewareAction::Respond {
+				should_validate_hosts: true,
+				response: Box::new(core::futures::future::ok(response)),
+			};
+		}
+
+		match self.inner {
+			Some(ref inner) => inner.on_request(req),
+			None => http::RequestMiddlewareAction::Proceed {
+				should_continue_on_invalid_cors: false,
+				request: req,
+			},
+		}
+	}
+}
+
+impl<T: ws::RequestMiddleware> ws::RequestMiddleware for DrainMiddleware<T> {
+	fn process(&self, req: &ws::ws::Request) -> ws::MiddlewareAction {
+		// Only handshakes go through the middleware, open sessions are left untouched.
+		if self.drain.is_draining() {
+			let mut response = ws::ws::Response::new(503, "Service Unavailable");
+			response.set_body(DRAINING_MESSAGE);
+			add_security_headers(&mut response);
+			return Some(response).into();
+		}
+
+		self.inner.process(req)
+	}
+}
+
 /// WebSockets RPC usage statistics.
 pub struct WsStats {
 	stats: Arc<RpcStats>,
@@ -206,6 +387,8 @@ impl ws::SessionStats for WsStats {
 /// WebSockets middleware dispatching requests to different handles dependning on metadata.
 pub struct WsDispatcher<M: core::Middleware<Metadata>> {
 	full_handler: core::MetaIoHandler<Metadata, M>,
+	origin_handlers: Vec<(String, core::MetaIoHandler<Metadata, M>)>,
+	authcodes_path: Option<PathBuf>,
 }
 
 impl<M: core::Middleware<Metadata>> WsDispatcher<M> {
@@ -213,8 +396,45 @@ impl<M: core::Middleware<Metadata>> WsDispatcher<M> {
 	pub fn new(full_handler: core::MetaIoHandler<Metadata, M>) -> Self {
 		WsDispatcher {
 			full_handler: full_handler,
+			origin_handlers: Vec::new(),
+			authcodes_path: None,
+		}
+	}
+
+	/// Checks tokens of already authorized sessions against given authorization codes file,
+	/// so that revoked tokens stop working without reconnecting.
+	pub fn with_authcodes(mut self, path: Option<PathBuf>) -> Self {
+		self.authcodes_path = path;
+		self
+	}
+
+	fn is_active(&self, authorization: &Authorization) -> bool {
+		match self.authcodes_path {
+			Some(ref path) => authcodes::AuthCodes::from_file(path).map(|codes| codes.is_active(authorization)).unwrap_or(false),
+			None => !authorization.is_expired(authcodes::DefaultTimeProvider.now()),
 		}
 	}
+
+	/// Serves sessions whose `Origin` header matched `pattern` during the handshake with given handler.
+	/// A pattern ending with `*` matches all origins with that prefix.
+	/// The patterns have to be given to `WsExtractor::with_origin_apis` as well.
+	pub fn with_origin(mut self, pattern: String, handler: core::MetaIoHandler<Metadata, M>) -> Self {
+		self.origin_handlers.push((pattern, handler));
+		self
+	}
+
+	fn origin_handler(&self, pattern: &str) -> Option<&core::MetaIoHandler<Metadata, M>> {
+		self.origin_handlers.iter()
+			.find(|&&(ref p, _)| p == pattern)
+			.map(|&(_, ref handler)| handler)
+	}
+}
+
+fn origin_matches(pattern: &str, origin: &str) -> bool {
+	match pattern.ends_with('*') {
+		true => origin.starts_with(&pattern[..pattern.len() - 1]),
+		false => pattern == origin,
+	}
 }
 
 impl<M: core::Middleware<Metadata>> core::Middleware<Metadata> for WsDispatcher<M> {
@@ -229,23 +449,77 @@ impl<M: core::Middleware<Metadata>> core::Middleware<Metadata> for WsDispatcher<
 	{
 		use self::core::futures::future::Either::{A, B};
 
-		let use_full = match &meta.origin {
-			&Origin::Signer { .. } => true,
-			_ => false,
+		if let Origin::Signer { .. } = meta.origin {
+			// tokens are checked on every request, as they may expire or be revoked while the session is open.
+			let error = match meta.authorization {
+				Some(ref authorization) if !self.is_active(authorization) => Some(errors::token_revoked()),
+				Some(ref authorization) if !methods(&request).all(|method| authorization.allows(method)) => Some(errors::token_out_of_scope()),
+				_ => None,
+			};
+
+			return match error {
+				Some(error) => B(Box::new(core::futures::future::ok(reject(request, error)))),
+				None => A(self.full_handler.handle_rpc_request(request, meta)),
+			};
+		}
+
+		let origin_handler = match meta.origin_apis {
+			Some(ref pattern) => self.origin_handler(pattern),
+			None => None,
 		};
 
-		if use_full {
-			A(self.full_handler.handle_rpc_request(request, meta))
-		} else {
-			B(Box::new(process(request, meta)))
+		match origin_handler {
+			Some(handler) => A(handler.handle_rpc_request(request, meta)),
+			None => B(Box::new(process(request, meta))),
 		}
 	}
 }
 
+/// Responds to every call of the request with given error.
+fn reject(request: core::Request, error: core::Error) -> Option<core::Response> {
+	let output = |call: core::Call| match call {
+		core::Call::MethodCall(call) => Some(core::Output::from(Err(error.clone()), call.id, call.jsonrpc)),
+		core::Call::Notification(_) => None,
+		core::Call::Invalid(id) => Some(core::Output::from(Err(core::Error::invalid_request()), id, None)),
+	};
+
+	match request {
+		core::Request::Single(call) => output(call).map(core::Response::Single),
+		core::Request::Batch(calls) => {
+			let outputs: Vec<_> = calls.into_iter().filter_map(output).collect();
+			match outputs.is_empty() {
+				true => None,
+				false => Some(core::Response::Batch(outputs)),
+			}
+		},
+	}
+}
+
+fn methods<'a>(request: &'a core::Request) -> Box<Iterator<Item=&'a str> + 'a> {
+	fn method(call: &core::Call) -> Option<&str> {
+		match *call {
+			core::Call::MethodCall(ref call) => Some(&call.method),
+			core::Call::Notification(ref notification) => Some(&notification.method),
+			core::Call::Invalid(_) => None,
+		}
+	}
+
+	match *request {
+		core::Request::Single(ref call) => Box::new(method(call).into_iter()),
+		core::Request::Batch(ref calls) => Box::new(calls.iter().filter_map(method)),
+	}
+}
+
 #[cfg(test)]
 mod tests {
-	use super::RpcExtractor;
-	use {HttpMetaExtractor, Origin};
+	use jsonrpc_core::futures::{future, Future, Stream};
+	use jsonrpc_core::futures::sync::mpsc;
+	use jsonrpc_core::{MetaIoHandler, Params, Value};
+	use tempdir::TempDir;
+	use hash::keccak;
+	use authcodes::AuthCodes;
+	use super::{RpcExtractor, NotificationQueue, WsDispatcher, origin_matches};
+	use {HttpMetaExtractor, Metadata, Origin};
 
 	#[test]
 	fn should_extract_rpc_origin() {
@@ -275,4 +549,89 @@ mod tests {
 		// then
 		assert_eq!(meta.origin, Origin::Dapps(dapp.into()));
 	}
+
+	#[test]
+	fn should_drop_notifications_over_the_limit() {
+		// given
+		let (sender, incoming) = mpsc::channel(0);
+		let (connection, outgoing) = mpsc::channel(0);
+		let mut queue = NotificationQueue::new(1, incoming, connection, 2);
+
+		// when
+		// every notification is sent with a new clone, just like the session does
+		for i in 0..5 {
+			sender.clone().try_send(i.to_string()).unwrap();
+		}
+		drop(sender);
+		future::lazy(|| queue.poll()).wait().unwrap();
+		let (first, outgoing) = outgoing.into_future().wait().map_err(|_| ()).unwrap();
+		future::lazy(|| queue.poll()).wait().unwrap();
+
+		// then
+		assert!(queue.queue.is_empty());
+		drop(queue);
+		assert_eq!(first, Some("0".into()));
+		assert_eq!(outgoing.collect().wait().unwrap(), vec!["1".to_owned()]);
+	}
+
+	#[test]
+	fn should_match_origin_patterns() {
+		assert!(origin_matches("parity://*", "parity://signer"));
+		assert!(origin_matches("https://wallet.parity.io", "https://wallet.parity.io"));
+		assert!(origin_matches("*", ""));
+		assert!(!origin_matches("https://wallet.parity.io", "https://wallet.parity.io.evil.com"));
+		assert!(!origin_matches("parity://*", "chrome-extension://signer"));
+	}
+
+	#[test]
+	fn should_select_origin_apis_during_handshake() {
+		let extractor = WsExtractor::new(None).with_origin_apis(vec!["https://wallet.parity.io".into(), "https://*".into()]);
+
+		assert_eq!(extractor.origin_apis(Some("https://wallet.parity.io")), Some("https://wallet.parity.io".into()));
+		assert_eq!(extractor.origin_apis(Some("https://other.io")), Some("https://*".into()));
+		assert_eq!(extractor.origin_apis(Some("http://other.io")), None);
+		assert_eq!(extractor.origin_apis(None), None);
+	}
+
+	#[test]
+	fn should_enforce_token_scope_and_revocation_on_open_sessions() {
+		// given
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().join("authcodes");
+		let mut codes = AuthCodes::new(vec![], || 100);
+		let code = codes.generate_scoped(None, Some(vec!["signer".into()]), None).unwrap().replace('-', "");
+		let authorization = codes.authorize(&keccak(format!("{}:100", code)), 100).unwrap();
+		codes.to_file(&path).unwrap();
+		let id = codes.tokens()[0].id.clone();
+
+		let mut full = MetaIoHandler::default();
+		full.add_method("signer_requestsToConfirm", |_: Params| Ok(Value::String("full".into())));
+		full.add_method("personal_sign", |_: Params| Ok(Value::String("full".into())));
+		let dispatcher = WsDispatcher::new(full).with_authcodes(Some(path.clone()));
+		let mut io = MetaIoHandler::with_middleware(dispatcher);
+		io.add_method("personal_sign", |_: Params| Ok(Value::String("safe".into())));
+
+		let mut meta = Metadata::default();
+		meta.origin = Origin::Signer { session: Default::default(), dapp: Default::default() };
+		meta.authorization = Some(authorization);
+		let request = |method: &str| format!(r#"{{"jsonrpc":"2.0","method":"{}","params":[],"id":1}}"#, method);
+
+		// then
+		assert_eq!(io.handle_request_sync(&request("signer_requestsToConfirm"), meta.clone()), Some(r#"{"jsonrpc":"2.0","result":"full","id":1}"#.into()));
+		assert_eq!(
+			io.handle_request_sync(&request("personal_sign"), meta.clone()),
+			Some(r#"{"jsonrpc":"2.0","error":{"code":-32040,"message":"Method is not in the scope of the authorization token of this session."},"id":1}"#.into())
+		);
+
+		// when
+		let mut codes = AuthCodes::from_file(&path).unwrap();
+		codes.revoke(&id);
+		codes.to_file(&path).unwrap();
+
+		// then
+		assert_eq!(
+			io.handle_request_sync(&request("signer_requestsToConfirm"), meta),
+			Some(r#"{"jsonrpc":"2.0","error":{"code":-32040,"message":"Authorization token of this session has expired or was revoked."},"id":1}"#.into())
+		);
+	}
 }
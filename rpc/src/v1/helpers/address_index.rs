@@ -0,0 +1,41 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transactions recorded in the address index.
+
+use ethcore::client::{AddressTransaction, BlockChainClient, BlockId};
+use v1::types::Transaction;
+
+/// Loads the given indexed transactions from their blocks.
+///
+/// The index follows reorganizations with a delay, so transactions of blocks
+/// retracted since they were indexed are skipped.
+pub fn indexed_transactions<C, I>(client: &C, transactions: I) -> Vec<Transaction> where
+	C: BlockChainClient + ?Sized,
+	I: IntoIterator<Item = AddressTransaction>,
+{
+	let eip86_transition = client.eip86_transition();
+	transactions.into_iter()
+		.filter_map(|indexed| {
+			client.block(BlockId::Number(indexed.block_number))
+				.and_then(|block| block.view().localized_transactions().into_iter().nth(indexed.transaction_index))
+				.and_then(|transaction| match transaction.hash() == indexed.hash {
+					true => Some(Transaction::from_localized(transaction, eip86_transition)),
+					false => None,
+				})
+		})
+		.collect()
+}
@@ -0,0 +1,152 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rules for automatic approval of requests in the signer queue.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use hash::keccak;
+use serde_json;
+
+use v1::helpers::{ConfirmationPayload, FilledTransactionRequest};
+use v1::types::{H160, U256};
+
+/// Single approval rule. All conditions specified by the rule have to be met.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalRule {
+	/// Allowed senders, any sender if not specified.
+	pub from: Option<Vec<H160>>,
+	/// Allowed recipients, any recipient (including contract creation) if not specified.
+	pub to: Option<Vec<H160>>,
+	/// Maximal transferred value.
+	#[serde(rename="maxValue")]
+	pub max_value: Option<U256>,
+	/// Maximal gas price.
+	#[serde(rename="maxGasPrice")]
+	pub max_gas_price: Option<U256>,
+	/// Allowed contract methods, e.g. `transfer(address,uint256)`. Any data if not specified.
+	pub methods: Option<Vec<String>>,
+}
+
+impl ApprovalRule {
+	fn is_empty(&self) -> bool {
+		self.from.is_none() && self.to.is_none() && self.max_value.is_none() &&
+			self.max_gas_price.is_none() && self.methods.is_none()
+	}
+
+	fn matches(&self, request: &FilledTransactionRequest) -> bool {
+		let from: H160 = request.from.into();
+		let to: Option<H160> = request.to.map(Into::into);
+
+		self.from.as_ref().map_or(true, |allowed| allowed.contains(&from)) &&
+			self.to.as_ref().map_or(true, |allowed| to.map_or(false, |to| allowed.contains(&to))) &&
+			self.max_value.map_or(true, |max| request.value <= max.into()) &&
+			self.max_gas_price.map_or(true, |max| request.gas_price <= max.into()) &&
+			self.methods.as_ref().map_or(true, |methods| methods.iter().any(|method| {
+				request.data.len() >= 4 && keccak(normalize_method(method).as_bytes())[..4] == request.data[..4]
+			}))
+	}
+}
+
+/// Set of rules for automatic approval of transactions.
+/// Signing and decryption requests always wait for manual confirmation.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalRules {
+	/// Rules, request is approved if any of them matches.
+	pub rules: Vec<ApprovalRule>,
+}
+
+impl ApprovalRules {
+	/// Reads rules from JSON file.
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let file = File::open(path).map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+		Self::from_reader(file).map_err(|e| format!("Invalid approval rules in {}: {}", path.display(), e))
+	}
+
+	/// Reads rules from JSON. Rules without any condition are rejected, since they would approve every transaction.
+	pub fn from_reader<R: Read>(reader: R) -> Result<Self, String> {
+		let rules: ApprovalRules = serde_json::from_reader(reader).map_err(|e| e.to_string())?;
+		if let Some(index) = rules.rules.iter().position(ApprovalRule::is_empty) {
+			return Err(format!("rule {} has no conditions and would approve every transaction", index));
+		}
+		Ok(rules)
+	}
+
+	/// Checks if the request can be confirmed without user interaction.
+	pub fn matches(&self, payload: &ConfirmationPayload) -> bool {
+		match *payload {
+			ConfirmationPayload::SendTransaction(ref request) |
+			ConfirmationPayload::SignTransaction(ref request) => self.rules.iter().any(|rule| rule.matches(request)),
+			ConfirmationPayload::EthSignMessage(..) | ConfirmationPayload::Decrypt(..) => false,
+		}
+	}
+}
+
+/// Strips whitespace from a method signature, so `transfer(address, uint256)` matches the canonical form.
+fn normalize_method(method: &str) -> String {
+	method.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use v1::helpers::{ConfirmationPayload, FilledTransactionRequest};
+	use super::ApprovalRules;
+
+	fn request(to: u64, value: u64, data: Vec<u8>) -> ConfirmationPayload {
+		ConfirmationPayload::SendTransaction(FilledTransactionRequest {
+			from: 1.into(),
+			used_default_from: false,
+			to: Some(to.into()),
+			gas_price: 20.into(),
+			gas: 21_000.into(),
+			value: value.into(),
+			data: data,
+			nonce: None,
+			condition: None,
+		})
+	}
+
+	#[test]
+	fn should_match_rules() {
+		let rules = ApprovalRules::from_reader(&br#"{"rules":[
+			{"from":["0x0000000000000000000000000000000000000001"],"to":["0x0000000000000000000000000000000000000002"],"maxValue":"0x64","maxGasPrice":"0x14"},
+			{"to":["0x0000000000000000000000000000000000000003"],"methods":[" transfer(address, uint256)"]}
+		]}"#[..]).unwrap();
+
+		assert!(rules.matches(&request(2, 100, vec![])));
+		assert!(!rules.matches(&request(2, 101, vec![])));
+		assert!(!rules.matches(&request(4, 1, vec![])));
+		assert!(rules.matches(&request(3, 0, vec![0xa9, 0x05, 0x9c, 0xbb, 0])));
+		assert!(!rules.matches(&request(3, 0, vec![0x09, 0x5e, 0xa7, 0xb3, 0])));
+		assert!(!rules.matches(&request(3, 0, vec![])));
+		assert!(!rules.matches(&ConfirmationPayload::EthSignMessage(1.into(), vec![1].into())));
+	}
+
+	#[test]
+	fn should_reject_unknown_fields() {
+		assert!(ApprovalRules::from_reader(&br#"{"rules":[{"maxValue":"0x1","password":"x"}]}"#[..]).is_err());
+	}
+
+	#[test]
+	fn should_reject_empty_rules() {
+		assert!(ApprovalRules::from_reader(&br#"{"rules":[{"maxValue":"0x1"},{}]}"#[..]).is_err());
+		assert!(ApprovalRules::from_reader(&br#"{"rules":[]}"#[..]).is_ok());
+	}
+}
@@ -77,6 +77,14 @@ pub trait Dispatcher: Send + Sync + Clone {
 	/// "Dispatch" a local transaction.
 	fn dispatch_transaction(&self, signed_transaction: PendingTransaction)
 		-> Result<H256>;
+
+	/// Reserve the next nonce of an account for a transaction signed elsewhere.
+	/// Resolves once all nonces reserved earlier for this account are released.
+	fn reserve_nonce(&self, from: Address) -> BoxFuture<U256>;
+
+	/// Release a nonce reserved with `reserve_nonce`, marking it as used if a transaction with
+	/// this nonce was sent. Returns false if the nonce is not reserved.
+	fn release_nonce(&self, from: Address, nonce: U256, used: bool) -> bool;
 }
 
 /// A dispatcher which uses references to a client and miner in order to sign
@@ -185,6 +193,20 @@ impl<C: miner::BlockChainClient + BlockChainClient, M: MinerService> Dispatcher
 	fn dispatch_transaction(&self, signed_transaction: PendingTransaction) -> Result<H256> {
 		Self::dispatch_transaction(&*self.client, &*self.miner, signed_transaction, true)
 	}
+
+	fn reserve_nonce(&self, from: Address) -> BoxFuture<U256> {
+		let state = self.state_nonce(&from);
+		let reserved = self.nonces.lock().reserve(from, state);
+		let nonces = self.nonces.clone();
+
+		Box::new(reserved
+			.map_err(|_| errors::internal("Nonce reservation failure", ""))
+			.map(move |ready| nonces.lock().hold(from, ready)))
+	}
+
+	fn release_nonce(&self, from: Address, nonce: U256, used: bool) -> bool {
+		self.nonces.lock().release(from, nonce, used)
+	}
 }
 
 /// Get a recent gas price corpus.
@@ -416,6 +438,23 @@ impl Dispatcher for LightDispatcher {
 			.map_err(errors::transaction)
 			.map(|_| hash)
 	}
+
+	fn reserve_nonce(&self, from: Address) -> BoxFuture<U256> {
+		let nonces = self.nonces.clone();
+		Box::new(self.next_nonce(from)
+			.map_err(|_| errors::no_light_peers())
+			.and_then(move |nonce| {
+				let reserved = nonces.lock().reserve(from, nonce);
+
+				reserved
+					.map_err(|_| errors::internal("Nonce reservation failure", ""))
+					.map(move |ready| nonces.lock().hold(from, ready))
+			}))
+	}
+
+	fn release_nonce(&self, from: Address, nonce: U256, used: bool) -> bool {
+		self.nonces.lock().release(from, nonce, used)
+	}
 }
 
 fn sign_transaction(
@@ -0,0 +1,135 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Graceful draining of the HTTP and WebSockets RPC servers.
+
+use std::thread;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use jsonrpc_core::Result;
+use parking_lot::{Mutex, RwLock};
+use v1::helpers::errors;
+
+/// Draining state shared between the RPC servers.
+///
+/// Once draining starts the servers refuse new requests and connections,
+/// while already open sessions keep being served until the grace period ends.
+#[derive(Default)]
+pub struct RpcDrain {
+	deadline: RwLock<Option<Instant>>,
+	on_finish: Mutex<Vec<Box<Fn() + Send>>>,
+}
+
+impl RpcDrain {
+	/// Start draining with given grace period.
+	/// Returns `false` if draining has already started.
+	pub fn start(&self, grace: Duration) -> ::std::result::Result<bool, String> {
+		let mut deadline = self.deadline.write();
+		if deadline.is_some() {
+			return Ok(false);
+		}
+
+		*deadline = Some(Instant::now().checked_add(grace).ok_or_else(|| "Grace period is too long.".to_owned())?);
+		Ok(true)
+	}
+
+	/// Returns true if the servers should not accept new requests.
+	pub fn is_draining(&self) -> bool {
+		self.deadline.read().is_some()
+	}
+
+	/// Returns time remaining until the servers are shut down.
+	pub fn remaining(&self) -> Option<Duration> {
+		self.deadline.read().map(|deadline| {
+			let now = Instant::now();
+			if deadline > now { deadline - now } else { Duration::from_secs(0) }
+		})
+	}
+
+	/// Register an action to run when the grace period is over.
+	pub fn on_finish<F>(&self, f: F) where F: Fn() + Send + 'static {
+		self.on_finish.lock().push(Box::new(f));
+	}
+
+	/// Run all registered shutdown actions.
+	pub fn finish(&self) {
+		let actions = ::std::mem::replace(&mut *self.on_finish.lock(), Vec::new());
+		for action in actions {
+			action();
+		}
+	}
+}
+
+/// Starts draining the servers and shuts them down once the grace period of `seconds` is over.
+/// Returns `false` if draining has already started.
+pub fn start_draining(drain: &Arc<RpcDrain>, seconds: u64) -> Result<bool> {
+	let grace = Duration::from_secs(seconds);
+	if !drain.start(grace).map_err(|e| errors::invalid_params("seconds", e))? {
+		return Ok(false);
+	}
+
+	info!("Draining RPC servers, shutting down in {} seconds.", seconds);
+	let drain = drain.clone();
+	thread::Builder::new()
+		.name("rpc-drain".into())
+		.spawn(move || {
+			thread::sleep(grace);
+			drain.finish();
+		})
+		.map_err(|e| errors::internal("Unable to schedule RPC shutdown", e))?;
+	Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use std::time::Duration;
+	use super::RpcDrain;
+
+	#[test]
+	fn should_drain_once_and_run_actions() {
+		// given
+		let drain = RpcDrain::default();
+		let finished = Arc::new(AtomicUsize::new(0));
+		{
+			let finished = finished.clone();
+			drain.on_finish(move || { finished.fetch_add(1, Ordering::SeqCst); });
+		}
+		assert!(!drain.is_draining());
+		assert_eq!(drain.remaining(), None);
+
+		// when
+		assert!(drain.start(Duration::from_secs(30)).unwrap());
+		assert!(!drain.start(Duration::from_secs(60)).unwrap());
+		drain.finish();
+		drain.finish();
+
+		// then
+		assert!(drain.is_draining());
+		assert!(drain.remaining().unwrap() <= Duration::from_secs(30));
+		assert_eq!(finished.load(Ordering::SeqCst), 1);
+	}
+
+	#[test]
+	fn should_reject_too_long_grace_period() {
+		let drain = RpcDrain::default();
+
+		assert!(drain.start(Duration::from_secs(u64::max_value())).is_err());
+		assert!(!drain.is_draining());
+	}
+}
@@ -20,6 +20,7 @@ use std::fmt;
 
 use ethcore::account_provider::{SignError as AccountError};
 use ethcore::error::{Error as EthcoreError, ErrorKind, CallError};
+use ethcore::executed::decode_revert_reason;
 use jsonrpc_core::{futures, Error, ErrorCode, Value};
 use rlp::DecoderError;
 use transaction::Error as TransactionError;
@@ -35,6 +36,10 @@ mod codes {
 	pub const NO_WORK_REQUIRED: i64 = -32004;
 	pub const UNKNOWN_ERROR: i64 = -32009;
 	pub const TRANSACTION_ERROR: i64 = -32010;
+	pub const TRANSACTION_RLP_ERROR: i64 = -32011;
+	pub const TRANSACTION_SIGNATURE_ERROR: i64 = -32012;
+	pub const TRANSACTION_CHAIN_ID_ERROR: i64 = -32013;
+	pub const TRANSACTION_TOO_BIG: i64 = -32014;
 	pub const EXECUTION_ERROR: i64 = -32015;
 	pub const EXCEPTION_ERROR: i64 = -32016;
 	pub const DATABASE_ERROR: i64 = -32017;
@@ -50,6 +55,7 @@ mod codes {
 	pub const FETCH_ERROR: i64 = -32060;
 	pub const NO_LIGHT_PEERS: i64 = -32065;
 	pub const DEPRECATED: i64 = -32070;
+	pub const REGISTRAR_ERROR: i64 = -32075;
 }
 
 pub fn unimplemented(details: Option<String>) -> Error {
@@ -138,11 +144,19 @@ pub fn execution<T: fmt::Debug>(data: T) -> Error {
 pub fn state_pruned() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
-		message: "This request is not supported because your node is running with state pruning. Run with --pruning=archive.".into(),
+		message: "This request is not supported because your node is running with state pruning. Run with --pruning=archive or increase --state-query-history.".into(),
 		data: None,
 	}
 }
 
+pub fn transaction_not_indexed(first_block: Option<u64>) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "Transaction not found. Transactions are only partially indexed by hash on this node. Run with --tx-index=all to look up any transaction.".into(),
+		data: first_block.map(|n| Value::String(format!("Transactions are indexed from block #{}", n))),
+	}
+}
+
 pub fn state_corrupt() -> Error {
 	internal("State corrupt", "")
 }
@@ -203,6 +217,30 @@ pub fn token(e: String) -> Error {
 	}
 }
 
+pub fn token_scope() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REQUEST_REJECTED),
+		message: "Scoped authorization tokens are not allowed to manage tokens.".into(),
+		data: None,
+	}
+}
+
+pub fn token_revoked() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REQUEST_REJECTED),
+		message: "Authorization token of this session has expired or was revoked.".into(),
+		data: None,
+	}
+}
+
+pub fn token_out_of_scope() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REQUEST_REJECTED),
+		message: "Method is not in the scope of the authorization token of this session.".into(),
+		data: None,
+	}
+}
+
 pub fn signer_disabled() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
@@ -267,6 +305,22 @@ pub fn fetch<T: fmt::Debug>(error: T) -> Error {
 	}
 }
 
+pub fn registrar<T: fmt::Debug>(error: T) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REGISTRAR_ERROR),
+		message: "Error while accessing the registrar contract.".into(),
+		data: Some(Value::String(format!("{:?}", error))),
+	}
+}
+
+pub fn unresolved_name(name: &str) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REGISTRAR_ERROR),
+		message: format!("Name {} is not registered.", name),
+		data: None,
+	}
+}
+
 pub fn signing(error: AccountError) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::ACCOUNT_LOCKED),
@@ -335,11 +389,26 @@ pub fn transaction_message(error: &TransactionError) -> String {
 	}
 }
 
+/// Error code of a transaction error.
+///
+/// Transactions which can never be accepted, whatever the state of the node, get codes of their own.
+fn transaction_code(error: &TransactionError) -> i64 {
+	use self::TransactionError::*;
+
+	match *error {
+		InvalidRlp(_) => codes::TRANSACTION_RLP_ERROR,
+		InvalidSignature(_) => codes::TRANSACTION_SIGNATURE_ERROR,
+		InvalidChainId => codes::TRANSACTION_CHAIN_ID_ERROR,
+		TooBig => codes::TRANSACTION_TOO_BIG,
+		_ => codes::TRANSACTION_ERROR,
+	}
+}
+
 pub fn transaction<T: Into<EthcoreError>>(error: T) -> Error {
 	let error = error.into();
 	if let ErrorKind::Transaction(ref e) = *error.kind() {
 		Error {
-			code: ErrorCode::ServerError(codes::TRANSACTION_ERROR),
+			code: ErrorCode::ServerError(transaction_code(e)),
 			message: transaction_message(e),
 			data: None,
 		}
@@ -379,6 +448,7 @@ pub fn call(error: CallError) -> Error {
 		CallError::StateCorrupt => state_corrupt(),
 		CallError::Exceptional => exceptional(),
 		CallError::Execution(e) => execution(e),
+		CallError::Reverted(reason) => reverted(&reason),
 		CallError::TransactionNotFound => internal("{}, this should not be the case with eth_call, most likely a bug.", CallError::TransactionNotFound),
 	}
 }
@@ -391,13 +461,26 @@ pub fn vm(error: &VMError, output: &[u8]) -> Error {
 		error => format!("{}", error),
 	};
 
+	let message = match (error, decode_revert_reason(output)) {
+		(&VMError::Reverted, Some(reason)) => format!("VM execution error: {}", reason),
+		_ => "VM execution error.".into(),
+	};
+
 	Error {
 		code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
-		message: "VM execution error.".into(),
+		message,
 		data: Some(Value::String(data)),
 	}
 }
 
+pub fn reverted(reason: &str) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
+		message: format!("VM execution error: {}", reason),
+		data: Some(Value::String(format!("{} {}", VMError::Reverted, reason))),
+	}
+}
+
 pub fn unknown_block() -> Error {
 	Error {
 		code: ErrorCode::InvalidParams,
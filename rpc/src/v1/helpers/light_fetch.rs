@@ -202,6 +202,7 @@ impl LightFetch {
 			BlockNumber::Num(n) => BlockId::Number(n),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
 			BlockNumber::Pending => {
 				warn!("`Pending` is deprecated and may be removed in future versions. Falling back to `Latest`");
 				BlockId::Latest
@@ -318,6 +319,7 @@ impl LightFetch {
 		let block_number = |id| match id {
 			BlockId::Earliest => Some(0),
 			BlockId::Latest => Some(best_number),
+			BlockId::Finalized => self.client.block_header(BlockId::Finalized).map(|hdr| hdr.number()),
 			BlockId::Hash(h) => self.client.block_header(BlockId::Hash(h)).map(|hdr| hdr.number()),
 			BlockId::Number(x) => Some(x),
 		};
@@ -0,0 +1,117 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Local registry of contract method signatures (4byte directory).
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use hash::keccak;
+
+/// Signatures known without any registry file.
+const BUILTIN_SIGNATURES: &'static [&'static str] = &[
+	"transfer(address,uint256)",
+	"transferFrom(address,address,uint256)",
+	"approve(address,uint256)",
+	"safeTransferFrom(address,address,uint256)",
+	"setApprovalForAll(address,bool)",
+	"deposit()",
+	"withdraw(uint256)",
+];
+
+/// Maps 4-byte method selectors to their signatures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodRegistry {
+	signatures: HashMap<[u8; 4], String>,
+}
+
+impl Default for MethodRegistry {
+	fn default() -> Self {
+		let mut registry = MethodRegistry {
+			signatures: HashMap::new(),
+		};
+		for signature in BUILTIN_SIGNATURES {
+			registry.insert(signature);
+		}
+		registry
+	}
+}
+
+impl MethodRegistry {
+	/// Creates a registry with builtin signatures and signatures read from the file.
+	/// The file contains one signature per line, lines starting with `#` are ignored.
+	pub fn from_file(path: &Path) -> io::Result<Self> {
+		let mut registry = MethodRegistry::default();
+		for line in BufReader::new(File::open(path)?).lines() {
+			let line = line?;
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			registry.insert(line);
+		}
+		Ok(registry)
+	}
+
+	/// Registers a method signature, e.g. `transfer(address,uint256)`.
+	pub fn insert(&mut self, signature: &str) {
+		let signature: String = signature.chars().filter(|c| !c.is_whitespace()).collect();
+		let mut selector = [0u8; 4];
+		selector.copy_from_slice(&keccak(signature.as_bytes())[..4]);
+		self.signatures.insert(selector, signature);
+	}
+
+	/// Returns the signature of the method called with given transaction data.
+	pub fn method(&self, data: &[u8]) -> Option<&str> {
+		if data.len() < 4 {
+			return None;
+		}
+		let mut selector = [0u8; 4];
+		selector.copy_from_slice(&data[..4]);
+		self.signatures.get(&selector).map(|s| s.as_str())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::File;
+	use std::io::Write;
+	use tempdir::TempDir;
+	use super::MethodRegistry;
+
+	#[test]
+	fn should_resolve_builtin_signatures() {
+		let registry = MethodRegistry::default();
+		assert_eq!(registry.method(&[0xa9, 0x05, 0x9c, 0xbb, 0, 0]), Some("transfer(address,uint256)"));
+		assert_eq!(registry.method(&[0x09, 0x5e, 0xa7, 0xb3]), Some("approve(address,uint256)"));
+		assert_eq!(registry.method(&[0xa9, 0x05, 0x9c]), None);
+		assert_eq!(registry.method(&[1, 2, 3, 4]), None);
+	}
+
+	#[test]
+	fn should_read_signatures_from_file() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().join("signatures");
+		File::create(&path).unwrap().write_all(b"# comment\n\nbalanceOf(address)\n  totalSupply( )\n").unwrap();
+
+		let registry = MethodRegistry::from_file(&path).unwrap();
+		assert_eq!(registry.method(&[0x70, 0xa0, 0x82, 0x31]), Some("balanceOf(address)"));
+		assert_eq!(registry.method(&[0x18, 0x16, 0x0d, 0xdd]), Some("totalSupply()"));
+		assert_eq!(registry.method(&[0xa9, 0x05, 0x9c, 0xbb]), Some("transfer(address,uint256)"));
+	}
+}
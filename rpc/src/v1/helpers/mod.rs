@@ -17,16 +17,22 @@
 #[macro_use]
 pub mod errors;
 
+pub mod address_index;
 pub mod block_import;
 pub mod dapps;
 pub mod dispatch;
+pub mod drain;
 pub mod fake_sign;
 pub mod ipfs;
 pub mod light_fetch;
+pub mod names;
 pub mod nonce;
 pub mod oneshot;
 pub mod secretstore;
+pub mod tokens;
 
+mod approval_rules;
+mod method_registry;
 mod network_settings;
 mod poll_filter;
 mod poll_manager;
@@ -37,6 +43,8 @@ mod subscribers;
 mod subscription_manager;
 
 pub use self::dispatch::{Dispatcher, FullDispatcher};
+pub use self::approval_rules::{ApprovalRule, ApprovalRules};
+pub use self::method_registry::MethodRegistry;
 pub use self::network_settings::NetworkSettings;
 pub use self::poll_manager::PollManager;
 pub use self::poll_filter::{PollFilter, limit_logs};
@@ -0,0 +1,184 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Registry names passed to RPC methods in place of addresses.
+//!
+//! Names are resolved to the `A` record of the registrar before the call is deserialized,
+//! so the methods themselves only ever see addresses.
+
+use std::sync::Arc;
+
+use ethcore::client::{BlockId, RegistryInfo};
+use ethereum_types::Address;
+use jsonrpc_core::{Error, MethodCall, Params, Value};
+use serde_json;
+
+use v1::helpers::errors;
+use v1::types::{BlockNumber, H160};
+
+/// Methods taking a transaction request as the first parameter, with the position of their block parameter.
+const TRANSACTION_METHODS: &'static [(&'static str, Option<usize>)] = &[
+	("eth_call", Some(1)),
+	("eth_estimateGas", Some(1)),
+	("eth_sendTransaction", None),
+	("eth_signTransaction", None),
+	("parity_postTransaction", None),
+	("personal_sendTransaction", None),
+	("personal_signTransaction", None),
+];
+
+/// Methods taking an address as the first parameter, with the position of their block parameter.
+const ADDRESS_METHODS: &'static [(&'static str, usize)] = &[
+	("eth_getBalance", 1),
+	("eth_getCode", 1),
+	("eth_getTransactionCount", 1),
+	("eth_getStorageAt", 2),
+];
+
+/// Records the names resolved while serving a request, e.g. to name the addresses of signer confirmations.
+pub trait ResolvedNames {
+	/// Records that given name was resolved to given address.
+	fn name_resolved(&mut self, address: Address, name: String);
+}
+
+/// Resolves registry names.
+pub struct NameResolver {
+	resolve: Box<Fn(&str, BlockId) -> Option<Address> + Send + Sync>,
+}
+
+impl NameResolver {
+	/// Creates a resolver looking names up with given function.
+	pub fn new<F>(resolve: F) -> Self where
+		F: Fn(&str, BlockId) -> Option<Address> + Send + Sync + 'static,
+	{
+		NameResolver {
+			resolve: Box::new(resolve),
+		}
+	}
+
+	/// Creates a resolver looking names up in the registrar of the client's chain.
+	pub fn with_client<C: RegistryInfo + Send + Sync + 'static>(client: Arc<C>) -> Self {
+		NameResolver::new(move |name, block| client.registry_address(name.into(), block))
+	}
+
+	/// Returns the address given name is registered to at given block.
+	pub fn resolve(&self, name: &str, block: BlockId) -> Option<Address> {
+		(self.resolve)(name, block)
+	}
+
+	/// Replaces names passed in place of addresses in the parameters of a call.
+	///
+	/// Names are resolved at the block the call is made at, or the latest block
+	/// for `pending` and methods sending transactions. Returns the resolved addresses with their names.
+	pub fn resolve_call(&self, call: &mut MethodCall) -> Result<Vec<(Address, String)>, Error> {
+		let mut resolved = Vec::new();
+		let params = match call.params {
+			Some(Params::Array(ref mut params)) => params,
+			_ => return Ok(resolved),
+		};
+
+		if let Some(&(_, position)) = TRANSACTION_METHODS.iter().find(|&&(method, _)| method == call.method) {
+			let block = block_id(position.and_then(|position| params.get(position)));
+			if let Some(&mut Value::Object(ref mut request)) = params.get_mut(0) {
+				for field in &["from", "to"] {
+					if let Some(value) = request.get_mut(*field) {
+						resolved.extend(self.resolve_value(value, block.clone())?);
+					}
+				}
+			}
+		} else if let Some(&(_, position)) = ADDRESS_METHODS.iter().find(|&&(method, _)| method == call.method) {
+			let block = block_id(params.get(position));
+			if let Some(value) = params.get_mut(0) {
+				resolved.extend(self.resolve_value(value, block)?);
+			}
+		}
+
+		Ok(resolved)
+	}
+
+	fn resolve_value(&self, value: &mut Value, block: BlockId) -> Result<Option<(Address, String)>, Error> {
+		let (address, name) = match *value {
+			Value::String(ref name) if is_name(name) => (self.resolve(name, block).ok_or_else(|| errors::unresolved_name(name))?, name.clone()),
+			_ => return Ok(None),
+		};
+		*value = serde_json::to_value(H160::from(address)).expect("H160 is always serializable; qed");
+		Ok(Some((address, name)))
+	}
+}
+
+/// Addresses are always passed as 0x-prefixed hex, anything else is taken for a name.
+fn is_name(value: &str) -> bool {
+	!value.is_empty() && !value.starts_with("0x")
+}
+
+fn block_id(param: Option<&Value>) -> BlockId {
+	match param.and_then(|param| serde_json::from_value(param.clone()).ok()) {
+		Some(BlockNumber::Num(number)) => BlockId::Number(number),
+		Some(BlockNumber::Earliest) => BlockId::Earliest,
+		Some(BlockNumber::Finalized) => BlockId::Finalized,
+		Some(BlockNumber::Latest) | Some(BlockNumber::Pending) | None => BlockId::Latest,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethcore::client::BlockId;
+	use ethereum_types::Address;
+	use jsonrpc_core::{Call, Request};
+	use serde_json;
+	use super::NameResolver;
+
+	fn resolver() -> NameResolver {
+		NameResolver::new(|name, block| match (name, block) {
+			("alice", BlockId::Number(1)) => Some(Address::from(1)),
+			("alice", _) => Some(Address::from(2)),
+			_ => None,
+		})
+	}
+
+	fn call(request: &str) -> ::jsonrpc_core::MethodCall {
+		match serde_json::from_str(request).unwrap() {
+			Request::Single(Call::MethodCall(call)) => call,
+			_ => panic!("Expected a method call"),
+		}
+	}
+
+	#[test]
+	fn should_resolve_names_at_block_of_call() {
+		let resolver = resolver();
+		let mut eth_call = call(r#"{"jsonrpc":"2.0","method":"eth_call","params":[{"to":"alice","from":"0x0000000000000000000000000000000000000003"},"0x1"],"id":1}"#);
+		let mut balance = call(r#"{"jsonrpc":"2.0","method":"eth_getBalance","params":["alice"],"id":1}"#);
+
+		assert_eq!(resolver.resolve_call(&mut eth_call).unwrap(), vec![(Address::from(1), "alice".to_owned())]);
+		assert_eq!(resolver.resolve_call(&mut balance).unwrap(), vec![(Address::from(2), "alice".to_owned())]);
+
+		assert_eq!(
+			serde_json::to_string(&eth_call.params).unwrap(),
+			r#"[{"from":"0x0000000000000000000000000000000000000003","to":"0x0000000000000000000000000000000000000001"},"0x1"]"#
+		);
+		assert_eq!(serde_json::to_string(&balance.params).unwrap(), r#"["0x0000000000000000000000000000000000000002"]"#);
+	}
+
+	#[test]
+	fn should_reject_unregistered_names() {
+		let resolver = resolver();
+		let mut send = call(r#"{"jsonrpc":"2.0","method":"eth_sendTransaction","params":[{"to":"bob"}],"id":1}"#);
+		let mut other = call(r#"{"jsonrpc":"2.0","method":"eth_getLogs","params":[{"address":"bob"}],"id":1}"#);
+
+		assert_eq!(resolver.resolve_call(&mut send).unwrap_err().message, "Name bob is not registered.");
+		assert_eq!(resolver.resolve_call(&mut other).unwrap(), vec![]);
+	}
+}
@@ -14,29 +14,52 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{cmp, mem};
+use std::{cmp, fmt, mem};
 use std::collections::HashMap;
 use std::sync::{atomic, Arc};
 use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::time::Duration;
 
 use ethereum_types::{U256, Address};
 use futures::{Future, future, Poll, Async};
 use futures::future::Either;
 use futures::sync::oneshot;
 use futures_cpupool::CpuPool;
+use parking_lot::Mutex;
+use tokio_timer::{self, Timer};
+
+/// Nonces held for transactions signed outside of the node, with the id of the hold.
+type Held = HashMap<(Address, U256), (Ready, usize)>;
 
 /// Manages currently reserved and prospective nonces
 /// for multiple senders.
-#[derive(Debug)]
 pub struct Reservations {
 	nonces: HashMap<Address, SenderReservations>,
+	held: Arc<Mutex<Held>>,
+	holds: usize,
+	hold_timeout: Duration,
+	timer: Option<Timer>,
 	pool: CpuPool,
 }
+
+impl fmt::Debug for Reservations {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Reservations")
+			.field("nonces", &self.nonces)
+			.field("held", &self.held)
+			.field("hold_timeout", &self.hold_timeout)
+			.finish()
+	}
+}
+
 impl Reservations {
 	/// A maximal number of reserved nonces in the hashmap
 	/// before we start clearing the unused ones.
 	const CLEAN_AT: usize = 512;
 
+	/// Number of seconds a held nonce is kept before it's released unused.
+	const HOLD_TIMEOUT_SECS: u64 = 60;
+
 	/// Create new nonces manager and spawn a single-threaded cpu pool
 	/// for progressing execution of dropped nonces.
 	pub fn new() -> Self {
@@ -47,6 +70,10 @@ impl Reservations {
 	pub fn with_pool(pool: CpuPool) -> Self {
 		Reservations {
 			nonces: Default::default(),
+			held: Default::default(),
+			holds: 0,
+			hold_timeout: Duration::from_secs(Self::HOLD_TIMEOUT_SECS),
+			timer: None,
 			pool,
 		}
 	}
@@ -64,6 +91,47 @@ impl Reservations {
 			.or_insert_with(move || SenderReservations::with_pool(pool.clone()))
 			.reserve_nonce(minimal)
 	}
+
+	/// Hold a ready nonce until it's released with `release` or times out.
+	///
+	/// Used for nonces of transactions signed outside of the node.
+	/// Returns the value of the held nonce.
+	pub fn hold(&mut self, sender: Address, ready: Ready) -> U256 {
+		let nonce = ready.value;
+		self.holds = self.holds.wrapping_add(1);
+		let id = self.holds;
+		self.held.lock().insert((sender, nonce), (ready, id));
+
+		// dropping the nonce releases it unused, unless it was released and held again since.
+		let held = self.held.clone();
+		let timer = self.timer.get_or_insert_with(|| tokio_timer::wheel().build());
+		let expiry = timer.sleep(self.hold_timeout)
+			.map_err(|e| warn!("Nonce hold timer error: {:?}", e))
+			.map(move |_| {
+				let mut held = held.lock();
+				if held.get(&(sender, nonce)).map_or(false, |&(_, hold)| hold == id) {
+					held.remove(&(sender, nonce));
+				}
+			});
+		self.pool.spawn(expiry).forget();
+
+		nonce
+	}
+
+	/// Release a held nonce, marking it as used if a transaction with this nonce was sent.
+	///
+	/// Returns false if the nonce is not held.
+	pub fn release(&mut self, sender: Address, nonce: U256, used: bool) -> bool {
+		match self.held.lock().remove(&(sender, nonce)) {
+			Some((ready, _)) => {
+				if used {
+					ready.mark_used();
+				}
+				true
+			},
+			None => false,
+		}
+	}
 }
 
 /// Manages currently reserved and prospective nonces.
@@ -311,4 +379,59 @@ mod tests {
 		assert_eq!(n1.prospective_value(), &U256::from(5));
 		assert_eq!(n2.prospective_value(), &U256::from(6));
 	}
+
+	#[test]
+	fn should_hold_nonces_until_released() {
+		let mut nonces = Reservations::new();
+		let sender = Address::from(1);
+
+		let r = nonces.reserve(sender, 5.into()).wait().unwrap();
+		assert_eq!(nonces.hold(sender, r), U256::from(5));
+
+		// Next nonce is resolved once the held one is released.
+		let n2 = nonces.reserve(sender, 5.into());
+		assert!(!nonces.release(sender, 6.into(), true));
+		assert!(nonces.release(sender, 5.into(), true));
+		let r = n2.wait().unwrap();
+		assert_eq!(r.value(), &U256::from(6));
+		assert_eq!(nonces.hold(sender, r), U256::from(6));
+
+		// Nonce released unused is reserved again.
+		assert!(nonces.release(sender, 6.into(), false));
+		assert!(!nonces.release(sender, 6.into(), false));
+		let r = nonces.reserve(sender, 5.into()).wait().unwrap();
+		assert_eq!(r.value(), &U256::from(6));
+	}
+
+	#[test]
+	fn should_release_held_nonces_after_timeout() {
+		let mut nonces = Reservations::new();
+		nonces.hold_timeout = Duration::from_millis(200);
+		let sender = Address::from(1);
+
+		let r = nonces.reserve(sender, 5.into()).wait().unwrap();
+		assert_eq!(nonces.hold(sender, r), U256::from(5));
+
+		// resolves once the held nonce expires unused.
+		let r = nonces.reserve(sender, 5.into()).wait().unwrap();
+		assert_eq!(r.value(), &U256::from(5));
+		assert!(!nonces.release(sender, 5.into(), true));
+	}
+
+	#[test]
+	fn should_skip_dropped_reservations_of_held_nonces() {
+		let mut nonces = Reservations::new();
+		let sender = Address::from(1);
+
+		let r = nonces.reserve(sender, 5.into()).wait().unwrap();
+		assert_eq!(nonces.hold(sender, r), U256::from(5));
+
+		// a caller giving up on its reservation doesn't block the ones after it.
+		let n2 = nonces.reserve(sender, 5.into());
+		let n3 = nonces.reserve(sender, 5.into());
+		drop(n2);
+		assert!(nonces.release(sender, 5.into(), true));
+		let r = n3.wait().unwrap();
+		assert_eq!(r.value(), &U256::from(6));
+	}
 }
@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
+
 use ethereum_types::{U256, Address};
 use bytes::Bytes;
 
@@ -106,6 +108,8 @@ pub struct ConfirmationRequest {
 	pub payload: ConfirmationPayload,
 	/// Request origin
 	pub origin: Origin,
+	/// Registry names the addresses of the request were resolved from
+	pub names: BTreeMap<Address, String>,
 }
 
 /// Payload to confirm in Trusted Signer
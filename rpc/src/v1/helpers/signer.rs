@@ -16,22 +16,47 @@
 
 use std::sync::Arc;
 use std::ops::Deref;
+use std::path::PathBuf;
+use futures::{future, Future};
+use futures::future::Either;
+use futures_cpupool::{CpuPool, CpuFuture};
 use http::Origin;
+use jsonrpc_core::{BoxFuture, Error};
 use parking_lot::Mutex;
 use transient_hashmap::TransientHashMap;
 
+use ethcore::account_provider::AccountProvider;
+use ethkey::Password;
 use ethstore::random_string;
 
-use v1::helpers::signing_queue::{ConfirmationsQueue};
+use authcodes::{AuthCodes, TokenInfo};
+
+use v1::helpers::ConfirmationPayload;
+use v1::helpers::approval_rules::ApprovalRules;
+use v1::helpers::method_registry::MethodRegistry;
+use v1::helpers::signing_queue::{ConfirmationsQueue, SigningQueue};
+use v1::types::ConfirmationRequest;
 
 const TOKEN_LIFETIME_SECS: u32 = 3600;
 
+/// Rules and passwords used to confirm requests without user interaction.
+struct AutoApproval {
+	rules: ApprovalRules,
+	passwords: Vec<Password>,
+	/// Key derivation is too slow to run on the RPC threads.
+	pool: CpuPool,
+}
+
 /// Manages communication with Signer crate
 pub struct SignerService {
 	is_enabled: bool,
 	queue: Arc<ConfirmationsQueue>,
 	web_proxy_tokens: Mutex<TransientHashMap<String, Origin>>,
 	generate_new_token: Box<Fn() -> Result<String, String> + Send + Sync + 'static>,
+	method_registry: MethodRegistry,
+	account_names: Option<Arc<AccountProvider>>,
+	auto_approval: Option<AutoApproval>,
+	token_store: Option<PathBuf>,
 }
 
 impl SignerService {
@@ -43,9 +68,84 @@ impl SignerService {
 			web_proxy_tokens: Mutex::new(TransientHashMap::new(TOKEN_LIFETIME_SECS)),
 			generate_new_token: Box::new(new_token),
 			is_enabled: is_enabled,
+			method_registry: MethodRegistry::default(),
+			account_names: None,
+			auto_approval: None,
+			token_store: None,
 		}
 	}
 
+	/// Sets path of the authorization codes file used to manage named tokens.
+	pub fn with_token_store(mut self, path: PathBuf) -> Self {
+		self.token_store = Some(path);
+		self
+	}
+
+	/// Enables automatic approval of requests matching the rules.
+	/// Passwords are used to unlock the sender account.
+	pub fn with_auto_approval(mut self, rules: ApprovalRules, passwords: Vec<Password>) -> Self {
+		self.auto_approval = Some(AutoApproval {
+			rules,
+			passwords,
+			pool: CpuPool::new(1),
+		});
+		self
+	}
+
+	/// Confirms a request matching the auto-approval rules with the password unlocking its sender.
+	/// Passwords are checked and `confirm` is run on a dedicated worker thread.
+	/// Returns `None` if the request does not match the rules. The future resolves to `None`
+	/// if no password unlocks the sender and the request has to be confirmed manually.
+	pub fn auto_approve<F, T>(&self, accounts: &Arc<AccountProvider>, payload: &ConfirmationPayload, confirm: F) -> Option<CpuFuture<Option<T>, Error>> where
+		F: FnOnce(Password) -> BoxFuture<T> + Send + 'static,
+		T: Send + 'static,
+	{
+		let approval = self.auto_approval.as_ref()?;
+		if !approval.rules.matches(payload) {
+			return None;
+		}
+
+		let sender = payload.sender();
+		let accounts = accounts.clone();
+		let passwords = approval.passwords.clone();
+		Some(approval.pool.spawn_fn(move || {
+			match passwords.into_iter().find(|password| accounts.test_password(&sender, password).unwrap_or(false)) {
+				Some(password) => {
+					info!(target: "signer", "Auto-approving request from {:?}", sender);
+					Either::A(confirm(password).map(Some))
+				},
+				None => {
+					warn!(target: "signer", "Request from {:?} matches auto-approval rules, but no password unlocks the account.", sender);
+					Either::B(future::ok(None))
+				},
+			}
+		}))
+	}
+
+	/// Sets registry used to decode methods called by pending transactions.
+	pub fn with_method_registry(mut self, registry: MethodRegistry) -> Self {
+		self.method_registry = registry;
+		self
+	}
+
+	/// Sets accounts and address book used to name addresses involved in pending requests.
+	pub fn with_account_names(mut self, accounts: Arc<AccountProvider>) -> Self {
+		self.account_names = Some(accounts);
+		self
+	}
+
+	/// Returns pending requests along with details describing them to the user.
+	pub fn requests_with_details(&self) -> Vec<ConfirmationRequest> {
+		self.queue.requests()
+			.into_iter()
+			.map(|request| ConfirmationRequest::with_details(request, &self.method_registry))
+			.map(|request| match self.account_names {
+				Some(ref accounts) => request.with_names(|address| accounts.address_name(address)),
+				None => request,
+			})
+			.collect()
+	}
+
 	/// Checks if the token is valid web proxy access token.
 	pub fn web_proxy_access_token_domain(&self, token: &String) -> Option<Origin> {
 		self.web_proxy_tokens.lock().get(token).cloned()
@@ -65,6 +165,34 @@ impl SignerService {
 		(self.generate_new_token)()
 	}
 
+	/// Generates new named signer authorization token limited to given methods and valid for `expiry` seconds.
+	pub fn generate_scoped_token(&self, name: Option<String>, scope: Option<Vec<String>>, expiry: Option<u64>) -> Result<String, String> {
+		self.with_codes(true, |codes| codes.generate_scoped(name, scope, expiry).map_err(|e| e.to_string()))
+	}
+
+	/// Returns information about existing authorization tokens.
+	pub fn tokens(&self) -> Result<Vec<TokenInfo>, String> {
+		self.with_codes(false, |codes| Ok(codes.tokens()))
+	}
+
+	/// Revokes authorization tokens with given id or name.
+	pub fn revoke_token(&self, id_or_name: &str) -> Result<bool, String> {
+		self.with_codes(true, |codes| Ok(codes.revoke(id_or_name) > 0))
+	}
+
+	fn with_codes<F, T>(&self, save: bool, f: F) -> Result<T, String> where
+		F: FnOnce(&mut AuthCodes) -> Result<T, String>,
+	{
+		let path = self.token_store.as_ref().ok_or_else(|| "Token store is not available.".to_owned())?;
+		let mut codes = AuthCodes::from_file(path).map_err(|e| e.to_string())?;
+		codes.clear_garbage();
+		let result = f(&mut codes)?;
+		if save {
+			codes.to_file(path).map_err(|e| e.to_string())?;
+		}
+		Ok(result)
+	}
+
 	/// Returns a reference to `ConfirmationsQueue`
 	pub fn queue(&self) -> Arc<ConfirmationsQueue> {
 		self.queue.clone()
@@ -73,7 +73,12 @@ pub trait SigningQueue: Send + Sync {
 	/// Add new request to the queue.
 	/// Returns a `Result` wrapping  `ConfirmationReceiver` together with it's unique id in the queue.
 	/// `ConfirmationReceiver` is a `Future` awaiting for resolution of the given request.
-	fn add_request(&self, request: ConfirmationPayload, origin: Origin) -> Result<(U256, ConfirmationReceiver), QueueAddError>;
+	fn add_request(&self, request: ConfirmationPayload, origin: Origin) -> Result<(U256, ConfirmationReceiver), QueueAddError> {
+		self.add_named_request(request, origin, BTreeMap::new())
+	}
+
+	/// Add new request to the queue, along with the registry names its addresses were resolved from.
+	fn add_named_request(&self, request: ConfirmationPayload, origin: Origin, names: BTreeMap<Address, String>) -> Result<(U256, ConfirmationReceiver), QueueAddError>;
 
 	/// Notifies possible token holders that request was rejected.
 	fn request_rejected(&self, sender: ConfirmationSender) -> Option<ConfirmationRequest>;
@@ -160,7 +165,7 @@ impl Drop for ConfirmationsQueue {
 }
 
 impl SigningQueue for ConfirmationsQueue {
-	fn add_request(&self, request: ConfirmationPayload, origin: Origin) -> Result<(U256, ConfirmationReceiver), QueueAddError> {
+	fn add_named_request(&self, request: ConfirmationPayload, origin: Origin, names: BTreeMap<Address, String>) -> Result<(U256, ConfirmationReceiver), QueueAddError> {
 		if self.len() > QUEUE_LIMIT {
 			return Err(QueueAddError::LimitReached);
 		}
@@ -185,6 +190,7 @@ impl SigningQueue for ConfirmationsQueue {
 					id,
 					payload: request,
 					origin,
+					names,
 				},
 			});
 			(id, receiver)
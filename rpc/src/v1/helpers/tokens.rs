@@ -0,0 +1,172 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Calls to ERC-20 token contracts and the token registry.
+
+use ethereum_types::{Address, U256};
+use transaction::{Action, SignedTransaction, Transaction};
+
+const BALANCE_OF: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+const DECIMALS: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+const SYMBOL: [u8; 4] = [0x95, 0xd8, 0x9b, 0x41];
+const TOKEN_COUNT: [u8; 4] = [0x9f, 0x18, 0x1b, 0x5e];
+const TOKEN: [u8; 4] = [0x04, 0x42, 0x15, 0xc6];
+
+/// Gas given to each call, plenty for any sane token.
+const CALL_GAS: u64 = 1_000_000;
+
+/// Name of the token registry in the registrar.
+pub const TOKEN_REGISTRY: &'static str = "tokenreg";
+
+/// Symbol and decimals of a token, if the token implements the optional ERC-20 methods.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TokenMetadata {
+	/// Token symbol
+	pub symbol: Option<String>,
+	/// Number of decimals of the token
+	pub decimals: Option<u8>,
+}
+
+fn call(contract: Address, selector: [u8; 4], argument: Option<U256>) -> SignedTransaction {
+	let mut data = selector.to_vec();
+	if let Some(argument) = argument {
+		let mut word = [0u8; 32];
+		argument.to_big_endian(&mut word);
+		data.extend_from_slice(&word);
+	}
+
+	Transaction {
+		nonce: U256::zero(),
+		action: Action::Call(contract),
+		gas: CALL_GAS.into(),
+		gas_price: U256::zero(),
+		value: U256::zero(),
+		data,
+	}.fake_sign(Address::default())
+}
+
+/// Call of `balanceOf(owner)`.
+pub fn balance_of(token: Address, owner: &Address) -> SignedTransaction {
+	call(token, BALANCE_OF, Some(U256::from(&owner[..])))
+}
+
+/// Call of `decimals()`.
+pub fn decimals(token: Address) -> SignedTransaction {
+	call(token, DECIMALS, None)
+}
+
+/// Call of `symbol()`.
+pub fn symbol(token: Address) -> SignedTransaction {
+	call(token, SYMBOL, None)
+}
+
+/// Call of the registry's `tokenCount()`.
+pub fn token_count(registry: Address) -> SignedTransaction {
+	call(registry, TOKEN_COUNT, None)
+}
+
+/// Call of the registry's `token(index)`.
+pub fn token(registry: Address, index: u64) -> SignedTransaction {
+	call(registry, TOKEN, Some(index.into()))
+}
+
+/// Decodes an `uint256` result.
+pub fn decode_uint(output: &[u8]) -> Option<U256> {
+	match output.len() >= 32 {
+		true => Some(U256::from(&output[..32])),
+		false => None,
+	}
+}
+
+/// Decodes an `uint8` result.
+pub fn decode_decimals(output: &[u8]) -> Option<u8> {
+	decode_uint(output).and_then(|decimals| match decimals <= U256::from(u8::max_value()) {
+		true => Some(decimals.low_u32() as u8),
+		false => None,
+	})
+}
+
+/// Decodes the address of a token returned by the registry's `token(index)`.
+pub fn decode_token_address(output: &[u8]) -> Option<Address> {
+	match output.len() >= 32 && output[..12].iter().all(|b| *b == 0) {
+		true => Some(Address::from(&output[12..32])),
+		false => None,
+	}
+}
+
+/// Decodes a `string` result, or a `bytes32` one returned by some older tokens.
+pub fn decode_string(output: &[u8]) -> Option<String> {
+	let bytes = match output.len() {
+		32 => {
+			let end = output.iter().position(|b| *b == 0).unwrap_or(32);
+			&output[..end]
+		},
+		len => {
+			let word = |at: usize| match at.checked_add(32) {
+				Some(end) if end <= len => {
+					let value = U256::from(&output[at..end]);
+					match value <= U256::from(len) {
+						true => Some(value.low_u64() as usize),
+						false => None,
+					}
+				},
+				_ => None,
+			};
+			let offset = word(0)?;
+			let size = word(offset)?;
+			match (offset + 32).checked_add(size) {
+				Some(end) if end <= len => &output[offset + 32..end],
+				_ => return None,
+			}
+		},
+	};
+
+	match String::from_utf8(bytes.to_vec()) {
+		Ok(ref string) if string.is_empty() || string.chars().any(char::is_control) => None,
+		Ok(string) => Some(string),
+		Err(_) => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rustc_hex::FromHex;
+	use super::{decode_string, decode_decimals};
+
+	#[test]
+	fn should_decode_string_and_bytes32_symbols() {
+		let string: Vec<u8> = "0000000000000000000000000000000000000000000000000000000000000020\
+			0000000000000000000000000000000000000000000000000000000000000003\
+			4441490000000000000000000000000000000000000000000000000000000000".from_hex().unwrap();
+		assert_eq!(decode_string(&string), Some("DAI".into()));
+
+		let bytes32: Vec<u8> = "4d4b520000000000000000000000000000000000000000000000000000000000".from_hex().unwrap();
+		assert_eq!(decode_string(&bytes32), Some("MKR".into()));
+
+		assert_eq!(decode_string(&[0u8; 32]), None);
+		assert_eq!(decode_string(&string[..64]), None);
+	}
+
+	#[test]
+	fn should_decode_decimals() {
+		let mut output = [0u8; 32];
+		output[31] = 18;
+		assert_eq!(decode_decimals(&output), Some(18));
+		output[30] = 1;
+		assert_eq!(decode_decimals(&output), None);
+		assert_eq!(decode_decimals(&[]), None);
+	}
+}
@@ -0,0 +1,101 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique api implementation.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use ethcore::client::{BlockChainClient, BlockId, EngineInfo};
+use ethcore::engines::Clique as CliqueEngine;
+
+use jsonrpc_core::Result;
+use jsonrpc_macros::Trailing;
+use v1::helpers::errors;
+use v1::traits::Clique;
+use v1::types::{BlockNumber, CliqueSnapshot, H160, H256, block_number_to_id};
+
+/// Clique api implementation.
+pub struct CliqueClient<C> {
+	client: Arc<C>,
+}
+
+impl<C> CliqueClient<C> {
+	/// Creates new Clique client.
+	pub fn new(client: &Arc<C>) -> Self {
+		CliqueClient {
+			client: client.clone(),
+		}
+	}
+}
+
+impl<C: BlockChainClient + EngineInfo> CliqueClient<C> {
+	fn engine(&self) -> Result<&CliqueEngine> {
+		self.client.engine().as_clique()
+			.ok_or_else(|| errors::unsupported("The chain is not using the Clique consensus engine.", None))
+	}
+
+	fn snapshot_at(&self, id: BlockId) -> Result<Option<CliqueSnapshot>> {
+		let engine = self.engine()?;
+		match self.client.block_hash(id) {
+			Some(hash) => engine.snapshot(hash)
+				.map(|snapshot| Some(snapshot.into()))
+				.map_err(|e| errors::internal("Failed to compute Clique signers.", e)),
+			None => Ok(None),
+		}
+	}
+}
+
+fn to_block_id(number: Trailing<BlockNumber>) -> BlockId {
+	match number.unwrap_or_default() {
+		// the pending block is not sealed yet
+		BlockNumber::Pending => BlockId::Latest,
+		number => block_number_to_id(number),
+	}
+}
+
+impl<C> Clique for CliqueClient<C> where
+	C: BlockChainClient + EngineInfo + 'static,
+{
+	fn snapshot(&self, number: Trailing<BlockNumber>) -> Result<Option<CliqueSnapshot>> {
+		self.snapshot_at(to_block_id(number))
+	}
+
+	fn snapshot_at_hash(&self, hash: H256) -> Result<Option<CliqueSnapshot>> {
+		self.snapshot_at(BlockId::Hash(hash.into()))
+	}
+
+	fn signers(&self, number: Trailing<BlockNumber>) -> Result<Option<Vec<H160>>> {
+		Ok(self.snapshot(number)?.map(|snapshot| snapshot.signers))
+	}
+
+	fn signers_at_hash(&self, hash: H256) -> Result<Option<Vec<H160>>> {
+		Ok(self.snapshot_at_hash(hash)?.map(|snapshot| snapshot.signers))
+	}
+
+	fn proposals(&self) -> Result<BTreeMap<H160, bool>> {
+		Ok(self.engine()?.proposals().into_iter().map(|(address, authorize)| (address.into(), authorize)).collect())
+	}
+
+	fn propose(&self, address: H160, authorize: bool) -> Result<bool> {
+		self.engine()?.propose(address.into(), authorize);
+		Ok(true)
+	}
+
+	fn discard(&self, address: H160) -> Result<bool> {
+		Ok(self.engine()?.discard(&address.into()))
+	}
+}
@@ -0,0 +1,267 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Debug api implementation.
+
+use std::cmp;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use ethcore::account_diff::Diff;
+use ethcore::client::{BlockChainClient, CallAnalytics, StateClient, StateInfo, Call, BlockId, TransactionId};
+use ethcore::executed::Executed;
+use ethcore::header::Header;
+use ethcore::state_diff::StateDiff;
+use transaction::SignedTransaction;
+
+use jsonrpc_core::Result;
+use jsonrpc_macros::Trailing;
+use v1::Metadata;
+use v1::traits::Debug;
+use v1::helpers::{errors, fake_sign};
+use v1::types::{
+	BlockNumber, CallRequest, CallProfile, H160, H256, TracerOptions, TracerResult, Tracer,
+	CallFrame, PrestateAccount, AccountRange, StorageRange, four_byte_counts, op_count, block_number_to_id,
+};
+
+/// Maximal number of items returned by a single state range query.
+const MAX_RANGE_RESULTS: u64 = 1024;
+
+/// Debug api implementation.
+pub struct DebugClient<C> {
+	client: Arc<C>,
+}
+
+impl<C> DebugClient<C> {
+	/// Creates new Debug client.
+	pub fn new(client: &Arc<C>) -> Self {
+		DebugClient {
+			client: client.clone(),
+		}
+	}
+}
+
+impl<C: BlockChainClient> DebugClient<C> {
+	/// Returns the first `count` transactions of given block, to be replayed without analytics.
+	fn preceding_transactions(&self, id: BlockId, count: usize) -> Result<Vec<(SignedTransaction, CallAnalytics)>> {
+		let body = self.client.block_body(id).ok_or(errors::state_pruned())?;
+		body.transactions().into_iter()
+			.take(count)
+			.map(|tx| SignedTransaction::new(tx).map(|tx| (tx, CallAnalytics::default())))
+			.collect::<::std::result::Result<Vec<_>, _>>()
+			.map_err(errors::transaction)
+	}
+}
+
+/// Executes the transaction on top of given state with the profiler attached.
+fn profile<C: Call>(client: &C, transaction: &SignedTransaction, state: &mut C::State, header: &Header) -> Result<CallProfile> {
+	let start = Instant::now();
+	let executed = client.profile(transaction, state, header).map_err(errors::call)?;
+	let time = start.elapsed();
+
+	// calls to accounts without code don't run the VM
+	Ok(CallProfile::new(executed.vm_trace.unwrap_or_default(), time))
+}
+
+fn state_range_block_id(block: BlockNumber) -> Result<BlockId> {
+	match block {
+		BlockNumber::Pending => Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+		block => Ok(block_number_to_id(block)),
+	}
+}
+
+fn tracer_analytics(tracer: Tracer) -> CallAnalytics {
+	CallAnalytics {
+		transaction_tracing: true,
+		vm_tracing: tracer == Tracer::OpCount,
+		state_diffing: tracer == Tracer::Prestate,
+	}
+}
+
+/// Turns the execution traced with `tracer_analytics` into the tracer output.
+/// `state` is expected to be the state right after the execution.
+fn tracer_result<S: StateInfo>(tracer: Tracer, executed: Executed, state: &S) -> Result<TracerResult> {
+	Ok(match tracer {
+		Tracer::Call => TracerResult::Call(CallFrame::from_traces(executed.trace)),
+		Tracer::FourByte => TracerResult::FourByte(four_byte_counts(&executed.trace)),
+		Tracer::OpCount => TracerResult::OpCount(executed.vm_trace.as_ref().map_or(0, op_count)),
+		Tracer::Prestate => match executed.state_diff {
+			Some(ref diff) => TracerResult::Prestate(prestate(diff, state)?),
+			None => TracerResult::Prestate(BTreeMap::new()),
+		},
+	})
+}
+
+/// Reconstructs accounts touched by the execution as they were before it.
+/// Modified values are taken from the diff, everything else is read from the post-state,
+/// which the execution left unchanged. Storage covers the slots read or written.
+fn prestate<S: StateInfo>(diff: &StateDiff, state: &S) -> Result<BTreeMap<H160, PrestateAccount>> {
+	let mut touched = state.accessed().unwrap_or_default();
+	for (address, account) in diff.get() {
+		touched.entry(address.clone()).or_insert_with(Default::default).extend(account.storage.keys().cloned());
+	}
+
+	let mut accounts = BTreeMap::new();
+	for (address, keys) in touched {
+		let account = diff.get().get(&address);
+
+		// accounts created by the execution did not exist before
+		if let Some(&Diff::Born(_)) = account.map(|account| &account.balance) {
+			continue;
+		}
+
+		let balance = match account.and_then(|account| account.balance.pre()) {
+			Some(balance) => balance.clone(),
+			None => state.balance(&address).map_err(|_| errors::state_corrupt())?,
+		};
+		let nonce = match account.and_then(|account| account.nonce.pre()) {
+			Some(nonce) => nonce.clone(),
+			None => state.nonce(&address).map_err(|_| errors::state_corrupt())?,
+		};
+		let code = match account.and_then(|account| account.code.pre()) {
+			Some(code) => code.clone(),
+			None => state.code(&address).map_err(|_| errors::state_corrupt())?.map_or_else(Vec::new, |code| (*code).clone()),
+		};
+
+		let mut storage = BTreeMap::new();
+		for key in keys {
+			let value = match account.and_then(|account| account.storage.get(&key)) {
+				Some(&Diff::Born(_)) => Default::default(),
+				Some(value) => match value.pre() {
+					Some(value) => value.clone(),
+					None => state.storage_at(&address, &key).map_err(|_| errors::state_corrupt())?,
+				},
+				None => state.storage_at(&address, &key).map_err(|_| errors::state_corrupt())?,
+			};
+			storage.insert(key.into(), value.into());
+		}
+
+		accounts.insert(address.into(), PrestateAccount {
+			balance: balance.into(),
+			nonce: nonce.low_u64(),
+			code: if code.is_empty() { None } else { Some(code.into()) },
+			storage: storage,
+		});
+	}
+	Ok(accounts)
+}
+
+impl<C, S> Debug for DebugClient<C> where
+	S: StateInfo + 'static,
+	C: BlockChainClient + StateClient<State=S> + Call<State=S> + 'static
+{
+	type Metadata = Metadata;
+
+	fn profile_call(&self, meta: Self::Metadata, request: CallRequest, block: Trailing<BlockNumber>) -> Result<CallProfile> {
+		let block = block.unwrap_or_default();
+
+		let request = CallRequest::into(request);
+		let signed = fake_sign::sign_call(request, meta.is_dapp())?;
+
+		let id = match block {
+			BlockNumber::Num(num) => BlockId::Number(num),
+			BlockNumber::Earliest => BlockId::Earliest,
+			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
+
+			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+		};
+
+		let mut state = self.client.state_at(id).ok_or(errors::state_pruned())?;
+		let header = self.client.block_header(id).ok_or(errors::state_pruned())?;
+		let header = header.decode().map_err(errors::decode)?;
+
+		profile(&*self.client, &signed, &mut state, &header)
+	}
+
+	fn profile_transaction(&self, hash: H256) -> Result<Option<CallProfile>> {
+		let transaction = match self.client.transaction(TransactionId::Hash(hash.into())) {
+			Some(transaction) => transaction,
+			None => return Ok(None),
+		};
+
+		let id = BlockId::Hash(transaction.block_hash);
+		let header = self.client.block_header(id).ok_or(errors::state_pruned())?;
+		let header = header.decode().map_err(errors::decode)?;
+		let mut state = self.client.state_at(BlockId::Hash(*header.parent_hash())).ok_or(errors::state_pruned())?;
+
+		// preceding transactions of the block are executed without profiling
+		let preceding = self.preceding_transactions(id, transaction.transaction_index)?;
+		self.client.call_many(&preceding, &mut state, &header).map_err(errors::call)?;
+
+		let signed = SignedTransaction::new(transaction.signed).map_err(errors::transaction)?;
+		profile(&*self.client, &signed, &mut state, &header).map(Some)
+	}
+
+	fn trace_call(&self, meta: Self::Metadata, request: CallRequest, block: BlockNumber, options: TracerOptions) -> Result<TracerResult> {
+		let request = CallRequest::into(request);
+		let signed = fake_sign::sign_call(request, meta.is_dapp())?;
+
+		let id = match block {
+			BlockNumber::Num(num) => BlockId::Number(num),
+			BlockNumber::Earliest => BlockId::Earliest,
+			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
+
+			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+		};
+
+		let mut state = self.client.state_at(id).ok_or(errors::state_pruned())?;
+		let header = self.client.block_header(id).ok_or(errors::state_pruned())?;
+		let header = header.decode().map_err(errors::decode)?;
+
+		let executed = self.client.call(&signed, tracer_analytics(options.tracer), &mut state, &header).map_err(errors::call)?;
+		tracer_result(options.tracer, executed, &state)
+	}
+
+	fn trace_transaction(&self, hash: H256, options: TracerOptions) -> Result<Option<TracerResult>> {
+		let transaction = match self.client.transaction(TransactionId::Hash(hash.into())) {
+			Some(transaction) => transaction,
+			None => return Ok(None),
+		};
+
+		let id = BlockId::Hash(transaction.block_hash);
+		let header = self.client.block_header(id).ok_or(errors::state_pruned())?;
+		let header = header.decode().map_err(errors::decode)?;
+		let mut state = self.client.state_at(BlockId::Hash(*header.parent_hash())).ok_or(errors::state_pruned())?;
+
+		// preceding transactions of the block are executed without tracing
+		let mut transactions = self.preceding_transactions(id, transaction.transaction_index)?;
+		let signed = SignedTransaction::new(transaction.signed).map_err(errors::transaction)?;
+		transactions.push((signed, tracer_analytics(options.tracer)));
+
+		let executed = self.client.call_many(&transactions, &mut state, &header)
+			.map_err(errors::call)?
+			.pop()
+			.expect("at least one transaction was executed; qed");
+		tracer_result(options.tracer, executed, &state).map(Some)
+	}
+
+	fn account_range(&self, block: BlockNumber, start: H256, count: u64) -> Result<AccountRange> {
+		let id = state_range_block_id(block)?;
+		self.client.account_range(id, &start.into(), cmp::min(count, MAX_RANGE_RESULTS))
+			.map(Into::into)
+			.ok_or_else(errors::state_pruned)
+	}
+
+	fn storage_range_at(&self, block: BlockNumber, address: H160, start: H256, count: u64) -> Result<StorageRange> {
+		let id = state_range_block_id(block)?;
+		self.client.storage_range(id, &address.into(), &start.into(), cmp::min(count, MAX_RANGE_RESULTS))
+			.map(Into::into)
+			.ok_or_else(errors::state_pruned)
+	}
+}
@@ -20,18 +20,21 @@ use std::thread;
 use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 use std::sync::Arc;
 
-use rlp::{self, Rlp};
+use rlp;
 use ethereum_types::{U256, H64, H160, H256, Address};
 use parking_lot::Mutex;
+use lru_cache::LruCache;
 
 use ethash::SeedHashCompute;
+use hash::keccak;
 use ethcore::account_provider::{AccountProvider, DappId};
-use ethcore::client::{BlockChainClient, BlockId, TransactionId, UncleId, StateOrBlock, StateClient, StateInfo, Call, EngineInfo};
+use ethcore::client::{BlockChainClient, BlockId, TransactionId, TransactionIndex, UncleId, StateOrBlock, StateClient, StateInfo, Call, EngineInfo, ProvingBlockChainClient};
 use ethcore::ethereum::Ethash;
 use ethcore::filter::Filter as EthcoreFilter;
 use ethcore::header::{BlockNumber as EthBlockNumber};
 use ethcore::log_entry::LogEntry;
 use ethcore::miner::{self, MinerService};
+use ethcore::receipt::TransactionOutcome;
 use ethcore::snapshot::SnapshotService;
 use ethcore::encoded;
 use sync::{SyncProvider};
@@ -48,11 +51,14 @@ use v1::helpers::block_import::is_major_importing;
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256, block_number_to_id,
 };
 use v1::metadata::Metadata;
 
+/// Number of revert reasons of failed transactions kept to avoid replaying them on every receipt query.
+const REVERT_REASON_CACHE_SIZE: usize = 1024;
+
 const EXTRA_INFO_PROOF: &'static str = "Object exists in blockchain (fetched earlier), extra_info is always available if object exists; qed";
 
 /// Eth RPC options
@@ -66,7 +72,9 @@ pub struct EthClientOptions {
 	/// Gas Price Percentile used as default gas price.
 	pub gas_price_percentile: usize,
 	/// Set the timeout for the internal poll manager
-	pub poll_lifetime: u32
+	pub poll_lifetime: u32,
+	/// Replay failed transactions to include their revert reason in receipts
+	pub fat_receipts: bool,
 }
 
 impl EthClientOptions {
@@ -87,6 +95,7 @@ impl Default for EthClientOptions {
 			send_block_number_in_get_work: true,
 			poll_lifetime: 60u32,
 			gas_price_percentile: 50,
+			fat_receipts: false,
 		}
 	}
 }
@@ -108,6 +117,7 @@ pub struct EthClient<C, SN: ?Sized, S: ?Sized, M, EM> where
 	seed_compute: Mutex<SeedHashCompute>,
 	options: EthClientOptions,
 	eip86_transition: u64,
+	revert_reasons: Mutex<LruCache<(H256, H256), Option<String>>>,
 }
 
 #[derive(Debug)]
@@ -170,9 +180,33 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> EthClient<C, SN, S
 			seed_compute: Mutex::new(SeedHashCompute::new()),
 			options: options,
 			eip86_transition: client.eip86_transition(),
+			revert_reasons: Mutex::new(LruCache::new(REVERT_REASON_CACHE_SIZE)),
 		}
 	}
 
+	/// Revert reason of a failed transaction, replaying its block up to it at most once.
+	fn revert_reason(&self, block_hash: H256, index: usize, hash: H256) -> Option<String> {
+		if let Some(reason) = self.revert_reasons.lock().get_mut(&(block_hash, hash)) {
+			return reason.clone();
+		}
+
+		let reason = self.client.replay(TransactionId::Location(BlockId::Hash(block_hash), index), Default::default())
+			.ok()
+			.and_then(|executed| executed.revert_reason());
+		self.revert_reasons.lock().insert((block_hash, hash), reason.clone());
+		reason
+	}
+
+	/// Network head linked by head-first sync which is not imported yet.
+	fn sync_head(&self) -> Option<(encoded::Block, U256)> {
+		let best = self.client.chain_info().best_block_number;
+		self.sync.head_block().and_then(|(block, difficulty)| if block.number() > best {
+			Some((block, difficulty))
+		} else {
+			None
+		})
+	}
+
 	fn rich_block(&self, id: BlockNumberOrId, include_txs: bool) -> Result<Option<RichBlock>> {
 		let client = &self.client;
 
@@ -207,9 +241,18 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> EthClient<C, SN, S
 				}
 			},
 
+			BlockNumberOrId::Number(BlockNumber::Latest) => match self.sync_head() {
+				Some((block, difficulty)) => {
+					let extra = self.client.engine().extra_info(&block.decode_header());
+					(Some(block), Some(difficulty), Some(extra), false)
+				},
+				None => client_query(BlockId::Latest),
+			},
+
 			BlockNumberOrId::Number(num) => {
 				let id = match num {
 					BlockNumber::Latest => BlockId::Latest,
+					BlockNumber::Finalized => BlockId::Finalized,
 					BlockNumber::Earliest => BlockId::Earliest,
 					BlockNumber::Num(n) => BlockId::Number(n),
 					BlockNumber::Pending => unreachable!() // Already covered
@@ -412,6 +455,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> EthClient<C, SN, S
 			BlockNumber::Num(num) => BlockId::Number(num).into(),
 			BlockNumber::Earliest => BlockId::Earliest.into(),
 			BlockNumber::Latest => BlockId::Latest.into(),
+			BlockNumber::Finalized => BlockId::Finalized.into(),
 
 			BlockNumber::Pending => {
 				let info = self.client.chain_info();
@@ -453,6 +497,7 @@ fn check_known<C>(client: &C, number: BlockNumber) -> Result<()> where C: BlockC
 
 		BlockNumber::Num(n) => BlockId::Number(n),
 		BlockNumber::Latest => BlockId::Latest,
+		BlockNumber::Finalized => BlockId::Finalized,
 		BlockNumber::Earliest => BlockId::Earliest,
 	};
 
@@ -462,10 +507,25 @@ fn check_known<C>(client: &C, number: BlockNumber) -> Result<()> where C: BlockC
 	}
 }
 
+/// Fails if a transaction that was not found might just not be indexed.
+fn check_indexed<C>(client: &C) -> Result<()> where C: BlockChainClient {
+	match client.transaction_index() {
+		TransactionIndex::All => Ok(()),
+		TransactionIndex::Recent(blocks) => {
+			let best_block = client.chain_info().best_block_number;
+			match best_block + 1 > blocks {
+				true => Err(errors::transaction_not_indexed(Some(best_block + 1 - blocks))),
+				false => Ok(()),
+			}
+		},
+		TransactionIndex::None => Err(errors::transaction_not_indexed(None)),
+	}
+}
+
 const MAX_QUEUE_SIZE_TO_MINE_ON: usize = 4;	// because uncles go back 6.
 
 impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<C, SN, S, M, EM> where
-	C: miner::BlockChainClient + BlockChainClient + StateClient<State=T> + Call<State=T> + EngineInfo + 'static,
+	C: miner::BlockChainClient + BlockChainClient + ProvingBlockChainClient + StateClient<State=T> + Call<State=T> + EngineInfo + 'static,
 	SN: SnapshotService + 'static,
 	S: SyncProvider + 'static,
 	M: MinerService<State=T> + 'static,
@@ -540,7 +600,9 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 	}
 
 	fn block_number(&self) -> Result<RpcU256> {
-		Ok(RpcU256::from(self.client.chain_info().best_block_number))
+		let best = self.client.chain_info().best_block_number;
+		let head = self.sync_head().map_or(best, |(block, _)| block.number());
+		Ok(RpcU256::from(head))
 	}
 
 	fn balance(&self, address: RpcH160, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256> {
@@ -650,6 +712,49 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		Box::new(future::done(res))
 	}
 
+	fn proof(&self, address: RpcH160, keys: Vec<RpcU256>, num: Trailing<BlockNumber>) -> BoxFuture<EthAccount> {
+		let address: Address = RpcH160::into(address);
+		let account_key = keccak(&address);
+
+		// pending state is not committed to a trie, prove against the latest block instead.
+		let id = match num.unwrap_or_default() {
+			BlockNumber::Pending => BlockId::Latest,
+			number => {
+				try_bf!(check_known(&*self.client, number.clone()));
+				block_number_to_id(number)
+			},
+		};
+
+		let (account_proof, account) = match self.client.prove_account(account_key, id) {
+			Some(proof) => proof,
+			None => return Box::new(future::err(errors::state_pruned())),
+		};
+
+		let mut storage_proof = Vec::with_capacity(keys.len());
+		for key in keys {
+			let key: U256 = key.into();
+			let (proof, value) = match self.client.prove_storage(account_key, keccak(&H256::from(key)), id) {
+				Some(proof) => proof,
+				None => return Box::new(future::err(errors::state_pruned())),
+			};
+			storage_proof.push(StorageProof {
+				key: key.into(),
+				value: U256::from(&*value).into(),
+				proof: proof.into_iter().map(Bytes::new).collect(),
+			});
+		}
+
+		Box::new(future::ok(EthAccount {
+			address: address.into(),
+			balance: account.balance.into(),
+			nonce: account.nonce.into(),
+			code_hash: account.code_hash.into(),
+			storage_hash: account.storage_root.into(),
+			account_proof: account_proof.into_iter().map(Bytes::new).collect(),
+			storage_proof: storage_proof,
+		}))
+	}
+
 	fn block_by_hash(&self, hash: RpcH256, include_txs: bool) -> BoxFuture<Option<RichBlock>> {
 		Box::new(future::done(self.rich_block(BlockId::Hash(hash.into()).into(), include_txs)))
 	}
@@ -666,6 +771,10 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				.map(|t| Transaction::from_pending(t.pending().clone(), block_number + 1, self.eip86_transition))
 		});
 
+		if tx.is_none() {
+			try_bf!(check_indexed(&*self.client));
+		}
+
 		Box::new(future::ok(tx))
 	}
 
@@ -678,6 +787,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		let block_id = match num {
 			BlockNumber::Latest => PendingOrBlock::Block(BlockId::Latest),
 			BlockNumber::Earliest => PendingOrBlock::Block(BlockId::Earliest),
+			BlockNumber::Finalized => PendingOrBlock::Block(BlockId::Finalized),
 			BlockNumber::Num(num) => PendingOrBlock::Block(BlockId::Number(num)),
 			BlockNumber::Pending => PendingOrBlock::Pending,
 		};
@@ -693,8 +803,21 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		match (self.miner.pending_receipt(best_block, &hash), self.options.allow_pending_receipt_query) {
 			(Some(receipt), true) => Box::new(future::ok(Some(receipt.into()))),
 			_ => {
-				let receipt = self.client.transaction_receipt(TransactionId::Hash(hash));
-				Box::new(future::ok(receipt.map(Into::into)))
+				let receipt = self.client.transaction_receipt(TransactionId::Hash(hash)).map(|receipt| {
+					let failed = receipt.outcome == TransactionOutcome::StatusCode(0);
+					let (block_hash, index) = (receipt.block_hash, receipt.transaction_index);
+					let mut receipt = Receipt::from(receipt);
+					if failed && self.options.fat_receipts {
+						receipt.revert_reason = self.revert_reason(block_hash, index, hash);
+					}
+					receipt
+				});
+
+				if receipt.is_none() {
+					try_bf!(check_indexed(&*self.client));
+				}
+
+				Box::new(future::ok(receipt))
 			}
 		}
 	}
@@ -710,6 +833,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		let id = match num {
 			BlockNumber::Latest => PendingUncleId { id: PendingOrBlock::Block(BlockId::Latest), position: index.value() },
 			BlockNumber::Earliest => PendingUncleId { id: PendingOrBlock::Block(BlockId::Earliest), position: index.value() },
+			BlockNumber::Finalized => PendingUncleId { id: PendingOrBlock::Block(BlockId::Finalized), position: index.value() },
 			BlockNumber::Num(num) => PendingUncleId { id: PendingOrBlock::Block(BlockId::Number(num)), position: index.value() },
 
 			BlockNumber::Pending => PendingUncleId { id: PendingOrBlock::Pending, position: index.value() },
@@ -820,9 +944,14 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 	}
 
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<RpcH256> {
-		Rlp::new(&raw.into_vec()).as_val()
-			.map_err(errors::rlp)
-			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))
+		let best_header = self.client.best_block_header();
+
+		self.client.engine().decode_transaction(&raw.into_vec(), best_header.number() + 1)
+			.and_then(|tx| {
+				self.client.engine().verify_transaction_basic(&tx, &best_header)?;
+				Ok(SignedTransaction::new(tx)?)
+			})
+			.map_err(errors::transaction)
 			.and_then(|signed_transaction| {
 				FullDispatcher::dispatch_transaction(
 					&*self.client,
@@ -855,6 +984,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				BlockNumber::Num(num) => BlockId::Number(num),
 				BlockNumber::Earliest => BlockId::Earliest,
 				BlockNumber::Latest => BlockId::Latest,
+				BlockNumber::Finalized => BlockId::Finalized,
 				BlockNumber::Pending => unreachable!(), // Already covered
 			};
 
@@ -894,6 +1024,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				BlockNumber::Num(num) => BlockId::Number(num),
 				BlockNumber::Earliest => BlockId::Earliest,
 				BlockNumber::Latest => BlockId::Latest,
+				BlockNumber::Finalized => BlockId::Finalized,
 				BlockNumber::Pending => unreachable!(), // Already covered
 			};
 
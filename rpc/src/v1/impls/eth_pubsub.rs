@@ -17,7 +17,7 @@
 //! Eth PUB-SUB rpc implementation.
 
 use std::sync::{Arc, Weak};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 use jsonrpc_core::{BoxFuture, Result, Error};
@@ -30,8 +30,9 @@ use v1::helpers::{errors, limit_logs, Subscribers};
 use v1::helpers::light_fetch::LightFetch;
 use v1::metadata::Metadata;
 use v1::traits::EthPubSub;
-use v1::types::{pubsub, RichHeader, Log};
+use v1::types::{pubsub, RichHeader, Log, AccountStateInfo, U256};
 
+use ethcore::account_provider::AccountProvider;
 use ethcore::encoded;
 use ethcore::filter::Filter as EthFilter;
 use ethcore::client::{BlockChainClient, ChainNotify, ChainRoute, ChainRouteType, BlockId};
@@ -40,7 +41,7 @@ use light::cache::Cache;
 use light::on_demand::OnDemand;
 use light::client::{LightChainClient, LightChainNotify};
 use parity_reactor::Remote;
-use ethereum_types::H256;
+use ethereum_types::{H256, Address};
 use bytes::Bytes;
 use parking_lot::{RwLock, Mutex};
 
@@ -52,14 +53,30 @@ pub struct EthPubSubClient<C> {
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
 	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	watch_only_subscribers: Option<Arc<RwLock<Subscribers<Client>>>>,
 }
 
 impl<C> EthPubSubClient<C> {
 	/// Creates new `EthPubSubClient`.
 	pub fn new(client: Arc<C>, remote: Remote) -> Self {
+		Self::build(client, remote, None)
+	}
+
+	/// Creates new `EthPubSubClient` which also notifies about watch-only accounts changes.
+	pub fn with_accounts(client: Arc<C>, remote: Remote, accounts: Arc<AccountProvider>) -> Self {
+		Self::build(client, remote, Some(accounts))
+	}
+
+	fn build(client: Arc<C>, remote: Remote, accounts: Option<Arc<AccountProvider>>) -> Self {
 		let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let transactions_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+		let watch_only = accounts.map(|accounts| WatchOnly {
+			accounts,
+			subscribers: Arc::new(RwLock::new(Subscribers::default())),
+			last: Mutex::new(HashMap::new()),
+		});
+		let watch_only_subscribers = watch_only.as_ref().map(|watch_only| watch_only.subscribers.clone());
 
 		EthPubSubClient {
 			handler: Arc::new(ChainNotificationHandler {
@@ -68,21 +85,36 @@ impl<C> EthPubSubClient<C> {
 				heads_subscribers: heads_subscribers.clone(),
 				logs_subscribers: logs_subscribers.clone(),
 				transactions_subscribers: transactions_subscribers.clone(),
+				watch_only,
 			}),
 			heads_subscribers,
 			logs_subscribers,
 			transactions_subscribers,
+			watch_only_subscribers,
 		}
 	}
 
 	/// Creates new `EthPubSubCient` with deterministic subscription ids.
 	#[cfg(test)]
 	pub fn new_test(client: Arc<C>, remote: Remote) -> Self {
-		let client = Self::new(client, remote);
-		*client.heads_subscribers.write() = Subscribers::new_test();
-		*client.logs_subscribers.write() = Subscribers::new_test();
-		*client.transactions_subscribers.write() = Subscribers::new_test();
-		client
+		Self::new(client, remote).with_test_ids()
+	}
+
+	/// Creates new `EthPubSubCient` tracking watch-only accounts with deterministic subscription ids.
+	#[cfg(test)]
+	pub fn new_test_with_accounts(client: Arc<C>, remote: Remote, accounts: Arc<AccountProvider>) -> Self {
+		Self::with_accounts(client, remote, accounts).with_test_ids()
+	}
+
+	#[cfg(test)]
+	fn with_test_ids(self) -> Self {
+		*self.heads_subscribers.write() = Subscribers::new_test();
+		*self.logs_subscribers.write() = Subscribers::new_test();
+		*self.transactions_subscribers.write() = Subscribers::new_test();
+		if let Some(ref subscribers) = self.watch_only_subscribers {
+			*subscribers.write() = Subscribers::new_test();
+		}
+		self
 	}
 
 	/// Returns a chain notification handler.
@@ -119,6 +151,15 @@ pub struct ChainNotificationHandler<C> {
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
 	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	watch_only: Option<WatchOnly>,
+}
+
+/// Watch-only accounts tracked for `watchOnlyAccounts` subscribers.
+struct WatchOnly {
+	accounts: Arc<AccountProvider>,
+	subscribers: Arc<RwLock<Subscribers<Client>>>,
+	/// Balance and nonce last seen for each watch-only address.
+	last: Mutex<HashMap<Address, (U256, U256)>>,
 }
 
 impl<C> ChainNotificationHandler<C> {
@@ -174,6 +215,38 @@ impl<C> ChainNotificationHandler<C> {
 		}
 	}
 
+	fn notify_watch_only<F>(&self, state: F) where
+		F: Fn(&Address) -> AccountStateInfo,
+	{
+		let watch_only = match self.watch_only {
+			Some(ref watch_only) => watch_only,
+			None => return,
+		};
+
+		let subscribers = watch_only.subscribers.read();
+		let mut last = watch_only.last.lock();
+		// Nothing is tracked without subscribers, so that a new one doesn't get compared against a stale state.
+		if subscribers.is_empty() {
+			last.clear();
+			return;
+		}
+
+		let addresses = watch_only.accounts.watch_only_info();
+		last.retain(|address, _| addresses.contains_key(address));
+
+		for address in addresses.keys() {
+			let info = state(address);
+			let current = (info.balance, info.nonce);
+			if last.insert(*address, current) == Some(current) {
+				continue;
+			}
+
+			for subscriber in subscribers.values() {
+				Self::notify(&self.remote, subscriber, pubsub::Result::AccountState(info.clone()));
+			}
+		}
+	}
+
 	/// Notify all subscribers about new transaction hashes.
 	pub fn notify_new_transactions(&self, hashes: &[H256]) {
 		for subscriber in self.transactions_subscribers.read().values() {
@@ -261,6 +334,18 @@ impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
 					}).collect()),
 			}
 		});
+
+		// Watch-only accounts at the new best block.
+		if !route.route().is_empty() {
+			self.notify_watch_only(|address| AccountStateInfo {
+				address: (*address).into(),
+				balance: self.client.latest_balance(address).into(),
+				nonce: self.client.latest_nonce(address).into(),
+				has_code: self.client.code(address, BlockId::Latest.into())
+					.and_then(|code| code)
+					.map_or(false, |code| !code.is_empty()),
+			});
+		}
 	}
 }
 
@@ -296,6 +381,16 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 			(pubsub::Kind::NewPendingTransactions, _) => {
 				errors::invalid_params("newPendingTransactions", "Expected no parameters.")
 			},
+			(pubsub::Kind::WatchOnlyAccounts, None) => match self.watch_only_subscribers {
+				Some(ref subscribers) => {
+					subscribers.write().push(subscriber);
+					return;
+				},
+				None => errors::unimplemented(None),
+			},
+			(pubsub::Kind::WatchOnlyAccounts, _) => {
+				errors::invalid_params("watchOnlyAccounts", "Expected no parameters.")
+			},
 			_ => {
 				errors::unimplemented(None)
 			},
@@ -308,7 +403,8 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 		let res = self.heads_subscribers.write().remove(&id).is_some();
 		let res2 = self.logs_subscribers.write().remove(&id).is_some();
 		let res3 = self.transactions_subscribers.write().remove(&id).is_some();
+		let res4 = self.watch_only_subscribers.as_ref().map_or(false, |subscribers| subscribers.write().remove(&id).is_some());
 
-		Ok(res || res2 || res3)
+		Ok(res || res2 || res3 || res4)
 	}
 }
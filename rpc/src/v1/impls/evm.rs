@@ -0,0 +1,70 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dev chain block sealing api implementation.
+
+use std::sync::Arc;
+
+use ethcore::client::{EngineClient, EngineInfo};
+use ethcore::engines::InstantSeal;
+use ethcore::machine::EthereumMachine;
+
+use jsonrpc_core::Result;
+use v1::helpers::errors;
+use v1::traits::Evm;
+
+/// Dev chain block sealing api implementation.
+pub struct EvmClient<C> {
+	client: Arc<C>,
+}
+
+impl<C> EvmClient<C> {
+	/// Creates new Evm client.
+	pub fn new(client: &Arc<C>) -> Self {
+		EvmClient {
+			client: client.clone(),
+		}
+	}
+}
+
+impl<C: EngineInfo> EvmClient<C> {
+	fn engine(&self) -> Result<&InstantSeal<EthereumMachine>> {
+		self.client.engine().as_instant_seal()
+			.ok_or_else(|| errors::unsupported("The chain is not using the instant seal engine.", None))
+	}
+}
+
+impl<C> Evm for EvmClient<C> where
+	C: EngineClient + EngineInfo + 'static,
+{
+	fn mine(&self) -> Result<String> {
+		let engine = self.engine()?;
+		let best_block = self.client.chain_info().best_block_number;
+
+		engine.request_seal();
+		self.client.update_sealing();
+
+		if self.client.chain_info().best_block_number > best_block {
+			Ok("0x0".into())
+		} else {
+			Err(errors::internal("No block was sealed on top of the best block.", best_block))
+		}
+	}
+
+	fn increase_time(&self, seconds: u64) -> Result<u64> {
+		Ok(self.engine()?.increase_time(seconds))
+	}
+}
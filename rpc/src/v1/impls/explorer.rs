@@ -0,0 +1,139 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Explorer api implementation.
+
+use std::cmp;
+use std::sync::Arc;
+
+use ethcore::client::{BlockChainClient, BlockId, ExplorerIndex, TransactionId};
+
+use jsonrpc_core::Result;
+use jsonrpc_macros::Trailing;
+use v1::helpers::{address_index, errors};
+use v1::traits::Explorer;
+use v1::types::{
+	AddressTransactionsOptions, BlockNumber, ExplorerBlock, ExplorerTransaction, H160, H256, Transaction,
+	TransactionDirection, block_number_to_id,
+};
+
+/// Maximal number of blocks or transactions returned by a single call.
+const MAX_PAGE_SIZE: u64 = 1000;
+/// Number of transactions `parity_getTransactionsByAddress` returns if no limit is given.
+const DEFAULT_ADDRESS_TRANSACTIONS: u64 = 100;
+
+/// Explorer api implementation.
+pub struct ExplorerClient<C> {
+	client: Arc<C>,
+	index: Option<Arc<ExplorerIndex>>,
+}
+
+impl<C> ExplorerClient<C> {
+	/// Creates new Explorer client. Address history is only available with an index.
+	pub fn new(client: &Arc<C>, index: Option<Arc<ExplorerIndex>>) -> Self {
+		ExplorerClient {
+			client: client.clone(),
+			index,
+		}
+	}
+}
+
+impl<C> Explorer for ExplorerClient<C> where
+	C: BlockChainClient + 'static,
+{
+	fn block_list(&self, count: u64, from: Trailing<BlockNumber>) -> Result<Vec<ExplorerBlock>> {
+		let from = match from.unwrap_or_default() {
+			// the pending block is not sealed yet
+			BlockNumber::Pending => BlockId::Latest,
+			number => block_number_to_id(number),
+		};
+		let from = match self.client.block_number(from) {
+			Some(number) => number,
+			None => return Ok(Vec::new()),
+		};
+
+		let count = cmp::min(cmp::min(count, MAX_PAGE_SIZE), from + 1);
+		Ok((0..count)
+			.filter_map(|offset| self.client.block(BlockId::Number(from - offset)))
+			.map(Into::into)
+			.collect())
+	}
+
+	fn address_history(&self, address: H160, count: u64, skip: Trailing<u64>) -> Result<Vec<Transaction>> {
+		let index = self.index.as_ref()
+			.ok_or_else(|| errors::unsupported("The explorer index is disabled. Run with --enable-explorer-index to enable it.", None))?;
+
+		let count = cmp::min(count, MAX_PAGE_SIZE) as usize;
+		let mut skip = skip.unwrap_or_default();
+		let transactions = index.address_transactions(&address.into(), 0, u64::max_value(), None, count, |_| match skip {
+			0 => true,
+			_ => {
+				skip -= 1;
+				false
+			},
+		});
+		Ok(address_index::indexed_transactions(&*self.client, transactions))
+	}
+
+	fn transactions_by_address(&self, address: H160, options: Trailing<AddressTransactionsOptions>) -> Result<Vec<Transaction>> {
+		let index = self.index.as_ref()
+			.ok_or_else(|| errors::unsupported("The explorer index is disabled. Run with --enable-explorer-index to enable it.", None))?;
+		let options = options.unwrap_or_default();
+
+		let best_block = self.client.chain_info().best_block_number;
+		let to_number = |number: BlockNumber| match number {
+			BlockNumber::Num(number) => Ok(number),
+			// the pending block is not indexed
+			BlockNumber::Pending => Ok(best_block + 1),
+			number => self.client.block_number(block_number_to_id(number)).ok_or_else(errors::unknown_block),
+		};
+		let from = match options.from_block {
+			Some(number) => to_number(number)?,
+			None => 0,
+		};
+		let to = match options.to_block {
+			Some(number) => to_number(number)?,
+			None => best_block,
+		};
+		let after = options.after.map(|after| {
+			let (number, index): (u64, u64) = (after.block_number.into(), after.transaction_index.into());
+			(number, cmp::min(index, u32::max_value() as u64) as usize)
+		});
+
+		let limit = cmp::min(options.limit.unwrap_or(DEFAULT_ADDRESS_TRANSACTIONS), MAX_PAGE_SIZE) as usize;
+		let transactions = index.address_transactions(&address.into(), from, to, after, limit, |transaction| match options.direction {
+			Some(TransactionDirection::Sent) => transaction.sent,
+			Some(TransactionDirection::Received) => transaction.received,
+			None => true,
+		});
+		Ok(address_index::indexed_transactions(&*self.client, transactions))
+	}
+
+	fn transaction_details(&self, hash: H256) -> Result<Option<ExplorerTransaction>> {
+		let id = TransactionId::Hash(hash.into());
+		let transaction = match self.client.transaction(id.clone()) {
+			Some(transaction) => transaction,
+			None => return Ok(None),
+		};
+
+		let block = self.client.block(BlockId::Hash(transaction.block_hash)).map(Into::into);
+		Ok(Some(ExplorerTransaction {
+			transaction: Transaction::from_localized(transaction, self.client.eip86_transition()),
+			receipt: self.client.transaction_receipt(id).map(Into::into),
+			block,
+		}))
+	}
+}
@@ -36,7 +36,6 @@ use sync::LightSync;
 use hash::{KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP};
 use ethereum_types::U256;
 use parking_lot::{RwLock, Mutex};
-use rlp::Rlp;
 use transaction::SignedTransaction;
 
 use v1::impls::eth_filter::Filterable;
@@ -46,7 +45,7 @@ use v1::helpers::light_fetch::{self, LightFetch};
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
@@ -75,6 +74,7 @@ impl<T> EthClient<T> {
 			BlockNumber::Num(n) => BlockId::Number(n),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
 			BlockNumber::Pending => {
 				warn!("`Pending` is deprecated and may be removed in future versions. Falling back to `Latest`");
 				BlockId::Latest
@@ -377,15 +377,14 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<RpcH256> {
 		let best_header = self.client.best_block_header().decode().map_err(errors::decode)?;
 
-		Rlp::new(&raw.into_vec()).as_val()
-			.map_err(errors::rlp)
+		self.client.engine().decode_transaction(&raw.into_vec(), best_header.number() + 1)
 			.and_then(|tx| {
-				self.client.engine().verify_transaction_basic(&tx, &best_header)
-					.map_err(errors::transaction)?;
-
-				let signed = SignedTransaction::new(tx).map_err(errors::transaction)?;
+				self.client.engine().verify_transaction_basic(&tx, &best_header)?;
+				Ok(SignedTransaction::new(tx)?)
+			})
+			.map_err(errors::transaction)
+			.and_then(|signed| {
 				let hash = signed.hash();
-
 				self.transaction_queue.write().import(signed.into())
 					.map(|_| hash)
 					.map_err(errors::transaction)
@@ -520,6 +519,10 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn proof(&self, _address: RpcH160, _keys: Vec<RpcU256>, _num: Trailing<BlockNumber>) -> BoxFuture<EthAccount> {
+		Box::new(future::err(errors::light_unimplemented(None)))
+	}
+
 	fn submit_work(&self, _nonce: RpcH64, _pow_hash: RpcH256, _mix_hash: RpcH256) -> Result<bool> {
 		Err(errors::light_unimplemented(None))
 	}
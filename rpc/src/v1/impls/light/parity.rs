@@ -25,7 +25,7 @@ use ethkey::{crypto::ecies, Brain, Generator};
 use ethstore::random_phrase;
 use sync::LightSyncProvider;
 use ethcore::account_provider::AccountProvider;
-use ethcore_logger::RotatingLogger;
+use ethcore_logger::{LogLevel, RotatingLogger};
 use node_health::{NodeHealth, Health};
 use ethcore::ids::BlockId;
 
@@ -40,13 +40,15 @@ use v1::helpers::light_fetch::LightFetch;
 use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
-	Bytes, U256, U64, H160, H256, H512, CallRequest,
-	Peers, Transaction, RpcSettings, Histogram,
+	Bytes, U256, U64, H160, H256, H512, CallRequest, CallBundleResult, GasEstimate, TransactionPreview,
+	Peers, PeerPermission, Transaction, RpcSettings, Histogram,
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, Header, RichHeader,
+	AccountInfo, HwAccountInfo, AccountStateInfo, Header, RichHeader, ForkStatus, ReorgAlert,
+	ValidatorSetInfo, MisbehaviorReport, FinalityStatus, LogRecord,
 };
+use v1::impls::parity::DEFAULT_RECENT_LOGS;
 use Host;
 
 /// Parity implementation for light client.
@@ -181,7 +183,24 @@ impl Parity for ParityClient {
 	}
 
 	fn dev_logs_levels(&self) -> Result<String> {
-		Ok(self.logger.levels().to_owned())
+		Ok(self.logger.levels())
+	}
+
+	fn get_logging(&self) -> Result<String> {
+		Ok(self.logger.levels())
+	}
+
+	fn recent_logs(&self, level: Option<String>, target: Option<String>, limit: Trailing<usize>) -> Result<Vec<LogRecord>> {
+		let level = match level {
+			Some(level) => Some(level.parse::<LogLevel>().map_err(|_| errors::invalid_params("level", level))?),
+			None => None,
+		};
+		let limit = match limit.unwrap_or_default() {
+			0 => DEFAULT_RECENT_LOGS,
+			limit => limit,
+		};
+
+		Ok(self.logger.recent(level, target.as_ref().map(String::as_str), limit).into_iter().map(Into::into).collect())
 	}
 
 	fn net_chain(&self) -> Result<String> {
@@ -200,6 +219,10 @@ impl Parity for ParityClient {
 		})
 	}
 
+	fn net_peer_permissions(&self) -> Result<Vec<PeerPermission>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn net_port(&self) -> Result<u16> {
 		Ok(self.settings.network_port)
 	}
@@ -258,6 +281,10 @@ impl Parity for ParityClient {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn accounts_info_at(&self, _: Vec<H160>, _: Trailing<BlockNumber>) -> Result<Vec<AccountStateInfo>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn encrypt_message(&self, key: H512, phrase: Bytes) -> Result<Bytes> {
 		ecies::encrypt(&key.into(), &DEFAULT_MAC, &phrase.0)
 			.map_err(errors::encryption)
@@ -382,6 +409,14 @@ impl Parity for ParityClient {
 		})
 	}
 
+	fn fork_status(&self) -> Result<ForkStatus> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn pending_reorg(&self) -> Result<Option<ReorgAlert>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn node_kind(&self) -> Result<::v1::types::NodeKind> {
 		use ::v1::types::{NodeKind, Availability, Capability};
 
@@ -428,6 +463,7 @@ impl Parity for ParityClient {
 			BlockNumber::Num(n) => BlockId::Number(n),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest | BlockNumber::Pending => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
 		};
 
 		Box::new(self.fetcher().header(id).and_then(from_encoded))
@@ -441,8 +477,37 @@ impl Parity for ParityClient {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn estimate_gas_detailed(&self, _meta: Self::Metadata, _request: CallRequest, _block: Trailing<BlockNumber>) -> Result<GasEstimate> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn call_bundle(&self, _transactions: Vec<Bytes>, _block: Trailing<BlockNumber>) -> Result<Vec<CallBundleResult>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn preview_transaction(&self, _transaction: Bytes) -> Result<TransactionPreview> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn node_health(&self) -> BoxFuture<Health> {
 		Box::new(self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err)))
 	}
+
+	fn time_drift(&self) -> BoxFuture<i64> {
+		Box::new(self.health.time_drift()
+			.map_err(|err| errors::internal("Unable to measure time drift.", err)))
+	}
+
+	fn validator_set(&self, _block: Trailing<BlockNumber>) -> Result<Option<ValidatorSetInfo>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn validator_misbehavior_reports(&self) -> Result<Vec<MisbehaviorReport>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn finality_status(&self) -> Result<FinalityStatus> {
+		Ok(self.client.finality_status().into())
+	}
 }
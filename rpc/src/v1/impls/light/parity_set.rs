@@ -21,33 +21,47 @@ use std::io;
 use std::sync::Arc;
 
 use sync::ManageNetwork;
+use ethcore_logger::RotatingLogger;
 use fetch::{self, Fetch};
 use futures_cpupool::CpuPool;
 use hash::keccak_buffer;
 
 use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_core::futures::Future;
+use jsonrpc_macros::Trailing;
 use v1::helpers::dapps::DappsService;
+use v1::helpers::drain::{self, RpcDrain};
 use v1::helpers::errors;
 use v1::traits::ParitySet;
-use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
+use v1::types::{BlockNumber, Bytes, H160, H256, U256, ReleaseInfo, PendingUpdate, Transaction, LocalDapp, TokenList, TokenBalance};
 
 /// Parity-specific rpc interface for operations altering the settings.
 pub struct ParitySetClient<F> {
 	net: Arc<ManageNetwork>,
+	logger: Arc<RotatingLogger>,
 	dapps: Option<Arc<DappsService>>,
 	fetch: F,
 	pool: CpuPool,
+	drain: Arc<RpcDrain>,
 }
 
 impl<F: Fetch> ParitySetClient<F> {
 	/// Creates new `ParitySetClient` with given `Fetch`.
-	pub fn new(net: Arc<ManageNetwork>, dapps: Option<Arc<DappsService>>, fetch: F, p: CpuPool) -> Self {
+	pub fn new(
+		net: Arc<ManageNetwork>,
+		logger: Arc<RotatingLogger>,
+		dapps: Option<Arc<DappsService>>,
+		fetch: F,
+		p: CpuPool,
+		drain: Arc<RpcDrain>,
+	) -> Self {
 		ParitySetClient {
 			net: net,
+			logger: logger,
 			dapps: dapps,
 			fetch: fetch,
 			pool: p,
+			drain: drain,
 		}
 	}
 }
@@ -156,7 +170,44 @@ impl<F: Fetch> ParitySet for ParitySetClient<F> {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn pending_update(&self) -> Result<Option<PendingUpdate>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn defer_update(&self, _blocks: u64) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn rollback_update(&self) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn remove_transaction(&self, _hash: H256) -> Result<Option<Transaction>> {
 		Err(errors::light_unimplemented(None))
 	}
+
+	fn accept_reorg(&self) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn set_logging_targets(&self, targets: String) -> Result<bool> {
+		self.logger.set_levels(&targets).map_err(|e| errors::invalid_params("targets", e))?;
+		Ok(true)
+	}
+
+	fn drain_rpc(&self, seconds: u64) -> Result<bool> {
+		drain::start_draining(&self.drain, seconds)
+	}
+
+	fn block_witness(&self, _: BlockNumber) -> Result<Vec<Bytes>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn verify_block_witness(&self, _: Bytes, _: Vec<Bytes>) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn token_balances(&self, _: H160, _: TokenList, _: Trailing<BlockNumber>) -> Result<Vec<TokenBalance>> {
+		Err(errors::light_unimplemented(None))
+	}
 }
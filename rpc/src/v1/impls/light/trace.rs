@@ -21,7 +21,7 @@ use jsonrpc_macros::Trailing;
 use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::errors;
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256, StateDiffTarget, TransactionStateDiff};
 
 /// Traces api implementation.
 // TODO: all calling APIs should be possible w. proved remote TX execution.
@@ -38,6 +38,10 @@ impl Traces for TracesClient {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn state_diff_at(&self, _target: StateDiffTarget) -> Result<Option<Vec<TransactionStateDiff>>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn transaction_traces(&self, _transaction_hash: H256) -> Result<Option<Vec<LocalizedTrace>>> {
 		Err(errors::light_unimplemented(None))
 	}
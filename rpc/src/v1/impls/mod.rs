@@ -16,12 +16,18 @@
 
 //! Ethereum rpc interface implementation.
 
+mod clique;
+mod debug;
 mod eth;
 mod eth_filter;
 mod eth_pubsub;
+mod evm;
+mod explorer;
 mod net;
 mod parity;
 mod parity_accounts;
+mod parity_nonces;
+mod parity_registrar;
 mod parity_set;
 mod personal;
 mod pubsub;
@@ -36,12 +42,18 @@ mod private;
 
 pub mod light;
 
+pub use self::clique::CliqueClient;
+pub use self::debug::DebugClient;
 pub use self::eth::{EthClient, EthClientOptions};
 pub use self::eth_filter::EthFilterClient;
 pub use self::eth_pubsub::EthPubSubClient;
+pub use self::evm::EvmClient;
+pub use self::explorer::ExplorerClient;
 pub use self::net::NetClient;
 pub use self::parity::ParityClient;
 pub use self::parity_accounts::ParityAccountsClient;
+pub use self::parity_nonces::ParityNoncesClient;
+pub use self::parity_registrar::ParityRegistrarClient;
 pub use self::parity_set::ParitySetClient;
 pub use self::personal::PersonalClient;
 pub use self::pubsub::PubSubClient;
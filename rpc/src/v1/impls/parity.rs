@@ -19,7 +19,8 @@ use std::sync::Arc;
 use std::str::FromStr;
 use std::collections::{BTreeMap, HashSet};
 
-use ethereum_types::Address;
+use ethereum_types::{Address, U256 as EthU256};
+use hash::KECCAK_EMPTY;
 use version::version_data;
 
 use crypto::DEFAULT_MAC;
@@ -27,11 +28,14 @@ use ethkey::{crypto::ecies, Brain, Generator};
 use ethstore::random_phrase;
 use sync::{SyncProvider, ManageNetwork};
 use ethcore::account_provider::AccountProvider;
-use ethcore::client::{BlockChainClient, StateClient, Call};
+use ethcore::client::{BlockChainClient, StateClient, Call, EngineInfo};
 use ethcore::ids::BlockId;
 use ethcore::miner::{self, MinerService};
 use ethcore::state::StateInfo;
-use ethcore_logger::RotatingLogger;
+use rlp::Rlp;
+use transaction::{Action, SignedTransaction};
+use ethcore_logger::{LogLevel, RotatingLogger};
+use node_filter::NodeFilter;
 use node_health::{NodeHealth, Health};
 use updater::{Service as UpdateService};
 use jsonrpc_core::{BoxFuture, Result};
@@ -41,16 +45,22 @@ use v1::helpers::{self, errors, fake_sign, ipfs, SigningQueue, SignerService, Ne
 use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
-	Bytes, U256, U64, H160, H256, H512, CallRequest,
-	Peers, Transaction, RpcSettings, Histogram,
+	Bytes, U256, U64, H160, H256, H512, CallRequest, CallBundleResult, GasEstimate, TransactionPreview,
+	Peers, PeerPermission, Transaction, RpcSettings, Histogram,
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader,
+	AccountInfo, HwAccountInfo, AccountStateInfo,
+	RichHeader, ForkStatus, ReorgAlert, ValidatorSetInfo, MisbehaviorReport, FinalityStatus, LogRecord,
 	block_number_to_id
 };
 use Host;
 
+/// Number of records `parity_recentLogs` returns if no limit is given.
+pub const DEFAULT_RECENT_LOGS: usize = 100;
+/// Maximal number of accounts `parity_getAccountsInfoAt` reads at once.
+pub const MAX_ACCOUNTS_INFO: usize = 1000;
+
 /// Parity implementation.
 pub struct ParityClient<C, M, U> {
 	client: Arc<C>,
@@ -65,6 +75,7 @@ pub struct ParityClient<C, M, U> {
 	signer: Option<Arc<SignerService>>,
 	dapps_address: Option<Host>,
 	ws_address: Option<Host>,
+	node_filter: Option<Arc<NodeFilter>>,
 	eip86_transition: u64,
 }
 
@@ -85,6 +96,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 		signer: Option<Arc<SignerService>>,
 		dapps_address: Option<Host>,
 		ws_address: Option<Host>,
+		node_filter: Option<Arc<NodeFilter>>,
 	) -> Self {
 		let eip86_transition = client.eip86_transition();
 		ParityClient {
@@ -100,6 +112,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 			signer,
 			dapps_address,
 			ws_address,
+			node_filter,
 			eip86_transition,
 		}
 	}
@@ -107,7 +120,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 
 impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 	S: StateInfo + 'static,
-	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + 'static,
+	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + EngineInfo + 'static,
 	M: MinerService<State=S> + 'static,
 	U: UpdateService + 'static,
 {
@@ -183,7 +196,24 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 	}
 
 	fn dev_logs_levels(&self) -> Result<String> {
-		Ok(self.logger.levels().to_owned())
+		Ok(self.logger.levels())
+	}
+
+	fn get_logging(&self) -> Result<String> {
+		Ok(self.logger.levels())
+	}
+
+	fn recent_logs(&self, level: Option<String>, target: Option<String>, limit: Trailing<usize>) -> Result<Vec<LogRecord>> {
+		let level = match level {
+			Some(level) => Some(level.parse::<LogLevel>().map_err(|_| errors::invalid_params("level", level))?),
+			None => None,
+		};
+		let limit = match limit.unwrap_or_default() {
+			0 => DEFAULT_RECENT_LOGS,
+			limit => limit,
+		};
+
+		Ok(self.logger.recent(level, target.as_ref().map(String::as_str), limit).into_iter().map(Into::into).collect())
 	}
 
 	fn net_chain(&self) -> Result<String> {
@@ -212,6 +242,12 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		})
 	}
 
+	fn net_peer_permissions(&self) -> Result<Vec<PeerPermission>> {
+		Ok(self.node_filter.as_ref()
+			.map(|filter| filter.permissions().into_iter().map(Into::into).collect())
+			.unwrap_or_default())
+	}
+
 	fn net_port(&self) -> Result<u16> {
 		Ok(self.settings.network_port)
 	}
@@ -296,6 +332,30 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 			.map(|a| a.into_iter().map(Into::into).collect()))
 	}
 
+	fn accounts_info_at(&self, addresses: Vec<H160>, num: Trailing<BlockNumber>) -> Result<Vec<AccountStateInfo>> {
+		if addresses.len() > MAX_ACCOUNTS_INFO {
+			return Err(errors::invalid_params("addresses", format!("At most {} accounts can be queried at once.", MAX_ACCOUNTS_INFO)));
+		}
+
+		let state = match num.unwrap_or_default() {
+			BlockNumber::Pending => {
+				let info = self.client.chain_info();
+				self.miner.pending_state(info.best_block_number).ok_or(errors::state_pruned())?
+			},
+			num => self.client.state_at(block_number_to_id(num)).ok_or(errors::state_pruned())?,
+		};
+
+		addresses.into_iter().map(|address| {
+			let a: Address = address.clone().into();
+			Ok(AccountStateInfo {
+				address,
+				balance: state.balance(&a).map_err(|_| errors::state_corrupt())?.into(),
+				nonce: state.nonce(&a).map_err(|_| errors::state_corrupt())?.into(),
+				has_code: state.code_hash(&a).map_err(|_| errors::state_corrupt())? != KECCAK_EMPTY,
+			})
+		}).collect()
+	}
+
 	fn encrypt_message(&self, key: H512, phrase: Bytes) -> Result<Bytes> {
 		ecies::encrypt(&key.into(), &DEFAULT_MAC, &phrase.0)
 			.map_err(errors::encryption)
@@ -397,6 +457,14 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		})
 	}
 
+	fn fork_status(&self) -> Result<ForkStatus> {
+		Ok(self.sync.fork_status().into())
+	}
+
+	fn pending_reorg(&self) -> Result<Option<ReorgAlert>> {
+		Ok(self.client.pending_reorg().map(Into::into))
+	}
+
 	fn node_kind(&self) -> Result<::v1::types::NodeKind> {
 		use ::v1::types::{NodeKind, Availability, Capability};
 
@@ -420,6 +488,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 				BlockNumber::Num(num) => BlockId::Number(num),
 				BlockNumber::Earliest => BlockId::Earliest,
 				BlockNumber::Latest => BlockId::Latest,
+				BlockNumber::Finalized => BlockId::Finalized,
 				BlockNumber::Pending => unreachable!(), // Already covered
 			};
 
@@ -461,6 +530,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 				BlockNumber::Num(num) => BlockId::Number(num),
 				BlockNumber::Earliest => BlockId::Earliest,
 				BlockNumber::Latest => BlockId::Latest,
+				BlockNumber::Finalized => BlockId::Finalized,
 				BlockNumber::Pending => unreachable!(), // Already covered
 			};
 
@@ -475,8 +545,151 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 				.map_err(errors::call)
 	}
 
+	fn estimate_gas_detailed(&self, meta: Self::Metadata, request: CallRequest, num: Trailing<BlockNumber>) -> Result<GasEstimate> {
+		let signed = fake_sign::sign_call(request.into(), meta.is_dapp())?;
+		let num = num.unwrap_or_default();
+
+		let (mut state, header) = if num == BlockNumber::Pending {
+			let info = self.client.chain_info();
+			let state = self.miner.pending_state(info.best_block_number).ok_or(errors::state_pruned())?;
+			let header = self.miner.pending_block_header(info.best_block_number).ok_or(errors::state_pruned())?;
+
+			(state, header)
+		} else {
+			let id = block_number_to_id(num);
+			let state = self.client.state_at(id).ok_or(errors::state_pruned())?;
+			let header = self.client.block_header(id).ok_or(errors::state_pruned())?.decode().map_err(errors::decode)?;
+
+			(state, header)
+		};
+
+		let gas = self.client.estimate_gas(&signed, &state, &header).map_err(errors::call)?;
+		let schedule = self.client.engine().schedule(header.number());
+		let intrinsic_gas = EthU256::from(signed.gas_required(&schedule));
+		let mut warnings = Vec::new();
+
+		let code_deposit_gas = match signed.action {
+			Action::Create => {
+				// run the creation with the estimated gas to learn the size of the deployed code
+				let mut tx = signed.as_unsigned().clone();
+				tx.gas = gas;
+				let tx = tx.fake_sign(signed.sender());
+				let code_size = self.client.call(&tx, Default::default(), &mut state, &header).map_err(errors::call)?.output.len();
+				if code_size > schedule.create_data_limit {
+					warnings.push(format!("Contract code of {} bytes exceeds the limit of {} bytes.", code_size, schedule.create_data_limit));
+				}
+				EthU256::from(code_size * schedule.create_data_gas)
+			},
+			Action::Call(_) => EthU256::zero(),
+		};
+
+		let gas_limit = *header.gas_limit();
+		if intrinsic_gas > gas_limit {
+			warnings.push(format!("Transaction data alone costs {} gas, more than the block gas limit of {}.", intrinsic_gas, gas_limit));
+		} else if gas > gas_limit {
+			warnings.push(format!("Estimated gas of {} exceeds the block gas limit of {}.", gas, gas_limit));
+		} else if gas > gas_limit / 2 {
+			warnings.push(format!("Estimated gas of {} is more than half of the block gas limit of {}, the transaction may take long to be included.", gas, gas_limit));
+		}
+
+		Ok(GasEstimate {
+			gas: gas.into(),
+			intrinsic_gas: intrinsic_gas.into(),
+			execution_gas: gas.saturating_sub(intrinsic_gas).saturating_sub(code_deposit_gas).into(),
+			code_deposit_gas: code_deposit_gas.into(),
+			warnings,
+		})
+	}
+
+	fn call_bundle(&self, transactions: Vec<Bytes>, num: Trailing<BlockNumber>) -> Result<Vec<CallBundleResult>> {
+		let transactions = transactions
+			.into_iter()
+			.map(|raw| {
+				let tx = Rlp::new(&raw.into_vec()).as_val().map_err(|e| errors::invalid_params("Transaction is not valid RLP", e))?;
+				let signed = SignedTransaction::new(tx).map_err(errors::transaction)?;
+				Ok((signed, Default::default()))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		let num = num.unwrap_or_default();
+
+		let (mut state, header) = if num == BlockNumber::Pending {
+			let info = self.client.chain_info();
+			let state = self.miner.pending_state(info.best_block_number).ok_or(errors::state_pruned())?;
+			let header = self.miner.pending_block_header(info.best_block_number).ok_or(errors::state_pruned())?;
+
+			(state, header)
+		} else {
+			let id = match num {
+				BlockNumber::Num(num) => BlockId::Number(num),
+				BlockNumber::Earliest => BlockId::Earliest,
+				BlockNumber::Latest => BlockId::Latest,
+				BlockNumber::Finalized => BlockId::Finalized,
+				BlockNumber::Pending => unreachable!(), // Already covered
+			};
+
+			let state = self.client.state_at(id).ok_or(errors::state_pruned())?;
+			let header = self.client.block_header(id).ok_or(errors::state_pruned())?.decode().map_err(errors::decode)?;
+
+			(state, header)
+		};
+
+		let hashes = transactions.iter().map(|&(ref tx, _)| tx.hash()).collect::<Vec<_>>();
+		self.client.call_many(&transactions, &mut state, &header)
+				.map(|res| hashes.into_iter().zip(res).map(CallBundleResult::from).collect())
+				.map_err(errors::call)
+	}
+
+	fn preview_transaction(&self, raw: Bytes) -> Result<TransactionPreview> {
+		let tx = Rlp::new(&raw.into_vec()).as_val().map_err(|e| errors::invalid_params("Transaction is not valid RLP", e))?;
+		let signed = SignedTransaction::new(tx).map_err(errors::transaction)?;
+		signed.verify_basic(true, self.client.signing_chain_id(), false).map_err(errors::transaction)?;
+
+		let state = self.client.latest_state();
+		let header = self.client.best_block_header();
+
+		let transaction = Transaction::from_signed(signed.clone(), header.number(), self.eip86_transition);
+		self.client.preview(&signed, &state, &header)
+			.map(|executed| (transaction, executed).into())
+			.map_err(errors::call)
+	}
+
 	fn node_health(&self) -> BoxFuture<Health> {
 		Box::new(self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err)))
 	}
+
+	fn time_drift(&self) -> BoxFuture<i64> {
+		Box::new(self.health.time_drift()
+			.map_err(|err| errors::internal("Unable to measure time drift.", err)))
+	}
+
+	fn validator_set(&self, number: Trailing<BlockNumber>) -> Result<Option<ValidatorSetInfo>> {
+		let id = match number.unwrap_or_default() {
+			// validators of the pending block are determined by the latest one
+			BlockNumber::Pending => BlockId::Latest,
+			number => block_number_to_id(number),
+		};
+		let hash = match self.client.block_hash(id) {
+			Some(hash) => hash,
+			None => return Ok(None),
+		};
+
+		match self.client.engine().validator_set_info(&hash) {
+			Some(info) => info
+				.map(|info| Some(info.into()))
+				.map_err(|e| errors::internal("Failed to query the validator set.", e)),
+			None => Err(errors::unsupported("The chain is not using a validator set based consensus engine.", None)),
+		}
+	}
+
+	fn validator_misbehavior_reports(&self) -> Result<Vec<MisbehaviorReport>> {
+		self.client.engine().misbehavior_reports()
+			.map(|reports| reports.into_iter().map(Into::into).collect())
+			.ok_or_else(|| errors::unsupported("The chain is not using a validator set based consensus engine.", None))
+	}
+
+	fn finality_status(&self) -> Result<FinalityStatus> {
+		Ok(self.client.finality_status().into())
+	}
 }
@@ -22,10 +22,11 @@ use ethereum_types::Address;
 use ethkey::{Brain, Generator, Secret};
 use ethstore::KeyFile;
 use ethcore::account_provider::AccountProvider;
+use ethjson::misc::AccountMeta;
 use jsonrpc_core::Result;
 use v1::helpers::errors;
 use v1::traits::ParityAccounts;
-use v1::types::{H160 as RpcH160, H256 as RpcH256, H520 as RpcH520, DappId, Derive, DeriveHierarchical, DeriveHash, ExtAccountInfo};
+use v1::types::{H160 as RpcH160, H256 as RpcH256, H520 as RpcH520, DappId, Derive, DeriveHierarchical, DeriveHash, ExtAccountInfo, WatchOnlyAccount};
 use ethkey::Password;
 
 /// Account management (personal) rpc implementation.
@@ -42,23 +43,38 @@ impl ParityAccountsClient {
 	}
 }
 
+impl ParityAccountsClient {
+	fn with_notes<I>(&self, info: I) -> Vec<(RpcH160, ExtAccountInfo)> where
+		I: Iterator<Item = (Address, AccountMeta)>,
+	{
+		let notes = self.accounts.notes_info();
+		info.map(|(address, v)| {
+			let n = notes.get(&address).cloned().unwrap_or_default();
+			(address.into(), ExtAccountInfo {
+				name: v.name,
+				meta: v.meta,
+				uuid: v.uuid.map(|uuid| uuid.to_string()),
+				tags: n.tags,
+				notes: n.notes,
+			})
+		}).collect()
+	}
+}
+
 impl ParityAccounts for ParityAccountsClient {
 	fn all_accounts_info(&self) -> Result<BTreeMap<RpcH160, ExtAccountInfo>> {
 		let info = self.accounts.accounts_info().map_err(|e| errors::account("Could not fetch account info.", e))?;
 		let other = self.accounts.addresses_info();
+		let watch_only = self.accounts.watch_only_info();
 
 		let account_iter = info
 			.into_iter()
 			.chain(other.into_iter())
-			.map(|(address, v)| (address.into(), ExtAccountInfo {
-				name: v.name,
-				meta: v.meta,
-				uuid: v.uuid.map(|uuid| uuid.to_string())
-			}));
+			.chain(watch_only.into_iter());
 
 		let mut accounts: BTreeMap<RpcH160, ExtAccountInfo> = BTreeMap::new();
 
-		for (address, account) in account_iter {
+		for (address, account) in self.with_notes(account_iter) {
 			match accounts.entry(address) {
 				// Insert only if occupied entry isn't already an account with UUID
 				Entry::Occupied(ref mut occupied) if occupied.get().uuid.is_none() => {
@@ -74,6 +90,10 @@ impl ParityAccounts for ParityAccountsClient {
 		Ok(accounts)
 	}
 
+	fn address_book(&self) -> Result<BTreeMap<RpcH160, ExtAccountInfo>> {
+		Ok(self.with_notes(self.accounts.addresses_info().into_iter()).collect())
+	}
+
 	fn new_account_from_phrase(&self, phrase: String, pass: Password) -> Result<RpcH160> {
 		let brain = Brain::new(phrase).generate().unwrap();
 		self.accounts.insert_account(brain.secret().clone(), &pass)
@@ -127,6 +147,24 @@ impl ParityAccounts for ParityAccountsClient {
 		Ok(true)
 	}
 
+	fn add_watch_only(&self, addr: RpcH160, name: String) -> Result<bool> {
+		self.accounts.add_watch_only(addr.into(), name)
+			.map(|_| true)
+			.map_err(|e| errors::account("Could not add watch-only address.", e))
+	}
+
+	fn remove_watch_only(&self, addr: RpcH160) -> Result<bool> {
+		Ok(self.accounts.remove_watch_only(addr.into()))
+	}
+
+	fn watch_only_accounts(&self) -> Result<BTreeMap<RpcH160, WatchOnlyAccount>> {
+		Ok(self.accounts.watch_only_info()
+			.into_iter()
+			.map(|(a, v)| (a.into(), WatchOnlyAccount { name: v.name, meta: v.meta }))
+			.collect()
+		)
+	}
+
 	fn set_account_name(&self, addr: RpcH160, name: String) -> Result<bool> {
 		let addr: Address = addr.into();
 
@@ -135,6 +173,16 @@ impl ParityAccounts for ParityAccountsClient {
 		Ok(true)
 	}
 
+	fn set_account_tags(&self, addr: RpcH160, tags: Vec<String>) -> Result<bool> {
+		self.accounts.set_account_tags(addr.into(), tags);
+		Ok(true)
+	}
+
+	fn set_account_notes(&self, addr: RpcH160, notes: String) -> Result<bool> {
+		self.accounts.set_account_notes(addr.into(), notes);
+		Ok(true)
+	}
+
 	fn set_account_meta(&self, addr: RpcH160, meta: String) -> Result<bool> {
 		let addr: Address = addr.into();
 
@@ -0,0 +1,47 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parity nonces rpc implementation.
+
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_core::futures::Future;
+use v1::helpers::dispatch::Dispatcher;
+use v1::traits::ParityNonces;
+use v1::types::{H160, U256};
+
+/// Nonces rpc implementation reserving nonces through the given dispatcher.
+pub struct ParityNoncesClient<D> {
+	dispatcher: D,
+}
+
+impl<D: Dispatcher + 'static> ParityNoncesClient<D> {
+	/// Creates new `ParityNoncesClient`.
+	pub fn new(dispatcher: D) -> Self {
+		ParityNoncesClient {
+			dispatcher,
+		}
+	}
+}
+
+impl<D: Dispatcher + 'static> ParityNonces for ParityNoncesClient<D> {
+	fn reserve_nonce(&self, address: H160) -> BoxFuture<U256> {
+		Box::new(self.dispatcher.reserve_nonce(address.into()).map(Into::into))
+	}
+
+	fn release_nonce(&self, address: H160, nonce: U256, used: bool) -> Result<bool> {
+		Ok(self.dispatcher.release_nonce(address.into(), nonce.into(), used))
+	}
+}
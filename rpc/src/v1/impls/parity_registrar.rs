@@ -0,0 +1,86 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parity registrar rpc implementation.
+
+use std::sync::Arc;
+
+use registrar::{Registrar, RegistrarClient, RegistrarTransaction, Asynchronous};
+
+use jsonrpc_core::BoxFuture;
+use jsonrpc_core::futures::Future;
+use v1::helpers::errors;
+use v1::metadata::Metadata;
+use v1::traits::{EthSigning, ParityRegistrar};
+use v1::types::{H160, H256, U256, TransactionRequest};
+
+/// Registrar rpc implementation submitting transactions through the given signing client.
+pub struct ParityRegistrarClient<S> {
+	signing: Arc<S>,
+	registrar: Registrar,
+}
+
+impl<S> ParityRegistrarClient<S> {
+	/// Creates new `ParityRegistrarClient`.
+	pub fn new(signing: S, client: Arc<RegistrarClient<Call=Asynchronous>>) -> Self {
+		ParityRegistrarClient {
+			signing: Arc::new(signing),
+			registrar: Registrar::new(client),
+		}
+	}
+}
+
+impl<S: EthSigning<Metadata=Metadata> + 'static> ParityRegistrarClient<S> {
+	fn send(&self, meta: Metadata, from: H160, transaction: Result<RegistrarTransaction, String>) -> BoxFuture<H256> {
+		let transaction = try_bf!(transaction.map_err(errors::registrar));
+		self.signing.send_transaction(meta, request(from, transaction, None))
+	}
+}
+
+fn request(from: H160, transaction: RegistrarTransaction, value: Option<U256>) -> TransactionRequest {
+	TransactionRequest {
+		from: Some(from),
+		to: Some(transaction.to.into()),
+		value,
+		data: Some(transaction.data.into()),
+		..Default::default()
+	}
+}
+
+impl<S: EthSigning<Metadata=Metadata> + 'static> ParityRegistrar for ParityRegistrarClient<S> {
+	type Metadata = Metadata;
+
+	fn reserve(&self, meta: Self::Metadata, from: H160, name: String) -> BoxFuture<H256> {
+		let transaction = try_bf!(self.registrar.reserve(&name).map_err(errors::registrar));
+		let signing = self.signing.clone();
+
+		Box::new(self.registrar.fee()
+			.map_err(errors::registrar)
+			.and_then(move |fee| signing.send_transaction(meta, request(from, transaction, Some(fee.into())))))
+	}
+
+	fn set_address(&self, meta: Self::Metadata, from: H160, name: String, address: H160) -> BoxFuture<H256> {
+		self.send(meta, from, self.registrar.set_address(&name, address.into()))
+	}
+
+	fn propose_reverse(&self, meta: Self::Metadata, from: H160, name: String, address: H160) -> BoxFuture<H256> {
+		self.send(meta, from, self.registrar.propose_reverse(&name, address.into()))
+	}
+
+	fn confirm_reverse(&self, meta: Self::Metadata, from: H160, name: String) -> BoxFuture<H256> {
+		self.send(meta, from, self.registrar.confirm_reverse(&name))
+	}
+}
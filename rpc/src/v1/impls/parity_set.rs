@@ -15,24 +15,42 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 /// Parity-specific rpc interface for operations altering the settings.
+use std::cmp;
 use std::io;
 use std::sync::Arc;
 use std::time::Duration;
+use std::collections::HashMap;
 
-use ethcore::client::{BlockChainClient, Mode};
+use ethereum_types::Address;
+use ethcore::client::{BlockChainClient, ProvingBlockChainClient, StateClient, Call, Mode};
+use ethcore::ids::BlockId;
 use ethcore::miner::MinerService;
+use ethcore::state::StateInfo;
+use ethcore_logger::RotatingLogger;
 use sync::ManageNetwork;
 use fetch::{self, Fetch};
 use futures_cpupool::CpuPool;
 use hash::keccak_buffer;
+use parking_lot::Mutex;
+use transaction::SignedTransaction;
 use updater::{Service as UpdateService};
 
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::Future;
+use jsonrpc_macros::Trailing;
 use v1::helpers::dapps::DappsService;
-use v1::helpers::errors;
+use v1::helpers::drain::{self, RpcDrain};
+use v1::helpers::{errors, tokens};
 use v1::traits::ParitySet;
-use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
+use v1::types::{
+	BlockNumber, Bytes, H160, H256, U256, ReleaseInfo, PendingUpdate, Transaction, LocalDapp, TokenList, TokenBalance,
+	block_number_to_id,
+};
+
+/// Maximal number of tokens `parity_tokenBalances` queries.
+pub const MAX_TOKENS: usize = 100;
+/// Maximal number of tokens whose metadata is cached.
+const TOKEN_METADATA_CACHE_SIZE: usize = 10_000;
 
 /// Parity-specific rpc interface for operations altering the settings.
 pub struct ParitySetClient<C, M, U, F = fetch::Client> {
@@ -40,9 +58,12 @@ pub struct ParitySetClient<C, M, U, F = fetch::Client> {
 	miner: Arc<M>,
 	updater: Arc<U>,
 	net: Arc<ManageNetwork>,
+	logger: Arc<RotatingLogger>,
 	dapps: Option<Arc<DappsService>>,
 	fetch: F,
 	pool: CpuPool,
+	drain: Arc<RpcDrain>,
+	token_metadata: Mutex<HashMap<(Address, H256), tokens::TokenMetadata>>,
 	eip86_transition: u64,
 }
 
@@ -55,26 +76,32 @@ impl<C, M, U, F> ParitySetClient<C, M, U, F>
 		miner: &Arc<M>,
 		updater: &Arc<U>,
 		net: &Arc<ManageNetwork>,
+		logger: Arc<RotatingLogger>,
 		dapps: Option<Arc<DappsService>>,
 		fetch: F,
 		pool: CpuPool,
+		drain: Arc<RpcDrain>,
 	) -> Self {
 		ParitySetClient {
 			client: client.clone(),
 			miner: miner.clone(),
 			updater: updater.clone(),
 			net: net.clone(),
+			logger: logger,
 			dapps: dapps,
 			fetch: fetch,
 			pool: pool,
+			drain: drain,
+			token_metadata: Default::default(),
 			eip86_transition: client.eip86_transition(),
 		}
 	}
 }
 
-impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
-	C: BlockChainClient + 'static,
-	M: MinerService + 'static,
+impl<C, M, U, F, S> ParitySet for ParitySetClient<C, M, U, F> where
+	S: StateInfo + Clone + 'static,
+	C: BlockChainClient + ProvingBlockChainClient + StateClient<State=S> + Call<State=S> + 'static,
+	M: MinerService<State=S> + 'static,
 	U: UpdateService + 'static,
 	F: Fetch + 'static,
 {
@@ -202,6 +229,18 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 		Ok(self.updater.execute_upgrade())
 	}
 
+	fn pending_update(&self) -> Result<Option<PendingUpdate>> {
+		Ok(self.updater.pending_update().map(Into::into))
+	}
+
+	fn defer_update(&self, blocks: u64) -> Result<bool> {
+		Ok(self.updater.defer_update(blocks))
+	}
+
+	fn rollback_update(&self) -> Result<bool> {
+		Ok(self.updater.rollback())
+	}
+
 	fn remove_transaction(&self, hash: H256) -> Result<Option<Transaction>> {
 		let block_number = self.client.chain_info().best_block_number;
 		let hash = hash.into();
@@ -210,4 +249,128 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 		   .map(|t| Transaction::from_pending(t.pending().clone(), block_number + 1, self.eip86_transition))
 		)
 	}
+
+	fn accept_reorg(&self) -> Result<bool> {
+		Ok(self.client.accept_reorg())
+	}
+
+	fn set_logging_targets(&self, targets: String) -> Result<bool> {
+		self.logger.set_levels(&targets).map_err(|e| errors::invalid_params("targets", e))?;
+		Ok(true)
+	}
+
+	fn drain_rpc(&self, seconds: u64) -> Result<bool> {
+		drain::start_draining(&self.drain, seconds)
+	}
+
+	fn block_witness(&self, number: BlockNumber) -> Result<Vec<Bytes>> {
+		let id = match number {
+			BlockNumber::Pending => return Err(errors::unsupported("Witnesses are only available for imported blocks.", None)),
+			number => block_number_to_id(number),
+		};
+
+		self.client.block_witness(id)
+			.map(|witness| witness.into_iter().map(Bytes::new).collect())
+			.map_err(|e| errors::internal("Failed to re-execute the block.", e))
+	}
+
+	fn verify_block_witness(&self, block: Bytes, witness: Vec<Bytes>) -> Result<bool> {
+		let witness: Vec<_> = witness.into_iter().map(Bytes::into_vec).collect();
+
+		self.client.verify_block_witness(block.into_vec(), &witness)
+			.map_err(|e| errors::internal("Failed to verify the block with the witness.", e))
+	}
+
+	fn token_balances(&self, owner: H160, list: TokenList, num: Trailing<BlockNumber>) -> Result<Vec<TokenBalance>> {
+		let num = num.unwrap_or_default();
+
+		let (state, header) = if num == BlockNumber::Pending {
+			let info = self.client.chain_info();
+			let state = self.miner.pending_state(info.best_block_number).ok_or(errors::state_pruned())?;
+			let header = self.miner.pending_block_header(info.best_block_number).ok_or(errors::state_pruned())?;
+
+			(state, header)
+		} else {
+			let id = block_number_to_id(num);
+			let state = self.client.state_at(id).ok_or(errors::state_pruned())?;
+			let header = self.client.block_header(id).ok_or(errors::state_pruned())?.decode().map_err(errors::decode)?;
+
+			(state, header)
+		};
+
+		// every call runs on its own copy of the state, so that no token affects what the others return
+		let call = |transaction: SignedTransaction| {
+			let mut state = state.clone();
+			self.client.call(&transaction, Default::default(), &mut state, &header)
+				.map(|executed| match executed.exception {
+					None => Some(executed.output),
+					Some(_) => None,
+				})
+		};
+
+		let tokens: Vec<Address> = match list {
+			TokenList::Tokens(tokens) => tokens.into_iter().map(Into::into).collect(),
+			TokenList::Registry => {
+				let id = match num {
+					BlockNumber::Pending => BlockId::Latest,
+					num => block_number_to_id(num),
+				};
+				let registry = self.client.registry_address(tokens::TOKEN_REGISTRY.into(), id)
+					.ok_or_else(|| errors::unsupported("The token registry is not available on this chain.", None))?;
+				let count = call(tokens::token_count(registry))
+					.map_err(errors::call)?
+					.and_then(|output| tokens::decode_uint(&output))
+					.unwrap_or_default();
+				let count = cmp::min(count, (MAX_TOKENS as u64).into()).low_u64();
+
+				(0..count)
+					.filter_map(|index| call(tokens::token(registry, index)).ok().and_then(|output| output))
+					.filter_map(|output| tokens::decode_token_address(&output))
+					.collect()
+			},
+		};
+		if tokens.len() > MAX_TOKENS {
+			return Err(errors::invalid_params("tokens", format!("At most {} tokens can be queried at once.", MAX_TOKENS)));
+		}
+
+		let owner: Address = owner.into();
+		tokens.into_iter().map(|token| {
+			// a contract created again at the same address may have other metadata
+			let key = (token, state.code_hash(&token).map_err(|_| errors::state_corrupt())?);
+			let cached = self.token_metadata.lock().get(&key).cloned();
+			let metadata = match cached {
+				Some(metadata) => metadata,
+				None => {
+					let symbol = call(tokens::symbol(token));
+					let decimals = call(tokens::decimals(token));
+					let metadata = tokens::TokenMetadata {
+						symbol: symbol.as_ref().ok().and_then(Option::as_ref).and_then(|output| tokens::decode_string(output)),
+						decimals: decimals.as_ref().ok().and_then(Option::as_ref).and_then(|output| tokens::decode_decimals(output)),
+					};
+
+					// failed calls are tried again with the next query
+					if let (Ok(Some(_)), Ok(Some(_))) = (symbol, decimals) {
+						let mut cache = self.token_metadata.lock();
+						if cache.len() >= TOKEN_METADATA_CACHE_SIZE {
+							cache.clear();
+						}
+						cache.insert(key, metadata.clone());
+					}
+					metadata
+				},
+			};
+
+			let balance = call(tokens::balance_of(token, &owner))
+				.ok()
+				.and_then(|output| output)
+				.and_then(|output| tokens::decode_uint(&output));
+
+			Ok(TokenBalance {
+				token: token.into(),
+				balance: balance.map(Into::into),
+				symbol: metadata.symbol,
+				decimals: metadata.decimals,
+			})
+		}).collect()
+	}
 }
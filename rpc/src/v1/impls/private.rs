@@ -18,15 +18,13 @@
 
 use std::sync::Arc;
 
-use rlp::Rlp;
-
 use ethcore_private_tx::Provider as PrivateTransactionManager;
 use ethereum_types::Address;
 use transaction::SignedTransaction;
 
 use jsonrpc_core::{Error};
 use v1::types::{Bytes, PrivateTransactionReceipt, H160, H256, TransactionRequest, U256,
-	BlockNumber, PrivateTransactionReceiptAndTransaction, CallRequest, block_number_to_id};
+	BlockNumber, PrivateTransactionReceiptAndTransaction, PrivateTransactionStatus, CallRequest, block_number_to_id};
 use v1::traits::Private;
 use v1::metadata::Metadata;
 use v1::helpers::{errors, fake_sign};
@@ -56,18 +54,14 @@ impl Private for PrivateClient {
 	type Metadata = Metadata;
 
 	fn send_transaction(&self, request: Bytes) -> Result<PrivateTransactionReceipt, Error> {
-		let signed_transaction = Rlp::new(&request.into_vec()).as_val()
-			.map_err(errors::rlp)
-			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))?;
+		let signed_transaction = SignedTransaction::from_rlp_bytes(&request.into_vec()).map_err(errors::transaction)?;
 		let client = self.unwrap_manager()?;
 		let receipt = client.create_private_transaction(signed_transaction).map_err(|e| errors::private_message(e))?;
 		Ok(receipt.into())
 	}
 
 	fn compose_deployment_transaction(&self, block_number: BlockNumber, request: Bytes, validators: Vec<H160>, gas_price: U256) -> Result<PrivateTransactionReceiptAndTransaction, Error> {
-		let signed_transaction = Rlp::new(&request.into_vec()).as_val()
-			.map_err(errors::rlp)
-			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))?;
+		let signed_transaction = SignedTransaction::from_rlp_bytes(&request.into_vec()).map_err(errors::transaction)?;
 		let client = self.unwrap_manager()?;
 
 		let addresses: Vec<Address> = validators.into_iter().map(Into::into).collect();
@@ -100,6 +94,39 @@ impl Private for PrivateClient {
 		})
 	}
 
+	fn rotate_validators(&self, block_number: BlockNumber, contract_address: H160, from: H160, validators: Vec<H160>, gas_price: U256) -> Result<PrivateTransactionReceiptAndTransaction, Error> {
+		let client = self.unwrap_manager()?;
+
+		let addresses: Vec<Address> = validators.into_iter().map(Into::into).collect();
+		let id = match block_number {
+			BlockNumber::Pending => return Err(errors::private_message_block_id_not_supported()),
+			num => block_number_to_id(num)
+		};
+
+		let (transaction, contract_address) = client.public_rotation_transaction(id, &contract_address.into(), &from.clone().into(), addresses.as_slice(), gas_price.into())
+			.map_err(|e| errors::private_message(e))?;
+		let tx_hash = transaction.hash(None);
+		let request = TransactionRequest {
+			from: Some(from),
+			to: None,
+			nonce: Some(transaction.nonce.into()),
+			gas_price: Some(transaction.gas_price.into()),
+			gas: Some(transaction.gas.into()),
+			value: Some(transaction.value.into()),
+			data: Some(transaction.data.into()),
+			condition: None,
+		};
+
+		Ok(PrivateTransactionReceiptAndTransaction {
+			transaction: request,
+			receipt: PrivateTransactionReceipt {
+				transaction_hash: tx_hash.into(),
+				contract_address: Some(contract_address.into()),
+				status_code: 0,
+			}
+		})
+	}
+
 	fn private_call(&self, meta: Self::Metadata, block_number: BlockNumber, request: CallRequest) -> Result<Bytes, Error> {
 		let id = match block_number {
 			BlockNumber::Pending => return Err(errors::private_message_block_id_not_supported()),
@@ -113,6 +140,11 @@ impl Private for PrivateClient {
 		Ok(executed_result.output.into())
 	}
 
+	fn transaction_status(&self, transaction_hash: H256) -> Result<Option<PrivateTransactionStatus>, Error> {
+		let client = self.unwrap_manager()?;
+		Ok(client.private_transaction_status(&transaction_hash.into()).map(Into::into))
+	}
+
 	fn private_contract_key(&self, contract_address: H160) -> Result<H256, Error> {
 		let client = self.unwrap_manager()?;
 		let key = client.contract_key_id(&contract_address.into()).map_err(|e| errors::private_message(e))?;
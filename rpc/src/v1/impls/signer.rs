@@ -22,7 +22,6 @@ use ethcore::account_provider::AccountProvider;
 use ethkey;
 use parity_reactor::Remote;
 use parking_lot::Mutex;
-use rlp::Rlp;
 use transaction::{SignedTransaction, PendingTransaction};
 
 use jsonrpc_core::{Result, BoxFuture, Error};
@@ -34,7 +33,7 @@ use v1::helpers::dispatch::{self, Dispatcher, WithToken, eth_data_hash};
 use v1::helpers::{errors, SignerService, SigningQueue, ConfirmationPayload, FilledTransactionRequest, Subscribers};
 use v1::metadata::Metadata;
 use v1::traits::Signer;
-use v1::types::{TransactionModification, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken, U256, Bytes};
+use v1::types::{TransactionModification, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken, U256, Bytes, AuthorizationToken};
 
 /// Transactions confirmation (personal) rpc implementation.
 pub struct SignerClient<D: Dispatcher> {
@@ -57,7 +56,7 @@ impl<D: Dispatcher + 'static> SignerClient<D> {
 		let s = Arc::downgrade(signer);
 		signer.queue().on_event(move |_event| {
 			if let (Some(s), Some(subs)) = (s.upgrade(), subs.upgrade()) {
-				let requests = s.requests().into_iter().map(Into::into).collect::<Vec<ConfirmationRequest>>();
+				let requests = s.requests_with_details();
 				for subscription in subs.lock().values() {
 					let subscription: &Sink<_> = subscription;
 					remote.spawn(subscription
@@ -123,8 +122,7 @@ impl<D: Dispatcher + 'static> SignerClient<D> {
 	fn verify_transaction<F>(bytes: Bytes, request: FilledTransactionRequest, process: F) -> Result<ConfirmationResponse> where
 		F: FnOnce(PendingTransaction) -> Result<ConfirmationResponse>,
 	{
-		let signed_transaction = Rlp::new(&bytes.0).as_val().map_err(errors::rlp)?;
-		let signed_transaction = SignedTransaction::new(signed_transaction).map_err(|e| errors::invalid_params("Invalid signature.", e))?;
+		let signed_transaction = SignedTransaction::from_rlp_bytes(&bytes.0).map_err(errors::transaction)?;
 		let sender = signed_transaction.sender();
 
 		// Verification
@@ -156,11 +154,7 @@ impl<D: Dispatcher + 'static> Signer for SignerClient<D> {
 	type Metadata = Metadata;
 
 	fn requests_to_confirm(&self) -> Result<Vec<ConfirmationRequest>> {
-		Ok(self.signer.requests()
-			.into_iter()
-			.map(Into::into)
-			.collect()
-		)
+		Ok(self.signer.requests_with_details())
 	}
 
 	// TODO [ToDr] TransactionModification is redundant for some calls
@@ -234,11 +228,31 @@ impl<D: Dispatcher + 'static> Signer for SignerClient<D> {
 		Ok(res.is_some())
 	}
 
-	fn generate_token(&self) -> Result<String> {
+	fn generate_token(&self, meta: Self::Metadata) -> Result<String> {
+		ensure_unrestricted(&meta)?;
 		self.signer.generate_token()
 			.map_err(|e| errors::token(e))
 	}
 
+	fn generate_scoped_token(&self, meta: Self::Metadata, name: Option<String>, scope: Option<Vec<String>>, expiry: Option<u64>) -> Result<String> {
+		ensure_unrestricted(&meta)?;
+		self.signer.generate_scoped_token(name, scope, expiry)
+			.map_err(|e| errors::token(e))
+	}
+
+	fn authorization_tokens(&self, meta: Self::Metadata) -> Result<Vec<AuthorizationToken>> {
+		ensure_unrestricted(&meta)?;
+		self.signer.tokens()
+			.map(|tokens| tokens.into_iter().map(Into::into).collect())
+			.map_err(|e| errors::token(e))
+	}
+
+	fn revoke_token(&self, meta: Self::Metadata, id: String) -> Result<bool> {
+		ensure_unrestricted(&meta)?;
+		self.signer.revoke_token(&id)
+			.map_err(|e| errors::token(e))
+	}
+
 	fn generate_web_proxy_token(&self, domain: String) -> Result<String> {
 		Ok(self.signer.generate_web_proxy_access_token(domain.into()))
 	}
@@ -252,3 +266,11 @@ impl<D: Dispatcher + 'static> Signer for SignerClient<D> {
 		Ok(res)
 	}
 }
+
+/// Token management is only available to sessions authorized with an unrestricted token.
+fn ensure_unrestricted(meta: &Metadata) -> Result<()> {
+	match meta.authorization {
+		Some(ref authorization) if authorization.scope.is_some() => Err(errors::token_scope()),
+		_ => Ok(()),
+	}
+}
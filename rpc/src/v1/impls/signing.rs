@@ -16,9 +16,10 @@
 
 //! Signing RPC implementation.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use transient_hashmap::TransientHashMap;
-use ethereum_types::U256;
+use ethereum_types::{Address, U256};
 use parking_lot::Mutex;
 
 use ethcore::account_provider::AccountProvider;
@@ -108,7 +109,7 @@ impl<D: Dispatcher + 'static> SigningQueueClient<D> {
 		}
 	}
 
-	fn dispatch(&self, payload: RpcConfirmationPayload, default_account: DefaultAccount, origin: Origin) -> BoxFuture<DispatchResult> {
+	fn dispatch(&self, payload: RpcConfirmationPayload, default_account: DefaultAccount, origin: Origin, names: BTreeMap<Address, String>) -> BoxFuture<DispatchResult> {
 		let accounts = self.accounts.clone();
 		let default_account = match default_account {
 			DefaultAccount::Provided(acc) => acc,
@@ -121,12 +122,27 @@ impl<D: Dispatcher + 'static> SigningQueueClient<D> {
 			.and_then(move |payload| {
 				let sender = payload.sender();
 				if accounts.is_unlocked(&sender) {
-					Either::A(dispatch::execute(dispatcher, accounts, payload, dispatch::SignWith::Nothing)
+					Either::A(Either::A(dispatch::execute(dispatcher, accounts, payload, dispatch::SignWith::Nothing)
 						.map(|v| v.into_value())
-						.map(DispatchResult::Value))
+						.map(DispatchResult::Value)))
+				} else if let Some(approval) = signer.auto_approve(&accounts, &payload, {
+					let (dispatcher, accounts, payload) = (dispatcher.clone(), accounts.clone(), payload.clone());
+					move |password| -> BoxFuture<_> {
+						Box::new(dispatch::execute(dispatcher, accounts, payload, dispatch::SignWith::Password(password))
+							.map(|v| v.into_value()))
+					}
+				}) {
+					Either::A(Either::B(approval.and_then(move |value| match value {
+						Some(value) => Either::A(future::ok(DispatchResult::Value(value))),
+						None => Either::B(future::done(
+							signer.add_named_request(payload, origin, names)
+								.map(|(id, future)| DispatchResult::Future(id, future))
+								.map_err(|_| errors::request_rejected_limit())
+						)),
+					})))
 				} else {
 					Either::B(future::done(
-						signer.add_request(payload, origin)
+						signer.add_named_request(payload, origin, names)
 							.map(|(id, future)| DispatchResult::Future(id, future))
 							.map_err(|_| errors::request_rejected_limit())
 					))
@@ -150,7 +166,8 @@ impl<D: Dispatcher + 'static> ParitySigning for SigningQueueClient<D> {
 		Box::new(self.dispatch(
 			RpcConfirmationPayload::EthSignMessage((address.clone(), data).into()),
 			DefaultAccount::Provided(address.into()),
-			meta.origin
+			meta.origin,
+			meta.resolved_names,
 		).map(move |result| match result {
 			DispatchResult::Value(v) => RpcEither::Or(v),
 			DispatchResult::Future(id, future) => {
@@ -164,7 +181,7 @@ impl<D: Dispatcher + 'static> ParitySigning for SigningQueueClient<D> {
 		let remote = self.remote.clone();
 		let confirmations = self.confirmations.clone();
 
-		Box::new(self.dispatch(RpcConfirmationPayload::SendTransaction(request), meta.dapp_id().into(), meta.origin)
+		Box::new(self.dispatch(RpcConfirmationPayload::SendTransaction(request), meta.dapp_id().into(), meta.origin, meta.resolved_names)
 			.map(|result| match result {
 				DispatchResult::Value(v) => RpcEither::Or(v),
 				DispatchResult::Future(id, future) => {
@@ -188,6 +205,7 @@ impl<D: Dispatcher + 'static> ParitySigning for SigningQueueClient<D> {
 			RpcConfirmationPayload::Decrypt((address.clone(), data).into()),
 			address.into(),
 			meta.origin,
+			meta.resolved_names,
 		);
 
 		// when dispatch is complete - wait for result and then
@@ -208,6 +226,7 @@ impl<D: Dispatcher + 'static> EthSigning for SigningQueueClient<D> {
 			RpcConfirmationPayload::EthSignMessage((address.clone(), data).into()),
 			address.into(),
 			meta.origin,
+			meta.resolved_names,
 		);
 
 		Box::new(res.flatten().and_then(move |response| {
@@ -223,6 +242,7 @@ impl<D: Dispatcher + 'static> EthSigning for SigningQueueClient<D> {
 			RpcConfirmationPayload::SendTransaction(request),
 			meta.dapp_id().into(),
 			meta.origin,
+			meta.resolved_names,
 		);
 
 		Box::new(res.flatten().and_then(move |response| {
@@ -238,6 +258,7 @@ impl<D: Dispatcher + 'static> EthSigning for SigningQueueClient<D> {
 			RpcConfirmationPayload::SignTransaction(request),
 			meta.dapp_id().into(),
 			meta.origin,
+			meta.resolved_names,
 		);
 
 		Box::new(res.flatten().and_then(move |response| {
@@ -27,7 +27,7 @@ use jsonrpc_macros::Trailing;
 use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::{errors, fake_sign};
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256, block_number_to_id};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256, block_number_to_id, StateDiffTarget, TransactionStateDiff};
 
 fn to_call_analytics(flags: TraceOptions) -> CallAnalytics {
 	CallAnalytics {
@@ -72,6 +72,36 @@ impl<C, S> Traces for TracesClient<C> where
 			.map(|traces| traces.into_iter().map(LocalizedTrace::from).collect()))
 	}
 
+	fn state_diff_at(&self, target: StateDiffTarget) -> Result<Option<Vec<TransactionStateDiff>>> {
+		match target {
+			StateDiffTarget::Block(BlockNumber::Pending) => Ok(None),
+			StateDiffTarget::Block(num) => Ok(self.client.block_state_diffs(block_number_to_id(num))
+				.map(|diffs| diffs.into_iter()
+					.enumerate()
+					.map(|(position, (hash, diff))| TransactionStateDiff {
+						transaction_hash: hash.into(),
+						transaction_position: position,
+						state_diff: diff.into(),
+					})
+					.collect()
+				)),
+			StateDiffTarget::Transaction(hash) => {
+				let id = TransactionId::Hash(hash.clone().into());
+				let position = match self.client.transaction(id.clone()) {
+					Some(transaction) => transaction.transaction_index,
+					None => return Ok(None),
+				};
+
+				Ok(self.client.transaction_state_diff(id)
+					.map(|diff| vec![TransactionStateDiff {
+						transaction_hash: hash,
+						transaction_position: position,
+						state_diff: diff.into(),
+					}]))
+			},
+		}
+	}
+
 	fn transaction_traces(&self, transaction_hash: H256) -> Result<Option<Vec<LocalizedTrace>>> {
 		Ok(self.client.transaction_traces(TransactionId::Hash(transaction_hash.into()))
 			.map(|traces| traces.into_iter().map(LocalizedTrace::from).collect()))
@@ -97,6 +127,7 @@ impl<C, S> Traces for TracesClient<C> where
 			BlockNumber::Num(num) => BlockId::Number(num),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
 
 			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
 		};
@@ -124,6 +155,7 @@ impl<C, S> Traces for TracesClient<C> where
 			BlockNumber::Num(num) => BlockId::Number(num),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
 
 			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
 		};
@@ -146,6 +178,7 @@ impl<C, S> Traces for TracesClient<C> where
 			BlockNumber::Num(num) => BlockId::Number(num),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
 
 			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
 		};
@@ -169,6 +202,7 @@ impl<C, S> Traces for TracesClient<C> where
 			BlockNumber::Num(num) => BlockId::Number(num),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
 
 			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
 		};
@@ -20,10 +20,15 @@ use std::fmt;
 use std::sync::Arc;
 use std::sync::atomic::{self, AtomicUsize};
 use std::time;
+use ethcore_logger::{with_request_id, request_id};
 use futures_cpupool as pool;
 use jsonrpc_core as rpc;
+use jsonrpc_core::futures::{Future, Poll};
 use order_stat;
 use parking_lot::RwLock;
+use rand;
+
+use v1::helpers::names::{NameResolver, ResolvedNames};
 
 pub use self::pool::CpuPool;
 
@@ -182,20 +187,121 @@ pub trait ActivityNotifier: Send + Sync + 'static {
 	fn active(&self);
 }
 
+/// Processing pool dedicated to a set of methods.
+#[derive(Clone)]
+pub struct MethodPool {
+	pool: CpuPool,
+	methods: Arc<Vec<String>>,
+}
+
+impl MethodPool {
+	/// Create new pool handling given methods.
+	/// A method ending with `*` matches all methods starting with the preceding prefix.
+	pub fn new(pool: CpuPool, methods: Vec<String>) -> Self {
+		MethodPool {
+			pool,
+			methods: Arc::new(methods),
+		}
+	}
+
+	/// Returns true if given method should be processed in this pool.
+	pub fn handles(&self, method: &str) -> bool {
+		self.methods.iter().any(|pattern| match pattern.ends_with('*') {
+			true => method.starts_with(&pattern[..pattern.len() - 1]),
+			false => method == pattern,
+		})
+	}
+}
+
+/// Lanes processing selected methods apart from the shared pool.
+#[derive(Default, Clone)]
+pub struct Lanes {
+	/// Reserved lane for health and status methods, served even when the node is saturated.
+	pub priority: Option<MethodPool>,
+	/// Lane for expensive methods, so they can't starve cheap calls.
+	pub heavy: Option<MethodPool>,
+}
+
+impl Lanes {
+	/// Returns the lane given method is processed in, if it has a dedicated one.
+	pub fn lane_for(&self, method: &str) -> Option<&MethodPool> {
+		self.priority.iter().chain(self.heavy.iter()).find(|lane| lane.handles(method))
+	}
+}
+
+/// Future polled with log lines tagged with the id of the request it serves.
+pub struct Traced<F> {
+	id: String,
+	inner: F,
+}
+
+impl<F: Future> Future for Traced<F> {
+	type Item = F::Item;
+	type Error = F::Error;
+
+	fn poll(&mut self) -> Poll<F::Item, F::Error> {
+		let Traced { ref id, ref mut inner } = *self;
+		with_request_id(id, || inner.poll())
+	}
+}
+
+/// Generates a new request id.
+pub fn new_request_id() -> String {
+	format!("{:016x}", rand::random::<u64>())
+}
+
 /// Stats-counting RPC middleware
+///
+/// Every request gets an id which tags all log lines produced while serving it.
+/// The id of the request being dispatched on the current thread is used, if any
+/// (e.g. the `X-Request-Id` of HTTP requests), a new one is generated otherwise.
 pub struct Middleware<T: ActivityNotifier = ClientNotifier> {
 	stats: Arc<RpcStats>,
 	notifier: T,
 	pool: Option<CpuPool>,
+	lanes: Lanes,
+	names: Option<Arc<NameResolver>>,
 }
 
 impl<T: ActivityNotifier> Middleware<T> {
 	/// Create new Middleware with stats counter and activity notifier.
-	pub fn new(stats: Arc<RpcStats>, notifier: T, pool: Option<CpuPool>) -> Self {
+	pub fn new(stats: Arc<RpcStats>, notifier: T, pool: Option<CpuPool>, lanes: Lanes) -> Self {
 		Middleware {
 			stats,
 			notifier,
 			pool,
+			lanes,
+			names: None,
+		}
+	}
+
+	/// Resolves registry names passed in place of addresses before requests are processed.
+	pub fn with_names(mut self, names: Arc<NameResolver>) -> Self {
+		self.names = Some(names);
+		self
+	}
+
+	/// Resolves names in the calls of a request and records them in its metadata.
+	/// Calls with unregistered names are removed from the request, returns their error outputs.
+	fn resolve_names<M: ResolvedNames>(&self, request: &mut rpc::Request, meta: &mut M) -> Vec<rpc::Output> {
+		let names = match self.names {
+			Some(ref names) => names,
+			None => return Vec::new(),
+		};
+		match *request {
+			rpc::Request::Single(ref mut call) => resolve_call(names, call, meta).err().into_iter().collect(),
+			rpc::Request::Batch(ref mut calls) => {
+				let mut failed = Vec::new();
+				let resolved: Vec<_> = calls.drain(..).filter_map(|mut call| match resolve_call(names, &mut call, meta) {
+					Ok(()) => Some(call),
+					Err(output) => {
+						failed.push(output);
+						None
+					},
+				}).collect();
+				*calls = resolved;
+				failed
+			},
 		}
 	}
 
@@ -204,39 +310,78 @@ impl<T: ActivityNotifier> Middleware<T> {
 	}
 }
 
-impl<M: rpc::Metadata, T: ActivityNotifier> rpc::Middleware<M> for Middleware<T> {
+fn resolve_call<M: ResolvedNames>(names: &NameResolver, call: &mut rpc::Call, meta: &mut M) -> Result<(), rpc::Output> {
+	if let rpc::Call::MethodCall(ref mut call) = *call {
+		let resolved = names.resolve_call(call)
+			.map_err(|error| rpc::Output::from(Err(error), call.id.clone(), call.jsonrpc.clone()))?;
+		for (address, name) in resolved {
+			meta.name_resolved(address, name);
+		}
+	}
+	Ok(())
+}
+
+impl<M: rpc::Metadata + ResolvedNames, T: ActivityNotifier> rpc::Middleware<M> for Middleware<T> {
 	type Future = rpc::futures::future::Either<
 		pool::CpuFuture<Option<rpc::Response>, ()>,
 		rpc::FutureResponse,
 	>;
 
-	fn on_request<F, X>(&self, request: rpc::Request, meta: M, process: F) -> Self::Future where
+	fn on_request<F, X>(&self, mut request: rpc::Request, mut meta: M, process: F) -> Self::Future where
 		F: FnOnce(rpc::Request, M) -> X,
 		X: rpc::futures::Future<Item=Option<rpc::Response>, Error=()> + Send + 'static,
 	{
+		use self::rpc::futures::future;
 		use self::rpc::futures::future::Either::{A, B};
 
 		let start = time::Instant::now();
 
 		self.notifier.active();
 		self.stats.count_request();
+		let failed = self.resolve_names(&mut request, &mut meta);
+		match request {
+			rpc::Request::Single(_) if !failed.is_empty() => {
+				let output = failed.into_iter().next().expect("failed is not empty; qed");
+				return B(Box::new(future::ok(Some(rpc::Response::Single(output)))));
+			},
+			rpc::Request::Batch(ref calls) if calls.is_empty() && !failed.is_empty() => {
+				return B(Box::new(future::ok(Some(rpc::Response::Batch(failed)))));
+			},
+			_ => {},
+		}
 
 		let id = match request {
 			rpc::Request::Single(rpc::Call::MethodCall(ref call)) => Some(call.id.clone()),
 			_ => None,
 		};
+		let lane = match request {
+			rpc::Request::Single(rpc::Call::MethodCall(ref call)) => self.lanes.lane_for(&call.method),
+			rpc::Request::Single(rpc::Call::Notification(ref notification)) => self.lanes.lane_for(&notification.method),
+			_ => None,
+		};
 		let stats = self.stats.clone();
-		let future = process(request, meta).map(move |res| {
+		let request_id = request_id().unwrap_or_else(new_request_id);
+		let future = with_request_id(&request_id, || process(request, meta));
+		let future = Traced { id: request_id, inner: future }.map(move |res| {
 			let time = Self::as_micro(start.elapsed());
 			if time > 10_000 {
 				debug!(target: "rpc", "[{:?}] Took {}ms", id, time / 1_000);
 			}
 			stats.add_roundtrip(time);
-			res
+			if failed.is_empty() {
+				return res;
+			}
+			match res {
+				Some(rpc::Response::Batch(mut outputs)) => {
+					outputs.extend(failed);
+					Some(rpc::Response::Batch(outputs))
+				},
+				_ => Some(rpc::Response::Batch(failed)),
+			}
 		});
 
-		match self.pool {
-			Some(ref pool) => A(pool.spawn(future)),
+		match lane.map(|lane| &lane.pool).or(self.pool.as_ref()) {
+			Some(pool) => A(pool.spawn(future)),
 			None => B(Box::new(future)),
 		}
 	}
@@ -257,7 +402,7 @@ impl ActivityNotifier for ClientNotifier {
 #[cfg(test)]
 mod tests {
 
-	use super::{RateCalculator, StatsCalculator, RpcStats};
+	use super::{RateCalculator, StatsCalculator, RpcStats, MethodPool, Lanes, CpuPool};
 
 	#[test]
 	fn should_calculate_rate() {
@@ -314,6 +459,73 @@ mod tests {
 		assert_eq!(stats.approximated_roundtrip(), 125);
 	}
 
+	#[test]
+	fn should_match_heavy_methods() {
+		let heavy = MethodPool::new(CpuPool::new(1), vec!["eth_getLogs".into(), "trace_*".into()]);
+
+		assert!(heavy.handles("eth_getLogs"));
+		assert!(heavy.handles("trace_filter"));
+		assert!(heavy.handles("trace_replayTransaction"));
+		assert!(!heavy.handles("eth_getLogsCount"));
+		assert!(!heavy.handles("eth_blockNumber"));
+	}
+
+	#[test]
+	fn should_pick_priority_lane_first() {
+		use std::ptr;
+
+		let lanes = Lanes {
+			priority: Some(MethodPool::new(CpuPool::new(1), vec!["eth_blockNumber".into(), "parity_nodeHealth".into()])),
+			heavy: Some(MethodPool::new(CpuPool::new(1), vec!["eth_*".into()])),
+		};
+		let priority = lanes.priority.as_ref().unwrap();
+		let heavy = lanes.heavy.as_ref().unwrap();
+
+		assert!(ptr::eq(lanes.lane_for("eth_blockNumber").unwrap(), priority));
+		assert!(ptr::eq(lanes.lane_for("eth_getLogs").unwrap(), heavy));
+		assert!(lanes.lane_for("net_version").is_none());
+		assert!(Lanes::default().lane_for("eth_blockNumber").is_none());
+	}
+
+	#[test]
+	fn should_return_errors_of_unregistered_names_in_batches() {
+		use std::sync::Arc;
+		use ethereum_types::Address;
+		use jsonrpc_core::{MetaIoHandler, Params, Value};
+		use serde_json;
+		use v1::helpers::names::NameResolver;
+		use v1::metadata::Metadata;
+		use super::{ActivityNotifier, Middleware};
+
+		struct Notifier;
+		impl ActivityNotifier for Notifier {
+			fn active(&self) {}
+		}
+
+		let names = NameResolver::new(|name, _| match name {
+			"alice" => Some(Address::from(1)),
+			_ => None,
+		});
+		let middleware = Middleware::new(Arc::new(RpcStats::default()), Notifier, None, Lanes::default()).with_names(Arc::new(names));
+		let mut io = MetaIoHandler::with_middleware(middleware);
+		io.add_method_with_meta("eth_getBalance", |_params: Params, meta: Metadata| {
+			Ok(Value::String(meta.resolved_names[&Address::from(1)].clone()))
+		});
+
+		let single = r#"{"jsonrpc":"2.0","method":"eth_getBalance","params":["bob"],"id":1}"#;
+		let batch = r#"[
+			{"jsonrpc":"2.0","method":"eth_getBalance","params":["alice"],"id":1},
+			{"jsonrpc":"2.0","method":"eth_getBalance","params":["bob"],"id":2}
+		]"#;
+		let single: Value = serde_json::from_str(&io.handle_request_sync(single, Metadata::default()).unwrap()).unwrap();
+		let batch: Value = serde_json::from_str(&io.handle_request_sync(batch, Metadata::default()).unwrap()).unwrap();
+
+		assert_eq!(single["error"]["message"], "Name bob is not registered.");
+		assert_eq!(batch[0]["result"], "alice");
+		assert_eq!(batch[1]["id"], 2);
+		assert_eq!(batch[1]["error"]["message"], "Name bob is not registered.");
+	}
+
 	#[test]
 	fn should_be_sync_and_send() {
 		let stats = RpcStats::default();
@@ -15,11 +15,15 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Parity RPC requests Metadata.
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use ethereum_types::Address;
 use jsonrpc_core;
 use jsonrpc_pubsub::{Session, PubSubMetadata};
 
+use authcodes::Authorization;
+use v1::helpers::names::ResolvedNames;
 use v1::types::{DappId, Origin};
 
 /// RPC methods metadata.
@@ -29,6 +33,12 @@ pub struct Metadata {
 	pub origin: Origin,
 	/// Request PubSub Session
 	pub session: Option<Arc<Session>>,
+	/// Access granted by the token used to authorize `Signer` session
+	pub authorization: Option<Authorization>,
+	/// Origin pattern matched during the WebSockets handshake, selecting the APIs of the session
+	pub origin_apis: Option<String>,
+	/// Registry names passed in place of addresses, by the address they were resolved to
+	pub resolved_names: BTreeMap<Address, String>,
 }
 
 impl Metadata {
@@ -53,6 +63,12 @@ impl Metadata {
 	}
 }
 
+impl ResolvedNames for Metadata {
+	fn name_resolved(&mut self, address: Address, name: String) {
+		self.resolved_names.insert(address, name);
+	}
+}
+
 impl jsonrpc_core::Metadata for Metadata {}
 impl PubSubMetadata for Metadata {
 	fn session(&self) -> Option<Arc<Session>> {
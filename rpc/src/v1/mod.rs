@@ -41,17 +41,25 @@ pub mod informant;
 pub mod metadata;
 pub mod traits;
 
-pub use self::traits::{Web3, Eth, EthFilter, EthPubSub, EthSigning, Net, Parity, ParityAccounts, ParitySet, ParitySigning, PubSub, Signer, Personal, Traces, Rpc, SecretStore, Private};
+pub use self::traits::{Web3, Clique, Debug, Eth, EthFilter, EthPubSub, EthSigning, Evm, Explorer, Net, Parity, ParityAccounts, ParityNonces, ParityRegistrar, ParitySet, ParitySigning, PubSub, Signer, Personal, Traces, Rpc, SecretStore, Private};
 pub use self::impls::*;
-pub use self::helpers::{NetworkSettings, block_import, dispatch};
+pub use self::helpers::{NetworkSettings, block_import, dispatch, drain, names};
 pub use self::metadata::Metadata;
 pub use self::types::Origin;
-pub use self::extractors::{RpcExtractor, WsExtractor, WsStats, WsDispatcher};
+pub use self::extractors::{RpcExtractor, IpcExtractor, WsExtractor, WsStats, WsDispatcher, DrainMiddleware};
 
 /// Signer utilities
 pub mod signer {
-	pub use super::helpers::{SigningQueue, SignerService, ConfirmationsQueue};
-	pub use super::types::{ConfirmationRequest, TransactionModification, U256, TransactionCondition};
+	pub use super::helpers::{SigningQueue, SignerService, ConfirmationsQueue, QueueEvent, MethodRegistry, ApprovalRules};
+	pub use super::types::{
+		ConfirmationRequest, ConfirmationPayload, ConfirmationDetails, TransactionModification, TransactionRequest,
+		SignRequest, DecryptRequest, U256, TransactionCondition,
+	};
+}
+
+/// Types of parameters and results of the typed clients in `parity::rpc_client`
+pub mod client {
+	pub use super::types::{BlockNumber, Bytes, H160, H256, H512, H520, U64, U256};
 }
 
 /// Dapps integration utilities
@@ -17,9 +17,10 @@
 //! Test implementation of SyncProvider.
 
 use std::collections::BTreeMap;
-use ethereum_types::H256;
+use ethereum_types::{H256, U256};
+use ethcore::encoded;
 use parking_lot::RwLock;
-use sync::{SyncProvider, EthProtocolInfo, SyncStatus, SyncState, PeerInfo, TransactionStats};
+use sync::{SyncProvider, EthProtocolInfo, SyncStatus, SyncState, PeerInfo, TransactionStats, ForkStatus, ForkAlert};
 
 /// TestSyncProvider config.
 pub struct Config {
@@ -122,4 +123,27 @@ impl SyncProvider for TestSyncProvider {
 			}
 		]
 	}
+
+	fn fork_status(&self) -> ForkStatus {
+		ForkStatus {
+			alert_depth: Some(3),
+			alerts: vec![
+				ForkAlert {
+					peer_id: 1,
+					ancestor_number: 7,
+					ancestor_hash: 7.into(),
+					our_number: 10,
+					our_hash: 10.into(),
+					their_number: 11,
+					their_hash: 11.into(),
+					depth: 4,
+					detected_at: 1_500_000_000,
+				}
+			],
+		}
+	}
+
+	fn head_block(&self) -> Option<(encoded::Block, U256)> {
+		None
+	}
 }
@@ -18,7 +18,7 @@
 
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use semver::Version;
-use updater::{Service as UpdateService, CapState, ReleaseInfo, VersionInfo, OperationsInfo, ReleaseTrack};
+use updater::{Service as UpdateService, CapState, ReleaseInfo, VersionInfo, OperationsInfo, ReleaseTrack, PendingUpdate, UpdateStage};
 
 /// Test implementation of fetcher. Will always return the same file.
 #[derive(Default)]
@@ -94,4 +94,21 @@ impl UpdateService for TestUpdater {
 			minor: None,
 		})
 	}
+
+	fn pending_update(&self) -> Option<PendingUpdate> {
+		self.upgrade_ready().map(|release| PendingUpdate {
+			release,
+			stage: UpdateStage::Ready,
+			delayed_until: None,
+			error: None,
+		})
+	}
+
+	fn defer_update(&self, _blocks: u64) -> bool {
+		self.pending_update().is_some()
+	}
+
+	fn rollback(&self) -> bool {
+		self.updated.swap(false, Ordering::Relaxed)
+	}
 }
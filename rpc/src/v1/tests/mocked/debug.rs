@@ -0,0 +1,151 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ethcore::executed::Executed;
+use ethcore::trace::{FlatTrace, Profile, ProfileStats};
+use ethcore::trace::trace::{Action, Res, Call, CallResult};
+use ethcore::client::TestBlockChainClient;
+
+use vm::CallType;
+
+use jsonrpc_core::IoHandler;
+use serde_json;
+use v1::{Metadata, Debug, DebugClient};
+
+fn call(trace_address: Vec<usize>, subtraces: usize, input: Vec<u8>) -> FlatTrace {
+	FlatTrace {
+		action: Action::Call(Call {
+			from: 0xf.into(),
+			to: 0x10.into(),
+			value: 0x1.into(),
+			gas: 0x100.into(),
+			input,
+			call_type: CallType::Call,
+		}),
+		result: Res::Call(CallResult {
+			gas_used: 0x10.into(),
+			output: vec![],
+		}),
+		subtraces,
+		trace_address: trace_address.into_iter().collect(),
+	}
+}
+
+fn io() -> IoHandler<Metadata> {
+	let client = Arc::new(TestBlockChainClient::new());
+	let mut profile = Profile::default();
+	profile.opcodes.insert(0x60, ProfileStats { count: 2, gas: 6.into(), time: Duration::new(0, 120) });
+	profile.frames.insert("root".into(), ProfileStats { count: 2, gas: 6.into(), time: Duration::new(0, 120) });
+	profile.stacks.insert("root;PUSH1".into(), 6.into());
+	client.set_execution_profile(profile);
+	*client.execution_result.write() = Some(Ok(Executed {
+		exception: None,
+		gas: 20_000.into(),
+		gas_used: 10_000.into(),
+		refunded: 0.into(),
+		cumulative_gas_used: 10_000.into(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![1, 2, 3],
+		trace: vec![
+			call(vec![], 1, vec![0xa9, 0x05, 0x9c, 0xbb]),
+			call(vec![0], 0, vec![0xa9, 0x05, 0x9c, 0xbb, 1, 2]),
+		],
+		vm_trace: None,
+		state_diff: None,
+	}));
+
+	let mut io = IoHandler::default();
+	io.extend_with(DebugClient::new(&client).to_delegate());
+	io
+}
+
+#[test]
+fn rpc_debug_trace_call_with_call_tracer() {
+	let io = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceCall","params":[{}, "latest", {"tracer":"callTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"calls":[{"from":"0x000000000000000000000000000000000000000f","gas":"0x100","gasUsed":"0x10","input":"0xa9059cbb0102","output":"0x","to":"0x0000000000000000000000000000000000000010","type":"CALL","value":"0x1"}],"from":"0x000000000000000000000000000000000000000f","gas":"0x100","gasUsed":"0x10","input":"0xa9059cbb","output":"0x","to":"0x0000000000000000000000000000000000000010","type":"CALL","value":"0x1"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_call_with_4byte_tracer() {
+	let io = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceCall","params":[{}, "latest", {"tracer":"4byteTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"0xa9059cbb-0":1,"0xa9059cbb-2":1},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_profile_call_reports_opcode_gas_and_time() {
+	let io = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_profileCall","params":[{}],"id":1}"#;
+	let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+	let result = &response["result"];
+
+	assert_eq!(result["gasUsed"], "0x6");
+	assert_eq!(result["opcodes"], serde_json::from_str::<serde_json::Value>(r#"[{"op":96,"opName":"PUSH1","count":2,"gas":"0x6","time":120}]"#).unwrap());
+	assert_eq!(result["frames"], serde_json::from_str::<serde_json::Value>(r#"[{"frame":"root","count":2,"gas":"0x6","time":120}]"#).unwrap());
+	assert_eq!(result["folded"], serde_json::from_str::<serde_json::Value>(r#"["root;PUSH1 6"]"#).unwrap());
+}
+
+#[test]
+fn rpc_debug_profile_transaction_not_found() {
+	let io = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_profileTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_transaction_not_found() {
+	let io = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"tracer":"callTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_account_range_pruned() {
+	let io = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_accountRange","params":["latest", "0x0000000000000000000000000000000000000000000000000000000000000000", 10],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive or increase --state-query-history."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_storage_range_at_rejects_pending() {
+	let io = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_storageRangeAt","params":["pending", "0x0000000000000000000000000000000000000001", "0x0000000000000000000000000000000000000000000000000000000000000000", 10],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: `BlockNumber::Pending` is not supported","data":"()"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -955,18 +955,34 @@ fn rpc_eth_send_transaction_error() {
 #[test]
 fn rpc_eth_send_raw_transaction_error() {
 	let tester = EthTester::default();
+	let request = |raw: &str| r#"{"jsonrpc":"2.0","method":"eth_sendRawTransaction","params":[""#.to_owned() + raw + r#""],"id":1}"#;
 
-	let req = r#"{
-		"jsonrpc": "2.0",
-		"method": "eth_sendRawTransaction",
-		"params": [
-			"0x0123"
-		],
-		"id": 1
-	}"#;
-	let res = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid RLP.","data":"RlpExpectedToBeList"},"id":1}"#.into();
+	let res = r#"{"jsonrpc":"2.0","error":{"code":-32011,"message":"Invalid RLP data: RlpExpectedToBeList"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request("0x01")), Some(res.into()));
 
-	assert_eq!(tester.io.handle_request_sync(&req), Some(res));
+	let res = r#"{"jsonrpc":"2.0","error":{"code":-32011,"message":"Invalid RLP data: 1 bytes following the transaction"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request("0x0123")), Some(res.into()));
+
+	// a valid transaction with `v` replaced by 30, which no sender can be recovered for.
+	let invalid_v = "0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ea048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804";
+	let res = tester.io.handle_request_sync(&request(invalid_v)).unwrap();
+	assert!(res.starts_with(r#"{"jsonrpc":"2.0","error":{"code":-32012,"message":"Invalid signature: "#), "{}", res);
+
+	// signed for chain 1, while the test chain has id 2.
+	let other_chain = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::from(1),
+		gas: U256::from(21_000),
+		action: Action::Call(Address::from(1)),
+		value: U256::from(1),
+		data: vec![],
+	}.sign(&Secret::from([69u8; 32]), Some(1));
+	let res = r#"{"jsonrpc":"2.0","error":{"code":-32013,"message":"Invalid chain id."},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request(&format!("0x{}", rlp::encode(&other_chain).into_vec().to_hex()))), Some(res.into()));
+
+	// over the default limit of 300kB.
+	let res = r#"{"jsonrpc":"2.0","error":{"code":-32014,"message":"Transaction is too big, see chain specification for the limit."},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request(&format!("0x{}", "00".repeat(300 * 1024 + 1)))), Some(res.into()));
 }
 
 #[test]
@@ -1049,6 +1065,60 @@ fn rpc_eth_transaction_receipt() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_transaction_receipt_revert_reason_is_cached() {
+	let receipt = LocalizedReceipt {
+		from: 1.into(),
+		to: Some(2.into()),
+		transaction_hash: 5.into(),
+		transaction_index: 0,
+		block_hash: 7.into(),
+		block_number: 1,
+		cumulative_gas_used: 0x10.into(),
+		gas_used: 0x10.into(),
+		contract_address: None,
+		logs: vec![],
+		log_bloom: 0.into(),
+		outcome: TransactionOutcome::StatusCode(0),
+	};
+
+	// Error("Not authorised")
+	let output = "08c379a0\
+		0000000000000000000000000000000000000000000000000000000000000020\
+		000000000000000000000000000000000000000000000000000000000000000e\
+		4e6f7420617574686f7269736564000000000000000000000000000000000000".from_hex().unwrap();
+
+	let tester = EthTester::new_with_options(EthClientOptions::with(|options| options.fat_receipts = true));
+	tester.client.set_transaction_receipt(TransactionId::Hash(5.into()), receipt);
+	tester.client.set_execution_result(Ok(Executed {
+		exception: Some(::vm::Error::Reverted),
+		gas: 0x20.into(),
+		gas_used: 0x10.into(),
+		refunded: 0.into(),
+		cumulative_gas_used: 0x10.into(),
+		logs: vec![],
+		contracts_created: vec![],
+		output,
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+	}));
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getTransactionReceipt",
+		"params": ["0x0000000000000000000000000000000000000000000000000000000000000005"],
+		"id": 1
+	}"#;
+
+	let response = tester.io.handle_request_sync(request).unwrap();
+	assert!(response.contains(r#""revertReason":"Not authorised""#), "{}", response);
+
+	// the second query is answered without replaying the transaction
+	tester.client.set_execution_result(Err(::ethcore::executed::CallError::StatePruned));
+	assert_eq!(tester.io.handle_request_sync(request).unwrap(), response);
+}
+
 #[test]
 fn rpc_eth_transaction_receipt_null() {
 	let tester = EthTester::default();
@@ -1064,6 +1134,30 @@ fn rpc_eth_transaction_receipt_null() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_transaction_not_indexed() {
+	use ethcore::client::TransactionIndex;
+
+	let tester = EthTester::default();
+	*tester.client.tx_index.write() = TransactionIndex::None;
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getTransactionByHash",
+		"params": ["0xb903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Transaction not found. Transactions are only partially indexed by hash on this node. Run with --tx-index=all to look up any transaction."},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	tester.add_blocks(10, EachBlockWith::Nothing);
+	*tester.client.tx_index.write() = TransactionIndex::Recent(4);
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Transaction not found. Transactions are only partially indexed by hash on this node. Run with --tx-index=all to look up any transaction.","data":"Transactions are indexed from block #7"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 // These tests are incorrect: their output is undefined as long as eth_getCompilers is [].
 // Will ignore for now, but should probably be replaced by more substantial tests which check
 // the output of eth_getCompilers to determine whether to test. CI systems can then be preinstalled
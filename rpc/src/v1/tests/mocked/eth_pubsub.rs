@@ -24,6 +24,7 @@ use std::time::Duration;
 
 use v1::{EthPubSub, EthPubSubClient, Metadata};
 
+use ethcore::account_provider::AccountProvider;
 use ethcore::client::{TestBlockChainClient, EachBlockWith, ChainNotify, ChainRoute, ChainRouteType};
 use parity_reactor::EventLoop;
 
@@ -200,6 +201,60 @@ fn should_subscribe_to_pending_transactions() {
 	assert_eq!(res, None);
 }
 
+#[test]
+fn should_subscribe_to_watch_only_accounts() {
+	// given
+	let el = EventLoop::spawn();
+	let mut client = TestBlockChainClient::new();
+	client.add_blocks(3, EachBlockWith::Nothing);
+	let h3 = client.block_hash_delta_minus(1);
+	let h2 = client.block_hash_delta_minus(2);
+	let h1 = client.block_hash_delta_minus(3);
+	let client = Arc::new(client);
+	let watched = "000baba1000baba2000baba3000baba4000baba5".parse().unwrap();
+	let accounts = Arc::new(AccountProvider::transient_provider());
+	accounts.add_watch_only(watched, "Cold".into()).unwrap();
+	client.set_balance(watched, 5.into());
+
+	let pubsub = EthPubSubClient::new_test_with_accounts(client.clone(), el.remote(), accounts);
+	let handler = pubsub.handler().upgrade().unwrap();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	// Subscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["watchOnlyAccounts"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// The first block reports the current state
+	handler.new_blocks(vec![], vec![], ChainRoute::new(vec![(h1, ChainRouteType::Enacted)]), vec![], vec![], DURATION_ZERO);
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"address":"0x000baba1000baba2000baba3000baba4000baba5","balance":"0x5","hasCode":false,"nonce":"0x0"},"subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	// Unchanged accounts are not reported
+	handler.new_blocks(vec![], vec![], ChainRoute::new(vec![(h2, ChainRouteType::Enacted)]), vec![], vec![], DURATION_ZERO);
+	client.set_nonce(watched, 1.into());
+	handler.new_blocks(vec![], vec![], ChainRoute::new(vec![(h3, ChainRouteType::Enacted)]), vec![], vec![], DURATION_ZERO);
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"address":"0x000baba1000baba2000baba3000baba4000baba5","balance":"0x5","hasCode":false,"nonce":"0x1"},"subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	// And unsubscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_unsubscribe", "params": ["0x416d77337e24399d"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata), Some(response.to_owned()));
+
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
+
 #[test]
 fn should_return_unimplemented() {
 	// given
@@ -219,4 +274,7 @@ fn should_return_unimplemented() {
 	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not implemented yet. Please create an issue on Github repo."},"id":1}"#;
 	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["syncing"], "id": 1}"#;
 	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+	// Watch-only accounts are not tracked without the account provider
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["watchOnlyAccounts"], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
 }
@@ -0,0 +1,53 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::client::TestBlockChainClient;
+use ethcore::spec::Spec;
+
+use jsonrpc_core::IoHandler;
+use v1::{Evm, EvmClient};
+
+fn io(client: TestBlockChainClient) -> IoHandler {
+	let client = Arc::new(client);
+
+	let mut io = IoHandler::default();
+	io.extend_with(EvmClient::new(&client).to_delegate());
+	io
+}
+
+#[test]
+fn rpc_evm_increase_time() {
+	let io = io(TestBlockChainClient::new_with_spec(Spec::new_instant()));
+
+	let request = r#"{"jsonrpc":"2.0","method":"evm_increaseTime","params":[60],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":60,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc":"2.0","method":"evm_increaseTime","params":[40],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":100,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_evm_requires_instant_seal_engine() {
+	let io = io(TestBlockChainClient::new());
+
+	let request = r#"{"jsonrpc":"2.0","method":"evm_mine","params":[],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"The chain is not using the instant seal engine."},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -0,0 +1,126 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::client::{BlockChainClient, BlockId, EachBlockWith, ExplorerIndex, TestBlockChainClient};
+use kvdb_memorydb;
+use serde_json::{self, Value};
+use transaction::SignedTransaction;
+
+use jsonrpc_core::IoHandler;
+use v1::{Explorer, ExplorerClient};
+
+fn request(io: &IoHandler, method: &str, params: &str) -> Value {
+	let request = format!(r#"{{"jsonrpc":"2.0","method":"{}","params":{},"id":1}}"#, method, params);
+	serde_json::from_str(&io.handle_request_sync(&request).unwrap()).unwrap()
+}
+
+#[test]
+fn rpc_explorer_block_list() {
+	let client = Arc::new(TestBlockChainClient::new());
+	client.add_blocks(3, EachBlockWith::Transaction);
+	let mut io = IoHandler::default();
+	io.extend_with(ExplorerClient::new(&client, None).to_delegate());
+
+	let response = request(&io, "explorer_blockList", "[2]");
+	let blocks = response["result"].as_array().unwrap();
+	assert_eq!(blocks.len(), 2);
+	assert_eq!(blocks[0]["number"].as_u64(), Some(3));
+	assert_eq!(blocks[0]["parentHash"], blocks[1]["hash"]);
+	assert_eq!(blocks[0]["transactionCount"].as_u64(), Some(1));
+
+	let response = request(&io, "explorer_blockList", r#"[10, "0x1"]"#);
+	assert_eq!(response["result"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn rpc_explorer_address_history() {
+	let client = Arc::new(TestBlockChainClient::new());
+	client.add_blocks(3, EachBlockWith::Transaction);
+	let chain = client.clone() as Arc<BlockChainClient>;
+	let index = Arc::new(ExplorerIndex::new(Arc::new(kvdb_memorydb::create(0)), Arc::downgrade(&chain)));
+	index.catch_up();
+
+	let mut io = IoHandler::default();
+	io.extend_with(ExplorerClient::new(&client, Some(index)).to_delegate());
+
+	let block = client.block(BlockId::Number(2)).unwrap();
+	let transaction = SignedTransaction::new(block.transactions().remove(0)).unwrap();
+	let params = format!(r#"["{:?}", 10]"#, transaction.sender());
+	let response = request(&io, "explorer_addressHistory", &params);
+	let transactions = response["result"].as_array().unwrap();
+	assert_eq!(transactions.len(), 1);
+	assert_eq!(transactions[0]["hash"].as_str(), Some(&*format!("{:?}", transaction.hash())));
+	assert_eq!(transactions[0]["blockNumber"].as_str(), Some("0x2"));
+
+	let params = format!(r#"["{:?}", 10, 1]"#, transaction.sender());
+	let response = request(&io, "explorer_addressHistory", &params);
+	assert!(response["result"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn rpc_explorer_address_history_requires_index() {
+	let client = Arc::new(TestBlockChainClient::new());
+	let mut io = IoHandler::default();
+	io.extend_with(ExplorerClient::new(&client, None).to_delegate());
+
+	let request = r#"{"jsonrpc":"2.0","method":"explorer_addressHistory","params":["0x0000000000000000000000000000000000000005", 10],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"The explorer index is disabled. Run with --enable-explorer-index to enable it."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_transactions_by_address() {
+	let client = Arc::new(TestBlockChainClient::new());
+	client.add_blocks(3, EachBlockWith::Transaction);
+	let chain = client.clone() as Arc<BlockChainClient>;
+	let index = Arc::new(ExplorerIndex::new(Arc::new(kvdb_memorydb::create(0)), Arc::downgrade(&chain)));
+	index.catch_up();
+
+	let mut io = IoHandler::default();
+	io.extend_with(ExplorerClient::new(&client, Some(index)).to_delegate());
+
+	let block = client.block(BlockId::Number(1)).unwrap();
+	let transaction = SignedTransaction::new(block.transactions().remove(0)).unwrap();
+
+	let response = request(&io, "parity_getTransactionsByAddress", &format!(r#"["{:?}"]"#, transaction.sender()));
+	assert_eq!(response["result"][0]["hash"], format!("{:?}", transaction.hash()).as_str());
+
+	let response = request(&io, "parity_getTransactionsByAddress", &format!(r#"["{:?}",{{"direction":"received"}}]"#, transaction.sender()));
+	assert_eq!(response["result"].as_array().unwrap().len(), 0);
+
+	let response = request(&io, "parity_getTransactionsByAddress", &format!(r#"["{:?}",{{"fromBlock":"0x2"}}]"#, transaction.sender()));
+	assert_eq!(response["result"].as_array().unwrap().len(), 0);
+
+	let params = format!(r#"["{:?}",{{"after":{{"blockNumber":"0x2","transactionIndex":"0x0"}}}}]"#, transaction.sender());
+	assert_eq!(request(&io, "parity_getTransactionsByAddress", &params)["result"].as_array().unwrap().len(), 1);
+	let params = format!(r#"["{:?}",{{"after":{{"blockNumber":"0x1","transactionIndex":"0x0"}}}}]"#, transaction.sender());
+	assert_eq!(request(&io, "parity_getTransactionsByAddress", &params)["result"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn rpc_parity_transactions_by_address_requires_index() {
+	let client = Arc::new(TestBlockChainClient::new());
+	let mut io = IoHandler::default();
+	io.extend_with(ExplorerClient::new(&client, None).to_delegate());
+
+	let request = r#"{"jsonrpc":"2.0","method":"parity_getTransactionsByAddress","params":["0x0000000000000000000000000000000000000005"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"The explorer index is disabled. Run with --enable-explorer-index to enable it."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
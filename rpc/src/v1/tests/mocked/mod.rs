@@ -17,12 +17,18 @@
 //! RPC mocked tests. Most of these test that the RPC server is serializing and forwarding
 //! method calls properly.
 
+mod clique;
+mod debug;
 mod eth;
 mod eth_pubsub;
+mod evm;
+mod explorer;
 mod manage_network;
 mod net;
 mod parity;
 mod parity_accounts;
+mod parity_nonces;
+mod parity_registrar;
 mod parity_set;
 mod personal;
 mod pubsub;
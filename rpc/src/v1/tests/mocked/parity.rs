@@ -17,7 +17,7 @@
 use std::sync::Arc;
 use ethcore::account_provider::AccountProvider;
 use ethcore::client::{TestBlockChainClient, Executed};
-use ethcore_logger::RotatingLogger;
+use ethcore_logger::{LogEntry, LogLevel, RotatingLogger};
 use ethereum_types::{Address, U256, H256};
 use ethstore::ethkey::{Generator, Random};
 use miner::pool::local_transactions::Status as LocalTransactionStatus;
@@ -62,6 +62,7 @@ impl Dependencies {
 				Arc::new(FakeSync),
 				node_health::TimeChecker::new::<String>(&[], node_health::CpuPool::new(1)),
 				parity_reactor::Remote::new_sync(),
+				Default::default(),
 			),
 			updater: Arc::new(TestUpdater::default()),
 			logger: Arc::new(RotatingLogger::new("rpc=trace".to_owned())),
@@ -94,6 +95,7 @@ impl Dependencies {
 			signer,
 			self.dapps_address.clone(),
 			self.ws_address.clone(),
+			None,
 		)
 	}
 
@@ -115,6 +117,8 @@ struct FakeSync;
 impl node_health::SyncStatus for FakeSync {
 	fn is_major_importing(&self) -> bool { false }
 	fn peers(&self) -> (usize, usize) { (4, 25) }
+	fn blocks_behind(&self) -> Option<u64> { Some(0) }
+	fn disk_space(&self) -> Option<(u64, bool)> { None }
 }
 
 #[test]
@@ -290,6 +294,33 @@ fn rpc_parity_dev_logs_levels() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_recent_logs() {
+	let deps = Dependencies::new();
+	deps.logger.record(LogEntry { timestamp: 1, level: LogLevel::Info, target: "sync".into(), message: "a".into() });
+	deps.logger.record(LogEntry { timestamp: 2, level: LogLevel::Debug, target: "miner".into(), message: "b".into() });
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_recentLogs", "params":["info", null], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"level":"INFO","message":"a","target":"sync","timestamp":1}],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_recentLogs", "params":[null, null, 1], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"level":"DEBUG","message":"b","target":"miner","timestamp":2}],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_get_logging() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getLogging", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"rpc=trace","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_transactions_limit() {
 	let deps = Dependencies::new();
@@ -334,6 +365,53 @@ fn rpc_parity_net_peers() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_net_peer_permissions() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_netPeerPermissions", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_fork_status() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_forkStatus", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"alertDepth":"0x3","alerts":[{"ancestorHash":"0x0000000000000000000000000000000000000000000000000000000000000007","ancestorNumber":"0x7","depth":"0x4","detectedAt":1500000000,"ourHash":"0x000000000000000000000000000000000000000000000000000000000000000a","ourNumber":"0xa","peerId":1,"theirHash":"0x000000000000000000000000000000000000000000000000000000000000000b","theirNumber":"0xb"}]},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_pending_reorg() {
+	use ethcore::client::ReorgAlert;
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_pendingReorg", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	*deps.client.pending_reorg.write() = Some(ReorgAlert {
+		best_number: 10,
+		best_hash: 10.into(),
+		ancestor_number: 5,
+		ancestor_hash: 5.into(),
+		new_number: 11,
+		new_hash: 11.into(),
+		depth: 5,
+		detected_at: 1_500_000_000,
+	});
+	let response = r#"{"jsonrpc":"2.0","result":{"ancestorHash":"0x0000000000000000000000000000000000000000000000000000000000000005","ancestorNumber":"0x5","bestHash":"0x000000000000000000000000000000000000000000000000000000000000000a","bestNumber":"0xa","depth":"0x5","detectedAt":1500000000,"newHash":"0x000000000000000000000000000000000000000000000000000000000000000b","newNumber":"0xb"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_net_port() {
 	let deps = Dependencies::new();
@@ -576,13 +654,213 @@ fn rpc_parity_call() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_estimate_gas_detailed() {
+	use serde_json;
+
+	let deps = Dependencies::new();
+	deps.client.set_execution_result(Ok(Executed {
+		exception: None,
+		gas: U256::zero(),
+		gas_used: U256::from(0x5208),
+		refunded: U256::zero(),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![0x12, 0x34, 0xff],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+	}));
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc":"2.0","method":"parity_estimateGasDetailed","params":[{"from":"0xb60e8dd61c5d32be8058bb8eb970870f07233155","data":"0x6003600c60003960036000f3"}],"id":1}"#;
+	let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+
+	// the test client estimates 21000 gas, deploying 3 bytes of code costs 600
+	assert_eq!(response["result"]["gas"], "0x5208");
+	assert_eq!(response["result"]["codeDepositGas"], "0x258");
+}
+
+#[test]
+fn rpc_parity_call_bundle() {
+	let deps = Dependencies::new();
+	deps.client.set_execution_result(Ok(Executed {
+		exception: Some(::vm::Error::Reverted),
+		gas: U256::zero(),
+		gas_used: U256::from(0x5208),
+		refunded: U256::zero(),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![0x12, 0x34, 0xff],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+	}));
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc":"2.0","method":"parity_callBundle","params":[["0xf869018609184e72a0008276c094d46e8dd67c5d32be8058bb8eb970870f07244567849184e72a801ba0617f39c1a107b63302449c476d96a6cb17a5842fc98ff0c5bcf4d5c4d8166b95a009fdb6097c6196b9bbafc3a59f02f38d91baeef23d0c60a8e4f23c7714cea3a9"], "latest"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"transactionHash":"0xb928b661a60b2f3a329545a1213773dd99340ce8132395ea886ff78b43ca438d","gasUsed":"0x5208","logs":[],"output":"0x1234ff","status":"0x0","error":"Reverted","revertReason":null}],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_preview_transaction() {
+	use std::collections::BTreeMap;
+	use ethcore::account_diff::{AccountDiff, Diff};
+	use ethcore::state_diff::StateDiff;
+	use serde_json;
+
+	let account_diff = |balance, nonce| AccountDiff {
+		balance: balance,
+		nonce: nonce,
+		code: Diff::Same,
+		storage: BTreeMap::new(),
+	};
+	let mut diff = BTreeMap::new();
+	diff.insert(Address::from(1), account_diff(Diff::Changed(U256::from(0x10), U256::from(0x5)), Diff::Changed(U256::from(1), U256::from(2))));
+	diff.insert(Address::from(2), account_diff(Diff::Born(U256::from(0xb)), Diff::Born(U256::zero())));
+	diff.insert(Address::from(3), account_diff(Diff::Same, Diff::Changed(U256::from(0), U256::from(1))));
+
+	let deps = Dependencies::new();
+	deps.client.set_execution_result(Ok(Executed {
+		exception: None,
+		gas: U256::zero(),
+		gas_used: U256::from(0x5208),
+		refunded: U256::zero(),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: Some(StateDiff { raw: diff }),
+	}));
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc":"2.0","method":"parity_previewTransaction","params":["0xf869018609184e72a0008276c094d46e8dd67c5d32be8058bb8eb970870f07244567849184e72a801ba0617f39c1a107b63302449c476d96a6cb17a5842fc98ff0c5bcf4d5c4d8166b95a009fdb6097c6196b9bbafc3a59f02f38d91baeef23d0c60a8e4f23c7714cea3a9"],"id":1}"#;
+	let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+	let result = &response["result"];
+
+	assert_eq!(result["transaction"]["hash"], "0xb928b661a60b2f3a329545a1213773dd99340ce8132395ea886ff78b43ca438d");
+	assert_eq!(result["transaction"]["to"], "0xd46e8dd67c5d32be8058bb8eb970870f07244567");
+	assert_eq!(result["gasUsed"], "0x5208");
+	assert_eq!(result["status"], "0x1");
+	assert_eq!(result["balanceChanges"], serde_json::from_str::<serde_json::Value>(r#"{
+		"0x0000000000000000000000000000000000000001":{"before":"0x10","after":"0x5"},
+		"0x0000000000000000000000000000000000000002":{"before":"0x0","after":"0xb"}
+	}"#).unwrap());
+}
+
+#[test]
+fn rpc_parity_preview_transaction_rejects_other_chain_id() {
+	use rlp;
+	use rustc_hex::ToHex;
+	use transaction::{Transaction, Action};
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let signed = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::from(0x9184e72a000u64),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from(2)),
+		value: U256::from(0x9184e72au64),
+		data: vec![],
+	}.sign(Random.generate().unwrap().secret(), Some(1));
+
+	let request = r#"{"jsonrpc":"2.0","method":"parity_previewTransaction","params":["0x"#.to_owned()
+		+ &rlp::encode(&signed).into_vec().to_hex()
+		+ r#""],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32013,"message":"Invalid chain id."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_node_health() {
 	let deps = Dependencies::new();
 	let io = deps.default_client();
 
 	let request = r#"{"jsonrpc": "2.0", "method": "parity_nodeHealth", "params":[], "id": 1}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"peers":{"details":[4,25],"message":"","status":"ok"},"sync":{"details":false,"message":"","status":"ok"},"time":{"details":0,"message":"","status":"ok"}},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"peers":{"details":[4,25],"message":"","status":"ok"},"sync":{"details":false,"message":"","status":"ok"},"time":{"details":0,"message":"","status":"ok"},"disk":{"details":null,"message":"","status":"ok"}},"id":1}"#;
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_parity_time_drift() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_timeDrift", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":0,"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_validator_set() {
+	use ethcore::spec::Spec;
+
+	let mut deps = Dependencies::new();
+	deps.client = Arc::new(TestBlockChainClient::new_with_spec(Spec::new_test_round()));
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_validatorSet", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"pendingChanges":[],"validators":["0x7d577a597b2742b498cb5cf0c26cdcd726d39e6e","0x82a978b3f5962a5b0957d9ee9eef472ee55b42f1"]},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_validatorSet", "params":["0x10"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_validatorMisbehaviorReports", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_validator_set_requires_validator_engine() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_validatorSet", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"The chain is not using a validator set based consensus engine."},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_validatorMisbehaviorReports", "params":[], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_finality_status() {
+	use ethcore::client::{EachBlockWith, ChainInfo};
+
+	let deps = Dependencies::new();
+	deps.client.add_blocks(3, EachBlockWith::Nothing);
+	let io = deps.default_client();
+
+	let best_hash = deps.client.chain_info().best_block_hash;
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_finalityStatus", "params":[], "id": 1}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":{{"bestHash":"0x{:x}","bestNumber":"0x3","depth":null,"finalizedHash":"0x{:x}","finalizedNumber":"0x3","rule":"instant"}},"id":1}}"#, best_hash, best_hash);
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_parity_accounts_info_at_limits_addresses() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let addresses: Vec<String> = (0..1001).map(|i| format!("\"0x{:040x}\"", i)).collect();
+	let request = format!(r#"{{"jsonrpc":"2.0","method":"parity_getAccountsInfoAt","params":[[{}]],"id":1}}"#, addresses.join(","));
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: addresses","data":"\"At most 1000 accounts can be queried at once.\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc":"2.0","method":"parity_getAccountsInfoAt","params":[[]],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
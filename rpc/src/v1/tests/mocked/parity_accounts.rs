@@ -272,6 +272,54 @@ fn should_be_able_to_remove_address() {
 	assert_eq!(res, Some(response.into()));
 }
 
+#[test]
+fn should_be_able_to_set_tags_and_notes() {
+	let tester = setup();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setAccountName", "params": ["0x000baba1000baba2000baba3000baba4000baba5", "Exchange"], "id": 1}"#;
+	tester.io.handle_request_sync(request).unwrap();
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setAccountTags", "params": ["0x000baba1000baba2000baba3000baba4000baba5", ["cex"]], "id": 2}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":2}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setAccountNotes", "params": ["0x000baba1000baba2000baba3000baba4000baba5", "Deposits only"], "id": 3}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":3}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_addressBook", "params": [], "id": 4}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"0x000baba1000baba2000baba3000baba4000baba5":{"meta":"{}","name":"Exchange","notes":"Deposits only","tags":["cex"]}},"id":4}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+}
+
+#[test]
+fn should_be_able_to_add_and_remove_watch_only_address() {
+	let tester = setup();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_addWatchOnly", "params": ["0x000baba1000baba2000baba3000baba4000baba5", "Cold"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+	assert!(tester.accounts.is_watch_only(&"000baba1000baba2000baba3000baba4000baba5".parse().unwrap()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_allAccountsInfo", "params": [], "id": 2}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"0x000baba1000baba2000baba3000baba4000baba5":{"meta":"{}","name":"Cold"}},"id":2}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_watchOnlyAccounts", "params": [], "id": 3}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"0x000baba1000baba2000baba3000baba4000baba5":{"meta":"{}","name":"Cold"}},"id":3}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_removeWatchOnly", "params": ["0x000baba1000baba2000baba3000baba4000baba5"], "id": 4}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":4}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_removeWatchOnly", "params": ["0x000baba1000baba2000baba3000baba4000baba5"], "id": 5}"#;
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":5}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_watchOnlyAccounts", "params": [], "id": 6}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{},"id":6}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.into()));
+}
+
 #[test]
 fn rpc_parity_new_vault() {
 	let tempdir = TempDir::new("").unwrap();
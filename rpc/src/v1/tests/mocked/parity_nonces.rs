@@ -0,0 +1,70 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::client::TestBlockChainClient;
+use ethereum_types::Address;
+use parking_lot::Mutex;
+
+use jsonrpc_core::IoHandler;
+use jsonrpc_core::futures::Future;
+use v1::{ParityNonces, ParityNoncesClient};
+use v1::helpers::nonce;
+use v1::helpers::dispatch::FullDispatcher;
+use v1::tests::helpers::TestMinerService;
+
+fn setup() -> IoHandler {
+	let client = Arc::new(TestBlockChainClient::new());
+	let miner = Arc::new(TestMinerService::default());
+	let reservations = Arc::new(Mutex::new(nonce::Reservations::new()));
+	let dispatcher = FullDispatcher::new(client, miner, reservations, 50);
+
+	let mut io = IoHandler::default();
+	io.extend_with(ParityNoncesClient::new(dispatcher).to_delegate());
+	io
+}
+
+#[test]
+fn should_reserve_and_release_nonce() {
+	// given
+	let io = setup();
+	let from = format!("{:x}", Address::random());
+	let reserve = r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_reserveNonce",
+		"params": ["0x"#.to_owned() + &from + r#""],
+		"id": 1
+	}"#;
+	let release = |nonce: &str| r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_releaseNonce",
+		"params": ["0x"#.to_owned() + &from + r#"",""# + nonce + r#"",true],
+		"id": 1
+	}"#;
+
+	// when
+	let res = io.handle_request(&reserve).wait().unwrap();
+	assert_eq!(res, Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+	let res = io.handle_request(&release("0x0")).wait().unwrap();
+	assert_eq!(res, Some(r#"{"jsonrpc":"2.0","result":true,"id":1}"#.to_owned()));
+
+	// then
+	let res = io.handle_request(&release("0x0")).wait().unwrap();
+	assert_eq!(res, Some(r#"{"jsonrpc":"2.0","result":false,"id":1}"#.to_owned()));
+	let res = io.handle_request(&reserve).wait().unwrap();
+	assert_eq!(res, Some(r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#.to_owned()));
+}
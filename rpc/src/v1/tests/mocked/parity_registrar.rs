@@ -0,0 +1,107 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::account_provider::AccountProvider;
+use ethcore::client::TestBlockChainClient;
+use ethereum_types::{Address, U256};
+use parking_lot::Mutex;
+use registrar::{Registrar, RegistrarClient, Asynchronous};
+use transaction::Action;
+
+use jsonrpc_core::IoHandler;
+use jsonrpc_core::futures::future;
+use v1::{ParityRegistrar, ParityRegistrarClient, SigningUnsafeClient};
+use v1::helpers::nonce;
+use v1::helpers::dispatch::FullDispatcher;
+use v1::metadata::Metadata;
+use v1::tests::helpers::TestMinerService;
+
+struct FakeRegistrar {
+	address: Option<Address>,
+	fee: U256,
+}
+
+impl RegistrarClient for FakeRegistrar {
+	type Call = Asynchronous;
+
+	fn registrar_address(&self) -> Result<Address, String> {
+		self.address.ok_or_else(|| "Registrar not defined.".into())
+	}
+
+	fn call_contract(&self, _address: Address, _data: Vec<u8>) -> Self::Call {
+		let mut output = vec![0u8; 32];
+		self.fee.to_big_endian(&mut output);
+		Box::new(future::ok(output))
+	}
+}
+
+struct Tester {
+	io: IoHandler<Metadata>,
+	accounts: Arc<AccountProvider>,
+	miner: Arc<TestMinerService>,
+	registrar: Arc<FakeRegistrar>,
+}
+
+fn setup(address: Option<Address>) -> Tester {
+	let client = Arc::new(TestBlockChainClient::new());
+	let miner = Arc::new(TestMinerService::default());
+	let accounts = Arc::new(AccountProvider::transient_provider());
+	let registrar = Arc::new(FakeRegistrar { address, fee: 100.into() });
+	let reservations = Arc::new(Mutex::new(nonce::Reservations::new()));
+	let dispatcher = FullDispatcher::new(client, miner.clone(), reservations, 50);
+	let signing = SigningUnsafeClient::new(&accounts, dispatcher);
+
+	let mut io = IoHandler::default();
+	io.extend_with(ParityRegistrarClient::new(signing, registrar.clone()).to_delegate());
+
+	Tester { io, accounts, miner, registrar }
+}
+
+#[test]
+fn rpc_parity_registrar_reserve() {
+	let registrar_address = Address::from(0xab);
+	let tester = setup(Some(registrar_address));
+	let address = tester.accounts.new_account(&"".into()).unwrap();
+	tester.accounts.unlock_account_permanently(address, "".into()).unwrap();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_registrarReserve", "params": [""#.to_owned()
+		+ &format!("0x{:x}", address)
+		+ r#"", "parity"], "id": 1}"#;
+	let response = tester.io.handle_request_sync(&request).unwrap();
+
+	let imported = tester.miner.imported_transactions.lock();
+	assert_eq!(imported.len(), 1);
+	let transaction = &imported[0];
+	let expected = Registrar::new(tester.registrar.clone()).reserve("parity").unwrap();
+	assert_eq!(transaction.sender(), address);
+	assert_eq!(transaction.action, Action::Call(registrar_address));
+	assert_eq!(transaction.value, 100.into());
+	assert_eq!(transaction.data, expected.data);
+	assert_eq!(response, format!(r#"{{"jsonrpc":"2.0","result":"0x{:x}","id":1}}"#, transaction.hash()));
+}
+
+#[test]
+fn rpc_parity_registrar_without_registrar() {
+	let tester = setup(None);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_registrarConfirmReverse", "params": ["0x0000000000000000000000000000000000000001", "parity"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32075,"message":"Error while accessing the registrar contract.","data":"\"Registrar not defined.\""},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(tester.miner.imported_transactions.lock().len(), 0);
+}
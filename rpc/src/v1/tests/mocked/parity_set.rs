@@ -23,9 +23,11 @@ use ethcore::miner::MinerService;
 use ethcore::client::TestBlockChainClient;
 use sync::ManageNetwork;
 use futures_cpupool::CpuPool;
+use ethcore_logger::RotatingLogger;
 
 use jsonrpc_core::IoHandler;
 use v1::{ParitySet, ParitySetClient};
+use v1::helpers::drain::RpcDrain;
 use v1::tests::helpers::{TestMinerService, TestUpdater, TestDappsService};
 use super::manage_network::TestManageNetwork;
 
@@ -54,10 +56,21 @@ fn parity_set_client(
 	miner: &Arc<TestMinerService>,
 	updater: &Arc<TestUpdater>,
 	net: &Arc<TestManageNetwork>,
+) -> TestParitySetClient {
+	parity_set_client_with_drain(client, miner, updater, net, Arc::new(RpcDrain::default()))
+}
+
+fn parity_set_client_with_drain(
+	client: &Arc<TestBlockChainClient>,
+	miner: &Arc<TestMinerService>,
+	updater: &Arc<TestUpdater>,
+	net: &Arc<TestManageNetwork>,
+	drain: Arc<RpcDrain>,
 ) -> TestParitySetClient {
 	let dapps_service = Arc::new(TestDappsService);
 	let pool = CpuPool::new(1);
-	ParitySetClient::new(client, miner, updater, &(net.clone() as Arc<ManageNetwork>), Some(dapps_service), FakeFetch::new(Some(1)), pool)
+	let logger = Arc::new(RotatingLogger::new("rpc=trace".to_owned()));
+	ParitySetClient::new(client, miner, updater, &(net.clone() as Arc<ManageNetwork>), logger, Some(dapps_service), FakeFetch::new(Some(1)), pool, drain)
 }
 
 #[test]
@@ -98,6 +111,54 @@ fn rpc_parity_upgrade_ready() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_pending_update() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_pendingUpdate", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"delayedUntil":null,"error":null,"release":{"binary":"0x00000000000000000000000000000000000000000000000000000000000005e6","fork":15100,"is_critical":true,"version":{"hash":"0x0000000000000000000000000000000000000097","track":"beta","version":{"major":1,"minor":5,"patch":1}}},"stage":"ready"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_deferUpdate", "params": [100], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	updater.set_updated(true);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_pendingUpdate", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_deferUpdate", "params": [100], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_rollback_update() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_rollbackUpdate", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	updater.set_updated(true);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_rollbackUpdate", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_set_min_gas_price() {
 	let miner = miner_service();
@@ -240,6 +301,36 @@ fn rpc_parity_remove_transaction() {
 	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_accept_reorg() {
+	use ethcore::client::ReorgAlert;
+
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_acceptReorg", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	*client.pending_reorg.write() = Some(ReorgAlert {
+		best_number: 10,
+		best_hash: 10.into(),
+		ancestor_number: 5,
+		ancestor_hash: 5.into(),
+		new_number: 11,
+		new_hash: 11.into(),
+		depth: 5,
+		detected_at: 1_500_000_000,
+	});
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert!(client.pending_reorg.read().is_none());
+}
+
 #[test]
 fn rpc_parity_set_dapps_list() {
 	let miner = miner_service();
@@ -254,3 +345,119 @@ fn rpc_parity_set_dapps_list() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_parity_set_logging_targets_rejects_invalid_levels() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setLoggingTargets", "params":["sync=loud"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: targets","data":"\"Invalid logging directive: sync=loud\""},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_drain_rpc() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let drain = Arc::new(RpcDrain::default());
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client_with_drain(&client, &miner, &updater, &network, drain.clone()).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_drainRpc", "params":[60], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert!(drain.is_draining());
+
+	let response = r#"{"jsonrpc":"2.0","result":false,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_drain_rpc_rejects_too_long_grace_period() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let drain = Arc::new(RpcDrain::default());
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client_with_drain(&client, &miner, &updater, &network, drain.clone()).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_drainRpc", "params":[18446744073709551615], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: seconds","data":"\"Grace period is too long.\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert!(!drain.is_draining());
+}
+
+#[test]
+fn rpc_parity_token_balances() {
+	use ethcore::client::Executed;
+	use ethcore::error::CallError;
+	use serde_json;
+
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let executed = |value: u8| {
+		let mut output = vec![0u8; 32];
+		output[31] = value;
+		Ok(Executed {
+			exception: None,
+			gas: U256::zero(),
+			gas_used: U256::zero(),
+			refunded: U256::zero(),
+			cumulative_gas_used: U256::zero(),
+			logs: vec![],
+			contracts_created: vec![],
+			output,
+			trace: vec![],
+			vm_trace: None,
+			state_diff: None,
+		})
+	};
+	let request = r#"{"jsonrpc":"2.0","method":"parity_tokenBalances","params":["0x0000000000000000000000000000000000000001",["0x0000000000000000000000000000000000000005"]],"id":1}"#;
+	let balance = |io: &IoHandler| {
+		let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+		response["result"][0].clone()
+	};
+
+	// failed calls aren't cached
+	client.set_execution_result(Err(CallError::StatePruned));
+	let result = balance(&io);
+	assert_eq!(result["token"], "0x0000000000000000000000000000000000000005");
+	assert!(result["balance"].is_null());
+	assert!(result["decimals"].is_null());
+
+	// every call of the test client returns 18, which is no valid symbol
+	client.set_execution_result(executed(0x12));
+	let result = balance(&io);
+	assert_eq!(result["balance"], "0x12");
+	assert_eq!(result["decimals"], 18);
+	assert!(result["symbol"].is_null());
+
+	// the metadata is cached, the balance is not
+	client.set_execution_result(executed(0x6));
+	let result = balance(&io);
+	assert_eq!(result["balance"], "0x6");
+	assert_eq!(result["decimals"], 18);
+
+	let request = r#"{"jsonrpc":"2.0","method":"parity_tokenBalances","params":["0x0000000000000000000000000000000000000001","registry"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"The token registry is not available on this chain."},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let tokens: Vec<String> = (0..101).map(|i| format!("\"0x{:040x}\"", i)).collect();
+	let request = format!(r#"{{"jsonrpc":"2.0","method":"parity_tokenBalances","params":["0x0000000000000000000000000000000000000001",[{}]],"id":1}}"#, tokens.join(","));
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: tokens","data":"\"At most 100 tokens can be queried at once.\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+}
@@ -15,6 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use ethereum_types::{U256, Address};
 use bytes::ToPretty;
@@ -27,7 +28,9 @@ use rlp::encode;
 use transaction::{Transaction, Action, SignedTransaction};
 
 use serde_json;
-use jsonrpc_core::IoHandler;
+use tempdir::TempDir;
+use jsonrpc_core::{IoHandler, Response, Output, Value};
+use authcodes::Authorization;
 use v1::{SignerClient, Signer, Origin};
 use v1::metadata::Metadata;
 use v1::tests::helpers::TestMinerService;
@@ -56,7 +59,11 @@ fn miner_service() -> Arc<TestMinerService> {
 }
 
 fn signer_tester() -> SignerTester {
-	let signer = Arc::new(SignerService::new_test(false));
+	signer_tester_with(SignerService::new_test(false))
+}
+
+fn signer_tester_with(signer: SignerService) -> SignerTester {
+	let signer = Arc::new(signer);
 	let accounts = accounts_provider();
 	let client = blockchain_client();
 	let miner = miner_service();
@@ -105,6 +112,34 @@ fn should_return_list_of_items_to_confirm() {
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
 }
 
+#[test]
+fn should_name_addresses_resolved_from_registry_names() {
+	// given
+	let tester = signer_tester();
+	let mut names = BTreeMap::new();
+	names.insert(Address::from(2), "alice".to_owned());
+	let _send_future = tester.signer.add_named_request(ConfirmationPayload::SendTransaction(FilledTransactionRequest {
+		from: Address::from(1),
+		used_default_from: false,
+		to: Some(Address::from(2)),
+		gas_price: U256::from(10_000),
+		gas: U256::from(21_000),
+		value: U256::from(1),
+		data: vec![],
+		nonce: None,
+		condition: None,
+	}), Origin::Unknown, names).unwrap();
+
+	// when
+	let request = r#"{"jsonrpc":"2.0","method":"signer_requestsToConfirm","params":[],"id":1}"#;
+	let response: Value = serde_json::from_str(&tester.io.handle_request_sync(&request).unwrap()).unwrap();
+
+	// then
+	let names = &response["result"][0]["details"]["names"];
+	assert_eq!(names["0x0000000000000000000000000000000000000002"], "alice");
+	assert!(names.get("0x0000000000000000000000000000000000000001").is_none());
+}
+
 #[test]
 fn should_reject_transaction_from_queue_without_dispatching() {
 	// given
@@ -554,9 +589,72 @@ fn should_generate_new_token() {
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
 }
 
+#[test]
+fn should_generate_list_and_revoke_scoped_tokens() {
+	// given
+	let dir = TempDir::new("signer-tokens").unwrap();
+	let tester = signer_tester_with(SignerService::new_test(false).with_token_store(dir.path().join("authcodes")));
+
+	// when
+	let request = r#"{
+		"jsonrpc":"2.0",
+		"method":"signer_generateScopedAuthorizationToken",
+		"params":["monitoring", ["signer_requestsToConfirm"], 3600],
+		"id":1
+	}"#;
+	let response = tester.io.handle_request_sync(&request).unwrap();
+	let token = match serde_json::from_str::<Response>(&response).unwrap() {
+		Response::Single(Output::Success(ref success)) => success.result.as_str().unwrap().to_owned(),
+		_ => panic!("Expected a token."),
+	};
+	assert_eq!(token.len(), 19);
+
+	let request = r#"{"jsonrpc":"2.0","method":"signer_authorizationTokens","params":[],"id":1}"#;
+	let response = tester.io.handle_request_sync(&request).unwrap();
+	let tokens: Value = serde_json::from_str(&response).unwrap();
+	let tokens = tokens["result"].as_array().unwrap();
+	assert_eq!(tokens.len(), 1);
+	assert_eq!(tokens[0]["name"], "monitoring");
+	assert_eq!(tokens[0]["scope"][0], "signer_requestsToConfirm");
+	assert_eq!(tokens[0]["expiresAt"].as_u64(), tokens[0]["createdAt"].as_u64().map(|t| t + 3600));
+
+	let request = r#"{"jsonrpc":"2.0","method":"signer_revokeAuthorizationToken","params":["monitoring"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+	let request = r#"{"jsonrpc":"2.0","method":"signer_authorizationTokens","params":[],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
+#[test]
+fn should_not_manage_tokens_from_scoped_session() {
+	// given
+	let tester = signer_tester();
+	let mut meta = Metadata::default();
+	meta.authorization = Some(Authorization {
+		hash: Default::default(),
+		token: Some(Default::default()),
+		scope: Some(vec!["signer".into()]),
+		expires_at: None,
+	});
+
+	// when
+	let request = r#"{
+		"jsonrpc":"2.0",
+		"method":"signer_generateAuthorizationToken",
+		"params":[],
+		"id":1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32040,"message":"Scoped authorization tokens are not allowed to manage tokens."},"id":1}"#;
+
+	// then
+	assert_eq!((*tester.io).handle_request_sync(&request, meta), Some(response.to_owned()));
+}
+
 #[test]
 fn should_generate_new_web_proxy_token() {
-	use jsonrpc_core::{Response, Output, Value};
 	// given
 	let tester = signer_tester();
 
@@ -25,7 +25,7 @@ use jsonrpc_core::futures::Future;
 use v1::impls::SigningQueueClient;
 use v1::metadata::Metadata;
 use v1::traits::{EthSigning, ParitySigning, Parity};
-use v1::helpers::{nonce, SignerService, SigningQueue, FullDispatcher};
+use v1::helpers::{nonce, SignerService, SigningQueue, FullDispatcher, ApprovalRules};
 use v1::types::{ConfirmationResponse, RichRawTransaction};
 use v1::tests::helpers::TestMinerService;
 use v1::tests::mocked::parity;
@@ -52,7 +52,13 @@ struct SigningTester {
 
 impl Default for SigningTester {
 	fn default() -> Self {
-		let signer = Arc::new(SignerService::new_test(false));
+		SigningTester::new(SignerService::new_test(false))
+	}
+}
+
+impl SigningTester {
+	fn new(signer: SignerService) -> Self {
+		let signer = Arc::new(signer);
 		let client = Arc::new(TestBlockChainClient::default());
 		let miner = Arc::new(TestMinerService::default());
 		let accounts = Arc::new(AccountProvider::transient_provider());
@@ -387,6 +393,44 @@ fn should_dispatch_transaction_if_account_is_unlock() {
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
 }
 
+#[test]
+fn should_dispatch_transaction_matching_auto_approval_rules() {
+	// given
+	let rules: ApprovalRules = serde_json::from_str(r#"{"rules":[{"to":["0xd46e8dd67c5d32be8058bb8eb970870f07244567"],"maxValue":"0x9184e72a"}]}"#).unwrap();
+	let tester = SigningTester::new(SignerService::new_test(false).with_auto_approval(rules, vec!["other".into(), "test".into()]));
+	let acc = tester.accounts.new_account(&"test".into()).unwrap();
+
+	let t = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::from(0x9184e72a000u64),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(0x9184e72au64),
+		data: vec![]
+	};
+	let signature = tester.accounts.sign(acc, Some("test".into()), t.hash(None)).unwrap();
+	let t = t.with_signature(signature, None);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_sendTransaction",
+		"params": [{
+			"from": ""#.to_owned() + format!("0x{:x}", acc).as_ref() + r#"",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"gas": "0x76c0",
+			"gasPrice": "0x9184e72a000",
+			"value": "0x9184e72a"
+		}],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:x}", t.hash()).as_ref() + r#"","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+	assert_eq!(tester.signer.requests().len(), 0);
+}
+
 #[test]
 fn should_decrypt_message_if_account_is_unlocked() {
 	// given
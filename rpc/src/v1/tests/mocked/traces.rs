@@ -14,8 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use ethcore::account_diff::{AccountDiff, Diff};
+use ethcore::state_diff::StateDiff;
 use ethcore::executed::{Executed, CallError};
 use ethcore::trace::trace::{Action, Res, Call};
 use ethcore::trace::LocalizedTrace;
@@ -186,7 +189,7 @@ fn rpc_trace_call_state_pruned() {
 	*tester.client.execution_result.write() = Some(Err(CallError::StatePruned));
 
 	let request = r#"{"jsonrpc":"2.0","method":"trace_call","params":[{}, ["stateDiff", "vmTrace", "trace"]],"id":1}"#;
-	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive or increase --state-query-history."},"id":1}"#;
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -207,7 +210,35 @@ fn rpc_trace_raw_transaction_state_pruned() {
 	*tester.client.execution_result.write() = Some(Err(CallError::StatePruned));
 
 	let request = r#"{"jsonrpc":"2.0","method":"trace_rawTransaction","params":["0xf869018609184e72a0008276c094d46e8dd67c5d32be8058bb8eb970870f07244567849184e72a801ba0617f39c1a107b63302449c476d96a6cb17a5842fc98ff0c5bcf4d5c4d8166b95a009fdb6097c6196b9bbafc3a59f02f38d91baeef23d0c60a8e4f23c7714cea3a9", ["stateDiff", "vmTrace", "trace"]],"id":1}"#;
-	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive or increase --state-query-history."},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_trace_state_diff_at_block() {
+	let tester = io();
+	let mut raw = BTreeMap::new();
+	raw.insert(0xf.into(), AccountDiff {
+		balance: Diff::Changed(0x10.into(), 0xf.into()),
+		nonce: Diff::Same,
+		code: Diff::Same,
+		storage: BTreeMap::new(),
+	});
+	*tester.client.state_diffs.write() = Some(vec![(5.into(), StateDiff { raw })]);
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_stateDiffAt","params":["0x10"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000005","transactionPosition":0,"stateDiff":{"0x000000000000000000000000000000000000000f":{"balance":{"*":{"from":"0x10","to":"0xf"}},"nonce":"=","code":"=","storage":{}}}}],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_trace_state_diff_at_missing_transaction() {
+	let tester = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_stateDiffAt","params":["0x0000000000000000000000000000000000000000000000000000000000000005"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -228,7 +259,7 @@ fn rpc_trace_replay_transaction_state_pruned() {
 	*tester.client.execution_result.write() = Some(Err(CallError::StatePruned));
 
 	let request = r#"{"jsonrpc":"2.0","method":"trace_replayTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", ["trace", "stateDiff", "vmTrace"]],"id":1}"#;
-	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive or increase --state-query-history."},"id":1}"#;
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
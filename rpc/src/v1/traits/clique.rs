@@ -0,0 +1,56 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique signer voting rpc interface.
+
+use std::collections::BTreeMap;
+
+use jsonrpc_core::Result;
+use jsonrpc_macros::Trailing;
+use v1::types::{BlockNumber, CliqueSnapshot, H160, H256};
+
+build_rpc_trait! {
+	/// Clique signer voting rpc interface.
+	pub trait Clique {
+		/// Returns the signer snapshot at given block, or latest.
+		#[rpc(name = "clique_getSnapshot")]
+		fn snapshot(&self, Trailing<BlockNumber>) -> Result<Option<CliqueSnapshot>>;
+
+		/// Returns the signer snapshot at the block with given hash.
+		#[rpc(name = "clique_getSnapshotAtHash")]
+		fn snapshot_at_hash(&self, H256) -> Result<Option<CliqueSnapshot>>;
+
+		/// Returns the authorized signers at given block, or latest.
+		#[rpc(name = "clique_getSigners")]
+		fn signers(&self, Trailing<BlockNumber>) -> Result<Option<Vec<H160>>>;
+
+		/// Returns the authorized signers at the block with given hash.
+		#[rpc(name = "clique_getSignersAtHash")]
+		fn signers_at_hash(&self, H256) -> Result<Option<Vec<H160>>>;
+
+		/// Returns the accounts this node votes on, and whether to authorize them.
+		#[rpc(name = "clique_proposals")]
+		fn proposals(&self) -> Result<BTreeMap<H160, bool>>;
+
+		/// Starts voting on authorizing or deauthorizing given account in the sealed blocks.
+		#[rpc(name = "clique_propose")]
+		fn propose(&self, H160, bool) -> Result<bool>;
+
+		/// Stops voting on given account. Returns whether there was such a proposal.
+		#[rpc(name = "clique_discard")]
+		fn discard(&self, H160) -> Result<bool>;
+	}
+}
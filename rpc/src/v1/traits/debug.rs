@@ -0,0 +1,55 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Debug-specific rpc interface.
+
+use jsonrpc_core::Result;
+use jsonrpc_macros::Trailing;
+use v1::types::{BlockNumber, CallRequest, CallProfile, H160, H256, TracerOptions, TracerResult, AccountRange, StorageRange};
+
+build_rpc_trait! {
+	/// Debug-specific rpc interface.
+	pub trait Debug {
+		type Metadata;
+
+		/// Executes the given call and returns gas and time spent, aggregated by opcode and call frame.
+		#[rpc(meta, name = "debug_profileCall")]
+		fn profile_call(&self, Self::Metadata, CallRequest, Trailing<BlockNumber>) -> Result<CallProfile>;
+
+		/// Re-executes the given transaction on top of its block's preceding transactions and
+		/// returns gas and time spent, aggregated by opcode and call frame.
+		#[rpc(name = "debug_profileTransaction")]
+		fn profile_transaction(&self, H256) -> Result<Option<CallProfile>>;
+
+		/// Executes the given call on top of given block and returns the output of selected tracer.
+		#[rpc(meta, name = "debug_traceCall")]
+		fn trace_call(&self, Self::Metadata, CallRequest, BlockNumber, TracerOptions) -> Result<TracerResult>;
+
+		/// Re-executes the given transaction and returns the output of selected tracer.
+		#[rpc(name = "debug_traceTransaction")]
+		fn trace_transaction(&self, H256, TracerOptions) -> Result<Option<TracerResult>>;
+
+		/// Returns up to the given number of accounts of the state at given block, ordered by
+		/// the hash of their address and starting at the given address hash.
+		#[rpc(name = "debug_accountRange")]
+		fn account_range(&self, BlockNumber, H256, u64) -> Result<AccountRange>;
+
+		/// Returns up to the given number of storage slots of the given account at given block,
+		/// ordered by the hash of their key and starting at the given key hash.
+		#[rpc(name = "debug_storageRangeAt")]
+		fn storage_range_at(&self, BlockNumber, H160, H256, u64) -> Result<StorageRange>;
+	}
+}
@@ -19,7 +19,7 @@ use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_macros::Trailing;
 
 use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Index};
-use v1::types::{Log, Receipt, SyncStatus, Transaction, Work};
+use v1::types::{Log, Receipt, SyncStatus, Transaction, Work, EthAccount};
 use v1::types::{H64, H160, H256, U256};
 
 build_rpc_trait! {
@@ -99,6 +99,10 @@ build_rpc_trait! {
 		#[rpc(name = "eth_getCode")]
 		fn code_at(&self, H160, Trailing<BlockNumber>) -> BoxFuture<Bytes>;
 
+		/// Returns the account and the given storage slots with merkle proofs at given time (block number).
+		#[rpc(name = "eth_getProof")]
+		fn proof(&self, H160, Vec<U256>, Trailing<BlockNumber>) -> BoxFuture<EthAccount>;
+
 		/// Sends signed transaction, returning its hash.
 		#[rpc(name = "eth_sendRawTransaction")]
 		fn send_raw_transaction(&self, Bytes) -> Result<H256>;
@@ -0,0 +1,33 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dev chain block sealing rpc interface.
+
+use jsonrpc_core::Result;
+
+build_rpc_trait! {
+	/// Dev chain block sealing rpc interface, compatible with Ethereum testing frameworks.
+	pub trait Evm {
+		/// Seals a new block, even if there are no transactions to include.
+		#[rpc(name = "evm_mine")]
+		fn mine(&self) -> Result<String>;
+
+		/// Moves the timestamps of new blocks forward by the given number of seconds.
+		/// Returns the total number of seconds added so far.
+		#[rpc(name = "evm_increaseTime")]
+		fn increase_time(&self, u64) -> Result<u64>;
+	}
+}
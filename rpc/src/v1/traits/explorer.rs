@@ -0,0 +1,45 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Explorer rpc interface.
+
+use jsonrpc_core::Result;
+use jsonrpc_macros::Trailing;
+use v1::types::{AddressTransactionsOptions, BlockNumber, ExplorerBlock, ExplorerTransaction, H160, H256, Transaction};
+
+build_rpc_trait! {
+	/// Compact queries for block explorers.
+	pub trait Explorer {
+		/// Returns summaries of up to given number of blocks, starting at given block (or latest)
+		/// and going back towards genesis.
+		#[rpc(name = "explorer_blockList")]
+		fn block_list(&self, u64, Trailing<BlockNumber>) -> Result<Vec<ExplorerBlock>>;
+
+		/// Returns up to given number of transactions sent from or to given address, newest first,
+		/// skipping given number of transactions. Requires the explorer index.
+		#[rpc(name = "explorer_addressHistory")]
+		fn address_history(&self, H160, u64, Trailing<u64>) -> Result<Vec<Transaction>>;
+
+		/// Returns the transactions sent from or to given address, newest first, filtered and paged
+		/// with given options. Requires the explorer index.
+		#[rpc(name = "parity_getTransactionsByAddress")]
+		fn transactions_by_address(&self, H160, Trailing<AddressTransactionsOptions>) -> Result<Vec<Transaction>>;
+
+		/// Returns the transaction with given hash, with its receipt and block.
+		#[rpc(name = "explorer_txDetails")]
+		fn transaction_details(&self, H256) -> Result<Option<ExplorerTransaction>>;
+	}
+}
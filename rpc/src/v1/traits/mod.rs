@@ -17,12 +17,18 @@
 //! Ethereum rpc interfaces.
 
 pub mod web3;
+pub mod clique;
+pub mod debug;
 pub mod eth;
 pub mod eth_pubsub;
 pub mod eth_signing;
+pub mod evm;
+pub mod explorer;
 pub mod net;
 pub mod parity;
 pub mod parity_accounts;
+pub mod parity_nonces;
+pub mod parity_registrar;
 pub mod parity_set;
 pub mod parity_signing;
 pub mod personal;
@@ -34,12 +40,18 @@ pub mod secretstore;
 pub mod private;
 
 pub use self::web3::Web3;
+pub use self::clique::Clique;
+pub use self::debug::Debug;
 pub use self::eth::{Eth, EthFilter};
 pub use self::eth_pubsub::EthPubSub;
 pub use self::eth_signing::EthSigning;
+pub use self::evm::Evm;
+pub use self::explorer::Explorer;
 pub use self::net::Net;
 pub use self::parity::Parity;
 pub use self::parity_accounts::ParityAccounts;
+pub use self::parity_nonces::ParityNonces;
+pub use self::parity_registrar::ParityRegistrar;
 pub use self::parity_set::ParitySet;
 pub use self::parity_signing::ParitySigning;
 pub use self::personal::Personal;
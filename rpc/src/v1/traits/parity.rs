@@ -23,12 +23,13 @@ use jsonrpc_macros::Trailing;
 
 use node_health::Health;
 use v1::types::{
-	H160, H256, H512, U256, U64, Bytes, CallRequest,
-	Peers, Transaction, RpcSettings, Histogram,
+	H160, H256, H512, U256, U64, Bytes, CallRequest, CallBundleResult, GasEstimate, TransactionPreview,
+	Peers, PeerPermission, Transaction, RpcSettings, Histogram,
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader,
+	AccountInfo, HwAccountInfo, AccountStateInfo, RichHeader, ForkStatus, ReorgAlert,
+	ValidatorSetInfo, MisbehaviorReport, FinalityStatus, LogRecord,
 };
 
 build_rpc_trait! {
@@ -80,6 +81,16 @@ build_rpc_trait! {
 		#[rpc(name = "parity_devLogsLevels")]
 		fn dev_logs_levels(&self) -> Result<String>;
 
+		/// Returns the logging filter currently in effect.
+		#[rpc(name = "parity_getLogging")]
+		fn get_logging(&self) -> Result<String>;
+
+		/// Returns recent log records, latest first. Optionally filtered by minimal severity
+		/// (e.g. `"warn"`), target (including its submodules) and a maximal count (0 or omitted means 100).
+		/// Can be streamed with `parity_subscribe("parity_recentLogs", ...)`.
+		#[rpc(name = "parity_recentLogs")]
+		fn recent_logs(&self, Option<String>, Option<String>, Trailing<usize>) -> Result<Vec<LogRecord>>;
+
 		/// Returns chain name - DEPRECATED. Use `parity_chainName` instead.
 		#[rpc(name = "parity_netChain")]
 		fn net_chain(&self) -> Result<String>;
@@ -88,6 +99,11 @@ build_rpc_trait! {
 		#[rpc(name = "parity_netPeers")]
 		fn net_peers(&self) -> Result<Peers>;
 
+		/// Returns the decisions of the node permissions filter made at the latest block,
+		/// including local overrides. Empty if the chain doesn't use a node permissions contract.
+		#[rpc(name = "parity_netPeerPermissions")]
+		fn net_peer_permissions(&self) -> Result<Vec<PeerPermission>>;
+
 		/// Returns network port
 		#[rpc(name = "parity_netPort")]
 		fn net_port(&self) -> Result<u16>;
@@ -134,6 +150,11 @@ build_rpc_trait! {
 		#[rpc(name = "parity_listStorageKeys")]
 		fn list_storage_keys(&self, H160, u64, Option<H256>, Trailing<BlockNumber>) -> Result<Option<Vec<H256>>>;
 
+		/// Returns balance, nonce and presence of code of each given address,
+		/// all read from the state at the given block. At most 1000 addresses can be given.
+		#[rpc(name = "parity_getAccountsInfoAt")]
+		fn accounts_info_at(&self, Vec<H160>, Trailing<BlockNumber>) -> Result<Vec<AccountStateInfo>>;
+
 		/// Encrypt some data with a public key under ECIES.
 		/// First parameter is the 512-byte destination public key, second is the message.
 		#[rpc(name = "parity_encryptMessage")]
@@ -207,6 +228,14 @@ build_rpc_trait! {
 		#[rpc(name = "parity_chainStatus")]
 		fn chain_status(&self) -> Result<ChainStatus>;
 
+		/// Get competing branches detected on the network.
+		#[rpc(name = "parity_forkStatus")]
+		fn fork_status(&self) -> Result<ForkStatus>;
+
+		/// Get the reorganization which halted block import, if any.
+		#[rpc(name = "parity_pendingReorg")]
+		fn pending_reorg(&self) -> Result<Option<ReorgAlert>>;
+
 		/// Get node kind info.
 		#[rpc(name = "parity_nodeKind")]
 		fn node_kind(&self) -> Result<::v1::types::NodeKind>;
@@ -224,8 +253,42 @@ build_rpc_trait! {
 		#[rpc(meta, name = "parity_call")]
 		fn call(&self, Self::Metadata, Vec<CallRequest>, Trailing<BlockNumber>) -> Result<Vec<Bytes>>;
 
+		/// Estimates gas of a transaction and splits it into intrinsic, execution and code deposit gas.
+		/// Warns if the transaction is too large to be practically included in a block.
+		#[rpc(meta, name = "parity_estimateGasDetailed")]
+		fn estimate_gas_detailed(&self, Self::Metadata, CallRequest, Trailing<BlockNumber>) -> Result<GasEstimate>;
+
+		/// Executes the given signed raw transactions one after another on top of the given block,
+		/// without broadcasting them, and returns the outcome of each of them.
+		#[rpc(name = "parity_callBundle")]
+		fn call_bundle(&self, Vec<Bytes>, Trailing<BlockNumber>) -> Result<Vec<CallBundleResult>>;
+
+		/// Decodes the given signed raw transaction and executes it on top of the latest block,
+		/// without broadcasting it. The chain id, the sender's nonce and balance are checked
+		/// as for a transaction included in the next block. Returns the decoded transaction
+		/// along with its outcome and the balances it changes.
+		#[rpc(name = "parity_previewTransaction")]
+		fn preview_transaction(&self, Bytes) -> Result<TransactionPreview>;
+
 		/// Returns node's health report.
 		#[rpc(name = "parity_nodeHealth")]
 		fn node_health(&self) -> BoxFuture<Health>;
+
+		/// Returns the difference between the local clock and NTP time in milliseconds.
+		#[rpc(name = "parity_timeDrift")]
+		fn time_drift(&self) -> BoxFuture<i64>;
+
+		/// Returns the validators of the block after the given one, along with
+		/// validator set changes signalled but not finalized yet.
+		#[rpc(name = "parity_validatorSet")]
+		fn validator_set(&self, Trailing<BlockNumber>) -> Result<Option<ValidatorSetInfo>>;
+
+		/// Returns validator misbehaviour reported by this node, oldest first.
+		#[rpc(name = "parity_validatorMisbehaviorReports")]
+		fn validator_misbehavior_reports(&self) -> Result<Vec<MisbehaviorReport>>;
+
+		/// Returns the best block and the latest block considered final by the consensus engine.
+		#[rpc(name = "parity_finalityStatus")]
+		fn finality_status(&self) -> Result<FinalityStatus>;
 	}
 }
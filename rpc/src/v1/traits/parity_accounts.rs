@@ -20,7 +20,7 @@ use std::collections::BTreeMap;
 use jsonrpc_core::Result;
 use ethkey::Password;
 use ethstore::KeyFile;
-use v1::types::{H160, H256, H520, DappId, DeriveHash, DeriveHierarchical, ExtAccountInfo};
+use v1::types::{H160, H256, H520, DappId, DeriveHash, DeriveHierarchical, ExtAccountInfo, WatchOnlyAccount};
 
 build_rpc_trait! {
 	/// Personal Parity rpc interface.
@@ -29,6 +29,10 @@ build_rpc_trait! {
 		#[rpc(name = "parity_allAccountsInfo")]
 		fn all_accounts_info(&self) -> Result<BTreeMap<H160, ExtAccountInfo>>;
 
+		/// Returns address book entries.
+		#[rpc(name = "parity_addressBook")]
+		fn address_book(&self) -> Result<BTreeMap<H160, ExtAccountInfo>>;
+
 		/// Creates new account from the given phrase using standard brainwallet mechanism.
 		/// Second parameter is password for the new account.
 		#[rpc(name = "parity_newAccountFromPhrase")]
@@ -64,10 +68,33 @@ build_rpc_trait! {
 		#[rpc(name = "parity_removeAddress")]
 		fn remove_address(&self, H160) -> Result<bool>;
 
+		/// Adds an address which is tracked like an account but can't sign.
+		/// Arguments: `address`, `name`.
+		#[rpc(name = "parity_addWatchOnly")]
+		fn add_watch_only(&self, H160, String) -> Result<bool>;
+
+		/// Stops tracking a watch-only address.
+		/// Arguments: `address`
+		#[rpc(name = "parity_removeWatchOnly")]
+		fn remove_watch_only(&self, H160) -> Result<bool>;
+
+		/// Returns watch-only addresses. Their balance and nonce changes
+		/// are notified to `watchOnlyAccounts` subscribers of `eth_subscribe`.
+		#[rpc(name = "parity_watchOnlyAccounts")]
+		fn watch_only_accounts(&self) -> Result<BTreeMap<H160, WatchOnlyAccount>>;
+
 		/// Set an account's name.
 		#[rpc(name = "parity_setAccountName")]
 		fn set_account_name(&self, H160, String) -> Result<bool>;
 
+		/// Set tags of an account or address book entry.
+		#[rpc(name = "parity_setAccountTags")]
+		fn set_account_tags(&self, H160, Vec<String>) -> Result<bool>;
+
+		/// Set notes of an account or address book entry.
+		#[rpc(name = "parity_setAccountNotes")]
+		fn set_account_notes(&self, H160, String) -> Result<bool>;
+
 		/// Set an account's metadata string.
 		#[rpc(name = "parity_setAccountMeta")]
 		fn set_account_meta(&self, H160, String) -> Result<bool>;
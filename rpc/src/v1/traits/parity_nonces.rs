@@ -0,0 +1,38 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parity-specific rpc interface for reserving nonces of transactions signed externally.
+
+use jsonrpc_core::{BoxFuture, Result};
+
+use v1::types::{H160, U256};
+
+build_rpc_trait! {
+	/// Parity-specific rpc interface for reserving nonces of transactions signed externally.
+	pub trait ParityNonces {
+		/// Reserves the next nonce of an account for a transaction signed externally.
+		/// Resolves once all nonces reserved earlier for this account are released.
+		/// Nonces that are not released within a minute are released as unused.
+		#[rpc(name = "parity_reserveNonce")]
+		fn reserve_nonce(&self, H160) -> BoxFuture<U256>;
+
+		/// Releases a nonce reserved with `parity_reserveNonce`.
+		/// The last parameter tells whether a transaction with this nonce was sent.
+		/// Returns false if the nonce was not reserved.
+		#[rpc(name = "parity_releaseNonce")]
+		fn release_nonce(&self, H160, U256, bool) -> Result<bool>;
+	}
+}
@@ -0,0 +1,46 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parity-specific rpc interface for updating the registrar contract.
+
+use jsonrpc_core::BoxFuture;
+
+use v1::types::{H160, H256};
+
+build_rpc_trait! {
+	/// Parity-specific rpc interface for updating the registrar contract.
+	/// Transactions are sent from the given account like `eth_sendTransaction`,
+	/// so they go through the signer queue if it's enabled.
+	pub trait ParityRegistrar {
+		type Metadata;
+
+		/// Reserves a name, paying the registrar fee. Returns the transaction hash.
+		#[rpc(meta, name = "parity_registrarReserve")]
+		fn reserve(&self, Self::Metadata, H160, String) -> BoxFuture<H256>;
+
+		/// Points a reserved name to an address. Returns the transaction hash.
+		#[rpc(meta, name = "parity_registrarSetAddress")]
+		fn set_address(&self, Self::Metadata, H160, String, H160) -> BoxFuture<H256>;
+
+		/// Proposes a name as the reverse entry of an address. Returns the transaction hash.
+		#[rpc(meta, name = "parity_registrarProposeReverse")]
+		fn propose_reverse(&self, Self::Metadata, H160, String, H160) -> BoxFuture<H256>;
+
+		/// Confirms the reverse entry proposed for the sending account. Returns the transaction hash.
+		#[rpc(meta, name = "parity_registrarConfirmReverse")]
+		fn confirm_reverse(&self, Self::Metadata, H160, String) -> BoxFuture<H256>;
+	}
+}
@@ -18,7 +18,9 @@
 
 use jsonrpc_core::{BoxFuture, Result};
 
-use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
+use jsonrpc_macros::Trailing;
+
+use v1::types::{BlockNumber, Bytes, H160, H256, U256, ReleaseInfo, PendingUpdate, Transaction, LocalDapp, TokenList, TokenBalance};
 
 build_rpc_trait! {
 	/// Parity-specific rpc interface for operations altering the settings.
@@ -111,6 +113,20 @@ build_rpc_trait! {
 		#[rpc(name = "parity_executeUpgrade")]
 		fn execute_upgrade(&self) -> Result<bool>;
 
+		/// Returns the update which is currently being prepared, if any.
+		#[rpc(name = "parity_pendingUpdate")]
+		fn pending_update(&self) -> Result<Option<PendingUpdate>>;
+
+		/// Postpones the pending update by the given number of blocks.
+		/// Returns `false` if there is no pending update.
+		#[rpc(name = "parity_deferUpdate")]
+		fn defer_update(&self, u64) -> Result<bool>;
+
+		/// Switches back to the previously installed release and restarts.
+		/// Returns `false` if there is no previous release.
+		#[rpc(name = "parity_rollbackUpdate")]
+		fn rollback_update(&self) -> Result<bool>;
+
 		/// Removes transaction from transaction queue.
 		/// Makes sense only for transactions that were not propagated to other peers yet
 		/// like scheduled transactions or transactions in future.
@@ -119,5 +135,39 @@ build_rpc_trait! {
 		/// Returns `true` when transaction was removed, `false` if it was not found.
 		#[rpc(name = "parity_removeTransaction")]
 		fn remove_transaction(&self, H256) -> Result<Option<Transaction>>;
+
+		/// Accepts the reorganization which exceeded the maximal reorganization depth
+		/// and resumes block import.
+		/// Returns `false` if block import is not halted.
+		#[rpc(name = "parity_acceptReorg")]
+		fn accept_reorg(&self) -> Result<bool>;
+
+		/// Replaces the logging filter at runtime, e.g. with `sync=trace,miner=debug`.
+		/// Uses the same syntax as `--logging`.
+		#[rpc(name = "parity_setLoggingTargets")]
+		fn set_logging_targets(&self, String) -> Result<bool>;
+
+		/// Stops accepting new HTTP and WebSockets requests and shuts both servers down
+		/// after the given number of seconds. Open sessions are served until then.
+		/// Returns `false` if the servers are already draining.
+		#[rpc(name = "parity_drainRpc")]
+		fn drain_rpc(&self, u64) -> Result<bool>;
+
+		/// Re-executes the given block and returns every state trie node and code it reads from
+		/// the state of its parent.
+		#[rpc(name = "parity_blockWitness")]
+		fn block_witness(&self, BlockNumber) -> Result<Vec<Bytes>>;
+
+		/// Verifies the given encoded block, whose parent has to be imported, and re-executes it
+		/// using only the state items of the given witness.
+		/// Returns whether the resulting state, receipts and gas used match the block.
+		#[rpc(name = "parity_verifyBlockWitness")]
+		fn verify_block_witness(&self, Bytes, Vec<Bytes>) -> Result<bool>;
+
+		/// Returns the balances of given address in the given ERC-20 tokens, or in the first 100 tokens
+		/// of the token registry, all read from the state at the given block. At most 100 tokens
+		/// can be given.
+		#[rpc(name = "parity_tokenBalances")]
+		fn token_balances(&self, H160, TokenList, Trailing<BlockNumber>) -> Result<Vec<TokenBalance>>;
 	}
 }
@@ -19,7 +19,7 @@
 use jsonrpc_core::Error;
 
 use v1::types::{Bytes, PrivateTransactionReceipt, H160, H256, U256, BlockNumber,
-	PrivateTransactionReceiptAndTransaction, CallRequest};
+	PrivateTransactionReceiptAndTransaction, PrivateTransactionStatus, CallRequest};
 
 build_rpc_trait! {
 	/// Private transaction management RPC interface.
@@ -34,10 +34,22 @@ build_rpc_trait! {
 		#[rpc(name = "private_composeDeploymentTransaction")]
 		fn compose_deployment_transaction(&self, BlockNumber, Bytes, Vec<H160>, U256) -> Result<PrivateTransactionReceiptAndTransaction, Error>;
 
+		/// Creates a transaction for deployment of a successor contract with the same code and state,
+		/// validated by the new set of validators. Must be sent by one of the current validators.
+		/// Once the successor is mined, the old contract is retired by sending (`private_sendTransaction`)
+		/// a private transaction to it with the data `"parity-private-contract-retired!" ++ successor address`.
+		#[rpc(name = "private_rotateValidators")]
+		fn rotate_validators(&self, BlockNumber, H160, H160, Vec<H160>, U256) -> Result<PrivateTransactionReceiptAndTransaction, Error>;
+
 		/// Make a call to the private contract
 		#[rpc(meta, name = "private_call")]
 		fn private_call(&self, Self::Metadata, BlockNumber, CallRequest) -> Result<Bytes, Error>;
 
+		/// Returns status of private transaction sent by this node: collected signatures,
+		/// missing validators and processing errors.
+		#[rpc(name = "private_transactionStatus")]
+		fn transaction_status(&self, H256) -> Result<Option<PrivateTransactionStatus>, Error>;
+
 		/// Retrieve the id of the key associated with the contract
 		#[rpc(name = "private_contractKey")]
 		fn private_contract_key(&self, H160) -> Result<H256, Error>;
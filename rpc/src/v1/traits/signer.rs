@@ -19,7 +19,7 @@ use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_pubsub::SubscriptionId;
 use jsonrpc_macros::pubsub::Subscriber;
 
-use v1::types::{U256, Bytes, TransactionModification, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken};
+use v1::types::{U256, Bytes, TransactionModification, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken, AuthorizationToken};
 
 build_rpc_trait! {
 	/// Signer extension for confirmations rpc interface.
@@ -47,8 +47,20 @@ build_rpc_trait! {
 		fn reject_request(&self, U256) -> Result<bool>;
 
 		/// Generates new authorization token.
-		#[rpc(name = "signer_generateAuthorizationToken")]
-		fn generate_token(&self) -> Result<String>;
+		#[rpc(meta, name = "signer_generateAuthorizationToken")]
+		fn generate_token(&self, Self::Metadata) -> Result<String>;
+
+		/// Generates new named authorization token limited to given methods or namespaces and valid for given number of seconds.
+		#[rpc(meta, name = "signer_generateScopedAuthorizationToken")]
+		fn generate_scoped_token(&self, Self::Metadata, Option<String>, Option<Vec<String>>, Option<u64>) -> Result<String>;
+
+		/// Returns a list of existing authorization tokens.
+		#[rpc(meta, name = "signer_authorizationTokens")]
+		fn authorization_tokens(&self, Self::Metadata) -> Result<Vec<AuthorizationToken>>;
+
+		/// Revokes authorization tokens with given id or name.
+		#[rpc(meta, name = "signer_revokeAuthorizationToken")]
+		fn revoke_token(&self, Self::Metadata, String) -> Result<bool>;
 
 		/// Generates new web proxy access token for particular domain.
 		#[rpc(name = "signer_generateWebProxyAccessToken")]
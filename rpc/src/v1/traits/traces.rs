@@ -18,7 +18,7 @@
 
 use jsonrpc_core::Result;
 use jsonrpc_macros::Trailing;
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, H256, TraceOptions};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, H256, TraceOptions, StateDiffTarget, TransactionStateDiff};
 
 build_rpc_trait! {
 	/// Traces specific rpc interface.
@@ -41,6 +41,10 @@ build_rpc_trait! {
 		#[rpc(name = "trace_block")]
 		fn block_traces(&self, BlockNumber) -> Result<Option<Vec<LocalizedTrace>>>;
 
+		/// Returns state diffs recorded for given block or transaction.
+		#[rpc(name = "trace_stateDiffAt")]
+		fn state_diff_at(&self, StateDiffTarget) -> Result<Option<Vec<TransactionStateDiff>>>;
+
 		/// Executes the given call and returns a number of possible traces for it.
 		#[rpc(meta, name = "trace_call")]
 		fn call(&self, Self::Metadata, CallRequest, TraceOptions, Trailing<BlockNumber>) -> Result<TraceResults>;
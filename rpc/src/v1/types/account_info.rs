@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use v1::types::{H160, H256, U256, Bytes};
+
 /// Account information.
 #[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct AccountInfo {
@@ -31,6 +33,12 @@ pub struct ExtAccountInfo {
 	/// Account UUID (`None` for address book entries)
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub uuid: Option<String>,
+	/// Account tags
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub tags: Vec<String>,
+	/// Account notes
+	#[serde(skip_serializing_if = "String::is_empty")]
+	pub notes: String,
 }
 
 /// Hardware wallet information.
@@ -41,3 +49,57 @@ pub struct HwAccountInfo {
 	/// Device manufacturer.
 	pub manufacturer: String,
 }
+
+/// Watch-only account information (used by `parity_watchOnlyAccounts`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct WatchOnlyAccount {
+	/// Account name
+	pub name: String,
+	/// Account meta JSON
+	pub meta: String,
+}
+
+/// Account state summary (used by `parity_getAccountsInfoAt` and `watchOnlyAccounts` subscriptions).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateInfo {
+	/// Account address
+	pub address: H160,
+	/// Account balance
+	pub balance: U256,
+	/// Account nonce
+	pub nonce: U256,
+	/// Whether the account has code
+	pub has_code: bool,
+}
+
+/// Account state with merkle proofs (used by `eth_getProof`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthAccount {
+	/// Account address
+	pub address: H160,
+	/// Account balance
+	pub balance: U256,
+	/// Account nonce
+	pub nonce: U256,
+	/// Hash of the account code
+	pub code_hash: H256,
+	/// Root of the account storage trie
+	pub storage_hash: H256,
+	/// Trie nodes from the state root to the account, in order from the root
+	pub account_proof: Vec<Bytes>,
+	/// Proofs of the requested storage slots
+	pub storage_proof: Vec<StorageProof>,
+}
+
+/// Storage slot value with a merkle proof.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct StorageProof {
+	/// Storage key
+	pub key: U256,
+	/// Storage value
+	pub value: U256,
+	/// Trie nodes from the storage root to the slot, in order from the root
+	pub proof: Vec<Bytes>,
+}
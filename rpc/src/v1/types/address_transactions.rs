@@ -0,0 +1,84 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Options of `parity_getTransactionsByAddress`.
+
+use v1::types::{BlockNumber, U64};
+
+/// Role of the queried address in the returned transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum TransactionDirection {
+	/// Transactions sent by the address
+	#[serde(rename="sent")]
+	Sent,
+	/// Transactions sent to the address
+	#[serde(rename="received")]
+	Received,
+}
+
+/// Position of a transaction in the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionPosition {
+	/// Number of the including block
+	#[serde(rename="blockNumber")]
+	pub block_number: U64,
+	/// Index of the transaction in the block
+	#[serde(rename="transactionIndex")]
+	pub transaction_index: U64,
+}
+
+/// Filter and page of the transactions of an address.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddressTransactionsOptions {
+	/// Only sent or only received transactions; both if omitted
+	pub direction: Option<TransactionDirection>,
+	/// First block of the range (inclusive)
+	#[serde(rename="fromBlock")]
+	pub from_block: Option<BlockNumber>,
+	/// Last block of the range (inclusive)
+	#[serde(rename="toBlock")]
+	pub to_block: Option<BlockNumber>,
+	/// Position of the last transaction of the previous page; the page continues with older transactions
+	pub after: Option<TransactionPosition>,
+	/// Maximal number of transactions to return
+	pub limit: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use v1::types::BlockNumber;
+	use super::{AddressTransactionsOptions, TransactionDirection, TransactionPosition};
+
+	#[test]
+	fn should_deserialize_options() {
+		let s = r#"{"direction":"received","fromBlock":"0x10","after":{"blockNumber":"0x20","transactionIndex":"0x1"},"limit":20}"#;
+		let deserialized: AddressTransactionsOptions = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, AddressTransactionsOptions {
+			direction: Some(TransactionDirection::Received),
+			from_block: Some(BlockNumber::Num(16)),
+			to_block: None,
+			after: Some(TransactionPosition {
+				block_number: 0x20u64.into(),
+				transaction_index: 1u64.into(),
+			}),
+			limit: Some(20),
+		});
+		assert!(serde_json::from_str::<AddressTransactionsOptions>(r#"{"direction":"both"}"#).is_err());
+	}
+}
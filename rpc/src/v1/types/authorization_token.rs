@@ -0,0 +1,72 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer authorization token information.
+
+use authcodes::TokenInfo;
+
+/// Signer authorization token (used by `signer_authorizationTokens`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationToken {
+	/// Token identifier (not the secret itself)
+	pub id: String,
+	/// Token name
+	pub name: Option<String>,
+	/// Methods or namespaces the token is limited to (`None` for unrestricted)
+	pub scope: Option<Vec<String>>,
+	/// Creation timestamp
+	pub created_at: u64,
+	/// Last use timestamp
+	pub last_used_at: Option<u64>,
+	/// Expiry timestamp
+	pub expires_at: Option<u64>,
+}
+
+impl From<TokenInfo> for AuthorizationToken {
+	fn from(info: TokenInfo) -> Self {
+		AuthorizationToken {
+			id: info.id,
+			name: info.name,
+			scope: info.scope,
+			created_at: info.created_at,
+			last_used_at: info.last_used_at,
+			expires_at: info.expires_at,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::AuthorizationToken;
+
+	#[test]
+	fn authorization_token_serialization() {
+		let token = AuthorizationToken {
+			id: "0a1b2c3d".into(),
+			name: Some("monitoring".into()),
+			scope: Some(vec!["signer_requestsToConfirm".into()]),
+			created_at: 10,
+			last_used_at: None,
+			expires_at: Some(3610),
+		};
+		assert_eq!(
+			serde_json::to_string(&token).unwrap(),
+			r#"{"id":"0a1b2c3d","name":"monitoring","scope":["signer_requestsToConfirm"],"createdAt":10,"lastUsedAt":null,"expiresAt":3610}"#
+		);
+	}
+}
@@ -0,0 +1,56 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethcore::executed::Executed;
+use ethereum_types::H256 as EthH256;
+use v1::types::{Log, H256, U256, U64, Bytes};
+
+/// Outcome of a single transaction simulated as part of a bundle.
+#[derive(Debug, Serialize)]
+pub struct CallBundleResult {
+	/// Transaction Hash
+	#[serde(rename="transactionHash")]
+	pub transaction_hash: H256,
+	/// Gas used
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+	/// Logs
+	pub logs: Vec<Log>,
+	/// Output
+	pub output: Bytes,
+	/// Status code
+	#[serde(rename="status")]
+	pub status_code: U64,
+	/// Exception that caused the transaction to fail, if any
+	pub error: Option<String>,
+	/// Reason given for reverting the transaction, if any
+	#[serde(rename="revertReason")]
+	pub revert_reason: Option<String>,
+}
+
+impl From<(EthH256, Executed)> for CallBundleResult {
+	fn from((hash, executed): (EthH256, Executed)) -> Self {
+		CallBundleResult {
+			transaction_hash: hash.into(),
+			gas_used: executed.gas_used.into(),
+			revert_reason: executed.revert_reason(),
+			status_code: if executed.exception.is_none() { 1u64 } else { 0u64 }.into(),
+			error: executed.exception.map(|e| e.to_string()),
+			logs: executed.logs.into_iter().map(Into::into).collect(),
+			output: executed.output.into(),
+		}
+	}
+}
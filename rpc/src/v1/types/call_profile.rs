@@ -0,0 +1,144 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use ethcore::trace::{Profile, op_name};
+use ethereum_types::U256 as EthU256;
+use v1::types::U256;
+
+/// Gas and wall-clock time spent executing a transaction, aggregated by opcode and call frame.
+///
+/// Frames are named after the instruction that entered them (e.g. `root;CALL@7`)
+/// and the costs of a CALL/CREATE exclude what was spent inside the frame it opened.
+#[derive(Debug, Serialize)]
+pub struct CallProfile {
+	/// Total gas used by the executed code.
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+	/// Wall-clock time of the whole execution in microseconds.
+	#[serde(rename="time")]
+	pub time: u64,
+	/// Costs by opcode.
+	#[serde(rename="opcodes")]
+	pub opcodes: Vec<OpcodeProfile>,
+	/// Costs by call frame.
+	#[serde(rename="frames")]
+	pub frames: Vec<FrameProfile>,
+	/// Gas by frame and opcode as collapsed stacks, ready for flamegraph tools.
+	#[serde(rename="folded")]
+	pub folded: Vec<String>,
+}
+
+/// Costs of a single opcode.
+#[derive(Debug, Serialize)]
+pub struct OpcodeProfile {
+	/// Opcode
+	#[serde(rename="op")]
+	pub op: u8,
+	/// Opcode name
+	#[serde(rename="opName")]
+	pub op_name: String,
+	/// Number of executions
+	#[serde(rename="count")]
+	pub count: usize,
+	/// Gas spent
+	#[serde(rename="gas")]
+	pub gas: U256,
+	/// Wall-clock time spent in nanoseconds
+	#[serde(rename="time")]
+	pub time: u64,
+}
+
+/// Costs of a single call frame.
+#[derive(Debug, Serialize)]
+pub struct FrameProfile {
+	/// Frame path
+	#[serde(rename="frame")]
+	pub frame: String,
+	/// Number of executed instructions
+	#[serde(rename="count")]
+	pub count: usize,
+	/// Gas spent
+	#[serde(rename="gas")]
+	pub gas: U256,
+	/// Wall-clock time spent in nanoseconds
+	#[serde(rename="time")]
+	pub time: u64,
+}
+
+fn as_nanos(time: &Duration) -> u64 {
+	time.as_secs() * 1_000_000_000 + time.subsec_nanos() as u64
+}
+
+impl CallProfile {
+	/// Builds the response out of the collected profile and the time the whole execution took.
+	pub fn new(profile: Profile, time: Duration) -> Self {
+		let gas_used = profile.frames.values().fold(EthU256::zero(), |acc, stats| acc.saturating_add(stats.gas));
+
+		CallProfile {
+			gas_used: gas_used.into(),
+			time: as_nanos(&time) / 1_000,
+			folded: profile.folded(),
+			opcodes: profile.opcodes.into_iter().map(|(op, stats)| OpcodeProfile {
+				op,
+				op_name: op_name(op),
+				count: stats.count,
+				gas: stats.gas.into(),
+				time: as_nanos(&stats.time),
+			}).collect(),
+			frames: profile.frames.into_iter().map(|(frame, stats)| FrameProfile {
+				frame,
+				count: stats.count,
+				gas: stats.gas.into(),
+				time: as_nanos(&stats.time),
+			}).collect(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+	use serde_json;
+	use ethcore::trace::{Profile, ProfileStats};
+	use super::CallProfile;
+
+	fn stats(count: usize, gas: u64, nanos: u32) -> ProfileStats {
+		ProfileStats {
+			count,
+			gas: gas.into(),
+			time: Duration::new(0, nanos),
+		}
+	}
+
+	#[test]
+	fn should_serialize_profile_with_opcode_timing() {
+		let mut profile = Profile::default();
+		profile.opcodes.insert(0x01, stats(2, 6, 40));
+		profile.opcodes.insert(0x60, stats(3, 9, 30));
+		profile.opcodes.insert(0xf1, stats(1, 100, 5000));
+		profile.frames.insert("root".into(), stats(3, 106, 5050));
+		profile.frames.insert("root;CALL@2".into(), stats(3, 9, 20));
+		profile.stacks.insert("root;CALL 100".into(), 100.into());
+		profile.stacks.insert("root;CALL@2;PUSH1 6".into(), 6.into());
+
+		let profile = CallProfile::new(profile, Duration::from_millis(2));
+		let serialized = serde_json::to_string(&profile).unwrap();
+
+		assert_eq!(serialized, r#"{"gasUsed":"0x73","time":2000,"opcodes":[{"op":1,"opName":"ADD","count":2,"gas":"0x6","time":40},{"op":96,"opName":"PUSH1","count":3,"gas":"0x9","time":30},{"op":241,"opName":"CALL","count":1,"gas":"0x64","time":5000}],"frames":[{"frame":"root","count":3,"gas":"0x6a","time":5050},{"frame":"root;CALL@2","count":3,"gas":"0x9","time":20}],"folded":["root;CALL 100","root;CALL@2;PUSH1 6"]}"#);
+	}
+}
@@ -0,0 +1,127 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique signer snapshots.
+
+use std::collections::BTreeMap;
+
+use ethcore::engines::clique;
+use v1::types::{H160, H256};
+
+/// Authorization state of the Clique signers at a block.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CliqueSnapshot {
+	/// Block number
+	#[serde(rename="number")]
+	pub number: u64,
+	/// Block hash
+	#[serde(rename="hash")]
+	pub hash: H256,
+	/// Authorized signers
+	#[serde(rename="signers")]
+	pub signers: Vec<H160>,
+	/// Signers of the recent blocks, by block number
+	#[serde(rename="recents")]
+	pub recents: BTreeMap<u64, H160>,
+	/// Votes cast since the last checkpoint
+	#[serde(rename="votes")]
+	pub votes: Vec<CliqueVote>,
+	/// Vote tally by voted account
+	#[serde(rename="tally")]
+	pub tally: BTreeMap<H160, CliqueTally>,
+}
+
+/// Vote cast by a Clique signer.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CliqueVote {
+	/// Signer casting the vote
+	#[serde(rename="signer")]
+	pub signer: H160,
+	/// Block the vote was cast in
+	#[serde(rename="block")]
+	pub block: u64,
+	/// Account voted on
+	#[serde(rename="address")]
+	pub address: H160,
+	/// Whether to authorize or deauthorize the account
+	#[serde(rename="authorize")]
+	pub authorize: bool,
+}
+
+/// Running tally of the votes on an account.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CliqueTally {
+	/// Whether the votes are about authorizing or deauthorizing the account
+	#[serde(rename="authorize")]
+	pub authorize: bool,
+	/// Number of votes
+	#[serde(rename="votes")]
+	pub votes: usize,
+}
+
+impl From<clique::Snapshot> for CliqueSnapshot {
+	fn from(s: clique::Snapshot) -> Self {
+		CliqueSnapshot {
+			number: s.number,
+			hash: s.hash.into(),
+			signers: s.signers.into_iter().map(Into::into).collect(),
+			recents: s.recents.into_iter().map(|(number, signer)| (number, signer.into())).collect(),
+			votes: s.votes.into_iter().map(Into::into).collect(),
+			tally: s.tally.into_iter().map(|(address, tally)| (address.into(), tally.into())).collect(),
+		}
+	}
+}
+
+impl From<clique::Vote> for CliqueVote {
+	fn from(v: clique::Vote) -> Self {
+		CliqueVote {
+			signer: v.signer.into(),
+			block: v.block,
+			address: v.address.into(),
+			authorize: v.authorize,
+		}
+	}
+}
+
+impl From<clique::Tally> for CliqueTally {
+	fn from(t: clique::Tally) -> Self {
+		CliqueTally {
+			authorize: t.authorize,
+			votes: t.votes,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::engines::clique;
+	use ethereum_types::{H160, H256};
+	use super::CliqueSnapshot;
+
+	#[test]
+	fn snapshot_serialization() {
+		let signer = H160::from(1);
+		let candidate = H160::from(2);
+		let mut snapshot = clique::Snapshot::new(1, H256::from(3), vec![signer].into_iter().collect());
+		snapshot.recents.insert(1, signer);
+		snapshot.votes.push(clique::Vote { signer, block: 1, address: candidate, authorize: true });
+		snapshot.tally.insert(candidate, clique::Tally { authorize: true, votes: 1 });
+
+		let serialized = serde_json::to_string(&CliqueSnapshot::from(snapshot)).unwrap();
+		assert_eq!(serialized, r#"{"number":1,"hash":"0x0000000000000000000000000000000000000000000000000000000000000003","signers":["0x0000000000000000000000000000000000000001"],"recents":{"1":"0x0000000000000000000000000000000000000001"},"votes":[{"signer":"0x0000000000000000000000000000000000000001","block":1,"address":"0x0000000000000000000000000000000000000002","authorize":true}],"tally":{"0x0000000000000000000000000000000000000002":{"authorize":true,"votes":1}}}"#);
+	}
+}
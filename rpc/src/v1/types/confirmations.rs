@@ -17,12 +17,14 @@
 //! Types used in Confirmations queue (Trusted Signer)
 
 use std::fmt;
+use std::collections::BTreeMap;
 use serde::{Serialize, Serializer};
 use ansi_term::Colour;
 use bytes::ToPretty;
+use ethereum_types::Address;
 
 use v1::types::{U256, TransactionRequest, RichRawTransaction, H160, H256, H520, Bytes, TransactionCondition, Origin};
-use v1::helpers;
+use v1::helpers::{self, MethodRegistry};
 use ethkey::Password;
 
 /// Confirmation waiting in a queue
@@ -35,6 +37,47 @@ pub struct ConfirmationRequest {
 	pub payload: ConfirmationPayload,
 	/// Request origin
 	pub origin: Origin,
+	/// Human readable details of the payload
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub details: Option<ConfirmationDetails>,
+}
+
+impl ConfirmationRequest {
+	/// Converts the request and decodes its payload using given method registry.
+	pub fn with_details(c: helpers::ConfirmationRequest, registry: &MethodRegistry) -> Self {
+		let details = ConfirmationDetails::new(&c.payload, registry);
+		let mut request: ConfirmationRequest = c.into();
+		if let Some(details) = details {
+			// keep the names the addresses were resolved from
+			let names = request.details.take().map(|d| d.names).unwrap_or_default();
+			request.details = Some(ConfirmationDetails { names, ..details });
+		}
+		request
+	}
+
+	/// Adds human readable names of the addresses involved in the request.
+	pub fn with_names<F>(mut self, name: F) -> Self where
+		F: Fn(&Address) -> Option<String>,
+	{
+		let mut addresses = match self.payload {
+			ConfirmationPayload::SendTransaction(ref t) |
+			ConfirmationPayload::SignTransaction(ref t) => t.from.iter().chain(t.to.iter()).cloned().collect(),
+			ConfirmationPayload::EthSignMessage(ref r) => vec![r.address.clone()],
+			ConfirmationPayload::Decrypt(ref r) => vec![r.address.clone()],
+		};
+		if let Some(transfer) = self.details.as_ref().and_then(|d| d.token_transfer.as_ref()) {
+			addresses.push(transfer.from.clone());
+			addresses.push(transfer.to.clone());
+		}
+
+		let names: BTreeMap<H160, String> = addresses.into_iter()
+			.filter_map(|address| name(&address.clone().into()).map(|name| (address, name)))
+			.collect();
+		if !names.is_empty() {
+			self.details.get_or_insert_with(Default::default).names.extend(names);
+		}
+		self
+	}
 }
 
 impl From<helpers::ConfirmationRequest> for ConfirmationRequest {
@@ -43,13 +86,232 @@ impl From<helpers::ConfirmationRequest> for ConfirmationRequest {
 			id: c.id.into(),
 			payload: c.payload.into(),
 			origin: c.origin,
+			details: match c.names.is_empty() {
+				true => None,
+				false => Some(ConfirmationDetails {
+					names: c.names.into_iter().map(|(address, name)| (address.into(), name)).collect(),
+					..Default::default()
+				}),
+			},
+		}
+	}
+}
+
+/// Decoded contents of a confirmation payload.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfirmationDetails {
+	/// Signature of the called contract method
+	pub method: Option<String>,
+	/// Token transfer or approval made by the transaction
+	#[serde(rename="tokenTransfer")]
+	pub token_transfer: Option<TokenTransfer>,
+	/// EIP-191 interpretation of the message to sign
+	#[serde(rename="signedData")]
+	pub signed_data: Option<SignedData>,
+	/// Names of the accounts and address book entries involved, and the registry names
+	/// addresses of the request were resolved from
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub names: BTreeMap<H160, String>,
+}
+
+impl ConfirmationDetails {
+	/// Decodes the payload, returns `None` if nothing could be decoded.
+	pub fn new(payload: &helpers::ConfirmationPayload, registry: &MethodRegistry) -> Option<Self> {
+		let details = match *payload {
+			helpers::ConfirmationPayload::SendTransaction(ref request) |
+			helpers::ConfirmationPayload::SignTransaction(ref request) => ConfirmationDetails {
+				method: registry.method(&request.data).map(Into::into),
+				token_transfer: TokenTransfer::decode(request),
+				..Default::default()
+			},
+			helpers::ConfirmationPayload::EthSignMessage(_, ref data) => ConfirmationDetails {
+				signed_data: SignedData::decode(data),
+				..Default::default()
+			},
+			helpers::ConfirmationPayload::Decrypt(..) => return None,
+		};
+
+		match details == Default::default() {
+			true => None,
+			false => Some(details),
+		}
+	}
+}
+
+/// Kind of a token operation
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TokenTransferKind {
+	/// `transfer` or `transferFrom`
+	#[serde(rename="transfer")]
+	Transfer,
+	/// `approve`
+	#[serde(rename="approve")]
+	Approve,
+}
+
+/// ERC-20 token operation performed by a transaction
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenTransfer {
+	/// Operation kind
+	pub kind: TokenTransferKind,
+	/// Token contract
+	pub token: H160,
+	/// Owner of the tokens
+	pub from: H160,
+	/// Recipient or approved spender
+	pub to: H160,
+	/// Amount of tokens
+	pub value: U256,
+}
+
+impl TokenTransfer {
+	fn decode(request: &helpers::FilledTransactionRequest) -> Option<Self> {
+		const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+		const TRANSFER_FROM: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+		const APPROVE: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+
+		let token = request.to?;
+		let data = &request.data;
+		if data.len() < 4 || (data.len() - 4) % 32 != 0 {
+			return None;
+		}
+		let args: Vec<&[u8]> = data[4..].chunks(32).collect();
+		let address = |word: &[u8]| match word[..12].iter().all(|b| *b == 0) {
+			true => Some(H160::from(&word[12..])),
+			false => None,
+		};
+		let value = |word: &[u8]| U256::from(word);
+
+		let (kind, from, to, value) = match (&data[..4], args.len()) {
+			(selector, 2) if selector == TRANSFER => (TokenTransferKind::Transfer, request.from.into(), address(args[0])?, value(args[1])),
+			(selector, 3) if selector == TRANSFER_FROM => (TokenTransferKind::Transfer, address(args[0])?, address(args[1])?, value(args[2])),
+			(selector, 2) if selector == APPROVE => (TokenTransferKind::Approve, request.from.into(), address(args[0])?, value(args[1])),
+			_ => return None,
+		};
+
+		Some(TokenTransfer {
+			kind,
+			token: token.into(),
+			from,
+			to,
+			value,
+		})
+	}
+}
+
+/// Message to sign interpreted according to EIP-191
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum SignedData {
+	/// Human readable text message
+	#[serde(rename="message")]
+	Message {
+		/// Message text
+		text: String,
+	},
+	/// Data with intended validator (version `0x00`)
+	#[serde(rename="validator")]
+	Validator {
+		/// Intended validator
+		validator: H160,
+		/// Data to sign
+		data: Bytes,
+	},
+	/// EIP-712 typed structured data (version `0x01`)
+	#[serde(rename="typedData")]
+	TypedData {
+		/// Hash of the domain
+		#[serde(rename="domainSeparator")]
+		domain_separator: H256,
+		/// Hash of the typed message
+		#[serde(rename="messageHash")]
+		message_hash: H256,
+	},
+}
+
+impl SignedData {
+	fn decode(data: &[u8]) -> Option<Self> {
+		match (data.get(0), data.get(1)) {
+			(Some(&0x19), Some(&0x00)) if data.len() >= 22 => Some(SignedData::Validator {
+				validator: H160::from(&data[2..22]),
+				data: data[22..].to_vec().into(),
+			}),
+			(Some(&0x19), Some(&0x01)) if data.len() == 66 => Some(SignedData::TypedData {
+				domain_separator: H256::from(&data[2..34]),
+				message_hash: H256::from(&data[34..66]),
+			}),
+			_ => match ::std::str::from_utf8(data) {
+				Ok(text) if !text.is_empty() && text.chars().all(|c| !c.is_control() || c.is_whitespace()) => {
+					Some(SignedData::Message { text: text.into() })
+				},
+				_ => None,
+			},
 		}
 	}
 }
 
 impl fmt::Display for ConfirmationRequest {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "#{}: {} coming from {}", self.id, self.payload, self.origin)
+		write!(f, "#{}: {} coming from {}", self.id, self.payload, self.origin)?;
+		match self.details {
+			Some(ref details) => write!(f, "\n    {}", details),
+			None => Ok(()),
+		}
+	}
+}
+
+impl fmt::Display for ConfirmationDetails {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut parts = Vec::new();
+		if let Some(ref method) = self.method {
+			parts.push(format!("calls {}", Colour::White.bold().paint(method.as_str())));
+		}
+		if let Some(ref transfer) = self.token_transfer {
+			parts.push(transfer.to_string());
+		}
+		if let Some(ref data) = self.signed_data {
+			parts.push(data.to_string());
+		}
+		write!(f, "{}", parts.join(", "))
+	}
+}
+
+impl fmt::Display for TokenTransfer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.kind {
+			TokenTransferKind::Transfer => write!(
+				f,
+				"transfers {} of token 0x{:?} from 0x{:?} to {}",
+				self.value,
+				self.token,
+				self.from,
+				Colour::White.bold().paint(format!("0x{:?}", self.to)),
+			),
+			TokenTransferKind::Approve => write!(
+				f,
+				"approves {} to spend {} of token 0x{:?}",
+				Colour::White.bold().paint(format!("0x{:?}", self.to)),
+				self.value,
+				self.token,
+			),
+		}
+	}
+}
+
+impl fmt::Display for SignedData {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			SignedData::Message { ref text } => write!(f, "message \"{}\"", text),
+			SignedData::Validator { ref validator, ref data } => write!(f, "data 0x{} for validator 0x{:?}", data.0.pretty(), validator),
+			SignedData::TypedData { ref domain_separator, ref message_hash } => write!(
+				f,
+				"EIP-712 typed data with domain 0x{:?} and message 0x{:?}",
+				domain_separator,
+				message_hash,
+			),
+		}
 	}
 }
 
@@ -258,6 +520,7 @@ mod tests {
 			id: 15.into(),
 			payload: helpers::ConfirmationPayload::EthSignMessage(1.into(), vec![5].into()),
 			origin: Origin::Rpc("test service".into()),
+			names: Default::default(),
 		};
 
 		// when
@@ -287,7 +550,8 @@ mod tests {
 			origin: Origin::Signer {
 				dapp: "http://parity.io".into(),
 				session: 5.into(),
-			}
+			},
+			names: Default::default(),
 		};
 
 		// when
@@ -315,6 +579,7 @@ mod tests {
 				condition: None,
 			}),
 			origin: Origin::Dapps("http://parity.io".into()),
+			names: Default::default(),
 		};
 
 		// when
@@ -325,6 +590,113 @@ mod tests {
 		assert_eq!(res.unwrap(), expected.to_owned());
 	}
 
+	#[test]
+	fn should_serialize_token_transfer_details() {
+		// given
+		let mut data = vec![0xa9, 0x05, 0x9c, 0xbb];
+		data.extend_from_slice(&H256::from(5).0);
+		data.extend_from_slice(&H256::from(1_000).0);
+		let request = helpers::ConfirmationRequest {
+			id: 15.into(),
+			payload: helpers::ConfirmationPayload::SendTransaction(helpers::FilledTransactionRequest {
+				from: 1.into(),
+				used_default_from: false,
+				to: Some(2.into()),
+				gas: 15_000.into(),
+				gas_price: 10_000.into(),
+				value: 0.into(),
+				data: data,
+				nonce: None,
+				condition: None,
+			}),
+			origin: Origin::Unknown,
+			names: Default::default(),
+		};
+
+		// when
+		let res = ConfirmationRequest::with_details(request, &MethodRegistry::default()).details.unwrap();
+
+		// then
+		assert_eq!(res.method, Some("transfer(address,uint256)".into()));
+		let expected = r#"{"kind":"transfer","token":"0x0000000000000000000000000000000000000002","from":"0x0000000000000000000000000000000000000001","to":"0x0000000000000000000000000000000000000005","value":"0x3e8"}"#;
+		assert_eq!(serde_json::to_string(&res.token_transfer).unwrap(), expected);
+	}
+
+	#[test]
+	fn should_add_names_of_involved_addresses() {
+		// given
+		let request = helpers::ConfirmationRequest {
+			id: 15.into(),
+			payload: helpers::ConfirmationPayload::EthSignMessage(1.into(), vec![5]),
+			origin: Origin::Unknown,
+			names: Default::default(),
+		};
+		let name = |address: &Address| match *address == 1.into() {
+			true => Some("Main".to_owned()),
+			false => None,
+		};
+
+		// when
+		let res = ConfirmationRequest::from(request.clone()).with_names(&name);
+		let none = ConfirmationRequest::from(request).with_names(|_: &Address| None);
+
+		// then
+		let expected = r#"{"method":null,"tokenTransfer":null,"signedData":null,"names":{"0x0000000000000000000000000000000000000001":"Main"}}"#;
+		assert_eq!(serde_json::to_string(&res.details).unwrap(), expected);
+		assert_eq!(none.details, None);
+	}
+
+	#[test]
+	fn should_keep_resolved_names() {
+		// given
+		let mut names = BTreeMap::new();
+		names.insert(Address::from(1), "alice".to_owned());
+		names.insert(Address::from(2), "bob".to_owned());
+		let request = helpers::ConfirmationRequest {
+			id: 15.into(),
+			payload: helpers::ConfirmationPayload::EthSignMessage(1.into(), vec![5]),
+			origin: Origin::Unknown,
+			names,
+		};
+		let name = |address: &Address| match *address == 1.into() {
+			true => Some("Main".to_owned()),
+			false => None,
+		};
+
+		// when
+		let res = ConfirmationRequest::with_details(request, &MethodRegistry::default()).with_names(&name);
+
+		// then
+		let expected = r#"{"method":null,"tokenTransfer":null,"signedData":null,"names":{"0x0000000000000000000000000000000000000001":"Main","0x0000000000000000000000000000000000000002":"bob"}}"#;
+		assert_eq!(serde_json::to_string(&res.details).unwrap(), expected);
+	}
+
+	#[test]
+	fn should_decode_signed_data() {
+		let registry = MethodRegistry::default();
+		let details = |data: Vec<u8>| ConfirmationDetails::new(&helpers::ConfirmationPayload::EthSignMessage(1.into(), data), &registry)
+			.and_then(|d| d.signed_data);
+
+		let mut typed = vec![0x19, 0x01];
+		typed.extend_from_slice(&H256::from(1).0);
+		typed.extend_from_slice(&H256::from(2).0);
+		assert_eq!(details(typed), Some(SignedData::TypedData {
+			domain_separator: H256::from(1),
+			message_hash: H256::from(2),
+		}));
+
+		let mut validated = vec![0x19, 0x00];
+		validated.extend_from_slice(&H160::from(3).0);
+		validated.push(7);
+		assert_eq!(details(validated), Some(SignedData::Validator {
+			validator: H160::from(3),
+			data: vec![7].into(),
+		}));
+
+		assert_eq!(details(b"Sign in\n".to_vec()), Some(SignedData::Message { text: "Sign in\n".into() }));
+		assert_eq!(details(vec![5]), None);
+	}
+
 	#[test]
 	fn should_serialize_decrypt_confirmation() {
 		// given
@@ -334,6 +706,7 @@ mod tests {
 				10.into(), vec![1, 2, 3].into(),
 			),
 			origin: Default::default(),
+			names: Default::default(),
 		};
 
 		// when
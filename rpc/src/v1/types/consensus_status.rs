@@ -167,3 +167,56 @@ impl Into<OperationsInfo> for updater::OperationsInfo {
 		}
 	}
 }
+
+/// Stage of an update which is being prepared.
+#[derive(Debug, PartialEq, Serialize)]
+pub enum UpdateStage {
+	/// Waiting for a block number to fetch the release.
+	#[serde(rename="waiting")]
+	Waiting,
+	/// Fetching the release binary.
+	#[serde(rename="fetching")]
+	Fetching,
+	/// Ready to be installed.
+	#[serde(rename="ready")]
+	Ready,
+	/// Failed signature verification.
+	#[serde(rename="rejected")]
+	Rejected,
+}
+
+impl Into<UpdateStage> for updater::UpdateStage {
+	fn into(self) -> UpdateStage {
+		match self {
+			updater::UpdateStage::Waiting => UpdateStage::Waiting,
+			updater::UpdateStage::Fetching => UpdateStage::Fetching,
+			updater::UpdateStage::Ready => UpdateStage::Ready,
+			updater::UpdateStage::Rejected => UpdateStage::Rejected,
+		}
+	}
+}
+
+/// Information on an update which is being prepared.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PendingUpdate {
+	/// The release being updated to.
+	pub release: ReleaseInfo,
+	/// Stage of the update.
+	pub stage: UpdateStage,
+	/// Block number until which the update is delayed, if any.
+	#[serde(rename="delayedUntil")]
+	pub delayed_until: Option<u64>,
+	/// Reason the update was rejected, if it was.
+	pub error: Option<String>,
+}
+
+impl Into<PendingUpdate> for updater::PendingUpdate {
+	fn into(self) -> PendingUpdate {
+		PendingUpdate {
+			release: self.release.into(),
+			stage: self.stage.into(),
+			delayed_until: self.delayed_until,
+			error: self.error,
+		}
+	}
+}
@@ -0,0 +1,69 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Explorer API types.
+
+use ethcore::encoded;
+use v1::types::{H160, H256, Receipt, Transaction, U256};
+
+/// Summary of a block, as listed by `explorer_blockList`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplorerBlock {
+	/// Block number
+	pub number: u64,
+	/// Block hash
+	pub hash: H256,
+	/// Hash of the parent block
+	pub parent_hash: H256,
+	/// Block timestamp
+	pub timestamp: u64,
+	/// Block author
+	pub author: H160,
+	/// Number of transactions in the block
+	pub transaction_count: usize,
+	/// Gas used by the transactions in the block
+	pub gas_used: U256,
+	/// Block gas limit
+	pub gas_limit: U256,
+}
+
+impl From<encoded::Block> for ExplorerBlock {
+	fn from(block: encoded::Block) -> Self {
+		ExplorerBlock {
+			number: block.number(),
+			hash: block.hash().into(),
+			parent_hash: block.parent_hash().into(),
+			timestamp: block.timestamp(),
+			author: block.author().into(),
+			transaction_count: block.transactions_count(),
+			gas_used: block.gas_used().into(),
+			gas_limit: block.gas_limit().into(),
+		}
+	}
+}
+
+/// Transaction with its receipt and block, as returned by `explorer_txDetails`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplorerTransaction {
+	/// The transaction
+	pub transaction: Transaction,
+	/// Receipt of the transaction, if still available
+	pub receipt: Option<Receipt>,
+	/// Block including the transaction
+	pub block: Option<ExplorerBlock>,
+}
@@ -76,6 +76,7 @@ impl Into<EthFilter> for Filter {
 			BlockNumber::Num(n) => BlockId::Number(n),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest | BlockNumber::Pending => BlockId::Latest,
+			BlockNumber::Finalized => BlockId::Finalized,
 		};
 
 		EthFilter {
@@ -0,0 +1,103 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block finality status.
+
+use ethcore::client;
+use v1::types::{H256, U256};
+
+/// How blocks become irreversible under the consensus engine.
+#[derive(Debug, PartialEq, Serialize)]
+pub enum FinalityRule {
+	/// Blocks are final as soon as they are sealed
+	#[serde(rename="instant")]
+	Instant,
+	/// Blocks are final once the consensus engine marks them so
+	#[serde(rename="marked")]
+	Marked,
+	/// Blocks are final once buried under `depth` blocks
+	#[serde(rename="depth")]
+	Depth,
+}
+
+/// Best and latest finalized block of the canonical chain.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FinalityStatus {
+	/// Rule used to determine finality
+	#[serde(rename="rule")]
+	pub rule: FinalityRule,
+	/// Number of confirmations required, for the `depth` rule
+	#[serde(rename="depth")]
+	pub depth: Option<U256>,
+	/// Best block number
+	#[serde(rename="bestNumber")]
+	pub best_number: U256,
+	/// Best block hash
+	#[serde(rename="bestHash")]
+	pub best_hash: H256,
+	/// Latest finalized block number
+	#[serde(rename="finalizedNumber")]
+	pub finalized_number: U256,
+	/// Latest finalized block hash
+	#[serde(rename="finalizedHash")]
+	pub finalized_hash: H256,
+}
+
+impl From<client::FinalityRule> for FinalityRule {
+	fn from(r: client::FinalityRule) -> Self {
+		match r {
+			client::FinalityRule::Instant => FinalityRule::Instant,
+			client::FinalityRule::Marked => FinalityRule::Marked,
+			client::FinalityRule::Depth => FinalityRule::Depth,
+		}
+	}
+}
+
+impl From<client::FinalityStatus> for FinalityStatus {
+	fn from(s: client::FinalityStatus) -> Self {
+		FinalityStatus {
+			rule: s.rule.into(),
+			depth: s.depth.map(Into::into),
+			best_number: s.best_number.into(),
+			best_hash: s.best_hash.into(),
+			finalized_number: s.finalized_number.into(),
+			finalized_hash: s.finalized_hash.into(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::client;
+	use ethereum_types::H256;
+	use super::FinalityStatus;
+
+	#[test]
+	fn finality_status_serialization() {
+		let status: FinalityStatus = client::FinalityStatus {
+			rule: client::FinalityRule::Depth,
+			depth: Some(12),
+			best_number: 20,
+			best_hash: H256::from(2),
+			finalized_number: 8,
+			finalized_hash: H256::from(1),
+		}.into();
+
+		let serialized = serde_json::to_string(&status).unwrap();
+		assert_eq!(serialized, r#"{"rule":"depth","depth":"0xc","bestNumber":"0x14","bestHash":"0x0000000000000000000000000000000000000000000000000000000000000002","finalizedNumber":"0x8","finalizedHash":"0x0000000000000000000000000000000000000000000000000000000000000001"}"#);
+	}
+}
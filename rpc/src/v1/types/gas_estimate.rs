@@ -0,0 +1,33 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use v1::types::U256;
+
+/// Gas estimate split into its components (used by `parity_estimateGasDetailed`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasEstimate {
+	/// Estimated gas limit of the transaction
+	pub gas: U256,
+	/// Base cost of the transaction and its data
+	pub intrinsic_gas: U256,
+	/// Gas used by executing the code
+	pub execution_gas: U256,
+	/// Cost of storing the code of a created contract
+	pub code_deposit_gas: U256,
+	/// Reasons why the transaction may not be included in a block
+	pub warnings: Vec<String>,
+}
@@ -0,0 +1,43 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Structured log records kept by the node.
+
+use ethcore_logger::LogEntry;
+
+/// A recent log record.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct LogRecord {
+	/// Unix timestamp, in seconds.
+	pub timestamp: i64,
+	/// Level, e.g. `INFO`.
+	pub level: String,
+	/// Log target, usually the module.
+	pub target: String,
+	/// Message, without colour codes.
+	pub message: String,
+}
+
+impl From<LogEntry> for LogRecord {
+	fn from(e: LogEntry) -> Self {
+		LogRecord {
+			timestamp: e.timestamp,
+			level: e.level.to_string(),
+			target: e.target,
+			message: e.message,
+		}
+	}
+}
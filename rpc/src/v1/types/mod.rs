@@ -17,70 +17,103 @@
 //! RPC types
 
 mod account_info;
+mod address_transactions;
+mod authorization_token;
 mod block;
 mod block_number;
 mod bytes;
+mod call_bundle;
+mod call_profile;
 mod call_request;
+mod clique;
 mod confirmations;
 mod consensus_status;
 mod dapps;
 mod derivation;
+mod explorer;
 mod filter;
+mod finality;
+mod gas_estimate;
 mod hash;
 mod histogram;
 mod index;
 mod log;
+mod log_record;
 mod node_kind;
 mod provenance;
 mod receipt;
 mod rpc_settings;
 mod secretstore;
+mod state_diff;
+mod state_range;
 mod sync;
+mod token_balance;
 mod trace;
 mod trace_filter;
+mod tracer;
 mod transaction;
 mod transaction_request;
 mod transaction_condition;
+mod transaction_preview;
 mod uint;
+mod validator_set;
 mod work;
 mod private_receipt;
 
 pub mod pubsub;
 
-pub use self::account_info::{AccountInfo, ExtAccountInfo, HwAccountInfo};
+pub use self::account_info::{AccountInfo, ExtAccountInfo, HwAccountInfo, WatchOnlyAccount, AccountStateInfo, EthAccount, StorageProof};
+pub use self::address_transactions::{AddressTransactionsOptions, TransactionDirection, TransactionPosition};
+pub use self::authorization_token::AuthorizationToken;
 pub use self::bytes::Bytes;
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
 pub use self::block_number::{BlockNumber, block_number_to_id};
+pub use self::call_bundle::CallBundleResult;
+pub use self::call_profile::{CallProfile, OpcodeProfile, FrameProfile};
 pub use self::call_request::CallRequest;
+pub use self::clique::{CliqueSnapshot, CliqueVote, CliqueTally};
 pub use self::confirmations::{
 	ConfirmationPayload, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken,
-	TransactionModification, SignRequest, DecryptRequest, Either
+	TransactionModification, SignRequest, DecryptRequest, Either,
+	ConfirmationDetails, TokenTransfer, TokenTransferKind, SignedData,
 };
 pub use self::consensus_status::*;
 pub use self::dapps::LocalDapp;
 pub use self::derivation::{DeriveHash, DeriveHierarchical, Derive};
+pub use self::explorer::{ExplorerBlock, ExplorerTransaction};
 pub use self::filter::{Filter, FilterChanges};
+pub use self::finality::{FinalityRule, FinalityStatus};
+pub use self::gas_estimate::GasEstimate;
 pub use self::hash::{H64, H160, H256, H512, H520, H2048};
 pub use self::histogram::Histogram;
 pub use self::index::Index;
 pub use self::log::Log;
+pub use self::log_record::LogRecord;
 pub use self::node_kind::{NodeKind, Availability, Capability};
 pub use self::provenance::{Origin, DappId};
 pub use self::receipt::Receipt;
 pub use self::rpc_settings::RpcSettings;
 pub use self::secretstore::EncryptedDocumentKey;
+pub use self::state_diff::{StateDiffTarget, TransactionStateDiff};
+pub use self::state_range::{AccountRange, AccountRangeItem, StorageRange, StorageRangeItem};
 pub use self::sync::{
 	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
+	ForkStatus, ForkAlert, ReorgAlert, PeerPermission,
 };
+pub use self::token_balance::{TokenList, TokenBalance};
 pub use self::trace::{LocalizedTrace, TraceResults};
 pub use self::trace_filter::TraceFilter;
+pub use self::tracer::{Tracer, TracerOptions, TracerResult, CallFrame, PrestateAccount, four_byte_counts, op_count};
 pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus};
 pub use self::transaction_request::TransactionRequest;
 pub use self::transaction_condition::TransactionCondition;
+pub use self::transaction_preview::{TransactionPreview, BalanceChange};
 pub use self::uint::{U128, U256, U64};
+pub use self::validator_set::{ValidatorSetInfo, PendingValidatorChange, MisbehaviorKind, MisbehaviorReport};
 pub use self::work::Work;
-pub use self::private_receipt::{PrivateTransactionReceipt, PrivateTransactionReceiptAndTransaction};
+pub use self::private_receipt::{PrivateTransactionReceipt, PrivateTransactionReceiptAndTransaction, PrivateTransactionStatus,
+	PrivateTransactionStatusKind};
 
 // TODO [ToDr] Refactor to a proper type Vec of enums?
 /// Expected tracing type.
@@ -15,7 +15,8 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use v1::types::{H160, H256, TransactionRequest};
-use ethcore_private_tx::{Receipt as EthPrivateReceipt};
+use ethcore_private_tx::{Receipt as EthPrivateReceipt, PrivateTransactionStatus as EthPrivateTransactionStatus,
+	PrivateTransactionState};
 
 /// Receipt
 #[derive(Debug, Serialize)]
@@ -51,3 +52,99 @@ pub struct PrivateTransactionReceiptAndTransaction {
 	#[serde(rename="transaction")]
 	pub transaction: TransactionRequest,
 }
+
+/// State of private transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PrivateTransactionStatusKind {
+	/// Waiting for signatures of validators
+	#[serde(rename="pending")]
+	Pending,
+	/// All signatures are collected, public transaction is sent to the queue
+	#[serde(rename="signed")]
+	Signed,
+	/// Public transaction creation failed
+	#[serde(rename="failed")]
+	Failed,
+}
+
+/// Status of private transaction
+#[derive(Debug, Serialize)]
+pub struct PrivateTransactionStatus {
+	/// State of the transaction
+	#[serde(rename="status")]
+	pub status: PrivateTransactionStatusKind,
+	/// Hash of the public transaction, if all signatures are collected
+	#[serde(rename="publicTransactionHash")]
+	pub public_transaction_hash: Option<H256>,
+	/// Validators required to sign the transaction
+	#[serde(rename="validators")]
+	pub validators: Vec<H160>,
+	/// Validators which have signed the transaction
+	#[serde(rename="signedBy")]
+	pub signed_by: Vec<H160>,
+	/// Validators which haven't signed the transaction yet
+	#[serde(rename="missingValidators")]
+	pub missing_validators: Vec<H160>,
+	/// Errors occurred while processing signatures
+	#[serde(rename="errors")]
+	pub errors: Vec<String>,
+}
+
+impl From<EthPrivateTransactionStatus> for PrivateTransactionStatus {
+	fn from(s: EthPrivateTransactionStatus) -> Self {
+		let (status, public_transaction_hash) = match s.state {
+			PrivateTransactionState::Pending => (PrivateTransactionStatusKind::Pending, None),
+			PrivateTransactionState::Signed(hash) => (PrivateTransactionStatusKind::Signed, Some(hash.into())),
+			PrivateTransactionState::Failed => (PrivateTransactionStatusKind::Failed, None),
+		};
+
+		PrivateTransactionStatus {
+			status,
+			public_transaction_hash,
+			missing_validators: s.missing_validators().into_iter().map(Into::into).collect(),
+			validators: s.validators.into_iter().map(Into::into).collect(),
+			signed_by: s.signed_by.into_iter().map(Into::into).collect(),
+			errors: s.errors,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore_private_tx::{PrivateTransactionStatus as EthPrivateTransactionStatus, PrivateTransactionState};
+	use super::PrivateTransactionStatus;
+
+	#[test]
+	fn status_serialization() {
+		let status = EthPrivateTransactionStatus {
+			state: PrivateTransactionState::Pending,
+			validators: vec![1.into(), 2.into()],
+			signed_by: vec![2.into()],
+			errors: vec!["State is incorrect".into()],
+		};
+		let serialized = serde_json::to_string(&PrivateTransactionStatus::from(status)).unwrap();
+		assert_eq!(serialized, r#"{"status":"pending","publicTransactionHash":null,"validators":["0x0000000000000000000000000000000000000001","0x0000000000000000000000000000000000000002"],"signedBy":["0x0000000000000000000000000000000000000002"],"missingValidators":["0x0000000000000000000000000000000000000001"],"errors":["State is incorrect"]}"#);
+	}
+
+	#[test]
+	fn signed_status_serialization() {
+		let status = EthPrivateTransactionStatus {
+			state: PrivateTransactionState::Signed(3.into()),
+			validators: vec![1.into()],
+			signed_by: vec![1.into()],
+			errors: vec![],
+		};
+		let serialized = serde_json::to_string(&PrivateTransactionStatus::from(status)).unwrap();
+		assert_eq!(serialized, r#"{"status":"signed","publicTransactionHash":"0x0000000000000000000000000000000000000000000000000000000000000003","validators":["0x0000000000000000000000000000000000000001"],"signedBy":["0x0000000000000000000000000000000000000001"],"missingValidators":[],"errors":[]}"#);
+
+		let status = EthPrivateTransactionStatus {
+			state: PrivateTransactionState::Failed,
+			validators: vec![],
+			signed_by: vec![],
+			errors: vec![],
+		};
+		let serialized = serde_json::to_string(&PrivateTransactionStatus::from(status)).unwrap();
+		assert_eq!(serialized, r#"{"status":"failed","publicTransactionHash":null,"validators":[],"signedBy":[],"missingValidators":[],"errors":[]}"#);
+	}
+}
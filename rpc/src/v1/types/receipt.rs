@@ -56,6 +56,9 @@ pub struct Receipt {
 	/// Status code
 	#[serde(rename="status")]
 	pub status_code: Option<U64>,
+	/// Reason given for reverting the transaction, if known
+	#[serde(rename="revertReason", skip_serializing_if = "Option::is_none")]
+	pub revert_reason: Option<String>,
 }
 
 impl Receipt {
@@ -90,6 +93,7 @@ impl From<LocalizedReceipt> for Receipt {
 			status_code: Self::outcome_to_status_code(&r.outcome),
 			state_root: Self::outcome_to_state_root(r.outcome),
 			logs_bloom: r.log_bloom.into(),
+			revert_reason: None,
 		}
 	}
 }
@@ -110,6 +114,7 @@ impl From<RichReceipt> for Receipt {
 			status_code: Self::outcome_to_status_code(&r.outcome),
 			state_root: Self::outcome_to_state_root(r.outcome),
 			logs_bloom: r.log_bloom.into(),
+			revert_reason: None,
 		}
 	}
 }
@@ -130,6 +135,7 @@ impl From<EthReceipt> for Receipt {
 			status_code: Self::outcome_to_status_code(&r.outcome),
 			state_root: Self::outcome_to_state_root(r.outcome),
 			logs_bloom: r.log_bloom.into(),
+			revert_reason: None,
 		}
 	}
 }
@@ -137,6 +143,7 @@ impl From<EthReceipt> for Receipt {
 #[cfg(test)]
 mod tests {
 	use serde_json;
+	use ethcore::receipt::{Receipt as EthReceipt, TransactionOutcome};
 	use v1::types::{Log, Receipt};
 
 	#[test]
@@ -172,9 +179,22 @@ mod tests {
 			logs_bloom: 15.into(),
 			state_root: Some(10.into()),
 			status_code: Some(1u64.into()),
+			revert_reason: None,
 		};
 
 		let serialized = serde_json::to_string(&receipt).unwrap();
 		assert_eq!(serialized, s);
 	}
+
+	#[test]
+	fn receipt_with_revert_reason_serialization() {
+		let receipt = Receipt {
+			status_code: Some(0u64.into()),
+			revert_reason: Some("Not authorised".into()),
+			..EthReceipt::new(TransactionOutcome::StatusCode(0), 0x10.into(), vec![]).into()
+		};
+
+		let serialized = serde_json::to_string(&receipt).unwrap();
+		assert!(serialized.ends_with(r#""status":"0x0","revertReason":"Not authorised"}"#));
+	}
 }
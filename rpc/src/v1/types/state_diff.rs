@@ -0,0 +1,73 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Deserializer};
+use serde::de::IntoDeserializer;
+use v1::types::{BlockNumber, H256};
+use v1::types::trace::StateDiff;
+
+/// Block or transaction to return the recorded state diffs of.
+#[derive(Debug, PartialEq, Clone)]
+pub enum StateDiffTarget {
+	/// All transactions of the block.
+	Block(BlockNumber),
+	/// Transaction with given hash.
+	Transaction(H256),
+}
+
+impl<'a> Deserialize<'a> for StateDiffTarget {
+	fn deserialize<D>(deserializer: D) -> Result<StateDiffTarget, D::Error> where D: Deserializer<'a> {
+		let value = String::deserialize(deserializer)?;
+		// hashes are the only 32-byte long values accepted here
+		if value.len() == 66 {
+			H256::deserialize(IntoDeserializer::<D::Error>::into_deserializer(value)).map(StateDiffTarget::Transaction)
+		} else {
+			BlockNumber::deserialize(IntoDeserializer::<D::Error>::into_deserializer(value)).map(StateDiffTarget::Block)
+		}
+	}
+}
+
+/// State diff recorded for a transaction.
+#[derive(Debug, Serialize)]
+pub struct TransactionStateDiff {
+	/// Transaction hash
+	#[serde(rename="transactionHash")]
+	pub transaction_hash: H256,
+	/// Transaction position in the block
+	#[serde(rename="transactionPosition")]
+	pub transaction_position: usize,
+	/// Changes of the accounts touched by the transaction
+	#[serde(rename="stateDiff")]
+	pub state_diff: StateDiff,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use v1::types::BlockNumber;
+	use super::StateDiffTarget;
+
+	#[test]
+	fn should_deserialize_state_diff_target() {
+		let s = r#"["0xa", "latest", "0x000000000000000000000000000000000000000000000000000000000000000a"]"#;
+		let deserialized: Vec<StateDiffTarget> = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, vec![
+			StateDiffTarget::Block(BlockNumber::Num(10)),
+			StateDiffTarget::Block(BlockNumber::Latest),
+			StateDiffTarget::Transaction(10.into()),
+		]);
+	}
+}
@@ -0,0 +1,102 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pages of accounts and storage slots of a state.
+
+use std::collections::BTreeMap;
+
+use ethcore::state_range::{StateRange, RangeAccount, RangeStorage};
+use v1::types::{H160, H256, U256};
+
+/// A page of accounts, keyed by the hash of their address.
+#[derive(Debug, Serialize)]
+pub struct AccountRange {
+	/// Accounts
+	#[serde(rename="accounts")]
+	pub accounts: BTreeMap<H256, AccountRangeItem>,
+	/// Address hash to continue the iteration from, if there are more accounts.
+	#[serde(rename="next")]
+	pub next: Option<H256>,
+}
+
+/// Account of an account range.
+#[derive(Debug, Serialize)]
+pub struct AccountRangeItem {
+	/// Address, known only if the node keeps the preimages of state keys (fat DB).
+	#[serde(rename="address")]
+	pub address: Option<H160>,
+	/// Balance
+	#[serde(rename="balance")]
+	pub balance: U256,
+	/// Nonce
+	#[serde(rename="nonce")]
+	pub nonce: U256,
+	/// Root of the storage trie
+	#[serde(rename="root")]
+	pub root: H256,
+	/// Hash of the code
+	#[serde(rename="codeHash")]
+	pub code_hash: H256,
+}
+
+impl From<StateRange<RangeAccount>> for AccountRange {
+	fn from(range: StateRange<RangeAccount>) -> Self {
+		AccountRange {
+			accounts: range.items.into_iter().map(|item| (item.hash.into(), AccountRangeItem {
+				address: item.address.map(Into::into),
+				balance: item.account.balance.into(),
+				nonce: item.account.nonce.into(),
+				root: item.account.storage_root.into(),
+				code_hash: item.account.code_hash.into(),
+			})).collect(),
+			next: range.next.map(Into::into),
+		}
+	}
+}
+
+/// A page of storage slots, keyed by the hash of their key.
+#[derive(Debug, Serialize)]
+pub struct StorageRange {
+	/// Storage slots
+	#[serde(rename="storage")]
+	pub storage: BTreeMap<H256, StorageRangeItem>,
+	/// Key hash to continue the iteration from, if there are more slots.
+	#[serde(rename="nextKey")]
+	pub next_key: Option<H256>,
+}
+
+/// Storage slot of a storage range.
+#[derive(Debug, Serialize)]
+pub struct StorageRangeItem {
+	/// Key, known only if the node keeps the preimages of state keys (fat DB).
+	#[serde(rename="key")]
+	pub key: Option<H256>,
+	/// Value
+	#[serde(rename="value")]
+	pub value: H256,
+}
+
+impl From<StateRange<RangeStorage>> for StorageRange {
+	fn from(range: StateRange<RangeStorage>) -> Self {
+		StorageRange {
+			storage: range.items.into_iter().map(|item| (item.hash.into(), StorageRangeItem {
+				key: item.key.map(Into::into),
+				value: item.value.into(),
+			})).collect(),
+			next_key: range.next.map(Into::into),
+		}
+	}
+}
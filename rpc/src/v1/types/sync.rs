@@ -17,7 +17,9 @@
 use std::collections::BTreeMap;
 use sync::{self, PeerInfo as SyncPeerInfo, TransactionStats as SyncTransactionStats};
 use serde::{Serialize, Serializer};
-use v1::types::{U256, H512};
+use ethcore::client::ReorgAlert as ReorgAlertInfo;
+use node_filter::{Permission, Source};
+use v1::types::{U256, H256, H512};
 
 /// Sync info
 #[derive(Default, Debug, Serialize, PartialEq)]
@@ -197,11 +199,147 @@ pub struct ChainStatus {
 	pub block_gap: Option<(U256, U256)>,
 }
 
+/// Competing branch detected on the network.
+#[derive(Default, Debug, Serialize)]
+pub struct ForkAlert {
+	/// Peer which announced the branch.
+	#[serde(rename="peerId")]
+	pub peer_id: usize,
+	/// Number of the best common ancestor.
+	#[serde(rename="ancestorNumber")]
+	pub ancestor_number: U256,
+	/// Hash of the best common ancestor.
+	#[serde(rename="ancestorHash")]
+	pub ancestor_hash: H256,
+	/// Our best block number when the branch was detected.
+	#[serde(rename="ourNumber")]
+	pub our_number: U256,
+	/// Our best block hash when the branch was detected.
+	#[serde(rename="ourHash")]
+	pub our_hash: H256,
+	/// Latest known block number of the branch.
+	#[serde(rename="theirNumber")]
+	pub their_number: U256,
+	/// Latest known block hash of the branch.
+	#[serde(rename="theirHash")]
+	pub their_hash: H256,
+	/// Length of the branch since the common ancestor.
+	pub depth: U256,
+	/// UNIX timestamp when the branch was detected.
+	#[serde(rename="detectedAt")]
+	pub detected_at: u64,
+}
+
+impl From<sync::ForkAlert> for ForkAlert {
+	fn from(a: sync::ForkAlert) -> Self {
+		ForkAlert {
+			peer_id: a.peer_id,
+			ancestor_number: a.ancestor_number.into(),
+			ancestor_hash: a.ancestor_hash.into(),
+			our_number: a.our_number.into(),
+			our_hash: a.our_hash.into(),
+			their_number: a.their_number.into(),
+			their_hash: a.their_hash.into(),
+			depth: a.depth.into(),
+			detected_at: a.detected_at,
+		}
+	}
+}
+
+/// Reorganization which halted block import.
+#[derive(Default, Debug, Serialize)]
+pub struct ReorgAlert {
+	/// Best block number when import was halted.
+	#[serde(rename="bestNumber")]
+	pub best_number: U256,
+	/// Best block hash when import was halted.
+	#[serde(rename="bestHash")]
+	pub best_hash: H256,
+	/// Number of the best common ancestor.
+	#[serde(rename="ancestorNumber")]
+	pub ancestor_number: U256,
+	/// Hash of the best common ancestor.
+	#[serde(rename="ancestorHash")]
+	pub ancestor_hash: H256,
+	/// Number of the block which would become the new best block.
+	#[serde(rename="newNumber")]
+	pub new_number: U256,
+	/// Hash of the block which would become the new best block.
+	#[serde(rename="newHash")]
+	pub new_hash: H256,
+	/// Number of canonical blocks which would be retracted.
+	pub depth: U256,
+	/// UNIX timestamp when import was halted.
+	#[serde(rename="detectedAt")]
+	pub detected_at: u64,
+}
+
+impl From<ReorgAlertInfo> for ReorgAlert {
+	fn from(a: ReorgAlertInfo) -> Self {
+		ReorgAlert {
+			best_number: a.best_number.into(),
+			best_hash: a.best_hash.into(),
+			ancestor_number: a.ancestor_number.into(),
+			ancestor_hash: a.ancestor_hash.into(),
+			new_number: a.new_number.into(),
+			new_hash: a.new_hash.into(),
+			depth: a.depth.into(),
+			detected_at: a.detected_at,
+		}
+	}
+}
+
+/// Fork monitoring status.
+#[derive(Default, Debug, Serialize)]
+pub struct ForkStatus {
+	/// Minimal branch length raising an alert, `null` if monitoring is disabled.
+	#[serde(rename="alertDepth")]
+	pub alert_depth: Option<U256>,
+	/// Recently detected forks, oldest first.
+	pub alerts: Vec<ForkAlert>,
+}
+
+impl From<sync::ForkStatus> for ForkStatus {
+	fn from(s: sync::ForkStatus) -> Self {
+		ForkStatus {
+			alert_depth: s.alert_depth.map(Into::into),
+			alerts: s.alerts.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Connection decision of the node permissions filter.
+#[derive(Default, Debug, Serialize)]
+pub struct PeerPermission {
+	/// Node id.
+	pub id: H512,
+	/// Whether connections with the node are allowed.
+	pub allowed: bool,
+	/// Where the decision comes from: `contract` or `override`.
+	pub source: String,
+	/// Hash of the block the decision was made at.
+	pub block: H256,
+}
+
+impl From<Permission> for PeerPermission {
+	fn from(p: Permission) -> Self {
+		PeerPermission {
+			id: p.node.into(),
+			allowed: p.allowed,
+			source: match p.source {
+				Source::Contract => "contract".into(),
+				Source::Override => "override".into(),
+			},
+			block: p.block.into(),
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use serde_json;
 	use std::collections::BTreeMap;
-	use super::{SyncInfo, SyncStatus, Peers, TransactionStats, ChainStatus};
+	use super::{SyncInfo, SyncStatus, Peers, TransactionStats, ChainStatus, ForkStatus};
 
 	#[test]
 	fn test_serialize_sync_info() {
@@ -240,6 +378,13 @@ mod tests {
 		assert_eq!(serialized, r#"{"blockGap":["0x1","0x5"]}"#);
 	}
 
+	#[test]
+	fn test_serialize_fork_status() {
+		let t = ForkStatus::default();
+		let serialized = serde_json::to_string(&t).unwrap();
+		assert_eq!(serialized, r#"{"alertDepth":null,"alerts":[]}"#);
+	}
+
 	#[test]
 	fn test_serialize_transaction_stats() {
 		let stats = TransactionStats {
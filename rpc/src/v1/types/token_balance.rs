@@ -0,0 +1,73 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types of `parity_tokenBalances`.
+
+use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+use serde_json::{Value, from_value};
+use v1::types::{H160, U256};
+
+/// Tokens to query balances of.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenList {
+	/// Given token contracts
+	Tokens(Vec<H160>),
+	/// All tokens of the token registry (`"registry"`)
+	Registry,
+}
+
+impl<'a> Deserialize<'a> for TokenList {
+	fn deserialize<D>(deserializer: D) -> Result<TokenList, D::Error>
+	where D: Deserializer<'a> {
+		let v: Value = Deserialize::deserialize(deserializer)?;
+
+		if v.as_str() == Some("registry") {
+			return Ok(TokenList::Registry);
+		}
+
+		from_value(v).map(TokenList::Tokens)
+			.map_err(|err| D::Error::custom(format!("Expected a list of token addresses or \"registry\": {}", err)))
+	}
+}
+
+/// Balance of a token.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalance {
+	/// Token contract
+	pub token: H160,
+	/// Balance in the token's base units, `None` if the balance couldn't be read
+	pub balance: Option<U256>,
+	/// Token symbol
+	pub symbol: Option<String>,
+	/// Number of decimals of the token
+	pub decimals: Option<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::TokenList;
+
+	#[test]
+	fn should_deserialize_token_list() {
+		let list: TokenList = serde_json::from_str(r#"["0x0000000000000000000000000000000000000005"]"#).unwrap();
+		assert_eq!(list, TokenList::Tokens(vec![5.into()]));
+		assert_eq!(serde_json::from_str::<TokenList>(r#""registry""#).unwrap(), TokenList::Registry);
+		assert!(serde_json::from_str::<TokenList>(r#""tokens""#).is_err());
+	}
+}
@@ -0,0 +1,332 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Built-in structured tracers.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::vec;
+use rustc_hex::ToHex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{Error, Visitor};
+use ethcore::trace::{FlatTrace, VMTrace, trace};
+use vm::CallType;
+use v1::types::{Bytes, H160, H256, U256};
+
+/// Built-in tracer selectable per trace request.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Tracer {
+	/// Tree of call frames.
+	Call,
+	/// Accounts touched by the execution, as they were before it.
+	Prestate,
+	/// Number of executed instructions.
+	OpCount,
+	/// Number of calls by function selector and call data size.
+	FourByte,
+}
+
+impl<'a> Deserialize<'a> for Tracer {
+	fn deserialize<D>(deserializer: D) -> Result<Tracer, D::Error> where D: Deserializer<'a> {
+		deserializer.deserialize_any(TracerVisitor)
+	}
+}
+
+struct TracerVisitor;
+
+impl<'a> Visitor<'a> for TracerVisitor {
+	type Value = Tracer;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "one of 'callTracer', 'prestateTracer', 'opcountTracer' or '4byteTracer'")
+	}
+
+	fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> where E: Error {
+		match value {
+			"callTracer" => Ok(Tracer::Call),
+			"prestateTracer" => Ok(Tracer::Prestate),
+			"opcount" | "opcountTracer" => Ok(Tracer::OpCount),
+			"4byte" | "4byteTracer" => Ok(Tracer::FourByte),
+			_ => Err(Error::custom(format!("Unknown tracer: {}", value))),
+		}
+	}
+
+	fn visit_string<E>(self, value: String) -> Result<Self::Value, E> where E: Error {
+		self.visit_str(value.as_ref())
+	}
+}
+
+/// Options of a structured trace request.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TracerOptions {
+	/// Tracer to run
+	pub tracer: Tracer,
+}
+
+/// Call frame reported by the call tracer.
+#[derive(Debug, Serialize)]
+pub struct CallFrame {
+	/// Frame type, e.g. CALL, DELEGATECALL, CREATE or SELFDESTRUCT
+	#[serde(rename="type")]
+	pub frame_type: String,
+	/// Sender
+	pub from: H160,
+	/// Recipient, missing for failed creations
+	#[serde(skip_serializing_if="Option::is_none")]
+	pub to: Option<H160>,
+	/// Transferred value, missing for delegate and static calls
+	#[serde(skip_serializing_if="Option::is_none")]
+	pub value: Option<U256>,
+	/// Gas provided to the frame
+	pub gas: U256,
+	/// Gas used by the frame
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+	/// Call data or init code
+	pub input: Bytes,
+	/// Returned data or deployed code
+	#[serde(skip_serializing_if="Option::is_none")]
+	pub output: Option<Bytes>,
+	/// Error, if the frame failed
+	#[serde(skip_serializing_if="Option::is_none")]
+	pub error: Option<String>,
+	/// Nested frames
+	#[serde(skip_serializing_if="Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+	/// Builds the tree of call frames out of flat transaction traces.
+	pub fn from_traces(traces: Vec<FlatTrace>) -> Option<CallFrame> {
+		Self::next(&mut traces.into_iter())
+	}
+
+	// flat traces are ordered depth-first, each followed by its subtraces
+	fn next(traces: &mut vec::IntoIter<FlatTrace>) -> Option<CallFrame> {
+		let trace = traces.next()?;
+		let subtraces = trace.subtraces;
+		let mut frame = CallFrame::from(trace);
+		for _ in 0..subtraces {
+			frame.calls.extend(Self::next(traces));
+		}
+		Some(frame)
+	}
+}
+
+impl From<FlatTrace> for CallFrame {
+	fn from(t: FlatTrace) -> Self {
+		let mut frame = match t.action {
+			trace::Action::Call(call) => CallFrame {
+				frame_type: match call.call_type {
+					CallType::CallCode => "CALLCODE",
+					CallType::DelegateCall => "DELEGATECALL",
+					CallType::StaticCall => "STATICCALL",
+					CallType::None | CallType::Call => "CALL",
+				}.into(),
+				from: call.from.into(),
+				to: Some(call.to.into()),
+				value: match call.call_type {
+					CallType::DelegateCall | CallType::StaticCall => None,
+					_ => Some(call.value.into()),
+				},
+				gas: call.gas.into(),
+				gas_used: 0.into(),
+				input: call.input.into(),
+				output: None,
+				error: None,
+				calls: vec![],
+			},
+			trace::Action::Create(create) => CallFrame {
+				frame_type: "CREATE".into(),
+				from: create.from.into(),
+				to: None,
+				value: Some(create.value.into()),
+				gas: create.gas.into(),
+				gas_used: 0.into(),
+				input: create.init.into(),
+				output: None,
+				error: None,
+				calls: vec![],
+			},
+			trace::Action::Suicide(suicide) => CallFrame {
+				frame_type: "SELFDESTRUCT".into(),
+				from: suicide.address.into(),
+				to: Some(suicide.refund_address.into()),
+				value: Some(suicide.balance.into()),
+				gas: 0.into(),
+				gas_used: 0.into(),
+				input: Bytes::default(),
+				output: None,
+				error: None,
+				calls: vec![],
+			},
+			trace::Action::Reward(reward) => CallFrame {
+				frame_type: "REWARD".into(),
+				from: reward.author.into(),
+				to: None,
+				value: Some(reward.value.into()),
+				gas: 0.into(),
+				gas_used: 0.into(),
+				input: Bytes::default(),
+				output: None,
+				error: None,
+				calls: vec![],
+			},
+		};
+
+		match t.result {
+			trace::Res::Call(res) => {
+				frame.gas_used = res.gas_used.into();
+				frame.output = Some(res.output.into());
+			},
+			trace::Res::Create(res) => {
+				frame.gas_used = res.gas_used.into();
+				frame.to = Some(res.address.into());
+				frame.output = Some(res.code.into());
+			},
+			trace::Res::FailedCall(error) | trace::Res::FailedCreate(error) => {
+				frame.gas_used = frame.gas.clone();
+				frame.error = Some(error.to_string());
+			},
+			trace::Res::None => {},
+		}
+
+		frame
+	}
+}
+
+/// Account state reported by the prestate tracer.
+#[derive(Debug, Serialize)]
+pub struct PrestateAccount {
+	/// Balance
+	pub balance: U256,
+	/// Nonce
+	pub nonce: u64,
+	/// Code, missing for accounts without code
+	#[serde(skip_serializing_if="Option::is_none")]
+	pub code: Option<Bytes>,
+	/// Storage slots read or written by the execution
+	#[serde(skip_serializing_if="BTreeMap::is_empty")]
+	pub storage: BTreeMap<H256, H256>,
+}
+
+/// Counts calls by function selector and call data size, keyed like `0x27dc297e-128`.
+pub fn four_byte_counts(traces: &[FlatTrace]) -> BTreeMap<String, usize> {
+	let mut counts = BTreeMap::new();
+	for t in traces {
+		if let trace::Action::Call(ref call) = t.action {
+			if call.input.len() >= 4 {
+				let key = format!("0x{}-{}", call.input[..4].to_hex(), call.input.len() - 4);
+				*counts.entry(key).or_insert(0) += 1;
+			}
+		}
+	}
+	counts
+}
+
+/// Counts instructions executed in given VM trace, including nested frames.
+pub fn op_count(trace: &VMTrace) -> usize {
+	trace.operations.len() + trace.subs.iter().map(op_count).sum::<usize>()
+}
+
+/// Output of a built-in tracer.
+#[derive(Debug)]
+pub enum TracerResult {
+	/// Output of the call tracer
+	Call(Option<CallFrame>),
+	/// Output of the prestate tracer
+	Prestate(BTreeMap<H160, PrestateAccount>),
+	/// Output of the opcount tracer
+	OpCount(usize),
+	/// Output of the 4byte tracer
+	FourByte(BTreeMap<String, usize>),
+}
+
+impl Serialize for TracerResult {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+		match *self {
+			TracerResult::Call(ref frame) => frame.serialize(serializer),
+			TracerResult::Prestate(ref accounts) => accounts.serialize(serializer),
+			TracerResult::OpCount(ref count) => count.serialize(serializer),
+			TracerResult::FourByte(ref counts) => counts.serialize(serializer),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::trace::{FlatTrace, TraceError};
+	use ethcore::trace::trace::{Action, Res, Call, CallResult};
+	use vm::CallType;
+	use super::*;
+
+	fn call(trace_address: Vec<usize>, subtraces: usize, input: Vec<u8>, result: Res) -> FlatTrace {
+		FlatTrace {
+			action: Action::Call(Call {
+				from: 1.into(),
+				to: 2.into(),
+				value: 3.into(),
+				gas: 100.into(),
+				input,
+				call_type: CallType::Call,
+			}),
+			result,
+			subtraces,
+			trace_address: trace_address.into_iter().collect(),
+		}
+	}
+
+	#[test]
+	fn should_deserialize_tracer_options() {
+		let options: TracerOptions = serde_json::from_str(r#"{"tracer":"4byteTracer"}"#).unwrap();
+		assert_eq!(options.tracer, Tracer::FourByte);
+		let options: TracerOptions = serde_json::from_str(r#"{"tracer":"opcount"}"#).unwrap();
+		assert_eq!(options.tracer, Tracer::OpCount);
+		assert!(serde_json::from_str::<TracerOptions>(r#"{"tracer":"jsTracer"}"#).is_err());
+	}
+
+	#[test]
+	fn should_build_call_frames() {
+		let ok = || Res::Call(CallResult { gas_used: 10.into(), output: vec![] });
+		let traces = vec![
+			call(vec![], 2, vec![], ok()),
+			call(vec![0], 1, vec![], ok()),
+			call(vec![0, 0], 0, vec![], Res::FailedCall(TraceError::Reverted)),
+			call(vec![1], 0, vec![], ok()),
+		];
+
+		let frame = CallFrame::from_traces(traces).unwrap();
+		assert_eq!(frame.calls.len(), 2);
+		assert_eq!(frame.calls[0].calls.len(), 1);
+		assert_eq!(frame.calls[0].calls[0].error, Some("Reverted".into()));
+		assert_eq!(frame.calls[1].calls.len(), 0);
+	}
+
+	#[test]
+	fn should_count_four_bytes() {
+		let ok = || Res::Call(CallResult { gas_used: 10.into(), output: vec![] });
+		let traces = vec![
+			call(vec![], 2, vec![0xa9, 0x05, 0x9c, 0xbb, 0, 0], ok()),
+			call(vec![0], 0, vec![0xa9, 0x05, 0x9c, 0xbb, 0, 0], ok()),
+			call(vec![1], 0, vec![0x01], ok()),
+		];
+
+		let serialized = serde_json::to_string(&TracerResult::FourByte(four_byte_counts(&traces))).unwrap();
+		assert_eq!(serialized, r#"{"0xa9059cbb-2":2}"#);
+	}
+}
@@ -0,0 +1,79 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use ethcore::executed::Executed;
+use v1::types::{Log, Transaction, H160, U256, U64, Bytes};
+
+/// Outcome of simulating a signed transaction without broadcasting it.
+#[derive(Debug, Serialize)]
+pub struct TransactionPreview {
+	/// Decoded transaction
+	pub transaction: Transaction,
+	/// Gas used
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+	/// Logs
+	pub logs: Vec<Log>,
+	/// Output
+	pub output: Bytes,
+	/// Status code
+	#[serde(rename="status")]
+	pub status_code: U64,
+	/// Exception that caused the transaction to fail, if any
+	pub error: Option<String>,
+	/// Reason given for reverting the transaction, if any
+	#[serde(rename="revertReason")]
+	pub revert_reason: Option<String>,
+	/// Balances changed by the transaction, including fees paid to the block author
+	#[serde(rename="balanceChanges")]
+	pub balance_changes: BTreeMap<H160, BalanceChange>,
+}
+
+/// Balance of an account before and after a transaction.
+#[derive(Debug, Serialize)]
+pub struct BalanceChange {
+	/// Balance before
+	pub before: U256,
+	/// Balance after
+	pub after: U256,
+}
+
+impl From<(Transaction, Executed)> for TransactionPreview {
+	fn from((transaction, executed): (Transaction, Executed)) -> Self {
+		let balance_changes = executed.state_diff.as_ref().map_or_else(BTreeMap::new, |diff| {
+			diff.raw.iter()
+				.filter(|&(_, account)| !account.balance.is_same())
+				.map(|(address, account)| (address.clone().into(), BalanceChange {
+					before: account.balance.pre().cloned().unwrap_or_default().into(),
+					after: account.balance.post().cloned().unwrap_or_default().into(),
+				}))
+				.collect()
+		});
+
+		TransactionPreview {
+			transaction,
+			gas_used: executed.gas_used.into(),
+			revert_reason: executed.revert_reason(),
+			status_code: if executed.exception.is_none() { 1u64 } else { 0u64 }.into(),
+			error: executed.exception.map(|e| e.to_string()),
+			logs: executed.logs.into_iter().map(Into::into).collect(),
+			output: executed.output.into(),
+			balance_changes,
+		}
+	}
+}
@@ -0,0 +1,153 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Validator set status.
+
+use ethcore::engines;
+use v1::types::{H160, H256};
+
+/// Validators of a block along with the changes awaiting finality.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ValidatorSetInfo {
+	/// Current validators
+	#[serde(rename="validators")]
+	pub validators: Vec<H160>,
+	/// Changes signalled by the validator set contract, but not finalized yet
+	#[serde(rename="pendingChanges")]
+	pub pending_changes: Vec<PendingValidatorChange>,
+}
+
+/// Validator set change awaiting finality.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PendingValidatorChange {
+	/// Hash of the block signalling the change
+	#[serde(rename="blockHash")]
+	pub block_hash: H256,
+	/// Number of the block signalling the change
+	#[serde(rename="blockNumber")]
+	pub block_number: u64,
+	/// Validators after the change
+	#[serde(rename="validators")]
+	pub validators: Vec<H160>,
+}
+
+/// Severity of the misbehaviour.
+#[derive(Debug, PartialEq, Serialize)]
+pub enum MisbehaviorKind {
+	/// Benign misbehaviour
+	#[serde(rename="benign")]
+	Benign,
+	/// Malicious misbehaviour
+	#[serde(rename="malicious")]
+	Malicious,
+}
+
+/// Validator misbehaviour reported by this node.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MisbehaviorReport {
+	/// Reported validator
+	#[serde(rename="validator")]
+	pub validator: H160,
+	/// Severity of the misbehaviour
+	#[serde(rename="kind")]
+	pub kind: MisbehaviorKind,
+	/// Block at which the validator set was activated
+	#[serde(rename="setBlock")]
+	pub set_block: u64,
+	/// Block at which the misbehaviour happened
+	#[serde(rename="block")]
+	pub block: u64,
+	/// UNIX timestamp of the report
+	#[serde(rename="timestamp")]
+	pub timestamp: u64,
+}
+
+impl From<engines::ValidatorSetInfo> for ValidatorSetInfo {
+	fn from(i: engines::ValidatorSetInfo) -> Self {
+		ValidatorSetInfo {
+			validators: i.validators.into_iter().map(Into::into).collect(),
+			pending_changes: i.pending_changes.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl From<engines::PendingValidatorChange> for PendingValidatorChange {
+	fn from(c: engines::PendingValidatorChange) -> Self {
+		PendingValidatorChange {
+			block_hash: c.block_hash.into(),
+			block_number: c.block_number,
+			validators: c.validators.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl From<engines::MisbehaviorKind> for MisbehaviorKind {
+	fn from(k: engines::MisbehaviorKind) -> Self {
+		match k {
+			engines::MisbehaviorKind::Benign => MisbehaviorKind::Benign,
+			engines::MisbehaviorKind::Malicious => MisbehaviorKind::Malicious,
+		}
+	}
+}
+
+impl From<engines::MisbehaviorReport> for MisbehaviorReport {
+	fn from(r: engines::MisbehaviorReport) -> Self {
+		MisbehaviorReport {
+			validator: r.validator.into(),
+			kind: r.kind.into(),
+			set_block: r.set_block,
+			block: r.block,
+			timestamp: r.timestamp,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::engines;
+	use ethereum_types::{H160, H256};
+	use super::{ValidatorSetInfo, MisbehaviorReport};
+
+	#[test]
+	fn validator_set_serialization() {
+		let info: ValidatorSetInfo = engines::ValidatorSetInfo {
+			validators: vec![H160::from(1)],
+			pending_changes: vec![engines::PendingValidatorChange {
+				block_hash: H256::from(2),
+				block_number: 5,
+				validators: vec![H160::from(1), H160::from(3)],
+			}],
+		}.into();
+
+		let serialized = serde_json::to_string(&info).unwrap();
+		assert_eq!(serialized, r#"{"validators":["0x0000000000000000000000000000000000000001"],"pendingChanges":[{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000002","blockNumber":5,"validators":["0x0000000000000000000000000000000000000001","0x0000000000000000000000000000000000000003"]}]}"#);
+	}
+
+	#[test]
+	fn report_serialization() {
+		let report: MisbehaviorReport = engines::MisbehaviorReport {
+			validator: H160::from(1),
+			kind: engines::MisbehaviorKind::Malicious,
+			set_block: 0,
+			block: 10,
+			timestamp: 1000,
+		}.into();
+
+		let serialized = serde_json::to_string(&report).unwrap();
+		assert_eq!(serialized, r#"{"validator":"0x0000000000000000000000000000000000000001","kind":"malicious","setBlock":0,"block":10,"timestamp":1000}"#);
+	}
+}
@@ -0,0 +1,182 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Filters selecting confirmation requests for batch operations.
+
+use std::str::FromStr;
+
+use ethereum_types::{U256, Address};
+use rpc::signer::{ConfirmationRequest, ConfirmationPayload};
+
+/// Kind of a confirmation request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestKind {
+	/// Send or sign transaction.
+	Transaction,
+	/// Sign message.
+	Sign,
+	/// Decrypt message.
+	Decrypt,
+}
+
+/// Filter matching confirmation requests.
+///
+/// Parsed from a comma-delimited list of `key=value` conditions, all of which have to match, e.g.
+/// `kind=transaction,from=0x00a3...,to=0x6e1c...,method=transfer,max-value=1000000000000000000`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RequestFilter {
+	/// Kind of the request.
+	pub kind: Option<RequestKind>,
+	/// Sender or signing account.
+	pub from: Option<Address>,
+	/// Transaction recipient.
+	pub to: Option<Address>,
+	/// Name of the called contract method.
+	pub method: Option<String>,
+	/// Maximal transaction value in wei.
+	pub max_value: Option<U256>,
+}
+
+impl FromStr for RequestFilter {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut filter = RequestFilter::default();
+		for condition in s.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+			let mut parts = condition.splitn(2, '=');
+			let (key, value) = match (parts.next(), parts.next()) {
+				(Some(key), Some(value)) => (key.trim(), value.trim()),
+				_ => return Err(format!("Invalid filter condition: {}", condition)),
+			};
+			match key {
+				"kind" => filter.kind = Some(match value {
+					"transaction" => RequestKind::Transaction,
+					"sign" => RequestKind::Sign,
+					"decrypt" => RequestKind::Decrypt,
+					_ => return Err(format!("Invalid request kind: {}. Expected transaction, sign or decrypt.", value)),
+				}),
+				"from" => filter.from = Some(to_address(value)?),
+				"to" => filter.to = Some(to_address(value)?),
+				"method" => filter.method = Some(value.into()),
+				"max-value" => filter.max_value = Some(U256::from_dec_str(value).map_err(|_| format!("Invalid value: {}", value))?),
+				_ => return Err(format!("Unknown filter key: {}. Expected kind, from, to, method or max-value.", key)),
+			}
+		}
+		// an empty filter would match, and approve, every request.
+		if filter == RequestFilter::default() {
+			return Err("Empty request filter. At least one condition is required.".into());
+		}
+		Ok(filter)
+	}
+}
+
+impl RequestFilter {
+	/// Checks if given request matches all conditions of the filter.
+	pub fn matches(&self, request: &ConfirmationRequest) -> bool {
+		let (kind, from, transaction) = match request.payload {
+			ConfirmationPayload::SendTransaction(ref t) | ConfirmationPayload::SignTransaction(ref t) =>
+				(RequestKind::Transaction, t.from.clone(), Some(t)),
+			ConfirmationPayload::EthSignMessage(ref r) => (RequestKind::Sign, Some(r.address.clone()), None),
+			ConfirmationPayload::Decrypt(ref r) => (RequestKind::Decrypt, Some(r.address.clone()), None),
+		};
+		let from: Option<Address> = from.map(Into::into);
+		let to: Option<Address> = transaction.and_then(|t| t.to.clone()).map(Into::into);
+		let method = request.details.as_ref()
+			.and_then(|details| details.method.as_ref())
+			.map(|method| method.split('(').next().unwrap_or(""));
+
+		self.kind.map_or(true, |k| k == kind)
+			&& self.from.map_or(true, |f| from == Some(f))
+			&& self.to.map_or(true, |t| to == Some(t))
+			&& self.method.as_ref().map_or(true, |m| method == Some(m.as_str()))
+			&& self.max_value.map_or(true, |max| match transaction {
+				Some(t) => t.value.map_or_else(U256::zero, |value| value.into()) <= max,
+				None => false,
+			})
+	}
+}
+
+fn to_address(s: &str) -> Result<Address, String> {
+	let clean = if s.starts_with("0x") { &s[2..] } else { s };
+	clean.parse().map_err(|_| format!("Invalid address: {}", s))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+	use ethereum_types::Address;
+	use rpc::signer::{ConfirmationRequest, ConfirmationPayload, ConfirmationDetails, TransactionRequest, SignRequest};
+	use super::{RequestFilter, RequestKind};
+
+	fn transaction(value: u64, method: Option<&str>) -> ConfirmationRequest {
+		ConfirmationRequest {
+			id: 1.into(),
+			payload: ConfirmationPayload::SendTransaction(TransactionRequest {
+				from: Some(Address::from(1).into()),
+				to: Some(Address::from(2).into()),
+				value: Some(value.into()),
+				..Default::default()
+			}),
+			origin: Default::default(),
+			details: method.map(|method| ConfirmationDetails {
+				method: Some(method.into()),
+				..Default::default()
+			}),
+		}
+	}
+
+	#[test]
+	fn should_parse_filter() {
+		let filter = RequestFilter::from_str("kind=transaction, to=0x0000000000000000000000000000000000000002,method=transfer,max-value=100").unwrap();
+		assert_eq!(filter, RequestFilter {
+			kind: Some(RequestKind::Transaction),
+			from: None,
+			to: Some(Address::from(2)),
+			method: Some("transfer".into()),
+			max_value: Some(100.into()),
+		});
+		assert!(RequestFilter::from_str("gas=5").is_err());
+		assert!(RequestFilter::from_str("to").is_err());
+		assert!(RequestFilter::from_str("kind=call").is_err());
+		assert!(RequestFilter::from_str("").is_err());
+		assert!(RequestFilter::from_str(" , ").is_err());
+	}
+
+	#[test]
+	fn should_match_requests() {
+		let filter = RequestFilter::from_str("to=0x0000000000000000000000000000000000000002,max-value=100").unwrap();
+		assert!(filter.matches(&transaction(100, None)));
+		assert!(!filter.matches(&transaction(101, None)));
+
+		let filter = RequestFilter::from_str("method=transfer").unwrap();
+		assert!(filter.matches(&transaction(0, Some("transfer(address,uint256)"))));
+		assert!(!filter.matches(&transaction(0, Some("approve(address,uint256)"))));
+		assert!(!filter.matches(&transaction(0, None)));
+
+		let sign = ConfirmationRequest {
+			id: 2.into(),
+			payload: ConfirmationPayload::EthSignMessage(SignRequest {
+				address: Address::from(1).into(),
+				data: vec![1].into(),
+			}),
+			origin: Default::default(),
+			details: None,
+		};
+		assert!(RequestFilter::from_str("from=0x0000000000000000000000000000000000000001").unwrap().matches(&sign));
+		assert!(!RequestFilter::from_str("kind=transaction").unwrap().matches(&sign));
+		assert!(!RequestFilter::from_str("max-value=1").unwrap().matches(&sign));
+	}
+}
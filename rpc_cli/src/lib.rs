@@ -14,12 +14,19 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+extern crate ethereum_types;
 extern crate futures;
 extern crate rpassword;
+#[macro_use]
+extern crate serde_json;
 
 extern crate parity_rpc as rpc;
 extern crate parity_rpc_client as client;
 
+mod filter;
+
+pub use filter::{RequestFilter, RequestKind};
+
 use rpc::signer::{U256, ConfirmationRequest};
 use client::signer_client::SignerRpc;
 use std::io::{Write, BufRead, BufReader, stdout, stdin};
@@ -79,7 +86,20 @@ fn sign_transactions(
 	}).wait()?
 }
 
-fn list_transactions(signer: &mut SignerRpc) -> Result<String, String> {
+fn requests_to_confirm(signer: &mut SignerRpc) -> Result<Vec<ConfirmationRequest>, String> {
+	signer.requests_to_confirm().map(|reqs| {
+		reqs.map_err(|err| format!("error: {:?}", err))
+	}).map_err(|err| {
+		format!("{:?}", err)
+	}).wait()?
+}
+
+fn list_transactions(signer: &mut SignerRpc, json: bool) -> Result<String, String> {
+	if json {
+		let reqs = requests_to_confirm(signer)?;
+		return serde_json::to_string_pretty(&reqs).map_err(|err| format!("{:?}", err));
+	}
+
 	signer.requests_to_confirm().map(|reqs| {
 		match reqs {
 			Ok(ref reqs) if reqs.is_empty() => {
@@ -114,6 +134,42 @@ fn sign_transaction(
 	}).wait()?
 }
 
+fn approve_transactions(
+	signer: &mut SignerRpc,
+	filter: &RequestFilter,
+	password: &str,
+	json: bool,
+) -> Result<String, String> {
+	let reqs = requests_to_confirm(signer)?;
+	let results = reqs.into_iter()
+		.filter(|r| filter.matches(r))
+		.map(|r| (r.id, sign_transaction(signer, r.id, password)))
+		.collect::<Vec<_>>();
+
+	if json {
+		let results = results.into_iter().map(|(id, result)| match result {
+			Ok(message) => json!({ "id": format!("{:#x}", id), "result": message }),
+			Err(error) => json!({ "id": format!("{:#x}", id), "error": error }),
+		}).collect::<Vec<_>>();
+		return serde_json::to_string_pretty(&results).map_err(|err| format!("{:?}", err));
+	}
+
+	if results.is_empty() {
+		return Ok("No matching transactions in signing queue".to_owned());
+	}
+
+	let total = results.len();
+	let approved = results.iter().filter(|&&(_, ref result)| result.is_ok()).count();
+	let mut output = results.into_iter()
+		.map(|(id, result)| match result {
+			Ok(message) => message,
+			Err(error) => format!("Failed to sign transaction id {:#x}: {}", id, error),
+		})
+		.collect::<Vec<String>>();
+	output.push(format!("Approved {} of {} matching transactions", approved, total));
+	Ok(output.join("\n"))
+}
+
 fn reject_transaction(
 	signer: &mut SignerRpc, id: U256) -> Result<String, String>
 {
@@ -131,13 +187,13 @@ fn reject_transaction(
 // cmds
 
 pub fn signer_list(
-	signerport: u16, authfile: PathBuf
+	signerport: u16, authfile: PathBuf, json: bool
 ) -> Result<String, String> {
 	let addr = &format!("ws://127.0.0.1:{}", signerport);
 	let mut signer = SignerRpc::new(addr, &authfile).map_err(|err| {
 		format!("{:?}", err)
 	})?;
-	list_transactions(&mut signer)
+	list_transactions(&mut signer, json)
 }
 
 pub fn signer_reject(
@@ -151,33 +207,51 @@ pub fn signer_reject(
 	reject_transaction(&mut signer, U256::from(id))
 }
 
-pub fn signer_sign(
-	id: Option<usize>,
+pub fn signer_approve_all(
+	filter: Option<String>,
 	pwfile: Option<PathBuf>,
 	signerport: u16,
-	authfile: PathBuf
+	authfile: PathBuf,
+	json: bool,
 ) -> Result<String, String> {
-	let password;
+	let filter = filter.ok_or(format!("--matching filter required for signer approve-all"))?;
+	let filter = filter.parse::<RequestFilter>()?;
+	let password = read_password(pwfile)?;
+
+	let addr = &format!("ws://127.0.0.1:{}", signerport);
+	let mut signer = SignerRpc::new(addr, &authfile).map_err(|err| {
+		format!("{:?}", err)
+	})?;
+	approve_transactions(&mut signer, &filter, &password, json)
+}
+
+fn read_password(pwfile: Option<PathBuf>) -> Result<String, String> {
 	match pwfile {
 		Some(pwfile) => {
 			match File::open(pwfile) {
 				Ok(fd) => {
 					match BufReader::new(fd).lines().next() {
-						Some(Ok(line)) => password = line,
-						_ => return Err(format!("No password in file"))
+						Some(Ok(line)) => Ok(line),
+						_ => Err(format!("No password in file"))
 					}
 				},
 				Err(e) =>
-					return Err(format!("Could not open password file: {}", e))
+					Err(format!("Could not open password file: {}", e))
 			}
 		}
 		None => {
-			password = match rpassword::prompt_password_stdout("Password: ") {
-				Ok(p) => p,
-				Err(e) => return Err(format!("{}", e)),
-			}
+			rpassword::prompt_password_stdout("Password: ").map_err(|e| format!("{}", e))
 		}
 	}
+}
+
+pub fn signer_sign(
+	id: Option<usize>,
+	pwfile: Option<PathBuf>,
+	signerport: u16,
+	authfile: PathBuf
+) -> Result<String, String> {
+	let password = read_password(pwfile)?;
 
 	let addr = &format!("ws://127.0.0.1:{}", signerport);
 	let mut signer = SignerRpc::new(addr, &authfile).map_err(|err| {
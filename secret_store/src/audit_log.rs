@@ -0,0 +1,325 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use byteorder::{BigEndian, ByteOrder};
+use ethereum_types::Address;
+use ethkey::Signature;
+use kvdb::KeyValueDB;
+use parking_lot::Mutex;
+use serde_json;
+use acl_storage::AclStorage;
+use types::{Error, ServerKeyId, Requester};
+use serialization::{SerializableAddress, SerializableSignature};
+
+/// Database column of audit log.
+pub const COL_AUDIT_LOG: Option<u32> = Some(0);
+/// Number of database columns used by secret store.
+pub const NUM_COLUMNS: Option<u32> = Some(1);
+/// Key of the next entry sequence number. Shorter than entries keys, so never clashes with them.
+const SEQUENCE_KEY: &'static [u8] = b"sequence";
+
+/// Audited operation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOperation {
+	/// Server key generation.
+	GenerateServerKey,
+	/// Document key storing.
+	StoreDocumentKey,
+	/// Document key generation.
+	GenerateDocumentKey,
+	/// Document key retrieval.
+	RetrieveDocumentKey,
+	/// Document key shadow retrieval.
+	RetrieveDocumentKeyShadow,
+	/// Schnorr message signing.
+	SignSchnorr,
+	/// ECDSA message signing.
+	SignEcdsa,
+	/// ACL check performed by this node.
+	AclCheck,
+}
+
+/// Single audit log entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+	/// Unix timestamp of the operation.
+	pub timestamp: u64,
+	/// Audited operation.
+	pub operation: AuditOperation,
+	/// Address of the requester, if known.
+	pub requester: Option<Address>,
+	/// Signature the requester has provided, if any.
+	pub requester_signature: Option<Signature>,
+	/// Error, if the operation has failed (or access has been denied).
+	pub error: Option<String>,
+}
+
+/// Serializable audit log entry (used both in database and HTTP responses).
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializableAuditEntry {
+	/// Unix timestamp of the operation.
+	pub timestamp: u64,
+	/// Audited operation.
+	pub operation: AuditOperation,
+	/// Address of the requester, if known.
+	pub requester: Option<SerializableAddress>,
+	/// Signature the requester has provided, if any.
+	pub requester_signature: Option<SerializableSignature>,
+	/// Is operation successful?
+	pub success: bool,
+	/// Error, if the operation has failed (or access has been denied).
+	pub error: Option<String>,
+}
+
+/// Audit log of operations on server keys.
+pub trait AuditLog: Send + Sync {
+	/// Record new entry for given key.
+	fn record(&self, key_id: &ServerKeyId, entry: AuditEntry) -> Result<(), Error>;
+	/// Get all entries of given key, ordered by time.
+	fn entries(&self, key_id: &ServerKeyId) -> Result<Vec<AuditEntry>, Error>;
+}
+
+/// Audit log, stored in dedicated column of secret store database.
+pub struct PersistentAuditLog {
+	db: Arc<KeyValueDB>,
+	/// Sequence number of the next entry.
+	sequence: Mutex<u64>,
+}
+
+/// ACL storage, recording outcome of every check in the audit log.
+pub struct AuditedAclStorage {
+	acl_storage: Arc<AclStorage>,
+	audit_log: Arc<AuditLog>,
+}
+
+impl AuditEntry {
+	/// Create new entry for the operation, made now.
+	pub fn new<T>(key_id: &ServerKeyId, operation: AuditOperation, requester: Option<&Requester>, result: &Result<T, Error>) -> Self {
+		let requester_signature = match requester {
+			Some(&Requester::Signature(ref signature)) => Some(signature.clone()),
+			_ => None,
+		};
+		let requester = match requester {
+			Some(&Requester::Address(ref address)) => Some(address.clone()),
+			Some(requester) => requester.address(key_id).ok(),
+			None => None,
+		};
+
+		AuditEntry {
+			timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default(),
+			operation: operation,
+			requester: requester,
+			requester_signature: requester_signature,
+			error: result.as_ref().err().map(|e| e.to_string()),
+		}
+	}
+}
+
+impl From<AuditEntry> for SerializableAuditEntry {
+	fn from(entry: AuditEntry) -> Self {
+		SerializableAuditEntry {
+			timestamp: entry.timestamp,
+			operation: entry.operation,
+			requester: entry.requester.map(Into::into),
+			requester_signature: entry.requester_signature.map(Into::into),
+			success: entry.error.is_none(),
+			error: entry.error,
+		}
+	}
+}
+
+impl From<SerializableAuditEntry> for AuditEntry {
+	fn from(entry: SerializableAuditEntry) -> Self {
+		AuditEntry {
+			timestamp: entry.timestamp,
+			operation: entry.operation,
+			requester: entry.requester.map(Into::into),
+			requester_signature: entry.requester_signature.map(Into::into),
+			error: entry.error,
+		}
+	}
+}
+
+impl PersistentAuditLog {
+	/// Create new persistent audit log, continuing the sequence of entries stored in the database.
+	pub fn new(db: Arc<KeyValueDB>) -> Result<Self, Error> {
+		let sequence = match db.get(COL_AUDIT_LOG, SEQUENCE_KEY)? {
+			Some(ref sequence) if sequence.len() == 8 => BigEndian::read_u64(sequence),
+			Some(_) => return Err(Error::Database("invalid audit log sequence number".into())),
+			None => 0,
+		};
+
+		Ok(PersistentAuditLog {
+			db: db,
+			sequence: Mutex::new(sequence),
+		})
+	}
+}
+
+impl AuditLog for PersistentAuditLog {
+	fn record(&self, key_id: &ServerKeyId, entry: AuditEntry) -> Result<(), Error> {
+		let entry: SerializableAuditEntry = entry.into();
+		let db_value = serde_json::to_vec(&entry).map_err(|e| Error::Database(e.to_string()))?;
+
+		// key_id ++ timestamp ++ sequence keeps entries of single key together && ordered by time
+		// sequence is persisted together with the entry, so that entries aren't overwritten after restart
+		let mut sequence = self.sequence.lock();
+		let mut db_key = [0u8; 48];
+		db_key[..32].copy_from_slice(&**key_id);
+		BigEndian::write_u64(&mut db_key[32..40], entry.timestamp);
+		BigEndian::write_u64(&mut db_key[40..], *sequence);
+		let mut next_sequence = [0u8; 8];
+		BigEndian::write_u64(&mut next_sequence, *sequence + 1);
+
+		let mut batch = self.db.transaction();
+		batch.put(COL_AUDIT_LOG, &db_key, &db_value);
+		batch.put(COL_AUDIT_LOG, SEQUENCE_KEY, &next_sequence);
+		self.db.write(batch)?;
+		*sequence += 1;
+		Ok(())
+	}
+
+	fn entries(&self, key_id: &ServerKeyId) -> Result<Vec<AuditEntry>, Error> {
+		self.db.iter_from_prefix(COL_AUDIT_LOG, &**key_id)
+			.take_while(|&(ref db_key, _)| db_key.starts_with(&**key_id))
+			.map(|(_, db_value)| serde_json::from_slice::<SerializableAuditEntry>(&db_value)
+				.map(Into::into)
+				.map_err(|e| Error::Database(e.to_string())))
+			.collect()
+	}
+}
+
+impl AuditedAclStorage {
+	/// Create new audited ACL storage.
+	pub fn new(acl_storage: Arc<AclStorage>, audit_log: Arc<AuditLog>) -> Self {
+		AuditedAclStorage {
+			acl_storage: acl_storage,
+			audit_log: audit_log,
+		}
+	}
+}
+
+impl AclStorage for AuditedAclStorage {
+	fn check(&self, requester: Address, document: &ServerKeyId) -> Result<bool, Error> {
+		let result = self.acl_storage.check(requester, document);
+		let outcome = match result {
+			Ok(true) => Ok(()),
+			Ok(false) => Err(Error::AccessDenied),
+			Err(ref error) => Err(error.clone()),
+		};
+		if let Err(error) = self.audit_log.record(document, AuditEntry::new(document, AuditOperation::AclCheck, Some(&Requester::Address(requester)), &outcome)) {
+			warn!(target: "secretstore", "Failed to record ACL check in audit log: {}", error);
+		}
+		result
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	extern crate tempdir;
+
+	use std::collections::HashMap;
+	use std::sync::Arc;
+	use parking_lot::RwLock;
+	use self::tempdir::TempDir;
+	use ethkey::{Random, Generator, sign};
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+	use acl_storage::{AclStorage, DummyAclStorage};
+	use types::{Error, ServerKeyId, Requester};
+	use super::{AuditLog, AuditEntry, AuditOperation, AuditedAclStorage, PersistentAuditLog, NUM_COLUMNS};
+
+	/// In-memory audit log
+	#[derive(Default)]
+	pub struct DummyAuditLog {
+		entries: RwLock<HashMap<ServerKeyId, Vec<AuditEntry>>>,
+	}
+
+	impl AuditLog for DummyAuditLog {
+		fn record(&self, key_id: &ServerKeyId, entry: AuditEntry) -> Result<(), Error> {
+			self.entries.write().entry(key_id.clone()).or_insert_with(Vec::new).push(entry);
+			Ok(())
+		}
+
+		fn entries(&self, key_id: &ServerKeyId) -> Result<Vec<AuditEntry>, Error> {
+			Ok(self.entries.read().get(key_id).cloned().unwrap_or_default())
+		}
+	}
+
+	#[test]
+	fn persistent_audit_log_works() {
+		let tempdir = TempDir::new("").unwrap();
+		let db = Database::open(&DatabaseConfig::with_columns(NUM_COLUMNS), tempdir.path().to_str().unwrap()).unwrap();
+		let audit_log = PersistentAuditLog::new(Arc::new(db)).unwrap();
+
+		let key1 = ServerKeyId::from(1);
+		let key2 = ServerKeyId::from(2);
+		let key_pair = Random.generate().unwrap();
+		let requester = Requester::Signature(sign(key_pair.secret(), &key1).unwrap());
+		let entry1 = AuditEntry::new(&key1, AuditOperation::GenerateServerKey, Some(&requester), &Ok::<_, Error>(()));
+		let entry2 = AuditEntry::new(&key1, AuditOperation::RetrieveDocumentKey, Some(&requester), &Err::<(), _>(Error::AccessDenied));
+		let entry3 = AuditEntry::new(&key2, AuditOperation::SignSchnorr, None, &Ok::<_, Error>(()));
+		audit_log.record(&key1, entry1.clone()).unwrap();
+		audit_log.record(&key1, entry2.clone()).unwrap();
+		audit_log.record(&key2, entry3.clone()).unwrap();
+
+		assert_eq!(entry1.requester, Some(key_pair.address()));
+		assert_eq!(entry2.error, Some(Error::AccessDenied.to_string()));
+		assert_eq!(audit_log.entries(&key1).unwrap(), vec![entry1, entry2]);
+		assert_eq!(audit_log.entries(&key2).unwrap(), vec![entry3]);
+		assert_eq!(audit_log.entries(&ServerKeyId::from(3)).unwrap(), vec![]);
+	}
+
+	#[test]
+	fn persistent_audit_log_keeps_entries_after_restart() {
+		let tempdir = TempDir::new("").unwrap();
+		let db = Arc::new(Database::open(&DatabaseConfig::with_columns(NUM_COLUMNS), tempdir.path().to_str().unwrap()).unwrap());
+
+		let key = ServerKeyId::from(1);
+		let mut entry1 = AuditEntry::new(&key, AuditOperation::GenerateServerKey, None, &Ok::<_, Error>(()));
+		let mut entry2 = AuditEntry::new(&key, AuditOperation::SignEcdsa, None, &Ok::<_, Error>(()));
+		entry1.timestamp = 100;
+		entry2.timestamp = 100;
+
+		PersistentAuditLog::new(db.clone()).unwrap().record(&key, entry1.clone()).unwrap();
+		let audit_log = PersistentAuditLog::new(db).unwrap();
+		audit_log.record(&key, entry2.clone()).unwrap();
+
+		assert_eq!(audit_log.entries(&key).unwrap(), vec![entry1, entry2]);
+	}
+
+	#[test]
+	fn audited_acl_storage_records_checks() {
+		let key = ServerKeyId::from(1);
+		let requester = Random.generate().unwrap().address();
+		let acl_storage = Arc::new(DummyAclStorage::default());
+		let audit_log = Arc::new(DummyAuditLog::default());
+		let audited = AuditedAclStorage::new(acl_storage.clone(), audit_log.clone());
+
+		assert_eq!(audited.check(requester, &key), Ok(true));
+		acl_storage.prohibit(requester, key.clone());
+		assert_eq!(audited.check(requester, &key), Ok(false));
+
+		let entries = audit_log.entries(&key).unwrap();
+		assert_eq!(entries.len(), 2);
+		assert!(entries.iter().all(|e| e.operation == AuditOperation::AclCheck && e.requester == Some(requester)));
+		assert_eq!(entries[0].error, None);
+		assert_eq!(entries[1].error, Some(Error::AccessDenied.to_string()));
+	}
+}
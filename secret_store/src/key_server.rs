@@ -22,12 +22,13 @@ use futures::{self, Future};
 use parking_lot::Mutex;
 use tokio_core::reactor::Core;
 use crypto::DEFAULT_MAC;
-use ethkey::crypto;
+use ethkey::{crypto, public_to_address};
 use super::acl_storage::AclStorage;
 use super::key_storage::KeyStorage;
 use super::key_server_set::KeyServerSet;
-use key_server_cluster::{math, ClusterCore};
-use traits::{AdminSessionsServer, ServerKeyGenerator, DocumentKeyServer, MessageSigner, KeyServer, NodeKeyPair};
+use super::audit_log::{AuditLog, AuditEntry, AuditOperation};
+use key_server_cluster::{math, ClusterCore, TlsContext};
+use traits::{AdminSessionsServer, AuditLogServer, ServerKeyGenerator, DocumentKeyServer, MessageSigner, KeyServer, NodeKeyPair};
 use types::{Error, Public, RequestSignature, Requester, ServerKeyId, EncryptedDocumentKey, EncryptedDocumentKeyShadow,
 	ClusterConfiguration, MessageHash, EncryptedMessageSignature, NodeId};
 use key_server_cluster::{ClusterClient, ClusterConfiguration as NetClusterConfiguration};
@@ -35,6 +36,9 @@ use key_server_cluster::{ClusterClient, ClusterConfiguration as NetClusterConfig
 /// Secret store key server implementation
 pub struct KeyServerImpl {
 	data: Arc<Mutex<KeyServerCore>>,
+	key_storage: Arc<KeyStorage>,
+	audit_log: Arc<AuditLog>,
+	admin_public: Option<Public>,
 }
 
 /// Secret store key server data.
@@ -46,9 +50,12 @@ pub struct KeyServerCore {
 
 impl KeyServerImpl {
 	/// Create new key server instance
-	pub fn new(config: &ClusterConfiguration, key_server_set: Arc<KeyServerSet>, self_key_pair: Arc<NodeKeyPair>, acl_storage: Arc<AclStorage>, key_storage: Arc<KeyStorage>) -> Result<Self, Error> {
+	pub fn new(config: &ClusterConfiguration, key_server_set: Arc<KeyServerSet>, self_key_pair: Arc<NodeKeyPair>, acl_storage: Arc<AclStorage>, key_storage: Arc<KeyStorage>, audit_log: Arc<AuditLog>) -> Result<Self, Error> {
 		Ok(KeyServerImpl {
-			data: Arc::new(Mutex::new(KeyServerCore::new(config, key_server_set, self_key_pair, acl_storage, key_storage)?)),
+			data: Arc::new(Mutex::new(KeyServerCore::new(config, key_server_set, self_key_pair, acl_storage, key_storage.clone())?)),
+			key_storage: key_storage,
+			audit_log: audit_log,
+			admin_public: config.admin_public.clone(),
 		})
 	}
 
@@ -56,6 +63,34 @@ impl KeyServerImpl {
 	pub fn cluster(&self) -> Arc<ClusterClient> {
 		self.data.lock().cluster.clone()
 	}
+
+	/// Execute operation and record its outcome in the audit log.
+	fn audited<T, F>(&self, key_id: &ServerKeyId, operation: AuditOperation, requester: &Requester, f: F) -> Result<T, Error> where F: FnOnce() -> Result<T, Error> {
+		let result = f();
+		if let Err(error) = self.audit_log.record(key_id, AuditEntry::new(key_id, operation, Some(requester), &result)) {
+			warn!(target: "secretstore", "Failed to record {:?} of key {} in audit log: {}", operation, key_id, error);
+		}
+		result
+	}
+
+	/// Generate server key without recording it in the audit log.
+	fn generate_server_key(&self, key_id: &ServerKeyId, author: &Requester, threshold: usize) -> Result<Public, Error> {
+		// recover requestor' public key from signature
+		let address = author.address(key_id).map_err(Error::InsufficientRequesterData)?;
+
+		// generate server key
+		let generation_session = self.data.lock().cluster.new_generation_session(key_id.clone(), None, address, threshold)?;
+		generation_session.wait(None)
+			.expect("when wait is called without timeout it always returns Some; qed")
+			.map_err(Into::into)
+	}
+
+	/// Store document key without recording it in the audit log.
+	fn store_encrypted_document_key(&self, key_id: &ServerKeyId, author: &Requester, common_point: Public, encrypted_document_key: Public) -> Result<(), Error> {
+		let encryption_session = self.data.lock().cluster.new_encryption_session(key_id.clone(),
+			author.clone(), common_point, encrypted_document_key)?;
+		encryption_session.wait(None).map_err(Into::into)
+	}
 }
 
 impl KeyServer for KeyServerImpl {}
@@ -68,109 +103,132 @@ impl AdminSessionsServer for KeyServerImpl {
 			.expect("new_servers_set_change_session creates servers_set_change_session; qed")
 			.wait().map_err(Into::into)
 	}
+
+	fn reshare_key(&self, key_id: &ServerKeyId, old_set_signature: RequestSignature, new_set_signature: RequestSignature, new_servers_set: BTreeSet<NodeId>) -> Result<(), Error> {
+		let share_add_session = self.data.lock().cluster
+			.new_share_add_session(key_id.clone(), new_servers_set, old_set_signature, new_set_signature)?;
+		share_add_session.as_share_add()
+			.expect("new_share_add_session creates share_add_session; qed")
+			.wait().map_err(Into::into)
+	}
+}
+
+impl AuditLogServer for KeyServerImpl {
+	fn audit_log(&self, key_id: &ServerKeyId, requester: &Requester) -> Result<Vec<AuditEntry>, Error> {
+		let public = requester.public(key_id).map_err(Error::InsufficientRequesterData)?;
+		let author = self.key_storage.get(key_id)?.map(|key_share| key_share.author);
+		let is_admin = self.admin_public.as_ref() == Some(&public);
+		if !is_admin && author != Some(public_to_address(&public)) {
+			return Err(Error::AccessDenied);
+		}
+
+		self.audit_log.entries(key_id)
+	}
 }
 
 impl ServerKeyGenerator for KeyServerImpl {
 	fn generate_key(&self, key_id: &ServerKeyId, author: &Requester, threshold: usize) -> Result<Public, Error> {
-		// recover requestor' public key from signature
-		let address = author.address(key_id).map_err(Error::InsufficientRequesterData)?;
-
-		// generate server key
-		let generation_session = self.data.lock().cluster.new_generation_session(key_id.clone(), None, address, threshold)?;
-		generation_session.wait(None)
-			.expect("when wait is called without timeout it always returns Some; qed")
-			.map_err(Into::into)
+		self.audited(key_id, AuditOperation::GenerateServerKey, author,
+			|| self.generate_server_key(key_id, author, threshold))
 	}
 }
 
 impl DocumentKeyServer for KeyServerImpl {
 	fn store_document_key(&self, key_id: &ServerKeyId, author: &Requester, common_point: Public, encrypted_document_key: Public) -> Result<(), Error> {
-		// store encrypted key
-		let encryption_session = self.data.lock().cluster.new_encryption_session(key_id.clone(),
-			author.clone(), common_point, encrypted_document_key)?;
-		encryption_session.wait(None).map_err(Into::into)
+		self.audited(key_id, AuditOperation::StoreDocumentKey, author,
+			|| self.store_encrypted_document_key(key_id, author, common_point, encrypted_document_key))
 	}
 
 	fn generate_document_key(&self, key_id: &ServerKeyId, author: &Requester, threshold: usize) -> Result<EncryptedDocumentKey, Error> {
-		// recover requestor' public key from signature
-		let public = author.public(key_id).map_err(Error::InsufficientRequesterData)?;
+		self.audited(key_id, AuditOperation::GenerateDocumentKey, author, || {
+			// recover requestor' public key from signature
+			let public = author.public(key_id).map_err(Error::InsufficientRequesterData)?;
 
-		// generate server key
-		let server_key = self.generate_key(key_id, author, threshold)?;
+			// generate server key (recorded as the part of document key generation)
+			let server_key = self.generate_server_key(key_id, author, threshold)?;
 
-		// generate random document key
-		let document_key = math::generate_random_point()?;
-		let encrypted_document_key = math::encrypt_secret(&document_key, &server_key)?;
+			// generate random document key
+			let document_key = math::generate_random_point()?;
+			let encrypted_document_key = math::encrypt_secret(&document_key, &server_key)?;
 
-		// store document key in the storage
-		self.store_document_key(key_id, author, encrypted_document_key.common_point, encrypted_document_key.encrypted_point)?;
+			// store document key in the storage
+			self.store_encrypted_document_key(key_id, author, encrypted_document_key.common_point, encrypted_document_key.encrypted_point)?;
 
-		// encrypt document key with requestor public key
-		let document_key = crypto::ecies::encrypt(&public, &DEFAULT_MAC, &document_key)
-			.map_err(|err| Error::Internal(format!("Error encrypting document key: {}", err)))?;
-		Ok(document_key)
+			// encrypt document key with requestor public key
+			let document_key = crypto::ecies::encrypt(&public, &DEFAULT_MAC, &document_key)
+				.map_err(|err| Error::Internal(format!("Error encrypting document key: {}", err)))?;
+			Ok(document_key)
+		})
 	}
 
 	fn restore_document_key(&self, key_id: &ServerKeyId, requester: &Requester) -> Result<EncryptedDocumentKey, Error> {
-		// recover requestor' public key from signature
-		let public = requester.public(key_id).map_err(Error::InsufficientRequesterData)?;
-
-		// decrypt document key
-		let decryption_session = self.data.lock().cluster.new_decryption_session(key_id.clone(),
-			None, requester.clone(), None, false, false)?;
-		let document_key = decryption_session.wait(None)
-			.expect("when wait is called without timeout it always returns Some; qed")?
-			.decrypted_secret;
-
-		// encrypt document key with requestor public key
-		let document_key = crypto::ecies::encrypt(&public, &DEFAULT_MAC, &document_key)
-			.map_err(|err| Error::Internal(format!("Error encrypting document key: {}", err)))?;
-		Ok(document_key)
+		self.audited(key_id, AuditOperation::RetrieveDocumentKey, requester, || {
+			// recover requestor' public key from signature
+			let public = requester.public(key_id).map_err(Error::InsufficientRequesterData)?;
+
+			// decrypt document key
+			let decryption_session = self.data.lock().cluster.new_decryption_session(key_id.clone(),
+				None, requester.clone(), None, false, false)?;
+			let document_key = decryption_session.wait(None)
+				.expect("when wait is called without timeout it always returns Some; qed")?
+				.decrypted_secret;
+
+			// encrypt document key with requestor public key
+			let document_key = crypto::ecies::encrypt(&public, &DEFAULT_MAC, &document_key)
+				.map_err(|err| Error::Internal(format!("Error encrypting document key: {}", err)))?;
+			Ok(document_key)
+		})
 	}
 
 	fn restore_document_key_shadow(&self, key_id: &ServerKeyId, requester: &Requester) -> Result<EncryptedDocumentKeyShadow, Error> {
-		let decryption_session = self.data.lock().cluster.new_decryption_session(key_id.clone(),
-			None, requester.clone(), None, true, false)?;
-		decryption_session.wait(None)
-			.expect("when wait is called without timeout it always returns Some; qed")
-			.map_err(Into::into)
+		self.audited(key_id, AuditOperation::RetrieveDocumentKeyShadow, requester, || {
+			let decryption_session = self.data.lock().cluster.new_decryption_session(key_id.clone(),
+				None, requester.clone(), None, true, false)?;
+			decryption_session.wait(None)
+				.expect("when wait is called without timeout it always returns Some; qed")
+				.map_err(Into::into)
+		})
 	}
 }
 
 impl MessageSigner for KeyServerImpl {
 	fn sign_message_schnorr(&self, key_id: &ServerKeyId, requester: &Requester, message: MessageHash) -> Result<EncryptedMessageSignature, Error> {
-		// recover requestor' public key from signature
-		let public = requester.public(key_id).map_err(Error::InsufficientRequesterData)?;
+		self.audited(key_id, AuditOperation::SignSchnorr, requester, || {
+			// recover requestor' public key from signature
+			let public = requester.public(key_id).map_err(Error::InsufficientRequesterData)?;
 
-		// sign message
-		let signing_session = self.data.lock().cluster.new_schnorr_signing_session(key_id.clone(),
-			requester.clone().into(), None, message)?;
-		let message_signature = signing_session.wait()?;
-
-		// compose two message signature components into single one
-		let mut combined_signature = [0; 64];
-		combined_signature[..32].clone_from_slice(&**message_signature.0);
-		combined_signature[32..].clone_from_slice(&**message_signature.1);
-
-		// encrypt combined signature with requestor public key
-		let message_signature = crypto::ecies::encrypt(&public, &DEFAULT_MAC, &combined_signature)
-			.map_err(|err| Error::Internal(format!("Error encrypting message signature: {}", err)))?;
-		Ok(message_signature)
+			// sign message
+			let signing_session = self.data.lock().cluster.new_schnorr_signing_session(key_id.clone(),
+				requester.clone().into(), None, message)?;
+			let message_signature = signing_session.wait()?;
+
+			// compose two message signature components into single one
+			let mut combined_signature = [0; 64];
+			combined_signature[..32].clone_from_slice(&**message_signature.0);
+			combined_signature[32..].clone_from_slice(&**message_signature.1);
+
+			// encrypt combined signature with requestor public key
+			let message_signature = crypto::ecies::encrypt(&public, &DEFAULT_MAC, &combined_signature)
+				.map_err(|err| Error::Internal(format!("Error encrypting message signature: {}", err)))?;
+			Ok(message_signature)
+		})
 	}
 
 	fn sign_message_ecdsa(&self, key_id: &ServerKeyId, requester: &Requester, message: MessageHash) -> Result<EncryptedMessageSignature, Error> {
-		// recover requestor' public key from signature
-		let public = requester.public(key_id).map_err(Error::InsufficientRequesterData)?;
+		self.audited(key_id, AuditOperation::SignEcdsa, requester, || {
+			// recover requestor' public key from signature
+			let public = requester.public(key_id).map_err(Error::InsufficientRequesterData)?;
 
-		// sign message
-		let signing_session = self.data.lock().cluster.new_ecdsa_signing_session(key_id.clone(),
-			requester.clone().into(), None, message)?;
-		let message_signature = signing_session.wait()?;
-
-		// encrypt combined signature with requestor public key
-		let message_signature = crypto::ecies::encrypt(&public, &DEFAULT_MAC, &*message_signature)
-			.map_err(|err| Error::Internal(format!("Error encrypting message signature: {}", err)))?;
-		Ok(message_signature)
+			// sign message
+			let signing_session = self.data.lock().cluster.new_ecdsa_signing_session(key_id.clone(),
+				requester.clone().into(), None, message)?;
+			let message_signature = signing_session.wait()?;
+
+			// encrypt combined signature with requestor public key
+			let message_signature = crypto::ecies::encrypt(&public, &DEFAULT_MAC, &*message_signature)
+				.map_err(|err| Error::Internal(format!("Error encrypting message signature: {}", err)))?;
+			Ok(message_signature)
+		})
 	}
 }
 
@@ -186,6 +244,10 @@ impl KeyServerCore {
 			key_storage: key_storage,
 			admin_public: config.admin_public.clone(),
 			auto_migrate_enabled: config.auto_migrate_enabled,
+			tls: match config.tls {
+				Some(ref tls) => Some(Arc::new(TlsContext::new(tls.clone())?)),
+				None => None,
+			},
 		};
 
 		let (stop, stopped) = futures::oneshot();
@@ -235,14 +297,16 @@ pub mod tests {
 	use acl_storage::DummyAclStorage;
 	use key_storage::KeyStorage;
 	use key_storage::tests::DummyKeyStorage;
+	use audit_log::{AuditEntry, AuditOperation};
+	use audit_log::tests::DummyAuditLog;
 	use node_key_pair::PlainNodeKeyPair;
 	use key_server_set::tests::MapKeyServerSet;
-	use key_server_cluster::math;
+	use key_server_cluster::{math, ordered_nodes_hash};
 	use ethereum_types::{H256, H520};
 	use types::{Error, Public, ClusterConfiguration, NodeAddress, RequestSignature, ServerKeyId,
 		EncryptedDocumentKey, EncryptedDocumentKeyShadow, MessageHash, EncryptedMessageSignature,
 		Requester, NodeId};
-	use traits::{AdminSessionsServer, ServerKeyGenerator, DocumentKeyServer, MessageSigner, KeyServer};
+	use traits::{AdminSessionsServer, AuditLogServer, ServerKeyGenerator, DocumentKeyServer, MessageSigner, KeyServer};
 	use super::KeyServerImpl;
 
 	#[derive(Default)]
@@ -254,6 +318,16 @@ pub mod tests {
 		fn change_servers_set(&self, _old_set_signature: RequestSignature, _new_set_signature: RequestSignature, _new_servers_set: BTreeSet<NodeId>) -> Result<(), Error> {
 			unimplemented!("test-only")
 		}
+
+		fn reshare_key(&self, _key_id: &ServerKeyId, _old_set_signature: RequestSignature, _new_set_signature: RequestSignature, _new_servers_set: BTreeSet<NodeId>) -> Result<(), Error> {
+			unimplemented!("test-only")
+		}
+	}
+
+	impl AuditLogServer for DummyKeyServer {
+		fn audit_log(&self, _key_id: &ServerKeyId, _requester: &Requester) -> Result<Vec<AuditEntry>, Error> {
+			unimplemented!("test-only")
+		}
 	}
 
 	impl ServerKeyGenerator for DummyKeyServer {
@@ -291,6 +365,10 @@ pub mod tests {
 	}
 
 	fn make_key_servers(start_port: u16, num_nodes: usize) -> (Vec<KeyServerImpl>, Vec<Arc<DummyKeyStorage>>) {
+		make_key_servers_with_admin(start_port, num_nodes, None)
+	}
+
+	fn make_key_servers_with_admin(start_port: u16, num_nodes: usize, admin_public: Option<Public>) -> (Vec<KeyServerImpl>, Vec<Arc<DummyKeyStorage>>) {
 		let key_pairs: Vec<_> = (0..num_nodes).map(|_| Random.generate().unwrap()).collect();
 		let configs: Vec<_> = (0..num_nodes).map(|i| ClusterConfiguration {
 				threads: 1,
@@ -305,8 +383,9 @@ pub mod tests {
 					})).collect(),
 				key_server_set_contract_address: None,
 				allow_connecting_to_higher_nodes: false,
-				admin_public: None,
+				admin_public: admin_public.clone(),
 				auto_migrate_enabled: false,
+				tls: None,
 			}).collect();
 		let key_servers_set: BTreeMap<Public, SocketAddr> = configs[0].nodes.iter()
 			.map(|(k, a)| (k.clone(), format!("{}:{}", a.address, a.port).parse().unwrap()))
@@ -316,7 +395,8 @@ pub mod tests {
 			KeyServerImpl::new(&cfg, Arc::new(MapKeyServerSet::new(false, key_servers_set.clone())),
 				Arc::new(PlainNodeKeyPair::new(key_pairs[i].clone())),
 				Arc::new(DummyAclStorage::default()),
-				key_storages[i].clone()).unwrap()
+				key_storages[i].clone(),
+				Arc::new(DummyAuditLog::default())).unwrap()
 		).collect();
 
 		// wait until connections are established. It is fast => do not bother with events here
@@ -530,4 +610,72 @@ pub mod tests {
 	fn servers_set_change_session_works_over_network() {
 		// TODO [Test]
 	}
+
+	#[test]
+	fn key_is_reshared_to_node_that_does_not_have_key_share() {
+		//::logger::init_log();
+		let admin_key_pair = Random.generate().unwrap();
+		let (key_servers, key_storages) = make_key_servers_with_admin(6130, 3, Some(admin_key_pair.public().clone()));
+		let threshold = 1;
+
+		// generate server key
+		let server_key_id = Random.generate().unwrap().secret().clone();
+		let requestor_secret = Random.generate().unwrap().secret().clone();
+		let signature = ethkey::sign(&requestor_secret, &server_key_id).unwrap();
+		let server_public = key_servers[0].generate_key(&server_key_id, &signature.into(), threshold).unwrap();
+
+		// make node2 a new node for this key: remove its share && forget its id number on other nodes
+		let node2 = key_storages[0].get(&server_key_id).unwrap().unwrap().versions[0].id_numbers.keys()
+			.find(|n| !key_servers[2].cluster().cluster_state().connected.contains(n))
+			.cloned().unwrap();
+		key_storages[2].remove(&server_key_id).unwrap();
+		for key_storage in &key_storages[0..2] {
+			let mut key_share = key_storage.get(&server_key_id).unwrap().unwrap();
+			key_share.versions[0].id_numbers.remove(&node2);
+			key_storage.update(server_key_id.clone(), key_share).unwrap();
+		}
+
+		// reshare key to all nodes
+		let old_nodes_set: BTreeSet<NodeId> = key_storages[0].get(&server_key_id).unwrap().unwrap().versions[0].id_numbers.keys().cloned().collect();
+		let new_nodes_set: BTreeSet<NodeId> = old_nodes_set.iter().cloned().chain(::std::iter::once(node2.clone())).collect();
+		let old_set_signature = ethkey::sign(admin_key_pair.secret(), &ordered_nodes_hash(&old_nodes_set)).unwrap();
+		let new_set_signature = ethkey::sign(admin_key_pair.secret(), &ordered_nodes_hash(&new_nodes_set)).unwrap();
+		key_servers[0].reshare_key(&server_key_id, old_set_signature, new_set_signature, new_nodes_set.clone()).unwrap();
+
+		// node2 now has a share of the same key
+		let key_share = key_storages[2].get(&server_key_id).unwrap().unwrap();
+		assert_eq!(key_share.public, server_public);
+		assert_eq!(key_share.last_version().unwrap().id_numbers.keys().cloned().collect::<BTreeSet<_>>(), new_nodes_set);
+	}
+
+	#[test]
+	fn reshare_key_fails_if_key_is_unknown() {
+		//::logger::init_log();
+		let admin_key_pair = Random.generate().unwrap();
+		let (key_servers, _) = make_key_servers_with_admin(6133, 1, Some(admin_key_pair.public().clone()));
+
+		let nodes_set: BTreeSet<NodeId> = ::std::iter::once(Default::default()).collect();
+		let set_signature = ethkey::sign(admin_key_pair.secret(), &ordered_nodes_hash(&nodes_set)).unwrap();
+		assert_eq!(key_servers[0].reshare_key(&Default::default(), set_signature.clone(), set_signature, nodes_set),
+			Err(Error::ServerKeyIsNotFound));
+	}
+
+	#[test]
+	fn audit_log_is_available_to_key_author_only() {
+		//::logger::init_log();
+		let (key_servers, _) = make_key_servers(6125, 1);
+
+		let document = Random.generate().unwrap().secret().clone();
+		let secret = Random.generate().unwrap().secret().clone();
+		let signature = ethkey::sign(&secret, &document).unwrap();
+		key_servers[0].generate_document_key(&document, &signature.clone().into(), 0).unwrap();
+		key_servers[0].restore_document_key(&document, &signature.clone().into()).unwrap();
+
+		let operations: Vec<_> = key_servers[0].audit_log(&document, &signature.into()).unwrap()
+			.into_iter().map(|entry| entry.operation).collect();
+		assert_eq!(operations, vec![AuditOperation::GenerateDocumentKey, AuditOperation::RetrieveDocumentKey]);
+
+		let other_signature = ethkey::sign(Random.generate().unwrap().secret(), &document).unwrap();
+		assert_eq!(key_servers[0].audit_log(&document, &other_signature.into()), Err(Error::AccessDenied));
+	}
 }
@@ -184,6 +184,12 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 		})
 	}
 
+	/// Wait for session completion.
+	pub fn wait(&self) -> Result<(), Error> {
+		Self::wait_session(&self.core.completed, &self.data, None, |data| data.result.clone())
+			.expect("wait_session returns Some if called without timeout; qed")
+	}
+
 	/// Set pre-established consensus data.
 	pub fn set_consensus_output(&self, version: &H256, consensus_group: BTreeSet<NodeId>, version_holders: BTreeSet<NodeId>, mut new_nodes_map: BTreeMap<NodeId, Option<Secret>>) -> Result<(), Error> {
 		let mut data = self.data.lock();
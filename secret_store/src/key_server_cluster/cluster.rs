@@ -41,7 +41,7 @@ use key_server_cluster::signing_session_schnorr::{SessionImpl as SchnorrSigningS
 use key_server_cluster::key_version_negotiation_session::{SessionImpl as KeyVersionNegotiationSession,
 	IsolatedSessionTransport as KeyVersionNegotiationSessionTransport, ContinueAction};
 use key_server_cluster::io::{DeadlineStatus, ReadMessage, SharedTcpStream, read_encrypted_message, WriteMessage, write_encrypted_message};
-use key_server_cluster::net::{accept_connection as net_accept_connection, connect as net_connect, Connection as NetConnection};
+use key_server_cluster::net::{accept_connection as net_accept_connection, connect as net_connect, Connection as NetConnection, TlsContext};
 use key_server_cluster::connection_trigger::{Maintain, ConnectionTrigger, SimpleConnectionTrigger, ServersSetChangeSessionCreatorConnector};
 use key_server_cluster::connection_trigger_with_migration::ConnectionTriggerWithMigration;
 
@@ -79,6 +79,8 @@ pub trait ClusterClient: Send + Sync {
 	fn new_key_version_negotiation_session(&self, session_id: SessionId) -> Result<Arc<KeyVersionNegotiationSession<KeyVersionNegotiationSessionTransport>>, Error>;
 	/// Start new servers set change session.
 	fn new_servers_set_change_session(&self, session_id: Option<SessionId>, migration_id: Option<H256>, new_nodes_set: BTreeSet<NodeId>, old_set_signature: Signature, new_set_signature: Signature) -> Result<Arc<AdminSession>, Error>;
+	/// Start new share add session, re-sharing the last version of single key to the new nodes set.
+	fn new_share_add_session(&self, session_id: SessionId, new_nodes_set: BTreeSet<NodeId>, old_set_signature: Signature, new_set_signature: Signature) -> Result<Arc<AdminSession>, Error>;
 
 	/// Listen for new generation sessions.
 	fn add_generation_listener(&self, listener: Arc<ClusterSessionsListener<GenerationSession>>);
@@ -139,6 +141,8 @@ pub struct ClusterConfiguration {
 	/// Should key servers set change session should be started when servers set changes.
 	/// This will only work when servers set is configured using KeyServerSet contract.
 	pub auto_migrate_enabled: bool,
+	/// Mutual TLS context. If None, connections are only protected by session-level encryption.
+	pub tls: Option<Arc<TlsContext>>,
 }
 
 /// Cluster state.
@@ -301,7 +305,8 @@ impl ClusterCore {
 	/// Connect to socket using given context and handle.
 	fn connect_future(handle: &Handle, data: Arc<ClusterData>, node_address: SocketAddr) -> BoxedEmptyFuture {
 		let disconnected_nodes = data.connections.disconnected_nodes().keys().cloned().collect();
-		Box::new(net_connect(&node_address, handle, data.self_key_pair.clone(), disconnected_nodes)
+		let tls_config = data.config.tls.as_ref().map(|tls| tls.client_config());
+		Box::new(net_connect(&node_address, handle, data.self_key_pair.clone(), disconnected_nodes, tls_config)
 			.then(move |result| ClusterCore::process_connection_result(data, Some(node_address), result))
 			.then(|_| finished(())))
 	}
@@ -327,7 +332,8 @@ impl ClusterCore {
 
 	/// Accept connection future.
 	fn accept_connection_future(handle: &Handle, data: Arc<ClusterData>, stream: TcpStream, node_address: SocketAddr) -> BoxedEmptyFuture {
-		Box::new(net_accept_connection(node_address, stream, handle, data.self_key_pair.clone())
+		let tls_config = data.config.tls.as_ref().map(|tls| tls.server_config());
+		Box::new(net_accept_connection(node_address, stream, handle, data.self_key_pair.clone(), tls_config)
 			.then(move |result| ClusterCore::process_connection_result(data, None, result))
 			.then(|_| finished(())))
 	}
@@ -348,6 +354,11 @@ impl ClusterCore {
 	fn maintain(data: Arc<ClusterData>) {
 		trace!(target: "secretstore_net", "{}: executing maintain procedures", data.self_key_pair.public());
 
+		// pick up rotated certificates: new connections are using new certificates, while existing are left intact
+		if let Some(ref tls) = data.config.tls {
+			tls.reload_if_changed();
+		}
+
 		ClusterCore::keep_alive(data.clone());
 		ClusterCore::connect_disconnected_nodes(data.clone());
 		data.sessions.stop_stalled_sessions();
@@ -1074,6 +1085,21 @@ impl ClusterClient for ClusterClientImpl {
 			session, &self.data.sessions.admin_sessions)
 	}
 
+	fn new_share_add_session(&self, session_id: SessionId, new_nodes_set: BTreeSet<NodeId>, old_set_signature: Signature, new_set_signature: Signature) -> Result<Arc<AdminSession>, Error> {
+		let key_share = self.data.config.key_storage.get(&session_id)?.ok_or(Error::ServerKeyIsNotFound)?;
+		let version = key_share.last_version()?.hash.clone();
+
+		let cluster = create_cluster_view(&self.data, true)?;
+		let creation_data = Some(AdminSessionCreationData::ShareAdd(version.clone()));
+		let session = self.data.sessions.admin_sessions.insert(cluster, self.data.self_key_pair.public().clone(), session_id, None, true, creation_data)?;
+		let initialization_result = session.as_share_add().expect("share add session is created; qed")
+			.initialize(Some(version), Some(new_nodes_set), Some(old_set_signature), Some(new_set_signature));
+
+		Self::process_initialization_result(
+			initialization_result,
+			session, &self.data.sessions.admin_sessions)
+	}
+
 	fn add_generation_listener(&self, listener: Arc<ClusterSessionsListener<GenerationSession>>) {
 		self.data.sessions.generation_sessions.add_listener(listener);
 	}
@@ -1122,8 +1148,9 @@ pub mod tests {
 	use tokio_core::reactor::Core;
 	use ethereum_types::{Address, H256};
 	use ethkey::{Random, Generator, Public, Signature, sign};
+	use types::TlsConfiguration;
 	use key_server_cluster::{NodeId, SessionId, Requester, Error, DummyAclStorage, DummyKeyStorage,
-		MapKeyServerSet, PlainNodeKeyPair, KeyStorage};
+		MapKeyServerSet, PlainNodeKeyPair, KeyStorage, TlsContext};
 	use key_server_cluster::message::Message;
 	use key_server_cluster::cluster::{Cluster, ClusterCore, ClusterConfiguration, ClusterClient, ClusterState};
 	use key_server_cluster::cluster_sessions::{ClusterSession, AdminSession, ClusterSessionsListener};
@@ -1167,6 +1194,7 @@ pub mod tests {
 
 		fn new_key_version_negotiation_session(&self, _session_id: SessionId) -> Result<Arc<KeyVersionNegotiationSession<KeyVersionNegotiationSessionTransport>>, Error> { unimplemented!("test-only") }
 		fn new_servers_set_change_session(&self, _session_id: Option<SessionId>, _migration_id: Option<H256>, _new_nodes_set: BTreeSet<NodeId>, _old_set_signature: Signature, _new_set_signature: Signature) -> Result<Arc<AdminSession>, Error> { unimplemented!("test-only") }
+		fn new_share_add_session(&self, _session_id: SessionId, _new_nodes_set: BTreeSet<NodeId>, _old_set_signature: Signature, _new_set_signature: Signature) -> Result<Arc<AdminSession>, Error> { unimplemented!("test-only") }
 
 		fn add_generation_listener(&self, _listener: Arc<ClusterSessionsListener<GenerationSession>>) {}
 		fn add_decryption_listener(&self, _listener: Arc<ClusterSessionsListener<DecryptionSession>>) {}
@@ -1262,6 +1290,10 @@ pub mod tests {
 	}
 
 	pub fn make_clusters(core: &Core, ports_begin: u16, num_nodes: usize) -> Vec<Arc<ClusterCore>> {
+		make_clusters_with_tls(core, ports_begin, num_nodes, None)
+	}
+
+	pub fn make_clusters_with_tls(core: &Core, ports_begin: u16, num_nodes: usize, tls: Option<Arc<TlsContext>>) -> Vec<Arc<ClusterCore>> {
 		let key_pairs: Vec<_> = (0..num_nodes).map(|_| Random.generate().unwrap()).collect();
 		let cluster_params: Vec<_> = (0..num_nodes).map(|i| ClusterConfiguration {
 			threads: 1,
@@ -1275,6 +1307,7 @@ pub mod tests {
 			acl_storage: Arc::new(DummyAclStorage::default()),
 			admin_public: None,
 			auto_migrate_enabled: false,
+			tls: tls.clone(),
 		}).collect();
 		let clusters: Vec<_> = cluster_params.into_iter().enumerate()
 			.map(|(_, params)| ClusterCore::new(core.handle(), params).unwrap())
@@ -1300,6 +1333,20 @@ pub mod tests {
 		loop_until(&mut core, TIMEOUT, || clusters.iter().all(all_connections_established));
 	}
 
+	#[test]
+	fn cluster_connects_to_other_nodes_over_tls() {
+		let mut core = Core::new().unwrap();
+		let tls = TlsContext::new(TlsConfiguration {
+			certificate_path: concat!(env!("CARGO_MANIFEST_DIR"), "/res/tls/node1.crt").into(),
+			private_key_path: concat!(env!("CARGO_MANIFEST_DIR"), "/res/tls/node1.key").into(),
+			ca_path: concat!(env!("CARGO_MANIFEST_DIR"), "/res/tls/ca.crt").into(),
+		}).unwrap();
+		let clusters = make_clusters_with_tls(&core, 6150, 3, Some(Arc::new(tls)));
+		run_clusters(&clusters);
+		// TLS handshakes are slow in debug builds
+		loop_until(&mut core, Duration::from_secs(5), || clusters.iter().all(all_connections_established));
+	}
+
 	#[test]
 	fn cluster_wont_start_generation_session_if_not_fully_connected() {
 		let core = Core::new().unwrap();
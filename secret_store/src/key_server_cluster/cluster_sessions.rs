@@ -494,6 +494,13 @@ impl Ord for SessionIdWithSubSession {
 }
 
 impl AdminSession {
+	pub fn as_share_add(&self) -> Option<&ShareAddSessionImpl<ShareAddTransport>> {
+		match *self {
+			AdminSession::ShareAdd(ref session) => Some(session),
+			_ => None
+		}
+	}
+
 	pub fn as_servers_set_change(&self) -> Option<&ServersSetChangeSessionImpl> {
 		match *self {
 			AdminSession::ServersSetChange(ref session) => Some(session),
@@ -591,6 +598,7 @@ mod tests {
 			acl_storage: Arc::new(DummyAclStorage::default()),
 			admin_public: Some(Random.generate().unwrap().public().clone()),
 			auto_migrate_enabled: false,
+			tls: None,
 		};
 		ClusterSessions::new(&config, Arc::new(SimpleServersSetChangeSessionCreatorConnector {
 			admin_public: Some(Random.generate().unwrap().public().clone()),
@@ -18,25 +18,38 @@ use std::sync::Arc;
 use std::net::Shutdown;
 use std::io::{Read, Write, Error};
 use futures::Poll;
+use parking_lot::Mutex;
+use rustls::{ClientSession, ServerSession};
 use tokio_io::{AsyncRead, AsyncWrite};
 use tokio_core::net::TcpStream;
+use tokio_rustls::TlsStream;
 
-/// Read+Write implementation for Arc<TcpStream>.
+/// Stream of connection between key servers.
+pub enum NetStream {
+	/// Plain TCP stream.
+	Plain(TcpStream),
+	/// TLS stream of outbound connection.
+	TlsClient(TlsStream<TcpStream, ClientSession>),
+	/// TLS stream of inbound connection.
+	TlsServer(TlsStream<TcpStream, ServerSession>),
+}
+
+/// Read+Write implementation for shared connection stream.
 pub struct SharedTcpStream {
-	io: Arc<TcpStream>,
+	io: Arc<Mutex<NetStream>>,
 }
 
 impl SharedTcpStream {
-	pub fn new(a: Arc<TcpStream>) -> Self {
+	pub fn new(a: Arc<Mutex<NetStream>>) -> Self {
 		SharedTcpStream {
 			io: a,
 		}
 	}
 }
 
-impl From<TcpStream> for SharedTcpStream {
-	fn from(a: TcpStream) -> Self {
-		SharedTcpStream::new(Arc::new(a))
+impl From<NetStream> for SharedTcpStream {
+	fn from(a: NetStream) -> Self {
+		SharedTcpStream::new(Arc::new(Mutex::new(a)))
 	}
 }
 
@@ -44,23 +57,23 @@ impl AsyncRead for SharedTcpStream {}
 
 impl AsyncWrite for SharedTcpStream {
 	fn shutdown(&mut self) -> Poll<(), Error> {
-		self.io.shutdown(Shutdown::Both).map(Into::into)
+		self.io.lock().shutdown()
 	}
 }
 
 impl Read for SharedTcpStream {
 	fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
-		Read::read(&mut (&*self.io as &TcpStream), buf)
+		self.io.lock().read(buf)
 	}
 }
 
 impl Write for SharedTcpStream {
 	fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
-		Write::write(&mut (&*self.io as &TcpStream), buf)
+		self.io.lock().write(buf)
 	}
 
 	fn flush(&mut self) -> Result<(), Error> {
-		Write::flush(&mut (&*self.io as &TcpStream))
+		self.io.lock().flush()
 	}
 }
 
@@ -69,3 +82,43 @@ impl Clone for SharedTcpStream {
 		SharedTcpStream::new(self.io.clone())
 	}
 }
+
+impl AsyncRead for NetStream {}
+
+impl AsyncWrite for NetStream {
+	fn shutdown(&mut self) -> Poll<(), Error> {
+		match *self {
+			NetStream::Plain(ref stream) => stream.shutdown(Shutdown::Both).map(Into::into),
+			NetStream::TlsClient(ref mut stream) => stream.shutdown(),
+			NetStream::TlsServer(ref mut stream) => stream.shutdown(),
+		}
+	}
+}
+
+impl Read for NetStream {
+	fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+		match *self {
+			NetStream::Plain(ref mut stream) => stream.read(buf),
+			NetStream::TlsClient(ref mut stream) => stream.read(buf),
+			NetStream::TlsServer(ref mut stream) => stream.read(buf),
+		}
+	}
+}
+
+impl Write for NetStream {
+	fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+		match *self {
+			NetStream::Plain(ref mut stream) => stream.write(buf),
+			NetStream::TlsClient(ref mut stream) => stream.write(buf),
+			NetStream::TlsServer(ref mut stream) => stream.write(buf),
+		}
+	}
+
+	fn flush(&mut self) -> Result<(), Error> {
+		match *self {
+			NetStream::Plain(ref mut stream) => stream.flush(),
+			NetStream::TlsClient(ref mut stream) => stream.flush(),
+			NetStream::TlsServer(ref mut stream) => stream.flush(),
+		}
+	}
+}
@@ -25,6 +25,7 @@ pub use super::serialization::{SerializableSignature, SerializableH256, Serializ
 	SerializableRequester, SerializableMessageHash, SerializableAddress};
 pub use self::cluster::{ClusterCore, ClusterConfiguration, ClusterClient};
 pub use self::cluster_sessions::{ClusterSession, ClusterSessionsListener};
+pub use self::net::TlsContext;
 #[cfg(test)]
 pub use self::cluster::tests::DummyClusterClient;
 
@@ -35,6 +36,8 @@ pub use super::key_storage::tests::DummyKeyStorage;
 pub use super::acl_storage::DummyAclStorage;
 #[cfg(test)]
 pub use super::key_server_set::tests::MapKeyServerSet;
+#[cfg(test)]
+pub use self::jobs::servers_set_change_access_job::ordered_nodes_hash;
 
 pub type SessionId = ServerKeyId;
 
@@ -18,27 +18,43 @@ use std::io;
 use std::sync::Arc;
 use std::net::SocketAddr;
 use std::time::Duration;
-use futures::{Future, Poll};
+use futures::{Future, Poll, Async};
+use rustls::ServerConfig;
 use tokio_core::reactor::Handle;
 use tokio_core::net::TcpStream;
+use tokio_rustls::{ServerConfigExt, AcceptAsync};
 use key_server_cluster::{Error, NodeKeyPair};
-use key_server_cluster::io::{accept_handshake, Handshake, Deadline, deadline};
+use key_server_cluster::io::{accept_handshake, Handshake, Deadline, deadline, NetStream};
 use key_server_cluster::net::Connection;
 
 /// Create future for accepting incoming connection.
-pub fn accept_connection(address: SocketAddr, stream: TcpStream, handle: &Handle, self_key_pair: Arc<NodeKeyPair>) -> Deadline<AcceptConnection> {
+pub fn accept_connection(address: SocketAddr, stream: TcpStream, handle: &Handle, self_key_pair: Arc<NodeKeyPair>, tls_config: Option<Arc<ServerConfig>>) -> Deadline<AcceptConnection> {
+	let state = match tls_config {
+		Some(tls_config) => AcceptState::TlsAccept(tls_config.accept_async(stream)),
+		None => AcceptState::Handshake(accept_handshake(NetStream::Plain(stream), self_key_pair.clone())),
+	};
 	let accept = AcceptConnection {
-		handshake: accept_handshake(stream, self_key_pair),
+		state: state,
 		address: address,
+		self_key_pair: self_key_pair,
 	};
 
 	deadline(Duration::new(5, 0), handle, accept).expect("Failed to create timeout")
 }
 
+/// Accept connection state.
+enum AcceptState {
+	/// TLS handshake (only when TLS is enabled).
+	TlsAccept(AcceptAsync<TcpStream>),
+	/// Key server handshake.
+	Handshake(Handshake<NetStream>),
+}
+
 /// Future for accepting incoming connection.
 pub struct AcceptConnection {
-	handshake: Handshake<TcpStream>,
+	state: AcceptState,
 	address: SocketAddr,
+	self_key_pair: Arc<NodeKeyPair>,
 }
 
 impl Future for AcceptConnection {
@@ -46,7 +62,28 @@ impl Future for AcceptConnection {
 	type Error = io::Error;
 
 	fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-		let (stream, result) = try_ready!(self.handshake.poll());
+		let next_state = match self.state {
+			AcceptState::TlsAccept(ref mut future) => match future.poll() {
+				Ok(Async::Ready(stream)) => AcceptState::Handshake(accept_handshake(NetStream::TlsServer(stream), self.self_key_pair.clone())),
+				Ok(Async::NotReady) => return Ok(Async::NotReady),
+				Err(err) => return Ok(Async::Ready(Err(Error::Io(format!("TLS handshake has failed: {}", err))))),
+			},
+			AcceptState::Handshake(_) => return self.poll_handshake(),
+		};
+
+		// by polling again, we register new future
+		self.state = next_state;
+		self.poll()
+	}
+}
+
+impl AcceptConnection {
+	fn poll_handshake(&mut self) -> Poll<Result<Connection, Error>, io::Error> {
+		let handshake = match self.state {
+			AcceptState::Handshake(ref mut handshake) => handshake,
+			AcceptState::TlsAccept(_) => unreachable!("poll_handshake is only called in Handshake state; qed"),
+		};
+		let (stream, result) = try_ready!(handshake.poll());
 		let result = match result {
 			Ok(result) => result,
 			Err(err) => return Ok(Err(err).into()),
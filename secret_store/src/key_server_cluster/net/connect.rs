@@ -22,17 +22,21 @@ use std::net::SocketAddr;
 use futures::{Future, Poll, Async};
 use tokio_core::reactor::Handle;
 use tokio_core::net::{TcpStream, TcpStreamNew};
+use rustls::ClientConfig;
+use tokio_rustls::{ClientConfigExt, ConnectAsync};
+use webpki::DNSNameRef;
 use key_server_cluster::{Error, NodeId, NodeKeyPair};
-use key_server_cluster::io::{handshake, Handshake, Deadline, deadline};
-use key_server_cluster::net::Connection;
+use key_server_cluster::io::{handshake, Handshake, Deadline, deadline, NetStream};
+use key_server_cluster::net::{Connection, TLS_SERVER_NAME};
 
 /// Create future for connecting to other node.
-pub fn connect(address: &SocketAddr, handle: &Handle, self_key_pair: Arc<NodeKeyPair>, trusted_nodes: BTreeSet<NodeId>) -> Deadline<Connect> {
+pub fn connect(address: &SocketAddr, handle: &Handle, self_key_pair: Arc<NodeKeyPair>, trusted_nodes: BTreeSet<NodeId>, tls_config: Option<Arc<ClientConfig>>) -> Deadline<Connect> {
 	let connect = Connect {
 		state: ConnectState::TcpConnect(TcpStream::connect(address, handle)),
 		address: address.clone(),
 		self_key_pair: self_key_pair,
 		trusted_nodes: trusted_nodes,
+		tls_config: tls_config,
 	};
 
 	deadline(Duration::new(5, 0), handle, connect).expect("Failed to create timeout")
@@ -40,7 +44,8 @@ pub fn connect(address: &SocketAddr, handle: &Handle, self_key_pair: Arc<NodeKey
 
 enum ConnectState {
 	TcpConnect(TcpStreamNew),
-	Handshake(Handshake<TcpStream>),
+	TlsConnect(ConnectAsync<TcpStream>),
+	Handshake(Handshake<NetStream>),
 	Connected,
 }
 
@@ -50,6 +55,7 @@ pub struct Connect {
 	address: SocketAddr,
 	self_key_pair: Arc<NodeKeyPair>,
 	trusted_nodes: BTreeSet<NodeId>,
+	tls_config: Option<Arc<ClientConfig>>,
 }
 
 impl Future for Connect {
@@ -60,7 +66,25 @@ impl Future for Connect {
 		let (next, result) = match self.state {
 			ConnectState::TcpConnect(ref mut future) => {
 				let stream = try_ready!(future.poll());
-				let handshake = handshake(stream, self.self_key_pair.clone(), self.trusted_nodes.clone());
+				match self.tls_config {
+					Some(ref tls_config) => {
+						let server_name = DNSNameRef::try_from_ascii_str(TLS_SERVER_NAME)
+							.expect("TLS_SERVER_NAME is a valid DNS name; qed");
+						(ConnectState::TlsConnect(tls_config.connect_async(server_name, stream)), Async::NotReady)
+					},
+					None => {
+						let handshake = handshake(NetStream::Plain(stream), self.self_key_pair.clone(), self.trusted_nodes.clone());
+						(ConnectState::Handshake(handshake), Async::NotReady)
+					},
+				}
+			},
+			ConnectState::TlsConnect(ref mut future) => {
+				let stream = match future.poll() {
+					Ok(Async::Ready(stream)) => stream,
+					Ok(Async::NotReady) => return Ok(Async::NotReady),
+					Err(err) => return Ok(Async::Ready(Err(Error::Io(format!("TLS handshake has failed: {}", err))))),
+				};
+				let handshake = handshake(NetStream::TlsClient(stream), self.self_key_pair.clone(), self.trusted_nodes.clone());
 				(ConnectState::Handshake(handshake), Async::NotReady)
 			},
 			ConnectState::Handshake(ref mut future) => {
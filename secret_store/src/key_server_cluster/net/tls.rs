@@ -0,0 +1,222 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::sync::Arc;
+use std::time::SystemTime;
+use parking_lot::RwLock;
+use rustls::{Certificate, PrivateKey, ClientConfig, ServerConfig};
+use rustls::internal::pemfile;
+use key_server_cluster::Error;
+use types::TlsConfiguration;
+
+/// DNS name that certificates of all key servers must be issued for.
+/// Key servers are identified by their public keys, so the name is the same for every node.
+pub const TLS_SERVER_NAME: &'static str = "secretstore";
+
+/// Mutual TLS context, shared by all connections of the key server.
+/// Certificates are re-read from disk when files are changed. New configuration is only
+/// used by new connections, so established connections survive the rotation.
+pub struct TlsContext {
+	/// Paths to certificates && private key.
+	config: TlsConfiguration,
+	/// Currently active configuration.
+	state: RwLock<TlsState>,
+}
+
+/// Loaded TLS configuration.
+struct TlsState {
+	/// Modification times of certificate, private key and CA files.
+	modified: Vec<Option<SystemTime>>,
+	/// Configuration of outbound connections.
+	client: Arc<ClientConfig>,
+	/// Configuration of inbound connections.
+	server: Arc<ServerConfig>,
+}
+
+impl TlsContext {
+	/// Load TLS context from files.
+	pub fn new(config: TlsConfiguration) -> Result<Self, Error> {
+		let state = TlsState::load(&config)?;
+		Ok(TlsContext {
+			config: config,
+			state: RwLock::new(state),
+		})
+	}
+
+	/// Configuration for new outbound connection.
+	pub fn client_config(&self) -> Arc<ClientConfig> {
+		self.state.read().client.clone()
+	}
+
+	/// Configuration for new inbound connection.
+	pub fn server_config(&self) -> Arc<ServerConfig> {
+		self.state.read().server.clone()
+	}
+
+	/// Reload configuration if any of files has been changed since last load.
+	/// If new files are invalid, previous configuration is kept.
+	pub fn reload_if_changed(&self) -> bool {
+		if modification_times(&self.config) == self.state.read().modified {
+			return false;
+		}
+
+		match TlsState::load(&self.config) {
+			Ok(state) => {
+				*self.state.write() = state;
+				info!(target: "secretstore_net", "Reloaded TLS certificates from {}", self.config.certificate_path);
+				true
+			},
+			Err(error) => {
+				warn!(target: "secretstore_net", "Failed to reload TLS certificates: {}. Previous certificates are used", error);
+				// do not retry until files are changed again
+				self.state.write().modified = modification_times(&self.config);
+				false
+			},
+		}
+	}
+}
+
+impl TlsState {
+	fn load(config: &TlsConfiguration) -> Result<Self, Error> {
+		let modified = modification_times(config);
+		let certificates = read_certificates(&config.certificate_path)?;
+		let private_key = read_private_key(&config.private_key_path)?;
+		let ca_certificates = read_certificates(&config.ca_path)?;
+
+		let mut server = ServerConfig::new();
+		server.set_single_cert(certificates.clone(), private_key.clone());
+		server.set_client_auth_roots(ca_certificates.clone(), true);
+
+		let mut client = ClientConfig::new();
+		for ca_certificate in &ca_certificates {
+			client.root_store.add(ca_certificate)
+				.map_err(|e| Error::Internal(format!("invalid CA certificate in {}: {:?}", config.ca_path, e)))?;
+		}
+		client.set_single_client_cert(certificates, private_key);
+
+		Ok(TlsState {
+			modified: modified,
+			client: Arc::new(client),
+			server: Arc::new(server),
+		})
+	}
+}
+
+fn modification_times(config: &TlsConfiguration) -> Vec<Option<SystemTime>> {
+	[&config.certificate_path, &config.private_key_path, &config.ca_path].iter()
+		.map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+		.collect()
+}
+
+fn read_certificates(path: &str) -> Result<Vec<Certificate>, Error> {
+	let mut reader = BufReader::new(File::open(path)?);
+	match pemfile::certs(&mut reader) {
+		Ok(ref certificates) if certificates.is_empty() => Err(Error::Internal(format!("no certificates found in {}", path))),
+		Ok(certificates) => Ok(certificates),
+		Err(_) => Err(Error::Internal(format!("invalid certificates file {}", path))),
+	}
+}
+
+fn read_private_key(path: &str) -> Result<PrivateKey, Error> {
+	let pkcs8_keys = pemfile::pkcs8_private_keys(&mut BufReader::new(File::open(path)?))
+		.map_err(|_| Error::Internal(format!("invalid private key file {}", path)))?;
+	let rsa_keys = pemfile::rsa_private_keys(&mut BufReader::new(File::open(path)?))
+		.map_err(|_| Error::Internal(format!("invalid private key file {}", path)))?;
+	pkcs8_keys.into_iter().chain(rsa_keys.into_iter()).next()
+		.ok_or_else(|| Error::Internal(format!("no private key found in {}", path)))
+}
+
+#[cfg(test)]
+mod tests {
+	extern crate tempdir;
+
+	use std::fs::{self, File};
+	use std::io::Write;
+	use std::sync::Arc;
+	use std::thread;
+	use std::time::Duration;
+	use self::tempdir::TempDir;
+	use types::TlsConfiguration;
+	use super::TlsContext;
+
+	fn fixture(name: &str) -> String {
+		format!("{}/res/tls/{}", env!("CARGO_MANIFEST_DIR"), name)
+	}
+
+	/// Copy fixture to given path, making sure that modification time of the file changes.
+	fn replace_with_fixture(path: &str, name: &str) {
+		let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+		loop {
+			fs::copy(fixture(name), path).unwrap();
+			if fs::metadata(path).and_then(|m| m.modified()).ok() != modified {
+				break;
+			}
+			thread::sleep(Duration::from_millis(10));
+		}
+	}
+
+	#[test]
+	fn tls_context_fails_on_missing_or_invalid_files() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = |name: &str| tempdir.path().join(name).to_str().unwrap().to_owned();
+		let config = TlsConfiguration {
+			certificate_path: path("node.crt"),
+			private_key_path: path("node.key"),
+			ca_path: path("ca.crt"),
+		};
+		assert!(TlsContext::new(config.clone()).is_err());
+
+		for name in &["node.crt", "node.key", "ca.crt"] {
+			File::create(path(name)).unwrap().write_all(b"not a pem file").unwrap();
+		}
+		assert!(TlsContext::new(config).is_err());
+	}
+
+	#[test]
+	fn tls_context_reloads_changed_certificates() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = |name: &str| tempdir.path().join(name).to_str().unwrap().to_owned();
+		let config = TlsConfiguration {
+			certificate_path: path("node.crt"),
+			private_key_path: path("node.key"),
+			ca_path: path("ca.crt"),
+		};
+		replace_with_fixture(&config.certificate_path, "node1.crt");
+		replace_with_fixture(&config.private_key_path, "node1.key");
+		replace_with_fixture(&config.ca_path, "ca.crt");
+
+		let context = TlsContext::new(config.clone()).unwrap();
+		let (client_config, server_config) = (context.client_config(), context.server_config());
+		assert!(!context.reload_if_changed());
+		assert!(Arc::ptr_eq(&client_config, &context.client_config()));
+
+		// rotate certificate of the node
+		replace_with_fixture(&config.certificate_path, "node2.crt");
+		replace_with_fixture(&config.private_key_path, "node2.key");
+		assert!(context.reload_if_changed());
+		assert!(!Arc::ptr_eq(&client_config, &context.client_config()));
+		assert!(!Arc::ptr_eq(&server_config, &context.server_config()));
+
+		// invalid certificate is not loaded && previous configuration is kept
+		let (client_config, server_config) = (context.client_config(), context.server_config());
+		File::create(&config.certificate_path).unwrap().write_all(b"not a pem file").unwrap();
+		assert!(!context.reload_if_changed());
+		assert!(Arc::ptr_eq(&client_config, &context.client_config()));
+		assert!(Arc::ptr_eq(&server_config, &context.server_config()));
+	}
+}
@@ -263,6 +263,13 @@ impl CachedContract {
 		};
 		contract.update_contract_address();
 
+		// read servers set from registry right away, so that the node doesn't stay isolated until the next block
+		if contract.contract_address_source.is_some() {
+			if let Some(client) = contract.client.get() {
+				contract.read_from_registry(&*client);
+			}
+		}
+
 		Ok(contract)
 	}
 
@@ -576,11 +583,33 @@ fn block_confirmations(client: &BlockChainClient, block: H256) -> Option<u64> {
 pub mod tests {
 	use std::collections::BTreeMap;
 	use std::net::SocketAddr;
-	use ethereum_types::H256;
+	use bytes::Bytes;
+	use ethereum_types::{H256, Address};
 	use ethkey::Public;
-	use super::{update_future_set, update_number_of_confirmations, FutureNewSet,
+	use super::{update_future_set, update_number_of_confirmations, FutureNewSet, CachedContract, KeyServerSubset,
 		KeyServerSet, KeyServerSetSnapshot, MIGRATION_CONFIRMATIONS_REQUIRED};
 
+	/// Key server subset with the registry contents given as (address, public, ip) entries.
+	struct DummyKeyServerSubset(Vec<(Address, Bytes, String)>);
+
+	impl<F: Fn(Vec<u8>) -> Result<Vec<u8>, String>> KeyServerSubset<F> for DummyKeyServerSubset {
+		fn read_list(&self, _f: &F) -> Result<Vec<Address>, String> {
+			Ok(self.0.iter().map(|s| s.0.clone()).collect())
+		}
+
+		fn read_public(&self, address: Address, _f: &F) -> Result<Bytes, String> {
+			self.0.iter().find(|s| s.0 == address).map(|s| s.1.clone()).ok_or_else(|| "unknown key server".into())
+		}
+
+		fn read_address(&self, address: Address, _f: &F) -> Result<String, String> {
+			self.0.iter().find(|s| s.0 == address).map(|s| s.2.clone()).ok_or_else(|| "unknown key server".into())
+		}
+	}
+
+	fn read_key_server_set(subset: DummyKeyServerSubset) -> BTreeMap<Public, SocketAddr> {
+		CachedContract::read_key_server_set(subset, |_| Err("no contract calls in tests".into()))
+	}
+
 	#[derive(Default)]
 	pub struct MapKeyServerSet {
 		is_isolated: bool,
@@ -825,4 +854,35 @@ pub mod tests {
 		}));
 		assert_eq!(snapshot, snapshot_copy);
 	}
+
+	#[test]
+	fn key_server_set_is_read_from_registry() {
+		let key_servers = read_key_server_set(DummyKeyServerSubset(vec![
+			(1.into(), Public::from(1).to_vec(), "127.0.0.1:12000".into()),
+			(2.into(), Public::from(2).to_vec(), "127.0.0.1:12001".into()),
+		]));
+		assert_eq!(key_servers, vec![
+			(1.into(), "127.0.0.1:12000".parse().unwrap()),
+			(2.into(), "127.0.0.1:12001".parse().unwrap()),
+		].into_iter().collect());
+	}
+
+	#[test]
+	fn key_servers_with_invalid_public_or_address_are_ignored() {
+		let key_servers = read_key_server_set(DummyKeyServerSubset(vec![
+			(1.into(), vec![1; 63], "127.0.0.1:12000".into()),
+			(2.into(), Public::from(2).to_vec(), "localhost".into()),
+			(3.into(), Public::from(3).to_vec(), "127.0.0.1:12002".into()),
+		]));
+		assert_eq!(key_servers, vec![(3.into(), "127.0.0.1:12002".parse().unwrap())].into_iter().collect());
+	}
+
+	#[test]
+	fn key_server_with_duplicate_address_is_ignored() {
+		let key_servers = read_key_server_set(DummyKeyServerSubset(vec![
+			(1.into(), Public::from(1).to_vec(), "127.0.0.1:12000".into()),
+			(2.into(), Public::from(2).to_vec(), "127.0.0.1:12000".into()),
+		]));
+		assert_eq!(key_servers, vec![(1.into(), "127.0.0.1:12000".parse().unwrap())].into_iter().collect());
+	}
 }
@@ -30,6 +30,7 @@ extern crate keccak_hash as hash;
 extern crate kvdb;
 extern crate parking_lot;
 extern crate rustc_hex;
+extern crate rustls;
 extern crate serde;
 extern crate serde_json;
 extern crate tiny_keccak;
@@ -37,8 +38,10 @@ extern crate tokio;
 extern crate tokio_core;
 extern crate tokio_io;
 extern crate tokio_proto;
+extern crate tokio_rustls;
 extern crate tokio_service;
 extern crate url;
+extern crate webpki;
 
 #[macro_use]
 extern crate ethabi_derive;
@@ -62,6 +65,7 @@ mod helpers;
 
 mod traits;
 mod acl_storage;
+mod audit_log;
 mod key_server;
 mod key_storage;
 mod serialization;
@@ -77,8 +81,9 @@ use ethcore::miner::Miner;
 use sync::SyncProvider;
 
 pub use types::{ServerKeyId, EncryptedDocumentKey, RequestSignature, Public,
-	Error, NodeAddress, ContractAddress, ServiceConfiguration, ClusterConfiguration};
+	Error, NodeAddress, ContractAddress, ServiceConfiguration, ClusterConfiguration, TlsConfiguration};
 pub use traits::{NodeKeyPair, KeyServer};
+pub use audit_log::NUM_COLUMNS;
 pub use self::node_key_pair::{PlainNodeKeyPair, KeyStoreNodeKeyPair};
 
 /// Start new key server instance
@@ -91,8 +96,10 @@ pub fn start(client: Arc<Client>, sync: Arc<SyncProvider>, miner: Arc<Miner>, se
 
 	let key_server_set = key_server_set::OnChainKeyServerSet::new(trusted_client.clone(), config.cluster_config.key_server_set_contract_address.take(),
 		self_key_pair.clone(), config.cluster_config.auto_migrate_enabled, config.cluster_config.nodes.clone())?;
+	let audit_log: Arc<audit_log::AuditLog> = Arc::new(audit_log::PersistentAuditLog::new(db.clone())?);
+	let acl_storage: Arc<acl_storage::AclStorage> = Arc::new(audit_log::AuditedAclStorage::new(acl_storage, audit_log.clone()));
 	let key_storage = Arc::new(key_storage::PersistentKeyStorage::new(db)?);
-	let key_server = Arc::new(key_server::KeyServerImpl::new(&config.cluster_config, key_server_set.clone(), self_key_pair.clone(), acl_storage.clone(), key_storage.clone())?);
+	let key_server = Arc::new(key_server::KeyServerImpl::new(&config.cluster_config, key_server_set.clone(), self_key_pair.clone(), acl_storage.clone(), key_storage.clone(), audit_log)?);
 	let cluster = key_server.cluster();
 	let key_server: Arc<KeyServer> = key_server;
 
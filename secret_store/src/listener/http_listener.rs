@@ -26,8 +26,10 @@ use tokio::runtime::Runtime;
 use tokio_service::Service;
 use futures::{future, Future, Stream};
 use url::percent_encoding::percent_decode;
+use hash::keccak;
 
 use traits::KeyServer;
+use audit_log::{AuditEntry, SerializableAuditEntry};
 use serialization::{SerializableEncryptedDocumentKeyShadow, SerializableBytes, SerializablePublic};
 use types::{Error, Public, MessageHash, NodeAddress, RequestSignature, ServerKeyId,
 	EncryptedDocumentKey, EncryptedDocumentKeyShadow, NodeId};
@@ -40,7 +42,11 @@ use types::{Error, Public, MessageHash, NodeAddress, RequestSignature, ServerKey
 /// To get document key shadow:						GET			/shadow/{server_key_id}/{signature}
 /// To generate Schnorr signature with server key:	GET			/schnorr/{server_key_id}/{signature}/{message_hash}
 /// To generate ECDSA signature with server key:	GET			/ecdsa/{server_key_id}/{signature}/{message_hash}
+/// To generate Schnorr signature of arbitrary data:	POST		/schnorr/{server_key_id}/{signature} + BODY: data to sign (Keccak-256 hash of data is signed)
+/// To generate ECDSA signature of arbitrary data:	POST		/ecdsa/{server_key_id}/{signature} + BODY: data to sign (Keccak-256 hash of data is signed)
 /// To change servers set:							POST		/admin/servers_set_change/{old_signature}/{new_signature} + BODY: json array of hex-encoded nodes ids
+/// To re-share single key to new servers set:		POST		/admin/reshare/{server_key_id}/{old_signature}/{new_signature} + BODY: json array of hex-encoded nodes ids
+/// To get audit log of server key:					GET			/audit/{server_key_id}/{signature}
 
 pub struct KeyServerHttpListener {
 	_runtime: Runtime,
@@ -68,6 +74,10 @@ enum Request {
 	EcdsaSignMessage(ServerKeyId, RequestSignature, MessageHash),
 	/// Change servers set.
 	ChangeServersSet(RequestSignature, RequestSignature, BTreeSet<NodeId>),
+	/// Re-share single key to new servers set.
+	ReshareKey(ServerKeyId, RequestSignature, RequestSignature, BTreeSet<NodeId>),
+	/// Get audit log of given key.
+	GetAuditLog(ServerKeyId, RequestSignature),
 }
 
 /// Cloneable http handler
@@ -193,6 +203,24 @@ impl KeyServerHttpHandler {
 						err
 					}))
 				},
+			Request::ReshareKey(document, old_set_signature, new_set_signature, new_servers_set) => {
+				return_empty(&req_uri, self.handler.key_server.upgrade()
+					.map(|key_server| key_server.reshare_key(&document, old_set_signature, new_set_signature, new_servers_set))
+					.unwrap_or(Err(Error::Internal("KeyServer is already destroyed".into())))
+					.map_err(|err| {
+						warn!(target: "secretstore", "ReshareKey request {} has failed with: {}", req_uri, err);
+						err
+					}))
+				},
+			Request::GetAuditLog(document, signature) => {
+				return_audit_log(&req_uri, self.handler.key_server.upgrade()
+					.map(|key_server| key_server.audit_log(&document, &signature.into()))
+					.unwrap_or(Err(Error::Internal("KeyServer is already destroyed".into())))
+					.map_err(|err| {
+						warn!(target: "secretstore", "GetAuditLog request {} has failed with: {}", req_uri, err);
+						err
+					}))
+			},
 			Request::Invalid => {
 				warn!(target: "secretstore", "Ignoring invalid {}-request {}", req_method, req_uri);
 				HttpResponse::new().with_status(HttpStatusCode::BadRequest)
@@ -254,6 +282,12 @@ fn return_document_key_shadow(req_uri: &Uri, document_key_shadow: Result<Encrypt
 	})))
 }
 
+fn return_audit_log(req_uri: &Uri, audit_log: Result<Vec<AuditEntry>, Error>) -> HttpResponse {
+	return_bytes(req_uri, audit_log.map(|entries| Some(entries.into_iter()
+		.map(Into::into)
+		.collect::<Vec<SerializableAuditEntry>>())))
+}
+
 fn return_bytes<T: Serialize>(req_uri: &Uri, result: Result<Option<T>, Error>) -> HttpResponse {
 	match result {
 		Ok(Some(result)) => match serde_json::to_vec(&result) {
@@ -307,7 +341,7 @@ fn parse_request(method: &HttpMethod, uri_path: &str, body: &[u8]) -> Request {
 		return parse_admin_request(method, path, body);
 	}
 
-	let (prefix, args_offset) = if &path[0] == "shadow" || &path[0] == "schnorr" || &path[0] == "ecdsa"
+	let (prefix, args_offset) = if &path[0] == "shadow" || &path[0] == "schnorr" || &path[0] == "ecdsa" || &path[0] == "audit"
 		{ (&*path[0], 1) } else { ("", 0) };
 	let args_count = path.len() - args_offset;
 	if args_count < 2 || path[args_offset].is_empty() || path[args_offset + 1].is_empty() {
@@ -342,22 +376,36 @@ fn parse_request(method: &HttpMethod, uri_path: &str, body: &[u8]) -> Request {
 			Request::SchnorrSignMessage(document, signature, message_hash),
 		("ecdsa", 3, &HttpMethod::Get, _, Some(Ok(message_hash)), _, _) =>
 			Request::EcdsaSignMessage(document, signature, message_hash),
+		("schnorr", 2, &HttpMethod::Post, _, _, _, _) if !body.is_empty() =>
+			Request::SchnorrSignMessage(document, signature, keccak(body)),
+		("ecdsa", 2, &HttpMethod::Post, _, _, _, _) if !body.is_empty() =>
+			Request::EcdsaSignMessage(document, signature, keccak(body)),
+		("audit", 2, &HttpMethod::Get, _, _, _, _) =>
+			Request::GetAuditLog(document, signature),
 		_ => Request::Invalid,
 	}
 }
 
 fn parse_admin_request(method: &HttpMethod, path: Vec<String>, body: &[u8]) -> Request {
 	let args_count = path.len();
-	if *method != HttpMethod::Post || args_count != 4 || path[1] != "servers_set_change" {
+	let (document, args_offset) = match (args_count, path.get(1).map(|p| p.as_str())) {
+		(4, Some("servers_set_change")) => (None, 2),
+		(5, Some("reshare")) => match path[2].parse() {
+			Ok(document) => (Some(document), 3),
+			_ => return Request::Invalid,
+		},
+		_ => return Request::Invalid,
+	};
+	if *method != HttpMethod::Post {
 		return Request::Invalid;
 	}
 
-	let old_set_signature = match path[2].parse() {
+	let old_set_signature = match path[args_offset].parse() {
 		Ok(signature) => signature,
 		_ => return Request::Invalid,
 	};
 
-	let new_set_signature = match path[3].parse() {
+	let new_set_signature = match path[args_offset + 1].parse() {
 		Ok(signature) => signature,
 		_ => return Request::Invalid,
 	};
@@ -367,8 +415,11 @@ fn parse_admin_request(method: &HttpMethod, path: Vec<String>, body: &[u8]) -> R
 		_ => return Request::Invalid,
 	};
 
-	Request::ChangeServersSet(old_set_signature, new_set_signature,
-		new_servers_set.into_iter().map(Into::into).collect())
+	let new_servers_set = new_servers_set.into_iter().map(Into::into).collect();
+	match document {
+		Some(document) => Request::ReshareKey(document, old_set_signature, new_set_signature, new_servers_set),
+		None => Request::ChangeServersSet(old_set_signature, new_set_signature, new_servers_set),
+	}
 }
 
 #[cfg(test)]
@@ -376,6 +427,7 @@ mod tests {
 	use std::sync::Arc;
 	use hyper::Method as HttpMethod;
 	use ethkey::Public;
+	use hash::keccak;
 	use traits::KeyServer;
 	use key_server::tests::DummyKeyServer;
 	use types::NodeAddress;
@@ -428,6 +480,16 @@ mod tests {
 			Request::EcdsaSignMessage("0000000000000000000000000000000000000000000000000000000000000001".into(),
 				"a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01".parse().unwrap(),
 				"281b6bf43cb86d0dc7b98e1b7def4a80f3ce16d28d2308f934f116767306f06c".parse().unwrap()));
+		// POST		/schnorr/{server_key_id}/{signature} + body					=> schnorr-sign hash of arbitrary data with server key
+		assert_eq!(parse_request(&HttpMethod::Post, "/schnorr/0000000000000000000000000000000000000000000000000000000000000001/a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01", b"data to sign"),
+			Request::SchnorrSignMessage("0000000000000000000000000000000000000000000000000000000000000001".into(),
+				"a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01".parse().unwrap(),
+				keccak(b"data to sign")));
+		// POST		/ecdsa/{server_key_id}/{signature} + body					=> ecdsa-sign hash of arbitrary data with server key
+		assert_eq!(parse_request(&HttpMethod::Post, "/ecdsa/0000000000000000000000000000000000000000000000000000000000000001/a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01", b"data to sign"),
+			Request::EcdsaSignMessage("0000000000000000000000000000000000000000000000000000000000000001".into(),
+				"a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01".parse().unwrap(),
+				keccak(b"data to sign")));
 		// POST		/admin/servers_set_change/{old_set_signature}/{new_set_signature} + body
 		let node1: Public = "843645726384530ffb0c52f175278143b5a93959af7864460f5a4fec9afd1450cfb8aef63dec90657f43f55b13e0a73c7524d4e9a13c051b4e5f1e53f39ecd91".parse().unwrap();
 		let node2: Public = "07230e34ebfe41337d3ed53b186b3861751f2401ee74b988bba55694e2a6f60c757677e194be2e53c3523cc8548694e636e6acb35c4e8fdc5e29d28679b9b2f3".parse().unwrap();
@@ -440,6 +502,20 @@ mod tests {
 				"b199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01".parse().unwrap(),
 				nodes,
 			));
+		// POST		/admin/reshare/{server_key_id}/{old_set_signature}/{new_set_signature} + body
+		let nodes = vec![node1].into_iter().collect();
+		assert_eq!(parse_request(&HttpMethod::Post, "/admin/reshare/0000000000000000000000000000000000000000000000000000000000000001/a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01/b199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01",
+			&r#"["0x843645726384530ffb0c52f175278143b5a93959af7864460f5a4fec9afd1450cfb8aef63dec90657f43f55b13e0a73c7524d4e9a13c051b4e5f1e53f39ecd91"]"#.as_bytes()),
+			Request::ReshareKey(
+				"0000000000000000000000000000000000000000000000000000000000000001".into(),
+				"a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01".parse().unwrap(),
+				"b199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01".parse().unwrap(),
+				nodes,
+			));
+		// GET		/audit/{server_key_id}/{signature}
+		assert_eq!(parse_request(&HttpMethod::Get, "/audit/0000000000000000000000000000000000000000000000000000000000000001/a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01", Default::default()),
+			Request::GetAuditLog("0000000000000000000000000000000000000000000000000000000000000001".into(),
+			"a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01".parse().unwrap()));
 	}
 
 	#[test]
@@ -451,6 +527,8 @@ mod tests {
 		assert_eq!(parse_request(&HttpMethod::Get, "/0000000000000000000000000000000000000000000000000000000000000001", Default::default()), Request::Invalid);
 		assert_eq!(parse_request(&HttpMethod::Get, "/0000000000000000000000000000000000000000000000000000000000000001/", Default::default()), Request::Invalid);
 		assert_eq!(parse_request(&HttpMethod::Get, "/a/b", Default::default()), Request::Invalid);
+		assert_eq!(parse_request(&HttpMethod::Post, "/schnorr/0000000000000000000000000000000000000000000000000000000000000001/a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01", Default::default()), Request::Invalid);
+		assert_eq!(parse_request(&HttpMethod::Post, "/audit/0000000000000000000000000000000000000000000000000000000000000001/a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01", Default::default()), Request::Invalid);
 		assert_eq!(parse_request(&HttpMethod::Get, "/schnorr/0000000000000000000000000000000000000000000000000000000000000001/a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01/0000000000000000000000000000000000000000000000000000000000000002/0000000000000000000000000000000000000000000000000000000000000002", Default::default()), Request::Invalid);
 		assert_eq!(parse_request(&HttpMethod::Get, "/ecdsa/0000000000000000000000000000000000000000000000000000000000000001/a199fb39e11eefb61c78a4074a53c0d4424600a3e74aad4fb9d93a26c30d067e1d4d29936de0c73f19827394a1dd049480a0d581aee7ae7546968da7d3d1c2fd01/0000000000000000000000000000000000000000000000000000000000000002/0000000000000000000000000000000000000000000000000000000000000002", Default::default()), Request::Invalid);
 		assert_eq!(parse_request(&HttpMethod::Post, "/admin/servers_set_change/xxx/yyy",
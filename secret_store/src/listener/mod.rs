@@ -22,7 +22,8 @@ mod tasks_queue;
 
 use std::collections::BTreeSet;
 use std::sync::Arc;
-use traits::{ServerKeyGenerator, DocumentKeyServer, MessageSigner, AdminSessionsServer, KeyServer};
+use traits::{ServerKeyGenerator, DocumentKeyServer, MessageSigner, AdminSessionsServer, AuditLogServer, KeyServer};
+use audit_log::AuditEntry;
 use types::{Error, Public, MessageHash, EncryptedMessageSignature, RequestSignature, ServerKeyId,
 	EncryptedDocumentKey, EncryptedDocumentKeyShadow, NodeId, Requester};
 
@@ -109,4 +110,14 @@ impl AdminSessionsServer for Listener {
 	fn change_servers_set(&self, old_set_signature: RequestSignature, new_set_signature: RequestSignature, new_servers_set: BTreeSet<NodeId>) -> Result<(), Error> {
 		self.key_server.change_servers_set(old_set_signature, new_set_signature, new_servers_set)
 	}
+
+	fn reshare_key(&self, key_id: &ServerKeyId, old_set_signature: RequestSignature, new_set_signature: RequestSignature, new_servers_set: BTreeSet<NodeId>) -> Result<(), Error> {
+		self.key_server.reshare_key(key_id, old_set_signature, new_set_signature, new_servers_set)
+	}
+}
+
+impl AuditLogServer for Listener {
+	fn audit_log(&self, key_id: &ServerKeyId, requester: &Requester) -> Result<Vec<AuditEntry>, Error> {
+		self.key_server.audit_log(key_id, requester)
+	}
 }
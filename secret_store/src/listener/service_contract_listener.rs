@@ -130,7 +130,8 @@ impl ServiceContractListener {
 	/// Create new service contract listener.
 	pub fn new(params: ServiceContractListenerParams) -> Result<Arc<ServiceContractListener>, Error> {
 		let data = Arc::new(ServiceContractListenerData {
-			last_retry: AtomicUsize::new(0),
+			// read pending requests on first enacted block => requests made while key server was offline are processed immediately
+			last_retry: AtomicUsize::new(RETRY_INTERVAL_BLOCKS),
 			retry_data: Default::default(),
 			tasks_queue: Arc::new(TasksQueue::new()),
 			contract: params.contract,
@@ -586,6 +587,8 @@ fn is_processed_by_this_key_server(key_server_set: &KeyServerSet, node: &NodeId,
 mod tests {
 	use std::sync::Arc;
 	use std::sync::atomic::Ordering;
+	use std::time::Duration;
+	use ethcore::client::{ChainNotify, ChainRoute, ChainRouteType};
 	use ethkey::{Random, Generator, KeyPair};
 	use listener::service_contract::ServiceContract;
 	use listener::service_contract::tests::DummyServiceContract;
@@ -801,6 +804,18 @@ mod tests {
 		assert_eq!(listener.data.tasks_queue.snapshot().len(), 0);
 	}
 
+	#[test]
+	fn pending_requests_are_retried_on_first_enacted_block() {
+		let listener = make_service_contract_listener(None, None, None, None, None);
+		let route = || ChainRoute::new(vec![(Default::default(), ChainRouteType::Enacted)]);
+		listener.new_blocks(vec![], vec![], route(), vec![], vec![], Duration::from_secs(0));
+		assert_eq!(listener.data.tasks_queue.snapshot().pop_back(), Some(ServiceTask::Retry));
+
+		// next retry is scheduled after RETRY_INTERVAL_BLOCKS
+		listener.new_blocks(vec![], vec![], route(), vec![], vec![], Duration::from_secs(0));
+		assert_eq!(listener.data.tasks_queue.snapshot().len(), 1);
+	}
+
 	#[test]
 	fn tasks_are_not_scheduled_on_isolated_node() {
 		let mut contract = DummyServiceContract::default();
@@ -19,6 +19,7 @@ use ethkey::{KeyPair, Signature, Error as EthKeyError};
 use ethereum_types::{H256, Address};
 use types::{Error, Public, ServerKeyId, MessageHash, EncryptedMessageSignature, RequestSignature, Requester,
 	EncryptedDocumentKey, EncryptedDocumentKeyShadow, NodeId};
+use audit_log::AuditEntry;
 
 /// Node key pair.
 pub trait NodeKeyPair: Send + Sync {
@@ -98,8 +99,18 @@ pub trait AdminSessionsServer {
 	/// WARNING: newly generated keys will be distributed among all cluster nodes. So this session
 	/// must be followed with cluster nodes change (either via contract, or config files).
 	fn change_servers_set(&self, old_set_signature: RequestSignature, new_set_signature: RequestSignature, new_servers_set: BTreeSet<NodeId>) -> Result<(), Error>;
+	/// Re-share single key so that nodes in new_servers_set (which must include current owners) became owners of its shares.
+	/// The key itself is never recovered: new shares are computed from secret subshares of current owners.
+	fn reshare_key(&self, key_id: &ServerKeyId, old_set_signature: RequestSignature, new_set_signature: RequestSignature, new_servers_set: BTreeSet<NodeId>) -> Result<(), Error>;
+}
+
+/// Audit log server.
+pub trait AuditLogServer {
+	/// Get all recorded operations on given key, ordered by time.
+	/// `requester` must be either the author of the key, or the administrator of the key server.
+	fn audit_log(&self, key_id: &ServerKeyId, requester: &Requester) -> Result<Vec<AuditEntry>, Error>;
 }
 
 /// Key server.
-pub trait KeyServer: AdminSessionsServer + DocumentKeyServer + MessageSigner + Send + Sync {
+pub trait KeyServer: AdminSessionsServer + AuditLogServer + DocumentKeyServer + MessageSigner + Send + Sync {
 }
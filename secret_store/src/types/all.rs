@@ -91,6 +91,20 @@ pub struct ClusterConfiguration {
 	/// Should key servers set change session should be started when servers set changes.
 	/// This will only work when servers set is configured using KeyServerSet contract.
 	pub auto_migrate_enabled: bool,
+	/// Mutual TLS configuration of connections between key servers. If None, TLS is not used.
+	pub tls: Option<TlsConfiguration>,
+}
+
+/// Mutual TLS configuration of connections between key servers.
+/// Files are watched for changes, so certificates could be rotated without restart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsConfiguration {
+	/// Path to PEM-encoded certificate chain of this node.
+	pub certificate_path: String,
+	/// Path to PEM-encoded private key of this node.
+	pub private_key_path: String,
+	/// Path to PEM-encoded certificate(s) of CA, which has issued certificates of all key servers.
+	pub ca_path: String,
 }
 
 /// Shadow decryption result.
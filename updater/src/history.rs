@@ -0,0 +1,171 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bookkeeping of installed release binaries in the updates directory.
+//!
+//! `latest` holds the name of the binary to run. `history` lists the previously
+//! installed binaries, oldest first, and `rolled-back` every binary which was
+//! rolled back from, so that none of them is installed again.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+const LATEST: &'static str = "latest";
+const HISTORY: &'static str = "history";
+const ROLLED_BACK: &'static str = "rolled-back";
+
+fn read(path: &Path, name: &str) -> io::Result<Option<String>> {
+	match fs::File::open(path.join(name)) {
+		Ok(mut f) => {
+			let mut content = String::new();
+			f.read_to_string(&mut content)?;
+			Ok(Some(content))
+		},
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e),
+	}
+}
+
+fn write(path: &Path, name: &str, content: &str) -> io::Result<()> {
+	// TODO: creating then writing is a bit fragile. would be nice to make it atomic.
+	fs::File::create(path.join(name)).and_then(|mut f| f.write_all(content.as_bytes()))
+}
+
+fn remove(path: &Path, name: &str) -> io::Result<()> {
+	match fs::remove_file(path.join(name)) {
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+		res => res,
+	}
+}
+
+fn lines<T: ::std::iter::FromIterator<String> + Default>(path: &Path, name: &str) -> io::Result<T> {
+	Ok(read(path, name)?
+		.map(|h| h.lines().filter(|l| !l.is_empty()).map(Into::into).collect())
+		.unwrap_or_default())
+}
+
+fn history(path: &Path) -> io::Result<Vec<String>> {
+	lines(path, HISTORY)
+}
+
+/// Name of the currently installed binary, if any.
+pub fn latest(path: &Path) -> io::Result<Option<String>> {
+	read(path, LATEST)
+}
+
+/// Names of the binaries which were rolled back from.
+pub fn rolled_back(path: &Path) -> BTreeSet<String> {
+	lines(path, ROLLED_BACK).unwrap_or_default()
+}
+
+/// Installs the binary `file`, remembering the previously installed one. Binaries beyond
+/// the `keep` most recent previous ones are deleted.
+pub fn install(path: &Path, file: &str, keep: usize) -> io::Result<()> {
+	let mut history = history(path)?;
+	history.retain(|f| f != file);
+	if let Some(previous) = latest(path)? {
+		if previous != file {
+			history.push(previous);
+		}
+	}
+
+	let excess = history.len().saturating_sub(keep);
+	for old in history.drain(..excess) {
+		trace!(target: "updater", "Removing old release binary {}", old);
+		remove(path, &old)?;
+		remove(path, &format!("{}.sig", old))?;
+	}
+
+	write(path, HISTORY, &history.join("\n"))?;
+	write(path, LATEST, file)
+}
+
+/// Switches back to the previously installed binary. The binary rolled back from is kept,
+/// but won't be installed again by the updater.
+/// Returns the name of the binary now installed, or `None` if there were no previous
+/// updates and the originally installed binary will be run.
+pub fn rollback(path: &Path) -> Result<Option<String>, String> {
+	let current = latest(path)
+		.map_err(|e| format!("Unable to read installed release: {}", e))?
+		.ok_or_else(|| "No update has been installed; there is nothing to roll back.".to_owned())?;
+	let mut history = history(path).map_err(|e| format!("Unable to read release history: {}", e))?;
+	let previous = history.pop();
+
+	let res = match previous {
+		Some(ref previous) => {
+			if !path.join(previous).exists() {
+				return Err(format!("Previous release binary {} is missing.", path.join(previous).display()));
+			}
+			write(path, HISTORY, &history.join("\n")).and_then(|_| write(path, LATEST, previous))
+		},
+		None => remove(path, LATEST),
+	};
+
+	let mut rolled_back = rolled_back(path);
+	rolled_back.insert(current);
+	let rolled_back = rolled_back.into_iter().collect::<Vec<_>>().join("\n");
+
+	res.and_then(|_| write(path, ROLLED_BACK, &rolled_back))
+		.map_err(|e| format!("Unable to roll back: {}", e))?;
+
+	Ok(previous)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::File;
+	use tempdir::TempDir;
+	use super::*;
+
+	#[test]
+	fn should_keep_previous_binaries() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path();
+		for file in &["parity-1", "parity-2", "parity-3", "parity-4"] {
+			File::create(path.join(file)).unwrap();
+			install(path, file, 2).unwrap();
+		}
+
+		assert_eq!(latest(path).unwrap(), Some("parity-4".into()));
+		assert_eq!(history(path).unwrap(), vec!["parity-2".to_owned(), "parity-3".to_owned()]);
+		assert!(!path.join("parity-1").exists());
+		assert!(path.join("parity-2").exists());
+	}
+
+	#[test]
+	fn should_roll_back_to_previous_binary() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path();
+		assert!(rollback(path).is_err());
+
+		for file in &["parity-1", "parity-2"] {
+			File::create(path.join(file)).unwrap();
+			install(path, file, 2).unwrap();
+		}
+
+		assert_eq!(rollback(path), Ok(Some("parity-1".into())));
+		assert_eq!(latest(path).unwrap(), Some("parity-1".into()));
+		assert_eq!(rolled_back(path), vec!["parity-2".to_owned()].into_iter().collect());
+
+		// back to the originally installed binary
+		assert_eq!(rollback(path), Ok(None));
+		assert_eq!(latest(path).unwrap(), None);
+		// every release rolled back from is remembered
+		assert_eq!(rolled_back(path), vec!["parity-1".to_owned(), "parity-2".to_owned()].into_iter().collect());
+	}
+}
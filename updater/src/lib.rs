@@ -21,6 +21,7 @@ extern crate ethcore;
 extern crate ethcore_bytes as bytes;
 extern crate ethcore_sync as sync;
 extern crate ethereum_types;
+extern crate ethkey;
 extern crate keccak_hash as hash;
 extern crate parity_hash_fetch as hash_fetch;
 extern crate parity_version as version;
@@ -46,10 +47,12 @@ extern crate tempdir;
 #[macro_use]
 extern crate matches;
 
+mod history;
 mod updater;
 mod types;
 mod service;
 
 pub use service::Service;
-pub use types::{ReleaseInfo, OperationsInfo, CapState, VersionInfo, ReleaseTrack};
+pub use types::{ReleaseInfo, OperationsInfo, CapState, VersionInfo, ReleaseTrack, UpdateStage, PendingUpdate};
+pub use history::rollback;
 pub use updater::{Updater, UpdateFilter, UpdatePolicy};
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use types::{CapState, ReleaseInfo, OperationsInfo, VersionInfo};
+use types::{CapState, ReleaseInfo, OperationsInfo, VersionInfo, PendingUpdate};
 
 pub trait Service: Send + Sync {
 	/// Is the currently running client capable of supporting the current chain?
@@ -34,4 +34,15 @@ pub trait Service: Send + Sync {
 
 	/// Information gathered concerning the release.
 	fn info(&self) -> Option<OperationsInfo>;
+
+	/// The update which is currently being prepared, if any.
+	fn pending_update(&self) -> Option<PendingUpdate>;
+
+	/// Postpone the pending update until the given number of blocks have been imported.
+	/// @returns `false` if there is no pending update.
+	fn defer_update(&self, blocks: u64) -> bool;
+
+	/// Switches back to the previously installed release and restarts the client.
+	/// @returns `false` if there is no previous release to switch to.
+	fn rollback(&self) -> bool;
 }
@@ -63,3 +63,29 @@ pub enum CapState {
 impl Default for CapState {
 	fn default() -> Self { CapState::Unknown }
 }
+
+/// Stage of an update which is being prepared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpdateStage {
+	/// Waiting for a block number to fetch the release.
+	Waiting,
+	/// Fetching the release binary.
+	Fetching,
+	/// Release binary fetched and ready to be installed.
+	Ready,
+	/// Release binary failed signature verification and won't be installed.
+	Rejected,
+}
+
+/// Information on an update which is being prepared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingUpdate {
+	/// The release being updated to.
+	pub release: ReleaseInfo,
+	/// Stage of the update.
+	pub stage: UpdateStage,
+	/// Block number until which the update is delayed, if any.
+	pub delayed_until: Option<u64>,
+	/// Reason the update was rejected, if it was.
+	pub error: Option<String>,
+}
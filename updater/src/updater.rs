@@ -16,25 +16,29 @@
 
 use std::cmp;
 use std::fs;
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::io::{self, Read};
+use std::path::PathBuf;
 use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 
 use parking_lot::{Mutex, MutexGuard};
 use rand::{self, Rng};
+use semver::Version;
 use target_info::Target;
 
 use bytes::Bytes;
 use ethcore::BlockNumber;
 use ethcore::filter::Filter;
 use ethcore::client::{BlockId, BlockChainClient, ChainNotify, ChainRoute};
-use ethereum_types::H256;
+use ethereum_types::{H256, Public};
+use ethkey::{self, Signature};
+use hash::keccak_buffer;
 use sync::{SyncProvider};
 use hash_fetch::{self as fetch, HashFetch};
+use history;
 use path::restrict_permissions_owner;
 use service::Service;
-use types::{ReleaseInfo, OperationsInfo, CapState, VersionInfo, ReleaseTrack};
+use types::{ReleaseInfo, OperationsInfo, CapState, VersionInfo, ReleaseTrack, PendingUpdate, UpdateStage};
 use version;
 
 use_contract!(operations_contract, "Operations", "res/operations.json");
@@ -69,6 +73,13 @@ pub struct UpdatePolicy {
 	pub max_delay: u64,
 	/// Number of blocks between each check for updates.
 	pub frequency: u64,
+	/// Releases newer than this version are ignored.
+	pub max_version: Option<Version>,
+	/// Keys trusted to sign releases. If not empty, a release is only installed
+	/// if its binary comes with a detached signature made by one of them.
+	pub trusted_keys: Vec<Public>,
+	/// Number of previously installed release binaries to keep for rolling back.
+	pub keep_previous: usize,
 }
 
 impl Default for UpdatePolicy {
@@ -82,6 +93,9 @@ impl Default for UpdatePolicy {
 			max_size: 128 * 1024 * 1024,
 			max_delay: 100,
 			frequency: 20,
+			max_version: None,
+			trusted_keys: Vec::new(),
+			keep_previous: 2,
 		}
 	}
 }
@@ -119,6 +133,13 @@ enum UpdaterStatus {
 	Installed {
 		release: ReleaseInfo,
 	},
+	/// Updater refused to install a release which failed signature verification,
+	/// and waits until it fetches the signature again.
+	Rejected {
+		release: ReleaseInfo,
+		reason: String,
+		retry_at: Instant,
+	},
 }
 
 impl Default for UpdaterStatus {
@@ -132,6 +153,8 @@ struct UpdaterState {
 	latest: Option<OperationsInfo>,
 	capability: CapState,
 	status: UpdaterStatus,
+	/// Block number until which the pending update was deferred.
+	deferred_until: Option<BlockNumber>,
 }
 
 /// Service for checking for updates and determining whether we can achieve consensus.
@@ -157,6 +180,12 @@ pub struct Updater<O = OperationsContractClient, F = fetch::Client, T = StdTimeP
 
 const CLIENT_ID: &'static str = "parity";
 
+/// Maximal size of a detached release signature.
+const MAX_SIGNATURE_SIZE: usize = 1024;
+
+/// Delay before fetching the signature of a rejected release again.
+const REJECTED_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
 lazy_static! {
 	static ref CLIENT_ID_HASH: H256 = CLIENT_ID.as_bytes().into();
 }
@@ -423,50 +452,153 @@ impl<O: OperationsClient, F: HashFetch, T: TimeProvider, R: GenRange> Updater<O,
 			match res {
 				// We've successfully fetched the binary
 				Ok(path) => {
-					let setup = |path: &Path| -> Result<(), String> {
-						let dest = self.updates_path(&Updater::update_file_name(&release.version));
-						if !dest.exists() {
-							info!(target: "updater", "Fetched latest version ({}) OK to {}", release.version, path.display());
-							fs::create_dir_all(dest.parent().expect("at least one thing pushed; qed")).map_err(|e| format!("Unable to create updates path: {:?}", e))?;
-							fs::copy(path, &dest).map_err(|e| format!("Unable to copy update: {:?}", e))?;
-							restrict_permissions_owner(&dest, false, true).map_err(|e| format!("Unable to update permissions: {}", e))?;
-							info!(target: "updater", "Copied updated binary to {}", dest.display());
+					let dest = self.updates_path(&Updater::update_file_name(&release.version));
+					let fresh = !dest.exists();
+					if fresh {
+						info!(target: "updater", "Fetched latest version ({}) OK to {}", release.version, path.display());
+						if let Err(e) = fs::create_dir_all(dest.parent().expect("at least one thing pushed; qed")) {
+							state.status = UpdaterStatus::Disabled;
+							warn!("Unable to create updates path: {:?}", e);
+							return;
+						}
+						if let Err(e) = fs::copy(&path, &dest) {
+							let _ = fs::remove_file(&dest);
+							// The fetched binary may be gone already, fetch it again
+							return self.back_off(&mut state, release, binary, retries, format!("Unable to copy update: {:?}", e));
 						}
+						info!(target: "updater", "Copied updated binary to {}", dest.display());
+					}
 
-						Ok(())
+					let setup = match fresh {
+						true => restrict_permissions_owner(&dest, false, true).map_err(|e| format!("Unable to update permissions: {}", e)),
+						false => Ok(()),
 					};
 
 					// There was a fatal error setting up the update, disable the updater
-					if let Err(err) = setup(&path) {
+					if let Err(err) = setup {
 						state.status = UpdaterStatus::Disabled;
 						warn!("{}", err);
+					} else if self.signature_missing(release) {
+						self.fetch_signature(latest, binary);
 					} else {
 						state.status = UpdaterStatus::Ready { release: release.clone() };
 						self.updater_step(state);
 					}
 				},
 				// There was an error fetching the update, apply a backoff delay before retrying
-				Err(err) => {
-					let delay = 2usize.pow(retries) as u64;
-					// cap maximum backoff to 1 day
-					let delay = cmp::min(delay, 24 * 60 * 60);
-					let backoff = (retries, self.time_provider.now() + Duration::from_secs(delay));
+				Err(err) => self.back_off(&mut state, release, binary, retries, format!("{:?}", err)),
+			}
+		}
+	}
 
-					state.status = UpdaterStatus::FetchBackoff { release: release.clone(), backoff, binary };
+	fn on_fetch_signature(&self, latest: &OperationsInfo, res: Result<PathBuf, fetch::Error>) {
+		let mut state = self.state.lock();
 
-					warn!("Unable to fetch update ({}): {:?}, retrying in {} seconds.", release.version, err, delay);
+		// Bail out if the latest release has changed in the meantime
+		if state.latest.as_ref() != Some(&latest) {
+			return;
+		}
+
+		if let UpdaterStatus::Fetching { ref release, binary, retries } = state.status.clone() {
+			let dest = self.signature_path(release);
+			let copied = res.map_err(|err| format!("{:?}", err)).and_then(|path| {
+				let copied = fs::copy(&path, &dest).map_err(|e| format!("Unable to copy signature: {:?}", e));
+				let _ = fs::remove_file(&path);
+				copied
+			});
+
+			match copied {
+				Ok(_) => {
+					info!(target: "updater", "Fetched signature of latest version ({}) to {}", release.version, dest.display());
+					state.status = UpdaterStatus::Ready { release: release.clone() };
+					self.updater_step(state);
 				},
+				Err(err) => self.back_off(&mut state, release, binary, retries, err),
+			}
+		}
+	}
+
+	/// Applies a backoff delay before retrying a failed fetch.
+	fn back_off(&self, state: &mut UpdaterState, release: &ReleaseInfo, binary: H256, retries: u32, err: String) {
+		let delay = 2usize.pow(retries) as u64;
+		// cap maximum backoff to 1 day
+		let delay = cmp::min(delay, 24 * 60 * 60);
+		let backoff = (retries, self.time_provider.now() + Duration::from_secs(delay));
+
+		state.status = UpdaterStatus::FetchBackoff { release: release.clone(), backoff, binary };
+
+		warn!("Unable to fetch update ({}): {}, retrying in {} seconds.", release.version, err, delay);
+	}
+
+	fn signature_path(&self, release: &ReleaseInfo) -> PathBuf {
+		self.updates_path(&format!("{}.sig", Updater::update_file_name(&release.version)))
+	}
+
+	/// Whether the release has to be signed, but its signature hasn't been fetched yet.
+	fn signature_missing(&self, release: &ReleaseInfo) -> bool {
+		!self.update_policy.trusted_keys.is_empty() && !self.signature_path(release).exists()
+	}
+
+	fn fetch_signature(&self, latest: &OperationsInfo, binary: H256) {
+		info!(target: "updater", "Attempting to get signature of parity binary {}", binary);
+		let weak_self = self.weak_self.lock().clone();
+		let latest = latest.clone();
+		let f = move |res: Result<PathBuf, fetch::Error>| {
+			if let Some(this) = weak_self.upgrade() {
+				this.on_fetch_signature(&latest, res)
 			}
+		};
+
+		self.fetcher.fetch_signature(
+			binary,
+			fetch::Abort::default().with_max_size(MAX_SIGNATURE_SIZE),
+			Box::new(f));
+	}
+
+	/// Checks the detached signature of a fetched release binary against the trusted keys.
+	fn verify_signature(&self, release: &ReleaseInfo) -> Result<(), String> {
+		if self.update_policy.trusted_keys.is_empty() {
+			return Ok(());
+		}
+
+		let file = Updater::update_file_name(&release.version);
+		let signature_path = self.signature_path(release);
+
+		let hash = fs::File::open(self.updates_path(&file))
+			.and_then(|f| keccak_buffer(&mut io::BufReader::new(f)))
+			.map_err(|e| format!("Unable to read update binary: {}", e))?;
+
+		let mut signature = String::new();
+		fs::File::open(&signature_path)
+			.and_then(|mut f| f.read_to_string(&mut signature))
+			.map_err(|e| format!("Unable to read signature from {}: {}", signature_path.display(), e))?;
+
+		let signature: Signature = signature.trim().trim_left_matches("0x").parse()
+			.map_err(|e| format!("Invalid signature in {}: {}", signature_path.display(), e))?;
+		let signer = ethkey::recover(&signature, &hash)
+			.map_err(|e| format!("Invalid signature in {}: {}", signature_path.display(), e))?;
+
+		if self.update_policy.trusted_keys.contains(&signer) {
+			Ok(())
+		} else {
+			Err(format!("Release {} is signed by an untrusted key {:?}", release.version, signer))
 		}
 	}
 
 	fn execute_upgrade(&self, mut state: MutexGuard<UpdaterState>) -> bool {
 		if let UpdaterStatus::Ready { ref release } = state.status.clone() {
+			if let Err(reason) = self.verify_signature(release) {
+				warn!(target: "updater", "Refusing to install update {}: {}", release.version, reason);
+				// the signature is fetched again later, in case it gets fixed.
+				let _ = fs::remove_file(self.signature_path(release));
+				let retry_at = self.time_provider.now() + REJECTED_RETRY_DELAY;
+				state.status = UpdaterStatus::Rejected { release: release.clone(), reason, retry_at };
+				return false;
+			}
+
 			let file = Updater::update_file_name(&release.version);
-			let path = self.updates_path("latest");
 
-			// TODO: creating then writing is a bit fragile. would be nice to make it atomic.
-			if let Err(err) = fs::File::create(&path).and_then(|mut f| f.write_all(file.as_bytes())) {
+			if let Err(err) = history::install(&self.update_policy.path, &file, self.update_policy.keep_previous) {
 				state.status = UpdaterStatus::Disabled;
 
 				warn!(target: "updater", "Unable to create soft-link for update {:?}", err);
@@ -491,6 +623,11 @@ impl<O: OperationsClient, F: HashFetch, T: TimeProvider, R: GenRange> Updater<O,
 	fn updater_step(&self, mut state: MutexGuard<UpdaterState>) {
 		let current_block_number = self.client.upgrade().map_or(0, |c| c.block_number(BlockId::Latest).unwrap_or(0));
 
+		// the pending update has been deferred
+		if state.deferred_until.map_or(false, |until| current_block_number < until) {
+			return;
+		}
+
 		if let Some(latest) = state.latest.clone() {
 			let fetch = |latest, binary| {
 				info!(target: "updater", "Attempting to get parity binary {}", binary);
@@ -512,6 +649,8 @@ impl<O: OperationsClient, F: HashFetch, T: TimeProvider, R: GenRange> Updater<O,
 				UpdaterStatus::Disabled => {},
 				// the update has already been installed
 				UpdaterStatus::Installed { ref release, .. } if *release == latest.track => {},
+				// the update failed verification, wait before fetching its signature again
+				UpdaterStatus::Rejected { ref release, retry_at, .. } if *release == latest.track && self.time_provider.now() < retry_at => {},
 				// we're currently fetching this update
 				UpdaterStatus::Fetching { ref release, .. } if *release == latest.track => {},
 				// the fetch has failed and we're backing off the next retry
@@ -525,6 +664,12 @@ impl<O: OperationsClient, F: HashFetch, T: TimeProvider, R: GenRange> Updater<O,
 					state.status = UpdaterStatus::Fetching { release: release.clone(), binary, retries: 1 };
 					fetch(latest, binary);
 				},
+				// fetch the signature of the rejected update again
+				UpdaterStatus::Rejected { ref release, .. } if *release == latest.track && release.binary.is_some() => {
+					let binary = release.binary.expect("checked by the match guard; qed");
+					state.status = UpdaterStatus::Fetching { release: release.clone(), binary, retries: 1 };
+					self.fetch_signature(&latest, binary);
+				},
 				// we're ready to retry the fetch after we applied a backoff for the previous failure
 				UpdaterStatus::FetchBackoff { ref release, backoff, binary } if *release == latest.track && self.time_provider.now() >= backoff.1 => {
 					state.status = UpdaterStatus::Fetching { release: release.clone(), binary, retries: backoff.0 + 1 };
@@ -557,8 +702,28 @@ impl<O: OperationsClient, F: HashFetch, T: TimeProvider, R: GenRange> Updater<O,
 							return;
 						}
 
-						let path = self.updates_path(&Updater::update_file_name(&latest.track.version));
-						if path.exists() {
+						// Bail out if the release is newer than the version we're pinned to
+						if let Some(ref max_version) = self.update_policy.max_version {
+							if latest.track.version.version > *max_version {
+								trace!(target: "updater", "Ignoring release {} newer than the maximum version {}", latest.track.version, max_version);
+								return;
+							}
+						}
+
+						// Bail out if the release has been rolled back from
+						let file = Updater::update_file_name(&latest.track.version);
+						if history::rolled_back(&self.update_policy.path).contains(&file) {
+							trace!(target: "updater", "Ignoring release {} which has been rolled back", latest.track.version);
+							return;
+						}
+
+						let path = self.updates_path(&file);
+						if path.exists() && self.signature_missing(&latest.track) {
+							info!(target: "updater", "Already fetched binary, fetching its signature.");
+							state.status = UpdaterStatus::Fetching { release: latest.track.clone(), binary, retries: 1 };
+							self.fetch_signature(&latest, binary);
+
+						} else if path.exists() {
 							info!(target: "updater", "Already fetched binary.");
 							state.status = UpdaterStatus::Ready { release: latest.track.clone() };
 							self.updater_step(state);
@@ -652,6 +817,7 @@ impl<O: OperationsClient, F: HashFetch, T: TimeProvider, R: GenRange> Updater<O,
 
 				// Update latest release
 				state.latest = Some(latest);
+				state.deferred_until = None;
 			}
 		}
 
@@ -692,16 +858,70 @@ impl<O: OperationsClient, F: HashFetch, T: TimeProvider, R: GenRange> Service fo
 	fn info(&self) -> Option<OperationsInfo> {
 		self.state.lock().latest.clone()
 	}
+
+	fn pending_update(&self) -> Option<PendingUpdate> {
+		let state = self.state.lock();
+		let (release, stage, block_number) = match state.status {
+			UpdaterStatus::Waiting { ref release, block_number, .. } => (release.clone(), UpdateStage::Waiting, Some(block_number)),
+			UpdaterStatus::Fetching { ref release, .. } |
+			UpdaterStatus::FetchBackoff { ref release, .. } => (release.clone(), UpdateStage::Fetching, None),
+			UpdaterStatus::Ready { ref release } => (release.clone(), UpdateStage::Ready, None),
+			UpdaterStatus::Rejected { ref release, ref reason, .. } => return Some(PendingUpdate {
+				release: release.clone(),
+				stage: UpdateStage::Rejected,
+				delayed_until: None,
+				error: Some(reason.clone()),
+			}),
+			_ => return None,
+		};
+
+		Some(PendingUpdate {
+			release,
+			stage,
+			delayed_until: cmp::max(block_number, state.deferred_until),
+			error: None,
+		})
+	}
+
+	fn defer_update(&self, blocks: u64) -> bool {
+		if self.pending_update().is_none() {
+			return false;
+		}
+
+		let current_block_number = self.client.upgrade().map_or(0, |c| c.block_number(BlockId::Latest).unwrap_or(0));
+		let until = current_block_number.saturating_add(blocks);
+		info!(target: "updater", "Pending update deferred until block {}", until);
+		self.state.lock().deferred_until = Some(until);
+		true
+	}
+
+	fn rollback(&self) -> bool {
+		match history::rollback(&self.update_policy.path) {
+			Ok(previous) => {
+				info!(target: "updater", "Rolled back to {}", previous.as_ref().map_or("the originally installed binary", |p| p.as_str()));
+				match *self.exit_handler.lock() {
+					Some(ref h) => (*h)(),
+					None => info!(target: "updater", "Rollback complete, ready for restart."),
+				}
+				true
+			},
+			Err(err) => {
+				warn!(target: "updater", "{}", err);
+				false
+			},
+		}
+	}
 }
 
 #[cfg(test)]
 pub mod tests {
 	use std::fs::File;
-	use std::io::Read;
+	use std::io::{Read, Write};
 	use std::sync::Arc;
 	use semver::Version;
 	use tempdir::TempDir;
 	use ethcore::client::{TestBlockChainClient, EachBlockWith};
+	use ethkey::{Random, Generator};
 	use self::fetch::Error;
 	use super::*;
 
@@ -735,11 +955,18 @@ pub mod tests {
 	#[derive(Clone)]
 	struct FakeFetch {
 		on_done: Arc<Mutex<Option<Box<Fn(Result<PathBuf, Error>) + Send>>>>,
+		on_signature: Arc<Mutex<Option<Box<Fn(Result<PathBuf, Error>) + Send>>>>,
 	}
 
 	impl FakeFetch {
 		fn new() -> FakeFetch {
-			FakeFetch { on_done: Arc::new(Mutex::new(None)) }
+			FakeFetch { on_done: Arc::new(Mutex::new(None)), on_signature: Arc::new(Mutex::new(None)) }
+		}
+
+		fn trigger_signature(&self, result: Option<PathBuf>) {
+			if let Some(ref on_done) = *self.on_signature.lock() {
+				on_done(result.ok_or(Error::NoResolution))
+			}
 		}
 
 		fn trigger(&self, result: Option<PathBuf>) {
@@ -753,6 +980,10 @@ pub mod tests {
 		fn fetch(&self, _hash: H256, _abort: fetch::Abort, on_done: Box<Fn(Result<PathBuf, Error>) + Send>) {
 			*self.on_done.lock() = Some(on_done);
 		}
+
+		fn fetch_signature(&self, _hash: H256, _abort: fetch::Abort, on_done: Box<Fn(Result<PathBuf, Error>) + Send>) {
+			*self.on_signature.lock() = Some(on_done);
+		}
 	}
 
 	#[derive(Clone)]
@@ -1117,7 +1348,11 @@ pub mod tests {
 
 	#[test]
 	fn should_stay_disabled_after_fatal_error() {
-		let (update_policy, tempdir) = update_policy();
+		let (mut update_policy, tempdir) = update_policy();
+		// a file is in the way of the updates path
+		let updates_path = tempdir.path().join("updates");
+		File::create(&updates_path).unwrap();
+		update_policy.path = updates_path.join("parity");
 		let (client, updater, operations_client, fetcher, ..) = setup(update_policy);
 		let (_, _, latest) = new_upgrade("1.0.1");
 
@@ -1125,8 +1360,9 @@ pub mod tests {
 		operations_client.set_result(Some(latest.clone()), None);
 
 		updater.poll();
-		// trigger the fetch but don't create the file on-disk. this should lead to a fatal error that disables the updater
+		// trigger the fetch, the updates path can't be created. this should lead to a fatal error that disables the updater
 		let update_file = tempdir.path().join("parity");
+		File::create(update_file.clone()).unwrap();
 		fetcher.trigger(Some(update_file));
 
 		assert_eq!(updater.state.lock().status, UpdaterStatus::Disabled);
@@ -1146,6 +1382,186 @@ pub mod tests {
 		assert_eq!(updater.state.lock().status, UpdaterStatus::Disabled);
 	}
 
+	#[test]
+	fn should_back_off_if_fetched_update_is_gone() {
+		let (update_policy, tempdir) = update_policy();
+		let (_client, updater, operations_client, fetcher, ..) = setup(update_policy);
+		let (_, latest_release, latest) = new_upgrade("1.0.1");
+
+		// mock operations contract with a new version
+		operations_client.set_result(Some(latest.clone()), None);
+
+		updater.poll();
+		// trigger the fetch but don't create the file on-disk, it's fetched again later
+		let update_file = tempdir.path().join("parity");
+		fetcher.trigger(Some(update_file));
+
+		assert_matches!(
+			updater.state.lock().status,
+			UpdaterStatus::FetchBackoff { ref release, ref backoff, .. } if *release == latest_release && backoff.0 == 1);
+	}
+
+	#[test]
+	fn should_not_update_past_max_version() {
+		let (mut update_policy, _) = update_policy();
+		update_policy.max_version = Some(Version::parse("1.0.1").unwrap());
+
+		let (_client, updater, operations_client, ..) = setup(update_policy);
+		let (_, _, latest) = new_upgrade("1.0.2");
+		operations_client.set_result(Some(latest.clone()), None);
+
+		updater.poll();
+
+		// the release is newer than the pinned version so it should be ignored
+		assert_eq!(updater.state.lock().status, UpdaterStatus::Idle);
+
+		let (_, latest_release, latest) = new_upgrade("1.0.1");
+		operations_client.set_result(Some(latest.clone()), None);
+
+		updater.poll();
+
+		assert_matches!(
+			updater.state.lock().status,
+			UpdaterStatus::Fetching { ref release, .. } if *release == latest_release);
+	}
+
+	#[test]
+	fn should_defer_pending_update() {
+		let (update_policy, _) = update_policy();
+		let (client, updater, operations_client, _, _, rng) = setup(update_policy);
+
+		let (_, latest_release, latest) = new_upgrade("1.0.1");
+		operations_client.set_result(Some(latest.clone()), Some(0));
+		rng.set_result(5);
+
+		// there's nothing to defer yet
+		assert!(!updater.defer_update(10));
+
+		updater.poll();
+
+		assert_eq!(updater.pending_update(), Some(PendingUpdate {
+			release: latest_release.clone(),
+			stage: UpdateStage::Waiting,
+			delayed_until: Some(5),
+			error: None,
+		}));
+
+		assert!(updater.defer_update(10));
+		assert_eq!(updater.pending_update().unwrap().delayed_until, Some(10));
+
+		client.add_blocks(6, EachBlockWith::Nothing);
+		updater.poll();
+
+		// the update should still be waiting since it was deferred
+		assert_matches!(
+			updater.state.lock().status,
+			UpdaterStatus::Waiting { ref release, .. } if *release == latest_release);
+
+		client.add_blocks(4, EachBlockWith::Nothing);
+		updater.poll();
+
+		assert_matches!(
+			updater.state.lock().status,
+			UpdaterStatus::Fetching { ref release, .. } if *release == latest_release);
+		assert_eq!(updater.pending_update().unwrap().stage, UpdateStage::Fetching);
+	}
+
+	#[test]
+	fn should_reject_release_without_trusted_signature() {
+		let trusted = Random.generate().unwrap();
+		let untrusted = Random.generate().unwrap();
+
+		let (mut update_policy, tempdir) = update_policy();
+		update_policy.trusted_keys = vec![trusted.public().clone()];
+
+		let (_client, updater, operations_client, fetcher, time_provider, ..) = setup(update_policy);
+		let (latest_version, latest_release, latest) = new_upgrade("1.0.1");
+		operations_client.set_result(Some(latest.clone()), None);
+
+		updater.poll();
+
+		let update_file = tempdir.path().join("parity");
+		File::create(update_file.clone()).unwrap().write_all(b"binary").unwrap();
+		fetcher.trigger(Some(update_file));
+
+		// the signature is fetched along with the binary
+		assert_matches!(
+			updater.state.lock().status,
+			UpdaterStatus::Fetching { ref release, .. } if *release == latest_release);
+		fetcher.trigger_signature(None);
+		assert_matches!(
+			updater.state.lock().status,
+			UpdaterStatus::FetchBackoff { ref release, .. } if *release == latest_release);
+
+		// signed by an untrusted key
+		let binary = tempdir.path().join(Updater::update_file_name(&latest_version));
+		let hash = keccak_buffer(&mut io::BufReader::new(File::open(&binary).unwrap())).unwrap();
+		let sign = |secret| {
+			let path = tempdir.path().join("fetched.sig");
+			let signature = ethkey::sign(secret, &hash).unwrap();
+			File::create(&path).unwrap().write_all(format!("{}", signature).as_bytes()).unwrap();
+			path
+		};
+
+		time_provider.set_result(Instant::now() + Duration::from_secs(60));
+		updater.poll();
+		fetcher.trigger(Some(binary.clone()));
+		fetcher.trigger_signature(Some(sign(untrusted.secret())));
+		assert_eq!(updater.state.lock().status, UpdaterStatus::Ready { release: latest_release.clone() });
+
+		assert!(!<TestUpdater as Service>::execute_upgrade(&*updater));
+		assert_matches!(
+			updater.state.lock().status,
+			UpdaterStatus::Rejected { ref release, .. } if *release == latest_release);
+		assert_eq!(updater.pending_update().unwrap().stage, UpdateStage::Rejected);
+		assert!(!tempdir.path().join("latest").exists());
+
+		// the signature is fetched again once the retry delay has passed
+		updater.poll();
+		assert_matches!(updater.state.lock().status, UpdaterStatus::Rejected { .. });
+
+		time_provider.set_result(Instant::now() + REJECTED_RETRY_DELAY + Duration::from_secs(120));
+		updater.poll();
+		assert_matches!(updater.state.lock().status, UpdaterStatus::Fetching { .. });
+
+		// signed by a trusted key
+		fetcher.trigger_signature(Some(sign(trusted.secret())));
+		assert_eq!(updater.state.lock().status, UpdaterStatus::Ready { release: latest_release.clone() });
+		assert!(<TestUpdater as Service>::execute_upgrade(&*updater));
+		assert_eq!(updater.state.lock().status, UpdaterStatus::Installed { release: latest_release });
+		assert!(tempdir.path().join("latest").exists());
+	}
+
+	#[test]
+	fn should_not_reinstall_rolled_back_release() {
+		let (update_policy, tempdir) = update_policy();
+		let (_client, updater, operations_client, fetcher, ..) = setup(update_policy);
+		let (_, latest_release, latest) = new_upgrade("1.0.1");
+		operations_client.set_result(Some(latest.clone()), None);
+
+		updater.poll();
+
+		let update_file = tempdir.path().join("parity");
+		File::create(update_file.clone()).unwrap();
+		fetcher.trigger(Some(update_file));
+		assert!(<TestUpdater as Service>::execute_upgrade(&*updater));
+		assert_eq!(updater.state.lock().status, UpdaterStatus::Installed { release: latest_release });
+
+		// roll back to the originally installed binary
+		assert!(<TestUpdater as Service>::rollback(&*updater));
+		assert!(!tempdir.path().join("latest").exists());
+		assert!(!<TestUpdater as Service>::rollback(&*updater));
+
+		// the release isn't picked up again
+		{
+			let mut state = updater.state.lock();
+			state.latest = None;
+			state.status = UpdaterStatus::Idle;
+		}
+		updater.poll();
+		assert_eq!(updater.state.lock().status, UpdaterStatus::Idle);
+	}
+
 	#[test]
 	fn should_ignore_current_fetch_on_new_release() {
 		let (update_policy, _) = update_policy();
@@ -14,275 +14,325 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{io, fmt};
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::{io, fs};
 
 use ethbloom;
 
-use file::{File, FileIterator};
+use file::File;
+
+/// Name of the file describing the layout of the database.
+const CONFIG_FILE_NAME: &'static str = "config";
+/// Maximal number of levels.
+const MAX_LEVELS: u32 = 8;
+
+/// Layout of the blooms database.
+///
+/// The layout is stored with the database when it's created, later changes
+/// require rebuilding the database.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+	/// Number of levels, including the bottom level of header blooms.
+	pub levels: u32,
+	/// Number of blooms represented by a single bloom of the level above.
+	/// Must be a power of two.
+	pub fan_out: u64,
+}
 
-/// Bloom positions in database files.
-#[derive(Debug)]
-struct Positions {
-	top: u64,
-	mid: u64,
-	bot: u64
+impl Default for Config {
+	fn default() -> Self {
+		Config {
+			levels: 3,
+			fan_out: 16,
+		}
+	}
 }
 
-impl Positions {
-	fn from_index(index: u64) -> Self {
-		Positions {
-			top: index >> 8,
-			mid: index >> 4,
-			bot: index,
+impl Config {
+	fn validate(&self) -> io::Result<()> {
+		if self.levels == 0 || self.levels > MAX_LEVELS {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("number of bloom levels must be between 1 and {}", MAX_LEVELS)));
+		}
+		if self.fan_out < 2 || !self.fan_out.is_power_of_two() {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "bloom fan out must be a power of two greater than 1"));
+		}
+		if self.fan_out.trailing_zeros() * (self.levels - 1) >= 64 {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "top bloom level must represent less than 2^64 blooms"));
+		}
+		Ok(())
+	}
+
+	/// Number of bottom level blooms represented by a single bloom of `level`, as a power of two.
+	fn shift(&self, level: u32) -> u32 {
+		self.fan_out.trailing_zeros() * level
+	}
+
+	fn file_name(&self, level: u32) -> String {
+		match (self.levels, level) {
+			// layout of databases created before it became configurable
+			(3, 0) => "bot.bdb".into(),
+			(3, 1) => "mid.bdb".into(),
+			(3, 2) => "top.bdb".into(),
+			(_, level) => format!("level{}.bdb", level),
+		}
+	}
+
+	/// Reads the layout of an existing database.
+	fn load(path: &Path) -> io::Result<Option<Config>> {
+		let mut contents = String::new();
+		match fs::File::open(path.join(CONFIG_FILE_NAME)) {
+			Ok(mut file) => file.read_to_string(&mut contents)?,
+			// databases created before the layout became configurable have default one
+			Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+				return Ok(match path.join("bot.bdb").exists() {
+					true => Some(Config::default()),
+					false => None,
+				});
+			},
+			Err(err) => return Err(err),
+		};
+
+		let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid blooms database config");
+		let mut config = Config::default();
+		for line in contents.lines() {
+			let mut parts = line.splitn(2, '=');
+			match (parts.next(), parts.next()) {
+				(Some("levels"), Some(levels)) => config.levels = levels.parse().map_err(|_| invalid())?,
+				(Some("fan_out"), Some(fan_out)) => config.fan_out = fan_out.parse().map_err(|_| invalid())?,
+				_ => return Err(invalid()),
+			}
 		}
+
+		Ok(Some(config))
+	}
+
+	fn save(&self, path: &Path) -> io::Result<()> {
+		let mut file = fs::File::create(path.join(CONFIG_FILE_NAME))?;
+		write!(file, "levels={}\nfan_out={}\n", self.levels, self.fan_out)?;
+		file.flush()
 	}
 }
 
 /// Blooms database.
 pub struct Database {
-	/// Top level bloom file
+	/// Bloom files, starting with the bottom level.
 	///
-	/// Every bloom represents 16 blooms on mid level
-	top: File,
-	/// Mid level bloom file
-	///
-	/// Every bloom represents 16 blooms on bot level
-	mid: File,
-	/// Bot level bloom file
-	///
-	/// Every bloom is an ethereum header bloom
-	bot: File,
+	/// Every bloom on the bottom level is an ethereum header bloom,
+	/// every bloom on the level above represents `fan_out` blooms on the level below.
+	levels: Vec<File>,
+	/// Database layout
+	config: Config,
 	/// Database path
 	path: PathBuf,
 }
 
 impl Database {
-	/// Opens blooms database.
-	pub fn open<P>(path: P) -> io::Result<Database> where P: AsRef<Path> {
+	/// Opens blooms database. The layout of an existing database takes precedence over `config`.
+	pub fn open<P>(path: P, config: Config) -> io::Result<Database> where P: AsRef<Path> {
 		let path = path.as_ref();
+		let config = match Config::load(path)? {
+			Some(stored) => stored,
+			None => {
+				config.validate()?;
+				config.save(path)?;
+				config
+			},
+		};
+		config.validate()?;
+
 		let database = Database {
-			top: File::open(path.join("top.bdb"))?,
-			mid: File::open(path.join("mid.bdb"))?,
-			bot: File::open(path.join("bot.bdb"))?,
+			levels: Self::open_levels(path, &config)?,
+			config,
 			path: path.to_owned(),
 		};
 
 		Ok(database)
 	}
 
+	fn open_levels(path: &Path, config: &Config) -> io::Result<Vec<File>> {
+		(0..config.levels)
+			.map(|level| File::open(path.join(config.file_name(level))))
+			.collect()
+	}
+
+	/// Returns the layout of the database.
+	pub fn config(&self) -> Config {
+		self.config
+	}
+
 	/// Reopens the database at the same location.
 	pub fn reopen(&mut self) -> io::Result<()> {
-		self.top = File::open(self.path.join("top.bdb"))?;
-		self.mid = File::open(self.path.join("mid.bdb"))?;
-		self.bot = File::open(self.path.join("bot.bdb"))?;
+		self.levels = Self::open_levels(&self.path, &self.config)?;
 		Ok(())
 	}
 
 	/// Insert consecutive blooms into database starting with positon from.
 	pub fn insert_blooms<'a, I, B>(&mut self, from: u64, blooms: I) -> io::Result<()>
 	where ethbloom::BloomRef<'a>: From<B>, I: Iterator<Item = B> {
+		let config = self.config;
 		for (index, bloom) in (from..).into_iter().zip(blooms.map(Into::into)) {
-			let pos = Positions::from_index(index);
-
 			// constant forks make lead to increased ration of false positives in bloom filters
-			// since we do not rebuild top or mid level, but we should not be worried about that
+			// since we do not rebuild upper levels, but we should not be worried about that
 			// most of the time events at block n(a) occur also on block n(b) or n+1(b)
-			self.top.accrue_bloom::<ethbloom::BloomRef>(pos.top, bloom)?;
-			self.mid.accrue_bloom::<ethbloom::BloomRef>(pos.mid, bloom)?;
-			self.bot.replace_bloom::<ethbloom::BloomRef>(pos.bot, bloom)?;
+			self.levels[0].replace_bloom::<ethbloom::BloomRef>(index, bloom)?;
+			for level in 1..config.levels {
+				self.levels[level as usize].accrue_bloom::<ethbloom::BloomRef>(index >> config.shift(level), bloom)?;
+			}
 		}
-		self.top.flush()?;
-		self.mid.flush()?;
-		self.bot.flush()
-	}
-
-	/// Returns an iterator yielding all indexes containing given bloom.
-	pub fn iterate_matching<'a, 'b, B, I, II>(&'a mut self, from: u64, to: u64, blooms: II) -> io::Result<DatabaseIterator<'a, II>>
-	where ethbloom::BloomRef<'b>: From<B>, 'b: 'a, II: IntoIterator<Item = B, IntoIter = I> + Copy, I: Iterator<Item = B> {
-		let index = from / 256 * 256;
-		let pos = Positions::from_index(index);
-
-		let iter = DatabaseIterator {
-			top: self.top.iterator_from(pos.top)?,
-			mid: self.mid.iterator_from(pos.mid)?,
-			bot: self.bot.iterator_from(pos.bot)?,
-			state: IteratorState::Top,
-			from,
-			to,
-			index,
-			blooms,
-		};
 
-		Ok(iter)
+		for file in &mut self.levels {
+			file.flush()?;
+		}
+		Ok(())
 	}
-}
 
-fn contains_any<'a, I, B>(bloom: ethbloom::Bloom, mut iterator: I) -> bool
-where ethbloom::BloomRef<'a>: From<B>, I: Iterator<Item = B> {
-	iterator.any(|item| bloom.contains_bloom(item))
-}
+	/// Returns all indexes in the range `from..to` (inclusive) containing given bloom.
+	pub fn matching<'b, B, I, II>(&self, from: u64, to: u64, blooms: II) -> io::Result<Vec<u64>>
+	where ethbloom::BloomRef<'b>: From<B>, II: IntoIterator<Item = B, IntoIter = I> + Copy, I: Iterator<Item = B> {
+		let mut result = Vec::new();
+		if from > to {
+			return Ok(result);
+		}
 
-/// Blooms database iterator
-pub struct DatabaseIterator<'a, I> {
-	top: FileIterator<'a>,
-	mid: FileIterator<'a>,
-	bot: FileIterator<'a>,
-	state: IteratorState,
-	from: u64,
-	to: u64,
-	index: u64,
-	blooms: I,
-}
+		let top = self.config.levels - 1;
+		let shift = self.config.shift(top);
+		for pos in (from >> shift)..((to >> shift) + 1) {
+			if !self.search(top, pos, from, to, blooms, &mut result)? {
+				break;
+			}
+		}
 
-impl<'a, I> fmt::Debug for DatabaseIterator<'a, I> {
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		f.debug_struct("DatabaseIterator")
-			.field("state", &self.state)
-			.field("from", &self.from)
-			.field("to", &self.to)
-			.field("index", &self.index)
-			.field("blooms", &"...")
-			.field("top", &"...")
-			.field("mid", &"...")
-			.field("bot", &"...")
-			.finish()
+		Ok(result)
 	}
-}
 
-/// Database iterator state.
-#[derive(Debug)]
-enum IteratorState {
-	/// Iterator should read top level bloom
-	Top,
-	/// Iterator should read mid level bloom `x` more times
-	Mid(usize),
-	/// Iterator should read mid level bloom `mid` more times
-	/// and bot level `mix * 16 + bot` times
-	Bot { mid: usize, bot: usize },
-}
+	/// Searches the bloom at `pos` of `level` and the blooms it represents.
+	/// Returns `false` if the end of the database has been reached.
+	fn search<'b, B, I, II>(&self, level: u32, pos: u64, from: u64, to: u64, blooms: II, result: &mut Vec<u64>) -> io::Result<bool>
+	where ethbloom::BloomRef<'b>: From<B>, II: IntoIterator<Item = B, IntoIter = I> + Copy, I: Iterator<Item = B> {
+		let bloom = match self.levels[level as usize].read_bloom(pos) {
+			Ok(bloom) => bloom,
+			Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+			Err(err) => return Err(err),
+		};
 
-impl<'a, 'b, B, I, II> Iterator for DatabaseIterator<'a, II>
-where ethbloom::BloomRef<'b>: From<B>, 'b: 'a, II: IntoIterator<Item = B, IntoIter = I> + Copy, I: Iterator<Item = B> {
-	type Item = io::Result<u64>;
-
-	fn next(&mut self) -> Option<Self::Item> {
-		macro_rules! try_o {
-			($expr: expr) => {
-				match $expr {
-					Err(err) => return Some(Err(err)),
-					Ok(ok) => ok,
-				}
-			}
+		if !contains_any(bloom, blooms.into_iter()) {
+			return Ok(true);
 		}
 
-		macro_rules! next_bloom {
-			($iter: expr) => {
-				try_o!($iter.next()?)
+		if level == 0 {
+			if pos >= from && pos <= to {
+				result.push(pos);
 			}
+			return Ok(true);
 		}
 
-		loop {
-			if self.index > self.to {
-				return None;
+		let child_shift = self.config.shift(level - 1);
+		let first_child = pos * self.config.fan_out;
+		for child in first_child..(first_child + self.config.fan_out) {
+			let first = child << child_shift;
+			let last = first + (1 << child_shift) - 1;
+			if last < from {
+				continue;
 			}
-
-			self.state = match self.state {
-				IteratorState::Top => {
-					if contains_any(next_bloom!(self.top), self.blooms.into_iter()) {
-						IteratorState::Mid(16)
-					} else {
-						self.index += 256;
-						try_o!(self.mid.advance(16));
-						try_o!(self.bot.advance(256));
-						IteratorState::Top
-					}
-				},
-				IteratorState::Mid(left) => {
-					if left == 0 {
-						IteratorState::Top
-					} else if contains_any(next_bloom!(self.mid), self.blooms.into_iter()) && self.index + 16 >= self.from {
-						IteratorState::Bot { mid: left - 1, bot: 16 }
-					} else {
-						self.index += 16;
-						try_o!(self.bot.advance(16));
-						IteratorState::Mid(left - 1)
-					}
-				},
-				IteratorState::Bot { mid, bot } => {
-					if bot == 0 {
-						IteratorState::Mid(mid)
-					} else if contains_any(next_bloom!(self.bot), self.blooms.into_iter()) && self.index >= self.from {
-						let result = self.index;
-						self.index += 1;
-						self.state = IteratorState::Bot { mid, bot: bot - 1 };
-						return Some(Ok(result));
-					} else {
-						self.index += 1;
-						IteratorState::Bot { mid, bot: bot - 1 }
-					}
-				}
+			if first > to {
+				break;
+			}
+			if !self.search(level - 1, child, from, to, blooms, result)? {
+				return Ok(false);
 			}
 		}
+
+		Ok(true)
 	}
 }
 
+fn contains_any<'a, I, B>(bloom: ethbloom::Bloom, mut iterator: I) -> bool
+where ethbloom::BloomRef<'a>: From<B>, I: Iterator<Item = B> {
+	iterator.any(|item| bloom.contains_bloom(item))
+}
+
 #[cfg(test)]
 mod tests {
 	use ethbloom::Bloom;
 	use tempdir::TempDir;
-	use super::Database;
+	use super::{Config, Database};
 
 	#[test]
 	fn test_database() {
 		let tempdir = TempDir::new("").unwrap();
-		let mut database = Database::open(tempdir.path()).unwrap();
+		let mut database = Database::open(tempdir.path(), Default::default()).unwrap();
 		database.insert_blooms(0, vec![Bloom::from(0), Bloom::from(0x01), Bloom::from(0x10), Bloom::from(0x11)].iter()).unwrap();
 
-		let matches = database.iterate_matching(0, 3, Some(&Bloom::from(0))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(0, 3, Some(&Bloom::from(0))).unwrap();
 		assert_eq!(matches, vec![0, 1, 2, 3]);
 
-		let matches = database.iterate_matching(0, 4, Some(&Bloom::from(0))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(0, 4, Some(&Bloom::from(0))).unwrap();
 		assert_eq!(matches, vec![0, 1, 2, 3]);
 
-		let matches = database.iterate_matching(1, 3, Some(&Bloom::from(0))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(1, 3, Some(&Bloom::from(0))).unwrap();
 		assert_eq!(matches, vec![1, 2, 3]);
 
-		let matches = database.iterate_matching(1, 2, Some(&Bloom::from(0))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(1, 2, Some(&Bloom::from(0))).unwrap();
 		assert_eq!(matches, vec![1, 2]);
 
-		let matches = database.iterate_matching(0, 3, Some(&Bloom::from(0x01))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(0, 3, Some(&Bloom::from(0x01))).unwrap();
 		assert_eq!(matches, vec![1, 3]);
 
-		let matches = database.iterate_matching(0, 3, Some(&Bloom::from(0x10))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(0, 3, Some(&Bloom::from(0x10))).unwrap();
 		assert_eq!(matches, vec![2, 3]);
 
-		let matches = database.iterate_matching(2, 2, Some(&Bloom::from(0x10))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(2, 2, Some(&Bloom::from(0x10))).unwrap();
 		assert_eq!(matches, vec![2]);
 	}
 
 	#[test]
 	fn test_database2() {
 		let tempdir = TempDir::new("").unwrap();
-		let mut database = Database::open(tempdir.path()).unwrap();
+		let mut database = Database::open(tempdir.path(), Default::default()).unwrap();
 		database.insert_blooms(254, vec![Bloom::from(0x100), Bloom::from(0x01), Bloom::from(0x10), Bloom::from(0x11)].iter()).unwrap();
 
-		let matches = database.iterate_matching(0, 257, Some(&Bloom::from(0x01))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(0, 257, Some(&Bloom::from(0x01))).unwrap();
 		assert_eq!(matches, vec![255, 257]);
 
-		let matches = database.iterate_matching(0, 258, Some(&Bloom::from(0x100))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(0, 258, Some(&Bloom::from(0x100))).unwrap();
 		assert_eq!(matches, vec![254]);
 
-		let matches = database.iterate_matching(0, 256, Some(&Bloom::from(0x01))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(0, 256, Some(&Bloom::from(0x01))).unwrap();
 		assert_eq!(matches, vec![255]);
 
-		let matches = database.iterate_matching(255, 255, Some(&Bloom::from(0x01))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(255, 255, Some(&Bloom::from(0x01))).unwrap();
 		assert_eq!(matches, vec![255]);
 
-		let matches = database.iterate_matching(256, 256, Some(&Bloom::from(0x10))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(256, 256, Some(&Bloom::from(0x10))).unwrap();
 		assert_eq!(matches, vec![256]);
 
-		let matches = database.iterate_matching(256, 257, Some(&Bloom::from(0x10))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		let matches = database.matching(256, 257, Some(&Bloom::from(0x10))).unwrap();
 		assert_eq!(matches, vec![256, 257]);
 	}
+
+	#[test]
+	fn test_database_config() {
+		let tempdir = TempDir::new("").unwrap();
+		let config = Config { levels: 4, fan_out: 4 };
+		{
+			let mut database = Database::open(tempdir.path(), config).unwrap();
+			let blooms: Vec<_> = (0..300u64).map(|i| if i % 100 == 7 { Bloom::from(0x01) } else { Bloom::from(0x10) }).collect();
+			database.insert_blooms(0, blooms.iter()).unwrap();
+
+			assert_eq!(database.matching(0, 299, Some(&Bloom::from(0x01))).unwrap(), vec![7, 107, 207]);
+			assert_eq!(database.matching(8, 207, Some(&Bloom::from(0x01))).unwrap(), vec![107, 207]);
+			assert_eq!(database.matching(300, 1000, Some(&Bloom::from(0x10))).unwrap(), vec![]);
+		}
+
+		// the stored layout takes precedence.
+		let database = Database::open(tempdir.path(), Config::default()).unwrap();
+		assert_eq!(database.config(), config);
+		assert_eq!(database.matching(0, 299, Some(&Bloom::from(0x01))).unwrap(), vec![7, 107, 207]);
+
+		let tempdir = TempDir::new("").unwrap();
+		assert!(Database::open(tempdir.path(), Config { levels: 2, fan_out: 10 }).is_err());
+		assert!(Database::open(tempdir.path(), Config { levels: 8, fan_out: 1 << 11 }).is_err());
+		assert!(Database::open(tempdir.path(), Config { levels: 8, fan_out: 1 << 9 }).is_ok());
+	}
 }
@@ -87,54 +87,12 @@ impl File {
 		file_ref.write_all(ethbloom::BloomRef::from(bloom).data())
 	}
 
-	/// Returns an iterator over file.
-	///
-	/// This function needs to be mutable `fs::File` is just a shared reference a system file handle.
-	/// https://users.rust-lang.org/t/how-to-handle-match-with-irrelevant-ok--/6291/15
-	pub fn iterator_from(&mut self, pos: u64) -> io::Result<FileIterator> {
-		let mut buf_reader = io::BufReader::new(&self.file);
-		buf_reader.seek(SeekFrom::Start(pos * 256))?;
-
-		let iter = FileIterator {
-			file: buf_reader,
-		};
-
-		Ok(iter)
-	}
-
 	/// Flush outstanding modifications to the disk
 	pub fn flush(&mut self) -> io::Result<()> {
 		self.file.flush()
 	}
 }
 
-/// Iterator over blooms of a single file.
-pub struct FileIterator<'a> {
-	/// Backing file.
-	file: io::BufReader<&'a fs::File>,
-}
-
-impl<'a> FileIterator<'a> {
-	/// Advance file by n blooms
-	pub fn advance(&mut self, n: u64) -> io::Result<()> {
-		self.file.seek(SeekFrom::Current(n as i64 * 256))?;
-		Ok(())
-	}
-}
-
-impl<'a> Iterator for FileIterator<'a> {
-	type Item = io::Result<ethbloom::Bloom>;
-
-	fn next(&mut self) -> Option<Self::Item> {
-		let mut bloom = ethbloom::Bloom::default();
-		match self.file.read_exact(&mut bloom) {
-			Ok(_) => Some(Ok(bloom)),
-			Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => None,
-			Err(err) => Some(Err(err)),
-		}
-	}
-}
-
 #[cfg(test)]
 mod tests {
 	use ethbloom::Bloom;
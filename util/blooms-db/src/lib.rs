@@ -31,6 +31,8 @@ use std::io;
 use std::path::Path;
 use parking_lot::Mutex;
 
+pub use db::Config;
+
 /// Threadsafe API for blooms database.
 ///
 /// # Warning
@@ -47,13 +49,29 @@ impl Database {
 	///
 	/// * `path` - database directory
 	pub fn open<P>(path: P) -> io::Result<Database> where P: AsRef<Path> {
+		Self::open_with_config(path, Config::default())
+	}
+
+	/// Creates new database handle, creating the database with given layout if it does not exist.
+	/// The layout of an existing database is never changed.
+	///
+	/// # Arguments
+	///
+	/// * `path` - database directory
+	/// * `config` - layout of a new database
+	pub fn open_with_config<P>(path: P, config: Config) -> io::Result<Database> where P: AsRef<Path> {
 		let result = Database {
-			database: Mutex::new(db::Database::open(path)?),
+			database: Mutex::new(db::Database::open(path, config)?),
 		};
 
 		Ok(result)
 	}
 
+	/// Returns the layout of the database.
+	pub fn config(&self) -> Config {
+		self.database.lock().config()
+	}
+
 	/// Reopens database at the same location.
 	pub fn reopen(&self) -> io::Result<()> {
 		self.database.lock().reopen()
@@ -79,8 +97,6 @@ impl Database {
 	/// * `blooms` - searched pattern
 	pub fn filter<'a, B, I, II>(&self, from: u64, to: u64, blooms: II) -> io::Result<Vec<u64>>
 	where ethbloom::BloomRef<'a>: From<B>, II: IntoIterator<Item = B, IntoIter = I> + Copy, I: Iterator<Item = B> {
-		self.database.lock()
-			.iterate_matching(from, to, blooms)?
-			.collect::<Result<Vec<u64>, _>>()
+		self.database.lock().matching(from, to, blooms)
 	}
 }
@@ -20,8 +20,13 @@
 extern crate app_dirs;
 extern crate ethereum_types;
 extern crate journaldb;
+#[cfg(unix)]
+extern crate libc;
+#[cfg(windows)]
+extern crate winapi;
 
 pub mod helpers;
+mod space;
 use std::{env, fs};
 use std::path::{PathBuf, Path};
 use ethereum_types::{H64, H256};
@@ -31,6 +36,8 @@ use app_dirs::{AppInfo, get_app_root, AppDataType};
 // re-export platform-specific functions
 use platform::*;
 
+pub use space::available_space;
+
 /// Platform-specific chains path - Windows only
 #[cfg(target_os = "windows")] pub const CHAINS_PATH: &'static str = "$LOCAL/chains";
 /// Platform-specific chains path
@@ -50,6 +57,8 @@ const LEGACY_CLIENT_DB_VER_STR: &'static str = "5.3";
 pub struct Directories {
 	/// Base dir
 	pub base: String,
+	/// Writable dir for node state, same as `base` unless the base dir is read-only
+	pub state: String,
 	/// Database dir
 	pub db: String,
 	/// Cache dir
@@ -70,6 +79,7 @@ impl Default for Directories {
 		let local_dir = default_local_path();
 		Directories {
 			base: replace_home(&data_dir, "$BASE"),
+			state: replace_home(&data_dir, "$BASE"),
 			db: replace_home_and_local(&data_dir, &local_dir, CHAINS_PATH),
 			cache: replace_home_and_local(&data_dir, &local_dir, CACHE_PATH),
 			keys: replace_home(&data_dir, "$BASE/keys"),
@@ -84,6 +94,7 @@ impl Directories {
 	/// Create local directories
 	pub fn create_dirs(&self, dapps_enabled: bool, signer_enabled: bool, secretstore_enabled: bool) -> Result<(), String> {
 		fs::create_dir_all(&self.base).map_err(|e| e.to_string())?;
+		fs::create_dir_all(&self.state).map_err(|e| e.to_string())?;
 		fs::create_dir_all(&self.db).map_err(|e| e.to_string())?;
 		fs::create_dir_all(&self.cache).map_err(|e| e.to_string())?;
 		fs::create_dir_all(&self.keys).map_err(|e| e.to_string())?;
@@ -112,11 +123,21 @@ impl Directories {
 
 	/// Get the ipc sockets path
 	pub fn ipc_path(&self) -> PathBuf {
-		let mut dir = Path::new(&self.base).to_path_buf();
+		let mut dir = Path::new(&self.state).to_path_buf();
 		dir.push("ipc");
 		dir
 	}
 
+	/// Get the path for content downloaded at runtime.
+	/// Kept with the cache unless node state lives in a separate dir.
+	pub fn blobs_path(&self) -> PathBuf {
+		if self.state == self.base {
+			Path::new(&self.cache).join("blobs")
+		} else {
+			Path::new(&self.state).join("blobs")
+		}
+	}
+
 	/// Legacy keys path
 	// TODO: remove in 1.7
 	pub fn legacy_keys_path(&self, testnet: bool) -> PathBuf {
@@ -135,6 +156,15 @@ impl Directories {
 		dir.push(spec_name);
 		dir
 	}
+
+	/// Get the address book path.
+	/// Kept in the base dir unless node state lives in a separate dir.
+	pub fn address_book_path(&self, spec_name: &str) -> PathBuf {
+		let mut dir = PathBuf::from(&self.state);
+		dir.push("address_book");
+		dir.push(spec_name);
+		dir
+	}
 }
 
 #[derive(Debug, PartialEq)]
@@ -179,6 +209,11 @@ impl DatabaseDirectories {
 		self.db_root_path().join(pruning.as_internal_name_str())
 	}
 
+	/// Explorer index path, next to the client database it indexes
+	pub fn explorer_path(&self, pruning: Algorithm) -> PathBuf {
+		self.db_path(pruning).join("explorer")
+	}
+
 	/// Get the root path for database
 	// TODO: remove in 1.7
 	pub fn legacy_version_path(&self, pruning: Algorithm) -> PathBuf {
@@ -217,6 +252,11 @@ impl DatabaseDirectories {
 	pub fn network_path(&self) -> PathBuf {
 		self.spec_root_path().join("network")
 	}
+
+	/// Get the path for blocks pinned through the IPFS API.
+	pub fn ipfs_path(&self) -> PathBuf {
+		self.db_root_path().join("ipfs")
+	}
 }
 
 /// Default data path
@@ -343,6 +383,7 @@ mod tests {
 		let local_dir = super::default_local_path();
 		let expected = Directories {
 			base: replace_home(&data_dir, "$BASE"),
+			state: replace_home(&data_dir, "$BASE"),
 			db: replace_home_and_local(&data_dir, &local_dir,
 				if cfg!(target_os = "windows") { "$LOCAL/chains" }
 				else { "$BASE/chains" }
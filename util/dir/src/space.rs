@@ -0,0 +1,94 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Free disk space queries.
+
+use std::io;
+use std::path::Path;
+
+/// Returns the number of bytes available to the current user on the filesystem containing `path`.
+pub fn available_space<P: AsRef<Path>>(path: P) -> io::Result<u64> {
+	platform::available_space(path.as_ref())
+}
+
+#[cfg(unix)]
+mod platform {
+	use std::{io, mem};
+	use std::ffi::CString;
+	use std::os::unix::ffi::OsStrExt;
+	use std::path::Path;
+	use libc;
+
+	pub fn available_space(path: &Path) -> io::Result<u64> {
+		let path = CString::new(path.as_os_str().as_bytes())
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+		unsafe {
+			let mut stat: libc::statvfs = mem::zeroed();
+			if libc::statvfs(path.as_ptr(), &mut stat) != 0 {
+				return Err(io::Error::last_os_error());
+			}
+			Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+		}
+	}
+}
+
+#[cfg(windows)]
+mod platform {
+	use std::{io, ptr};
+	use std::os::windows::ffi::OsStrExt;
+	use std::path::Path;
+	use winapi::um::fileapi::GetDiskFreeSpaceExW;
+	use winapi::um::winnt::ULARGE_INTEGER;
+
+	pub fn available_space(path: &Path) -> io::Result<u64> {
+		let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+
+		unsafe {
+			let mut available: ULARGE_INTEGER = ::std::mem::zeroed();
+			if GetDiskFreeSpaceExW(path.as_ptr(), &mut available, ptr::null_mut(), ptr::null_mut()) == 0 {
+				return Err(io::Error::last_os_error());
+			}
+			Ok(*available.QuadPart())
+		}
+	}
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+	use std::io;
+	use std::path::Path;
+
+	pub fn available_space(_path: &Path) -> io::Result<u64> {
+		Err(io::Error::new(io::ErrorKind::Other, "Disk space queries are not supported on this platform"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::env;
+	use super::available_space;
+
+	#[test]
+	fn should_report_available_space() {
+		assert!(available_space(env::temp_dir()).is_ok());
+	}
+
+	#[test]
+	fn should_fail_for_missing_path() {
+		assert!(available_space("/this/path/does/not/exist").is_err());
+	}
+}
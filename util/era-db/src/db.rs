@@ -0,0 +1,350 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::{io, fs};
+
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
+use tiny_keccak::keccak256;
+
+/// Number of blocks in a single era.
+pub const BLOCKS_PER_ERA: u64 = 8192;
+/// Length of the record header: body and receipts lengths.
+const HEADER_LEN: u64 = 8;
+/// Length of the record checksum.
+const CHECKSUM_LEN: u64 = 8;
+/// Length of a single index entry.
+const INDEX_ENTRY_LEN: u64 = 8;
+/// Maximum number of eras kept open for reading.
+const MAX_OPEN_ERAS: usize = 16;
+
+/// Block data stored in an era file.
+pub struct Record {
+	pub body: Vec<u8>,
+	pub receipts: Vec<u8>,
+}
+
+fn checksum(body: &[u8], receipts: &[u8]) -> [u8; CHECKSUM_LEN as usize] {
+	let mut data = Vec::with_capacity(body.len() + receipts.len());
+	data.extend_from_slice(body);
+	data.extend_from_slice(receipts);
+	let hash = keccak256(&data);
+	let mut result = [0u8; CHECKSUM_LEN as usize];
+	result.copy_from_slice(&hash[..CHECKSUM_LEN as usize]);
+	result
+}
+
+fn invalid_data(msg: String) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Data and index file of a single era.
+struct Era {
+	data: fs::File,
+	index: fs::File,
+}
+
+impl Era {
+	fn open(path: &Path, era: u64, write: bool) -> io::Result<Era> {
+		let open = |name: String| fs::OpenOptions::new()
+			.read(true)
+			.write(write)
+			.create(write)
+			.open(path.join(name));
+
+		Ok(Era {
+			data: open(format!("{:06}.era", era))?,
+			index: open(format!("{:06}.idx", era))?,
+		})
+	}
+
+	/// Number of records in the era.
+	fn len(&self) -> io::Result<u64> {
+		Ok(self.index.metadata()?.len() / INDEX_ENTRY_LEN)
+	}
+
+	/// Discards data which is not covered by the index and index entries of records
+	/// which were not fully written, e.g. after an unclean shutdown.
+	fn repair(&mut self) -> io::Result<()> {
+		let file_len = self.data.metadata()?.len();
+		let mut len = self.len()?;
+		let mut data_len = 0;
+		while len > 0 {
+			let offset = self.offset(len - 1)?;
+			if let Some(end) = self.record_end(offset, file_len)? {
+				data_len = end;
+				break;
+			}
+			len -= 1;
+		}
+
+		self.index.set_len(len * INDEX_ENTRY_LEN)?;
+		self.data.set_len(data_len)
+	}
+
+	/// Returns the end of the record at given offset if it is fully contained in the data file.
+	fn record_end(&mut self, offset: u64, file_len: u64) -> io::Result<Option<u64>> {
+		if offset + HEADER_LEN > file_len {
+			return Ok(None);
+		}
+
+		self.data.seek(SeekFrom::Start(offset))?;
+		let body_len = self.data.read_u32::<LittleEndian>()? as u64;
+		let receipts_len = self.data.read_u32::<LittleEndian>()? as u64;
+		let end = offset + HEADER_LEN + body_len + receipts_len + CHECKSUM_LEN;
+		match end <= file_len {
+			true => Ok(Some(end)),
+			false => Ok(None),
+		}
+	}
+
+	fn offset(&mut self, position: u64) -> io::Result<u64> {
+		self.index.seek(SeekFrom::Start(position * INDEX_ENTRY_LEN))?;
+		self.index.read_u64::<LittleEndian>()
+	}
+
+	fn read(&mut self, position: u64) -> io::Result<Record> {
+		let offset = self.offset(position)?;
+		self.data.seek(SeekFrom::Start(offset))?;
+
+		let mut header = [0u8; HEADER_LEN as usize];
+		self.data.read_exact(&mut header)?;
+		let mut body = vec![0u8; LittleEndian::read_u32(&header[..4]) as usize];
+		let mut receipts = vec![0u8; LittleEndian::read_u32(&header[4..]) as usize];
+		let mut expected = [0u8; CHECKSUM_LEN as usize];
+		self.data.read_exact(&mut body)?;
+		self.data.read_exact(&mut receipts)?;
+		self.data.read_exact(&mut expected)?;
+
+		if checksum(&body, &receipts) != expected {
+			return Err(invalid_data(format!("checksum mismatch of era record at offset {}", offset)));
+		}
+
+		Ok(Record { body, receipts })
+	}
+
+	fn append(&mut self, body: &[u8], receipts: &[u8]) -> io::Result<()> {
+		let offset = self.data.seek(SeekFrom::End(0))?;
+		self.data.write_u32::<LittleEndian>(body.len() as u32)?;
+		self.data.write_u32::<LittleEndian>(receipts.len() as u32)?;
+		self.data.write_all(body)?;
+		self.data.write_all(receipts)?;
+		self.data.write_all(&checksum(body, receipts))?;
+
+		self.index.seek(SeekFrom::End(0))?;
+		self.index.write_u64::<LittleEndian>(offset)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		// data must be durable before the index pointing at it.
+		self.data.sync_data()?;
+		self.index.sync_data()
+	}
+}
+
+/// Ancient blocks database.
+pub struct Database {
+	/// Database path
+	path: PathBuf,
+	/// Era being appended to
+	current: Era,
+	/// Number of the first block which is not stored yet
+	next_number: u64,
+	/// Sealed eras opened for reading
+	sealed: HashMap<u64, Era>,
+}
+
+impl Database {
+	/// Opens ancient blocks database.
+	pub fn open<P>(path: P) -> io::Result<Database> where P: AsRef<Path> {
+		let path = path.as_ref();
+		fs::create_dir_all(path)?;
+
+		// the last era is the one with the highest number.
+		let mut last_era = 0;
+		for entry in fs::read_dir(path)? {
+			let name = entry?.file_name();
+			let name = name.to_string_lossy();
+			if name.ends_with(".idx") {
+				if let Ok(era) = name.trim_right_matches(".idx").parse::<u64>() {
+					last_era = ::std::cmp::max(last_era, era);
+				}
+			}
+		}
+
+		let mut current = Era::open(path, last_era, true)?;
+		current.repair()?;
+		let next_number = last_era * BLOCKS_PER_ERA + current.len()?;
+
+		let database = Database {
+			path: path.to_owned(),
+			current,
+			next_number,
+			sealed: HashMap::new(),
+		};
+
+		Ok(database)
+	}
+
+	pub fn next_number(&self) -> u64 {
+		self.next_number
+	}
+
+	pub fn append(&mut self, number: u64, body: &[u8], receipts: &[u8]) -> io::Result<()> {
+		if number != self.next_number {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("expected block {}, got {}", self.next_number, number)));
+		}
+
+		if number > 0 && number % BLOCKS_PER_ERA == 0 {
+			self.current.flush()?;
+			self.current = Era::open(&self.path, number / BLOCKS_PER_ERA, true)?;
+		}
+
+		self.current.append(body, receipts)?;
+		self.next_number += 1;
+		Ok(())
+	}
+
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.current.flush()
+	}
+
+	pub fn read(&mut self, number: u64) -> io::Result<Option<Record>> {
+		if number >= self.next_number {
+			return Ok(None);
+		}
+
+		let era = number / BLOCKS_PER_ERA;
+		let position = number % BLOCKS_PER_ERA;
+		if era == self.next_number / BLOCKS_PER_ERA {
+			return self.current.read(position).map(Some);
+		}
+
+		if !self.sealed.contains_key(&era) {
+			if self.sealed.len() >= MAX_OPEN_ERAS {
+				self.sealed.clear();
+			}
+			let sealed = Era::open(&self.path, era, false)?;
+			self.sealed.insert(era, sealed);
+		}
+
+		self.sealed.get_mut(&era)
+			.expect("era was inserted above; qed")
+			.read(position)
+			.map(Some)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::io::{Read, Write};
+	use tempdir::TempDir;
+	use super::{Database, BLOCKS_PER_ERA};
+
+	#[test]
+	fn test_era_database() {
+		let tempdir = TempDir::new("").unwrap();
+		{
+			let mut database = Database::open(tempdir.path()).unwrap();
+			assert_eq!(database.next_number(), 0);
+			for number in 0..BLOCKS_PER_ERA + 2 {
+				database.append(number, format!("body{}", number).as_bytes(), b"receipts").unwrap();
+			}
+			assert!(database.append(0, b"body", b"receipts").is_err());
+			database.flush().unwrap();
+
+			let record = database.read(3).unwrap().unwrap();
+			assert_eq!(record.body, b"body3".to_vec());
+			assert_eq!(record.receipts, b"receipts".to_vec());
+			assert!(database.read(BLOCKS_PER_ERA + 2).unwrap().is_none());
+		}
+
+		let mut database = Database::open(tempdir.path()).unwrap();
+		assert_eq!(database.next_number(), BLOCKS_PER_ERA + 2);
+		assert_eq!(database.read(BLOCKS_PER_ERA + 1).unwrap().unwrap().body, format!("body{}", BLOCKS_PER_ERA + 1).into_bytes());
+	}
+
+	#[test]
+	fn test_discards_partial_record() {
+		let tempdir = TempDir::new("").unwrap();
+		{
+			let mut database = Database::open(tempdir.path()).unwrap();
+			database.append(0, b"body", b"receipts").unwrap();
+			database.flush().unwrap();
+		}
+
+		// a record written without its index entry.
+		let data_path = tempdir.path().join("000000.era");
+		let len = fs::metadata(&data_path).unwrap().len();
+		fs::OpenOptions::new().append(true).open(&data_path).unwrap().write_all(b"garbage").unwrap();
+
+		let mut database = Database::open(tempdir.path()).unwrap();
+		assert_eq!(database.next_number(), 1);
+		assert_eq!(fs::metadata(&data_path).unwrap().len(), len);
+		database.append(1, b"body1", b"").unwrap();
+		assert_eq!(database.read(1).unwrap().unwrap().body, b"body1".to_vec());
+	}
+
+	#[test]
+	fn test_detects_corruption() {
+		let tempdir = TempDir::new("").unwrap();
+		{
+			let mut database = Database::open(tempdir.path()).unwrap();
+			database.append(0, b"body", b"receipts").unwrap();
+			database.flush().unwrap();
+		}
+
+		let data_path = tempdir.path().join("000000.era");
+		let mut data = Vec::new();
+		fs::File::open(&data_path).unwrap().read_to_end(&mut data).unwrap();
+		data[9] ^= 0xff;
+		fs::File::create(&data_path).unwrap().write_all(&data).unwrap();
+
+		let mut database = Database::open(tempdir.path()).unwrap();
+		assert!(database.read(0).is_err());
+	}
+
+	#[test]
+	fn test_discards_index_of_truncated_record() {
+		let tempdir = TempDir::new("").unwrap();
+		let data_path = tempdir.path().join("000000.era");
+		let index_path = tempdir.path().join("000000.idx");
+		let len = {
+			let mut database = Database::open(tempdir.path()).unwrap();
+			database.append(0, b"body", b"receipts").unwrap();
+			database.flush().unwrap();
+			let len = fs::metadata(&data_path).unwrap().len();
+			database.append(1, b"body1", b"receipts1").unwrap();
+			database.flush().unwrap();
+			len
+		};
+
+		// the index entry of the second record made it to disk, but its data did not.
+		let data = fs::OpenOptions::new().write(true).open(&data_path).unwrap();
+		data.set_len(len + 3).unwrap();
+
+		let mut database = Database::open(tempdir.path()).unwrap();
+		assert_eq!(database.next_number(), 1);
+		assert_eq!(fs::metadata(&data_path).unwrap().len(), len);
+		assert_eq!(fs::metadata(&index_path).unwrap().len(), 8);
+		assert_eq!(database.read(0).unwrap().unwrap().body, b"body".to_vec());
+		database.append(1, b"body1", b"").unwrap();
+		assert_eq!(database.read(1).unwrap().unwrap().body, b"body1".to_vec());
+	}
+}
@@ -0,0 +1,89 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Append-only storage of ancient blocks.
+//!
+//! Block bodies and receipts are kept in era files of `BLOCKS_PER_ERA` consecutive blocks.
+//! Every era consists of a data file, holding checksummed records, and an index file with
+//! the offset of every record. Sealed eras are never modified and can be copied between nodes.
+
+extern crate byteorder;
+extern crate parking_lot;
+extern crate tiny_keccak;
+
+#[cfg(test)]
+extern crate tempdir;
+
+mod db;
+
+use std::io;
+use std::path::Path;
+use parking_lot::Mutex;
+
+pub use db::BLOCKS_PER_ERA;
+
+/// Threadsafe API for ancient blocks database.
+pub struct Database {
+	database: Mutex<db::Database>,
+}
+
+impl Database {
+	/// Opens the database, creating it if it does not exist.
+	/// Records which were only partially written are discarded.
+	///
+	/// # Arguments
+	///
+	/// * `path` - database directory
+	pub fn open<P>(path: P) -> io::Result<Database> where P: AsRef<Path> {
+		let result = Database {
+			database: Mutex::new(db::Database::open(path)?),
+		};
+
+		Ok(result)
+	}
+
+	/// Number of the first block which is not stored yet.
+	/// Blocks are stored consecutively, starting with the genesis block.
+	pub fn next_number(&self) -> u64 {
+		self.database.lock().next_number()
+	}
+
+	/// Appends the block `number`, which must be equal to `next_number`.
+	///
+	/// # Arguments
+	///
+	/// * `number` - number of the block
+	/// * `body` - encoded block body
+	/// * `receipts` - encoded block receipts
+	pub fn append(&self, number: u64, body: &[u8], receipts: &[u8]) -> io::Result<()> {
+		self.database.lock().append(number, body, receipts)
+	}
+
+	/// Makes the appended blocks durable.
+	pub fn flush(&self) -> io::Result<()> {
+		self.database.lock().flush()
+	}
+
+	/// Returns the body of block `number`, if it is stored.
+	pub fn body(&self, number: u64) -> io::Result<Option<Vec<u8>>> {
+		Ok(self.database.lock().read(number)?.map(|record| record.body))
+	}
+
+	/// Returns the receipts of block `number`, if they are stored.
+	pub fn receipts(&self, number: u64) -> io::Result<Option<Vec<u8>>> {
+		Ok(self.database.lock().read(number)?.map(|record| record.receipts))
+	}
+}
@@ -104,7 +104,8 @@ impl KeyValueDB for InMemory {
 			Some(map) => Box::new(
 				map.clone()
 					.into_iter()
-					.skip_while(move |&(ref k, _)| !k.starts_with(prefix))
+					// seek like the on-disk database does: start from the first key not less than `prefix`
+					.skip_while(move |&(ref k, _)| &k[..] < prefix)
 					.map(|(k, v)| (k.into_boxed_slice(), v.into_vec().into_boxed_slice()))
 			),
 			None => Box::new(None.into_iter()),
@@ -33,6 +33,7 @@ use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::{fs, mem, result};
+use std::io::{self, Read, Write};
 
 use parking_lot::{Mutex, MutexGuard, RwLock};
 use rocksdb::{
@@ -55,6 +56,8 @@ use std::fs::File;
 use std::path::PathBuf;
 
 const DB_DEFAULT_MEMORY_BUDGET_MB: usize = 128;
+/// Number of attempts at taking a checkpoint of a database that is being written to.
+const CHECKPOINT_ATTEMPTS: usize = 10;
 
 enum KeyState {
 	Insert(DBValue),
@@ -166,6 +169,9 @@ pub struct DatabaseConfig {
 	pub columns: Option<u32>,
 	/// Should we keep WAL enabled?
 	pub wal: bool,
+	/// Should a corrupted database be repaired when it's opened?
+	/// If not, opening fails with an error naming the affected columns.
+	pub repair_on_corruption: bool,
 }
 
 impl DatabaseConfig {
@@ -194,6 +200,7 @@ impl Default for DatabaseConfig {
 			compaction: CompactionProfile::default(),
 			columns: None,
 			wal: true,
+			repair_on_corruption: true,
 		}
 	}
 }
@@ -258,22 +265,109 @@ pub struct Database {
 	flushing_lock: Mutex<bool>,
 }
 
+/// Name of the overlay column `index`, as used in corruption reports.
+fn column_name(index: usize) -> String {
+	match index {
+		0 => "default".into(),
+		c => format!("col{}", c - 1),
+	}
+}
+
 #[inline]
-fn check_for_corruption<T, P: AsRef<Path>>(path: P, res: result::Result<T, String>) -> result::Result<T, String> {
+fn check_for_corruption<T, P: AsRef<Path>>(path: P, columns: &[usize], res: result::Result<T, String>) -> result::Result<T, String> {
 	if let Err(ref s) = res {
 		if s.starts_with("Corruption:") {
 			warn!("DB corrupted: {}. Repair will be triggered on next restart", s);
-			let _ = fs::File::create(path.as_ref().join(Database::CORRUPTION_FILE_NAME));
+			mark_corrupted(path.as_ref(), columns, s);
 		}
 	}
 
 	res
 }
 
+/// Records a corruption in the marker file, one line per affected column.
+fn mark_corrupted(path: &Path, columns: &[usize], error: &str) {
+	let marker = fs::OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(path.join(Database::CORRUPTION_FILE_NAME));
+
+	// ignore errors - the marker is best effort
+	if let Ok(mut marker) = marker {
+		for column in columns {
+			let _ = writeln!(marker, "{}\t{}", column_name(*column), error);
+		}
+	}
+}
+
 fn is_corrupted(s: &str) -> bool {
 	s.starts_with("Corruption:") || s.starts_with("Invalid argument: You have to open all column families")
 }
 
+/// Creates a consistent checkpoint of the database at `path` in `target`, which must not exist.
+///
+/// The database may be open in another process: table files are immutable and get hard-linked
+/// (or copied if linking is not possible), while the manifest and write-ahead logs are copied.
+/// The checkpoint is retried if the manifest changes in the meantime, so the result always
+/// describes a single state of the database and can be opened with `Database::open`.
+pub fn create_checkpoint<P: AsRef<Path>, Q: AsRef<Path>>(path: P, target: Q) -> io::Result<()> {
+	let (path, target) = (path.as_ref(), target.as_ref());
+	if target.exists() {
+		return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", target.display())));
+	}
+
+	for _ in 0..CHECKPOINT_ATTEMPTS {
+		fs::create_dir_all(target)?;
+		match try_checkpoint(path, target) {
+			Ok(true) => return Ok(()),
+			// the manifest changed or a file was removed by a compaction in the meantime
+			Ok(false) => {},
+			Err(ref e) if e.kind() == io::ErrorKind::NotFound => {},
+			Err(e) => return Err(e),
+		}
+		fs::remove_dir_all(target)?;
+	}
+
+	Err(io::Error::new(io::ErrorKind::Other, "database changed during every checkpoint attempt"))
+}
+
+/// Name and length of the manifest currently in use by the database at `path`.
+fn manifest_state(path: &Path) -> io::Result<(String, u64)> {
+	let mut current = String::new();
+	fs::File::open(path.join("CURRENT"))?.read_to_string(&mut current)?;
+	let manifest = current.trim().to_owned();
+	let len = fs::metadata(path.join(&manifest))?.len();
+	Ok((manifest, len))
+}
+
+/// Returns `false` if the manifest changed while the files were being copied.
+fn try_checkpoint(path: &Path, target: &Path) -> io::Result<bool> {
+	let before = manifest_state(path)?;
+
+	for entry in fs::read_dir(path)? {
+		let entry = entry?;
+		if !entry.file_type()?.is_file() {
+			continue;
+		}
+
+		let name = entry.file_name();
+		let name_str = name.to_string_lossy().into_owned();
+		if name_str.ends_with(".sst") {
+			if fs::hard_link(entry.path(), target.join(&name)).is_err() {
+				fs::copy(entry.path(), target.join(&name))?;
+			}
+		} else if name_str.ends_with(".log") || name_str.starts_with("OPTIONS-") || name_str == before.0 {
+			fs::copy(entry.path(), target.join(&name))?;
+		}
+	}
+
+	let mut current = fs::File::create(target.join("CURRENT"))?;
+	writeln!(current, "{}", before.0)?;
+	current.sync_all()?;
+
+	Ok(manifest_state(path)? == before)
+}
+
 impl Database {
 	const CORRUPTION_FILE_NAME: &'static str = "CORRUPTED";
 
@@ -282,6 +376,32 @@ impl Database {
 		Database::open(&DatabaseConfig::default(), path)
 	}
 
+	/// Columns reported as corrupted for the database at `path`.
+	/// Returns `None` if the database isn't marked as corrupted; the list may be empty
+	/// if the corruption was detected without knowing the column.
+	pub fn corrupted_columns(path: &str) -> Option<Vec<String>> {
+		let marker = Path::new(path).join(Database::CORRUPTION_FILE_NAME);
+		let mut contents = String::new();
+		fs::File::open(marker).and_then(|mut f| f.read_to_string(&mut contents)).ok()?;
+
+		let mut columns: Vec<String> = contents.lines()
+			.filter_map(|line| line.split('\t').next())
+			.filter(|column| !column.is_empty())
+			.map(Into::into)
+			.collect();
+		columns.sort();
+		columns.dedup();
+		Some(columns)
+	}
+
+	fn corruption_error(path: &str) -> String {
+		let columns = Database::corrupted_columns(path).unwrap_or_default();
+		format!("Database at {} is corrupted (affected columns: {}) and automatic repair is disabled",
+			path,
+			if columns.is_empty() { "unknown".into() } else { columns.join(", ") },
+		)
+	}
+
 	/// Open database file. Creates if it does not exist.
 	pub fn open(config: &DatabaseConfig, path: &str) -> Result<Database> {
 		let mut opts = Options::new();
@@ -309,6 +429,9 @@ impl Database {
 		// attempt database repair if it has been previously marked as corrupted
 		let db_corrupted = Path::new(path).join(Database::CORRUPTION_FILE_NAME);
 		if db_corrupted.exists() {
+			if !config.repair_on_corruption {
+				return Err(Database::corruption_error(path).into());
+			}
 			warn!("DB has been previously marked as corrupted, attempting repair");
 			DB::repair(&opts, path)?;
 			fs::remove_file(db_corrupted)?;
@@ -358,6 +481,10 @@ impl Database {
 		let db = match db {
 			Ok(db) => db,
 			Err(ref s) if is_corrupted(s) => {
+				if !config.repair_on_corruption {
+					mark_corrupted(Path::new(path), &[], s);
+					return Err(format!("{}: {}", Database::corruption_error(path), s).into());
+				}
 				warn!("DB corrupted: {}, attempting repair", s);
 				DB::repair(&opts, path)?;
 
@@ -443,8 +570,13 @@ impl Database {
 					}
 				}
 
+				let columns: Vec<_> = self.flushing.read().iter().enumerate()
+					.filter(|&(_, column)| !column.is_empty())
+					.map(|(c, _)| c)
+					.collect();
 				check_for_corruption(
 					&self.path,
+					&columns,
 					db.write_opt(batch, &self.write_opts))?;
 
 				for column in self.flushing.write().iter_mut() {
@@ -478,9 +610,14 @@ impl Database {
 			Some(DBAndColumns { ref db, ref cfs }) => {
 				let batch = WriteBatch::new();
 				let ops = tr.ops;
+				let mut columns = Vec::new();
 				for op in ops {
 					// remove any buffered operation for this key
-					self.overlay.write()[Self::to_overlay_column(op.col())].remove(op.key());
+					let column = Self::to_overlay_column(op.col());
+					self.overlay.write()[column].remove(op.key());
+					if !columns.contains(&column) {
+						columns.push(column);
+					}
 
 					match op {
 						DBOp::Insert { col, key, value } => {
@@ -494,6 +631,7 @@ impl Database {
 
 				check_for_corruption(
 					&self.path,
+					&columns,
 					db.write_opt(batch, &self.write_opts)).map_err(Into::into)
 			},
 			None => Err("Database is closed".into())
@@ -776,6 +914,48 @@ mod tests {
 		test_db(&DatabaseConfig::default());
 	}
 
+	#[test]
+	fn checkpoint_of_open_db() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().join("db");
+		let checkpoint = tempdir.path().join("checkpoint");
+		let db = Database::open_default(path.to_str().unwrap()).unwrap();
+
+		let mut batch = db.transaction();
+		batch.put(None, b"cat", b"dog");
+		db.write(batch).unwrap();
+
+		create_checkpoint(&path, &checkpoint).unwrap();
+		assert!(create_checkpoint(&path, &checkpoint).is_err());
+
+		let mut batch = db.transaction();
+		batch.put(None, b"cow", b"moo");
+		db.write(batch).unwrap();
+
+		let restored = Database::open_default(checkpoint.to_str().unwrap()).unwrap();
+		assert_eq!(&*restored.get(None, b"cat").unwrap().unwrap(), b"dog");
+		assert!(restored.get(None, b"cow").unwrap().is_none());
+	}
+
+	#[test]
+	fn corrupted_db_is_not_repaired_when_disabled() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().to_str().unwrap();
+		let _ = Database::open_default(path).unwrap();
+
+		mark_corrupted(tempdir.path(), &[0, 3], "Corruption: bad block");
+		assert_eq!(Database::corrupted_columns(path), Some(vec!["col2".to_owned(), "default".to_owned()]));
+
+		let mut config = DatabaseConfig::default();
+		config.repair_on_corruption = false;
+		let err = Database::open(&config, path).err().unwrap();
+		assert!(format!("{}", err).contains("affected columns: col2, default"));
+
+		// repair is attempted with the default config and clears the marker.
+		let _ = Database::open_default(path).unwrap();
+		assert_eq!(Database::corrupted_columns(path), None);
+	}
+
 	#[test]
 	#[cfg(target_os = "linux")]
 	fn df_to_rotational() {
@@ -273,6 +273,7 @@ impl Manager {
 			compaction: config.compaction_profile,
 			columns: columns,
 			wal: true,
+			repair_on_corruption: true,
 		};
 
 		let db_root = database_path(old_path);
@@ -40,7 +40,7 @@ use PROTOCOL_VERSION;
 use node_table::*;
 use network::{NetworkConfiguration, NetworkIoMessage, ProtocolId, PeerId, PacketId};
 use network::{NonReservedPeerMode, NetworkContext as NetworkContextTrait};
-use network::{SessionInfo, Error, ErrorKind, DisconnectReason, NetworkProtocolHandler};
+use network::{SessionInfo, SessionCapabilityInfo, Error, ErrorKind, DisconnectReason, NetworkProtocolHandler};
 use discovery::{Discovery, TableUpdates, NodeEntry, MAX_DATAGRAM_SIZE};
 use ip_utils::{map_external_address, select_public_address};
 use path::restrict_permissions_owner;
@@ -190,6 +190,11 @@ impl<'s> NetworkContextTrait for NetworkContext<'s> {
 	}
 
 	fn subprotocol_name(&self) -> ProtocolId { self.protocol }
+
+	fn refresh_discovery(&self) {
+		self.io.message(NetworkIoMessage::RefreshDiscovery)
+			.unwrap_or_else(|e| warn!("Error sending network IO message: {:?}", e));
+	}
 }
 
 /// Shared host information
@@ -249,6 +254,9 @@ pub struct Host {
 	timers: RwLock<HashMap<TimerToken, ProtocolTimer>>,
 	timer_counter: RwLock<usize>,
 	reserved_nodes: RwLock<HashSet<NodeId>>,
+	/// Subprotocols of ready sessions. Kept outside of the sessions so that
+	/// protocol slots can be counted without locking every session.
+	ready_protocols: RwLock<HashMap<StreamToken, HashSet<ProtocolId>>>,
 	stopping: AtomicBool,
 	filter: Option<Arc<ConnectionFilter>>,
 }
@@ -305,6 +313,7 @@ impl Host {
 			timers: RwLock::new(HashMap::new()),
 			timer_counter: RwLock::new(USER_TIMER),
 			reserved_nodes: RwLock::new(HashSet::new()),
+			ready_protocols: RwLock::new(HashMap::new()),
 			stopping: AtomicBool::new(false),
 			filter: filter,
 		};
@@ -507,6 +516,25 @@ impl Host {
 		(handshakes, egress, ingress)
 	}
 
+	/// Checks whether a peer with given capabilities may take a connection slot
+	/// without using slots kept for protocols which are below their configured minimum.
+	fn has_protocol_slot(&self, capabilities: &[SessionCapabilityInfo], max_peers: usize) -> bool {
+		let min_protocol_peers = self.info.read().config.min_protocol_peers.clone();
+		if min_protocol_peers.is_empty() {
+			return true;
+		}
+
+		let ready_protocols = self.ready_protocols.read();
+		let connected = ready_protocols.len();
+		let mut protocol_peers = HashMap::new();
+		for protocol in ready_protocols.values().flat_map(|protocols| protocols.iter()) {
+			*protocol_peers.entry(*protocol).or_insert(0) += 1;
+		}
+
+		let protocols: Vec<_> = capabilities.iter().map(|c| c.protocol).collect();
+		protocol_slot_available(&min_protocol_peers, &protocol_peers, connected, &protocols, max_peers)
+	}
+
 	fn connecting_to(&self, id: &NodeId) -> bool {
 		self.sessions.read().iter().any(|e| e.lock().id() == Some(id))
 	}
@@ -738,6 +766,13 @@ impl Host {
 								}
 							}
 
+							if !self.reserved_nodes.read().contains(&id) && !self.has_protocol_slot(&s.info.capabilities, max_peers) {
+								trace!(target: "network", "No free protocol slot for {:?}", id);
+								s.disconnect(io, DisconnectReason::TooManyPeers);
+								kill = true;
+								break;
+							}
+
 							if !self.filter.as_ref().map_or(true, |f| f.connection_allowed(&self_id, &id, ConnectionDirection::Inbound)) {
 								trace!(target: "network", "Inbound connection not allowed for {:?}", id);
 								s.disconnect(io, DisconnectReason::UnexpectedIdentity);
@@ -746,6 +781,7 @@ impl Host {
 							}
 
 							ready_id = Some(id);
+							self.ready_protocols.write().insert(token, s.info.capabilities.iter().map(|c| c.protocol).collect());
 
 							// Add it to the node table
 							if !s.info.originated {
@@ -896,6 +932,7 @@ impl Host {
 		let mut failure_id = None;
 		let mut deregister = false;
 		let mut expired_session = None;
+		self.ready_protocols.write().remove(&token);
 		if let FIRST_SESSION ... LAST_SESSION = token {
 			let sessions = self.sessions.read();
 			if let Some(session) = sessions.get(token).cloned() {
@@ -1103,6 +1140,11 @@ impl IoHandler<NetworkIoMessage> for Host {
 			},
 			NetworkIoMessage::InitPublicInterface =>
 				self.init_public_interface(io).unwrap_or_else(|e| warn!("Error initializing public interface: {:?}", e)),
+			NetworkIoMessage::RefreshDiscovery => {
+				debug!(target: "network", "Refreshing discovery on request");
+				self.discovery.lock().as_mut().map(|d| d.refresh());
+				io.update_registration(DISCOVERY).unwrap_or_else(|e| debug!("Error updating discovery registration: {:?}", e));
+			},
 			_ => {}	// ignore others.
 		}
 	}
@@ -1193,6 +1235,23 @@ fn save_key(path: &Path, key: &Secret) {
 	}
 }
 
+/// Returns `true` if there is a free slot for a peer supporting `protocols`
+/// once the slots still missing to fulfill `min_protocol_peers` are set aside.
+fn protocol_slot_available(
+	min_protocol_peers: &HashMap<ProtocolId, u32>,
+	protocol_peers: &HashMap<ProtocolId, u32>,
+	connected: usize,
+	protocols: &[ProtocolId],
+	max_peers: usize,
+) -> bool {
+	let held: usize = min_protocol_peers.iter()
+		.filter(|&(protocol, _)| !protocols.contains(protocol))
+		.map(|(protocol, min)| min.saturating_sub(*protocol_peers.get(protocol).unwrap_or(&0)) as usize)
+		.sum();
+
+	connected + held < max_peers
+}
+
 fn load_key(path: &Path) -> Option<Secret> {
 	let mut path_buf = PathBuf::from(path);
 	path_buf.push("key");
@@ -1239,3 +1298,21 @@ fn host_client_url() {
 	let host: Host = Host::new(config, None).unwrap();
 	assert!(host.local_url().starts_with("enode://101b3ef5a4ea7a1c7928e24c4c75fd053c235d7b80c22ae5c03d145d0ac7396e2a4ffff9adee3133a7b05044a5cee08115fd65145e5165d646bde371010d803c@"));
 }
+
+#[test]
+fn protocol_slots_are_kept_for_missing_protocols() {
+	let mut min_protocol_peers = HashMap::new();
+	min_protocol_peers.insert(*b"par", 4);
+	min_protocol_peers.insert(*b"pip", 2);
+	let mut protocol_peers = HashMap::new();
+	protocol_peers.insert(*b"par", 1);
+
+	// 3 `par` and 2 `pip` slots are kept out of 10.
+	assert!(protocol_slot_available(&min_protocol_peers, &protocol_peers, 4, &[*b"eth"], 10));
+	assert!(!protocol_slot_available(&min_protocol_peers, &protocol_peers, 5, &[*b"eth"], 10));
+	// a peer supporting `par` may use the slots kept for it.
+	assert!(protocol_slot_available(&min_protocol_peers, &protocol_peers, 7, &[*b"eth", *b"par"], 10));
+	assert!(!protocol_slot_available(&min_protocol_peers, &protocol_peers, 8, &[*b"eth", *b"par"], 10));
+	// no reservations configured.
+	assert!(protocol_slot_available(&HashMap::new(), &HashMap::new(), 9, &[*b"eth"], 10));
+}
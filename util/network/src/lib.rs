@@ -89,6 +89,8 @@ pub enum NetworkIoMessage {
 	DisablePeer(PeerId),
 	/// Network has been started with the host as the given enode.
 	NetworkStarted(String),
+	/// Restart node discovery.
+	RefreshDiscovery,
 }
 
 /// Shared session information
@@ -195,6 +197,8 @@ pub struct NetworkConfiguration {
 	pub max_handshakes: u32,
 	/// Reserved protocols. Peers with <key> protocol get additional <value> connection slots.
 	pub reserved_protocols: HashMap<ProtocolId, u32>,
+	/// Minimal protocol peers. At least <value> of the regular connection slots are kept for peers supporting <key> protocol.
+	pub min_protocol_peers: HashMap<ProtocolId, u32>,
 	/// List of reserved node addresses.
 	pub reserved_nodes: Vec<String>,
 	/// The non-reserved peer mode.
@@ -228,6 +232,7 @@ impl NetworkConfiguration {
 			max_peers: 50,
 			max_handshakes: 64,
 			reserved_protocols: HashMap::new(),
+			min_protocol_peers: HashMap::new(),
 			ip_filter: IpFilter::default(),
 			reserved_nodes: Vec::new(),
 			non_reserved_mode: NonReservedPeerMode::Accept,
@@ -285,6 +290,9 @@ pub trait NetworkContext {
 
 	/// Returns this object's subprotocol name.
 	fn subprotocol_name(&self) -> ProtocolId;
+
+	/// Restart node discovery.
+	fn refresh_discovery(&self);
 }
 
 impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
@@ -331,6 +339,10 @@ impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
 	fn subprotocol_name(&self) -> ProtocolId {
 		(**self).subprotocol_name()
 	}
+
+	fn refresh_discovery(&self) {
+		(**self).refresh_discovery()
+	}
 }
 
 /// Network IO protocol handler. This needs to be implemented for each new subprotocol.
@@ -23,6 +23,7 @@
 #![cfg_attr(feature = "cargo-clippy", deny(clippy, clippy_pedantic))]
 
 extern crate docopt;
+extern crate ethereum_types;
 extern crate ethcore_network_devp2p as devp2p;
 extern crate ethcore_network as net;
 extern crate parity_whisper as whisper;
@@ -41,6 +42,7 @@ extern crate log as rlog;
 extern crate serde_derive;
 
 use docopt::Docopt;
+use ethereum_types::H512;
 use std::{fmt, io, process, env, sync::Arc};
 use jsonrpc_core::{Metadata, MetaIoHandler};
 use jsonrpc_pubsub::{PubSubMetadata, Session};
@@ -104,6 +106,26 @@ impl whisper::rpc::PoolHandle for WhisperPoolHandle {
 	fn pool_status(&self) -> whisper::net::PoolStatus {
 		self.handle.pool_status()
 	}
+
+	fn set_min_pow(&self, min_pow: f64) {
+		self.with_proto_context(whisper::net::PROTOCOL_ID, &mut |ctx| {
+			self.handle.set_min_pow(min_pow, ctx);
+		});
+	}
+
+	fn set_bloom_filter(&self, bloom: Option<H512>) {
+		self.with_proto_context(whisper::net::PROTOCOL_ID, &mut |ctx| {
+			self.handle.set_bloom_filter(bloom, ctx);
+		});
+	}
+
+	fn request_messages(&self, mail_server: H512, request: whisper::net::MailRequest) -> bool {
+		let mut res = false;
+		self.with_proto_context(whisper::net::PROTOCOL_ID, &mut |ctx| {
+			res = self.handle.request_messages(&request, &mail_server, ctx);
+		});
+		res
+	}
 }
 
 impl WhisperPoolHandle {
@@ -238,6 +260,8 @@ fn execute<S, I>(command: I) -> Result<(), Error> where I: IntoIterator<Item=S>,
 
 	io.extend_with(whisper::rpc::Whisper::to_delegate(whisper_factory.make_handler(shared_network.clone())));
 	io.extend_with(whisper::rpc::WhisperPubSub::to_delegate(whisper_factory.make_handler(shared_network.clone())));
+	io.extend_with(whisper::rpc::WhisperSet::to_delegate(whisper_factory.make_handler(shared_network.clone())));
+	io.extend_with(whisper::rpc::WhisperBridge::to_delegate(whisper_factory.make_handler(shared_network.clone())));
 
 	let server = jsonrpc_http_server::ServerBuilder::new(io)
 		.cors(DomainsValidation::AllowOnly(vec![AccessControlAllowOrigin::Null]))
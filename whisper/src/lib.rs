@@ -22,7 +22,9 @@ extern crate ethcore_crypto as crypto;
 extern crate ethcore_network as network;
 extern crate ethereum_types;
 extern crate ethkey;
+extern crate futures;
 extern crate hex;
+extern crate kvdb;
 extern crate mem;
 extern crate ordered_float;
 extern crate parking_lot;
@@ -51,6 +53,9 @@ extern crate serde_derive;
 #[cfg(test)]
 extern crate serde_json;
 
+#[cfg(test)]
+extern crate kvdb_memorydb;
+
 pub use self::message::Message;
 pub use self::net::{Network, MessageHandler};
 
@@ -0,0 +1,210 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Whisper mail server. Persists relayed envelopes and serves historical
+//! envelopes to peers which present the mail server symmetric key.
+
+use std::sync::Arc;
+use std::time::{self, SystemTime};
+
+use byteorder::{BigEndian, ByteOrder};
+use ethereum_types::{H256, H512};
+use kvdb::KeyValueDB;
+use rlp::{self, DecoderError, RlpStream, Rlp};
+
+use message::{Envelope, Message};
+
+/// Request for historical envelopes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MailRequest {
+	/// Symmetric key of the mail server.
+	pub key: H256,
+	/// Lower bound of envelopes issue time (unix seconds, inclusive).
+	pub from: u64,
+	/// Upper bound of envelopes issue time (unix seconds, inclusive).
+	pub to: u64,
+	/// Bloom filter of requested topics.
+	pub bloom: H512,
+}
+
+impl rlp::Encodable for MailRequest {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(4)
+			.append(&self.key)
+			.append(&self.from)
+			.append(&self.to)
+			.append(&self.bloom);
+	}
+}
+
+impl rlp::Decodable for MailRequest {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 4 { return Err(DecoderError::RlpIncorrectListLen) }
+
+		Ok(MailRequest {
+			key: rlp.val_at(0)?,
+			from: rlp.val_at(1)?,
+			to: rlp.val_at(2)?,
+			bloom: rlp.val_at(3)?,
+		})
+	}
+}
+
+/// Prefix of envelope keys: issue time ++ envelope hash.
+const ENVELOPE_PREFIX: u8 = 0;
+/// Prefix of expiry index keys: expiry time ++ envelope hash. Values are envelope keys.
+const EXPIRY_PREFIX: u8 = 1;
+
+/// Mail server, storing envelopes in the database.
+pub struct MailServer {
+	db: Arc<KeyValueDB>,
+	col: Option<u32>,
+	key: H256,
+}
+
+impl MailServer {
+	/// Create a new mail server, storing envelopes in the given database column.
+	/// Only requests carrying `key` are served.
+	pub fn new(db: Arc<KeyValueDB>, col: Option<u32>, key: H256) -> Self {
+		MailServer {
+			db: db,
+			col: col,
+			key: key,
+		}
+	}
+
+	/// Persist messages.
+	pub fn store(&self, messages: &[Message]) {
+		if messages.is_empty() { return }
+
+		let mut batch = self.db.transaction();
+		for message in messages {
+			let envelope = message.envelope();
+			let envelope_key = db_key(ENVELOPE_PREFIX, envelope.expiry - envelope.ttl, message.hash());
+			batch.put_vec(self.col, &envelope_key, ::rlp::encode(envelope).into_vec());
+			batch.put(self.col, &db_key(EXPIRY_PREFIX, envelope.expiry, message.hash()), &envelope_key);
+		}
+
+		if let Err(e) = self.db.write(batch) {
+			warn!(target: "whisper", "Failed to store messages in mail server database: {}", e);
+		}
+	}
+
+	/// Get stored envelopes matching the request. Returns `None` if the request
+	/// carries wrong key.
+	pub fn envelopes(&self, request: &MailRequest) -> Option<Vec<Envelope>> {
+		if request.key != self.key { return None }
+
+		let from = db_key(ENVELOPE_PREFIX, request.from, &H256::zero());
+		let envelopes = self.db.iter_from_prefix(self.col, &from)
+			.take_while(|&(ref key, _)| key[0] == ENVELOPE_PREFIX && BigEndian::read_u64(&key[1..9]) <= request.to)
+			.filter_map(|(_, value)| ::rlp::decode::<Envelope>(&value).ok())
+			.filter(|envelope| {
+				let bloom = ::message::bloom_topics(&envelope.topics);
+				&(&bloom & &request.bloom) == &bloom
+			})
+			.collect();
+
+		Some(envelopes)
+	}
+
+	/// Remove envelopes which have expired before `now`.
+	pub fn prune(&self, now: SystemTime) {
+		let now = now.duration_since(time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+		let mut batch = self.db.transaction();
+		for (key, envelope_key) in self.db.iter_from_prefix(self.col, &[EXPIRY_PREFIX])
+			.take_while(|&(ref key, _)| key[0] == EXPIRY_PREFIX && BigEndian::read_u64(&key[1..9]) <= now)
+		{
+			batch.delete(self.col, &envelope_key);
+			batch.delete(self.col, &key);
+		}
+
+		if let Err(e) = self.db.write(batch) {
+			warn!(target: "whisper", "Failed to prune mail server database: {}", e);
+		}
+	}
+}
+
+// keys are ordered by time, so that envelopes could be found by seeking to the given time.
+fn db_key(prefix: u8, time: u64, hash: &H256) -> [u8; 41] {
+	let mut key = [0u8; 41];
+	key[0] = prefix;
+	BigEndian::write_u64(&mut key[1..9], time);
+	key[9..].copy_from_slice(&hash[..]);
+	key
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use std::time::{self, Duration, SystemTime};
+	use ethereum_types::{H256, H512};
+	use message::{CreateParams, Message, Topic};
+	use super::{MailRequest, MailServer};
+
+	fn message(topic: Topic, ttl: u64) -> Message {
+		Message::create(CreateParams {
+			ttl: ttl,
+			payload: vec![1, 2, 3],
+			topics: vec![topic],
+			work: 0,
+		}).unwrap()
+	}
+
+	#[test]
+	fn request_rlp_roundtrip() {
+		let request = MailRequest { key: H256::from(1), from: 10, to: 20, bloom: Topic([1, 2, 3, 4]).bloom() };
+		assert_eq!(::rlp::decode::<MailRequest>(&::rlp::encode(&request)), request);
+	}
+
+	#[test]
+	fn serves_stored_envelopes_with_valid_key_only() {
+		let key = H256::from(1);
+		let mail_server = MailServer::new(Arc::new(::kvdb_memorydb::create(0)), None, key);
+		let first = message(Topic([1, 1, 1, 1]), 100);
+		let second = message(Topic([2, 2, 2, 2]), 100);
+		mail_server.store(&[first.clone(), second.clone()]);
+
+		let now = SystemTime::now().duration_since(time::UNIX_EPOCH).unwrap().as_secs();
+		let mut request = MailRequest { key: key, from: now - 10, to: now + 10, bloom: H512::from_slice(&[0xff; 64]) };
+		assert_eq!(mail_server.envelopes(&request).unwrap().len(), 2);
+
+		request.bloom = Topic([1, 1, 1, 1]).bloom();
+		assert_eq!(mail_server.envelopes(&request), Some(vec![first.envelope().clone()]));
+
+		request.from = now + 20;
+		request.to = now + 30;
+		assert_eq!(mail_server.envelopes(&request), Some(vec![]));
+
+		request.key = H256::from(2);
+		assert_eq!(mail_server.envelopes(&request), None);
+	}
+
+	#[test]
+	fn prunes_expired_envelopes() {
+		let key = H256::from(1);
+		let mail_server = MailServer::new(Arc::new(::kvdb_memorydb::create(0)), None, key);
+		mail_server.store(&[message(Topic([1, 1, 1, 1]), 100)]);
+
+		let request = MailRequest { key: key, from: 0, to: u64::max_value(), bloom: H512::from_slice(&[0xff; 64]) };
+		mail_server.prune(SystemTime::now());
+		assert_eq!(mail_server.envelopes(&request).unwrap().len(), 1);
+
+		mail_server.prune(SystemTime::now() + Duration::from_secs(200));
+		assert_eq!(mail_server.envelopes(&request).unwrap().len(), 0);
+	}
+}
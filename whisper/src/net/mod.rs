@@ -19,7 +19,7 @@
 use std::collections::{HashMap, HashSet};
 use std::cmp::Ordering;
 use std::fmt;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use std::sync::Arc;
 
 use ethereum_types::{H256, H512};
@@ -30,6 +30,9 @@ use rlp::{DecoderError, RlpStream, Rlp};
 
 use message::{Message, Error as MessageError};
 
+pub use self::mailserver::{MailServer, MailRequest};
+
+mod mailserver;
 #[cfg(test)]
 mod tests;
 
@@ -66,7 +69,9 @@ mod packet {
 	pub const POW_REQUIREMENT: u8 = 2;
 	pub const TOPIC_FILTER: u8 = 3;
 
-	// 126, 127 for mail server stuff we will never implement here.
+	// mail server packets.
+	pub const P2P_REQUEST: u8 = 126;
+	pub const P2P_MESSAGE: u8 = 127;
 }
 
 /// Handles messages within a single packet.
@@ -324,6 +329,8 @@ struct Peer {
 	pow_requirement: f64,
 	is_parity: bool,
 	_protocol_version: usize,
+	received: (Instant, usize),
+	mail_request_pending: bool,
 }
 
 impl Peer {
@@ -360,6 +367,19 @@ impl Peer {
 		self.pow_requirement = pow_requirement;
 	}
 
+	// note that the peer has sent `count` envelopes. returns the number of them
+	// which fit into the rate limit of the current one-second window.
+	fn note_received(&mut self, count: usize, max_per_second: usize) -> usize {
+		let now = Instant::now();
+		if now.duration_since(self.received.0) >= Duration::from_secs(1) {
+			self.received = (now, 0);
+		}
+
+		let allowed = ::std::cmp::min(count, max_per_second.saturating_sub(self.received.1));
+		self.received.1 += allowed;
+		allowed
+	}
+
 	fn can_send_messages(&self) -> bool {
 		match self.state {
 			State::Unconfirmed(_) => false,
@@ -368,6 +388,37 @@ impl Peer {
 	}
 }
 
+/// Limits applied to envelopes received from peers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+	/// Minimum PoW of accepted envelopes. Announced to peers when non-zero.
+	pub min_pow: f64,
+	/// Bloom filter of accepted topics. Announced to peers when set.
+	pub bloom: Option<H512>,
+	/// Maximum number of envelopes accepted from a single peer per second.
+	pub max_envelopes_per_second: Option<usize>,
+}
+
+impl Default for Limits {
+	fn default() -> Self {
+		Limits {
+			min_pow: 0f64,
+			bloom: None,
+			max_envelopes_per_second: None,
+		}
+	}
+}
+
+impl Limits {
+	// whether a message received from a peer is within the limits.
+	fn accepts(&self, message: &Message) -> bool {
+		if message.work_proved() < self.min_pow { return false }
+
+		self.bloom.as_ref()
+			.map_or(true, |filter| &(filter & message.bloom()) == message.bloom())
+	}
+}
+
 /// Pool status.
 pub struct PoolStatus {
 	/// Required PoW to be accepted into the pool
@@ -423,6 +474,8 @@ pub struct Network<T> {
 	messages: Arc<RwLock<Messages>>,
 	handler: T,
 	peers: RwLock<HashMap<PeerId, Mutex<Peer>>>,
+	mail_server: Option<Arc<MailServer>>,
+	limits: RwLock<Limits>,
 }
 
 // public API.
@@ -433,6 +486,62 @@ impl<T> Network<T> {
 			messages: Arc::new(RwLock::new(Messages::new(messages_size_bytes))),
 			handler: handler,
 			peers: RwLock::new(HashMap::new()),
+			mail_server: None,
+			limits: RwLock::new(Limits::default()),
+		}
+	}
+
+	/// Apply the given limits to envelopes received from peers.
+	pub fn with_limits(self, limits: Limits) -> Self {
+		*self.limits.write() = limits;
+		self
+	}
+
+	/// Get limits applied to envelopes received from peers.
+	pub fn limits(&self) -> Limits {
+		self.limits.read().clone()
+	}
+
+	/// Set minimum PoW of envelopes accepted from peers and announce it to them.
+	pub fn set_min_pow<C: ?Sized + Context>(&self, min_pow: f64, context: &C) {
+		self.limits.write().min_pow = min_pow;
+		for peer in self.peers.read().keys() {
+			self.send_pow_requirement(context, peer, min_pow);
+		}
+	}
+
+	/// Set bloom filter of topics accepted from peers and announce it to them.
+	/// All topics are accepted if `None`.
+	pub fn set_bloom_filter<C: ?Sized + Context>(&self, bloom: Option<H512>, context: &C) {
+		self.limits.write().bloom = bloom;
+		let announced = bloom.unwrap_or_else(|| H512::from_slice(&[0xff; 64]));
+		for peer in self.peers.read().keys() {
+			context.send(*peer, packet::TOPIC_FILTER, ::rlp::encode(&announced).into_vec());
+		}
+	}
+
+	/// Persist all relayed messages in the mail server and serve them to peers on request.
+	pub fn with_mail_server(mut self, mail_server: Arc<MailServer>) -> Self {
+		self.mail_server = Some(mail_server);
+		self
+	}
+
+	/// Request historical messages from the connected node, which is running a mail server.
+	/// Received messages are passed to the message handler, but not relayed.
+	/// Returns false if the node is not connected.
+	pub fn request_messages<C: ?Sized + Context>(&self, request: &MailRequest, node: &NodeId, context: &C) -> bool {
+		let peers = self.peers.read();
+		let peer_id = peers.iter()
+			.find(|&(_, peer)| peer.lock().node_key == *node)
+			.map(|(peer_id, _)| *peer_id);
+
+		match peer_id {
+			Some(peer_id) => {
+				peers[&peer_id].lock().mail_request_pending = true;
+				context.send(peer_id, packet::P2P_REQUEST, ::rlp::encode(request).into_vec());
+				true
+			},
+			None => false,
 		}
 	}
 
@@ -440,6 +549,10 @@ impl<T> Network<T> {
 	pub fn post_message<C: ?Sized + Context>(&self, message: Message, context: &C) -> bool
 		where T: MessageHandler
 	{
+		if let Some(ref mail_server) = self.mail_server {
+			mail_server.store(&[message.clone()]);
+		}
+
 		let ok = self.messages.write().insert(message);
 		if ok { self.rally(context) }
 		ok
@@ -447,7 +560,22 @@ impl<T> Network<T> {
 
 	/// Get number of messages and amount of memory used by them.
 	pub fn pool_status(&self) -> PoolStatus {
-		self.messages.read().status()
+		let mut status = self.messages.read().status();
+		let min_pow = self.limits.read().min_pow;
+		if min_pow > 0f64 {
+			status.required_pow = Some(status.required_pow.map_or(min_pow, |pow| pow.max(min_pow)));
+		}
+		status
+	}
+
+	// announce PoW requirement to the peer. peers treat zero requirement as invalid,
+	// so it is lifted by announcing the smallest normal value instead.
+	fn send_pow_requirement<C: ?Sized + Context>(&self, io: &C, peer: &PeerId, min_pow: f64) {
+		use byteorder::{ByteOrder, BigEndian};
+
+		let mut bytes = [0u8; 8];
+		BigEndian::write_f64(&mut bytes, min_pow.max(::std::f64::MIN_POSITIVE));
+		io.send(*peer, packet::POW_REQUIREMENT, ::rlp::encode(&bytes.to_vec()).into_vec());
 	}
 }
 
@@ -459,6 +587,9 @@ impl<T: MessageHandler> Network<T> {
 		// prune messages.
 		let now = SystemTime::now();
 		let pruned_hashes = self.messages.write().prune(now);
+		if let Some(ref mail_server) = self.mail_server {
+			mail_server.prune(now);
+		}
 
 		let messages = self.messages.read();
 		let peers = self.peers.read();
@@ -523,16 +654,16 @@ impl<T: MessageHandler> Network<T> {
 		}
 	}
 
-	fn on_messages(&self, peer: &PeerId, message_packet: Rlp)
+	fn on_messages(&self, peer_id: &PeerId, message_packet: Rlp)
 		-> Result<(), Error>
 	{
 		let mut messages_vec = {
 			let peers = self.peers.read();
-			let peer = match peers.get(peer) {
+			let peer = match peers.get(peer_id) {
 				Some(peer) => peer,
 				None => {
 					debug!(target: "whisper", "Received message from unknown peer.");
-					return Err(Error::UnknownPeer(*peer));
+					return Err(Error::UnknownPeer(*peer_id));
 				}
 			};
 
@@ -548,8 +679,17 @@ impl<T: MessageHandler> Network<T> {
 
 			if messages_vec.is_empty() { return Ok(()) }
 
-			// disallow duplicates in packet.
-			messages_vec.retain(|message| peer.note_known(&message));
+			let limits = self.limits.read();
+			if let Some(max_per_second) = limits.max_envelopes_per_second {
+				let allowed = peer.note_received(messages_vec.len(), max_per_second);
+				if allowed < messages_vec.len() {
+					debug!(target: "whisper", "Peer {} exceeded rate limit, dropping {} envelopes", peer_id, messages_vec.len() - allowed);
+					messages_vec.truncate(allowed);
+				}
+			}
+
+			// drop envelopes outside of our limits and disallow duplicates in packet.
+			messages_vec.retain(|message| limits.accepts(&message) && peer.note_known(&message));
 			messages_vec
 		};
 
@@ -561,6 +701,10 @@ impl<T: MessageHandler> Network<T> {
 
 		self.handler.handle_messages(&messages_vec);
 
+		if let Some(ref mail_server) = self.mail_server {
+			mail_server.store(&messages_vec);
+		}
+
 		for message in messages_vec {
 			messages.insert(message);
 		}
@@ -627,6 +771,96 @@ impl<T: MessageHandler> Network<T> {
 		Ok(())
 	}
 
+	// serve historical messages from the mail server.
+	fn on_p2p_request<C: ?Sized + Context>(&self, io: &C, peer: &PeerId, request: Rlp)
+		-> Result<(), Error>
+	{
+		// cannot be greater than 16MB (protocol limitation)
+		const MAX_MESSAGES_PACKET_SIZE: usize = 8 * 1024 * 1024;
+
+		if !self.peers.read().get(peer).map_or(false, |peer| peer.lock().can_send_messages()) {
+			return Err(Error::UnexpectedMessage);
+		}
+
+		let mail_server = match self.mail_server {
+			Some(ref mail_server) => mail_server,
+			None => return Ok(()),
+		};
+
+		let request: MailRequest = request.as_val()?;
+		let envelopes = match mail_server.envelopes(&request) {
+			Some(envelopes) => envelopes,
+			None => {
+				debug!(target: "whisper", "Ignoring mail request with invalid key from peer {}", peer);
+				Vec::new()
+			}
+		};
+
+		trace!(target: "whisper", "Serving {} historical envelopes to peer {}", envelopes.len(), peer);
+		let mut stream = RlpStream::new();
+		let mut count = 0;
+		stream.begin_unbounded_list();
+		for envelope in envelopes {
+			let encoded = ::rlp::encode(&envelope);
+
+			// start new packet when this one is full.
+			if count != 0 && stream.estimate_size(encoded.len()) > MAX_MESSAGES_PACKET_SIZE {
+				stream.complete_unbounded_list();
+				io.send(*peer, packet::P2P_MESSAGE, stream.out());
+
+				stream = RlpStream::new();
+				count = 0;
+				stream.begin_unbounded_list();
+			}
+
+			stream.append_raw(&encoded, 1);
+			count += 1;
+		}
+
+		if count != 0 {
+			stream.complete_unbounded_list();
+			io.send(*peer, packet::P2P_MESSAGE, stream.out());
+		}
+
+		// empty packet completes the response.
+		io.send(*peer, packet::P2P_MESSAGE, ::rlp::EMPTY_LIST_RLP.to_vec());
+
+		Ok(())
+	}
+
+	// handle historical messages, sent by the mail server in response to our request.
+	fn on_p2p_messages(&self, peer: &PeerId, message_packet: Rlp)
+		-> Result<(), Error>
+	{
+		{
+			let peers = self.peers.read();
+			let mut peer_data = match peers.get(peer) {
+				Some(peer_data) => peer_data.lock(),
+				None => return Err(Error::UnknownPeer(*peer)),
+			};
+
+			if !peer_data.can_send_messages() || !peer_data.mail_request_pending {
+				return Err(Error::UnexpectedMessage);
+			}
+
+			// empty packet completes the response.
+			if message_packet.item_count()? == 0 {
+				peer_data.mail_request_pending = false;
+				return Ok(());
+			}
+		}
+
+		let now = SystemTime::now();
+		let messages_vec = message_packet.iter().map(|rlp| Message::decode(rlp, now))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		if !messages_vec.is_empty() {
+			self.handler.handle_messages(&messages_vec);
+		}
+
+		Ok(())
+	}
+
 	fn on_connect<C: ?Sized + Context>(&self, io: &C, peer: &PeerId) {
 		trace!(target: "whisper", "Connecting peer {}", peer);
 
@@ -655,9 +889,19 @@ impl<T: MessageHandler> Network<T> {
 			pow_requirement: 0f64,
 			is_parity: io.protocol_version(PARITY_PROTOCOL_ID, *peer).is_some(),
 			_protocol_version: version,
+			received: (Instant::now(), 0),
+			mail_request_pending: false,
 		}));
 
 		io.send(*peer, packet::STATUS, ::rlp::EMPTY_LIST_RLP.to_vec());
+
+		let limits = self.limits();
+		if limits.min_pow > 0f64 {
+			self.send_pow_requirement(io, peer, limits.min_pow);
+		}
+		if let Some(bloom) = limits.bloom {
+			io.send(*peer, packet::TOPIC_FILTER, ::rlp::encode(&bloom).into_vec());
+		}
 	}
 
 	fn on_packet<C: ?Sized + Context>(&self, io: &C, peer: &PeerId, packet_id: u8, data: &[u8]) {
@@ -667,6 +911,8 @@ impl<T: MessageHandler> Network<T> {
 			packet::MESSAGES => self.on_messages(peer, rlp),
 			packet::POW_REQUIREMENT => self.on_pow_requirement(peer, rlp),
 			packet::TOPIC_FILTER => self.on_topic_filter(peer, rlp),
+			packet::P2P_REQUEST => self.on_p2p_request(io, peer, rlp),
+			packet::P2P_MESSAGE => self.on_p2p_messages(peer, rlp),
 			_ => Ok(()), // ignore unknown packets.
 		};
 
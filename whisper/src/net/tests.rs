@@ -19,10 +19,14 @@
 use std::collections::HashSet;
 use std::sync::mpsc;
 
+use std::sync::Arc;
+use std::time::{self, SystemTime};
+
+use ethereum_types::{H256, H512};
 use parking_lot::Mutex;
 use network::{NodeId, PeerId};
 
-use message::{CreateParams, Message};
+use message::{CreateParams, Message, Topic};
 use super::*;
 
 struct TestHandler(Mutex<mpsc::Sender<Message>>);
@@ -52,6 +56,12 @@ impl TestPeer {
 			disconnected: Mutex::new(HashSet::new()),
 		}
 	}
+
+	fn with_mail_server(mail_server: Arc<MailServer>) -> Self {
+		let mut peer = TestPeer::create();
+		peer.network = peer.network.with_mail_server(mail_server);
+		peer
+	}
 }
 
 struct TestNetwork {
@@ -60,7 +70,11 @@ struct TestNetwork {
 
 impl TestNetwork {
 	fn new(n_peers: usize) -> Self {
-		let unconnected_peers: Vec<_> = (0..n_peers).map(|_| TestPeer::create()).collect();
+		TestNetwork::connect((0..n_peers).map(|_| TestPeer::create()).collect())
+	}
+
+	fn connect(unconnected_peers: Vec<TestPeer>) -> Self {
+		let n_peers = unconnected_peers.len();
 		for i in 0..n_peers {
 			for j in (i + 1)..n_peers {
 				let (peer1, peer2) = (&unconnected_peers[i], &unconnected_peers[j]);
@@ -190,3 +204,132 @@ fn message_gets_relayed() {
 		assert_eq!(network.peers[i].recv.try_recv().unwrap(), message);
 	}
 }
+
+fn mail_request(key: H256) -> MailRequest {
+	let now = SystemTime::now().duration_since(time::UNIX_EPOCH).unwrap().as_secs();
+	MailRequest { key: key, from: 0, to: now + 1, bloom: H512::from_slice(&[0xff; 64]) }
+}
+
+fn mail_server_network(key: H256) -> TestNetwork {
+	let mail_server = Arc::new(MailServer::new(Arc::new(::kvdb_memorydb::create(0)), None, key));
+	TestNetwork::connect(vec![TestPeer::with_mail_server(mail_server), TestPeer::create()])
+}
+
+#[test]
+fn mail_server_serves_historical_messages() {
+	let key = H256::from(1);
+	let network = mail_server_network(key);
+	let messages: Vec<_> = (0..3).map(|i| Message::create(CreateParams {
+		ttl: 500,
+		payload: vec![i; 10],
+		topics: vec![[0, 1, 2, 3].into()],
+		work: 25,
+	}).unwrap()).collect();
+
+	for message in &messages {
+		network.post_message_from(0, message.clone());
+		assert_eq!(network.peers[1].recv.try_recv().unwrap(), *message);
+	}
+
+	let mail_server_node = NodeId::default();
+	assert!(network.peers[1].network.request_messages(&mail_request(H256::from(2)), &mail_server_node, &TestContext::new(&network.peers, 1)));
+	assert!(network.peers[1].recv.try_recv().is_err());
+
+	assert!(network.peers[1].network.request_messages(&mail_request(key), &mail_server_node, &TestContext::new(&network.peers, 1)));
+	let mut received: Vec<_> = network.peers[1].recv.try_iter().map(|message| message.hash().clone()).collect();
+	let mut expected: Vec<_> = messages.iter().map(|message| message.hash().clone()).collect();
+	received.sort();
+	expected.sort();
+	assert_eq!(received, expected);
+	assert!(network.peers[1].disconnected.lock().is_empty());
+}
+
+#[test]
+fn mail_request_to_unknown_node_is_not_sent() {
+	let network = mail_server_network(H256::from(1));
+	let mut unknown_node = NodeId::default();
+	unknown_node[0] = 42;
+
+	assert!(!network.peers[1].network.request_messages(&mail_request(H256::from(1)), &unknown_node, &TestContext::new(&network.peers, 1)));
+}
+
+#[test]
+fn unsolicited_mail_server_messages_are_rejected() {
+	let network = mail_server_network(H256::from(1));
+	let message = Message::create(CreateParams {
+		ttl: 500,
+		payload: b"this is my payload, pal".to_vec(),
+		topics: vec![[0, 1, 2, 3].into()],
+		work: 25,
+	}).unwrap();
+
+	let mut stream = RlpStream::new_list(1);
+	stream.append(message.envelope());
+	TestContext::new(&network.peers, 0).send(1, packet::P2P_MESSAGE, stream.out());
+
+	assert!(network.peers[1].recv.try_recv().is_err());
+	assert!(network.peers[1].disconnected.lock().contains(&0));
+}
+
+#[test]
+fn min_pow_is_announced_to_peers() {
+	let network = TestNetwork::new(2);
+	let message = Message::create(CreateParams {
+		ttl: 500,
+		payload: b"this is my payload, pal".to_vec(),
+		topics: vec![[0, 1, 2, 3].into()],
+		work: 25,
+	}).unwrap();
+
+	network.peers[1].network.set_min_pow(message.work_proved() * 2f64, &TestContext::new(&network.peers, 1));
+	assert_eq!(network.peers[1].network.pool_status().required_pow, Some(message.work_proved() * 2f64));
+
+	network.post_message_from(0, message);
+	assert!(network.peers[1].recv.try_recv().is_err());
+	assert!(network.peers[0].disconnected.lock().is_empty());
+}
+
+#[test]
+fn envelopes_above_rate_limit_are_dropped() {
+	let mut limited = TestPeer::create();
+	limited.network = limited.network.with_limits(Limits {
+		max_envelopes_per_second: Some(1),
+		..Default::default()
+	});
+	let network = TestNetwork::connect(vec![TestPeer::create(), limited]);
+
+	let message = |payload: &[u8]| Message::create(CreateParams {
+		ttl: 500,
+		payload: payload.to_vec(),
+		topics: vec![[0, 1, 2, 3].into()],
+		work: 25,
+	}).unwrap();
+
+	let first = message(b"first");
+	network.post_message_from(0, first.clone());
+	network.post_message_from(0, message(b"second"));
+
+	assert_eq!(network.peers[1].recv.try_recv().unwrap(), first);
+	assert!(network.peers[1].recv.try_recv().is_err());
+}
+
+#[test]
+fn bloom_filter_is_reset() {
+	let network = TestNetwork::new(2);
+	let message = |payload: &[u8], topic: [u8; 4]| Message::create(CreateParams {
+		ttl: 500,
+		payload: payload.to_vec(),
+		topics: vec![topic.into()],
+		work: 25,
+	}).unwrap();
+
+	network.peers[1].network.set_bloom_filter(Some(Topic([0, 1, 2, 3]).bloom()), &TestContext::new(&network.peers, 1));
+	network.post_message_from(0, message(b"first", [4, 5, 6, 7]));
+	assert!(network.peers[1].recv.try_recv().is_err());
+
+	network.peers[1].network.set_bloom_filter(None, &TestContext::new(&network.peers, 1));
+	let second = message(b"second", [4, 5, 6, 7]);
+	network.post_message_from(0, second.clone());
+	assert_eq!(network.peers[1].recv.try_recv().unwrap(), second);
+	assert!(network.peers[0].disconnected.lock().is_empty());
+}
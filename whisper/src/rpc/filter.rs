@@ -21,6 +21,7 @@ use std::{sync::{Arc, atomic, atomic::AtomicBool, mpsc}, thread};
 
 use ethereum_types::{H256, H512};
 use ethkey::Public;
+use futures::Future;
 use jsonrpc_macros::pubsub::{Subscriber, Sink};
 use parking_lot::{Mutex, RwLock};
 use rand::{Rng, OsRng};
@@ -35,6 +36,8 @@ pub enum Kind {
 	Poll,
 	/// Subscription filter pushes data to subscriber immediately.
 	Subscription,
+	/// Envelope subscription pushes raw envelopes to subscriber immediately.
+	EnvelopeSubscription,
 }
 
 pub type ItemBuffer = Arc<Mutex<Vec<FilterItem>>>;
@@ -42,6 +45,7 @@ pub type ItemBuffer = Arc<Mutex<Vec<FilterItem>>>;
 enum FilterEntry {
 	Poll(Arc<Filter>, ItemBuffer),
 	Subscription(Arc<Filter>, Sink<FilterItem>),
+	Envelopes(Vec<Topic>, Sink<types::Bytes>),
 }
 
 /// Filter manager. Handles filters as well as a thread for doing decryption
@@ -49,6 +53,8 @@ enum FilterEntry {
 pub struct Manager {
 	key_store: Arc<RwLock<KeyStore>>,
 	filters: RwLock<HashMap<H256, FilterEntry>>,
+	// subscriptions whose subscriber went away, pruned on the next batch.
+	dead: Arc<Mutex<Vec<H256>>>,
 	tx: Mutex<mpsc::Sender<Box<Fn() + Send>>>,
 	join: Option<thread::JoinHandle<()>>,
 	exit: Arc<AtomicBool>,
@@ -79,6 +85,7 @@ impl Manager {
 		Ok(Manager {
 			key_store: Arc::new(RwLock::new(KeyStore::new()?)),
 			filters: RwLock::new(HashMap::new()),
+			dead: Arc::new(Mutex::new(Vec::new())),
 			tx: Mutex::new(tx),
 			join: Some(join_handle),
 			exit: e,
@@ -95,6 +102,7 @@ impl Manager {
 		self.filters.read().get(id).map(|filter| match *filter {
 			FilterEntry::Poll(_, _) => Kind::Poll,
 			FilterEntry::Subscription(_, _) => Kind::Subscription,
+			FilterEntry::Envelopes(_, _) => Kind::EnvelopeSubscription,
 		})
 	}
 
@@ -132,10 +140,28 @@ impl Manager {
 			.map_err(|_| "subscriber disconnected")
 	}
 
+	/// Insert new subscription to raw envelopes carrying any of the given
+	/// abridged topics. Envelopes are neither decrypted nor decoded, so that
+	/// light clients could handle them on their own.
+	pub fn insert_envelope_subscription(&self, topics: Vec<Topic>, sub: Subscriber<types::Bytes>)
+		-> Result<(), &'static str>
+	{
+		let id: H256 = OsRng::new()
+			.map_err(|_| "unable to acquire secure randomness")?
+			.gen();
+
+		sub.assign_id(::jsonrpc_pubsub::SubscriptionId::String(format!("{:x}", id)))
+			.map(move |sink| {
+				let entry = FilterEntry::Envelopes(topics, sink);
+				self.filters.write().insert(id, entry);
+			})
+			.map_err(|_| "subscriber disconnected")
+	}
+
 	/// Poll changes on filter identified by ID.
 	pub fn poll_changes(&self, id: &H256) -> Option<Vec<FilterItem>> {
 		self.filters.read().get(id).and_then(|filter| match *filter {
-			FilterEntry::Subscription(_, _) => None,
+			FilterEntry::Subscription(_, _) | FilterEntry::Envelopes(_, _) => None,
 			FilterEntry::Poll(_, ref changes)
 				=> Some(::std::mem::replace(&mut *changes.lock(), Vec::new())),
 		})
@@ -145,18 +171,22 @@ impl Manager {
 // machinery for attaching the manager to the network instance.
 impl ::net::MessageHandler for Arc<Manager> {
 	fn handle_messages(&self, messages: &[Message]) {
+		self.prune_dead();
+
 		let filters = self.filters.read();
 		let filters_iter = filters
-			.values()
-			.flat_map(|filter| messages.iter().map(move |msg| (filter, msg))) ;
+			.iter()
+			.flat_map(|(id, filter)| messages.iter().map(move |msg| (id, filter, msg))) ;
 
-		for	(filter, message) in filters_iter {
+		for	(id, filter, message) in filters_iter {
 			// if the message matches any of the possible bloom filters,
 			// send to thread pool to attempt decryption and avoid
 			// blocking the network thread for long.
 			let failed_send = match *filter {
 				FilterEntry::Poll(ref filter, _) | FilterEntry::Subscription(ref filter, _)
 					if !filter.basic_matches(message) => None,
+				FilterEntry::Envelopes(ref topics, _)
+					if !topics.iter().any(|topic| message.topics().contains(topic)) => None,
 				FilterEntry::Poll(ref filter, ref buffer) => {
 					let (message, key_store) = (message.clone(), self.key_store.clone());
 					let (filter, buffer) = (filter.clone(), buffer.clone());
@@ -172,15 +202,32 @@ impl ::net::MessageHandler for Arc<Manager> {
 				FilterEntry::Subscription(ref filter, ref sink) => {
 					let (message, key_store) = (message.clone(), self.key_store.clone());
 					let (filter, sink) = (filter.clone(), sink.clone());
+					let (id, dead) = (*id, self.dead.clone());
 
 					self.tx.lock().send(Box::new(move || {
 						filter.handle_message(
 							&message,
 							&*key_store,
-							|matched| { let _ = sink.notify(Ok(matched)); },
+							|matched| if sink.notify(Ok(matched)).wait().is_err() {
+								dead.lock().push(id);
+							},
 						)
 					})).err().map(|x| x.0)
 				}
+				FilterEntry::Envelopes(_, ref sink) => {
+					// raw envelopes are passed through as-is: no need to
+					// decrypt, only to keep the network thread from waiting
+					// on the subscriber.
+					let encoded = ::rlp::encode(message.envelope()).into_vec();
+					let sink = sink.clone();
+					let (id, dead) = (*id, self.dead.clone());
+
+					self.tx.lock().send(Box::new(move || {
+						if sink.notify(Ok(HexEncode(encoded.clone()))).wait().is_err() {
+							dead.lock().push(id);
+						}
+					})).err().map(|x| x.0)
+				}
 			};
 
 			// if we failed to send work, no option but to do it locally.
@@ -188,6 +235,23 @@ impl ::net::MessageHandler for Arc<Manager> {
 				(local_work)()
 			}
 		}
+
+		drop(filters);
+		self.prune_dead();
+	}
+}
+
+impl Manager {
+	// remove subscriptions which failed to be notified.
+	fn prune_dead(&self) {
+		let dead = ::std::mem::replace(&mut *self.dead.lock(), Vec::new());
+		if dead.is_empty() { return }
+
+		let mut filters = self.filters.write();
+		for id in dead {
+			trace!(target: "parity_whisper", "Dropping subscription {:?}: subscriber is gone", id);
+			filters.remove(&id);
+		}
 	}
 }
 
@@ -435,4 +499,35 @@ mod tests {
 
 		assert_eq!(items.get(), 1);
 	}
+
+	#[test]
+	fn drops_envelope_subscription_of_gone_subscriber() {
+		use std::time::{Duration, Instant};
+		use jsonrpc_macros::pubsub::Subscriber;
+		use net::MessageHandler;
+
+		let topic = Topic([1, 2, 3, 4]);
+		let manager = Arc::new(Manager::new().unwrap());
+		let (sub, _id, notifications) = Subscriber::new_test("shh_subscribeEnvelopes");
+		manager.insert_envelope_subscription(vec![topic], sub).unwrap();
+		assert_eq!(manager.filters.read().len(), 1);
+
+		drop(notifications);
+		let message = Message::create(CreateParams {
+			ttl: 100,
+			payload: vec![1, 3, 5, 7, 9],
+			topics: vec![topic],
+			work: 0,
+		}).unwrap();
+		manager.handle_messages(&[message]);
+
+		// notification happens on the worker; the failure is picked up by
+		// a later batch.
+		let deadline = Instant::now() + Duration::from_secs(5);
+		while !manager.filters.read().is_empty() {
+			assert!(Instant::now() < deadline, "dead subscription was not dropped");
+			::std::thread::sleep(Duration::from_millis(10));
+			manager.handle_messages(&[]);
+		}
+	}
 }
@@ -27,7 +27,7 @@ use jsonrpc_core::{Error, ErrorCode, Metadata};
 use jsonrpc_pubsub::{Session, PubSubMetadata, SubscriptionId};
 use jsonrpc_macros::pubsub;
 
-use ethereum_types::H256;
+use ethereum_types::{H256, H512};
 use mem::Memzero;
 use parking_lot::RwLock;
 
@@ -124,6 +124,34 @@ build_rpc_trait! {
 		/// Delete polled filter. Return bool indicating success.
 		#[rpc(name = "shh_deleteMessageFilter")]
 		fn delete_filter(&self, types::Identity) -> Result<bool, Error>;
+
+		/// Relay an RLP-encoded envelope, which was sealed by the caller.
+		/// Returns false if PoW too low.
+		#[rpc(name = "shh_postEnvelope")]
+		fn post_envelope(&self, types::Bytes) -> Result<bool, Error>;
+
+		/// Request historical messages from the connected mail server.
+		/// Received messages are delivered to matching filters and subscriptions.
+		/// Returns false if the mail server is not connected.
+		#[rpc(name = "shh_requestMessages")]
+		fn request_messages(&self, types::MailRequest) -> Result<bool, Error>;
+	}
+}
+
+build_rpc_trait! {
+	/// Whisper RPC methods changing limits of the node. Affect all users of the node.
+	pub trait WhisperSet {
+		/// Set minimum PoW of envelopes accepted from peers.
+		#[rpc(name = "shh_setMinPoW")]
+		fn set_min_pow(&self, f64) -> Result<bool, Error>;
+
+		/// Set bloom filter of topics accepted from peers.
+		#[rpc(name = "shh_setBloomFilter")]
+		fn set_bloom_filter(&self, types::Bloom) -> Result<bool, Error>;
+
+		/// Reset bloom filter, so that envelopes with any topics are accepted from peers.
+		#[rpc(name = "shh_resetBloomFilter")]
+		fn reset_bloom_filter(&self) -> Result<bool, Error>;
 	}
 }
 
@@ -145,6 +173,26 @@ build_rpc_trait! {
 	}
 }
 
+build_rpc_trait! {
+	/// Whisper bridge for clients which cannot connect to the whisper network,
+	/// e.g. browsers. Raw envelopes are relayed as-is, encryption and PoW
+	/// are left to the client.
+	pub trait WhisperBridge {
+		type Metadata;
+
+		#[pubsub(name = "shh_envelope")] {
+			/// Subscribe to RLP-encoded envelopes matching any of the abridged topics.
+			#[rpc(name = "shh_subscribeEnvelopes")]
+			fn subscribe_envelopes(&self, Self::Metadata, pubsub::Subscriber<types::Bytes>, types::EnvelopeFilterRequest);
+
+			/// Unsubscribe from envelopes subscription matching given ID. Return
+			/// true on success, error otherwise.
+			#[rpc(name = "shh_unsubscribeEnvelopes")]
+			fn unsubscribe_envelopes(&self, SubscriptionId) -> Result<bool, Error>;
+		}
+	}
+}
+
 /// Something which can send messages to the network.
 pub trait PoolHandle: Send + Sync {
 	/// Give message to the whisper network for relay.
@@ -153,6 +201,16 @@ pub trait PoolHandle: Send + Sync {
 
 	/// Number of messages and memory used by resident messages.
 	fn pool_status(&self) -> ::net::PoolStatus;
+
+	/// Set minimum PoW of envelopes accepted from peers.
+	fn set_min_pow(&self, min_pow: f64);
+
+	/// Set bloom filter of topics accepted from peers. Accepts all topics if `None`.
+	fn set_bloom_filter(&self, bloom: Option<H512>);
+
+	/// Request historical messages from the connected mail server.
+	/// Returns false if the mail server is not connected.
+	fn request_messages(&self, mail_server: H512, request: ::net::MailRequest) -> bool;
 }
 
 /// Default, simple metadata implementation.
@@ -356,6 +414,52 @@ impl<P: PoolHandle + 'static, M: Send + Sync + 'static> Whisper for WhisperClien
 	fn delete_filter(&self, id: types::Identity) -> Result<bool, Error> {
 		Ok(self.delete_filter_kind(id.into_inner(), filter::Kind::Poll))
 	}
+
+	fn post_envelope(&self, envelope: types::Bytes) -> Result<bool, Error> {
+		let message = Message::decode(::rlp::Rlp::new(&envelope), ::std::time::SystemTime::now())
+			.map_err(|e| whisper_error(format!("Invalid envelope: {}", e)))?;
+
+		Ok(self.pool.relay(message))
+	}
+
+	fn request_messages(&self, req: types::MailRequest) -> Result<bool, Error> {
+		let key = self.store.read().symmetric(&req.key.into_inner())
+			.map(|key| H256::from_slice(&key[..]))
+			.ok_or_else(|| whisper_error("no such symmetric key"))?;
+
+		let bloom = match req.topics.is_empty() {
+			true => H512::from_slice(&[0xff; 64]),
+			false => ::message::bloom_topics(&req.topics.iter().map(|topic| abridge_topic(&topic.0)).collect::<Vec<_>>()),
+		};
+
+		Ok(self.pool.request_messages(req.peer.into_inner(), ::net::MailRequest {
+			key: key,
+			from: req.from,
+			to: req.to,
+			bloom: bloom,
+		}))
+	}
+}
+
+impl<P: PoolHandle + 'static, M: Send + Sync + 'static> WhisperSet for WhisperClient<P, M> {
+	fn set_min_pow(&self, min_pow: f64) -> Result<bool, Error> {
+		if !min_pow.is_finite() || min_pow < 0f64 {
+			return Err(whisper_error("Minimum PoW must be a non-negative number"));
+		}
+
+		self.pool.set_min_pow(min_pow);
+		Ok(true)
+	}
+
+	fn set_bloom_filter(&self, bloom: types::Bloom) -> Result<bool, Error> {
+		self.pool.set_bloom_filter(Some(bloom.into_inner()));
+		Ok(true)
+	}
+
+	fn reset_bloom_filter(&self) -> Result<bool, Error> {
+		self.pool.set_bloom_filter(None);
+		Ok(true)
+	}
 }
 
 impl<P: PoolHandle + 'static, M: Send + Sync + PubSubMetadata> WhisperPubSub for WhisperClient<P, M> {
@@ -390,3 +494,37 @@ impl<P: PoolHandle + 'static, M: Send + Sync + PubSubMetadata> WhisperPubSub for
 		res.map_err(whisper_error)
 	}
 }
+
+impl<P: PoolHandle + 'static, M: Send + Sync + PubSubMetadata> WhisperBridge for WhisperClient<P, M> {
+	type Metadata = M;
+
+	fn subscribe_envelopes(
+		&self,
+		_meta: Self::Metadata,
+		subscriber: pubsub::Subscriber<types::Bytes>,
+		req: types::EnvelopeFilterRequest,
+	) {
+		if req.topics.is_empty() {
+			let _ = subscriber.reject(whisper_error("no topics for filter"));
+			return;
+		}
+
+		let topics = req.topics.into_iter().map(|topic| Topic(topic.into_inner().0)).collect();
+		if let Err(e) = self.filter_manager.insert_envelope_subscription(topics, subscriber) {
+			debug!(target: "whisper", "Failed to add envelope subscription: {}", e);
+		}
+	}
+
+	fn unsubscribe_envelopes(&self, id: SubscriptionId) -> Result<bool, Error> {
+		use std::str::FromStr;
+
+		let res = match id {
+			SubscriptionId::String(s) => H256::from_str(&s)
+				.map_err(|_| "unrecognized ID")
+				.map(|id| self.delete_filter_kind(id, filter::Kind::EnvelopeSubscription)),
+			SubscriptionId::Number(_) => Err("unrecognized ID"),
+		};
+
+		res.map_err(whisper_error)
+	}
+}
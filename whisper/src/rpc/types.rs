@@ -91,13 +91,14 @@ pub type Public = HexEncode<::ethkey::Public>;
 pub type Private = HexEncode<H256>;
 
 /// Abridged topic is four bytes.
-// only used in tests for now.
-#[cfg(test)]
 pub type AbridgedTopic = HexEncode<H32>;
 
 /// 32-byte AES key.
 pub type Symmetric = HexEncode<H256>;
 
+/// 64-byte topics bloom filter.
+pub type Bloom = HexEncode<H512>;
+
 impl<T: HexEncodable> Serialize for HexEncode<T> {
 	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
 		let data = &self.0[..];
@@ -206,6 +207,33 @@ pub struct FilterRequest {
 	pub topics: Vec<Bytes>,
 }
 
+/// Request for raw envelopes subscription.
+#[derive(Deserialize)]
+pub struct EnvelopeFilterRequest {
+	/// Abridged topics. Envelopes carrying any of them are matched.
+	/// Cannot be empty.
+	pub topics: Vec<AbridgedTopic>,
+}
+
+/// Request for historical messages from a mail server.
+#[derive(Deserialize)]
+pub struct MailRequest {
+	/// Node ID of the connected peer running the mail server.
+	pub peer: HexEncode<H512>,
+
+	/// ID of the symmetric key of the mail server.
+	pub key: Identity,
+
+	/// Lower bound of messages issue time (unix seconds, inclusive).
+	pub from: u64,
+
+	/// Upper bound of messages issue time (unix seconds, inclusive).
+	pub to: u64,
+
+	/// Topics of requested messages. All messages are requested if empty.
+	pub topics: Vec<Bytes>,
+}
+
 /// A message captured by a filter or subscription.
 #[derive(Serialize, Clone)]
 pub struct FilterItem {
@@ -295,4 +323,13 @@ mod tests {
 		assert!(topic2.is_err());
 		assert_eq!(topic3, topic);
 	}
+
+	#[test]
+	fn deserialize_envelope_filter_request() {
+		let req: EnvelopeFilterRequest = serde_json::from_str(r#"{"topics":["0x0102030F","0xdeadbeef"]}"#).unwrap();
+		assert_eq!(req.topics, vec![
+			AbridgedTopic::new([1, 2, 3, 15].into()),
+			AbridgedTopic::new([0xde, 0xad, 0xbe, 0xef].into()),
+		]);
+	}
 }